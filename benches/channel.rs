@@ -0,0 +1,64 @@
+//! `ShellChannel::read_data` through the whole channel, idle and with a
+//! daemon thread writing all the while
+//!
+//! `cargo bench --bench channel`. The channels are named after the process
+//! and unlinked when their daemon is dropped.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use venom_memory::{ChannelConfig, DaemonChannel, ShellChannel};
+
+const SIZES: [usize; 3] = [64, 1024, 64 * 1024];
+
+fn channel(what: &str) -> (DaemonChannel, ShellChannel) {
+    let namespace = format!("bench_{}_{}", std::process::id(), what);
+    let config = ChannelConfig {
+        data_size: 64 * 1024,
+        ..ChannelConfig::default()
+    };
+    let daemon = DaemonChannel::create(&namespace, config).unwrap();
+    let shell = ShellChannel::connect(&namespace).unwrap();
+    (daemon, shell)
+}
+
+fn read_data(c: &mut Criterion) {
+    let (daemon, shell) = channel("read");
+    daemon.write_data(&vec![0xA5; daemon.data_size()]);
+    let mut group = c.benchmark_group("channel_read_data");
+    for size in SIZES {
+        let mut buf = vec![0u8; size];
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_function(BenchmarkId::from_parameter(size), |b| b.iter(|| black_box(shell.read_data(&mut buf))));
+    }
+    group.finish();
+}
+
+/// Reads of 1KB while another thread keeps rewriting it: the retries a
+/// reader makes when a write lands in the middle of its copy
+fn read_data_contended(c: &mut Criterion) {
+    let (daemon, shell) = channel("contended");
+    let stop = Arc::new(AtomicBool::new(false));
+    let writer = {
+        let stop = stop.clone();
+        std::thread::spawn(move || {
+            let data = vec![0xA5; 1024];
+            while !stop.load(Ordering::Relaxed) {
+                daemon.write_data(&data);
+            }
+            daemon
+        })
+    };
+
+    let mut buf = vec![0u8; 1024];
+    let mut group = c.benchmark_group("channel_read_data_contended");
+    group.throughput(Throughput::Bytes(buf.len() as u64));
+    group.bench_function(BenchmarkId::from_parameter(buf.len()), |b| b.iter(|| black_box(shell.read_data(&mut buf))));
+    group.finish();
+
+    stop.store(true, Ordering::Relaxed);
+    drop(writer.join().unwrap());
+}
+
+criterion_group!(benches, read_data, read_data_contended);
+criterion_main!(benches);
@@ -0,0 +1,64 @@
+//! The command queue: a 64-byte command pushed and popped again
+//!
+//! `cargo bench --bench mpsc`. The queue is bounded, so each iteration of
+//! `mpsc/try_push_try_pop` pushes one command and pops it; `mpsc/try_push`
+//! times a queue's worth of pushes at a time and drains it untimed.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use std::time::{Duration, Instant};
+use venom_memory::mpsc_queue::{
+    CmdFullPolicy, MpscConsumer, MpscProducer, MpscQueueHeader, DEFAULT_CMD_PAYLOAD_SIZE, MAX_CMD_SIZE,
+};
+use venom_memory::shm::VenomShm;
+
+const SLOTS: usize = 32;
+const COMMAND: [u8; 64] = [0x5A; 64];
+
+/// A queue in a shared memory region of its own, unlinked when the region
+/// is dropped
+fn queue() -> (VenomShm, MpscProducer, MpscConsumer) {
+    let name = format!("bench_{}_mpsc", std::process::id());
+    let shm = VenomShm::create(&name, MpscQueueHeader::size_for_slots(SLOTS, DEFAULT_CMD_PAYLOAD_SIZE)).unwrap();
+    unsafe {
+        let header = shm.as_ptr() as *mut MpscQueueHeader;
+        MpscQueueHeader::init(header, SLOTS, DEFAULT_CMD_PAYLOAD_SIZE, CmdFullPolicy::Reject);
+        let producer = MpscProducer::from_raw(header, 1);
+        let consumer = MpscConsumer::from_raw(header);
+        (shm, producer, consumer)
+    }
+}
+
+fn push_pop(c: &mut Criterion) {
+    let (_shm, producer, consumer) = queue();
+    let mut buf = [0u8; MAX_CMD_SIZE];
+    let mut group = c.benchmark_group("mpsc");
+    group.throughput(Throughput::Bytes(COMMAND.len() as u64));
+
+    group.bench_function("try_push_try_pop", |b| {
+        b.iter(|| {
+            assert!(producer.try_push(black_box(&COMMAND)));
+            black_box(consumer.try_pop(&mut buf))
+        })
+    });
+    group.bench_function("try_push", |b| {
+        b.iter_custom(|iters| {
+            let mut elapsed = Duration::ZERO;
+            let mut left = iters;
+            while left > 0 {
+                let batch = left.min(SLOTS as u64);
+                let start = Instant::now();
+                for _ in 0..batch {
+                    assert!(producer.try_push(black_box(&COMMAND)));
+                }
+                elapsed += start.elapsed();
+                while consumer.try_pop(&mut buf).is_some() {}
+                left -= batch;
+            }
+            elapsed
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, push_pop);
+criterion_main!(benches);
@@ -0,0 +1,100 @@
+//! Raw SeqLock reads and writes, without the channel around them
+//!
+//! `cargo bench --bench seqlock`. Each size is its own benchmark
+//! (`seqlock_read/64`, `seqlock_write/65536`, ...) with its throughput in
+//! bytes, so a slower copy path shows up as lower bytes/s.
+//!
+//! `seqlock_fresh_read` and `seqlock_parts_write` compare the paths for a
+//! buffer that has to be allocated for the read (zeroed for `read`, or not
+//! for `read_uninit`) and for a payload in two parts (concatenated for
+//! `write_with_len`, or given to `write_vectored` as they are).
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use venom_memory::seqlock::{SeqLockHeader, SeqLockReader, SeqLockWriter};
+use venom_memory::shm::VenomShm;
+
+const SIZES: [usize; 3] = [64, 1024, 64 * 1024];
+
+/// A SeqLock of `size` bytes in a shared memory region of its own,
+/// unlinked when the region is dropped
+fn seqlock(size: usize) -> (VenomShm, SeqLockWriter, SeqLockReader) {
+    let name = format!("bench_{}_seqlock_{}", std::process::id(), size);
+    let shm = VenomShm::create(&name, std::mem::size_of::<SeqLockHeader>() + size).unwrap();
+    unsafe {
+        let header = shm.as_ptr() as *mut SeqLockHeader;
+        SeqLockHeader::init(header, size);
+        let data = shm.as_ptr().add(std::mem::size_of::<SeqLockHeader>());
+        let writer = SeqLockWriter::from_raw(header, data);
+        let reader = SeqLockReader::from_raw(header, data);
+        (shm, writer, reader)
+    }
+}
+
+fn read(c: &mut Criterion) {
+    let mut group = c.benchmark_group("seqlock_read");
+    for size in SIZES {
+        let (_shm, writer, reader) = seqlock(size);
+        writer.write(&vec![0xA5; size]);
+        let mut buf = vec![0u8; size];
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_function(BenchmarkId::from_parameter(size), |b| b.iter(|| black_box(reader.read(&mut buf))));
+    }
+    group.finish();
+}
+
+fn write(c: &mut Criterion) {
+    let mut group = c.benchmark_group("seqlock_write");
+    for size in SIZES {
+        let (_shm, writer, _reader) = seqlock(size);
+        let data = vec![0xA5; size];
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_function(BenchmarkId::from_parameter(size), |b| b.iter(|| writer.write(black_box(&data))));
+    }
+    group.finish();
+}
+
+fn fresh_read(c: &mut Criterion) {
+    let mut group = c.benchmark_group("seqlock_fresh_read");
+    for size in SIZES {
+        let (_shm, writer, reader) = seqlock(size);
+        writer.write(&vec![0xA5; size]);
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_function(BenchmarkId::new("zeroed", size), |b| {
+            b.iter(|| {
+                let mut buf = vec![0u8; size];
+                reader.read(&mut buf);
+                black_box(buf)
+            })
+        });
+        group.bench_function(BenchmarkId::new("uninit", size), |b| {
+            b.iter(|| {
+                let mut buf = Vec::with_capacity(size);
+                let len = reader.read_uninit(buf.spare_capacity_mut());
+                unsafe { buf.set_len(len) };
+                black_box(buf)
+            })
+        });
+    }
+    group.finish();
+}
+
+fn parts_write(c: &mut Criterion) {
+    let mut group = c.benchmark_group("seqlock_parts_write");
+    for size in SIZES {
+        let (_shm, writer, _reader) = seqlock(size);
+        // A 16-byte header and a body filling the rest of the region
+        let header = [0x5A; 16];
+        let body = vec![0xA5; size - 8 - header.len()];
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_function(BenchmarkId::new("concat", size), |b| {
+            b.iter(|| writer.write_with_len(&[&header[..], black_box(&body)].concat()))
+        });
+        group.bench_function(BenchmarkId::new("vectored", size), |b| {
+            b.iter(|| writer.write_vectored(&[&header, black_box(&body)]))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, read, write, fresh_read, parts_write);
+criterion_main!(benches);
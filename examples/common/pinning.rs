@@ -0,0 +1,50 @@
+//! Where the benchmark examples put their threads
+//!
+//! Included with `#[path = "common/pinning.rs"] mod pinning;`. Channel `i`'s
+//! writer goes on the `2i`-th core the process may use and its reader on the
+//! next one, wrapping around when there are more threads than cores, so a
+//! writer and its reader never share a core while there are enough of them.
+
+use venom_memory::affinity;
+
+pub struct Pinning {
+    cores: Vec<usize>,
+}
+
+impl Pinning {
+    /// Cores the calling thread may use; if they can't be read, nothing is pinned
+    pub fn new() -> Self {
+        Pinning { cores: affinity::thread_cores().unwrap_or_default() }
+    }
+
+    pub fn writer_core(&self, channel: usize) -> Option<usize> {
+        self.core(2 * channel)
+    }
+
+    pub fn reader_core(&self, channel: usize) -> Option<usize> {
+        self.core(2 * channel + 1)
+    }
+
+    fn core(&self, thread: usize) -> Option<usize> {
+        self.cores.get(thread % self.cores.len().max(1)).copied()
+    }
+
+    /// Header lines, one per channel
+    pub fn describe(&self, channels: usize) -> Vec<String> {
+        let core = |core: Option<usize>| core.map_or("unpinned".to_string(), |core| format!("core {}", core));
+        (0..channels)
+            .map(|i| format!("channel {}: writer on {}, reader on {}", i, core(self.writer_core(i)), core(self.reader_core(i))))
+            .collect()
+    }
+}
+
+/// Name the calling thread and pin it to `core`; a thread that can't be
+/// pinned runs unpinned
+pub fn enter(name: &str, core: Option<usize>) {
+    let _ = affinity::set_thread_name(name);
+    if let Some(core) = core {
+        if let Err(e) = affinity::pin_current_thread(core) {
+            eprintln!("{}: {}", name, e);
+        }
+    }
+}
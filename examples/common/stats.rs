@@ -0,0 +1,184 @@
+//! Latency statistics shared by the benchmark examples
+//!
+//! Included with `#[path = "common/stats.rs"] mod stats;`. Each channel's
+//! reader records into a [`Histogram`] of its own; the results tables show
+//! percentiles rather than min/avg/max, since one scheduler hiccup decides
+//! the max of a run and says nothing about the rest of it.
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// Linear sub-buckets per power of two: values are kept to within 1/128
+const SUB_BITS: u32 = 7;
+const SUB_BUCKETS: u64 = 1 << SUB_BITS;
+
+/// Percentiles of the results tables and the JSON file
+pub const PERCENTILES: [f64; 4] = [50.0, 90.0, 99.0, 99.9];
+
+/// Percentiles the ASCII curve has a row for
+const CURVE: [f64; 8] = [0.0, 50.0, 75.0, 90.0, 99.0, 99.9, 99.99, 100.0];
+
+/// Latencies in nanoseconds, bucketed the way an HDR histogram buckets
+/// them: exactly below 128ns, and in 128 equal steps within each power of
+/// two above that
+pub struct Histogram {
+    counts: Vec<u64>,
+    count: u64,
+    sum: u128,
+    min: u64,
+    max: u64,
+}
+
+impl Histogram {
+    pub fn new() -> Self {
+        Self {
+            counts: vec![0; ((64 - SUB_BITS as usize) + 1) << SUB_BITS],
+            count: 0,
+            sum: 0,
+            min: u64::MAX,
+            max: 0,
+        }
+    }
+
+    #[inline(always)]
+    pub fn record(&mut self, latency_ns: u64) {
+        self.counts[index(latency_ns)] += 1;
+        self.count += 1;
+        self.sum += latency_ns as u128;
+        self.min = self.min.min(latency_ns);
+        self.max = self.max.max(latency_ns);
+    }
+
+    /// Add `other`'s values to this one
+    pub fn merge(&mut self, other: &Histogram) {
+        for (count, other) in self.counts.iter_mut().zip(&other.counts) {
+            *count += other;
+        }
+        self.count += other.count;
+        self.sum += other.sum;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn mean(&self) -> f64 {
+        if self.count == 0 { 0.0 } else { self.sum as f64 / self.count as f64 }
+    }
+
+    pub fn min(&self) -> u64 {
+        if self.count == 0 { 0 } else { self.min }
+    }
+
+    pub fn max(&self) -> u64 {
+        self.max
+    }
+
+    /// The value `percentile`% of the recorded ones are at or below, to
+    /// within the histogram's precision
+    pub fn percentile(&self, percentile: f64) -> u64 {
+        if self.count == 0 {
+            return 0;
+        }
+        let rank = ((percentile / 100.0 * self.count as f64).ceil() as u64).clamp(1, self.count);
+        let mut seen = 0;
+        for (i, &count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return highest_in(i).clamp(self.min, self.max);
+            }
+        }
+        self.max
+    }
+}
+
+fn index(value: u64) -> usize {
+    if value < SUB_BUCKETS {
+        return value as usize;
+    }
+    let shift = 63 - value.leading_zeros() - SUB_BITS;
+    (((shift + 1) as u64) << SUB_BITS | ((value >> shift) - SUB_BUCKETS)) as usize
+}
+
+/// Largest value that lands in bucket `index`
+fn highest_in(index: usize) -> u64 {
+    let index = index as u64;
+    if index < SUB_BUCKETS {
+        return index;
+    }
+    let shift = (index >> SUB_BITS) - 1;
+    let sub = index & (SUB_BUCKETS - 1);
+    ((SUB_BUCKETS + sub) << shift) + ((1u64 << shift) - 1)
+}
+
+fn micros(ns: u64) -> f64 {
+    ns as f64 / 1000.0
+}
+
+/// One row per channel: count, the percentiles and the max, in µs
+pub fn print_table(channels: &[Histogram]) {
+    println!("\n┌─────────┬───────────┬──────────┬──────────┬──────────┬──────────┬────────────┐");
+    println!("│ Channel │ Successful│ p50 (µs) │ p90 (µs) │ p99 (µs) │ p99.9 µs │ Max (µs)   │");
+    println!("├─────────┼───────────┼──────────┼──────────┼──────────┼──────────┼────────────┤");
+    for (i, histogram) in channels.iter().enumerate() {
+        let [p50, p90, p99, p999] = PERCENTILES.map(|p| micros(histogram.percentile(p)));
+        println!(
+            "│  {:>3}    │ {:>8}  │ {:>8.2} │ {:>8.2} │ {:>8.2} │ {:>8.2} │ {:>10.2} │",
+            i, histogram.count(), p50, p90, p99, p999, micros(histogram.max())
+        );
+    }
+    println!("└─────────┴───────────┴──────────┴──────────┴──────────┴──────────┴────────────┘");
+}
+
+/// The percentiles of `histogram` as bars on a log scale from its min to
+/// its max, so the tail's distance from the median shows at a glance
+pub fn print_curve(histogram: &Histogram) {
+    const WIDTH: f64 = 40.0;
+    let (min, max) = (histogram.min().max(1) as f64, histogram.max().max(1) as f64);
+    let span = (max / min).ln();
+    for percentile in CURVE {
+        let value = histogram.percentile(percentile);
+        let bar = if span > 0.0 { ((value.max(1) as f64 / min).ln() / span * WIDTH).round() as usize } else { 0 };
+        println!("   {:>7} {:>10.2} µs │{}", format!("p{}", percentile), micros(value), "█".repeat(bar.max(1)));
+    }
+}
+
+/// One run as a JSON object: its parameters, then the aggregate and each
+/// channel's percentiles in nanoseconds
+pub fn run_json(params: &[(&str, f64)], channels: &[Histogram], aggregate: &Histogram) -> String {
+    let mut json = String::from("{");
+    for (name, value) in params {
+        write!(json, "\"{}\": {}, ", name, value).unwrap();
+    }
+    write!(json, "\"aggregate\": {}, \"per_channel\": [", percentiles_json(aggregate)).unwrap();
+    let channels: Vec<String> = channels.iter().map(percentiles_json).collect();
+    json + &channels.join(", ") + "]}"
+}
+
+fn percentiles_json(histogram: &Histogram) -> String {
+    let mut json = format!("{{\"count\": {}, \"mean_ns\": {:.1}, \"min_ns\": {}", histogram.count(), histogram.mean(), histogram.min());
+    for percentile in PERCENTILES {
+        write!(json, ", \"p{}_ns\": {}", percentile, histogram.percentile(percentile)).unwrap();
+    }
+    write!(json, ", \"max_ns\": {}}}", histogram.max()).unwrap();
+    json
+}
+
+/// `runs` (from [`run_json`]) with the example's name, for diffing runs
+pub fn write_json(path: &Path, example: &str, runs: &[String]) -> std::io::Result<()> {
+    let json = format!("{{\n  \"example\": \"{}\",\n  \"runs\": [\n    {}\n  ]\n}}\n", example, runs.join(",\n    "));
+    std::fs::write(path, json)
+}
+
+/// The `--json FILE` argument, if given
+pub fn json_arg() -> Option<std::path::PathBuf> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--json" {
+            return args.next().map(Into::into);
+        }
+    }
+    None
+}
@@ -4,7 +4,6 @@
 //! from connected shells.
 
 use venom_memory::{ChannelConfig, DaemonChannel};
-use std::io::{self, Write};
 
 fn main() {
     let namespace = std::env::args()
@@ -21,6 +20,7 @@ fn main() {
         data_size: 64 * 1024,  // 64KB
         cmd_slots: 32,
         max_clients: 16,
+        ..ChannelConfig::default()
     };
 
     let daemon = match DaemonChannel::create(&namespace, config) {
@@ -0,0 +1,155 @@
+//! Raw Mapping - a channel over memory mapped by someone else
+//!
+//! The daemon maps a file on tmpfs with `memmap2` and lays a channel over
+//! the mapping with `VenomShm::from_raw_parts` and
+//! `DaemonChannel::create_in`; the client maps the same file and connects
+//! with `ShellChannel::connect_in`. Commands, responses and data go through
+//! the channel as they would over a named one.
+//!
+//! The `MmapMut` keeps the mapping, so the handles are made with `owned`
+//! false and the `MmapMut` kept alive for as long as the channel. Any other
+//! crate's mapping, a `shared_memory::Shmem` say, goes the same way.
+//!
+//! ```text
+//! cargo run --example raw_mapping             # both
+//! cargo run --example raw_mapping -- daemon   # just the daemon
+//! cargo run --example raw_mapping -- client   # just the client
+//! ```
+
+use memmap2::MmapMut;
+use std::fs::OpenOptions;
+use std::os::unix::fs::OpenOptionsExt;
+use std::time::{Duration, Instant};
+use venom_memory::shm::VenomShm;
+use venom_memory::{ChannelConfig, DaemonChannel, ShellChannel};
+
+const PATH: &str = "/dev/shm/venom_raw_mapping.region";
+const COMMANDS: u32 = 10;
+
+fn main() {
+    println!("╔═══════════════════════════════════════════════════════════════╗");
+    println!("║         VenomMemory Rust - Raw Mapping                        ║");
+    println!("║         a channel over a file mapped by hand                  ║");
+    println!("╚═══════════════════════════════════════════════════════════════╝");
+
+    match std::env::args().nth(1).as_deref() {
+        Some("daemon") => daemon(),
+        Some("client") => client(),
+        _ => {
+            let daemon = std::thread::spawn(daemon);
+            // Give the daemon time to lay the channel out
+            std::thread::sleep(Duration::from_millis(200));
+            client();
+            daemon.join().unwrap();
+        }
+    }
+}
+
+/// Map all of `PATH` shared, read-write, creating it `len` bytes long if
+/// `len` is given
+fn map(len: Option<usize>) -> std::io::Result<MmapMut> {
+    let file = OpenOptions::new().read(true).write(true).create(len.is_some()).truncate(false).mode(0o600).open(PATH)?;
+    if let Some(len) = len {
+        file.set_len(len as u64)?;
+    }
+    // SAFETY: the file is this example's, and changes under the mapping
+    // only through the channel
+    unsafe { MmapMut::map_mut(&file) }
+}
+
+/// A handle on `mapping`, which must outlive it and anything built on it
+fn view(mapping: &mut MmapMut) -> VenomShm {
+    // SAFETY: a MAP_SHARED mapping the caller keeps alive; `owned` false,
+    // as the MmapMut unmaps it
+    unsafe { VenomShm::from_raw_parts(mapping.as_mut_ptr(), mapping.len(), false) }.expect("empty mapping")
+}
+
+fn daemon() {
+    let config = ChannelConfig { max_clients: 4, ..ChannelConfig::default() };
+    let len = config.region_size().unwrap();
+    let mut mapping = match map(Some(len)) {
+        Ok(mapping) => mapping,
+        Err(e) => {
+            eprintln!("[Daemon] Failed to map {}: {}", PATH, e);
+            std::process::exit(1);
+        }
+    };
+
+    let daemon = match DaemonChannel::create_in(view(&mut mapping), config) {
+        Ok(daemon) => daemon,
+        Err(e) => {
+            eprintln!("[Daemon] Failed to create channel: {}", e);
+            std::process::exit(1);
+        }
+    };
+    println!("[Daemon] Channel in {} ({} bytes)", PATH, len);
+
+    // Answer each command with its bytes reversed, and keep the last one
+    // in the data region
+    let mut buf = vec![0u8; daemon.cmd_payload_size()];
+    let mut handled = 0;
+    let start = Instant::now();
+    while handled < COMMANDS && start.elapsed() < Duration::from_secs(10) {
+        match daemon.try_recv_command(&mut buf) {
+            Some((client_id, len)) => {
+                let cmd = &mut buf[..len];
+                daemon.write_data_with_len(cmd);
+                cmd.reverse();
+                if let Err(e) = daemon.respond(client_id, cmd) {
+                    eprintln!("[Daemon] Failed to respond to client {}: {}", client_id, e);
+                }
+                handled += 1;
+            }
+            None => std::thread::sleep(Duration::from_micros(100)),
+        }
+    }
+    println!("[Daemon] Handled {} commands", handled);
+
+    // The file is this example's to remove; the channel has no name to
+    let _ = std::fs::remove_file(PATH);
+}
+
+fn client() {
+    let mut mapping = match map(None) {
+        Ok(mapping) => mapping,
+        Err(e) => {
+            eprintln!("[Client] Failed to map {}: {}", PATH, e);
+            std::process::exit(1);
+        }
+    };
+
+    let shell = match ShellChannel::connect_in(view(&mut mapping)) {
+        Ok(shell) => shell,
+        Err(e) => {
+            eprintln!("[Client] Failed to connect: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let region = shell.data_region();
+    println!("[Client] Connected as client {}; data region of {} bytes at {:p}", shell.client_id(), region.len, region.ptr);
+
+    let mut buf = vec![0u8; shell.cmd_payload_size()];
+    for i in 0..COMMANDS {
+        let cmd = format!("command {}", i);
+        match shell.request_timeout(cmd.as_bytes(), &mut buf, Duration::from_secs(1)) {
+            Ok(Some(len)) => {
+                let mut data = [0u8; 64];
+                let data_len = shell.read_data_with_len(&mut data);
+                println!(
+                    "[Client] sent {:?}, got {:?}, data {:?}",
+                    cmd,
+                    String::from_utf8_lossy(&buf[..len]),
+                    String::from_utf8_lossy(&data[..data_len])
+                );
+            }
+            Ok(None) => {
+                eprintln!("[Client] No response to {:?}", cmd);
+                std::process::exit(1);
+            }
+            Err(e) => {
+                eprintln!("[Client] {:?} failed: {}", cmd, e);
+                std::process::exit(1);
+            }
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! Serialized Messages Example
+//!
+//! A daemon thread publishes an evolving, enum-based message type and a shell
+//! thread decodes it - no hand-written framing or #[repr(C)] structs.
+//!
+//! Run with: cargo run --example serde_messages --features serde
+
+use serde::{Deserialize, Serialize};
+use std::thread;
+use std::time::Duration;
+use venom_memory::{ChannelConfig, DaemonChannel, ShellChannel};
+
+#[derive(Debug, Serialize, Deserialize)]
+enum Event {
+    Started { version: String },
+    Progress { step: u32, total: u32 },
+    Warning(String),
+    Finished,
+}
+
+fn main() {
+    let namespace = "serde_demo";
+    let daemon = DaemonChannel::create(namespace, ChannelConfig::default())
+        .expect("Failed to create channel");
+
+    println!("[Daemon] Channel '{}' created", namespace);
+
+    let reader = thread::spawn(move || {
+        let shell = ShellChannel::connect(namespace).expect("Failed to connect");
+        let mut last = String::new();
+
+        loop {
+            match shell.read_deserialized::<Event>() {
+                Ok(event) => {
+                    let text = format!("{:?}", event);
+                    if text != last {
+                        println!("[Shell] {}", text);
+                        last = text;
+                    }
+                    if matches!(event, Event::Finished) {
+                        break;
+                    }
+                }
+                // Nothing published yet
+                Err(_) => thread::sleep(Duration::from_millis(1)),
+            }
+        }
+    });
+
+    let events = [
+        Event::Started {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        },
+        Event::Progress { step: 1, total: 3 },
+        Event::Warning("disk almost full".to_string()),
+        Event::Progress { step: 2, total: 3 },
+        Event::Progress { step: 3, total: 3 },
+        Event::Finished,
+    ];
+
+    for event in &events {
+        daemon.write_serialized(event).expect("Failed to publish");
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    reader.join().unwrap();
+    println!("[Daemon] Done");
+}
@@ -47,7 +47,7 @@ fn main() {
     let mut stdout = io::stdout();
 
     // Check for single command mode
-    if let Some(cmd) = std::env::args().nth(2) {
+    if std::env::args().nth(2).is_some() {
         let args: Vec<String> = std::env::args().skip(2).collect();
         let full_cmd = args.join(" ");
         execute_command(&shell, &full_cmd);
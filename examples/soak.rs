@@ -0,0 +1,291 @@
+//! Soak - one channel kept busy for a long time, watching for leaks
+//!
+//! A daemon writes and answers commands while a churn thread connects and
+//! drops shells, 10 000 a round, and a client reads and sends commands
+//! without a break. Every report prints the process's RSS, open fds and
+//! mappings (`/proc/self`) along with the channel's counters; all of them
+//! should stay flat however long it runs, the counters aside.
+//!
+//! With a duration, the last report is checked against the first: RSS may
+//! grow by [`RSS_SLACK`], mappings by [`MAPS_SLACK`], and fds by the one
+//! the churn thread may hold open at either. The run fails otherwise, or if
+//! the client lost a response.
+//!
+//! At the hundred thousand connects a second or so of a release build, the
+//! client IDs wrap around after half a day; left running that long, the
+//! soak goes through it.
+//!
+//! ```text
+//! cargo run --release --example soak                  # until killed
+//! cargo run --release --example soak -- --short       # 10 minutes, checked
+//! cargo run --release --example soak -- --minutes 60  # an hour, checked
+//! ```
+//!
+//! `--shells N` sets the shells a round, `--report SECS` the time between
+//! reports (30 by default).
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use venom_memory::seqlock::is_newer;
+use venom_memory::{ChannelConfig, DaemonChannel, ShellChannel, VenomError};
+
+const NAMESPACE: &str = "venom_soak";
+/// Minutes of `--short`
+const SHORT_MINUTES: f64 = 10.0;
+/// RSS the last report may have over the first
+const RSS_SLACK: u64 = 4 * 1024 * 1024;
+/// Mappings the last report may have over the first, for the allocator's
+const MAPS_SLACK: usize = 8;
+/// Fds the last report may have over the first: the churn thread's shell
+const FDS_SLACK: usize = 1;
+
+/// What the threads count, for the reports
+#[derive(Default)]
+struct Counters {
+    connects: AtomicU64,
+    rounds: AtomicU64,
+    reads: AtomicU64,
+    requests: AtomicU64,
+    /// Requests that got no response in time
+    missed: AtomicU64,
+    /// Data the client read with a sequence older than one it read before
+    out_of_order: AtomicU64,
+}
+
+/// What `/proc/self` says about the process
+#[derive(Debug, Clone, Copy)]
+struct Usage {
+    rss: u64,
+    fds: usize,
+    maps: usize,
+}
+
+impl Usage {
+    fn now() -> Self {
+        let statm = std::fs::read_to_string("/proc/self/statm").unwrap_or_default();
+        let pages: u64 = statm.split_whitespace().nth(1).and_then(|pages| pages.parse().ok()).unwrap_or(0);
+        let fds = std::fs::read_dir("/proc/self/fd").map(|dir| dir.count()).unwrap_or(0);
+        let maps = std::fs::read_to_string("/proc/self/maps").map(|maps| maps.lines().count()).unwrap_or(0);
+        Usage { rss: pages * rustix::param::page_size() as u64, fds, maps }
+    }
+}
+
+struct Args {
+    minutes: Option<f64>,
+    shells: u64,
+    report: Duration,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args { minutes: None, shells: 10_000, report: Duration::from_secs(30) };
+    let mut argv = std::env::args().skip(1);
+    while let Some(arg) = argv.next() {
+        let mut value = |name: &str| argv.next().ok_or_else(|| format!("{} needs a value", name));
+        match arg.as_str() {
+            "--short" => args.minutes = Some(SHORT_MINUTES),
+            "--minutes" => args.minutes = Some(value("--minutes")?.parse().map_err(|e| format!("--minutes: {}", e))?),
+            "--shells" => args.shells = value("--shells")?.parse().map_err(|e| format!("--shells: {}", e))?,
+            "--report" => {
+                let secs: f64 = value("--report")?.parse().map_err(|e| format!("--report: {}", e))?;
+                args.report = Duration::from_secs_f64(secs);
+            }
+            other => return Err(format!("unknown argument {}", other)),
+        }
+    }
+    Ok(args)
+}
+
+fn main() {
+    println!("╔═══════════════════════════════════════════════════════════════╗");
+    println!("║         VenomMemory Rust - Soak                               ║");
+    println!("║         churning shells, watching RSS, fds and counters       ║");
+    println!("╚═══════════════════════════════════════════════════════════════╝");
+
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}", e);
+            eprintln!("Usage: soak [--short | --minutes N] [--shells N] [--report SECS]");
+            std::process::exit(2);
+        }
+    };
+    let config = ChannelConfig { data_size: 4096, cmd_slots: 32, max_clients: 16, ..ChannelConfig::default() };
+    // A run killed before left its channel behind
+    let daemon = match DaemonChannel::create_force(NAMESPACE, config) {
+        Ok(daemon) => daemon,
+        Err(e) => {
+            eprintln!("Failed to create channel: {}", e);
+            std::process::exit(1);
+        }
+    };
+    match args.minutes {
+        Some(minutes) => println!("Running for {} minutes, {} shells a round", minutes, args.shells),
+        None => println!("Running until killed, {} shells a round", args.shells),
+    }
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let counters = Arc::new(Counters::default());
+    let churn = {
+        let (stop, counters) = (Arc::clone(&stop), Arc::clone(&counters));
+        thread::spawn(move || churn(&stop, &counters, args.shells))
+    };
+    let client = {
+        let (stop, counters) = (Arc::clone(&stop), Arc::clone(&counters));
+        thread::spawn(move || client(&stop, &counters))
+    };
+
+    // The daemon: a write every 100 µs, and an answer to every command
+    let start = Instant::now();
+    let deadline = args.minutes.map(|minutes| start + Duration::from_secs_f64(minutes * 60.0));
+    let mut next_report = start + args.report;
+    let mut first: Option<Usage> = None;
+    let mut last = Usage::now();
+    let mut buf = [0u8; 64];
+    let mut writes = 0u64;
+    while deadline.is_none_or(|deadline| Instant::now() < deadline) {
+        writes += 1;
+        daemon.write_data_with_len(&writes.to_le_bytes());
+        while let Some((client_id, len)) = daemon.try_recv_command(&mut buf) {
+            // Churned shells may be gone before their answer
+            match daemon.respond(client_id, &buf[..len]) {
+                Ok(()) | Err(VenomError::InvalidClientId(_)) => {}
+                Err(e) => eprintln!("[Daemon] Failed to respond to client {}: {}", client_id, e),
+            }
+        }
+        if Instant::now() >= next_report {
+            next_report += args.report;
+            last = Usage::now();
+            first.get_or_insert(last);
+            report(start, &daemon, &counters, last);
+        }
+        thread::sleep(Duration::from_micros(100));
+    }
+
+    stop.store(true, Ordering::Release);
+    churn.join().unwrap();
+    client.join().unwrap();
+    if deadline.is_none() {
+        return;
+    }
+
+    let Some(first) = first else {
+        println!("Too short for a report; nothing to compare");
+        return;
+    };
+    println!("\nFirst report: {:?}", first);
+    println!("Last report:  {:?}", last);
+    let mut failures = Vec::new();
+    if last.rss > first.rss + RSS_SLACK {
+        failures.push(format!("RSS grew by {} KiB", (last.rss - first.rss) / 1024));
+    }
+    if last.fds > first.fds + FDS_SLACK {
+        failures.push(format!("{} fds open, {} at first", last.fds, first.fds));
+    }
+    if last.maps > first.maps + MAPS_SLACK {
+        failures.push(format!("{} mappings, {} at first", last.maps, first.maps));
+    }
+    for (count, what) in [(&counters.missed, "responses missed"), (&counters.out_of_order, "reads out of order")] {
+        let count = count.load(Ordering::Relaxed);
+        if count > 0 {
+            failures.push(format!("{} {}", count, what));
+        }
+    }
+    if failures.is_empty() {
+        println!("✅ Flat: no growth in RSS, fds or mappings");
+    } else {
+        for failure in &failures {
+            eprintln!("❌ {}", failure);
+        }
+        std::process::exit(1);
+    }
+}
+
+fn report(start: Instant, daemon: &DaemonChannel, counters: &Counters, usage: Usage) {
+    let stats = daemon.stats();
+    println!(
+        "[{:>7.1}s] RSS {} KiB, {} fds, {} maps | {} connects in {} rounds, {} connected | {} reads, {} requests ({} missed) | {} commands, {} corrupt",
+        start.elapsed().as_secs_f64(),
+        usage.rss / 1024,
+        usage.fds,
+        usage.maps,
+        counters.connects.load(Ordering::Relaxed),
+        counters.rounds.load(Ordering::Relaxed),
+        daemon.client_count(),
+        counters.reads.load(Ordering::Relaxed),
+        counters.requests.load(Ordering::Relaxed),
+        counters.missed.load(Ordering::Relaxed),
+        stats.commands_received,
+        stats.corrupt_slots,
+    );
+}
+
+/// Connect and drop shells, `shells` a round, each sending a command and
+/// reading the data before it goes
+fn churn(stop: &AtomicBool, counters: &Counters, shells: u64) {
+    let mut buf = [0u8; 64];
+    while !stop.load(Ordering::Acquire) {
+        for _ in 0..shells {
+            if stop.load(Ordering::Acquire) {
+                return;
+            }
+            match ShellChannel::connect_named(NAMESPACE, "churn") {
+                Ok(shell) => {
+                    shell.try_send_command(b"hello");
+                    shell.read_data_with_len(&mut buf);
+                    counters.connects.fetch_add(1, Ordering::Relaxed);
+                }
+                // Every slot taken for a moment, by shells on their way out
+                Err(VenomError::TooManyClients { .. }) => thread::yield_now(),
+                Err(e) => {
+                    eprintln!("[Churn] Failed to connect: {}", e);
+                    thread::sleep(Duration::from_millis(10));
+                }
+            }
+        }
+        counters.rounds.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Read the data and make requests for as long as the run lasts, from one
+/// shell
+fn client(stop: &AtomicBool, counters: &Counters) {
+    let shell = match ShellChannel::connect_named(NAMESPACE, "client") {
+        Ok(shell) => shell,
+        Err(e) => {
+            eprintln!("[Client] Failed to connect: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let mut buf = [0u8; 64];
+    let mut last_sequence = 0;
+    let mut request = 0u64;
+    while !stop.load(Ordering::Acquire) {
+        for _ in 0..16 {
+            let frame = shell.snapshot();
+            if let Some(frame) = frame {
+                if last_sequence != 0 && is_newer(last_sequence, frame.generation) {
+                    counters.out_of_order.fetch_add(1, Ordering::Relaxed);
+                }
+                last_sequence = frame.generation;
+            }
+            counters.reads.fetch_add(1, Ordering::Relaxed);
+        }
+
+        request += 1;
+        let cmd = request.to_le_bytes();
+        match shell.request_timeout(&cmd, &mut buf, Duration::from_secs(1)) {
+            Ok(Some(len)) if buf[..len] == cmd => {}
+            Ok(_) => {
+                counters.missed.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(e) => {
+                eprintln!("[Client] Request failed: {}", e);
+                counters.missed.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        counters.requests.fetch_add(1, Ordering::Relaxed);
+        thread::sleep(Duration::from_micros(200));
+    }
+}
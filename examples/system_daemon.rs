@@ -112,6 +112,7 @@ fn main() {
         data_size: std::mem::size_of::<SystemStats>() + 64,
         cmd_slots: 16,
         max_clients: 8,
+        ..ChannelConfig::default()
     };
     
     let daemon = DaemonChannel::create("system_monitor", config)
@@ -142,8 +143,10 @@ fn main() {
         let curr_times = read_cpu_times();
         
         // Calculate usage
-        let mut stats = SystemStats::default();
-        stats.core_count = (curr_times.len().saturating_sub(1)).min(16) as u32;
+        let mut stats = SystemStats {
+            core_count: (curr_times.len().saturating_sub(1)).min(16) as u32,
+            ..Default::default()
+        };
         
         // Check if we should fake 100%
         let fake_mode = fake_until.map(|t| Instant::now() < t).unwrap_or(false);
@@ -0,0 +1,62 @@
+//! Tokio Client Example
+//!
+//! Connects to the example daemon without blocking the runtime: sends a few
+//! requests, then prints data updates as they arrive.
+//!
+//! Usage:
+//!   cargo run --example daemon
+//!   cargo run --example tokio_client --features async
+
+use std::time::Duration;
+use venom_memory::AsyncShellChannel;
+
+#[tokio::main]
+async fn main() {
+    let namespace = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "venom_demo".to_string());
+
+    let mut shell = match AsyncShellChannel::connect(&namespace) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("[Tokio] Failed to connect: {}", e);
+            eprintln!("[Tokio] Make sure the daemon is running first!");
+            std::process::exit(1);
+        }
+    };
+
+    println!(
+        "[Tokio] Connected! Client ID: {}",
+        shell.inner().client_id()
+    );
+
+    for cmd in ["ping", "time", "stats"] {
+        match shell.request(cmd.as_bytes()).await {
+            Ok(response) => println!("[Tokio] {} -> {}", cmd, String::from_utf8_lossy(&response)),
+            Err(e) => eprintln!("[Tokio] {} failed: {}", cmd, e),
+        }
+    }
+
+    println!("[Tokio] Watching for updates for 10s...");
+    let watch = async {
+        loop {
+            let update = shell.next_update().await;
+            println!("[Tokio] Update: {}", String::from_utf8_lossy(&update));
+        }
+    };
+
+    // Other tasks keep running while we wait
+    let ticker = async {
+        let mut interval = tokio::time::interval(Duration::from_secs(2));
+        loop {
+            interval.tick().await;
+            println!("[Tokio] (runtime still responsive)");
+        }
+    };
+
+    tokio::select! {
+        _ = watch => {}
+        _ = ticker => {}
+        _ = tokio::time::sleep(Duration::from_secs(10)) => {}
+    }
+}
@@ -4,7 +4,17 @@
 //! - Direct shared memory access
 //! - No command queue overhead for large data
 //! - Pure SeqLock reads
+//!
+//! `--json FILE` also writes each run's parameters and latency percentiles
+//! to FILE.
+
+#[path = "common/pinning.rs"]
+mod pinning;
+#[path = "common/stats.rs"]
+mod stats;
 
+use pinning::Pinning;
+use stats::Histogram;
 use std::sync::{Arc, Barrier};
 use std::sync::atomic::{AtomicU64, AtomicBool, Ordering};
 use std::thread;
@@ -98,50 +108,17 @@ fn cleanup_channel(name: &str) {
     }
 }
 
-struct ChannelStats {
-    successful: AtomicU64,
-    total_latency_ns: AtomicU64,
-    min_latency_ns: AtomicU64,
-    max_latency_ns: AtomicU64,
-}
-
-impl ChannelStats {
-    fn new() -> Self {
-        Self {
-            successful: AtomicU64::new(0),
-            total_latency_ns: AtomicU64::new(0),
-            min_latency_ns: AtomicU64::new(u64::MAX),
-            max_latency_ns: AtomicU64::new(0),
-        }
-    }
-
-    #[inline(always)]
-    fn record(&self, latency_ns: u64) {
-        self.successful.fetch_add(1, Ordering::Relaxed);
-        self.total_latency_ns.fetch_add(latency_ns, Ordering::Relaxed);
-        
-        // Relaxed min/max - not perfectly accurate but fast
-        let min = self.min_latency_ns.load(Ordering::Relaxed);
-        if latency_ns < min {
-            self.min_latency_ns.store(latency_ns, Ordering::Relaxed);
-        }
-        let max = self.max_latency_ns.load(Ordering::Relaxed);
-        if latency_ns > max {
-            self.max_latency_ns.store(latency_ns, Ordering::Relaxed);
-        }
-    }
-}
-
-fn run_test(num_channels: usize, data_size: usize, iterations: u64) {
+/// Returns the run as JSON, for `--json`
+fn run_test(num_channels: usize, data_size: usize, iterations: u64) -> String {
     println!("\n═══════════════════════════════════════════════════════════════");
     println!("Testing: {} parallel channels, {} bytes ({:.2} KB)", 
              num_channels, data_size, data_size as f64 / 1024.0);
+    let pinning = Pinning::new();
+    for line in pinning.describe(num_channels) {
+        println!("  {}", line);
+    }
     println!("═══════════════════════════════════════════════════════════════");
     
-    let stats: Vec<Arc<ChannelStats>> = (0..num_channels)
-        .map(|_| Arc::new(ChannelStats::new()))
-        .collect();
-    
     let start_barrier = Arc::new(Barrier::new(num_channels * 2 + 1));
     let stop_flag = Arc::new(AtomicBool::new(false));
     
@@ -162,14 +139,15 @@ fn run_test(num_channels: usize, data_size: usize, iterations: u64) {
     
     // Spawn writer (daemon) threads
     let mut writer_handles = Vec::new();
-    for i in 0..num_channels {
+    for (i, &(ptr, _size)) in channels.iter().enumerate() {
         let start_barrier = Arc::clone(&start_barrier);
         let stop_flag = Arc::clone(&stop_flag);
-        let (ptr, _size) = channels[i];
         let ptr_addr = ptr as usize;  // Convert to usize for Send
         let test_data = test_data.clone();
+        let writer_core = pinning.writer_core(i);
         
         let handle = thread::spawn(move || {
+            pinning::enter(&format!("writer-{}", i), writer_core);
             let ptr = ptr_addr as *mut u8;
             let header = ptr as *mut ChannelData;
             let data_ptr = unsafe { ptr.add(std::mem::size_of::<ChannelData>()) };
@@ -213,20 +191,20 @@ fn run_test(num_channels: usize, data_size: usize, iterations: u64) {
     let mut reader_handles = Vec::new();
     for i in 0..num_channels {
         let start_barrier = Arc::clone(&start_barrier);
-        let stats = Arc::clone(&stats[i]);
-        let data_size = data_size;
-        let iterations = iterations;
         
         // Open the channel
         let name = format!("ch_{}", i);
         let (ptr, _) = open_channel(&name);
         let ptr_addr = ptr as usize;  // Convert to usize for Send
+        let reader_core = pinning.reader_core(i);
         
         let handle = thread::spawn(move || {
+            pinning::enter(&format!("reader-{}", i), reader_core);
             let ptr = ptr_addr as *mut u8;
             let header = ptr as *mut ChannelData;
             let data_ptr = unsafe { ptr.add(std::mem::size_of::<ChannelData>()) };
             let mut read_buf = vec![0u8; data_size];
+            let mut histogram = Histogram::new();
             
             start_barrier.wait();
             
@@ -266,8 +244,9 @@ fn run_test(num_channels: usize, data_size: usize, iterations: u64) {
                 }
                 
                 let elapsed = start.elapsed().as_nanos() as u64;
-                stats.record(elapsed);
+                histogram.record(elapsed);
             }
+            histogram
         });
         reader_handles.push(handle);
     }
@@ -277,9 +256,7 @@ fn run_test(num_channels: usize, data_size: usize, iterations: u64) {
     start_barrier.wait();
     
     // Wait for readers to complete
-    for handle in reader_handles {
-        handle.join().unwrap();
-    }
+    let histograms: Vec<Histogram> = reader_handles.into_iter().map(|h| h.join().unwrap()).collect();
     
     let test_duration = test_start.elapsed();
     
@@ -288,45 +265,14 @@ fn run_test(num_channels: usize, data_size: usize, iterations: u64) {
     thread::sleep(Duration::from_millis(10));
     
     // Print results
-    println!("\n┌─────────┬───────────┬──────────┬──────────────┐");
-    println!("│ Channel │ Successful│ Avg (µs) │ Max (µs)     │");
-    println!("├─────────┼───────────┼──────────┼──────────────┤");
-    
-    let mut total_successful: u64 = 0;
-    let mut total_latency_ns: u64 = 0;
-    let mut global_min_ns: u64 = u64::MAX;
-    let mut global_max_ns: u64 = 0;
-    
-    for (i, stat) in stats.iter().enumerate() {
-        let successful = stat.successful.load(Ordering::Relaxed);
-        let latency_ns = stat.total_latency_ns.load(Ordering::Relaxed);
-        let min_ns = stat.min_latency_ns.load(Ordering::Relaxed);
-        let max_ns = stat.max_latency_ns.load(Ordering::Relaxed);
-        
-        let avg_us = if successful > 0 {
-            latency_ns as f64 / successful as f64 / 1000.0
-        } else {
-            0.0
-        };
-        let max_us = max_ns as f64 / 1000.0;
-        
-        println!("│  {:>3}    │  {:>8}  │  {:>7.2} │  {:>11.2} │",
-                 i, successful, avg_us, max_us);
-        
-        total_successful += successful;
-        total_latency_ns += latency_ns;
-        global_min_ns = global_min_ns.min(min_ns);
-        global_max_ns = global_max_ns.max(max_ns);
+    stats::print_table(&histograms);
+    let mut aggregate = Histogram::new();
+    for histogram in &histograms {
+        aggregate.merge(histogram);
     }
-    
-    println!("└─────────┴───────────┴──────────┴──────────────┘");
+    let total_successful = aggregate.count();
     
     let duration_secs = test_duration.as_secs_f64();
-    let avg_latency_us = if total_successful > 0 {
-        total_latency_ns as f64 / total_successful as f64 / 1000.0
-    } else {
-        0.0
-    };
     let throughput = total_successful as f64 / duration_secs;
     // Bandwidth: each request sends nothing, receives data_size (bidirectional would double)
     let bandwidth_mb = throughput * (data_size as f64 * 2.0) / 1_000_000.0;
@@ -336,19 +282,27 @@ fn run_test(num_channels: usize, data_size: usize, iterations: u64) {
     println!("   Channels:         {}", num_channels);
     println!("   Total successful: {} / {}", total_successful, num_channels as u64 * iterations);
     println!("   Test duration:    {:.2} seconds", duration_secs);
-    println!("   Avg latency:      {:.2} µs", avg_latency_us);
-    println!("   Min latency:      {:.2} µs", global_min_ns as f64 / 1000.0);
-    println!("   Max latency:      {:.2} µs ({:.2} ms)", 
-             global_max_ns as f64 / 1000.0, global_max_ns as f64 / 1_000_000.0);
+    println!("   Mean latency:     {:.2} µs", aggregate.mean() / 1000.0);
     println!("   ⚡ THROUGHPUT:     {:.0} req/s (total)", throughput);
     println!("   📶 BANDWIDTH:      {:.2} MB/s = {:.2} GB/s (total, bidirectional)", 
              bandwidth_mb, bandwidth_gb);
+    println!("\n📈 LATENCY PERCENTILES (all channels):");
+    stats::print_curve(&aggregate);
     
     // Cleanup
     for i in 0..num_channels {
         let name = format!("ch_{}", i);
         cleanup_channel(&name);
     }
+    
+    let params = [
+        ("channels", num_channels as f64),
+        ("data_size", data_size as f64),
+        ("iterations", iterations as f64),
+        ("duration_secs", duration_secs),
+        ("throughput_rps", throughput),
+    ];
+    stats::run_json(&params, &histograms, &aggregate)
 }
 
 fn main() {
@@ -357,16 +311,26 @@ fn main() {
     println!("║   Goal: Beat 23.3 GB/s with 4 channels @ 256KB                ║");
     println!("║   Available CPUs: {}                                           ║", 
              std::thread::available_parallelism().map(|p| p.get()).unwrap_or(1));
+    println!("║   Pinning: channel i's writer on core 2i, reader on 2i+1      ║");
     println!("╚═══════════════════════════════════════════════════════════════╝");
     
+    let json = stats::json_arg();
+    
     // Warm up
-    run_test(2, 1024, 10_000);
+    let mut runs = vec![run_test(2, 1024, 10_000)];
     
     // THE TARGET: 4 channels, 256KB
-    run_test(4, 256 * 1024, ITERATIONS);
+    runs.push(run_test(4, 256 * 1024, ITERATIONS));
     
     // Also test 8 channels
-    run_test(4, 256 * 1024, ITERATIONS);
+    runs.push(run_test(4, 256 * 1024, ITERATIONS));
+    
+    if let Some(path) = json {
+        match stats::write_json(&path, "ultra_test", &runs) {
+            Ok(()) => println!("\nResults written to {}", path.display()),
+            Err(e) => eprintln!("\nFailed to write {}: {}", path.display(), e),
+        }
+    }
     
     println!("\n╔═══════════════════════════════════════════════════════════════╗");
     println!("║                    Test Complete!                             ║");
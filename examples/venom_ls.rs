@@ -0,0 +1,85 @@
+//! venom_ls - List VenomMemory channels on this machine
+//!
+//! Usage:
+//!   cargo run --example venom_ls               # print a table of channels
+//!   cargo run --example venom_ls -- --events   # with each channel's recent events
+//!   cargo run --example venom_ls -- --gc       # remove channels whose daemon died
+//!   cargo run --example venom_ls -- --gc --dry-run
+
+use std::time::SystemTime;
+use venom_memory::{discover, ShellChannel};
+
+fn format_size(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else if bytes >= 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let gc = args.iter().any(|a| a == "--gc");
+    let dry_run = args.iter().any(|a| a == "--dry-run");
+    let events = args.iter().any(|a| a == "--events");
+
+    if gc {
+        match discover::gc(dry_run) {
+            Ok(removed) if removed.is_empty() => println!("No stale channels found"),
+            Ok(removed) => {
+                let verb = if dry_run { "Would remove" } else { "Removed" };
+                for namespace in removed {
+                    println!("{} {}", verb, namespace);
+                }
+            }
+            Err(e) => {
+                eprintln!("[venom_ls] gc failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let channels = discover::list_channels();
+    if channels.is_empty() {
+        println!("No VenomMemory channels found");
+        return;
+    }
+
+    println!(
+        "{:<32} {:>10} {:>4} {:>8} {:>6} {:>7}",
+        "NAMESPACE", "SIZE", "VER", "PID", "STATE", "CLIENTS"
+    );
+    for ch in channels {
+        println!(
+            "{:<32} {:>10} {:>4} {:>8} {:>6} {:>7}",
+            ch.namespace,
+            format_size(ch.size),
+            ch.version,
+            ch.owner_pid,
+            if ch.owner_alive { "live" } else { "dead" },
+            ch.client_count
+        );
+        if events {
+            print_events(&ch.namespace);
+        }
+    }
+}
+
+/// The channel's event log, oldest first, with how long ago each happened
+fn print_events(namespace: &str) {
+    let shell = match ShellChannel::connect_read_only(namespace) {
+        Ok(shell) => shell,
+        Err(e) => {
+            println!("    (can't read events: {})", e);
+            return;
+        }
+    };
+    let now = SystemTime::now();
+    for logged in shell.recent_events() {
+        let age = now.duration_since(logged.time).unwrap_or_default();
+        println!("    {:>9.1}s ago  {}", age.as_secs_f64(), logged.event);
+    }
+}
@@ -0,0 +1,39 @@
+//! Raw bytes as a command queue region (header and slots, or less of it)
+//! the way a hostile or buggy shell could leave it, drained by the daemon
+//!
+//! `cargo +nightly fuzz run mpsc_pop -- -max_len=300000`
+//!
+//! A region that can't hold what its header claims has to be refused;
+//! any other has to be popped without reading outside it, and no command
+//! may come out longer than a slot holds. The first byte sizes the
+//! daemon's buffer.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use std::alloc::{alloc_zeroed, dealloc, Layout};
+use venom_memory::mpsc_queue::{MpscConsumer, MAX_CMD_SIZE, MAX_SLOTS};
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&buf_size, region)) = data.split_first() else { return };
+
+    // Exactly the region's bytes, so reading past them is caught
+    let layout = Layout::from_size_align(region.len().max(1), 64).unwrap();
+    unsafe {
+        let ptr = alloc_zeroed(layout);
+        ptr.copy_from_nonoverlapping(region.as_ptr(), region.len());
+
+        if let Ok(consumer) = MpscConsumer::from_region(ptr, region.len()) {
+            let mut buf = vec![0u8; buf_size as usize * 64];
+            // Twice around the largest ring
+            for _ in 0..2 * MAX_SLOTS {
+                if let Ok(Some((_, len))) = consumer.try_pop_checked(&mut buf) {
+                    assert!(len <= consumer.cmd_payload_size() && len <= MAX_CMD_SIZE);
+                }
+                let _ = consumer.try_pop(&mut buf);
+            }
+        }
+
+        dealloc(ptr, layout);
+    }
+});
@@ -0,0 +1,50 @@
+//! Raw bytes as the data region of a channel, read back the way shells
+//! read length-prefixed data
+//!
+//! `cargo +nightly fuzz run read_with_len`
+//!
+//! Whatever the length prefix says (zero, more than the region, more than
+//! the buffer), reading has to stay inside the region and the buffer, and
+//! the length returned can't be more than the region holds. The first byte
+//! sizes one of the buffers.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use std::alloc::{alloc_zeroed, dealloc, Layout};
+use venom_memory::layout::seqlock::LEN_PREFIX;
+use venom_memory::seqlock::{SeqLockHeader, SeqLockReader};
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&buf_size, region)) = data.split_first() else { return };
+    let header_size = std::mem::size_of::<SeqLockHeader>();
+    let capacity = region.len().saturating_sub(LEN_PREFIX);
+
+    // Exactly the header and the region's bytes, so reading past them is caught
+    let layout = Layout::from_size_align(header_size + region.len(), 64).unwrap();
+    unsafe {
+        let ptr = alloc_zeroed(layout);
+        SeqLockHeader::init(ptr.cast(), region.len());
+        let data_ptr = ptr.add(header_size);
+        data_ptr.copy_from_nonoverlapping(region.as_ptr(), region.len());
+        let reader = SeqLockReader::from_raw(ptr.cast(), data_ptr);
+
+        for size in [0, buf_size as usize, capacity, region.len() + 64] {
+            let mut buf = vec![0u8; size];
+            let len = reader.read_with_len(&mut buf);
+            assert!(len <= capacity);
+
+            let copied = len.min(size);
+            if copied > 0 {
+                assert_eq!(buf[..copied], region[LEN_PREFIX..LEN_PREFIX + copied]);
+            }
+
+            match reader.read_with_len_checked(&mut buf) {
+                Ok(checked) => assert_eq!(checked, len),
+                Err(_) => assert_eq!(len, capacity),
+            }
+        }
+
+        dealloc(ptr, layout);
+    }
+});
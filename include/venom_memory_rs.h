@@ -5,6 +5,9 @@
 #include <stdint.h>
 #include <stdbool.h>
 
+// No function here unwinds a panic into the caller: one inside the library
+// returns the function's failure value (NULL, false, 0, or its error code)
+
 typedef struct VenomDaemonHandle VenomDaemonHandle;
 typedef struct VenomShellHandle VenomShellHandle;
 
@@ -14,23 +17,132 @@ typedef struct {
     size_t max_clients;
 } VenomConfig;
 
+// One part of a venom_daemon_write_vectored write, like struct iovec
+typedef struct {
+    const uint8_t* base;  // may be NULL when len is 0
+    size_t len;
+} VenomIoVec;
+
+// Set struct_size to sizeof(VenomConfigEx): fields past it take their
+// defaults, so code built against an older header keeps working
+typedef struct {
+    size_t struct_size;
+    size_t data_size;
+    size_t cmd_slots;
+    size_t max_clients;
+    size_t cmd_payload_size;  // longest command, 1 to 4096, rounded up to 64; 0 for 4096
+    size_t cmd_full_policy;   // a VENOM_CMD_FULL_* value; anything else fails
+    size_t shm_mode;          // permission bits of the shared memory, e.g. 0660; 0 for 0664 less the umask
+} VenomConfigEx;
+
+// Set struct_size to sizeof(VenomStats): only the fields that fit are
+// written, so code built against an older header keeps working
+typedef struct {
+    size_t struct_size;
+    uint64_t commands_received;         // taken off the queue, corrupt ones included
+    uint64_t corrupt_slots;             // dropped as corrupt
+    uint64_t dropped_commands;          // dropped by shells under VENOM_CMD_FULL_OVERWRITE_OLDEST
+    uint64_t discarded_large_commands;  // chunked messages discarded unfinished
+    uint64_t total_full_events;         // sends that found the queue full
+} VenomStats;
+
+// What venom_shell_read_frame read, all from the same write
+typedef struct {
+    size_t len;            // bytes stored in the buffer
+    uint64_t generation;   // even, never 0; later writes are larger until it wraps past UINT64_MAX
+    uint64_t timestamp_ns; // as venom_shell_read_data_stamped stores it; 0 if unstamped
+    bool truncated;        // the write was longer than max_len
+} VenomFrame;
+
+// What sending does when every command slot is full
+#define VENOM_CMD_FULL_REJECT 0           // the send fails
+#define VENOM_CMD_FULL_OVERWRITE_OLDEST 1 // the oldest unread command is dropped
+
+// Longest name venom_shell_connect_named registers
+#define VENOM_CLIENT_NAME_MAX 32
+
+// Longest message venom_shell_send_large_command sends
+#define VENOM_MAX_LARGE_COMMAND (1 << 20)
+
+// What venom_shell_send_command_timeout returns
+#define VENOM_SEND_OK 0
+#define VENOM_SEND_TIMEOUT (-1) // the queue stayed full for timeout_ms
+#define VENOM_SEND_ERROR (-2)   // command too long, or a read-only shell
+
+// What venom_shell_request returns when it gets no response
+#define VENOM_REQUEST_TIMEOUT (-1) // none within timeout_ms
+#define VENOM_REQUEST_ERROR (-2)   // command too long, read-only shell, or a daemon without responses
+
 #ifdef __cplusplus
 extern "C" {
 #endif
 
-// Daemon
+// Daemon; NULL if a running daemon owns the name (one left by a dead daemon is taken over)
 VenomDaemonHandle* venom_daemon_create(const char* name, VenomConfig config);
+VenomDaemonHandle* venom_daemon_create_ex(const char* name, const VenomConfigEx* config);
+// Also removes the channel's name, unless another daemon has taken it over
 void venom_daemon_destroy(VenomDaemonHandle* handle);
+// Remove the name now: no new shells; false if already gone or taken over
+bool venom_daemon_unlink(VenomDaemonHandle* handle);
 void venom_daemon_write_data(VenomDaemonHandle* handle, const uint8_t* data, size_t len);
+// The parts one after another as one write, read back as their concatenation
+void venom_daemon_write_vectored(VenomDaemonHandle* handle, const VenomIoVec* iov, size_t count);
 uint8_t* venom_daemon_get_shm_ptr(VenomDaemonHandle* handle);
+// Spins until a command arrives; returns its length and stores the sender's client ID
+size_t venom_daemon_recv_command(VenomDaemonHandle* handle, uint8_t* buf, size_t max_len, uint32_t* out_client_id);
+// 0 if no command is waiting
+size_t venom_daemon_try_recv_command(VenomDaemonHandle* handle, uint8_t* buf, size_t max_len, uint32_t* out_client_id);
+// To that shell alone, for its venom_shell_request; replaces a response it
+// hasn't taken. False if it isn't connected or data is longer than a command
+bool venom_daemon_respond(VenomDaemonHandle* handle, uint32_t client_id, const uint8_t* data, size_t len);
+// False if stats is NULL or its struct_size too small
+bool venom_daemon_stats(VenomDaemonHandle* handle, VenomStats* stats);
+uint32_t venom_daemon_client_count(VenomDaemonHandle* handle);
+// Shells stay connected; false if data_size is smaller than now
+bool venom_daemon_resize_data(VenomDaemonHandle* handle, size_t data_size);
+// NUL-terminated into buf; returns its length, 0 if unknown or unnamed
+size_t venom_daemon_client_name(VenomDaemonHandle* handle, uint32_t client_id, char* buf, size_t max_len);
+// A chunked message, or a plain command; 0 if none is whole yet. Returns the
+// full length, which may exceed max_len (the rest is cut); a
+// VENOM_MAX_LARGE_COMMAND buffer holds any
+size_t venom_daemon_try_recv_large_command(VenomDaemonHandle* handle, uint8_t* buf, size_t max_len, uint32_t* out_client_id);
 
 // Shell
 VenomShellHandle* venom_shell_connect(const char* name);
+// client_name is cut to VENOM_CLIENT_NAME_MAX bytes of valid UTF-8; duplicates are fine
+VenomShellHandle* venom_shell_connect_named(const char* name, const char* client_name);
 void venom_shell_destroy(VenomShellHandle* handle);
+// Returns at most max_len; 0 if the length in shared memory is corrupt
 size_t venom_shell_read_data(VenomShellHandle* handle, uint8_t* buf, size_t max_len);
+// Also stores the CLOCK_MONOTONIC_RAW ns of the daemon's write (0 if unstamped)
+size_t venom_shell_read_data_stamped(VenomShellHandle* handle, uint8_t* buf, size_t max_len, uint64_t* out_timestamp_ns);
+// False before the daemon's first write, or if the length is corrupt
+bool venom_shell_read_frame(VenomShellHandle* handle, uint8_t* buf, size_t max_len, VenomFrame* out_frame);
 uint32_t venom_shell_id(VenomShellHandle* handle);
+// False once the daemon has exited; reads then return its last data
+bool venom_shell_daemon_alive(VenomShellHandle* handle);
 bool venom_shell_send_command(VenomShellHandle* handle, const uint8_t* cmd, size_t len);
+// Waits up to timeout_ms for room in a full queue; a VENOM_SEND_* value
+int32_t venom_shell_send_command_timeout(VenomShellHandle* handle, const uint8_t* cmd, size_t len, uint32_t timeout_ms);
+// Sends cmd and waits for the daemon's venom_daemon_respond, a full queue
+// included; the response length (cut to resp_max) or VENOM_REQUEST_*
+ptrdiff_t venom_shell_request(VenomShellHandle* handle, const uint8_t* cmd, size_t cmd_len, uint8_t* resp_buf, size_t resp_max, uint32_t timeout_ms);
+// Up to VENOM_MAX_LARGE_COMMAND bytes in chunks; spins while the queue is full
+bool venom_shell_send_large_command(VenomShellHandle* handle, const uint8_t* data, size_t len);
+// payload behind a 4-byte envelope: tag, then len, each a little-endian
+// uint16_t; spins while the queue is full
+bool venom_shell_send_typed(VenomShellHandle* handle, uint16_t tag, const uint8_t* payload, size_t len);
 const uint8_t* venom_shell_get_shm_ptr(VenomShellHandle* handle);
+// Longest payload a read returns; size read buffers with it (grows on resize)
+size_t venom_channel_data_capacity(VenomShellHandle* handle);
+// Commands not yet taken by the daemon, up to cmd_slots; advisory
+size_t venom_shell_cmd_queue_len(VenomShellHandle* handle);
+
+// Now, in the clock of the timestamps above (same machine only)
+uint64_t venom_clock_ns(void);
+
+// Crate version the library was built from, like "0.1.0" (static, don't free)
+const char* venom_version(void);
 
 #ifdef __cplusplus
 }
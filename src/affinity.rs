@@ -0,0 +1,155 @@
+//! Pinning threads to CPU cores, and naming them
+//!
+//! A daemon's writer usually wants a core of its own and readers spread
+//! over the others; without pinning, the scheduler moving them around shows
+//! up as latency outliers. [`ChannelConfig::writer_core`](crate::ChannelConfig::writer_core)
+//! pins the thread that creates a daemon.
+//!
+//! Everything here acts on the calling thread, and needs Linux: elsewhere
+//! it fails with [`VenomError::Unsupported`].
+
+use crate::error::{Result, VenomError};
+
+/// Bytes of a thread name Linux keeps
+pub const THREAD_NAME_MAX: usize = 15;
+
+/// Keep the calling thread on `core`
+pub fn pin_current_thread(core: usize) -> Result<()> {
+    pin_to_cores(&[core])
+}
+
+/// Keep the calling thread on `cores`, between which the scheduler may
+/// still move it
+///
+/// Fails with [`VenomError::InvalidConfig`] if `cores` is empty or names a
+/// core past the last one Linux supports, and with [`VenomError::Thread`]
+/// if none of them is one the thread may run on.
+pub fn pin_to_cores(cores: &[usize]) -> Result<()> {
+    imp::pin_to_cores(cores)
+}
+
+/// Cores the calling thread may run on, lowest first
+pub fn thread_cores() -> Result<Vec<usize>> {
+    imp::thread_cores()
+}
+
+/// Name the calling thread, for `top -H`, `ps -L` and debuggers
+///
+/// Names longer than [`THREAD_NAME_MAX`] bytes are cut to it, at a
+/// character boundary; one with a NUL byte fails with
+/// [`VenomError::InvalidConfig`].
+pub fn set_thread_name(name: &str) -> Result<()> {
+    let mut end = name.len().min(THREAD_NAME_MAX);
+    while !name.is_char_boundary(end) {
+        end -= 1;
+    }
+    let name = std::ffi::CString::new(&name[..end])
+        .map_err(|_| VenomError::InvalidConfig { reason: format!("thread name {:?} has a NUL byte", name) })?;
+    imp::set_thread_name(&name)
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use super::*;
+    use rustix::process::{sched_getaffinity, sched_setaffinity, CpuSet};
+    use std::ffi::CStr;
+
+    pub(super) fn pin_to_cores(cores: &[usize]) -> Result<()> {
+        if cores.is_empty() {
+            return Err(VenomError::InvalidConfig { reason: "no cores to pin the thread to".to_string() });
+        }
+        let mut set = CpuSet::new();
+        for &core in cores {
+            if core >= CpuSet::MAX_CPU {
+                return Err(VenomError::InvalidConfig {
+                    reason: format!("core {} is past the last one Linux supports ({})", core, CpuSet::MAX_CPU - 1),
+                });
+            }
+            set.set(core);
+        }
+        // No pid: the calling thread, not the whole process
+        sched_setaffinity(None, &set).map_err(|e| VenomError::Thread {
+            action: format!("pin the thread to cores {:?}", cores),
+            source: e.into(),
+        })
+    }
+
+    pub(super) fn thread_cores() -> Result<Vec<usize>> {
+        let set = sched_getaffinity(None).map_err(|e| VenomError::Thread {
+            action: "read the thread's cores".to_string(),
+            source: e.into(),
+        })?;
+        Ok((0..CpuSet::MAX_CPU).filter(|&core| set.is_set(core)).collect())
+    }
+
+    pub(super) fn set_thread_name(name: &CStr) -> Result<()> {
+        rustix::thread::set_name(name).map_err(|e| VenomError::Thread {
+            action: format!("name the thread {:?}", name),
+            source: e.into(),
+        })
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod imp {
+    use super::*;
+    use std::ffi::CStr;
+
+    fn unsupported<T>(feature: &str) -> Result<T> {
+        Err(VenomError::Unsupported { feature: feature.to_string() })
+    }
+
+    pub(super) fn pin_to_cores(_cores: &[usize]) -> Result<()> {
+        unsupported("Pinning threads to cores")
+    }
+
+    pub(super) fn thread_cores() -> Result<Vec<usize>> {
+        unsupported("Reading a thread's cores")
+    }
+
+    pub(super) fn set_thread_name(_name: &CStr) -> Result<()> {
+        unsupported("Naming threads")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Run `f` on a thread of its own, so pinning doesn't outlive the test
+    fn on_own_thread(f: impl FnOnce() + Send) {
+        std::thread::scope(|scope| {
+            scope.spawn(f);
+        });
+    }
+
+    #[test]
+    fn test_pin_current_thread() {
+        on_own_thread(|| {
+            let cores = thread_cores().unwrap();
+            let last = *cores.last().unwrap();
+            pin_current_thread(last).unwrap();
+            assert_eq!(thread_cores().unwrap(), [last]);
+
+            pin_to_cores(&cores).unwrap();
+            assert_eq!(thread_cores().unwrap(), cores);
+
+            assert!(matches!(pin_to_cores(&[]), Err(VenomError::InvalidConfig { .. })));
+            assert!(matches!(pin_current_thread(usize::MAX), Err(VenomError::InvalidConfig { .. })));
+            assert_eq!(thread_cores().unwrap(), cores);
+        });
+    }
+
+    #[test]
+    fn test_set_thread_name() {
+        on_own_thread(|| {
+            let comm = || std::fs::read_to_string("/proc/thread-self/comm").unwrap().trim_end().to_string();
+            set_thread_name("venom-writer").unwrap();
+            assert_eq!(comm(), "venom-writer");
+            // 14 bytes, then a 2-byte 'é' that would straddle the limit
+            set_thread_name("venom-lecteur-é").unwrap();
+            assert_eq!(comm(), "venom-lecteur-");
+            assert!(matches!(set_thread_name("nul\0"), Err(VenomError::InvalidConfig { .. })));
+        });
+    }
+}
@@ -0,0 +1,246 @@
+//! Async shell for tokio (requires the `async` feature)
+//!
+//! Wraps a [`ShellChannel`] so it can wait for updates and responses without
+//! spinning on an executor thread. Waiting is adaptive:
+//!
+//! 1. a short busy-spin (updates often land within a few hundred ns)
+//! 2. a handful of cooperative yields back to the scheduler
+//! 3. a timer-backed poll every [`POLL_INTERVAL`] until something changes
+
+use crate::channel::snapshot::Frame;
+use crate::channel::ShellChannel;
+use crate::error::{Result, VenomError};
+use futures_core::Stream;
+use std::future::{poll_fn, Future};
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use std::time::Duration;
+use tokio::time::Sleep;
+
+/// Busy-spin iterations before yielding to the runtime
+const SPIN_LIMIT: u32 = 64;
+
+/// Cooperative yields before falling back to the timer
+const YIELD_LIMIT: u32 = 16;
+
+/// Interval of the timer-backed poll
+pub const POLL_INTERVAL: Duration = Duration::from_micros(500);
+
+/// Spin -> yield -> sleep backoff for polling shared memory
+struct Backoff {
+    spins: u32,
+    yields: u32,
+    sleep: Option<Pin<Box<Sleep>>>,
+}
+
+impl Backoff {
+    fn new() -> Self {
+        Self {
+            spins: 0,
+            yields: 0,
+            sleep: None,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.spins = 0;
+        self.yields = 0;
+        self.sleep = None;
+    }
+
+    /// Returns `Ready` when the caller should check again
+    fn poll_wait(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.spins < SPIN_LIMIT {
+            self.spins += 1;
+            core::hint::spin_loop();
+            return Poll::Ready(());
+        }
+
+        if self.yields < YIELD_LIMIT {
+            self.yields += 1;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+
+        let sleep = self
+            .sleep
+            .get_or_insert_with(|| Box::pin(tokio::time::sleep(POLL_INTERVAL)));
+        ready!(sleep.as_mut().poll(cx));
+        self.sleep = None;
+        Poll::Ready(())
+    }
+}
+
+/// Async wrapper around [`ShellChannel`]
+pub struct AsyncShellChannel {
+    inner: ShellChannel,
+    last_seq: u64,
+    backoff: Backoff,
+}
+
+impl AsyncShellChannel {
+    /// Connect to an existing channel
+    pub fn connect(namespace: &str) -> Result<Self> {
+        ShellChannel::connect(namespace).map(Self::new)
+    }
+
+    /// Wrap an already connected shell
+    pub fn new(inner: ShellChannel) -> Self {
+        Self {
+            inner,
+            last_seq: 0,
+            backoff: Backoff::new(),
+        }
+    }
+
+    /// Access the underlying synchronous shell
+    pub fn inner(&self) -> &ShellChannel {
+        &self.inner
+    }
+
+    /// Wait for the daemon to publish new data and return it as a [`Frame`]
+    ///
+    /// The first call returns immediately if the daemon has already written
+    /// something since the channel was created.
+    pub async fn next_update(&mut self) -> Frame {
+        poll_fn(|cx| self.poll_update(cx)).await
+    }
+
+    /// Send a command and wait for the daemon's response
+    ///
+    /// The response is the next write to the data region after the command
+    /// was queued.
+    pub async fn request(&self, cmd: &[u8]) -> Result<Vec<u8>> {
+        if cmd.len() > self.inner.cmd_payload_size() {
+            return Err(VenomError::BufferOverflow {
+                max: self.inner.cmd_payload_size(),
+                got: cmd.len(),
+            });
+        }
+
+        let start_seq = self.inner.data_sequence();
+        let mut backoff = Backoff::new();
+
+        poll_fn(|cx| loop {
+            if self.inner.try_send_command(cmd) {
+                return Poll::Ready(());
+            }
+            ready!(backoff.poll_wait(cx));
+        })
+        .await;
+
+        backoff.reset();
+        poll_fn(|cx| loop {
+            let seq = self.inner.data_sequence();
+            if seq & 1 == 0 && seq != start_seq {
+                return Poll::Ready(());
+            }
+            ready!(backoff.poll_wait(cx));
+        })
+        .await;
+
+        Ok(self.read_latest())
+    }
+
+    fn read_latest(&self) -> Vec<u8> {
+        let mut buf = vec![0u8; self.inner.data_size()];
+        let len = self.inner.read_data_with_len(&mut buf);
+        buf.truncate(len);
+        buf
+    }
+
+    fn poll_update(&mut self, cx: &mut Context<'_>) -> Poll<Frame> {
+        loop {
+            let seq = self.inner.data_sequence();
+            if seq & 1 == 0 && seq != self.last_seq {
+                // None if the prefix is corrupt: wait for the next write
+                if let Some(frame) = self.inner.snapshot() {
+                    self.last_seq = frame.generation;
+                    self.backoff.reset();
+                    return Poll::Ready(frame);
+                }
+            }
+            ready!(self.backoff.poll_wait(cx));
+        }
+    }
+}
+
+impl From<ShellChannel> for AsyncShellChannel {
+    fn from(inner: ShellChannel) -> Self {
+        Self::new(inner)
+    }
+}
+
+/// Endless stream of data updates
+impl Stream for AsyncShellChannel {
+    type Item = Frame;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_update(cx).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mpsc_queue::MAX_CMD_SIZE;
+    use crate::{ChannelConfig, DaemonChannel};
+    use std::thread;
+    use std::time::Instant;
+
+    #[tokio::test]
+    async fn test_next_update_fast_path() {
+        let daemon = DaemonChannel::create("test_async_fast", ChannelConfig::default()).unwrap();
+        daemon.write_data_with_len(b"already here");
+
+        let mut shell = AsyncShellChannel::connect("test_async_fast").unwrap();
+        let start = Instant::now();
+        let frame = shell.next_update().await;
+        assert_eq!(&*frame, b"already here");
+        assert_eq!(frame.generation, shell.inner().data_sequence());
+        assert!(start.elapsed() < Duration::from_millis(10));
+    }
+
+    #[tokio::test]
+    async fn test_next_update_slow_path() {
+        let daemon = DaemonChannel::create("test_async_slow", ChannelConfig::default()).unwrap();
+        let mut shell = AsyncShellChannel::connect("test_async_slow").unwrap();
+
+        let writer = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            daemon.write_data_with_len(b"late update");
+            daemon
+        });
+
+        let start = Instant::now();
+        let update = poll_fn(|cx| Pin::new(&mut shell).poll_next(cx)).await;
+        assert_eq!(update.as_deref(), Some(&b"late update"[..]));
+        assert!(!update.unwrap().truncated);
+        assert!(start.elapsed() >= Duration::from_millis(40));
+
+        drop(writer.join().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_request_response() {
+        let daemon = DaemonChannel::create("test_async_request", ChannelConfig::default()).unwrap();
+        let shell = AsyncShellChannel::connect("test_async_request").unwrap();
+
+        let server = thread::spawn(move || {
+            let mut buf = [0u8; 64];
+            let (_, len) = daemon.recv_command(&mut buf);
+            let mut response = b"echo:".to_vec();
+            response.extend_from_slice(&buf[..len]);
+            daemon.write_data_with_len(&response);
+            daemon
+        });
+
+        assert_eq!(shell.request(b"ping").await.unwrap(), b"echo:ping");
+        assert!(matches!(
+            shell.request(&[0u8; MAX_CMD_SIZE + 1]).await,
+            Err(VenomError::BufferOverflow { .. })
+        ));
+
+        drop(server.join().unwrap());
+    }
+}
@@ -1,13 +1,16 @@
-//! C Bindings for VenomMemory
+//! C Bindings for VenomMemory (the `bindings` feature, on by default)
 //!
 //! Provides a raw C API for creating and connecting to channels.
 
-use crate::channel::{ChannelConfig, DaemonChannel, ShellChannel};
-use crate::error::Result;
-use std::ffi::{CStr, CString};
-use std::os::raw::{c_char, c_void};
+use crate::channel::{ChannelConfig, DaemonChannel, ShellChannel, CLIENT_NAME_MAX};
+use crate::mpsc_queue::CmdFullPolicy;
+use crate::shm::ShmPermissions;
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::slice;
 use std::ptr;
+use std::time::Duration;
 
 // Opaque handles
 pub struct VenomDaemonHandle(DaemonChannel);
@@ -20,8 +23,90 @@ pub struct VenomConfig {
     pub max_clients: usize,
 }
 
+/// One part of a `venom_daemon_write_vectored` write, like `struct iovec`
+#[repr(C)]
+pub struct VenomIoVec {
+    pub base: *const u8,
+    pub len: usize,
+}
+
+/// Configuration of `venom_daemon_create_ex`
+///
+/// `struct_size` is `sizeof(VenomConfigEx)` as the caller was compiled:
+/// fields past it take their defaults, so callers built against an older
+/// header keep working when fields are added.
+#[repr(C)]
+pub struct VenomConfigEx {
+    pub struct_size: usize,
+    pub data_size: usize,
+    pub cmd_slots: usize,
+    pub max_clients: usize,
+    /// 0 for the default
+    pub cmd_payload_size: usize,
+    /// 0 to fail sends on a full queue, 1 to drop the oldest command
+    /// ([`CmdFullPolicy`])
+    pub cmd_full_policy: usize,
+    /// Permission bits of the shared memory object ([`ShmPermissions`]);
+    /// 0 for the default, 0664 less the umask
+    pub shm_mode: usize,
+}
+
+/// Command counters of a daemon, filled in by `venom_daemon_stats`
+///
+/// `struct_size` is `sizeof(VenomStats)` as the caller was compiled: only
+/// the fields that fit in it are written, so callers built against an
+/// older header keep working when fields are added.
+#[repr(C)]
+pub struct VenomStats {
+    pub struct_size: usize,
+    pub commands_received: u64,
+    pub corrupt_slots: u64,
+    pub dropped_commands: u64,
+    pub discarded_large_commands: u64,
+    pub total_full_events: u64,
+}
+
+/// What `venom_shell_read_frame` read along with the payload
+#[repr(C)]
+pub struct VenomFrame {
+    /// Bytes stored in the buffer
+    pub len: usize,
+    /// Sequence number of the write: even, never 0, and newer for every
+    /// later write, wrapping around after `u64::MAX`
+    pub generation: u64,
+    /// As `venom_shell_read_data_stamped` stores it; 0 if unstamped
+    pub timestamp_ns: u64,
+    /// The payload was longer than the buffer, which holds its start
+    pub truncated: bool,
+}
+
+/// `venom_shell_send_command_timeout` sent the command
+pub const VENOM_SEND_OK: i32 = 0;
+/// `venom_shell_send_command_timeout` found the queue full until the timeout
+pub const VENOM_SEND_TIMEOUT: i32 = -1;
+/// `venom_shell_send_command_timeout` failed: the command is too long or
+/// the shell read-only
+pub const VENOM_SEND_ERROR: i32 = -2;
+
+/// `venom_shell_request` timed out
+pub const VENOM_REQUEST_TIMEOUT: isize = -1;
+/// `venom_shell_request` failed: the command is too long, the shell
+/// read-only, or the daemon older than per-client responses
+pub const VENOM_REQUEST_ERROR: isize = -2;
+
+/// Run the body of an exported function, returning `fallback` instead of
+/// unwinding into the caller's C code if it panics
+fn guard<T>(fallback: T, body: impl FnOnce() -> T) -> T {
+    catch_unwind(AssertUnwindSafe(body)).unwrap_or(fallback)
+}
+
 /// Create a new daemon channel
 ///
+/// Commands get the default payload size; `venom_daemon_create_ex` sets it.
+/// Returns null if another daemon that is still running owns the name; a
+/// channel left behind by one that died is taken over, as by
+/// `DaemonChannel::create_force`.
+///
 /// # Safety
 /// name must be a valid null-terminated string
 #[no_mangle]
@@ -29,6 +114,67 @@ pub unsafe extern "C" fn venom_daemon_create(
     name: *const c_char,
     config: VenomConfig,
 ) -> *mut VenomDaemonHandle {
+    guard(ptr::null_mut(), || {
+        create(name, ChannelConfig {
+            data_size: config.data_size,
+            cmd_slots: config.cmd_slots,
+            max_clients: config.max_clients,
+            ..ChannelConfig::default()
+        })
+    })
+}
+
+/// Create a new daemon channel from a [`VenomConfigEx`]
+///
+/// Returns null if `config` is null, its `struct_size` is too small for
+/// the fields `VenomConfig` has, `cmd_full_policy` is neither 0 nor 1, or
+/// `shm_mode` has bits besides the permission bits.
+///
+/// # Safety
+/// name must be a valid null-terminated string, and config must be valid
+/// for reads of `struct_size` bytes
+#[no_mangle]
+pub unsafe extern "C" fn venom_daemon_create_ex(
+    name: *const c_char,
+    config: *const VenomConfigEx,
+) -> *mut VenomDaemonHandle {
+    guard(ptr::null_mut(), || {
+        let has = |field: usize| (*config).struct_size >= field + std::mem::size_of::<usize>();
+        if config.is_null() || !has(std::mem::offset_of!(VenomConfigEx, max_clients)) {
+            return ptr::null_mut();
+        }
+        let defaults = ChannelConfig::default();
+        let cmd_payload_size = match has(std::mem::offset_of!(VenomConfigEx, cmd_payload_size)) {
+            true if (*config).cmd_payload_size != 0 => (*config).cmd_payload_size,
+            _ => defaults.cmd_payload_size,
+        };
+        let cmd_full_policy = match has(std::mem::offset_of!(VenomConfigEx, cmd_full_policy)) {
+            true => match u8::try_from((*config).cmd_full_policy).ok().and_then(CmdFullPolicy::from_u8) {
+                Some(policy) => policy,
+                None => return ptr::null_mut(),
+            },
+            false => defaults.cmd_full_policy,
+        };
+        let permissions = match has(std::mem::offset_of!(VenomConfigEx, shm_mode)) {
+            true if (*config).shm_mode != 0 => match u32::try_from((*config).shm_mode) {
+                Ok(mode) => ShmPermissions { mode: Some(mode), group: None },
+                Err(_) => return ptr::null_mut(),
+            },
+            _ => defaults.permissions.clone(),
+        };
+        create(name, ChannelConfig {
+            data_size: (*config).data_size,
+            cmd_slots: (*config).cmd_slots,
+            max_clients: (*config).max_clients,
+            cmd_payload_size,
+            cmd_full_policy,
+            permissions,
+            ..defaults
+        })
+    })
+}
+
+unsafe fn create(name: *const c_char, config: ChannelConfig) -> *mut VenomDaemonHandle {
     if name.is_null() {
         return ptr::null_mut();
     }
@@ -39,29 +185,53 @@ pub unsafe extern "C" fn venom_daemon_create(
         Err(_) => return ptr::null_mut(),
     };
 
-    let rust_config = ChannelConfig {
-        data_size: config.data_size,
-        cmd_slots: config.cmd_slots,
-        max_clients: config.max_clients,
-    };
+    // Clients in any language can then tell how old the data they read is
+    let rust_config = ChannelConfig { stamp_writes: true, ..config };
 
-    match DaemonChannel::create(str_slice, rust_config) {
+    // Daemons restarted after a crash would otherwise find their own old channel in the way
+    match DaemonChannel::create_force(str_slice, rust_config) {
         Ok(daemon) => Box::into_raw(Box::new(VenomDaemonHandle(daemon))),
         Err(_) => ptr::null_mut(),
     }
 }
 
 /// Destroy a daemon handle
+///
+/// # Safety
+/// handle must be null or a pointer returned by `venom_daemon_create`
+/// that has not already been destroyed
 #[no_mangle]
 pub unsafe extern "C" fn venom_daemon_destroy(handle: *mut VenomDaemonHandle) {
-    if !handle.is_null() {
-        drop(Box::from_raw(handle));
-    }
+    guard((), || {
+        if !handle.is_null() {
+            drop(Box::from_raw(handle));
+        }
+    })
+}
+
+/// Remove the channel's name now instead of in `venom_daemon_destroy`:
+/// no new shell can connect, connected ones keep reading
+///
+/// Returns false if the name was already removed or belongs to another
+/// daemon by now.
+///
+/// # Safety
+/// handle must be a live daemon handle
+#[no_mangle]
+pub unsafe extern "C" fn venom_daemon_unlink(handle: *mut VenomDaemonHandle) -> bool {
+    guard(false, || {
+        let daemon = &(*handle).0;
+        daemon.unlink().unwrap_or(false)
+    })
 }
 
 /// Daemon: Wait for command (blocking/spinning)
 ///
 /// Returns cmd length. Writes cmd into buf and client_id into out_client_id.
+///
+/// # Safety
+/// handle must be a live daemon handle and buf must be valid for
+/// `max_len` bytes of writes
 #[no_mangle]
 pub unsafe extern "C" fn venom_daemon_recv_command(
     handle: *mut VenomDaemonHandle,
@@ -69,19 +239,25 @@ pub unsafe extern "C" fn venom_daemon_recv_command(
     max_len: usize,
     out_client_id: *mut u32,
 ) -> usize {
-    let daemon = &(*handle).0;
-    let slice = slice::from_raw_parts_mut(buf, max_len);
-    let (client_id, len) = daemon.recv_command(slice);
-    if !out_client_id.is_null() {
-        *out_client_id = client_id;
-    }
-    len
+    guard(0, || {
+        let daemon = &(*handle).0;
+        let slice = slice::from_raw_parts_mut(buf, max_len);
+        let (client_id, len) = daemon.recv_command(slice);
+        if !out_client_id.is_null() {
+            *out_client_id = client_id;
+        }
+        len
+    })
 }
 
 /// Daemon: Try to receive command (non-blocking)
 ///
 /// Returns cmd length if command available, 0 if no command.
 /// Writes cmd into buf and client_id into out_client_id.
+///
+/// # Safety
+/// handle must be a live daemon handle and buf must be valid for
+/// `max_len` bytes of writes
 #[no_mangle]
 pub unsafe extern "C" fn venom_daemon_try_recv_command(
     handle: *mut VenomDaemonHandle,
@@ -89,103 +265,1085 @@ pub unsafe extern "C" fn venom_daemon_try_recv_command(
     max_len: usize,
     out_client_id: *mut u32,
 ) -> usize {
-    let daemon = &(*handle).0;
-    let slice = slice::from_raw_parts_mut(buf, max_len);
-    match daemon.try_recv_command(slice) {
-        Some((client_id, len)) => {
-            if !out_client_id.is_null() {
-                *out_client_id = client_id;
+    guard(0, || {
+        let daemon = &(*handle).0;
+        let slice = slice::from_raw_parts_mut(buf, max_len);
+        match daemon.try_recv_command(slice) {
+            Some((client_id, len)) => {
+                if !out_client_id.is_null() {
+                    *out_client_id = client_id;
+                }
+                len
             }
-            len
+            None => 0,
         }
-        None => 0,
-    }
+    })
+}
+
+/// Daemon: Try to receive a message sent with
+/// `venom_shell_send_large_command`, or a command sent without chunking
+///
+/// Returns the message length, 0 if no message is whole yet. A message
+/// longer than `max_len` is cut to fit and its full length returned; a
+/// buffer of `MAX_LARGE_COMMAND` (1 MiB) bytes holds any. Writes client_id
+/// into out_client_id.
+///
+/// # Safety
+/// handle must be a live daemon handle and buf must be valid for
+/// `max_len` bytes of writes
+#[no_mangle]
+pub unsafe extern "C" fn venom_daemon_try_recv_large_command(
+    handle: *mut VenomDaemonHandle,
+    buf: *mut u8,
+    max_len: usize,
+    out_client_id: *mut u32,
+) -> usize {
+    guard(0, || {
+        let daemon = &(*handle).0;
+        let mut message = Vec::new();
+        match daemon.try_recv_large_command(&mut message) {
+            Some((client_id, len)) => {
+                let copy_len = len.min(max_len);
+                std::ptr::copy_nonoverlapping(message.as_ptr(), buf, copy_len);
+                if !out_client_id.is_null() {
+                    *out_client_id = client_id;
+                }
+                len
+            }
+            None => 0,
+        }
+    })
 }
 
 /// Daemon: Write data to shared memory
+///
+/// # Safety
+/// handle must be a live daemon handle and data must be valid for
+/// `len` bytes of reads
 #[no_mangle]
 pub unsafe extern "C" fn venom_daemon_write_data(
     handle: *mut VenomDaemonHandle,
     data: *const u8,
     len: usize,
 ) {
-    let daemon = &(*handle).0;
-    let slice = slice::from_raw_parts(data, len);
-    daemon.write_data_with_len(slice);
+    guard((), || {
+        let daemon = &(*handle).0;
+        let slice = slice::from_raw_parts(data, len);
+        daemon.write_data_with_len(slice);
+    })
+}
+
+/// Daemon: Write `count` parts one after another as one write, read back
+/// as their concatenation by `venom_shell_read_data`
+///
+/// # Safety
+/// handle must be a live daemon handle; iov must be valid for `count`
+/// parts (or null with a `count` of 0), and each part's base valid for
+/// `len` bytes of reads (or null with a `len` of 0)
+#[no_mangle]
+pub unsafe extern "C" fn venom_daemon_write_vectored(
+    handle: *mut VenomDaemonHandle,
+    iov: *const VenomIoVec,
+    count: usize,
+) {
+    guard((), || {
+        let daemon = &(*handle).0;
+        let iov: &[VenomIoVec] = if iov.is_null() { &[] } else { slice::from_raw_parts(iov, count) };
+        let parts: Vec<&[u8]> = iov
+            .iter()
+            .map(|part| if part.base.is_null() { &[][..] } else { slice::from_raw_parts(part.base, part.len) })
+            .collect();
+        daemon.write_vectored(&parts);
+    })
+}
+
+/// Daemon: Grow the data region to `data_size` bytes; connected shells
+/// move to it on their next read
+///
+/// Returns false, leaving the region as it is, if `data_size` is smaller
+/// than the current size or the channel can't grow.
+///
+/// # Safety
+/// handle must be a live daemon handle
+#[no_mangle]
+pub unsafe extern "C" fn venom_daemon_resize_data(handle: *mut VenomDaemonHandle, data_size: usize) -> bool {
+    guard(false, || {
+        let daemon = &mut (*handle).0;
+        daemon.resize_data(data_size).is_ok()
+    })
+}
+
+/// Daemon: Name the shell with this client ID connected with, for logs
+///
+/// Copies the name into buf, NUL-terminated and cut short if it doesn't
+/// fit in `max_len` bytes, and returns its length without the NUL; 0 if the
+/// client isn't connected or gave no name. Names are at most
+/// `CLIENT_NAME_MAX` (32) bytes.
+///
+/// # Safety
+/// handle must be a live daemon handle and buf must be valid for
+/// `max_len` bytes of writes
+#[no_mangle]
+pub unsafe extern "C" fn venom_daemon_client_name(
+    handle: *mut VenomDaemonHandle,
+    client_id: u32,
+    buf: *mut c_char,
+    max_len: usize,
+) -> usize {
+    guard(0, || {
+        let daemon = &(*handle).0;
+        if buf.is_null() || max_len == 0 {
+            return 0;
+        }
+        let name = daemon.client_name(client_id).unwrap_or_default();
+        let name = name.as_bytes();
+        let len = name.len().min(max_len - 1);
+        ptr::copy_nonoverlapping(name.as_ptr(), buf.cast::<u8>(), len);
+        *buf.add(len) = 0;
+        len
+    })
+}
+
+/// Daemon: Send `len` bytes to the shell with this client ID alone, for
+/// its `venom_shell_request`
+///
+/// A shell holds one response, so one it hasn't taken yet is replaced.
+/// Returns false if no such shell is connected or the response is longer
+/// than the channel's commands.
+///
+/// # Safety
+/// handle must be a live daemon handle and data must be valid for
+/// `len` bytes of reads (or null with a `len` of 0)
+#[no_mangle]
+pub unsafe extern "C" fn venom_daemon_respond(
+    handle: *mut VenomDaemonHandle,
+    client_id: u32,
+    data: *const u8,
+    len: usize,
+) -> bool {
+    guard(false, || {
+        let daemon = &(*handle).0;
+        let slice = if data.is_null() { &[][..] } else { slice::from_raw_parts(data, len) };
+        daemon.respond(client_id, slice).is_ok()
+    })
+}
+
+/// Daemon: Fill in the command counters of the daemon's queue, as far as
+/// `stats->struct_size` reaches
+///
+/// Returns false, writing nothing, if stats is null or its `struct_size`
+/// doesn't reach past itself.
+///
+/// # Safety
+/// handle must be a live daemon handle, and stats valid for writes of
+/// `struct_size` bytes
+#[no_mangle]
+pub unsafe extern "C" fn venom_daemon_stats(handle: *mut VenomDaemonHandle, stats: *mut VenomStats) -> bool {
+    guard(false, || {
+        let fits = |field: usize| (*stats).struct_size >= field + std::mem::size_of::<u64>();
+        if stats.is_null() || !fits(std::mem::offset_of!(VenomStats, commands_received)) {
+            return false;
+        }
+        let daemon = &(*handle).0;
+        let counters = daemon.stats();
+        (*stats).commands_received = counters.commands_received;
+        if fits(std::mem::offset_of!(VenomStats, corrupt_slots)) {
+            (*stats).corrupt_slots = counters.corrupt_slots;
+        }
+        if fits(std::mem::offset_of!(VenomStats, dropped_commands)) {
+            (*stats).dropped_commands = counters.dropped_commands;
+        }
+        if fits(std::mem::offset_of!(VenomStats, discarded_large_commands)) {
+            (*stats).discarded_large_commands = counters.discarded_large_commands;
+        }
+        if fits(std::mem::offset_of!(VenomStats, total_full_events)) {
+            (*stats).total_full_events = counters.total_full_events;
+        }
+        true
+    })
+}
+
+/// Daemon: Number of shells currently connected
+///
+/// # Safety
+/// handle must be a live daemon handle
+#[no_mangle]
+pub unsafe extern "C" fn venom_daemon_client_count(handle: *mut VenomDaemonHandle) -> u32 {
+    guard(0, || {
+        let daemon = &(*handle).0;
+        daemon.client_count()
+    })
 }
 
 /// Get raw pointer to shared memory (offset to data region)
 /// This allows implementing custom zero-copy protocols in C
+///
+/// # Safety
+/// handle must be a live daemon handle
 #[no_mangle]
 pub unsafe extern "C" fn venom_daemon_get_shm_ptr(handle: *mut VenomDaemonHandle) -> *mut u8 {
-    let daemon = &(*handle).0;
-    daemon.as_ptr()
+    guard(ptr::null_mut(), || {
+        let daemon = &(*handle).0;
+        daemon.as_ptr()
+    })
 }
 
 // --- Shell Side ---
 
 /// Connect to an existing channel
+///
+/// # Safety
+/// name must be a valid null-terminated string
 #[no_mangle]
 pub unsafe extern "C" fn venom_shell_connect(name: *const c_char) -> *mut VenomShellHandle {
-    if name.is_null() {
-        return ptr::null_mut();
-    }
+    guard(ptr::null_mut(), || {
+        if name.is_null() {
+            return ptr::null_mut();
+        }
 
-    let c_str = CStr::from_ptr(name);
-    let str_slice = match c_str.to_str() {
-        Ok(s) => s,
-        Err(_) => return ptr::null_mut(),
-    };
+        let c_str = CStr::from_ptr(name);
+        let str_slice = match c_str.to_str() {
+            Ok(s) => s,
+            Err(_) => return ptr::null_mut(),
+        };
 
-    match ShellChannel::connect(str_slice) {
-        Ok(shell) => Box::into_raw(Box::new(VenomShellHandle(shell))),
-        Err(_) => ptr::null_mut(),
-    }
+        match ShellChannel::connect(str_slice) {
+            Ok(shell) => Box::into_raw(Box::new(VenomShellHandle(shell))),
+            Err(_) => ptr::null_mut(),
+        }
+    })
+}
+
+/// Connect to an existing channel under a name the daemon can show
+///
+/// The name is cut to `CLIENT_NAME_MAX` (32) bytes, and further to the
+/// part before any invalid UTF-8; null connects without a name.
+///
+/// # Safety
+/// name must be a valid null-terminated string, and client_name null or one
+#[no_mangle]
+pub unsafe extern "C" fn venom_shell_connect_named(
+    name: *const c_char,
+    client_name: *const c_char,
+) -> *mut VenomShellHandle {
+    guard(ptr::null_mut(), || {
+        if name.is_null() {
+            return ptr::null_mut();
+        }
+
+        let str_slice = match CStr::from_ptr(name).to_str() {
+            Ok(s) => s,
+            Err(_) => return ptr::null_mut(),
+        };
+        let client_name = if client_name.is_null() { &[][..] } else { CStr::from_ptr(client_name).to_bytes() };
+        let client_name = &client_name[..client_name.len().min(CLIENT_NAME_MAX)];
+        let client_name = match std::str::from_utf8(client_name) {
+            Ok(s) => s,
+            Err(e) => std::str::from_utf8(&client_name[..e.valid_up_to()]).unwrap_or_default(),
+        };
+
+        match ShellChannel::connect_named(str_slice, client_name) {
+            Ok(shell) => Box::into_raw(Box::new(VenomShellHandle(shell))),
+            Err(_) => ptr::null_mut(),
+        }
+    })
 }
 
 /// Destroy a shell handle
+///
+/// # Safety
+/// handle must be null or a pointer returned by `venom_shell_connect` or
+/// `venom_shell_connect_named` that has not already been destroyed
 #[no_mangle]
 pub unsafe extern "C" fn venom_shell_destroy(handle: *mut VenomShellHandle) {
-    if !handle.is_null() {
-        drop(Box::from_raw(handle));
-    }
+    guard((), || {
+        if !handle.is_null() {
+            drop(Box::from_raw(handle));
+        }
+    })
 }
 
 /// Shell: Read data from shared memory
-/// Returns bytes read (actual data length, may be larger than buffer)
+/// Returns bytes read: never more than `max_len`, so callers can use it
+/// as the length of `buf`; 0 if the length in shared memory is corrupt
+///
+/// # Safety
+/// handle must be a live shell handle and buf must be null with a
+/// `max_len` of 0, or valid for `max_len` bytes of writes
 #[no_mangle]
 pub unsafe extern "C" fn venom_shell_read_data(
     handle: *mut VenomShellHandle,
     buf: *mut u8,
     max_len: usize,
 ) -> usize {
-    let shell = &(*handle).0;
-    let slice = slice::from_raw_parts_mut(buf, max_len);
-    shell.read_data_with_len(slice)
+    guard(0, || {
+        let shell = &(*handle).0;
+        let slice: &mut [u8] = if buf.is_null() { &mut [] } else { slice::from_raw_parts_mut(buf, max_len) };
+        shell.try_read_data_with_len(slice).map_or(0, |len| len.min(slice.len()))
+    })
+}
+
+/// Shell: Read data from shared memory, like `venom_shell_read_data`, and
+/// store the time the daemon wrote it into `out_timestamp_ns`
+///
+/// The time is in nanoseconds of CLOCK_MONOTONIC_RAW (see `venom_clock_ns`),
+/// so it only compares with clocks read on the same machine; 0 if the
+/// daemon doesn't stamp its writes.
+///
+/// # Safety
+/// As for `venom_shell_read_data`; out_timestamp_ns must be null or valid
+/// for a write
+#[no_mangle]
+pub unsafe extern "C" fn venom_shell_read_data_stamped(
+    handle: *mut VenomShellHandle,
+    buf: *mut u8,
+    max_len: usize,
+    out_timestamp_ns: *mut u64,
+) -> usize {
+    guard(0, || {
+        let shell = &(*handle).0;
+        let slice: &mut [u8] = if buf.is_null() { &mut [] } else { slice::from_raw_parts_mut(buf, max_len) };
+        let (timestamp, len) = shell.try_read_data_with_len_stamped(slice).unwrap_or((0, 0));
+        if !out_timestamp_ns.is_null() {
+            *out_timestamp_ns = timestamp;
+        }
+        len.min(slice.len())
+    })
+}
+
+/// Shell: Read the daemon's latest write into `buf` and describe it in
+/// `out_frame`, the payload, sequence number and timestamp all from the
+/// same read
+///
+/// False, leaving `out_frame` alone, before the daemon's first write or if
+/// the length in shared memory is corrupt.
+///
+/// # Safety
+/// As for `venom_shell_read_data`; out_frame must be valid for a write
+#[no_mangle]
+pub unsafe extern "C" fn venom_shell_read_frame(
+    handle: *mut VenomShellHandle,
+    buf: *mut u8,
+    max_len: usize,
+    out_frame: *mut VenomFrame,
+) -> bool {
+    guard(false, || {
+        let shell = &(*handle).0;
+        let slice: &mut [u8] = if buf.is_null() { &mut [] } else { slice::from_raw_parts_mut(buf, max_len) };
+        let Some((generation, timestamp_ns, len)) = shell.snapshot_into(slice) else {
+            return false;
+        };
+        *out_frame = VenomFrame { len: len.min(slice.len()), generation, timestamp_ns, truncated: len > slice.len() };
+        true
+    })
+}
+
+/// Longest payload the daemon's writes hold whole: a buffer this long
+/// always fits what `venom_shell_read_data` returns
+///
+/// It grows if the daemon calls `venom_daemon_resize_data`.
+///
+/// # Safety
+/// handle must be a live shell handle
+#[no_mangle]
+pub unsafe extern "C" fn venom_channel_data_capacity(handle: *mut VenomShellHandle) -> usize {
+    guard(0, || {
+        let shell = &(*handle).0;
+        shell.data_capacity()
+    })
+}
+
+/// Shell: Commands in the queue that the daemon hasn't taken yet, up to
+/// the channel's `cmd_slots`
+///
+/// Advisory: other shells and the daemon change it meanwhile.
+///
+/// # Safety
+/// handle must be a live shell handle
+#[no_mangle]
+pub unsafe extern "C" fn venom_shell_cmd_queue_len(handle: *mut VenomShellHandle) -> usize {
+    guard(0, || {
+        let shell = &(*handle).0;
+        shell.cmd_queue_len()
+    })
+}
+
+/// Now, in the clock of `venom_shell_read_data_stamped`'s timestamps:
+/// `venom_clock_ns() - timestamp` is how long ago the daemon wrote the data
+#[no_mangle]
+pub extern "C" fn venom_clock_ns() -> u64 {
+    guard(0, || {
+        crate::seqlock::now_ns()
+    })
+}
+
+/// Crate version the library was built from, like `"0.1.0"`; a static
+/// string the caller must not free
+#[no_mangle]
+pub extern "C" fn venom_version() -> *const c_char {
+    guard(ptr::null(), || {
+        concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+    })
 }
 
 /// Shell: Get Client ID
+///
+/// # Safety
+/// handle must be a live shell handle
 #[no_mangle]
 pub unsafe extern "C" fn venom_shell_id(handle: *mut VenomShellHandle) -> u32 {
-    let shell = &(*handle).0;
-    shell.client_id()
+    guard(0, || {
+        let shell = &(*handle).0;
+        shell.client_id()
+    })
+}
+
+/// Whether the daemon that created the channel is still running; once it
+/// isn't, reads return the last data it wrote
+///
+/// # Safety
+/// handle must be a live shell handle
+#[no_mangle]
+pub unsafe extern "C" fn venom_shell_daemon_alive(handle: *mut VenomShellHandle) -> bool {
+    guard(false, || {
+        let shell = &(*handle).0;
+        shell.daemon_alive()
+    })
 }
 
 /// Shell: Send command
+///
+/// # Safety
+/// handle must be a live shell handle and cmd must be valid for
+/// `len` bytes of reads (or null with a `len` of 0)
 #[no_mangle]
 pub unsafe extern "C" fn venom_shell_send_command(
     handle: *mut VenomShellHandle,
     cmd: *const u8,
     len: usize,
 ) -> bool {
-    let shell = &(*handle).0;
-    let slice = slice::from_raw_parts(cmd, len);
-    shell.try_send_command(slice)
+    guard(false, || {
+        let shell = &(*handle).0;
+        let slice = if cmd.is_null() { &[][..] } else { slice::from_raw_parts(cmd, len) };
+        shell.try_send_command(slice)
+    })
+}
+
+/// Shell: Send a command, waiting up to `timeout_ms` for space in a full
+/// queue
+///
+/// Returns `VENOM_SEND_OK` (0), `VENOM_SEND_TIMEOUT` (-1) if the queue
+/// stayed full, or `VENOM_SEND_ERROR` (-2) if the command is too long or
+/// the shell is read-only.
+///
+/// # Safety
+/// handle must be a live shell handle and cmd must be valid for
+/// `len` bytes of reads
+#[no_mangle]
+pub unsafe extern "C" fn venom_shell_send_command_timeout(
+    handle: *mut VenomShellHandle,
+    cmd: *const u8,
+    len: usize,
+    timeout_ms: u32,
+) -> i32 {
+    guard(VENOM_SEND_ERROR, || {
+        let shell = &(*handle).0;
+        let slice = if cmd.is_null() { &[][..] } else { slice::from_raw_parts(cmd, len) };
+        match shell.send_command_timeout(slice, Duration::from_millis(timeout_ms.into())) {
+            Ok(()) => VENOM_SEND_OK,
+            Err(crate::VenomError::QueueFull) => VENOM_SEND_TIMEOUT,
+            Err(_) => VENOM_SEND_ERROR,
+        }
+    })
+}
+
+/// Shell: Send a message of up to `MAX_LARGE_COMMAND` (1 MiB) bytes in
+/// chunks, for `venom_daemon_try_recv_large_command`; spins while the
+/// queue is full
+///
+/// Returns false if the message is too long, the channel's commands are
+/// too short to carry a chunk, or the shell is read-only.
+///
+/// # Safety
+/// handle must be a live shell handle and data must be valid for
+/// `len` bytes of reads (or null with a `len` of 0)
+#[no_mangle]
+pub unsafe extern "C" fn venom_shell_send_large_command(
+    handle: *mut VenomShellHandle,
+    data: *const u8,
+    len: usize,
+) -> bool {
+    guard(false, || {
+        let shell = &(*handle).0;
+        let slice = if data.is_null() { &[][..] } else { slice::from_raw_parts(data, len) };
+        shell.send_large_command(slice).is_ok()
+    })
+}
+
+/// Shell: Send `len` bytes of payload as a typed command tagged `tag`,
+/// behind the 4-byte envelope of `typed_command`; spins while the queue is
+/// full
+///
+/// Returns false if the envelope and payload are longer than the channel's
+/// commands, or the shell is read-only.
+///
+/// # Safety
+/// handle must be a live shell handle and payload must be valid for
+/// `len` bytes of reads
+#[no_mangle]
+pub unsafe extern "C" fn venom_shell_send_typed(
+    handle: *mut VenomShellHandle,
+    tag: u16,
+    payload: *const u8,
+    len: usize,
+) -> bool {
+    guard(false, || {
+        let shell = &(*handle).0;
+        let slice = if len == 0 { &[][..] } else { slice::from_raw_parts(payload, len) };
+        shell.send_typed(tag, slice).is_ok()
+    })
+}
+
+/// Shell: Send a command and wait up to `timeout_ms` for the daemon's
+/// `venom_daemon_respond` to this shell, a full queue included
+///
+/// Returns the response's length, cut to `resp_max`;
+/// `VENOM_REQUEST_TIMEOUT` (-1) on timeout, and `VENOM_REQUEST_ERROR` (-2)
+/// if the command is too long, the shell read-only, or the daemon older
+/// than per-client responses.
+///
+/// # Safety
+/// handle must be a live shell handle, cmd must be valid for `cmd_len`
+/// bytes of reads, and resp_buf null with a `resp_max` of 0 or valid for
+/// `resp_max` bytes of writes
+#[no_mangle]
+pub unsafe extern "C" fn venom_shell_request(
+    handle: *mut VenomShellHandle,
+    cmd: *const u8,
+    cmd_len: usize,
+    resp_buf: *mut u8,
+    resp_max: usize,
+    timeout_ms: u32,
+) -> isize {
+    guard(VENOM_REQUEST_ERROR, || {
+        let shell = &(*handle).0;
+        let cmd = if cmd.is_null() { &[][..] } else { slice::from_raw_parts(cmd, cmd_len) };
+        let resp: &mut [u8] = if resp_buf.is_null() { &mut [] } else { slice::from_raw_parts_mut(resp_buf, resp_max) };
+        match shell.request_timeout(cmd, resp, Duration::from_millis(timeout_ms.into())) {
+            Ok(Some(len)) => len as isize,
+            Ok(None) => VENOM_REQUEST_TIMEOUT,
+            Err(_) => VENOM_REQUEST_ERROR,
+        }
+    })
 }
 
 /// Get raw pointer to shared memory for shell
+///
+/// # Safety
+/// handle must be a live shell handle
 #[no_mangle]
 pub unsafe extern "C" fn venom_shell_get_shm_ptr(handle: *mut VenomShellHandle) -> *const u8 {
-    let shell = &(*handle).0;
-    shell.as_ptr()
+    guard(ptr::null(), || {
+        let shell = &(*handle).0;
+        shell.as_ptr()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout;
+    use std::ffi::CString;
+
+    #[test]
+    fn test_shell_read_data_stamped() {
+        let name = CString::new("test_bindings_stamped").unwrap();
+        let config = VenomConfig { data_size: 4096, cmd_slots: 4, max_clients: 4 };
+        unsafe {
+            let daemon = venom_daemon_create(name.as_ptr(), config);
+            let shell = venom_shell_connect(name.as_ptr());
+            assert!(!daemon.is_null() && !shell.is_null());
+
+            venom_daemon_write_data(daemon, b"stamped".as_ptr(), 7);
+            let mut buf = [0u8; 256];
+            let mut timestamp = 0;
+            assert_eq!(venom_shell_read_data_stamped(shell, buf.as_mut_ptr(), buf.len(), &mut timestamp), 7);
+            assert!(timestamp > 0 && timestamp <= venom_clock_ns());
+            assert_eq!(venom_shell_read_data_stamped(shell, buf.as_mut_ptr(), 3, ptr::null_mut()), 3);
+
+            venom_shell_destroy(shell);
+            venom_daemon_destroy(daemon);
+        }
+    }
+
+    #[test]
+    fn test_shell_read_frame() {
+        let name = CString::new("test_bindings_frame").unwrap();
+        let config = VenomConfig { data_size: 4096, cmd_slots: 4, max_clients: 4 };
+        unsafe {
+            let daemon = venom_daemon_create(name.as_ptr(), config);
+            let shell = venom_shell_connect(name.as_ptr());
+            assert!(!daemon.is_null() && !shell.is_null());
+
+            let mut buf = [0u8; 256];
+            let mut frame = VenomFrame { len: 0, generation: 0, timestamp_ns: 0, truncated: false };
+            assert!(!venom_shell_read_frame(shell, buf.as_mut_ptr(), buf.len(), &mut frame));
+
+            venom_daemon_write_data(daemon, b"framed".as_ptr(), 6);
+            assert!(venom_shell_read_frame(shell, buf.as_mut_ptr(), buf.len(), &mut frame));
+            assert_eq!((&buf[..frame.len], frame.truncated), (&b"framed"[..], false));
+            assert_eq!(frame.generation, (*shell).0.data_sequence());
+            assert!(frame.timestamp_ns > 0 && frame.timestamp_ns <= venom_clock_ns());
+
+            let first = frame.generation;
+            venom_daemon_write_data(daemon, b"longer".as_ptr(), 6);
+            assert!(venom_shell_read_frame(shell, buf.as_mut_ptr(), 2, &mut frame));
+            assert_eq!((&buf[..frame.len], frame.truncated), (&b"lo"[..], true));
+            assert!(frame.generation > first);
+
+            venom_shell_destroy(shell);
+            venom_daemon_destroy(daemon);
+        }
+    }
+
+    #[test]
+    fn test_daemon_resize_data() {
+        let name = CString::new("test_bindings_resize").unwrap();
+        let config = VenomConfig { data_size: 64, cmd_slots: 4, max_clients: 4 };
+        unsafe {
+            let daemon = venom_daemon_create(name.as_ptr(), config);
+            let shell = venom_shell_connect(name.as_ptr());
+            assert!(!daemon.is_null() && !shell.is_null());
+
+            assert_eq!(venom_channel_data_capacity(shell), 56);
+            assert!(!venom_daemon_resize_data(daemon, 32));
+            assert!(venom_daemon_resize_data(daemon, 8192));
+            assert_eq!(venom_channel_data_capacity(shell), 8184);
+            let frame = [3u8; 1000];
+            venom_daemon_write_data(daemon, frame.as_ptr(), frame.len());
+            let mut buf = [0u8; 1000];
+            assert_eq!(venom_shell_read_data(shell, buf.as_mut_ptr(), buf.len()), 1000);
+            assert_eq!(buf, frame);
+
+            venom_shell_destroy(shell);
+            venom_daemon_destroy(daemon);
+        }
+    }
+
+    #[test]
+    fn test_shell_cmd_queue_len() {
+        let name = CString::new("test_bindings_queue_len").unwrap();
+        let config = VenomConfig { data_size: 4096, cmd_slots: 4, max_clients: 4 };
+        unsafe {
+            let daemon = venom_daemon_create(name.as_ptr(), config);
+            let shell = venom_shell_connect(name.as_ptr());
+            assert!(!daemon.is_null() && !shell.is_null());
+
+            assert_eq!(venom_shell_cmd_queue_len(shell), 0);
+            for i in 0..3u8 {
+                assert!(venom_shell_send_command(shell, &i, 1));
+            }
+            assert_eq!(venom_shell_cmd_queue_len(shell), 3);
+            let mut buf = [0u8; 8];
+            let mut client_id = 0;
+            assert_eq!(venom_daemon_try_recv_command(daemon, buf.as_mut_ptr(), buf.len(), &mut client_id), 1);
+            assert_eq!(venom_shell_cmd_queue_len(shell), 2);
+
+            venom_shell_destroy(shell);
+            venom_daemon_destroy(daemon);
+        }
+    }
+
+    #[test]
+    fn test_send_typed() {
+        let name = CString::new("test_bindings_send_typed").unwrap();
+        let config = VenomConfig { data_size: 4096, cmd_slots: 8, max_clients: 4 };
+        unsafe {
+            let daemon = venom_daemon_create(name.as_ptr(), config);
+            let shell = venom_shell_connect(name.as_ptr());
+            assert!(!daemon.is_null() && !shell.is_null());
+
+            let value = 250i32.to_le_bytes();
+            assert!(venom_shell_send_typed(shell, 2, value.as_ptr(), value.len()));
+            assert!(venom_shell_send_typed(shell, 1, std::ptr::null(), 0));
+            let mut buf = [0u8; 64];
+            let len = venom_daemon_try_recv_command(daemon, buf.as_mut_ptr(), buf.len(), std::ptr::null_mut());
+            assert_eq!(&buf[..len], b"\x02\x00\x04\x00\xfa\x00\x00\x00");
+            let len = venom_daemon_try_recv_command(daemon, buf.as_mut_ptr(), buf.len(), std::ptr::null_mut());
+            assert_eq!(crate::typed_command::parse(&buf[..len]), Some((1, &[][..])));
+
+            let too_long = vec![0u8; crate::mpsc_queue::MAX_CMD_SIZE];
+            assert!(!venom_shell_send_typed(shell, 1, too_long.as_ptr(), too_long.len()));
+
+            venom_shell_destroy(shell);
+            venom_daemon_destroy(daemon);
+        }
+    }
+
+    #[test]
+    fn test_large_command() {
+        let name = CString::new("test_bindings_large_command").unwrap();
+        let config = VenomConfig { data_size: 4096, cmd_slots: 8, max_clients: 4 };
+        unsafe {
+            let daemon = venom_daemon_create(name.as_ptr(), config);
+            let shell = venom_shell_connect(name.as_ptr());
+            assert!(!daemon.is_null() && !shell.is_null());
+
+            let message: Vec<u8> = (0..10_000).map(|i| i as u8).collect();
+            assert!(venom_shell_send_large_command(shell, message.as_ptr(), message.len()));
+            let mut buf = vec![0u8; 16 * 1024];
+            let mut client_id = 0;
+            let len = venom_daemon_try_recv_large_command(daemon, buf.as_mut_ptr(), buf.len(), &mut client_id);
+            assert_eq!((len, client_id), (message.len(), venom_shell_id(shell)));
+            assert_eq!(&buf[..len], message);
+
+            // Too small a buffer gets the start, and the whole length
+            assert!(venom_shell_send_large_command(shell, message.as_ptr(), message.len()));
+            let len = venom_daemon_try_recv_large_command(daemon, buf.as_mut_ptr(), 100, std::ptr::null_mut());
+            assert_eq!((len, &buf[..100]), (message.len(), &message[..100]));
+            assert_eq!(venom_daemon_try_recv_large_command(daemon, buf.as_mut_ptr(), buf.len(), &mut client_id), 0);
+
+            // A null buffer with no length is an empty message
+            assert!(venom_shell_send_large_command(shell, ptr::null(), 0));
+            assert!(venom_shell_send_command(shell, ptr::null(), 0));
+
+            venom_shell_destroy(shell);
+            venom_daemon_destroy(daemon);
+        }
+    }
+
+    #[test]
+    fn test_write_vectored() {
+        let name = CString::new("test_bindings_write_vectored").unwrap();
+        let config = VenomConfig { data_size: 4096, cmd_slots: 8, max_clients: 4 };
+        unsafe {
+            let daemon = venom_daemon_create(name.as_ptr(), config);
+            let shell = venom_shell_connect(name.as_ptr());
+            assert!(!daemon.is_null() && !shell.is_null());
+
+            let iov = [
+                VenomIoVec { base: b"GET ".as_ptr(), len: 4 },
+                VenomIoVec { base: ptr::null(), len: 0 },
+                VenomIoVec { base: b"/status".as_ptr(), len: 7 },
+            ];
+            venom_daemon_write_vectored(daemon, iov.as_ptr(), iov.len());
+            let mut buf = [0u8; 64];
+            let len = venom_shell_read_data(shell, buf.as_mut_ptr(), buf.len());
+            assert_eq!(&buf[..len], b"GET /status");
+
+            venom_daemon_write_vectored(daemon, ptr::null(), 0);
+            assert_eq!(venom_shell_read_data(shell, buf.as_mut_ptr(), buf.len()), 0);
+
+            venom_shell_destroy(shell);
+            venom_daemon_destroy(daemon);
+        }
+    }
+
+    #[test]
+    fn test_create_ex_payload_size() {
+        let name = CString::new("test_bindings_create_ex").unwrap();
+        let config = |struct_size, cmd_payload_size| VenomConfigEx {
+            struct_size,
+            data_size: 4096,
+            cmd_slots: 4,
+            max_clients: 4,
+            cmd_payload_size,
+            cmd_full_policy: 0,
+            shm_mode: 0,
+        };
+        let payload_of = |config: VenomConfigEx| unsafe {
+            let daemon = venom_daemon_create_ex(name.as_ptr(), &config);
+            let payload = (!daemon.is_null()).then(|| (*daemon).0.cmd_payload_size());
+            venom_daemon_destroy(daemon);
+            payload
+        };
+        let full = std::mem::size_of::<VenomConfigEx>();
+        let before_payload = std::mem::offset_of!(VenomConfigEx, cmd_payload_size);
+
+        assert_eq!(payload_of(config(full, 8)), Some(64));
+        assert_eq!(payload_of(config(full, 512)), Some(512));
+        assert_eq!(payload_of(config(full, 0)), Some(crate::mpsc_queue::DEFAULT_CMD_PAYLOAD_SIZE));
+        // A caller from before the field: whatever follows is ignored
+        assert_eq!(payload_of(config(before_payload, 8)), Some(crate::mpsc_queue::DEFAULT_CMD_PAYLOAD_SIZE));
+        assert_eq!(payload_of(config(before_payload - 1, 8)), None);
+        assert_eq!(payload_of(config(full, crate::mpsc_queue::MAX_CMD_SIZE + 1)), None);
+        assert!(unsafe { venom_daemon_create_ex(name.as_ptr(), ptr::null()) }.is_null());
+    }
+
+    #[test]
+    fn test_create_ex_full_policy() {
+        let name = CString::new("test_bindings_full_policy").unwrap();
+        let config = |struct_size, cmd_full_policy| VenomConfigEx {
+            struct_size,
+            data_size: 4096,
+            cmd_slots: 4,
+            max_clients: 4,
+            cmd_payload_size: 0,
+            cmd_full_policy,
+            shm_mode: 0,
+        };
+        let sent_of = |config: VenomConfigEx| unsafe {
+            let daemon = venom_daemon_create_ex(name.as_ptr(), &config);
+            if daemon.is_null() {
+                return None;
+            }
+            let shell = venom_shell_connect(name.as_ptr());
+            let sent = (0..6u8).filter(|i| venom_shell_send_command(shell, i, 1)).count();
+            venom_shell_destroy(shell);
+            venom_daemon_destroy(daemon);
+            Some(sent)
+        };
+        let full = std::mem::size_of::<VenomConfigEx>();
+
+        assert_eq!(sent_of(config(full, 0)), Some(4));
+        assert_eq!(sent_of(config(full, 1)), Some(6));
+        assert_eq!(sent_of(config(full, 2)), None);
+        // A caller from before the field gets the default
+        assert_eq!(sent_of(config(std::mem::offset_of!(VenomConfigEx, cmd_full_policy), 1)), Some(4));
+    }
+
+    #[test]
+    fn test_create_ex_shm_mode() {
+        let name = CString::new("test_bindings_shm_mode").unwrap();
+        let config = |struct_size, shm_mode| VenomConfigEx {
+            struct_size,
+            data_size: 4096,
+            cmd_slots: 4,
+            max_clients: 4,
+            cmd_payload_size: 0,
+            cmd_full_policy: 0,
+            shm_mode,
+        };
+        let mode_of = |config: VenomConfigEx| unsafe {
+            let daemon = venom_daemon_create_ex(name.as_ptr(), &config);
+            let mode = (!daemon.is_null())
+                .then(|| rustix::fs::stat(crate::shm::shm_path("test_bindings_shm_mode")).unwrap().st_mode & 0o777);
+            venom_daemon_destroy(daemon);
+            mode
+        };
+        let full = std::mem::size_of::<VenomConfigEx>();
+        let default = Some(ShmPermissions::DEFAULT_MODE & !crate::shm::umask());
+
+        assert_eq!(mode_of(config(full, 0o600)), Some(0o600));
+        assert_eq!(mode_of(config(full, 0)), default);
+        assert_eq!(mode_of(config(full, 0o4755)), None);
+        // A caller from before the field gets the default
+        assert_eq!(mode_of(config(std::mem::offset_of!(VenomConfigEx, shm_mode), 0o600)), default);
+    }
+
+    #[test]
+    fn test_client_names_truncated() {
+        let name = CString::new("test_bindings_names").unwrap();
+        let config = VenomConfig { data_size: 4096, cmd_slots: 4, max_clients: 4 };
+        // 31 ASCII bytes and a 2-byte 'é' straddling the limit, then bytes
+        // that aren't UTF-8
+        let long = CString::new(format!("{}é", "a".repeat(31))).unwrap();
+        let invalid = CString::new(&b"gui\xff\xfe"[..]).unwrap();
+        unsafe {
+            let daemon = venom_daemon_create(name.as_ptr(), config);
+            let shells = [long.as_ptr(), invalid.as_ptr(), ptr::null()]
+                .map(|client_name| venom_shell_connect_named(name.as_ptr(), client_name));
+            assert!(!daemon.is_null() && shells.iter().all(|shell| !shell.is_null()));
+
+            let mut buf = [0 as c_char; 64];
+            let mut name_of = |shell: *mut VenomShellHandle, max_len: usize| {
+                let len = venom_daemon_client_name(daemon, venom_shell_id(shell), buf.as_mut_ptr(), max_len);
+                let name = CStr::from_ptr(buf.as_ptr()).to_str().unwrap().to_owned();
+                assert_eq!(name.len(), len);
+                name
+            };
+            assert_eq!(name_of(shells[0], 64), "a".repeat(31));
+            assert_eq!(name_of(shells[1], 64), "gui");
+            assert_eq!(name_of(shells[1], 3), "gu", "cut to the buffer");
+            assert_eq!(name_of(shells[2], 64), "");
+            assert_eq!(venom_daemon_client_name(daemon, 99, buf.as_mut_ptr(), buf.len()), 0);
+            for shell in shells {
+                venom_shell_destroy(shell);
+            }
+            venom_daemon_destroy(daemon);
+        }
+    }
+
+    #[test]
+    fn test_shell_read_data_clamped() {
+        let namespace = "test_bindings_read_data";
+        let daemon = DaemonChannel::create(namespace, ChannelConfig::default()).unwrap();
+        let name = CString::new(namespace).unwrap();
+        let shell = unsafe { venom_shell_connect(name.as_ptr()) };
+        assert!(!shell.is_null());
+
+        // Python and Dart read with fixed 256-byte buffers
+        let mut buf = [0u8; 256];
+        daemon.write_data_with_len(&[7u8; 1000]);
+        assert_eq!(unsafe { venom_shell_read_data(shell, buf.as_mut_ptr(), buf.len()) }, 256);
+        assert_eq!(unsafe { venom_shell_read_data(shell, ptr::null_mut(), 0) }, 0);
+
+        // A length prefix poked past the end of the region
+        unsafe {
+            let base = daemon.as_ptr();
+            let seqlock_offset = *(base.add(layout::header::SEQLOCK_OFFSET) as *const usize);
+            let prefix = base.add(seqlock_offset + layout::seqlock::SIZE) as *mut u64;
+            for len in [(daemon.data_size() - layout::seqlock::LEN_PREFIX + 1) as u64, u64::MAX] {
+                prefix.write(len);
+                assert_eq!(venom_shell_read_data(shell, buf.as_mut_ptr(), buf.len()), 0);
+                assert!(matches!(
+                    (*shell).0.try_read_data_with_len(&mut buf),
+                    Err(crate::VenomError::Corrupted { len: got, .. }) if got == len
+                ));
+                assert!((*shell).0.read_data_with_len(&mut buf) < daemon.data_size());
+            }
+            venom_shell_destroy(shell);
+        }
+    }
+
+    #[test]
+    fn test_version() {
+        let version = unsafe { CStr::from_ptr(venom_version()) };
+        assert_eq!(version.to_str().unwrap(), crate::VERSION);
+    }
+
+    #[test]
+    fn test_respond_and_request() {
+        let name = CString::new("test_bindings_respond").unwrap();
+        let config = VenomConfig { data_size: 4096, cmd_slots: 4, max_clients: 4 };
+        unsafe {
+            let daemon = venom_daemon_create(name.as_ptr(), config);
+            let shells = [venom_shell_connect(name.as_ptr()), venom_shell_connect(name.as_ptr())];
+            assert!(!daemon.is_null() && shells.iter().all(|shell| !shell.is_null()));
+            assert_eq!(venom_daemon_client_count(daemon), 2);
+
+            let mut buf = [0u8; 64];
+            assert_eq!(venom_shell_request(shells[0], b"hi".as_ptr(), 2, buf.as_mut_ptr(), buf.len(), 10), VENOM_REQUEST_TIMEOUT);
+            let mut client_id = 0;
+            assert_eq!(venom_daemon_try_recv_command(daemon, buf.as_mut_ptr(), buf.len(), &mut client_id), 2);
+            assert_eq!(client_id, venom_shell_id(shells[0]));
+            assert!(!venom_daemon_respond(daemon, 99, b"x".as_ptr(), 1));
+            let too_long = [0u8; 4097];
+            assert!(!venom_daemon_respond(daemon, client_id, too_long.as_ptr(), too_long.len()));
+            assert_eq!(
+                venom_shell_request(shells[0], too_long.as_ptr(), too_long.len(), buf.as_mut_ptr(), buf.len(), 10),
+                VENOM_REQUEST_ERROR
+            );
+
+            // The daemon answers from another thread; the other shell hears nothing
+            let (daemon_addr, shell_addr) = (daemon as usize, shells[1] as usize);
+            let responder = std::thread::spawn(move || {
+                let daemon = daemon_addr as *mut VenomDaemonHandle;
+                let mut cmd = [0u8; 64];
+                let mut client_id = 0;
+                let len = venom_daemon_recv_command(daemon, cmd.as_mut_ptr(), cmd.len(), &mut client_id);
+                assert_eq!(&cmd[..len], b"ping");
+                assert!(venom_daemon_respond(daemon, client_id, b"pong".as_ptr(), 4));
+            });
+            let len = venom_shell_request(shells[0], b"ping".as_ptr(), 4, buf.as_mut_ptr(), buf.len(), 5000);
+            responder.join().unwrap();
+            assert_eq!(&buf[..len as usize], b"pong");
+            let other = shell_addr as *mut VenomShellHandle;
+            assert_eq!(venom_shell_request(other, b"?".as_ptr(), 1, ptr::null_mut(), 0, 0), VENOM_REQUEST_TIMEOUT);
+
+            for shell in shells {
+                venom_shell_destroy(shell);
+            }
+            assert_eq!(venom_daemon_client_count(daemon), 0);
+            venom_daemon_destroy(daemon);
+        }
+    }
+
+    #[test]
+    fn test_daemon_stats() {
+        let name = CString::new("test_bindings_stats").unwrap();
+        let config = VenomConfig { data_size: 4096, cmd_slots: 4, max_clients: 4 };
+        unsafe {
+            let daemon = venom_daemon_create(name.as_ptr(), config);
+            let shell = venom_shell_connect(name.as_ptr());
+            assert!(!daemon.is_null() && !shell.is_null());
+
+            let mut buf = [0u8; 8];
+            for i in 0..3u8 {
+                assert!(venom_shell_send_command(shell, &i, 1));
+                assert_eq!(venom_daemon_try_recv_command(daemon, buf.as_mut_ptr(), buf.len(), ptr::null_mut()), 1);
+            }
+            let mut stats = VenomStats {
+                struct_size: std::mem::size_of::<VenomStats>(),
+                commands_received: 0,
+                corrupt_slots: 7,
+                dropped_commands: 7,
+                discarded_large_commands: 7,
+                total_full_events: 7,
+            };
+            assert!(venom_daemon_stats(daemon, &mut stats));
+            assert_eq!(
+                (stats.commands_received, stats.corrupt_slots, stats.dropped_commands, stats.discarded_large_commands),
+                (3, 0, 0, 0)
+            );
+            assert_eq!(stats.total_full_events, 0);
+
+            // A caller from before the later fields: they're left alone
+            stats = VenomStats { struct_size: std::mem::offset_of!(VenomStats, corrupt_slots), ..stats };
+            stats.corrupt_slots = 7;
+            assert!(venom_daemon_stats(daemon, &mut stats));
+            assert_eq!((stats.commands_received, stats.corrupt_slots), (3, 7));
+            stats.struct_size = std::mem::size_of::<usize>();
+            assert!(!venom_daemon_stats(daemon, &mut stats));
+            assert!(!venom_daemon_stats(daemon, ptr::null_mut()));
+
+            venom_shell_destroy(shell);
+            venom_daemon_destroy(daemon);
+        }
+    }
+
+    #[test]
+    fn test_send_command_timeout() {
+        let name = CString::new("test_bindings_send_timeout").unwrap();
+        let config = VenomConfig { data_size: 4096, cmd_slots: 2, max_clients: 4 };
+        unsafe {
+            let daemon = venom_daemon_create(name.as_ptr(), config);
+            let shell = venom_shell_connect(name.as_ptr());
+            assert!(!daemon.is_null() && !shell.is_null());
+
+            for i in 0..2u8 {
+                assert_eq!(venom_shell_send_command_timeout(shell, &i, 1, 1), VENOM_SEND_OK);
+            }
+            let started = std::time::Instant::now();
+            assert_eq!(venom_shell_send_command_timeout(shell, b"x".as_ptr(), 1, 5), VENOM_SEND_TIMEOUT);
+            assert!(started.elapsed() >= Duration::from_millis(5));
+            let too_long = [0u8; 4097];
+            assert_eq!(venom_shell_send_command_timeout(shell, too_long.as_ptr(), too_long.len(), 5), VENOM_SEND_ERROR);
+
+            let mut stats = VenomStats {
+                struct_size: std::mem::size_of::<VenomStats>(),
+                commands_received: 0,
+                corrupt_slots: 0,
+                dropped_commands: 0,
+                discarded_large_commands: 0,
+                total_full_events: 0,
+            };
+            assert!(venom_daemon_stats(daemon, &mut stats));
+            assert_eq!(stats.total_full_events, 1);
+            assert_eq!((*shell).0.rejected_sends(), 1);
+
+            venom_shell_destroy(shell);
+            venom_daemon_destroy(daemon);
+        }
+    }
+
+    #[test]
+    fn test_unlink_and_daemon_alive() {
+        let name = CString::new("test_bindings_unlink").unwrap();
+        let config = VenomConfig { data_size: 4096, cmd_slots: 4, max_clients: 4 };
+        unsafe {
+            let daemon = venom_daemon_create(name.as_ptr(), config);
+            let shell = venom_shell_connect(name.as_ptr());
+            assert!(!daemon.is_null() && !shell.is_null());
+            assert!(venom_shell_daemon_alive(shell));
+
+            assert!(venom_daemon_unlink(daemon));
+            assert!(!venom_daemon_unlink(daemon));
+            assert!(venom_shell_connect(name.as_ptr()).is_null());
+            assert!(venom_shell_daemon_alive(shell));
+
+            venom_daemon_destroy(daemon);
+            venom_shell_destroy(shell);
+        }
+    }
 }
@@ -2,15 +2,33 @@
 //!
 //! Provides easy-to-use interfaces for daemon (writer) and shell (reader) processes.
 
+use crate::affinity;
 use crate::error::{Result, VenomError};
-use crate::mpsc_queue::{MpscConsumer, MpscProducer, MpscQueueHeader, MAX_CMD_SIZE};
-use crate::seqlock::{SeqLockHeader, SeqLockReader, SeqLockWriter};
-use crate::shm::VenomShm;
-use std::sync::atomic::{AtomicU32, Ordering};
+use crate::event_log::{self, ChannelEvent, EventLog, EventLogHeader, LoggedEvent};
+use crate::large_command::{self, Envelope, Reassembly, LARGE_COMMAND_TIMEOUT};
+use crate::layout::seqlock::LEN_PREFIX;
+use crate::layout::to_usize;
+use crate::mpsc_queue::{
+    self, CmdFullPolicy, MpscConsumer, MpscProducer, MpscQueueHeader, DEFAULT_CMD_PAYLOAD_SIZE, MAX_CMD_SIZE,
+};
+use crate::namespace;
+use crate::seqlock::{now_ns, SeqLockHeader, SeqLockReader, SeqLockWriter};
+use crate::shm::{ShmMapping, ShmPermissions, VenomShm};
+use crate::typed_command::{self, Handlers};
+use std::cell::{Cell, RefCell};
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicPtr, AtomicU32, AtomicU64, Ordering};
+use std::sync::{mpsc, Mutex, PoisonError};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+pub mod frames;
+pub mod snapshot;
+pub mod split;
+pub mod standby;
 
 /// Magic number for channel validation
-const VENOM_MAGIC: u32 = 0x564E4F4D; // "VNOM"
-const VENOM_VERSION: u32 = 2;
+pub(crate) const VENOM_MAGIC: u32 = 0x564E4F4D; // "VNOM"
+pub(crate) const VENOM_VERSION: u32 = 5;
 
 /// Default data region size (64KB)
 const DEFAULT_DATA_SIZE: usize = 64 * 1024;
@@ -21,15 +39,55 @@ const DEFAULT_CMD_SLOTS: usize = 32;
 /// Cache line size
 const CACHE_LINE_SIZE: usize = 64;
 
+/// How long [`DaemonChannel::resize_data`] keeps writing the old data region
+/// for shells that haven't moved to the new one
+pub const RESIZE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Longest client name, in bytes, a shell can register with
+/// [`ShellChannel::connect_named`]
+pub const CLIENT_NAME_MAX: usize = 32;
+
 /// Channel configuration
 #[derive(Clone)]
 pub struct ChannelConfig {
     /// Size of the data region in bytes
     pub data_size: usize,
-    /// Number of command queue slots
+    /// Number of command queue slots, 1 to
+    /// [`MAX_SLOTS`](crate::mpsc_queue::MAX_SLOTS); rounded up to a power
+    /// of two
     pub cmd_slots: usize,
-    /// Maximum number of clients
+    /// Longest command a shell can send, 1 to
+    /// [`MAX_CMD_SIZE`](crate::mpsc_queue::MAX_CMD_SIZE) bytes; rounded up
+    /// to whole cache lines
+    pub cmd_payload_size: usize,
+    /// What sending does when every command slot is full: fail, or drop
+    /// the oldest command the daemon hasn't taken
+    pub cmd_full_policy: CmdFullPolicy,
+    /// Maximum number of clients; a shell connecting while this many are
+    /// connected fails with [`VenomError::TooManyClients`]
     pub max_clients: usize,
+    /// Scope the channel name to the current user (`/venom_{uid}_{name}`)
+    pub user_scoped: bool,
+    /// Stamp every write with the time it was made, for
+    /// [`ShellChannel::last_write_timestamp`] and
+    /// [`ShellChannel::read_data_stamped`]
+    pub stamp_writes: bool,
+    /// Mode and group of the shared memory object, which decide who can
+    /// connect: shells need read and write permission, read-only shells
+    /// just read
+    pub permissions: ShmPermissions,
+    /// Core to pin the thread creating the daemon to, as
+    /// [`affinity::pin_current_thread`] does, once the channel is created,
+    /// for daemons that write from the thread they create it on; None
+    /// leaves it be
+    pub writer_core: Option<usize>,
+    /// How long [`DaemonChannel::try_recv_large_command`] keeps a message
+    /// that gets no further chunk
+    pub large_command_timeout: Duration,
+    /// Events the channel's [event log](crate::event_log) keeps, at most
+    /// [`MAX_EVENT_LOG_SLOTS`](event_log::MAX_EVENT_LOG_SLOTS); 0 keeps
+    /// none
+    pub event_log_slots: usize,
 }
 
 impl Default for ChannelConfig {
@@ -37,46 +95,402 @@ impl Default for ChannelConfig {
         Self {
             data_size: DEFAULT_DATA_SIZE,
             cmd_slots: DEFAULT_CMD_SLOTS,
+            cmd_payload_size: DEFAULT_CMD_PAYLOAD_SIZE,
+            cmd_full_policy: CmdFullPolicy::Reject,
             max_clients: 16,
+            user_scoped: false,
+            stamp_writes: false,
+            permissions: ShmPermissions::default(),
+            writer_core: None,
+            large_command_timeout: LARGE_COMMAND_TIMEOUT,
+            event_log_slots: event_log::DEFAULT_EVENT_LOG_SLOTS,
         }
     }
 }
 
+impl ChannelConfig {
+    /// Bytes a channel of this config takes, the least a region handed to
+    /// [`DaemonChannel::create_in`] must have
+    ///
+    /// A `cmd_slots`, `cmd_payload_size` or `event_log_slots` out of range
+    /// fails with [`VenomError::InvalidConfig`].
+    pub fn region_size(&self) -> Result<usize> {
+        let (config, cmd_payload_size) = self.clone().checked()?;
+        Ok(ChannelHeader::total_size(&config, cmd_payload_size))
+    }
+
+    /// The config with `cmd_slots` rounded as [`mpsc_queue::cmd_slots`]
+    /// rounds it, and the payload size of its command slots; fails as
+    /// those two do, and on too many `event_log_slots`
+    fn checked(self) -> Result<(Self, usize)> {
+        EventLog::check_slots(self.event_log_slots)?;
+        let cmd_payload_size = mpsc_queue::cmd_payload_size(self.cmd_payload_size)?;
+        Ok((Self { cmd_slots: mpsc_queue::cmd_slots(self.cmd_slots)?, ..self }, cmd_payload_size))
+    }
+}
+
 /// Channel header stored at the beginning of shared memory
 #[repr(C)]
-struct ChannelHeader {
-    magic: u32,
-    version: u32,
-    data_size: usize,
-    cmd_slots: usize,
-    max_clients: usize,
-    next_client_id: AtomicU32,
+pub(crate) struct ChannelHeader {
+    pub(crate) magic: u32,
+    pub(crate) version: u32,
+    /// Size of the current data region
+    pub(crate) data_size: AtomicU64,
+    pub(crate) cmd_slots: u64,
+    pub(crate) max_clients: u64,
+    pub(crate) next_client_id: AtomicU32,
+    pub(crate) owner_pid: AtomicU32,
     // Offsets to regions
-    seqlock_offset: usize,
-    cmd_queue_offset: usize,
-    _pad: [u8; CACHE_LINE_SIZE - 48],
+    pub(crate) seqlock_offset: AtomicU64,
+    pub(crate) cmd_queue_offset: u64,
+    /// Number of currently connected shells
+    pub(crate) client_count: AtomicU32,
+    /// Bumped by a standby taking the channel over: the fencing token of
+    /// the daemon that owns it, checked before every write
+    pub(crate) owner_generation: AtomicU32,
+    /// Offset of the client table; 0 in channels of daemons older than it
+    pub(crate) clients_offset: u64,
+    /// Bumped each time the daemon moves the data region, after
+    /// `seqlock_offset` and `data_size` point at the new one
+    pub(crate) data_generation: AtomicU32,
+    /// PID of the [standby](standby::StandbyChannel) waiting to take the
+    /// channel over; 0 if there is none
+    pub(crate) standby_pid: AtomicU32,
+    /// Offset of the response slots, one per client table slot; 0 in
+    /// channels of daemons older than them
+    pub(crate) responses_offset: u64,
+    /// [`now_ns`] of the owner's last write or
+    /// [`heartbeat`](DaemonChannel::heartbeat); 0 in channels of daemons
+    /// older than it
+    pub(crate) heartbeat_ns: AtomicU64,
+    /// 1 once `next_client_id` has wrapped around (see [`take_client_id`]):
+    /// every client ID has been handed out, those above it too
+    pub(crate) client_ids_wrapped: AtomicU32,
+    _wrapped_pad: [u8; 4],
+    /// Offset of the [event log](crate::event_log); 0 in channels without
+    /// one, those of daemons older than it included
+    pub(crate) events_offset: u64,
 }
 
 impl ChannelHeader {
-    fn total_size(config: &ChannelConfig) -> usize {
+    fn total_size(config: &ChannelConfig, cmd_payload_size: usize) -> usize {
         let header_size = std::mem::size_of::<ChannelHeader>();
         let seqlock_size = std::mem::size_of::<SeqLockHeader>() + config.data_size;
-        let cmd_queue_size = MpscQueueHeader::size_for_slots(config.cmd_slots);
+        let cmd_queue_size = MpscQueueHeader::size_for_slots(config.cmd_slots, cmd_payload_size);
+        let clients_size = config.max_clients * std::mem::size_of::<ClientSlot>();
+        let responses_size = config.max_clients * ResponseSlot::size_for_payload(cmd_payload_size);
 
         // Align each region to cache line
         let align = |size: usize| -> usize { (size + CACHE_LINE_SIZE - 1) & !(CACHE_LINE_SIZE - 1) };
 
-        align(header_size) + align(seqlock_size) + align(cmd_queue_size)
+        let events_size = EventLog::size_for_slots(config.event_log_slots);
+        let regions = align(header_size) + align(seqlock_size) + align(cmd_queue_size) + align(clients_size);
+        if events_size == 0 {
+            regions + responses_size
+        } else {
+            regions + align(responses_size) + events_size
+        }
+    }
+}
+
+/// `client_id` of a slot a shell is filling in
+const CLIENT_CLAIMING: u32 = crate::layout::client::CLAIMING;
+
+/// One entry of the client table: a connected shell and the name it gave
+#[repr(C)]
+pub(crate) struct ClientSlot {
+    /// 0 while free, [`CLIENT_CLAIMING`] while a shell fills in the rest,
+    /// then the shell's client ID
+    pub(crate) client_id: AtomicU32,
+    /// Process of the shell, so slots of crashed shells can be taken over
+    pub(crate) pid: AtomicU32,
+    /// Wall clock time of the connect, in nanoseconds since the Unix epoch
+    pub(crate) connected_since: AtomicU64,
+    /// Length of `name`; 0 for a shell that didn't give one
+    pub(crate) name_len: AtomicU32,
+    pub(crate) name: [u8; CLIENT_NAME_MAX],
+    /// `data_generation` of the data region the shell reads
+    pub(crate) data_generation: AtomicU32,
+}
+
+/// The last response the daemon sent the shell in the client slot of the
+/// same index, with [`DaemonChannel::respond`]; the response follows it
+///
+/// The daemon makes `sequence` odd, fills in the rest and makes it even
+/// again, as for the data region.
+#[repr(C)]
+pub(crate) struct ResponseSlot {
+    pub(crate) sequence: AtomicU64,
+    /// Client ID the response is for; a shell that took the client slot
+    /// since ignores it
+    pub(crate) client_id: AtomicU32,
+    /// Length of the response, at most the channel's `cmd_payload_size`
+    pub(crate) len: AtomicU32,
+    /// Padding to the cache line the response starts on
+    _pad: [u8; CACHE_LINE_SIZE - 16],
+}
+
+impl ResponseSlot {
+    /// Bytes from one slot to the next in a channel with this payload size
+    pub(crate) const fn size_for_payload(cmd_payload_size: usize) -> usize {
+        std::mem::size_of::<ResponseSlot>() + cmd_payload_size
+    }
+
+    /// The response data of `slot`
+    ///
+    /// # Safety
+    /// `slot` must point to a slot in the channel's mapping.
+    unsafe fn data(slot: *const ResponseSlot) -> *const u8 {
+        slot.cast::<u8>().add(std::mem::size_of::<ResponseSlot>())
+    }
+}
+
+/// What a client slot held at one point in time
+struct ClientEntry {
+    client_id: u32,
+    name: Option<String>,
+    connected_since: SystemTime,
+}
+
+impl ClientSlot {
+    /// Take a free slot of the `max_clients`-slot table, or one left behind
+    /// by a process that's gone, and publish `client_id` and `name` in it,
+    /// along with the `data_generation` the shell reads
+    ///
+    /// # Safety
+    /// `table` must point to `max_clients` slots in the channel's mapping.
+    unsafe fn claim(
+        table: *mut ClientSlot,
+        max_clients: usize,
+        client_id: u32,
+        name: &str,
+        data_generation: u32,
+    ) -> Option<*mut ClientSlot> {
+        let slots = || (0..max_clients).map(|i| table.add(i));
+        let slot = slots()
+            .find(|&slot| {
+                (*slot).client_id.compare_exchange(0, CLIENT_CLAIMING, Ordering::AcqRel, Ordering::Relaxed).is_ok()
+            })
+            .or_else(|| {
+                slots().find(|&slot| {
+                    let owner = (*slot).client_id.load(Ordering::Acquire);
+                    owner != 0
+                        && owner != CLIENT_CLAIMING
+                        && !process_alive((*slot).pid.load(Ordering::Relaxed))
+                        && (*slot)
+                            .client_id
+                            .compare_exchange(owner, CLIENT_CLAIMING, Ordering::AcqRel, Ordering::Relaxed)
+                            .is_ok()
+                })
+            })?;
+
+        let since = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        (*slot).pid.store(rustix::process::getpid().as_raw_nonzero().get() as u32, Ordering::Relaxed);
+        (*slot).connected_since.store(since.as_nanos() as u64, Ordering::Relaxed);
+        std::ptr::copy_nonoverlapping(name.as_ptr(), std::ptr::addr_of_mut!((*slot).name).cast::<u8>(), name.len());
+        (*slot).name_len.store(name.len() as u32, Ordering::Relaxed);
+        (*slot).data_generation.store(data_generation, Ordering::Relaxed);
+        (*slot).client_id.store(client_id, Ordering::Release);
+        Some(slot)
+    }
+
+    /// Copy out the slot's client, if one is published in it
+    ///
+    /// # Safety
+    /// `slot` must point into the channel's mapping.
+    unsafe fn entry(slot: *const ClientSlot) -> Option<ClientEntry> {
+        let client_id = (*slot).client_id.load(Ordering::Acquire);
+        if client_id == 0 || client_id == CLIENT_CLAIMING {
+            return None;
+        }
+        let len = ((*slot).name_len.load(Ordering::Relaxed) as usize).min(CLIENT_NAME_MAX);
+        let mut name = [0u8; CLIENT_NAME_MAX];
+        std::ptr::copy_nonoverlapping(std::ptr::addr_of!((*slot).name).cast::<u8>(), name.as_mut_ptr(), len);
+        let since = (*slot).connected_since.load(Ordering::Relaxed);
+
+        // The shell may have left and another taken the slot meanwhile
+        std::sync::atomic::fence(Ordering::Acquire);
+        if (*slot).client_id.load(Ordering::Relaxed) != client_id {
+            return None;
+        }
+        Some(ClientEntry {
+            client_id,
+            // Shells that don't link this library may write anything
+            name: (len > 0).then(|| std::str::from_utf8(&name[..len]).ok().map(str::to_owned)).flatten(),
+            connected_since: UNIX_EPOCH + Duration::from_nanos(since),
+        })
+    }
+}
+
+/// Check whether a process with the given PID is still running
+pub(crate) fn process_alive(pid: u32) -> bool {
+    match rustix::process::Pid::from_raw(pid as i32) {
+        // EPERM still means the process exists, it just isn't ours
+        Some(pid) => !matches!(
+            rustix::process::test_kill_process(pid),
+            Err(rustix::io::Errno::SRCH)
+        ),
+        None => false,
+    }
+}
+
+/// The event log the header points at, checked to lie in the `size`-byte
+/// mapping at `base`; None if the channel has none
+///
+/// # Safety
+/// `header` must be the channel header at `base`.
+unsafe fn event_log_in(base: *const u8, header: *const ChannelHeader, size: usize) -> Result<Option<EventLog>> {
+    let events_offset = to_usize((*header).events_offset);
+    if events_offset == 0 {
+        return Ok(None);
+    }
+    if !events_offset.is_multiple_of(CACHE_LINE_SIZE) || events_offset > size {
+        return Err(VenomError::CorruptRegion {
+            reason: format!("event log at offset {} of a {}-byte channel", events_offset, size),
+        });
+    }
+    EventLog::from_region(base.add(events_offset), size - events_offset).map(Some)
+}
+
+/// Check a region from [`VenomShm::from_raw_parts`] is aligned as the
+/// channel's layout wants
+fn check_region(shm: &VenomShm) -> Result<()> {
+    if !(shm.as_ptr() as usize).is_multiple_of(CACHE_LINE_SIZE) {
+        return Err(VenomError::InvalidConfig {
+            reason: format!("region at {:p} isn't aligned to a {}-byte cache line", shm.as_ptr(), CACHE_LINE_SIZE),
+        });
+    }
+    Ok(())
+}
+
+/// Take the next client ID for a connecting shell
+///
+/// IDs go from 1 up to `u32::MAX - 1` and then start over at 1: neither 0
+/// nor [`CLIENT_CLAIMING`] names a shell. The shell taking the last one
+/// sets `client_ids_wrapped` before moving `next_client_id` back, so a
+/// daemon that sees the count start over sees the flag too, and goes on
+/// taking commands from shells with IDs above it.
+///
+/// # Safety
+/// `header` must point to a channel header
+unsafe fn take_client_id(header: *const ChannelHeader) -> u32 {
+    let next = &(*header).next_client_id;
+    let mut current = next.load(Ordering::Acquire);
+    loop {
+        let following = if current >= CLIENT_CLAIMING - 1 { 1 } else { current + 1 };
+        if following == 1 {
+            (*header).client_ids_wrapped.store(1, Ordering::Release);
+        }
+        match next.compare_exchange_weak(current, following, Ordering::AcqRel, Ordering::Acquire) {
+            // Shells of older versions count with fetch-add, past the
+            // reserved IDs too
+            Ok(_) if current == 0 || current == CLIENT_CLAIMING => current = following,
+            Ok(_) => return current,
+            Err(actual) => current = actual,
+        }
+    }
+}
+
+/// Return the PID the channel `name` records as its owner, if it has one
+///
+/// The magic isn't checked: a daemon stores its PID before it, so one
+/// still setting up its channel is found too.
+fn recorded_owner(name: &str) -> Option<u32> {
+    let shm = VenomShm::open_read_only(name).ok()?;
+    if shm.size() < std::mem::size_of::<ChannelHeader>() {
+        return None;
     }
+
+    let header = shm.as_ptr() as *const ChannelHeader;
+    let pid = unsafe { (*header).owner_pid.load(Ordering::Acquire) };
+    (pid != 0).then_some(pid)
+}
+
+/// A data region of the channel: the one it was created with, or one
+/// [`DaemonChannel::resize_data`] added past its end
+struct DataRegion<T> {
+    /// SeqLockWriter or SeqLockReader of the region
+    seqlock: T,
+    /// `data_generation` the region was published under
+    generation: u32,
+    /// None for the region in the channel's first mapping
+    _mapping: Option<ShmMapping>,
+}
+
+/// Command counters of a daemon's queue, from [`DaemonChannel::stats`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DaemonStats {
+    /// Commands taken off the queue, corrupt ones included
+    pub commands_received: u64,
+    /// Commands dropped because their slot claimed more than a slot holds
+    /// or came from a client ID the channel never handed out
+    pub corrupt_slots: u64,
+    /// Commands shells dropped, unread, to make room under
+    /// [`CmdFullPolicy::OverwriteOldest`]
+    pub dropped_commands: u64,
+    /// Messages [`DaemonChannel::try_recv_large_command`] discarded
+    /// unfinished, and chunks whose envelope made no sense
+    pub discarded_large_commands: u64,
+    /// Sends that found the queue full, whether the shell then gave up,
+    /// waited for room or dropped the oldest command
+    pub total_full_events: u64,
+    /// Commands the [command filter](DaemonChannel::set_command_filter)
+    /// dropped with [`FilterAction::DropAndCount`]
+    pub filtered_commands: u64,
+}
+
+/// What a [command filter](DaemonChannel::set_command_filter) does with a
+/// command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterAction {
+    /// Pass the command on
+    Accept,
+    /// Drop the command unseen
+    Drop,
+    /// Drop the command and count it in [`DaemonStats::filtered_commands`]
+    DropAndCount,
+}
+
+/// Filter of [`DaemonChannel::set_command_filter`]
+type IdFilter = dyn Fn(u32, &[u8]) -> FilterAction + Send;
+/// Filter of [`DaemonChannel::set_named_command_filter`]
+type NameFilter = dyn Fn(u32, Option<&str>, &[u8]) -> FilterAction + Send;
+
+/// A filter installed with [`DaemonChannel::set_command_filter`] or
+/// [`DaemonChannel::set_named_command_filter`]
+enum CommandFilter {
+    ById(Box<IdFilter>),
+    ByName(Box<NameFilter>),
 }
 
 /// Daemon (Writer) side of the channel
 pub struct DaemonChannel {
     shm: VenomShm,
-    #[allow(dead_code)]
     header: *mut ChannelHeader,
-    data_writer: SeqLockWriter,
+    data: DataRegion<SeqLockWriter>,
+    /// Regions the data moved out of, still written while a shell may read
+    /// them, with when the data moved
+    retiring: RefCell<Vec<(DataRegion<SeqLockWriter>, Instant)>>,
+    /// Size of the shared memory object
+    shm_len: usize,
+    cmd_queue: *const MpscQueueHeader,
     cmd_consumer: MpscConsumer,
+    clients: *const ClientSlot,
+    max_clients: usize,
+    /// `max_clients` response slots, a slot for each client slot
+    responses: *mut ResponseSlot,
+    /// Large commands some of whose chunks have arrived
+    reassembly: RefCell<Reassembly>,
+    /// `owner_generation` the daemon owns the channel under
+    owner_generation: u32,
+    /// Consulted for every command before it's handed out
+    filter: Option<CommandFilter>,
+    /// Commands `filter` dropped and counted
+    filtered_commands: Cell<u64>,
+    /// Where [`dispatch_pending`](Self::dispatch_pending) sends commands
+    handlers: RefCell<Handlers>,
+    /// None in a channel without an event log
+    events: Option<EventLog>,
 }
 
 // SAFETY: DaemonChannel is designed for single-threaded use
@@ -84,10 +498,90 @@ unsafe impl Send for DaemonChannel {}
 
 impl DaemonChannel {
     /// Create a new channel as the daemon (owner)
+    ///
+    /// Fails with [`VenomError::NamespaceInUse`] if the namespace already
+    /// holds a channel, even one a dead daemon left behind: see
+    /// [`create_force`](Self::create_force). A `cmd_slots` or
+    /// `cmd_payload_size` out of range fails with [`VenomError::InvalidConfig`].
     pub fn create(namespace: &str, config: ChannelConfig) -> Result<Self> {
-        let total_size = ChannelHeader::total_size(&config);
-        let shm = VenomShm::create(namespace, total_size)?;
+        Self::create_with(namespace, config, false)
+    }
+
+    /// [`create`](Self::create), taking over a channel whose daemon has
+    /// exited without removing it
+    ///
+    /// The old channel is removed and a new one created in its place; shells
+    /// still connected to the old one keep their mapping of it and see its
+    /// daemon gone. Fails with [`VenomError::NamespaceInUse`] if the daemon
+    /// the channel records is still running.
+    pub fn create_force(namespace: &str, config: ChannelConfig) -> Result<Self> {
+        Self::create_with(namespace, config, true)
+    }
+
+    fn create_with(namespace: &str, config: ChannelConfig, force: bool) -> Result<Self> {
+        let name = namespace::resolve(namespace, config.user_scoped)?;
+        let (config, cmd_payload_size) = config.checked()?;
+
+        // O_EXCL: of two daemons creating the channel at once, one fails
+        // here rather than setting it up again under the other
+        let total_size = ChannelHeader::total_size(&config, cmd_payload_size);
+        let name_taken = |shm: &Result<VenomShm>| {
+            matches!(shm, Err(VenomError::ShmCreate { source, .. }) if source.kind() == std::io::ErrorKind::AlreadyExists)
+        };
+        let mut shm = VenomShm::create_with(&name, total_size, &config.permissions);
+        if force && name_taken(&shm) && !recorded_owner(&name).is_some_and(process_alive) {
+            // A daemon forcing at the same time may remove it first, and
+            // create its own before this one
+            let _ = VenomShm::unlink(&name);
+            shm = VenomShm::create_with(&name, total_size, &config.permissions);
+        }
+        if name_taken(&shm) {
+            let pid = recorded_owner(&name);
+            return Err(VenomError::NamespaceInUse { name, pid });
+        }
+        let daemon = Self::init(shm?, &config, cmd_payload_size, total_size)?;
+        // Only once the channel is up: a daemon that fails to create it
+        // leaves its thread where it was. One that fails to pin is dropped,
+        // removing the channel again
+        if let Some(core) = config.writer_core {
+            affinity::pin_current_thread(core)?;
+        }
+        Ok(daemon)
+    }
 
+    /// Create a channel in a region mapped by someone else, e.g. one from
+    /// [`VenomShm::from_raw_parts`], for shells to [connect to](ShellChannel::connect_in)
+    ///
+    /// The region's first [`region_size`](ChannelConfig::region_size)
+    /// bytes are zeroed and the channel laid out in them as [`create`](Self::create) would;
+    /// `config`'s `user_scoped` and `permissions` don't apply. The channel
+    /// has no name, and can't be [resized](Self::resize_data) unless `shm`
+    /// is a named object.
+    ///
+    /// A region too small for `config`, or one not aligned to a cache line,
+    /// fails with [`VenomError::InvalidConfig`], as does a
+    /// `cmd_slots` or `cmd_payload_size` out of range.
+    pub fn create_in(shm: VenomShm, config: ChannelConfig) -> Result<Self> {
+        let (config, cmd_payload_size) = config.checked()?;
+        let total_size = ChannelHeader::total_size(&config, cmd_payload_size);
+        check_region(&shm)?;
+        if shm.size() < total_size {
+            return Err(VenomError::InvalidConfig {
+                reason: format!("{}-byte region, the channel needs {} bytes", shm.size(), total_size),
+            });
+        }
+
+        // SAFETY: from_raw_parts' caller vouches for the region's bytes
+        unsafe { std::ptr::write_bytes(shm.as_ptr(), 0, total_size) };
+        let daemon = Self::init(shm, &config, cmd_payload_size, total_size)?;
+        if let Some(core) = config.writer_core {
+            affinity::pin_current_thread(core)?;
+        }
+        Ok(daemon)
+    }
+
+    /// Lay the channel out in `shm`, zeroed and at least `total_size` bytes
+    fn init(shm: VenomShm, config: &ChannelConfig, cmd_payload_size: usize, total_size: usize) -> Result<Self> {
         let base = shm.as_ptr();
         let header = base as *mut ChannelHeader;
 
@@ -97,17 +591,38 @@ impl DaemonChannel {
         let seqlock_size = std::mem::size_of::<SeqLockHeader>() + config.data_size;
         let cmd_queue_offset =
             seqlock_offset + ((seqlock_size + CACHE_LINE_SIZE - 1) & !(CACHE_LINE_SIZE - 1));
+        let cmd_queue_size = MpscQueueHeader::size_for_slots(config.cmd_slots, cmd_payload_size);
+        let clients_offset =
+            cmd_queue_offset + ((cmd_queue_size + CACHE_LINE_SIZE - 1) & !(CACHE_LINE_SIZE - 1));
+        let clients_size = config.max_clients * std::mem::size_of::<ClientSlot>();
+        let responses_offset = clients_offset + ((clients_size + CACHE_LINE_SIZE - 1) & !(CACHE_LINE_SIZE - 1));
+        let responses_size = config.max_clients * ResponseSlot::size_for_payload(cmd_payload_size);
+        let events_offset = match config.event_log_slots {
+            0 => 0,
+            _ => responses_offset + ((responses_size + CACHE_LINE_SIZE - 1) & !(CACHE_LINE_SIZE - 1)),
+        };
 
         unsafe {
-            // Initialize header
-            (*header).magic = VENOM_MAGIC;
+            // Initialize header (the magic last, below: shells in other
+            // processes may map the region while it is being set up)
             (*header).version = VENOM_VERSION;
-            (*header).data_size = config.data_size;
-            (*header).cmd_slots = config.cmd_slots;
-            (*header).max_clients = config.max_clients;
+            (*header).data_size = AtomicU64::new(config.data_size as u64);
+            (*header).cmd_slots = config.cmd_slots as u64;
+            (*header).max_clients = config.max_clients as u64;
             (*header).next_client_id = AtomicU32::new(1);
-            (*header).seqlock_offset = seqlock_offset;
-            (*header).cmd_queue_offset = cmd_queue_offset;
+            (*header).client_count = AtomicU32::new(0);
+            (*header).owner_pid =
+                AtomicU32::new(rustix::process::getpid().as_raw_nonzero().get() as u32);
+            (*header).seqlock_offset = AtomicU64::new(seqlock_offset as u64);
+            (*header).cmd_queue_offset = cmd_queue_offset as u64;
+            (*header).clients_offset = clients_offset as u64;
+            (*header).data_generation = AtomicU32::new(0);
+            (*header).responses_offset = responses_offset as u64;
+            (*header).owner_generation = AtomicU32::new(0);
+            (*header).standby_pid = AtomicU32::new(0);
+            (*header).heartbeat_ns = AtomicU64::new(now_ns());
+            (*header).client_ids_wrapped = AtomicU32::new(0);
+            (*header).events_offset = events_offset as u64;
 
             // Initialize SeqLock
             let seqlock_header = base.add(seqlock_offset) as *mut SeqLockHeader;
@@ -115,53 +630,556 @@ impl DaemonChannel {
 
             // Initialize command queue
             let cmd_queue_header = base.add(cmd_queue_offset) as *mut MpscQueueHeader;
-            MpscQueueHeader::init(cmd_queue_header, config.cmd_slots);
+            MpscQueueHeader::init(cmd_queue_header, config.cmd_slots, cmd_payload_size, config.cmd_full_policy);
+
+            // Every client slot starts out free
+            let clients = base.add(clients_offset) as *mut ClientSlot;
+            std::ptr::write_bytes(clients, 0, config.max_clients);
+            let responses = base.add(responses_offset) as *mut ResponseSlot;
+            let events = (events_offset != 0)
+                .then(|| EventLog::init(base.add(events_offset) as *mut EventLogHeader, config.event_log_slots));
 
             // Create writer and consumer
             let data_ptr = base.add(seqlock_offset + std::mem::size_of::<SeqLockHeader>());
-            let data_writer = SeqLockWriter::from_raw(seqlock_header, data_ptr);
-            let cmd_consumer = MpscConsumer::from_raw(cmd_queue_header);
+            let data_writer = SeqLockWriter::from_raw(seqlock_header, data_ptr).stamped(config.stamp_writes);
+            let cmd_consumer = MpscConsumer::from_raw(cmd_queue_header).with_event_log(events);
+            if let Some(events) = &events {
+                events.log(ChannelEvent::DaemonCreated { pid: (*header).owner_pid.load(Ordering::Relaxed) });
+            }
+
+            std::sync::atomic::fence(Ordering::Release);
+            std::ptr::write_volatile(&mut (*header).magic, VENOM_MAGIC);
 
             Ok(Self {
                 shm,
                 header,
-                data_writer,
+                data: DataRegion { seqlock: data_writer, generation: 0, _mapping: None },
+                retiring: RefCell::new(Vec::new()),
+                shm_len: total_size,
+                cmd_queue: cmd_queue_header,
                 cmd_consumer,
+                clients,
+                max_clients: config.max_clients,
+                responses,
+                reassembly: RefCell::new(Reassembly::new(cmd_payload_size, config.large_command_timeout)),
+                owner_generation: 0,
+                filter: None,
+                filtered_commands: Cell::new(0),
+                handlers: RefCell::new(Handlers::default()),
+                events,
+            })
+        }
+    }
+
+    /// The daemon side of a channel another daemon created, for a standby
+    /// that has claimed it under `owner_generation`
+    ///
+    /// Writes go on from the data region's sequence and stamp them if the
+    /// last owner did; large commands the last owner was reassembling are
+    /// lost.
+    fn adopt(mut shm: VenomShm, owner_generation: u32) -> Result<Self> {
+        let base = shm.as_ptr();
+        let header = base as *mut ChannelHeader;
+        let size = shm.size();
+        let corrupt = |reason: String| VenomError::CorruptRegion { reason };
+
+        unsafe {
+            let cmd_queue_offset = to_usize((*header).cmd_queue_offset);
+            if !cmd_queue_offset.is_multiple_of(CACHE_LINE_SIZE) || cmd_queue_offset > size {
+                return Err(corrupt(format!("command queue at offset {} of a {}-byte channel", cmd_queue_offset, size)));
+            }
+            let cmd_queue = base.add(cmd_queue_offset) as *const MpscQueueHeader;
+            let (_, cmd_payload_size, _) = MpscQueueHeader::check_region(cmd_queue.cast(), size - cmd_queue_offset)?;
+
+            let max_clients = to_usize((*header).max_clients);
+            let clients_offset = to_usize((*header).clients_offset);
+            let responses_offset = to_usize((*header).responses_offset);
+            let region_fits = |offset: usize, slot_size: usize| {
+                offset != 0
+                    && offset.is_multiple_of(CACHE_LINE_SIZE)
+                    && max_clients
+                        .checked_mul(slot_size)
+                        .and_then(|len| len.checked_add(offset))
+                        .is_some_and(|end| end <= size)
+            };
+            if !region_fits(clients_offset, std::mem::size_of::<ClientSlot>())
+                || !region_fits(responses_offset, ResponseSlot::size_for_payload(cmd_payload_size))
+            {
+                return Err(corrupt(format!(
+                    "{}-client table at offset {} and responses at offset {} of a {}-byte channel",
+                    max_clients, clients_offset, responses_offset, size
+                )));
+            }
+
+            // The data region may have moved past the first mapping
+            let data_generation = (*header).data_generation.load(Ordering::Acquire);
+            let seqlock_offset = to_usize((*header).seqlock_offset.load(Ordering::Relaxed));
+            let data_size = to_usize((*header).data_size.load(Ordering::Relaxed));
+            let len = data_size.checked_add(std::mem::size_of::<SeqLockHeader>());
+            let end = len.and_then(|len| len.checked_add(seqlock_offset));
+            let shm_len = shm.file_len()?;
+            if !seqlock_offset.is_multiple_of(CACHE_LINE_SIZE) || end.is_none_or(|end| end > shm_len) {
+                return Err(corrupt(format!(
+                    "{}-byte data region at offset {} of a {}-byte channel",
+                    data_size, seqlock_offset, shm_len
+                )));
+            }
+            let mapping = match end {
+                Some(end) if end <= size => None,
+                _ => Some(shm.map_range(seqlock_offset, len.unwrap())?),
+            };
+            let seqlock_header = match &mapping {
+                Some(mapping) => mapping.as_ptr(),
+                None => base.add(seqlock_offset),
+            } as *mut SeqLockHeader;
+            if (*seqlock_header).data_size() > data_size {
+                return Err(corrupt(format!(
+                    "{}-byte data region in a {}-byte one",
+                    (*seqlock_header).data_size(),
+                    data_size
+                )));
+            }
+
+            // A write the last owner didn't finish leaves the sequence odd,
+            // and readers waiting for it to end
+            let sequence = &(*seqlock_header).sequence.0;
+            if sequence.load(Ordering::Acquire) % 2 == 1 {
+                sequence.fetch_add(1, Ordering::Release);
+            }
+            let events = event_log_in(base, header, size)?;
+            let stamp = (*seqlock_header).timestamp_ns.load(Ordering::Relaxed) != 0;
+            let data_ptr = seqlock_header.cast::<u8>().add(std::mem::size_of::<SeqLockHeader>());
+            let data_writer = SeqLockWriter::from_raw(seqlock_header, data_ptr).stamped(stamp);
+
+            // The name is this daemon's to remove now
+            shm.set_owner(true);
+            Ok(Self {
+                shm,
+                header,
+                data: DataRegion { seqlock: data_writer, generation: data_generation, _mapping: mapping },
+                retiring: RefCell::new(Vec::new()),
+                shm_len,
+                cmd_queue,
+                cmd_consumer: MpscConsumer::from_raw(cmd_queue).with_event_log(events),
+                clients: base.add(clients_offset) as *const ClientSlot,
+                max_clients,
+                responses: base.add(responses_offset) as *mut ResponseSlot,
+                reassembly: RefCell::new(Reassembly::new(cmd_payload_size, LARGE_COMMAND_TIMEOUT)),
+                owner_generation,
+                filter: None,
+                filtered_commands: Cell::new(0),
+                handlers: RefCell::new(Handlers::default()),
+                events,
             })
         }
     }
 
     /// Write data to the shared region
     ///
-    /// All connected shells will be able to read this data
+    /// All connected shells will be able to read this data. Once a standby
+    /// has taken the channel over ([`owns_channel`](Self::owns_channel) is
+    /// false) writes are dropped, here and below.
     #[inline]
     pub fn write_data(&self, data: &[u8]) {
-        self.data_writer.write(data);
+        if !self.heartbeat() {
+            return;
+        }
+        self.data.seqlock.write(data);
+        self.write_retiring(data.len(), |seqlock| seqlock.write(data));
     }
 
     /// Write data with length prefix (for variable-size data)
     #[inline]
     pub fn write_data_with_len(&self, data: &[u8]) {
-        self.data_writer.write_with_len(data);
+        if !self.heartbeat() {
+            return;
+        }
+        self.data.seqlock.write_with_len(data);
+        self.write_retiring(LEN_PREFIX + data.len(), |seqlock| seqlock.write_with_len(data));
+    }
+
+    /// Write `parts` one after another with a length prefix of their total,
+    /// in one write: shells read it as if
+    /// [`write_data_with_len`](Self::write_data_with_len) had written them
+    /// concatenated, and never see some parts without the others
+    ///
+    /// Saves copying a header and body into a buffer of their own first.
+    /// Past [`data_capacity`](Self::data_capacity) the parts are cut.
+    #[inline]
+    pub fn write_vectored(&self, parts: &[&[u8]]) {
+        if !self.heartbeat() {
+            return;
+        }
+        self.data.seqlock.write_vectored(parts);
+        let len = parts.iter().map(|part| part.len()).sum::<usize>();
+        self.write_retiring(LEN_PREFIX + len, |seqlock| seqlock.write_vectored(parts));
+    }
+
+    /// Whether the daemon still owns the channel: false once a
+    /// [standby](standby::StandbyChannel) has taken it over, after which it
+    /// must stop writing
+    ///
+    /// Writes check this first, and are dropped once it's false, as are
+    /// [`respond`](Self::respond) and
+    /// [`try_recv_command`](Self::try_recv_command). A daemon stalled in
+    /// the middle of a write when the standby takes over finishes that one
+    /// write.
+    #[inline]
+    pub fn owns_channel(&self) -> bool {
+        unsafe { (*self.header).owner_generation.load(Ordering::Acquire) == self.owner_generation }
+    }
+
+    /// Ownership generation the daemon owns the channel under: 0 for the
+    /// daemon that created it, one more for each standby that took it over
+    pub fn owner_generation(&self) -> u32 {
+        self.owner_generation
+    }
+
+    /// Tell a standby watching the channel that the daemon is alive;
+    /// returns [`owns_channel`](Self::owns_channel)
+    ///
+    /// Writes do this themselves: a daemon that may go longer than the
+    /// standby's [heartbeat timeout](standby::HEARTBEAT_TIMEOUT) without
+    /// writing calls it in between.
+    #[inline]
+    pub fn heartbeat(&self) -> bool {
+        if !self.owns_channel() {
+            return false;
+        }
+        unsafe { (*self.header).heartbeat_ns.store(now_ns(), Ordering::Relaxed) };
+        true
+    }
+
+    /// Size of the shared data region in bytes
+    #[inline]
+    pub fn data_size(&self) -> usize {
+        self.data.seqlock.data_size()
+    }
+
+    /// Longest payload [`write_data_with_len`](Self::write_data_with_len)
+    /// stores whole, the data region less its length prefix; longer ones
+    /// are cut short
+    #[inline]
+    pub fn data_capacity(&self) -> usize {
+        self.data_size().saturating_sub(LEN_PREFIX)
+    }
+
+    /// Grow the data region to `data_size` bytes, with shells staying
+    /// connected
+    ///
+    /// The new region is added past the end of the channel, starting out
+    /// with the current data and sequence, and shells move to it on their
+    /// next read. Until every connected shell has moved, or for at most
+    /// [`RESIZE_TIMEOUT`], writes that fit in the old region also go to it;
+    /// a shell still reading there gets the last one that did, rather than
+    /// one cut short. The new region
+    /// lies outside [`as_ptr`](Self::as_ptr)'s mapping.
+    ///
+    /// Fails with [`VenomError::InvalidConfig`] if `data_size` is smaller
+    /// than the current size, and [`VenomError::OwnershipLost`] once a
+    /// standby has taken the channel over.
+    pub fn resize_data(&mut self, data_size: usize) -> Result<()> {
+        self.check_owner()?;
+        let current = self.data_size();
+        if data_size < current {
+            return Err(VenomError::InvalidConfig {
+                reason: format!("data region can only grow: {} bytes asked, {} now", data_size, current),
+            });
+        }
+        if data_size == current {
+            return Ok(());
+        }
+
+        // mmap maps whole pages; starting on one wastes none of the region
+        let offset = self.shm_len.next_multiple_of(rustix::param::page_size());
+        let len = std::mem::size_of::<SeqLockHeader>() + data_size;
+        self.shm.set_len(offset + len)?;
+        let mapping = self.shm.map_range(offset, len)?;
+
+        let header = unsafe { &*self.header };
+        let generation = header.data_generation.load(Ordering::Relaxed).wrapping_add(1);
+        let seqlock = unsafe { self.data.seqlock.successor(mapping.as_ptr().cast(), data_size) };
+        header.seqlock_offset.store(offset as u64, Ordering::Relaxed);
+        header.data_size.store(data_size as u64, Ordering::Relaxed);
+        header.data_generation.store(generation, Ordering::Release);
+
+        let old = std::mem::replace(&mut self.data, DataRegion { seqlock, generation, _mapping: Some(mapping) });
+        self.retiring.get_mut().push((old, Instant::now()));
+        self.shm_len = offset + len;
+        self.log_event(ChannelEvent::Resized { data_size: data_size as u64 });
+        Ok(())
+    }
+
+    /// Repeat a write of `len` bytes in the regions the data moved out of
+    /// that hold it, dropping those every connected shell has left and
+    /// those past [`RESIZE_TIMEOUT`]
+    #[inline]
+    fn write_retiring(&self, len: usize, write: impl Fn(&SeqLockWriter)) {
+        let mut retiring = self.retiring.borrow_mut();
+        if retiring.is_empty() {
+            return;
+        }
+        retiring.retain(|(region, since)| since.elapsed() < RESIZE_TIMEOUT && !self.all_moved_past(region.generation));
+        for (region, _) in retiring.iter().filter(|(region, _)| len <= region.seqlock.data_size()) {
+            write(&region.seqlock);
+        }
+    }
+
+    /// Whether every connected shell reads a region published after `generation`
+    fn all_moved_past(&self, generation: u32) -> bool {
+        (0..self.max_clients).all(|i| unsafe {
+            let slot = self.clients.add(i);
+            let client_id = (*slot).client_id.load(Ordering::Acquire);
+            let reads = (*slot).data_generation.load(Ordering::Acquire);
+            client_id == 0 || client_id == CLIENT_CLAIMING || (reads.wrapping_sub(generation) as i32) > 0
+        })
     }
 
     /// Try to receive a command from any shell
     ///
-    /// Returns `Some((client_id, data_length))` if a command is available
+    /// Returns `Some((client_id, data_length))` if a command is available.
+    /// Commands longer than a slot, or from client IDs no shell was given,
+    /// are skipped and counted in [`stats`](Self::stats), as are commands
+    /// the [command filter](Self::set_command_filter) drops.
     #[inline]
     pub fn try_recv_command(&self, buf: &mut [u8]) -> Option<(u32, usize)> {
-        self.cmd_consumer.try_pop(buf)
+        if !self.owns_channel() {
+            return None;
+        }
+        // IDs are handed out from 1 up, and every one of them once the
+        // count has wrapped; the flag is set before it does
+        let next_client_id = unsafe { (*self.header).next_client_id.load(Ordering::Acquire) };
+        let wrapped = unsafe { (*self.header).client_ids_wrapped.load(Ordering::Acquire) } != 0;
+        let handed_out = |client_id| {
+            client_id != 0 && client_id != CLIENT_CLAIMING && (wrapped || client_id < next_client_id)
+        };
+        loop {
+            let (client_id, len) = self.cmd_consumer.try_pop_valid(buf, handed_out)?;
+            let Some(filter) = &self.filter else {
+                return Some((client_id, len));
+            };
+            let cmd = &buf[..len.min(buf.len())];
+            let action = match filter {
+                CommandFilter::ById(filter) => filter(client_id, cmd),
+                CommandFilter::ByName(filter) => filter(client_id, self.client_name(client_id).as_deref(), cmd),
+            };
+            match action {
+                FilterAction::Accept => return Some((client_id, len)),
+                FilterAction::Drop => {}
+                FilterAction::DropAndCount => self.filtered_commands.set(self.filtered_commands.get() + 1),
+            }
+        }
+    }
+
+    /// Have `filter` look at every command, with the sender's client ID,
+    /// before [`try_recv_command`](Self::try_recv_command) and everything
+    /// built on it hand it out, and drop the ones it doesn't accept
+    ///
+    /// Any process that can open the channel can send commands; this is
+    /// how a daemon turns away senders it doesn't want. Replaces the filter
+    /// installed before, if any. Without one, commands are only checked
+    /// for a valid client ID.
+    pub fn set_command_filter(&mut self, filter: impl Fn(u32, &[u8]) -> FilterAction + Send + 'static) {
+        self.filter = Some(CommandFilter::ById(Box::new(filter)));
+    }
+
+    /// [`set_command_filter`](Self::set_command_filter), with the name the
+    /// sender registered with, as [`client_name`](Self::client_name) gives it
+    ///
+    /// Names are informational, and any shell may claim any name: a name
+    /// filter keeps out confused clients, not hostile ones.
+    pub fn set_named_command_filter(
+        &mut self,
+        filter: impl Fn(u32, Option<&str>, &[u8]) -> FilterAction + Send + 'static,
+    ) {
+        self.filter = Some(CommandFilter::ByName(Box::new(filter)));
+    }
+
+    /// Have [`dispatch_pending`](Self::dispatch_pending) pass the payload
+    /// of every [typed command](typed_command) tagged `tag` to `handler`,
+    /// with the daemon and the sender's client ID
+    ///
+    /// Replaces the handler registered for `tag` before, if any.
+    pub fn on(&mut self, tag: u16, handler: impl FnMut(&DaemonChannel, u32, &[u8]) + Send + 'static) {
+        self.handlers.get_mut().insert(tag, Box::new(handler));
+    }
+
+    /// Have [`dispatch_pending`](Self::dispatch_pending) pass `handler` the
+    /// commands no handler of [`on`](Self::on) takes: those of other tags,
+    /// and those that aren't typed at all
+    ///
+    /// It gets the whole command, envelope included;
+    /// [`typed_command::parse`] takes it apart. Without a fallback such
+    /// commands are dropped.
+    pub fn on_unknown(&mut self, handler: impl FnMut(&DaemonChannel, u32, &[u8]) + Send + 'static) {
+        self.handlers.get_mut().set_unknown(Box::new(handler));
+    }
+
+    /// Take every queued command and pass it to the handler of its tag, as
+    /// registered with [`on`](Self::on) and [`on_unknown`](Self::on_unknown);
+    /// returns how many were taken
+    ///
+    /// Commands pass the [command filter](Self::set_command_filter) first,
+    /// as with [`try_recv_command`](Self::try_recv_command). A handler may
+    /// write, respond and take commands itself; dispatching again from
+    /// inside one takes nothing and returns 0.
+    pub fn dispatch_pending(&self) -> usize {
+        let Ok(mut handlers) = self.handlers.try_borrow_mut() else {
+            return 0;
+        };
+        let mut cmd_buf = [0u8; MAX_CMD_SIZE];
+        let mut taken = 0;
+        while let Some((client_id, len)) = self.try_recv_command(&mut cmd_buf) {
+            handlers.dispatch(self, client_id, &cmd_buf[..len]);
+            taken += 1;
+        }
+        taken
+    }
+
+    /// Remove the command filter, accepting every command again
+    pub fn clear_command_filter(&mut self) {
+        self.filter = None;
     }
 
     /// Receive a command, spinning until one is available
     #[inline]
     pub fn recv_command(&self, buf: &mut [u8]) -> (u32, usize) {
-        self.cmd_consumer.pop(buf)
+        loop {
+            if let Some(result) = self.try_recv_command(buf) {
+                return result;
+            }
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Try to receive a message sent with
+    /// [`send_large_command`](ShellChannel::send_large_command), putting
+    /// its chunks back together
+    ///
+    /// Returns `Some((client_id, message_length))` once a message is whole,
+    /// with the message in `buf`. Commands sent without chunking are
+    /// returned as they are, so a daemon can receive both through this;
+    /// see [`large_command`] for the chunk format and when unfinished
+    /// messages are discarded.
+    pub fn try_recv_large_command(&self, buf: &mut Vec<u8>) -> Option<(u32, usize)> {
+        let mut reassembly = self.reassembly.borrow_mut();
+        reassembly.expire(Instant::now());
+        let mut cmd = [0u8; MAX_CMD_SIZE];
+        while let Some((client_id, len)) = self.try_recv_command(&mut cmd) {
+            let message = match Envelope::parse(&cmd[..len]) {
+                Some((envelope, payload)) => reassembly.feed(client_id, envelope, payload, Instant::now()),
+                None => Some(cmd[..len].to_vec()),
+            };
+            if let Some(message) = message {
+                *buf = message;
+                return Some((client_id, buf.len()));
+            }
+        }
+        None
+    }
+
+    /// Longest command a shell can send, `cmd_payload_size` as the
+    /// channel rounded it
+    pub fn cmd_payload_size(&self) -> usize {
+        self.cmd_consumer.cmd_payload_size()
+    }
+
+    /// Commands received so far, how many of them were dropped as corrupt
+    /// or by the command filter, how many shells dropped before they were
+    /// received, and how often shells found the queue full
+    pub fn stats(&self) -> DaemonStats {
+        let queue = unsafe { &*self.cmd_queue };
+        DaemonStats {
+            commands_received: queue.consumed(),
+            corrupt_slots: queue.corrupt_slots(),
+            dropped_commands: queue.dropped_commands(),
+            discarded_large_commands: self.reassembly.borrow().discarded(),
+            total_full_events: queue.total_full_events(),
+            filtered_commands: self.filtered_commands.get(),
+        }
+    }
+
+    /// The events still in the channel's [event log](crate::event_log),
+    /// oldest first; none if the daemon was created with
+    /// `event_log_slots: 0`
+    pub fn recent_events(&self) -> Vec<LoggedEvent> {
+        self.events.map_or_else(Vec::new, |events| events.recent())
+    }
+
+    /// Log `event` to the channel's event log, if it has one
+    pub(crate) fn log_event(&self, event: ChannelEvent) {
+        if let Some(events) = &self.events {
+            events.log(event);
+        }
+    }
+
+    /// Name the shell with this client ID gave
+    /// [`connect_named`](ShellChannel::connect_named), if it's connected
+    /// and gave one
+    ///
+    /// Names are informational: several shells may use the same one.
+    pub fn client_name(&self, client_id: u32) -> Option<String> {
+        self.entries().find(|entry| entry.client_id == client_id)?.name
+    }
+
+    /// The connected shells: client ID, name and when they connected
+    pub fn clients(&self) -> impl Iterator<Item = (u32, Option<String>, SystemTime)> + '_ {
+        self.entries().map(|entry| (entry.client_id, entry.name, entry.connected_since))
+    }
+
+    /// Number of shells currently connected, read-only ones not included
+    pub fn client_count(&self) -> u32 {
+        unsafe { (*self.header).client_count.load(Ordering::Acquire) }
+    }
+
+    /// Send `data` to the shell with this client ID alone, for its
+    /// [`try_recv_response`](ShellChannel::try_recv_response) or
+    /// [`request_timeout`](ShellChannel::request_timeout)
+    ///
+    /// A shell holds one response: one it hasn't taken yet is replaced.
+    /// Fails with [`VenomError::InvalidClientId`] if no such shell is
+    /// connected, [`VenomError::BufferOverflow`] if `data` is longer
+    /// than [`cmd_payload_size`](Self::cmd_payload_size), and
+    /// [`VenomError::OwnershipLost`] once a standby has taken the channel over.
+    pub fn respond(&self, client_id: u32, data: &[u8]) -> Result<()> {
+        self.check_owner()?;
+        let cmd_payload_size = self.cmd_payload_size();
+        if data.len() > cmd_payload_size {
+            return Err(VenomError::BufferOverflow { max: cmd_payload_size, got: data.len() });
+        }
+        let index = (0..self.max_clients)
+            .find(|&i| client_id != 0 && unsafe { (*self.clients.add(i)).client_id.load(Ordering::Acquire) } == client_id)
+            .ok_or(VenomError::InvalidClientId(client_id))?;
+
+        unsafe {
+            let slot = self.responses.cast::<u8>().add(index * ResponseSlot::size_for_payload(cmd_payload_size))
+                as *mut ResponseSlot;
+            (*slot).sequence.fetch_add(1, Ordering::Release);
+            (*slot).client_id.store(client_id, Ordering::Relaxed);
+            (*slot).len.store(data.len() as u32, Ordering::Relaxed);
+            std::ptr::copy_nonoverlapping(data.as_ptr(), ResponseSlot::data(slot).cast_mut(), data.len());
+            std::sync::atomic::fence(Ordering::Release);
+            (*slot).sequence.fetch_add(1, Ordering::Release);
+        }
+        Ok(())
+    }
+
+    fn check_owner(&self) -> Result<()> {
+        if self.owns_channel() {
+            return Ok(());
+        }
+        let generation = unsafe { (*self.header).owner_generation.load(Ordering::Acquire) };
+        Err(VenomError::OwnershipLost { generation })
+    }
+
+    fn entries(&self) -> impl Iterator<Item = ClientEntry> + '_ {
+        (0..self.max_clients).filter_map(|i| unsafe { ClientSlot::entry(self.clients.add(i)) })
     }
 
     /// Run the daemon loop with a handler function
     ///
-    /// The handler receives (client_id, command) and returns the response data
+    /// The handler receives (client_id, command) and returns the response data.
+    /// Corrupt commands never reach it; [`stats`](Self::stats) counts them.
     pub fn run<F>(&self, mut handler: F)
     where
         F: FnMut(u32, &[u8]) -> Vec<u8>,
@@ -185,25 +1203,131 @@ impl DaemonChannel {
         }
     }
 
+    /// [`run`](Self::run), with the handler spread over `workers` threads
+    /// so one slow command doesn't hold up every other client
+    ///
+    /// This thread keeps popping commands and hands each to worker
+    /// `client_id % workers`, so a client's commands are handled in the
+    /// order it sent them; commands of different clients may finish in any
+    /// order. Responses come back to this thread, which stays the only
+    /// writer of the data region. `__SHUTDOWN__` stops taking commands, and
+    /// returns once those already handed out are handled and written.
+    pub fn run_parallel<F>(&self, workers: usize, handler: F)
+    where
+        F: Fn(u32, &[u8]) -> Vec<u8> + Send + Sync,
+    {
+        let workers = workers.max(1);
+        let mut cmd_buf = [0u8; MAX_CMD_SIZE];
+        let (response_tx, responses) = mpsc::channel::<Vec<u8>>();
+
+        std::thread::scope(|scope| {
+            let queues: Vec<_> = (0..workers)
+                .map(|_| {
+                    let (queue, commands) = mpsc::channel::<(u32, Vec<u8>)>();
+                    let (response_tx, handler) = (response_tx.clone(), &handler);
+                    scope.spawn(move || {
+                        for (client_id, cmd) in commands {
+                            if response_tx.send(handler(client_id, &cmd)).is_err() {
+                                break;
+                            }
+                        }
+                    });
+                    queue
+                })
+                .collect();
+            drop(response_tx);
+
+            loop {
+                for response in responses.try_iter() {
+                    self.write_data_with_len(&response);
+                }
+                let Some((client_id, cmd_len)) = self.try_recv_command(&mut cmd_buf) else {
+                    core::hint::spin_loop();
+                    continue;
+                };
+                let cmd = &cmd_buf[..cmd_len];
+                if cmd == b"__SHUTDOWN__" {
+                    break;
+                }
+                // Fails only if the worker panicked, which the scope re-raises
+                let _ = queues[client_id as usize % workers].send((client_id, cmd.to_vec()));
+            }
+
+            // Workers finish their queues and hang up
+            drop(queues);
+            for response in responses {
+                self.write_data_with_len(&response);
+            }
+        });
+    }
+
     /// Get the namespace of the channel
     pub fn namespace(&self) -> &str {
         self.shm.name()
     }
 
+    /// Remove the channel's name now instead of on drop: no new shell can
+    /// connect, and the ones connected keep reading until they see the
+    /// daemon gone
+    ///
+    /// Returns false if the name had already been removed or taken over by
+    /// another daemon. Dropping the channel afterwards does nothing more.
+    pub fn unlink(&self) -> Result<bool> {
+        self.shm.unlink_own()
+    }
+
     /// Get raw pointer to shared memory base
     pub fn as_ptr(&self) -> *mut u8 {
         self.shm.as_ptr()
     }
 }
 
+impl Drop for DaemonChannel {
+    fn drop(&mut self) {
+        let header = unsafe { &*self.header };
+        let standby = header.standby_pid.load(Ordering::Acquire);
+        if !self.owns_channel() {
+            // The name belongs to the standby that took over
+            self.shm.set_owner(false);
+        } else if standby != 0 && process_alive(standby) {
+            // Hand the channel to the standby rather than remove it
+            self.shm.set_owner(false);
+            header.owner_pid.store(0, Ordering::Release);
+        }
+    }
+}
+
 /// Shell (Reader) side of the channel
 pub struct ShellChannel {
     shm: VenomShm,
-    #[allow(dead_code)]
     header: *const ChannelHeader,
-    data_reader: SeqLockReader,
-    cmd_producer: MpscProducer,
+    /// Region reads go to, one of `regions`
+    data: AtomicPtr<DataRegion<SeqLockReader>>,
+    /// Every region the shell has mapped; kept until it's dropped, as
+    /// another thread may still be reading an old one, and boxed so `data`
+    /// stays valid while the list grows
+    #[allow(clippy::vec_box)]
+    regions: Mutex<Vec<Box<DataRegion<SeqLockReader>>>>,
+    cmd_queue: *const MpscQueueHeader,
+    cmd_slots: usize,
+    cmd_payload_size: usize,
+    /// None for a read-only shell
+    cmd_producer: Option<MpscProducer>,
     client_id: u32,
+    /// Entry in the client table; None for a read-only shell, or in a
+    /// channel without a table
+    client_slot: Option<*mut ClientSlot>,
+    /// Response slot of `client_slot`; None for a read-only shell, or in a
+    /// channel without response slots
+    response: Option<*const ResponseSlot>,
+    /// `sequence` of the last response slot contents looked at
+    response_seq: AtomicU64,
+    /// ID of the next message [`send_large_command`](Self::send_large_command) sends
+    next_message_id: AtomicU32,
+    /// Generation of the newest [`Frame`](snapshot::Frame) taken
+    last_snapshot: AtomicU64,
+    /// None in a channel without an event log
+    events: Option<EventLog>,
 }
 
 // SAFETY: ShellChannel uses atomic operations
@@ -213,45 +1337,310 @@ unsafe impl Sync for ShellChannel {}
 impl ShellChannel {
     /// Connect to an existing channel as a shell (reader/command sender)
     pub fn connect(namespace: &str) -> Result<Self> {
-        let shm = VenomShm::open(namespace)?;
+        Self::connect_resolved(namespace::resolve(namespace, false)?, "")
+    }
+
+    /// [`connect`](Self::connect), registering a name the daemon can look
+    /// up with [`DaemonChannel::client_name`], e.g. for its logs
+    ///
+    /// The name is at most [`CLIENT_NAME_MAX`] bytes; several shells may
+    /// use the same one.
+    pub fn connect_named(namespace: &str, client_name: &str) -> Result<Self> {
+        if client_name.len() > CLIENT_NAME_MAX {
+            return Err(VenomError::InvalidClientName {
+                reason: format!("{} bytes, the limit is {}", client_name.len(), CLIENT_NAME_MAX),
+            });
+        }
+        Self::connect_resolved(namespace::resolve(namespace, false)?, client_name)
+    }
+
+    /// Connect to a channel created with [`ChannelConfig::user_scoped`]
+    pub fn connect_user_scoped(namespace: &str) -> Result<Self> {
+        Self::connect_resolved(namespace::resolve(namespace, true)?, "")
+    }
+
+    /// Map a channel read-only, to watch it without taking part
+    ///
+    /// The shell doesn't register with the channel: it has no client ID
+    /// (`client_id()` is 0) and isn't counted among the connected shells.
+    /// It reads data like any other shell but can't send commands.
+    pub fn connect_read_only(namespace: &str) -> Result<Self> {
+        Self::open_resolved(namespace::resolve(namespace, false)?, None)
+    }
+
+    /// Connect to a channel [created](DaemonChannel::create_in) in a
+    /// region mapped by someone else, e.g. one from
+    /// [`VenomShm::from_raw_parts`] over the same bytes as the daemon's
+    ///
+    /// The region is checked as [`connect`](Self::connect) checks a named
+    /// channel's; one not aligned to a cache line fails with
+    /// [`VenomError::InvalidConfig`]. The shell can't follow the daemon to
+    /// a [resized](DaemonChannel::resize_data) data region unless `shm` is
+    /// a named object.
+    pub fn connect_in(shm: VenomShm) -> Result<Self> {
+        check_region(&shm)?;
+        Self::open_in(shm, Some(""))
+    }
+
+    fn connect_resolved(name: String, client_name: &str) -> Result<Self> {
+        Self::open_resolved(name, Some(client_name))
+    }
+
+    /// Map the channel; `client_name` is None for a read-only shell
+    fn open_resolved(name: String, client_name: Option<&str>) -> Result<Self> {
+        let shm = if client_name.is_none() { VenomShm::open_read_only(&name)? } else { VenomShm::open(&name)? };
+        Self::open_in(shm, client_name)
+    }
+
+    /// Take part in the channel mapped by `shm`
+    fn open_in(shm: VenomShm, client_name: Option<&str>) -> Result<Self> {
+        let read_only = client_name.is_none();
         let base = shm.as_ptr();
         let header = base as *const ChannelHeader;
+        let size = shm.size();
+        if size < std::mem::size_of::<ChannelHeader>() {
+            return Err(VenomError::CorruptRegion {
+                reason: format!("{} bytes can't hold a channel header", size),
+            });
+        }
 
         unsafe {
-            // Validate magic
-            let magic = (*header).magic;
+            // Validate magic; the rest of the header is only complete once it's set
+            let magic = std::ptr::read_volatile(&(*header).magic);
             if magic != VENOM_MAGIC {
                 return Err(VenomError::InvalidMagic {
                     expected: VENOM_MAGIC,
                     got: magic,
                 });
             }
+            std::sync::atomic::fence(Ordering::Acquire);
+            let version = (*header).version;
+            if version != VENOM_VERSION {
+                return Err(VenomError::UnsupportedVersion { expected: VENOM_VERSION, got: version });
+            }
 
-            // Get client ID
-            let client_id = (*header).next_client_id.fetch_add(1, Ordering::AcqRel);
+            // Every region the header points at has to lie inside the
+            // mapping; sizes are read once and kept
+            let data_generation = (*header).data_generation.load(Ordering::Acquire);
+            let seqlock_offset = to_usize((*header).seqlock_offset.load(Ordering::Relaxed));
+            let cmd_queue_offset = to_usize((*header).cmd_queue_offset);
+            let data_offset = seqlock_offset.checked_add(std::mem::size_of::<SeqLockHeader>());
+            if !seqlock_offset.is_multiple_of(CACHE_LINE_SIZE)
+                || !cmd_queue_offset.is_multiple_of(CACHE_LINE_SIZE)
+                || cmd_queue_offset > size
+            {
+                return Err(VenomError::CorruptRegion {
+                    reason: format!(
+                        "regions at offsets {} and {} of a {}-byte channel",
+                        seqlock_offset, cmd_queue_offset, size
+                    ),
+                });
+            }
 
-            // Get offsets
-            let seqlock_offset = (*header).seqlock_offset;
-            let cmd_queue_offset = (*header).cmd_queue_offset;
+            // A data region the daemon grew into since the object was
+            // mapped lies past the mapping
+            let data = match data_offset {
+                Some(data_offset) if data_offset <= size => {
+                    let seqlock_header = base.add(seqlock_offset) as *const SeqLockHeader;
+                    let data_reader = SeqLockReader::from_raw(seqlock_header, base.add(data_offset));
+                    if data_reader.data_size() > size - data_offset {
+                        return Err(VenomError::CorruptRegion {
+                            reason: format!(
+                                "{}-byte data region at offset {} of a {}-byte channel",
+                                data_reader.data_size(),
+                                data_offset,
+                                size
+                            ),
+                        });
+                    }
+                    DataRegion { seqlock: data_reader, generation: data_generation, _mapping: None }
+                }
+                _ => Self::map_data_region(&shm, header, data_generation)?,
+            };
 
-            // Create reader and producer
-            let seqlock_header = base.add(seqlock_offset) as *const SeqLockHeader;
-            let data_ptr = base.add(seqlock_offset + std::mem::size_of::<SeqLockHeader>());
-            let data_reader = SeqLockReader::from_raw(seqlock_header, data_ptr);
+            let cmd_queue = base.add(cmd_queue_offset) as *const MpscQueueHeader;
+            let (cmd_slots, cmd_payload_size, cmd_full_policy) =
+                MpscQueueHeader::check_region(cmd_queue.cast(), size - cmd_queue_offset)?;
 
-            let cmd_queue_header = base.add(cmd_queue_offset) as *const MpscQueueHeader;
-            let cmd_producer = MpscProducer::from_raw(cmd_queue_header, client_id);
+            // Daemons older than the client table leave its offset at 0
+            let clients_offset = to_usize((*header).clients_offset);
+            let max_clients = to_usize((*header).max_clients);
+            let clients = if clients_offset == 0 {
+                None
+            } else {
+                let clients_end = max_clients
+                    .checked_mul(std::mem::size_of::<ClientSlot>())
+                    .and_then(|len| len.checked_add(clients_offset));
+                if !clients_offset.is_multiple_of(CACHE_LINE_SIZE) || clients_end.is_none_or(|end| end > size) {
+                    return Err(VenomError::CorruptRegion {
+                        reason: format!(
+                            "{}-client table at offset {} of a {}-byte channel",
+                            max_clients, clients_offset, size
+                        ),
+                    });
+                }
+                Some(base.add(clients_offset) as *mut ClientSlot)
+            };
 
+            // Daemons older than the response slots leave their offset at 0
+            let responses_offset = to_usize((*header).responses_offset);
+            let responses = if responses_offset == 0 {
+                None
+            } else {
+                let responses_end = max_clients
+                    .checked_mul(ResponseSlot::size_for_payload(cmd_payload_size))
+                    .and_then(|len| len.checked_add(responses_offset));
+                if !responses_offset.is_multiple_of(CACHE_LINE_SIZE) || responses_end.is_none_or(|end| end > size) {
+                    return Err(VenomError::CorruptRegion {
+                        reason: format!(
+                            "{} response slots at offset {} of a {}-byte channel",
+                            max_clients, responses_offset, size
+                        ),
+                    });
+                }
+                Some(base.add(responses_offset))
+            };
+
+            let events = event_log_in(base, header, size)?;
+
+            // Get client ID, and a slot in the client table
+            let (client_id, client_slot) = match client_name {
+                None => (0, None),
+                Some(client_name) => {
+                    let client_id = take_client_id(header);
+                    let client_slot = match clients {
+                        Some(table) => Some(
+                            ClientSlot::claim(table, max_clients, client_id, client_name, data_generation)
+                                .ok_or(VenomError::TooManyClients { max: max_clients })?,
+                        ),
+                        None => None,
+                    };
+                    (*header).client_count.fetch_add(1, Ordering::AcqRel);
+                    if let Some(events) = &events {
+                        let pid = rustix::process::getpid().as_raw_nonzero().get() as u32;
+                        events.log(ChannelEvent::ClientConnected { client_id, pid });
+                    }
+                    (client_id, client_slot)
+                }
+            };
+
+            let response = match (client_slot, clients, responses) {
+                (Some(slot), Some(table), Some(responses)) => {
+                    let index = slot.offset_from(table) as usize;
+                    Some(responses.add(index * ResponseSlot::size_for_payload(cmd_payload_size)) as *const ResponseSlot)
+                }
+                _ => None,
+            };
+            // A response left for a shell that had the slot before isn't this one's
+            let response_seq = response.map_or(0, |slot| (*slot).sequence.load(Ordering::Acquire));
+
+            let cmd_producer =
+                (!read_only).then(|| {
+                    MpscProducer::with_slots(cmd_queue, cmd_slots, cmd_payload_size, cmd_full_policy, client_id)
+                        .with_event_log(events)
+                });
+
+            let mut data = Box::new(data);
             Ok(Self {
                 shm,
                 header,
-                data_reader,
+                data: AtomicPtr::new(&mut *data),
+                regions: Mutex::new(vec![data]),
+                cmd_queue,
+                cmd_slots,
+                cmd_payload_size,
                 cmd_producer,
                 client_id,
+                client_slot,
+                response,
+                response_seq: AtomicU64::new(response_seq),
+                next_message_id: AtomicU32::new(0),
+                last_snapshot: AtomicU64::new(0),
+                events,
             })
         }
     }
 
+    /// Map the data region the header points at, one the daemon grew into
+    ///
+    /// # Safety
+    /// `header` must be the channel header in `shm`'s mapping.
+    unsafe fn map_data_region(
+        shm: &VenomShm,
+        header: *const ChannelHeader,
+        generation: u32,
+    ) -> Result<DataRegion<SeqLockReader>> {
+        let seqlock_offset = to_usize((*header).seqlock_offset.load(Ordering::Relaxed));
+        let data_size = to_usize((*header).data_size.load(Ordering::Relaxed));
+        let len = data_size.checked_add(std::mem::size_of::<SeqLockHeader>());
+        let shm_len = shm.file_len()?;
+        if !seqlock_offset.is_multiple_of(CACHE_LINE_SIZE)
+            || len.and_then(|len| len.checked_add(seqlock_offset)).is_none_or(|end| end > shm_len)
+        {
+            return Err(VenomError::CorruptRegion {
+                reason: format!(
+                    "{}-byte data region at offset {} of a {}-byte channel",
+                    data_size, seqlock_offset, shm_len
+                ),
+            });
+        }
+
+        let mapping = shm.map_range(seqlock_offset, len.unwrap())?;
+        let seqlock_header = mapping.as_ptr() as *const SeqLockHeader;
+        let data_reader =
+            SeqLockReader::from_raw(seqlock_header, mapping.as_ptr().add(std::mem::size_of::<SeqLockHeader>()));
+        if data_reader.data_size() > data_size {
+            return Err(VenomError::CorruptRegion {
+                reason: format!(
+                    "{}-byte data region in a {}-byte mapping",
+                    data_reader.data_size(),
+                    data_size
+                ),
+            });
+        }
+        Ok(DataRegion { seqlock: data_reader, generation, _mapping: Some(mapping) })
+    }
+
+    /// Reader of the current data region, moving to a new one first if the
+    /// daemon [resized](DaemonChannel::resize_data) it
+    #[inline]
+    fn data_reader(&self) -> &SeqLockReader {
+        // SAFETY: regions live as long as the shell
+        let region = unsafe { &*self.data.load(Ordering::Acquire) };
+        let generation = unsafe { (*self.header).data_generation.load(Ordering::Acquire) };
+        if generation == region.generation {
+            &region.seqlock
+        } else {
+            self.remap_data(generation)
+        }
+    }
+
+    #[cold]
+    fn remap_data(&self, generation: u32) -> &SeqLockReader {
+        let mut regions = self.regions.lock().unwrap_or_else(PoisonError::into_inner);
+        // Another thread may have moved meanwhile
+        let current = unsafe { &*self.data.load(Ordering::Acquire) };
+        if current.generation == generation {
+            return &current.seqlock;
+        }
+
+        match unsafe { Self::map_data_region(&self.shm, self.header, generation) } {
+            Ok(region) => {
+                let mut region = Box::new(region);
+                let ptr: *mut DataRegion<SeqLockReader> = &mut *region;
+                regions.push(region);
+                self.data.store(ptr, Ordering::Release);
+                if let Some(slot) = self.client_slot {
+                    unsafe { (*slot).data_generation.store(generation, Ordering::Release) };
+                }
+                unsafe { &(*ptr).seqlock }
+            }
+            // The daemon keeps the old region up to date for a while, so
+            // read that one and try again next time
+            Err(_) => &current.seqlock,
+        }
+    }
+
     /// Get this client's ID
     #[inline]
     pub fn client_id(&self) -> u32 {
@@ -263,7 +1652,34 @@ impl ShellChannel {
     /// Returns the number of bytes read
     #[inline]
     pub fn read_data(&self, buf: &mut [u8]) -> usize {
-        self.data_reader.read(buf)
+        self.data_reader().read(buf)
+    }
+
+    /// [`read_data`](Self::read_data) into a buffer that needn't be
+    /// initialized, such as a `Vec`'s spare capacity, saving zeroing it
+    /// first
+    ///
+    /// Returns the number of bytes read, all of them initialized.
+    #[inline]
+    pub fn read_into_uninit(&self, buf: &mut [MaybeUninit<u8>]) -> usize {
+        self.data_reader().read_uninit(buf)
+    }
+
+    /// [`read_data`](Self::read_data), with the timestamp of the write the
+    /// data comes from: nanoseconds of `CLOCK_MONOTONIC_RAW`, comparable
+    /// with [`seqlock::now_ns`](crate::seqlock::now_ns) on the same
+    /// machine only. 0 unless the daemon set
+    /// [`ChannelConfig::stamp_writes`].
+    #[inline]
+    pub fn read_data_stamped(&self, buf: &mut [u8]) -> (u64, usize) {
+        self.data_reader().read_stamped(buf)
+    }
+
+    /// Timestamp of the daemon's last write, as in
+    /// [`read_data_stamped`](Self::read_data_stamped)
+    #[inline]
+    pub fn last_write_timestamp(&self) -> u64 {
+        self.data_reader().timestamp()
     }
 
     /// Read data with length prefix
@@ -271,39 +1687,158 @@ impl ShellChannel {
     /// Returns the actual data length
     #[inline]
     pub fn read_data_with_len(&self, buf: &mut [u8]) -> usize {
-        self.data_reader.read_with_len(buf)
+        self.data_reader().read_with_len(buf)
+    }
+
+    /// Read data with length prefix, failing with
+    /// [`VenomError::InvalidLength`] if the prefix claims more than the
+    /// data region holds
+    #[inline]
+    pub fn read_data_with_len_checked(&self, buf: &mut [u8]) -> Result<usize> {
+        self.data_reader().read_with_len_checked(buf)
+    }
+
+    /// Read data with length prefix, reading again while the prefix claims
+    /// more than the data region holds and failing with
+    /// [`VenomError::Corrupted`] if it keeps doing so
+    #[inline]
+    pub fn try_read_data_with_len(&self, buf: &mut [u8]) -> Result<usize> {
+        self.data_reader().try_read_with_len(buf)
+    }
+
+    /// [`try_read_data_with_len`](Self::try_read_data_with_len), with the
+    /// write's timestamp as in [`read_data_stamped`](Self::read_data_stamped)
+    #[inline]
+    pub fn try_read_data_with_len_stamped(&self, buf: &mut [u8]) -> Result<(u64, usize)> {
+        self.data_reader().try_read_with_len_stamped(buf)
+    }
+
+    /// Size of the shared data region in bytes
+    #[inline]
+    pub fn data_size(&self) -> usize {
+        self.data_reader().data_size()
+    }
+
+    /// Longest payload the daemon's length-prefixed writes hold, so a
+    /// buffer this long always fits what
+    /// [`read_data_with_len`](Self::read_data_with_len) returns
+    ///
+    /// It grows if the daemon [resizes](DaemonChannel::resize_data) the
+    /// data region.
+    #[inline]
+    pub fn data_capacity(&self) -> usize {
+        self.data_size().saturating_sub(LEN_PREFIX)
+    }
+
+    /// Sequence number of the data region
+    ///
+    /// Even values are stable and change on every daemon write; odd values
+    /// mean a write is in progress.
+    #[inline]
+    pub fn data_sequence(&self) -> u64 {
+        self.data_reader().sequence()
     }
 
     /// Try to read data (non-blocking)
     #[inline]
     pub fn try_read_data(&self, buf: &mut [u8]) -> Option<usize> {
-        self.data_reader.try_read(buf)
+        self.data_reader().try_read(buf)
     }
 
     /// Send a command to the daemon
     ///
-    /// Returns `true` if successful, `false` if queue is full
+    /// Returns `true` if successful, `false` if queue is full, the command
+    /// is longer than [`cmd_payload_size`](Self::cmd_payload_size) or the
+    /// shell is read-only. A channel created with
+    /// [`CmdFullPolicy::OverwriteOldest`] is never full: the oldest command
+    /// is dropped instead.
     #[inline]
     pub fn try_send_command(&self, cmd: &[u8]) -> bool {
-        self.cmd_producer.try_push(cmd)
+        self.cmd_producer.as_ref().is_some_and(|producer| producer.try_push(cmd))
+    }
+
+    /// Send a command, waiting up to `timeout` for space in a full queue
+    ///
+    /// The wait spins, then yields, then sleeps in short steps; see
+    /// [`MpscProducer::push_timeout`]. Fails with [`VenomError::QueueFull`]
+    /// once `timeout` has passed (counted in
+    /// [`rejected_sends`](Self::rejected_sends)),
+    /// [`VenomError::BufferOverflow`] if the command is longer than
+    /// [`cmd_payload_size`](Self::cmd_payload_size), and
+    /// [`VenomError::ReadOnly`] on a read-only shell.
+    pub fn send_command_timeout(&self, cmd: &[u8], timeout: Duration) -> Result<()> {
+        let producer = self.cmd_producer.as_ref().ok_or(VenomError::ReadOnly)?;
+        producer.push_timeout(cmd, timeout)
     }
 
-    /// Send a command, spinning until space is available
+    /// Sends of this shell that gave up because the queue was full: failed
+    /// [`try_send_command`](Self::try_send_command)s and timed out
+    /// [`send_command_timeout`](Self::send_command_timeout)s
+    ///
+    /// The daemon sees how often any shell found the queue full in
+    /// [`DaemonStats::total_full_events`].
+    pub fn rejected_sends(&self) -> u64 {
+        self.cmd_producer.as_ref().map_or(0, MpscProducer::rejected_sends)
+    }
+
+    /// Send a command, spinning until space is available (never, under
+    /// [`CmdFullPolicy::OverwriteOldest`])
+    ///
+    /// # Panics
+    /// If the shell is read-only ([`connect_read_only`](Self::connect_read_only)),
+    /// or the command is longer than [`cmd_payload_size`](Self::cmd_payload_size)
     #[inline]
     pub fn send_command(&self, cmd: &[u8]) {
-        self.cmd_producer.push(cmd)
+        self.producer().push(cmd)
+    }
+
+    /// Send a message of up to [`MAX_LARGE_COMMAND`](large_command::MAX_LARGE_COMMAND)
+    /// bytes, in as many commands as it takes, for
+    /// [`DaemonChannel::try_recv_large_command`] to put back together
+    ///
+    /// Spins while the queue is full, like [`send_command`](Self::send_command);
+    /// under [`CmdFullPolicy::OverwriteOldest`] a chunk may be dropped
+    /// instead, and the daemon then discards the message. Fails with
+    /// [`VenomError::ReadOnly`] on a read-only shell,
+    /// [`VenomError::BufferOverflow`] if the message is too long, and
+    /// [`VenomError::InvalidConfig`] if the channel's commands are too short
+    /// to carry a chunk.
+    pub fn send_large_command(&self, message: &[u8]) -> Result<()> {
+        let producer = self.cmd_producer.as_ref().ok_or(VenomError::ReadOnly)?;
+        let message_id = self.next_message_id.fetch_add(1, Ordering::Relaxed);
+        large_command::send_chunks(message_id, message, self.cmd_payload_size, |cmd| producer.push(cmd))
+    }
+
+    /// Send `payload` as a [typed command](typed_command) tagged `tag`, for
+    /// the daemon's handler of that tag
+    /// ([`DaemonChannel::on`]); the envelope takes
+    /// [`ENVELOPE_SIZE`](typed_command::ENVELOPE_SIZE) bytes of the command
+    ///
+    /// Spins while the queue is full, like [`send_command`](Self::send_command).
+    /// Fails with [`VenomError::ReadOnly`] on a read-only shell, and
+    /// [`VenomError::BufferOverflow`] if the envelope and payload are
+    /// longer than [`cmd_payload_size`](Self::cmd_payload_size).
+    pub fn send_typed(&self, tag: u16, payload: &[u8]) -> Result<()> {
+        let producer = self.cmd_producer.as_ref().ok_or(VenomError::ReadOnly)?;
+        let mut cmd = [0u8; MAX_CMD_SIZE];
+        let len = typed_command::encode(tag, payload, &mut cmd[..self.cmd_payload_size.min(MAX_CMD_SIZE)])?;
+        producer.push(&cmd[..len]);
+        Ok(())
     }
 
     /// Send a command and wait for response
     ///
     /// This sends the command, then spins reading the data region
-    /// until a new response appears
+    /// until it holds anything: any daemon write counts as the response,
+    /// whoever it was for, and a daemon that never writes hangs it.
+    /// [`request_timeout`](Self::request_timeout) waits for this shell's
+    /// own response instead.
+    ///
+    /// # Panics
+    /// If the shell is read-only
+    #[deprecated(note = "any write to the data region counts as the response; use request_timeout")]
     pub fn request(&self, cmd: &[u8], response_buf: &mut [u8]) -> usize {
-        // Send command
         self.send_command(cmd);
-
-        // Spin reading until we get a response
-        // In a real implementation, you'd have per-client response slots
         loop {
             let len = self.read_data_with_len(response_buf);
             if len > 0 {
@@ -313,6 +1848,149 @@ impl ShellChannel {
         }
     }
 
+    /// Take the response the daemon last sent this shell with
+    /// [`DaemonChannel::respond`], if there's one it hasn't taken yet
+    ///
+    /// Returns its length; a response longer than `buf` is cut to fit. None
+    /// as well while the daemon is writing one, and always for a read-only
+    /// shell or in a channel of a daemon older than responses.
+    pub fn try_recv_response(&self, buf: &mut [u8]) -> Option<usize> {
+        let slot = self.response?;
+        unsafe {
+            let seq1 = (*slot).sequence.load(Ordering::Acquire);
+            if seq1 & 1 == 1 || seq1 == self.response_seq.load(Ordering::Relaxed) {
+                return None;
+            }
+            let client_id = (*slot).client_id.load(Ordering::Relaxed);
+            let len = ((*slot).len.load(Ordering::Relaxed) as usize).min(self.cmd_payload_size).min(buf.len());
+            std::ptr::copy_nonoverlapping(ResponseSlot::data(slot), buf.as_mut_ptr(), len);
+            std::sync::atomic::fence(Ordering::Acquire);
+            if (*slot).sequence.load(Ordering::Acquire) != seq1 {
+                return None;
+            }
+            self.response_seq.store(seq1, Ordering::Relaxed);
+            (client_id == self.client_id).then_some(len)
+        }
+    }
+
+    /// Send a command and wait up to `timeout` for the daemon's
+    /// [response](DaemonChannel::respond) to this shell
+    ///
+    /// Unlike [`request`](Self::request), the response is this shell's
+    /// alone, and a queue that stays full counts against the timeout too.
+    /// Returns the response's length, cut to `response_buf` like
+    /// [`try_recv_response`](Self::try_recv_response), or None on timeout.
+    /// A response left over from an earlier request is dropped first, but
+    /// one the daemon sends late, after this command went out, is taken for
+    /// this command's. Fails
+    /// with [`VenomError::ReadOnly`] on a read-only shell,
+    /// [`VenomError::BufferOverflow`] if the command is longer than
+    /// [`cmd_payload_size`](Self::cmd_payload_size), and
+    /// [`VenomError::InvalidConfig`] in a channel of a daemon older than
+    /// responses.
+    pub fn request_timeout(&self, cmd: &[u8], response_buf: &mut [u8], timeout: Duration) -> Result<Option<usize>> {
+        let producer = self.cmd_producer.as_ref().ok_or(VenomError::ReadOnly)?;
+        if cmd.len() > self.cmd_payload_size {
+            return Err(VenomError::BufferOverflow { max: self.cmd_payload_size, got: cmd.len() });
+        }
+        if self.response.is_none() {
+            return Err(VenomError::InvalidConfig {
+                reason: "the channel's daemon predates per-client responses".to_string(),
+            });
+        }
+
+        let deadline = Instant::now() + timeout;
+        // The shell holds one response, so one take empties it
+        let _ = self.try_recv_response(response_buf);
+        while !producer.try_push(cmd) {
+            if Instant::now() >= deadline {
+                return Ok(None);
+            }
+            core::hint::spin_loop();
+        }
+        loop {
+            if let Some(len) = self.try_recv_response(response_buf) {
+                return Ok(Some(len));
+            }
+            if Instant::now() >= deadline {
+                return Ok(None);
+            }
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Check whether the daemon that owns the channel is still running
+    ///
+    /// The mapping stays readable after the daemon dies, so a shell can't
+    /// tell from the data alone; the last write is still there to read.
+    /// Once a [standby](standby::StandbyChannel) takes the channel over,
+    /// this is about the standby.
+    pub fn daemon_alive(&self) -> bool {
+        process_alive(self.daemon_pid())
+    }
+
+    /// PID of the daemon that owns the channel; 0 while a daemon that
+    /// exited hands it to its standby
+    pub fn daemon_pid(&self) -> u32 {
+        unsafe { (*self.header).owner_pid.load(Ordering::Acquire) }
+    }
+
+    /// Number of shells currently connected, read-only ones not included
+    pub fn client_count(&self) -> u32 {
+        unsafe { (*self.header).client_count.load(Ordering::Acquire) }
+    }
+
+    /// Commands the daemon has taken off the queue since the channel was
+    /// created
+    pub fn commands_received(&self) -> u64 {
+        unsafe { (*self.cmd_queue).consumed() }
+    }
+
+    /// Commands sent that the daemon hasn't taken yet
+    pub fn commands_pending(&self) -> usize {
+        unsafe { (*self.cmd_queue).pending(self.cmd_slots, self.cmd_payload_size) }
+    }
+
+    /// Commands in the queue: sent, or being sent, and not yet taken by
+    /// the daemon
+    ///
+    /// Advisory, as other shells send and the daemon takes commands
+    /// meanwhile; a shell can back off as it nears
+    /// [`cmd_queue_capacity`](Self::cmd_queue_capacity), rather than find
+    /// out from a failed send. Cheaper than
+    /// [`commands_pending`](Self::commands_pending), which looks at every
+    /// slot.
+    pub fn cmd_queue_len(&self) -> usize {
+        unsafe { (*self.cmd_queue).queued(self.cmd_slots) }
+    }
+
+    /// Commands the queue holds before sends fail, or drop the oldest under
+    /// [`CmdFullPolicy::OverwriteOldest`]
+    pub fn cmd_queue_capacity(&self) -> usize {
+        self.cmd_slots
+    }
+
+    /// Longest command the channel carries; longer ones can't be sent
+    pub fn cmd_payload_size(&self) -> usize {
+        self.cmd_payload_size
+    }
+
+    /// Whether the shell was opened with [`connect_read_only`](Self::connect_read_only)
+    pub fn is_read_only(&self) -> bool {
+        self.cmd_producer.is_none()
+    }
+
+    /// The events still in the channel's [event log](crate::event_log),
+    /// oldest first, as [`DaemonChannel::recent_events`] reads them; none
+    /// in a channel without a log
+    pub fn recent_events(&self) -> Vec<LoggedEvent> {
+        self.events.map_or_else(Vec::new, |events| events.recent())
+    }
+
+    fn producer(&self) -> &MpscProducer {
+        self.cmd_producer.as_ref().expect("read-only ShellChannel can't send commands")
+    }
+
     /// Get the namespace of the channel
     pub fn namespace(&self) -> &str {
         self.shm.name()
@@ -322,13 +2000,59 @@ impl ShellChannel {
     pub fn as_ptr(&self) -> *const u8 {
         self.shm.as_ptr()
     }
+
+    /// The bytes of the data region, past its SeqLock header, for code
+    /// that wants a pointer and a length rather than reads
+    ///
+    /// The region is the daemon's to write at any time: a plain read of it
+    /// may see a write half done, and turning it into a `&[u8]` is
+    /// undefined behaviour while the daemon is running. Code expecting a
+    /// byte slice gets a consistent one from [`snapshot`](Self::snapshot),
+    /// a [`Frame`](snapshot::Frame) that is `AsRef<[u8]>`.
+    ///
+    /// # Safety
+    /// Reading through `ptr` is sound only with volatile or atomic reads,
+    /// checked against [`data_sequence`](Self::data_sequence) before and
+    /// after as the SeqLock does; writing through it never is. The region
+    /// is valid while the shell lives, and stays where it is until the
+    /// daemon [resizes](DaemonChannel::resize_data) it: call this again
+    /// after `data_sequence` or [`data_size`](Self::data_size) changes.
+    pub fn data_region(&self) -> RawRegion {
+        let reader = self.data_reader();
+        RawRegion { ptr: reader.data_ptr(), len: reader.data_size() }
+    }
+}
+
+/// Pointer and length of a region in a channel's mapping, from
+/// [`ShellChannel::data_region`], whose docs say how it may be read
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RawRegion {
+    /// First byte of the region
+    pub ptr: *const u8,
+    /// Bytes in the region
+    pub len: usize,
+}
+
+impl Drop for ShellChannel {
+    fn drop(&mut self) {
+        if !self.is_read_only() {
+            unsafe {
+                (*self.header).client_count.fetch_sub(1, Ordering::AcqRel);
+            }
+            if let Some(events) = &self.events {
+                events.log(ChannelEvent::ClientDisconnected { client_id: self.client_id });
+            }
+        }
+        if let Some(slot) = self.client_slot {
+            unsafe { (*slot).client_id.store(0, Ordering::Release) };
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::thread;
-    use std::time::Duration;
+    use std::collections::HashMap;
 
     #[test]
     fn test_channel_create_connect() {
@@ -342,6 +2066,7 @@ mod tests {
         let shell = ShellChannel::connect(namespace).unwrap();
 
         assert_eq!(shell.client_id(), 1);
+        assert!(shell.daemon_alive());
 
         // Write data from daemon
         daemon.write_data(b"Hello from daemon!");
@@ -354,4 +2079,1102 @@ mod tests {
         drop(shell);
         drop(daemon);
     }
+
+    /// Two mappings of one memfd of `len` bytes, as two processes sharing
+    /// a file would have
+    fn raw_mappings(len: usize) -> (VenomShm, VenomShm) {
+        use rustix::mm::{mmap, MapFlags, ProtFlags};
+        let fd = rustix::fs::memfd_create("venom_raw", rustix::fs::MemfdFlags::CLOEXEC).unwrap();
+        rustix::fs::ftruncate(&fd, len as u64).unwrap();
+        let map = || unsafe {
+            let ptr = mmap(std::ptr::null_mut(), len, ProtFlags::READ | ProtFlags::WRITE, MapFlags::SHARED, &fd, 0);
+            VenomShm::from_raw_parts(ptr.unwrap().cast(), len, true).unwrap()
+        };
+        (map(), map())
+    }
+
+    #[test]
+    fn test_channel_in_raw_parts() {
+        let config = ChannelConfig { max_clients: 2, ..ChannelConfig::default() };
+        let (daemon_shm, shell_shm) = raw_mappings(config.region_size().unwrap());
+        assert_ne!(daemon_shm.as_ptr(), shell_shm.as_ptr());
+        let daemon = DaemonChannel::create_in(daemon_shm, config).unwrap();
+        let shell = ShellChannel::connect_in(shell_shm).unwrap();
+        assert_eq!((shell.client_id(), daemon.client_count(), shell.namespace()), (1, 1, ""));
+
+        daemon.write_data_with_len(b"over raw parts");
+        let mut buf = [0u8; 64];
+        let len = shell.read_data_with_len(&mut buf);
+        assert_eq!(&buf[..len], b"over raw parts");
+
+        shell.send_command(b"ping");
+        let (client_id, len) = daemon.try_recv_command(&mut buf).unwrap();
+        assert_eq!((client_id, &buf[..len]), (shell.client_id(), &b"ping"[..]));
+        daemon.respond(client_id, b"pong").unwrap();
+        assert_eq!(shell.try_recv_response(&mut buf), Some(4));
+        assert_eq!(&buf[..4], b"pong");
+
+        // The data region holds what read_data reads, length prefix first
+        let region = shell.data_region();
+        assert_eq!(region.len, shell.data_size());
+        let bytes: Vec<u8> = (0..8 + 14).map(|i| unsafe { region.ptr.add(i).read_volatile() }).collect();
+        assert_eq!((&bytes[..8], &bytes[8..]), (&14u64.to_le_bytes()[..], &b"over raw parts"[..]));
+
+        // No object behind it to grow
+        let mut daemon = daemon;
+        assert!(matches!(daemon.resize_data(2 * region.len), Err(VenomError::InvalidConfig { .. })));
+        assert_eq!(shell.data_region(), region);
+    }
+
+    #[test]
+    fn test_raw_parts_checked() {
+        let config = ChannelConfig::default();
+        let len = config.region_size().unwrap();
+        let (small, _) = raw_mappings(len / 2);
+        assert!(matches!(DaemonChannel::create_in(small, config.clone()), Err(VenomError::InvalidConfig { .. })));
+
+        // Nothing laid out in it yet
+        let (daemon_shm, shell_shm) = raw_mappings(len);
+        assert!(matches!(ShellChannel::connect_in(shell_shm), Err(VenomError::InvalidMagic { .. })));
+
+        let unaligned = unsafe { VenomShm::from_raw_parts(daemon_shm.as_ptr().add(8), len - 8, false).unwrap() };
+        assert!(matches!(DaemonChannel::create_in(unaligned, config), Err(VenomError::InvalidConfig { .. })));
+        assert!(unsafe { VenomShm::from_raw_parts(std::ptr::null_mut(), len, false) }.is_err());
+    }
+
+    #[test]
+    fn test_read_only_shell() {
+        let namespace = "test_channel_read_only";
+        let daemon = DaemonChannel::create(namespace, ChannelConfig::default()).unwrap();
+        let shell = ShellChannel::connect(namespace).unwrap();
+
+        let observer = ShellChannel::connect_read_only(namespace).unwrap();
+        assert!(observer.is_read_only() && !shell.is_read_only());
+        assert_eq!((observer.client_id(), observer.client_count()), (0, 1));
+        assert_eq!(observer.daemon_pid(), std::process::id());
+        assert!(!observer.try_send_command(b"nope"));
+
+        daemon.write_data(b"watched");
+        let mut buf = [0u8; 7];
+        observer.read_data(&mut buf);
+        assert_eq!(&buf, b"watched");
+        assert_eq!(observer.data_sequence(), 2);
+
+        // The observer sees the queue fill and drain
+        shell.send_command(b"one");
+        shell.send_command(b"two");
+        assert_eq!((observer.commands_pending(), observer.commands_received()), (2, 0));
+        let mut cmd = [0u8; 8];
+        daemon.try_recv_command(&mut cmd).unwrap();
+        assert_eq!((observer.commands_pending(), observer.commands_received()), (1, 1));
+
+        // Neither joining nor leaving touches the count
+        drop(observer);
+        assert_eq!(shell.client_count(), 1);
+    }
+
+    #[test]
+    fn test_connect_checks_layout() {
+        let namespace = "test_channel_corrupt";
+        let daemon = DaemonChannel::create(namespace, ChannelConfig::default()).unwrap();
+        let header = daemon.as_ptr() as *mut ChannelHeader;
+        let corrupt = |what: &str| match ShellChannel::connect(namespace) {
+            Err(VenomError::CorruptRegion { .. }) => {}
+            other => panic!("{}: expected CorruptRegion, got {:?}", what, other.map(|_| ())),
+        };
+
+        unsafe {
+            let cmd_queue_offset = (*header).cmd_queue_offset;
+            (*header).cmd_queue_offset = u64::MAX - 63;
+            corrupt("queue past the end");
+            (*header).cmd_queue_offset = cmd_queue_offset + 1;
+            corrupt("unaligned queue");
+            (*header).cmd_queue_offset = cmd_queue_offset;
+
+            let seqlock_offset = (*header).seqlock_offset.load(Ordering::Relaxed);
+            (*header).seqlock_offset.store(u64::MAX - 63, Ordering::Relaxed);
+            corrupt("data region past the end");
+            (*header).seqlock_offset.store(1 << 30, Ordering::Relaxed);
+            corrupt("data region past the end of the object");
+            (*header).seqlock_offset.store(seqlock_offset, Ordering::Relaxed);
+
+            let seqlock = daemon.as_ptr().add(seqlock_offset as usize) as *mut SeqLockHeader;
+            (*seqlock).data_size = u64::MAX;
+            corrupt("data region larger than the channel");
+            (*seqlock).data_size = DEFAULT_DATA_SIZE as u64;
+
+            let queue = daemon.as_ptr().add(cmd_queue_offset as usize) as *mut MpscQueueHeader;
+            (*queue).num_slots = 0;
+            corrupt("no command slots");
+            (*queue).num_slots = DEFAULT_CMD_SLOTS as u64;
+            (*queue).cmd_payload_size = u64::MAX - 63;
+            corrupt("command slots past the end");
+            (*queue).cmd_payload_size = DEFAULT_CMD_PAYLOAD_SIZE as u64;
+
+            (*header).max_clients = u64::MAX / 2;
+            corrupt("client table past the end");
+            (*header).max_clients = ChannelConfig::default().max_clients as u64;
+
+            let responses_offset = (*header).responses_offset;
+            (*header).responses_offset = u64::MAX - 63;
+            corrupt("response slots past the end");
+            (*header).responses_offset = responses_offset;
+
+            (*header).version = VENOM_VERSION - 1;
+            assert!(matches!(
+                ShellChannel::connect(namespace),
+                Err(VenomError::UnsupportedVersion { expected: VENOM_VERSION, .. })
+            ));
+            (*header).version = VENOM_VERSION;
+        }
+
+        // Nothing taken by the failed attempts
+        let shell = ShellChannel::connect(namespace).unwrap();
+        assert_eq!((shell.client_id(), shell.client_count()), (1, 1));
+    }
+
+    #[test]
+    fn test_client_names() {
+        let namespace = "test_channel_names";
+        let config = ChannelConfig { max_clients: 3, ..ChannelConfig::default() };
+        let daemon = DaemonChannel::create(namespace, config).unwrap();
+
+        let gui = ShellChannel::connect_named(namespace, "gui-frontend").unwrap();
+        let anonymous = ShellChannel::connect(namespace).unwrap();
+        let twin = ShellChannel::connect_named(namespace, "gui-frontend").unwrap();
+        assert_eq!(daemon.client_name(gui.client_id()).as_deref(), Some("gui-frontend"));
+        assert_eq!(daemon.client_name(twin.client_id()).as_deref(), Some("gui-frontend"));
+        assert_eq!(daemon.client_name(anonymous.client_id()), None);
+        assert_eq!(daemon.client_name(99), None);
+
+        let clients: Vec<_> = daemon.clients().map(|(id, name, _)| (id, name)).collect();
+        let gui_name = Some("gui-frontend".to_string());
+        assert_eq!(clients, [(1, gui_name.clone()), (2, None), (3, gui_name)]);
+        let (_, _, since) = daemon.clients().next().unwrap();
+        assert!(since.elapsed().unwrap() < Duration::from_secs(60));
+
+        // Every slot is taken
+        assert!(matches!(ShellChannel::connect(namespace), Err(VenomError::TooManyClients { max: 3 })));
+        assert!(ShellChannel::connect_read_only(namespace).is_ok(), "observers don't take a slot");
+        assert!(matches!(
+            ShellChannel::connect_named(namespace, &"x".repeat(CLIENT_NAME_MAX + 1)),
+            Err(VenomError::InvalidClientName { .. })
+        ));
+
+        // A disconnected shell's slot goes to the next one, and its name
+        // with it
+        drop(anonymous);
+        let cli = ShellChannel::connect_named(namespace, "cli").unwrap();
+        let ids: Vec<_> = daemon.clients().map(|(id, _, _)| id).collect();
+        assert_eq!(ids, [1, cli.client_id(), 3]);
+        assert_eq!(daemon.client_name(cli.client_id()).as_deref(), Some("cli"));
+        drop(gui);
+        assert_eq!(daemon.client_name(1), None);
+        assert_eq!(daemon.clients().count(), 2);
+    }
+
+    #[test]
+    fn test_client_ids_wrap() {
+        let namespace = "test_channel_id_wrap";
+        let config = ChannelConfig { max_clients: 4, ..ChannelConfig::default() };
+        let daemon = DaemonChannel::create(namespace, config).unwrap();
+        let header = unsafe { &*daemon.header };
+        // As if 2^32 - 4 shells had connected
+        header.next_client_id.store(u32::MAX - 2, Ordering::Relaxed);
+
+        let early = ShellChannel::connect(namespace).unwrap();
+        assert_eq!(header.client_ids_wrapped.load(Ordering::Relaxed), 0);
+        let last = ShellChannel::connect_named(namespace, "last").unwrap();
+        assert_eq!(header.client_ids_wrapped.load(Ordering::Relaxed), 1);
+        // Past CLIENT_CLAIMING and 0
+        let first = ShellChannel::connect(namespace).unwrap();
+        assert_eq!([early.client_id(), last.client_id(), first.client_id()], [u32::MAX - 2, u32::MAX - 1, 1]);
+
+        // Shells from both sides of the wrap are heard, and answered
+        let shells = [&early, &last, &first];
+        for shell in shells {
+            shell.send_command(b"ping");
+        }
+        let mut buf = [0u8; 16];
+        let senders: Vec<_> = std::iter::from_fn(|| daemon.try_recv_command(&mut buf).map(|(id, _)| id)).collect();
+        assert_eq!(senders, [u32::MAX - 2, u32::MAX - 1, 1]);
+        for shell in shells {
+            daemon.respond(shell.client_id(), b"pong").unwrap();
+            assert_eq!(shell.try_recv_response(&mut buf), Some(4));
+        }
+        assert_eq!(daemon.client_name(u32::MAX - 1).as_deref(), Some("last"));
+        assert_eq!(daemon.stats().corrupt_slots, 0);
+
+        // Shells of older versions count past the reserved IDs with
+        // fetch-add; the next one to connect skips them
+        header.next_client_id.store(u32::MAX, Ordering::Relaxed);
+        assert_eq!(ShellChannel::connect(namespace).unwrap().client_id(), 1);
+    }
+
+    #[test]
+    fn test_event_log() {
+        let namespace = "test_channel_events";
+        let config = ChannelConfig { cmd_slots: 2, ..ChannelConfig::default() };
+        let mut daemon = DaemonChannel::create(namespace, config).unwrap();
+        // Watching doesn't show up in the log
+        let watcher = ShellChannel::connect_read_only(namespace).unwrap();
+
+        let first = ShellChannel::connect(namespace).unwrap();
+        let second = ShellChannel::connect(namespace).unwrap();
+        drop(first);
+        // One event for the episode, however many sends find the queue full
+        for _ in 0..4 {
+            second.try_send_command(b"cmd");
+        }
+        let mut buf = [0u8; 8];
+        daemon.try_recv_command(&mut buf).unwrap();
+        assert!(second.try_send_command(b"cmd"));
+        drop(second);
+        daemon.resize_data(128 * 1024).unwrap();
+
+        let pid = std::process::id();
+        let expected = [
+            ChannelEvent::DaemonCreated { pid },
+            ChannelEvent::ClientConnected { client_id: 1, pid },
+            ChannelEvent::ClientConnected { client_id: 2, pid },
+            ChannelEvent::ClientDisconnected { client_id: 1 },
+            ChannelEvent::QueueFull { client_id: 2 },
+            ChannelEvent::ClientDisconnected { client_id: 2 },
+            ChannelEvent::Resized { data_size: 128 * 1024 },
+        ];
+        let logged = watcher.recent_events();
+        assert_eq!(logged.iter().map(|logged| logged.event).collect::<Vec<_>>(), expected);
+        assert!(logged.windows(2).all(|pair| pair[0].time <= pair[1].time));
+        assert_eq!(daemon.recent_events(), logged);
+    }
+
+    #[test]
+    fn test_event_log_slots() {
+        let namespace = "test_channel_event_slots";
+        let config = ChannelConfig { event_log_slots: 2, ..ChannelConfig::default() };
+        let daemon = DaemonChannel::create(namespace, config).unwrap();
+        for _ in 0..3 {
+            ShellChannel::connect(namespace).unwrap();
+        }
+        // The oldest overwritten
+        let events: Vec<_> = daemon.recent_events().into_iter().map(|logged| logged.event).collect();
+        assert_eq!(events, [ChannelEvent::ClientConnected { client_id: 3, pid: std::process::id() }, ChannelEvent::ClientDisconnected { client_id: 3 }]);
+        drop(daemon);
+
+        let config = ChannelConfig { event_log_slots: 0, ..ChannelConfig::default() };
+        assert!(config.region_size().unwrap() < ChannelConfig::default().region_size().unwrap());
+        let daemon = DaemonChannel::create(namespace, config).unwrap();
+        let shell = ShellChannel::connect(namespace).unwrap();
+        assert!(daemon.recent_events().is_empty() && shell.recent_events().is_empty());
+        drop(daemon);
+
+        let config = ChannelConfig { event_log_slots: event_log::MAX_EVENT_LOG_SLOTS + 1, ..ChannelConfig::default() };
+        assert!(matches!(DaemonChannel::create(namespace, config), Err(VenomError::InvalidConfig { .. })));
+    }
+
+    #[test]
+    fn test_command_filter() {
+        let namespace = "test_channel_filter";
+        let mut daemon = DaemonChannel::create(namespace, ChannelConfig::default()).unwrap();
+        let shell1 = ShellChannel::connect(namespace).unwrap();
+        let shell2 = ShellChannel::connect(namespace).unwrap();
+        daemon.set_command_filter(|client_id, _| match client_id {
+            2 => FilterAction::DropAndCount,
+            _ => FilterAction::Accept,
+        });
+
+        for i in 0..5u8 {
+            assert!(shell1.try_send_command(&[i]));
+            assert!(shell2.try_send_command(&[i]));
+        }
+        assert!(shell2.try_send_command(b"__SHUTDOWN__"));
+        assert!(shell1.try_send_command(b"__SHUTDOWN__"));
+
+        let mut seen = Vec::new();
+        daemon.run(|client_id, cmd| {
+            seen.push((client_id, cmd[0]));
+            Vec::new()
+        });
+        assert_eq!(seen, (0..5).map(|i| (1, i)).collect::<Vec<_>>());
+        let stats = daemon.stats();
+        assert_eq!((stats.commands_received, stats.filtered_commands), (12, 6));
+    }
+
+    #[test]
+    fn test_named_command_filter() {
+        let namespace = "test_channel_named_filter";
+        let mut daemon = DaemonChannel::create(namespace, ChannelConfig::default()).unwrap();
+        let gui = ShellChannel::connect_named(namespace, "gui").unwrap();
+        let stray = ShellChannel::connect(namespace).unwrap();
+        daemon.set_named_command_filter(|_, name, cmd| match (name, cmd) {
+            (Some("gui"), _) => FilterAction::Accept,
+            (_, b"status") => FilterAction::Accept,
+            _ => FilterAction::Drop,
+        });
+
+        assert!(stray.try_send_command(b"set"));
+        assert!(stray.try_send_command(b"status"));
+        assert!(gui.try_send_command(b"set"));
+        let mut buf = [0u8; 16];
+        assert_eq!(daemon.try_recv_command(&mut buf), Some((stray.client_id(), 6)));
+        assert_eq!(daemon.try_recv_command(&mut buf), Some((gui.client_id(), 3)));
+        assert_eq!(daemon.try_recv_command(&mut buf), None);
+        // Dropped without counting
+        assert_eq!(daemon.stats().filtered_commands, 0);
+
+        daemon.clear_command_filter();
+        assert!(stray.try_send_command(b"set"));
+        assert_eq!(daemon.try_recv_command(&mut buf), Some((stray.client_id(), 3)));
+    }
+
+    #[test]
+    fn test_respond_to_one_client() {
+        let namespace = "test_channel_respond";
+        let config = ChannelConfig { max_clients: 2, ..ChannelConfig::default() };
+        let daemon = DaemonChannel::create(namespace, config).unwrap();
+        let first = ShellChannel::connect(namespace).unwrap();
+        let second = ShellChannel::connect(namespace).unwrap();
+        assert_eq!(daemon.client_count(), 2);
+
+        let mut buf = [0u8; 64];
+        assert_eq!(first.try_recv_response(&mut buf), None);
+        daemon.respond(second.client_id(), b"for you").unwrap();
+        assert_eq!(first.try_recv_response(&mut buf), None);
+        assert_eq!(second.try_recv_response(&mut buf), Some(7));
+        assert_eq!(&buf[..7], b"for you");
+        assert_eq!(second.try_recv_response(&mut buf), None, "taken once");
+
+        // A later response replaces one not taken yet
+        daemon.respond(first.client_id(), b"old").unwrap();
+        daemon.respond(first.client_id(), b"new!").unwrap();
+        assert_eq!(first.try_recv_response(&mut buf[..2]), Some(2), "cut to the buffer");
+        assert_eq!(&buf[..2], b"ne");
+
+        assert!(matches!(daemon.respond(99, b"x"), Err(VenomError::InvalidClientId(99))));
+        let too_long = vec![0u8; daemon.cmd_payload_size() + 1];
+        assert!(matches!(daemon.respond(first.client_id(), &too_long), Err(VenomError::BufferOverflow { .. })));
+        let observer = ShellChannel::connect_read_only(namespace).unwrap();
+        assert_eq!(observer.try_recv_response(&mut buf), None);
+
+        // The next shell in a slot doesn't get its last shell's response
+        daemon.respond(second.client_id(), b"stale").unwrap();
+        drop(second);
+        let third = ShellChannel::connect(namespace).unwrap();
+        assert_eq!(third.try_recv_response(&mut buf), None);
+    }
+
+    #[test]
+    fn test_typed_commands_routed() {
+        use std::sync::{Arc, Mutex};
+
+        let namespace = "test_channel_typed";
+        let mut daemon = DaemonChannel::create(namespace, ChannelConfig::default()).unwrap();
+        let shell = ShellChannel::connect(namespace).unwrap();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = |what: &'static str| {
+            let seen = Arc::clone(&seen);
+            move |_: &DaemonChannel, client_id: u32, payload: &[u8]| {
+                seen.lock().unwrap().push((what, client_id, payload.to_vec()))
+            }
+        };
+        daemon.on(1, log("one"));
+        daemon.on(2, |daemon, client_id, payload| {
+            let mut reply = payload.to_vec();
+            reply.reverse();
+            daemon.respond(client_id, &reply).unwrap();
+            // Dispatching again from a handler takes nothing
+            assert_eq!(daemon.dispatch_pending(), 0);
+        });
+
+        shell.send_typed(2, b"abc").unwrap();
+        shell.send_typed(1, b"first").unwrap();
+        shell.send_typed(1, b"").unwrap();
+        assert_eq!(daemon.dispatch_pending(), 3);
+        assert_eq!(daemon.dispatch_pending(), 0);
+        let id = shell.client_id();
+        assert_eq!(*seen.lock().unwrap(), [("one", id, b"first".to_vec()), ("one", id, Vec::new())]);
+        let mut buf = [0u8; 8];
+        assert_eq!(shell.try_recv_response(&mut buf), Some(3));
+        assert_eq!(&buf[..3], b"cba");
+
+        // Unknown tags and untyped commands are dropped, then go to the
+        // fallback whole
+        shell.send_typed(9, b"lost").unwrap();
+        assert_eq!(daemon.dispatch_pending(), 1);
+        daemon.on_unknown(log("unknown"));
+        shell.send_typed(9, b"x").unwrap();
+        shell.send_command(b"raw");
+        assert_eq!(daemon.dispatch_pending(), 2);
+        assert_eq!(
+            seen.lock().unwrap()[2..],
+            [("unknown", id, b"\x09\x00\x01\x00x".to_vec()), ("unknown", id, b"raw".to_vec())]
+        );
+
+        // The envelope counts against the command size
+        let too_long = vec![0u8; shell.cmd_payload_size() - typed_command::ENVELOPE_SIZE + 1];
+        assert!(matches!(shell.send_typed(1, &too_long), Err(VenomError::BufferOverflow { .. })));
+        let observer = ShellChannel::connect_read_only(namespace).unwrap();
+        assert!(matches!(observer.send_typed(1, b""), Err(VenomError::ReadOnly)));
+    }
+
+    #[test]
+    fn test_request_timeout() {
+        let namespace = "test_channel_request_timeout";
+        let config = ChannelConfig { cmd_slots: 2, ..ChannelConfig::default() };
+        let daemon = DaemonChannel::create(namespace, config).unwrap();
+        let shell = ShellChannel::connect(namespace).unwrap();
+        let mut buf = [0u8; 64];
+
+        // Nobody answers
+        assert_eq!(shell.request_timeout(b"one", &mut buf, Duration::from_millis(10)).unwrap(), None);
+        assert_eq!(shell.request_timeout(b"two", &mut buf, Duration::from_millis(10)).unwrap(), None);
+        assert_eq!(shell.cmd_queue_len(), 2);
+        let started = Instant::now();
+        assert_eq!(shell.request_timeout(b"full", &mut buf, Duration::from_millis(10)).unwrap(), None);
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        // Late answers to the requests that timed out are dropped by the next
+        let mut cmd = [0u8; 64];
+        while let Some((client_id, _)) = daemon.try_recv_command(&mut cmd) {
+            daemon.respond(client_id, b"late").unwrap();
+        }
+        std::thread::scope(|scope| {
+            let requester = scope.spawn(|| {
+                let mut buf = [0u8; 64];
+                let len = shell.request_timeout(b"ping", &mut buf, Duration::from_secs(5)).unwrap().unwrap();
+                buf[..len].to_vec()
+            });
+            let (client_id, len) = daemon.recv_command(&mut cmd);
+            let mut response = b"re: ".to_vec();
+            response.extend_from_slice(&cmd[..len]);
+            daemon.respond(client_id, &response).unwrap();
+            assert_eq!(requester.join().unwrap(), b"re: ping");
+        });
+
+        let observer = ShellChannel::connect_read_only(namespace).unwrap();
+        assert!(matches!(observer.request_timeout(b"x", &mut buf, Duration::ZERO), Err(VenomError::ReadOnly)));
+        let too_long = vec![0u8; shell.cmd_payload_size() + 1];
+        assert!(matches!(
+            shell.request_timeout(&too_long, &mut buf, Duration::ZERO),
+            Err(VenomError::BufferOverflow { .. })
+        ));
+    }
+
+    #[test]
+    fn test_stamped_writes() {
+        let namespace = "test_channel_stamped";
+        let config = ChannelConfig { stamp_writes: true, ..ChannelConfig::default() };
+        let daemon = DaemonChannel::create(namespace, config).unwrap();
+        let shell = ShellChannel::connect(namespace).unwrap();
+        assert_eq!(shell.last_write_timestamp(), 0, "nothing written yet");
+
+        let mut buf = [0u8; 16];
+        let mut last = 0;
+        for frame in [&b"first"[..], b"second"] {
+            daemon.write_data_with_len(frame);
+            let (timestamp, len) = shell.try_read_data_with_len_stamped(&mut buf).unwrap();
+            assert_eq!(&buf[..len], frame);
+            let age = crate::seqlock::now_ns() - timestamp;
+            assert!(age < 1_000_000_000, "written {}ns ago", age);
+            assert!(timestamp >= last);
+            assert_eq!(shell.last_write_timestamp(), timestamp);
+            last = timestamp;
+        }
+    }
+
+    #[test]
+    fn test_corrupt_commands_skipped() {
+        let namespace = "test_channel_corrupt_commands";
+        let daemon = DaemonChannel::create(namespace, ChannelConfig::default()).unwrap();
+        let shell = ShellChannel::connect(namespace).unwrap();
+        let queue = daemon.cmd_queue;
+        let slots = unsafe { queue.cast::<u8>().add(std::mem::size_of::<MpscQueueHeader>()) };
+        let slot_size = crate::mpsc_queue::CommandSlot::size_for_payload(daemon.cmd_payload_size());
+
+        // Too long, from ID 0 (read-only shells), from an ID never handed out
+        let forged = [(1, MAX_CMD_SIZE as u32 + 1), (0, 4), (99, 4)];
+        unsafe {
+            for (i, &(client_id, len)) in forged.iter().enumerate() {
+                let slot = &*(slots.add(i * slot_size) as *const crate::mpsc_queue::CommandSlot);
+                slot.client_id.store(client_id, Ordering::Relaxed);
+                slot.cmd_len.store(len, Ordering::Relaxed);
+                slot.state.store(crate::layout::slot::READY, Ordering::Release);
+            }
+            let write_idx = &*(queue.cast::<u8>().add(crate::layout::queue::WRITE_IDX) as *const std::sync::atomic::AtomicU64);
+            write_idx.store(forged.len() as u64, Ordering::Release);
+        }
+        shell.send_command(b"ping");
+        shell.send_command(b"__SHUTDOWN__");
+
+        let mut handled = Vec::new();
+        daemon.run(|client_id, cmd| {
+            handled.push((client_id, cmd.to_vec()));
+            Vec::new()
+        });
+        assert_eq!(handled, [(shell.client_id(), b"ping".to_vec())]);
+        assert_eq!(daemon.stats(), DaemonStats { commands_received: 5, corrupt_slots: 3, dropped_commands: 0, discarded_large_commands: 0, total_full_events: 0, filtered_commands: 0 });
+        let corrupt: Vec<_> = daemon
+            .recent_events()
+            .into_iter()
+            .filter_map(|logged| match logged.event {
+                ChannelEvent::CorruptCommand { client_id } => Some(client_id),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(corrupt, [1, 0, 99]);
+    }
+
+    #[test]
+    fn test_cmd_payload_size() {
+        for (requested, payload) in [(8, 64), (512, 512)] {
+            let namespace = format!("test_channel_payload_{}", requested);
+            let config = ChannelConfig { cmd_payload_size: requested, ..ChannelConfig::default() };
+            let daemon = DaemonChannel::create(&namespace, config).unwrap();
+            let shell = ShellChannel::connect(&namespace).unwrap();
+            assert_eq!((daemon.cmd_payload_size(), shell.cmd_payload_size()), (payload, payload));
+
+            let mut buf = [0u8; MAX_CMD_SIZE];
+            for len in [1, requested, payload] {
+                let cmd = vec![len as u8; len];
+                assert!(shell.try_send_command(&cmd));
+                assert_eq!(daemon.try_recv_command(&mut buf), Some((shell.client_id(), len)));
+                assert_eq!(&buf[..len], &cmd[..]);
+            }
+            assert!(!shell.try_send_command(&vec![0; payload + 1]));
+            assert_eq!(daemon.try_recv_command(&mut buf), None);
+        }
+
+        for cmd_payload_size in [0, MAX_CMD_SIZE + 1] {
+            let config = ChannelConfig { cmd_payload_size, ..ChannelConfig::default() };
+            assert!(matches!(
+                DaemonChannel::create("test_channel_payload_invalid", config),
+                Err(VenomError::InvalidConfig { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_cmd_slots_rounded() {
+        let namespace = "test_channel_slots";
+        let config = ChannelConfig { cmd_slots: 3, ..ChannelConfig::default() };
+        let daemon = DaemonChannel::create(namespace, config.clone()).unwrap();
+        let shell = ShellChannel::connect(namespace).unwrap();
+        assert_eq!(shell.cmd_queue_capacity(), 4);
+        assert_eq!(config.region_size().unwrap(), ChannelConfig { cmd_slots: 4, ..config }.region_size().unwrap());
+        for i in 0..4u8 {
+            assert!(shell.try_send_command(&[i]));
+        }
+        assert!(!shell.try_send_command(b"full"));
+        drop(daemon);
+
+        for cmd_slots in [0, crate::mpsc_queue::MAX_SLOTS + 1] {
+            let config = ChannelConfig { cmd_slots, ..ChannelConfig::default() };
+            assert!(matches!(config.region_size(), Err(VenomError::InvalidConfig { .. })));
+            assert!(matches!(
+                DaemonChannel::create("test_channel_slots_invalid", config),
+                Err(VenomError::InvalidConfig { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_cmd_full_policy() {
+        let mut buf = [0u8; 8];
+        for (policy, sent, received) in [(CmdFullPolicy::Reject, 4, [0, 1, 2, 3]), (CmdFullPolicy::OverwriteOldest, 10, [6, 7, 8, 9])] {
+            let namespace = format!("test_channel_full_{:?}", policy);
+            let config = ChannelConfig { cmd_slots: 4, cmd_full_policy: policy, ..ChannelConfig::default() };
+            let daemon = DaemonChannel::create(&namespace, config).unwrap();
+            let shell = ShellChannel::connect(&namespace).unwrap();
+
+            let accepted = (0..10u8).filter(|i| shell.try_send_command(&[*i])).count();
+            assert_eq!(accepted, sent, "{:?}", policy);
+            let got: Vec<_> = std::iter::from_fn(|| daemon.try_recv_command(&mut buf).map(|_| buf[0])).collect();
+            assert_eq!(got, received);
+            let stats = daemon.stats();
+            assert_eq!((stats.commands_received, stats.dropped_commands), (4, sent as u64 - 4));
+            // Every send past the fourth found the queue full
+            assert_eq!(stats.total_full_events, 6);
+            assert_eq!(shell.rejected_sends(), 10 - sent as u64);
+        }
+    }
+
+    #[test]
+    fn test_send_command_timeout() {
+        let namespace = "test_channel_send_timeout";
+        let config = ChannelConfig { cmd_slots: 2, ..ChannelConfig::default() };
+        let daemon = DaemonChannel::create(namespace, config).unwrap();
+        let shell = ShellChannel::connect(namespace).unwrap();
+        shell.send_command_timeout(b"one", Duration::from_millis(2)).unwrap();
+        shell.send_command_timeout(b"two", Duration::from_millis(2)).unwrap();
+
+        // The daemon takes nothing
+        let started = Instant::now();
+        let result = shell.send_command_timeout(b"three", Duration::from_millis(2));
+        let waited = started.elapsed();
+        assert!(matches!(result, Err(VenomError::QueueFull)));
+        assert!(waited >= Duration::from_millis(2) && waited < Duration::from_millis(200), "waited {:?}", waited);
+        assert_eq!(shell.rejected_sends(), 1);
+        assert_eq!(daemon.stats().total_full_events, 1);
+
+        let mut buf = [0u8; 8];
+        daemon.try_recv_command(&mut buf).unwrap();
+        shell.send_command_timeout(b"three", Duration::from_millis(2)).unwrap();
+        assert_eq!((shell.rejected_sends(), daemon.stats().total_full_events), (1, 1));
+
+        let observer = ShellChannel::connect_read_only(namespace).unwrap();
+        assert!(matches!(observer.send_command_timeout(b"x", Duration::ZERO), Err(VenomError::ReadOnly)));
+        assert_eq!(observer.rejected_sends(), 0);
+    }
+
+    #[test]
+    fn test_capacity_and_queue_len() {
+        let namespace = "test_channel_capacity";
+        let config = ChannelConfig { data_size: 64, cmd_slots: 4, ..ChannelConfig::default() };
+        let mut daemon = DaemonChannel::create(namespace, config).unwrap();
+        let shell = ShellChannel::connect(namespace).unwrap();
+        assert_eq!((daemon.data_capacity(), shell.data_capacity()), (56, 56));
+
+        // A payload of the capacity comes back whole
+        daemon.write_data_with_len(&[7; 56]);
+        let mut buf = vec![0u8; shell.data_capacity()];
+        assert_eq!(shell.read_data_with_len(&mut buf), 56);
+        daemon.resize_data(4096).unwrap();
+        assert_eq!((daemon.data_capacity(), shell.data_capacity()), (4088, 4088));
+
+        assert_eq!(shell.cmd_queue_capacity(), 4);
+        let mut lens = vec![shell.cmd_queue_len()];
+        for i in 0..4u8 {
+            assert!(shell.try_send_command(&[i]));
+            lens.push(shell.cmd_queue_len());
+        }
+        while daemon.try_recv_command(&mut buf).is_some() {
+            lens.push(shell.cmd_queue_len());
+        }
+        assert_eq!(lens, [0, 1, 2, 3, 4, 3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn test_writer_core() {
+        // On a thread of its own, so the pinning doesn't outlive the test
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                let core = *affinity::thread_cores().unwrap().last().unwrap();
+                let config = ChannelConfig { writer_core: Some(core), ..ChannelConfig::default() };
+                let _daemon = DaemonChannel::create("test_channel_writer_core", config).unwrap();
+                assert_eq!(affinity::thread_cores().unwrap(), [core]);
+
+                let config = ChannelConfig { writer_core: Some(usize::MAX), ..ChannelConfig::default() };
+                let result = DaemonChannel::create("test_channel_writer_core_bad", config);
+                assert!(matches!(result, Err(VenomError::InvalidConfig { .. })));
+                // The failed pin takes its channel with it
+                assert!(ShellChannel::connect("test_channel_writer_core_bad").is_err());
+            });
+        });
+
+        // A daemon that fails to create its channel stays unpinned
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                let _daemon = DaemonChannel::create("test_channel_writer_core_taken", ChannelConfig::default()).unwrap();
+                let cores = affinity::thread_cores().unwrap();
+                let config = ChannelConfig { writer_core: Some(cores[0]), ..ChannelConfig::default() };
+                let result = DaemonChannel::create("test_channel_writer_core_taken", config);
+                assert!(matches!(result, Err(VenomError::NamespaceInUse { .. })));
+                assert_eq!(affinity::thread_cores().unwrap(), cores);
+            });
+        });
+    }
+
+    #[test]
+    fn test_large_command_from_two_clients() {
+        let namespace = "test_channel_large_command";
+        let config = ChannelConfig { cmd_slots: 4, cmd_payload_size: 512, ..ChannelConfig::default() };
+        let daemon = DaemonChannel::create(namespace, config).unwrap();
+        let messages: Vec<Vec<u8>> = (0..2u8).map(|c| (0..10 * 1024).map(|i| (i % 251) as u8 ^ c).collect()).collect();
+
+        // Four slots for 21 chunks each: the daemon receives while they send
+        let received = std::thread::scope(|scope| {
+            for message in &messages {
+                scope.spawn(move || {
+                    let shell = ShellChannel::connect(namespace).unwrap();
+                    shell.send_large_command(message).unwrap();
+                    shell.send_command(b"plain");
+                    shell
+                });
+            }
+            let mut received = HashMap::new();
+            let mut buf = Vec::new();
+            while received.values().map(Vec::len).sum::<usize>() < 4 {
+                match daemon.try_recv_large_command(&mut buf) {
+                    Some((client_id, len)) => {
+                        assert_eq!(len, buf.len());
+                        received.entry(client_id).or_insert_with(Vec::new).push(buf.clone());
+                    }
+                    None => std::thread::yield_now(),
+                }
+            }
+            received
+        });
+        for (client_id, message) in [1, 2].into_iter().zip(&messages) {
+            assert_eq!(received[&client_id], [message.clone(), b"plain".to_vec()]);
+        }
+        assert_eq!(daemon.stats().discarded_large_commands, 0);
+
+        let observer = ShellChannel::connect_read_only(namespace).unwrap();
+        assert!(matches!(observer.send_large_command(b"x"), Err(VenomError::ReadOnly)));
+    }
+
+    #[test]
+    fn test_large_command_dropped_chunk_times_out() {
+        let namespace = "test_channel_large_command_timeout";
+        let timeout = Duration::from_millis(50);
+        let config = ChannelConfig { cmd_payload_size: 64, large_command_timeout: timeout, ..ChannelConfig::default() };
+        let daemon = DaemonChannel::create(namespace, config).unwrap();
+        let shell = ShellChannel::connect(namespace).unwrap();
+
+        // Every chunk of a message but its last
+        let mut chunks = Vec::new();
+        large_command::send_chunks(9, &[1; 200], 64, |cmd| chunks.push(cmd.to_vec())).unwrap();
+        for cmd in &chunks[..chunks.len() - 1] {
+            shell.send_command(cmd);
+        }
+        let mut buf = Vec::new();
+        assert_eq!(daemon.try_recv_large_command(&mut buf), None);
+        assert_eq!(daemon.stats().discarded_large_commands, 0);
+
+        std::thread::sleep(timeout);
+        assert_eq!(daemon.try_recv_large_command(&mut buf), None);
+        assert_eq!(daemon.stats().discarded_large_commands, 1);
+        // Its last chunk, late, completes nothing
+        shell.send_command(chunks.last().unwrap());
+        assert_eq!(daemon.try_recv_large_command(&mut buf), None);
+
+        shell.send_large_command(&[2; 200]).unwrap();
+        assert_eq!(daemon.try_recv_large_command(&mut buf), Some((1, 200)));
+        assert_eq!(buf, [2; 200]);
+    }
+
+    #[test]
+    fn test_write_vectored() {
+        let namespace = "test_channel_vectored";
+        let config = ChannelConfig { data_size: 64, ..ChannelConfig::default() };
+        let mut daemon = DaemonChannel::create(namespace, config).unwrap();
+        let shell = ShellChannel::connect(namespace).unwrap();
+
+        let header = 5u32.to_le_bytes();
+        daemon.write_vectored(&[&header, b"hello"]);
+        let mut buf = [0u8; 64];
+        assert_eq!(shell.data_sequence(), 2, "one write");
+        let len = shell.try_read_data_with_len(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"\x05\0\0\0hello");
+
+        // The raw region, prefix and all, into uninitialized memory
+        let mut raw = Vec::with_capacity(64);
+        let read = shell.read_into_uninit(raw.spare_capacity_mut());
+        unsafe { raw.set_len(read) };
+        assert_eq!(read, 64);
+        assert_eq!(raw[..LEN_PREFIX + 9], [&9u64.to_le_bytes()[..], &header, b"hello"].concat());
+
+        // While a resize retires the old region, both get the write
+        daemon.resize_data(4096).unwrap();
+        daemon.write_vectored(&[b"after", b" ", b"resize"]);
+        assert_eq!(daemon.retiring.borrow().len(), 1);
+        let len = shell.try_read_data_with_len(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"after resize");
+    }
+
+    #[test]
+    fn test_resize_data() {
+        let namespace = "test_channel_resize";
+        let config = ChannelConfig { data_size: 64, ..ChannelConfig::default() };
+        let mut daemon = DaemonChannel::create(namespace, config).unwrap();
+        let shell = ShellChannel::connect(namespace).unwrap();
+        let observer = ShellChannel::connect_read_only(namespace).unwrap();
+        daemon.write_data_with_len(b"before");
+
+        assert!(matches!(daemon.resize_data(32), Err(VenomError::InvalidConfig { .. })));
+        daemon.resize_data(64 * 1024).unwrap();
+        assert_eq!(daemon.data_size(), 64 * 1024);
+
+        // Shells move over with the data and sequence as they were
+        let mut buf = vec![0u8; 64 * 1024];
+        for shell in [&shell, &observer] {
+            assert_eq!(shell.data_sequence(), 2);
+            assert_eq!(shell.data_size(), 64 * 1024);
+            let len = shell.try_read_data_with_len(&mut buf).unwrap();
+            assert_eq!(&buf[..len], b"before");
+        }
+
+        // The old region is written until the shell has moved, then dropped
+        let late = ShellChannel::connect(namespace).unwrap();
+        daemon.write_data_with_len(&[7; 1000]);
+        assert!(daemon.retiring.borrow().is_empty());
+        for shell in [&shell, &late] {
+            assert_eq!(shell.try_read_data_with_len(&mut buf).unwrap(), 1000);
+            assert!(buf[..1000].iter().all(|&b| b == 7));
+        }
+
+        let lagging = ShellChannel::connect(namespace).unwrap();
+        daemon.resize_data(128 * 1024).unwrap();
+        daemon.write_data_with_len(b"after");
+        assert_eq!(daemon.retiring.borrow().len(), 1);
+        shell.read_data_with_len(&mut buf);
+        late.read_data_with_len(&mut buf);
+        daemon.write_data_with_len(b"after");
+        assert_eq!(daemon.retiring.borrow().len(), 1, "one shell is still on the old region");
+        assert_eq!(lagging.read_data_with_len(&mut buf), 5);
+        daemon.write_data_with_len(b"again");
+        assert!(daemon.retiring.borrow().is_empty());
+        let len = lagging.read_data_with_len(&mut buf);
+        assert_eq!(&buf[..len], b"again");
+        assert_eq!(lagging.data_sequence(), 10);
+    }
+
+    #[test]
+    fn test_resize_data_old_region_never_cut() {
+        let namespace = "test_channel_resize_cut";
+        let config = ChannelConfig { data_size: 64, ..ChannelConfig::default() };
+        let mut daemon = DaemonChannel::create(namespace, config).unwrap();
+        let shell = ShellChannel::connect(namespace).unwrap();
+        daemon.write_data_with_len(b"small");
+        daemon.resize_data(4096).unwrap();
+
+        // What a read that started before the shell moved still sees
+        let old = unsafe { &(*shell.data.load(Ordering::Acquire)).seqlock };
+        let mut buf = [0u8; 4096];
+        daemon.write_data_with_len(&[1; 56]);
+        assert_eq!(old.try_read_with_len(&mut buf).unwrap(), 56, "fits, so written");
+        daemon.write_data_with_len(&[2; 57]);
+        daemon.write_vectored(&[&[3; 50], &[3; 50]]);
+        assert_eq!(old.try_read_with_len(&mut buf).unwrap(), 56, "the last write that fit, whole");
+        assert!(buf[..56].iter().all(|&b| b == 1));
+
+        assert_eq!(shell.try_read_data_with_len(&mut buf).unwrap(), 100);
+    }
+
+    #[test]
+    fn test_resize_data_under_readers() {
+        let namespace = "test_channel_resize_live";
+        let config = ChannelConfig { data_size: 64, ..ChannelConfig::default() };
+        let mut daemon = DaemonChannel::create(namespace, config).unwrap();
+        let shared = ShellChannel::connect(namespace).unwrap();
+        let done = std::sync::atomic::AtomicBool::new(false);
+
+        // Frame n is n bytes, each of them n % 251
+        let check = |shell: &ShellChannel, buf: &mut [u8], last: &mut usize| {
+            let len = shell.try_read_data_with_len(buf).unwrap();
+            assert!(buf[..len].iter().all(|&b| b as usize == len % 251), "torn {}-byte frame", len);
+            assert!(len >= *last, "frame {} after {}", len, *last);
+            *last = len;
+        };
+        std::thread::scope(|scope| {
+            for _ in 0..2 {
+                scope.spawn(|| {
+                    let (mut buf, mut last) = (vec![0u8; 8192], 0);
+                    while !done.load(Ordering::Relaxed) {
+                        check(&shared, &mut buf, &mut last);
+                        std::thread::yield_now();
+                    }
+                });
+                scope.spawn(|| {
+                    let shell = ShellChannel::connect(namespace).unwrap();
+                    let (mut buf, mut last) = (vec![0u8; 8192], 0);
+                    while !done.load(Ordering::Relaxed) {
+                        check(&shell, &mut buf, &mut last);
+                        std::thread::yield_now();
+                    }
+                });
+            }
+
+            let mut frame = Vec::new();
+            for len in 1..4096 {
+                if len + 8 > daemon.data_size() {
+                    daemon.resize_data(daemon.data_size() * 2).unwrap();
+                }
+                frame.clear();
+                frame.resize(len, (len % 251) as u8);
+                daemon.write_data_with_len(&frame);
+                if len % 64 == 0 {
+                    std::thread::yield_now();
+                }
+            }
+            done.store(true, Ordering::Relaxed);
+        });
+        assert_eq!(shared.data_size(), 8192);
+        assert_eq!(shared.read_data_with_len(&mut [0u8; 8192]), 4095);
+    }
+
+    #[test]
+    fn test_run_parallel_slow_client() {
+        let namespace = "test_channel_run_parallel_slow";
+        let daemon = DaemonChannel::create(namespace, ChannelConfig::default()).unwrap();
+        let slow = ShellChannel::connect(namespace).unwrap();
+        let fast = ShellChannel::connect(namespace).unwrap();
+
+        let fast_latency = std::thread::scope(|scope| {
+            let client = scope.spawn(|| {
+                slow.send_command(b"slow");
+                std::thread::sleep(Duration::from_millis(20));
+                let sent = std::time::Instant::now();
+                fast.send_command(b"fast");
+                let mut buf = [0u8; 16];
+                while fast.read_data_with_len(&mut buf) != 4 || &buf[..4] != b"fast" {
+                    assert!(sent.elapsed() < Duration::from_secs(5), "no response for the fast client");
+                    std::thread::yield_now();
+                }
+                let latency = sent.elapsed();
+                fast.send_command(b"__SHUTDOWN__");
+                latency
+            });
+            daemon.run_parallel(2, |_, cmd| {
+                if cmd == b"slow" {
+                    std::thread::sleep(Duration::from_millis(500));
+                }
+                cmd.to_vec()
+            });
+            client.join().unwrap()
+        });
+        assert!(fast_latency < Duration::from_millis(250), "fast client waited {fast_latency:?}");
+
+        // Shutdown waited for the slow command and wrote its response
+        let mut buf = [0u8; 16];
+        assert_eq!(fast.read_data_with_len(&mut buf), 4);
+        assert_eq!(&buf[..4], b"slow");
+    }
+
+    #[test]
+    fn test_run_parallel_keeps_client_order() {
+        let namespace = "test_channel_run_parallel_order";
+        let daemon = DaemonChannel::create(namespace, ChannelConfig::default()).unwrap();
+        let shells: Vec<_> = (0..3).map(|_| ShellChannel::connect(namespace).unwrap()).collect();
+        let handled = std::sync::Mutex::new(Vec::new());
+
+        std::thread::scope(|scope| {
+            let shells = &shells;
+            scope.spawn(move || {
+                std::thread::scope(|senders| {
+                    for shell in shells {
+                        senders.spawn(move || {
+                            for n in 0u32..200 {
+                                // Stay clear of a full queue, where pushes fail
+                                while shell.commands_pending() > 16 {
+                                    std::thread::yield_now();
+                                }
+                                shell.send_command(&n.to_le_bytes());
+                            }
+                        });
+                    }
+                });
+                shells[0].send_command(b"__SHUTDOWN__");
+            });
+            daemon.run_parallel(4, |client_id, cmd| {
+                let n = u32::from_le_bytes(cmd.try_into().unwrap());
+                // Uneven work, so workers drift apart
+                std::thread::sleep(Duration::from_micros(u64::from(n % 7) * 20));
+                handled.lock().unwrap().push((client_id, n));
+                Vec::new()
+            });
+        });
+
+        let handled = handled.into_inner().unwrap();
+        assert_eq!(handled.len(), 600);
+        for shell in &shells {
+            let order: Vec<u32> = handled.iter().filter(|&&(id, _)| id == shell.client_id()).map(|&(_, n)| n).collect();
+            assert_eq!(order, (0..200).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_create_detects_live_namespace() {
+        let namespace = "test_channel_in_use";
+        let daemon = DaemonChannel::create(namespace, ChannelConfig::default()).unwrap();
+
+        match DaemonChannel::create(namespace, ChannelConfig::default()) {
+            Err(VenomError::NamespaceInUse { name, pid }) => {
+                assert_eq!(name, namespace);
+                assert_eq!(pid, Some(std::process::id()));
+            }
+            other => panic!("expected NamespaceInUse, got {:?}", other.err()),
+        }
+        assert!(matches!(
+            DaemonChannel::create_force(namespace, ChannelConfig::default()),
+            Err(VenomError::NamespaceInUse { .. })
+        ));
+
+        // The original channel must be left untouched
+        daemon.write_data(b"still here");
+        let shell = ShellChannel::connect(namespace).unwrap();
+        let mut buf = [0u8; 16];
+        shell.read_data(&mut buf);
+        assert_eq!(&buf[..10], b"still here");
+    }
+
+    #[test]
+    fn test_create_force_takes_over_stale_namespace() {
+        let namespace = "test_channel_stale";
+        let config = ChannelConfig::default();
+
+        // Leave behind a channel whose owner no longer exists
+        let stale = DaemonChannel::create(namespace, config.clone()).unwrap();
+        let shell = ShellChannel::connect(namespace).unwrap();
+        stale.write_data(b"old");
+        unsafe { (*stale.header).owner_pid.store(i32::MAX as u32, Ordering::Release) };
+
+        // Only taken over when asked to
+        match DaemonChannel::create(namespace, config.clone()) {
+            Err(VenomError::NamespaceInUse { pid, .. }) => assert_eq!(pid, Some(i32::MAX as u32)),
+            other => panic!("expected NamespaceInUse, got {:?}", other.err()),
+        }
+        let daemon = DaemonChannel::create_force(namespace, config).unwrap();
+        assert_eq!(daemon.namespace(), namespace);
+        daemon.write_data(b"new");
+
+        // The old shell keeps the old channel; new ones get the new one
+        let mut buf = [0u8; 3];
+        shell.read_data(&mut buf);
+        assert_eq!((&buf, shell.daemon_alive()), (b"old", false));
+        ShellChannel::connect(namespace).unwrap().read_data(&mut buf);
+        assert_eq!(&buf, b"new");
+
+        // Nor does the old handle remove the new channel on its way out
+        assert!(!stale.unlink().unwrap());
+        drop(stale);
+        assert!(ShellChannel::connect(namespace).is_ok());
+    }
+
+    #[test]
+    fn test_unlink() {
+        let namespace = "test_channel_unlink";
+        let daemon = DaemonChannel::create(namespace, ChannelConfig::default()).unwrap();
+        let shell = ShellChannel::connect(namespace).unwrap();
+
+        assert!(daemon.unlink().unwrap());
+        assert!(ShellChannel::connect(namespace).is_err());
+        assert!(!daemon.unlink().unwrap());
+
+        // Connected shells still read it
+        daemon.write_data(b"after");
+        let mut buf = [0u8; 5];
+        shell.read_data(&mut buf);
+        assert_eq!((&buf, shell.daemon_alive()), (b"after", true));
+
+        // A new daemon can take the name while the old one still runs
+        let next = DaemonChannel::create(namespace, ChannelConfig::default()).unwrap();
+        drop(daemon);
+        assert!(ShellChannel::connect(namespace).is_ok());
+        drop(next);
+    }
+
+    #[test]
+    fn test_user_scoped_namespace() {
+        let config = ChannelConfig {
+            user_scoped: true,
+            ..ChannelConfig::default()
+        };
+        let daemon = DaemonChannel::create("test_channel_scoped", config).unwrap();
+        let uid = rustix::process::getuid().as_raw();
+        assert_eq!(daemon.namespace(), format!("{}_test_channel_scoped", uid));
+
+        assert!(ShellChannel::connect_user_scoped("test_channel_scoped").is_ok());
+        assert!(ShellChannel::connect("test_channel_scoped").is_err());
+    }
 }
@@ -0,0 +1,361 @@
+//! Streaming video frames through a channel
+//!
+//! A frame can be megabytes: copied with
+//! [`write_data`](DaemonChannel::write_data), a reader copying it out while
+//! the next one is written would retry until the writer paused. Instead
+//! [`FrameWriter`] splits the data region into [`FRAME_SLOTS`] slots and
+//! writes each frame to the slot after the last one's, so the newest frame
+//! stays put for two frame intervals while [`FrameReader`] copies it.
+//!
+//! A channel carries frames or ordinary data, not both: the region holds
+//! the slots, not a [`write_data`](DaemonChannel::write_data) payload.
+//!
+//! # Layout
+//!
+//! Within the data region, every field in native byte order; fields
+//! documented as atomic must only be accessed with atomic operations.
+//!
+//! | Offset | Field | |
+//! |--------|-------|-|
+//! | 0 | `u32` magic | [`FRAMES_MAGIC`], once a writer has set the region up |
+//! | 4 | `u32` slots | [`FRAME_SLOTS`] |
+//! | 8 | `u64` slot size | Bytes from one slot to the next, a multiple of 64 |
+//! | 16 | atomic `u64` latest | Number of the newest whole frame, from 1; 0 before the first |
+//!
+//! Slot `i` starts at `64 + i * slot_size` and holds frame `n` when
+//! `n % slots == i`:
+//!
+//! | Offset | Field | |
+//! |--------|-------|-|
+//! | 0 | atomic `u64` sequence | Odd while the writer fills the slot |
+//! | 8 | atomic `u64` frame number | |
+//! | 16, 20, 24 | atomic `u32` width, height, stride | Stride in bytes |
+//! | 32 | atomic `u64` pts | Presentation time in ns, as the writer gave it |
+//! | 40 | atomic `u64` length | Bytes of pixels, `stride * height` |
+//! | 64 | pixels | |
+//!
+//! A reader loads `latest`, then reads its slot like a SeqLock: it copies
+//! the header and pixels between two loads of the sequence, and keeps the
+//! copy if the sequence was even and unchanged and the frame number is
+//! the one it was after.
+
+use super::{DaemonChannel, ShellChannel};
+use crate::error::{Result, VenomError};
+use std::sync::atomic::{fence, AtomicU32, AtomicU64, Ordering};
+
+/// Magic of a data region set up for frames, `"VFRM"` in little-endian byte order
+pub const FRAMES_MAGIC: u32 = u32::from_le_bytes(*b"VFRM");
+/// Frames the region holds: the newest, the one before it, and the one
+/// being written
+pub const FRAME_SLOTS: usize = 3;
+/// Bytes before the first slot, and before each slot's pixels
+const HEADER_SIZE: usize = 64;
+
+/// Header at the start of the data region
+#[repr(C)]
+struct RegionHeader {
+    magic: AtomicU32,
+    slots: AtomicU32,
+    slot_size: AtomicU64,
+    latest: AtomicU64,
+}
+
+/// Header at the start of each slot
+#[repr(C)]
+struct SlotHeader {
+    sequence: AtomicU64,
+    frame_number: AtomicU64,
+    width: AtomicU32,
+    height: AtomicU32,
+    stride: AtomicU32,
+    _pad: u32,
+    pts_ns: AtomicU64,
+    len: AtomicU64,
+}
+
+/// A frame's header, as [`FrameReader::read_since`] returns it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameHeader {
+    /// Counts up from 1; a gap from the last frame read is frames missed
+    pub frame_number: u64,
+    pub width: u32,
+    pub height: u32,
+    /// Bytes from one row to the next
+    pub stride: u32,
+    /// Presentation time in nanoseconds, as the writer gave it
+    pub pts_ns: u64,
+    /// Bytes of pixels, `stride * height`
+    pub len: usize,
+}
+
+/// Data region size that holds frames of up to `max_frame_len` bytes,
+/// for [`ChannelConfig::data_size`](super::ChannelConfig::data_size)
+pub const fn data_size_for(max_frame_len: usize) -> usize {
+    HEADER_SIZE + FRAME_SLOTS * slot_size_for(max_frame_len)
+}
+
+const fn slot_size_for(max_frame_len: usize) -> usize {
+    (HEADER_SIZE + max_frame_len).div_ceil(HEADER_SIZE) * HEADER_SIZE
+}
+
+/// # Safety
+/// `region` must be valid for a [`RegionHeader`] and 8-aligned
+unsafe fn region_header<'a>(region: *const u8) -> &'a RegionHeader {
+    &*(region as *const RegionHeader)
+}
+
+/// # Safety
+/// `region` must hold `slot + 1` slots of `slot_size` bytes after its header
+unsafe fn slot_at(region: *const u8, slot_size: usize, slot: usize) -> *const u8 {
+    region.add(HEADER_SIZE + slot * slot_size)
+}
+
+/// Writes frames into a daemon's data region
+pub struct FrameWriter<'a> {
+    daemon: &'a DaemonChannel,
+    region: *mut u8,
+    slot_size: usize,
+    latest: u64,
+}
+
+impl<'a> FrameWriter<'a> {
+    /// Set the daemon's data region up for frames
+    ///
+    /// Numbering carries on from the last frame of a writer before this
+    /// one. Fails with [`VenomError::InvalidConfig`] if the region can't
+    /// hold [`FRAME_SLOTS`] frames of at least a byte; size it with
+    /// [`data_size_for`].
+    pub fn new(daemon: &'a DaemonChannel) -> Result<Self> {
+        let data_size = daemon.data_size();
+        let slot_size = data_size.saturating_sub(HEADER_SIZE) / FRAME_SLOTS / HEADER_SIZE * HEADER_SIZE;
+        if slot_size <= HEADER_SIZE {
+            return Err(VenomError::InvalidConfig {
+                reason: format!("a {}-byte data region can't hold {} frames", data_size, FRAME_SLOTS),
+            });
+        }
+
+        let region = daemon.data.seqlock.data_ptr();
+        let header = unsafe { region_header(region) };
+        let set_up = header.magic.load(Ordering::Acquire) == FRAMES_MAGIC
+            && header.slots.load(Ordering::Relaxed) as usize == FRAME_SLOTS
+            && header.slot_size.load(Ordering::Relaxed) as usize == slot_size;
+        if !set_up {
+            // Readers ignore the region until the magic is back
+            header.magic.store(0, Ordering::Release);
+            header.latest.store(0, Ordering::Relaxed);
+            for slot in 0..FRAME_SLOTS {
+                let slot = unsafe { &*(slot_at(region, slot_size, slot) as *const SlotHeader) };
+                slot.sequence.store(0, Ordering::Relaxed);
+                slot.frame_number.store(0, Ordering::Relaxed);
+            }
+            header.slots.store(FRAME_SLOTS as u32, Ordering::Relaxed);
+            header.slot_size.store(slot_size as u64, Ordering::Relaxed);
+            header.magic.store(FRAMES_MAGIC, Ordering::Release);
+        }
+        let latest = header.latest.load(Ordering::Relaxed);
+        Ok(FrameWriter { daemon, region, slot_size, latest })
+    }
+
+    /// Longest frame a slot holds
+    pub fn max_frame_len(&self) -> usize {
+        self.slot_size - HEADER_SIZE
+    }
+
+    /// Number of the last frame written; 0 before the first
+    pub fn latest(&self) -> u64 {
+        self.latest
+    }
+
+    /// Channel the frames go to
+    pub fn daemon(&self) -> &DaemonChannel {
+        self.daemon
+    }
+
+    /// Write a frame of `height` rows, `stride` bytes apart, and make it
+    /// the newest; returns its number
+    ///
+    /// Fails with [`VenomError::InvalidConfig`] if `pixels` isn't
+    /// `stride * height` bytes, and with [`VenomError::BufferOverflow`] if
+    /// it's longer than [`max_frame_len`](Self::max_frame_len).
+    pub fn write(&mut self, width: u32, height: u32, stride: u32, pts_ns: u64, pixels: &[u8]) -> Result<u64> {
+        if stride as usize * height as usize != pixels.len() {
+            return Err(VenomError::InvalidConfig {
+                reason: format!("{} bytes of pixels for {} rows of {} bytes", pixels.len(), height, stride),
+            });
+        }
+        if pixels.len() > self.max_frame_len() {
+            return Err(VenomError::BufferOverflow { max: self.max_frame_len(), got: pixels.len() });
+        }
+
+        let frame_number = self.latest + 1;
+        let slot_ptr = unsafe { slot_at(self.region, self.slot_size, frame_number as usize % FRAME_SLOTS) };
+        let slot = unsafe { &*(slot_ptr as *const SlotHeader) };
+        slot.sequence.fetch_add(1, Ordering::Release);
+        // Nothing below may be seen before the sequence turns odd
+        fence(Ordering::Release);
+        slot.frame_number.store(frame_number, Ordering::Relaxed);
+        slot.width.store(width, Ordering::Relaxed);
+        slot.height.store(height, Ordering::Relaxed);
+        slot.stride.store(stride, Ordering::Relaxed);
+        slot.pts_ns.store(pts_ns, Ordering::Relaxed);
+        slot.len.store(pixels.len() as u64, Ordering::Relaxed);
+        unsafe {
+            std::ptr::copy_nonoverlapping(pixels.as_ptr(), slot_ptr.add(HEADER_SIZE) as *mut u8, pixels.len());
+        }
+        slot.sequence.fetch_add(1, Ordering::Release);
+
+        unsafe { region_header(self.region) }.latest.store(frame_number, Ordering::Release);
+        self.latest = frame_number;
+        Ok(frame_number)
+    }
+}
+
+/// Reads the frames a [`FrameWriter`] writes, from a shell
+pub struct FrameReader<'a> {
+    shell: &'a ShellChannel,
+}
+
+impl<'a> FrameReader<'a> {
+    pub fn new(shell: &'a ShellChannel) -> Self {
+        FrameReader { shell }
+    }
+
+    /// The region's slot size, None until a writer has set it up
+    fn layout(&self) -> Result<Option<(*const u8, usize)>> {
+        let reader = self.shell.data_reader();
+        let region = reader.data_ptr();
+        let header = unsafe { region_header(region) };
+        if header.magic.load(Ordering::Acquire) != FRAMES_MAGIC {
+            return Ok(None);
+        }
+        let (slots, slot_size) = (header.slots.load(Ordering::Relaxed), header.slot_size.load(Ordering::Relaxed));
+        let fits = (slots as usize == FRAME_SLOTS)
+            && (slot_size as usize).is_multiple_of(HEADER_SIZE)
+            && slot_size as usize > HEADER_SIZE
+            && (slot_size as usize)
+                .checked_mul(FRAME_SLOTS)
+                .and_then(|size| size.checked_add(HEADER_SIZE))
+                .is_some_and(|size| size <= reader.data_size());
+        if !fits {
+            return Err(VenomError::CorruptRegion {
+                reason: format!(
+                    "{} frame slots of {} bytes in a {}-byte data region",
+                    slots,
+                    slot_size,
+                    reader.data_size()
+                ),
+            });
+        }
+        Ok(Some((region, slot_size as usize)))
+    }
+
+    /// Number of the newest frame; 0 before the first
+    pub fn latest(&self) -> Result<u64> {
+        Ok(match self.layout()? {
+            Some((region, _)) => unsafe { region_header(region) }.latest.load(Ordering::Acquire),
+            None => 0,
+        })
+    }
+
+    /// Copy the newest frame into `buf` if its number is past `after`
+    ///
+    /// Returns its header, or None if there's no newer frame; frames
+    /// between `after` and the one returned were missed. Fails with
+    /// [`VenomError::CorruptRegion`] if the region's layout doesn't fit it.
+    pub fn read_since(&self, after: u64, buf: &mut Vec<u8>) -> Result<Option<FrameHeader>> {
+        let Some((region, slot_size)) = self.layout()? else {
+            return Ok(None);
+        };
+        let max_frame_len = slot_size - HEADER_SIZE;
+        loop {
+            let frame_number = unsafe { region_header(region) }.latest.load(Ordering::Acquire);
+            if frame_number <= after {
+                return Ok(None);
+            }
+            let slot_ptr = unsafe { slot_at(region, slot_size, frame_number as usize % FRAME_SLOTS) };
+            let slot = unsafe { &*(slot_ptr as *const SlotHeader) };
+            let sequence = slot.sequence.load(Ordering::Acquire);
+            if sequence % 2 == 1 {
+                // The writer lapped us; it may need this CPU to finish
+                std::thread::yield_now();
+                continue;
+            }
+
+            let header = FrameHeader {
+                frame_number: slot.frame_number.load(Ordering::Relaxed),
+                width: slot.width.load(Ordering::Relaxed),
+                height: slot.height.load(Ordering::Relaxed),
+                stride: slot.stride.load(Ordering::Relaxed),
+                pts_ns: slot.pts_ns.load(Ordering::Relaxed),
+                len: (slot.len.load(Ordering::Relaxed) as usize).min(max_frame_len),
+            };
+            buf.resize(header.len, 0);
+            unsafe { std::ptr::copy_nonoverlapping(slot_ptr.add(HEADER_SIZE), buf.as_mut_ptr(), header.len) };
+            fence(Ordering::Acquire);
+            if slot.sequence.load(Ordering::Relaxed) == sequence && header.frame_number == frame_number {
+                return Ok(Some(header));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ChannelConfig;
+
+    fn frame(width: u32, height: u32, shade: u8) -> Vec<u8> {
+        vec![shade; width as usize * 4 * height as usize]
+    }
+
+    #[test]
+    fn test_frame_header_round_trips() {
+        let namespace = "test_frames_round_trip";
+        let config = ChannelConfig { data_size: data_size_for(64 * 48 * 4), ..ChannelConfig::default() };
+        let daemon = DaemonChannel::create(namespace, config).unwrap();
+        let shell = ShellChannel::connect(namespace).unwrap();
+        let reader = FrameReader::new(&shell);
+        let mut buf = Vec::new();
+        assert_eq!(reader.read_since(0, &mut buf).unwrap(), None);
+
+        let mut writer = FrameWriter::new(&daemon).unwrap();
+        assert_eq!(writer.max_frame_len(), 64 * 48 * 4);
+        assert_eq!(writer.write(64, 48, 256, 1_000, &frame(64, 48, 7)).unwrap(), 1);
+        let header = reader.read_since(0, &mut buf).unwrap().unwrap();
+        assert_eq!(header, FrameHeader { frame_number: 1, width: 64, height: 48, stride: 256, pts_ns: 1_000, len: 64 * 48 * 4 });
+        assert_eq!(buf, frame(64, 48, 7));
+        assert_eq!(reader.read_since(1, &mut buf).unwrap(), None);
+
+        // A smaller frame in the next slot, then frames lapping the slots:
+        // only the newest is read, and the gap says how many were missed
+        writer.write(32, 16, 128, 2_000, &frame(32, 16, 8)).unwrap();
+        let header = reader.read_since(1, &mut buf).unwrap().unwrap();
+        assert_eq!((header.frame_number, header.width, header.height, header.len), (2, 32, 16, 32 * 16 * 4));
+        for shade in 9..14 {
+            writer.write(64, 48, 256, shade as u64, &frame(64, 48, shade)).unwrap();
+        }
+        let header = reader.read_since(2, &mut buf).unwrap().unwrap();
+        assert_eq!((header.frame_number, header.pts_ns), (7, 13));
+        assert_eq!(buf, frame(64, 48, 13));
+
+        // A new writer carries on the numbering
+        let mut writer = FrameWriter::new(&daemon).unwrap();
+        assert_eq!(writer.write(1, 1, 4, 0, &[1, 2, 3, 4]).unwrap(), 8);
+        assert_eq!(reader.latest().unwrap(), 8);
+    }
+
+    #[test]
+    fn test_frame_writer_checks() {
+        let config = ChannelConfig { data_size: data_size_for(1024), ..ChannelConfig::default() };
+        let daemon = DaemonChannel::create("test_frames_checks", config).unwrap();
+        let mut writer = FrameWriter::new(&daemon).unwrap();
+        assert!(matches!(writer.write(16, 16, 64, 0, &[0; 1024]), Ok(1)));
+        assert!(matches!(writer.write(16, 17, 64, 0, &[0; 1088]), Err(VenomError::BufferOverflow { max: 1024, got: 1088 })));
+        assert!(matches!(writer.write(16, 16, 64, 0, &[0; 1000]), Err(VenomError::InvalidConfig { .. })));
+        assert_eq!(writer.latest(), 1);
+
+        let config = ChannelConfig { data_size: 256, ..ChannelConfig::default() };
+        let small = DaemonChannel::create("test_frames_small", config).unwrap();
+        assert!(matches!(FrameWriter::new(&small), Err(VenomError::InvalidConfig { .. })));
+    }
+}
@@ -0,0 +1,301 @@
+//! Owned snapshots of a channel's data
+//!
+//! [`ShellChannel::snapshot`] copies the latest length-prefixed write out
+//! together with its sequence number and timestamp, all from the same
+//! SeqLock read, so a [`Frame`] never pairs one write's payload with
+//! another's timestamp.
+
+use super::ShellChannel;
+use crate::error::{Result, VenomError};
+use crate::seqlock::{is_newer, now_ns};
+use std::ops::Deref;
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+/// Polls [`ShellChannel::snapshot_blocking`] spins through before it sleeps
+const SPIN_LIMIT: u32 = 64;
+/// Longest sleep between two polls of [`ShellChannel::snapshot_blocking`]
+const POLL_SLEEP: Duration = Duration::from_micros(50);
+
+/// One write of the daemon, copied out of the channel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// The payload, at most as long as the snapshot was allowed to take
+    pub data: Vec<u8>,
+    /// Sequence number of the write: even, never 0, and newer for every
+    /// later write by [`is_newer`], which orders them across the wrap
+    pub generation: u64,
+    /// [`now_ns`] when the daemon wrote it; None if the daemon doesn't
+    /// stamp its writes
+    pub timestamp: Option<u64>,
+    /// The payload was longer than the snapshot took, and `data` is its start
+    pub truncated: bool,
+}
+
+impl Frame {
+    /// How long ago the daemon wrote the frame; None if it wasn't stamped
+    pub fn age(&self) -> Option<Duration> {
+        self.timestamp.map(|timestamp| Duration::from_nanos(now_ns().saturating_sub(timestamp)))
+    }
+
+    /// The payload, without the rest of the frame
+    pub fn into_vec(self) -> Vec<u8> {
+        self.data
+    }
+
+    /// A `T` read from the start of the payload; None if the payload is
+    /// shorter than a `T`
+    pub fn decode<T: Pod>(&self) -> Option<T> {
+        if self.data.len() < std::mem::size_of::<T>() {
+            return None;
+        }
+        // SAFETY: the payload holds size_of::<T>() bytes, and any of them make a T
+        Some(unsafe { std::ptr::read_unaligned(self.data.as_ptr().cast::<T>()) })
+    }
+}
+
+impl Deref for Frame {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+impl AsRef<[u8]> for Frame {
+    fn as_ref(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Frame {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut frame = serializer.serialize_struct("Frame", 4)?;
+        frame.serialize_field("data", &self.data)?;
+        frame.serialize_field("generation", &self.generation)?;
+        frame.serialize_field("timestamp", &self.timestamp)?;
+        frame.serialize_field("truncated", &self.truncated)?;
+        frame.end()
+    }
+}
+
+/// Types every bit pattern of whose size is a valid value, which
+/// [`Frame::decode`] can read out of a payload
+///
+/// # Safety
+/// Any `size_of::<Self>()` bytes must make a valid value: no `bool`s,
+/// `char`s, enums, references or pointers. `#[repr(C)]` structs of
+/// integers, floats and arrays of them qualify, such as the state structs
+/// `venom init` generates.
+pub unsafe trait Pod: Copy + 'static {}
+
+macro_rules! impl_pod {
+    ($($ty:ty),*) => {
+        $(unsafe impl Pod for $ty {})*
+    };
+}
+
+impl_pod!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+impl ShellChannel {
+    /// The daemon's latest length-prefixed write as a [`Frame`]: payload,
+    /// sequence number and timestamp from the same read
+    ///
+    /// None before the daemon's first write, or if the length prefix stays
+    /// larger than the data region (as
+    /// [`try_read_data_with_len`](Self::try_read_data_with_len) fails).
+    pub fn snapshot(&self) -> Option<Frame> {
+        self.snapshot_max(usize::MAX)
+    }
+
+    /// [`snapshot`](Self::snapshot), copying at most `max_len` bytes of the
+    /// payload; a longer one is cut and the frame marked
+    /// [`truncated`](Frame::truncated)
+    pub fn snapshot_max(&self, max_len: usize) -> Option<Frame> {
+        let max_len = max_len.min(self.data_capacity());
+        let mut data = vec![0u8; max_len];
+        let (generation, timestamp, len) = self.snapshot_into(&mut data)?;
+        data.truncate(len);
+        Some(Frame { data, generation, timestamp: (timestamp != 0).then_some(timestamp), truncated: len > max_len })
+    }
+
+    /// [`snapshot`](Self::snapshot) into `buf`: (generation, timestamp or
+    /// 0, whole payload length), of which `buf` holds at most its length
+    pub(crate) fn snapshot_into(&self, buf: &mut [u8]) -> Option<(u64, u64, usize)> {
+        let (generation, timestamp, len) = self.data_reader().try_read_with_len_sequenced(buf).ok()?;
+        if generation == 0 {
+            return None;
+        }
+        let _ = self.last_snapshot.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |last| {
+            (last == 0 || is_newer(generation, last)).then_some(generation)
+        });
+        Some((generation, timestamp, len))
+    }
+
+    /// Wait up to `timeout` for a write newer than the last frame this
+    /// shell took, and return it as [`snapshot`](Self::snapshot) does
+    ///
+    /// The first call returns at once if the daemon has written anything.
+    /// Fails with [`VenomError::Timeout`] if no newer write came.
+    pub fn snapshot_blocking(&self, timeout: Duration) -> Result<Frame> {
+        let deadline = Instant::now() + timeout;
+        let mut spins = 0;
+        loop {
+            let last = self.last_snapshot.load(Ordering::Relaxed);
+            let sequence = self.data_sequence();
+            let newer = |sequence| last == 0 || is_newer(sequence, last);
+            if sequence & 1 == 0 && newer(sequence) {
+                match self.snapshot() {
+                    Some(frame) if newer(frame.generation) => return Ok(frame),
+                    _ => {}
+                }
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(VenomError::Timeout(timeout));
+            }
+            if spins < SPIN_LIMIT {
+                spins += 1;
+                core::hint::spin_loop();
+            } else {
+                std::thread::sleep(POLL_SLEEP.min(deadline - now));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ChannelConfig, DaemonChannel};
+
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Reading {
+        sensor: u32,
+        value: f32,
+        seen: u64,
+    }
+
+    unsafe impl Pod for Reading {}
+
+    #[test]
+    fn test_snapshot_matches_write() {
+        let namespace = "test_snapshot_write";
+        let config = ChannelConfig { stamp_writes: true, ..ChannelConfig::default() };
+        let daemon = DaemonChannel::create(namespace, config).unwrap();
+        let shell = ShellChannel::connect(namespace).unwrap();
+        assert_eq!(shell.snapshot(), None);
+
+        let reading = Reading { sensor: 7, value: 21.5, seen: 3 };
+        let bytes =
+            unsafe { std::slice::from_raw_parts((&reading as *const Reading).cast::<u8>(), size_of::<Reading>()) };
+        let before = now_ns();
+        daemon.write_data_with_len(bytes);
+
+        let frame = shell.snapshot().unwrap();
+        assert_eq!(&*frame, bytes);
+        assert!(!frame.truncated);
+        assert_eq!(frame.generation, shell.data_sequence());
+        assert_eq!(frame.timestamp, Some(shell.last_write_timestamp()));
+        assert!(frame.timestamp.unwrap() >= before);
+        assert_eq!(frame.decode::<Reading>(), Some(reading));
+        assert_eq!(frame.decode::<[u64; 3]>(), None);
+
+        // Every write moves the generation on
+        daemon.write_data_with_len(b"next");
+        let next = shell.snapshot().unwrap();
+        assert!(next.generation > frame.generation);
+        assert!(next.timestamp >= frame.timestamp);
+        assert_eq!(next.into_vec(), b"next");
+    }
+
+    #[test]
+    fn test_snapshot_unstamped() {
+        let namespace = "test_snapshot_unstamped";
+        let daemon = DaemonChannel::create(namespace, ChannelConfig::default()).unwrap();
+        let shell = ShellChannel::connect(namespace).unwrap();
+        daemon.write_data_with_len(b"data");
+        let frame = shell.snapshot().unwrap();
+        assert_eq!((frame.timestamp, frame.age()), (None, None));
+    }
+
+    #[test]
+    fn test_snapshot_truncated() {
+        let namespace = "test_snapshot_truncated";
+        let daemon = DaemonChannel::create(namespace, ChannelConfig::default()).unwrap();
+        let shell = ShellChannel::connect(namespace).unwrap();
+        let payload: Vec<u8> = (0..100).collect();
+        daemon.write_data_with_len(&payload);
+
+        let frame = shell.snapshot_max(16).unwrap();
+        assert!(frame.truncated);
+        assert_eq!(&*frame, &payload[..16]);
+        let frame = shell.snapshot_max(100).unwrap();
+        assert!(!frame.truncated);
+        assert_eq!(frame.len(), 100);
+    }
+
+    #[test]
+    fn test_snapshot_blocking() {
+        let namespace = "test_snapshot_blocking";
+        let daemon = DaemonChannel::create(namespace, ChannelConfig::default()).unwrap();
+        let shell = ShellChannel::connect(namespace).unwrap();
+        let timeout = Duration::from_millis(20);
+        assert!(matches!(shell.snapshot_blocking(timeout), Err(VenomError::Timeout(_))));
+
+        daemon.write_data_with_len(b"first");
+        assert_eq!(shell.snapshot_blocking(timeout).unwrap().data, b"first");
+        // Already taken
+        let start = Instant::now();
+        assert!(matches!(shell.snapshot_blocking(timeout), Err(VenomError::Timeout(_))));
+        assert!(start.elapsed() >= timeout);
+
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                let frame = shell.snapshot_blocking(Duration::from_secs(5)).unwrap();
+                assert_eq!(frame.data, b"second");
+            });
+            std::thread::sleep(Duration::from_millis(10));
+            daemon.write_data_with_len(b"second");
+        });
+    }
+
+    #[test]
+    fn test_snapshot_across_wrap() {
+        let namespace = "test_snapshot_wrap";
+        let daemon = DaemonChannel::create(namespace, ChannelConfig::default()).unwrap();
+        let shell = ShellChannel::connect(namespace).unwrap();
+        // As if the daemon had written 2^63 - 2 times
+        unsafe {
+            let offset = (*daemon.header).seqlock_offset.load(Ordering::Relaxed) as usize;
+            let seqlock = (daemon.header as *const u8).add(offset) as *const crate::seqlock::SeqLockHeader;
+            (*seqlock).sequence.0.store(u64::MAX - 3, Ordering::Relaxed);
+        }
+        let timeout = Duration::from_millis(20);
+
+        daemon.write_data_with_len(b"last before the wrap");
+        assert_eq!(shell.snapshot_blocking(timeout).unwrap().generation, u64::MAX - 1);
+        assert!(matches!(shell.snapshot_blocking(timeout), Err(VenomError::Timeout(_))));
+
+        daemon.write_data_with_len(b"first after it");
+        let frame = shell.snapshot_blocking(timeout).unwrap();
+        assert_eq!((frame.generation, &*frame.data), (2, &b"first after it"[..]));
+        assert!(matches!(shell.snapshot_blocking(timeout), Err(VenomError::Timeout(_))));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_frame_serializes() {
+        let frame = Frame { data: vec![1, 2], generation: 4, timestamp: None, truncated: false };
+        assert_eq!(
+            serde_json::to_string(&frame).unwrap(),
+            r#"{"data":[1,2],"generation":4,"timestamp":null,"truncated":false}"#
+        );
+    }
+}
@@ -0,0 +1,259 @@
+//! A shell split into a reading half and a sending half
+//!
+//! [`ShellChannel`] is already `Sync`, so one shell can be shared by
+//! reference between threads; [`split`](ShellChannel::split) is for when
+//! the threads need owned handles, each exposing only its side.
+//!
+//! Both halves share the one mapping and the one registration behind an
+//! `Arc`: there is a single client ID, it stays registered until the last
+//! handle is dropped, and the daemon sees one client. That has two
+//! consequences for clones of a half:
+//!
+//! - **Responses:** the client has one response slot, so the daemon's
+//!   [`respond`](super::DaemonChannel::respond) reaches whichever
+//!   [`ShellSender`] clone takes it first. Requests made from several
+//!   clones at once can take each other's responses; give each requesting
+//!   thread a shell of its own instead.
+//! - **Snapshots:** [`ShellReader::snapshot_blocking`] waits for a write
+//!   newer than the last frame any clone took.
+//!
+//! ```
+//! use std::time::Duration;
+//! use venom_memory::{ChannelConfig, DaemonChannel, ShellChannel};
+//!
+//! let daemon = DaemonChannel::create("doc_split", ChannelConfig::default())?;
+//! let (reader, sender) = ShellChannel::connect("doc_split")?.split();
+//!
+//! let updates = std::thread::spawn(move || reader.snapshot_blocking(Duration::from_secs(5)));
+//! sender.try_send_command(b"refresh");
+//!
+//! let mut cmd = [0u8; 64];
+//! while daemon.try_recv_command(&mut cmd).is_none() {}
+//! daemon.write_data_with_len(b"fresh");
+//! assert_eq!(&*updates.join().unwrap()?, b"fresh");
+//! # Ok::<(), venom_memory::VenomError>(())
+//! ```
+
+use super::snapshot::Frame;
+use super::ShellChannel;
+use crate::error::Result;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The reading half of a [split](ShellChannel::split) shell
+#[derive(Clone)]
+pub struct ShellReader {
+    shell: Arc<ShellChannel>,
+}
+
+/// The sending half of a [split](ShellChannel::split) shell
+///
+/// Clones send under the same client ID and share its response slot.
+#[derive(Clone)]
+pub struct ShellSender {
+    shell: Arc<ShellChannel>,
+}
+
+impl ShellChannel {
+    /// Split the shell into a [`ShellReader`] and a [`ShellSender`] that can
+    /// move to different threads
+    ///
+    /// Both keep the shell's client ID; it's released when the last of them,
+    /// or of their clones, is dropped. See [the module docs](self) for what
+    /// sharing it means for responses.
+    pub fn split(self) -> (ShellReader, ShellSender) {
+        let shell = Arc::new(self);
+        (ShellReader { shell: Arc::clone(&shell) }, ShellSender { shell })
+    }
+}
+
+impl ShellReader {
+    /// [`ShellChannel::read_data`]
+    #[inline]
+    pub fn read_data(&self, buf: &mut [u8]) -> usize {
+        self.shell.read_data(buf)
+    }
+
+    /// [`ShellChannel::read_data_with_len`]
+    #[inline]
+    pub fn read_data_with_len(&self, buf: &mut [u8]) -> usize {
+        self.shell.read_data_with_len(buf)
+    }
+
+    /// [`ShellChannel::try_read_data_with_len`]
+    #[inline]
+    pub fn try_read_data_with_len(&self, buf: &mut [u8]) -> Result<usize> {
+        self.shell.try_read_data_with_len(buf)
+    }
+
+    /// [`ShellChannel::read_data_stamped`]
+    #[inline]
+    pub fn read_data_stamped(&self, buf: &mut [u8]) -> (u64, usize) {
+        self.shell.read_data_stamped(buf)
+    }
+
+    /// [`ShellChannel::snapshot`]
+    pub fn snapshot(&self) -> Option<Frame> {
+        self.shell.snapshot()
+    }
+
+    /// [`ShellChannel::snapshot_blocking`]; a frame any clone of this
+    /// reader took counts as taken
+    pub fn snapshot_blocking(&self, timeout: Duration) -> Result<Frame> {
+        self.shell.snapshot_blocking(timeout)
+    }
+
+    /// [`ShellChannel::data_sequence`]
+    #[inline]
+    pub fn data_sequence(&self) -> u64 {
+        self.shell.data_sequence()
+    }
+
+    /// [`ShellChannel::data_capacity`]
+    #[inline]
+    pub fn data_capacity(&self) -> usize {
+        self.shell.data_capacity()
+    }
+
+    /// [`ShellChannel::daemon_alive`]
+    pub fn daemon_alive(&self) -> bool {
+        self.shell.daemon_alive()
+    }
+}
+
+impl ShellSender {
+    /// The client ID this half and the reader share
+    #[inline]
+    pub fn client_id(&self) -> u32 {
+        self.shell.client_id()
+    }
+
+    /// [`ShellChannel::try_send_command`]
+    #[inline]
+    pub fn try_send_command(&self, cmd: &[u8]) -> bool {
+        self.shell.try_send_command(cmd)
+    }
+
+    /// [`ShellChannel::send_command_timeout`]
+    pub fn send_command_timeout(&self, cmd: &[u8], timeout: Duration) -> Result<()> {
+        self.shell.send_command_timeout(cmd, timeout)
+    }
+
+    /// [`ShellChannel::send_large_command`]
+    pub fn send_large_command(&self, message: &[u8]) -> Result<()> {
+        self.shell.send_large_command(message)
+    }
+
+    /// [`ShellChannel::send_typed`]
+    pub fn send_typed(&self, tag: u16, payload: &[u8]) -> Result<()> {
+        self.shell.send_typed(tag, payload)
+    }
+
+    /// [`ShellChannel::request_timeout`]; a clone requesting at the same
+    /// time may take the response instead
+    pub fn request_timeout(&self, cmd: &[u8], response_buf: &mut [u8], timeout: Duration) -> Result<Option<usize>> {
+        self.shell.request_timeout(cmd, response_buf, timeout)
+    }
+
+    /// [`ShellChannel::try_recv_response`]
+    pub fn try_recv_response(&self, buf: &mut [u8]) -> Option<usize> {
+        self.shell.try_recv_response(buf)
+    }
+
+    /// [`ShellChannel::rejected_sends`]
+    pub fn rejected_sends(&self) -> u64 {
+        self.shell.rejected_sends()
+    }
+
+    /// [`ShellChannel::cmd_queue_len`]
+    pub fn cmd_queue_len(&self) -> usize {
+        self.shell.cmd_queue_len()
+    }
+
+    /// [`ShellChannel::daemon_alive`]
+    pub fn daemon_alive(&self) -> bool {
+        self.shell.daemon_alive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ChannelConfig, DaemonChannel};
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn test_split_halves_are_send_sync() {
+        assert_send_sync::<ShellReader>();
+        assert_send_sync::<ShellSender>();
+    }
+
+    #[test]
+    fn test_split_read_and_send_concurrently() {
+        let namespace = "test_split_concurrent";
+        let daemon = DaemonChannel::create(namespace, ChannelConfig::default()).unwrap();
+        let shell = ShellChannel::connect(namespace).unwrap();
+        let client_id = shell.client_id();
+        let (reader, sender) = shell.split();
+        assert_eq!(sender.client_id(), client_id);
+        const COMMANDS: u64 = 2000;
+
+        std::thread::scope(|scope| {
+            // Readers see every value in order, never a torn one
+            for reader in [reader.clone(), reader] {
+                scope.spawn(move || {
+                    let mut buf = [0u8; 16];
+                    let mut last = 0;
+                    while last < COMMANDS {
+                        if reader.read_data_with_len(&mut buf) == 16 {
+                            let value = u64::from_le_bytes(buf[..8].try_into().unwrap());
+                            assert_eq!(buf[8..], value.to_le_bytes());
+                            assert!(value >= last);
+                            last = value;
+                        }
+                    }
+                });
+            }
+            for sender in [sender.clone(), sender] {
+                scope.spawn(move || {
+                    for _ in 0..COMMANDS / 2 {
+                        sender.send_command_timeout(b"next", Duration::from_secs(5)).unwrap();
+                    }
+                });
+            }
+
+            // The daemon counts the commands and publishes the count twice over
+            let mut buf = [0u8; 64];
+            let mut received = 0u64;
+            while received < COMMANDS {
+                if let Some((id, _)) = daemon.try_recv_command(&mut buf) {
+                    assert_eq!(id, client_id);
+                    received += 1;
+                    let bytes = received.to_le_bytes();
+                    daemon.write_data_with_len(&[bytes, bytes].concat());
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn test_split_keeps_client_until_last_half() {
+        let namespace = "test_split_client";
+        let daemon = DaemonChannel::create(namespace, ChannelConfig::default()).unwrap();
+        let (reader, sender) = ShellChannel::connect(namespace).unwrap().split();
+        let client_id = sender.client_id();
+        assert_eq!(daemon.client_count(), 1);
+
+        // Responses reach the sender
+        daemon.respond(client_id, b"pong").unwrap();
+        let mut buf = [0u8; 8];
+        assert_eq!(sender.try_recv_response(&mut buf), Some(4));
+
+        drop(sender);
+        assert_eq!(daemon.client_count(), 1);
+        drop(reader);
+        assert_eq!(daemon.client_count(), 0);
+        assert!(matches!(daemon.respond(client_id, b"x"), Err(crate::VenomError::InvalidClientId(_))));
+    }
+}
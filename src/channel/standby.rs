@@ -0,0 +1,297 @@
+//! Hot standby: a second daemon that takes a channel over when its owner dies
+//!
+//! A [`StandbyChannel`] maps the channel of a running daemon and registers
+//! in the header's `standby_pid`. It watches the owner through two header
+//! fields: `owner_pid`, and `heartbeat_ns`, which the owner stamps on every
+//! write and [`heartbeat`](DaemonChannel::heartbeat). Once the owner's
+//! process is gone, or its heartbeat is older than the
+//! [timeout](StandbyChannel::heartbeat_timeout), the standby increments
+//! `owner_generation` with a compare-and-swap, records its own PID and
+//! carries on as the channel's [`DaemonChannel`]. Shells stay connected:
+//! they read the same data region and queue commands to the same queue.
+//!
+//! `owner_generation` is the fencing token. A daemon checks it against the
+//! generation it owns the channel under before every write, so a primary
+//! that was only stalled, not dead, finds it has lost the channel
+//! ([`owns_channel`](DaemonChannel::owns_channel)) and its writes are
+//! dropped. A primary dropped while a standby is registered hands the
+//! channel over by clearing `owner_pid`, instead of removing its name. The
+//! takeover is recorded in the channel's [event log](crate::event_log).
+
+use super::{process_alive, ChannelHeader, DaemonChannel, VENOM_MAGIC, VENOM_VERSION};
+use crate::error::{Result, VenomError};
+use crate::event_log::ChannelEvent;
+use crate::namespace;
+use crate::seqlock::now_ns;
+use crate::shm::VenomShm;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+/// How long a [`StandbyChannel`] waits, by default, after the owner's last
+/// write or heartbeat before taking the channel over
+pub const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A daemon waiting to take a channel over from the daemon that owns it,
+/// from [`DaemonChannel::connect_standby`]
+///
+/// A channel has at most one standby; dropping it before it takes over
+/// leaves the channel as it was.
+pub struct StandbyChannel {
+    shm: VenomShm,
+    header: *mut ChannelHeader,
+    pid: u32,
+    heartbeat_timeout: Duration,
+}
+
+// SAFETY: the header is only accessed with atomic operations
+unsafe impl Send for StandbyChannel {}
+
+impl DaemonChannel {
+    /// Map the channel of a running daemon as its standby, to take it over
+    /// with [`wait_for_takeover`](StandbyChannel::wait_for_takeover) once
+    /// that daemon dies
+    ///
+    /// Fails with [`VenomError::InvalidConfig`] if another live process is
+    /// already the channel's standby, or if the channel's daemon predates
+    /// standbys.
+    pub fn connect_standby(namespace: &str) -> Result<StandbyChannel> {
+        let name = namespace::resolve(namespace, false)?;
+        let shm = VenomShm::open(&name)?;
+        if shm.size() < std::mem::size_of::<ChannelHeader>() {
+            return Err(VenomError::CorruptRegion {
+                reason: format!("{} bytes can't hold a channel header", shm.size()),
+            });
+        }
+
+        let header = shm.as_ptr() as *mut ChannelHeader;
+        let pid = rustix::process::getpid().as_raw_nonzero().get() as u32;
+        unsafe {
+            let magic = std::ptr::read_volatile(&(*header).magic);
+            if magic != VENOM_MAGIC {
+                return Err(VenomError::InvalidMagic { expected: VENOM_MAGIC, got: magic });
+            }
+            std::sync::atomic::fence(Ordering::Acquire);
+            let version = (*header).version;
+            if version != VENOM_VERSION {
+                return Err(VenomError::UnsupportedVersion { expected: VENOM_VERSION, got: version });
+            }
+            // Daemons older than standbys don't heartbeat or check the
+            // fencing token; older still lack the client table
+            if (*header).heartbeat_ns.load(Ordering::Acquire) == 0 || (*header).responses_offset == 0 {
+                return Err(VenomError::InvalidConfig {
+                    reason: "the channel's daemon predates standbys".into(),
+                });
+            }
+
+            // Replace a standby that exited without unregistering
+            let standby = &(*header).standby_pid;
+            let mut current = standby.load(Ordering::Acquire);
+            while let Err(other) = standby.compare_exchange(current, pid, Ordering::AcqRel, Ordering::Acquire) {
+                if other != 0 && process_alive(other) {
+                    return Err(VenomError::InvalidConfig {
+                        reason: format!("the channel already has a standby, pid {}", other),
+                    });
+                }
+                current = other;
+            }
+            if current != 0 && process_alive(current) {
+                standby.store(current, Ordering::Release);
+                return Err(VenomError::InvalidConfig {
+                    reason: format!("the channel already has a standby, pid {}", current),
+                });
+            }
+        }
+
+        Ok(StandbyChannel { shm, header, pid, heartbeat_timeout: HEARTBEAT_TIMEOUT })
+    }
+}
+
+impl StandbyChannel {
+    /// Take the channel over once the owner's last write or heartbeat is
+    /// `timeout` old, rather than [`HEARTBEAT_TIMEOUT`]
+    pub fn heartbeat_timeout(mut self, timeout: Duration) -> Self {
+        self.heartbeat_timeout = timeout;
+        self
+    }
+
+    /// Whether the daemon that owns the channel is running and heartbeating
+    pub fn owner_alive(&self) -> bool {
+        let header = self.header();
+        let pid = header.owner_pid.load(Ordering::Acquire);
+        let heartbeat = header.heartbeat_ns.load(Ordering::Acquire);
+        let age = Duration::from_nanos(now_ns().saturating_sub(heartbeat));
+        pid != 0 && process_alive(pid) && age < self.heartbeat_timeout
+    }
+
+    /// PID of the daemon that owns the channel
+    pub fn owner_pid(&self) -> u32 {
+        self.header().owner_pid.load(Ordering::Acquire)
+    }
+
+    /// Check on the owner every `poll` until it dies, then take the channel
+    /// over and return it as this process's daemon
+    ///
+    /// Fails if the channel's regions no longer make sense; the channel is
+    /// this process's by then, and has no daemon writing it.
+    pub fn wait_for_takeover(self, poll: Duration) -> Result<DaemonChannel> {
+        loop {
+            if !self.owner_alive() {
+                if let Some(generation) = self.claim() {
+                    return self.into_daemon(generation);
+                }
+            }
+            std::thread::sleep(poll);
+        }
+    }
+
+    /// Move the ownership generation on, making this process the owner;
+    /// None if another process did first
+    fn claim(&self) -> Option<u32> {
+        let header = self.header();
+        let generation = header.owner_generation.load(Ordering::Acquire);
+        let next = generation.wrapping_add(1);
+        header
+            .owner_generation
+            .compare_exchange(generation, next, Ordering::AcqRel, Ordering::Relaxed)
+            .ok()?;
+        header.heartbeat_ns.store(now_ns(), Ordering::Relaxed);
+        header.owner_pid.store(self.pid, Ordering::Release);
+        Some(next)
+    }
+
+    fn into_daemon(self, generation: u32) -> Result<DaemonChannel> {
+        let header = self.header();
+        let _ = header.standby_pid.compare_exchange(self.pid, 0, Ordering::AcqRel, Ordering::Relaxed);
+        // Drop would unregister again
+        let this = std::mem::ManuallyDrop::new(self);
+        let shm = unsafe { std::ptr::read(&this.shm) };
+        let daemon = DaemonChannel::adopt(shm, generation)?;
+        daemon.log_event(ChannelEvent::TakenOver { pid: this.pid, owner_generation: generation });
+        Ok(daemon)
+    }
+
+    fn header(&self) -> &ChannelHeader {
+        unsafe { &*self.header }
+    }
+}
+
+impl Drop for StandbyChannel {
+    fn drop(&mut self) {
+        let _ = self.header().standby_pid.compare_exchange(self.pid, 0, Ordering::AcqRel, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ChannelConfig, ShellChannel};
+    use std::time::Instant;
+
+    const POLL: Duration = Duration::from_millis(1);
+
+    #[test]
+    fn test_standby_takes_over_dropped_primary() {
+        let namespace = "test_standby_handover";
+        let primary = DaemonChannel::create_force(namespace, ChannelConfig::default()).unwrap();
+        primary.write_data_with_len(&0u64.to_le_bytes());
+        let standby = DaemonChannel::connect_standby(namespace).unwrap();
+        let shell = ShellChannel::connect(namespace).unwrap();
+        assert!(standby.owner_alive());
+
+        std::thread::scope(|scope| {
+            scope.spawn(move || {
+                for i in 1..=50u64 {
+                    primary.write_data_with_len(&i.to_le_bytes());
+                    std::thread::sleep(POLL);
+                }
+                // Dropped with a standby registered: handed over
+            });
+            scope.spawn(move || {
+                let daemon = standby.wait_for_takeover(POLL).unwrap();
+                assert_eq!(daemon.owner_generation(), 1);
+                let pid = std::process::id();
+                assert_eq!(
+                    daemon.recent_events().last().map(|logged| logged.event),
+                    Some(ChannelEvent::TakenOver { pid, owner_generation: 1 })
+                );
+                for i in 51..=100u64 {
+                    daemon.write_data_with_len(&i.to_le_bytes());
+                    std::thread::sleep(POLL);
+                }
+                // The shell checks the name is still there before this drops
+                std::thread::sleep(Duration::from_millis(100));
+            });
+
+            // The data keeps moving on, from one daemon and then the other
+            let mut buf = [0u8; 8];
+            let mut last = 0;
+            let mut changed = Instant::now();
+            while last < 100 {
+                shell.read_data_with_len(&mut buf);
+                let value = u64::from_le_bytes(buf);
+                assert!(value >= last, "went back from {} to {}", last, value);
+                if value > last {
+                    last = value;
+                    changed = Instant::now();
+                }
+                assert!(changed.elapsed() < Duration::from_millis(500), "stuck at {}", last);
+            }
+            assert!(shell.daemon_alive());
+            assert!(ShellChannel::connect(namespace).is_ok());
+        });
+    }
+
+    #[test]
+    fn test_stalled_primary_is_fenced() {
+        let namespace = "test_standby_fencing";
+        let primary = DaemonChannel::create_force(namespace, ChannelConfig::default()).unwrap();
+        primary.write_data_with_len(b"primary");
+        let shell = ShellChannel::connect(namespace).unwrap();
+
+        // The primary's process is alive, but it stops heartbeating
+        let standby = DaemonChannel::connect_standby(namespace)
+            .unwrap()
+            .heartbeat_timeout(Duration::from_millis(20));
+        let start = Instant::now();
+        let daemon = standby.wait_for_takeover(POLL).unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(15));
+        assert!(daemon.owns_channel());
+        daemon.write_data_with_len(b"standby");
+
+        // The primary wakes up and finds it lost the channel
+        assert!(!primary.owns_channel());
+        assert!(!primary.heartbeat());
+        primary.write_data_with_len(b"zombie");
+        assert!(matches!(
+            primary.respond(shell.client_id(), b"x"),
+            Err(VenomError::OwnershipLost { generation: 1 })
+        ));
+        let mut buf = [0u8; 16];
+        let len = shell.read_data_with_len(&mut buf);
+        assert_eq!(&buf[..len], b"standby");
+
+        // Commands go to the new owner, not the stalled one
+        assert!(shell.try_send_command(b"cmd"));
+        assert_eq!(primary.try_recv_command(&mut buf), None);
+        assert_eq!(daemon.try_recv_command(&mut buf), Some((shell.client_id(), 3)));
+
+        // Dropping the stalled primary leaves the name to the new owner
+        drop(primary);
+        assert!(ShellChannel::connect(namespace).is_ok());
+        drop(daemon);
+        assert!(ShellChannel::connect(namespace).is_err());
+    }
+
+    #[test]
+    fn test_one_standby_per_channel() {
+        let namespace = "test_standby_single";
+        let _primary = DaemonChannel::create_force(namespace, ChannelConfig::default()).unwrap();
+        let standby = DaemonChannel::connect_standby(namespace).unwrap();
+        assert!(matches!(
+            DaemonChannel::connect_standby(namespace),
+            Err(VenomError::InvalidConfig { .. })
+        ));
+        drop(standby);
+        assert!(DaemonChannel::connect_standby(namespace).is_ok());
+    }
+}
@@ -0,0 +1,148 @@
+//! Serialized payload helpers (requires the `serde` feature)
+//!
+//! For protocols that don't map onto a fixed `#[repr(C)]` struct, values can
+//! be published as length-prefixed serialized messages. [`postcard`] is the
+//! compact wire format; JSON variants are provided for debugging.
+
+use crate::channel::{DaemonChannel, ShellChannel};
+use crate::error::{Result, VenomError};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+impl DaemonChannel {
+    /// Publish `value` encoded with postcard
+    ///
+    /// Fails with [`VenomError::BufferOverflow`] if the encoded value doesn't
+    /// fit in the data region.
+    pub fn write_serialized<T: Serialize>(&self, value: &T) -> Result<()> {
+        let bytes = postcard::to_allocvec(value).map_err(|e| VenomError::Codec(e.to_string()))?;
+        self.write_encoded(&bytes)
+    }
+
+    /// Publish `value` encoded as JSON
+    pub fn write_json<T: Serialize>(&self, value: &T) -> Result<()> {
+        let bytes = serde_json::to_vec(value).map_err(|e| VenomError::Codec(e.to_string()))?;
+        self.write_encoded(&bytes)
+    }
+
+    fn write_encoded(&self, bytes: &[u8]) -> Result<()> {
+        let max = self.data_capacity();
+        if bytes.len() > max {
+            return Err(VenomError::BufferOverflow {
+                max,
+                got: bytes.len(),
+            });
+        }
+
+        self.write_data_with_len(bytes);
+        Ok(())
+    }
+}
+
+impl ShellChannel {
+    /// Read the latest postcard-encoded value published by the daemon
+    ///
+    /// Fails with [`VenomError::InvalidLength`] if the length in shared
+    /// memory is corrupt, and [`VenomError::Codec`] if the bytes don't
+    /// decode.
+    pub fn read_deserialized<T: DeserializeOwned>(&self) -> Result<T> {
+        let bytes = self.read_encoded()?;
+        postcard::from_bytes(&bytes).map_err(|e| VenomError::Codec(e.to_string()))
+    }
+
+    /// Read the latest JSON-encoded value published by the daemon
+    pub fn read_json<T: DeserializeOwned>(&self) -> Result<T> {
+        let bytes = self.read_encoded()?;
+        serde_json::from_slice(&bytes).map_err(|e| VenomError::Codec(e.to_string()))
+    }
+
+    fn read_encoded(&self) -> Result<Vec<u8>> {
+        let mut buf = vec![0u8; self.data_capacity()];
+        let len = self.read_data_with_len_checked(&mut buf)?;
+
+        buf.truncate(len);
+        Ok(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::seqlock::LEN_PREFIX;
+    use crate::ChannelConfig;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Message {
+        Idle,
+        Reading { sensor: String, value: f32 },
+        Batch(Vec<u16>),
+    }
+
+    fn small_config() -> ChannelConfig {
+        ChannelConfig {
+            data_size: 64,
+            ..ChannelConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_postcard_and_json_round_trip() {
+        let daemon =
+            DaemonChannel::create("test_codec_round_trip", ChannelConfig::default()).unwrap();
+        let shell = ShellChannel::connect("test_codec_round_trip").unwrap();
+
+        let msg = Message::Reading {
+            sensor: "temp0".into(),
+            value: 21.5,
+        };
+        daemon.write_serialized(&msg).unwrap();
+        assert_eq!(shell.read_deserialized::<Message>().unwrap(), msg);
+
+        daemon.write_json(&Message::Batch(vec![1, 2, 3])).unwrap();
+        assert_eq!(
+            shell.read_json::<Message>().unwrap(),
+            Message::Batch(vec![1, 2, 3])
+        );
+    }
+
+    #[test]
+    fn test_encoded_value_exceeds_data_size() {
+        let daemon = DaemonChannel::create("test_codec_overflow", small_config()).unwrap();
+        let shell = ShellChannel::connect("test_codec_overflow").unwrap();
+
+        daemon.write_serialized(&Message::Idle).unwrap();
+
+        let big = Message::Batch(vec![0xFFFF; 64]);
+        match daemon.write_serialized(&big) {
+            Err(VenomError::BufferOverflow { max, got }) => {
+                assert_eq!(max, 64 - LEN_PREFIX);
+                assert!(got > max);
+            }
+            other => panic!("expected BufferOverflow, got {:?}", other),
+        }
+        assert!(matches!(
+            daemon.write_json(&big),
+            Err(VenomError::BufferOverflow { .. })
+        ));
+
+        // The previous value must still be intact
+        assert_eq!(shell.read_deserialized::<Message>().unwrap(), Message::Idle);
+    }
+
+    #[test]
+    fn test_decode_failure_is_codec_error() {
+        let daemon = DaemonChannel::create("test_codec_garbage", small_config()).unwrap();
+        let shell = ShellChannel::connect("test_codec_garbage").unwrap();
+
+        daemon.write_data_with_len(b"not json");
+        assert!(matches!(
+            shell.read_json::<Message>(),
+            Err(VenomError::Codec(_))
+        ));
+        assert!(matches!(
+            shell.read_deserialized::<Message>(),
+            Err(VenomError::Codec(_))
+        ));
+    }
+}
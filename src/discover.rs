@@ -0,0 +1,156 @@
+//! Discovery of VenomMemory channels on the system (requires the `discover` feature)
+//!
+//! Scans the POSIX shared memory directory for `venom_*` objects, reads their
+//! headers read-only and reports which ones still have a live daemon. Channels
+//! whose owner has died can be garbage-collected with [`gc`].
+
+use crate::channel::{process_alive, ChannelHeader, VENOM_MAGIC};
+use crate::error::{Result, VenomError};
+use crate::shm::{VenomShm, VENOM_SHM_PREFIX};
+use std::fs::{self, File};
+use std::io::Read;
+use std::sync::atomic::Ordering;
+
+/// Directory where Linux exposes POSIX shared memory objects
+const SHM_DIR: &str = "/dev/shm";
+
+/// Summary of a channel found in shared memory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelSummary {
+    /// Namespace as passed to `DaemonChannel::create` (without the `venom_` prefix)
+    pub namespace: String,
+    /// Total size of the shared memory object in bytes
+    pub size: u64,
+    /// Channel layout version
+    pub version: u32,
+    /// PID of the daemon that created the channel
+    pub owner_pid: u32,
+    /// Whether the owning daemon is still running
+    pub owner_alive: bool,
+    /// Number of shells currently connected
+    pub client_count: u32,
+}
+
+/// List every VenomMemory channel in shared memory
+///
+/// Objects with the `venom_` prefix that don't carry a valid channel header
+/// (e.g. raw `VenomShm` regions) are skipped.
+pub fn list_channels() -> Vec<ChannelSummary> {
+    let prefix = VENOM_SHM_PREFIX.trim_start_matches('/');
+    let Ok(entries) = fs::read_dir(SHM_DIR) else {
+        return Vec::new();
+    };
+
+    let mut channels: Vec<ChannelSummary> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let file_name = entry.file_name().into_string().ok()?;
+            let namespace = file_name.strip_prefix(prefix)?.to_string();
+            read_summary(&entry.path(), namespace)
+        })
+        .collect();
+
+    channels.sort_by(|a, b| a.namespace.cmp(&b.namespace));
+    channels
+}
+
+/// Unlink every channel whose owning daemon is no longer running
+///
+/// With `dry_run` set nothing is removed. Returns the namespaces that were
+/// (or would have been) removed.
+pub fn gc(dry_run: bool) -> Result<Vec<String>> {
+    let mut removed = Vec::new();
+
+    for channel in list_channels() {
+        if channel.owner_alive {
+            continue;
+        }
+        if !dry_run {
+            match VenomShm::unlink(&channel.namespace) {
+                Ok(()) => {}
+                // Someone else cleaned it up in the meantime
+                Err(VenomError::ShmOpen { source, .. })
+                    if source.kind() == std::io::ErrorKind::NotFound =>
+                {
+                    continue
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        removed.push(channel.namespace);
+    }
+
+    Ok(removed)
+}
+
+fn read_summary(path: &std::path::Path, namespace: String) -> Option<ChannelSummary> {
+    let mut file = File::open(path).ok()?;
+    let size = file.metadata().ok()?.len();
+
+    let mut bytes = [0u8; std::mem::size_of::<ChannelHeader>()];
+    file.read_exact(&mut bytes).ok()?;
+
+    // SAFETY: the buffer is exactly one header long and every field is plain data
+    let header: ChannelHeader = unsafe { std::ptr::read_unaligned(bytes.as_ptr().cast()) };
+    if header.magic != VENOM_MAGIC {
+        return None;
+    }
+
+    let owner_pid = header.owner_pid.load(Ordering::Relaxed);
+    Some(ChannelSummary {
+        namespace,
+        size,
+        version: header.version,
+        owner_pid,
+        owner_alive: process_alive(owner_pid),
+        client_count: header.client_count.load(Ordering::Relaxed),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ChannelConfig, DaemonChannel, ShellChannel};
+
+    fn find<'a>(channels: &'a [ChannelSummary], namespace: &str) -> Option<&'a ChannelSummary> {
+        channels.iter().find(|c| c.namespace == namespace)
+    }
+
+    #[test]
+    fn test_list_and_gc() {
+        let live_name = "test_discover_live";
+        let dead_name = "test_discover_dead";
+
+        let _live = DaemonChannel::create(live_name, ChannelConfig::default()).unwrap();
+        let _shell = ShellChannel::connect(live_name).unwrap();
+
+        // Simulate a crashed daemon: forge a dead owner and skip the unlink on drop
+        let dead = DaemonChannel::create(dead_name, ChannelConfig::default()).unwrap();
+        unsafe {
+            let header = dead.as_ptr() as *mut ChannelHeader;
+            (*header).owner_pid.store(i32::MAX as u32, Ordering::Release);
+        }
+        std::mem::forget(dead);
+
+        let channels = list_channels();
+        let live_summary = find(&channels, live_name).expect("live channel listed");
+        assert!(live_summary.owner_alive);
+        assert_eq!(live_summary.owner_pid, std::process::id());
+        assert_eq!(live_summary.client_count, 1);
+
+        let dead_summary = find(&channels, dead_name).expect("dead channel listed");
+        assert!(!dead_summary.owner_alive);
+
+        // A dry run only: a real one would also take the stale channels of
+        // tests running alongside, and any left on the machine
+        let would_remove = gc(true).unwrap();
+        assert!(would_remove.iter().any(|n| n == dead_name));
+        assert!(!would_remove.iter().any(|n| n == live_name));
+        assert!(find(&list_channels(), dead_name).is_some());
+
+        VenomShm::unlink(dead_name).unwrap();
+        let channels = list_channels();
+        assert!(find(&channels, dead_name).is_none());
+        assert!(find(&channels, live_name).is_some());
+    }
+}
@@ -45,6 +45,14 @@ pub enum VenomError {
     #[error("Command queue is full")]
     QueueFull,
 
+    /// A read-only shell was asked to send a command
+    #[error("Shell is read-only: it can't send commands")]
+    ReadOnly,
+
+    /// What was waited for didn't happen in time
+    #[error("Timed out after {0:?}")]
+    Timeout(std::time::Duration),
+
     /// Command queue is empty
     #[error("Command queue is empty")]
     QueueEmpty,
@@ -56,4 +64,74 @@ pub enum VenomError {
     /// Namespace too long
     #[error("Namespace too long: max {max} chars, got {got}")]
     NamespaceTooLong { max: usize, got: usize },
+
+    /// Namespace is not a valid channel name
+    #[error("Invalid namespace: {reason}")]
+    InvalidNamespace { reason: String },
+
+    /// Name a shell asked to register with isn't usable
+    #[error("Invalid client name: {reason}")]
+    InvalidClientName { reason: String },
+
+    /// Every client slot of the channel is taken
+    #[error("Channel is full: {max} clients connected")]
+    TooManyClients { max: usize },
+
+    /// A channel can't be created with this configuration
+    #[error("Invalid channel config: {reason}")]
+    InvalidConfig { reason: String },
+
+    /// Channel laid out by a different version of the library
+    #[error("Unsupported channel layout version: expected {expected}, got {got}")]
+    UnsupportedVersion { expected: u32, got: u32 },
+
+    /// Namespace already holds a channel, of a running daemon or one that
+    /// exited without removing it; `pid` is the owner its header records
+    #[error(
+        "Namespace '{name}' is already in use{}",
+        .pid.map(|pid| format!(" by pid {}", pid)).unwrap_or_default()
+    )]
+    NamespaceInUse { name: String, pid: Option<u32> },
+
+    /// A command slot claims more bytes than a slot holds
+    #[error("Command of {len} bytes from client {client_id} exceeds the {max}-byte slot")]
+    CommandTooLarge { client_id: u32, len: usize, max: usize },
+
+    /// A length prefix claims more bytes than the data region holds
+    #[error("Length prefix of {len} bytes exceeds the {max}-byte data region")]
+    InvalidLength { len: u64, max: usize },
+
+    /// A length prefix stayed larger than the data region over every retry,
+    /// so it isn't a write in flight
+    #[error("Length prefix of {len} bytes exceeds the {max}-byte data region after {attempts} reads")]
+    Corrupted { len: u64, max: usize, attempts: u32 },
+
+    /// The shared memory object's permissions don't allow what was asked
+    #[error("Permission denied on {path}: {reason}")]
+    PermissionDenied { path: String, reason: String },
+
+    /// Pinning or naming a thread failed
+    #[error("Failed to {action}: {source}")]
+    Thread {
+        action: String,
+        #[source]
+        source: io::Error,
+    },
+
+    /// Not available on this platform
+    #[error("{feature} is not supported on this platform")]
+    Unsupported { feature: String },
+
+    /// A standby daemon took the channel over; `generation` is the
+    /// ownership generation it took it under
+    #[error("Channel was taken over by a standby daemon (ownership generation {generation})")]
+    OwnershipLost { generation: u32 },
+
+    /// Shared memory doesn't hold what its header says
+    #[error("Corrupt shared memory region: {reason}")]
+    CorruptRegion { reason: String },
+
+    /// Failed to encode or decode a serialized payload
+    #[error("Codec error: {0}")]
+    Codec(String),
 }
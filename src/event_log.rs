@@ -0,0 +1,349 @@
+//! A black-box recorder of what happened to a channel
+//!
+//! The last few notable events of a channel — the daemon creating it,
+//! shells connecting and disconnecting, sends finding the queue full,
+//! commands dropped as corrupt, the data region growing, a standby taking
+//! over — are kept in a ring of [`ChannelConfig::event_log_slots`](crate::ChannelConfig::event_log_slots)
+//! slots inside the channel, written by whichever side saw them.
+//! [`DaemonChannel::recent_events`](crate::DaemonChannel::recent_events)
+//! and [`ShellChannel::recent_events`](crate::ShellChannel::recent_events)
+//! read it back from any handle, read-only shells included, which is how
+//! `venom-top` and `venom_ls` show it.
+//!
+//! The log is for diagnostics: the oldest event is overwritten once the
+//! ring is full, and an event whose slot another writer holds at that
+//! moment is dropped rather than waited for. Channels of daemons older than
+//! the log have none, and read as having no events.
+//!
+//! # Layout
+//!
+//! A [`layout::event_log`](crate::layout::event_log) header, then
+//! `num_slots` [`layout::event`](crate::layout::event) slots. A writer
+//! takes index `i` with a `fetch_add` on `next` and moves the `sequence`
+//! of slot `i % num_slots` from an even value below `2i + 1` to `2i + 1`
+//! with a compare-and-swap, fills in the slot and stores `2i + 2`. A
+//! reader keeps the copy of slot `i` if its sequence read `2i + 2` both
+//! before and after it.
+
+use crate::error::{Result, VenomError};
+use crate::layout::to_usize;
+use std::fmt;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Slots of the event log, by default
+pub const DEFAULT_EVENT_LOG_SLOTS: usize = 64;
+
+/// Most slots an event log can have
+pub const MAX_EVENT_LOG_SLOTS: usize = 4096;
+
+/// Something that happened to a channel, as kept in its event log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelEvent {
+    /// The daemon in process `pid` created the channel
+    DaemonCreated { pid: u32 },
+    /// A shell in process `pid` connected and was given `client_id`
+    ClientConnected { client_id: u32, pid: u32 },
+    /// The shell with `client_id` disconnected
+    ClientDisconnected { client_id: u32 },
+    /// A send of the shell with `client_id` found the command queue full,
+    /// the first since one of its sends last got through
+    QueueFull { client_id: u32 },
+    /// The daemon dropped a command from `client_id` as corrupt: longer
+    /// than a slot holds, or from an ID no shell was given
+    CorruptCommand { client_id: u32 },
+    /// The daemon grew the data region to `data_size` bytes
+    Resized { data_size: u64 },
+    /// The standby in process `pid` took the channel over, the daemon from
+    /// then on owning it under `owner_generation`
+    TakenOver { pid: u32, owner_generation: u32 },
+    /// An event this version doesn't know, logged by a newer one
+    Unknown { code: u32, arg: u32, value: u64 },
+}
+
+impl ChannelEvent {
+    /// The `code`, `arg` and `value` of the event's slot
+    fn encode(self) -> (u32, u32, u64) {
+        match self {
+            ChannelEvent::DaemonCreated { pid } => (1, pid, 0),
+            ChannelEvent::ClientConnected { client_id, pid } => (2, client_id, pid as u64),
+            ChannelEvent::ClientDisconnected { client_id } => (3, client_id, 0),
+            ChannelEvent::QueueFull { client_id } => (4, client_id, 0),
+            ChannelEvent::CorruptCommand { client_id } => (5, client_id, 0),
+            ChannelEvent::Resized { data_size } => (6, 0, data_size),
+            ChannelEvent::TakenOver { pid, owner_generation } => (7, pid, owner_generation as u64),
+            ChannelEvent::Unknown { code, arg, value } => (code, arg, value),
+        }
+    }
+
+    fn decode(code: u32, arg: u32, value: u64) -> Self {
+        match code {
+            1 => ChannelEvent::DaemonCreated { pid: arg },
+            2 => ChannelEvent::ClientConnected { client_id: arg, pid: value as u32 },
+            3 => ChannelEvent::ClientDisconnected { client_id: arg },
+            4 => ChannelEvent::QueueFull { client_id: arg },
+            5 => ChannelEvent::CorruptCommand { client_id: arg },
+            6 => ChannelEvent::Resized { data_size: value },
+            7 => ChannelEvent::TakenOver { pid: arg, owner_generation: value as u32 },
+            _ => ChannelEvent::Unknown { code, arg, value },
+        }
+    }
+}
+
+impl fmt::Display for ChannelEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChannelEvent::DaemonCreated { pid } => write!(f, "daemon created the channel (pid {})", pid),
+            ChannelEvent::ClientConnected { client_id, pid } => {
+                write!(f, "client {} connected (pid {})", client_id, pid)
+            }
+            ChannelEvent::ClientDisconnected { client_id } => write!(f, "client {} disconnected", client_id),
+            ChannelEvent::QueueFull { client_id } => write!(f, "client {} found the command queue full", client_id),
+            ChannelEvent::CorruptCommand { client_id } => write!(f, "corrupt command from client {} dropped", client_id),
+            ChannelEvent::Resized { data_size } => write!(f, "data region grown to {} bytes", data_size),
+            ChannelEvent::TakenOver { pid, owner_generation } => {
+                write!(f, "standby took over (pid {}, generation {})", pid, owner_generation)
+            }
+            ChannelEvent::Unknown { code, arg, value } => write!(f, "event {} ({}, {})", code, arg, value),
+        }
+    }
+}
+
+/// An event read back from the log, with when it was logged
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoggedEvent {
+    /// Wall clock time the event was logged
+    pub time: SystemTime,
+    pub event: ChannelEvent,
+}
+
+/// Header of the event log; the slots follow it
+#[repr(C)]
+pub(crate) struct EventLogHeader {
+    /// Index the next event takes
+    pub(crate) next: AtomicU64,
+    pub(crate) num_slots: u64,
+    _pad: [u8; 48],
+}
+
+/// One event of the log
+#[repr(C)]
+pub(crate) struct EventSlot {
+    /// `2i + 1` while event `i` is written into the slot, `2i + 2` once it is
+    pub(crate) sequence: AtomicU64,
+    /// Wall clock time of the event, in nanoseconds since the Unix epoch
+    pub(crate) time_ns: AtomicU64,
+    pub(crate) code: AtomicU32,
+    pub(crate) arg: AtomicU32,
+    pub(crate) value: AtomicU64,
+}
+
+/// Handle on a channel's event log, for writing and reading it
+#[derive(Clone, Copy)]
+pub(crate) struct EventLog {
+    header: *const EventLogHeader,
+    /// Read once, when the handle was made
+    num_slots: usize,
+}
+
+// SAFETY: the log is only accessed with atomic operations
+unsafe impl Send for EventLog {}
+unsafe impl Sync for EventLog {}
+
+impl EventLog {
+    /// Bytes a log of `num_slots` slots takes; 0 for none
+    pub(crate) const fn size_for_slots(num_slots: usize) -> usize {
+        if num_slots == 0 {
+            return 0;
+        }
+        std::mem::size_of::<EventLogHeader>() + num_slots * std::mem::size_of::<EventSlot>()
+    }
+
+    /// Check a configured slot count
+    pub(crate) fn check_slots(num_slots: usize) -> Result<()> {
+        if num_slots > MAX_EVENT_LOG_SLOTS {
+            return Err(VenomError::InvalidConfig {
+                reason: format!("{} event log slots, at most {}", num_slots, MAX_EVENT_LOG_SLOTS),
+            });
+        }
+        Ok(())
+    }
+
+    /// Set up an empty log of `num_slots` slots at `ptr`
+    ///
+    /// # Safety
+    /// `ptr` must point to [`size_for_slots`](Self::size_for_slots) zeroed
+    /// bytes, aligned to 64, that outlive the handle.
+    pub(crate) unsafe fn init(ptr: *mut EventLogHeader, num_slots: usize) -> Self {
+        (*ptr).next = AtomicU64::new(0);
+        (*ptr).num_slots = num_slots as u64;
+        Self { header: ptr, num_slots }
+    }
+
+    /// The log at `region`, written by anyone, checking its slots fit in
+    /// the `len` bytes from there
+    ///
+    /// # Safety
+    /// `region` must be valid for reads and writes of `len` bytes, aligned
+    /// to 64, and outlive the handle.
+    pub(crate) unsafe fn from_region(region: *const u8, len: usize) -> Result<Self> {
+        let header = region as *const EventLogHeader;
+        let num_slots = if len < std::mem::size_of::<EventLogHeader>() { 0 } else { to_usize((*header).num_slots) };
+        if num_slots == 0 || num_slots > MAX_EVENT_LOG_SLOTS || Self::size_for_slots(num_slots) > len {
+            return Err(VenomError::CorruptRegion {
+                reason: format!("event log of {} slots in {} bytes", num_slots, len),
+            });
+        }
+        Ok(Self { header, num_slots })
+    }
+
+    fn slot(&self, index: u64) -> &EventSlot {
+        let i = (index % self.num_slots as u64) as usize;
+        // SAFETY: the slots were checked to lie in the mapping
+        unsafe { &*(self.header.add(1) as *const EventSlot).add(i) }
+    }
+
+    /// Log `event`, overwriting the oldest one if the log is full
+    ///
+    /// Dropped if a writer of an event a lap before or after this one is
+    /// still in its slot.
+    pub(crate) fn log(&self, event: ChannelEvent) {
+        let (code, arg, value) = event.encode();
+        let index = unsafe { (*self.header).next.fetch_add(1, Ordering::Relaxed) };
+        let slot = self.slot(index);
+        let writing = index.wrapping_mul(2).wrapping_add(1);
+        let current = slot.sequence.load(Ordering::Relaxed);
+        if current % 2 == 1
+            || current > writing
+            || slot.sequence.compare_exchange(current, writing, Ordering::AcqRel, Ordering::Relaxed).is_err()
+        {
+            return;
+        }
+        let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        slot.time_ns.store(time.as_nanos() as u64, Ordering::Relaxed);
+        slot.code.store(code, Ordering::Relaxed);
+        slot.arg.store(arg, Ordering::Relaxed);
+        slot.value.store(value, Ordering::Relaxed);
+        slot.sequence.store(writing + 1, Ordering::Release);
+    }
+
+    /// The events still in the log, oldest first; those being written are
+    /// left out
+    pub(crate) fn recent(&self) -> Vec<LoggedEvent> {
+        let next = unsafe { (*self.header).next.load(Ordering::Acquire) };
+        let first = next.saturating_sub(self.num_slots as u64);
+        (first..next).filter_map(|index| self.read(index)).collect()
+    }
+
+    /// Event `index`, if its slot still holds it, written in full
+    fn read(&self, index: u64) -> Option<LoggedEvent> {
+        let slot = self.slot(index);
+        let written = index.wrapping_mul(2).wrapping_add(2);
+        if slot.sequence.load(Ordering::Acquire) != written {
+            return None;
+        }
+        let time = slot.time_ns.load(Ordering::Relaxed);
+        let code = slot.code.load(Ordering::Relaxed);
+        let arg = slot.arg.load(Ordering::Relaxed);
+        let value = slot.value.load(Ordering::Relaxed);
+        std::sync::atomic::fence(Ordering::Acquire);
+        if slot.sequence.load(Ordering::Relaxed) != written {
+            return None;
+        }
+        Some(LoggedEvent { time: UNIX_EPOCH + Duration::from_nanos(time), event: ChannelEvent::decode(code, arg, value) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A zeroed, cache-line aligned region for a log of `num_slots`
+    fn region(num_slots: usize) -> Vec<[u64; 8]> {
+        vec![[0u64; 8]; EventLog::size_for_slots(num_slots).div_ceil(64)]
+    }
+
+    #[test]
+    fn test_events_round_trip() {
+        let events = [
+            ChannelEvent::DaemonCreated { pid: 7 },
+            ChannelEvent::ClientConnected { client_id: 1, pid: 8 },
+            ChannelEvent::ClientDisconnected { client_id: 1 },
+            ChannelEvent::QueueFull { client_id: 2 },
+            ChannelEvent::CorruptCommand { client_id: 3 },
+            ChannelEvent::Resized { data_size: 1 << 40 },
+            ChannelEvent::TakenOver { pid: 9, owner_generation: 2 },
+            ChannelEvent::Unknown { code: 99, arg: 1, value: 2 },
+        ];
+        for event in events {
+            let (code, arg, value) = event.encode();
+            assert_eq!(ChannelEvent::decode(code, arg, value), event);
+        }
+    }
+
+    #[test]
+    fn test_oldest_overwritten() {
+        let mut region = region(4);
+        let log = unsafe { EventLog::init(region.as_mut_ptr().cast(), 4) };
+        assert!(log.recent().is_empty());
+
+        for client_id in 1..=6 {
+            log.log(ChannelEvent::ClientDisconnected { client_id });
+        }
+        let ids: Vec<_> = log
+            .recent()
+            .into_iter()
+            .map(|logged| match logged.event {
+                ChannelEvent::ClientDisconnected { client_id } => client_id,
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        assert_eq!(ids, [3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_slot_held_drops_event() {
+        let mut region = region(2);
+        let log = unsafe { EventLog::init(region.as_mut_ptr().cast(), 2) };
+        // A writer of event 0 stalled in its slot
+        log.slot(0).sequence.store(1, Ordering::Relaxed);
+        unsafe { (*log.header).next.store(1, Ordering::Relaxed) };
+
+        log.log(ChannelEvent::QueueFull { client_id: 1 });
+        log.log(ChannelEvent::QueueFull { client_id: 2 });
+        assert_eq!(log.recent().iter().map(|logged| logged.event).collect::<Vec<_>>(), [ChannelEvent::QueueFull { client_id: 1 }]);
+    }
+
+    #[test]
+    fn test_region_checked() {
+        let mut region = region(4);
+        unsafe {
+            EventLog::init(region.as_mut_ptr().cast(), 4);
+            let len = EventLog::size_for_slots(4);
+            assert!(EventLog::from_region(region.as_ptr().cast(), len).is_ok());
+            assert!(matches!(
+                EventLog::from_region(region.as_ptr().cast(), len - 1),
+                Err(VenomError::CorruptRegion { .. })
+            ));
+            assert!(EventLog::from_region(region.as_ptr().cast(), 0).is_err());
+        }
+    }
+
+    #[test]
+    fn test_concurrent_writers() {
+        let mut region = region(64);
+        let log = unsafe { EventLog::init(region.as_mut_ptr().cast(), 64) };
+        std::thread::scope(|s| {
+            for thread in 0..4 {
+                s.spawn(move || {
+                    for i in 0..1000 {
+                        log.log(ChannelEvent::Unknown { code: 100, arg: thread, value: i });
+                    }
+                });
+            }
+        });
+        let recent = log.recent();
+        assert!(!recent.is_empty() && recent.len() <= 64);
+        assert!(recent.iter().all(|logged| matches!(logged.event, ChannelEvent::Unknown { code: 100, arg: 0..4, value: 0..1000 })));
+    }
+}
@@ -0,0 +1,287 @@
+//! Commands longer than a command slot, sent as chunks
+//!
+//! [`ShellChannel::send_large_command`](crate::ShellChannel::send_large_command)
+//! splits a message into commands of the channel's `cmd_payload_size`, each
+//! starting with an envelope, and
+//! [`DaemonChannel::try_recv_large_command`](crate::DaemonChannel::try_recv_large_command)
+//! puts the message back together. Chunks of messages from several shells,
+//! or several threads of one, may interleave.
+//!
+//! # Envelope
+//!
+//! [`ENVELOPE_SIZE`] bytes, every field a little-endian `u32`:
+//!
+//! | Offset | Field | |
+//! |--------|-------|-|
+//! | 0 | [`CHUNK_MAGIC`] | `"CHNK"` |
+//! | 4 | `message_id` | Picked by the shell; unique among its messages in flight |
+//! | 8 | `chunk_index` | 0 to `total_chunks - 1`, sent in order |
+//! | 12 | `total_chunks` | At least 1 |
+//!
+//! The payload follows. Every chunk but the last fills its command; a
+//! message is at most [`MAX_LARGE_COMMAND`] bytes, and an empty one is a
+//! single chunk with no payload. The daemon keeps up to
+//! [`MAX_PARTIAL_COMMANDS`] messages in progress; a message that gets no
+//! chunk for [`ChannelConfig::large_command_timeout`](crate::ChannelConfig::large_command_timeout),
+//! or whose chunks arrive out of order (one was dropped), is discarded.
+//! Commands that don't start with the magic are delivered as they are.
+
+use crate::error::{Result, VenomError};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// First field of every envelope, `"CHNK"` in little-endian byte order
+pub const CHUNK_MAGIC: u32 = u32::from_le_bytes(*b"CHNK");
+/// Bytes of envelope before each chunk's payload
+pub const ENVELOPE_SIZE: usize = 16;
+/// Longest message
+pub const MAX_LARGE_COMMAND: usize = 1 << 20;
+/// Messages the daemon keeps in progress at once; a new one past them
+/// discards the one that got a chunk least recently
+pub const MAX_PARTIAL_COMMANDS: usize = 64;
+/// Default of [`ChannelConfig::large_command_timeout`](crate::ChannelConfig::large_command_timeout)
+pub const LARGE_COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Envelope of one chunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Envelope {
+    pub(crate) message_id: u32,
+    pub(crate) chunk_index: u32,
+    pub(crate) total_chunks: u32,
+}
+
+impl Envelope {
+    /// Split a command into its envelope and payload; None if it has none
+    pub(crate) fn parse(cmd: &[u8]) -> Option<(Envelope, &[u8])> {
+        if cmd.len() < ENVELOPE_SIZE {
+            return None;
+        }
+        let field = |i: usize| u32::from_le_bytes(cmd[i * 4..i * 4 + 4].try_into().unwrap());
+        if field(0) != CHUNK_MAGIC {
+            return None;
+        }
+        let envelope = Envelope { message_id: field(1), chunk_index: field(2), total_chunks: field(3) };
+        Some((envelope, &cmd[ENVELOPE_SIZE..]))
+    }
+
+    /// Write the envelope and `payload` into `cmd`, replacing what it held
+    pub(crate) fn encode(&self, payload: &[u8], cmd: &mut Vec<u8>) {
+        cmd.clear();
+        for field in [CHUNK_MAGIC, self.message_id, self.chunk_index, self.total_chunks] {
+            cmd.extend_from_slice(&field.to_le_bytes());
+        }
+        cmd.extend_from_slice(payload);
+    }
+}
+
+/// Split `message` into chunks for commands of `cmd_payload_size` bytes
+/// and pass each to `send`, envelope included, in order
+pub(crate) fn send_chunks(
+    message_id: u32,
+    message: &[u8],
+    cmd_payload_size: usize,
+    mut send: impl FnMut(&[u8]),
+) -> Result<()> {
+    if cmd_payload_size <= ENVELOPE_SIZE {
+        return Err(VenomError::InvalidConfig {
+            reason: format!(
+                "commands of {} bytes leave no room for a chunk after the {}-byte envelope",
+                cmd_payload_size, ENVELOPE_SIZE
+            ),
+        });
+    }
+    if message.len() > MAX_LARGE_COMMAND {
+        return Err(VenomError::BufferOverflow { max: MAX_LARGE_COMMAND, got: message.len() });
+    }
+    let chunk_size = cmd_payload_size - ENVELOPE_SIZE;
+    let total_chunks = message.len().div_ceil(chunk_size).max(1) as u32;
+    let mut cmd = Vec::with_capacity(cmd_payload_size);
+    for chunk_index in 0..total_chunks {
+        let start = chunk_index as usize * chunk_size;
+        let payload = &message[start..message.len().min(start + chunk_size)];
+        Envelope { message_id, chunk_index, total_chunks }.encode(payload, &mut cmd);
+        send(&cmd);
+    }
+    Ok(())
+}
+
+/// A message some of whose chunks have arrived
+struct Partial {
+    data: Vec<u8>,
+    next_chunk: u32,
+    total_chunks: u32,
+    last_chunk_at: Instant,
+}
+
+/// The daemon's messages in progress, by client ID and message ID
+pub(crate) struct Reassembly {
+    partials: HashMap<(u32, u32), Partial>,
+    /// Most chunks a message of at most [`MAX_LARGE_COMMAND`] bytes takes
+    max_chunks: u32,
+    timeout: Duration,
+    /// Messages discarded unfinished, and malformed envelopes
+    discarded: u64,
+}
+
+impl Reassembly {
+    pub(crate) fn new(cmd_payload_size: usize, timeout: Duration) -> Self {
+        let chunk_size = cmd_payload_size.saturating_sub(ENVELOPE_SIZE).max(1);
+        Reassembly {
+            partials: HashMap::new(),
+            max_chunks: MAX_LARGE_COMMAND.div_ceil(chunk_size).min(u32::MAX as usize) as u32,
+            timeout,
+            discarded: 0,
+        }
+    }
+
+    pub(crate) fn discarded(&self) -> u64 {
+        self.discarded
+    }
+
+    /// Discard messages that got no chunk for the timeout
+    pub(crate) fn expire(&mut self, now: Instant) {
+        let before = self.partials.len();
+        let timeout = self.timeout;
+        self.partials.retain(|_, partial| now.duration_since(partial.last_chunk_at) < timeout);
+        self.discarded += (before - self.partials.len()) as u64;
+    }
+
+    /// Take in a chunk from `client_id`; returns the message it completes
+    pub(crate) fn feed(&mut self, client_id: u32, envelope: Envelope, payload: &[u8], now: Instant) -> Option<Vec<u8>> {
+        let key = (client_id, envelope.message_id);
+        let Envelope { chunk_index, total_chunks, .. } = envelope;
+        if total_chunks == 0 || chunk_index >= total_chunks || total_chunks > self.max_chunks {
+            self.discard(key);
+            self.discarded += 1;
+            return None;
+        }
+
+        if chunk_index == 0 {
+            // A message ID reused while its last message is unfinished
+            self.discard(key);
+            if total_chunks == 1 {
+                return Some(payload.to_vec());
+            }
+            if self.partials.len() >= MAX_PARTIAL_COMMANDS {
+                let oldest = self.partials.iter().min_by_key(|(_, partial)| partial.last_chunk_at).map(|(key, _)| *key);
+                if let Some(oldest) = oldest {
+                    self.discard(oldest);
+                }
+            }
+            let partial = Partial { data: payload.to_vec(), next_chunk: 1, total_chunks, last_chunk_at: now };
+            self.partials.insert(key, partial);
+            return None;
+        }
+
+        // The start of the message was dropped, or it was discarded already
+        let partial = self.partials.get_mut(&key)?;
+        if chunk_index != partial.next_chunk || total_chunks != partial.total_chunks {
+            self.discard(key);
+            return None;
+        }
+        partial.data.extend_from_slice(payload);
+        partial.next_chunk += 1;
+        partial.last_chunk_at = now;
+        if partial.next_chunk < total_chunks {
+            return None;
+        }
+        self.partials.remove(&key).map(|partial| partial.data)
+    }
+
+    fn discard(&mut self, key: (u32, u32)) {
+        if self.partials.remove(&key).is_some() {
+            self.discarded += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunks(message_id: u32, message: &[u8], cmd_payload_size: usize) -> Vec<Vec<u8>> {
+        let mut chunks = Vec::new();
+        send_chunks(message_id, message, cmd_payload_size, |cmd| chunks.push(cmd.to_vec())).unwrap();
+        chunks
+    }
+
+    fn feed(reassembly: &mut Reassembly, client_id: u32, cmd: &[u8], now: Instant) -> Option<Vec<u8>> {
+        let (envelope, payload) = Envelope::parse(cmd).unwrap();
+        reassembly.feed(client_id, envelope, payload, now)
+    }
+
+    #[test]
+    fn test_envelope_layout() {
+        let split = chunks(7, &[1, 2, 3, 4, 5], 20);
+        assert_eq!(split.len(), 2);
+        assert_eq!(&split[1][..4], b"CHNK");
+        assert_eq!(split[1], [b"CHNK".as_slice(), &7u32.to_le_bytes(), &1u32.to_le_bytes(), &2u32.to_le_bytes(), &[5]].concat());
+        assert_eq!(Envelope::parse(b"CHNK too short"), None);
+        assert_eq!(Envelope::parse(b"not a chunk at all"), None);
+
+        // An empty message still takes a chunk
+        assert_eq!(chunks(1, &[], 20), [[b"CHNK".as_slice(), &1u32.to_le_bytes(), &0u32.to_le_bytes(), &1u32.to_le_bytes()].concat()]);
+        assert!(matches!(send_chunks(1, &[0; 4], 16, |_| {}), Err(VenomError::InvalidConfig { .. })));
+        assert!(matches!(send_chunks(1, &vec![0; MAX_LARGE_COMMAND + 1], 4096, |_| {}), Err(VenomError::BufferOverflow { .. })));
+    }
+
+    #[test]
+    fn test_reassembly_interleaved() {
+        let now = Instant::now();
+        let mut reassembly = Reassembly::new(64, LARGE_COMMAND_TIMEOUT);
+        let a: Vec<u8> = (0..200).map(|i| i as u8).collect();
+        let b: Vec<u8> = (0..150).map(|i| (i * 3) as u8).collect();
+        // Same message ID from two clients, and two messages of one client
+        let (a, b, c) = (chunks(1, &a, 64), chunks(1, &b, 64), chunks(2, &a, 64));
+        let mut done = Vec::new();
+        for i in 0..a.len() {
+            for (client_id, chunks) in [(1, &a), (2, &b), (1, &c)] {
+                if let Some(message) = chunks.get(i).and_then(|cmd| feed(&mut reassembly, client_id, cmd, now)) {
+                    done.push((client_id, message.len()));
+                }
+            }
+        }
+        assert_eq!(done, [(2, 150), (1, 200), (1, 200)]);
+        assert_eq!(reassembly.discarded(), 0);
+    }
+
+    #[test]
+    fn test_reassembly_discards() {
+        let start = Instant::now();
+        let mut reassembly = Reassembly::new(64, Duration::from_secs(1));
+        let message = chunks(1, &[9; 100], 64);
+
+        // Out of order: the middle chunk was dropped
+        feed(&mut reassembly, 1, &message[0], start);
+        assert_eq!(feed(&mut reassembly, 1, &message[2], start), None);
+        assert_eq!(reassembly.discarded(), 1);
+
+        // No chunk for the timeout
+        feed(&mut reassembly, 1, &message[0], start);
+        reassembly.expire(start + Duration::from_millis(999));
+        assert_eq!(reassembly.discarded(), 1);
+        reassembly.expire(start + Duration::from_secs(1));
+        assert_eq!(reassembly.discarded(), 2);
+        assert_eq!(feed(&mut reassembly, 1, &message[1], start), None);
+
+        // Past the bound, the least recently fed message goes
+        for client_id in 0..=MAX_PARTIAL_COMMANDS as u32 {
+            feed(&mut reassembly, client_id, &message[0], start + Duration::from_millis(client_id as u64));
+        }
+        assert_eq!(reassembly.discarded(), 3);
+        assert_eq!(feed(&mut reassembly, 0, &message[1], start), None);
+        feed(&mut reassembly, 1, &message[1], start);
+        assert_eq!(feed(&mut reassembly, 1, &message[2], start).map(|m| m.len()), Some(100));
+
+        // Malformed envelopes
+        let bad = |chunk_index: u32, total_chunks: u32| {
+            let mut cmd = Vec::new();
+            Envelope { message_id: 5, chunk_index, total_chunks }.encode(&[], &mut cmd);
+            cmd
+        };
+        for cmd in [bad(0, 0), bad(3, 3), bad(0, u32::MAX)] {
+            assert_eq!(feed(&mut reassembly, 1, &cmd, start), None);
+        }
+        assert_eq!(reassembly.discarded(), 6);
+    }
+}
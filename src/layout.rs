@@ -0,0 +1,510 @@
+//! Shared memory layout of a channel
+//!
+//! A channel is one shared memory object, `/dev/shm/venom_<name>`, laid out
+//! as a [`ChannelHeader`](crate::channel) at offset 0, the SeqLock-protected
+//! data region at `seqlock_offset`, the command queue at `cmd_queue_offset`,
+//! the client table at `clients_offset`, the response slots at
+//! `responses_offset` and the event log at `events_offset`. The offsets below are part of the wire format: they
+//! only change together with [`VERSION`], so a client that maps the object
+//! itself instead of linking this library (the pure-Go client of
+//! `venom init --go-nocgo`) keeps working across releases of the same version.
+//!
+//! The daemon may move the data region to a larger one past the end of the
+//! object ([`DaemonChannel::resize_data`](crate::DaemonChannel::resize_data)):
+//! it fills in the new region, points `seqlock_offset` and `data_size` at it
+//! and then increments [`header::DATA_GENERATION`]. A client that sees the
+//! generation change maps the object again, reads the new region from then
+//! on and stores the generation in [`client::DATA_GENERATION`] of its slot;
+//! the daemon keeps writing the old region until every client has, or for
+//! [`RESIZE_TIMEOUT`](crate::channel::RESIZE_TIMEOUT).
+//!
+//! A [standby](crate::channel::standby) daemon takes the channel over by
+//! incrementing [`header::OWNER_GENERATION`] with a compare-and-swap and then
+//! storing its PID in [`header::OWNER_PID`]; clients keep reading as before.
+//!
+//! All offsets are in bytes for 64-bit targets, and every multi-byte field is
+//! in native byte order. Every field is fixed-width and every gap between
+//! fields is an explicit, zeroed padding field. Fields documented as atomic
+//! must only be accessed with atomic operations.
+//!
+//! [`FIELDS`] lists the offsets again with the values this version of the
+//! format specifies: [`self_test`] checks that the structs still match them,
+//! and [`markdown`] renders them as `docs/LAYOUT.md` (`cargo xtask layout`)
+//! for clients written in other languages.
+
+use crate::channel::{ChannelHeader, ClientSlot, ResponseSlot, VENOM_MAGIC, VENOM_VERSION};
+use crate::event_log::{EventLogHeader, EventSlot};
+use crate::mpsc_queue::{slot_state, CommandSlot, MpscQueueHeader};
+use crate::seqlock::SeqLockHeader;
+use crate::shm::VENOM_SHM_PREFIX;
+use std::mem::{offset_of, size_of};
+
+/// A size or offset read from shared memory as a `usize`: one too large
+/// for it saturates, so the bounds checks after it reject it
+#[inline]
+pub(crate) fn to_usize(value: u64) -> usize {
+    usize::try_from(value).unwrap_or(usize::MAX)
+}
+
+/// `magic` of every channel
+pub const MAGIC: u32 = VENOM_MAGIC;
+/// `version` of channels with this layout
+pub const VERSION: u32 = VENOM_VERSION;
+/// Prefix of the shared memory object name (`shm_open("/venom_<name>")`)
+pub const SHM_PREFIX: &str = VENOM_SHM_PREFIX;
+
+/// Channel header, at offset 0
+pub mod header {
+    use super::*;
+
+    /// `u32`
+    pub const MAGIC: usize = offset_of!(ChannelHeader, magic);
+    /// `u32`
+    pub const VERSION: usize = offset_of!(ChannelHeader, version);
+    /// Atomic `u64`: size of the current data region
+    pub const DATA_SIZE: usize = offset_of!(ChannelHeader, data_size);
+    /// `u64`
+    pub const CMD_SLOTS: usize = offset_of!(ChannelHeader, cmd_slots);
+    /// `u64`
+    pub const MAX_CLIENTS: usize = offset_of!(ChannelHeader, max_clients);
+    /// Atomic `u32`: the client ID the next shell to connect takes, with a
+    /// compare-and-swap; after `u32::MAX - 1` comes 1, neither 0 nor
+    /// [`client::CLAIMING`] being an ID
+    pub const NEXT_CLIENT_ID: usize = offset_of!(ChannelHeader, next_client_id);
+    /// Atomic `u32`: PID of the daemon that owns the channel
+    pub const OWNER_PID: usize = offset_of!(ChannelHeader, owner_pid);
+    /// Atomic `u64`: offset of the current SeqLock header
+    pub const SEQLOCK_OFFSET: usize = offset_of!(ChannelHeader, seqlock_offset);
+    /// `u64`: offset of the command queue header
+    pub const CMD_QUEUE_OFFSET: usize = offset_of!(ChannelHeader, cmd_queue_offset);
+    /// Atomic `u32`: shells increment it on connect and decrement it on close
+    pub const CLIENT_COUNT: usize = offset_of!(ChannelHeader, client_count);
+    /// Atomic `u32`: incremented by a standby taking the channel over; the
+    /// owner checks it is still its own before every write
+    pub const OWNER_GENERATION: usize = offset_of!(ChannelHeader, owner_generation);
+    /// `u64`: offset of the client table; 0 from daemons older than it
+    pub const CLIENTS_OFFSET: usize = offset_of!(ChannelHeader, clients_offset);
+    /// Atomic `u32`: incremented, with release ordering, each time the data
+    /// region moves
+    pub const DATA_GENERATION: usize = offset_of!(ChannelHeader, data_generation);
+    /// Atomic `u32`: PID of the standby waiting to take the channel over, 0 if none
+    pub const STANDBY_PID: usize = offset_of!(ChannelHeader, standby_pid);
+    /// `u64`: offset of the response slots; 0 from daemons older than them
+    pub const RESPONSES_OFFSET: usize = offset_of!(ChannelHeader, responses_offset);
+    /// Atomic `u64`: `CLOCK_MONOTONIC_RAW` ns of the owner's last write or
+    /// heartbeat; 0 from daemons older than it
+    pub const HEARTBEAT: usize = offset_of!(ChannelHeader, heartbeat_ns);
+    /// Atomic `u32`: set to 1 by the shell taking client ID `u32::MAX - 1`
+    /// before it stores 1 in `NEXT_CLIENT_ID`; from then on every ID has
+    /// been handed out, those above `NEXT_CLIENT_ID` too
+    pub const CLIENT_IDS_WRAPPED: usize = offset_of!(ChannelHeader, client_ids_wrapped);
+    /// `u64`: offset of the event log; 0 if the daemon keeps none, or is
+    /// older than it
+    pub const EVENTS_OFFSET: usize = offset_of!(ChannelHeader, events_offset);
+    pub const SIZE: usize = size_of::<ChannelHeader>();
+}
+
+/// SeqLock header, at `header::SEQLOCK_OFFSET`; the data region follows it
+///
+/// A reader loads the sequence, retries while it is odd, copies the data,
+/// and keeps the copy if the sequence is unchanged afterwards. Data written
+/// through the C API (`venom_daemon_write_data`) starts with its length, a
+/// little-endian `u64`, and the payload follows at [`LEN_PREFIX`](seqlock::LEN_PREFIX).
+pub mod seqlock {
+    use super::*;
+
+    /// Atomic `u64`: odd while the daemon is writing; two more per write,
+    /// wrapping past `u64::MAX` to 2, so 0 only before the first write
+    pub const SEQUENCE: usize = offset_of!(SeqLockHeader, sequence);
+    /// `u64`: size of the data region
+    pub const DATA_SIZE: usize = offset_of!(SeqLockHeader, data_size);
+    /// Atomic `u64`: `CLOCK_MONOTONIC_RAW` nanoseconds of the last write,
+    /// written while the sequence is odd; 0 if the daemon doesn't stamp
+    pub const TIMESTAMP: usize = offset_of!(SeqLockHeader, timestamp_ns);
+    /// Offset of the data region from the SeqLock header
+    pub const SIZE: usize = size_of::<SeqLockHeader>();
+    /// Offset of the payload in a length-prefixed data region
+    pub const LEN_PREFIX: usize = size_of::<u64>();
+}
+
+/// Command queue header, at `header::CMD_QUEUE_OFFSET`; `num_slots` slots follow it
+///
+/// A shell claims slot `fetch_add(write_idx, 1) % num_slots`, moves its state
+/// from [`slot::EMPTY`] to [`slot::WRITING`] with a compare-and-swap (the
+/// queue is full if that fails), fills in the slot and then stores
+/// [`slot::READY`].
+pub mod queue {
+    use super::*;
+
+    /// Atomic `u64`: next slot to claim
+    pub const WRITE_IDX: usize = offset_of!(MpscQueueHeader, write_idx);
+    /// Atomic `u64`: commands taken so far, by the daemon or dropped; the
+    /// oldest waiting one is in slot `READ_IDX % num_slots`. Whoever takes
+    /// it moves that slot from `READY` to `PROCESSING`, then this index up
+    /// by one with a compare-and-swap, and empties the slot when done
+    pub const READ_IDX: usize = offset_of!(MpscQueueHeader, read_idx);
+    /// `u64`: a power of two, so that slot `index % num_slots` carries on
+    /// around the slots when the `u64` indices wrap; any count from
+    /// daemons older than that
+    pub const NUM_SLOTS: usize = offset_of!(MpscQueueHeader, num_slots);
+    /// Atomic `u64`: commands the daemon dropped as corrupt; always zero
+    /// from daemons older than the counter
+    pub const CORRUPT_SLOTS: usize = offset_of!(MpscQueueHeader, corrupt_slots);
+    /// `u64`: command bytes each slot holds, a multiple of 64; slot `i`
+    /// starts `i * (slot::CMD_DATA + cmd_payload_size)` after the header
+    pub const CMD_PAYLOAD_SIZE: usize = offset_of!(MpscQueueHeader, cmd_payload_size);
+    /// Atomic `u64`: commands producers dropped to make room
+    pub const DROPPED_COMMANDS: usize = offset_of!(MpscQueueHeader, dropped_commands);
+    /// `u8`: what a push does when the queue is full, a
+    /// [`CmdFullPolicy`](crate::mpsc_queue::CmdFullPolicy): 0 fails it, 1
+    /// drops the oldest command
+    pub const CMD_FULL_POLICY: usize = offset_of!(MpscQueueHeader, cmd_full_policy);
+    /// Atomic `u64`: sends that found the queue full; producers add one per
+    /// send, however often it retries. Always zero from daemons older than
+    /// the counter
+    pub const TOTAL_FULL_EVENTS: usize = offset_of!(MpscQueueHeader, total_full_events);
+    /// Offset of the first slot from the queue header
+    pub const SIZE: usize = size_of::<MpscQueueHeader>();
+}
+
+/// One command slot
+pub mod slot {
+    use super::*;
+
+    /// Atomic `u8`; the three bytes after it are always zero, so it can
+    /// also be accessed as an atomic `u32`
+    pub const STATE: usize = offset_of!(CommandSlot, state);
+    /// Atomic `u32`: client ID of the sender
+    pub const CLIENT_ID: usize = offset_of!(CommandSlot, client_id);
+    /// Atomic `u32`: length of the command
+    pub const CMD_LEN: usize = offset_of!(CommandSlot, cmd_len);
+    /// Command bytes, up to the queue's `cmd_payload_size`
+    pub const CMD_DATA: usize = size_of::<CommandSlot>();
+
+    /// `state` values
+    pub const EMPTY: u8 = slot_state::EMPTY;
+    pub const WRITING: u8 = slot_state::WRITING;
+    pub const READY: u8 = slot_state::READY;
+    pub const PROCESSING: u8 = slot_state::PROCESSING;
+}
+
+/// One slot of the client table, at `header::CLIENTS_OFFSET`; there are
+/// `max_clients` of them
+///
+/// A connecting shell moves a slot's `client_id` from 0 to [`client::CLAIMING`]
+/// with a compare-and-swap, fills in the other fields and then stores its
+/// client ID; it stores 0 again when it disconnects. A slot whose `pid` is no
+/// longer running may be claimed the same way from its client ID.
+pub mod client {
+    use super::*;
+
+    /// Atomic `u32`
+    pub const CLIENT_ID: usize = offset_of!(ClientSlot, client_id);
+    /// Atomic `u32`: process of the shell
+    pub const PID: usize = offset_of!(ClientSlot, pid);
+    /// Atomic `u64`: connect time, in nanoseconds since the Unix epoch
+    pub const CONNECTED_SINCE: usize = offset_of!(ClientSlot, connected_since);
+    /// Atomic `u32`: length of the name; 0 if the shell gave none
+    pub const NAME_LEN: usize = offset_of!(ClientSlot, name_len);
+    /// UTF-8 name, up to [`CLIENT_NAME_MAX`](crate::channel::CLIENT_NAME_MAX) bytes
+    pub const NAME: usize = offset_of!(ClientSlot, name);
+    /// Atomic `u32`: [`header::DATA_GENERATION`] of the data region the
+    /// client reads
+    pub const DATA_GENERATION: usize = offset_of!(ClientSlot, data_generation);
+    pub const SIZE: usize = size_of::<ClientSlot>();
+
+    /// `client_id` of a slot that is being filled in
+    pub const CLAIMING: u32 = u32::MAX;
+}
+
+/// One response slot, at `header::RESPONSES_OFFSET`; there are `max_clients`
+/// of them, slot `i` for the client in slot `i` of the client table, each
+/// [`response::DATA`] plus the queue's `cmd_payload_size` bytes long
+///
+/// The daemon makes `sequence` odd, fills in the other fields and the
+/// response, and makes it even again. A client takes a response once: when
+/// `sequence` is even, differs from the last one it saw, is unchanged after
+/// the copy, and `client_id` is its own.
+pub mod response {
+    use super::*;
+
+    /// Atomic `u64`: odd while the daemon is writing
+    pub const SEQUENCE: usize = offset_of!(ResponseSlot, sequence);
+    /// Atomic `u32`: client the response is for
+    pub const CLIENT_ID: usize = offset_of!(ResponseSlot, client_id);
+    /// Atomic `u32`: length of the response
+    pub const LEN: usize = offset_of!(ResponseSlot, len);
+    /// Response bytes, up to the queue's `cmd_payload_size`
+    pub const DATA: usize = size_of::<ResponseSlot>();
+}
+
+/// Event log header, at `header::EVENTS_OFFSET`; `num_slots` [`event`]
+/// slots follow it
+///
+/// A writer takes index `i` with a `fetch_add` on `NEXT` and moves the
+/// `SEQUENCE` of slot `i % NUM_SLOTS` from an even value below `2i + 1` to
+/// `2i + 1` with a compare-and-swap, giving up on the event if it can't;
+/// it fills in the slot and stores `2i + 2`. A reader keeps its copy of
+/// slot `i` if `SEQUENCE` read `2i + 2` before and after it.
+pub mod event_log {
+    use super::*;
+
+    /// Atomic `u64`: index the next event takes
+    pub const NEXT: usize = offset_of!(EventLogHeader, next);
+    /// `u64`: slots following the header
+    pub const NUM_SLOTS: usize = offset_of!(EventLogHeader, num_slots);
+    pub const SIZE: usize = size_of::<EventLogHeader>();
+}
+
+/// One event slot; slot `i` starts `i * event::SIZE` after the
+/// [`event_log`] header
+pub mod event {
+    use super::*;
+
+    /// Atomic `u64`: `2i + 1` while event `i` is written, `2i + 2` after
+    pub const SEQUENCE: usize = offset_of!(EventSlot, sequence);
+    /// Atomic `u64`: time of the event, in nanoseconds since the Unix epoch
+    pub const TIME: usize = offset_of!(EventSlot, time_ns);
+    /// Atomic `u32`: what happened, a [`ChannelEvent`](crate::event_log::ChannelEvent)
+    pub const CODE: usize = offset_of!(EventSlot, code);
+    /// Atomic `u32`: PID or client ID, by code
+    pub const ARG: usize = offset_of!(EventSlot, arg);
+    /// Atomic `u64`: PID, size or generation, by code
+    pub const VALUE: usize = offset_of!(EventSlot, value);
+    pub const SIZE: usize = size_of::<EventSlot>();
+}
+
+/// One field of [`FIELDS`]
+#[derive(Debug, Clone, Copy)]
+pub struct Field {
+    /// Module of the offset const: `header`, `seqlock`, `queue`, `slot`,
+    /// `client`, `response`, `event_log` or `event`
+    pub region: &'static str,
+    /// Name of the offset const; `SIZE` is the size of the whole struct
+    pub name: &'static str,
+    /// Type of the field, `atomic` ones only to be accessed atomically
+    pub ty: &'static str,
+    /// Offset the const holds, as the struct is laid out
+    pub offset: usize,
+    /// Offset [`VERSION`] of the format specifies, on 64-bit targets
+    pub spec: usize,
+    /// What the field holds, one line of Markdown
+    pub doc: &'static str,
+}
+
+const fn field(region: &'static str, name: &'static str, ty: &'static str, offset: usize, spec: usize, doc: &'static str) -> Field {
+    Field { region, name, ty, offset, spec, doc }
+}
+
+/// Every offset and size of the format, region by region in the order of
+/// the modules above, against the value the format specifies
+pub const FIELDS: &[Field] = &[
+    field("header", "MAGIC", "u32", header::MAGIC, 0, "`MAGIC`, stored last when the daemon creates the channel"),
+    field("header", "VERSION", "u32", header::VERSION, 4, "`VERSION`"),
+    field("header", "DATA_SIZE", "atomic u64", header::DATA_SIZE, 8, "Size of the current data region"),
+    field("header", "CMD_SLOTS", "u64", header::CMD_SLOTS, 16, "Command slots, as the daemon was configured"),
+    field("header", "MAX_CLIENTS", "u64", header::MAX_CLIENTS, 24, "Slots of the client table"),
+    field("header", "NEXT_CLIENT_ID", "atomic u32", header::NEXT_CLIENT_ID, 32, "A connecting shell takes its client ID with compare-and-swap; after `0xFFFFFFFE` comes 1"),
+    field("header", "OWNER_PID", "atomic u32", header::OWNER_PID, 36, "PID of the daemon that owns the channel"),
+    field("header", "SEQLOCK_OFFSET", "atomic u64", header::SEQLOCK_OFFSET, 40, "Offset of the current SeqLock header"),
+    field("header", "CMD_QUEUE_OFFSET", "u64", header::CMD_QUEUE_OFFSET, 48, "Offset of the command queue header"),
+    field("header", "CLIENT_COUNT", "atomic u32", header::CLIENT_COUNT, 56, "Connected shells"),
+    field("header", "OWNER_GENERATION", "atomic u32", header::OWNER_GENERATION, 60, "Incremented by a standby taking the channel over"),
+    field("header", "CLIENTS_OFFSET", "u64", header::CLIENTS_OFFSET, 64, "Offset of the client table; 0 from daemons older than it"),
+    field("header", "DATA_GENERATION", "atomic u32", header::DATA_GENERATION, 72, "Incremented each time the data region moves"),
+    field("header", "STANDBY_PID", "atomic u32", header::STANDBY_PID, 76, "PID of the standby daemon, 0 if none"),
+    field("header", "RESPONSES_OFFSET", "u64", header::RESPONSES_OFFSET, 80, "Offset of the response slots; 0 from daemons older than them"),
+    field("header", "HEARTBEAT", "atomic u64", header::HEARTBEAT, 88, "`CLOCK_MONOTONIC_RAW` ns of the owner's last write or heartbeat; 0 from daemons older than it"),
+    field("header", "CLIENT_IDS_WRAPPED", "atomic u32", header::CLIENT_IDS_WRAPPED, 96, "Set to 1 before `NEXT_CLIENT_ID` goes back to 1: every ID has been handed out"),
+    field("header", "EVENTS_OFFSET", "u64", header::EVENTS_OFFSET, 104, "Offset of the event log; 0 if the daemon keeps none, or is older than it"),
+    field("header", "SIZE", "", header::SIZE, 112, ""),
+    field("seqlock", "SEQUENCE", "atomic u64", seqlock::SEQUENCE, 0, "Odd while the daemon is writing"),
+    field("seqlock", "DATA_SIZE", "u64", seqlock::DATA_SIZE, 64, "Size of the data region"),
+    field("seqlock", "TIMESTAMP", "atomic u64", seqlock::TIMESTAMP, 72, "`CLOCK_MONOTONIC_RAW` ns of the last write; 0 if the daemon doesn't stamp"),
+    field("seqlock", "SIZE", "", seqlock::SIZE, 128, "The data region starts here"),
+    field("seqlock", "LEN_PREFIX", "", seqlock::LEN_PREFIX, 8, "Offset of the payload in the data region, after its little-endian u64 length"),
+    field("queue", "WRITE_IDX", "atomic u64", queue::WRITE_IDX, 0, "Next slot to claim"),
+    field("queue", "READ_IDX", "atomic u64", queue::READ_IDX, 64, "Commands taken so far; the oldest waiting one is in slot `READ_IDX % NUM_SLOTS`"),
+    field("queue", "NUM_SLOTS", "u64", queue::NUM_SLOTS, 128, "Slots following the header; a power of two (any count from daemons older than that)"),
+    field("queue", "CORRUPT_SLOTS", "atomic u64", queue::CORRUPT_SLOTS, 136, "Commands the daemon dropped as corrupt"),
+    field("queue", "CMD_PAYLOAD_SIZE", "u64", queue::CMD_PAYLOAD_SIZE, 144, "Command bytes each slot holds, a multiple of 64"),
+    field("queue", "DROPPED_COMMANDS", "atomic u64", queue::DROPPED_COMMANDS, 152, "Commands producers dropped to make room"),
+    field("queue", "CMD_FULL_POLICY", "u8", queue::CMD_FULL_POLICY, 160, "On a full queue, 0 fails a push and 1 drops the oldest command"),
+    field("queue", "TOTAL_FULL_EVENTS", "atomic u64", queue::TOTAL_FULL_EVENTS, 168, "Sends that found the queue full, one per send however often it retries"),
+    field("queue", "SIZE", "", queue::SIZE, 192, "The first slot starts here"),
+    field("slot", "STATE", "atomic u8", slot::STATE, 0, "`EMPTY`, `WRITING`, `READY` or `PROCESSING`; the three bytes after it are zero"),
+    field("slot", "CLIENT_ID", "atomic u32", slot::CLIENT_ID, 4, "Client ID of the sender"),
+    field("slot", "CMD_LEN", "atomic u32", slot::CMD_LEN, 8, "Length of the command"),
+    field("slot", "CMD_DATA", "", slot::CMD_DATA, 64, "Command bytes; slot `i` starts `i * (CMD_DATA + CMD_PAYLOAD_SIZE)` after the queue header"),
+    field("client", "CLIENT_ID", "atomic u32", client::CLIENT_ID, 0, "0 if free, `CLAIMING` while being filled in"),
+    field("client", "PID", "atomic u32", client::PID, 4, "Process of the shell"),
+    field("client", "CONNECTED_SINCE", "atomic u64", client::CONNECTED_SINCE, 8, "Connect time, ns since the Unix epoch"),
+    field("client", "NAME_LEN", "atomic u32", client::NAME_LEN, 16, "Length of the name; 0 if the shell gave none"),
+    field("client", "NAME", "[u8; 32]", client::NAME, 20, "UTF-8 name"),
+    field("client", "DATA_GENERATION", "atomic u32", client::DATA_GENERATION, 52, "`DATA_GENERATION` of the data region the shell reads"),
+    field("client", "SIZE", "", client::SIZE, 56, ""),
+    field("response", "SEQUENCE", "atomic u64", response::SEQUENCE, 0, "Odd while the daemon is writing"),
+    field("response", "CLIENT_ID", "atomic u32", response::CLIENT_ID, 8, "Client the response is for"),
+    field("response", "LEN", "atomic u32", response::LEN, 12, "Length of the response"),
+    field("response", "DATA", "", response::DATA, 64, "Response bytes; slot `i` starts `i * (DATA + CMD_PAYLOAD_SIZE)` after `RESPONSES_OFFSET`"),
+    field("event_log", "NEXT", "atomic u64", event_log::NEXT, 0, "Index the next event takes"),
+    field("event_log", "NUM_SLOTS", "u64", event_log::NUM_SLOTS, 8, "Event slots following the header"),
+    field("event_log", "SIZE", "", event_log::SIZE, 64, "The first slot starts here"),
+    field("event", "SEQUENCE", "atomic u64", event::SEQUENCE, 0, "`2i + 1` while event `i` is written, `2i + 2` after"),
+    field("event", "TIME", "atomic u64", event::TIME, 8, "Time of the event, ns since the Unix epoch"),
+    field("event", "CODE", "atomic u32", event::CODE, 16, "1 daemon created, 2 client connected, 3 client disconnected, 4 queue full, 5 corrupt command, 6 data region grown, 7 standby took over"),
+    field("event", "ARG", "atomic u32", event::ARG, 20, "PID for 1 and 7, client ID for 2 to 5"),
+    field("event", "VALUE", "atomic u64", event::VALUE, 24, "PID for 2, new data size for 6, owner generation for 7"),
+    field("event", "SIZE", "", event::SIZE, 32, "Slot `i` starts `i * SIZE` after the header"),
+];
+
+/// Check every offset and size of [`FIELDS`] against the format
+///
+/// A no-op on a correct build; it exists so the layout can't change
+/// without the change being noticed, and runs as a unit test.
+///
+/// # Panics
+/// If a struct no longer matches the format, naming the first field that
+/// moved.
+#[cfg(target_pointer_width = "64")]
+pub fn self_test() {
+    for field in FIELDS {
+        assert_eq!(
+            field.offset, field.spec,
+            "{}::{} is {}, version {} of the layout says {}",
+            field.region, field.name, field.offset, VERSION, field.spec
+        );
+    }
+}
+
+/// Where each region of [`FIELDS`] lies, and how it is used
+const REGIONS: [(&str, &str); 8] = [
+    ("header", "Channel header, at offset 0."),
+    (
+        "seqlock",
+        "SeqLock header, at `header::SEQLOCK_OFFSET`; the data region follows it. A reader loads `SEQUENCE`, retries \
+         while it is odd, copies the data, and keeps the copy if `SEQUENCE` is unchanged afterwards. `SEQUENCE` \
+         goes up by two per write and wraps past `u64::MAX` to 2, so it is 0 only before the first write.",
+    ),
+    (
+        "queue",
+        "Command queue header, at `header::CMD_QUEUE_OFFSET`; `NUM_SLOTS` slots follow it. A shell claims slot \
+         `fetch_add(WRITE_IDX, 1) % NUM_SLOTS`, moves its state from `EMPTY` to `WRITING` with a compare-and-swap, \
+         fills in the slot and then stores `READY`.",
+    ),
+    ("slot", "One command slot."),
+    (
+        "client",
+        "One slot of the client table, at `header::CLIENTS_OFFSET`; there are `MAX_CLIENTS` of them. A connecting \
+         shell moves `CLIENT_ID` from 0 to `CLAIMING` with a compare-and-swap, fills in the other fields and then \
+         stores its client ID; it stores 0 again when it disconnects.",
+    ),
+    (
+        "response",
+        "One response slot, at `header::RESPONSES_OFFSET`; slot `i` holds the last response the daemon sent the \
+         client in slot `i` of the client table. The daemon makes `SEQUENCE` odd, fills in the slot and makes it \
+         even again; a client takes the response when `SEQUENCE` is even, new to it and unchanged after the copy, \
+         and `CLIENT_ID` is its own.",
+    ),
+    (
+        "event_log",
+        "Event log header, at `header::EVENTS_OFFSET`; `NUM_SLOTS` event slots follow it. A writer takes index `i` \
+         with `fetch_add(NEXT, 1)` and moves `SEQUENCE` of slot `i % NUM_SLOTS` from an even value below `2i + 1` \
+         to `2i + 1` with a compare-and-swap, giving up on the event if it can't; it fills in the slot and stores \
+         `2i + 2`. A reader keeps its copy of slot `i` if `SEQUENCE` read `2i + 2` before and after it.",
+    ),
+    ("event", "One event slot."),
+];
+
+/// The layout as a Markdown document, the contents of `docs/LAYOUT.md`
+pub fn markdown() -> String {
+    let mut out = format!(
+        "# Channel layout, version {}\n\n\
+         <!-- Generated from venom_memory::layout by `cargo xtask layout`; don't edit. -->\n\n\
+         A channel is the shared memory object `{}<name>` (`/dev/shm/venom_<name>`). Offsets are in bytes \
+         on 64-bit targets; every multi-byte field is in native byte order, gaps between fields are zeroed \
+         padding, and atomic fields must only be accessed with atomic operations.\n\n\
+         | Constant | Value |\n|----------|-------|\n\
+         | `MAGIC` | `0x{:08X}` |\n| `VERSION` | {} |\n\
+         | `slot::EMPTY`, `WRITING`, `READY`, `PROCESSING` | {}, {}, {}, {} |\n\
+         | `client::CLAIMING` | `0x{:08X}` |\n",
+        VERSION,
+        SHM_PREFIX,
+        MAGIC,
+        VERSION,
+        slot::EMPTY,
+        slot::WRITING,
+        slot::READY,
+        slot::PROCESSING,
+        client::CLAIMING,
+    );
+    for (region, about) in REGIONS {
+        out += &format!("\n## `{}`\n\n{}\n\n| Offset | Field | Type | |\n|--------|-------|------|-|\n", region, about);
+        for field in FIELDS.iter().filter(|field| field.region == region) {
+            let ty = if field.ty.is_empty() { String::new() } else { format!("`{}`", field.ty) };
+            out += &format!("| {} | `{}` | {} | {} |\n", field.offset, field.name, ty, field.doc);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The layout is a stable format; a change here needs a new VERSION
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn test_layout_is_stable() {
+        assert_eq!((MAGIC, VERSION, SHM_PREFIX), (0x564E4F4D, 5, "/venom_"));
+        assert_eq!(
+            [header::MAGIC, header::VERSION, header::DATA_SIZE, header::CMD_SLOTS, header::MAX_CLIENTS],
+            [0, 4, 8, 16, 24]
+        );
+        assert_eq!(
+            [header::NEXT_CLIENT_ID, header::OWNER_PID, header::SEQLOCK_OFFSET, header::CMD_QUEUE_OFFSET, header::CLIENT_COUNT],
+            [32, 36, 40, 48, 56]
+        );
+        assert_eq!(
+            [header::OWNER_GENERATION, header::CLIENTS_OFFSET, header::DATA_GENERATION, header::STANDBY_PID],
+            [60, 64, 72, 76]
+        );
+        assert_eq!(
+            [header::RESPONSES_OFFSET, header::HEARTBEAT, header::CLIENT_IDS_WRAPPED, header::EVENTS_OFFSET, header::SIZE],
+            [80, 88, 96, 104, 112]
+        );
+        assert_eq!([seqlock::SEQUENCE, seqlock::DATA_SIZE, seqlock::TIMESTAMP, seqlock::SIZE, seqlock::LEN_PREFIX], [0, 64, 72, 128, 8]);
+        assert_eq!(
+            [queue::WRITE_IDX, queue::READ_IDX, queue::NUM_SLOTS, queue::CORRUPT_SLOTS, queue::CMD_PAYLOAD_SIZE],
+            [0, 64, 128, 136, 144]
+        );
+        assert_eq!([queue::DROPPED_COMMANDS, queue::CMD_FULL_POLICY, queue::TOTAL_FULL_EVENTS, queue::SIZE], [152, 160, 168, 192]);
+        assert_eq!([slot::STATE, slot::CLIENT_ID, slot::CMD_LEN, slot::CMD_DATA], [0, 4, 8, 64]);
+        assert_eq!([slot::EMPTY, slot::WRITING, slot::READY, slot::PROCESSING], [0, 1, 2, 3]);
+        assert_eq!(
+            [client::CLIENT_ID, client::PID, client::CONNECTED_SINCE, client::NAME_LEN, client::NAME],
+            [0, 4, 8, 16, 20]
+        );
+        assert_eq!([client::DATA_GENERATION, client::SIZE], [52, 56]
+        );
+        assert_eq!([response::SEQUENCE, response::CLIENT_ID, response::LEN, response::DATA], [0, 8, 12, 64]);
+        assert_eq!([event_log::NEXT, event_log::NUM_SLOTS, event_log::SIZE], [0, 8, 64]);
+        assert_eq!([event::SEQUENCE, event::TIME, event::CODE, event::ARG, event::VALUE, event::SIZE], [0, 8, 16, 20, 24, 32]);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn test_self_test() {
+        self_test();
+        // Every const of every region is listed
+        assert_eq!(FIELDS.len(), 57);
+        assert!(REGIONS.iter().all(|(region, _)| FIELDS.iter().any(|field| field.region == *region)));
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn test_layout_md_is_current() {
+        let committed = include_str!("../docs/LAYOUT.md");
+        assert!(committed == markdown(), "docs/LAYOUT.md is out of date: run `cargo xtask layout`");
+    }
+}
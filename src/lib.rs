@@ -10,15 +10,55 @@
 //!
 //! # Performance
 //!
-//! - Data reads: < 50ns (SeqLock)
-//! - Command sends: < 100ns (MPSC lock-free queue)
+//! Figures for payloads up to 1KB, from the criterion benchmarks in
+//! `benches/` (`cargo bench`):
+//!
+//! - Data reads: < 50ns (SeqLock; `seqlock_read`, `channel_read_data`,
+//!   `channel_read_data_contended` with a writer running)
+//! - Command sends: < 100ns (MPSC lock-free queue; `mpsc/try_push`,
+//!   `mpsc/try_push_try_pop`)
+//!
+//! # Features
+//!
+//! With none of them, the crate is the channel stack (shared memory,
+//! SeqLock, command queue) over `rustix` and `thiserror` alone.
+//!
+//! - `bindings` (default): the C API in `bindings`; `cargo xtask cdylib`
+//!   builds it into `libvenom_memory.so`
+//! - `discover`: `discover`, listing the channels on the system
+//! - `serde`: `codec`, typed messages over postcard or JSON
+//! - `async`: `AsyncShellChannel`, over tokio
+//! - `gui`: the `gui_monitor` example
 
 pub mod error;
+pub mod affinity;
+pub mod namespace;
 pub mod shm;
 pub mod seqlock;
+pub mod event_log;
 pub mod mpsc_queue;
+pub mod large_command;
+pub mod typed_command;
 pub mod channel;
+pub mod layout;
+#[cfg(feature = "discover")]
+pub mod discover;
+#[cfg(feature = "bindings")]
 pub mod bindings;
+#[cfg(feature = "serde")]
+pub mod codec;
+#[cfg(feature = "async")]
+pub mod async_channel;
+
+/// Version of this crate; `venom_version()` reports it from the C library
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub use error::{VenomError, Result};
-pub use channel::{DaemonChannel, ShellChannel, ChannelConfig};
+pub use channel::{DaemonChannel, DaemonStats, FilterAction, RawRegion, ShellChannel, ChannelConfig, CLIENT_NAME_MAX, RESIZE_TIMEOUT};
+pub use channel::snapshot::{Frame, Pod};
+pub use channel::split::{ShellReader, ShellSender};
+pub use event_log::{ChannelEvent, LoggedEvent};
+pub use mpsc_queue::CmdFullPolicy;
+pub use shm::ShmPermissions;
+#[cfg(feature = "async")]
+pub use async_channel::AsyncShellChannel;
@@ -4,93 +4,406 @@
 //! to a single daemon (consumer) without locks.
 //!
 //! # Design
-//! - Fixed-size slots with state machine
-//! - Producers: atomic claim -> write -> publish
-//! - Consumer: read -> process -> release
+//! - Fixed-size slots with state machine; the slot size is set per queue
+//!   (`cmd_payload_size` in the header)
+//! - Producers: claim (by CAS, while a slot is free) -> write -> publish
+//! - Consumer: take -> read -> release; taking the oldest command claims
+//!   its slot, then moves the read index past it, both by CAS
+//! - When every slot is full, producers fail or, with
+//!   [`CmdFullPolicy::OverwriteOldest`], take the oldest command the same
+//!   way the consumer does and drop it
+//!
+//! # Wrapping
+//! The read and write indices are `u64`s that count every command and wrap
+//! around after `u64::MAX`; everything comparing them subtracts with
+//! wrapping. Index `i` goes in slot `i % num_slots`, which keeps counting
+//! around the slots across the wrap only because [`cmd_slots`] makes the
+//! number of slots a power of two.
 
-use std::sync::atomic::{AtomicU32, AtomicU64, AtomicU8, Ordering};
+use crate::error::{Result, VenomError};
+use crate::event_log::{ChannelEvent, EventLog};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicU8, Ordering};
+use std::time::{Duration, Instant};
 
-/// Maximum command size in bytes
+/// Largest command payload a queue can be set up for, in bytes; no
+/// command is longer, so a buffer of this size takes any of them
 pub const MAX_CMD_SIZE: usize = 4096;
 
+/// Command payload of a queue that doesn't ask for another
+pub const DEFAULT_CMD_PAYLOAD_SIZE: usize = MAX_CMD_SIZE;
+
 /// Maximum number of command slots
 pub const MAX_SLOTS: usize = 64;
 
 /// Cache line size
 const CACHE_LINE_SIZE: usize = 64;
 
+/// Busy-spin retries of [`MpscProducer::push_timeout`] before it yields
+const SPIN_LIMIT: u32 = 64;
+
+/// Yields of [`MpscProducer::push_timeout`] before it sleeps
+const YIELD_LIMIT: u32 = 16;
+
+/// Longest sleep between retries of [`MpscProducer::push_timeout`]
+const RETRY_SLEEP: Duration = Duration::from_micros(50);
+
 /// Slot states
-mod slot_state {
+pub(crate) mod slot_state {
     pub const EMPTY: u8 = 0;
     pub const WRITING: u8 = 1;
     pub const READY: u8 = 2;
     pub const PROCESSING: u8 = 3;
 }
 
+/// What a push does when every slot holds a command the consumer hasn't
+/// taken; stored in the queue header, so every producer does the same
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CmdFullPolicy {
+    /// The push fails: [`MpscProducer::try_push`] returns `false` and
+    /// [`MpscProducer::push`] spins until the consumer makes room
+    #[default]
+    Reject = 0,
+    /// The oldest command is dropped to make room and counted in
+    /// [`MpscQueueHeader::dropped_commands`]; pushes never wait for the
+    /// consumer
+    OverwriteOldest = 1,
+}
+
+impl CmdFullPolicy {
+    /// The policy stored as `byte`, if it is one
+    pub fn from_u8(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(Self::Reject),
+            1 => Some(Self::OverwriteOldest),
+            _ => None,
+        }
+    }
+}
+
 /// Padding to cache line
 #[repr(C, align(64))]
-struct CachePadded<T>(T);
+pub(crate) struct CachePadded<T>(T);
 
-/// A single command slot
+/// Header of a command slot; the command data follows it in the next
+/// cache line, `cmd_payload_size` bytes of it
 #[repr(C)]
 pub struct CommandSlot {
     /// Slot state (empty, writing, ready, processing)
-    state: AtomicU8,
+    pub(crate) state: AtomicU8,
+    /// Always zero
+    _state_pad: [u8; 3],
     /// Client ID that sent this command
-    client_id: AtomicU32,
+    pub(crate) client_id: AtomicU32,
     /// Length of command data
-    cmd_len: AtomicU32,
-    /// Padding for alignment
-    _pad: [u8; 64 - 9],
-    /// Command data (separate cache line)
-    cmd_data: [u8; MAX_CMD_SIZE],
+    pub(crate) cmd_len: AtomicU32,
+    /// Padding to the cache line the data starts on
+    _pad: [u8; CACHE_LINE_SIZE - 12],
+}
+
+impl CommandSlot {
+    /// Bytes from one slot to the next in a queue with this payload size
+    pub const fn size_for_payload(cmd_payload_size: usize) -> usize {
+        std::mem::size_of::<CommandSlot>() + cmd_payload_size
+    }
+
+    /// Slot `index` of the slots starting at `slots`
+    ///
+    /// # Safety
+    /// `slots` must point to more than `index` slots of this payload size
+    unsafe fn at(slots: *mut u8, index: usize, cmd_payload_size: usize) -> *mut CommandSlot {
+        slots.add(index * Self::size_for_payload(cmd_payload_size)) as *mut CommandSlot
+    }
+
+    /// The command data of `slot`
+    ///
+    /// # Safety
+    /// `slot` must come from [`at`](Self::at)
+    unsafe fn data(slot: *mut CommandSlot) -> *mut u8 {
+        (slot as *mut u8).add(std::mem::size_of::<CommandSlot>())
+    }
+}
+
+/// Payload size a queue asked for `requested` bytes gets: rounded up to
+/// whole cache lines, so every slot starts on one
+///
+/// Fails with [`VenomError::InvalidConfig`] outside 1 to [`MAX_CMD_SIZE`].
+pub fn cmd_payload_size(requested: usize) -> Result<usize> {
+    if requested == 0 || requested > MAX_CMD_SIZE {
+        return Err(VenomError::InvalidConfig {
+            reason: format!("command payload of {} bytes (1 to {} allowed)", requested, MAX_CMD_SIZE),
+        });
+    }
+    Ok(requested.div_ceil(CACHE_LINE_SIZE) * CACHE_LINE_SIZE)
+}
+
+/// Number of slots a queue asked for `requested` gets: rounded up to a
+/// power of two, so slot indices carry on across the wrap (see
+/// [Wrapping](self#wrapping))
+///
+/// Fails with [`VenomError::InvalidConfig`] outside 1 to [`MAX_SLOTS`].
+pub fn cmd_slots(requested: usize) -> Result<usize> {
+    if requested == 0 || requested > MAX_SLOTS {
+        return Err(VenomError::InvalidConfig {
+            reason: format!("command queue of {} slots (1 to {} allowed)", requested, MAX_SLOTS),
+        });
+    }
+    Ok(requested.next_power_of_two())
 }
 
 /// MPSC Queue header in shared memory
 #[repr(C)]
 pub struct MpscQueueHeader {
     /// Write index (producers increment this to claim slots)
-    write_idx: CachePadded<AtomicU64>,
+    pub(crate) write_idx: CachePadded<AtomicU64>,
     /// Read index (consumer's current position)
-    read_idx: CachePadded<AtomicU64>,
+    pub(crate) read_idx: CachePadded<AtomicU64>,
     /// Number of slots
-    num_slots: usize,
+    pub(crate) num_slots: u64,
+    /// Slots the consumer dropped as corrupt
+    pub(crate) corrupt_slots: AtomicU64,
+    /// Bytes of command data each slot holds, a multiple of the cache line
+    pub(crate) cmd_payload_size: u64,
+    /// Commands producers dropped to make room, under
+    /// [`CmdFullPolicy::OverwriteOldest`]
+    pub(crate) dropped_commands: AtomicU64,
+    /// A [`CmdFullPolicy`]
+    pub(crate) cmd_full_policy: u8,
+    _policy_pad: [u8; 7],
+    /// Sends that found the queue full, whether they then gave up, waited
+    /// or dropped the oldest command
+    pub(crate) total_full_events: AtomicU64,
     /// Padding
-    _pad: [u8; CACHE_LINE_SIZE - 8],
+    _pad: [u8; CACHE_LINE_SIZE - 48],
 }
 
 impl MpscQueueHeader {
-    /// Size of the queue in bytes (header + slots)
-    pub const fn size_for_slots(num_slots: usize) -> usize {
-        std::mem::size_of::<MpscQueueHeader>() + num_slots * std::mem::size_of::<CommandSlot>()
+    /// Size of the queue in bytes (header + slots); `cmd_payload_size` as
+    /// [`cmd_payload_size`] rounds it
+    pub const fn size_for_slots(num_slots: usize, cmd_payload_size: usize) -> usize {
+        std::mem::size_of::<MpscQueueHeader>() + num_slots * CommandSlot::size_for_payload(cmd_payload_size)
+    }
+
+    /// Commands the consumer has taken off the queue since it was created
+    ///
+    /// Read alongside [`dropped_commands`](Self::dropped_commands) while
+    /// producers drop, it can be one ahead for a moment.
+    #[inline]
+    pub fn consumed(&self) -> u64 {
+        let taken = self.read_idx.0.load(Ordering::Acquire);
+        taken.wrapping_sub(self.dropped_commands())
+    }
+
+    /// Commands producers dropped, oldest first, to make room under
+    /// [`CmdFullPolicy::OverwriteOldest`]
+    #[inline]
+    pub fn dropped_commands(&self) -> u64 {
+        self.dropped_commands.load(Ordering::Acquire)
+    }
+
+    /// Sends that found the queue full, by any producer: ones that failed
+    /// or timed out, ones that waited for room, and under
+    /// [`CmdFullPolicy::OverwriteOldest`] ones that dropped a command
+    ///
+    /// Always zero from daemons older than the counter.
+    #[inline]
+    pub fn total_full_events(&self) -> u64 {
+        self.total_full_events.load(Ordering::Relaxed)
+    }
+
+    /// Commands the consumer dropped because their slot didn't hold a
+    /// valid one, out of [`consumed`](Self::consumed)
+    #[inline]
+    pub fn corrupt_slots(&self) -> u64 {
+        self.corrupt_slots.load(Ordering::Relaxed)
+    }
+
+    /// Count a command dropped as corrupt after it was taken
+    #[inline]
+    pub(crate) fn record_corrupt(&self) {
+        self.corrupt_slots.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Number of slots, as the header says
+    ///
+    /// Anyone with the region mapped can overwrite it; handles read it once
+    /// and keep their own copy.
+    #[inline]
+    pub fn num_slots(&self) -> usize {
+        crate::layout::to_usize(self.num_slots)
+    }
+
+    /// Bytes of command data a slot holds, as the header says; handles
+    /// keep their own copy of this too
+    #[inline]
+    pub fn cmd_payload_size(&self) -> usize {
+        crate::layout::to_usize(self.cmd_payload_size)
+    }
+
+    /// What pushes do when the queue is full, if the header holds a
+    /// policy; handles keep their own copy
+    #[inline]
+    pub fn cmd_full_policy(&self) -> Option<CmdFullPolicy> {
+        CmdFullPolicy::from_u8(self.cmd_full_policy)
+    }
+
+    /// Check that a region of `len` bytes at `ptr` holds the header and
+    /// every slot the header claims, and return the number of slots, their
+    /// payload size and the full-queue policy
+    ///
+    /// # Safety
+    /// `ptr` must be valid for reads of `len` bytes and aligned to 64
+    pub unsafe fn check_region(ptr: *const u8, len: usize) -> Result<(usize, usize, CmdFullPolicy)> {
+        if len < std::mem::size_of::<MpscQueueHeader>() {
+            return Err(VenomError::CorruptRegion {
+                reason: format!("{} bytes can't hold a command queue header", len),
+            });
+        }
+        let header = &*(ptr as *const MpscQueueHeader);
+        let (num_slots, payload) = (header.num_slots(), header.cmd_payload_size());
+        if num_slots == 0 || num_slots > MAX_SLOTS {
+            return Err(VenomError::CorruptRegion {
+                reason: format!("command queue of {} slots (1 to {} allowed)", num_slots, MAX_SLOTS),
+            });
+        }
+        if cmd_payload_size(payload).ok() != Some(payload) {
+            return Err(VenomError::CorruptRegion {
+                reason: format!("command slots of {} bytes", payload),
+            });
+        }
+        if Self::size_for_slots(num_slots, payload) > len {
+            return Err(VenomError::CorruptRegion {
+                reason: format!("{} command slots don't fit in {} bytes", num_slots, len),
+            });
+        }
+        let policy = header.cmd_full_policy().ok_or_else(|| VenomError::CorruptRegion {
+            reason: format!("full command queue policy {}", header.cmd_full_policy),
+        })?;
+        Ok((num_slots, payload, policy))
+    }
+
+    /// Commands in the queue going by its indices: claimed by producers and
+    /// not yet taken, at most `num_slots`
+    ///
+    /// Advisory, for backing off before the queue fills: producers and the
+    /// consumer move the indices while it is read, and a command counts
+    /// from the moment its producer claims a slot, before it is written.
+    /// Unlike [`pending`](Self::pending) it doesn't look at the slots, so
+    /// it costs the same however many there are.
+    #[inline]
+    pub fn queued(&self, num_slots: usize) -> usize {
+        // The read index first: it never passes the write index, so the
+        // difference doesn't wrap below zero
+        let taken = self.read_idx.0.load(Ordering::Acquire);
+        let claimed = self.write_idx.0.load(Ordering::Acquire);
+        claimed.wrapping_sub(taken).min(num_slots as u64) as usize
+    }
+
+    /// Slots holding a command, whether it is still being written, waiting
+    /// or being taken
+    ///
+    /// # Safety
+    /// The header must be followed by at least `num_slots` slots of
+    /// `cmd_payload_size` bytes
+    pub unsafe fn pending(&self, num_slots: usize, cmd_payload_size: usize) -> usize {
+        let slots = Self::slots(self as *const Self);
+        (0..num_slots)
+            .filter(|&i| (*CommandSlot::at(slots, i, cmd_payload_size)).state.load(Ordering::Acquire) != slot_state::EMPTY)
+            .count()
     }
 
     /// Initialize a new queue header
     ///
+    /// `num_slots` must be one [`cmd_slots`] returned, and
+    /// `cmd_payload_size` one [`cmd_payload_size`] returned.
+    ///
     /// # Safety
-    /// Pointer must be valid and properly aligned
-    pub unsafe fn init(ptr: *mut Self, num_slots: usize) {
+    /// Pointer must be valid for [`size_for_slots`](Self::size_for_slots)
+    /// bytes and properly aligned
+    pub unsafe fn init(ptr: *mut Self, num_slots: usize, cmd_payload_size: usize, policy: CmdFullPolicy) {
         (*ptr).write_idx.0 = AtomicU64::new(0);
         (*ptr).read_idx.0 = AtomicU64::new(0);
-        (*ptr).num_slots = num_slots;
+        (*ptr).num_slots = num_slots as u64;
+        (*ptr).corrupt_slots = AtomicU64::new(0);
+        (*ptr).cmd_payload_size = cmd_payload_size as u64;
+        (*ptr).dropped_commands = AtomicU64::new(0);
+        (*ptr).cmd_full_policy = policy as u8;
+        (*ptr).total_full_events = AtomicU64::new(0);
 
         // Initialize all slots to empty
-        let slots_ptr = (ptr as *mut u8).add(std::mem::size_of::<MpscQueueHeader>())
-            as *mut CommandSlot;
+        let slots = Self::slots(ptr);
         for i in 0..num_slots {
-            let slot = &mut *slots_ptr.add(i);
+            let slot = &mut *CommandSlot::at(slots, i, cmd_payload_size);
             slot.state = AtomicU8::new(slot_state::EMPTY);
             slot.client_id = AtomicU32::new(0);
             slot.cmd_len = AtomicU32::new(0);
         }
     }
+
+    /// Initialize a queue header as if `index` commands had been through
+    /// it, to test the wrap without sending them
+    #[cfg(test)]
+    pub(crate) unsafe fn init_at(
+        ptr: *mut Self,
+        num_slots: usize,
+        cmd_payload_size: usize,
+        policy: CmdFullPolicy,
+        index: u64,
+    ) {
+        Self::init(ptr, num_slots, cmd_payload_size, policy);
+        (*ptr).write_idx.0.store(index, Ordering::Relaxed);
+        (*ptr).read_idx.0.store(index, Ordering::Relaxed);
+    }
+
+    /// The first slot; they always follow their header (see `size_for_slots`)
+    fn slots(header: *const Self) -> *mut u8 {
+        (header as *mut u8).wrapping_add(std::mem::size_of::<MpscQueueHeader>())
+    }
+
+    /// Take the oldest command, for the consumer to read or a producer to
+    /// drop; the caller empties its slot when done with it
+    ///
+    /// The slot is claimed first, then the read index moved past it, so of
+    /// everyone taking at once exactly one gets the command. `None` if it
+    /// isn't ready, or someone else is taking it.
+    ///
+    /// # Safety
+    /// `slots` must point to `num_slots` slots of `cmd_payload_size` bytes
+    unsafe fn take_oldest(&self, slots: *mut u8, num_slots: usize, cmd_payload_size: usize) -> Option<*mut CommandSlot> {
+        loop {
+            let read_idx = self.read_idx.0.load(Ordering::Acquire);
+            let slot_idx = (read_idx % num_slots as u64) as usize;
+            let slot_ptr = CommandSlot::at(slots, slot_idx, cmd_payload_size);
+            let slot = &*slot_ptr;
+
+            slot.state
+                .compare_exchange(slot_state::READY, slot_state::PROCESSING, Ordering::AcqRel, Ordering::Relaxed)
+                .ok()?;
+            let next = read_idx.wrapping_add(1);
+            if self.read_idx.0.compare_exchange(read_idx, next, Ordering::AcqRel, Ordering::Relaxed).is_ok() {
+                return Some(slot_ptr);
+            }
+            // The index moved on while we looked, so this is a newer command
+            // than the one we were after; put it back and look again
+            slot.state.store(slot_state::READY, Ordering::Release);
+        }
+    }
 }
 
 /// Producer handle for sending commands
 pub struct MpscProducer {
     header: *const MpscQueueHeader,
-    slots: *mut CommandSlot,
+    slots: *mut u8,
+    num_slots: usize,
+    cmd_payload_size: usize,
+    policy: CmdFullPolicy,
     client_id: u32,
+    /// Sends of this producer that gave up on a full queue
+    rejected_sends: AtomicU64,
+    /// Where the first full queue of each episode is logged
+    events: Option<EventLog>,
+    /// Set by a send finding the queue full, cleared by one getting through
+    in_full_episode: AtomicBool,
 }
 
 // SAFETY: Producers use atomic operations for thread safety
@@ -99,71 +412,269 @@ unsafe impl Send for MpscProducer {}
 impl MpscProducer {
     /// Create a producer from raw pointers
     ///
+    /// The number of slots, their payload size and the full-queue policy
+    /// are read once, here; a policy the header doesn't hold is taken as
+    /// [`CmdFullPolicy::Reject`].
+    ///
     /// # Safety
     /// Pointers must be valid and point to initialized queue
     pub unsafe fn from_raw(header: *const MpscQueueHeader, client_id: u32) -> Self {
-        let slots = (header as *mut u8).add(std::mem::size_of::<MpscQueueHeader>())
-            as *mut CommandSlot;
+        let policy = (*header).cmd_full_policy().unwrap_or_default();
+        Self::with_slots(header, (*header).num_slots(), (*header).cmd_payload_size(), policy, client_id)
+    }
+
+    /// A producer for `num_slots` slots of `cmd_payload_size` bytes, full
+    /// under `policy`, whatever the header says now
+    pub(crate) unsafe fn with_slots(
+        header: *const MpscQueueHeader,
+        num_slots: usize,
+        cmd_payload_size: usize,
+        policy: CmdFullPolicy,
+        client_id: u32,
+    ) -> Self {
         Self {
             header,
-            slots,
+            slots: MpscQueueHeader::slots(header),
+            num_slots,
+            cmd_payload_size,
+            policy,
             client_id,
+            rejected_sends: AtomicU64::new(0),
+            events: None,
+            in_full_episode: AtomicBool::new(false),
         }
     }
 
+    /// Log a [`ChannelEvent::QueueFull`] to `events` when a send finds the
+    /// queue full, once until one gets through again
+    pub(crate) fn with_event_log(self, events: Option<EventLog>) -> Self {
+        Self { events, ..self }
+    }
+
+    /// Longest command this producer can push
+    #[inline]
+    pub fn cmd_payload_size(&self) -> usize {
+        self.cmd_payload_size
+    }
+
+    /// What a push does when the queue is full
+    #[inline]
+    pub fn policy(&self) -> CmdFullPolicy {
+        self.policy
+    }
+
+    /// Pushes of this producer that gave up because the queue was full:
+    /// failed [`try_push`](Self::try_push)es and timed out
+    /// [`push_timeout`](Self::push_timeout)s
+    #[inline]
+    pub fn rejected_sends(&self) -> u64 {
+        self.rejected_sends.load(Ordering::Relaxed)
+    }
+
     /// Try to push a command (non-blocking)
     ///
-    /// Returns `true` if successful, `false` if queue is full
+    /// Returns `true` if successful, `false` if queue is full or the
+    /// command is longer than [`cmd_payload_size`](Self::cmd_payload_size).
+    /// Under [`CmdFullPolicy::OverwriteOldest`] a full queue drops its
+    /// oldest command instead, so only a long command fails.
     #[inline]
     pub fn try_push(&self, cmd: &[u8]) -> bool {
-        if cmd.len() > MAX_CMD_SIZE {
+        if cmd.len() > self.cmd_payload_size {
             return false;
         }
+        if self.push_once(cmd) {
+            return true;
+        }
+        self.record_full();
+        self.rejected_sends.fetch_add(1, Ordering::Relaxed);
+        false
+    }
+
+    /// Push a command, retrying for up to `timeout` while the queue is full
+    ///
+    /// Retries spin at first, then yield, then sleep up to 50 µs at a time,
+    /// so a short wait stays fast and a long one doesn't hold a CPU. Fails
+    /// with [`VenomError::QueueFull`] once `timeout` has passed, and
+    /// [`VenomError::BufferOverflow`] if the command is longer than
+    /// [`cmd_payload_size`](Self::cmd_payload_size). Under
+    /// [`CmdFullPolicy::OverwriteOldest`] it never waits.
+    pub fn push_timeout(&self, cmd: &[u8], timeout: Duration) -> Result<()> {
+        if cmd.len() > self.cmd_payload_size {
+            return Err(VenomError::BufferOverflow { max: self.cmd_payload_size, got: cmd.len() });
+        }
+        if self.push_once(cmd) {
+            return Ok(());
+        }
+        self.record_full();
+
+        let deadline = Instant::now() + timeout;
+        let mut retries = 0u32;
+        loop {
+            let now = Instant::now();
+            if now >= deadline {
+                self.rejected_sends.fetch_add(1, Ordering::Relaxed);
+                return Err(VenomError::QueueFull);
+            }
+            retries = retries.saturating_add(1);
+            if retries <= SPIN_LIMIT {
+                core::hint::spin_loop();
+            } else if retries <= SPIN_LIMIT + YIELD_LIMIT {
+                std::thread::yield_now();
+            } else {
+                std::thread::sleep(RETRY_SLEEP.min(deadline - now));
+            }
+            if self.push_once(cmd) {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Count a send that found the queue full in the header, and log it if
+    /// it's the first since one got through
+    #[inline]
+    fn record_full(&self) {
+        unsafe { (*self.header).total_full_events.fetch_add(1, Ordering::Relaxed) };
+        if let Some(events) = &self.events {
+            if !self.in_full_episode.swap(true, Ordering::Relaxed) {
+                events.log(ChannelEvent::QueueFull { client_id: self.client_id });
+            }
+        }
+    }
+
+    /// Note a send found room, ending the episode [`record_full`](Self::record_full) logged
+    #[inline]
+    fn end_full_episode(&self) {
+        if self.in_full_episode.load(Ordering::Relaxed) {
+            self.in_full_episode.store(false, Ordering::Relaxed);
+        }
+    }
+
+    /// One attempt at pushing a command that fits a slot, counting nothing
+    #[inline]
+    fn push_once(&self, cmd: &[u8]) -> bool {
+        if self.policy == CmdFullPolicy::OverwriteOldest {
+            self.push_overwriting(cmd);
+            return true;
+        }
 
         let header = unsafe { &*self.header };
-        let num_slots = header.num_slots;
 
-        // Claim a slot
+        // Claim the next index only while the queue has room: an index
+        // claimed and left unwritten would hold the consumer up until a
+        // later command filled its slot, out of order
+        let idx = loop {
+            // The read index first: it never passes the write index
+            let read_idx = header.read_idx.0.load(Ordering::Acquire);
+            let idx = header.write_idx.0.load(Ordering::Acquire);
+            if idx.wrapping_sub(read_idx) >= self.num_slots as u64 {
+                return false;
+            }
+            if header
+                .write_idx
+                .0
+                .compare_exchange_weak(idx, idx.wrapping_add(1), Ordering::AcqRel, Ordering::Relaxed)
+                .is_ok()
+            {
+                break idx;
+            }
+        };
+        let slot_idx = (idx % self.num_slots as u64) as usize;
+        let slot_ptr = unsafe { CommandSlot::at(self.slots, slot_idx, self.cmd_payload_size) };
+        let slot = unsafe { &*slot_ptr };
+
+        // The command a lap before ours has been taken, but whoever took it
+        // may not have emptied the slot yet: EMPTY -> WRITING
+        while slot
+            .state
+            .compare_exchange(slot_state::EMPTY, slot_state::WRITING, Ordering::AcqRel, Ordering::Relaxed)
+            .is_err()
+        {
+            // The taker may be on this CPU
+            std::thread::yield_now();
+        }
+
+        // Write client ID and data
+        slot.client_id.store(self.client_id, Ordering::Relaxed);
+        slot.cmd_len.store(cmd.len() as u32, Ordering::Relaxed);
+        unsafe {
+            std::ptr::copy_nonoverlapping(cmd.as_ptr(), CommandSlot::data(slot_ptr), cmd.len());
+        }
+
+        // Publish: WRITING -> READY
+        slot.state.store(slot_state::READY, Ordering::Release);
+        self.end_full_episode();
+        true
+    }
+
+    /// Claim the next index and write `cmd` to its slot, dropping the
+    /// oldest commands until the slot is free
+    ///
+    /// The slot is free once the command `num_slots` before ours has been
+    /// taken and its slot emptied; no one else writes it meanwhile, since
+    /// the next to use it waits for ours to be taken. The only waits are
+    /// for another producer to finish the command being dropped and for a
+    /// taker to empty a slot, never for the consumer to make room; they
+    /// yield the CPU, since that producer or taker may need it.
+    fn push_overwriting(&self, cmd: &[u8]) {
+        let header = unsafe { &*self.header };
         let idx = header.write_idx.0.fetch_add(1, Ordering::AcqRel);
-        let slot_idx = (idx as usize) % num_slots;
-        let slot = unsafe { &*self.slots.add(slot_idx) };
-
-        // Try to transition: EMPTY -> WRITING
-        match slot.state.compare_exchange(
-            slot_state::EMPTY,
-            slot_state::WRITING,
-            Ordering::AcqRel,
-            Ordering::Relaxed,
-        ) {
-            Ok(_) => {
-                // Write client ID and data
-                slot.client_id.store(self.client_id, Ordering::Relaxed);
-                slot.cmd_len.store(cmd.len() as u32, Ordering::Relaxed);
-
-                unsafe {
-                    let slot_ptr = self.slots.add(slot_idx);
-                    std::ptr::copy_nonoverlapping(
-                        cmd.as_ptr(),
-                        (*slot_ptr).cmd_data.as_mut_ptr(),
-                        cmd.len(),
-                    );
-                }
+        let slot_idx = (idx % self.num_slots as u64) as usize;
+        let slot_ptr = unsafe { CommandSlot::at(self.slots, slot_idx, self.cmd_payload_size) };
+        let slot = unsafe { &*slot_ptr };
 
-                // Publish: WRITING -> READY
-                slot.state.store(slot_state::READY, Ordering::Release);
-                true
-            }
-            Err(_) => {
-                // Slot not empty, queue might be full
-                false
+        let mut found_full = false;
+        loop {
+            let read_idx = header.read_idx.0.load(Ordering::Acquire);
+            if idx.wrapping_sub(read_idx) >= self.num_slots as u64 {
+                // Full as far as we are concerned: drop the oldest
+                if !found_full {
+                    found_full = true;
+                    self.record_full();
+                }
+                if let Some(oldest) = unsafe { header.take_oldest(self.slots, self.num_slots, self.cmd_payload_size) } {
+                    header.dropped_commands.fetch_add(1, Ordering::AcqRel);
+                    unsafe { (*oldest).state.store(slot_state::EMPTY, Ordering::Release) };
+                }
+            } else if slot
+                .state
+                .compare_exchange(slot_state::EMPTY, slot_state::WRITING, Ordering::AcqRel, Ordering::Relaxed)
+                .is_ok()
+            {
+                break;
             }
+            // Whoever we wait for may be on this CPU
+            std::thread::yield_now();
+        }
+
+        slot.client_id.store(self.client_id, Ordering::Relaxed);
+        slot.cmd_len.store(cmd.len() as u32, Ordering::Relaxed);
+        unsafe {
+            std::ptr::copy_nonoverlapping(cmd.as_ptr(), CommandSlot::data(slot_ptr), cmd.len());
+        }
+        slot.state.store(slot_state::READY, Ordering::Release);
+        if !found_full {
+            self.end_full_episode();
         }
     }
 
-    /// Push a command, spinning until space is available
+    /// Push a command, spinning until space is available; under
+    /// [`CmdFullPolicy::OverwriteOldest`] it never spins for space
+    ///
+    /// # Panics
+    /// If the command is longer than [`cmd_payload_size`](Self::cmd_payload_size)
     #[inline]
     pub fn push(&self, cmd: &[u8]) {
-        while !self.try_push(cmd) {
+        assert!(
+            cmd.len() <= self.cmd_payload_size,
+            "{}-byte command doesn't fit a {}-byte slot",
+            cmd.len(),
+            self.cmd_payload_size
+        );
+        if self.push_once(cmd) {
+            return;
+        }
+        self.record_full();
+        while !self.push_once(cmd) {
             core::hint::spin_loop();
         }
     }
@@ -172,7 +683,11 @@ impl MpscProducer {
 /// Consumer handle for receiving commands
 pub struct MpscConsumer {
     header: *const MpscQueueHeader,
-    slots: *mut CommandSlot,
+    slots: *mut u8,
+    num_slots: usize,
+    cmd_payload_size: usize,
+    /// Where commands dropped as corrupt are logged
+    events: Option<EventLog>,
 }
 
 // SAFETY: Only one consumer should exist
@@ -181,55 +696,112 @@ unsafe impl Send for MpscConsumer {}
 impl MpscConsumer {
     /// Create a consumer from raw pointer
     ///
+    /// The number of slots and their payload size are read once, here,
+    /// from a header the caller initialized.
+    ///
     /// # Safety
     /// Pointer must be valid and only one consumer should exist
     pub unsafe fn from_raw(header: *const MpscQueueHeader) -> Self {
-        let slots = (header as *mut u8).add(std::mem::size_of::<MpscQueueHeader>())
-            as *mut CommandSlot;
-        Self { header, slots }
+        Self::with_slots(header, (*header).num_slots(), (*header).cmd_payload_size())
+    }
+
+    /// Create a consumer over a region whose header may have been written
+    /// by anyone, checking it first ([`MpscQueueHeader::check_region`])
+    ///
+    /// # Safety
+    /// `region` must be valid for reads and writes of `len` bytes, aligned
+    /// to 64, and only one consumer should exist
+    pub unsafe fn from_region(region: *mut u8, len: usize) -> Result<Self> {
+        let (num_slots, cmd_payload_size, _) = MpscQueueHeader::check_region(region, len)?;
+        Ok(Self::with_slots(region as *const MpscQueueHeader, num_slots, cmd_payload_size))
+    }
+
+    unsafe fn with_slots(header: *const MpscQueueHeader, num_slots: usize, cmd_payload_size: usize) -> Self {
+        Self { header, slots: MpscQueueHeader::slots(header), num_slots, cmd_payload_size, events: None }
+    }
+
+    /// Log a [`ChannelEvent::CorruptCommand`] to `events` for each command
+    /// dropped as corrupt
+    pub(crate) fn with_event_log(self, events: Option<EventLog>) -> Self {
+        Self { events, ..self }
+    }
+
+    /// Count a command dropped as corrupt, and log it
+    fn record_corrupt(&self, client_id: u32) {
+        unsafe { (*self.header).record_corrupt() };
+        if let Some(events) = &self.events {
+            events.log(ChannelEvent::CorruptCommand { client_id });
+        }
+    }
+
+    /// Longest command this consumer takes; longer ones are corrupt
+    #[inline]
+    pub fn cmd_payload_size(&self) -> usize {
+        self.cmd_payload_size
     }
 
     /// Try to pop a command (non-blocking)
     ///
     /// Returns `Some((client_id, data_len))` if a command was read
     /// The data is copied into the provided buffer
+    ///
+    /// A slot claiming more than [`cmd_payload_size`](Self::cmd_payload_size)
+    /// bytes is skipped and the next one tried; [`try_pop_checked`](Self::try_pop_checked) reports
+    /// it instead.
     #[inline]
     pub fn try_pop(&self, buf: &mut [u8]) -> Option<(u32, usize)> {
-        let header = unsafe { &*self.header };
-        let num_slots = header.num_slots;
-
-        let read_idx = header.read_idx.0.load(Ordering::Acquire);
-        let slot_idx = (read_idx as usize) % num_slots;
-        let slot = unsafe { &*self.slots.add(slot_idx) };
+        self.try_pop_valid(buf, |_| true)
+    }
 
-        // Check if slot is ready
-        if slot.state.load(Ordering::Acquire) != slot_state::READY {
-            return None;
+    /// [`try_pop`](Self::try_pop), also skipping commands from senders
+    /// `known` rejects; those count as corrupt slots as well
+    pub fn try_pop_valid(&self, buf: &mut [u8], known: impl Fn(u32) -> bool) -> Option<(u32, usize)> {
+        // Bounded, so producers refilling the queue can't keep it here
+        for _ in 0..=self.num_slots {
+            match self.try_pop_checked(buf) {
+                Ok(Some((client_id, _))) if !known(client_id) => self.record_corrupt(client_id),
+                Ok(popped) => return popped,
+                Err(_) => {}
+            }
         }
+        None
+    }
 
-        // Mark as processing
-        slot.state.store(slot_state::PROCESSING, Ordering::Release);
+    /// Try to pop a command, failing with [`VenomError::CommandTooLarge`]
+    /// on a slot claiming more bytes than a slot holds
+    ///
+    /// The slot is released either way, so the queue moves on, and counted
+    /// in [`MpscQueueHeader::corrupt_slots`]. Nothing in
+    /// the slot or the header is trusted: the index wraps around the number
+    /// of slots the consumer was created with, and at most the payload size
+    /// it was created with is copied.
+    pub fn try_pop_checked(&self, buf: &mut [u8]) -> Result<Option<(u32, usize)>> {
+        let header = unsafe { &*self.header };
+
+        // Producers dropping the oldest command race us for it
+        let Some(slot_ptr) = (unsafe { header.take_oldest(self.slots, self.num_slots, self.cmd_payload_size) }) else {
+            return Ok(None);
+        };
+        let slot = unsafe { &*slot_ptr };
 
         // Read data
         let client_id = slot.client_id.load(Ordering::Relaxed);
         let cmd_len = slot.cmd_len.load(Ordering::Relaxed) as usize;
-        let copy_len = cmd_len.min(buf.len());
-
-        unsafe {
-            std::ptr::copy_nonoverlapping(
-                slot.cmd_data.as_ptr(),
-                buf.as_mut_ptr(),
-                copy_len,
-            );
-        }
+        let result = if cmd_len > self.cmd_payload_size {
+            self.record_corrupt(client_id);
+            Err(VenomError::CommandTooLarge { client_id, len: cmd_len, max: self.cmd_payload_size })
+        } else {
+            let copy_len = cmd_len.min(buf.len());
+            unsafe {
+                std::ptr::copy_nonoverlapping(CommandSlot::data(slot_ptr), buf.as_mut_ptr(), copy_len);
+            }
+            Ok(Some((client_id, cmd_len)))
+        };
 
         // Release slot: PROCESSING -> EMPTY
         slot.state.store(slot_state::EMPTY, Ordering::Release);
 
-        // Advance read index
-        header.read_idx.0.fetch_add(1, Ordering::Release);
-
-        Some((client_id, cmd_len))
+        result
     }
 
     /// Pop a command, spinning until one is available
@@ -263,14 +835,14 @@ mod tests {
     #[test]
     fn test_mpsc_basic() {
         let num_slots = 16;
-        let size = MpscQueueHeader::size_for_slots(num_slots);
+        let size = MpscQueueHeader::size_for_slots(num_slots, DEFAULT_CMD_PAYLOAD_SIZE);
 
         let layout = std::alloc::Layout::from_size_align(size, 64).unwrap();
         let ptr = unsafe { std::alloc::alloc_zeroed(layout) };
         let header = ptr as *mut MpscQueueHeader;
 
         unsafe {
-            MpscQueueHeader::init(header, num_slots);
+            MpscQueueHeader::init(header, num_slots, DEFAULT_CMD_PAYLOAD_SIZE, CmdFullPolicy::Reject);
         }
 
         let producer = unsafe { MpscProducer::from_raw(header, 1) };
@@ -279,6 +851,8 @@ mod tests {
         // Push a command
         let cmd = b"test command";
         assert!(producer.try_push(cmd));
+        let queue = unsafe { &*header };
+        assert_eq!((unsafe { queue.pending(num_slots, DEFAULT_CMD_PAYLOAD_SIZE) }, queue.consumed()), (1, 0));
 
         // Pop it
         let mut buf = [0u8; 256];
@@ -289,9 +863,478 @@ mod tests {
         assert_eq!(client_id, 1);
         assert_eq!(len, cmd.len());
         assert_eq!(&buf[..len], cmd);
+        assert_eq!((unsafe { queue.pending(num_slots, DEFAULT_CMD_PAYLOAD_SIZE) }, queue.consumed()), (0, 1));
 
         unsafe {
             std::alloc::dealloc(ptr, layout);
         }
     }
+
+    /// A zeroed, 64-aligned region of `len` bytes
+    struct Region {
+        ptr: *mut u8,
+        layout: std::alloc::Layout,
+    }
+
+    impl Region {
+        fn new(len: usize) -> Self {
+            let layout = std::alloc::Layout::from_size_align(len, 64).unwrap();
+            Region { ptr: unsafe { std::alloc::alloc_zeroed(layout) }, layout }
+        }
+
+        fn queue(len: usize, num_slots: usize) -> Self {
+            Region::queue_with(len, num_slots, DEFAULT_CMD_PAYLOAD_SIZE, CmdFullPolicy::Reject)
+        }
+
+        fn queue_with(len: usize, num_slots: usize, cmd_payload_size: usize, policy: CmdFullPolicy) -> Self {
+            let region = Region::new(len);
+            unsafe { MpscQueueHeader::init(region.ptr as *mut MpscQueueHeader, num_slots, cmd_payload_size, policy) };
+            region
+        }
+
+        /// A queue `index` commands in
+        fn queue_at(num_slots: usize, cmd_payload_size: usize, policy: CmdFullPolicy, index: u64) -> Self {
+            let region = Region::new(MpscQueueHeader::size_for_slots(num_slots, cmd_payload_size));
+            unsafe {
+                MpscQueueHeader::init_at(region.header(), num_slots, cmd_payload_size, policy, index);
+            }
+            region
+        }
+
+        fn header(&self) -> *mut MpscQueueHeader {
+            self.ptr as *mut MpscQueueHeader
+        }
+
+        fn slot(&self, i: usize) -> &CommandSlot {
+            let cmd_payload_size = unsafe { (*self.header()).cmd_payload_size() };
+            unsafe { &*CommandSlot::at(MpscQueueHeader::slots(self.ptr.cast()), i, cmd_payload_size) }
+        }
+    }
+
+    impl Drop for Region {
+        fn drop(&mut self) {
+            unsafe { std::alloc::dealloc(self.ptr, self.layout) };
+        }
+    }
+
+    #[test]
+    fn test_region_checked() {
+        let size = MpscQueueHeader::size_for_slots(4, DEFAULT_CMD_PAYLOAD_SIZE);
+        let check = |region: &Region, len| unsafe { MpscQueueHeader::check_region(region.ptr, len) };
+
+        assert_eq!(check(&Region::queue(size, 4), size).unwrap(), (4, DEFAULT_CMD_PAYLOAD_SIZE, CmdFullPolicy::Reject));
+        assert!(check(&Region::queue(size, 4), size - 1).is_err(), "truncated slots");
+        assert!(check(&Region::queue(size, 4), std::mem::size_of::<MpscQueueHeader>() - 1).is_err(), "truncated header");
+        assert!(check(&Region::queue(size, 0), size).is_err());
+        let odd = Region::queue(size, 4);
+        unsafe { (*odd.header()).cmd_payload_size = 100 };
+        assert!(matches!(check(&odd, size), Err(VenomError::CorruptRegion { .. })), "payload off the cache line");
+        let overwriting = Region::queue_with(size, 4, DEFAULT_CMD_PAYLOAD_SIZE, CmdFullPolicy::OverwriteOldest);
+        assert_eq!(check(&overwriting, size).unwrap().2, CmdFullPolicy::OverwriteOldest);
+        unsafe { (*overwriting.header()).cmd_full_policy = 2 };
+        assert!(matches!(check(&overwriting, size), Err(VenomError::CorruptRegion { .. })), "unknown policy");
+
+        let big = MpscQueueHeader::size_for_slots(MAX_SLOTS + 1, DEFAULT_CMD_PAYLOAD_SIZE);
+        assert_eq!(check(&Region::queue(big, MAX_SLOTS), big).unwrap().0, MAX_SLOTS);
+        assert!(matches!(check(&Region::queue(big, MAX_SLOTS + 1), big), Err(VenomError::CorruptRegion { .. })));
+    }
+
+    #[test]
+    fn test_command_length_checked() {
+        let size = MpscQueueHeader::size_for_slots(2, DEFAULT_CMD_PAYLOAD_SIZE);
+        let region = Region::queue(size, 2);
+        let consumer = unsafe { MpscConsumer::from_region(region.ptr, size).unwrap() };
+        let forge = |i: usize, len: u32| {
+            let slot = region.slot(i);
+            slot.cmd_len.store(len, Ordering::Relaxed);
+            slot.client_id.store(7, Ordering::Relaxed);
+            slot.state.store(slot_state::READY, Ordering::Release);
+        };
+
+        // A full slot is fine, even into a buffer that can't take it all
+        let mut buf = vec![0u8; MAX_CMD_SIZE + 64];
+        forge(0, MAX_CMD_SIZE as u32);
+        assert_eq!(consumer.try_pop_checked(&mut buf[..3]).unwrap(), Some((7, MAX_CMD_SIZE)));
+
+        // One byte more is dropped, and the queue goes on past it
+        forge(1, MAX_CMD_SIZE as u32 + 1);
+        forge(0, 0);
+        match consumer.try_pop_checked(&mut buf) {
+            Err(VenomError::CommandTooLarge { client_id: 7, len, max: MAX_CMD_SIZE }) => assert_eq!(len, MAX_CMD_SIZE + 1),
+            other => panic!("expected CommandTooLarge, got {:?}", other),
+        }
+        assert_eq!(region.slot(1).state.load(Ordering::Acquire), slot_state::EMPTY);
+        assert_eq!(consumer.try_pop(&mut buf), Some((7, 0)));
+
+        forge(1, u32::MAX);
+        assert_eq!(consumer.try_pop(&mut buf), None);
+        let header = unsafe { &*(region.ptr as *const MpscQueueHeader) };
+        assert_eq!((header.consumed(), header.corrupt_slots()), (4, 2));
+    }
+
+    #[test]
+    fn test_corrupt_slots_skipped() {
+        let size = MpscQueueHeader::size_for_slots(4, DEFAULT_CMD_PAYLOAD_SIZE);
+        let region = Region::queue(size, 4);
+        let consumer = unsafe { MpscConsumer::from_region(region.ptr, size).unwrap() };
+        let forge = |i: usize, client_id: u32, len: u32| {
+            let slot = region.slot(i);
+            slot.cmd_len.store(len, Ordering::Relaxed);
+            slot.client_id.store(client_id, Ordering::Relaxed);
+            slot.state.store(slot_state::READY, Ordering::Release);
+        };
+        forge(0, 1, u32::MAX);
+        forge(1, 0, 2);
+        forge(2, 1, 3);
+
+        // Both bad slots go in one call, and the good one after them comes out
+        let mut buf = [0u8; 8];
+        assert_eq!(consumer.try_pop_valid(&mut buf, |client_id| client_id != 0), Some((1, 3)));
+        assert_eq!(consumer.try_pop_valid(&mut buf, |_| true), None);
+        let header = unsafe { &*(region.ptr as *const MpscQueueHeader) };
+        assert_eq!((header.consumed(), header.corrupt_slots()), (3, 2));
+    }
+
+    #[test]
+    fn test_shared_counters_not_trusted() {
+        let size = MpscQueueHeader::size_for_slots(2, DEFAULT_CMD_PAYLOAD_SIZE);
+        let region = Region::queue(size, 2);
+        let consumer = unsafe { MpscConsumer::from_region(region.ptr, size).unwrap() };
+        let producer = unsafe { MpscProducer::from_raw(region.ptr as *const MpscQueueHeader, 3) };
+
+        // Rewritten after the handles were made: the index still wraps
+        // around two slots
+        let header = unsafe { &mut *(region.ptr as *mut MpscQueueHeader) };
+        header.num_slots = u64::MAX;
+        header.cmd_payload_size = u64::MAX;
+        header.read_idx.0.store(u64::MAX, Ordering::Relaxed);
+        header.write_idx.0.store(u64::MAX, Ordering::Relaxed);
+        assert!(producer.try_push(b"wraps"));
+        let mut buf = [0u8; 8];
+        assert_eq!(consumer.try_pop(&mut buf), Some((3, 5)));
+        assert_eq!(&buf[..5], b"wraps");
+        assert!(!producer.try_push(&[0; DEFAULT_CMD_PAYLOAD_SIZE + 1]));
+    }
+
+    #[test]
+    fn test_queued_tracks_pushes_and_pops() {
+        let size = MpscQueueHeader::size_for_slots(4, DEFAULT_CMD_PAYLOAD_SIZE);
+        let region = Region::queue(size, 4);
+        let consumer = unsafe { MpscConsumer::from_region(region.ptr, size).unwrap() };
+        let producer = unsafe { MpscProducer::from_raw(region.header(), 1) };
+        let header = unsafe { &*region.header() };
+        // Indices about to wrap around u64
+        header.read_idx.0.store(u64::MAX - 1, Ordering::Relaxed);
+        header.write_idx.0.store(u64::MAX - 1, Ordering::Relaxed);
+
+        let mut buf = [0u8; 8];
+        let mut lens = vec![header.queued(4)];
+        for _ in 0..4 {
+            assert!(producer.try_push(b"x"));
+            lens.push(header.queued(4));
+        }
+        for _ in 0..4 {
+            assert!(consumer.try_pop(&mut buf).is_some());
+            lens.push(header.queued(4));
+        }
+        assert_eq!(lens, [0, 1, 2, 3, 4, 3, 2, 1, 0]);
+        assert_eq!(header.write_idx.0.load(Ordering::Relaxed), 2);
+
+        // Indices further apart than the queue is long read as full
+        header.write_idx.0.store(10, Ordering::Relaxed);
+        assert_eq!(header.queued(4), 4);
+    }
+
+    #[test]
+    fn test_payload_size_rounded() {
+        let sizes: Vec<_> = [1, 8, 64, 65, 512, MAX_CMD_SIZE].into_iter().map(|n| cmd_payload_size(n).unwrap()).collect();
+        assert_eq!(sizes, [64, 64, 64, 128, 512, MAX_CMD_SIZE]);
+        assert!(matches!(cmd_payload_size(0), Err(VenomError::InvalidConfig { .. })));
+        assert!(matches!(cmd_payload_size(MAX_CMD_SIZE + 1), Err(VenomError::InvalidConfig { .. })));
+    }
+
+    #[test]
+    fn test_slots_rounded() {
+        let slots: Vec<_> = [1, 2, 3, 5, 16, 33, MAX_SLOTS].into_iter().map(|n| cmd_slots(n).unwrap()).collect();
+        assert_eq!(slots, [1, 2, 4, 8, 16, 64, MAX_SLOTS]);
+        assert!(matches!(cmd_slots(0), Err(VenomError::InvalidConfig { .. })));
+        assert!(matches!(cmd_slots(MAX_SLOTS + 1), Err(VenomError::InvalidConfig { .. })));
+    }
+
+    /// Three laps of the slots across the wrap of the indices, one and
+    /// several commands at a time: FIFO throughout, and the counters wrap
+    /// with the indices
+    #[test]
+    fn test_indices_wrap() {
+        let start = u64::MAX - 5;
+        for policy in [CmdFullPolicy::Reject, CmdFullPolicy::OverwriteOldest] {
+            let region = Region::queue_at(4, CACHE_LINE_SIZE, policy, start);
+            let consumer = unsafe { MpscConsumer::from_raw(region.header()) };
+            let producer = unsafe { MpscProducer::from_raw(region.header(), 1) };
+            let header = unsafe { &*region.header() };
+
+            let mut buf = [0u8; 8];
+            let mut next = 0u8;
+            let mut popped = Vec::new();
+            for batch in [1, 4, 3, 2, 4] {
+                for _ in 0..batch {
+                    assert!(producer.try_push(&[next]));
+                    next += 1;
+                }
+                assert_eq!(header.queued(4), batch);
+                popped.extend(std::iter::from_fn(|| consumer.try_pop(&mut buf).map(|_| buf[0])));
+            }
+            assert_eq!(popped, (0..next).collect::<Vec<_>>(), "{:?}", policy);
+            assert_eq!(header.write_idx.0.load(Ordering::Relaxed), start.wrapping_add(14));
+            assert_eq!(header.consumed(), start.wrapping_add(14));
+            assert_eq!(unsafe { header.pending(4, CACHE_LINE_SIZE) }, 0);
+        }
+
+        // Overwriting across the wrap drops the oldest, as anywhere else
+        let region = Region::queue_at(4, CACHE_LINE_SIZE, CmdFullPolicy::OverwriteOldest, u64::MAX - 1);
+        let consumer = unsafe { MpscConsumer::from_raw(region.header()) };
+        let producer = unsafe { MpscProducer::from_raw(region.header(), 1) };
+        for i in 0..7u8 {
+            assert!(producer.try_push(&[i]));
+        }
+        let mut buf = [0u8; 8];
+        let popped: Vec<_> = std::iter::from_fn(|| consumer.try_pop(&mut buf).map(|_| buf[0])).collect();
+        assert_eq!(popped, [3, 4, 5, 6]);
+        assert_eq!(unsafe { (*region.header()).dropped_commands() }, 3);
+    }
+
+    /// Producers pushing through the wrap of the indices while the consumer
+    /// takes: every command arrives once, each producer's in order
+    #[test]
+    fn test_indices_wrap_stress() {
+        const PRODUCERS: u32 = 4;
+        const PER_PRODUCER: u32 = 5_000;
+        let start = u64::MAX - u64::from(PRODUCERS * PER_PRODUCER / 2);
+        let region = Region::queue_at(8, CACHE_LINE_SIZE, CmdFullPolicy::Reject, start);
+        let consumer = unsafe { MpscConsumer::from_raw(region.header()) };
+        let producers: Vec<_> =
+            (1..=PRODUCERS).map(|id| unsafe { MpscProducer::from_raw(region.header(), id) }).collect();
+
+        let received = std::thread::scope(|s| {
+            for producer in producers {
+                s.spawn(move || {
+                    for seq in 0..PER_PRODUCER {
+                        while !producer.try_push(&seq.to_ne_bytes()) {
+                            std::thread::yield_now();
+                        }
+                    }
+                });
+            }
+            let mut received = Vec::new();
+            let mut buf = [0u8; CACHE_LINE_SIZE];
+            while received.len() < (PRODUCERS * PER_PRODUCER) as usize {
+                match consumer.try_pop(&mut buf) {
+                    Some((client_id, 4)) => received.push((client_id, u32::from_ne_bytes(buf[..4].try_into().unwrap()))),
+                    Some(other) => panic!("unexpected command {:?}", other),
+                    None => std::thread::yield_now(),
+                }
+            }
+            received
+        });
+
+        let mut next = std::collections::HashMap::new();
+        for (client_id, seq) in received {
+            let expected = next.entry(client_id).or_insert(0);
+            assert_eq!(seq, *expected, "client {}", client_id);
+            *expected += 1;
+        }
+        let header = unsafe { &*region.header() };
+        assert_eq!(header.consumed(), start.wrapping_add(u64::from(PRODUCERS * PER_PRODUCER)));
+        assert_eq!((header.queued(8), unsafe { header.pending(8, CACHE_LINE_SIZE) }), (0, 0));
+    }
+
+    #[test]
+    fn test_payload_sizes_round_trip() {
+        for requested in [8, 512] {
+            let payload = cmd_payload_size(requested).unwrap();
+            let size = MpscQueueHeader::size_for_slots(4, payload);
+            let region = Region::queue_with(size, 4, payload, CmdFullPolicy::Reject);
+            let consumer = unsafe { MpscConsumer::from_region(region.ptr, size).unwrap() };
+            let producer = unsafe { MpscProducer::from_raw(region.ptr as *const MpscQueueHeader, 1) };
+            assert_eq!((producer.cmd_payload_size(), consumer.cmd_payload_size()), (payload, payload));
+
+            // Every slot full to the last byte, each of them distinct
+            let mut buf = [0u8; MAX_CMD_SIZE];
+            for round in 0..3u8 {
+                for len in [0, 1, payload - 1, payload] {
+                    let cmd: Vec<u8> = (0..len).map(|i| (i as u8) ^ round).collect();
+                    assert!(producer.try_push(&cmd));
+                    assert_eq!(consumer.try_pop(&mut buf), Some((1, len)));
+                    assert_eq!(&buf[..len], &cmd[..]);
+                }
+            }
+            assert!(!producer.try_push(&vec![0; payload + 1]), "{}-byte slot", payload);
+            assert_eq!(consumer.try_pop(&mut buf), None);
+
+            // The consumer holds a slot to its own payload size too
+            region.slot(0).cmd_len.store(payload as u32 + 1, Ordering::Relaxed);
+            region.slot(0).state.store(slot_state::READY, Ordering::Release);
+            unsafe { (*region.header()).read_idx.0.store(0, Ordering::Relaxed) };
+            assert!(matches!(consumer.try_pop_checked(&mut buf), Err(VenomError::CommandTooLarge { max, .. }) if max == payload));
+        }
+    }
+
+    #[test]
+    fn test_overwrite_oldest_drops_oldest() {
+        let size = MpscQueueHeader::size_for_slots(2, DEFAULT_CMD_PAYLOAD_SIZE);
+        let region = Region::queue_with(size, 2, DEFAULT_CMD_PAYLOAD_SIZE, CmdFullPolicy::OverwriteOldest);
+        let consumer = unsafe { MpscConsumer::from_region(region.ptr, size).unwrap() };
+        let producer = unsafe { MpscProducer::from_raw(region.header(), 1) };
+        assert_eq!(producer.policy(), CmdFullPolicy::OverwriteOldest);
+
+        for i in 0..5u8 {
+            assert!(producer.try_push(&[i]));
+        }
+        let mut buf = [0u8; 8];
+        let popped: Vec<_> = std::iter::from_fn(|| consumer.try_pop(&mut buf).map(|_| buf[0])).collect();
+        assert_eq!(popped, [3, 4]);
+        let header = unsafe { &*region.header() };
+        assert_eq!((header.consumed(), header.dropped_commands()), (2, 3));
+
+        // Room again: nothing more is dropped
+        producer.push(b"x");
+        assert_eq!(consumer.try_pop(&mut buf), Some((1, 1)));
+        assert_eq!(header.dropped_commands(), 3);
+    }
+
+    #[test]
+    fn test_reject_full_keeps_order() {
+        let size = MpscQueueHeader::size_for_slots(2, DEFAULT_CMD_PAYLOAD_SIZE);
+        let region = Region::queue(size, 2);
+        let consumer = unsafe { MpscConsumer::from_region(region.ptr, size).unwrap() };
+        let producer = unsafe { MpscProducer::from_raw(region.header(), 1) };
+
+        // Failed pushes take no index, so the next command goes right
+        // after the ones already queued
+        let mut buf = [0u8; 8];
+        let mut popped = Vec::new();
+        for i in 0..6u8 {
+            if !producer.try_push(&[i]) {
+                assert_eq!(unsafe { (*region.header()).queued(2) }, 2);
+                assert!(consumer.try_pop(&mut buf).is_some());
+                popped.push(buf[0]);
+                assert!(producer.try_push(&[i]));
+            }
+        }
+        popped.extend(std::iter::from_fn(|| consumer.try_pop(&mut buf).map(|_| buf[0])));
+        assert_eq!(popped, [0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_push_timeout_counts_full_queue() {
+        let size = MpscQueueHeader::size_for_slots(2, DEFAULT_CMD_PAYLOAD_SIZE);
+        let region = Region::queue(size, 2);
+        let consumer = unsafe { MpscConsumer::from_region(region.ptr, size).unwrap() };
+        let producer = unsafe { MpscProducer::from_raw(region.header(), 1) };
+        let header = unsafe { &*region.header() };
+        assert!(producer.try_push(b"a") && producer.try_push(b"b"));
+        assert_eq!((producer.rejected_sends(), header.total_full_events()), (0, 0));
+
+        // Nobody takes commands: the push gives up after its timeout
+        let started = Instant::now();
+        let result = producer.push_timeout(b"c", Duration::from_millis(20));
+        let waited = started.elapsed();
+        assert!(matches!(result, Err(VenomError::QueueFull)));
+        assert!(waited >= Duration::from_millis(20) && waited < Duration::from_millis(500), "waited {:?}", waited);
+        assert!(!producer.try_push(b"d"));
+        assert_eq!((producer.rejected_sends(), header.total_full_events()), (2, 2));
+        assert!(matches!(
+            producer.push_timeout(&[0u8; DEFAULT_CMD_PAYLOAD_SIZE + 1], Duration::ZERO),
+            Err(VenomError::BufferOverflow { .. })
+        ));
+
+        // A push that waits for room and gets it is a full event, not a rejection
+        std::thread::scope(|scope| {
+            scope.spawn(move || {
+                std::thread::sleep(Duration::from_millis(5));
+                consumer.try_pop(&mut [0u8; 8]).unwrap();
+            });
+            producer.push_timeout(b"e", Duration::from_secs(5)).unwrap();
+        });
+        assert_eq!((producer.rejected_sends(), header.total_full_events()), (2, 3));
+        assert!(producer.push_timeout(b"f", Duration::ZERO).is_err());
+        assert_eq!((producer.rejected_sends(), header.total_full_events()), (3, 4));
+    }
+
+    #[test]
+    fn test_overwrite_counts_full_events() {
+        let size = MpscQueueHeader::size_for_slots(2, DEFAULT_CMD_PAYLOAD_SIZE);
+        let region = Region::queue_with(size, 2, DEFAULT_CMD_PAYLOAD_SIZE, CmdFullPolicy::OverwriteOldest);
+        let producer = unsafe { MpscProducer::from_raw(region.header(), 1) };
+        for i in 0..5u8 {
+            producer.push_timeout(&[i], Duration::ZERO).unwrap();
+        }
+        let header = unsafe { &*region.header() };
+        assert_eq!((header.dropped_commands(), header.total_full_events()), (3, 3));
+        assert_eq!(producer.rejected_sends(), 0);
+    }
+
+    /// Producers outrunning a slow consumer: every command arrives once or
+    /// is counted as dropped, and each producer's arrive in order
+    #[test]
+    fn test_overwrite_oldest_stress() {
+        const PRODUCERS: u32 = 4;
+        const PER_PRODUCER: u32 = 20_000;
+        let size = MpscQueueHeader::size_for_slots(8, CACHE_LINE_SIZE);
+        let region = Region::queue_with(size, 8, CACHE_LINE_SIZE, CmdFullPolicy::OverwriteOldest);
+        let consumer = unsafe { MpscConsumer::from_region(region.ptr, size).unwrap() };
+        let producers: Vec<_> =
+            (1..=PRODUCERS).map(|id| unsafe { MpscProducer::from_raw(region.header(), id) }).collect();
+        let done = std::sync::atomic::AtomicBool::new(false);
+        let done = &done;
+
+        let received = std::thread::scope(|s| {
+            let reader = s.spawn(move || {
+                let mut received = Vec::new();
+                let mut buf = [0u8; CACHE_LINE_SIZE];
+                loop {
+                    let finished = done.load(Ordering::Acquire);
+                    match consumer.try_pop(&mut buf) {
+                        Some((client_id, 4)) => {
+                            received.push((client_id, u32::from_ne_bytes(buf[..4].try_into().unwrap())));
+                            let slow = std::time::Instant::now() + std::time::Duration::from_micros(5);
+                            while std::time::Instant::now() < slow {
+                                core::hint::spin_loop();
+                            }
+                        }
+                        Some(other) => panic!("unexpected command {:?}", other),
+                        None if finished => return received,
+                        None => std::thread::yield_now(),
+                    }
+                }
+            });
+            let writers: Vec<_> = producers
+                .into_iter()
+                .map(|producer| {
+                    s.spawn(move || {
+                        for seq in 0..PER_PRODUCER {
+                            assert!(producer.try_push(&seq.to_ne_bytes()));
+                        }
+                    })
+                })
+                .collect();
+            writers.into_iter().for_each(|writer| writer.join().unwrap());
+            done.store(true, Ordering::Release);
+            reader.join().unwrap()
+        });
+
+        let mut last = std::collections::HashMap::new();
+        for &(client_id, seq) in &received {
+            assert!((1..=PRODUCERS).contains(&client_id));
+            if let Some(prev) = last.insert(client_id, seq) {
+                assert!(seq > prev, "client {} sent {} after {}", client_id, seq, prev);
+            }
+        }
+        let header = unsafe { &*region.header() };
+        let dropped = header.dropped_commands();
+        assert!(dropped > 0, "the consumer kept up; nothing was dropped");
+        assert_eq!(received.len() as u64 + dropped, (PRODUCERS * PER_PRODUCER) as u64);
+        assert_eq!(header.consumed(), received.len() as u64);
+        assert_eq!(unsafe { header.pending(8, CACHE_LINE_SIZE) }, 0);
+    }
 }
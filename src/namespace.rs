@@ -0,0 +1,150 @@
+//! Channel namespace validation and scoping
+//!
+//! Channel names end up as POSIX shared memory object names
+//! (`/dev/shm/venom_<name>` on Linux), so they must be a single path
+//! component that fits within `NAME_MAX`. Names are validated up front so
+//! callers get a readable error instead of an opaque `shm_open` failure.
+//!
+//! Names can optionally be scoped per-user (`/venom_{uid}_{name}`) so that
+//! two users running the same project don't collide on a channel called
+//! "monitor".
+
+use crate::error::{Result, VenomError};
+use crate::shm::MAX_NAME_LEN;
+
+/// Room reserved for the `{uid}_` scope prefix (u32::MAX is 10 digits + `_`)
+const USER_SCOPE_RESERVE: usize = 11;
+
+/// Maximum length of a channel namespace in bytes
+///
+/// Leaves room for the per-user scope prefix so a valid name stays valid
+/// whether or not [`ChannelConfig::user_scoped`](crate::ChannelConfig::user_scoped) is set.
+pub const MAX_NAMESPACE_LEN: usize = MAX_NAME_LEN - USER_SCOPE_RESERVE;
+
+/// Check that `name` is usable as a channel namespace
+///
+/// Accepted names are 1..=[`MAX_NAMESPACE_LEN`] bytes of ASCII letters,
+/// digits, `_`, `-` and `.`, and must not start with `.` or `-`.
+pub fn validate(name: &str) -> Result<()> {
+    if name.is_empty() {
+        return Err(invalid("namespace is empty"));
+    }
+
+    if name.len() > MAX_NAMESPACE_LEN {
+        return Err(invalid(format!(
+            "namespace is {} bytes long, max is {}",
+            name.len(),
+            MAX_NAMESPACE_LEN
+        )));
+    }
+
+    if let Some((pos, c)) = name
+        .char_indices()
+        .find(|&(_, c)| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')))
+    {
+        return Err(invalid(format!(
+            "namespace contains invalid character {:?} at position {}",
+            c, pos
+        )));
+    }
+
+    if name.starts_with('.') || name.starts_with('-') {
+        return Err(invalid("namespace must not start with '.' or '-'"));
+    }
+
+    Ok(())
+}
+
+/// Prefix `name` with the current user's uid (`{uid}_{name}`)
+///
+/// Does not validate `name`; use [`resolve`] for that.
+pub fn user_scoped(name: &str) -> String {
+    format!("{}_{}", rustix::process::getuid().as_raw(), name)
+}
+
+/// Validate `name` and apply per-user scoping if requested
+///
+/// Returns the name to hand to the shared memory layer.
+pub fn resolve(name: &str, user_scoped: bool) -> Result<String> {
+    validate(name)?;
+    if user_scoped {
+        Ok(self::user_scoped(name))
+    } else {
+        Ok(name.to_string())
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> VenomError {
+    VenomError::InvalidNamespace {
+        reason: reason.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reason(name: &str) -> String {
+        match validate(name) {
+            Err(VenomError::InvalidNamespace { reason }) => reason,
+            other => panic!("expected InvalidNamespace for {:?}, got {:?}", name, other),
+        }
+    }
+
+    #[test]
+    fn test_validate_accepts_common_names() {
+        for name in [
+            "monitor",
+            "venom_demo",
+            "sensor-1",
+            "audio.v2",
+            "_private",
+            "A9",
+        ] {
+            assert!(validate(name).is_ok(), "{} should be valid", name);
+        }
+        assert!(validate(&"a".repeat(MAX_NAMESPACE_LEN)).is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_empty() {
+        assert!(reason("").contains("empty"));
+    }
+
+    #[test]
+    fn test_validate_rejects_too_long() {
+        let name = "a".repeat(MAX_NAMESPACE_LEN + 1);
+        assert!(reason(&name).contains("max is"));
+    }
+
+    #[test]
+    fn test_validate_rejects_slash() {
+        assert!(reason("foo/bar").contains("'/'"));
+        assert!(reason("/foo").contains("position 0"));
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_charset() {
+        assert!(reason("has space").contains("' '"));
+        assert!(reason("nul\0byte").contains("invalid character"));
+        assert!(reason("caf\u{e9}").contains("invalid character"));
+    }
+
+    #[test]
+    fn test_validate_rejects_leading_dot_or_dash() {
+        assert!(reason(".hidden").contains("must not start"));
+        assert!(reason("..").contains("must not start"));
+        assert!(reason("-flag").contains("must not start"));
+    }
+
+    #[test]
+    fn test_resolve_user_scoped() {
+        let uid = rustix::process::getuid().as_raw();
+        assert_eq!(resolve("monitor", false).unwrap(), "monitor");
+        assert_eq!(
+            resolve("monitor", true).unwrap(),
+            format!("{}_monitor", uid)
+        );
+        assert!(resolve("bad/name", true).is_err());
+    }
+}
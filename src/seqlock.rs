@@ -4,27 +4,65 @@
 //! Readers never block - they simply retry if data changes during read.
 //!
 //! # Performance
-//! - Write: ~10ns (two atomic increments + memcpy)
-//! - Read: ~20-50ns (spin until consistent)
-
+//! Up to 1KB, in `cargo bench --bench seqlock`:
+//! - Write: ~20ns (two atomic increments + memcpy; `seqlock_write`)
+//! - Read: ~10-50ns (spin until consistent; `seqlock_read`)
+//!
+//! # Timestamps
+//! A writer made [`stamped`](SeqLockWriter::stamped) records [`now_ns`] in
+//! the header with every write, inside the same sequence as the data, so a
+//! reader gets the time of the write it copied. The clock is
+//! `CLOCK_MONOTONIC_RAW`: stamps only compare with readings taken on the
+//! same machine.
+//!
+//! # Wrapping
+//! The sequence is a `u64` that goes up by two per write and wraps around
+//! after `u64::MAX`. The write that would leave it at 0 leaves it at 2
+//! instead, so 0 keeps meaning that nothing has been written, and
+//! [`is_newer`] orders two sequences across the wrap.
+
+use crate::error::{Result, VenomError};
+use crate::layout::seqlock::LEN_PREFIX;
+use std::mem::MaybeUninit;
 use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Cache line size for most modern x86_64 CPUs
 const CACHE_LINE_SIZE: usize = 64;
 
+/// Reads [`SeqLockReader::try_read_with_len`] makes of a prefix larger
+/// than the region before it gives up on it
+pub const LEN_RETRIES: u32 = 64;
+
 /// Ensures the wrapped value is on its own cache line
 #[repr(C, align(64))]
 pub struct CacheAligned<T>(pub T);
 
+/// Nanoseconds of `CLOCK_MONOTONIC_RAW`, the clock of write timestamps
+#[inline]
+pub fn now_ns() -> u64 {
+    let ts = rustix::time::clock_gettime(rustix::time::ClockId::MonotonicRaw);
+    ts.tv_sec as u64 * 1_000_000_000 + ts.tv_nsec as u64
+}
+
+/// Whether the write with sequence `sequence` came after the one with
+/// `than`, across the wrap (see [Wrapping](self#wrapping)): of any two
+/// sequences less than `2^63` apart, the later one is newer
+#[inline]
+pub fn is_newer(sequence: u64, than: u64) -> bool {
+    (sequence.wrapping_sub(than) as i64) > 0
+}
+
 /// SeqLock header stored in shared memory
 #[repr(C)]
 pub struct SeqLockHeader {
     /// Sequence number: odd = write in progress, even = stable
-    sequence: CacheAligned<AtomicU64>,
+    pub(crate) sequence: CacheAligned<AtomicU64>,
     /// Size of the data region
-    data_size: usize,
+    pub(crate) data_size: u64,
+    /// [`now_ns`] at the last write; 0 if the writer doesn't stamp
+    pub(crate) timestamp_ns: AtomicU64,
     /// Padding to ensure data starts on cache line boundary
-    _pad: [u8; CACHE_LINE_SIZE - 16],
+    _pad: [u8; CACHE_LINE_SIZE - 24],
 }
 
 impl SeqLockHeader {
@@ -34,13 +72,22 @@ impl SeqLockHeader {
     /// The pointer must point to valid, properly aligned memory
     pub unsafe fn init(ptr: *mut Self, data_size: usize) {
         (*ptr).sequence.0 = AtomicU64::new(0);
-        (*ptr).data_size = data_size;
+        (*ptr).data_size = data_size as u64;
+        (*ptr).timestamp_ns = AtomicU64::new(0);
+    }
+
+    /// Initialize a header as if `sequence / 2` writes had been made, to
+    /// test the wrap without making them
+    #[cfg(test)]
+    pub(crate) unsafe fn init_at(ptr: *mut Self, data_size: usize, sequence: u64) {
+        Self::init(ptr, data_size);
+        (*ptr).sequence.0.store(sequence, Ordering::Relaxed);
     }
 
     /// Get the data size
     #[inline(always)]
     pub fn data_size(&self) -> usize {
-        self.data_size
+        crate::layout::to_usize(self.data_size)
     }
 }
 
@@ -48,6 +95,8 @@ impl SeqLockHeader {
 pub struct SeqLockWriter {
     header: *mut SeqLockHeader,
     data: *mut u8,
+    data_size: usize,
+    stamp: bool,
 }
 
 // SAFETY: SeqLockWriter only used by single writer
@@ -60,8 +109,64 @@ impl SeqLockWriter {
     /// - `header` must point to a valid, initialized SeqLockHeader
     /// - `data` must point to the data region immediately after the header
     /// - Only one SeqLockWriter should exist at a time
+    ///
+    /// The size of the data region is read once, here.
     pub unsafe fn from_raw(header: *mut SeqLockHeader, data: *mut u8) -> Self {
-        Self { header, data }
+        Self { header, data, data_size: (*header).data_size(), stamp: false }
+    }
+
+    /// Record a timestamp with every write (see [Timestamps](self#timestamps))
+    pub fn stamped(mut self, stamp: bool) -> Self {
+        self.stamp = stamp;
+        self
+    }
+
+    /// Size of the data region in bytes
+    #[inline(always)]
+    pub fn data_size(&self) -> usize {
+        self.data_size
+    }
+
+    /// Start of the data region, for layouts of its own within it
+    pub(crate) fn data_ptr(&self) -> *mut u8 {
+        self.data
+    }
+
+    /// Start a writer on a new region of `data_size` bytes, at least this
+    /// one's: its header takes this region's sequence and timestamp and the
+    /// data is copied over, so a reader moving to it sees the same write
+    ///
+    /// # Safety
+    /// `header` must be valid for a SeqLockHeader followed by `data_size`
+    /// bytes that nothing reads yet
+    pub(crate) unsafe fn successor(&self, header: *mut SeqLockHeader, data_size: usize) -> Self {
+        let current = &*self.header;
+        SeqLockHeader::init(header, data_size);
+        (*header).sequence.0.store(current.sequence.0.load(Ordering::Relaxed), Ordering::Relaxed);
+        (*header).timestamp_ns.store(current.timestamp_ns.load(Ordering::Relaxed), Ordering::Relaxed);
+
+        let data = header.cast::<u8>().add(std::mem::size_of::<SeqLockHeader>());
+        std::ptr::copy_nonoverlapping(self.data, data, self.data_size.min(data_size));
+        Self::from_raw(header, data).stamped(self.stamp)
+    }
+
+    /// Sequence made odd: a write has started; returns what
+    /// [`end`](Self::end) adds to make it even again
+    #[inline(always)]
+    fn begin(&self, header: &SeqLockHeader) -> u64 {
+        let before = header.sequence.0.fetch_add(1, Ordering::Release);
+        if self.stamp {
+            header.timestamp_ns.store(now_ns(), Ordering::Relaxed);
+        }
+        // Past 0 to 2 when the sequence wraps: 0 is never written to
+        if before == u64::MAX - 1 { 3 } else { 1 }
+    }
+
+    /// Sequence made even by `step` from [`begin`](Self::begin): the write
+    /// is complete
+    #[inline(always)]
+    fn end(&self, header: &SeqLockHeader, step: u64) {
+        header.sequence.0.fetch_add(step, Ordering::Release);
     }
 
     /// Write data to the shared region
@@ -73,12 +178,12 @@ impl SeqLockWriter {
     #[inline]
     pub fn write(&self, data: &[u8]) {
         let header = unsafe { &*self.header };
-        let max_size = header.data_size;
+        let max_size = self.data_size;
 
         let len = data.len().min(max_size);
 
         // Increment to odd - write in progress
-        header.sequence.0.fetch_add(1, Ordering::Release);
+        let step = self.begin(header);
 
         // Write data
         unsafe {
@@ -89,31 +194,64 @@ impl SeqLockWriter {
         std::sync::atomic::fence(Ordering::Release);
 
         // Increment to even - write complete
-        header.sequence.0.fetch_add(1, Ordering::Release);
+        self.end(header, step);
     }
 
     /// Write with length prefix (for variable-size data)
     #[inline]
     pub fn write_with_len(&self, data: &[u8]) {
         let header = unsafe { &*self.header };
-        let max_size = header.data_size;
+        // Too small for a prefix: there's nowhere to write
+        let Some(capacity) = self.data_size.checked_sub(LEN_PREFIX) else { return };
 
-        let len = data.len().min(max_size - 8);
+        let len = data.len().min(capacity);
 
         // Increment to odd
-        header.sequence.0.fetch_add(1, Ordering::Release);
+        let step = self.begin(header);
 
         // Write length + data
         unsafe {
             let len_bytes = (len as u64).to_le_bytes();
-            std::ptr::copy_nonoverlapping(len_bytes.as_ptr(), self.data, 8);
-            std::ptr::copy_nonoverlapping(data.as_ptr(), self.data.add(8), len);
+            std::ptr::copy_nonoverlapping(len_bytes.as_ptr(), self.data, LEN_PREFIX);
+            std::ptr::copy_nonoverlapping(data.as_ptr(), self.data.add(LEN_PREFIX), len);
         }
 
         std::sync::atomic::fence(Ordering::Release);
 
         // Increment to even
-        header.sequence.0.fetch_add(1, Ordering::Release);
+        self.end(header, step);
+    }
+
+    /// Write `parts` one after another with a length prefix of their total,
+    /// as [`write_with_len`](Self::write_with_len) would write them
+    /// concatenated, without concatenating them first
+    ///
+    /// Parts past what the region holds are cut, the prefix with them.
+    #[inline]
+    pub fn write_vectored(&self, parts: &[&[u8]]) {
+        let header = unsafe { &*self.header };
+        // Too small for a prefix: there's nowhere to write
+        let Some(capacity) = self.data_size.checked_sub(LEN_PREFIX) else { return };
+
+        let total: usize = parts.iter().map(|part| part.len()).sum();
+        let len = total.min(capacity);
+
+        let step = self.begin(header);
+
+        unsafe {
+            let len_bytes = (len as u64).to_le_bytes();
+            std::ptr::copy_nonoverlapping(len_bytes.as_ptr(), self.data, LEN_PREFIX);
+            let mut offset = 0;
+            for part in parts {
+                let n = part.len().min(len - offset);
+                std::ptr::copy_nonoverlapping(part.as_ptr(), self.data.add(LEN_PREFIX + offset), n);
+                offset += n;
+            }
+        }
+
+        std::sync::atomic::fence(Ordering::Release);
+
+        self.end(header, step);
     }
 }
 
@@ -121,6 +259,7 @@ impl SeqLockWriter {
 pub struct SeqLockReader {
     header: *const SeqLockHeader,
     data: *const u8,
+    data_size: usize,
 }
 
 // SAFETY: SeqLockReader is read-only and uses atomic operations
@@ -133,8 +272,44 @@ impl SeqLockReader {
     /// # Safety
     /// - `header` must point to a valid SeqLockHeader
     /// - `data` must point to the data region
+    ///
+    /// The size of the data region is read once, here: whoever else has the
+    /// region mapped can't make the reader copy past it later.
     pub unsafe fn from_raw(header: *const SeqLockHeader, data: *const u8) -> Self {
-        Self { header, data }
+        Self { header, data, data_size: (*header).data_size() }
+    }
+
+    /// Size of the data region in bytes
+    #[inline(always)]
+    pub fn data_size(&self) -> usize {
+        self.data_size
+    }
+
+    /// Start of the data region, for layouts of its own within it
+    pub(crate) fn data_ptr(&self) -> *const u8 {
+        self.data
+    }
+
+    /// Bytes a length-prefixed write can hold
+    #[inline(always)]
+    fn capacity(&self) -> usize {
+        self.data_size.saturating_sub(LEN_PREFIX)
+    }
+
+    /// Current sequence number (odd while a write is in progress)
+    ///
+    /// Changes on every write, so it can be used to detect new data
+    /// without copying it.
+    #[inline(always)]
+    pub fn sequence(&self) -> u64 {
+        unsafe { (*self.header).sequence.0.load(Ordering::Acquire) }
+    }
+
+    /// Timestamp of the last write ([`now_ns`]); 0 if the writer doesn't
+    /// stamp its writes
+    #[inline(always)]
+    pub fn timestamp(&self) -> u64 {
+        unsafe { (*self.header).timestamp_ns.load(Ordering::Acquire) }
     }
 
     /// Read data from the shared region
@@ -143,8 +318,36 @@ impl SeqLockReader {
     /// Returns the number of bytes read.
     #[inline]
     pub fn read(&self, buf: &mut [u8]) -> usize {
-        let header = unsafe { &*self.header };
-        let max_size = header.data_size.min(buf.len());
+        self.read_stamped(buf).1
+    }
+
+    /// [`read`](Self::read), with the timestamp of the write the data
+    /// comes from (0 if the writer doesn't stamp)
+    #[inline]
+    pub fn read_stamped(&self, buf: &mut [u8]) -> (u64, usize) {
+        // SAFETY: buf is valid for buf.len() bytes of writes
+        unsafe { self.read_raw(buf.as_mut_ptr(), buf.len()) }
+    }
+
+    /// [`read`](Self::read) into a buffer that needn't be initialized
+    ///
+    /// Returns the number of bytes read, all of them initialized: `buf`'s
+    /// length or the region's size, whichever is smaller.
+    #[inline]
+    pub fn read_uninit(&self, buf: &mut [MaybeUninit<u8>]) -> usize {
+        // SAFETY: as for read_stamped; the copy only writes to buf
+        unsafe { self.read_raw(buf.as_mut_ptr().cast(), buf.len()).1 }
+    }
+
+    /// Copy up to `buf_len` bytes of a consistent write to `buf`, returning
+    /// the write's timestamp and the bytes copied
+    ///
+    /// # Safety
+    /// `buf` must be valid for `buf_len` bytes of writes
+    #[inline(always)]
+    unsafe fn read_raw(&self, buf: *mut u8, buf_len: usize) -> (u64, usize) {
+        let header = &*self.header;
+        let max_size = self.data_size.min(buf_len);
 
         loop {
             // Read sequence (must be even = no write in progress)
@@ -154,11 +357,10 @@ impl SeqLockReader {
                 core::hint::spin_loop();
                 continue;
             }
+            let timestamp = header.timestamp_ns.load(Ordering::Relaxed);
 
             // Read data
-            unsafe {
-                std::ptr::copy_nonoverlapping(self.data, buf.as_mut_ptr(), max_size);
-            }
+            std::ptr::copy_nonoverlapping(self.data, buf, max_size);
 
             // Memory fence
             std::sync::atomic::fence(Ordering::Acquire);
@@ -167,7 +369,7 @@ impl SeqLockReader {
             let seq2 = header.sequence.0.load(Ordering::Acquire);
             if seq1 == seq2 {
                 // Consistent read!
-                return max_size;
+                return (timestamp, max_size);
             }
 
             // Sequence changed, retry
@@ -177,9 +379,70 @@ impl SeqLockReader {
 
     /// Read data with length prefix
     ///
-    /// Returns the actual data length (may be larger than buffer)
+    /// Returns the actual data length (may be larger than buffer). The
+    /// prefix is whatever is in shared memory: one larger than the region
+    /// can hold is cut to what it holds, and only that much is copied;
+    /// [`read_with_len_checked`](Self::read_with_len_checked) reports it.
     #[inline]
     pub fn read_with_len(&self, buf: &mut [u8]) -> usize {
+        self.read_prefixed(buf).0.min(self.capacity() as u64) as usize
+    }
+
+    /// Read data with length prefix, failing with
+    /// [`VenomError::InvalidLength`] if the prefix is larger than the
+    /// region can hold
+    #[inline]
+    pub fn read_with_len_checked(&self, buf: &mut [u8]) -> Result<usize> {
+        let (len, _, _) = self.read_prefixed(buf);
+        if len > self.capacity() as u64 {
+            return Err(VenomError::InvalidLength { len, max: self.capacity() });
+        }
+        Ok(len as usize)
+    }
+
+    /// Read data with length prefix, treating a prefix larger than the
+    /// region holds like a torn read: read again, and only after
+    /// [`LEN_RETRIES`] reads that all saw one fail with
+    /// [`VenomError::Corrupted`]
+    ///
+    /// Returns the actual data length (may be larger than buffer).
+    #[inline]
+    pub fn try_read_with_len(&self, buf: &mut [u8]) -> Result<usize> {
+        self.try_read_with_len_stamped(buf).map(|(_, len)| len)
+    }
+
+    /// [`try_read_with_len`](Self::try_read_with_len), with the timestamp
+    /// of the write the data comes from (0 if the writer doesn't stamp)
+    #[inline]
+    pub fn try_read_with_len_stamped(&self, buf: &mut [u8]) -> Result<(u64, usize)> {
+        self.try_read_with_len_sequenced(buf).map(|(_, timestamp, len)| (timestamp, len))
+    }
+
+    /// [`try_read_with_len_stamped`](Self::try_read_with_len_stamped), with
+    /// the sequence number of the write first: `(sequence, timestamp, len)`,
+    /// all three from the same read
+    #[inline]
+    pub fn try_read_with_len_sequenced(&self, buf: &mut [u8]) -> Result<(u64, u64, usize)> {
+        let mut len = 0;
+        for _ in 0..LEN_RETRIES {
+            let (timestamp, sequence);
+            (len, timestamp, sequence) = self.read_prefixed(buf);
+            if len <= self.capacity() as u64 {
+                return Ok((sequence, timestamp, len as usize));
+            }
+            core::hint::spin_loop();
+        }
+        Err(VenomError::Corrupted { len, max: self.capacity(), attempts: LEN_RETRIES })
+    }
+
+    /// The length prefix as written, the write's timestamp and its sequence
+    /// number, with up to as many bytes as the prefix says, the buffer
+    /// takes and the region holds copied into `buf`
+    fn read_prefixed(&self, buf: &mut [u8]) -> (u64, u64, u64) {
+        // Too small for a prefix: nothing can have been written with one
+        if self.data_size < LEN_PREFIX {
+            return (0, self.timestamp(), self.sequence());
+        }
         let header = unsafe { &*self.header };
 
         loop {
@@ -188,26 +451,27 @@ impl SeqLockReader {
                 core::hint::spin_loop();
                 continue;
             }
+            let timestamp = header.timestamp_ns.load(Ordering::Relaxed);
 
             // Read length
             let len = unsafe {
-                let mut len_bytes = [0u8; 8];
-                std::ptr::copy_nonoverlapping(self.data, len_bytes.as_mut_ptr(), 8);
-                u64::from_le_bytes(len_bytes) as usize
+                let mut len_bytes = [0u8; LEN_PREFIX];
+                std::ptr::copy_nonoverlapping(self.data, len_bytes.as_mut_ptr(), LEN_PREFIX);
+                u64::from_le_bytes(len_bytes)
             };
 
-            let copy_len = len.min(buf.len());
+            let copy_len = len.min(self.capacity() as u64).min(buf.len() as u64) as usize;
 
             // Read data
             unsafe {
-                std::ptr::copy_nonoverlapping(self.data.add(8), buf.as_mut_ptr(), copy_len);
+                std::ptr::copy_nonoverlapping(self.data.add(LEN_PREFIX), buf.as_mut_ptr(), copy_len);
             }
 
             std::sync::atomic::fence(Ordering::Acquire);
 
             let seq2 = header.sequence.0.load(Ordering::Acquire);
             if seq1 == seq2 {
-                return len;
+                return (len, timestamp, seq1);
             }
 
             core::hint::spin_loop();
@@ -220,7 +484,7 @@ impl SeqLockReader {
     #[inline]
     pub fn try_read(&self, buf: &mut [u8]) -> Option<usize> {
         let header = unsafe { &*self.header };
-        let max_size = header.data_size.min(buf.len());
+        let max_size = self.data_size.min(buf.len());
 
         let seq1 = header.sequence.0.load(Ordering::Acquire);
         if seq1 & 1 == 1 {
@@ -245,8 +509,6 @@ impl SeqLockReader {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::sync::Arc;
-    use std::thread;
 
     #[test]
     fn test_seqlock_basic() {
@@ -275,10 +537,221 @@ mod tests {
         // Read
         let mut buf = [0u8; 64];
         let len = reader.read(&mut buf);
+        assert!(len >= test_data.len());
         assert_eq!(&buf[..test_data.len()], test_data);
 
         unsafe {
             std::alloc::dealloc(ptr, layout);
         }
     }
+
+    /// A SeqLock over `data` (the region as a writer left it), followed by
+    /// a guard area the readers must never copy from
+    struct Region {
+        ptr: *mut u8,
+        layout: std::alloc::Layout,
+        reader: SeqLockReader,
+    }
+
+    const GUARD: u8 = 0xAA;
+
+    impl Region {
+        fn new(data: &[u8]) -> Self {
+            let header_size = std::mem::size_of::<SeqLockHeader>();
+            let layout = std::alloc::Layout::from_size_align(header_size + data.len() + 64, 64).unwrap();
+            unsafe {
+                let ptr = std::alloc::alloc_zeroed(layout);
+                SeqLockHeader::init(ptr as *mut SeqLockHeader, data.len());
+                let data_ptr = ptr.add(header_size);
+                std::ptr::copy_nonoverlapping(data.as_ptr(), data_ptr, data.len());
+                std::ptr::write_bytes(data_ptr.add(data.len()), GUARD, 64);
+                let reader = SeqLockReader::from_raw(ptr as *const SeqLockHeader, data_ptr);
+                Region { ptr, layout, reader }
+            }
+        }
+    }
+
+    impl Drop for Region {
+        fn drop(&mut self) {
+            unsafe { std::alloc::dealloc(self.ptr, self.layout) };
+        }
+    }
+
+    /// A 64-byte region whose prefix says `len`, the payload all ones
+    fn prefixed(len: u64) -> Region {
+        let mut data = len.to_le_bytes().to_vec();
+        data.resize(64, 1);
+        Region::new(&data)
+    }
+
+    #[test]
+    fn test_length_prefix_bounds() {
+        let mut buf = [0u8; 128];
+
+        // Zero, and exactly what the region holds
+        assert_eq!(prefixed(0).reader.read_with_len_checked(&mut buf).unwrap(), 0);
+        assert_eq!(prefixed(56).reader.read_with_len_checked(&mut buf).unwrap(), 56);
+        assert_eq!(&buf[..57], &[[1u8; 56].as_slice(), &[0]].concat());
+
+        // One more, or anything up to u64::MAX, is cut to the region
+        for len in [57, 1 << 32, u64::MAX] {
+            let region = prefixed(len);
+            buf.fill(0);
+            assert_eq!(region.reader.read_with_len(&mut buf), 56);
+            assert!(!buf.contains(&GUARD), "copied past the region");
+            match region.reader.read_with_len_checked(&mut buf) {
+                Err(VenomError::InvalidLength { len: got, max: 56 }) => assert_eq!(got, len),
+                other => panic!("expected InvalidLength, got {:?}", other),
+            }
+        }
+
+        // A buffer smaller than the data takes what fits; the length is
+        // still the whole payload's
+        let mut small = [0u8; 4];
+        assert_eq!(prefixed(56).reader.read_with_len(&mut small), 56);
+        assert_eq!(prefixed(u64::MAX).reader.read_with_len(&mut []), 56);
+    }
+
+    #[test]
+    fn test_write_timestamps() {
+        let region = Region::new(&[0u8; 64]);
+        let header = region.ptr as *mut SeqLockHeader;
+        let data = unsafe { region.ptr.add(std::mem::size_of::<SeqLockHeader>()) };
+        let mut buf = [0u8; 64];
+
+        let plain = unsafe { SeqLockWriter::from_raw(header, data) };
+        plain.write_with_len(b"plain");
+        assert_eq!(region.reader.try_read_with_len_stamped(&mut buf).unwrap(), (0, 5));
+
+        let stamped = unsafe { SeqLockWriter::from_raw(header, data) }.stamped(true);
+        let mut last = 0;
+        for _ in 0..3 {
+            let before = now_ns();
+            stamped.write(b"stamped");
+            let (timestamp, _) = region.reader.read_stamped(&mut buf);
+            assert!(timestamp >= before && timestamp <= now_ns() && timestamp >= last);
+            assert_eq!(region.reader.timestamp(), timestamp);
+            last = timestamp;
+        }
+    }
+
+    #[test]
+    fn test_try_read_with_len() {
+        let mut buf = [0u8; 128];
+        assert_eq!(prefixed(56).reader.try_read_with_len(&mut buf).unwrap(), 56);
+
+        for len in [57, u64::MAX] {
+            let region = prefixed(len);
+            buf.fill(0);
+            match region.reader.try_read_with_len(&mut buf) {
+                Err(VenomError::Corrupted { len: got, max: 56, attempts: LEN_RETRIES }) => assert_eq!(got, len),
+                other => panic!("expected Corrupted, got {:?}", other),
+            }
+            assert!(!buf.contains(&GUARD), "copied past the region");
+        }
+    }
+
+    #[test]
+    fn test_region_smaller_than_prefix() {
+        let mut buf = [0u8; 16];
+        for size in 0..LEN_PREFIX {
+            let region = Region::new(&vec![0xFF; size]);
+            assert_eq!(region.reader.read_with_len(&mut buf), 0);
+            assert_eq!(region.reader.read_with_len_checked(&mut buf).unwrap(), 0);
+            assert!(!buf.contains(&GUARD));
+
+            // Nor is there room to write one
+            let writer = unsafe { SeqLockWriter::from_raw(region.ptr as *mut SeqLockHeader, region.ptr.add(std::mem::size_of::<SeqLockHeader>())) };
+            writer.write_with_len(b"data");
+            writer.write_vectored(&[b"da", b"ta"]);
+            let guard = unsafe { std::slice::from_raw_parts(region.ptr.add(std::mem::size_of::<SeqLockHeader>() + size), 64) };
+            assert!(guard.iter().all(|&b| b == GUARD), "wrote past a {}-byte region", size);
+        }
+    }
+
+    #[test]
+    fn test_data_size_read_once() {
+        let region = prefixed(u64::MAX);
+        unsafe { (*(region.ptr as *mut SeqLockHeader)).data_size = u64::MAX };
+        let mut buf = [0u8; 256];
+        assert_eq!(region.reader.data_size(), 64);
+        assert_eq!(region.reader.read(&mut buf), 64);
+        assert_eq!(region.reader.read_with_len(&mut buf), 56);
+        assert!(!buf.contains(&GUARD));
+    }
+
+    #[test]
+    fn test_write_vectored() {
+        let region = Region::new(&[0u8; 64]);
+        let header = region.ptr as *mut SeqLockHeader;
+        let writer = unsafe { SeqLockWriter::from_raw(header, region.ptr.add(std::mem::size_of::<SeqLockHeader>())) };
+        let mut buf = [0u8; 128];
+
+        // Empty parts anywhere, and a part ending exactly where the next starts
+        writer.write_vectored(&[b"", b"head", b"", b"er", b"body", b""]);
+        assert_eq!(region.reader.read_with_len_checked(&mut buf).unwrap(), 10);
+        assert_eq!(&buf[..10], b"headerbody");
+
+        // Nothing at all
+        writer.write_vectored(&[]);
+        assert_eq!(region.reader.read_with_len_checked(&mut buf).unwrap(), 0);
+
+        // Exactly the capacity, then one past it: the part straddling the
+        // end is cut and the ones after it skipped
+        writer.write_vectored(&[&[1; 50], &[2; 6]]);
+        assert_eq!(region.reader.read_with_len_checked(&mut buf).unwrap(), 56);
+        writer.write_vectored(&[&[3; 50], &[4; 7], &[5; 10]]);
+        assert_eq!(region.reader.read_with_len_checked(&mut buf).unwrap(), 56);
+        assert_eq!(&buf[..56], [[3u8; 50].as_slice(), &[4; 6]].concat());
+        assert!(!buf.contains(&GUARD));
+
+        // The same bytes write_with_len writes
+        let mut prefixed = [0u8; 64];
+        writer.write_with_len(b"headerbody");
+        region.reader.read(&mut prefixed);
+        writer.write_vectored(&[b"header", b"body"]);
+        let mut vectored = [0u8; 64];
+        region.reader.read(&mut vectored);
+        assert_eq!(prefixed[..18], vectored[..18]);
+    }
+
+    #[test]
+    fn test_sequence_wraps() {
+        let region = Region::new(&[0u8; 64]);
+        let header = region.ptr as *mut SeqLockHeader;
+        let writer = unsafe {
+            SeqLockHeader::init_at(header, 64, u64::MAX - 5);
+            SeqLockWriter::from_raw(header, region.ptr.add(std::mem::size_of::<SeqLockHeader>()))
+        };
+        let mut buf = [0u8; 64];
+
+        // Two writes apart each time, past 0 from the write that would land on it
+        let mut last = region.reader.sequence();
+        for (i, expected) in [u64::MAX - 3, u64::MAX - 1, 2, 4].into_iter().enumerate() {
+            writer.write_with_len(&[i as u8; 3]);
+            let (sequence, _, len) = region.reader.try_read_with_len_sequenced(&mut buf).unwrap();
+            assert_eq!((sequence, len, buf[0]), (expected, 3, i as u8));
+            assert!(is_newer(sequence, last) && !is_newer(last, sequence));
+            last = sequence;
+        }
+        assert_eq!(region.reader.try_read(&mut buf), Some(64));
+
+        assert!(!is_newer(2, 2));
+        assert!(is_newer(2, u64::MAX - 1) && is_newer(4, 2));
+    }
+
+    #[test]
+    fn test_read_uninit() {
+        let region = Region::new(&[9u8; 64]);
+        let mut buf = [MaybeUninit::<u8>::uninit(); 128];
+        assert_eq!(region.reader.read_uninit(&mut buf), 64);
+        assert!(buf[..64].iter().all(|byte| unsafe { byte.assume_init() } == 9));
+        assert_eq!(region.reader.read_uninit(&mut buf[..10]), 10);
+
+        // Into a Vec's spare capacity, without zeroing it first
+        let mut vec = Vec::with_capacity(256);
+        let len = region.reader.read_uninit(vec.spare_capacity_mut());
+        unsafe { vec.set_len(len) };
+        assert_eq!(vec, [9u8; 64]);
+    }
 }
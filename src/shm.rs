@@ -8,17 +8,72 @@ use rustix::shm::{shm_open, shm_unlink, Mode, ShmOFlags};
 use std::ffi::CString;
 use std::ptr::NonNull;
 
-const VENOM_SHM_PREFIX: &str = "/venom_";
-const MAX_NAME_LEN: usize = 255 - VENOM_SHM_PREFIX.len();
+pub(crate) const VENOM_SHM_PREFIX: &str = "/venom_";
+pub(crate) const MAX_NAME_LEN: usize = 255 - VENOM_SHM_PREFIX.len();
+
+/// Where Linux keeps shared memory objects
+const SHM_DIR: &str = "/dev/shm";
+
+/// Who may open a shared memory object [`VenomShm::create_with`] makes
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShmPermissions {
+    /// Permission bits (`0o660`: owner and group read and write); applied
+    /// as given, whatever the umask. None for
+    /// [`DEFAULT_MODE`](Self::DEFAULT_MODE) less the umask, as for files
+    pub mode: Option<u32>,
+    /// Group to give the object to, a name from `/etc/group` or a numeric
+    /// GID; None keeps the creator's. Giving it to a group the creator
+    /// isn't in takes `CAP_CHOWN`.
+    pub group: Option<String>,
+}
+
+impl ShmPermissions {
+    /// Owner and group read and write, everyone else read: the mode asked
+    /// for when `mode` is None, before the umask
+    pub const DEFAULT_MODE: u32 = 0o664;
+}
+
+/// Path of the object `name` in [`SHM_DIR`], for messages
+pub(crate) fn shm_path(name: &str) -> String {
+    format!("{}{}{}", SHM_DIR, VENOM_SHM_PREFIX, name)
+}
+
+/// The process's umask, read rather than set: setting it would race with
+/// other tests
+#[cfg(test)]
+pub(crate) fn umask() -> u32 {
+    let status = std::fs::read_to_string("/proc/self/status").unwrap();
+    let umask = status.lines().find_map(|line| line.strip_prefix("Umask:")).unwrap();
+    u32::from_str_radix(umask.trim(), 8).unwrap()
+}
+
+/// GID of `group`, a name from `/etc/group` or a number
+fn group_id(group: &str) -> Result<u32> {
+    if let Ok(gid) = group.parse() {
+        return Ok(gid);
+    }
+    std::fs::read_to_string("/etc/group")
+        .ok()
+        .and_then(|groups| {
+            groups.lines().find_map(|line| {
+                let mut fields = line.split(':');
+                (fields.next()? == group).then(|| fields.nth(1)?.parse().ok()).flatten()
+            })
+        })
+        .ok_or_else(|| VenomError::InvalidConfig { reason: format!("no group named '{}' in /etc/group", group) })
+}
 
 /// Handle to a shared memory region
 pub struct VenomShm {
-    #[allow(dead_code)]
-    fd: OwnedFd,
+    /// None for a region from [`from_raw_parts`](Self::from_raw_parts)
+    fd: Option<OwnedFd>,
     addr: NonNull<u8>,
     size: usize,
     name: String,
     is_owner: bool,
+    read_only: bool,
+    /// Whether drop unmaps the region
+    unmap: bool,
 }
 
 // SAFETY: VenomShm can be safely shared between threads
@@ -29,6 +84,10 @@ unsafe impl Sync for VenomShm {}
 impl VenomShm {
     /// Create a new shared memory region
     ///
+    /// Fails with [`VenomError::ShmCreate`], of kind
+    /// [`AlreadyExists`](std::io::ErrorKind::AlreadyExists), if an object of
+    /// that name exists: another process may be using it.
+    ///
     /// # Arguments
     /// * `name` - Unique name for the shared memory (will be prefixed with "/venom_")
     /// * `size` - Size in bytes
@@ -36,6 +95,17 @@ impl VenomShm {
     /// # Returns
     /// A new VenomShm handle on success
     pub fn create(name: &str, size: usize) -> Result<Self> {
+        Self::create_with(name, size, &ShmPermissions::default())
+    }
+
+    /// [`create`](Self::create), with the object's mode and group set to
+    /// `permissions`
+    ///
+    /// A mode with bits besides the permission bits, or a group that
+    /// doesn't exist, fails with [`VenomError::InvalidConfig`]; a group the
+    /// process may not give the object to fails with
+    /// [`VenomError::PermissionDenied`].
+    pub fn create_with(name: &str, size: usize, permissions: &ShmPermissions) -> Result<Self> {
         if name.len() > MAX_NAME_LEN {
             return Err(VenomError::NamespaceTooLong {
                 max: MAX_NAME_LEN,
@@ -43,32 +113,44 @@ impl VenomShm {
             });
         }
 
+        if let Some(mode) = permissions.mode.filter(|mode| mode & !0o777 != 0) {
+            return Err(VenomError::InvalidConfig {
+                reason: format!("mode {:#o} has bits besides the permission bits", mode),
+            });
+        }
+        let gid = permissions.group.as_deref().map(group_id).transpose()?;
+
         let full_name = format!("{}{}", VENOM_SHM_PREFIX, name);
         let c_name = CString::new(full_name.clone()).unwrap();
 
-        // Try to create exclusively first, fall back to open if exists
-        let fd = match shm_open(
-            c_name.as_c_str(),
-            ShmOFlags::CREATE | ShmOFlags::EXCL | ShmOFlags::RDWR,
-            Mode::RUSR | Mode::WUSR | Mode::RGRP | Mode::WGRP | Mode::ROTH,
-        ) {
-            Ok(fd) => fd,
-            Err(_) => {
-                // Already exists, try to open
-                shm_open(c_name.as_c_str(), ShmOFlags::RDWR, Mode::empty()).map_err(|e| {
-                    VenomError::ShmCreate {
-                        name: name.to_string(),
-                        source: e.into(),
-                    }
-                })?
-            }
-        };
+        let mode = Mode::from_bits_truncate(permissions.mode.unwrap_or(ShmPermissions::DEFAULT_MODE));
+        let fd = shm_open(c_name.as_c_str(), ShmOFlags::CREATE | ShmOFlags::EXCL | ShmOFlags::RDWR, mode)
+            .map_err(|e| VenomError::ShmCreate {
+                name: name.to_string(),
+                source: e.into(),
+            })?;
 
-        // Set size
-        ftruncate(&fd, size as u64).map_err(|e| VenomError::Truncate(e.into()))?;
+        // shm_open applies the umask; fchmod doesn't, for a mode the caller chose
+        let chmod = |_| rustix::fs::fchmod(&fd, mode).map_err(|e| (e, "set its mode".to_string()));
+        let applied = permissions.mode.map_or(Ok(()), chmod).and_then(|()| {
+            gid.map_or(Ok(()), |gid| {
+                rustix::fs::fchown(&fd, None, Some(unsafe { rustix::fs::Gid::from_raw(gid) }))
+                    .map_err(|e| (e, format!("give it to group {}", permissions.group.as_deref().unwrap_or_default())))
+            })
+        });
+        if let Err((errno, action)) = applied {
+            let _ = shm_unlink(c_name.as_c_str());
+            return Err(match errno {
+                rustix::io::Errno::PERM | rustix::io::Errno::ACCESS => {
+                    VenomError::PermissionDenied { path: shm_path(name), reason: format!("can't {}", action) }
+                }
+                errno => VenomError::ShmCreate { name: name.to_string(), source: errno.into() },
+            });
+        }
 
-        // Map to memory
-        let addr = unsafe {
+        // Set size and map to memory; on failure the object goes too, as
+        // with O_EXCL it would refuse every later create of the name
+        let mapped = ftruncate(&fd, size as u64).map_err(|e| VenomError::Truncate(e.into())).and_then(|()| unsafe {
             mmap(
                 std::ptr::null_mut(),
                 size,
@@ -77,7 +159,14 @@ impl VenomShm {
                 &fd,
                 0,
             )
-            .map_err(|e| VenomError::Mmap(e.into()))?
+            .map_err(|e| VenomError::Mmap(e.into()))
+        });
+        let addr = match mapped {
+            Ok(addr) => addr,
+            Err(e) => {
+                let _ = shm_unlink(c_name.as_c_str());
+                return Err(e);
+            }
         };
 
         let addr = NonNull::new(addr.cast::<u8>()).expect("mmap returned null");
@@ -88,24 +177,50 @@ impl VenomShm {
         }
 
         Ok(Self {
-            fd,
+            fd: Some(fd),
             addr,
             size,
             name: name.to_string(),
             is_owner: true,
+            read_only: false,
+            unmap: true,
         })
     }
 
     /// Open an existing shared memory region
     pub fn open(name: &str) -> Result<Self> {
+        Self::open_with(name, false)
+    }
+
+    /// Open an existing shared memory region mapped read-only
+    ///
+    /// Any write through [`as_ptr`](Self::as_ptr) faults.
+    pub fn open_read_only(name: &str) -> Result<Self> {
+        Self::open_with(name, true)
+    }
+
+    fn open_with(name: &str, read_only: bool) -> Result<Self> {
         let full_name = format!("{}{}", VENOM_SHM_PREFIX, name);
         let c_name = CString::new(full_name).unwrap();
 
-        let fd = shm_open(c_name.as_c_str(), ShmOFlags::RDWR, Mode::empty()).map_err(|e| {
-            VenomError::ShmOpen {
+        let (flags, prot) = if read_only {
+            (ShmOFlags::RDONLY, ProtFlags::READ)
+        } else {
+            (ShmOFlags::RDWR, ProtFlags::READ | ProtFlags::WRITE)
+        };
+        let fd = shm_open(c_name.as_c_str(), flags, Mode::empty()).map_err(|e| match e {
+            rustix::io::Errno::ACCESS => VenomError::PermissionDenied {
+                path: shm_path(name),
+                reason: if read_only {
+                    "can't open it for reading".to_string()
+                } else {
+                    "can't open it for reading and writing; a read-only connection may still be allowed".to_string()
+                },
+            },
+            e => VenomError::ShmOpen {
                 name: name.to_string(),
                 source: e.into(),
-            }
+            },
         })?;
 
         // Get size from file
@@ -120,7 +235,7 @@ impl VenomShm {
             mmap(
                 std::ptr::null_mut(),
                 size,
-                ProtFlags::READ | ProtFlags::WRITE,
+                prot,
                 MapFlags::SHARED,
                 &fd,
                 0,
@@ -131,11 +246,94 @@ impl VenomShm {
         let addr = NonNull::new(addr.cast::<u8>()).expect("mmap returned null");
 
         Ok(Self {
-            fd,
+            fd: Some(fd),
             addr,
             size,
             name: name.to_string(),
             is_owner: false,
+            read_only,
+            unmap: true,
+        })
+    }
+
+    /// Wrap `len` bytes at `ptr` mapped by someone else, e.g. a
+    /// `memmap2::MmapMut` or a `shared_memory::Shmem`, so a channel can be
+    /// laid over them with [`DaemonChannel::create_in`] and
+    /// [`ShellChannel::connect_in`]
+    ///
+    /// If `owned`, drop unmaps the region with `munmap`; otherwise whoever
+    /// mapped it keeps doing so. The handle has no name (`name()` is
+    /// empty), never unlinks anything, and can't grow the region:
+    /// [`set_len`](Self::set_len) and [`map_range`](Self::map_range) fail
+    /// with [`VenomError::InvalidConfig`], so a channel over it can't be
+    /// [resized](crate::DaemonChannel::resize_data).
+    ///
+    /// A null `ptr`, or a `len` of 0, fails with
+    /// [`VenomError::InvalidConfig`]; the channels also want `ptr` aligned
+    /// to a cache line (64 bytes), which mappings always are.
+    ///
+    /// # Safety
+    /// - `ptr` must be valid for reads and writes of `len` bytes, for as
+    ///   long as the handle and anything built on it lives
+    /// - every process sharing the region must map the same bytes, e.g.
+    ///   with `MAP_SHARED` over one file, and only touch them through
+    ///   channels; the region may be written to concurrently at any time
+    /// - if `owned`, `ptr` and `len` must be exactly those of one `mmap`
+    ///   call, which nothing else unmaps
+    ///
+    /// # Example
+    ///
+    /// A daemon and a shell over two `memmap2` mappings of one file on
+    /// tmpfs, as two processes would map it:
+    ///
+    /// ```
+    /// use memmap2::MmapMut;
+    /// use venom_memory::shm::VenomShm;
+    /// use venom_memory::{ChannelConfig, DaemonChannel, ShellChannel};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let config = ChannelConfig { max_clients: 2, ..ChannelConfig::default() };
+    /// let path = "/dev/shm/venom_doc_from_raw_parts";
+    /// let file = std::fs::OpenOptions::new().read(true).write(true).create(true).truncate(true).open(path)?;
+    /// file.set_len(config.region_size()? as u64)?;
+    /// let mut daemon_map = unsafe { MmapMut::map_mut(&file)? };
+    /// let mut shell_map = unsafe { MmapMut::map_mut(&file)? };
+    ///
+    /// // SAFETY: the mappings are declared first, so they outlive the channels
+    /// let daemon_shm = unsafe { VenomShm::from_raw_parts(daemon_map.as_mut_ptr(), daemon_map.len(), false)? };
+    /// let shell_shm = unsafe { VenomShm::from_raw_parts(shell_map.as_mut_ptr(), shell_map.len(), false)? };
+    /// let daemon = DaemonChannel::create_in(daemon_shm, config)?;
+    /// let shell = ShellChannel::connect_in(shell_shm)?;
+    ///
+    /// daemon.write_data_with_len(b"hello");
+    /// let mut buf = [0u8; 16];
+    /// let len = shell.read_data_with_len(&mut buf);
+    /// assert_eq!(&buf[..len], b"hello");
+    /// // The bytes behind reads, for code that wants a pointer and length
+    /// assert_eq!(shell.data_region().len, shell.data_size());
+    ///
+    /// std::fs::remove_file(path)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`DaemonChannel::create_in`]: crate::DaemonChannel::create_in
+    /// [`ShellChannel::connect_in`]: crate::ShellChannel::connect_in
+    pub unsafe fn from_raw_parts(ptr: *mut u8, len: usize, owned: bool) -> Result<Self> {
+        let addr = NonNull::new(ptr)
+            .ok_or_else(|| VenomError::InvalidConfig { reason: "the region's pointer is null".to_string() })?;
+        if len == 0 {
+            return Err(VenomError::InvalidConfig { reason: "the region is empty".to_string() });
+        }
+
+        Ok(Self {
+            fd: None,
+            addr,
+            size: len,
+            name: String::new(),
+            is_owner: false,
+            read_only: false,
+            unmap: owned,
         })
     }
 
@@ -162,17 +360,118 @@ impl VenomShm {
     pub fn is_owner(&self) -> bool {
         self.is_owner
     }
+
+    /// Make this handle the owner, whose drop removes the object's name, or
+    /// stop it being one
+    pub(crate) fn set_owner(&mut self, owner: bool) {
+        self.is_owner = owner;
+    }
+
+    /// Current size of the shared memory object, which may have grown past
+    /// the mapping since it was opened
+    ///
+    /// A region from [`from_raw_parts`](Self::from_raw_parts) keeps the
+    /// size it was given.
+    pub fn file_len(&self) -> Result<usize> {
+        let Some(fd) = &self.fd else {
+            return Ok(self.size);
+        };
+        let stat = rustix::fs::fstat(fd).map_err(|e| VenomError::ShmOpen {
+            name: self.name.clone(),
+            source: e.into(),
+        })?;
+        Ok(stat.st_size as usize)
+    }
+
+    /// Resize the shared memory object to `len` bytes
+    ///
+    /// The mapping keeps its size: bytes past it are reached through
+    /// [`map_range`](Self::map_range).
+    pub fn set_len(&self, len: usize) -> Result<()> {
+        ftruncate(self.object()?, len as u64).map_err(|e| VenomError::Truncate(e.into()))
+    }
+
+    /// Map `len` bytes of the object from `offset`, with the same access as
+    /// this handle; `offset` needn't be page aligned
+    pub fn map_range(&self, offset: usize, len: usize) -> Result<ShmMapping> {
+        let skip = offset % rustix::param::page_size();
+        let prot = if self.read_only { ProtFlags::READ } else { ProtFlags::READ | ProtFlags::WRITE };
+        let addr = unsafe {
+            mmap(std::ptr::null_mut(), skip + len, prot, MapFlags::SHARED, self.object()?, (offset - skip) as u64)
+                .map_err(|e| VenomError::Mmap(e.into()))?
+        };
+
+        Ok(ShmMapping {
+            addr: NonNull::new(addr.cast::<u8>()).expect("mmap returned null"),
+            skip,
+            len,
+        })
+    }
+
+    /// The object behind the mapping, which a region from
+    /// [`from_raw_parts`](Self::from_raw_parts) doesn't have
+    fn object(&self) -> Result<&OwnedFd> {
+        self.fd.as_ref().ok_or_else(|| VenomError::InvalidConfig {
+            reason: "a region from raw parts can't be resized or mapped again".to_string(),
+        })
+    }
+
+    /// Whether the name still refers to the object this handle has open:
+    /// another process may have removed it and created a new one since
+    fn still_named(&self) -> bool {
+        let Some(ours) = &self.fd else {
+            return false;
+        };
+        let Ok(c_name) = CString::new(format!("{}{}", VENOM_SHM_PREFIX, self.name)) else {
+            return false;
+        };
+        let Ok(fd) = shm_open(c_name.as_c_str(), ShmOFlags::RDONLY, Mode::empty()) else {
+            return false;
+        };
+        match (rustix::fs::fstat(&fd), rustix::fs::fstat(ours)) {
+            (Ok(named), Ok(ours)) => (named.st_dev, named.st_ino) == (ours.st_dev, ours.st_ino),
+            _ => false,
+        }
+    }
+
+    /// Remove the name of the object this handle owns, as dropping it
+    /// would; returns false if it isn't the owner or the name has already
+    /// gone to another object
+    pub fn unlink_own(&self) -> Result<bool> {
+        if !self.is_owner || !self.still_named() {
+            return Ok(false);
+        }
+        Self::unlink(&self.name).map(|()| true)
+    }
+
+    /// Remove a shared memory object by name
+    ///
+    /// Existing mappings stay valid until they are dropped.
+    pub fn unlink(name: &str) -> Result<()> {
+        let full_name = format!("{}{}", VENOM_SHM_PREFIX, name);
+        let c_name = CString::new(full_name).map_err(|e| VenomError::ShmOpen {
+            name: name.to_string(),
+            source: e.into(),
+        })?;
+
+        shm_unlink(c_name.as_c_str()).map_err(|e| VenomError::ShmOpen {
+            name: name.to_string(),
+            source: e.into(),
+        })
+    }
 }
 
 impl Drop for VenomShm {
     fn drop(&mut self) {
-        // Unmap memory
-        unsafe {
-            let _ = munmap(self.addr.as_ptr().cast(), self.size);
+        // Unmap memory, unless someone else mapped it
+        if self.unmap {
+            unsafe {
+                let _ = munmap(self.addr.as_ptr().cast(), self.size);
+            }
         }
 
-        // If owner, unlink the shared memory
-        if self.is_owner {
+        // If owner, unlink the shared memory, unless it has been replaced
+        if self.is_owner && self.still_named() {
             let full_name = format!("{}{}", VENOM_SHM_PREFIX, self.name);
             if let Ok(c_name) = CString::new(full_name) {
                 let _ = shm_unlink(c_name.as_c_str());
@@ -181,6 +480,47 @@ impl Drop for VenomShm {
     }
 }
 
+/// Part of a shared memory object mapped by [`VenomShm::map_range`],
+/// unmapped on drop
+pub struct ShmMapping {
+    addr: NonNull<u8>,
+    /// Bytes mapped before the requested offset, to start on a page
+    skip: usize,
+    len: usize,
+}
+
+// SAFETY: as for VenomShm
+unsafe impl Send for ShmMapping {}
+unsafe impl Sync for ShmMapping {}
+
+impl ShmMapping {
+    /// Get raw pointer to the first requested byte
+    #[inline(always)]
+    pub fn as_ptr(&self) -> *mut u8 {
+        unsafe { self.addr.as_ptr().add(self.skip) }
+    }
+
+    /// Get size of the mapped range
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the range is empty
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl Drop for ShmMapping {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = munmap(self.addr.as_ptr().cast(), self.skip + self.len);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -200,6 +540,12 @@ mod tests {
             std::ptr::write(shm1.as_ptr(), 42u8);
         }
 
+        // The name is taken
+        match VenomShm::create(name, size) {
+            Err(VenomError::ShmCreate { source, .. }) => assert_eq!(source.kind(), std::io::ErrorKind::AlreadyExists),
+            other => panic!("expected ShmCreate, got {:?}", other.map(|_| ())),
+        }
+
         // Open from another "process"
         let shm2 = VenomShm::open(name).unwrap();
         assert!(!shm2.is_owner());
@@ -212,4 +558,128 @@ mod tests {
         drop(shm2);
         drop(shm1);
     }
+
+    #[test]
+    fn test_create_with_mode() {
+        let name = "test_shm_mode";
+        let permissions = ShmPermissions { mode: Some(0o640), group: None };
+        let _shm = VenomShm::create_with(name, 4096, &permissions).unwrap();
+        let stat = rustix::fs::stat(shm_path(name)).unwrap();
+        assert_eq!(stat.st_mode & 0o777, 0o640);
+
+        let permissions = ShmPermissions { mode: Some(0o1777), group: None };
+        assert!(matches!(
+            VenomShm::create_with("test_shm_bad_mode", 4096, &permissions),
+            Err(VenomError::InvalidConfig { .. })
+        ));
+        let permissions = ShmPermissions { mode: Some(0o660), group: Some("no_such_venom_group".to_string()) };
+        assert!(matches!(
+            VenomShm::create_with("test_shm_bad_group", 4096, &permissions),
+            Err(VenomError::InvalidConfig { .. })
+        ));
+    }
+
+    #[test]
+    fn test_create_with_group() {
+        let name = "test_shm_group";
+        if rustix::process::geteuid().is_root() {
+            // Root may give the object to any group
+            let permissions = ShmPermissions { mode: Some(0o660), group: Some("65534".to_string()) };
+            let _shm = VenomShm::create_with(name, 4096, &permissions).unwrap();
+            assert_eq!(rustix::fs::stat(shm_path(name)).unwrap().st_gid, 65534);
+        } else {
+            // Anyone else can't give it to root's group, and isn't left with it
+            let permissions = ShmPermissions { mode: Some(0o660), group: Some("0".to_string()) };
+            match VenomShm::create_with(name, 4096, &permissions) {
+                Err(VenomError::PermissionDenied { path, .. }) => assert_eq!(path, shm_path(name)),
+                other => panic!("expected PermissionDenied, got {:?}", other.map(|_| ())),
+            }
+            assert!(VenomShm::open(name).is_err());
+        }
+    }
+
+    #[test]
+    fn test_failed_create_frees_name() {
+        let name = "test_shm_failed_create";
+        // Past off_t, ftruncate refuses; an empty mapping, mmap does
+        assert!(matches!(VenomShm::create(name, usize::MAX), Err(VenomError::Truncate(_))));
+        assert!(VenomShm::open(name).is_err());
+        assert!(matches!(VenomShm::create(name, 0), Err(VenomError::Mmap(_))));
+        assert!(VenomShm::open(name).is_err());
+        assert!(VenomShm::create(name, 4096).is_ok());
+    }
+
+    #[test]
+    fn test_create_default_mode() {
+        let name = "test_shm_default_mode";
+        let _shm = VenomShm::create(name, 4096).unwrap();
+        let stat = rustix::fs::stat(shm_path(name)).unwrap();
+        assert_eq!(stat.st_mode & 0o777, ShmPermissions::DEFAULT_MODE & !umask());
+    }
+
+    #[test]
+    fn test_open_denied() {
+        let name = "test_shm_denied";
+        let permissions = ShmPermissions { mode: Some(0o400), group: None };
+        let _shm = VenomShm::create_with(name, 4096, &permissions).unwrap();
+        assert!(VenomShm::open_read_only(name).is_ok());
+        if rustix::process::geteuid().is_root() {
+            // Permission bits don't bind root
+            assert!(VenomShm::open(name).is_ok());
+        } else {
+            match VenomShm::open(name) {
+                Err(e @ VenomError::PermissionDenied { .. }) => assert!(e.to_string().contains(&shm_path(name))),
+                other => panic!("expected PermissionDenied, got {:?}", other.map(|_| ())),
+            }
+        }
+    }
+
+    #[test]
+    fn test_drop_spares_replacement() {
+        let name = "test_shm_replaced";
+        let old = VenomShm::create(name, 4096).unwrap();
+        VenomShm::unlink(name).unwrap();
+        let new = VenomShm::create(name, 4096).unwrap();
+
+        // Dropping the old owner leaves the new object's name alone
+        drop(old);
+        assert!(VenomShm::open(name).is_ok());
+        drop(new);
+        assert!(VenomShm::open(name).is_err());
+    }
+
+    #[test]
+    fn test_from_raw_parts() {
+        let shm = VenomShm::create("test_shm_raw_parts", 4096).unwrap();
+        let raw = unsafe { VenomShm::from_raw_parts(shm.as_ptr(), shm.size(), false).unwrap() };
+        assert_eq!((raw.name(), raw.is_owner(), raw.file_len().unwrap()), ("", false, 4096));
+        assert!(matches!(raw.set_len(8192), Err(VenomError::InvalidConfig { .. })));
+        assert!(matches!(raw.map_range(0, 64), Err(VenomError::InvalidConfig { .. })));
+        assert!(!raw.unlink_own().unwrap());
+
+        // Not unmapped, nor unlinked, with it
+        unsafe { raw.as_ptr().write(7) };
+        drop(raw);
+        assert_eq!(unsafe { shm.as_ptr().read() }, 7);
+        assert!(VenomShm::open("test_shm_raw_parts").is_ok());
+    }
+
+    #[test]
+    fn test_grow_and_map_range() {
+        let name = "test_shm_grow";
+        let shm1 = VenomShm::create(name, 4096).unwrap();
+        let shm2 = VenomShm::open(name).unwrap();
+
+        // Past the end of both mappings, and not on a page
+        shm1.set_len(3 * 4096).unwrap();
+        assert_eq!(shm2.file_len().unwrap(), 3 * 4096);
+        let range1 = shm1.map_range(4096 + 100, 64).unwrap();
+        let range2 = shm2.map_range(4096 + 100, 64).unwrap();
+        assert_eq!((range1.len(), shm1.size()), (64, 4096));
+
+        unsafe {
+            std::ptr::write(range1.as_ptr(), 7u8);
+            assert_eq!(std::ptr::read(range2.as_ptr()), 7u8);
+        }
+    }
 }
@@ -0,0 +1,108 @@
+//! Commands carrying a type tag, routed to a handler per tag
+//!
+//! [`ShellChannel::send_typed`](crate::ShellChannel::send_typed) sends a
+//! payload behind an envelope naming its type, and
+//! [`DaemonChannel::dispatch_pending`](crate::DaemonChannel::dispatch_pending)
+//! hands each command to the handler registered for its tag with
+//! [`DaemonChannel::on`](crate::DaemonChannel::on), or to the
+//! [`on_unknown`](crate::DaemonChannel::on_unknown) fallback, so a daemon
+//! doesn't match on the first byte of every command by hand. The envelope
+//! lives inside the command: [`try_recv_command`](crate::DaemonChannel::try_recv_command)
+//! still hands out typed commands as they are, envelope included.
+//!
+//! # Envelope
+//!
+//! [`ENVELOPE_SIZE`] bytes, every field a little-endian `u16`:
+//!
+//! | Offset | Field | |
+//! |--------|-------|-|
+//! | 0 | `tag` | Picked by the application; the generated projects use their `CmdType` |
+//! | 2 | `len` | Bytes of payload that follow |
+//!
+//! The payload follows; bytes past `len` are ignored. A command shorter
+//! than its envelope claims isn't typed, and goes to the fallback whole.
+
+use crate::channel::DaemonChannel;
+use crate::error::{Result, VenomError};
+use std::collections::HashMap;
+
+/// Bytes of envelope before each payload
+pub const ENVELOPE_SIZE: usize = 4;
+
+/// Split a typed command into its tag and payload; None if `cmd` is too
+/// short for its envelope
+pub fn parse(cmd: &[u8]) -> Option<(u16, &[u8])> {
+    let envelope = cmd.get(..ENVELOPE_SIZE)?;
+    let tag = u16::from_le_bytes([envelope[0], envelope[1]]);
+    let len = u16::from_le_bytes([envelope[2], envelope[3]]) as usize;
+    Some((tag, cmd[ENVELOPE_SIZE..].get(..len)?))
+}
+
+/// Write the envelope and `payload` to the start of `cmd`; returns the
+/// command's length
+///
+/// Fails with [`VenomError::BufferOverflow`] if they don't fit in `cmd`.
+pub(crate) fn encode(tag: u16, payload: &[u8], cmd: &mut [u8]) -> Result<usize> {
+    debug_assert!(cmd.len() <= ENVELOPE_SIZE + u16::MAX as usize, "commands are at most 4 KiB");
+    let len = ENVELOPE_SIZE + payload.len();
+    if len > cmd.len() {
+        return Err(VenomError::BufferOverflow { max: cmd.len(), got: len });
+    }
+    cmd[..2].copy_from_slice(&tag.to_le_bytes());
+    cmd[2..4].copy_from_slice(&(payload.len() as u16).to_le_bytes());
+    cmd[ENVELOPE_SIZE..len].copy_from_slice(payload);
+    Ok(len)
+}
+
+/// Called with the daemon, the sender's client ID and the command's payload
+pub(crate) type Handler = Box<dyn FnMut(&DaemonChannel, u32, &[u8]) + Send>;
+
+/// Handlers a daemon registered, by tag
+#[derive(Default)]
+pub(crate) struct Handlers {
+    by_tag: HashMap<u16, Handler>,
+    /// Gets whole commands with no handler for their tag, or no envelope
+    unknown: Option<Handler>,
+}
+
+impl Handlers {
+    pub(crate) fn insert(&mut self, tag: u16, handler: Handler) {
+        self.by_tag.insert(tag, handler);
+    }
+
+    pub(crate) fn set_unknown(&mut self, handler: Handler) {
+        self.unknown = Some(handler);
+    }
+
+    /// Pass `cmd` to the handler of its tag, or the fallback if there's
+    /// one
+    pub(crate) fn dispatch(&mut self, daemon: &DaemonChannel, client_id: u32, cmd: &[u8]) {
+        let handler = parse(cmd).and_then(|(tag, payload)| Some((self.by_tag.get_mut(&tag)?, payload)));
+        match (handler, &mut self.unknown) {
+            (Some((handler, payload)), _) => handler(daemon, client_id, payload),
+            (None, Some(unknown)) => unknown(daemon, client_id, cmd),
+            (None, None) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_and_parse() {
+        let mut cmd = [0u8; 16];
+        let len = encode(0x0102, b"payload", &mut cmd).unwrap();
+        assert_eq!(&cmd[..len], b"\x02\x01\x07\x00payload");
+        assert_eq!(parse(&cmd[..len]), Some((0x0102, &b"payload"[..])));
+
+        // Trailing bytes are ignored, missing ones make it untyped
+        assert_eq!(parse(&cmd), Some((0x0102, &b"payload"[..])));
+        assert_eq!(parse(&cmd[..len - 1]), None);
+        assert_eq!(parse(&cmd[..3]), None);
+
+        assert!(matches!(encode(1, &[0; 13], &mut cmd), Err(VenomError::BufferOverflow { max: 16, got: 17 })));
+        assert_eq!(encode(1, &[], &mut cmd).unwrap(), ENVELOPE_SIZE);
+    }
+}
@@ -0,0 +1,340 @@
+//! Daemon and shells in separate processes
+//!
+//! The unit tests keep both ends of a channel in one address space. Here
+//! the test binary runs itself again as the daemon and as each shell
+//! (`child_process`, picked by `VENOM_TEST_ROLE`), so every process maps
+//! the channel at its own address, shells connect at the same moment, and
+//! a daemon can be killed under them. Child processes and channels are
+//! cleaned up when a test ends, panics included.
+
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+use venom_memory::shm::VenomShm;
+use venom_memory::{ChannelConfig, DaemonChannel, ShellChannel, VenomError};
+
+const ROLE: &str = "VENOM_TEST_ROLE";
+const CHANNEL: &str = "VENOM_TEST_CHANNEL";
+const SHELLS: &str = "VENOM_TEST_SHELLS";
+
+/// How long any process waits for another before giving up
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Data region of the test channels; frames stay below it
+const DATA_SIZE: usize = 4096;
+
+/// Distinct frames each shell checks before it reports back
+const FRAMES: usize = 500;
+
+/// Frame `seq`: the sequence number, a payload whose length and bytes
+/// depend on it, and an FNV-1a checksum of both
+fn frame(seq: u64) -> Vec<u8> {
+    let len = 16 + (seq as usize * 37) % 2000;
+    let mut frame = seq.to_le_bytes().to_vec();
+    frame.extend((0..len).map(|i| (seq as usize).wrapping_mul(31).wrapping_add(i) as u8));
+    let sum = checksum(&frame);
+    frame.extend(sum.to_le_bytes());
+    frame
+}
+
+fn checksum(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, &b| (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// Sequence number of an intact frame; None for a torn or corrupt one
+fn check(frame: &[u8]) -> Option<u64> {
+    let (body, sum) = frame.split_at(frame.len().checked_sub(16)? + 8);
+    let seq = u64::from_le_bytes(body[..8].try_into().unwrap());
+    (checksum(body) == u64::from_le_bytes(sum.try_into().unwrap())).then_some(seq)
+}
+
+/// A channel name of its own for each test of each run, unlinked when
+/// the test ends
+struct Channel(String);
+
+impl Channel {
+    fn new(test: &str) -> Self {
+        Channel(format!("process_test_{}_{}", std::process::id(), test))
+    }
+
+    /// Whether the shared memory object is still there
+    fn exists(&self) -> bool {
+        Path::new("/dev/shm").join(format!("venom_{}", self.0)).exists()
+    }
+}
+
+impl Drop for Channel {
+    fn drop(&mut self) {
+        let _ = VenomShm::unlink(&self.0);
+    }
+}
+
+/// A child process, killed if the test ends before it does
+struct Process(Child);
+
+impl Process {
+    /// The test binary again, as `role` on `channel`
+    fn spawn(role: &str, channel: &Channel, shells: usize) -> Self {
+        let child = Command::new(std::env::current_exe().unwrap())
+            .args(["child_process", "--exact", "--nocapture", "--test-threads=1"])
+            .env(ROLE, role)
+            .env(CHANNEL, &channel.0)
+            .env(SHELLS, shells.to_string())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        Process(child)
+    }
+
+    /// Exit code; fails the test if the process doesn't exit in time
+    fn wait(&mut self) -> i32 {
+        let start = Instant::now();
+        loop {
+            if let Some(status) = self.0.try_wait().unwrap() {
+                return status.code().unwrap_or(-1);
+            }
+            assert!(start.elapsed() < TIMEOUT, "process {} didn't exit", self.0.id());
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    /// Values of the `key=value` lines the process printed, once it exited.
+    /// The first one shares its line with the harness's `test ... `
+    fn values(&mut self, key: &str) -> Vec<String> {
+        let mut out = String::new();
+        self.0.stdout.take().unwrap().read_to_string(&mut out).unwrap();
+        let prefix = format!("{}=", key);
+        out.lines().filter_map(|l| l.split_once(&prefix)).map(|(_, value)| value.to_string()).collect()
+    }
+
+    /// Block until the process prints `line`
+    fn expect_line(&mut self, line: &str) {
+        let mut stdout = BufReader::new(self.0.stdout.as_mut().unwrap());
+        let mut read = String::new();
+        while !read.trim_end().ends_with(line) {
+            read.clear();
+            assert!(stdout.read_line(&mut read).unwrap() > 0, "process {} exited before printing {}", self.0.id(), line);
+        }
+    }
+}
+
+impl Drop for Process {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// What a child process runs; nothing in the test run itself
+#[test]
+fn child_process() {
+    let Ok(role) = std::env::var(ROLE) else { return };
+    let name = std::env::var(CHANNEL).unwrap();
+    let shells = std::env::var(SHELLS).unwrap().parse().unwrap();
+    let code = match role.as_str() {
+        "daemon" => daemon(&name, shells),
+        "shell" => shell(&name),
+        "connect" => connect(&name).map_or(1, |shell| {
+            println!("client_id={}", shell.client_id());
+            0
+        }),
+        "watch" => watch(&name),
+        _ => 1,
+    };
+    std::process::exit(code);
+}
+
+/// Write frames until `shells` shells have reported back, every one with a
+/// client id of its own; with no shells, until killed
+fn daemon(name: &str, shells: usize) -> i32 {
+    let config = ChannelConfig {
+        data_size: DATA_SIZE,
+        ..ChannelConfig::default()
+    };
+    let daemon = match DaemonChannel::create(name, config) {
+        Ok(daemon) => daemon,
+        Err(e) => {
+            eprintln!("[daemon] {}", e);
+            return 1;
+        }
+    };
+
+    let start = Instant::now();
+    let mut ids = Vec::new();
+    let mut cmd = [0u8; 64];
+    let mut seq = 0;
+    while shells == 0 || ids.len() < shells {
+        daemon.write_data_with_len(&frame(seq));
+        seq += 1;
+        while let Some((client_id, len)) = daemon.try_recv_command(&mut cmd) {
+            if &cmd[..len] == b"done" {
+                ids.push(client_id);
+            }
+        }
+        if shells > 0 && start.elapsed() > TIMEOUT {
+            eprintln!("[daemon] {} of {} shells reported back", ids.len(), shells);
+            return 2;
+        }
+        std::thread::sleep(Duration::from_micros(50));
+    }
+
+    ids.sort_unstable();
+    ids.dedup();
+    if ids.len() == shells { 0 } else { 3 }
+}
+
+/// The channel as soon as the daemon has set it up
+fn connect(name: &str) -> Option<ShellChannel> {
+    let start = Instant::now();
+    loop {
+        match ShellChannel::connect(name) {
+            Ok(shell) => return Some(shell),
+            Err(e) if start.elapsed() > TIMEOUT => {
+                eprintln!("[shell] {}", e);
+                return None;
+            }
+            Err(_) => std::thread::sleep(Duration::from_millis(1)),
+        }
+    }
+}
+
+/// Check `FRAMES` frames as they come, then tell the daemon
+fn shell(name: &str) -> i32 {
+    let Some(shell) = connect(name) else { return 1 };
+    println!("client_id={}", shell.client_id());
+
+    let start = Instant::now();
+    let mut buf = vec![0u8; DATA_SIZE - 8];
+    let (mut seen, mut last) = (0, None);
+    while seen < FRAMES {
+        if start.elapsed() > TIMEOUT {
+            eprintln!("[shell {}] saw {} frames", shell.client_id(), seen);
+            return 2;
+        }
+        let len = shell.read_data_with_len(&mut buf);
+        if len == 0 {
+            continue;
+        }
+        match check(&buf[..len.min(buf.len())]) {
+            None => {
+                eprintln!("[shell {}] corrupt frame of {} bytes", shell.client_id(), len);
+                return 1;
+            }
+            Some(seq) if last.is_some_and(|last| seq < last) => {
+                eprintln!("[shell {}] frame {} after frame {}", shell.client_id(), seq, last.unwrap());
+                return 1;
+            }
+            Some(seq) if last != Some(seq) => {
+                seen += 1;
+                last = Some(seq);
+            }
+            Some(_) => {}
+        }
+    }
+    shell.send_command(b"done");
+    0
+}
+
+/// Wait for the daemon to die; its last frame has to stay readable
+fn watch(name: &str) -> i32 {
+    let Some(shell) = connect(name) else { return 1 };
+    let mut buf = vec![0u8; DATA_SIZE - 8];
+    while shell.read_data_with_len(&mut buf) == 0 {}
+    if !shell.daemon_alive() {
+        return 1;
+    }
+    println!("connected");
+
+    let start = Instant::now();
+    while shell.daemon_alive() {
+        if start.elapsed() > TIMEOUT {
+            return 2;
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    let len = shell.read_data_with_len(&mut buf);
+    if check(&buf[..len.min(buf.len())]).is_some() { 0 } else { 1 }
+}
+
+#[test]
+fn checksummed_frames_reach_every_shell() {
+    let channel = Channel::new("frames");
+    let shells = 4;
+    let mut daemon = Process::spawn("daemon", &channel, shells);
+    let mut clients: Vec<Process> = (0..shells).map(|_| Process::spawn("shell", &channel, 0)).collect();
+
+    for client in &mut clients {
+        assert_eq!(client.wait(), 0, "shell failed");
+    }
+    assert_eq!(daemon.wait(), 0, "daemon failed");
+    let mut ids: Vec<String> = clients.iter_mut().flat_map(|c| c.values("client_id")).collect();
+    ids.sort();
+    ids.dedup();
+    assert_eq!(ids.len(), shells);
+
+    // The daemon unlinks the channel on its way out
+    assert!(!channel.exists());
+}
+
+#[test]
+fn concurrent_connects_get_unique_ids() {
+    let channel = Channel::new("connect");
+    let _daemon = Process::spawn("daemon", &channel, 0);
+    let mut clients: Vec<Process> = (0..12).map(|_| Process::spawn("connect", &channel, 0)).collect();
+
+    let mut ids = Vec::new();
+    for client in &mut clients {
+        assert_eq!(client.wait(), 0, "shell failed to connect");
+        ids.extend(client.values("client_id"));
+    }
+    let count = ids.len();
+    ids.sort();
+    ids.dedup();
+    assert_eq!((count, ids.len()), (12, 12), "{:?}", ids);
+}
+
+#[test]
+fn shells_notice_a_killed_daemon() {
+    let channel = Channel::new("killed");
+    let mut daemon = Process::spawn("daemon", &channel, 0);
+    let mut watcher = Process::spawn("watch", &channel, 0);
+    watcher.expect_line("connected");
+
+    daemon.0.kill().unwrap();
+    daemon.wait();
+    assert_eq!(watcher.wait(), 0, "shell didn't see the daemon die, or lost its data");
+
+    // Left behind by the killed daemon, and taken over by the next one
+    assert!(channel.exists());
+    drop(DaemonChannel::create_force(&channel.0, ChannelConfig::default()).unwrap());
+    assert!(!channel.exists());
+}
+
+#[test]
+fn second_daemon_waits_for_the_first_to_die() {
+    let channel = Channel::new("second_daemon");
+    let mut daemon = Process::spawn("daemon", &channel, 0);
+    let mut watcher = Process::spawn("watch", &channel, 0);
+    watcher.expect_line("connected");
+
+    let owner = Some(daemon.0.id());
+    let in_use = |result: venom_memory::Result<DaemonChannel>| match result {
+        Err(VenomError::NamespaceInUse { pid, .. }) => pid,
+        other => panic!("expected NamespaceInUse, got {:?}", other.err()),
+    };
+    assert_eq!(in_use(DaemonChannel::create(&channel.0, ChannelConfig::default())), owner);
+    assert_eq!(in_use(DaemonChannel::create_force(&channel.0, ChannelConfig::default())), owner);
+
+    // Killed without cleaning up: still refused, unless forced
+    daemon.0.kill().unwrap();
+    daemon.wait();
+    assert_eq!(watcher.wait(), 0, "the second daemon disturbed the first one's channel");
+    assert_eq!(in_use(DaemonChannel::create(&channel.0, ChannelConfig::default())), owner);
+    let second = DaemonChannel::create_force(&channel.0, ChannelConfig::default()).unwrap();
+    assert_eq!(ShellChannel::connect(&channel.0).unwrap().daemon_pid(), std::process::id());
+    drop(second);
+    assert!(!channel.exists());
+}
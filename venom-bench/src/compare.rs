@@ -0,0 +1,199 @@
+//! Comparing two reports cell by cell
+
+use crate::report::{Cell, Report};
+
+/// Parse a tolerance given as a percentage, `10%` or `10`, into a fraction
+pub fn parse_tolerance(s: &str) -> Result<f64, String> {
+    let percent: f64 = s
+        .trim()
+        .trim_end_matches('%')
+        .parse()
+        .map_err(|_| format!("{:?} isn't a percentage like 10%", s))?;
+    if !(0.0..=1000.0).contains(&percent) {
+        return Err(format!("tolerance {}% is out of range", percent));
+    }
+    Ok(percent / 100.0)
+}
+
+/// How one metric of a cell moved
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub metric: &'static str,
+    pub baseline: f64,
+    pub current: f64,
+    /// Whether it got worse by more than the tolerance
+    pub regressed: bool,
+}
+
+impl Change {
+    /// Relative change from the baseline, `0.1` for 10% higher
+    pub fn relative(&self) -> f64 {
+        if self.baseline == 0.0 { 0.0 } else { (self.current - self.baseline) / self.baseline }
+    }
+}
+
+/// A cell both reports have, and how its metrics moved
+#[derive(Debug, Clone)]
+pub struct CellDiff {
+    pub label: String,
+    pub changes: Vec<Change>,
+}
+
+impl CellDiff {
+    pub fn regressed(&self) -> bool {
+        self.changes.iter().any(|change| change.regressed)
+    }
+}
+
+/// Outcome of comparing a current report against a baseline
+#[derive(Debug, Clone, Default)]
+pub struct Comparison {
+    pub cells: Vec<CellDiff>,
+    /// Cells of the baseline the current report doesn't have
+    pub missing: Vec<String>,
+}
+
+impl Comparison {
+    pub fn regressed(&self) -> bool {
+        self.cells.iter().any(CellDiff::regressed)
+    }
+}
+
+/// Compare `current` against `baseline`: a cell regressed if its p50 or
+/// p99 round trip grew, or its throughput shrank, by more than `tolerance`
+/// (a fraction)
+///
+/// Cells the baseline has no round trips for aren't judged.
+pub fn compare(baseline: &Report, current: &Report, tolerance: f64) -> Comparison {
+    let mut comparison = Comparison::default();
+    for base in &baseline.cells {
+        let Some(cell) = current.cells.iter().find(|cell| cell.key() == base.key()) else {
+            comparison.missing.push(base.label());
+            continue;
+        };
+        if base.round_trips == 0 {
+            continue;
+        }
+        comparison.cells.push(CellDiff { label: base.label(), changes: changes(base, cell, tolerance) });
+    }
+    comparison
+}
+
+fn changes(base: &Cell, cell: &Cell, tolerance: f64) -> Vec<Change> {
+    // Latencies regress upwards, throughput downwards
+    let higher_is_worse = |metric, baseline: f64, current: f64| Change {
+        metric,
+        baseline,
+        current,
+        regressed: current > baseline * (1.0 + tolerance),
+    };
+    vec![
+        higher_is_worse("p50", base.p50_ns as f64, cell.p50_ns as f64),
+        higher_is_worse("p99", base.p99_ns as f64, cell.p99_ns as f64),
+        Change {
+            metric: "throughput",
+            baseline: base.throughput,
+            current: cell.throughput,
+            regressed: cell.throughput < base.throughput * (1.0 - tolerance),
+        },
+    ]
+}
+
+/// Print the comparison, one line per cell
+pub fn print(comparison: &Comparison, tolerance: f64) {
+    for diff in &comparison.cells {
+        let changes: Vec<String> = diff
+            .changes
+            .iter()
+            .map(|change| format!("{} {:+.1}%{}", change.metric, change.relative() * 100.0, if change.regressed { "!" } else { "" }))
+            .collect();
+        let verdict = if diff.regressed() { "REGRESSED" } else { "ok" };
+        println!("{:<20} {:<9} {}", diff.label, verdict, changes.join("  "));
+    }
+    for label in &comparison.missing {
+        println!("{:<20} {:<9} not in the current results", label, "missing");
+    }
+    let regressed = comparison.cells.iter().filter(|diff| diff.regressed()).count();
+    println!(
+        "\n{} of {} cells regressed by more than {:.0}%",
+        regressed,
+        comparison.cells.len(),
+        tolerance * 100.0
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix::WaitMode;
+    use crate::report::{FORMAT_VERSION, Machine};
+
+    fn cell(channels: usize, mode: WaitMode, p50_ns: u64, p99_ns: u64, throughput: f64) -> Cell {
+        Cell {
+            channels,
+            payload_bytes: 64 * 1024,
+            mode,
+            round_trips: 1000,
+            p50_ns,
+            p99_ns,
+            throughput,
+            bandwidth_mb_s: throughput * 65536.0 / 1e6,
+        }
+    }
+
+    fn report(cells: Vec<Cell>) -> Report {
+        Report {
+            format_version: FORMAT_VERSION,
+            bench_version: "0.1.0".to_string(),
+            layout_version: 5,
+            machine: Machine { cpu_model: "test".to_string(), cpus: 8, arch: "x86_64".to_string(), kernel: "6.0".to_string() },
+            duration_ms: 1000,
+            pinned: false,
+            cells,
+        }
+    }
+
+    #[test]
+    fn test_parse_tolerance() {
+        assert_eq!(parse_tolerance("10%"), Ok(0.1));
+        assert_eq!(parse_tolerance("25"), Ok(0.25));
+        assert!(parse_tolerance("ten").is_err());
+        assert!(parse_tolerance("-5%").is_err());
+    }
+
+    #[test]
+    fn test_synthetic_regression_flagged() {
+        let baseline = report(vec![cell(1, WaitMode::Spin, 1000, 2000, 500_000.0), cell(4, WaitMode::Backoff, 5000, 9000, 80_000.0)]);
+        // Spin p50 15% slower; backoff within tolerance everywhere
+        let current = report(vec![cell(1, WaitMode::Spin, 1150, 2000, 500_000.0), cell(4, WaitMode::Backoff, 5400, 9500, 75_000.0)]);
+
+        let comparison = compare(&baseline, &current, 0.1);
+        assert!(comparison.regressed());
+        let flagged: Vec<_> = comparison.cells.iter().filter(|diff| diff.regressed()).map(|diff| diff.label.as_str()).collect();
+        assert_eq!(flagged, ["1ch 64KB spin"]);
+        let p50 = &comparison.cells[0].changes[0];
+        assert_eq!(p50.metric, "p50");
+        assert!((p50.relative() - 0.15).abs() < 1e-9);
+
+        // A wider tolerance lets it through
+        assert!(!compare(&baseline, &current, 0.2).regressed());
+    }
+
+    #[test]
+    fn test_throughput_drop_and_improvements() {
+        let baseline = report(vec![cell(8, WaitMode::Spin, 1000, 2000, 100_000.0)]);
+        let slower = report(vec![cell(8, WaitMode::Spin, 1000, 2000, 85_000.0)]);
+        let faster = report(vec![cell(8, WaitMode::Spin, 500, 900, 200_000.0)]);
+        assert!(compare(&baseline, &slower, 0.1).regressed());
+        assert!(!compare(&baseline, &faster, 0.1).regressed());
+    }
+
+    #[test]
+    fn test_missing_cells_reported() {
+        let baseline = report(vec![cell(1, WaitMode::Spin, 1000, 2000, 1.0), cell(8, WaitMode::Spin, 1000, 2000, 1.0)]);
+        let current = report(vec![cell(1, WaitMode::Spin, 1000, 2000, 1.0)]);
+        let comparison = compare(&baseline, &current, 0.1);
+        assert_eq!(comparison.missing, ["8ch 64KB spin"]);
+        assert!(!comparison.regressed());
+    }
+}
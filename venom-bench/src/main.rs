@@ -0,0 +1,111 @@
+use clap::{Parser, Subcommand};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+mod compare;
+mod matrix;
+mod report;
+
+use matrix::{CHANNELS, MODES, Options, PAYLOADS};
+use report::{FORMAT_VERSION, Machine, Report};
+
+#[derive(Parser, Debug)]
+#[command(name = "venom-bench")]
+#[command(about = "⏱️  Benchmark VenomMemory channels and compare runs", long_about = None)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Run the matrix (1/4/8 channels × 1KB/64KB/256KB payloads × spin/backoff)
+    /// and print the results as JSON
+    Run {
+        /// How long each cell runs, in milliseconds
+        #[arg(short, long, default_value_t = 1000)]
+        duration: u64,
+
+        /// Pin each daemon and shell thread to a CPU; results change a lot
+        /// with it, so compare pinned runs with pinned runs
+        #[arg(long)]
+        pin: bool,
+
+        /// Write the results here instead of to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Compare results against a baseline; exits 1 if any cell regressed
+    Compare {
+        baseline: PathBuf,
+        current: PathBuf,
+
+        /// How much worse a p50, p99 or throughput may get, as a percentage
+        #[arg(short, long, default_value = "10%", value_parser = compare::parse_tolerance)]
+        tolerance: f64,
+    },
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Run { duration, pin, output } => run(Options { duration: Duration::from_millis(duration), pin }, output),
+        Command::Compare { baseline, current, tolerance } => compare(&baseline, &current, tolerance),
+    };
+    match result {
+        Ok(true) => {}
+        Ok(false) => std::process::exit(1),
+        Err(e) => {
+            eprintln!("[venom-bench] {}", e);
+            std::process::exit(2);
+        }
+    }
+}
+
+fn run(options: Options, output: Option<PathBuf>) -> Result<bool, String> {
+    let mut cells = Vec::new();
+    for channels in CHANNELS {
+        for payload in PAYLOADS {
+            for mode in MODES {
+                let cell = matrix::run_cell(channels, payload, mode, options).map_err(|e| e.to_string())?;
+                eprintln!(
+                    "[venom-bench] {:<20} p50 {:>9.1}µs  p99 {:>9.1}µs  {:>10.0} trips/s  {:>9.1} MB/s",
+                    cell.label(),
+                    cell.p50_ns as f64 / 1000.0,
+                    cell.p99_ns as f64 / 1000.0,
+                    cell.throughput,
+                    cell.bandwidth_mb_s
+                );
+                cells.push(cell);
+            }
+        }
+    }
+
+    let report = Report {
+        format_version: FORMAT_VERSION,
+        bench_version: env!("CARGO_PKG_VERSION").to_string(),
+        layout_version: venom_memory::layout::VERSION,
+        machine: Machine::current(),
+        duration_ms: options.duration.as_millis() as u64,
+        pinned: options.pin,
+        cells,
+    };
+    let json = serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?;
+    match output {
+        Some(path) => fs::write(&path, json + "\n").map_err(|e| format!("{}: {}", path.display(), e))?,
+        None => println!("{}", json),
+    }
+    Ok(true)
+}
+
+fn compare(baseline: &Path, current: &Path, tolerance: f64) -> Result<bool, String> {
+    let (baseline, current) = (report::load(baseline)?, report::load(current)?);
+    if baseline.pinned != current.pinned || baseline.machine != current.machine {
+        eprintln!("[venom-bench] the runs differ in machine or pinning; differences may not be regressions");
+    }
+    let comparison = compare::compare(&baseline, &current, tolerance);
+    compare::print(&comparison, tolerance);
+    Ok(!comparison.regressed())
+}
@@ -0,0 +1,172 @@
+//! The benchmark matrix: channel counts × payload sizes × wait modes, each
+//! cell timed through `DaemonChannel` and `ShellChannel`
+
+use crate::report::Cell;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use venom_memory::{affinity, ChannelConfig, DaemonChannel, Result, ShellChannel};
+
+/// Daemon/shell pairs running at once
+pub const CHANNELS: [usize; 3] = [1, 4, 8];
+/// Bytes the daemon writes and the shell reads each round trip
+pub const PAYLOADS: [usize; 3] = [1024, 64 * 1024, 256 * 1024];
+pub const MODES: [WaitMode; 2] = [WaitMode::Spin, WaitMode::Backoff];
+
+/// How each side waits for the other
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WaitMode {
+    /// Poll without ever giving up the CPU
+    Spin,
+    /// Poll briefly, then yield, then sleep between polls
+    Backoff,
+}
+
+impl fmt::Display for WaitMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            WaitMode::Spin => "spin",
+            WaitMode::Backoff => "backoff",
+        })
+    }
+}
+
+/// Polls between checks of whether to give up waiting
+const POLLS_PER_CHECK: u32 = 1024;
+
+impl WaitMode {
+    /// Poll `ready` until it returns true, or until `give_up` does;
+    /// returns whether `ready` did
+    fn wait(self, mut ready: impl FnMut() -> bool, give_up: impl Fn() -> bool) -> bool {
+        let mut polls = 0u32;
+        loop {
+            if ready() {
+                return true;
+            }
+            polls = polls.wrapping_add(1);
+            if polls.is_multiple_of(POLLS_PER_CHECK) && give_up() {
+                return false;
+            }
+            match self {
+                WaitMode::Spin => std::hint::spin_loop(),
+                WaitMode::Backoff if polls < 64 => std::hint::spin_loop(),
+                WaitMode::Backoff if polls < 256 => std::thread::yield_now(),
+                WaitMode::Backoff => std::thread::sleep(Duration::from_micros(50)),
+            }
+        }
+    }
+}
+
+/// How to run each cell
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// How long each cell runs for
+    pub duration: Duration,
+    /// Pin each daemon and shell thread to a CPU, round robin over the
+    /// CPUs the process may use
+    pub pin: bool,
+}
+
+/// Run `channels` daemon/shell pairs passing `payload` bytes back and
+/// forth for `options.duration`
+pub fn run_cell(channels: usize, payload: usize, mode: WaitMode, options: Options) -> Result<Cell> {
+    let config = ChannelConfig { data_size: payload, cmd_slots: 4, max_clients: 1, ..ChannelConfig::default() };
+    let pairs = (0..channels)
+        .map(|i| {
+            let name = format!("bench_{}_{}", std::process::id(), i);
+            let daemon = DaemonChannel::create(&name, config.clone())?;
+            let shell = ShellChannel::connect(&name)?;
+            Ok((daemon, shell))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let cpus = if options.pin { affinity::thread_cores().unwrap_or_default() } else { Vec::new() };
+    let pin = |thread: usize| {
+        if let Some(&cpu) = cpus.get(thread % cpus.len().max(1))
+            && let Err(e) = affinity::pin_current_thread(cpu)
+        {
+            eprintln!("[venom-bench] can't pin to CPU {}: {}", cpu, e);
+        }
+    };
+
+    let started = Instant::now();
+    let deadline = started + options.duration;
+    let mut samples: Vec<u64> = std::thread::scope(|scope| {
+        let daemons: Vec<_> = pairs
+            .into_iter()
+            .enumerate()
+            .map(|(i, (daemon, shell))| {
+                let stop = AtomicBool::new(false);
+                scope.spawn(move || {
+                    std::thread::scope(|pair| {
+                        // Sequence before the first write, taken before the daemon can make one
+                        let mut seen = shell.data_sequence();
+                        let stop = &stop;
+                        pair.spawn(move || {
+                            pin(2 * i + 1);
+                            let mut buf = vec![0u8; payload];
+                            loop {
+                                let fresh = |seq: u64| seq != seen && seq.is_multiple_of(2);
+                                if !mode.wait(|| fresh(shell.data_sequence()), || stop.load(Ordering::Relaxed)) {
+                                    return;
+                                }
+                                // The daemon waits for us before writing again
+                                seen = shell.data_sequence();
+                                shell.read_data(&mut buf);
+                                shell.send_command(b"ack");
+                            }
+                        });
+
+                        pin(2 * i);
+                        let data = vec![0xA5u8; payload];
+                        let mut ack = [0u8; 8];
+                        let mut samples = Vec::new();
+                        while Instant::now() < deadline {
+                            let sent = Instant::now();
+                            daemon.write_data(&data);
+                            if !mode.wait(|| daemon.try_recv_command(&mut ack).is_some(), || Instant::now() >= deadline) {
+                                break;
+                            }
+                            samples.push(sent.elapsed().as_nanos() as u64);
+                        }
+                        stop.store(true, Ordering::Relaxed);
+                        samples
+                    })
+                })
+            })
+            .collect();
+        daemons.into_iter().flat_map(|daemon| daemon.join().expect("benchmark thread panicked")).collect()
+    });
+    let elapsed = started.elapsed().as_secs_f64();
+
+    samples.sort_unstable();
+    let percentile = |p: usize| samples.get((samples.len() * p / 100).min(samples.len().saturating_sub(1))).copied().unwrap_or(0);
+    let throughput = samples.len() as f64 / elapsed;
+    Ok(Cell {
+        channels,
+        payload_bytes: payload,
+        mode,
+        round_trips: samples.len() as u64,
+        p50_ns: percentile(50),
+        p99_ns: percentile(99),
+        throughput,
+        bandwidth_mb_s: throughput * payload as f64 / 1e6,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cell_round_trips() {
+        let options = Options { duration: Duration::from_millis(100), pin: false };
+        for mode in MODES {
+            let cell = run_cell(2, 1024, mode, options).unwrap();
+            assert!(cell.round_trips > 0, "{:?}", cell);
+            assert!(cell.p50_ns > 0 && cell.p50_ns <= cell.p99_ns, "{:?}", cell);
+            assert_eq!(cell.bandwidth_mb_s, cell.throughput * 1024.0 / 1e6);
+        }
+    }
+}
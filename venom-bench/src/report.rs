@@ -0,0 +1,110 @@
+//! The results document `venom-bench run` writes and `venom-bench compare`
+//! reads
+
+use crate::matrix::WaitMode;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Version of the document's layout; `compare` refuses documents of another
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Results of one `venom-bench run`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Report {
+    pub format_version: u32,
+    /// venom-bench's version, which is built with the venom_memory next to it
+    pub bench_version: String,
+    /// Version of the channel layout the library writes
+    pub layout_version: u32,
+    pub machine: Machine,
+    /// How long each cell ran for
+    pub duration_ms: u64,
+    /// Whether each thread was pinned to a CPU of its own
+    pub pinned: bool,
+    pub cells: Vec<Cell>,
+}
+
+/// What the run ran on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Machine {
+    pub cpu_model: String,
+    /// CPUs the run could use
+    pub cpus: usize,
+    pub arch: String,
+    pub kernel: String,
+}
+
+impl Machine {
+    /// This machine
+    pub fn current() -> Self {
+        let cpu_model = fs::read_to_string("/proc/cpuinfo")
+            .ok()
+            .and_then(|info| {
+                info.lines()
+                    .find_map(|line| line.strip_prefix("model name")?.split_once(':').map(|(_, model)| model.trim().to_string()))
+            })
+            .unwrap_or_else(|| "unknown".to_string());
+        let uname = rustix::system::uname();
+        Machine {
+            cpu_model,
+            cpus: venom_memory::affinity::thread_cores().map_or(1, |cpus| cpus.len()),
+            arch: std::env::consts::ARCH.to_string(),
+            kernel: uname.release().to_string_lossy().into_owned(),
+        }
+    }
+}
+
+/// One cell of the matrix: `channels` daemon/shell pairs passing
+/// `payload_bytes` back and forth at once
+///
+/// A round trip is the daemon writing the payload, the shell noticing the
+/// write and reading all of it, and the daemon receiving the shell's
+/// acknowledgement command.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cell {
+    pub channels: usize,
+    pub payload_bytes: usize,
+    pub mode: WaitMode,
+    /// Round trips over all channels
+    pub round_trips: u64,
+    /// Median round trip, in nanoseconds
+    pub p50_ns: u64,
+    /// 99th percentile round trip, in nanoseconds
+    pub p99_ns: u64,
+    /// Round trips per second, all channels together
+    pub throughput: f64,
+    /// Payload bytes read per second, all channels together, in MB/s
+    pub bandwidth_mb_s: f64,
+}
+
+impl Cell {
+    /// What tells cells apart: the same one in two reports has the same key
+    pub fn key(&self) -> (usize, usize, WaitMode) {
+        (self.channels, self.payload_bytes, self.mode)
+    }
+
+    /// `4ch 64KB spin`
+    pub fn label(&self) -> String {
+        let size = match self.payload_bytes {
+            n if n >= 1024 && n % 1024 == 0 => format!("{}KB", n / 1024),
+            n => format!("{}B", n),
+        };
+        format!("{}ch {} {}", self.channels, size, self.mode)
+    }
+}
+
+/// Read a report from `path`
+pub fn load(path: &Path) -> Result<Report, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let report: Report = serde_json::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+    if report.format_version != FORMAT_VERSION {
+        return Err(format!(
+            "{}: results format {}, this venom-bench reads {}",
+            path.display(),
+            report.format_version,
+            FORMAT_VERSION
+        ));
+    }
+    Ok(report)
+}
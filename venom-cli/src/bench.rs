@@ -0,0 +1,1038 @@
+//! `venom bench`: the same read-latency benchmark in several languages
+//!
+//! The clients' own latency readouts all measure slightly different things,
+//! so `bench/<lang>/` gets a dedicated benchmark per language that works
+//! identically everywhere: connect to the channel, do `--warmup` untimed
+//! reads, time `--iterations` reads one by one with the language's monotonic
+//! wall clock, and take nearest-rank percentiles of the sorted samples. Each
+//! prints a percentile table and writes `--out` (default `result.json`):
+//!
+//! ```json
+//! {"language": "c", "channel": "sensors", "warmup": 1000, "iterations": 100000,
+//!  "min_ns": 41, "mean_ns": 52, "p50_ns": 48, "p90_ns": 60, "p99_ns": 95,
+//!  "p999_ns": 410, "max_ns": 12040}
+//! ```
+//!
+//! `bench/collect` is a small Rust crate that builds and runs every one of
+//! them against the running daemon, prints a comparison table and gathers
+//! the results into `bench/results.json`.
+
+use crate::library::{library_name_for, LIBRARY_NAME};
+use crate::templates::{Language, ProjectConfig};
+
+/// Languages with a benchmark client
+pub const SUPPORTED: &[Language] = &[
+    Language::C,
+    Language::Cpp,
+    Language::Rust,
+    Language::Python,
+    Language::Go,
+    Language::Nim,
+];
+
+/// Defaults shared by every benchmark and the collector
+const WARMUP: u64 = 1000;
+const ITERATIONS: u64 = 100_000;
+
+/// Languages to benchmark for a project: `requested`, or else the project's
+/// own languages that have a benchmark client
+pub fn languages(config: &ProjectConfig, lang: Language, requested: &[Language]) -> Result<Vec<Language>, String> {
+    let supported = || SUPPORTED.iter().map(|l| l.id()).collect::<Vec<_>>().join(", ");
+    if let Some(missing) = requested.iter().find(|l| !SUPPORTED.contains(l)) {
+        return Err(format!("no benchmark client for {} (available: {})", missing.id(), supported()));
+    }
+    let candidates: Vec<Language> = if requested.is_empty() {
+        std::iter::once(lang).chain(config.clients.iter().copied()).collect()
+    } else {
+        requested.to_vec()
+    };
+    let mut langs = Vec::new();
+    for l in candidates {
+        if SUPPORTED.contains(&l) && !langs.contains(&l) {
+            langs.push(l);
+        }
+    }
+    if langs.is_empty() {
+        return Err(format!(
+            "none of this project's languages has a benchmark client; pick some with --lang ({})",
+            supported()
+        ));
+    }
+    Ok(langs)
+}
+
+/// Generate `bench/` in the project for `langs`
+pub fn generate(config: &ProjectConfig, langs: &[Language]) {
+    let base = format!("{}/bench", config.output_dir);
+    for &lang in langs {
+        let dir = format!("{}/{}", base, lang.id());
+        crate::create_dir(&dir);
+        match lang {
+            Language::C => {
+                crate::write_file(&format!("{}/bench.c", dir), &bench_c(config));
+                crate::write_file(&format!("{}/Makefile", dir), &makefile_c(config, "gcc", "-std=c11", "bench.c"));
+            }
+            Language::Cpp => {
+                crate::write_file(&format!("{}/bench.cpp", dir), &bench_cpp(config));
+                crate::write_file(&format!("{}/Makefile", dir), &makefile_c(config, "g++", "-std=c++17", "bench.cpp"));
+            }
+            Language::Rust => {
+                crate::create_dir(&format!("{}/src", dir));
+                crate::write_file(&format!("{}/Cargo.toml", dir), BENCH_RS_CARGO_TOML);
+                crate::write_file(&format!("{}/build.rs", dir), &bench_rs_build(config));
+                crate::write_file(&format!("{}/src/main.rs", dir), &bench_rs(config));
+            }
+            Language::Python => {
+                crate::write_file(&format!("{}/bench.py", dir), &bench_py(config));
+            }
+            Language::Go => {
+                crate::write_file(&format!("{}/go.mod", dir), "module bench\n\ngo 1.21\n");
+                crate::write_file(&format!("{}/main.go", dir), &bench_go(config));
+            }
+            Language::Nim => {
+                crate::write_file(&format!("{}/bench.nim", dir), &bench_nim(config));
+                crate::write_file(&format!("{}/Makefile", dir), &makefile_nim(config));
+            }
+            _ => unreachable!("checked by languages()"),
+        }
+    }
+    crate::create_dir(&format!("{}/collect/src", base));
+    crate::write_file(&format!("{}/collect/Cargo.toml", base), COLLECT_CARGO_TOML);
+    crate::write_file(&format!("{}/collect/src/main.rs", base), &collect_rs(langs));
+}
+
+/// `lib/` (or the first bundled target's `lib/<triple>/`) as seen from `bench/<lang>/`
+fn lib_dir(config: &ProjectConfig) -> String {
+    match config.targets.first() {
+        None => "../../lib".to_string(),
+        Some(triple) => format!("../../lib/{}", triple),
+    }
+}
+
+fn library_file(config: &ProjectConfig) -> &'static str {
+    config.targets.first().map_or(LIBRARY_NAME, |t| library_name_for(t))
+}
+
+/// How the collector builds and runs one language's benchmark, from `bench/<lang>/`
+fn build_and_run(lang: Language) -> (&'static [&'static str], &'static [&'static str]) {
+    match lang {
+        Language::C | Language::Cpp | Language::Nim => (&["make"], &["./bench"]),
+        Language::Rust => (&["cargo", "build", "--release", "--quiet"], &["./target/release/bench"]),
+        Language::Python => (&[], &["python3", "bench.py"]),
+        Language::Go => (&["go", "build", "-o", "bench", "."], &["./bench"]),
+        _ => unreachable!("checked by languages()"),
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// C / C++
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn bench_c(config: &ProjectConfig) -> String {
+    format!(r#"/**
+ * {name} read-latency benchmark (C) - generated by `venom bench`
+ * Usage: ./bench [--warmup N] [--iterations M] [--out FILE]
+ */
+
+#define _POSIX_C_SOURCE 199309L  /* clock_gettime under -std=c11 */
+#include <stdio.h>
+#include <stdlib.h>
+#include <stdint.h>
+#include <string.h>
+#include <time.h>
+
+typedef struct VenomShellHandle VenomShellHandle;
+extern VenomShellHandle* venom_shell_connect(const char* name);
+extern void venom_shell_destroy(VenomShellHandle* handle);
+extern size_t venom_shell_read_data(VenomShellHandle* handle, uint8_t* buf, size_t max_len);
+
+#define CHANNEL "{channel}"
+#define BUF_SIZE {data_size}
+
+static uint8_t g_buf[BUF_SIZE];
+
+static uint64_t now_ns(void) {{
+    struct timespec ts;
+    clock_gettime(CLOCK_MONOTONIC, &ts);
+    return (uint64_t)ts.tv_sec * 1000000000ULL + (uint64_t)ts.tv_nsec;
+}}
+
+static int cmp_u64(const void* a, const void* b) {{
+    uint64_t x = *(const uint64_t*)a, y = *(const uint64_t*)b;
+    return (x > y) - (x < y);
+}}
+
+/// Nearest-rank percentile of sorted samples; `permille` out of 1000
+static uint64_t percentile(const uint64_t* sorted, uint64_t n, uint64_t permille) {{
+    uint64_t rank = (permille * n + 999) / 1000;
+    return sorted[rank > 0 ? rank - 1 : 0];
+}}
+
+int main(int argc, char** argv) {{
+    uint64_t warmup = {warmup}, iterations = {iterations};
+    const char* out = "result.json";
+    for (int i = 1; i + 1 < argc; i += 2) {{
+        if (strcmp(argv[i], "--warmup") == 0) warmup = strtoull(argv[i + 1], NULL, 10);
+        else if (strcmp(argv[i], "--iterations") == 0) iterations = strtoull(argv[i + 1], NULL, 10);
+        else if (strcmp(argv[i], "--out") == 0) out = argv[i + 1];
+        else {{ fprintf(stderr, "unknown argument %s\n", argv[i]); return 2; }}
+    }}
+    if (iterations == 0) {{ fprintf(stderr, "--iterations must be at least 1\n"); return 2; }}
+
+    VenomShellHandle* shell = venom_shell_connect(CHANNEL);
+    if (!shell) {{ fprintf(stderr, "❌ Cannot connect to %s; start the daemon first\n", CHANNEL); return 1; }}
+    uint64_t* samples = malloc(iterations * sizeof(uint64_t));
+    if (!samples) {{ fprintf(stderr, "out of memory\n"); return 1; }}
+
+    for (uint64_t i = 0; i < warmup; i++) venom_shell_read_data(shell, g_buf, BUF_SIZE);
+    for (uint64_t i = 0; i < iterations; i++) {{
+        uint64_t start = now_ns();
+        venom_shell_read_data(shell, g_buf, BUF_SIZE);
+        samples[i] = now_ns() - start;
+    }}
+    venom_shell_destroy(shell);
+
+    qsort(samples, iterations, sizeof(uint64_t), cmp_u64);
+    uint64_t sum = 0;
+    for (uint64_t i = 0; i < iterations; i++) sum += samples[i];
+    uint64_t p50 = percentile(samples, iterations, 500), p90 = percentile(samples, iterations, 900);
+    uint64_t p99 = percentile(samples, iterations, 990), p999 = percentile(samples, iterations, 999);
+    uint64_t min = samples[0], max = samples[iterations - 1], mean = sum / iterations;
+
+    printf("📊 c: %llu reads of %s after %llu warmup\n", (unsigned long long)iterations, CHANNEL, (unsigned long long)warmup);
+    printf("  Percentile      Latency\n");
+    printf("     50.000%% %9llu ns\n", (unsigned long long)p50);
+    printf("     90.000%% %9llu ns\n", (unsigned long long)p90);
+    printf("     99.000%% %9llu ns\n", (unsigned long long)p99);
+    printf("     99.900%% %9llu ns\n", (unsigned long long)p999);
+    printf("    100.000%% %9llu ns\n", (unsigned long long)max);
+    printf("  Mean %llu ns, min %llu ns\n", (unsigned long long)mean, (unsigned long long)min);
+
+    FILE* f = fopen(out, "w");
+    if (!f) {{ perror(out); return 1; }}
+    fprintf(f, "{{\"language\": \"c\", \"channel\": \"%s\", \"warmup\": %llu, \"iterations\": %llu, "
+               "\"min_ns\": %llu, \"mean_ns\": %llu, \"p50_ns\": %llu, \"p90_ns\": %llu, "
+               "\"p99_ns\": %llu, \"p999_ns\": %llu, \"max_ns\": %llu}}\n",
+        CHANNEL, (unsigned long long)warmup, (unsigned long long)iterations,
+        (unsigned long long)min, (unsigned long long)mean, (unsigned long long)p50, (unsigned long long)p90,
+        (unsigned long long)p99, (unsigned long long)p999, (unsigned long long)max);
+    fclose(f);
+    free(samples);
+    return 0;
+}}
+"#,
+        name = config.name,
+        channel = config.channel,
+        data_size = config.data_size,
+        warmup = WARMUP,
+        iterations = ITERATIONS,
+    )
+}
+
+fn bench_cpp(config: &ProjectConfig) -> String {
+    format!(r#"/**
+ * {name} read-latency benchmark (C++) - generated by `venom bench`
+ * Usage: ./bench [--warmup N] [--iterations M] [--out FILE]
+ */
+
+#include <algorithm>
+#include <chrono>
+#include <cstdint>
+#include <cstdio>
+#include <fstream>
+#include <iostream>
+#include <numeric>
+#include <string>
+#include <vector>
+
+extern "C" {{
+    void* venom_shell_connect(const char* name);
+    void venom_shell_destroy(void* handle);
+    size_t venom_shell_read_data(void* handle, uint8_t* buf, size_t max_len);
+}}
+
+constexpr const char* CHANNEL = "{channel}";
+constexpr size_t BUF_SIZE = {data_size};
+
+/// Nearest-rank percentile of sorted samples; `permille` out of 1000
+static uint64_t percentile(const std::vector<uint64_t>& sorted, uint64_t permille) {{
+    uint64_t rank = (permille * sorted.size() + 999) / 1000;
+    return sorted[rank > 0 ? rank - 1 : 0];
+}}
+
+int main(int argc, char** argv) {{
+    uint64_t warmup = {warmup}, iterations = {iterations};
+    std::string out = "result.json";
+    for (int i = 1; i + 1 < argc; i += 2) {{
+        std::string arg = argv[i];
+        if (arg == "--warmup") warmup = std::stoull(argv[i + 1]);
+        else if (arg == "--iterations") iterations = std::stoull(argv[i + 1]);
+        else if (arg == "--out") out = argv[i + 1];
+        else {{ std::cerr << "unknown argument " << arg << "\n"; return 2; }}
+    }}
+    if (iterations == 0) {{ std::cerr << "--iterations must be at least 1\n"; return 2; }}
+
+    void* shell = venom_shell_connect(CHANNEL);
+    if (!shell) {{ std::cerr << "❌ Cannot connect to " << CHANNEL << "; start the daemon first\n"; return 1; }}
+    std::vector<uint8_t> buf(BUF_SIZE);
+    std::vector<uint64_t> samples(iterations);
+
+    for (uint64_t i = 0; i < warmup; i++) venom_shell_read_data(shell, buf.data(), BUF_SIZE);
+    for (auto& sample : samples) {{
+        auto start = std::chrono::steady_clock::now();
+        venom_shell_read_data(shell, buf.data(), BUF_SIZE);
+        sample = std::chrono::duration_cast<std::chrono::nanoseconds>(std::chrono::steady_clock::now() - start).count();
+    }}
+    venom_shell_destroy(shell);
+
+    std::sort(samples.begin(), samples.end());
+    uint64_t mean = std::accumulate(samples.begin(), samples.end(), uint64_t{{0}}) / iterations;
+    uint64_t p50 = percentile(samples, 500), p90 = percentile(samples, 900);
+    uint64_t p99 = percentile(samples, 990), p999 = percentile(samples, 999);
+    uint64_t min = samples.front(), max = samples.back();
+
+    std::printf("📊 cpp: %llu reads of %s after %llu warmup\n", (unsigned long long)iterations, CHANNEL, (unsigned long long)warmup);
+    std::printf("  Percentile      Latency\n");
+    std::printf("     50.000%% %9llu ns\n", (unsigned long long)p50);
+    std::printf("     90.000%% %9llu ns\n", (unsigned long long)p90);
+    std::printf("     99.000%% %9llu ns\n", (unsigned long long)p99);
+    std::printf("     99.900%% %9llu ns\n", (unsigned long long)p999);
+    std::printf("    100.000%% %9llu ns\n", (unsigned long long)max);
+    std::printf("  Mean %llu ns, min %llu ns\n", (unsigned long long)mean, (unsigned long long)min);
+
+    std::ofstream f(out);
+    if (!f) {{ std::cerr << "cannot write " << out << "\n"; return 1; }}
+    f << "{{\"language\": \"cpp\", \"channel\": \"" << CHANNEL << "\", \"warmup\": " << warmup
+      << ", \"iterations\": " << iterations << ", \"min_ns\": " << min << ", \"mean_ns\": " << mean
+      << ", \"p50_ns\": " << p50 << ", \"p90_ns\": " << p90 << ", \"p99_ns\": " << p99
+      << ", \"p999_ns\": " << p999 << ", \"max_ns\": " << max << "}}\n";
+    return 0;
+}}
+"#,
+        name = config.name,
+        channel = config.channel,
+        data_size = config.data_size,
+        warmup = WARMUP,
+        iterations = ITERATIONS,
+    )
+}
+
+fn makefile_c(config: &ProjectConfig, compiler: &str, std: &str, source: &str) -> String {
+    format!(r#"# {name} benchmark ({compiler})
+
+CC = {compiler}
+CFLAGS = {std} -Wall -Wextra -O2
+LDFLAGS = -L{lib} -lvenom_memory -Wl,-rpath,'$$ORIGIN/{lib}'
+
+.PHONY: all clean
+
+all: bench
+
+bench: {source}
+	@$(CC) $(CFLAGS) {source} -o bench $(LDFLAGS)
+
+clean:
+	@rm -f bench result.json
+"#,
+        name = config.name,
+        compiler = compiler,
+        std = std,
+        lib = lib_dir(config),
+        source = source,
+    )
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Rust
+// ═══════════════════════════════════════════════════════════════════════════
+
+const BENCH_RS_CARGO_TOML: &str = r#"[package]
+name = "bench"
+version = "0.1.0"
+edition = "2021"
+build = "build.rs"
+
+[dependencies]
+"#;
+
+fn bench_rs_build(config: &ProjectConfig) -> String {
+    format!(r#"fn main() {{
+    // Link the project's bundled library
+    let lib_dir = std::env::current_dir().unwrap().join("{lib}");
+    println!("cargo:rustc-link-search=native={{}}", lib_dir.display());
+    println!("cargo:rustc-link-lib=dylib=venom_memory");
+    println!("cargo:rustc-link-arg=-Wl,-rpath,{{}}", lib_dir.display());
+}}
+"#, lib = lib_dir(config))
+}
+
+fn bench_rs(config: &ProjectConfig) -> String {
+    format!(r#"//! {name} read-latency benchmark (Rust) - generated by `venom bench`
+//!
+//! Usage: ./target/release/bench [--warmup N] [--iterations M] [--out FILE]
+
+use std::ffi::{{c_char, c_void, CString}};
+use std::time::Instant;
+
+extern "C" {{
+    fn venom_shell_connect(name: *const c_char) -> *mut c_void;
+    fn venom_shell_destroy(handle: *mut c_void);
+    fn venom_shell_read_data(handle: *mut c_void, buf: *mut u8, max_len: usize) -> usize;
+}}
+
+const CHANNEL: &str = "{channel}";
+const BUF_SIZE: usize = {data_size};
+
+/// Nearest-rank percentile of sorted samples; `permille` out of 1000
+fn percentile(sorted: &[u64], permille: u64) -> u64 {{
+    let rank = (permille * sorted.len() as u64 + 999) / 1000;
+    sorted[rank.max(1) as usize - 1]
+}}
+
+fn main() {{
+    let args: Vec<String> = std::env::args().collect();
+    let arg = |flag: &str| args.windows(2).find(|w| w[0] == flag).map(|w| w[1].clone());
+    let warmup: u64 = arg("--warmup").map_or({warmup}, |v| v.parse().expect("--warmup"));
+    let iterations: u64 = arg("--iterations").map_or({iterations}, |v| v.parse().expect("--iterations"));
+    let out = arg("--out").unwrap_or_else(|| "result.json".to_string());
+    if iterations == 0 {{
+        eprintln!("--iterations must be at least 1");
+        std::process::exit(2);
+    }}
+
+    let name = CString::new(CHANNEL).unwrap();
+    let shell = unsafe {{ venom_shell_connect(name.as_ptr()) }};
+    if shell.is_null() {{
+        eprintln!("❌ Cannot connect to {{}}; start the daemon first", CHANNEL);
+        std::process::exit(1);
+    }}
+    let mut buf = vec![0u8; BUF_SIZE];
+    let mut samples = Vec::with_capacity(iterations as usize);
+
+    for _ in 0..warmup {{
+        unsafe {{ venom_shell_read_data(shell, buf.as_mut_ptr(), BUF_SIZE) }};
+    }}
+    for _ in 0..iterations {{
+        let start = Instant::now();
+        unsafe {{ venom_shell_read_data(shell, buf.as_mut_ptr(), BUF_SIZE) }};
+        samples.push(start.elapsed().as_nanos() as u64);
+    }}
+    unsafe {{ venom_shell_destroy(shell) }};
+
+    samples.sort_unstable();
+    let mean = samples.iter().sum::<u64>() / iterations;
+    let (p50, p90, p99, p999) = (percentile(&samples, 500), percentile(&samples, 900), percentile(&samples, 990), percentile(&samples, 999));
+    let (min, max) = (samples[0], samples[samples.len() - 1]);
+
+    println!("📊 rust: {{}} reads of {{}} after {{}} warmup", iterations, CHANNEL, warmup);
+    println!("  Percentile      Latency");
+    for (label, value) in [("50.000", p50), ("90.000", p90), ("99.000", p99), ("99.900", p999), ("100.000", max)] {{
+        println!("    {{:>7}}% {{:>9}} ns", label, value);
+    }}
+    println!("  Mean {{}} ns, min {{}} ns", mean, min);
+
+    let json = format!(
+        "{{{{\"language\": \"rust\", \"channel\": \"{{}}\", \"warmup\": {{}}, \"iterations\": {{}}, \
+         \"min_ns\": {{}}, \"mean_ns\": {{}}, \"p50_ns\": {{}}, \"p90_ns\": {{}}, \
+         \"p99_ns\": {{}}, \"p999_ns\": {{}}, \"max_ns\": {{}}}}}}\n",
+        CHANNEL, warmup, iterations, min, mean, p50, p90, p99, p999, max
+    );
+    if let Err(e) = std::fs::write(&out, json) {{
+        eprintln!("cannot write {{}}: {{}}", out, e);
+        std::process::exit(1);
+    }}
+}}
+"#,
+        name = config.name,
+        channel = config.channel,
+        data_size = config.data_size,
+        warmup = WARMUP,
+        iterations = ITERATIONS,
+    )
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Python
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn bench_py(config: &ProjectConfig) -> String {
+    format!(r#"#!/usr/bin/env python3
+"""{name} read-latency benchmark (Python) - generated by `venom bench`
+
+Usage: python3 bench.py [--warmup N] [--iterations M] [--out FILE]
+"""
+
+import argparse
+import ctypes
+import json
+import sys
+import time
+from pathlib import Path
+
+CHANNEL = "{channel}"
+BUF_SIZE = {data_size}
+LIBRARY = Path(__file__).resolve().parent / "{lib}" / "{library}"
+
+
+def percentile(sorted_ns, permille):
+    """Nearest-rank percentile of sorted samples; `permille` out of 1000"""
+    rank = (permille * len(sorted_ns) + 999) // 1000
+    return sorted_ns[max(rank, 1) - 1]
+
+
+def main():
+    parser = argparse.ArgumentParser(description=__doc__.splitlines()[0])
+    parser.add_argument("--warmup", type=int, default={warmup})
+    parser.add_argument("--iterations", type=int, default={iterations})
+    parser.add_argument("--out", default="result.json")
+    args = parser.parse_args()
+    if args.iterations < 1:
+        parser.error("--iterations must be at least 1")
+
+    lib = ctypes.CDLL(str(LIBRARY))
+    lib.venom_shell_connect.argtypes = [ctypes.c_char_p]
+    lib.venom_shell_connect.restype = ctypes.c_void_p
+    lib.venom_shell_destroy.argtypes = [ctypes.c_void_p]
+    lib.venom_shell_read_data.argtypes = [ctypes.c_void_p, ctypes.c_void_p, ctypes.c_size_t]
+    lib.venom_shell_read_data.restype = ctypes.c_size_t
+
+    shell = lib.venom_shell_connect(CHANNEL.encode())
+    if not shell:
+        print(f"❌ Cannot connect to {{CHANNEL}}; start the daemon first", file=sys.stderr)
+        return 1
+    buf = (ctypes.c_uint8 * BUF_SIZE)()
+    read = lib.venom_shell_read_data
+    clock = time.perf_counter_ns
+
+    for _ in range(args.warmup):
+        read(shell, buf, BUF_SIZE)
+    samples = []
+    for _ in range(args.iterations):
+        start = clock()
+        read(shell, buf, BUF_SIZE)
+        samples.append(clock() - start)
+    lib.venom_shell_destroy(shell)
+
+    samples.sort()
+    result = {{
+        "language": "python",
+        "channel": CHANNEL,
+        "warmup": args.warmup,
+        "iterations": args.iterations,
+        "min_ns": samples[0],
+        "mean_ns": sum(samples) // len(samples),
+        "p50_ns": percentile(samples, 500),
+        "p90_ns": percentile(samples, 900),
+        "p99_ns": percentile(samples, 990),
+        "p999_ns": percentile(samples, 999),
+        "max_ns": samples[-1],
+    }}
+
+    print(f"📊 python: {{args.iterations}} reads of {{CHANNEL}} after {{args.warmup}} warmup")
+    print("  Percentile      Latency")
+    for label, key in [("50.000", "p50_ns"), ("90.000", "p90_ns"), ("99.000", "p99_ns"), ("99.900", "p999_ns"), ("100.000", "max_ns")]:
+        print(f"    {{label:>7}}% {{result[key]:>9}} ns")
+    print(f"  Mean {{result['mean_ns']}} ns, min {{result['min_ns']}} ns")
+
+    Path(args.out).write_text(json.dumps(result) + "\n")
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
+"#,
+        name = config.name,
+        channel = config.channel,
+        data_size = config.data_size,
+        lib = lib_dir(config),
+        library = library_file(config),
+        warmup = WARMUP,
+        iterations = ITERATIONS,
+    )
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Go
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn bench_go(config: &ProjectConfig) -> String {
+    format!(r#"// {name} read-latency benchmark (Go) - generated by `venom bench`
+//
+// Usage: ./bench [--warmup N] [--iterations M] [--out FILE]
+package main
+
+/*
+#cgo LDFLAGS: -L${{SRCDIR}}/{lib} -lvenom_memory -Wl,-rpath,${{SRCDIR}}/{lib}
+#include <stdint.h>
+#include <stddef.h>
+#include <stdlib.h>
+
+typedef struct VenomShellHandle VenomShellHandle;
+extern VenomShellHandle* venom_shell_connect(const char* name);
+extern void venom_shell_destroy(VenomShellHandle* handle);
+extern size_t venom_shell_read_data(VenomShellHandle* handle, uint8_t* buf, size_t max_len);
+*/
+import "C"
+
+import (
+	"encoding/json"
+	"flag"
+	"fmt"
+	"os"
+	"sort"
+	"time"
+	"unsafe"
+)
+
+const channel = "{channel}"
+const bufSize = {data_size}
+
+type result struct {{
+	Language   string `json:"language"`
+	Channel    string `json:"channel"`
+	Warmup     uint64 `json:"warmup"`
+	Iterations uint64 `json:"iterations"`
+	MinNs      uint64 `json:"min_ns"`
+	MeanNs     uint64 `json:"mean_ns"`
+	P50Ns      uint64 `json:"p50_ns"`
+	P90Ns      uint64 `json:"p90_ns"`
+	P99Ns      uint64 `json:"p99_ns"`
+	P999Ns     uint64 `json:"p999_ns"`
+	MaxNs      uint64 `json:"max_ns"`
+}}
+
+// percentile is the nearest-rank percentile of sorted samples; permille out of 1000
+func percentile(sorted []uint64, permille uint64) uint64 {{
+	rank := (permille*uint64(len(sorted)) + 999) / 1000
+	if rank == 0 {{
+		rank = 1
+	}}
+	return sorted[rank-1]
+}}
+
+func main() {{
+	warmup := flag.Uint64("warmup", {warmup}, "untimed reads before measuring")
+	iterations := flag.Uint64("iterations", {iterations}, "timed reads")
+	out := flag.String("out", "result.json", "where to write the JSON result")
+	flag.Parse()
+	if *iterations == 0 {{
+		fmt.Fprintln(os.Stderr, "--iterations must be at least 1")
+		os.Exit(2)
+	}}
+
+	name := C.CString(channel)
+	defer C.free(unsafe.Pointer(name))
+	shell := C.venom_shell_connect(name)
+	if shell == nil {{
+		fmt.Fprintf(os.Stderr, "❌ Cannot connect to %s; start the daemon first\n", channel)
+		os.Exit(1)
+	}}
+	buf := (*C.uint8_t)(C.malloc(bufSize))
+	defer C.free(unsafe.Pointer(buf))
+
+	for i := uint64(0); i < *warmup; i++ {{
+		C.venom_shell_read_data(shell, buf, bufSize)
+	}}
+	samples := make([]uint64, *iterations)
+	for i := range samples {{
+		start := time.Now()
+		C.venom_shell_read_data(shell, buf, bufSize)
+		samples[i] = uint64(time.Since(start).Nanoseconds())
+	}}
+	C.venom_shell_destroy(shell)
+
+	sort.Slice(samples, func(a, b int) bool {{ return samples[a] < samples[b] }})
+	var sum uint64
+	for _, s := range samples {{
+		sum += s
+	}}
+	r := result{{
+		Language: "go", Channel: channel, Warmup: *warmup, Iterations: *iterations,
+		MinNs: samples[0], MeanNs: sum / *iterations,
+		P50Ns: percentile(samples, 500), P90Ns: percentile(samples, 900),
+		P99Ns: percentile(samples, 990), P999Ns: percentile(samples, 999),
+		MaxNs: samples[len(samples)-1],
+	}}
+
+	fmt.Printf("📊 go: %d reads of %s after %d warmup\n", r.Iterations, channel, r.Warmup)
+	fmt.Println("  Percentile      Latency")
+	for _, row := range []struct {{
+		label string
+		value uint64
+	}}{{{{"50.000", r.P50Ns}}, {{"90.000", r.P90Ns}}, {{"99.000", r.P99Ns}}, {{"99.900", r.P999Ns}}, {{"100.000", r.MaxNs}}}} {{
+		fmt.Printf("    %7s%% %9d ns\n", row.label, row.value)
+	}}
+	fmt.Printf("  Mean %d ns, min %d ns\n", r.MeanNs, r.MinNs)
+
+	data, _ := json.Marshal(r)
+	if err := os.WriteFile(*out, append(data, '\n'), 0o644); err != nil {{
+		fmt.Fprintln(os.Stderr, err)
+		os.Exit(1)
+	}}
+}}
+"#,
+        name = config.name,
+        channel = config.channel,
+        data_size = config.data_size,
+        lib = lib_dir(config),
+        warmup = WARMUP,
+        iterations = ITERATIONS,
+    )
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Nim
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn bench_nim(config: &ProjectConfig) -> String {
+    format!(r#"## {name} read-latency benchmark (Nim) - generated by `venom bench`
+##
+## Usage: ./bench [--warmup N] [--iterations M] [--out FILE]
+
+import std/[algorithm, json, monotimes, os, strformat, strutils, times]
+
+proc venom_shell_connect(name: cstring): pointer {{.importc, cdecl.}}
+proc venom_shell_destroy(handle: pointer) {{.importc, cdecl.}}
+proc venom_shell_read_data(handle: pointer, buf: ptr uint8, maxLen: csize_t): csize_t {{.importc, cdecl.}}
+
+const
+  Channel = "{channel}"
+  BufSize = {data_size}
+
+proc percentile(sorted: seq[uint64], permille: uint64): uint64 =
+  ## Nearest-rank percentile of sorted samples; `permille` out of 1000
+  let rank = (permille * uint64(sorted.len) + 999) div 1000
+  sorted[int(max(rank, 1'u64)) - 1]
+
+proc main(): int =
+  var
+    warmup = {warmup}'u64
+    iterations = {iterations}'u64
+    output = "result.json"
+  let params = commandLineParams()
+  var i = 0
+  while i + 1 < params.len:
+    case params[i]
+    of "--warmup": warmup = parseBiggestUInt(params[i + 1]).uint64
+    of "--iterations": iterations = parseBiggestUInt(params[i + 1]).uint64
+    of "--out": output = params[i + 1]
+    else:
+      stderr.writeLine "unknown argument " & params[i]
+      return 2
+    i += 2
+  if iterations == 0:
+    stderr.writeLine "--iterations must be at least 1"
+    return 2
+
+  let shell = venom_shell_connect(Channel)
+  if shell == nil:
+    stderr.writeLine "❌ Cannot connect to " & Channel & "; start the daemon first"
+    return 1
+  var buf = newSeq[uint8](BufSize)
+  var samples = newSeq[uint64](int(iterations))
+
+  for _ in 0'u64 ..< warmup:
+    discard venom_shell_read_data(shell, addr buf[0], csize_t(BufSize))
+  for s in samples.mitems:
+    # Monotonic wall clock; cpuTime would only count this process's CPU time
+    let start = getMonoTime()
+    discard venom_shell_read_data(shell, addr buf[0], csize_t(BufSize))
+    s = uint64((getMonoTime() - start).inNanoseconds)
+  venom_shell_destroy(shell)
+
+  samples.sort()
+  var total = 0'u64
+  for s in samples: total += s
+  let report = %*{{
+    "language": "nim",
+    "channel": Channel,
+    "warmup": warmup,
+    "iterations": iterations,
+    "min_ns": samples[0],
+    "mean_ns": total div iterations,
+    "p50_ns": percentile(samples, 500),
+    "p90_ns": percentile(samples, 900),
+    "p99_ns": percentile(samples, 990),
+    "p999_ns": percentile(samples, 999),
+    "max_ns": samples[^1],
+  }}
+
+  echo fmt"📊 nim: {{iterations}} reads of {{Channel}} after {{warmup}} warmup"
+  echo "  Percentile      Latency"
+  for (label, key) in [("50.000", "p50_ns"), ("90.000", "p90_ns"), ("99.000", "p99_ns"), ("99.900", "p999_ns"), ("100.000", "max_ns")]:
+    echo "    ", label.align(7), "% ", ($report[key].getBiggestInt).align(9), " ns"
+  echo fmt"""  Mean {{report["mean_ns"].getBiggestInt}} ns, min {{samples[0]}} ns"""
+
+  writeFile(output, $report & "\n")
+  0
+
+when isMainModule:
+  quit(main())
+"#,
+        name = config.name,
+        channel = config.channel,
+        data_size = config.data_size,
+        warmup = WARMUP,
+        iterations = ITERATIONS,
+    )
+}
+
+fn makefile_nim(config: &ProjectConfig) -> String {
+    format!(r#"# {name} benchmark (Nim)
+
+.PHONY: all clean
+
+all: bench
+
+bench: bench.nim
+	@nim c -d:release --hints:off --passL:"-L{lib} -lvenom_memory -Wl,-rpath,\$$ORIGIN/{lib}" -o:bench bench.nim
+
+clean:
+	@rm -f bench result.json
+"#,
+        name = config.name,
+        lib = lib_dir(config),
+    )
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Collector
+// ═══════════════════════════════════════════════════════════════════════════
+
+const COLLECT_CARGO_TOML: &str = r#"[package]
+name = "bench-collect"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+serde = { version = "1", features = ["derive"] }
+serde_json = "1"
+"#;
+
+fn collect_rs(langs: &[Language]) -> String {
+    let quoted = |args: &[&str]| args.iter().map(|a| format!("{:?}", a)).collect::<Vec<_>>().join(", ");
+    let benches: String = langs
+        .iter()
+        .map(|&lang| {
+            let (build, run) = build_and_run(lang);
+            format!(
+                "    Bench {{ language: \"{}\", build: &[{}], run: &[{}] }},\n",
+                lang.id(),
+                quoted(build),
+                quoted(run)
+            )
+        })
+        .collect();
+    format!(r#"//! bench-collect: build and run every benchmark in bench/, then compare them
+//!
+//! Start the project's daemon first, then:
+//!
+//!     cargo run --release -- [--warmup N] [--iterations M]
+//!
+//! Each benchmark writes bench/<language>/result.json; the collector prints a
+//! table of them and gathers them into bench/results.json.
+
+use serde::{{Deserialize, Serialize}};
+use std::path::{{Path, PathBuf}};
+use std::process::Command;
+
+/// One language's benchmark, built and run from bench/<language>/
+struct Bench {{
+    language: &'static str,
+    build: &'static [&'static str],
+    run: &'static [&'static str],
+}}
+
+const BENCHES: &[Bench] = &[
+{benches}];
+
+/// What every benchmark writes to its result.json
+#[derive(Serialize, Deserialize)]
+struct BenchResult {{
+    language: String,
+    channel: String,
+    warmup: u64,
+    iterations: u64,
+    min_ns: u64,
+    mean_ns: u64,
+    p50_ns: u64,
+    p90_ns: u64,
+    p99_ns: u64,
+    p999_ns: u64,
+    max_ns: u64,
+}}
+
+#[derive(Serialize)]
+struct Results {{
+    warmup: u64,
+    iterations: u64,
+    results: Vec<BenchResult>,
+    /// Languages whose benchmark did not build or run
+    failed: Vec<String>,
+}}
+
+/// Run `args[0]` with the rest as arguments in `dir`
+fn run(dir: &Path, args: &[String]) -> Result<(), String> {{
+    // A relative program path is resolved against our cwd, not current_dir
+    let program = if args[0].contains('/') {{ dir.join(&args[0]) }} else {{ PathBuf::from(&args[0]) }};
+    let status = Command::new(program)
+        .args(&args[1..])
+        .current_dir(dir)
+        .status()
+        .map_err(|e| format!("{{}}: {{}}", args[0], e))?;
+    if status.success() {{ Ok(()) }} else {{ Err(format!("`{{}}` failed ({{}})", args.join(" "), status)) }}
+}}
+
+fn bench(dir: &Path, bench: &Bench, warmup: u64, iterations: u64) -> Result<BenchResult, String> {{
+    if !bench.build.is_empty() {{
+        println!("🔨 {{}}: {{}}", bench.language, bench.build.join(" "));
+        run(dir, &bench.build.iter().map(|s| s.to_string()).collect::<Vec<_>>())?;
+    }}
+    let out = dir.join("result.json");
+    std::fs::remove_file(&out).ok();
+    let mut args: Vec<String> = bench.run.iter().map(|s| s.to_string()).collect();
+    args.extend(["--warmup".to_string(), warmup.to_string(), "--iterations".to_string(), iterations.to_string()]);
+    args.extend(["--out".to_string(), "result.json".to_string()]);
+    run(dir, &args)?;
+    let text = std::fs::read_to_string(&out).map_err(|e| format!("{{}}: {{}}", out.display(), e))?;
+    serde_json::from_str(&text).map_err(|e| format!("{{}}: {{}}", out.display(), e))
+}}
+
+fn main() {{
+    let args: Vec<String> = std::env::args().collect();
+    let arg = |flag: &str, default: u64| {{
+        args.windows(2).find(|w| w[0] == flag).map_or(default, |w| w[1].parse().expect(flag))
+    }};
+    let warmup = arg("--warmup", {warmup});
+    let iterations = arg("--iterations", {iterations});
+    let bench_dir = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap().to_path_buf();
+
+    let mut results = Results {{ warmup, iterations, results: Vec::new(), failed: Vec::new() }};
+    for b in BENCHES {{
+        match bench(&bench_dir.join(b.language), b, warmup, iterations) {{
+            Ok(result) => results.results.push(result),
+            Err(e) => {{
+                eprintln!("❌ {{}}: {{}}", b.language, e);
+                results.failed.push(b.language.to_string());
+            }}
+        }}
+        println!();
+    }}
+
+    println!("{{:<10}} {{:>10}} {{:>10}} {{:>10}} {{:>10}} {{:>10}} {{:>10}}", "language", "p50 ns", "p90 ns", "p99 ns", "p99.9 ns", "max ns", "mean ns");
+    for r in &results.results {{
+        println!(
+            "{{:<10}} {{:>10}} {{:>10}} {{:>10}} {{:>10}} {{:>10}} {{:>10}}",
+            r.language, r.p50_ns, r.p90_ns, r.p99_ns, r.p999_ns, r.max_ns, r.mean_ns
+        );
+    }}
+
+    let path = bench_dir.join("results.json");
+    let json = serde_json::to_string_pretty(&results).unwrap();
+    if let Err(e) = std::fs::write(&path, json + "\n") {{
+        eprintln!("❌ {{}}: {{}}", path.display(), e);
+        std::process::exit(1);
+    }}
+    println!("\n📄 {{}}", path.display());
+    if !results.failed.is_empty() {{
+        std::process::exit(1);
+    }}
+}}
+"#,
+        benches = benches,
+        warmup = WARMUP,
+        iterations = ITERATIONS,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::Schema;
+    use crate::templates::{self, License, PythonBinding};
+    use std::path::Path;
+    use std::process::{Command, Stdio};
+    use std::time::{Duration, Instant};
+
+    fn config(name: &str) -> ProjectConfig {
+        let dir = std::env::temp_dir().join(format!("venom-bench-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let channel = format!("{}-{}", name, std::process::id());
+        ProjectConfig {
+            name: name.to_string(),
+            magic: templates::channel_magic(&channel),
+            channel,
+            data_size: 16 * 1024,
+            cmd_slots: 32,
+            max_clients: 16,
+            shm_mode: 0o664,
+            output_dir: dir.display().to_string(),
+            python_binding: PythonBinding::Ctypes,
+            go_nocgo: false,
+            flutter_ui: true,
+            python_async: false,
+            schema: Schema::demo(),
+            targets: Vec::new(),
+            clients: vec![Language::Python],
+            root: String::new(),
+            service: false,
+            license: License::None,
+            extras: true,
+            license_year: 2026,
+        }
+    }
+
+    #[test]
+    fn test_languages() {
+        let config = config("benchlangs");
+        assert_eq!(languages(&config, Language::C, &[]).unwrap(), [Language::C, Language::Python]);
+        assert_eq!(languages(&config, Language::Lua, &[]).unwrap(), [Language::Python]);
+        assert_eq!(languages(&config, Language::C, &[Language::Rust]).unwrap(), [Language::Rust]);
+        assert!(languages(&config, Language::C, &[Language::Java]).unwrap_err().contains("java"));
+        let lua_only = ProjectConfig { clients: Vec::new(), ..config };
+        assert!(languages(&lua_only, Language::Lua, &[]).is_err());
+    }
+
+    #[test]
+    fn test_collector_compares_every_language() {
+        let config = config("benchall");
+        let dir = Path::new(&config.output_dir);
+        crate::generate_project(&config, Language::C);
+        generate(&config, &[Language::C, Language::Python, Language::Rust]);
+        assert!(Command::new("make").arg("-s").current_dir(dir.join("daemon")).status().unwrap().success());
+
+        let mut daemon = Command::new(dir.join(format!("daemon/{}_daemon", config.name)))
+            .stdout(Stdio::null())
+            .spawn()
+            .unwrap();
+        let shm = Path::new("/dev/shm").join(format!("venom_{}", config.channel));
+        let start = Instant::now();
+        while !shm.exists() && start.elapsed() < Duration::from_secs(10) {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        let collect = Command::new("cargo")
+            .args(["run", "--offline", "--quiet", "--manifest-path"])
+            .arg(dir.join("bench/collect/Cargo.toml"))
+            .args(["--", "--warmup", "10", "--iterations", "2000"])
+            .env("CARGO_NET_OFFLINE", "true")
+            .stdout(Stdio::null())
+            .status()
+            .unwrap();
+        unsafe { libc::kill(daemon.id() as i32, libc::SIGINT) };
+        daemon.wait().unwrap();
+        let results = std::fs::read_to_string(dir.join("bench/results.json"));
+        std::fs::remove_dir_all(dir).ok();
+
+        assert!(collect.success());
+        let results: serde_json::Value = serde_json::from_str(&results.unwrap()).unwrap();
+        let results = results["results"].as_array().unwrap();
+        let langs: Vec<&str> = results.iter().map(|r| r["language"].as_str().unwrap()).collect();
+        assert_eq!(langs, ["c", "python", "rust"]);
+        for r in results {
+            assert_eq!(r["iterations"], 2000);
+            let ns = |key: &str| r[key].as_u64().unwrap();
+            assert!(ns("min_ns") <= ns("p50_ns") && ns("p50_ns") <= ns("p99_ns"));
+            assert!(ns("p99_ns") <= ns("p999_ns") && ns("p999_ns") <= ns("max_ns"));
+        }
+    }
+}
@@ -0,0 +1,121 @@
+//! Channel clashes `venom init` warns about
+//!
+//! Nothing stops two projects on one machine from picking the same channel:
+//! the second daemon then fails to start while the first is running. Two
+//! channels can also end up with the same state magic, and a client pointed
+//! at the wrong one accepts its state as valid. Both are found by looking at
+//! the channels currently in `/dev/shm`; the state magic of a channel is
+//! read from its data region without connecting to it.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::PathBuf;
+use venom_memory::discover::{self, ChannelSummary};
+use venom_memory::layout;
+
+/// Directory Linux exposes POSIX shared memory objects in
+const SHM_DIR: &str = "/dev/shm";
+
+/// Warnings for a new project publishing state with `magic` on `channel`
+pub fn warnings(channel: &str, magic: u32) -> Vec<String> {
+    check(&discover::list_channels(), channel, magic, state_magic)
+}
+
+fn check(
+    channels: &[ChannelSummary],
+    channel: &str,
+    magic: u32,
+    state_magic: impl Fn(&str) -> Option<u32>,
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+    for c in channels {
+        if c.namespace == channel {
+            warnings.push(if c.owner_alive {
+                format!(
+                    "channel \"{}\" is in use by a running daemon (PID {}); the new daemon can't start until it exits",
+                    channel, c.owner_pid
+                )
+            } else {
+                format!(
+                    "channel \"{}\" is left over in {} from a daemon that exited (PID {})",
+                    channel, SHM_DIR, c.owner_pid
+                )
+            });
+        } else if c.owner_alive && state_magic(&c.namespace) == Some(magic) {
+            warnings.push(format!(
+                "channel \"{}\" already publishes state with magic 0x{:08X}; a client connected to the wrong channel would accept it",
+                c.namespace, magic
+            ));
+        }
+    }
+    warnings
+}
+
+/// Magic at the start of the state a channel currently publishes, if it has
+/// published at least that much through the length-prefixed C API
+fn state_magic(namespace: &str) -> Option<u32> {
+    let name = format!("{}{}", layout::SHM_PREFIX.trim_start_matches('/'), namespace);
+    let mut file = File::open(PathBuf::from(SHM_DIR).join(name)).ok()?;
+    let mut header = [0u8; layout::header::SIZE];
+    file.read_exact(&mut header).ok()?;
+    let at = layout::header::SEQLOCK_OFFSET;
+    let seqlock = usize::from_ne_bytes(header[at..at + size_of::<usize>()].try_into().ok()?);
+
+    let mut data = [0u8; layout::seqlock::LEN_PREFIX + 4];
+    file.seek(SeekFrom::Start((seqlock + layout::seqlock::SIZE) as u64)).ok()?;
+    file.read_exact(&mut data).ok()?;
+    let len = u64::from_le_bytes(data[..layout::seqlock::LEN_PREFIX].try_into().ok()?);
+    if len < 4 {
+        return None;
+    }
+    Some(u32::from_le_bytes(data[layout::seqlock::LEN_PREFIX..].try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use venom_memory::{ChannelConfig, DaemonChannel};
+
+    fn summary(namespace: &str, owner_alive: bool) -> ChannelSummary {
+        ChannelSummary {
+            namespace: namespace.to_string(),
+            size: 0,
+            version: layout::VERSION,
+            owner_pid: 4242,
+            owner_alive,
+            client_count: 0,
+        }
+    }
+
+    #[test]
+    fn test_warns_about_name_and_magic_clashes() {
+        let channels = [summary("sensors", true), summary("other", true), summary("stale", false)];
+        let magic_of = |name: &str| if name == "other" || name == "stale" { Some(0x1234) } else { None };
+
+        let taken = check(&channels, "sensors", 0x1234, magic_of);
+        assert_eq!(taken.len(), 2, "{:?}", taken);
+        assert!(taken[0].contains("running daemon (PID 4242)"), "{}", taken[0]);
+        assert!(taken[1].contains("\"other\" already publishes state with magic 0x00001234"), "{}", taken[1]);
+
+        let left_over = check(&channels, "stale", 0x5678, magic_of);
+        assert_eq!(left_over.len(), 1, "{:?}", left_over);
+        assert!(left_over[0].contains("from a daemon that exited"), "{}", left_over[0]);
+
+        assert!(check(&channels, "fresh", 0x5678, magic_of).is_empty());
+    }
+
+    #[test]
+    fn test_reads_state_magic_of_live_channel() {
+        let name = format!("test_cli_collision_{}", std::process::id());
+        let daemon = DaemonChannel::create(&name, ChannelConfig::default()).unwrap();
+        assert_eq!(state_magic(&name), None);
+        daemon.write_data_with_len(&0xCAFE_F00Du32.to_le_bytes());
+
+        let magic = state_magic(&name);
+        let warned = warnings("some_new_channel", 0xCAFE_F00D);
+        drop(daemon);
+
+        assert_eq!(magic, Some(0xCAFE_F00D));
+        assert!(warned.iter().any(|w| w.contains(&format!("\"{}\"", name))), "{:?}", warned);
+    }
+}
@@ -0,0 +1,119 @@
+//! Line diff for `venom init --diff`
+//!
+//! Generated files are a few hundred lines at most, so a plain LCS table is
+//! fast enough and keeps the CLI free of a diff dependency.
+
+/// Lines of context around each change
+const CONTEXT: usize = 3;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Op {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// Unified diff from `old` to `new`, or `None` when they're identical
+pub fn unified(path: &str, old: &str, new: &str) -> Option<String> {
+    if old == new {
+        return None;
+    }
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+
+    // lcs[i][j] = length of the LCS of a[i..] and b[j..]
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+
+    // Walk the table into (op, old line, new line) triples
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            ops.push((Op::Equal, i, j));
+            i += 1;
+            j += 1;
+        } else if i < a.len() && (j == b.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push((Op::Delete, i, j));
+            i += 1;
+        } else {
+            ops.push((Op::Insert, i, j));
+            j += 1;
+        }
+    }
+
+    // Only line endings differ
+    if ops.iter().all(|(op, _, _)| *op == Op::Equal) {
+        return Some(format!("--- {0}\n+++ {0} (generated)\n(whitespace at end of file differs)\n", path));
+    }
+
+    let mut out = format!("--- {0}\n+++ {0} (generated)\n", path);
+    let mut k = 0;
+    while k < ops.len() {
+        if ops[k].0 == Op::Equal {
+            k += 1;
+            continue;
+        }
+        // Extend the hunk while the next change is within 2 * CONTEXT lines
+        let start = k.saturating_sub(CONTEXT);
+        let mut end = k;
+        let mut equal_run = 0;
+        while end < ops.len() && equal_run <= 2 * CONTEXT {
+            if ops[end].0 == Op::Equal { equal_run += 1 } else { equal_run = 0 }
+            end += 1;
+        }
+        let end = (end - equal_run + CONTEXT).min(ops.len());
+
+        let hunk = &ops[start..end];
+        let old_len = hunk.iter().filter(|(op, _, _)| *op != Op::Insert).count();
+        let new_len = hunk.iter().filter(|(op, _, _)| *op != Op::Delete).count();
+        out.push_str(&format!("@@ -{},{} +{},{} @@\n", hunk[0].1 + 1, old_len, hunk[0].2 + 1, new_len));
+        for &(op, i, j) in hunk {
+            match op {
+                Op::Equal => out.push_str(&format!(" {}\n", a[i])),
+                Op::Delete => out.push_str(&format!("-{}\n", a[i])),
+                Op::Insert => out.push_str(&format!("+{}\n", b[j])),
+            }
+        }
+        k = end;
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identical_is_none() {
+        assert_eq!(unified("f", "a\nb\n", "a\nb\n"), None);
+    }
+
+    #[test]
+    fn test_hunk_with_context() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n";
+        let new = "1\n2\n3\n4\nfive\n6\n7\n8\n9\n";
+        assert_eq!(
+            unified("f", old, new).unwrap(),
+            "--- f\n+++ f (generated)\n@@ -2,7 +2,7 @@\n 2\n 3\n 4\n-5\n+five\n 6\n 7\n 8\n"
+        );
+    }
+
+    #[test]
+    fn test_distant_changes_get_separate_hunks() {
+        let old: String = (1..=30).map(|n| format!("{}\n", n)).collect();
+        let new: String = (1..=30)
+            .map(|n| match n {
+                2 => "two\n".to_string(),
+                28 => "twenty-eight\n".to_string(),
+                n => format!("{}\n", n),
+            })
+            .collect();
+        let diff = unified("f", &old, &new).unwrap();
+        assert_eq!(diff.matches("@@ -").count(), 2, "{}", diff);
+    }
+}
@@ -0,0 +1,686 @@
+//! `venom doctor`: sanity checks for a generated project
+//!
+//! Catches the usual causes of "the client shows zeros" before the user
+//! starts debugging at runtime: a missing `libvenom_memory.so` (or one that
+//! no longer matches the hash in `lib/libvenom_memory.json`), a channel or
+//! magic edited on one side only, a state struct whose size no longer agrees
+//! across languages, and Makefile/rpath references that point nowhere.
+//!
+//! The `[project]` table in `venom.toml` is the reference; every generated
+//! protocol file is compared against it.
+
+use console::style;
+use serde::Serialize;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::library::{self, library_name_for, Metadata, LIBRARY_NAME, METADATA_NAME};
+use crate::project;
+use crate::templates::{Language, Names};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// One row of the report
+#[derive(Debug, Serialize)]
+pub struct Check {
+    pub status: Status,
+    /// Kind of check (`library`, `protocol`, `layout`, `build`, ...)
+    pub check: &'static str,
+    /// File the check is about, relative to the project
+    pub subject: String,
+    pub message: String,
+}
+
+impl Check {
+    fn new(status: Status, check: &'static str, subject: &str, message: impl Into<String>) -> Self {
+        Self { status, check, subject: subject.to_string(), message: message.into() }
+    }
+}
+
+/// Files that define the channel name, magic and state size, and the
+/// language whose `venom add-client` rewrites them (`None` for files the
+/// daemon shares)
+const PROTOCOL_FILES: &[(&str, Option<Language>)] = &[
+    ("shared/protocol.h", None),
+    ("daemon/protocol.h", None),
+    ("shared/protocol.hpp", None),
+    ("src/lib.rs", None),
+    ("venom/venom.go", None),
+    ("src/venom.zig", None),
+    ("src/venom.nim", None),
+    ("index.js", Some(Language::Node)),
+    ("venom_binding.py", Some(Language::Python)),
+    ("VenomBinding.cs", Some(Language::CSharp)),
+    ("src/State.java", Some(Language::Java)),
+    ("state.lua", Some(Language::Lua)),
+    ("lib/venom_binding.dart", Some(Language::Flutter)),
+];
+
+/// Files whose state struct venom-watch can lay out
+const LAYOUT_FILES: &[&str] = &["shared/protocol.h", "daemon/protocol.h", "src/lib.rs"];
+
+/// Client files that load the library from `native/` instead of `lib/`
+const NATIVE_CLIENTS: &[&str] = &["VenomBinding.cs", "src/State.java", "Package.swift", "pubspec.yaml"];
+
+/// What every protocol file should agree on
+struct Expected {
+    name: Option<String>,
+    channel: Option<String>,
+    magic: Option<u64>,
+    size: Option<usize>,
+    /// Where the values came from, for messages
+    source: String,
+}
+
+/// Constants found in one protocol file
+#[derive(Default)]
+struct Constants {
+    channel: Option<String>,
+    magic: Option<u64>,
+    size: Option<usize>,
+}
+
+/// Run every check against the project in `dir`
+pub fn run(dir: &str) -> Vec<Check> {
+    let root = Path::new(dir);
+    let mut checks = Vec::new();
+    if !root.is_dir() {
+        checks.push(Check::new(Status::Fail, "project", dir, "no such directory"));
+        return checks;
+    }
+
+    let protocol_files = protocol_files(root);
+    let mut targets = Vec::new();
+    let expected = match project::load(dir) {
+        Ok((config, lang)) => {
+            checks.push(Check::new(
+                Status::Pass,
+                "manifest",
+                "venom.toml",
+                format!(
+                    "{} ({}), channel \"{}\", magic 0x{:08X}, {}-byte state",
+                    config.name,
+                    lang.id(),
+                    config.channel,
+                    config.magic,
+                    config.schema.size
+                ),
+            ));
+            targets = config.targets;
+            Expected {
+                name: Some(config.name),
+                channel: Some(config.channel),
+                magic: Some(config.magic as u64),
+                size: Some(config.schema.size),
+                source: "venom.toml".to_string(),
+            }
+        }
+        Err(e) => {
+            // Without a manifest, the files can still be checked against each other
+            let first = protocol_files.first();
+            let found = first.map(|(path, _)| read_constants(path)).unwrap_or_default();
+            let source = first.map(|(path, _)| relative(root, path)).unwrap_or_default();
+            checks.push(Check::new(
+                if first.is_some() { Status::Warn } else { Status::Fail },
+                "manifest",
+                "venom.toml",
+                match first {
+                    Some(_) => format!("{}; comparing against {} instead", e, source),
+                    None => format!("{}; is this a venom project?", e),
+                },
+            ));
+            Expected { name: None, channel: found.channel, magic: found.magic, size: found.size, source }
+        }
+    };
+
+    check_libraries(root, &targets, &mut checks);
+    for (path, lang) in &protocol_files {
+        checks.push(check_protocol(root, path, *lang, &expected));
+    }
+    if let (Some(name), Some(size)) = (&expected.name, expected.size) {
+        let struct_name = format!("{}State", Names::of(name).pascal);
+        for file in LAYOUT_FILES {
+            let path = root.join(file);
+            if path.exists() {
+                checks.push(check_layout(root, &path, &struct_name, size));
+            }
+        }
+    }
+    for path in build_files(root) {
+        checks.push(check_build_file(root, &path));
+    }
+
+    checks
+}
+
+/// Print the report as a table
+pub fn print_report(dir: &str, checks: &[Check]) {
+    println!();
+    println!("{}", style(format!("🩺 Checking {}...", dir)).cyan());
+    println!();
+
+    let width = checks.iter().map(|c| c.subject.len()).max().unwrap_or(0);
+    for c in checks {
+        let status = match c.status {
+            Status::Pass => style("PASS").green(),
+            Status::Warn => style("WARN").yellow(),
+            Status::Fail => style("FAIL").red().bold(),
+        };
+        println!("  {}  {:<9} {:<width$}  {}", status, c.check, c.subject, c.message, width = width);
+    }
+
+    let failed = checks.iter().filter(|c| c.status == Status::Fail).count();
+    let warned = checks.iter().filter(|c| c.status == Status::Warn).count();
+    println!();
+    if failed > 0 {
+        println!("{}", style(format!("❌ {} check(s) failed, {} warning(s)", failed, warned)).red().bold());
+    } else if warned > 0 {
+        println!("{}", style(format!("⚠️  All checks passed with {} warning(s)", warned)).yellow());
+    } else {
+        println!("{}", style("✅ All checks passed").green().bold());
+    }
+    println!();
+}
+
+/// Print the report as JSON (for CI)
+pub fn print_json(dir: &str, checks: &[Check]) {
+    #[derive(Serialize)]
+    struct Report<'a> {
+        project: &'a str,
+        ok: bool,
+        checks: &'a [Check],
+    }
+    let report = Report { project: dir, ok: !has_failures(checks), checks };
+    println!("{}", serde_json::to_string_pretty(&report).unwrap());
+}
+
+pub fn has_failures(checks: &[Check]) -> bool {
+    checks.iter().any(|c| c.status == Status::Fail)
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Library
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn check_libraries(root: &Path, targets: &[String], checks: &mut Vec<Check>) {
+    let mut dirs = vec!["lib"];
+    if NATIVE_CLIENTS.iter().any(|f| root.join(f).exists()) {
+        dirs.push("native");
+    }
+    for dir in dirs {
+        let mut libraries = Vec::new();
+        if targets.is_empty() {
+            libraries.push(format!("{}/{}", dir, LIBRARY_NAME));
+        }
+        for target in targets {
+            libraries.push(format!("{}/{}/{}", dir, target, library_name_for(target)));
+        }
+        for subject in libraries {
+            let check = check_library(&root.join(&subject), &subject);
+            let present = check.status != Status::Fail;
+            checks.push(check);
+            // Only lib/ has metadata; projects generated before it have none
+            let metadata = Path::new(&subject).with_file_name(METADATA_NAME);
+            if present && dir == "lib" && root.join(&metadata).exists() {
+                checks.push(check_metadata(root, &subject, &metadata));
+            }
+        }
+    }
+}
+
+fn check_library(path: &Path, subject: &str) -> Check {
+    let meta = match fs::metadata(path) {
+        Ok(meta) if meta.len() > 0 => meta,
+        Ok(_) => {
+            return Check::new(Status::Fail, "library", subject, "empty file; copy libvenom_memory.so over it");
+        }
+        Err(_) => {
+            return Check::new(
+                Status::Fail,
+                "library",
+                subject,
+                "missing; every `venom init` bundles one, copy it from a fresh project",
+            );
+        }
+    };
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if meta.permissions().mode() & 0o111 == 0 {
+            return Check::new(
+                Status::Warn,
+                "library",
+                subject,
+                format!("not executable; run `chmod +x {}`", subject),
+            );
+        }
+    }
+
+    Check::new(Status::Pass, "library", subject, format!("present ({} KB)", meta.len() / 1024))
+}
+
+/// Compare a library against the hash recorded when it was generated
+///
+/// A mismatch is only a warning: replacing the library with one's own build
+/// is legitimate, it just should be a deliberate choice.
+fn check_metadata(root: &Path, subject: &str, metadata: &Path) -> Check {
+    let recorded: Metadata = match fs::read_to_string(root.join(metadata)).map_err(|e| e.to_string()).and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string())) {
+        Ok(recorded) => recorded,
+        Err(e) => return Check::new(Status::Warn, "provenance", subject, format!("cannot read {}: {}", metadata.display(), e)),
+    };
+    let hash = match fs::read(root.join(subject)) {
+        Ok(binary) => library::sha256_hex(&binary),
+        Err(e) => return Check::new(Status::Warn, "provenance", subject, format!("cannot read: {}", e)),
+    };
+    let built_from = match (&recorded.version, &recorded.git) {
+        (Some(version), Some(git)) => format!("venom_memory {} ({})", version, git),
+        (Some(version), None) => format!("venom_memory {}", version),
+        _ => "a library from VENOM_LIB_DIR".to_string(),
+    };
+    if hash != recorded.sha256 {
+        return Check::new(
+            Status::Warn,
+            "provenance",
+            subject,
+            format!(
+                "SHA-256 {} doesn't match {} ({}, {}); it was replaced or corrupted since generation. \
+                 If it is your own build, ignore this; otherwise run `venom generate --force` to restore it",
+                &hash[..16],
+                metadata.display(),
+                &recorded.sha256[..recorded.sha256.len().min(16)],
+                built_from
+            ),
+        );
+    }
+    Check::new(Status::Pass, "provenance", subject, format!("matches {}: {}", metadata.display(), built_from))
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Protocol constants
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn protocol_files(root: &Path) -> Vec<(PathBuf, Option<Language>)> {
+    let mut files: Vec<(PathBuf, Option<Language>)> = PROTOCOL_FILES
+        .iter()
+        .map(|&(file, lang)| (root.join(file), lang))
+        .filter(|(path, _)| path.exists())
+        .collect();
+
+    // SwiftPM target directory is named after the project
+    if let Ok(entries) = fs::read_dir(root.join("Sources")) {
+        let mut swift: Vec<PathBuf> = entries
+            .flatten()
+            .map(|e| e.path().join("State.swift"))
+            .filter(|p| p.exists())
+            .collect();
+        swift.sort();
+        files.extend(swift.into_iter().map(|p| (p, Some(Language::Swift))));
+    }
+    files
+}
+
+fn check_protocol(root: &Path, path: &Path, lang: Option<Language>, expected: &Expected) -> Check {
+    let subject = relative(root, path);
+    let found = read_constants(path);
+    let mut mismatches = Vec::new();
+    let mut missing = Vec::new();
+
+    match (&found.channel, &expected.channel) {
+        (Some(f), Some(e)) if f != e => mismatches.push(format!("channel \"{}\" (expected \"{}\")", f, e)),
+        (None, _) => missing.push("channel"),
+        _ => {}
+    }
+    match (found.magic, expected.magic) {
+        (Some(f), Some(e)) if f != e => mismatches.push(format!("magic 0x{:08X} (expected 0x{:08X})", f, e)),
+        (None, _) => missing.push("magic"),
+        _ => {}
+    }
+    match (found.size, expected.size) {
+        (Some(f), Some(e)) if f != e => mismatches.push(format!("state size {} (expected {})", f, e)),
+        (None, _) => missing.push("state size"),
+        _ => {}
+    }
+
+    if !mismatches.is_empty() {
+        let fix = match lang {
+            Some(lang) => format!("`venom add-client --lang {} --force` regenerates it", lang.id()),
+            None => "fix it by hand or regenerate the project".to_string(),
+        };
+        return Check::new(
+            Status::Fail,
+            "protocol",
+            &subject,
+            format!("{} differs from {}; {}", mismatches.join(", "), expected.source, fix),
+        );
+    }
+    if !missing.is_empty() {
+        return Check::new(
+            Status::Warn,
+            "protocol",
+            &subject,
+            format!("no {} constant found; was it renamed by hand?", missing.join("/")),
+        );
+    }
+    Check::new(Status::Pass, "protocol", &subject, "channel, magic and state size match")
+}
+
+fn read_constants(path: &Path) -> Constants {
+    let text = fs::read_to_string(path).unwrap_or_default();
+    let mut found = Constants::default();
+
+    for line in text.lines() {
+        let Some((key, define, value)) = constant(line) else { continue };
+        let is = |name: &str| if define { key.ends_with(name) } else { key == name };
+
+        if found.channel.is_none() && is("channelname") {
+            if let Literal::Str(s) = value {
+                found.channel = Some(s);
+            }
+        } else if found.magic.is_none() && is("magic") {
+            if let Literal::Int(n) = value {
+                found.magic = Some(n);
+            }
+        } else if found.size.is_none() && (is("statesize") || (!define && key == "size")) {
+            if let Literal::Int(n) = value {
+                found.size = Some(n as usize);
+            }
+        }
+    }
+    found
+}
+
+enum Literal {
+    Str(String),
+    Int(u64),
+}
+
+/// Split a constant definition into (normalised name, is `#define`, value)
+///
+/// Understands `#define NAME value` and `... NAME[: type] = value` across the
+/// template languages. Names are lowercased with `_` removed so
+/// `CHANNEL_NAME`, `ChannelName` and `channelName` compare equal.
+fn constant(line: &str) -> Option<(String, bool, Literal)> {
+    let line = line.trim();
+    let (name, define, value) = if let Some(rest) = line.strip_prefix("#define ") {
+        let (name, value) = rest.trim().split_once(char::is_whitespace)?;
+        (name, true, value)
+    } else {
+        if line.starts_with("//") || line.starts_with("--") || line.starts_with('#') || line.contains("==") {
+            return None;
+        }
+        let (left, value) = line.split_once('=')?;
+        let left = left.split(':').next()?.trim();
+        let name = left.rsplit(char::is_whitespace).next()?.trim_end_matches('*');
+        (name, false, value)
+    };
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return None;
+    }
+    let key = name.to_ascii_lowercase().replace('_', "");
+    Some((key, define, literal(value.trim())?))
+}
+
+fn literal(value: &str) -> Option<Literal> {
+    if let Some(quote) = value.chars().next().filter(|c| *c == '"' || *c == '\'') {
+        let rest = &value[1..];
+        return Some(Literal::Str(rest[..rest.find(quote)?].to_string()));
+    }
+    if let Some(hex) = value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+        let digits: String = hex.chars().take_while(|c| c.is_ascii_hexdigit()).collect();
+        return u64::from_str_radix(&digits, 16).ok().map(Literal::Int);
+    }
+    let digits: String = value.chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok().map(Literal::Int)
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Struct layout (venom-watch)
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn check_layout(root: &Path, path: &Path, struct_name: &str, size: usize) -> Check {
+    let subject = relative(root, path);
+    match venom_watch::analyze_file(&path.to_path_buf(), struct_name) {
+        Ok(layout) if layout.total_size == size => Check::new(
+            Status::Pass,
+            "layout",
+            &subject,
+            format!("{} is {} bytes", struct_name, size),
+        ),
+        Ok(layout) => Check::new(
+            Status::Fail,
+            "layout",
+            &subject,
+            format!(
+                "{} is {} bytes but venom.toml describes {}; edit venom.toml and regenerate instead of the struct",
+                struct_name, layout.total_size, size
+            ),
+        ),
+        Err(e) => Check::new(Status::Warn, "layout", &subject, format!("could not analyze: {}", e)),
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Build files
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn build_files(root: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for dir in ["", "daemon", "client"] {
+        let path = root.join(dir).join("Makefile");
+        if path.exists() {
+            files.push(path);
+        }
+    }
+    let build_rs = root.join("build.rs");
+    if build_rs.exists() {
+        files.push(build_rs);
+    }
+    files
+}
+
+/// Check the `-L`, `-I`, rpath and `LD_LIBRARY_PATH` references in a Makefile
+/// or build.rs resolve
+fn check_build_file(root: &Path, path: &Path) -> Check {
+    let subject = relative(root, path);
+    let text = fs::read_to_string(path).unwrap_or_default();
+    let base = path.parent().unwrap_or(root);
+    // Binaries land next to the Makefile, or in target/<profile>/ for cargo
+    let origin = if path.ends_with("build.rs") { base.join("target/debug") } else { base.to_path_buf() };
+    // Multi-target Makefiles link lib/$(VENOM_TARGET); check the default target
+    let target = text
+        .lines()
+        .find_map(|line| line.strip_prefix("VENOM_TARGET ?="))
+        .map(str::trim);
+    let library = target.map_or(LIBRARY_NAME, library_name_for);
+
+    let mut refs = 0;
+    let mut broken = Vec::new();
+    for token in text.split(|c: char| c.is_whitespace() || c == '"') {
+        let token = token.trim_matches(|c| c == '\'' || c == '\\');
+        let (flag, value, want_lib) = if let Some(dir) = token.strip_prefix("-L").filter(|d| !d.is_empty()) {
+            ("-L", dir.to_string(), true)
+        } else if let Some(dir) = token.strip_prefix("-I").filter(|d| !d.is_empty()) {
+            ("-I", dir.to_string(), false)
+        } else if let Some((_, dir)) = token.split_once("-rpath,") {
+            ("rpath", dir.trim_matches(|c| c == '\'' || c == '\\').to_string(), true)
+        } else if let Some(dir) = token.strip_prefix("LD_LIBRARY_PATH=") {
+            ("LD_LIBRARY_PATH", dir.to_string(), true)
+        } else {
+            continue;
+        };
+
+        let mut value = value.replace("$(CURDIR)", &base.to_string_lossy());
+        if let Some(target) = target {
+            value = value.replace("$(VENOM_TARGET)", target);
+        }
+        // Paths built at build time (format strings, other make variables) can't be checked
+        if value.contains('{') || value.contains("$(") {
+            continue;
+        }
+        let resolved = match value.replace("$$", "$").strip_prefix("$ORIGIN") {
+            Some(rest) => normalize(&origin.join(rest.trim_start_matches('/'))),
+            None if value.starts_with('$') => continue,
+            None => normalize(&base.join(&value)),
+        };
+        refs += 1;
+        let ok = if want_lib { resolved.join(library).exists() } else { resolved.is_dir() };
+        if !ok {
+            let what = if want_lib { format!("no {} in", library) } else { "missing directory".to_string() };
+            broken.push(format!("{} {} -> {} {}", flag, value, what, relative(root, &resolved)));
+        }
+    }
+
+    if !broken.is_empty() {
+        Check::new(Status::Fail, "build", &subject, broken.join("; "))
+    } else if refs == 0 {
+        Check::new(Status::Pass, "build", &subject, "no library paths to check")
+    } else {
+        Check::new(Status::Pass, "build", &subject, format!("{} library/include path(s) resolve", refs))
+    }
+}
+
+/// Resolve `.`/`..` without touching the disk (target/ may not exist yet)
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !out.pop() {
+                    out.push("..");
+                }
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn relative(root: &Path, path: &Path) -> String {
+    let root = normalize(root);
+    let path = normalize(path);
+    match path.strip_prefix(&root) {
+        Ok(rel) if rel.as_os_str().is_empty() => ".".to_string(),
+        Ok(rel) => rel.display().to_string(),
+        Err(_) => path.display().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::Schema;
+    use crate::templates::{channel_magic, License, ProjectConfig, PythonBinding};
+
+    fn generate(name: &str, lang: Language) -> String {
+        let dir = std::env::temp_dir().join(format!("venom-doctor-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        let config = ProjectConfig {
+            name: name.to_string(),
+            channel: format!("{}_chan", name),
+            data_size: 16 * 1024,
+            cmd_slots: 32,
+            max_clients: 16,
+            shm_mode: 0o664,
+            output_dir: dir.display().to_string(),
+            python_binding: PythonBinding::Ctypes,
+            go_nocgo: false,
+            flutter_ui: true,
+            python_async: false,
+            magic: channel_magic(&format!("{}_chan", name)),
+            schema: Schema::demo(),
+            targets: Vec::new(),
+            clients: Vec::new(),
+            root: String::new(),
+            service: false,
+            license: License::None,
+            extras: true,
+            license_year: 2026,
+        };
+        crate::generate_project(&config, lang);
+        config.output_dir
+    }
+
+    #[test]
+    fn test_fresh_project_passes() {
+        let dir = generate("fresh", Language::Python);
+        let checks = run(&dir);
+        fs::remove_dir_all(&dir).ok();
+
+        assert!(!has_failures(&checks), "{:#?}", checks);
+        assert!(checks.iter().all(|c| c.status == Status::Pass), "{:#?}", checks);
+        for subject in ["shared/protocol.h", "venom_binding.py", "daemon/Makefile", "lib/libvenom_memory.so"] {
+            assert!(checks.iter().any(|c| c.subject == subject), "{} not checked", subject);
+        }
+        assert!(checks.iter().any(|c| c.check == "layout"));
+    }
+
+    #[test]
+    fn test_corrupted_magic_fails() {
+        let dir = generate("corrupt", Language::Python);
+        let binding = Path::new(&dir).join("venom_binding.py");
+        let magic = format!("0x{:08X}", channel_magic("corrupt_chan"));
+        let text = fs::read_to_string(&binding).unwrap();
+        fs::write(&binding, text.replacen(&magic, "0xDEADBEEF", 1)).unwrap();
+
+        let checks = run(&dir);
+        fs::remove_dir_all(&dir).ok();
+
+        assert!(has_failures(&checks));
+        let failed: Vec<&Check> = checks.iter().filter(|c| c.status == Status::Fail).collect();
+        assert_eq!(failed.len(), 1, "{:#?}", checks);
+        assert_eq!(failed[0].subject, "venom_binding.py");
+        assert!(failed[0].message.contains("0xDEADBEEF"));
+        assert!(failed[0].message.contains("--lang python"));
+    }
+
+    #[test]
+    fn test_modified_library_warns() {
+        let dir = generate("tampered", Language::C);
+        let lib = Path::new(&dir).join("lib").join(LIBRARY_NAME);
+        let clean = run(&dir);
+        let mut binary = fs::read(&lib).unwrap();
+        let middle = binary.len() / 2;
+        binary[middle] ^= 1;
+        fs::write(&lib, binary).unwrap();
+        let checks = run(&dir);
+        fs::remove_dir_all(&dir).ok();
+
+        let provenance = |checks: &[Check]| checks.iter().filter(|c| c.check == "provenance").map(|c| (c.status, c.message.clone())).collect::<Vec<_>>();
+        let before = provenance(&clean);
+        assert_eq!(before.len(), 1, "{:#?}", clean);
+        assert_eq!(before[0].0, Status::Pass);
+        assert!(before[0].1.contains(&format!("venom_memory {}", venom_memory::VERSION)), "{}", before[0].1);
+
+        // Flagged, but not a failure: it may be the user's own build
+        assert!(!has_failures(&checks), "{:#?}", checks);
+        let after = provenance(&checks);
+        assert_eq!(after.len(), 1);
+        assert_eq!(after[0].0, Status::Warn);
+        assert!(after[0].1.contains("doesn't match lib/libvenom_memory.json"), "{}", after[0].1);
+    }
+
+    #[test]
+    fn test_constant_parsing() {
+        let parse = |line: &str| constant(line).map(|(key, define, value)| {
+            let value = match value {
+                Literal::Str(s) => s,
+                Literal::Int(n) => n.to_string(),
+            };
+            (key, define, value)
+        });
+        assert_eq!(parse("#define DEMO_MAGIC 0x005651ED"), Some(("demomagic".into(), true, "5657069".into())));
+        assert_eq!(parse("  Magic*: uint32 = 0x005651ED'u32"), Some(("magic".into(), false, "5657069".into())));
+        assert_eq!(parse("constexpr const char* CHANNEL_NAME = \"demo\";"), Some(("channelname".into(), false, "demo".into())));
+        assert_eq!(parse("const String channelName = 'demo';"), Some(("channelname".into(), false, "demo".into())));
+        assert_eq!(parse("    g_state.magic = DEMO_MAGIC;"), None);
+        assert_eq!(parse("    return self.magic == MAGIC"), None);
+    }
+}
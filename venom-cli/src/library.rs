@@ -1,5 +1,17 @@
 //! Library embedding and distribution
 //! Bundles libvenom_memory.so with the CLI
+//!
+//! The embedded library is the host build. Libraries for other targets are
+//! read from `$VENOM_LIB_DIR/<triple>/libvenom_memory.{so,dylib}`, and
+//! `venom init --target <triple>` copies each one into `lib/<triple>/`.
+//!
+//! Next to every library in `lib/` goes `libvenom_memory.json`: the crate
+//! version and commit it was built from (when it is the embedded one) and
+//! its SHA-256, which `venom doctor` checks the file against.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 /// Embedded library binary
 pub const LIBRARY_BINARY: &[u8] = include_bytes!("../resources/libvenom_memory.so");
@@ -7,23 +19,272 @@ pub const LIBRARY_BINARY: &[u8] = include_bytes!("../resources/libvenom_memory.s
 /// Library filename
 pub const LIBRARY_NAME: &str = "libvenom_memory.so";
 
-/// Write the embedded library to the specified directory
-pub fn copy_library_to(dir: &str) {
+/// Target triple the embedded library was built for
+pub const EMBEDDED_TARGET: &str = "x86_64-unknown-linux-gnu";
+
+/// Commit the CLI, and so the embedded library, was built from; empty
+/// outside a git checkout
+pub const GIT_HASH: &str = env!("VENOM_GIT_HASH");
+
+/// Metadata file written next to each library in `lib/`
+pub const METADATA_NAME: &str = "libvenom_memory.json";
+
+/// Environment variable naming a directory of prebuilt libraries, one subdirectory per triple
+pub const LIB_DIR_ENV: &str = "VENOM_LIB_DIR";
+
+/// `lib/` of the project being extended; searched after `VENOM_LIB_DIR`
+static PROJECT_LIB_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Also take libraries from an existing project's `lib/<triple>/`
+/// (`venom add-client` copies them into `native/` for some clients)
+pub fn use_project_libraries(project_dir: &str) {
+    *PROJECT_LIB_DIR.lock().unwrap() = Some(Path::new(project_dir).join("lib"));
+}
+
+/// Library filename for a target triple
+pub fn library_name_for(triple: &str) -> &'static str {
+    if triple.contains("apple") { "libvenom_memory.dylib" } else { LIBRARY_NAME }
+}
+
+fn available_in(lib_dir: Option<&Path>) -> Vec<String> {
+    let mut targets = vec![EMBEDDED_TARGET.to_string()];
+    if let Some(entries) = lib_dir.and_then(|dir| std::fs::read_dir(dir).ok()) {
+        for entry in entries.flatten() {
+            let triple = entry.file_name().to_string_lossy().into_owned();
+            if entry.path().join(library_name_for(&triple)).is_file() && !targets.contains(&triple) {
+                targets.push(triple);
+            }
+        }
+    }
+    targets.sort();
+    targets
+}
+
+/// Library binary for a target triple
+pub fn library_for(triple: &str) -> Result<Vec<u8>, String> {
+    let found = library_in(std::env::var_os(LIB_DIR_ENV).as_deref().map(Path::new), triple);
+    if found.is_err() {
+        if let Some(dir) = PROJECT_LIB_DIR.lock().unwrap().as_deref() {
+            let path = dir.join(triple).join(library_name_for(triple));
+            if path.is_file() {
+                return std::fs::read(&path).map_err(|e| format!("cannot read {}: {}", path.display(), e));
+            }
+        }
+    }
+    found
+}
+
+fn library_in(lib_dir: Option<&Path>, triple: &str) -> Result<Vec<u8>, String> {
+    // A library in VENOM_LIB_DIR wins, so a fresh host build can replace the embedded one
+    if let Some(dir) = lib_dir {
+        let path = dir.join(triple).join(library_name_for(triple));
+        if path.is_file() {
+            return std::fs::read(&path).map_err(|e| format!("cannot read {}: {}", path.display(), e));
+        }
+    }
+    if triple == EMBEDDED_TARGET {
+        return Ok(LIBRARY_BINARY.to_vec());
+    }
+    let hint = match lib_dir {
+        Some(dir) => format!("add {}", dir.join(triple).join(library_name_for(triple)).display()),
+        None => format!("set {} to a directory containing {}/{}", LIB_DIR_ENV, triple, library_name_for(triple)),
+    };
+    Err(format!(
+        "no library for target {} (available: {}); {}",
+        triple,
+        available_in(lib_dir).join(", "),
+        hint
+    ))
+}
+
+/// Write the library to the specified directory
+///
+/// With no targets this is the embedded library in `lib/`; otherwise each
+/// target's library goes in `lib/<triple>/`.
+pub fn copy_library_to(dir: &str, targets: &[String]) {
     let lib_dir = format!("{}/lib", dir);
     crate::create_dir(&lib_dir);
-    
-    let lib_path = format!("{}/{}", lib_dir, LIBRARY_NAME);
-    std::fs::write(&lib_path, LIBRARY_BINARY)
-        .expect(&format!("Failed to write library to: {}", lib_path));
-    
+    install(&lib_dir, targets);
+    if targets.is_empty() {
+        write_metadata(&lib_dir, EMBEDDED_TARGET, LIBRARY_BINARY);
+    }
+    for triple in targets {
+        let binary = library_for(triple).unwrap_or_else(|e| panic!("{}", e));
+        write_metadata(&format!("{}/{}", lib_dir, triple), triple, &binary);
+    }
+}
+
+/// Where a bundled library came from, as recorded in `libvenom_memory.json`
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Metadata {
+    pub target: String,
+    /// `venom_memory` version, when the library is the one embedded in the CLI
+    pub version: Option<String>,
+    /// Commit it was built from, when known
+    pub git: Option<String>,
+    /// SHA-256 of the library file, in hex
+    pub sha256: String,
+}
+
+impl Metadata {
+    /// Metadata of `binary`, the library for `triple`
+    pub fn of(triple: &str, binary: &[u8]) -> Self {
+        // Libraries from VENOM_LIB_DIR are the user's own builds: only the hash is known
+        let embedded = binary == LIBRARY_BINARY;
+        Metadata {
+            target: triple.to_string(),
+            version: embedded.then(|| venom_memory::VERSION.to_string()),
+            git: Some(GIT_HASH.to_string()).filter(|hash| embedded && !hash.is_empty()),
+            sha256: sha256_hex(binary),
+        }
+    }
+}
+
+fn write_metadata(dir: &str, triple: &str, binary: &[u8]) {
+    let json = serde_json::to_string_pretty(&Metadata::of(triple, binary)).unwrap();
+    crate::write_file(&format!("{}/{}", dir, METADATA_NAME), &(json + "\n"));
+}
+
+/// SHA-256 of `data` as lowercase hex (FIPS 180-4)
+pub fn sha256_hex(data: &[u8]) -> String {
+    const K: [u32; 64] = [
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+        0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+        0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+        0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+        0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+        0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+        0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+    ];
+    let mut h: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+    ];
+
+    // Pad to a multiple of 64 bytes: 0x80, zeros, then the length in bits
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&(data.len() as u64 * 8).to_be_bytes());
+
+    for block in message.chunks_exact(64) {
+        let mut w = [0u32; 64];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes(word.try_into().unwrap());
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = h;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = hh.wrapping_add(s1).wrapping_add(ch).wrapping_add(K[i]).wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            (hh, g, f, e, d, c, b, a) = (g, f, e, d.wrapping_add(t1), c, b, a, t1.wrapping_add(t2));
+        }
+        for (h, v) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+            *h = h.wrapping_add(v);
+        }
+    }
+
+    h.iter().map(|word| format!("{:08x}", word)).collect()
+}
+
+/// Write the library into `dir` the same way `copy_library_to` lays out `lib/`
+/// (used for the `native/` copies some templates use)
+pub fn install(dir: &str, targets: &[String]) {
+    if targets.is_empty() {
+        write_library(&format!("{}/{}", dir, LIBRARY_NAME));
+        return;
+    }
+    for triple in targets {
+        // Targets are checked up front by `venom init`
+        let binary = library_for(triple).unwrap_or_else(|e| panic!("{}", e));
+        write_library_bytes(&format!("{}/{}/{}", dir, triple, library_name_for(triple)), &binary);
+    }
+}
+
+/// Write the embedded library to an exact path (the `native/` copies some templates use)
+pub fn write_library(path: &str) {
+    write_library_bytes(path, LIBRARY_BINARY);
+}
+
+/// Write `triple`'s library to an exact path (Android's `jniLibs/<abi>/`)
+pub fn install_at(path: &str, triple: &str) {
+    // Targets are checked up front by `venom init`
+    let binary = library_for(triple).unwrap_or_else(|e| panic!("{}", e));
+    write_library_bytes(path, &binary);
+}
+
+fn write_library_bytes(path: &str, binary: &[u8]) {
+    if let Some(parent) = std::path::Path::new(path).parent() {
+        crate::create_dir(&parent.to_string_lossy());
+    }
+    if !crate::write_binary(path, binary) {
+        return;
+    }
+
     // Make it executable (chmod +x)
     #[cfg(unix)]
     {
         use std::os::unix::fs::PermissionsExt;
-        let mut perms = std::fs::metadata(&lib_path).unwrap().permissions();
-        perms.set_mode(0o755);
-        std::fs::set_permissions(&lib_path, perms).ok();
+        if let Ok(meta) = std::fs::metadata(path) {
+            let mut perms = meta.permissions();
+            perms.set_mode(0o755);
+            std::fs::set_permissions(path, perms).ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sha256() {
+        assert_eq!(sha256_hex(b""), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        assert_eq!(sha256_hex(b"abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        // Two blocks, and a length that leaves no room for the padding in the first
+        assert_eq!(
+            sha256_hex(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+        assert_eq!(sha256_hex(&[b'a'; 1000]), "41edece42d63e8d9bf515a9ba6932e1c20cbc9f5a5d134645adb5db1b9737ea3");
+    }
+
+    #[test]
+    fn test_missing_target_lists_available() {
+        let err = library_in(None, "aarch64-unknown-linux-gnu").err().unwrap();
+        assert!(err.contains("available: x86_64-unknown-linux-gnu"), "{}", err);
+        assert!(err.contains(LIB_DIR_ENV), "{}", err);
+    }
+
+    #[test]
+    fn test_lib_dir_provides_targets() {
+        let dir = std::env::temp_dir().join(format!("venom-libdir-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("aarch64-unknown-linux-gnu")).unwrap();
+        std::fs::create_dir_all(dir.join("aarch64-apple-darwin")).unwrap();
+        std::fs::write(dir.join("aarch64-unknown-linux-gnu").join(LIBRARY_NAME), b"arm").unwrap();
+        std::fs::write(dir.join("aarch64-apple-darwin").join("libvenom_memory.dylib"), b"mac").unwrap();
+
+        assert_eq!(library_in(Some(&dir), "aarch64-unknown-linux-gnu").unwrap(), b"arm");
+        assert_eq!(library_in(Some(&dir), "aarch64-apple-darwin").unwrap(), b"mac");
+        assert_eq!(library_in(Some(&dir), EMBEDDED_TARGET).unwrap(), LIBRARY_BINARY);
+        assert_eq!(
+            available_in(Some(&dir)),
+            ["aarch64-apple-darwin", "aarch64-unknown-linux-gnu", EMBEDDED_TARGET]
+        );
+        let err = library_in(Some(&dir), "riscv64gc-unknown-linux-gnu").err().unwrap();
+        assert!(err.contains("available: aarch64-apple-darwin, aarch64-unknown-linux-gnu, x86_64"), "{}", err);
+
+        std::fs::remove_dir_all(&dir).ok();
     }
-    
-    println!("   {} {}", console::style("✓").green(), lib_path);
 }
@@ -3,16 +3,38 @@
 //! Clean modular structure:
 //!   - main.rs: Interactive UI only
 //!   - templates/: Code generation templates
+//!   - schema.rs: Protocol schema (state struct layout)
+//!   - project.rs: Project manifest (`[project]` in venom.toml), read back by
+//!     `venom generate` to regenerate the project
+//!   - doctor.rs: Project sanity checks (`venom doctor`)
+//!   - diff.rs: Line diff for `venom init --diff`
+//!   - run.rs: Build and run daemon + client together (`venom run`)
+//!   - bench.rs: Cross-language read-latency benchmark (`venom bench`)
+//!   - upgrade.rs: Refresh the library and bindings of a project (`venom upgrade`)
 
 mod templates;
 mod library;
+mod schema;
+mod project;
+mod doctor;
+mod diff;
+mod run;
+mod bench;
+mod upgrade;
+mod collision;
+mod verify;
 
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
 use console::style;
-use inquire::{Select, Text, Confirm};
+use inquire::{Select, MultiSelect, Text, Confirm};
+use inquire::validator::Validation;
+use std::cell::{Cell, RefCell};
 use std::fs;
-use std::path::Path;
-use templates::{ProjectConfig, Language};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use schema::Schema;
+use templates::{ProjectConfig, Language, License, PythonBinding};
+use venom_memory::namespace;
 
 #[derive(Parser)]
 #[command(name = "venom")]
@@ -26,16 +48,27 @@ struct Cli {
 enum Commands {
     /// Initialize a new VenomMemory project
     Init {
-        /// Project name
+        /// Project name; other characters than letters, digits, - and _ become -
+        #[arg(value_parser = templates::project_name)]
         name: String,
         
-        /// Programming language
-        #[arg(short, long, value_enum, default_value = "c")]
-        lang: LangArg,
+        /// Programming language; with a comma-separated list (`c,python,go`) the
+        /// first builds the daemon and the rest get a client under clients/<lang>/
+        #[arg(short, long, value_enum, value_delimiter = ',', default_value = "c")]
+        lang: Vec<LangArg>,
+
+        /// Language of the daemon when it isn't the first --lang
+        #[arg(long, value_enum)]
+        daemon_lang: Option<LangArg>,
         
         /// Shared memory channel name
-        #[arg(short, long)]
+        #[arg(short, long, value_parser = parse_channel)]
         channel: String,
+
+        /// Derive the state magic with the byte sum older versions used, so a
+        /// regenerated project still matches daemons deployed with it
+        #[arg(long)]
+        legacy_magic: bool,
         
         /// Data buffer size in KB
         #[arg(short, long, default_value = "16")]
@@ -48,10 +81,166 @@ enum Commands {
         /// Maximum number of clients
         #[arg(long, default_value = "16")]
         max_clients: usize,
+
+        /// Who can connect: permission bits of the channel's shared memory, in
+        /// octal (600 the daemon's user, 660 its group too, 664 others read-only)
+        #[arg(long, value_name = "OCTAL", default_value = "664", value_parser = parse_shm_mode)]
+        shm_mode: u32,
         
         /// Output directory
         #[arg(short, long)]
         output: Option<String>,
+
+        /// Python binding to generate (python only)
+        #[arg(long, value_enum, default_value = "ctypes")]
+        binding: BindingArg,
+
+        /// Also generate an asyncio client: venom_async.py and main_async.py (python only)
+        #[arg(long)]
+        python_async: bool,
+
+        /// Generate a pure-Go client that maps the channel itself (go only;
+        /// builds with CGO_ENABLED=0)
+        #[arg(long)]
+        go_nocgo: bool,
+
+        /// Generate the Flutter widget app in lib/main.dart (flutter only);
+        /// --flutter-ui=false keeps just the console client in bin/
+        #[arg(long, value_name = "BOOL", default_value_t = true, action = ArgAction::Set, num_args = 0..=1, default_missing_value = "true")]
+        flutter_ui: bool,
+
+        /// Protocol schema (venom.toml) describing the state struct; defaults to the system-monitor demo
+        #[arg(long)]
+        schema: Option<PathBuf>,
+
+        /// Overwrite files that already exist (by default they are kept)
+        #[arg(long, conflicts_with = "diff")]
+        force: bool,
+
+        /// Show how existing files would change, without writing anything
+        #[arg(long)]
+        diff: bool,
+
+        /// Bundle the library for a target triple under lib/<triple>/ (repeatable;
+        /// libraries other than the host's come from $VENOM_LIB_DIR/<triple>/)
+        #[arg(long = "target", value_name = "TRIPLE")]
+        targets: Vec<String>,
+
+        /// Also generate packaging/<name>.service (systemd) and its install Makefile
+        #[arg(long)]
+        service: bool,
+
+        /// License to write to LICENSE: mit, apache-2.0 or none
+        #[arg(long, value_name = "LICENSE", default_value = "none", value_parser = parse_license)]
+        license: License,
+
+        /// Leave out .gitignore, LICENSE and the .vscode/ debug configuration
+        #[arg(long, conflicts_with = "license")]
+        no_extras: bool,
+
+        /// After generating, check every decoder's field offsets against the C
+        /// header with venom-watch, and fail if any disagree
+        #[arg(long)]
+        verify: bool,
+    },
+
+    /// Regenerate a project from its venom.toml, without prompts
+    Generate {
+        /// Project directory (containing venom.toml)
+        #[arg(default_value = ".")]
+        path: String,
+
+        /// Overwrite files that already exist (by default they are kept)
+        #[arg(long, conflicts_with_all = ["diff", "check"])]
+        force: bool,
+
+        /// Show how existing files would change, without writing anything
+        #[arg(long, conflicts_with = "check")]
+        diff: bool,
+
+        /// Write nothing; list the files that differ from what venom.toml
+        /// generates and exit with status 1 if there are any (for CI)
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Add a client in another language to an existing project
+    AddClient {
+        /// Client language
+        #[arg(short, long, value_enum)]
+        lang: LangArg,
+
+        /// Project directory (containing venom.toml)
+        #[arg(short, long, default_value = ".")]
+        path: String,
+
+        /// Python binding to generate (python only)
+        #[arg(long, value_enum)]
+        binding: Option<BindingArg>,
+
+        /// Also generate the asyncio client (python only)
+        #[arg(long)]
+        python_async: bool,
+
+        /// Generate a pure-Go client that maps the channel itself (go only)
+        #[arg(long)]
+        go_nocgo: bool,
+
+        /// Generate the Flutter widget app (flutter only; default: as the project was generated)
+        #[arg(long, value_name = "BOOL", action = ArgAction::Set, num_args = 0..=1, default_missing_value = "true")]
+        flutter_ui: Option<bool>,
+
+        /// Overwrite files that already exist
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Check a generated project for missing libraries and protocol mismatches
+    Doctor {
+        /// Project directory (containing venom.toml)
+        #[arg(default_value = ".")]
+        path: String,
+
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Build a generated project and run its daemon and client together
+    Run {
+        /// Project directory (containing venom.toml)
+        #[arg(default_value = ".")]
+        path: String,
+
+        /// Stop the client after this many seconds (C, C++, Rust and Python clients)
+        #[arg(long, value_name = "SECONDS")]
+        duration: Option<u64>,
+    },
+
+    /// Replace a project's bundled library and binding files with this CLI's
+    Upgrade {
+        /// Project directory (containing venom.toml)
+        #[arg(default_value = ".")]
+        path: String,
+
+        /// Also overwrite binding files edited since they were generated
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Generate bench/: the same read-latency benchmark in several languages
+    Bench {
+        /// Project directory (containing venom.toml)
+        #[arg(default_value = ".")]
+        path: String,
+
+        /// Languages to benchmark (default: the project's own languages)
+        #[arg(short, long, value_enum, value_delimiter = ',')]
+        lang: Vec<LangArg>,
+
+        /// Overwrite files that already exist
+        #[arg(long)]
+        force: bool,
     },
 }
 
@@ -64,9 +253,29 @@ enum LangArg {
     Go,
     Zig,
     Nim,
+    Node,
+    Csharp,
+    Java,
+    Swift,
+    Lua,
     Flutter,
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, Debug, ValueEnum)]
+enum BindingArg {
+    Ctypes,
+    Pyo3,
+}
+
+impl From<BindingArg> for PythonBinding {
+    fn from(b: BindingArg) -> Self {
+        match b {
+            BindingArg::Ctypes => PythonBinding::Ctypes,
+            BindingArg::Pyo3 => PythonBinding::Pyo3,
+        }
+    }
+}
+
 impl From<LangArg> for Language {
     fn from(l: LangArg) -> Self {
         match l {
@@ -77,29 +286,153 @@ impl From<LangArg> for Language {
             LangArg::Go => Language::Go,
             LangArg::Zig => Language::Zig,
             LangArg::Nim => Language::Nim,
+            LangArg::Node => Language::Node,
+            LangArg::Csharp => Language::CSharp,
+            LangArg::Java => Language::Java,
+            LangArg::Swift => Language::Swift,
+            LangArg::Lua => Language::Lua,
             LangArg::Flutter => Language::Flutter,
         }
     }
 }
 
+fn parse_channel(s: &str) -> Result<String, String> {
+    namespace::validate(s).map_err(|e| e.to_string())?;
+    Ok(s.to_string())
+}
+
+/// Octal permission bits, as in `660`, `0660` or `0o660`
+fn parse_shm_mode(s: &str) -> Result<u32, String> {
+    match u32::from_str_radix(s.strip_prefix("0o").unwrap_or(s), 8) {
+        Ok(mode) if mode <= 0o777 => Ok(mode),
+        _ => Err(format!("{:?} isn't octal permission bits like 660", s)),
+    }
+}
+
+fn parse_license(s: &str) -> Result<License, String> {
+    License::from_id(&s.to_ascii_lowercase()).ok_or_else(|| format!("{:?} isn't one of mit, apache-2.0 or none", s))
+}
+
+/// Daemon language and extra client languages of a project generated for
+/// `langs`: the daemon is `daemon` or else the first entry, and every other
+/// language gets a client once
+fn split_languages(langs: &[Language], daemon: Option<Language>) -> (Language, Vec<Language>) {
+    let daemon = daemon.or(langs.first().copied()).unwrap_or(Language::C);
+    let mut clients = Vec::new();
+    for &lang in langs {
+        if lang != daemon && !clients.contains(&lang) {
+            clients.push(lang);
+        }
+    }
+    (daemon, clients)
+}
+
 fn main() {
     let cli = Cli::parse();
     
     match cli.command {
-        Some(Commands::Init { name, lang, channel, data_size, cmd_slots, max_clients, output }) => {
+        Some(Commands::Init { name, lang, daemon_lang, channel, legacy_magic, data_size, cmd_slots, max_clients, shm_mode, output, binding, python_async, go_nocgo, flutter_ui, schema, force, diff, mut targets, service, license, no_extras, verify }) => {
+            let schema = match schema {
+                Some(path) => Schema::load(&path).unwrap_or_else(|e| {
+                    eprintln!("{} {}", style("❌ Invalid schema:").red(), e);
+                    std::process::exit(1);
+                }),
+                None => Schema::demo(),
+            };
+            let mut unique = Vec::new();
+            for target in targets.drain(..) {
+                if !unique.contains(&target) {
+                    unique.push(target);
+                }
+            }
+            let targets = unique;
+            for target in &targets {
+                if let Err(e) = library::library_for(target) {
+                    eprintln!("{} {}", style("❌ Cannot bundle library:").red(), e);
+                    std::process::exit(1);
+                }
+            }
+            let langs: Vec<Language> = lang.into_iter().map(Into::into).collect();
+            let (lang, clients) = split_languages(&langs, daemon_lang.map(Into::into));
             let config = ProjectConfig {
                 name: name.clone(),
+                magic: if legacy_magic { templates::legacy_channel_magic(&channel) } else { templates::channel_magic(&channel) },
                 channel,
                 data_size: data_size * 1024,
                 cmd_slots,
                 max_clients,
+                shm_mode,
                 output_dir: output.unwrap_or(name),
+                python_binding: binding.into(),
+                go_nocgo,
+                flutter_ui,
+                python_async,
+                schema,
+                targets,
+                clients,
+                root: String::new(),
+                service,
+                license,
+                extras: !no_extras,
+                license_year: templates::extras::current_year(),
             };
-            generate_project(&config, lang.into());
+            if force {
+                set_write_mode(WriteMode::Force);
+            } else if diff {
+                set_write_mode(WriteMode::Diff);
+            }
+            warn_collisions(&config);
+            generate_project(&config, lang);
+            if verify {
+                verify_project(&config);
+            }
+        }
+        Some(Commands::Generate { path, force, diff, check }) => {
+            generate_from_manifest(&path, force, diff, check);
+        }
+        Some(Commands::AddClient { lang, path, binding, python_async, go_nocgo, flutter_ui, force }) => {
+            add_client(&path, lang.into(), binding.map(Into::into), python_async, go_nocgo, flutter_ui, force);
+        }
+        Some(Commands::Doctor { path, json }) => {
+            let checks = doctor::run(&path);
+            if json {
+                doctor::print_json(&path, &checks);
+            } else {
+                doctor::print_report(&path, &checks);
+            }
+            if doctor::has_failures(&checks) {
+                std::process::exit(1);
+            }
+        }
+        Some(Commands::Run { path, duration }) => {
+            match run::run(&path, duration) {
+                Ok(status) => std::process::exit(status.code().unwrap_or(1)),
+                Err(e) => {
+                    eprintln!("{} {}", style("❌ venom run:").red(), e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(Commands::Upgrade { path, force }) => {
+            println!();
+            println!("{}", style(format!("📦 Upgrading {}...", path)).cyan());
+            match upgrade::upgrade(&path, force) {
+                Ok(report) => upgrade::print_report(&report),
+                Err(e) => {
+                    eprintln!("{} {}", style("❌ venom upgrade:").red(), e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(Commands::Bench { path, lang, force }) => {
+            let langs: Vec<Language> = lang.into_iter().map(Into::into).collect();
+            generate_bench(&path, &langs, force);
         }
         None => {
             if let Some((config, lang)) = run_interactive_mode() {
+                warn_collisions(&config);
                 generate_project(&config, lang);
+                verify_project(&config);
             }
         }
     }
@@ -115,35 +448,102 @@ fn run_interactive_mode() -> Option<(ProjectConfig, Language)> {
     // Project name
     let name = Text::new("📁 Project name:")
         .with_placeholder("my_daemon")
-        .with_help_message("Name of your project directory")
-        .prompt().ok()?;
-    
-    if name.is_empty() {
-        println!("{}", style("❌ Project name cannot be empty").red());
-        return None;
-    }
+        .with_help_message("Name of your project directory; spaces and punctuation become -")
+        .with_validator(|input: &str| {
+            Ok(match templates::project_name(input) {
+                Ok(_) => Validation::Valid,
+                Err(e) => Validation::Invalid(e.into()),
+            })
+        })
+        .prompt().ok()
+        .and_then(|input| templates::project_name(&input).ok())?;
     
     // Channel name
     let channel = Text::new("📡 Channel name:")
         .with_default(&name)
-        .with_help_message("Shared memory channel identifier")
+        .with_help_message("Shared memory channel identifier (letters, digits, _ - .)")
+        .with_validator(|input: &str| {
+            Ok(match namespace::validate(input) {
+                Ok(()) => Validation::Valid,
+                Err(e) => Validation::Invalid(e.to_string().into()),
+            })
+        })
         .prompt().ok()?;
     
-    // Language
-    let lang_options = vec!["C", "C++", "Rust", "Python", "Go", "Zig", "Nim", "Flutter/Dart"];
-    let lang_choice = Select::new("🔤 Language:", lang_options)
-        .with_help_message("↑↓ to move, Enter to select")
+    // Languages
+    let lang_options = vec!["C", "C++", "Rust", "Python", "Go", "Zig", "Nim", "Node.js", "C#/.NET", "Java", "Swift", "Lua (LuaJIT)", "Flutter/Dart"];
+    let lang_choices = MultiSelect::new("🔤 Languages:", lang_options)
+        .with_help_message("↑↓ to move, Space to select, Enter to confirm; extra languages get a client under clients/")
         .prompt().ok()?;
+    if lang_choices.is_empty() {
+        println!("{}", style("❌ Select at least one language").red());
+        return None;
+    }
     
-    let lang = match lang_choice {
-        "C++" => Language::Cpp,
-        "Rust" => Language::Rust,
-        "Python" => Language::Python,
-        "Go" => Language::Go,
-        "Zig" => Language::Zig,
-        "Nim" => Language::Nim,
-        "Flutter/Dart" => Language::Flutter,
-        _ => Language::C,
+    // Daemon language
+    let daemon_choice = if lang_choices.len() > 1 {
+        Select::new("🛠️  Daemon language:", lang_choices.clone())
+            .with_help_message("The other languages get client-only projects")
+            .prompt().ok()?
+    } else {
+        lang_choices[0]
+    };
+    
+    let langs: Vec<Language> = lang_choices.iter().map(|choice| lang_from_choice(choice)).collect();
+    let (lang, clients) = split_languages(&langs, Some(lang_from_choice(daemon_choice)));
+
+    // Python binding
+    let python_binding = if langs.contains(&Language::Python) {
+        let choice = Select::new("🔌 Python binding:", vec!["ctypes", "PyO3 (venom_py)"])
+            .with_help_message("ctypes needs nothing extra, PyO3 is faster but needs maturin")
+            .prompt().ok()?;
+        if choice == "ctypes" { PythonBinding::Ctypes } else { PythonBinding::Pyo3 }
+    } else {
+        PythonBinding::default()
+    };
+    let python_async = langs.contains(&Language::Python)
+        && Confirm::new("🐍 Also generate an asyncio client (venom_async.py)?")
+            .with_default(false)
+            .with_help_message("AsyncVenomShell for asyncio apps, and main_async.py showing it")
+            .prompt().ok()?;
+
+    // Go client: cgo over the bundled library, or pure Go
+    let go_nocgo = if langs.contains(&Language::Go) {
+        let choice = Select::new("🔌 Go client:", vec!["cgo (bundled library)", "pure Go (no cgo)"])
+            .with_help_message("Pure Go maps the channel itself: CGO_ENABLED=0, static and cross-compiled builds")
+            .prompt().ok()?;
+        choice.starts_with("pure")
+    } else {
+        false
+    };
+
+    // Flutter client: widget app, or the console client alone
+    let flutter_ui = if langs.contains(&Language::Flutter) {
+        let choice = Select::new("📱 Flutter client:", vec!["Flutter app + console client", "console client only"])
+            .with_help_message("The app shows live stats with `flutter run -d linux`; the console client runs headless")
+            .prompt().ok()?;
+        choice.starts_with("Flutter")
+    } else {
+        true
+    };
+    
+    // Protocol schema
+    let schema_path = Text::new("🧬 Protocol schema:")
+        .with_placeholder("built-in system monitor demo")
+        .with_help_message("Path to a venom.toml describing the state struct, or empty for the demo")
+        .with_validator(|input: &str| {
+            Ok(match input.trim() {
+                "" => Validation::Valid,
+                path => match Schema::load(Path::new(path)) {
+                    Ok(_) => Validation::Valid,
+                    Err(e) => Validation::Invalid(e.into()),
+                },
+            })
+        })
+        .prompt().ok()?;
+    let schema = match schema_path.trim() {
+        "" => Schema::demo(),
+        path => Schema::load(Path::new(path)).ok()?,
     };
     
     // Data size
@@ -167,7 +567,7 @@ fn run_interactive_mode() -> Option<(ProjectConfig, Language)> {
     else { 16 * 1024 };
     
     // Command slots
-    let cmd_slots = Select::new("📨 Command slots:", vec!["16", "32", "64", "128"])
+    let cmd_slots = Select::new("📨 Command slots:", vec!["8", "16", "32", "64"])
         .with_starting_cursor(1).prompt().ok()?
         .parse::<usize>().unwrap_or(32);
     
@@ -176,6 +576,35 @@ fn run_interactive_mode() -> Option<(ProjectConfig, Language)> {
         .with_starting_cursor(2).prompt().ok()?
         .parse::<usize>().unwrap_or(16);
     
+    // Who can connect
+    let mode_options = vec![
+        "0600 - Only the daemon's user",
+        "0660 - The daemon's user and group",
+        "0664 - The daemon's user and group; others read-only",
+        "0666 - Everyone",
+    ];
+    let shm_mode = Select::new("🔒 Who can connect?", mode_options)
+        .with_starting_cursor(2)
+        .with_help_message("Permission bits of the channel's shared memory in /dev/shm")
+        .prompt().ok()
+        .and_then(|choice| parse_shm_mode(&choice[..4]).ok())?;
+    
+    // systemd service
+    let service = Confirm::new("⚙️  Generate a systemd service?")
+        .with_default(false)
+        .with_help_message("Adds packaging/ with a unit file and install/uninstall targets")
+        .prompt().ok()?;
+    
+    // License
+    let license = match Select::new("📜 License:", vec!["MIT", "Apache-2.0", "None"])
+        .with_help_message("Written to LICENSE, with this year and the project name")
+        .prompt().ok()?
+    {
+        "MIT" => License::Mit,
+        "Apache-2.0" => License::Apache2,
+        _ => License::None,
+    };
+    
     // Output directory
     let output_dir = Text::new("📂 Output directory:")
         .with_default(&format!("./{}", name))
@@ -186,12 +615,41 @@ fn run_interactive_mode() -> Option<(ProjectConfig, Language)> {
     println!("{}", style("═══════════════════════════════════════════").cyan());
     println!("{}", style("📋 Configuration Summary").cyan().bold());
     println!("{}", style("═══════════════════════════════════════════").cyan());
+    let names = templates::Names::of(&name);
     println!("   Project:     {}", style(&name).green());
+    println!(
+        "   Identifiers: {}",
+        style(format!("{} (packages), {} (types), {} (macros), {} (modules)", names.snake, names.pascal, names.upper, names.flat)).green()
+    );
     println!("   Channel:     {}", style(&channel).green());
     println!("   Language:    {}", style(format!("{:?}", lang)).green());
+    if !clients.is_empty() {
+        let names: Vec<String> = clients.iter().map(|c| format!("{:?}", c)).collect();
+        println!("   Clients:     {}", style(names.join(", ")).green());
+    }
+    if langs.contains(&Language::Python) {
+        println!("   Binding:     {}", style(format!("{:?}", python_binding)).green());
+    }
+    if python_async {
+        println!("   Python:      {}", style("sync + asyncio clients").green());
+    }
+    if go_nocgo {
+        println!("   Go client:   {}", style("pure Go (no cgo)").green());
+    }
+    if langs.contains(&Language::Flutter) {
+        println!("   Flutter:     {}", style(if flutter_ui { "app + console client" } else { "console client" }).green());
+    }
+    println!("   Schema:      {}", style(if schema_path.trim().is_empty() { "demo".to_string() } else { format!("{} ({} bytes)", schema_path.trim(), schema.size) }).green());
     println!("   Data size:   {}", style(format_size(data_size)).green());
     println!("   Cmd slots:   {}", style(cmd_slots).green());
     println!("   Max clients: {}", style(max_clients).green());
+    println!("   Access:      {}", style(format!("{:04o}", shm_mode)).green());
+    if service {
+        println!("   Service:     {}", style("systemd").green());
+    }
+    if license != License::None {
+        println!("   License:     {}", style(license.id()).green());
+    }
     println!("   Output:      {}", style(&output_dir).green());
     println!("{}", style("═══════════════════════════════════════════").cyan());
     println!();
@@ -201,7 +659,42 @@ fn run_interactive_mode() -> Option<(ProjectConfig, Language)> {
         return None;
     }
     
-    Some((ProjectConfig { name, channel, data_size, cmd_slots, max_clients, output_dir }, lang))
+    // Existing directory
+    let non_empty = fs::read_dir(&output_dir).is_ok_and(|mut entries| entries.next().is_some());
+    if non_empty {
+        let choice = Select::new(
+            &format!("⚠️  {} exists and is not empty:", output_dir),
+            vec!["Merge (keep existing files, add missing ones)", "Overwrite existing files", "Abort"],
+        )
+        .prompt().ok()?;
+        if choice.starts_with("Overwrite") {
+            set_write_mode(WriteMode::Force);
+        } else if choice == "Abort" {
+            println!("{}", style("❌ Cancelled").red());
+            return None;
+        }
+    }
+    
+    let magic = templates::channel_magic(&channel);
+    Some((ProjectConfig { name, channel, data_size, cmd_slots, max_clients, shm_mode, output_dir, python_binding, go_nocgo, flutter_ui, python_async, magic, schema, targets: Vec::new(), clients, root: String::new(), service, license, extras: true, license_year: templates::extras::current_year() }, lang))
+}
+
+fn lang_from_choice(choice: &str) -> Language {
+    match choice {
+        "C++" => Language::Cpp,
+        "Rust" => Language::Rust,
+        "Python" => Language::Python,
+        "Go" => Language::Go,
+        "Zig" => Language::Zig,
+        "Nim" => Language::Nim,
+        "Node.js" => Language::Node,
+        "C#/.NET" => Language::CSharp,
+        "Java" => Language::Java,
+        "Swift" => Language::Swift,
+        "Lua (LuaJIT)" => Language::Lua,
+        "Flutter/Dart" => Language::Flutter,
+        _ => Language::C,
+    }
 }
 
 fn print_header() {
@@ -222,69 +715,1203 @@ fn format_size(bytes: usize) -> String {
 // Project Generation
 // ═══════════════════════════════════════════════════════════════════════════
 
+/// Warn about the clashes `collision` finds for a new project, and about
+/// regenerating a project whose daemons use the legacy magic
+fn warn_collisions(config: &ProjectConfig) {
+    let mut warnings = collision::warnings(&config.channel, config.magic);
+    if let Ok((existing, _)) = project::load(&config.output_dir) {
+        if existing.magic != config.magic && existing.magic == templates::legacy_channel_magic(&config.channel) {
+            warnings.push(format!(
+                "{}/venom.toml has the legacy magic 0x{:08X}; use --legacy-magic to keep it for deployed daemons",
+                config.output_dir, existing.magic
+            ));
+        }
+    }
+    for warning in warnings {
+        println!("{}", style(format!("⚠️  {}", warning)).yellow());
+    }
+}
+
+/// `--verify`: compare the generated decoders' layouts, exiting on a mismatch
+fn verify_project(config: &ProjectConfig) {
+    if write_mode() == WriteMode::Diff {
+        return;
+    }
+    match verify::run(&config.output_dir, &config.name, &config.schema) {
+        Ok(report) => {
+            verify::print_report(&report);
+            if !report.passed() {
+                std::process::exit(1);
+            }
+        }
+        Err(e) => {
+            eprintln!("{} {}", style("❌ Cannot verify layouts:").red(), e);
+            std::process::exit(1);
+        }
+    }
+    println!();
+}
+
 fn generate_project(config: &ProjectConfig, lang: Language) {
     println!();
     println!("{}", style("📁 Creating project structure...").cyan());
     
-    templates::generate(config, lang);
-    
-    // Copy library to project
-    library::copy_library_to(&config.output_dir);
+    generate_files(config, lang);
     
     println!();
+    if write_mode() == WriteMode::Diff {
+        println!("{}", style("📝 Diff only, nothing was written (rerun with --force to apply)").yellow());
+        println!();
+        return;
+    }
+    let skipped = take_skipped();
+    if !skipped.is_empty() {
+        println!(
+            "{}",
+            style(format!("⚠️  Kept {} existing file(s); use --diff to compare or --force to overwrite", skipped.len())).yellow()
+        );
+    }
     println!("{}", style("✅ Project generated successfully!").green().bold());
+    let mut steps = run_steps(config, lang);
+    for &client in &config.clients {
+        let extra = run_steps(&config.for_client(client), client);
+        steps.setup.extend(extra.setup);
+        steps.clients.extend(extra.clients);
+    }
+    print_next_steps(&steps);
+    println!("   Or build and run both at once: venom run {}", config.output_dir);
     println!();
-    println!("{}", style("📖 Next steps:").yellow());
+}
+
+/// Write every file of a project: the `lang` project, its clients and
+/// extras, `venom.toml` and the library
+fn generate_files(config: &ProjectConfig, lang: Language) {
+    templates::generate(config, lang);
+    if config.service {
+        templates::service::generate(config, lang);
+    }
+    if config.extras {
+        templates::extras::generate(config, lang);
+    }
+    for &client in &config.clients {
+        templates::generate_client(&config.for_client(client), client);
+    }
+    let hashes = templates::bindings(config, lang)
+        .into_iter()
+        .map(|b| (b.path, project::content_hash(&b.content)))
+        .collect();
+    let manifest = config.schema.to_toml(&config.name) + &project::to_toml(config, lang) + &project::bindings_toml(&hashes);
+    write_file(&format!("{}/venom.toml", config.output_dir), &manifest);
     
-    match lang {
-        Language::C => {
-            println!("   cd {}/daemon && make run", config.output_dir);
-            println!("   cd {}/client && make run", config.output_dir);
+    // Copy library to project
+    library::copy_library_to(&config.output_dir, &config.targets);
+}
+
+/// Files of a project whose contents differ from what its `venom.toml`
+/// generates, or are missing, relative to the project and sorted
+///
+/// `venom.toml` itself isn't compared: it is the input, and may be laid out
+/// by hand.
+fn drifted_files(config: &ProjectConfig, lang: Language) -> Vec<String> {
+    let prefix = format!("{}/", config.output_dir);
+    let mut drifted: Vec<String> = dry_run(|| generate_files(config, lang))
+        .into_iter()
+        .map(|path| path.strip_prefix(&prefix).unwrap_or(&path).to_string())
+        .filter(|path| path != "venom.toml")
+        .collect();
+    drifted.sort();
+    drifted.dedup();
+    drifted
+}
+
+/// `venom generate`: regenerate the project in `path` from its `venom.toml`
+fn generate_from_manifest(path: &str, force: bool, diff: bool, check: bool) {
+    let (config, lang) = project::load(path).unwrap_or_else(|e| {
+        eprintln!("{} {}", style("❌ Not a venom project:").red(), e);
+        std::process::exit(1);
+    });
+    library::use_project_libraries(path);
+    for target in &config.targets {
+        if let Err(e) = library::library_for(target) {
+            eprintln!("{} {}", style("❌ Cannot bundle library:").red(), e);
+            std::process::exit(1);
         }
-        Language::Cpp => {
-            println!("   cd {}/daemon && make run", config.output_dir);
-            println!("   cd {}/client && make run", config.output_dir);
+    }
+    
+    if check {
+        let drifted = drifted_files(&config, lang);
+        if drifted.is_empty() {
+            println!("{}", style(format!("✅ {} matches its venom.toml", path)).green());
+            return;
         }
-        Language::Rust => {
-            println!("   cd {} && cargo run --bin daemon", config.output_dir);
-            println!("   cd {} && cargo run --bin client", config.output_dir);
+        eprintln!("{}", style(format!("❌ {} file(s) differ from what venom.toml generates:", drifted.len())).red());
+        for file in &drifted {
+            eprintln!("   {}", file);
         }
-        Language::Python => {
-            println!("   cd {}/daemon && make run", config.output_dir);
-            println!("   python3 {}/client.py", config.output_dir);
+        eprintln!("   Compare with `venom generate --diff {}`, or overwrite them with --force", path);
+        std::process::exit(1);
+    }
+    
+    if force {
+        set_write_mode(WriteMode::Force);
+    } else if diff {
+        set_write_mode(WriteMode::Diff);
+    }
+    generate_project(&config, lang);
+}
+
+fn print_next_steps(steps: &RunSteps) {
+    println!();
+    println!("{}", style("📖 Next steps:").yellow());
+    for line in steps.setup.iter().chain([&steps.daemon]).chain(&steps.clients) {
+        println!("   {}", line);
+    }
+    if !steps.service.is_empty() {
+        println!();
+        println!("{}", style("⚙️  As a systemd service:").yellow());
+        for line in &steps.service {
+            println!("   {}", line);
         }
-        Language::Go => {
-            println!("   cd {} && make run-daemon", config.output_dir);
-            println!("   cd {} && make run-client", config.output_dir);
+    }
+    println!();
+}
+
+/// Commands to build and run a generated project
+struct RunSteps {
+    /// One-time setup before the first run
+    setup: Vec<String>,
+    daemon: String,
+    /// One line per client (several for a multi-language project)
+    clients: Vec<String>,
+    /// Installing the systemd unit (`--service`)
+    service: Vec<String>,
+}
+
+fn run_steps(config: &ProjectConfig, lang: Language) -> RunSteps {
+    let dir = &config.output_dir;
+    let mut setup = Vec::new();
+    let (daemon, client) = match lang {
+        Language::C | Language::Cpp => (
+            format!("cd {}/daemon && make run", dir),
+            format!("cd {}/client && make run", dir),
+        ),
+        Language::Rust => (
+            format!("cd {} && cargo run --bin daemon", dir),
+            format!("cd {} && cargo run --bin client", dir),
+        ),
+        Language::Python => {
+            if config.python_binding == PythonBinding::Pyo3 {
+                setup.push("maturin develop --release -m <venom_memory_rs>/venom-py/Cargo.toml   # once".to_string());
+            }
+            (format!("cd {}/daemon && make run", dir), format!("python3 {}/client.py", dir))
         }
-        Language::Zig => {
-            println!("   cd {} && zig build run-daemon", config.output_dir);
-            println!("   cd {} && zig build run-client", config.output_dir);
+        Language::Go | Language::Nim => (
+            format!("cd {} && make run-daemon", dir),
+            format!("cd {} && make run-client", dir),
+        ),
+        Language::Zig => (
+            format!("cd {} && zig build run-daemon", dir),
+            format!("cd {} && zig build run-client", dir),
+        ),
+        Language::Node => {
+            setup.push("(cd <venom_memory_rs>/venom-node && npm install && npm run build)   # once".to_string());
+            setup.push(format!("cd {} && npm install <venom_memory_rs>/venom-node", dir));
+            (
+                format!("cd {}/daemon && make run    # Terminal 1", dir),
+                format!("cd {} && npm start          # Terminal 2", dir),
+            )
         }
-        Language::Nim => {
-            println!("   cd {} && make run-daemon", config.output_dir);
-            println!("   cd {} && make run-client", config.output_dir);
+        Language::CSharp => (
+            format!("cd {}/daemon && make run    # Terminal 1", dir),
+            format!("cd {} && dotnet run         # Terminal 2", dir),
+        ),
+        Language::Java => (
+            format!("cd {}/daemon && make run    # Terminal 1", dir),
+            format!("cd {} && ./run.sh           # Terminal 2", dir),
+        ),
+        Language::Swift => (
+            format!("cd {}/daemon && make run    # Terminal 1", dir),
+            format!("cd {} && swift run          # Terminal 2", dir),
+        ),
+        Language::Lua => (
+            format!("cd {}/daemon && make run", dir),
+            format!("cd {} && luajit client.lua", dir),
+        ),
+        Language::Flutter if config.flutter_ui => {
+            let snake = config.names().snake;
+            setup.push(format!(
+                "cd {} && flutter create --platforms=linux,android --project-name {} . && rm test/widget_test.dart   # once",
+                dir, snake
+            ));
+            setup.push(format!(
+                "echo 'include(${{CMAKE_CURRENT_SOURCE_DIR}}/venom_memory.cmake)' >> {}/linux/CMakeLists.txt   # once",
+                dir
+            ));
+            (
+                format!("cd {}/daemon && make run    # Terminal 1", dir),
+                format!("cd {} && flutter run -d linux   # Terminal 2", dir),
+            )
         }
         Language::Flutter => {
-            let snake = config.name.replace("-", "_");
-            println!("   cd {}/daemon && make run    # Terminal 1", config.output_dir);
-            println!("   cd {} && dart compile exe bin/{}.dart -o client && ./client   # Terminal 2", config.output_dir, snake);
+            let snake = config.names().snake;
+            (
+                format!("cd {}/daemon && make run    # Terminal 1", dir),
+                format!("cd {} && dart compile exe bin/{}.dart -o client && ./client   # Terminal 2", dir, snake),
+            )
         }
+    };
+    RunSteps { setup, daemon, clients: vec![client], service: templates::service::next_steps(config) }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Add Client
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn add_client(path: &str, lang: Language, binding: Option<PythonBinding>, python_async: bool, go_nocgo: bool, flutter_ui: Option<bool>, force: bool) {
+    let (mut config, project_lang) = project::load(path).unwrap_or_else(|e| {
+        eprintln!("{} {}", style("❌ Not a venom project:").red(), e);
+        std::process::exit(1);
+    });
+    if let Some(binding) = binding {
+        config.python_binding = binding;
+    }
+    if python_async {
+        config.python_async = true;
+    }
+    if go_nocgo {
+        config.go_nocgo = true;
+    }
+    if let Some(flutter_ui) = flutter_ui {
+        config.flutter_ui = flutter_ui;
+    }
+    library::use_project_libraries(path);
+    for target in &config.targets {
+        if let Err(e) = library::library_for(target) {
+            eprintln!("{} {}", style("❌ Cannot bundle library:").red(), e);
+            std::process::exit(1);
+        }
+    }
+    
+    let existing: Vec<String> = dry_run(|| templates::generate_client(&config, lang))
+        .into_iter()
+        .filter(|p| Path::new(p).exists())
+        .collect();
+    if !existing.is_empty() && !force {
+        eprintln!("{}", style("❌ These files already exist (use --force to overwrite):").red());
+        for p in &existing {
+            eprintln!("   {}", p);
+        }
+        std::process::exit(1);
+    }
+    
+    if force {
+        set_write_mode(WriteMode::Force);
+    }
+    
+    println!();
+    println!(
+        "{}",
+        style(format!("📁 Adding {} client to {} ({} project)...", lang.id(), config.name, project_lang.id())).cyan()
+    );
+    
+    templates::generate_client(&config, lang);
+    let lib_dir = format!("{}/lib", config.output_dir);
+    if !templates::bundled_libraries(&config, &lib_dir).iter().all(|p| Path::new(p).exists()) {
+        library::copy_library_to(&config.output_dir, &config.targets);
+    }
+    
+    // The daemon stays the one the project was generated with
+    let steps = RunSteps { daemon: run_steps(&config, project_lang).daemon, ..run_steps(&config, lang) };
+    println!();
+    println!("{}", style("✅ Client added!").green().bold());
+    print_next_steps(&steps);
+}
+
+fn generate_bench(path: &str, langs: &[Language], force: bool) {
+    let (config, project_lang) = project::load(path).unwrap_or_else(|e| {
+        eprintln!("{} {}", style("❌ Not a venom project:").red(), e);
+        std::process::exit(1);
+    });
+    let langs = bench::languages(&config, project_lang, langs).unwrap_or_else(|e| {
+        eprintln!("{} {}", style("❌ venom bench:").red(), e);
+        std::process::exit(1);
+    });
+    if force {
+        set_write_mode(WriteMode::Force);
     }
+
+    println!();
+    println!(
+        "{}",
+        style(format!(
+            "📁 Adding benchmarks to {}: {}...",
+            config.name,
+            langs.iter().map(|l| l.id()).collect::<Vec<_>>().join(", ")
+        ))
+        .cyan()
+    );
+    bench::generate(&config, &langs);
+
     println!();
+    println!("{}", style("✅ Benchmarks generated!").green().bold());
+    // Setup (venv, npm install, ...) is the client's; the benchmarks need none
+    let steps = RunSteps {
+        setup: Vec::new(),
+        clients: vec![format!("cargo run --release --manifest-path {}/bench/collect/Cargo.toml", config.output_dir)],
+        service: Vec::new(),
+        ..run_steps(&config, project_lang)
+    };
+    print_next_steps(&steps);
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
 // File Utilities (used by templates)
 // ═══════════════════════════════════════════════════════════════════════════
 
+thread_local! {
+    /// Paths recorded instead of written while a dry run is active; per
+    /// thread, like `WRITE_MODE`, so a test's dry run doesn't swallow the
+    /// writes of the tests running next to it
+    static DRY_RUN: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+}
+
+/// Run `generate` without touching the disk and return the files it would
+/// create or change
+pub fn dry_run(generate: impl FnOnce()) -> Vec<String> {
+    DRY_RUN.with(|planned| *planned.borrow_mut() = Some(Vec::new()));
+    generate();
+    DRY_RUN.with(|planned| planned.borrow_mut().take().unwrap_or_default())
+}
+
+pub fn is_dry_run() -> bool {
+    DRY_RUN.with(|planned| planned.borrow().is_some())
+}
+
+/// How `write_file` treats files that already exist
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum WriteMode {
+    /// Keep existing files, write only missing ones
+    Merge,
+    /// Overwrite existing files
+    Force,
+    /// Write nothing; print how each file would change
+    Diff,
+}
+
+thread_local! {
+    // Per thread so tests that force-write don't race the ones relying on Merge
+    static WRITE_MODE: Cell<WriteMode> = const { Cell::new(WriteMode::Merge) };
+}
+
+/// Existing files kept by the last `WriteMode::Merge` generation
+static SKIPPED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+pub fn set_write_mode(mode: WriteMode) {
+    WRITE_MODE.with(|m| m.set(mode));
+}
+
+pub fn write_mode() -> WriteMode {
+    WRITE_MODE.with(Cell::get)
+}
+
+/// Take the files skipped since the last call
+fn take_skipped() -> Vec<String> {
+    std::mem::take(&mut *SKIPPED.lock().unwrap())
+}
+
 pub fn create_dir(path: &str) {
-    fs::create_dir_all(path).expect(&format!("Failed to create: {}", path));
+    if is_dry_run() || write_mode() == WriteMode::Diff {
+        return;
+    }
+    fs::create_dir_all(path).unwrap_or_else(|_| panic!("Failed to create: {}", path));
 }
 
 pub fn write_file(path: &str, content: &str) {
+    write_output(path, content.as_bytes());
+}
+
+/// Write a binary file (the bundled library); returns whether it was written
+pub fn write_binary(path: &str, content: &[u8]) -> bool {
+    write_output(path, content)
+}
+
+fn write_output(path: &str, content: &[u8]) -> bool {
+    let planned = DRY_RUN.with(|planned| {
+        let mut planned = planned.borrow_mut();
+        let planned = planned.as_mut()?;
+        if fs::read(path).ok().as_deref() != Some(content) {
+            planned.push(path.to_string());
+        }
+        Some(())
+    });
+    if planned.is_some() {
+        return false;
+    }
+    let mode = write_mode();
+    match fs::read(path) {
+        Ok(old) if old == content => {
+            println!("   {} {}", style("=").dim(), style(format!("{} (unchanged)", path)).dim());
+            return false;
+        }
+        Ok(_) if mode == WriteMode::Merge => {
+            println!("   {} {}", style("~").yellow(), style(format!("{} (exists, skipped)", path)).yellow());
+            SKIPPED.lock().unwrap().push(path.to_string());
+            return false;
+        }
+        Ok(old) if mode == WriteMode::Diff => {
+            print_diff(path, &old, content);
+            return false;
+        }
+        Err(_) if mode == WriteMode::Diff => {
+            println!("   {} {}", style("+").green(), style(format!("{} (new file)", path)).green());
+            return false;
+        }
+        _ => {}
+    }
     let parent = Path::new(path).parent().unwrap();
     fs::create_dir_all(parent).ok();
-    fs::write(path, content).expect(&format!("Failed to write: {}", path));
+    fs::write(path, content).unwrap_or_else(|_| panic!("Failed to write: {}", path));
     println!("   {} {}", style("✓").green(), path);
+    true
+}
+
+fn print_diff(path: &str, old: &[u8], new: &[u8]) {
+    let (Ok(old), Ok(new)) = (std::str::from_utf8(old), std::str::from_utf8(new)) else {
+        println!("   {} {}", style("~").yellow(), style(format!("{} (binary, differs)", path)).yellow());
+        return;
+    };
+    let Some(diff) = diff::unified(path, old, new) else { return };
+    println!();
+    for line in diff.lines() {
+        match line.chars().next() {
+            Some('@') => println!("{}", style(line).cyan()),
+            Some('-') => println!("{}", style(line).red()),
+            Some('+') => println!("{}", style(line).green()),
+            _ => println!("{}", line),
+        }
+    }
+    println!();
+}
+
+/// Write a file shared with the daemon (protocol headers) unless it already exists
+pub fn write_file_if_missing(path: &str, content: &str) {
+    if !Path::new(path).exists() {
+        write_file(path, content);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regenerate_keeps_edits_unless_forced() {
+        let dir = std::env::temp_dir().join(format!("venom-regen-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let config = ProjectConfig {
+            name: "regen".to_string(),
+            channel: "regen".to_string(),
+            data_size: 16 * 1024,
+            cmd_slots: 32,
+            max_clients: 16,
+            shm_mode: 0o664,
+            output_dir: dir.display().to_string(),
+            python_binding: PythonBinding::Ctypes,
+            go_nocgo: false,
+            flutter_ui: true,
+            python_async: false,
+            magic: templates::channel_magic("regen"),
+            schema: Schema::demo(),
+            targets: Vec::new(),
+            clients: Vec::new(),
+            root: String::new(),
+            service: false,
+            license: License::None,
+            extras: true,
+            license_year: 2026,
+        };
+        let daemon = dir.join("daemon/src/main.c");
+
+        generate_project(&config, Language::C);
+        fs::write(&daemon, "// my daemon\n").unwrap();
+
+        // Default: existing files are kept
+        generate_project(&config, Language::C);
+        assert_eq!(fs::read_to_string(&daemon).unwrap(), "// my daemon\n");
+
+        // --force: replaced with the template
+        set_write_mode(WriteMode::Force);
+        generate_project(&config, Language::C);
+        set_write_mode(WriteMode::Merge);
+        let regenerated = fs::read_to_string(&daemon).unwrap();
+        fs::remove_dir_all(&dir).ok();
+        assert!(regenerated.contains("venom_daemon_create"), "{}", regenerated);
+    }
+
+    #[test]
+    fn test_target_links_library_from_its_subdirectory() {
+        let dir = std::env::temp_dir().join(format!("venom-target-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let triple = "aarch64-unknown-linux-gnu";
+        // Stand-in for a prebuilt aarch64 library, found like an existing project's lib/
+        let prebuilt = dir.join("prebuilt");
+        fs::create_dir_all(prebuilt.join("lib").join(triple)).unwrap();
+        fs::write(prebuilt.join("lib").join(triple).join(library::LIBRARY_NAME), b"aarch64").unwrap();
+        library::use_project_libraries(&prebuilt.display().to_string());
+
+        let config = ProjectConfig {
+            name: "cross".to_string(),
+            channel: "cross".to_string(),
+            data_size: 16 * 1024,
+            cmd_slots: 32,
+            max_clients: 16,
+            shm_mode: 0o664,
+            output_dir: dir.join("cross").display().to_string(),
+            python_binding: PythonBinding::Ctypes,
+            go_nocgo: false,
+            flutter_ui: true,
+            python_async: false,
+            magic: templates::channel_magic("cross"),
+            schema: Schema::demo(),
+            targets: vec![triple.to_string()],
+            clients: Vec::new(),
+            root: String::new(),
+            service: false,
+            license: License::None,
+            extras: true,
+            license_year: 2026,
+        };
+        generate_project(&config, Language::C);
+
+        let out = dir.join("cross");
+        let makefile = fs::read_to_string(out.join("daemon/Makefile")).unwrap();
+        let bundled = fs::read(out.join("lib").join(triple).join(library::LIBRARY_NAME)).unwrap();
+        let flat = out.join("lib").join(library::LIBRARY_NAME).exists();
+        let checks = doctor::run(&out.display().to_string());
+        fs::remove_dir_all(&dir).ok();
+
+        assert!(makefile.contains("VENOM_TARGET ?= aarch64-unknown-linux-gnu"), "{}", makefile);
+        assert!(makefile.contains("-L../lib/$(VENOM_TARGET)"), "{}", makefile);
+        assert_eq!(bundled, b"aarch64");
+        assert!(!flat);
+        assert!(!doctor::has_failures(&checks));
+    }
+
+    #[test]
+    fn test_extras_are_generated_unless_turned_off() {
+        let dir = std::env::temp_dir().join(format!("venom-extras-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let config = |extras: bool| ProjectConfig {
+            name: "extras".to_string(),
+            channel: "extras".to_string(),
+            data_size: 16 * 1024,
+            cmd_slots: 32,
+            max_clients: 16,
+            shm_mode: 0o664,
+            output_dir: dir.join(if extras { "with" } else { "without" }).display().to_string(),
+            python_binding: PythonBinding::Ctypes,
+            go_nocgo: false,
+            flutter_ui: true,
+            python_async: false,
+            magic: templates::channel_magic("extras"),
+            schema: Schema::demo(),
+            targets: Vec::new(),
+            clients: Vec::new(),
+            root: String::new(),
+            service: false,
+            license: if extras { License::Mit } else { License::None },
+            extras,
+            license_year: 2026,
+        };
+        generate_project(&config(true), Language::C);
+        generate_project(&config(false), Language::C);
+        let with = dir.join("with");
+        let gitignore = fs::read_to_string(with.join(".gitignore")).unwrap();
+        let license = fs::read_to_string(with.join("LICENSE")).unwrap();
+        let vscode = ["launch.json", "tasks.json"].map(|f| with.join(".vscode").join(f).is_file());
+        let without = [".gitignore", "LICENSE", ".vscode"].map(|f| dir.join("without").join(f).exists());
+        let (loaded, _) = project::load(&with.display().to_string()).unwrap();
+        fs::remove_dir_all(&dir).ok();
+
+        assert!(gitignore.contains("/daemon/extras_daemon\n"), "{}", gitignore);
+        assert!(license.starts_with("MIT License") && license.contains("The extras authors"), "{}", license);
+        assert_eq!(vscode, [true, true]);
+        assert_eq!(without, [false, false, false]);
+        assert_eq!(loaded.license, License::Mit);
+    }
+
+    #[test]
+    fn test_normalized_name_builds() {
+        let dir = std::env::temp_dir().join(format!("venom-names-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let name = templates::project_name("2nd Monitor!").unwrap();
+        let config = ProjectConfig {
+            name: name.clone(),
+            channel: "names".to_string(),
+            data_size: 16 * 1024,
+            cmd_slots: 32,
+            max_clients: 16,
+            shm_mode: 0o664,
+            output_dir: dir.display().to_string(),
+            python_binding: PythonBinding::Ctypes,
+            go_nocgo: false,
+            flutter_ui: true,
+            python_async: false,
+            magic: templates::channel_magic("names"),
+            schema: Schema::demo(),
+            targets: Vec::new(),
+            clients: vec![Language::Cpp],
+            root: String::new(),
+            service: false,
+            license: License::None,
+            extras: false,
+            license_year: 2026,
+        };
+        generate_project(&config, Language::C);
+        let header = fs::read_to_string(dir.join("shared/protocol.h")).unwrap();
+        let make = |path: &str| std::process::Command::new("make").current_dir(dir.join(path)).output().unwrap();
+        let (daemon, client) = (make("daemon"), make("clients/cpp/client"));
+        let built = dir.join("daemon/2nd-Monitor_daemon").is_file();
+        let (loaded, _) = project::load(&config.output_dir).unwrap();
+        fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(name, "2nd-Monitor");
+        assert!(header.contains("#ifndef VENOM_2ND_MONITOR_PROTOCOL_H"), "{}", header);
+        assert!(header.contains("} Venom2ndMonitorState;"), "{}", header);
+        assert!(daemon.status.success(), "{}", String::from_utf8_lossy(&daemon.stderr));
+        assert!(client.status.success(), "{}", String::from_utf8_lossy(&client.stderr));
+        assert!(built);
+        assert_eq!(loaded.name, name);
+    }
+
+    #[test]
+    fn test_generate_check_finds_drift() {
+        let dir = std::env::temp_dir().join(format!("venom-generate-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let config = |at: &str| ProjectConfig {
+            name: "regen".to_string(),
+            channel: "regen".to_string(),
+            data_size: 16 * 1024,
+            cmd_slots: 32,
+            max_clients: 16,
+            shm_mode: 0o660,
+            output_dir: dir.join(at).display().to_string(),
+            python_binding: PythonBinding::Pyo3,
+            go_nocgo: true,
+            flutter_ui: true,
+            python_async: false,
+            magic: templates::channel_magic("regen"),
+            schema: Schema::demo(),
+            targets: Vec::new(),
+            clients: vec![Language::Python, Language::Go, Language::Rust],
+            root: String::new(),
+            service: true,
+            license: License::Apache2,
+            extras: true,
+            license_year: 2020,
+        };
+        generate_project(&config("a"), Language::C);
+        generate_project(&config("b"), Language::C);
+        let a = dir.join("a").display().to_string();
+        let files = |root: &Path| {
+            let mut files = Vec::new();
+            let mut dirs = vec![root.to_path_buf()];
+            while let Some(d) = dirs.pop() {
+                for entry in fs::read_dir(d).unwrap().flatten() {
+                    if entry.path().is_dir() {
+                        dirs.push(entry.path());
+                    } else {
+                        files.push((entry.path().strip_prefix(root).unwrap().to_path_buf(), fs::read(entry.path()).unwrap()));
+                    }
+                }
+            }
+            files.sort();
+            files
+        };
+        let (files_a, files_b) = (files(&dir.join("a")), files(&dir.join("b")));
+
+        // Regenerated from venom.toml alone: nothing drifts
+        let (loaded, lang) = project::load(&a).unwrap();
+        let clean = drifted_files(&loaded, lang);
+        fs::write(dir.join("a/clients/python/venom_binding.py"), "# edited\n").unwrap();
+        fs::remove_file(dir.join("a/LICENSE")).unwrap();
+        let drifted = drifted_files(&loaded, lang);
+        fs::remove_dir_all(&dir).ok();
+
+        // The same config gives the same files wherever it is generated
+        assert!(files_a.len() > 20);
+        assert!(files_a == files_b, "{:?}", files_a.iter().zip(&files_b).find(|(a, b)| a != b).map(|(a, _)| &a.0));
+        assert_eq!(clean, Vec::<String>::new());
+        assert_eq!(drifted, ["LICENSE", "clients/python/venom_binding.py"]);
+        assert_eq!(loaded.license_year, 2020);
+        assert_eq!(loaded.python_binding, PythonBinding::Pyo3);
+    }
+
+    #[test]
+    fn test_split_languages() {
+        let langs = [Language::C, Language::Python, Language::Go, Language::Python];
+        assert_eq!(split_languages(&langs, None), (Language::C, vec![Language::Python, Language::Go]));
+        assert_eq!(
+            split_languages(&langs, Some(Language::Go)),
+            (Language::Go, vec![Language::C, Language::Python])
+        );
+        assert_eq!(split_languages(&[Language::Rust], None), (Language::Rust, vec![]));
+    }
+
+    #[test]
+    fn test_parse_shm_mode() {
+        assert_eq!(parse_shm_mode("660"), Ok(0o660));
+        assert_eq!(parse_shm_mode("0600"), Ok(0o600));
+        assert_eq!(parse_shm_mode("0o666"), Ok(0o666));
+        assert!(parse_shm_mode("1777").is_err());
+        assert!(parse_shm_mode("688").is_err());
+    }
+
+    #[test]
+    fn test_extra_clients_share_lib_and_protocol() {
+        let dir = std::env::temp_dir().join(format!("venom-multi-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let config = ProjectConfig {
+            name: "multi".to_string(),
+            channel: "multi".to_string(),
+            data_size: 16 * 1024,
+            cmd_slots: 32,
+            max_clients: 16,
+            shm_mode: 0o664,
+            output_dir: dir.display().to_string(),
+            python_binding: PythonBinding::Ctypes,
+            go_nocgo: false,
+            flutter_ui: true,
+            python_async: false,
+            magic: templates::channel_magic("multi"),
+            schema: Schema::demo(),
+            targets: Vec::new(),
+            clients: vec![Language::Cpp, Language::Python],
+            root: String::new(),
+            service: false,
+            license: License::None,
+            extras: true,
+            license_year: 2026,
+        };
+        generate_project(&config, Language::C);
+
+        let cpp = dir.join("clients/cpp/client");
+        let build = std::process::Command::new("make").current_dir(&cpp).output().unwrap();
+        let python = fs::read_to_string(dir.join("clients/python/venom_binding.py")).unwrap();
+        let (loaded, lang) = project::load(&dir.display().to_string()).unwrap();
+        let private_copies = ["clients/cpp/shared", "clients/cpp/lib", "clients/python/lib"]
+            .iter()
+            .any(|p| dir.join(p).exists());
+        fs::remove_dir_all(&dir).ok();
+
+        assert!(build.status.success(), "{}", String::from_utf8_lossy(&build.stderr));
+        assert!(python.contains("script_dir / \"../../lib\""), "{}", python);
+        assert!(python.contains(&format!("0x{:08X}", config.magic)));
+        assert!(!private_copies);
+        assert_eq!(lang, Language::C);
+        assert_eq!(loaded.clients, config.clients);
+    }
+
+    #[test]
+    fn test_service_unit_runs_the_built_daemon() {
+        let dir = std::env::temp_dir().join(format!("venom-service-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let channel = format!("svc-{}", std::process::id());
+        let config = |lang: Language| ProjectConfig {
+            name: "svc".to_string(),
+            channel: channel.clone(),
+            data_size: 16 * 1024,
+            cmd_slots: 32,
+            max_clients: 16,
+            shm_mode: 0o664,
+            output_dir: dir.join(lang.id()).display().to_string(),
+            python_binding: PythonBinding::Ctypes,
+            go_nocgo: false,
+            flutter_ui: true,
+            python_async: false,
+            magic: templates::channel_magic(&channel),
+            schema: Schema::demo(),
+            targets: Vec::new(),
+            clients: Vec::new(),
+            root: String::new(),
+            service: true,
+            license: License::None,
+            extras: true,
+            license_year: 2026,
+        };
+        let run = |cmd: &str, args: &[&str], cwd: &Path| {
+            let out = std::process::Command::new(cmd).args(args).current_dir(cwd).output().unwrap();
+            assert!(out.status.success(), "{} {:?}: {}", cmd, args, String::from_utf8_lossy(&out.stderr));
+        };
+
+        // C: the unit starts daemon/svc_daemon, which detaches with --daemonize
+        let c = dir.join("c");
+        generate_project(&config(Language::C), Language::C);
+        let unit = fs::read_to_string(c.join("packaging/svc.service")).unwrap();
+        assert!(unit.contains("ExecStart=@ROOT@/daemon/svc_daemon --daemonize"), "{}", unit);
+        assert!(unit.contains("ReadWritePaths=/dev/shm"), "{}", unit);
+        run("make", &[], &c.join("daemon"));
+        assert!(c.join("daemon/svc_daemon").is_file());
+        // Installing fills in where the project is
+        let destdir = format!("DESTDIR={}", dir.join("root").display());
+        run("make", &["install", &destdir, "SYSTEMD_DIR=/units"], &c.join("packaging"));
+        let installed = fs::read_to_string(dir.join("root/units/svc.service")).unwrap();
+        let root = fs::canonicalize(&c).unwrap();
+        assert!(installed.contains(&format!("ExecStart={}/daemon/svc_daemon --daemonize", root.display())), "{}", installed);
+        assert!(installed.contains(&format!("WorkingDirectory={}\n", root.display())), "{}", installed);
+        run("./svc_daemon", &["--daemonize", "--pidfile", "svc.pid", "--logfile", "svc.log"], &c.join("daemon"));
+        let pid: i32 = fs::read_to_string(c.join("daemon/svc.pid")).unwrap().trim().parse().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+        unsafe { libc::kill(pid, libc::SIGINT) };
+        for _ in 0..50 {
+            if !c.join("daemon/svc.pid").exists() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+        let log = fs::read_to_string(c.join("daemon/svc.log")).unwrap();
+        assert!(!c.join("daemon/svc.pid").exists(), "pidfile left behind");
+        assert!(log.contains("Goodbye"), "{}", log);
+        assert!(!log.contains('\r'), "{}", log);
+
+        // Rust: the unit starts the release build, and --daemonize compiles
+        let rust = dir.join("rust");
+        generate_project(&config(Language::Rust), Language::Rust);
+        let unit = fs::read_to_string(rust.join("packaging/svc.service")).unwrap();
+        let readme = fs::read_to_string(rust.join("README.md")).unwrap();
+        assert!(unit.contains("ExecStart=@ROOT@/target/release/daemon --daemonize"), "{}", unit);
+        assert!(readme.contains("systemctl enable --now svc"), "{}", readme);
+        run("cargo", &["build", "--offline", "--bin", "daemon"], &rust);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_go_nocgo_client_maps_the_channel_itself() {
+        let dir = std::env::temp_dir().join(format!("venom-nocgo-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let config = ProjectConfig {
+            name: "nocgo".to_string(),
+            channel: "nocgo".to_string(),
+            data_size: 16 * 1024,
+            cmd_slots: 32,
+            max_clients: 16,
+            shm_mode: 0o664,
+            output_dir: dir.display().to_string(),
+            python_binding: PythonBinding::Ctypes,
+            go_nocgo: true,
+            flutter_ui: true,
+            python_async: false,
+            magic: templates::channel_magic("nocgo"),
+            schema: Schema::demo(),
+            targets: Vec::new(),
+            clients: Vec::new(),
+            root: String::new(),
+            service: false,
+            license: License::None,
+            extras: true,
+            license_year: 2026,
+        };
+        generate_project(&config, Language::Go);
+        let read = |path: &str| fs::read_to_string(dir.join(path)).unwrap();
+        let (venom_go, daemon_go, go_mod, makefile) = (read("venom/venom.go"), read("venom/daemon.go"), read("go.mod"), read("Makefile"));
+        let (loaded, _) = project::load(&config.output_dir).unwrap();
+        fs::remove_dir_all(&dir).ok();
+
+        assert!(!venom_go.contains("import \"C\""), "{}", venom_go);
+        assert!(venom_go.contains(&format!("channelMagic   = 0x{:08X}", venom_memory::layout::MAGIC)), "{}", venom_go);
+        assert!(venom_go.contains(&format!("slotCmdData  = {}", venom_memory::layout::slot::CMD_DATA)), "{}", venom_go);
+        assert!(venom_go.contains(&format!("queueCmdPayloadSize  = {}", venom_memory::layout::queue::CMD_PAYLOAD_SIZE)), "{}", venom_go);
+        assert!(venom_go.contains(&format!("queueCmdFullPolicy   = {}", venom_memory::layout::queue::CMD_FULL_POLICY)), "{}", venom_go);
+        assert!(venom_go.contains(&format!("seqLenPrefix = {}", venom_memory::layout::seqlock::LEN_PREFIX)), "{}", venom_go);
+        assert!(daemon_go.contains("import \"C\"") && daemon_go.contains("func NewDaemon"), "{}", daemon_go);
+        assert!(go_mod.contains("require golang.org/x/sys"), "{}", go_mod);
+        assert!(makefile.contains("CGO_ENABLED=0 go build -o ../nocgo_client"), "{}", makefile);
+        assert!(loaded.go_nocgo);
+    }
+
+    #[test]
+    fn test_flutter_app_keeps_console_client() {
+        let dir = std::env::temp_dir().join(format!("venom-flutter-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let config = |flutter_ui: bool| ProjectConfig {
+            name: "flutter-mon".to_string(),
+            channel: "fluttermon".to_string(),
+            data_size: 16 * 1024,
+            cmd_slots: 32,
+            max_clients: 16,
+            shm_mode: 0o664,
+            output_dir: dir.join(if flutter_ui { "app" } else { "console" }).display().to_string(),
+            python_binding: PythonBinding::Ctypes,
+            go_nocgo: false,
+            flutter_ui,
+            python_async: false,
+            magic: templates::channel_magic("fluttermon"),
+            schema: Schema::demo(),
+            targets: Vec::new(),
+            clients: Vec::new(),
+            root: String::new(),
+            service: false,
+            license: License::None,
+            extras: true,
+            license_year: 2026,
+        };
+        let read = |path: &str| fs::read_to_string(dir.join(path)).unwrap();
+        generate_project(&config(true), Language::Flutter);
+        generate_project(&config(false), Language::Flutter);
+        let (app, reader, pubspec, cmake) = (
+            read("app/lib/main.dart"),
+            read("app/lib/venom_reader.dart"),
+            read("app/pubspec.yaml"),
+            read("app/linux/venom_memory.cmake"),
+        );
+        let console_pubspec = read("console/pubspec.yaml");
+        let (bin_kept, console_app_exists) =
+            (dir.join("app/bin/flutter_mon.dart").is_file(), dir.join("console/lib/main.dart").exists());
+        let (loaded, _) = project::load(&config(false).output_dir).unwrap();
+        fs::remove_dir_all(&dir).ok();
+
+        assert!(app.contains("MaterialApp(") && app.contains("_CoreBar(index: i"), "{}", app);
+        assert!(app.contains("VenomReader.start()"), "{}", app);
+        assert!(reader.contains("Isolate.spawn(_read") && reader.contains("Timer.periodic"), "{}", reader);
+        assert!(pubspec.contains("  flutter:\n    sdk: flutter\n"), "{}", pubspec);
+        assert!(cmake.contains("../native/libvenom_memory.so"), "{}", cmake);
+        assert!(bin_kept);
+        assert!(!console_app_exists);
+        assert!(!console_pubspec.contains("sdk: flutter"), "{}", console_pubspec);
+        assert!(!loaded.flutter_ui);
+    }
+
+    #[test]
+    fn test_generated_binding_tests_pass() {
+        let dir = std::env::temp_dir().join(format!("venom-golden-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        // Every field type, with padding between some of them
+        let schema = Schema::parse(
+            r#"
+            [[field]]
+            name = "flag"
+            type = "u8"
+            [[field]]
+            name = "total"
+            type = "u64"
+            [[field]]
+            name = "levels"
+            type = "i16"
+            len = 3
+            [[field]]
+            name = "offset"
+            type = "i64"
+            [[field]]
+            name = "ratio"
+            type = "f64"
+            [[field]]
+            name = "delta"
+            type = "i8"
+            [[field]]
+            name = "port"
+            type = "u16"
+            [[field]]
+            name = "count"
+            type = "u32"
+            [[field]]
+            name = "error"
+            type = "i32"
+            [[field]]
+            name = "loads"
+            type = "f32"
+            len = 2
+            "#,
+        )
+        .unwrap();
+        let config = |lang: Language| ProjectConfig {
+            name: "golden".to_string(),
+            channel: "golden".to_string(),
+            data_size: 16 * 1024,
+            cmd_slots: 32,
+            max_clients: 16,
+            shm_mode: 0o664,
+            output_dir: dir.join(lang.id()).display().to_string(),
+            python_binding: PythonBinding::Ctypes,
+            go_nocgo: false,
+            flutter_ui: true,
+            python_async: false,
+            magic: templates::channel_magic("golden"),
+            schema: schema.clone(),
+            targets: Vec::new(),
+            clients: Vec::new(),
+            root: String::new(),
+            service: false,
+            license: License::None,
+            extras: true,
+            license_year: 2026,
+        };
+        let run = |cmd: &str, args: &[&str], cwd: &Path| {
+            let out = std::process::Command::new(cmd)
+                .args(args)
+                .current_dir(cwd)
+                .env_remove(templates::LIVE_TEST_ENV)
+                .output()
+                .unwrap();
+            let output = String::from_utf8_lossy(&out.stdout).into_owned() + &String::from_utf8_lossy(&out.stderr);
+            assert!(out.status.success(), "{} {:?}: {}", cmd, args, output);
+            output
+        };
+
+        for lang in [Language::Rust, Language::Python, Language::C] {
+            generate_project(&config(lang), lang);
+        }
+        let rust = run("cargo", &["test", "--offline", "--lib"], &dir.join("rust"));
+        let python = run("python3", &["test_venom_binding.py"], &dir.join("python"));
+        let c = run("make", &["test"], &dir.join("c/client"));
+        fs::remove_dir_all(&dir).ok();
+
+        assert!(rust.contains("test tests::decodes_golden_state ... ok"), "{}", rust);
+        assert!(rust.contains("test tests::appends_channel_suffix ... ok"), "{}", rust);
+        assert!(rust.contains("test tests::encodes_typed_command ... ok"), "{}", rust);
+        assert!(python.contains("test_decodes_golden_state ... ok"), "{}", python);
+        assert!(python.contains("test_appends_channel_suffix ... ok"), "{}", python);
+        assert!(python.contains("test_encodes_typed_command ... ok"), "{}", python);
+        assert!(python.contains("test_reads_live_state ... skipped"), "{}", python);
+        assert!(c.contains("test_decodes_golden_state ... ok"), "{}", c);
+    }
+
+    #[test]
+    fn test_python_async_client() {
+        let dir = std::env::temp_dir().join(format!("venom-pyasync-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let custom = Schema::parse("[[field]]\nname = \"level\"\ntype = \"u16\"\n").unwrap();
+        let config = |binding: PythonBinding, schema: Schema, python_async: bool| ProjectConfig {
+            name: "pyasync".to_string(),
+            channel: "pyasync".to_string(),
+            data_size: 16 * 1024,
+            cmd_slots: 32,
+            max_clients: 16,
+            shm_mode: 0o664,
+            output_dir: dir.join(format!("{:?}-{}-{}", binding, schema.has_demo_fields(), python_async)).display().to_string(),
+            python_binding: binding,
+            go_nocgo: false,
+            flutter_ui: true,
+            python_async,
+            magic: templates::channel_magic("pyasync"),
+            schema,
+            targets: Vec::new(),
+            clients: Vec::new(),
+            root: String::new(),
+            service: false,
+            license: License::None,
+            extras: true,
+            license_year: 2026,
+        };
+
+        // Without the flag, the synchronous client alone
+        let plain = config(PythonBinding::Ctypes, Schema::demo(), false);
+        generate_project(&plain, Language::Python);
+        assert!(!Path::new(&plain.output_dir).join("venom_async.py").exists());
+
+        // The tests stand in for the library, so neither binding needs it built
+        for (binding, schema) in [(PythonBinding::Ctypes, Schema::demo()), (PythonBinding::Pyo3, custom)] {
+            let config = config(binding, schema, true);
+            generate_project(&config, Language::Python);
+            let out = std::process::Command::new("python3")
+                .arg("test_venom_async.py")
+                .current_dir(&config.output_dir)
+                .output()
+                .unwrap();
+            let output = String::from_utf8_lossy(&out.stdout).into_owned() + &String::from_utf8_lossy(&out.stderr);
+            assert!(out.status.success(), "{:?}: {}", binding, output);
+            assert!(output.contains("test_updates_yield_each_new_state ... ok"), "{}", output);
+            let compiled = std::process::Command::new("python3")
+                .args(["-m", "py_compile", "main_async.py"])
+                .current_dir(&config.output_dir)
+                .status()
+                .unwrap();
+            assert!(compiled.success(), "{:?}", binding);
+            let (loaded, _) = project::load(&config.output_dir).unwrap();
+            assert!(loaded.python_async);
+        }
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_channel_suffix_runs_instances_side_by_side() {
+        let dir = std::env::temp_dir().join(format!("venom-instances-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let channel = format!("instances-{}", std::process::id());
+        let config = ProjectConfig {
+            name: "instances".to_string(),
+            channel: channel.clone(),
+            data_size: 16 * 1024,
+            cmd_slots: 32,
+            max_clients: 16,
+            shm_mode: 0o664,
+            output_dir: dir.display().to_string(),
+            python_binding: PythonBinding::Ctypes,
+            go_nocgo: false,
+            flutter_ui: true,
+            python_async: false,
+            magic: templates::channel_magic(&channel),
+            schema: Schema::parse("[[field]]\nname = \"level\"\ntype = \"u16\"\n").unwrap(),
+            targets: Vec::new(),
+            clients: Vec::new(),
+            root: String::new(),
+            service: false,
+            license: License::None,
+            extras: true,
+            license_year: 2026,
+        };
+        generate_project(&config, Language::C);
+        for side in ["daemon", "client"] {
+            let built = std::process::Command::new("make").current_dir(dir.join(side)).output().unwrap();
+            assert!(built.status.success(), "{}", String::from_utf8_lossy(&built.stderr));
+        }
+
+        // One daemon per suffix, each on a channel of its own; cargo's
+        // LD_LIBRARY_PATH would pick up another build of the library
+        let mut daemons: Vec<_> = ["a", "b"]
+            .iter()
+            .map(|suffix| {
+                std::process::Command::new("./instances_daemon")
+                    .args(["--channel-suffix", suffix])
+                    .current_dir(dir.join("daemon"))
+                    .env_remove("LD_LIBRARY_PATH")
+                    .stdout(std::process::Stdio::null())
+                    .spawn()
+                    .unwrap()
+            })
+            .collect();
+        let shm = |suffix: &str| Path::new("/dev/shm").join(format!("venom_{}-{}", channel, suffix));
+        for _ in 0..100 {
+            if shm("a").exists() && shm("b").exists() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        let ping = |suffix: &str| {
+            let out = std::process::Command::new("./instances_client")
+                .args(["--channel-suffix", suffix, "--ping", "1"])
+                .current_dir(dir.join("client"))
+                .env_remove("LD_LIBRARY_PATH")
+                .output()
+                .unwrap();
+            (out.status.success(), String::from_utf8_lossy(&out.stdout).into_owned())
+        };
+        let (a, b, none) = (ping("a"), ping("b"), ping("c"));
+
+        // The second daemon going away leaves the first one's clients alone
+        daemons[1].kill().unwrap();
+        daemons[1].wait().unwrap();
+        let a_alone = ping("a");
+        daemons[0].kill().unwrap();
+        daemons[0].wait().unwrap();
+        for suffix in ["a", "b"] {
+            fs::remove_file(shm(suffix)).ok();
+        }
+        fs::remove_dir_all(&dir).ok();
+
+        assert!(a.0 && a.1.contains("1/1 replies"), "{}", a.1);
+        assert!(b.0 && b.1.contains("1/1 replies"), "{}", b.1);
+        assert!(!none.0, "{}", none.1);
+        assert!(a_alone.0, "{}", a_alone.1);
+    }
 }
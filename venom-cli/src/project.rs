@@ -0,0 +1,296 @@
+//! Project manifest
+//!
+//! `venom init` records the settings a project was generated with in a
+//! `[project]` table of its `venom.toml`, next to the schema:
+//!
+//! ```toml
+//! [project]
+//! name = "sensors"
+//! language = "c"
+//! channel = "sensors"
+//! data_size = 16384
+//! cmd_slots = 32
+//! max_clients = 16
+//! shm_mode = 0o660            # only when not 0o664
+//! magic = 0x5FD70E98
+//! targets = ["x86_64-unknown-linux-gnu", "aarch64-unknown-linux-gnu"]  # only with --target
+//! clients = ["python", "go"]  # extra clients under clients/<lang>/
+//! python_async = true         # only with --python-async
+//! go_nocgo = true             # only with --go-nocgo
+//! flutter_ui = false          # only with --flutter-ui=false
+//! service = true              # only with --service
+//! license = "mit"             # only with a license
+//! license_year = 2026         # year of its copyright line
+//! extras = false              # only with --no-extras
+//!
+//! [bindings]
+//! "venom_binding.py" = "5d1c0f2e8a9b3c47"
+//! ```
+//!
+//! Commands that work on an existing project (`venom add-client`) read it
+//! back so the code they generate matches the daemon byte for byte. The
+//! `[bindings]` table hashes each generated binding file as it was written,
+//! so `venom upgrade` can tell whether one was edited since.
+
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::schema::Schema;
+use crate::templates::{Language, License, ProjectConfig, PythonBinding};
+
+#[derive(Deserialize)]
+struct ManifestFile {
+    project: Option<ProjectTable>,
+    /// Binding file path -> `content_hash` of what was generated
+    #[serde(default)]
+    bindings: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProjectTable {
+    name: String,
+    language: String,
+    channel: String,
+    data_size: usize,
+    cmd_slots: usize,
+    max_clients: usize,
+    #[serde(default = "default_shm_mode")]
+    shm_mode: u32,
+    magic: u32,
+    python_binding: Option<String>,
+    #[serde(default)]
+    python_async: bool,
+    #[serde(default)]
+    go_nocgo: bool,
+    #[serde(default = "default_true")]
+    flutter_ui: bool,
+    #[serde(default)]
+    targets: Vec<String>,
+    #[serde(default)]
+    clients: Vec<String>,
+    #[serde(default)]
+    service: bool,
+    license: Option<String>,
+    license_year: Option<u64>,
+    #[serde(default = "default_true")]
+    extras: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_shm_mode() -> u32 {
+    venom_memory::ShmPermissions::DEFAULT_MODE
+}
+
+/// Render the `[project]` table for `venom.toml`
+pub fn to_toml(config: &ProjectConfig, lang: Language) -> String {
+    let mut out = format!(
+        "\n# Settings this project was generated with; used by `venom add-client`.\n\
+         [project]\n\
+         name = \"{}\"\n\
+         language = \"{}\"\n\
+         channel = \"{}\"\n\
+         data_size = {}\n\
+         cmd_slots = {}\n\
+         max_clients = {}\n",
+        config.name,
+        lang.id(),
+        config.channel,
+        config.data_size,
+        config.cmd_slots,
+        config.max_clients,
+    );
+    if config.shm_mode != default_shm_mode() {
+        out.push_str(&format!("shm_mode = 0o{:o}\n", config.shm_mode));
+    }
+    out.push_str(&format!("magic = 0x{:08X}\n", config.magic));
+    if lang == Language::Python || config.clients.contains(&Language::Python) {
+        let binding = match config.python_binding {
+            PythonBinding::Ctypes => "ctypes",
+            PythonBinding::Pyo3 => "pyo3",
+        };
+        out.push_str(&format!("python_binding = \"{}\"\n", binding));
+    }
+    if config.python_async {
+        out.push_str("python_async = true\n");
+    }
+    if config.go_nocgo {
+        out.push_str("go_nocgo = true\n");
+    }
+    if !config.flutter_ui {
+        out.push_str("flutter_ui = false\n");
+    }
+    if !config.targets.is_empty() {
+        let quoted: Vec<String> = config.targets.iter().map(|t| format!("\"{}\"", t)).collect();
+        out.push_str(&format!("targets = [{}]\n", quoted.join(", ")));
+    }
+    if !config.clients.is_empty() {
+        let quoted: Vec<String> = config.clients.iter().map(|l| format!("\"{}\"", l.id())).collect();
+        out.push_str(&format!("clients = [{}]\n", quoted.join(", ")));
+    }
+    if config.service {
+        out.push_str("service = true\n");
+    }
+    if config.license != License::None {
+        out.push_str(&format!("license = \"{}\"\n", config.license.id()));
+        out.push_str(&format!("license_year = {}\n", config.license_year));
+    }
+    if !config.extras {
+        out.push_str("extras = false\n");
+    }
+    out
+}
+
+/// Header of the `[bindings]` table; everything from here to the end of
+/// `venom.toml` belongs to it
+pub const BINDINGS_HEADER: &str = "\n# Hashes of the generated binding files; used by `venom upgrade`.\n[bindings]\n";
+
+/// Render the `[bindings]` table for `(path, hash)` pairs
+pub fn bindings_toml(hashes: &BTreeMap<String, String>) -> String {
+    let mut out = BINDINGS_HEADER.to_string();
+    for (path, hash) in hashes {
+        out.push_str(&format!("\"{}\" = \"{}\"\n", path, hash));
+    }
+    out
+}
+
+/// FNV-1a hash of a binding file, as stored in `[bindings]`
+pub fn content_hash(content: &str) -> String {
+    let hash = content.bytes().fold(0xcbf29ce484222325u64, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3));
+    format!("{:016x}", hash)
+}
+
+/// Load the project generated into `dir`
+///
+/// Returns the config (with `output_dir` set to `dir`) and the language the
+/// project was generated for.
+pub fn load(dir: &str) -> Result<(ProjectConfig, Language), String> {
+    let path = Path::new(dir).join("venom.toml");
+    let text = fs::read_to_string(&path)
+        .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+    parse(&text, dir).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Hashes of the binding files as generated, from the `[bindings]` table of
+/// `dir/venom.toml` (empty for projects generated before it existed)
+pub fn load_binding_hashes(dir: &str) -> Result<BTreeMap<String, String>, String> {
+    let path = Path::new(dir).join("venom.toml");
+    let text = fs::read_to_string(&path)
+        .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+    let file: ManifestFile = toml::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e.message()))?;
+    Ok(file.bindings)
+}
+
+fn parse(text: &str, dir: &str) -> Result<(ProjectConfig, Language), String> {
+    let file: ManifestFile = toml::from_str(text).map_err(|e| e.message().to_string())?;
+    let project = file.project.ok_or_else(|| {
+        "no [project] table (generated by an older venom; regenerate with `venom init`)".to_string()
+    })?;
+    let lang = Language::from_id(&project.language)
+        .ok_or_else(|| format!("unknown language {:?}", project.language))?;
+    let python_binding = match project.python_binding.as_deref() {
+        None | Some("ctypes") => PythonBinding::Ctypes,
+        Some("pyo3") => PythonBinding::Pyo3,
+        Some(other) => return Err(format!("unknown python_binding {:?}", other)),
+    };
+    let clients = project
+        .clients
+        .iter()
+        .map(|id| Language::from_id(id).ok_or_else(|| format!("unknown client language {:?}", id)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let license = match project.license.as_deref() {
+        None => License::None,
+        Some(id) => License::from_id(id).ok_or_else(|| format!("unknown license {:?}", id))?,
+    };
+    let name = crate::templates::project_name(&project.name)?;
+    if name != project.name {
+        return Err(format!("name {:?} isn't a valid project name (it would be {:?})", project.name, name));
+    }
+    let config = ProjectConfig {
+        name,
+        channel: project.channel,
+        data_size: project.data_size,
+        cmd_slots: project.cmd_slots,
+        max_clients: project.max_clients,
+        shm_mode: project.shm_mode,
+        output_dir: dir.to_string(),
+        python_binding,
+        go_nocgo: project.go_nocgo,
+        python_async: project.python_async,
+        flutter_ui: project.flutter_ui,
+        magic: project.magic,
+        schema: Schema::parse(text)?,
+        targets: project.targets,
+        clients,
+        root: String::new(),
+        service: project.service,
+        license,
+        extras: project.extras,
+        license_year: project.license_year.unwrap_or_else(crate::templates::extras::current_year),
+    };
+    Ok((config, lang))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_round_trip() {
+        let config = ProjectConfig {
+            name: "sensors".to_string(),
+            channel: "sensors".to_string(),
+            data_size: 16 * 1024,
+            cmd_slots: 32,
+            max_clients: 16,
+            shm_mode: 0o660,
+            output_dir: "sensors".to_string(),
+            python_binding: PythonBinding::Pyo3,
+            go_nocgo: true,
+            flutter_ui: false,
+            python_async: true,
+            magic: 0xDEADBEEF,
+            schema: Schema::demo(),
+            targets: vec!["aarch64-unknown-linux-gnu".to_string()],
+            clients: vec![Language::Go, Language::Node],
+            root: String::new(),
+            service: true,
+            license: License::Apache2,
+            extras: false,
+            license_year: 2026,
+        };
+        let text = config.schema.to_toml(&config.name) + &to_toml(&config, Language::Python);
+
+        let (loaded, lang) = parse(&text, "elsewhere").unwrap();
+        assert_eq!(lang, Language::Python);
+        assert_eq!(loaded.name, "sensors");
+        assert_eq!(loaded.channel, "sensors");
+        assert_eq!(loaded.data_size, 16 * 1024);
+        assert_eq!(loaded.shm_mode, 0o660);
+        assert_eq!(loaded.magic, 0xDEADBEEF);
+        assert_eq!(loaded.python_binding, PythonBinding::Pyo3);
+        assert!(loaded.go_nocgo);
+        assert!(!loaded.flutter_ui);
+        assert!(loaded.python_async);
+        assert_eq!(loaded.output_dir, "elsewhere");
+        assert_eq!(loaded.schema, config.schema);
+        assert_eq!(loaded.targets, config.targets);
+        assert_eq!(loaded.clients, config.clients);
+        assert!(loaded.service);
+        assert_eq!(loaded.license, License::Apache2);
+        assert_eq!(loaded.license_year, 2026);
+        assert!(!loaded.extras);
+    }
+
+    #[test]
+    fn test_schema_only_file_is_rejected() {
+        let text = Schema::demo().to_toml("old");
+        let err = parse(&text, ".").err().unwrap();
+        assert!(err.contains("[project]"));
+    }
+}
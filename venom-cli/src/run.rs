@@ -0,0 +1,541 @@
+//! `venom run`: build a generated project and run its daemon and client together
+//!
+//! Both sides are built with the project's own tooling (make, cargo, zig, ...)
+//! and a failing build is reported with its output unchanged. The daemon then
+//! starts in its own process group with its output going to a log file, and
+//! once its channel shows up in shared memory the client takes over the
+//! terminal. When the client exits, or Ctrl+C stops it, the daemon gets the
+//! SIGINT it would get from Ctrl+C in its own terminal (every template cleans
+//! up its channel on that) and is reaped.
+
+use console::style;
+use std::fs::{self, File};
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use venom_memory::discover;
+
+use crate::project;
+use crate::templates::{Language, ProjectConfig};
+
+/// How long the daemon gets to create its channel
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a daemon whose library doesn't record its PID gets to take over
+/// a channel left behind by an earlier run
+const STALE_GRACE: Duration = Duration::from_millis(500);
+
+/// How long the daemon gets to exit after SIGINT before it is killed
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// A program run from a directory of the project
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Step {
+    /// Directory relative to the project root
+    dir: &'static str,
+    /// Command on `PATH`, or a path relative to `dir` when it contains a `/`
+    program: String,
+    args: Vec<String>,
+}
+
+impl Step {
+    fn new(dir: &'static str, program: impl Into<String>, args: &[&str]) -> Self {
+        Self { dir, program: program.into(), args: args.iter().map(|a| a.to_string()).collect() }
+    }
+
+    fn command(&self, root: &Path, lib_dir: &Path) -> Command {
+        let dir = root.join(self.dir);
+        // Relative program paths are resolved against the parent's cwd, not current_dir
+        let program = if self.program.contains('/') { dir.join(&self.program) } else { PathBuf::from(&self.program) };
+        let mut cmd = Command::new(program);
+        cmd.args(&self.args).current_dir(&dir);
+        // Binaries built without an rpath (Go, Zig) find the bundled library this way
+        let ld_path = match std::env::var_os("LD_LIBRARY_PATH") {
+            Some(old) if !old.is_empty() => {
+                let mut paths = vec![lib_dir.to_path_buf()];
+                paths.extend(std::env::split_paths(&old));
+                std::env::join_paths(paths).unwrap_or_default()
+            }
+            _ => lib_dir.as_os_str().to_os_string(),
+        };
+        cmd.env("LD_LIBRARY_PATH", ld_path);
+        cmd
+    }
+
+    fn describe(&self) -> String {
+        let mut line = self.program.clone();
+        for arg in &self.args {
+            line.push(' ');
+            line.push_str(arg);
+        }
+        if self.dir == "." { line } else { format!("{} (in {}/)", line, self.dir) }
+    }
+}
+
+/// How to build and start one language's daemon and client
+#[derive(Debug)]
+struct Plan {
+    build: Vec<Step>,
+    daemon: Step,
+    client: Step,
+}
+
+fn plan(config: &ProjectConfig, lang: Language) -> Plan {
+    let name = &config.name;
+    let daemon_bin = format!("./{}_daemon", name);
+    let client_bin = format!("./{}_client", name);
+    // Languages without a daemon of their own ship the C one in daemon/
+    let c_daemon = Step::new("daemon", daemon_bin.as_str(), &[]);
+    let make_c_daemon = Step::new("daemon", "make", &[]);
+    match lang {
+        Language::C | Language::Cpp => Plan {
+            build: vec![make_c_daemon, Step::new("client", "make", &[])],
+            daemon: c_daemon,
+            client: Step::new("client", client_bin, &[]),
+        },
+        Language::Rust => Plan {
+            build: vec![Step::new(".", "cargo", &["build", "--bins"])],
+            daemon: Step::new(".", "./target/debug/daemon", &[]),
+            client: Step::new(".", "./target/debug/client", &[]),
+        },
+        Language::Go | Language::Nim => Plan {
+            build: vec![Step::new(".", "make", &[])],
+            daemon: Step::new(".", daemon_bin, &[]),
+            client: Step::new(".", client_bin, &[]),
+        },
+        Language::Zig => Plan {
+            build: vec![Step::new(".", "zig", &["build"])],
+            daemon: Step::new(".", format!("./zig-out/bin/{}_daemon", name), &[]),
+            client: Step::new(".", format!("./zig-out/bin/{}_client", name), &[]),
+        },
+        Language::Python => Plan {
+            build: vec![make_c_daemon],
+            daemon: c_daemon,
+            client: Step::new(".", "python3", &["client.py"]),
+        },
+        Language::Node => Plan {
+            build: vec![make_c_daemon],
+            daemon: c_daemon,
+            client: Step::new(".", "node", &["index.js"]),
+        },
+        Language::CSharp => Plan {
+            build: vec![make_c_daemon, Step::new(".", "dotnet", &["build"])],
+            daemon: c_daemon,
+            client: Step::new(".", "dotnet", &["run", "--no-build"]),
+        },
+        Language::Java => Plan {
+            // run.sh compiles the client itself
+            build: vec![make_c_daemon],
+            daemon: c_daemon,
+            client: Step::new(".", "./run.sh", &[]),
+        },
+        Language::Swift => Plan {
+            build: vec![make_c_daemon, Step::new(".", "swift", &["build"])],
+            daemon: c_daemon,
+            client: Step::new(".", "swift", &["run", "--skip-build"]),
+        },
+        Language::Lua => Plan {
+            build: vec![make_c_daemon],
+            daemon: c_daemon,
+            client: Step::new(".", "luajit", &["client.lua"]),
+        },
+        Language::Flutter => {
+            let entry = format!("bin/{}.dart", config.names().snake);
+            let mut build = vec![make_c_daemon];
+            // A package that depends on the Flutter SDK resolves with flutter, not dart
+            if config.flutter_ui {
+                build.push(Step::new(".", "flutter", &["pub", "get"]));
+            }
+            build.push(Step::new(".", "dart", &["compile", "exe", entry.as_str(), "-o", "client"]));
+            Plan {
+                build,
+                daemon: c_daemon,
+                client: Step::new(".", "./client", &[]),
+            }
+        }
+    }
+}
+
+/// Clients that understand `--duration SECONDS`
+fn supports_duration(lang: Language) -> bool {
+    matches!(lang, Language::C | Language::Cpp | Language::Rust | Language::Python)
+}
+
+/// Build the project in `path`, then run its daemon and client
+///
+/// Returns the client's exit status. `duration` is passed to the client as
+/// `--duration` so it exits on its own.
+pub fn run(path: &str, duration: Option<u64>) -> Result<ExitStatus, String> {
+    let (config, lang) = project::load(path)?;
+    launch(&config, lang, duration, Stdio::inherit())
+}
+
+fn launch(config: &ProjectConfig, lang: Language, duration: Option<u64>, client_out: Stdio) -> Result<ExitStatus, String> {
+    let mut plan = plan(config, lang);
+    if let Some(seconds) = duration {
+        if !supports_duration(lang) {
+            return Err(format!("--duration is not supported by {} clients", lang.id()));
+        }
+        plan.client.args.extend(["--duration".to_string(), seconds.to_string()]);
+    }
+    let root = fs::canonicalize(&config.output_dir)
+        .map_err(|e| format!("cannot open {}: {}", config.output_dir, e))?;
+    let lib_dir = match config.targets.first() {
+        None => root.join("lib"),
+        // The Makefiles default to the first bundled target too
+        Some(triple) => root.join("lib").join(triple),
+    };
+    catch_interrupts();
+
+    for step in &plan.build {
+        println!("{} {}", style("🔨 Building").cyan(), step.describe());
+        build(step, &root, &lib_dir)?;
+    }
+
+    if let Some(owner) = live_channel(&config.channel) {
+        return Err(format!(
+            "channel \"{}\" is already served by PID {}; stop that daemon first",
+            config.channel, owner
+        ));
+    }
+
+    let stale = discover::list_channels().iter().any(|c| c.namespace == config.channel);
+    println!("{} {}", style("🚀 Starting daemon").cyan(), plan.daemon.describe());
+    let log_path = std::env::temp_dir().join(format!("venom-run-{}.log", config.channel));
+    let mut daemon = Daemon::start(&plan.daemon, &root, &lib_dir, &log_path)?;
+    daemon.wait_for_channel(&config.channel, stale)?;
+    println!("{} {}", style("📡 Channel ready:").green(), config.channel);
+    println!("{} {}", style("▶️  Starting client").cyan(), plan.client.describe());
+
+    let status = plan
+        .client
+        .command(&root, &lib_dir)
+        .stdout(client_out)
+        .status()
+        .map_err(|e| spawn_error(&plan.client, e));
+
+    println!();
+    println!("{}", style("🛑 Stopping daemon...").cyan());
+    daemon.stop();
+    println!("   Daemon output: {}", log_path.display());
+    status
+}
+
+/// Run a build step, returning its output verbatim when it fails
+fn build(step: &Step, root: &Path, lib_dir: &Path) -> Result<(), String> {
+    let output = step.command(root, lib_dir).output().map_err(|e| spawn_error(step, e))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    Err(format!(
+        "build failed ({}) in {}\n{}{}",
+        output.status,
+        step.describe(),
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    ))
+}
+
+fn spawn_error(step: &Step, e: std::io::Error) -> String {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        format!("cannot run `{}` ({}): not found; is it installed?", step.program, step.describe())
+    } else {
+        format!("cannot run `{}`: {}", step.program, e)
+    }
+}
+
+/// PID of the live daemon serving `channel`, if any
+fn live_channel(channel: &str) -> Option<u32> {
+    discover::list_channels()
+        .into_iter()
+        .find(|c| c.namespace == channel && c.owner_alive)
+        .map(|c| c.owner_pid)
+}
+
+/// Set by Ctrl+C; only checked while waiting for the daemon
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn on_interrupt(_: libc::c_int) {
+    INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+/// Survive Ctrl+C so the daemon is always stopped; unlike SIG_IGN, the
+/// handler is reset to the default by exec, so the client still gets it
+fn catch_interrupts() {
+    unsafe {
+        libc::signal(libc::SIGINT, on_interrupt as extern "C" fn(libc::c_int) as libc::sighandler_t);
+    }
+}
+
+/// Daemon running in the background; stopped when dropped
+struct Daemon {
+    child: Child,
+    log_path: PathBuf,
+}
+
+impl Daemon {
+    fn start(step: &Step, root: &Path, lib_dir: &Path, log_path: &Path) -> Result<Self, String> {
+        let log = File::create(log_path).map_err(|e| format!("cannot create {}: {}", log_path.display(), e))?;
+        let log_err = log.try_clone().map_err(|e| e.to_string())?;
+        let child = step
+            .command(root, lib_dir)
+            .stdin(Stdio::null())
+            .stdout(log)
+            .stderr(log_err)
+            // Own process group: Ctrl+C in the terminal is for the client
+            .process_group(0)
+            .spawn()
+            .map_err(|e| spawn_error(step, e))?;
+        Ok(Self { child, log_path: log_path.to_path_buf() })
+    }
+
+    /// Poll shared memory until the daemon's channel appears
+    ///
+    /// `stale` says a dead daemon left the channel behind, so its mere presence
+    /// proves nothing.
+    fn wait_for_channel(&mut self, channel: &str, stale: bool) -> Result<(), String> {
+        let started = Instant::now();
+        let pid = self.child.id();
+        loop {
+            let owner = discover::list_channels().into_iter().find(|c| c.namespace == channel).map(|c| c.owner_pid);
+            match owner {
+                Some(owner) if owner == pid => return Ok(()),
+                // Libraries that predate owner tracking leave the PID at 0
+                Some(0) if !stale || started.elapsed() >= STALE_GRACE => return Ok(()),
+                _ => {}
+            }
+            if let Ok(Some(status)) = self.child.try_wait() {
+                return Err(format!(
+                    "daemon exited ({}) before creating channel \"{}\":\n{}",
+                    status,
+                    channel,
+                    fs::read_to_string(&self.log_path).unwrap_or_default()
+                ));
+            }
+            if INTERRUPTED.load(Ordering::SeqCst) {
+                return Err("interrupted".to_string());
+            }
+            if started.elapsed() >= STARTUP_TIMEOUT {
+                return Err(format!(
+                    "channel \"{}\" did not appear within {}s (daemon output: {})",
+                    channel,
+                    STARTUP_TIMEOUT.as_secs(),
+                    self.log_path.display()
+                ));
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+    }
+
+    /// SIGINT the daemon's process group, then SIGKILL if it lingers
+    fn stop(mut self) {
+        self.terminate();
+    }
+
+    fn terminate(&mut self) {
+        if let Ok(Some(_)) = self.child.try_wait() {
+            return;
+        }
+        let group = -(self.child.id() as libc::pid_t);
+        unsafe { libc::kill(group, libc::SIGINT) };
+        let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+        while Instant::now() < deadline {
+            if let Ok(Some(_)) = self.child.try_wait() {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        unsafe { libc::kill(group, libc::SIGKILL) };
+        self.child.wait().ok();
+    }
+}
+
+impl Drop for Daemon {
+    fn drop(&mut self) {
+        self.terminate();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::Schema;
+    use crate::templates::{self, License, PythonBinding};
+
+    fn config(name: &str) -> ProjectConfig {
+        let dir = std::env::temp_dir().join(format!("venom-run-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let channel = format!("{}-{}", name, std::process::id());
+        ProjectConfig {
+            name: name.to_string(),
+            magic: templates::channel_magic(&channel),
+            channel,
+            data_size: 16 * 1024,
+            cmd_slots: 32,
+            max_clients: 16,
+            shm_mode: 0o664,
+            output_dir: dir.display().to_string(),
+            python_binding: PythonBinding::Ctypes,
+            go_nocgo: false,
+            flutter_ui: true,
+            python_async: false,
+            schema: Schema::demo(),
+            targets: Vec::new(),
+            clients: Vec::new(),
+            root: String::new(),
+            service: false,
+            license: License::None,
+            extras: true,
+            license_year: 2026,
+        }
+    }
+
+    fn generate(lang: Language, name: &str) -> ProjectConfig {
+        let config = config(name);
+        crate::generate_project(&config, lang);
+        config
+    }
+
+    /// Build and run the generated pair for two seconds; the daemon must be gone afterwards
+    fn run_briefly(lang: Language, name: &str) {
+        let config = generate(lang, name);
+        let status = launch(&config, lang, Some(2), Stdio::null());
+        let shm_left = Path::new("/dev/shm").join(format!("venom_{}", config.channel)).exists();
+        fs::remove_dir_all(&config.output_dir).ok();
+        fs::remove_file(std::env::temp_dir().join(format!("venom-run-{}.log", config.channel))).ok();
+
+        assert!(status.unwrap().success());
+        assert!(!shm_left, "daemon did not remove its channel");
+    }
+
+    #[test]
+    fn test_run_c_project() {
+        run_briefly(Language::C, "runc");
+    }
+
+    #[test]
+    fn test_run_rust_project() {
+        run_briefly(Language::Rust, "runrs");
+    }
+
+    /// SIGTERM the daemon mid-run: its channel must go, and the client must
+    /// notice rather than keep showing the last state
+    fn terminate_daemon(lang: Language, name: &str) {
+        let config = generate(lang, name);
+        let root = fs::canonicalize(&config.output_dir).unwrap();
+        let lib_dir = root.join("lib");
+        let plan = plan(&config, lang);
+        for step in &plan.build {
+            build(step, &root, &lib_dir).unwrap();
+        }
+        let log_path = std::env::temp_dir().join(format!("venom-run-{}.log", config.channel));
+        let mut daemon = Daemon::start(&plan.daemon, &root, &lib_dir, &log_path).unwrap();
+        daemon.wait_for_channel(&config.channel, false).unwrap();
+        let client = plan
+            .client
+            .command(&root, &lib_dir)
+            .args(["--duration", "10"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        std::thread::sleep(Duration::from_millis(500));
+
+        unsafe { libc::kill(daemon.child.id() as libc::pid_t, libc::SIGTERM) };
+        let started = Instant::now();
+        let exited = loop {
+            match daemon.child.try_wait() {
+                Ok(Some(status)) => break Some(status),
+                _ if started.elapsed() >= SHUTDOWN_TIMEOUT => break None,
+                _ => std::thread::sleep(Duration::from_millis(20)),
+            }
+        };
+        let shm_left = Path::new("/dev/shm").join(format!("venom_{}", config.channel)).exists();
+        let output = client.wait_with_output().unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        drop(daemon);
+        fs::remove_dir_all(&config.output_dir).ok();
+        fs::remove_file(&log_path).ok();
+
+        assert!(exited.is_some(), "daemon ignored SIGTERM");
+        assert!(!shm_left, "daemon did not remove its channel on SIGTERM");
+        assert!(stdout.contains("Daemon offline"), "{}", stdout);
+    }
+
+    #[test]
+    fn test_sigterm_stops_c_daemon() {
+        terminate_daemon(Language::C, "termc");
+    }
+
+    #[test]
+    fn test_sigterm_stops_rust_daemon() {
+        terminate_daemon(Language::Rust, "termrs");
+    }
+
+    /// Build `lang`'s pair, start the daemon and run the client with `--ping 3`
+    fn ping_round_trip(lang: Language, name: &str) {
+        let config = generate(lang, name);
+        let root = fs::canonicalize(&config.output_dir).unwrap();
+        let lib_dir = root.join("lib");
+        let plan = plan(&config, lang);
+        for step in &plan.build {
+            build(step, &root, &lib_dir).unwrap();
+        }
+        let log_path = std::env::temp_dir().join(format!("venom-run-{}.log", config.channel));
+        let mut daemon = Daemon::start(&plan.daemon, &root, &lib_dir, &log_path).unwrap();
+        daemon.wait_for_channel(&config.channel, false).unwrap();
+        let output = plan
+            .client
+            .command(&root, &lib_dir)
+            .args(["--ping", "3"])
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        drop(daemon);
+        fs::remove_dir_all(&config.output_dir).ok();
+        fs::remove_file(&log_path).ok();
+
+        assert!(output.status.success(), "{}", stdout);
+        assert!(stdout.contains("Round trip: 3/3 replies"), "{}", stdout);
+    }
+
+    #[test]
+    fn test_ping_c_project() {
+        ping_round_trip(Language::C, "pingc");
+    }
+
+    #[test]
+    fn test_ping_cpp_project() {
+        ping_round_trip(Language::Cpp, "pingcpp");
+    }
+
+    #[test]
+    fn test_ping_rust_project() {
+        ping_round_trip(Language::Rust, "pingrs");
+    }
+
+    #[test]
+    fn test_ping_python_project() {
+        ping_round_trip(Language::Python, "pingpy");
+    }
+
+    #[test]
+    fn test_build_errors_are_reported_verbatim() {
+        let config = generate(Language::C, "runbroken");
+        fs::write(Path::new(&config.output_dir).join("daemon/src/main.c"), "#error this daemon is broken\n").unwrap();
+        let err = launch(&config, Language::C, Some(1), Stdio::null()).err().unwrap();
+        fs::remove_dir_all(&config.output_dir).ok();
+
+        assert!(err.contains("build failed"), "{}", err);
+        assert!(err.contains("#error this daemon is broken"), "{}", err);
+    }
+
+    #[test]
+    fn test_duration_needs_client_support() {
+        let err = launch(&config("runlua"), Language::Lua, Some(1), Stdio::null()).err().unwrap();
+        assert!(err.contains("--duration is not supported by lua clients"), "{}", err);
+    }
+}
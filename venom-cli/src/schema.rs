@@ -0,0 +1,559 @@
+//! Protocol schema for generated projects
+//!
+//! The state struct a daemon publishes is described once, as a list of
+//! fields in a `venom.toml`:
+//!
+//! ```toml
+//! [[field]]
+//! name = "cpu_usage_percent"
+//! type = "f32"
+//!
+//! [[field]]
+//! name = "cpu_cores"
+//! type = "f32"
+//! len = 16
+//! ```
+//!
+//! Every template renders its C header, struct and decoders from the same
+//! [`Schema`], so the languages can't drift apart. Offsets are computed here
+//! and nowhere else. Each struct starts with an implicit `magic`/`version`
+//! header, and any padding natural alignment would add is inserted as an
+//! explicit `_padN` byte array, so the packed C layout, `#[repr(C)]` and
+//! every hand-rolled decoder agree byte for byte.
+
+use serde::Deserialize;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// Scalar type of a schema field
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    U8,
+    U16,
+    U32,
+    U64,
+    I8,
+    I16,
+    I32,
+    I64,
+    F32,
+    F64,
+}
+
+impl FieldType {
+    /// Size in bytes (also the alignment)
+    pub fn size(self) -> usize {
+        match self {
+            FieldType::U8 | FieldType::I8 => 1,
+            FieldType::U16 | FieldType::I16 => 2,
+            FieldType::U32 | FieldType::I32 | FieldType::F32 => 4,
+            FieldType::U64 | FieldType::I64 | FieldType::F64 => 8,
+        }
+    }
+
+    pub fn is_float(self) -> bool {
+        matches!(self, FieldType::F32 | FieldType::F64)
+    }
+
+    pub fn is_signed(self) -> bool {
+        matches!(self, FieldType::I8 | FieldType::I16 | FieldType::I32 | FieldType::I64)
+    }
+
+    /// Schema spelling, which is also the Rust and Zig type name
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FieldType::U8 => "u8",
+            FieldType::U16 => "u16",
+            FieldType::U32 => "u32",
+            FieldType::U64 => "u64",
+            FieldType::I8 => "i8",
+            FieldType::I16 => "i16",
+            FieldType::I32 => "i32",
+            FieldType::I64 => "i64",
+            FieldType::F32 => "f32",
+            FieldType::F64 => "f64",
+        }
+    }
+
+    /// C type name (`<stdint.h>`)
+    pub fn c(self) -> &'static str {
+        match self {
+            FieldType::U8 => "uint8_t",
+            FieldType::U16 => "uint16_t",
+            FieldType::U32 => "uint32_t",
+            FieldType::U64 => "uint64_t",
+            FieldType::I8 => "int8_t",
+            FieldType::I16 => "int16_t",
+            FieldType::I32 => "int32_t",
+            FieldType::I64 => "int64_t",
+            FieldType::F32 => "float",
+            FieldType::F64 => "double",
+        }
+    }
+}
+
+/// One field of the state struct, with its computed offset
+#[derive(Clone, Debug, PartialEq)]
+pub struct Field {
+    pub name: String,
+    pub ty: FieldType,
+    /// `Some(n)` for a fixed-size array of `n` elements
+    pub len: Option<usize>,
+    pub offset: usize,
+    /// Alignment filler inserted by the layout pass (`_padN: [u8; n]`)
+    pub padding: bool,
+}
+
+impl Field {
+    /// Number of elements (1 for scalars)
+    pub fn count(&self) -> usize {
+        self.len.unwrap_or(1)
+    }
+
+    /// Total size in bytes
+    pub fn size(&self) -> usize {
+        self.ty.size() * self.count()
+    }
+}
+
+/// One element of the golden state (see [`Schema::golden`])
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Golden {
+    Int(i64),
+    Float(f64),
+}
+
+impl std::fmt::Display for Golden {
+    /// Literal spelling shared by every target language (`-1099511627777`, `3.25`)
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Golden::Int(v) => write!(f, "{}", v),
+            Golden::Float(v) => write!(f, "{:?}", v),
+        }
+    }
+}
+
+/// Golden value of element `n` (counted from 1 across the whole state) of type `ty`
+///
+/// Values are chosen to catch the usual decoder bugs: unsigned types use
+/// their top bit (or, for 64 bits, more than 32 bits), signed types are
+/// negative, and floats have a fractional part. All of them are exact in
+/// `f32`/`f64` and below 2^53, so every language can spell them as literals.
+fn golden_value(ty: FieldType, n: u64) -> Golden {
+    let n = n as i64;
+    match ty {
+        FieldType::U8 => Golden::Int(128 + n % 100),
+        FieldType::I8 => Golden::Int(-1 - n % 100),
+        FieldType::U16 => Golden::Int(40_000 + n % 20_000),
+        FieldType::I16 => Golden::Int(-1_000 - n % 20_000),
+        FieldType::U32 => Golden::Int(3_000_000_000 + n % 1_000_000),
+        FieldType::I32 => Golden::Int(-100_000 - n % 1_000_000),
+        FieldType::U64 => Golden::Int((1 << 40) + n),
+        FieldType::I64 => Golden::Int(-(1 << 40) - n),
+        FieldType::F32 => Golden::Float((n % 1_000_000) as f64 + 0.25),
+        FieldType::F64 => Golden::Float(-(n as f64) - 0.125),
+    }
+}
+
+/// Laid-out state struct
+#[derive(Clone, Debug, PartialEq)]
+pub struct Schema {
+    /// All fields in memory order, header and padding included
+    pub fields: Vec<Field>,
+    /// Total size in bytes
+    pub size: usize,
+}
+
+/// Fields of the system-monitor demo, after the magic/version header
+const DEMO_FIELDS: &[(&str, FieldType, Option<usize>)] = &[
+    ("cpu_usage_percent", FieldType::F32, None),
+    ("cpu_cores", FieldType::F32, Some(16)),
+    ("core_count", FieldType::U32, None),
+    ("memory_used_mb", FieldType::U32, None),
+    ("memory_total_mb", FieldType::U32, None),
+    ("uptime_seconds", FieldType::U64, None),
+    ("update_counter", FieldType::U64, None),
+    ("timestamp_ns", FieldType::U64, None),
+];
+
+/// Header every state struct starts with; clients check `magic` before decoding
+const HEADER_FIELDS: &[&str] = &["magic", "version"];
+
+/// Words that are keywords (or awkward builtins) in one of the target languages
+const RESERVED: &[&str] = &[
+    "and", "as", "async", "await", "bool", "break", "case", "catch", "char", "class", "const",
+    "continue", "def", "default", "defer", "do", "double", "else", "end", "enum", "export",
+    "extern", "false", "final", "float", "fn", "for", "from", "func", "function", "go", "if",
+    "impl", "import", "in", "int", "interface", "is", "len", "let", "local", "long", "loop",
+    "match", "mod", "mut", "new", "nil", "not", "null", "object", "or", "package", "proc",
+    "pub", "return", "self", "short", "static", "string", "struct", "super", "switch", "this",
+    "true", "type", "union", "unsigned", "use", "var", "void", "while", "with", "yield",
+];
+
+#[derive(Deserialize)]
+struct SchemaFile {
+    #[serde(rename = "field", default)]
+    fields: Vec<FieldDef>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FieldDef {
+    name: String,
+    #[serde(rename = "type")]
+    ty: FieldType,
+    len: Option<usize>,
+}
+
+impl Schema {
+    /// The CPU/RAM/uptime struct the templates have always generated (112 bytes)
+    pub fn demo() -> Self {
+        let defs = DEMO_FIELDS
+            .iter()
+            .map(|&(name, ty, len)| FieldDef { name: name.to_string(), ty, len })
+            .collect();
+        Self::build(defs).expect("demo schema is valid")
+    }
+
+    /// Load a schema file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+        Self::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Parse a schema from TOML text
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let file: SchemaFile = toml::from_str(text).map_err(|e| e.message().to_string())?;
+        Self::build(file.fields)
+    }
+
+    fn build(defs: Vec<FieldDef>) -> Result<Self, String> {
+        if defs.is_empty() {
+            return Err("schema has no [[field]] entries".to_string());
+        }
+
+        let mut fields = Vec::new();
+        let mut offset = 0;
+        let mut pads = 0;
+        let mut align = 1;
+
+        let header = HEADER_FIELDS
+            .iter()
+            .map(|&name| FieldDef { name: name.to_string(), ty: FieldType::U32, len: None });
+
+        for (i, def) in header.chain(defs).enumerate() {
+            if i >= HEADER_FIELDS.len() {
+                validate_field(&def, &fields)?;
+            }
+
+            let size = def.ty.size();
+            if offset % size != 0 {
+                let pad = size - offset % size;
+                fields.push(padding(&mut pads, offset, pad));
+                offset += pad;
+            }
+            align = align.max(size);
+
+            let field = Field { name: def.name, ty: def.ty, len: def.len, offset, padding: false };
+            offset += field.size();
+            fields.push(field);
+        }
+
+        if offset % align != 0 {
+            let pad = align - offset % align;
+            fields.push(padding(&mut pads, offset, pad));
+            offset += pad;
+        }
+
+        Ok(Self { fields, size: offset })
+    }
+
+    /// Fields that carry data (header included, padding skipped)
+    pub fn data_fields(&self) -> impl Iterator<Item = &Field> {
+        self.fields.iter().filter(|f| !f.padding)
+    }
+
+    /// Fields after the magic/version header (padding skipped)
+    pub fn user_fields(&self) -> impl Iterator<Item = &Field> {
+        self.data_fields().skip(HEADER_FIELDS.len())
+    }
+
+    pub fn field(&self, name: &str) -> Option<&Field> {
+        self.data_fields().find(|f| f.name == name)
+    }
+
+    /// Whether the demo daemon/client code can run against this schema
+    ///
+    /// True when every demo field is present with its original type and
+    /// length; extra fields are fine, they're decoded but left at zero.
+    pub fn has_demo_fields(&self) -> bool {
+        DEMO_FIELDS.iter().all(|&(name, ty, len)| {
+            self.field(name).is_some_and(|f| f.ty == ty && f.len == len)
+        })
+    }
+
+    /// Known value of every element of every data field, for the decode tests
+    /// of generated projects: `magic` and version 1 in the header, then
+    /// [`golden_value`]s. Decoding [`golden_bytes`](Self::golden_bytes) must
+    /// give back exactly these.
+    pub fn golden(&self, magic: u32) -> Vec<(&Field, Vec<Golden>)> {
+        let mut n = 0;
+        self.data_fields()
+            .map(|f| {
+                let values = match f.name.as_str() {
+                    "magic" => vec![Golden::Int(magic as i64)],
+                    "version" => vec![Golden::Int(1)],
+                    _ => (0..f.count())
+                        .map(|_| {
+                            n += 1;
+                            golden_value(f.ty, n)
+                        })
+                        .collect(),
+                };
+                (f, values)
+            })
+            .collect()
+    }
+
+    /// The state holding [`golden`](Self::golden), as the daemon would publish
+    /// it: little-endian, padding zeroed
+    pub fn golden_bytes(&self, magic: u32) -> Vec<u8> {
+        let mut out = vec![0u8; self.size];
+        for (f, values) in self.golden(magic) {
+            for (i, value) in values.into_iter().enumerate() {
+                let at = f.offset + i * f.ty.size();
+                let bytes = match (f.ty, value) {
+                    (FieldType::F32, Golden::Float(v)) => (v as f32).to_le_bytes().to_vec(),
+                    (FieldType::F64, Golden::Float(v)) => v.to_le_bytes().to_vec(),
+                    (_, Golden::Int(v)) => v.to_le_bytes()[..f.ty.size()].to_vec(),
+                    (_, Golden::Float(_)) => unreachable!("integer fields get integer values"),
+                };
+                out[at..at + bytes.len()].copy_from_slice(&bytes);
+            }
+        }
+        out
+    }
+
+    /// Render back to `venom.toml` form (header and padding are implicit)
+    pub fn to_toml(&self, name: &str) -> String {
+        let mut out = format!(
+            "# {} protocol schema\n\
+             #\n\
+             # Fields of the state struct, after the implicit magic/version header.\n\
+             # Types: u8 u16 u32 u64 i8 i16 i32 i64 f32 f64; `len` makes an array.\n\
+             # Regenerate with `venom init <name> --schema venom.toml --force ...` after editing.\n",
+            name
+        );
+        for f in self.user_fields() {
+            let _ = write!(out, "\n[[field]]\nname = \"{}\"\ntype = \"{}\"\n", f.name, f.ty.as_str());
+            if let Some(len) = f.len {
+                let _ = writeln!(out, "len = {}", len);
+            }
+        }
+        out
+    }
+
+    /// C member declarations, one per line with the given indent
+    ///
+    /// Array lengths are literals so tools like venom-watch can read them.
+    pub fn c_fields(&self, indent: &str) -> String {
+        let mut out = String::new();
+        for f in &self.fields {
+            let _ = match f.len {
+                Some(n) => writeln!(out, "{}{} {}[{}];", indent, f.ty.c(), f.name, n),
+                None => writeln!(out, "{}{} {};", indent, f.ty.c(), f.name),
+            };
+        }
+        out
+    }
+}
+
+fn padding(count: &mut usize, offset: usize, len: usize) -> Field {
+    let name = format!("_pad{}", count);
+    *count += 1;
+    Field { name, ty: FieldType::U8, len: Some(len), offset, padding: true }
+}
+
+fn validate_field(def: &FieldDef, seen: &[Field]) -> Result<(), String> {
+    let name = def.name.as_str();
+    let valid = name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if !valid {
+        return Err(format!(
+            "field name {:?} must be snake_case ASCII starting with a letter",
+            name
+        ));
+    }
+    if HEADER_FIELDS.contains(&name) {
+        return Err(format!("field name {:?} is reserved for the struct header", name));
+    }
+    if RESERVED.contains(&name) {
+        return Err(format!("field name {:?} is a keyword in one of the target languages", name));
+    }
+    if seen.iter().any(|f| f.name == name) {
+        return Err(format!("duplicate field {:?}", name));
+    }
+    if def.len == Some(0) {
+        return Err(format!("field {:?} has len = 0", name));
+    }
+    Ok(())
+}
+
+/// `lower_camel` spelling of a snake_case field name
+pub fn camel_case(name: &str) -> String {
+    let pascal = pascal_case(name);
+    let mut chars = pascal.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// `UpperCamel` spelling of a snake_case field name
+pub fn pascal_case(name: &str) -> String {
+    name.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_demo_layout_matches_legacy_struct() {
+        let schema = Schema::demo();
+        assert_eq!(schema.size, 112);
+        assert!(schema.fields.iter().all(|f| !f.padding));
+
+        let offsets: Vec<(&str, usize)> =
+            schema.fields.iter().map(|f| (f.name.as_str(), f.offset)).collect();
+        assert_eq!(
+            offsets,
+            [
+                ("magic", 0),
+                ("version", 4),
+                ("cpu_usage_percent", 8),
+                ("cpu_cores", 12),
+                ("core_count", 76),
+                ("memory_used_mb", 80),
+                ("memory_total_mb", 84),
+                ("uptime_seconds", 88),
+                ("update_counter", 96),
+                ("timestamp_ns", 104),
+            ]
+        );
+        assert!(schema.has_demo_fields());
+    }
+
+    #[test]
+    fn test_toml_round_trip() {
+        let demo = Schema::demo();
+        assert_eq!(Schema::parse(&demo.to_toml("demo")).unwrap(), demo);
+    }
+
+    #[test]
+    fn test_padding_is_explicit() {
+        let schema = Schema::parse(
+            r#"
+            [[field]]
+            name = "flag"
+            type = "u8"
+
+            [[field]]
+            name = "total"
+            type = "u64"
+
+            [[field]]
+            name = "level"
+            type = "i16"
+            "#,
+        )
+        .unwrap();
+
+        let layout: Vec<(&str, usize, usize)> =
+            schema.fields.iter().map(|f| (f.name.as_str(), f.offset, f.size())).collect();
+        assert_eq!(
+            layout,
+            [
+                ("magic", 0, 4),
+                ("version", 4, 4),
+                ("flag", 8, 1),
+                ("_pad0", 9, 7),
+                ("total", 16, 8),
+                ("level", 24, 2),
+                ("_pad1", 26, 6),
+            ]
+        );
+        assert_eq!(schema.size, 32);
+        assert!(!schema.has_demo_fields());
+    }
+
+    #[test]
+    fn test_golden_bytes_hold_golden_values() {
+        let schema = Schema::parse(
+            r#"
+            [[field]]
+            name = "flag"
+            type = "u8"
+
+            [[field]]
+            name = "total"
+            type = "i64"
+
+            [[field]]
+            name = "loads"
+            type = "f32"
+            len = 2
+            "#,
+        )
+        .unwrap();
+        let golden: Vec<(&str, Vec<Golden>)> =
+            schema.golden(0x1234).into_iter().map(|(f, v)| (f.name.as_str(), v)).collect();
+        assert_eq!(
+            golden,
+            [
+                ("magic", vec![Golden::Int(0x1234)]),
+                ("version", vec![Golden::Int(1)]),
+                ("flag", vec![Golden::Int(129)]),
+                ("total", vec![Golden::Int(-(1 << 40) - 2)]),
+                ("loads", vec![Golden::Float(3.25), Golden::Float(4.25)]),
+            ]
+        );
+
+        let bytes = schema.golden_bytes(0x1234);
+        assert_eq!(bytes.len(), schema.size);
+        assert_eq!(bytes[..9], [0x34, 0x12, 0, 0, 1, 0, 0, 0, 129]);
+        assert!(bytes[9..16].iter().all(|&b| b == 0), "padding is zeroed");
+        assert_eq!(i64::from_le_bytes(bytes[16..24].try_into().unwrap()), -(1 << 40) - 2);
+        assert_eq!(f32::from_le_bytes(bytes[28..32].try_into().unwrap()), 4.25);
+        assert_eq!(Golden::Float(4.25).to_string(), "4.25");
+        assert_eq!(Golden::Int(-9).to_string(), "-9");
+    }
+
+    #[test]
+    fn test_rejects_bad_fields() {
+        let parse = |name: &str, extra: &str| {
+            Schema::parse(&format!("[[field]]\nname = \"{}\"\ntype = \"u32\"\n{}", name, extra))
+        };
+        assert!(parse("magic", "").unwrap_err().contains("reserved"));
+        assert!(parse("type", "").unwrap_err().contains("keyword"));
+        assert!(parse("CamelCase", "").unwrap_err().contains("snake_case"));
+        assert!(parse("_hidden", "").unwrap_err().contains("snake_case"));
+        assert!(parse("zero", "len = 0").unwrap_err().contains("len = 0"));
+        assert!(Schema::parse("[[field]]\nname = \"x\"\ntype = \"u128\"\n").is_err());
+        assert!(Schema::parse("").unwrap_err().contains("no [[field]]"));
+
+        let dup = "[[field]]\nname = \"a\"\ntype = \"u8\"\n[[field]]\nname = \"a\"\ntype = \"u8\"\n";
+        assert!(Schema::parse(dup).unwrap_err().contains("duplicate"));
+    }
+}
@@ -1,13 +1,13 @@
 //! C Templates for VenomMemory projects
 
 use super::ProjectConfig;
+use crate::schema::{FieldType, Golden};
 
 pub fn generate(config: &ProjectConfig) {
     let base = &config.output_dir;
     
     crate::create_dir(&format!("{}/shared", base));
     crate::create_dir(&format!("{}/daemon/src", base));
-    crate::create_dir(&format!("{}/client/src", base));
     
     // Protocol header
     crate::write_file(&format!("{}/shared/protocol.h", base), &protocol_h(config));
@@ -17,40 +17,33 @@ pub fn generate(config: &ProjectConfig) {
     crate::write_file(&format!("{}/daemon/Makefile", base), &daemon_makefile(config));
     
     // Client
-    crate::write_file(&format!("{}/client/src/main.c", base), &client_main(config));
-    crate::write_file(&format!("{}/client/Makefile", base), &client_makefile(config));
+    generate_client(config);
     
     // README
     crate::write_file(&format!("{}/README.md", base), &readme(config));
 }
 
-fn upper_name(name: &str) -> String {
-    name.to_uppercase().replace("-", "_")
+/// Client files only (used by `venom add-client`)
+pub fn generate_client(config: &ProjectConfig) {
+    let base = &config.output_dir;
+    
+    crate::create_dir(&format!("{}/client/src", base));
+    crate::write_file_if_missing(&format!("{}/{}", base, config.at_root("shared/protocol.h")), &protocol_h(config));
+    crate::write_file(&format!("{}/client/src/main.c", base), &client_main(config));
+    crate::write_file(&format!("{}/client/src/test_state.c", base), &test_state_c(config));
+    crate::write_file(&format!("{}/client/Makefile", base), &client_makefile(config));
 }
 
-fn pascal_case(s: &str) -> String {
-    s.split(|c| c == '_' || c == '-')
-        .map(|word| {
-            let mut chars = word.chars();
-            match chars.next() {
-                None => String::new(),
-                Some(first) => first.to_uppercase().chain(chars).collect(),
-            }
-        })
-        .collect()
-}
 
-fn magic(channel: &str) -> u32 {
-    channel.bytes().fold(0x564E4Fu32, |acc, b| acc.wrapping_add(b as u32))
-}
 
 // ═══════════════════════════════════════════════════════════════════════════
 // Protocol Header
 // ═══════════════════════════════════════════════════════════════════════════
 
 fn protocol_h(config: &ProjectConfig) -> String {
-    let upper = upper_name(&config.name);
-    let pascal = pascal_case(&config.name);
+    let upper = config.names().upper;
+    let pascal = config.names().pascal;
+    let schema = &config.schema;
     
     format!(r#"#ifndef {upper}_PROTOCOL_H
 #define {upper}_PROTOCOL_H
@@ -67,62 +60,199 @@ fn protocol_h(config: &ProjectConfig) -> String {
 #define {upper}_DATA_SIZE {data_size}
 #define {upper}_CMD_SLOTS {cmd_slots}
 #define {upper}_MAX_CLIENTS {max_clients}
-#define {upper}_MAX_CORES 16
-
+#define {upper}_STATE_SIZE {state_size}
+{max_cores}
 // ═══════════════════════════════════════════════════════════════════════════
-// 📊 System Stats (Daemon writes, Clients read)
+// 📊 State (Daemon writes, Clients read) - generated from venom.toml
 // ═══════════════════════════════════════════════════════════════════════════
 
 typedef struct __attribute__((packed)) {{
-    uint32_t magic;
-    uint32_t version;
-    
-    float cpu_usage_percent;
-    float cpu_cores[{upper}_MAX_CORES];
-    uint32_t core_count;
-    
-    uint32_t memory_used_mb;
-    uint32_t memory_total_mb;
-    
-    uint64_t uptime_seconds;
-    uint64_t update_counter;
-    uint64_t timestamp_ns;
-}} {pascal}State;
+{fields}}} {pascal}State;
+
+_Static_assert(sizeof({pascal}State) == {upper}_STATE_SIZE, "{pascal}State size mismatch");
 
 // ═══════════════════════════════════════════════════════════════════════════
-// 📨 Commands
+// 📨 Commands (Clients send, Daemon applies)
 // ═══════════════════════════════════════════════════════════════════════════
 
-typedef enum {{
-    CMD_REFRESH = 1,
-    CMD_SET_INTERVAL,
-}} {pascal}CmdType;
-
-typedef struct __attribute__((packed)) {{
-    uint8_t cmd;
-    uint8_t _pad[3];
-    int32_t value;
-}} {pascal}Command;
-
+{commands}
 #endif // {upper}_PROTOCOL_H
 "#,
         upper = upper,
         pascal = pascal,
         channel = config.channel,
-        magic = magic(&config.channel),
+        magic = config.magic,
         data_size = config.data_size,
         cmd_slots = config.cmd_slots,
-        max_clients = config.max_clients
+        max_clients = config.max_clients,
+        state_size = schema.size,
+        max_cores = max_cores_define(config),
+        fields = schema.c_fields("    "),
+        commands = commands_h(config),
     )
 }
 
+/// Command definitions for protocol.h, and `{snake}_channel_name()`
+///
+/// Shared by every template whose daemon is written in C, so
+/// `handle_commands` in `command_handler_c` compiles against any of them.
+pub(super) fn commands_h(config: &ProjectConfig) -> String {
+    format!(r#"#include <stdio.h>  // snprintf in {snake}_channel_name
+
+#define {upper}_CHANNEL_NAME_MAX 256
+
+// The channel of one of several instances: "{channel}-SUFFIX", or
+// {upper}_CHANNEL_NAME itself for a NULL or empty suffix
+static inline const char* {snake}_channel_name(const char* suffix, char buf[{upper}_CHANNEL_NAME_MAX]) {{
+    if (!suffix || !*suffix) return {upper}_CHANNEL_NAME;
+    snprintf(buf, {upper}_CHANNEL_NAME_MAX, "%s-%s", {upper}_CHANNEL_NAME, suffix);
+    return buf;
+}}
+
+#define {upper}_INTERVAL_MS 100  // Default publish interval
+
+typedef enum {{
+    CMD_REFRESH = 1,     // Publish immediately
+    CMD_SET_INTERVAL,    // value: publish interval in ms (10-10000)
+    CMD_PING,            // value: sequence number, echoed back in a {pascal}Pong response
+}} {pascal}CmdType;
+
+// A typed command, as venom_shell_send_typed sends it: a tag, the bytes of
+// payload after the envelope, then the payload
+typedef struct __attribute__((packed)) {{
+    uint16_t cmd;        // A {pascal}CmdType
+    uint16_t len;        // sizeof(value)
+    int32_t value;
+}} {pascal}Command;
+
+#define {upper}_COMMAND(type, v) (({pascal}Command){{ .cmd = (type), .len = sizeof(int32_t), .value = (v) }})
+
+// Reply to a CMD_PING: the response to its client, and, for clients that
+// can't take responses, published right after the state until the next one
+typedef struct __attribute__((packed)) {{
+    uint32_t client_id;  // Client that sent the ping
+    uint32_t seq;        // Its value
+}} {pascal}Pong;
+"#, upper = config.names().upper, pascal = config.names().pascal, snake = config.names().snake, channel = config.channel)
+}
+
+/// `--channel-suffix SUFFIX` for the C daemons: publish on the channel of
+/// that instance instead of the default one
+///
+/// Defines `channel_from_args()`, which returns the channel to create;
+/// needs <string.h>.
+pub(super) fn channel_arg_c(config: &ProjectConfig) -> String {
+    format!(r#"static char g_channel_buf[{upper}_CHANNEL_NAME_MAX];
+
+/// --channel-suffix SUFFIX: run as one of several instances, on "{channel}-SUFFIX"
+static const char* channel_from_args(int argc, char** argv) {{
+    const char* suffix = NULL;
+    for (int i = 1; i + 1 < argc; i++) {{
+        if (strcmp(argv[i], "--channel-suffix") == 0) suffix = argv[++i];
+    }}
+    return {snake}_channel_name(suffix, g_channel_buf);
+}}
+"#, upper = config.names().upper, snake = config.names().snake, channel = config.channel)
+}
+
+/// Daemon-side command handling for a C daemon
+///
+/// Defines `g_interval_ms`, `now_ms()`, `publish()` and `handle_commands()`;
+/// the daemon polls `handle_commands` every 10 ms and publishes when it
+/// returns 1 or `g_interval_ms` has passed. Needs <stdio.h>, <string.h>,
+/// <time.h> and the `venom_daemon_write_data` and
+/// `venom_daemon_try_recv_command` declarations.
+pub(super) fn command_handler_c(config: &ProjectConfig) -> String {
+    format!(r#"static uint32_t g_interval_ms = {upper}_INTERVAL_MS;
+
+extern size_t venom_daemon_client_name(VenomDaemonHandle* handle, uint32_t client_id, char* buf, size_t max_len);
+extern bool venom_daemon_respond(VenomDaemonHandle* handle, uint32_t client_id, const uint8_t* data, size_t len);
+
+/// "3", or "3 (gui-frontend)" for a client that connected with a name
+static const char* client_label(VenomDaemonHandle* daemon, uint32_t client_id) {{
+    static char label[48];
+    char name[33];
+    if (venom_daemon_client_name(daemon, client_id, name, sizeof(name)) > 0)
+        snprintf(label, sizeof(label), "%u (%s)", client_id, name);
+    else
+        snprintf(label, sizeof(label), "%u", client_id);
+    return label;
+}}
+
+static uint64_t now_ms(void) {{
+    struct timespec ts;
+    clock_gettime(CLOCK_MONOTONIC, &ts);
+    return (uint64_t)ts.tv_sec * 1000 + (uint64_t)ts.tv_nsec / 1000000;
+}}
+
+static {pascal}Pong g_pong;
+static int g_pinged = 0;
+
+/// Write the state, followed by the reply to the last ping once there is one
+static void publish(VenomDaemonHandle* daemon, const {pascal}State* state) {{
+    uint8_t buf[sizeof({pascal}State) + sizeof({pascal}Pong)];
+    memcpy(buf, state, sizeof({pascal}State));
+    memcpy(buf + sizeof({pascal}State), &g_pong, sizeof(g_pong));
+    venom_daemon_write_data(daemon, buf, g_pinged ? sizeof(buf) : sizeof({pascal}State));
+}}
+
+/// Apply queued client commands; returns 1 if a client asked for a refresh
+static int handle_commands(VenomDaemonHandle* daemon) {{
+    uint8_t buf[64];
+    uint32_t client_id;
+    size_t len;
+    int refresh = 0;
+    while ((len = venom_daemon_try_recv_command(daemon, buf, sizeof(buf), &client_id)) > 0) {{
+        // Typed commands whose payload is a value; other lengths aren't ours
+        if (len < sizeof({pascal}Command)) continue;
+        {pascal}Command cmd;
+        memcpy(&cmd, buf, sizeof(cmd));
+        if (cmd.len != sizeof(cmd.value)) continue;
+        switch (cmd.cmd) {{
+        case CMD_REFRESH:
+            refresh = 1;
+            break;
+        case CMD_SET_INTERVAL:
+            g_interval_ms = cmd.value < 10 ? 10 : cmd.value > 10000 ? 10000 : (uint32_t)cmd.value;
+            printf("\n📥 Client %s: publishing every %u ms\n", client_label(daemon, client_id), g_interval_ms);
+            break;
+        case CMD_PING:
+            // Answered to the client, and by publishing now with the pong after the state
+            g_pong.client_id = client_id;
+            g_pong.seq = (uint32_t)cmd.value;
+            g_pinged = 1;
+            venom_daemon_respond(daemon, client_id, (const uint8_t*)&g_pong, sizeof(g_pong));
+            refresh = 1;
+            break;
+        default:
+            printf("\n📥 Client %s: unknown command %u\n", client_label(daemon, client_id), cmd.cmd);
+        }}
+    }}
+    return refresh;
+}}
+"#, upper = config.names().upper, pascal = config.names().pascal)
+}
+
+/// `MAX_CORES` for the demo daemon, sized from the schema's `cpu_cores`
+pub(super) fn max_cores_define(config: &ProjectConfig) -> String {
+    match config.schema.field("cpu_cores") {
+        Some(f) if config.schema.has_demo_fields() => {
+            format!("#define {}_MAX_CORES {}\n", config.names().upper, f.count())
+        }
+        _ => String::new(),
+    }
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // Daemon
 // ═══════════════════════════════════════════════════════════════════════════
 
 fn daemon_main(config: &ProjectConfig) -> String {
-    let upper = upper_name(&config.name);
-    let pascal = pascal_case(&config.name);
+    if !config.schema.has_demo_fields() {
+        return generic_daemon_c(config, "../shared/protocol.h");
+    }
+    let upper = config.names().upper;
+    let pascal = config.names().pascal;
     
     format!(r#"/**
  * {name} System Monitor Daemon - VenomMemory IPC
@@ -134,13 +264,14 @@ fn daemon_main(config: &ProjectConfig) -> String {
 #include <string.h>
 #include <signal.h>
 #include <unistd.h>
+#include <fcntl.h>
 #include <time.h>
 #include "../shared/protocol.h"
 
 // VenomMemory bindings
 typedef struct VenomDaemonHandle VenomDaemonHandle;
-typedef struct {{ size_t data_size; size_t cmd_slots; size_t max_clients; }} VenomConfig;
-extern VenomDaemonHandle* venom_daemon_create(const char* name, VenomConfig config);
+typedef struct {{ size_t struct_size; size_t data_size; size_t cmd_slots; size_t max_clients; size_t cmd_payload_size; size_t cmd_full_policy; size_t shm_mode; }} VenomConfigEx;
+extern VenomDaemonHandle* venom_daemon_create_ex(const char* name, const VenomConfigEx* config);
 extern void venom_daemon_destroy(VenomDaemonHandle* handle);
 extern void venom_daemon_write_data(VenomDaemonHandle* handle, const uint8_t* data, size_t len);
 extern size_t venom_daemon_try_recv_command(VenomDaemonHandle* handle, uint8_t* buf, size_t max_len, uint32_t* out_client_id);
@@ -154,6 +285,9 @@ static uint64_t prev_idle[{upper}_MAX_CORES + 1] = {{0}};
 
 static void signal_handler(int sig) {{ (void)sig; g_running = 0; }}
 
+{commands}
+{channel_arg}
+{daemonize}
 static void read_cpu_stats(void) {{
     FILE* f = fopen("/proc/stat", "r");
     if (!f) return;
@@ -211,50 +345,278 @@ static void update_stats(void) {{
     struct timespec ts;
     clock_gettime(CLOCK_MONOTONIC, &ts);
     g_state.timestamp_ns = (uint64_t)ts.tv_sec * 1000000000ULL + ts.tv_nsec;
-    venom_daemon_write_data(g_daemon, (const uint8_t*)&g_state, sizeof(g_state));
+    publish(g_daemon, &g_state);
 }}
 
-int main(void) {{
+int main(int argc, char** argv) {{
+    const char* channel = channel_from_args(argc, argv);
+    daemonize_from_args(argc, argv);
     printf("🖥️  {name} System Monitor (VenomMemory)\n");
     printf("═══════════════════════════════════════════════════════════════\n");
     signal(SIGINT, signal_handler);
     signal(SIGTERM, signal_handler);
     
-    VenomConfig config = {{ .data_size = {upper}_DATA_SIZE, .cmd_slots = {upper}_CMD_SLOTS, .max_clients = {upper}_MAX_CLIENTS }};
-    g_daemon = venom_daemon_create({upper}_CHANNEL_NAME, config);
+    VenomConfigEx config = {{ .struct_size = sizeof(VenomConfigEx), .data_size = {upper}_DATA_SIZE, .cmd_slots = {upper}_CMD_SLOTS, .max_clients = {upper}_MAX_CLIENTS, .cmd_payload_size = sizeof({pascal}Command), .shm_mode = 0{shm_mode:o} }};
+    g_daemon = venom_daemon_create_ex(channel, &config);
     if (!g_daemon) {{ printf("❌ Failed to create channel\n"); return 1; }}
     
-    printf("✅ Channel: %s | State: %zu bytes\n", {upper}_CHANNEL_NAME, sizeof({pascal}State));
+    printf("✅ Channel: %s | State: %zu bytes\n", channel, sizeof({pascal}State));
     update_stats();
     printf("🔍 Detected %u CPU cores\n🚀 Publishing... (Ctrl+C to stop)\n\n", g_state.core_count);
     
+    uint64_t next_publish = 0;
     while (g_running) {{
-        uint8_t cmd_buf[64];
-        uint32_t client_id;
-        while (venom_daemon_try_recv_command(g_daemon, cmd_buf, sizeof(cmd_buf), &client_id) > 0) {{
-            printf("📥 Command from client %u\n", client_id);
+        int refresh = handle_commands(g_daemon);
+        if (refresh || now_ms() >= next_publish) {{
+            update_stats();
+            if (!g_daemonized) {{
+                printf("\r🖥️  CPU: %5.1f%% | RAM: %u/%u MB | Uptime: %luh%lum | #%lu | %u ms   ",
+                    g_state.cpu_usage_percent, g_state.memory_used_mb, g_state.memory_total_mb,
+                    (unsigned long)(g_state.uptime_seconds / 3600), (unsigned long)((g_state.uptime_seconds % 3600) / 60),
+                    (unsigned long)g_state.update_counter, g_interval_ms);
+                fflush(stdout);
+            }}
+            next_publish = now_ms() + g_interval_ms;
         }}
-        update_stats();
-        printf("\r🖥️  CPU: %5.1f%% | RAM: %u/%u MB | Uptime: %luh%lum | #%lu   ",
-            g_state.cpu_usage_percent, g_state.memory_used_mb, g_state.memory_total_mb,
-            (unsigned long)(g_state.uptime_seconds / 3600), (unsigned long)((g_state.uptime_seconds % 3600) / 60),
-            (unsigned long)g_state.update_counter);
-        fflush(stdout);
-        usleep(100000);
+        usleep(10000);
     }}
     venom_daemon_destroy(g_daemon);
     printf("\n\n👋 Goodbye!\n");
     return 0;
 }}
-"#, name = config.name, upper = upper, pascal = pascal)
+"#, name = config.name, upper = upper, pascal = pascal, shm_mode = config.shm_mode, commands = command_handler_c(config), channel_arg = channel_arg_c(config), daemonize = daemonize_c(config))
+}
+
+/// Daemon for a custom schema: publishes the header and leaves the fields to the user
+///
+/// Shared by every template whose daemon is written in C; `header` is the
+/// include path of that template's protocol.h.
+pub(super) fn generic_daemon_c(config: &ProjectConfig, header: &str) -> String {
+    let upper = config.names().upper;
+    let pascal = config.names().pascal;
+    
+    format!(r#"/**
+ * {name} Daemon - VenomMemory IPC
+ * Publishes {pascal}State (see venom.toml) to shared memory.
+ */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <stdint.h>
+#include <string.h>
+#include <signal.h>
+#include <unistd.h>
+#include <fcntl.h>
+#include <time.h>
+#include "{header}"
+
+// VenomMemory bindings
+typedef struct VenomDaemonHandle VenomDaemonHandle;
+typedef struct {{ size_t struct_size; size_t data_size; size_t cmd_slots; size_t max_clients; size_t cmd_payload_size; size_t cmd_full_policy; size_t shm_mode; }} VenomConfigEx;
+extern VenomDaemonHandle* venom_daemon_create_ex(const char* name, const VenomConfigEx* config);
+extern void venom_daemon_destroy(VenomDaemonHandle* handle);
+extern void venom_daemon_write_data(VenomDaemonHandle* handle, const uint8_t* data, size_t len);
+extern size_t venom_daemon_try_recv_command(VenomDaemonHandle* handle, uint8_t* buf, size_t max_len, uint32_t* out_client_id);
+
+static volatile int g_running = 1;
+
+static void signal_handler(int sig) {{ (void)sig; g_running = 0; }}
+
+{commands}
+{channel_arg}
+{daemonize}
+int main(int argc, char** argv) {{
+    const char* channel = channel_from_args(argc, argv);
+    daemonize_from_args(argc, argv);
+    printf("🚀 {name} Daemon (VenomMemory)\n");
+    printf("═══════════════════════════════════════════════════════════════\n");
+    signal(SIGINT, signal_handler);
+    signal(SIGTERM, signal_handler);
+    
+    VenomConfigEx config = {{ .struct_size = sizeof(VenomConfigEx), .data_size = {data_size}, .cmd_slots = {cmd_slots}, .max_clients = {max_clients}, .cmd_payload_size = sizeof({pascal}Command), .shm_mode = 0{shm_mode:o} }};
+    VenomDaemonHandle* daemon = venom_daemon_create_ex(channel, &config);
+    if (!daemon) {{ printf("❌ Failed to create channel\n"); return 1; }}
+    
+    printf("✅ Channel: %s | State: %zu bytes\n🚀 Publishing... (Ctrl+C to stop)\n\n", channel, sizeof({pascal}State));
+    
+    {pascal}State state = {{0}};
+    state.magic = {upper}_MAGIC;
+    state.version = 1;
+    unsigned long updates = 0;
+    
+    uint64_t next_publish = 0;
+    while (g_running) {{
+        int refresh = handle_commands(daemon);
+        if (refresh || now_ms() >= next_publish) {{
+            // TODO: fill in the fields of {pascal}State
+            
+            publish(daemon, &state);
+            ++updates;
+            if (!g_daemonized) {{
+                printf("\r📤 Published update #%lu (every %u ms)   ", updates, g_interval_ms);
+                fflush(stdout);
+            }}
+            next_publish = now_ms() + g_interval_ms;
+        }}
+        usleep(10000);
+    }}
+    venom_daemon_destroy(daemon);
+    printf("\n\n👋 Goodbye!\n");
+    return 0;
+}}
+"#,
+        shm_mode = config.shm_mode,
+        name = config.name,
+        upper = upper,
+        pascal = pascal,
+        header = header,
+        data_size = config.data_size,
+        cmd_slots = config.cmd_slots,
+        max_clients = config.max_clients,
+        commands = command_handler_c(config),
+        channel_arg = channel_arg_c(config),
+        daemonize = daemonize_c(config),
+    )
+}
+
+/// `--daemonize` for the C daemons: fork into the background, write the
+/// child's pid to `--pidfile` and send stdout/stderr to `--logfile`
+///
+/// Defines `g_daemonized` and `daemonize_from_args()`; needs <stdlib.h>,
+/// <fcntl.h> and <unistd.h>.
+fn daemonize_c(config: &ProjectConfig) -> String {
+    format!(r#"static int g_daemonized = 0;
+static const char* g_pidfile = NULL;
+
+static void remove_pidfile(void) {{ unlink(g_pidfile); }}
+
+/// --daemonize [--pidfile PATH] [--logfile PATH]: detach from the terminal;
+/// the status line is left out and everything else goes to the logfile
+static void daemonize_from_args(int argc, char** argv) {{
+    const char* pidfile = "{name}_daemon.pid";
+    const char* logfile = "{name}_daemon.log";
+    int detach = 0;
+    for (int i = 1; i < argc; i++) {{
+        if (strcmp(argv[i], "--daemonize") == 0) detach = 1;
+        else if (strcmp(argv[i], "--pidfile") == 0 && i + 1 < argc) pidfile = argv[++i];
+        else if (strcmp(argv[i], "--logfile") == 0 && i + 1 < argc) logfile = argv[++i];
+    }}
+    if (!detach) return;
+    
+    int log = open(logfile, O_WRONLY | O_CREAT | O_APPEND, 0644);
+    if (log < 0) {{ perror(logfile); exit(1); }}
+    fflush(stdout);
+    pid_t pid = fork();
+    if (pid < 0) {{ perror("fork"); exit(1); }}
+    if (pid > 0) {{
+        // Written before the parent exits, so a supervisor reading it finds the child
+        FILE* f = fopen(pidfile, "w");
+        if (!f) {{ perror(pidfile); kill(pid, SIGTERM); exit(1); }}
+        fprintf(f, "%d\n", (int)pid);
+        fclose(f);
+        exit(0);
+    }}
+    
+    setsid();
+    int null = open("/dev/null", O_RDONLY);
+    if (null >= 0) {{ dup2(null, STDIN_FILENO); close(null); }}
+    dup2(log, STDOUT_FILENO);
+    dup2(log, STDERR_FILENO);
+    close(log);
+    setvbuf(stdout, NULL, _IOLBF, 0);
+    g_daemonized = 1;
+    g_pidfile = pidfile;
+    atexit(remove_pidfile);
+}}
+"#, name = config.name)
+}
+
+/// Client-side keys for a C client: `+`/`-` halve or double the daemon's
+/// publish interval, `r` asks for an immediate refresh
+///
+/// Defines `keys_begin()`, `keys_end()`, `handle_keys()` and `run_ping()`
+/// (`--ping`); needs <float.h>, <termios.h>, <unistd.h>, `g_running` and
+/// the shell declarations of the C clients.
+pub(super) fn command_keys_c(config: &ProjectConfig) -> String {
+    format!(r#"static uint32_t g_interval_ms = {upper}_INTERVAL_MS;
+static struct termios g_saved_tty;
+static int g_raw_tty = 0;
+
+/// Read single keys without Enter or echo (Ctrl+C still works)
+static void keys_begin(void) {{
+    if (!isatty(STDIN_FILENO) || tcgetattr(STDIN_FILENO, &g_saved_tty) != 0) return;
+    struct termios raw = g_saved_tty;
+    raw.c_lflag &= ~(ICANON | ECHO);
+    raw.c_cc[VMIN] = 0;
+    raw.c_cc[VTIME] = 0;
+    tcsetattr(STDIN_FILENO, TCSANOW, &raw);
+    g_raw_tty = 1;
+}}
+
+static void keys_end(void) {{
+    if (g_raw_tty) tcsetattr(STDIN_FILENO, TCSANOW, &g_saved_tty);
+}}
+
+static void send_command(VenomShellHandle* shell, {pascal}CmdType type, int32_t value) {{
+    venom_shell_send_typed(shell, (uint16_t)type, (const uint8_t*)&value, sizeof(value));
+}}
+
+static void handle_keys(VenomShellHandle* shell) {{
+    unsigned char key;
+    while (g_raw_tty && read(STDIN_FILENO, &key, 1) == 1) {{
+        if (key == '+' || key == '=') {{
+            g_interval_ms = g_interval_ms / 2 < 10 ? 10 : g_interval_ms / 2;
+            send_command(shell, CMD_SET_INTERVAL, (int32_t)g_interval_ms);
+        }} else if (key == '-') {{
+            g_interval_ms = g_interval_ms * 2 > 10000 ? 10000 : g_interval_ms * 2;
+            send_command(shell, CMD_SET_INTERVAL, (int32_t)g_interval_ms);
+        }} else if (key == 'r') {{
+            send_command(shell, CMD_REFRESH, 0);
+        }}
+    }}
+}}
+
+/// --ping COUNT: time COUNT round trips through the daemon; 0 if any came back
+///
+/// Each CMD_PING goes out with venom_shell_request, which waits up to a
+/// second for the {pascal}Pong the daemon sends this client alone.
+static int run_ping(VenomShellHandle* shell, int count) {{
+    uint32_t seq = (uint32_t)venom_clock_ns();
+    double rtt_min = DBL_MAX, rtt_max = 0.0, rtt_sum = 0.0;
+    int replies = 0;
+    for (int i = 1; i <= count && g_running; i++) {{
+        seq++;
+        {pascal}Command cmd = {upper}_COMMAND(CMD_PING, (int32_t)seq);
+        {pascal}Pong pong;
+        uint64_t sent_ns = venom_clock_ns();
+        ptrdiff_t len = venom_shell_request(shell, (const uint8_t*)&cmd, sizeof(cmd), (uint8_t*)&pong, sizeof(pong), 1000);
+        double rtt_us = (venom_clock_ns() - sent_ns) / 1000.0;
+        // A late pong to an earlier ping that timed out doesn't count
+        if (len != (ptrdiff_t)sizeof(pong) || pong.seq != seq) {{
+            printf("⚠️  No reply to ping #%d\n", i);
+            continue;
+        }}
+        printf("🏓 Pong #%d: %.1f µs\n", i, rtt_us);
+        if (rtt_us < rtt_min) rtt_min = rtt_us;
+        if (rtt_us > rtt_max) rtt_max = rtt_us;
+        rtt_sum += rtt_us;
+        replies++;
+        usleep(100000);
+    }}
+    if (replies == 0) return 1;
+    printf("📊 Round trip: %d/%d replies, min %.1f µs, avg %.1f µs, max %.1f µs\n",
+        replies, count, rtt_min, rtt_sum / replies, rtt_max);
+    return 0;
+}}
+"#, upper = config.names().upper, pascal = config.names().pascal)
 }
 
 fn daemon_makefile(config: &ProjectConfig) -> String {
     format!(r#"# {name} Daemon Makefile
 
-CC = gcc
-CFLAGS = -Wall -Wextra -O2 -I../shared
-LDFLAGS = -L../lib -lvenom_memory -Wl,-rpath,'$$ORIGIN/../lib'
+{target_var}CC = gcc
+CFLAGS = -Wall -Wextra -O2 -g -I../shared
+LDFLAGS = -L{lib} -lvenom_memory -Wl,-rpath,'$$ORIGIN/{lib}'
 
 TARGET = {name}_daemon
 SOURCES = src/main.c
@@ -272,8 +634,12 @@ clean:
 	@rm -f $(TARGET)
 
 run: $(TARGET)
-	@./$(TARGET)
-"#, name = config.name)
+	@./$(TARGET) $(ARGS)
+"#,
+        name = config.name,
+        target_var = super::make_target_var(config),
+        lib = super::make_lib_dir(config, "../lib"),
+    )
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -281,8 +647,11 @@ run: $(TARGET)
 // ═══════════════════════════════════════════════════════════════════════════
 
 fn client_main(config: &ProjectConfig) -> String {
-    let upper = upper_name(&config.name);
-    let pascal = pascal_case(&config.name);
+    if !config.schema.has_demo_fields() {
+        return generic_client(config);
+    }
+    let upper = config.names().upper;
+    let pascal = config.names().pascal;
     
     format!(r#"/**
  * {name} Status Bar - VenomMemory IPC Client
@@ -292,22 +661,31 @@ fn client_main(config: &ProjectConfig) -> String {
 
 #include <stdio.h>
 #include <stdlib.h>
+#include <stddef.h>
 #include <string.h>
 #include <unistd.h>
 #include <signal.h>
 #include <time.h>
 #include <float.h>
+#include <termios.h>
 #include "../shared/protocol.h"
 
 typedef struct VenomShellHandle VenomShellHandle;
-extern VenomShellHandle* venom_shell_connect(const char* name);
+extern VenomShellHandle* venom_shell_connect_named(const char* name, const char* client_name);
 extern void venom_shell_destroy(VenomShellHandle* handle);
-extern size_t venom_shell_read_data(VenomShellHandle* handle, uint8_t* buf, size_t max_len);
+extern size_t venom_shell_read_data_stamped(VenomShellHandle* handle, uint8_t* buf, size_t max_len, uint64_t* out_timestamp_ns);
+extern size_t venom_channel_data_capacity(VenomShellHandle* handle);
+extern uint64_t venom_clock_ns(void);
+extern const char* venom_version(void);
 extern uint32_t venom_shell_id(VenomShellHandle* handle);
+extern bool venom_shell_daemon_alive(VenomShellHandle* handle);
+extern bool venom_shell_send_typed(VenomShellHandle* handle, uint16_t tag, const uint8_t* payload, size_t len);
+extern ptrdiff_t venom_shell_request(VenomShellHandle* handle, const uint8_t* cmd, size_t cmd_len, uint8_t* resp_buf, size_t resp_max, uint32_t timeout_ms);
 
 static VenomShellHandle* g_shell = NULL;
 static volatile int g_running = 1;
 
+{keys}
 // Latency tracking
 static double g_latency_min = DBL_MAX;
 static double g_latency_max = 0.0;
@@ -335,33 +713,66 @@ static void print_bar(float pct, int w) {{
     printf("]");
 }}
 
-int main(void) {{
+int main(int argc, char** argv) {{
     signal(SIGINT, signal_handler);
     signal(SIGTERM, signal_handler);
+    signal(SIGALRM, signal_handler);
+    
+    // --duration SECONDS: exit on our own (for scripted runs)
+    // --ping COUNT: measure round trips through the daemon instead
+    // --channel-suffix SUFFIX: connect to that instance of the daemon
+    int ping = 0;
+    const char* suffix = NULL;
+    for (int i = 1; i + 1 < argc; i++) {{
+        if (strcmp(argv[i], "--duration") == 0) alarm((unsigned)atoi(argv[i + 1]));
+        else if (strcmp(argv[i], "--ping") == 0) ping = atoi(argv[i + 1]);
+        else if (strcmp(argv[i], "--channel-suffix") == 0) suffix = argv[i + 1];
+    }}
+    char channel_buf[{upper}_CHANNEL_NAME_MAX];
+    const char* channel = {snake}_channel_name(suffix, channel_buf);
     
     printf("╔═══════════════════════════════════════════════════════════════╗\n");
     printf("║   🖥️  {name} Status Bar (C)                                   ║\n");
     printf("╚═══════════════════════════════════════════════════════════════╝\n\n");
     
-    g_shell = venom_shell_connect({upper}_CHANNEL_NAME);
+    g_shell = venom_shell_connect_named(channel, "c-client");
     if (!g_shell) {{
         printf("❌ Failed to connect! Run the daemon first:\n   cd ../daemon && make run\n");
         return 1;
     }}
-    printf("✅ Connected! ID: %u\n📊 Reading stats... (Ctrl+C to exit)\n\n", venom_shell_id(g_shell));
+    printf("✅ Connected! ID: %u (libvenom_memory %s)\n", venom_shell_id(g_shell), venom_version());
+    if (ping > 0) {{
+        int status = run_ping(g_shell, ping);
+        venom_shell_destroy(g_shell);
+        return status;
+    }}
+    printf("📊 Reading stats... (Ctrl+C to exit)\n\n");
     sleep(1);
+    keys_begin();
     
-    uint8_t* buf = malloc(sizeof({pascal}State) + 256);
+    // Room for the longest payload the daemon can write
+    size_t buf_len = venom_channel_data_capacity(g_shell);
+    uint8_t* buf = malloc(buf_len);
     int frame = 0;
     
     while (g_running) {{
+        handle_keys(g_shell);
+        // Reads would keep returning the last state it wrote
+        if (!venom_shell_daemon_alive(g_shell)) {{
+            printf("\n❌ Daemon offline! Restart it:\n   cd ../daemon && make run\n");
+            break;
+        }}
+        
         // ═══════════════════════════════════════════════════════════════════
         // 📊 BENCHMARK: Measure read latency
         // ═══════════════════════════════════════════════════════════════════
+        uint64_t written_ns = 0;
         double t_start = get_time_us();
-        size_t len = venom_shell_read_data(g_shell, buf, sizeof({pascal}State) + 256);
+        size_t len = venom_shell_read_data_stamped(g_shell, buf, buf_len, &written_ns);
         double t_end = get_time_us();
         double latency_us = t_end - t_start;
+        // How long ago the daemon wrote what we read, in the library's clock
+        double age_us = written_ns ? (venom_clock_ns() - written_ns) / 1000.0 : 0.0;
         
         // Update stats
         if (latency_us < g_latency_min) g_latency_min = latency_us;
@@ -396,11 +807,14 @@ int main(void) {{
             printf("╠═══════════════════════════════════════════════════════════════╣\n");
             printf("║  📊 \033[96mRead Latency:\033[0m %.2f µs (min: %.2f, max: %.2f, avg: %.2f)  ║\n",
                 latency_us, g_latency_min, g_latency_max, avg_us);
+            printf("║  📡 \033[96mDaemon→client:\033[0m %.2f µs since the write                 ║\n", age_us);
             printf("╚═══════════════════════════════════════════════════════════════╝\n");
-            printf("  Cores: %u | Updates: %lu | Ctrl+C to exit\n", s->core_count, (unsigned long)s->update_counter);
+            printf("  Cores: %u | Updates: %lu | Interval: %u ms\n", s->core_count, (unsigned long)s->update_counter, g_interval_ms);
+            printf("  +/- faster/slower | r refresh | Ctrl+C to exit\n");
         }}
         usleep(100000);
     }}
+    keys_end();
     
     // Print final stats
     printf("\n\n📊 \033[96mFinal Latency Stats (C):\033[0m\n");
@@ -414,20 +828,292 @@ int main(void) {{
     printf("\n👋 Goodbye!\n");
     return 0;
 }}
-"#, name = config.name, upper = upper, pascal = pascal)
+"#, name = config.name, upper = upper, pascal = pascal, snake = config.names().snake, keys = command_keys_c(config))
+}
+
+/// Client for a custom schema: prints every field of the state
+fn generic_client(config: &ProjectConfig) -> String {
+    let upper = config.names().upper;
+    let pascal = config.names().pascal;
+    
+    let mut fields = String::new();
+    for f in config.schema.user_fields() {
+        let (fmt, cast) = printf_spec(f.ty);
+        match f.len {
+            Some(n) => fields.push_str(&format!(
+                "            printf(\"  %-24s\", \"{name}\");\n            for (int i = 0; i < {n}; i++) printf(\" {fmt}\", ({cast})s->{name}[i]);\n            printf(\"\\n\");\n",
+                name = f.name, n = n, fmt = fmt, cast = cast
+            )),
+            None => fields.push_str(&format!(
+                "            printf(\"  %-24s {fmt}\\n\", \"{name}\", ({cast})s->{name});\n",
+                name = f.name, fmt = fmt, cast = cast
+            )),
+        }
+    }
+    
+    format!(r#"/**
+ * {name} Client - VenomMemory IPC
+ * Prints every field of {pascal}State (see venom.toml).
+ * Includes read latency benchmarking.
+ */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <stddef.h>
+#include <string.h>
+#include <unistd.h>
+#include <signal.h>
+#include <time.h>
+#include <float.h>
+#include <termios.h>
+#include "../shared/protocol.h"
+
+typedef struct VenomShellHandle VenomShellHandle;
+extern VenomShellHandle* venom_shell_connect_named(const char* name, const char* client_name);
+extern void venom_shell_destroy(VenomShellHandle* handle);
+extern size_t venom_shell_read_data_stamped(VenomShellHandle* handle, uint8_t* buf, size_t max_len, uint64_t* out_timestamp_ns);
+extern size_t venom_channel_data_capacity(VenomShellHandle* handle);
+extern uint64_t venom_clock_ns(void);
+extern const char* venom_version(void);
+extern uint32_t venom_shell_id(VenomShellHandle* handle);
+extern bool venom_shell_daemon_alive(VenomShellHandle* handle);
+extern bool venom_shell_send_typed(VenomShellHandle* handle, uint16_t tag, const uint8_t* payload, size_t len);
+extern ptrdiff_t venom_shell_request(VenomShellHandle* handle, const uint8_t* cmd, size_t cmd_len, uint8_t* resp_buf, size_t resp_max, uint32_t timeout_ms);
+
+static volatile int g_running = 1;
+
+{keys}
+static void signal_handler(int sig) {{ (void)sig; g_running = 0; }}
+
+static double get_time_us(void) {{
+    struct timespec ts;
+    clock_gettime(CLOCK_MONOTONIC, &ts);
+    return ts.tv_sec * 1000000.0 + ts.tv_nsec / 1000.0;
+}}
+
+int main(int argc, char** argv) {{
+    signal(SIGINT, signal_handler);
+    signal(SIGTERM, signal_handler);
+    signal(SIGALRM, signal_handler);
+    
+    // --duration SECONDS: exit on our own (for scripted runs)
+    // --ping COUNT: measure round trips through the daemon instead
+    // --channel-suffix SUFFIX: connect to that instance of the daemon
+    int ping = 0;
+    const char* suffix = NULL;
+    for (int i = 1; i + 1 < argc; i++) {{
+        if (strcmp(argv[i], "--duration") == 0) alarm((unsigned)atoi(argv[i + 1]));
+        else if (strcmp(argv[i], "--ping") == 0) ping = atoi(argv[i + 1]);
+        else if (strcmp(argv[i], "--channel-suffix") == 0) suffix = argv[i + 1];
+    }}
+    char channel_buf[{upper}_CHANNEL_NAME_MAX];
+    const char* channel = {snake}_channel_name(suffix, channel_buf);
+    
+    VenomShellHandle* shell = venom_shell_connect_named(channel, "c-client");
+    if (!shell) {{
+        printf("❌ Failed to connect! Run the daemon first:\n   cd ../daemon && make run\n");
+        return 1;
+    }}
+    printf("✅ Connected! ID: %u (libvenom_memory %s)\n", venom_shell_id(shell), venom_version());
+    if (ping > 0) {{
+        int status = run_ping(shell, ping);
+        venom_shell_destroy(shell);
+        return status;
+    }}
+    keys_begin();
+    
+    // Room for the longest payload the daemon can write
+    size_t buf_len = venom_channel_data_capacity(shell);
+    uint8_t* buf = malloc(buf_len);
+    double lat_min = DBL_MAX, lat_max = 0.0, lat_sum = 0.0;
+    uint64_t lat_count = 0;
+    
+    while (g_running) {{
+        handle_keys(shell);
+        // Reads would keep returning the last state it wrote
+        if (!venom_shell_daemon_alive(shell)) {{
+            printf("\n❌ Daemon offline! Restart it:\n   cd ../daemon && make run\n");
+            break;
+        }}
+        
+        uint64_t written_ns = 0;
+        double t_start = get_time_us();
+        size_t len = venom_shell_read_data_stamped(shell, buf, buf_len, &written_ns);
+        double latency_us = get_time_us() - t_start;
+        // How long ago the daemon wrote what we read, in the library's clock
+        double age_us = written_ns ? (venom_clock_ns() - written_ns) / 1000.0 : 0.0;
+        
+        if (latency_us < lat_min) lat_min = latency_us;
+        if (latency_us > lat_max) lat_max = latency_us;
+        lat_sum += latency_us;
+        lat_count++;
+        
+        if (len >= sizeof({pascal}State)) {{
+            {pascal}State* s = ({pascal}State*)buf;
+            if (s->magic != {upper}_MAGIC) {{ usleep(100000); continue; }}
+            
+            printf("\033[2J\033[H"); // Clear screen
+            printf("🐍 {name} (C) | {pascal}State v%u | %zu bytes\n", s->version, sizeof({pascal}State));
+            printf("═══════════════════════════════════════════════════════════════\n");
+{fields}            printf("═══════════════════════════════════════════════════════════════\n");
+            printf("📊 Read Latency: %.2f µs (min: %.2f, max: %.2f, avg: %.2f)\n",
+                latency_us, lat_min, lat_max, lat_sum / lat_count);
+            printf("📡 Daemon→client: %.2f µs since the write\n", age_us);
+            printf("⏱️  Daemon interval: %u ms | +/- faster/slower | r refresh\n", g_interval_ms);
+        }}
+        usleep(100000);
+    }}
+    keys_end();
+    
+    printf("\n\n📊 Final Latency Stats (C): %lu samples, min %.2f µs, max %.2f µs, avg %.2f µs\n",
+        (unsigned long)lat_count, lat_min, lat_max, lat_sum / lat_count);
+    free(buf);
+    venom_shell_destroy(shell);
+    printf("\n👋 Goodbye!\n");
+    return 0;
+}}
+"#, name = config.name, upper = upper, pascal = pascal, snake = config.names().snake, fields = fields, keys = command_keys_c(config))
+}
+
+/// printf conversion and the cast that matches it
+/// `value` as a C/C++ literal of type `ty`
+fn c_literal(ty: FieldType, value: Golden) -> String {
+    let suffix = match ty {
+        FieldType::U8 | FieldType::U16 | FieldType::U32 => "u",
+        FieldType::U64 => "ULL",
+        FieldType::I64 => "LL",
+        FieldType::F32 => "f",
+        _ => "",
+    };
+    format!("{}{}", value, suffix)
+}
+
+/// `CHECK`s of the golden decode test against a state named `s`, shared by
+/// the C and C++ test programs
+pub(super) fn golden_checks(config: &ProjectConfig) -> String {
+    let mut out = String::new();
+    for (f, values) in config.schema.golden(config.magic) {
+        let values: Vec<String> = values.into_iter().map(|v| c_literal(f.ty, v)).collect();
+        match f.len {
+            Some(n) => out.push_str(&format!(
+                "    {{\n        static const {ty} want[{n}] = {{{values}}};\n        for (int i = 0; i < {n}; i++) CHECK(s.{name}[i] == want[i]);\n    }}\n",
+                ty = f.ty.c(), n = n, values = values.join(", "), name = f.name
+            )),
+            None => out.push_str(&format!("    CHECK(s.{} == {});\n", f.name, values[0])),
+        }
+    }
+    out
+}
+
+/// `make test`: a golden decode that always runs and a live read gated on
+/// `VENOM_LIVE_TEST`
+fn test_state_c(config: &ProjectConfig) -> String {
+    let upper = config.names().upper;
+    let pascal = config.names().pascal;
+
+    format!(r#"/**
+ * {name} protocol tests - run with `make test`
+ *
+ * test_decodes_golden_state decodes a state built from venom.toml, so it
+ * fails when protocol.h no longer matches the daemon's layout.
+ * test_reads_live_state needs the daemon running and {live_env}=1.
+ */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <unistd.h>
+#include "../shared/protocol.h"
+
+typedef struct VenomShellHandle VenomShellHandle;
+extern VenomShellHandle* venom_shell_connect(const char* name);
+extern void venom_shell_destroy(VenomShellHandle* handle);
+extern size_t venom_shell_read_data(VenomShellHandle* handle, uint8_t* buf, size_t max_len);
+
+static int g_failed = 0;
+
+#define CHECK(cond) do {{ \
+    if (!(cond)) {{ fprintf(stderr, "  %s:%d: CHECK(%s) failed\n", __FILE__, __LINE__, #cond); g_failed++; }} \
+}} while (0)
+
+#define RUN(test) do {{ \
+    int before = g_failed; \
+    test(); \
+    printf("%s ... %s\n", #test, g_failed == before ? "ok" : "FAILED"); \
+}} while (0)
+
+// Every field holds a distinct known value (see test_decodes_golden_state)
+static const uint8_t GOLDEN[{upper}_STATE_SIZE] = {{
+{golden}}};
+
+static void test_decodes_golden_state(void) {{
+    {pascal}State s;
+    memcpy(&s, GOLDEN, sizeof(s));
+{checks}}}
+
+static void test_reads_live_state(void) {{
+    VenomShellHandle* shell = venom_shell_connect({upper}_CHANNEL_NAME);
+    CHECK(shell != NULL);
+    if (!shell) return;
+    
+    static uint8_t buf[{upper}_DATA_SIZE];
+    size_t len = 0;
+    // The daemon may not have published yet
+    for (int i = 0; i < 50 && len == 0; i++) {{
+        len = venom_shell_read_data(shell, buf, sizeof(buf));
+        if (len == 0) usleep(20000);
+    }}
+    CHECK(len >= sizeof({pascal}State));
+    if (len >= sizeof({pascal}State)) {{
+        {pascal}State s;
+        memcpy(&s, buf, sizeof(s));
+        CHECK(s.magic == {upper}_MAGIC);
+        CHECK(s.version == 1);
+    }}
+    venom_shell_destroy(shell);
+}}
+
+int main(void) {{
+    RUN(test_decodes_golden_state);
+    if (getenv("{live_env}")) {{
+        RUN(test_reads_live_state);
+    }} else {{
+        printf("test_reads_live_state ... skipped: set {live_env}=1 with the daemon running\n");
+    }}
+    return g_failed ? 1 : 0;
+}}
+"#,
+        name = config.name,
+        upper = upper,
+        pascal = pascal,
+        golden = super::byte_rows(&config.schema.golden_bytes(config.magic), "    "),
+        checks = golden_checks(config),
+        live_env = super::LIVE_TEST_ENV
+    )
+}
+
+fn printf_spec(ty: FieldType) -> (&'static str, &'static str) {
+    match ty {
+        FieldType::F32 | FieldType::F64 => ("%.3f", "double"),
+        FieldType::U64 => ("%llu", "unsigned long long"),
+        FieldType::I64 => ("%lld", "long long"),
+        t if t.is_signed() => ("%d", "int"),
+        _ => ("%u", "unsigned"),
+    }
 }
 
 fn client_makefile(config: &ProjectConfig) -> String {
     format!(r#"# {name} Client Makefile
 
-CC = gcc
-CFLAGS = -Wall -Wextra -O2 -I../shared
-LDFLAGS = -L../lib -lvenom_memory -Wl,-rpath,'$$ORIGIN/../lib'
+{target_var}CC = gcc
+CFLAGS = -Wall -Wextra -O2 -g -I../{root}shared
+LDFLAGS = -L{lib} -lvenom_memory -Wl,-rpath,'$$ORIGIN/{lib}'
 
 TARGET = {name}_client
 SOURCES = src/main.c
+TEST = test_state
 
-.PHONY: all clean run
+.PHONY: all clean run test
 
 all: $(TARGET)
 
@@ -436,12 +1122,25 @@ $(TARGET): $(SOURCES)
 	@$(CC) $(CFLAGS) $(SOURCES) -o $(TARGET) $(LDFLAGS)
 	@echo "✅ Build complete"
 
+$(TEST): src/test_state.c
+	@$(CC) $(CFLAGS) $< -o $@ $(LDFLAGS)
+
 clean:
-	@rm -f $(TARGET)
+	@rm -f $(TARGET) $(TEST)
 
 run: $(TARGET)
-	@./$(TARGET)
-"#, name = config.name)
+	@./$(TARGET) $(ARGS)
+
+# {live_env}=1 make test also reads the running daemon
+test: $(TEST)
+	@./$(TEST)
+"#,
+        name = config.name,
+        target_var = super::make_target_var(config),
+        root = config.root,
+        lib = super::make_lib_dir(config, &format!("../{}", config.at_root("lib"))),
+        live_env = super::LIVE_TEST_ENV,
+    )
 }
 
 fn readme(config: &ProjectConfig) -> String {
@@ -459,6 +1158,12 @@ cd daemon && make run
 cd client && make run
 ```
 
+## Tests
+
+`cd client && make test` decodes a known state built from `venom.toml`, so it
+fails when `shared/protocol.h` no longer matches the daemon's layout. With
+the daemon running, `{live_env}=1 make test` also reads the live channel.
+{ping}{instances}
 ## Configuration
 
 | Setting | Value |
@@ -470,9 +1175,15 @@ cd client && make run
 
 ## Customization
 
-Edit `shared/protocol.h` to modify the data structure.
-"#,
+The state struct is described in `venom.toml`. Edit it and regenerate with
+`venom init {name} --schema venom.toml --force ...` (try `--diff` first) so
+the header and every decoder stay in sync; don't edit `shared/protocol.h` by hand.
+{service}"#,
         name = config.name,
+        service = super::service::readme_section(config),
+        live_env = super::LIVE_TEST_ENV,
+        ping = super::ping_readme_section(&format!("cd client && ./{}_client --ping 10", config.name)),
+        instances = super::instances_readme_section(&config.channel, "cd daemon && make run ARGS=\"--channel-suffix SUFFIX\"", "cd client && make run ARGS=\"--channel-suffix SUFFIX\"", &format!("`{}_channel_name()` in `shared/protocol.h`", config.names().snake)),
         channel = config.channel,
         data_size = config.data_size,
         cmd_slots = config.cmd_slots,
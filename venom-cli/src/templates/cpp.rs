@@ -6,13 +6,13 @@
 //! - Status bar client
 
 use super::ProjectConfig;
+use crate::schema::FieldType;
 
 pub fn generate(config: &ProjectConfig) {
     let base = &config.output_dir;
     
     crate::create_dir(&format!("{}/shared", base));
     crate::create_dir(&format!("{}/daemon/src", base));
-    crate::create_dir(&format!("{}/client/src", base));
     
     // Shared
     crate::write_file(&format!("{}/shared/protocol.hpp", base), &protocol_hpp(config));
@@ -23,44 +23,52 @@ pub fn generate(config: &ProjectConfig) {
     crate::write_file(&format!("{}/daemon/Makefile", base), &daemon_makefile(config));
     
     // Client
-    crate::write_file(&format!("{}/client/src/main.cpp", base), &client_main(config));
-    crate::write_file(&format!("{}/client/Makefile", base), &client_makefile(config));
+    generate_client(config);
     
     // README
     crate::write_file(&format!("{}/README.md", base), &readme(config));
 }
 
-fn magic(channel: &str) -> u32 {
-    channel.bytes().fold(0x564E4Fu32, |acc, b| acc.wrapping_add(b as u32))
-}
-
-fn upper_name(name: &str) -> String {
-    name.to_uppercase().replace("-", "_")
+/// Client files only (used by `venom add-client`)
+pub fn generate_client(config: &ProjectConfig) {
+    let base = &config.output_dir;
+    
+    crate::create_dir(&format!("{}/client/src", base));
+    crate::write_file_if_missing(&format!("{}/{}", base, config.at_root("shared/protocol.hpp")), &protocol_hpp(config));
+    crate::write_file_if_missing(&format!("{}/{}", base, config.at_root("shared/venom.hpp")), &venom_hpp(config));
+    crate::write_file(&format!("{}/client/src/main.cpp", base), &client_main(config));
+    crate::write_file(&format!("{}/client/src/test_state.cpp", base), &test_state_cpp(config));
+    crate::write_file(&format!("{}/client/Makefile", base), &client_makefile(config));
 }
 
-fn pascal_case(s: &str) -> String {
-    s.split(|c| c == '_' || c == '-')
-        .map(|word| {
-            let mut chars = word.chars();
-            match chars.next() {
-                None => String::new(),
-                Some(first) => first.to_uppercase().chain(chars).collect(),
-            }
-        })
-        .collect()
-}
 
 // ═══════════════════════════════════════════════════════════════════════════
 // Protocol Header (C++ style)
 // ═══════════════════════════════════════════════════════════════════════════
 
 fn protocol_hpp(config: &ProjectConfig) -> String {
-    let upper = upper_name(&config.name);
-    let pascal = pascal_case(&config.name);
+    let ns = config.names().flat;
+    let schema = &config.schema;
+    let mut fields = String::new();
+    for f in &schema.fields {
+        match f.len {
+            Some(n) => fields.push_str(&format!("    std::array<{}, {}> {}{{}};\n", f.ty.c(), n, f.name)),
+            None if f.ty == FieldType::F32 => fields.push_str(&format!("    float {} = 0.0f;\n", f.name)),
+            None if f.ty == FieldType::F64 => fields.push_str(&format!("    double {} = 0.0;\n", f.name)),
+            None => fields.push_str(&format!("    {} {} = 0;\n", f.ty.c(), f.name)),
+        }
+    }
+    let demo = schema.has_demo_fields();
+    let max_cores = match schema.field("cpu_cores") {
+        Some(f) if demo => format!("constexpr size_t MAX_CORES = {};\n", f.count()),
+        _ => String::new(),
+    };
+    let helpers = if demo { DEMO_HELPERS } else { "" };
     
     format!(r#"#pragma once
 #include <cstdint>
 #include <string>
+#include <string_view>
 #include <array>
 
 namespace {ns} {{
@@ -74,78 +82,124 @@ constexpr uint32_t MAGIC = 0x{magic:08X};
 constexpr size_t DATA_SIZE = {data_size};
 constexpr size_t CMD_SLOTS = {cmd_slots};
 constexpr size_t MAX_CLIENTS = {max_clients};
-constexpr size_t MAX_CORES = 16;
+constexpr size_t STATE_SIZE = {state_size};
+{max_cores}
+/// The channel of one of several instances: "{channel}-SUFFIX", or
+/// CHANNEL_NAME itself for an empty suffix
+inline std::string channel_name(std::string_view suffix = {{}}) {{
+    std::string name = CHANNEL_NAME;
+    if (!suffix.empty()) name.append("-").append(suffix);
+    return name;
+}}
 
 // ═══════════════════════════════════════════════════════════════════════════
-// State Structure
+// State Structure (generated from venom.toml)
 // ═══════════════════════════════════════════════════════════════════════════
 
 #pragma pack(push, 1)
 struct State {{
-    uint32_t magic = 0;
-    uint32_t version = 0;
-    float cpu_usage_percent = 0.0f;
-    std::array<float, MAX_CORES> cpu_cores{{}};
-    uint32_t core_count = 0;
-    uint32_t memory_used_mb = 0;
-    uint32_t memory_total_mb = 0;
-    uint64_t uptime_seconds = 0;
-    uint64_t update_counter = 0;
-    uint64_t timestamp_ns = 0;
-    
+{fields}    
     [[nodiscard]] bool is_valid() const {{ return magic == MAGIC; }}
-    
-    [[nodiscard]] float memory_percent() const {{
-        return memory_total_mb > 0 ? 
-            static_cast<float>(memory_used_mb) / memory_total_mb * 100.0f : 0.0f;
-    }}
-    
-    [[nodiscard]] std::string uptime_formatted() const {{
-        auto h = uptime_seconds / 3600;
-        auto m = (uptime_seconds % 3600) / 60;
-        return std::to_string(h) + "h " + std::to_string(m) + "m";
-    }}
+{helpers}}};
+#pragma pack(pop)
+
+static_assert(sizeof(State) == STATE_SIZE, "State struct size mismatch");
+
+// ═══════════════════════════════════════════════════════════════════════════
+// 📨 Commands (Clients send, Daemon applies)
+// ═══════════════════════════════════════════════════════════════════════════
+
+constexpr uint32_t INTERVAL_MS = 100;  // Default publish interval
+
+enum class CmdType : uint16_t {{
+    Refresh = 1,      // Publish immediately
+    SetInterval = 2,  // value: publish interval in ms (10-10000)
+    Ping = 3,         // value: sequence number, echoed back in a Pong response
+}};
+
+// A typed command, as venom_shell_send_typed sends it: a tag, the bytes of
+// payload after the envelope, then the payload
+#pragma pack(push, 1)
+struct Command {{
+    CmdType cmd = CmdType::Refresh;
+    uint16_t len = sizeof(int32_t);
+    int32_t value = 0;
 }};
 #pragma pack(pop)
 
-static_assert(sizeof(State) == 112, "State struct size mismatch");
+static_assert(sizeof(Command) == 8, "Command struct size mismatch");
+
+// Reply to a Ping, sent to its client alone
+#pragma pack(push, 1)
+struct Pong {{
+    uint32_t client_id = 0;  // Client that sent the ping
+    uint32_t seq = 0;        // Its value
+}};
+#pragma pack(pop)
 
 }} // namespace {ns}
 "#,
-        ns = pascal.to_lowercase(),
+        ns = ns,
         channel = config.channel,
-        magic = magic(&config.channel),
+        magic = config.magic,
         data_size = config.data_size,
         cmd_slots = config.cmd_slots,
-        max_clients = config.max_clients
+        max_clients = config.max_clients,
+        state_size = schema.size,
+        max_cores = max_cores,
+        fields = fields,
+        helpers = helpers
     )
 }
 
+/// Convenience accessors used by the system-monitor demo
+const DEMO_HELPERS: &str = r#"    
+    [[nodiscard]] float memory_percent() const {
+        return memory_total_mb > 0 ? 
+            static_cast<float>(memory_used_mb) / memory_total_mb * 100.0f : 0.0f;
+    }
+    
+    [[nodiscard]] std::string uptime_formatted() const {
+        auto h = uptime_seconds / 3600;
+        auto m = (uptime_seconds % 3600) / 60;
+        return std::to_string(h) + "h " + std::to_string(m) + "m";
+    }
+"#;
+
 // ═══════════════════════════════════════════════════════════════════════════
 // C++ Wrapper for VenomMemory
 // ═══════════════════════════════════════════════════════════════════════════
 
 fn venom_hpp(config: &ProjectConfig) -> String {
-    let pascal = pascal_case(&config.name);
+    let ns = config.names().flat;
     
     format!(r#"#pragma once
 #include "protocol.hpp"
 #include <memory>
 #include <stdexcept>
+#include <cstddef>
 #include <cstring>
+#include <chrono>
 
 // C bindings
 extern "C" {{
-    struct VenomConfig {{ size_t data_size; size_t cmd_slots; size_t max_clients; }};
-    void* venom_daemon_create(const char* name, VenomConfig config);
+    struct VenomConfigEx {{ size_t struct_size; size_t data_size; size_t cmd_slots; size_t max_clients; size_t cmd_payload_size; size_t cmd_full_policy; size_t shm_mode; }};
+    void* venom_daemon_create_ex(const char* name, const VenomConfigEx* config);
     void venom_daemon_destroy(void* handle);
     void venom_daemon_write_data(void* handle, const uint8_t* data, size_t len);
     size_t venom_daemon_try_recv_command(void* handle, uint8_t* buf, size_t max_len, uint32_t* out_client_id);
+    size_t venom_daemon_client_name(void* handle, uint32_t client_id, char* buf, size_t max_len);
+    bool venom_daemon_respond(void* handle, uint32_t client_id, const uint8_t* data, size_t len);
     
-    void* venom_shell_connect(const char* name);
+    void* venom_shell_connect_named(const char* name, const char* client_name);
     void venom_shell_destroy(void* handle);
-    size_t venom_shell_read_data(void* handle, uint8_t* buf, size_t max_len);
+    size_t venom_shell_read_data_stamped(void* handle, uint8_t* buf, size_t max_len, uint64_t* out_timestamp_ns);
+    uint64_t venom_clock_ns();
+    const char* venom_version();
     uint32_t venom_shell_id(void* handle);
+    bool venom_shell_daemon_alive(void* handle);
+    bool venom_shell_send_typed(void* handle, uint16_t tag, const uint8_t* payload, size_t len);
+    std::ptrdiff_t venom_shell_request(void* handle, const uint8_t* cmd, size_t cmd_len, uint8_t* resp_buf, size_t resp_max, uint32_t timeout_ms);
 }}
 
 namespace {ns} {{
@@ -156,9 +210,9 @@ namespace {ns} {{
 
 class Daemon {{
 public:
-    Daemon() {{
-        VenomConfig cfg{{DATA_SIZE, CMD_SLOTS, MAX_CLIENTS}};
-        handle_ = venom_daemon_create(CHANNEL_NAME, cfg);
+    explicit Daemon(const std::string& channel = CHANNEL_NAME) {{
+        VenomConfigEx cfg{{sizeof(VenomConfigEx), DATA_SIZE, CMD_SLOTS, MAX_CLIENTS, sizeof(Command), 0, 0{shm_mode:o}}};
+        handle_ = venom_daemon_create_ex(channel.c_str(), &cfg);
         if (!handle_) throw std::runtime_error("Failed to create daemon channel");
     }}
     
@@ -173,9 +227,36 @@ public:
         venom_daemon_write_data(handle_, reinterpret_cast<const uint8_t*>(&state), sizeof(State));
     }}
     
+    /// Answer a Ping with a Pong to its client alone
+    void pong(uint32_t client_id, uint32_t seq) {{
+        Pong pong{{client_id, seq}};
+        venom_daemon_respond(handle_, client_id, reinterpret_cast<const uint8_t*>(&pong), sizeof(Pong));
+    }}
+    
     [[nodiscard]] bool try_recv_command(uint8_t* buf, size_t max_len, uint32_t& client_id) {{
         return venom_daemon_try_recv_command(handle_, buf, max_len, &client_id) > 0;
     }}
+    
+    /// Next queued Command, skipping messages that aren't one: too short,
+    /// or typed commands with another payload
+    [[nodiscard]] bool try_recv(Command& cmd, uint32_t& client_id) {{
+        uint8_t buf[64];
+        size_t len;
+        while ((len = venom_daemon_try_recv_command(handle_, buf, sizeof(buf), &client_id)) > 0) {{
+            if (len < sizeof(Command)) continue;
+            std::memcpy(&cmd, buf, sizeof(Command));
+            if (cmd.len == sizeof(cmd.value)) return true;
+        }}
+        return false;
+    }}
+    
+    /// "3", or "3 (gui-frontend)" for a client that connected with a name
+    [[nodiscard]] std::string client_label(uint32_t client_id) const {{
+        char name[33];
+        std::string label = std::to_string(client_id);
+        if (venom_daemon_client_name(handle_, client_id, name, sizeof(name)) > 0) label += std::string(" (") + name + ")";
+        return label;
+    }}
 
 private:
     void* handle_ = nullptr;
@@ -187,8 +268,9 @@ private:
 
 class Shell {{
 public:
-    Shell() {{
-        handle_ = venom_shell_connect(CHANNEL_NAME);
+    /// client_name is what the daemon shows in its log; nullptr for none
+    explicit Shell(const char* client_name = nullptr, const std::string& channel = CHANNEL_NAME) {{
+        handle_ = venom_shell_connect_named(channel.c_str(), client_name);
         if (!handle_) throw std::runtime_error("Failed to connect - is daemon running?");
     }}
     
@@ -201,29 +283,162 @@ public:
     
     [[nodiscard]] uint32_t client_id() const {{ return venom_shell_id(handle_); }}
     
+    /// False once the daemon has exited; reads then return its last state
+    [[nodiscard]] bool daemon_alive() const {{ return venom_shell_daemon_alive(handle_); }}
+    
+    /// Version of the libvenom_memory that was loaded
+    [[nodiscard]] static std::string library_version() {{ return venom_version(); }}
+    
     [[nodiscard]] State read_state() {{
         State state{{}};
-        uint8_t buf[256];
-        size_t len = venom_shell_read_data(handle_, buf, sizeof(buf));
+        uint8_t buf[sizeof(State) + 64];
+        uint64_t written_ns = 0;
+        size_t len = venom_shell_read_data_stamped(handle_, buf, sizeof(buf), &written_ns);
         if (len >= sizeof(State)) std::memcpy(&state, buf, sizeof(State));
+        age_ns_ = written_ns ? venom_clock_ns() - written_ns : 0;
         return state;
     }}
+    
+    /// How long before the last read_state() the daemon wrote what it read
+    [[nodiscard]] double write_age_us() const {{ return age_ns_ / 1000.0; }}
+    
+    bool send(const Command& cmd) {{
+        return venom_shell_send_typed(handle_, static_cast<uint16_t>(cmd.cmd), reinterpret_cast<const uint8_t*>(&cmd.value),
+                                      sizeof(cmd.value));
+    }}
+    
+    bool set_interval(uint32_t ms) {{ return send(Command{{CmdType::SetInterval, sizeof(int32_t), static_cast<int32_t>(ms)}}); }}
+    bool refresh() {{ return send(Command{{CmdType::Refresh, sizeof(int32_t), 0}}); }}
+    
+    /// Round trip of a Ping carrying `seq` in µs; negative if no pong came back within a second
+    [[nodiscard]] double ping(uint32_t seq) {{
+        Command cmd{{CmdType::Ping, sizeof(int32_t), static_cast<int32_t>(seq)}};
+        Pong pong;
+        auto sent = std::chrono::steady_clock::now();
+        std::ptrdiff_t len = venom_shell_request(handle_, reinterpret_cast<const uint8_t*>(&cmd), sizeof(Command),
+                                                 reinterpret_cast<uint8_t*>(&pong), sizeof(Pong), 1000);
+        auto rtt = std::chrono::duration<double, std::micro>(std::chrono::steady_clock::now() - sent).count();
+        // A late pong to an earlier ping that timed out doesn't count
+        return len == static_cast<std::ptrdiff_t>(sizeof(Pong)) && pong.seq == seq ? rtt : -1.0;
+    }}
 
 private:
     void* handle_ = nullptr;
+    uint64_t age_ns_ = 0;
 }};
 
 }} // namespace {ns}
-"#, ns = pascal.to_lowercase())
+"#, ns = ns, shm_mode = config.shm_mode)
+}
+
+/// Daemon-side command handling, shared by both daemons
+///
+/// Defines `g_interval_ms` and `handle_commands()`; the daemon polls
+/// `handle_commands` every 10 ms and publishes when it returns true or
+/// `g_interval_ms` has passed.
+const COMMAND_HANDLER: &str = r#"static uint32_t g_interval_ms = INTERVAL_MS;
+
+/// Apply queued client commands; returns true if a client asked for a refresh
+bool handle_commands(Daemon& daemon) {
+    Command cmd;
+    uint32_t client_id;
+    bool refresh = false;
+    while (daemon.try_recv(cmd, client_id)) {
+        switch (cmd.cmd) {
+        case CmdType::Refresh:
+            refresh = true;
+            break;
+        case CmdType::SetInterval:
+            g_interval_ms = static_cast<uint32_t>(std::clamp<int32_t>(cmd.value, 10, 10000));
+            std::cout << "\n📥 Client " << daemon.client_label(client_id) << ": publishing every " << g_interval_ms << " ms\n";
+            break;
+        case CmdType::Ping:
+            daemon.pong(client_id, static_cast<uint32_t>(cmd.value));
+            break;
+        default:
+            std::cout << "\n📥 Client " << daemon.client_label(client_id) << ": unknown command " << static_cast<uint16_t>(cmd.cmd) << "\n";
+        }
+    }
+    return refresh;
 }
+"#;
+
+/// Client-side keys, shared by both clients: `+`/`-` halve or double the
+/// daemon's publish interval, `r` asks for an immediate refresh; also
+/// `run_ping()` for `--ping`
+const COMMAND_KEYS: &str = r#"/// Reads single keys without Enter or echo while alive (Ctrl+C still works)
+class RawKeys {
+public:
+    RawKeys() {
+        if (!isatty(STDIN_FILENO) || tcgetattr(STDIN_FILENO, &saved_) != 0) return;
+        termios raw = saved_;
+        raw.c_lflag &= ~(ICANON | ECHO);
+        raw.c_cc[VMIN] = 0;
+        raw.c_cc[VTIME] = 0;
+        active_ = tcsetattr(STDIN_FILENO, TCSANOW, &raw) == 0;
+    }
+    
+    ~RawKeys() { if (active_) tcsetattr(STDIN_FILENO, TCSANOW, &saved_); }
+    
+    RawKeys(const RawKeys&) = delete;
+    RawKeys& operator=(const RawKeys&) = delete;
+    
+    /// Next pressed key, or 0 if none is waiting
+    [[nodiscard]] char poll() const {
+        char key = 0;
+        return active_ && read(STDIN_FILENO, &key, 1) == 1 ? key : 0;
+    }
+
+private:
+    termios saved_{};
+    bool active_ = false;
+};
+
+static uint32_t g_interval_ms = INTERVAL_MS;
+
+void handle_keys(const RawKeys& keys, Shell& shell) {
+    while (char key = keys.poll()) {
+        if (key == '+' || key == '=') shell.set_interval(g_interval_ms = std::max(g_interval_ms / 2, 10u));
+        else if (key == '-') shell.set_interval(g_interval_ms = std::min(g_interval_ms * 2, 10000u));
+        else if (key == 'r') shell.refresh();
+    }
+}
+
+/// --ping COUNT: time COUNT round trips through the daemon; 0 if any came back
+int run_ping(Shell& shell, int count) {
+    // Sequence numbers from the clock, so no two runs send the same one
+    auto seq = static_cast<uint32_t>(std::chrono::steady_clock::now().time_since_epoch().count());
+    double rtt_min = std::numeric_limits<double>::max(), rtt_max = 0.0, rtt_sum = 0.0;
+    int replies = 0;
+    for (int i = 1; i <= count && g_running; i++) {
+        double rtt_us = shell.ping(++seq);
+        if (rtt_us < 0) {
+            std::cout << "⚠️  No reply to ping #" << i << "\n";
+            continue;
+        }
+        std::cout << "🏓 Pong #" << i << ": " << std::fixed << std::setprecision(1) << rtt_us << " µs\n";
+        rtt_min = std::min(rtt_min, rtt_us);
+        rtt_max = std::max(rtt_max, rtt_us);
+        rtt_sum += rtt_us;
+        replies++;
+        std::this_thread::sleep_for(std::chrono::milliseconds(100));
+    }
+    if (replies == 0) return 1;
+    std::cout << "📊 Round trip: " << replies << "/" << count << " replies, min " << rtt_min << " µs, avg "
+              << rtt_sum / replies << " µs, max " << rtt_max << " µs\n";
+    return 0;
+}
+"#;
 
 // ═══════════════════════════════════════════════════════════════════════════
 // Daemon
 // ═══════════════════════════════════════════════════════════════════════════
 
 fn daemon_main(config: &ProjectConfig) -> String {
-    let pascal = pascal_case(&config.name);
-    let ns = pascal.to_lowercase();
+    if !config.schema.has_demo_fields() {
+        return generic_daemon(config);
+    }
+    let ns = config.names().flat;
     
     format!(r##"/**
  * {name} System Monitor Daemon (C++)
@@ -238,6 +453,7 @@ fn daemon_main(config: &ProjectConfig) -> String {
 #include <csignal>
 #include <iomanip>
 #include <vector>
+#include <algorithm>
 
 using namespace {ns};
 
@@ -247,6 +463,7 @@ static std::vector<uint64_t> prev_idle(MAX_CORES + 1, 0);
 
 void signal_handler(int) {{ g_running = false; }}
 
+{commands}
 void read_cpu(State& state) {{
     std::ifstream f("/proc/stat");
     if (!f) return;
@@ -291,7 +508,14 @@ void read_uptime(State& state) {{
     if (f >> uptime) state.uptime_seconds = static_cast<uint64_t>(uptime);
 }}
 
-int main() {{
+int main(int argc, char** argv) {{
+    // --channel-suffix SUFFIX: run as one of several instances, on "{channel}-SUFFIX"
+    std::string_view suffix;
+    for (int i = 1; i + 1 < argc; i++) {{
+        if (std::string_view(argv[i]) == "--channel-suffix") suffix = argv[i + 1];
+    }}
+    const std::string channel = channel_name(suffix);
+    
     std::cout << "🖥️  {name} System Monitor (C++)\n";
     std::cout << "═══════════════════════════════════════════════════════════════\n";
     
@@ -299,15 +523,23 @@ int main() {{
     std::signal(SIGTERM, signal_handler);
     
     try {{
-        Daemon daemon;
-        std::cout << "✅ Channel: " << CHANNEL_NAME << "\n";
+        Daemon daemon(channel);
+        std::cout << "✅ Channel: " << channel << "\n";
         std::cout << "🚀 Publishing... (Ctrl+C to stop)\n\n";
         
         State state{{}};
         state.magic = MAGIC;
         state.version = 1;
         
+        auto next_publish = std::chrono::steady_clock::now();
         while (g_running) {{
+            bool refresh = handle_commands(daemon);
+            if (!refresh && std::chrono::steady_clock::now() < next_publish) {{
+                std::this_thread::sleep_for(std::chrono::milliseconds(10));
+                continue;
+            }}
+            next_publish = std::chrono::steady_clock::now() + std::chrono::milliseconds(g_interval_ms);
+            
             read_cpu(state);
             read_memory(state);
             read_uptime(state);
@@ -319,9 +551,78 @@ int main() {{
             
             std::cout << "\r🖥️  CPU: " << std::fixed << std::setprecision(1) << state.cpu_usage_percent
                       << "% | RAM: " << state.memory_used_mb << "/" << state.memory_total_mb << " MB"
-                      << " | #" << state.update_counter << "   " << std::flush;
+                      << " | #" << state.update_counter << " | " << g_interval_ms << " ms   " << std::flush;
+        }}
+        
+        std::cout << "\n\n👋 Goodbye!\n";
+    }} catch (const std::exception& e) {{
+        std::cerr << "❌ Error: " << e.what() << "\n";
+        return 1;
+    }}
+    return 0;
+}}
+"##, name = config.name, ns = ns, channel = config.channel, commands = COMMAND_HANDLER)
+}
+
+/// Daemon for a custom schema: publishes the header and leaves the fields to the user
+fn generic_daemon(config: &ProjectConfig) -> String {
+    let ns = config.names().flat;
+    
+    format!(r##"/**
+ * {name} Daemon (C++) - publishes State (see venom.toml)
+ */
+
+#include "../shared/venom.hpp"
+#include <iostream>
+#include <chrono>
+#include <thread>
+#include <csignal>
+#include <algorithm>
+
+using namespace {ns};
+
+static volatile bool g_running = true;
+
+void signal_handler(int) {{ g_running = false; }}
+
+{commands}
+int main(int argc, char** argv) {{
+    // --channel-suffix SUFFIX: run as one of several instances, on "{channel}-SUFFIX"
+    std::string_view suffix;
+    for (int i = 1; i + 1 < argc; i++) {{
+        if (std::string_view(argv[i]) == "--channel-suffix") suffix = argv[i + 1];
+    }}
+    const std::string channel = channel_name(suffix);
+    
+    std::cout << "🚀 {name} Daemon (C++)\n";
+    std::cout << "═══════════════════════════════════════════════════════════════\n";
+    
+    std::signal(SIGINT, signal_handler);
+    std::signal(SIGTERM, signal_handler);
+    
+    try {{
+        Daemon daemon(channel);
+        std::cout << "✅ Channel: " << channel << " | State: " << sizeof(State) << " bytes\n";
+        std::cout << "🚀 Publishing... (Ctrl+C to stop)\n\n";
+        
+        State state{{}};
+        state.magic = MAGIC;
+        state.version = 1;
+        uint64_t updates = 0;
+        
+        auto next_publish = std::chrono::steady_clock::now();
+        while (g_running) {{
+            bool refresh = handle_commands(daemon);
+            if (!refresh && std::chrono::steady_clock::now() < next_publish) {{
+                std::this_thread::sleep_for(std::chrono::milliseconds(10));
+                continue;
+            }}
+            next_publish = std::chrono::steady_clock::now() + std::chrono::milliseconds(g_interval_ms);
             
-            std::this_thread::sleep_for(std::chrono::milliseconds(100));
+            // TODO: fill in the fields of State
+            
+            daemon.write(state);
+            std::cout << "\r📤 Published update #" << ++updates << " (every " << g_interval_ms << " ms)   " << std::flush;
         }}
         
         std::cout << "\n\n👋 Goodbye!\n";
@@ -331,15 +632,15 @@ int main() {{
     }}
     return 0;
 }}
-"##, name = config.name, ns = ns)
+"##, name = config.name, ns = ns, channel = config.channel, commands = COMMAND_HANDLER)
 }
 
 fn daemon_makefile(config: &ProjectConfig) -> String {
     format!(r#"# {name} Daemon Makefile (C++)
 
-CXX = g++
-CXXFLAGS = -std=c++17 -Wall -Wextra -O2 -I../shared
-LDFLAGS = -L../lib -lvenom_memory -Wl,-rpath,'$$ORIGIN/../lib'
+{target_var}CXX = g++
+CXXFLAGS = -std=c++17 -Wall -Wextra -O2 -g -I../shared
+LDFLAGS = -L{lib} -lvenom_memory -Wl,-rpath,'$$ORIGIN/{lib}'
 
 TARGET = {name}_daemon
 SOURCES = src/main.cpp
@@ -357,8 +658,12 @@ clean:
 	@rm -f $(TARGET)
 
 run: $(TARGET)
-	@./$(TARGET)
-"#, name = config.name)
+	@./$(TARGET) $(ARGS)
+"#,
+        name = config.name,
+        target_var = super::make_target_var(config),
+        lib = super::make_lib_dir(config, "../lib"),
+    )
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -366,8 +671,10 @@ run: $(TARGET)
 // ═══════════════════════════════════════════════════════════════════════════
 
 fn client_main(config: &ProjectConfig) -> String {
-    let pascal = pascal_case(&config.name);
-    let ns = pascal.to_lowercase();
+    if !config.schema.has_demo_fields() {
+        return generic_client(config);
+    }
+    let ns = config.names().flat;
     
     format!(r##"/**
  * {name} Status Bar Client (C++) - with Benchmarking
@@ -380,6 +687,11 @@ fn client_main(config: &ProjectConfig) -> String {
 #include <csignal>
 #include <chrono>
 #include <limits>
+#include <algorithm>
+#include <cstdlib>
+#include <string_view>
+#include <termios.h>
+#include <unistd.h>
 
 using namespace {ns};
 
@@ -393,6 +705,7 @@ static uint64_t g_latency_count = 0;
 
 void signal_handler(int) {{ g_running = false; }}
 
+{keys}
 // ANSI colors
 const char* G = "\033[92m";
 const char* Y = "\033[93m";
@@ -411,22 +724,42 @@ void print_bar(float pct, int width = 25) {{
     std::cout << "]";
 }}
 
-int main() {{
+int main(int argc, char** argv) {{
     std::cout << "╔═══════════════════════════════════════════════════════════════╗\n";
     std::cout << "║   🖥️  {name} Status Bar (C++)                                  ║\n";
     std::cout << "╚═══════════════════════════════════════════════════════════════╝\n\n";
     
     std::signal(SIGINT, signal_handler);
     std::signal(SIGTERM, signal_handler);
+    std::signal(SIGALRM, signal_handler);
+    
+    // --duration SECONDS: exit on our own (for scripted runs)
+    // --ping COUNT: measure round trips through the daemon instead
+    // --channel-suffix SUFFIX: connect to that instance of the daemon
+    int ping = 0;
+    std::string_view suffix;
+    for (int i = 1; i + 1 < argc; i++) {{
+        if (std::string_view(argv[i]) == "--duration") alarm(static_cast<unsigned>(std::atoi(argv[i + 1])));
+        else if (std::string_view(argv[i]) == "--ping") ping = std::atoi(argv[i + 1]);
+        else if (std::string_view(argv[i]) == "--channel-suffix") suffix = argv[i + 1];
+    }}
     
     try {{
-        Shell shell;
-        std::cout << "✅ Connected! ID: " << shell.client_id() << "\n";
+        Shell shell("cpp-client", channel_name(suffix));
+        std::cout << "✅ Connected! ID: " << shell.client_id() << " (libvenom_memory " << Shell::library_version() << ")\n";
+        if (ping > 0) return run_ping(shell, ping);
         std::cout << "📊 Reading stats... (Ctrl+C to exit)\n\n";
         std::this_thread::sleep_for(std::chrono::seconds(1));
         
+        RawKeys keys;
         int frame = 0;
         while (g_running) {{
+            handle_keys(keys, shell);
+            if (!shell.daemon_alive()) {{
+                std::cout << "\n❌ Daemon offline! Restart it:\n   cd ../daemon && make run\n";
+                break;
+            }}
+            
             // ═══════════════════════════════════════════════════════════════════
             // 📊 BENCHMARK: Measure read latency
             // ═══════════════════════════════════════════════════════════════════
@@ -464,8 +797,10 @@ int main() {{
                 std::cout << "╠═══════════════════════════════════════════════════════════════╣\n";
                 std::cout << "║  📊 " << C << "Read Latency:" << RST << " " << std::fixed << std::setprecision(2) 
                           << latency_us << " µs (min: " << g_latency_min << ", max: " << g_latency_max << ", avg: " << avg_us << ")  ║\n";
+                std::cout << "║  📡 " << C << "Daemon→client:" << RST << " " << shell.write_age_us() << " µs since the write                 ║\n";
                 std::cout << "╚═══════════════════════════════════════════════════════════════╝\n";
-                std::cout << "  Cores: " << state.core_count << " | Updates: " << state.update_counter << " | Ctrl+C to exit\n";
+                std::cout << "  Cores: " << state.core_count << " | Updates: " << state.update_counter << " | Interval: " << g_interval_ms << " ms\n";
+                std::cout << "  +/- faster/slower | r refresh | Ctrl+C to exit\n";
             }}
             std::this_thread::sleep_for(std::chrono::milliseconds(100));
         }}
@@ -484,20 +819,206 @@ int main() {{
     }}
     return 0;
 }}
-"##, name = config.name, ns = ns)
+"##, name = config.name, ns = ns, keys = COMMAND_KEYS)
+}
+
+/// Client for a custom schema: prints every field of the state
+fn generic_client(config: &ProjectConfig) -> String {
+    let ns = config.names().flat;
+    let mut fields = String::new();
+    for f in config.schema.user_fields() {
+        // Unary + so 8-bit fields print as numbers, not characters
+        let value = match f.len {
+            Some(_) => format!("; for (auto v : state.{}) std::cout << ' ' << +v; std::cout", f.name),
+            None => format!(" << ' ' << +state.{}", f.name),
+        };
+        fields.push_str(&format!(
+            "                std::cout << \"  \" << std::setw(24) << std::left << \"{}\"{} << \"\\n\";\n",
+            f.name, value
+        ));
+    }
+    
+    format!(r##"/**
+ * {name} Client (C++) - prints every field of State (see venom.toml)
+ * Includes read latency benchmarking.
+ */
+
+#include "../shared/venom.hpp"
+#include <iostream>
+#include <iomanip>
+#include <thread>
+#include <csignal>
+#include <chrono>
+#include <limits>
+#include <algorithm>
+#include <cstdlib>
+#include <string_view>
+#include <termios.h>
+#include <unistd.h>
+
+using namespace {ns};
+
+static volatile bool g_running = true;
+
+void signal_handler(int) {{ g_running = false; }}
+
+{keys}
+int main(int argc, char** argv) {{
+    std::signal(SIGINT, signal_handler);
+    std::signal(SIGTERM, signal_handler);
+    std::signal(SIGALRM, signal_handler);
+    
+    // --duration SECONDS: exit on our own (for scripted runs)
+    // --ping COUNT: measure round trips through the daemon instead
+    // --channel-suffix SUFFIX: connect to that instance of the daemon
+    int ping = 0;
+    std::string_view suffix;
+    for (int i = 1; i + 1 < argc; i++) {{
+        if (std::string_view(argv[i]) == "--duration") alarm(static_cast<unsigned>(std::atoi(argv[i + 1])));
+        else if (std::string_view(argv[i]) == "--ping") ping = std::atoi(argv[i + 1]);
+        else if (std::string_view(argv[i]) == "--channel-suffix") suffix = argv[i + 1];
+    }}
+    
+    try {{
+        Shell shell("cpp-client", channel_name(suffix));
+        std::cout << "✅ Connected! ID: " << shell.client_id() << " (libvenom_memory " << Shell::library_version() << ")\n";
+        if (ping > 0) return run_ping(shell, ping);
+        
+        double lat_min = std::numeric_limits<double>::max(), lat_max = 0.0, lat_sum = 0.0;
+        uint64_t lat_count = 0;
+        
+        RawKeys keys;
+        while (g_running) {{
+            handle_keys(keys, shell);
+            if (!shell.daemon_alive()) {{
+                std::cout << "\n❌ Daemon offline! Restart it:\n   cd ../daemon && make run\n";
+                break;
+            }}
+            
+            auto t_start = std::chrono::steady_clock::now();
+            auto state = shell.read_state();
+            auto t_end = std::chrono::steady_clock::now();
+            double latency_us = std::chrono::duration<double, std::micro>(t_end - t_start).count();
+            
+            lat_min = std::min(lat_min, latency_us);
+            lat_max = std::max(lat_max, latency_us);
+            lat_sum += latency_us;
+            lat_count++;
+            
+            if (state.is_valid()) {{
+                std::cout << "\033[2J\033[H";  // Clear screen
+                std::cout << "🐍 {name} (C++) | State v" << state.version << " | " << sizeof(State) << " bytes\n";
+                std::cout << "═══════════════════════════════════════════════════════════════\n";
+{fields}                std::cout << "═══════════════════════════════════════════════════════════════\n";
+                std::cout << "📊 Read Latency: " << std::fixed << std::setprecision(2) << latency_us
+                          << " µs (min: " << lat_min << ", max: " << lat_max << ", avg: " << lat_sum / lat_count << ")\n";
+                std::cout << "📡 Daemon→client: " << shell.write_age_us() << " µs since the write\n";
+                std::cout.unsetf(std::ios::floatfield);
+                std::cout << "⏱️  Daemon interval: " << g_interval_ms << " ms | +/- faster/slower | r refresh\n";
+            }}
+            std::this_thread::sleep_for(std::chrono::milliseconds(100));
+        }}
+        
+        std::cout << "\n\n📊 Final Latency Stats (C++): " << lat_count << " samples, min " << std::fixed << std::setprecision(2)
+                  << lat_min << " µs, max " << lat_max << " µs, avg " << lat_sum / lat_count << " µs\n";
+        std::cout << "\n👋 Goodbye!\n";
+    }} catch (const std::exception& e) {{
+        std::cerr << "❌ Error: " << e.what() << "\n";
+        return 1;
+    }}
+    return 0;
+}}
+"##, name = config.name, ns = ns, fields = fields, keys = COMMAND_KEYS)
+}
+
+/// `make test`: a golden decode that always runs and a live read gated on
+/// `VENOM_LIVE_TEST`
+fn test_state_cpp(config: &ProjectConfig) -> String {
+    format!(r#"/**
+ * {name} protocol tests - run with `make test`
+ *
+ * test_decodes_golden_state decodes a state built from venom.toml, so it
+ * fails when protocol.hpp no longer matches the daemon's layout.
+ * test_reads_live_state needs the daemon running and {live_env}=1.
+ */
+
+#include "../shared/venom.hpp"
+#include <iostream>
+#include <chrono>
+#include <thread>
+#include <cstdlib>
+
+using namespace {ns};
+
+static int g_failed = 0;
+
+#define CHECK(cond) do {{ \
+    if (!(cond)) {{ std::cerr << "  " << __FILE__ << ":" << __LINE__ << ": CHECK(" #cond ") failed\n"; g_failed++; }} \
+}} while (0)
+
+template <typename Test>
+static void run(const char* name, Test test) {{
+    int before = g_failed;
+    try {{
+        test();
+    }} catch (const std::exception& e) {{
+        std::cerr << "  " << e.what() << "\n";
+        g_failed++;
+    }}
+    std::cout << name << " ... " << (g_failed == before ? "ok" : "FAILED") << "\n";
+}}
+
+// Every field holds a distinct known value (see test_decodes_golden_state)
+static const uint8_t GOLDEN[STATE_SIZE] = {{
+{golden}}};
+
+static void test_decodes_golden_state() {{
+    State s;
+    std::memcpy(&s, GOLDEN, sizeof(s));
+{checks}}}
+
+static void test_reads_live_state() {{
+    Shell shell;
+    State s = shell.read_state();
+    // The daemon may not have published yet
+    for (int i = 0; i < 50 && !s.is_valid(); i++) {{
+        std::this_thread::sleep_for(std::chrono::milliseconds(20));
+        s = shell.read_state();
+    }}
+    CHECK(s.magic == MAGIC);
+    CHECK(s.version == 1);
+}}
+
+int main() {{
+    run("test_decodes_golden_state", test_decodes_golden_state);
+    if (std::getenv("{live_env}")) {{
+        run("test_reads_live_state", test_reads_live_state);
+    }} else {{
+        std::cout << "test_reads_live_state ... skipped: set {live_env}=1 with the daemon running\n";
+    }}
+    return g_failed ? 1 : 0;
+}}
+"#,
+        name = config.name,
+        ns = config.names().flat,
+        golden = super::byte_rows(&config.schema.golden_bytes(config.magic), "    "),
+        checks = super::c::golden_checks(config),
+        live_env = super::LIVE_TEST_ENV
+    )
 }
 
 fn client_makefile(config: &ProjectConfig) -> String {
     format!(r#"# {name} Client Makefile (C++)
 
-CXX = g++
-CXXFLAGS = -std=c++17 -Wall -Wextra -O2 -I../shared
-LDFLAGS = -L../lib -lvenom_memory -Wl,-rpath,'$$ORIGIN/../lib'
+{target_var}CXX = g++
+CXXFLAGS = -std=c++17 -Wall -Wextra -O2 -g -I../{root}shared
+LDFLAGS = -L{lib} -lvenom_memory -Wl,-rpath,'$$ORIGIN/{lib}'
 
 TARGET = {name}_client
 SOURCES = src/main.cpp
+TEST = test_state
 
-.PHONY: all clean run
+.PHONY: all clean run test
 
 all: $(TARGET)
 
@@ -506,12 +1027,25 @@ $(TARGET): $(SOURCES)
 	@$(CXX) $(CXXFLAGS) $(SOURCES) -o $(TARGET) $(LDFLAGS)
 	@echo "✅ Build complete"
 
+$(TEST): src/test_state.cpp
+	@$(CXX) $(CXXFLAGS) $< -o $@ $(LDFLAGS)
+
 clean:
-	@rm -f $(TARGET)
+	@rm -f $(TARGET) $(TEST)
 
 run: $(TARGET)
-	@./$(TARGET)
-"#, name = config.name)
+	@./$(TARGET) $(ARGS)
+
+# {live_env}=1 make test also reads the running daemon
+test: $(TEST)
+	@./$(TEST)
+"#,
+        name = config.name,
+        target_var = super::make_target_var(config),
+        root = config.root,
+        lib = super::make_lib_dir(config, &format!("../{}", config.at_root("lib"))),
+        live_env = super::LIVE_TEST_ENV,
+    )
 }
 
 fn readme(config: &ProjectConfig) -> String {
@@ -529,6 +1063,12 @@ cd daemon && make run
 cd client && make run
 ```
 
+## Tests
+
+`cd client && make test` decodes a known state built from `venom.toml`, so it
+fails when `shared/protocol.hpp` no longer matches the daemon's layout. With
+the daemon running, `{live_env}=1 make test` also reads the live channel.
+{ping}{instances}
 ## Features
 
 - Modern C++17
@@ -542,9 +1082,13 @@ cd client && make run
 |---------|-------|
 | Channel | `{channel}` |
 | Magic | `0x{magic:08X}` |
-"#,
+{service}"#,
         name = config.name,
+        service = super::service::readme_section(config),
+        live_env = super::LIVE_TEST_ENV,
+        ping = super::ping_readme_section(&format!("cd client && ./{}_client --ping 10", config.name)),
+        instances = super::instances_readme_section(&config.channel, "cd daemon && make run ARGS=\"--channel-suffix SUFFIX\"", "cd client && make run ARGS=\"--channel-suffix SUFFIX\"", &format!("`{}::channel_name()` in `shared/protocol.hpp`", config.names().flat)),
         channel = config.channel,
-        magic = magic(&config.channel)
+        magic = config.magic
     )
 }
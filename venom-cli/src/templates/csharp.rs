@@ -0,0 +1,934 @@
+//! C#/.NET Templates for VenomMemory projects
+//!
+//! Generates a complete .NET console project with:
+//! - {Name}.csproj - SDK-style project (net8.0, unsafe blocks enabled)
+//! - VenomBinding.cs - P/Invoke bindings, {Name}State struct and VenomShell
+//! - Program.cs - Status bar client with read latency benchmarking
+//! - native/libvenom_memory.so - Bundled library (resolved at runtime)
+//! - daemon/ - Self-contained C daemon
+//! - README.md with usage instructions
+
+use super::ProjectConfig;
+use crate::schema::{self, FieldType};
+
+pub fn generate(config: &ProjectConfig) {
+    let base = &config.output_dir;
+    
+    // .NET project structure:
+    // - project root for .csproj and sources
+    // - native/ for the bundled .so library
+    crate::create_dir(&format!("{}/daemon/src", base));
+    
+    // C# client files
+    generate_client(config);
+    
+    // C Daemon files (so the C# project is self-contained)
+    crate::write_file(&format!("{}/daemon/src/main.c", base), &daemon_c(config));
+    crate::write_file(&format!("{}/daemon/Makefile", base), &daemon_makefile(config));
+    crate::write_file(&format!("{}/daemon/protocol.h", base), &protocol_h(config));
+    
+    crate::write_file(&format!("{}/README.md", base), &readme(config));
+    
+    // Also copy the library to daemon/ (for the C daemon)
+    crate::library::install(&format!("{}/daemon", base), &config.targets);
+}
+
+/// Client files only (used by `venom add-client`), plus the library in native/ for DllImport
+pub fn generate_client(config: &ProjectConfig) {
+    let base = &config.output_dir;
+    let pascal = config.names().pascal;
+    
+    crate::write_file(&format!("{}/{}.csproj", base, pascal), &csproj(config));
+    crate::write_file(&format!("{}/VenomBinding.cs", base), &venom_binding(config));
+    crate::write_file(&format!("{}/Program.cs", base), &program_cs(config));
+    
+    crate::library::install(&format!("{}/native", base), &config.targets);
+}
+
+
+
+// ═══════════════════════════════════════════════════════════════════════════
+// C Daemon (so the C# project is self-contained)
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn protocol_h(config: &ProjectConfig) -> String {
+    let upper = config.names().upper;
+    let pascal = config.names().pascal;
+    
+    format!(r#"#ifndef {upper}_PROTOCOL_H
+#define {upper}_PROTOCOL_H
+
+#include <stdint.h>
+#define {upper}_CHANNEL_NAME "{channel}"
+#define {upper}_MAGIC 0x{magic:08X}
+#define {upper}_STATE_SIZE {state_size}
+{max_cores}
+typedef struct __attribute__((packed)) {{
+{fields}}} {pascal}State;
+
+_Static_assert(sizeof({pascal}State) == {upper}_STATE_SIZE, "{pascal}State size mismatch");
+
+{commands}
+#endif
+"#,
+        upper = upper,
+        pascal = pascal,
+        channel = config.channel,
+        magic = config.magic,
+        state_size = config.schema.size,
+        max_cores = super::c::max_cores_define(config),
+        fields = config.schema.c_fields("    "),
+        commands = super::c::commands_h(config),
+    )
+}
+
+fn daemon_c(config: &ProjectConfig) -> String {
+    if !config.schema.has_demo_fields() {
+        return super::c::generic_daemon_c(config, "../protocol.h");
+    }
+    let upper = config.names().upper;
+    let pascal = config.names().pascal;
+    
+    format!(r#"/* {name} Daemon - VenomMemory */
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <signal.h>
+#include <unistd.h>
+#include <time.h>
+#include "../protocol.h"
+
+typedef struct VenomDaemonHandle VenomDaemonHandle;
+typedef struct {{ size_t struct_size; size_t data_size; size_t cmd_slots; size_t max_clients; size_t cmd_payload_size; size_t cmd_full_policy; size_t shm_mode; }} VenomConfigEx;
+extern VenomDaemonHandle* venom_daemon_create_ex(const char* name, const VenomConfigEx* config);
+extern void venom_daemon_destroy(VenomDaemonHandle* handle);
+extern void venom_daemon_write_data(VenomDaemonHandle* handle, const uint8_t* data, size_t len);
+extern size_t venom_daemon_try_recv_command(VenomDaemonHandle* handle, uint8_t* buf, size_t max_len, uint32_t* out_client_id);
+
+static VenomDaemonHandle* g_daemon = NULL;
+static {pascal}State g_state = {{0}};
+static volatile int g_running = 1;
+static uint64_t prev_total[{upper}_MAX_CORES + 1] = {{0}};
+static uint64_t prev_idle[{upper}_MAX_CORES + 1] = {{0}};
+
+static void signal_handler(int sig) {{ (void)sig; g_running = 0; }}
+
+{commands}
+{channel_arg}
+static void read_cpu(void) {{
+    FILE* f = fopen("/proc/stat", "r");
+    if (!f) return;
+    char line[256];
+    int idx = 0;
+    while (fgets(line, sizeof(line), f) && idx <= {upper}_MAX_CORES) {{
+        if (strncmp(line, "cpu", 3) != 0) continue;
+        uint64_t user, nice, system, idle, iowait, irq, softirq;
+        if (sscanf(line + (line[3] == ' ' ? 4 : 5), "%lu %lu %lu %lu %lu %lu %lu",
+                   &user, &nice, &system, &idle, &iowait, &irq, &softirq) != 7) continue;
+        uint64_t total = user + nice + system + idle + iowait + irq + softirq;
+        uint64_t idle_t = idle + iowait;
+        uint64_t td = total - prev_total[idx], id = idle_t - prev_idle[idx];
+        float usage = td > 0 ? (1.0f - (float)id / (float)td) * 100.0f : 0;
+        if (line[3] == ' ') g_state.cpu_usage_percent = usage;
+        else if (idx > 0 && idx <= {upper}_MAX_CORES) g_state.cpu_cores[idx-1] = usage;
+        prev_total[idx] = total; prev_idle[idx] = idle_t; idx++;
+    }}
+    g_state.core_count = idx > 1 ? idx - 1 : 0;
+    fclose(f);
+}}
+
+static void read_mem(void) {{
+    FILE* f = fopen("/proc/meminfo", "r");
+    if (!f) return;
+    char line[256];
+    uint64_t total = 0, avail = 0;
+    while (fgets(line, sizeof(line), f)) {{
+        if (strncmp(line, "MemTotal:", 9) == 0) sscanf(line + 9, "%lu", &total);
+        else if (strncmp(line, "MemAvailable:", 13) == 0) sscanf(line + 13, "%lu", &avail);
+    }}
+    g_state.memory_total_mb = (uint32_t)(total / 1024);
+    g_state.memory_used_mb = (uint32_t)((total - avail) / 1024);
+    fclose(f);
+}}
+
+static void read_uptime(void) {{
+    FILE* f = fopen("/proc/uptime", "r");
+    if (!f) return;
+    double up; if (fscanf(f, "%lf", &up) == 1) g_state.uptime_seconds = (uint64_t)up;
+    fclose(f);
+}}
+
+int main(int argc, char** argv) {{
+    printf("🖥️  {name} Daemon (VenomMemory)\\n");
+    printf("═══════════════════════════════════════════════════════════════\\n");
+    signal(SIGINT, signal_handler); signal(SIGTERM, signal_handler);
+    
+    VenomConfigEx cfg = {{ .struct_size = sizeof(VenomConfigEx), .data_size = 16384, .cmd_slots = 32, .max_clients = 16, .cmd_payload_size = sizeof({pascal}Command), .shm_mode = 0{shm_mode:o} }};
+    const char* channel = channel_from_args(argc, argv);
+    g_daemon = venom_daemon_create_ex(channel, &cfg);
+    if (!g_daemon) {{ printf("❌ Failed to create channel\\n"); return 1; }}
+    
+    printf("✅ Channel: %s\\n🚀 Publishing... (Ctrl+C to stop)\\n\\n", channel);
+    
+    uint64_t next_publish = 0;
+    while (g_running) {{
+        int refresh = handle_commands(g_daemon);
+        if (!refresh && now_ms() < next_publish) {{ usleep(10000); continue; }}
+        next_publish = now_ms() + g_interval_ms;
+        
+        read_cpu(); read_mem(); read_uptime();
+        g_state.magic = {upper}_MAGIC; g_state.version = 1; g_state.update_counter++;
+        struct timespec ts; clock_gettime(CLOCK_MONOTONIC, &ts);
+        g_state.timestamp_ns = (uint64_t)ts.tv_sec * 1000000000ULL + ts.tv_nsec;
+        publish(g_daemon, &g_state);
+        printf("\\r🖥️  CPU: %5.1f%% | RAM: %u/%u MB | #%lu | %u ms   ",
+            g_state.cpu_usage_percent, g_state.memory_used_mb, g_state.memory_total_mb,
+            (unsigned long)g_state.update_counter, g_interval_ms);
+        fflush(stdout);
+    }}
+    venom_daemon_destroy(g_daemon);
+    printf("\\n\\n👋 Goodbye!\\n");
+    return 0;
+}}
+"#, name = config.name, upper = upper, pascal = pascal, shm_mode = config.shm_mode, commands = super::c::command_handler_c(config), channel_arg = super::c::channel_arg_c(config))
+}
+
+fn daemon_makefile(config: &ProjectConfig) -> String {
+    format!(r#"# {name} Daemon Makefile
+
+{target_var}CC = gcc
+CFLAGS = -Wall -Wextra -O2
+LDFLAGS = -L.{sub} -lvenom_memory -Wl,-rpath,'$$ORIGIN{sub}'
+
+TARGET = {name}_daemon
+
+.PHONY: all clean run
+
+all: $(TARGET)
+
+$(TARGET): src/main.c
+	@echo "🔗 Building $(TARGET)..."
+	@$(CC) $(CFLAGS) src/main.c -o $(TARGET) $(LDFLAGS)
+	@echo "✅ Build complete"
+
+clean:
+	@rm -f $(TARGET)
+
+run: $(TARGET)
+	@./$(TARGET) $(ARGS)
+"#,
+        name = config.name,
+        target_var = super::make_target_var(config),
+        sub = super::make_lib_dir(config, ""),
+    )
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// C# Client
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn csproj(config: &ProjectConfig) -> String {
+    let pascal = config.names().pascal;
+    
+    format!(r#"<Project Sdk="Microsoft.NET.Sdk">
+
+  <PropertyGroup>
+    <OutputType>Exe</OutputType>
+    <TargetFramework>net8.0</TargetFramework>
+    <ImplicitUsings>enable</ImplicitUsings>
+    <Nullable>enable</Nullable>
+    <AllowUnsafeBlocks>true</AllowUnsafeBlocks>
+    <RootNamespace>{pascal}</RootNamespace>
+    <AssemblyName>{name}</AssemblyName>
+  </PropertyGroup>
+
+  <ItemGroup>
+    <!-- Resolved at runtime by the DllImportResolver in VenomBinding.cs -->
+    <None Include="{native}" CopyToOutputDirectory="PreserveNewest" />
+  </ItemGroup>
+
+</Project>
+"#,
+        name = config.name,
+        pascal = pascal,
+        native = if config.targets.is_empty() { "native/libvenom_memory.so" } else { "native/**" },
+    )
+}
+
+fn venom_binding(config: &ProjectConfig) -> String {
+    let pascal = config.names().pascal;
+    let schema = &config.schema;
+    
+    let max_cores = match schema.field("cpu_cores").and_then(|f| f.len) {
+        Some(n) if schema.has_demo_fields() => format!("    public const int MaxCores = {};\n", n),
+        _ => String::new(),
+    };
+    
+    let mut fields = String::new();
+    for f in &schema.fields {
+        let (access, name) = if f.padding {
+            ("private", f.name.clone())
+        } else {
+            ("public", schema::pascal_case(&f.name))
+        };
+        let line = match f.len {
+            Some(n) => format!("    {} fixed {} {}[{}];\n", access, cs_type(f.ty), name, n),
+            None => format!("    {} {} {};\n", access, cs_type(f.ty), name),
+        };
+        fields.push_str(&line);
+    }
+    
+    // With several bundled targets each native/<triple>/ copy is tried, and
+    // the first one that loads was built for this machine
+    let (locations, probe, library_location) = if config.targets.is_empty() {
+        (
+            r#"            // Next to the built assembly (copied by the .csproj)
+            Path.Combine(AppContext.BaseDirectory, "native", "libvenom_memory.so"),
+            // Project directory (dotnet run)
+            Path.Combine(Directory.GetCurrentDirectory(), "native", "libvenom_memory.so"),
+            // Absolute fallback
+            "/usr/local/lib/libvenom_memory.so",
+            "/usr/lib/libvenom_memory.so",
+"#.to_string(),
+            "            if (File.Exists(path)) return NativeLibrary.Load(path);\n",
+            "native/libvenom_memory.so",
+        )
+    } else {
+        let mut locations = String::new();
+        for t in &config.targets {
+            let file = crate::library::library_name_for(t);
+            locations.push_str(&format!(
+                "            Path.Combine(AppContext.BaseDirectory, \"native\", \"{t}\", \"{file}\"),\n            \
+                 Path.Combine(Directory.GetCurrentDirectory(), \"native\", \"{t}\", \"{file}\"),\n",
+                t = t,
+                file = file
+            ));
+        }
+        (
+            locations,
+            "            // A library built for another target fails to load\n            \
+             if (File.Exists(path) && NativeLibrary.TryLoad(path, out var handle)) return handle;\n",
+            "native/<triple>/ for this machine",
+        )
+    };
+
+    format!(r#"// VenomMemory P/Invoke Bindings for {name}
+//
+// Provides:
+// - {pascal}State: State from daemon ({state_size}-byte packed layout, see venom.toml)
+// - VenomShell: Connection to daemon
+//
+// Library location: {library_location}
+
+using System.Buffers.Binary;
+using System.Diagnostics;
+using System.Reflection;
+using System.Runtime.CompilerServices;
+using System.Runtime.InteropServices;
+
+namespace {pascal};
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Configuration
+// ═══════════════════════════════════════════════════════════════════════════
+
+public static class Protocol
+{{
+    public const string ChannelName = "{channel}";
+    public const uint Magic = 0x{magic:08X};
+{max_cores}    public const int StateSize = {state_size};
+    public const int IntervalMs = 100;
+    public const int CommandSize = 8;
+    /// <summary>Reply to the last ping: client ID and sequence number, both u32</summary>
+    public const int PongSize = 8;
+
+    /// <summary>The channel of one of several instances: "{channel}-SUFFIX", or ChannelName itself for no suffix</summary>
+    public static string InstanceChannel(string? suffix) =>
+        string.IsNullOrEmpty(suffix) ? ChannelName : $"{{ChannelName}}-{{suffix}}";
+}}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// 📨 Commands (Clients → Daemon), see CmdType in daemon/protocol.h
+// ═══════════════════════════════════════════════════════════════════════════
+
+public enum CmdType : ushort
+{{
+    /// <summary>Publish now</summary>
+    Refresh = 1,
+    /// <summary>Value = publish interval in ms (10-10000)</summary>
+    SetInterval = 2,
+    /// <summary>Value = sequence number, echoed back in a pong after the state</summary>
+    Ping = 3,
+}}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// State Structure (matches C struct layout exactly, generated from venom.toml)
+// ═══════════════════════════════════════════════════════════════════════════
+
+[StructLayout(LayoutKind.Sequential, Pack = 1)]
+public unsafe struct {pascal}State
+{{
+{fields}
+    /// <summary>Reinterpret raw bytes as state (must match C struct layout)</summary>
+    public static {pascal}State FromBytes(ReadOnlySpan<byte> bytes) =>
+        bytes.Length < Protocol.StateSize ? default : MemoryMarshal.Read<{pascal}State>(bytes);
+
+    public bool IsValid => Magic == Protocol.Magic;
+{helpers}}}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Native Functions
+// ═══════════════════════════════════════════════════════════════════════════
+
+internal static unsafe class Native
+{{
+    private const string Lib = "venom_memory";
+
+    [DllImport(Lib)]
+    public static extern IntPtr venom_shell_connect([MarshalAs(UnmanagedType.LPUTF8Str)] string name);
+
+    [DllImport(Lib)]
+    public static extern void venom_shell_destroy(IntPtr handle);
+
+    [DllImport(Lib)]
+    public static extern nuint venom_shell_read_data_stamped(IntPtr handle, byte* buf, nuint maxLen, out ulong timestampNs);
+
+    [DllImport(Lib)]
+    public static extern ulong venom_clock_ns();
+
+    [DllImport(Lib)]
+    public static extern IntPtr venom_version();
+
+    [DllImport(Lib)]
+    public static extern uint venom_shell_id(IntPtr handle);
+
+    [DllImport(Lib)]
+    [return: MarshalAs(UnmanagedType.U1)]
+    public static extern bool venom_shell_send_command(IntPtr handle, byte* cmd, nuint len);
+
+    /// <summary>Point "venom_memory" at {library_location} before any P/Invoke runs</summary>
+    [ModuleInitializer]
+    internal static void Init()
+    {{
+        NativeLibrary.SetDllImportResolver(typeof(Native).Assembly, Resolve);
+    }}
+
+    private static IntPtr Resolve(string name, Assembly assembly, DllImportSearchPath? searchPath)
+    {{
+        if (name != Lib) return IntPtr.Zero;
+
+        // List of possible locations to search
+        string[] locations =
+        {{
+{locations}        }};
+
+        foreach (var path in locations)
+        {{
+{probe}        }}
+
+        // Fall back to the default probing (LD_LIBRARY_PATH etc.)
+        return IntPtr.Zero;
+    }}
+}}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// VenomShell - Connection to VenomMemory Daemon
+// ═══════════════════════════════════════════════════════════════════════════
+
+public sealed unsafe class VenomShell : IDisposable
+{{
+    private IntPtr _handle;
+
+    static VenomShell()
+    {{
+        if (sizeof({pascal}State) != Protocol.StateSize)
+            throw new InvalidOperationException($"{pascal}State is {{sizeof({pascal}State)}} bytes, expected {{Protocol.StateSize}}");
+    }}
+
+    public VenomShell(string channel = Protocol.ChannelName)
+    {{
+        _handle = Native.venom_shell_connect(channel);
+        if (_handle == IntPtr.Zero)
+            throw new InvalidOperationException($"Failed to connect to channel \"{{channel}}\". Is the daemon running?");
+    }}
+
+    private IntPtr Handle =>
+        _handle != IntPtr.Zero ? _handle : throw new ObjectDisposedException(nameof(VenomShell));
+
+    /// <summary>Client ID assigned by the daemon</summary>
+    public uint ClientId => Native.venom_shell_id(Handle);
+
+    /// <summary>Version of the libvenom_memory that was loaded</summary>
+    public static string LibraryVersion => Marshal.PtrToStringAnsi(Native.venom_version()) ?? "";
+
+    /// <summary>µs between the daemon's write and the last read (0 if unstamped)</summary>
+    public double WriteAgeUs {{ get; private set; }}
+
+    /// <summary>Read raw data into <paramref name="buf"/>; returns the full data length</summary>
+    public int ReadRawData(Span<byte> buf)
+    {{
+        fixed (byte* p = buf)
+        {{
+            var len = (int)Native.venom_shell_read_data_stamped(Handle, p, (nuint)buf.Length, out var writtenNs);
+            if (writtenNs != 0)
+                WriteAgeUs = (Native.venom_clock_ns() - writtenNs) / 1000.0;
+            return len;
+        }}
+    }}
+
+    /// <summary>Read and parse state from daemon</summary>
+    public {pascal}State ReadState()
+    {{
+        Span<byte> buf = stackalloc byte[Protocol.StateSize];
+        var len = ReadRawData(buf);
+        return len < Protocol.StateSize ? default : {pascal}State.FromBytes(buf);
+    }}
+
+    /// <summary>Queue a command for the daemon; false if the queue is full</summary>
+    public bool SendCommand(ReadOnlySpan<byte> cmd)
+    {{
+        fixed (byte* p = cmd)
+        {{
+            return Native.venom_shell_send_command(Handle, p, (nuint)cmd.Length);
+        }}
+    }}
+
+    /// <summary>Send a Command, a typed command: cmd as the tag, then the
+    /// payload's length (4) and value, all little-endian</summary>
+    public bool SendCommand(CmdType cmd, int value = 0)
+    {{
+        Span<byte> buf = stackalloc byte[Protocol.CommandSize];
+        BinaryPrimitives.WriteUInt16LittleEndian(buf, (ushort)cmd);
+        BinaryPrimitives.WriteUInt16LittleEndian(buf[2..], sizeof(int));
+        BinaryPrimitives.WriteInt32LittleEndian(buf[4..], value);
+        return SendCommand(buf);
+    }}
+
+    /// <summary>Ask the daemon to publish every <paramref name="ms"/> milliseconds</summary>
+    public bool SetInterval(int ms) => SendCommand(CmdType.SetInterval, ms);
+
+    /// <summary>Ask the daemon to publish right away</summary>
+    public bool Refresh() => SendCommand(CmdType.Refresh);
+
+    /// <summary>
+    /// Round trip of a ping carrying <paramref name="seq"/> in µs, or null if no pong came back within a second
+    /// </summary>
+    /// <remarks>
+    /// The daemon answers by publishing at once with a pong after the state. It keeps
+    /// only the last one, so clients pinging at the same time can miss their reply.
+    /// </remarks>
+    public double? Ping(uint seq)
+    {{
+        var start = Stopwatch.GetTimestamp();
+        if (!SendCommand(CmdType.Ping, unchecked((int)seq)))
+            return null;
+        Span<byte> buf = stackalloc byte[Protocol.StateSize + Protocol.PongSize];
+        while (Stopwatch.GetElapsedTime(start).TotalSeconds < 1)
+        {{
+            if (ReadRawData(buf) < buf.Length)
+                continue;
+            var pong = buf[Protocol.StateSize..];
+            if (BinaryPrimitives.ReadUInt32LittleEndian(pong) == ClientId &&
+                BinaryPrimitives.ReadUInt32LittleEndian(pong[4..]) == seq)
+                return Stopwatch.GetElapsedTime(start).TotalMicroseconds;
+        }}
+        return null;
+    }}
+
+    public void Dispose()
+    {{
+        if (_handle != IntPtr.Zero)
+        {{
+            Native.venom_shell_destroy(_handle);
+            _handle = IntPtr.Zero;
+        }}
+        GC.SuppressFinalize(this);
+    }}
+
+    ~VenomShell() => Dispose();
+}}
+"#,
+        name = config.name,
+        channel = config.channel,
+        magic = config.magic,
+        pascal = pascal,
+        state_size = schema.size,
+        max_cores = max_cores,
+        fields = fields,
+        helpers = if schema.has_demo_fields() { DEMO_HELPERS } else { "" },
+        locations = locations,
+        probe = probe,
+        library_location = library_location,
+    )
+}
+
+/// Members used by the system-monitor demo
+const DEMO_HELPERS: &str = r#"
+    public float Core(int i) => (uint)i < Protocol.MaxCores ? CpuCores[i] : 0f;
+
+    public double MemoryUsagePercent =>
+        MemoryTotalMb > 0 ? MemoryUsedMb * 100.0 / MemoryTotalMb : 0;
+
+    public string UptimeFormatted => $"{UptimeSeconds / 3600}h {UptimeSeconds % 3600 / 60}m";
+"#;
+
+fn cs_type(ty: FieldType) -> &'static str {
+    match ty {
+        FieldType::U8 => "byte",
+        FieldType::U16 => "ushort",
+        FieldType::U32 => "uint",
+        FieldType::U64 => "ulong",
+        FieldType::I8 => "sbyte",
+        FieldType::I16 => "short",
+        FieldType::I32 => "int",
+        FieldType::I64 => "long",
+        FieldType::F32 => "float",
+        FieldType::F64 => "double",
+    }
+}
+
+/// Keyboard handling shared by both clients: +/- change the daemon's
+/// publish interval and r asks for an immediate publish; `--ping COUNT`
+/// times round trips instead of showing the state and `--channel-suffix
+/// SUFFIX` picks the daemon instance
+const COMMAND_KEYS: &str = r#"
+// ⌨️  +/- faster/slower, r refresh; returns the interval the daemon was asked for
+static int HandleKeys(VenomShell shell, int intervalMs)
+{
+    while (!Console.IsInputRedirected && Console.KeyAvailable)
+    {
+        switch (Console.ReadKey(intercept: true).KeyChar)
+        {
+            case '+' or '=':
+                intervalMs = Math.Max(10, intervalMs / 2);
+                shell.SetInterval(intervalMs);
+                break;
+            case '-':
+                intervalMs = Math.Min(10000, intervalMs * 2);
+                shell.SetInterval(intervalMs);
+                break;
+            case 'r':
+                shell.Refresh();
+                break;
+        }
+    }
+    return intervalMs;
+}
+
+// --ping COUNT from the command line, or 0
+static int PingArg(string[] args)
+{
+    var i = Array.IndexOf(args, "--ping");
+    return i >= 0 && i + 1 < args.Length && int.TryParse(args[i + 1], out var count) ? count : 0;
+}
+
+// --channel-suffix SUFFIX from the command line: the daemon instance to connect to
+static string ChannelArg(string[] args)
+{
+    var i = Array.IndexOf(args, "--channel-suffix");
+    return Protocol.InstanceChannel(i >= 0 && i + 1 < args.Length ? args[i + 1] : null);
+}
+
+// 🏓 Time count round trips through the daemon; returns the exit code (1 if none came back)
+static int RunPing(VenomShell shell, int count)
+{
+    // A pong for an earlier client with the same ID may still be published
+    var seq = unchecked((uint)Environment.TickCount64);
+    var rtts = new List<double>();
+    for (var i = 1; i <= count; i++)
+    {
+        if (shell.Ping(++seq) is not double rttUs)
+        {
+            Console.WriteLine($"⚠️  No reply to ping #{i}");
+            continue;
+        }
+        Console.WriteLine($"🏓 Pong #{i}: {rttUs:F1} µs");
+        rtts.Add(rttUs);
+        Thread.Sleep(100);
+    }
+    if (rtts.Count == 0)
+        return 1;
+    Console.WriteLine($"📊 Round trip: {rtts.Count}/{count} replies, min {rtts.Min():F1} µs, avg {rtts.Average():F1} µs, max {rtts.Max():F1} µs");
+    return 0;
+}
+"#;
+
+fn program_cs(config: &ProjectConfig) -> String {
+    if !config.schema.has_demo_fields() {
+        return generic_program_cs(config);
+    }
+    let pascal = config.names().pascal;
+    
+    format!(r#"// {name} - VenomMemory Client Example - with Benchmarking
+//
+// Demonstrates connecting to daemon and reading system stats.
+// Includes read latency measurements.
+
+using System.Diagnostics;
+using {pascal};
+
+// ANSI colors
+const string Cyan = "\x1B[96m";
+const string Reset = "\x1B[0m";
+
+Console.WriteLine("🖥️  {name} Client (C#/.NET)");
+Console.WriteLine("═══════════════════════════════════════════════════════════════");
+
+// Latency tracking
+var latencyMin = double.MaxValue;
+var latencyMax = 0.0;
+var latencySum = 0.0;
+var latencyCount = 0;
+var frame = 0;
+var intervalMs = Protocol.IntervalMs;
+
+// Handle Ctrl+C for final stats
+var running = true;
+Console.CancelKeyPress += (_, e) =>
+{{
+    e.Cancel = true;
+    running = false;
+}};
+
+try
+{{
+    using var shell = new VenomShell(ChannelArg(args));
+    Console.WriteLine($"✅ Connected! Client ID: {{shell.ClientId}} (libvenom_memory {{VenomShell.LibraryVersion}})");
+    if (PingArg(args) > 0)
+        return RunPing(shell, PingArg(args));
+    Console.WriteLine("📊 Reading system stats... (Ctrl+C to exit)\n");
+
+    while (running)
+    {{
+        intervalMs = HandleKeys(shell, intervalMs);
+
+        // ═══════════════════════════════════════════════════════════════════
+        // 📊 BENCHMARK: Measure read latency
+        // ═══════════════════════════════════════════════════════════════════
+        var start = Stopwatch.GetTimestamp();
+        var state = shell.ReadState();
+        var latencyUs = Stopwatch.GetElapsedTime(start).TotalMicroseconds;
+
+        // Update stats
+        if (latencyUs < latencyMin) latencyMin = latencyUs;
+        if (latencyUs > latencyMax) latencyMax = latencyUs;
+        latencySum += latencyUs;
+        latencyCount++;
+        var avgUs = latencySum / latencyCount;
+
+        if (state.IsValid)
+        {{
+            // Clear screen and move cursor to top
+            Console.Write("\x1B[2J\x1B[H");
+
+            Console.WriteLine("╔═══════════════════════════════════════════════════════════════╗");
+            Console.WriteLine($"║  🖥️  {name} Monitor (C#)    Frame: {{frame,-6}}              ║");
+            Console.WriteLine("╠═══════════════════════════════════════════════════════════════╣");
+            Console.WriteLine($"║  CPU: {{state.CpuUsagePercent,5:F1}}%  |  " +
+                              $"RAM: {{state.MemoryUsedMb}}/{{state.MemoryTotalMb}} MB  |  " +
+                              $"Uptime: {{state.UptimeFormatted}}  ║");
+            Console.WriteLine("╠═══════════════════════════════════════════════════════════════╣");
+
+            // Show per-core usage (all cores)
+            for (var i = 0; i < state.CoreCount; i++)
+            {{
+                Console.WriteLine($"║  Core {{i}}: {{state.Core(i),5:F1}}%                                                ║");
+            }}
+
+            Console.WriteLine("╠═══════════════════════════════════════════════════════════════╣");
+            Console.WriteLine($"║  Memory: {{state.MemoryUsagePercent:F1}}% used                                           ║");
+            Console.WriteLine("╠═══════════════════════════════════════════════════════════════╣");
+            Console.WriteLine($"║  📊 {{Cyan}}Read Latency:{{Reset}} {{latencyUs:F2}} µs (min: {{latencyMin:F2}}, max: {{latencyMax:F2}}, avg: {{avgUs:F2}})  ║");
+            Console.WriteLine($"║  📡 {{Cyan}}Daemon→client:{{Reset}} {{shell.WriteAgeUs:F2}} µs since the write                 ║");
+            Console.WriteLine("╚═══════════════════════════════════════════════════════════════╝");
+            Console.WriteLine($"  Updates: {{state.UpdateCounter}} | Interval: {{intervalMs}} ms");
+            Console.WriteLine("  +/- faster/slower | r refresh | Ctrl+C to exit");
+            frame++;
+        }}
+        else
+        {{
+            Console.WriteLine("⏳ Waiting for valid data from daemon...");
+        }}
+
+        Thread.Sleep(100);
+    }}
+}}
+catch (Exception e) when (e is InvalidOperationException or DllNotFoundException)
+{{
+    Console.WriteLine($"❌ Error: {{e.Message}}");
+    Console.WriteLine("\nMake sure:");
+    Console.WriteLine("  1. The daemon is running");
+    Console.WriteLine("  2. native/libvenom_memory.so exists");
+    return 1;
+}}
+
+Console.WriteLine("\n");
+Console.WriteLine($"📊 {{Cyan}}Final Latency Stats (C#/.NET):{{Reset}}");
+Console.WriteLine($"   Samples: {{latencyCount}}");
+Console.WriteLine($"   Min: {{latencyMin:F2}} µs");
+Console.WriteLine($"   Max: {{latencyMax:F2}} µs");
+Console.WriteLine($"   Avg: {{latencySum / latencyCount:F2}} µs");
+Console.WriteLine("\n👋 Goodbye!");
+return 0;
+{keys}"#, name = config.name, pascal = pascal, keys = COMMAND_KEYS)
+}
+
+/// Client for a custom schema: prints every field of the state
+fn generic_program_cs(config: &ProjectConfig) -> String {
+    let fields: String = config.schema.user_fields()
+        .map(|f| {
+            let name = schema::pascal_case(&f.name);
+            let value = match f.len {
+                Some(n) => format!(
+                    "{{string.Join(\", \", new ReadOnlySpan<{}>(state.{}, {}).ToArray())}}",
+                    cs_type(f.ty), name, n
+                ),
+                None => format!("{{state.{}}}", name),
+            };
+            format!("                Console.WriteLine($\"  {:<24} {}\");\n", f.name, value)
+        })
+        .collect();
+    
+    format!(r#"// {name} - VenomMemory Client - prints every field of the state (see venom.toml)
+
+using System.Diagnostics;
+using {pascal};
+
+Console.WriteLine("🖥️  {name} Client (C#/.NET)");
+Console.WriteLine("═══════════════════════════════════════════════════════════════");
+
+var latencyMin = double.MaxValue;
+var latencyMax = 0.0;
+var latencySum = 0.0;
+var latencyCount = 0;
+var intervalMs = Protocol.IntervalMs;
+
+var running = true;
+Console.CancelKeyPress += (_, e) =>
+{{
+    e.Cancel = true;
+    running = false;
+}};
+
+try
+{{
+    using var shell = new VenomShell(ChannelArg(args));
+    Console.WriteLine($"✅ Connected! Client ID: {{shell.ClientId}} (libvenom_memory {{VenomShell.LibraryVersion}})");
+    if (PingArg(args) > 0)
+        return RunPing(shell, PingArg(args));
+
+    while (running)
+    {{
+        intervalMs = HandleKeys(shell, intervalMs);
+
+        var start = Stopwatch.GetTimestamp();
+        var state = shell.ReadState();
+        var latencyUs = Stopwatch.GetElapsedTime(start).TotalMicroseconds;
+
+        if (latencyUs < latencyMin) latencyMin = latencyUs;
+        if (latencyUs > latencyMax) latencyMax = latencyUs;
+        latencySum += latencyUs;
+        latencyCount++;
+
+        if (state.IsValid)
+        {{
+            Console.Write("\x1B[2J\x1B[H");
+            Console.WriteLine($"🖥️  {name} | State v{{state.Version}} | {{Protocol.StateSize}} bytes");
+            Console.WriteLine("═══════════════════════════════════════════════════════════════");
+            unsafe
+            {{
+{fields}            }}
+            Console.WriteLine("═══════════════════════════════════════════════════════════════");
+            Console.WriteLine($"📊 Read Latency: {{latencyUs:F2}} µs (min: {{latencyMin:F2}}, max: {{latencyMax:F2}}, avg: {{latencySum / latencyCount:F2}})");
+            Console.WriteLine($"📡 Daemon→client: {{shell.WriteAgeUs:F2}} µs since the write");
+            Console.WriteLine($"⏱️  Daemon interval: {{intervalMs}} ms | +/- faster/slower | r refresh");
+        }}
+        else
+        {{
+            Console.WriteLine("⏳ Waiting for valid data from daemon...");
+        }}
+
+        Thread.Sleep(100);
+    }}
+}}
+catch (Exception e) when (e is InvalidOperationException or DllNotFoundException)
+{{
+    Console.WriteLine($"❌ Error: {{e.Message}}");
+    return 1;
+}}
+
+return 0;
+{keys}"#, name = config.name, pascal = config.names().pascal, fields = fields, keys = COMMAND_KEYS)
+}
+
+fn readme(config: &ProjectConfig) -> String {
+    let pascal = config.names().pascal;
+    format!(r#"# {name} (C#/.NET)
+
+VenomMemory .NET client for real-time system monitoring.
+
+## Project Structure
+
+```
+{name}/
+├── {pascal}.csproj
+├── VenomBinding.cs          # P/Invoke bindings & {pascal}State
+├── Program.cs               # Example client
+├── native/
+│   └── libvenom_memory.so   # Bundled VenomMemory library
+└── daemon/                  # C daemon (system monitor)
+    ├── src/main.c
+    └── Makefile
+```
+
+## Quick Start
+
+```bash
+# Terminal 1 - Start C daemon
+cd daemon && make run
+
+# Terminal 2 - Run the .NET client (requires .NET 8 SDK)
+dotnet run
+```
+
+## Usage in Your Code
+
+```csharp
+using {pascal};
+
+using var shell = new VenomShell();
+Console.WriteLine($"Connected! ID: {{shell.ClientId}}");
+
+// Read system stats
+var state = shell.ReadState();
+if (state.IsValid)
+{{
+    Console.WriteLine($"CPU: {{state.CpuUsagePercent:F1}}%");
+    Console.WriteLine($"RAM: {{state.MemoryUsedMb}}/{{state.MemoryTotalMb}} MB");
+    Console.WriteLine($"Uptime: {{state.UptimeFormatted}}");
+}}
+```
+
+## Configuration
+
+| Setting | Value |
+|---------|-------|
+| Channel | `{channel}` |
+| Magic | `0x{magic:08X}` |
+{ping}{instances}
+## Notes
+
+- `libvenom_memory.so` is loaded from `native/` by a `DllImportResolver`
+  registered in `VenomBinding.cs`; the `.csproj` copies it next to the build output
+- `{pascal}State` is read straight out of the buffer with `MemoryMarshal.Read`,
+  so its field order must match `daemon/protocol.h`
+{service}"#,
+        name = config.name,
+        service = super::service::readme_section(config),
+        ping = super::ping_readme_section("dotnet run -- --ping 10"),
+        instances = super::instances_readme_section(&config.channel, "cd daemon && make run ARGS=\"--channel-suffix SUFFIX\"", "dotnet run -- --channel-suffix SUFFIX", "`Protocol.InstanceChannel()`"),
+        channel = config.channel,
+        magic = config.magic,
+        pascal = pascal
+    )
+}
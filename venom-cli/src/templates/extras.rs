@@ -0,0 +1,344 @@
+//! Repository files around a project: `.gitignore`, `LICENSE` and the
+//! VS Code debug configuration (`venom init --no-extras` leaves them out)
+//!
+//! `.gitignore` covers what the project and each of its clients build;
+//! `.vscode/` launches the daemon, the client, or both under the debugger
+//! for the C, C++ and Rust templates.
+
+use super::{Language, ProjectConfig};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// License a project is published under (`--license`)
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum License {
+    /// No `LICENSE` file
+    #[default]
+    None,
+    Mit,
+    Apache2,
+}
+
+impl License {
+    /// Identifier used on the command line and in `venom.toml`
+    pub fn id(self) -> &'static str {
+        match self {
+            License::None => "none",
+            License::Mit => "mit",
+            License::Apache2 => "apache-2.0",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        [License::None, License::Mit, License::Apache2].into_iter().find(|l| l.id() == id)
+    }
+}
+
+/// Generate the extras of a `lang` project with the clients of `config`
+pub fn generate(config: &ProjectConfig, lang: Language) {
+    let base = &config.output_dir;
+    crate::write_file(&format!("{}/.gitignore", base), &gitignore(config, lang));
+    if let Some(license) = license(config) {
+        crate::write_file(&format!("{}/LICENSE", base), &license);
+    }
+    if let Some(launch) = launch_json(config, lang) {
+        crate::create_dir(&format!("{}/.vscode", base));
+        crate::write_file(&format!("{}/.vscode/launch.json", base), &launch);
+        if let Some(tasks) = tasks_json(lang) {
+            crate::write_file(&format!("{}/.vscode/tasks.json", base), &tasks);
+        }
+    }
+}
+
+/// Patterns ignoring what `lang` builds, relative to its directory; with
+/// `client_only`, just the client's (a project under `clients/<lang>/`)
+fn ignored(config: &ProjectConfig, lang: Language, client_only: bool) -> Vec<String> {
+    let name = &config.name;
+    // Every language but the ones building their own daemon ships the C one
+    let c_daemon = vec![format!("/daemon/{}_daemon", name)];
+    let (daemon, client): (Vec<String>, Vec<String>) = match lang {
+        Language::C | Language::Cpp => (
+            c_daemon,
+            vec![format!("/client/{}_client", name), "/client/test_state".to_string(), "*.o".to_string()],
+        ),
+        Language::Rust => (Vec::new(), vec!["/target/".to_string()]),
+        Language::Go => (
+            vec![format!("/{}_daemon", name)],
+            vec![format!("/{}_client", name), "/client_arm64".to_string()],
+        ),
+        Language::Nim => (
+            vec![format!("/{}_daemon", name)],
+            vec![format!("/{}_client", name), "nimcache/".to_string()],
+        ),
+        Language::Zig => (Vec::new(), vec!["/zig-out/".to_string(), "/.zig-cache/".to_string(), "/zig-cache/".to_string()]),
+        Language::Python => (c_daemon, vec!["__pycache__/".to_string(), "*.pyc".to_string(), "/.venv/".to_string()]),
+        Language::Node => (c_daemon, vec!["/node_modules/".to_string()]),
+        Language::CSharp => (c_daemon, vec!["/bin/".to_string(), "/obj/".to_string()]),
+        Language::Java => (c_daemon, vec!["/build/".to_string()]),
+        Language::Swift => (c_daemon, vec!["/.build/".to_string()]),
+        Language::Lua => (c_daemon, Vec::new()),
+        Language::Flutter => (
+            c_daemon,
+            vec!["/.dart_tool/".to_string(), "/build/".to_string(), "/client".to_string()],
+        ),
+    };
+    if client_only { client } else { daemon.into_iter().chain(client).collect() }
+}
+
+fn gitignore(config: &ProjectConfig, lang: Language) -> String {
+    let mut out = format!("# Build output of {} ({})\n", config.name, lang.id());
+    for pattern in ignored(config, lang, false) {
+        out.push_str(&pattern);
+        out.push('\n');
+    }
+    for &client in &config.clients {
+        out.push_str(&format!("\n# clients/{}/\n", client.id()));
+        for pattern in ignored(config, client, true) {
+            // Anchored patterns are relative to the client's directory
+            match pattern.strip_prefix('/') {
+                Some(path) => out.push_str(&format!("/clients/{}/{}\n", client.id(), path)),
+                None => out.push_str(&format!("{}\n", pattern)),
+            }
+        }
+    }
+    out
+}
+
+/// Year of the current UTC date, for a new project's `license_year`
+pub fn current_year() -> u64 {
+    year_of(SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()) / 86_400)
+}
+
+/// Year of the day `days` after 1970-01-01
+fn year_of(days: u64) -> u64 {
+    // Civil year of a day count since 1970-01-01, in 400-year eras starting on March 1st
+    let z = days + 719_468;
+    let day_of_era = z % 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    // January and February belong to the next year
+    let march_based_month = (5 * day_of_year + 2) / 153;
+    z / 146_097 * 400 + year_of_era + u64::from(march_based_month >= 10)
+}
+
+/// `LICENSE` of the project, or `None` without one
+///
+/// Apache-2.0 gets the notice the license's appendix asks every project to
+/// carry, pointing at the full text, rather than the text itself.
+fn license(config: &ProjectConfig) -> Option<String> {
+    let year = config.license_year;
+    let holder = format!("The {} authors", config.name);
+    match config.license {
+        License::None => None,
+        License::Mit => Some(format!(r#"MIT License
+
+Copyright (c) {year} {holder}
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+"#, year = year, holder = holder)),
+        License::Apache2 => Some(format!(r#"Copyright {year} {holder}
+
+Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License.
+"#, year = year, holder = holder)),
+    }
+}
+
+/// `.vscode/launch.json` for the C, C++ and Rust templates: the daemon, the
+/// client, and a compound starting both
+fn launch_json(config: &ProjectConfig, lang: Language) -> Option<String> {
+    let name = &config.name;
+    let (daemon, client) = match lang {
+        Language::C | Language::Cpp => (
+            debug_config(&format!("{} daemon", name), "cppdbg", &format!(
+                r#""program": "${{workspaceFolder}}/daemon/{name}_daemon",
+            "cwd": "${{workspaceFolder}}/daemon",
+            "MIMode": "gdb",
+            "preLaunchTask": "build daemon""#,
+                name = name
+            )),
+            debug_config(&format!("{} client", name), "cppdbg", &format!(
+                r#""program": "${{workspaceFolder}}/client/{name}_client",
+            "cwd": "${{workspaceFolder}}/client",
+            "MIMode": "gdb",
+            "preLaunchTask": "build client""#,
+                name = name
+            )),
+        ),
+        // CodeLLDB builds the binary through cargo itself
+        Language::Rust => (
+            debug_config(&format!("{} daemon", name), "lldb", r#""cargo": { "args": ["build", "--bin=daemon"] },
+            "cwd": "${workspaceFolder}""#),
+            debug_config(&format!("{} client", name), "lldb", r#""cargo": { "args": ["build", "--bin=client"] },
+            "cwd": "${workspaceFolder}""#),
+        ),
+        _ => return None,
+    };
+    Some(format!(r#"{{
+    "version": "0.2.0",
+    "configurations": [
+{daemon},
+{client}
+    ],
+    "compounds": [
+        {{
+            "name": "{name} daemon + client",
+            "configurations": ["{name} daemon", "{name} client"],
+            "stopAll": true
+        }}
+    ]
+}}
+"#, daemon = daemon, client = client, name = name))
+}
+
+fn debug_config(label: &str, kind: &str, fields: &str) -> String {
+    format!(r#"        {{
+            "name": "{label}",
+            "type": "{kind}",
+            "request": "launch",
+            {fields}
+        }}"#, label = label, kind = kind, fields = fields)
+}
+
+/// `.vscode/tasks.json` building what `launch.json` of a C or C++ project starts
+fn tasks_json(lang: Language) -> Option<String> {
+    if !matches!(lang, Language::C | Language::Cpp) {
+        return None;
+    }
+    Some(r#"{
+    "version": "2.0.0",
+    "tasks": [
+        {
+            "label": "build daemon",
+            "type": "shell",
+            "command": "make -C daemon",
+            "problemMatcher": ["$gcc"]
+        },
+        {
+            "label": "build client",
+            "type": "shell",
+            "command": "make -C client",
+            "problemMatcher": ["$gcc"]
+        }
+    ]
+}
+"#.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::Schema;
+    use crate::templates::PythonBinding;
+
+    fn project(clients: Vec<Language>, license: License) -> ProjectConfig {
+        ProjectConfig {
+            name: "extras".to_string(),
+            channel: "extras".to_string(),
+            data_size: 16 * 1024,
+            cmd_slots: 32,
+            max_clients: 16,
+            shm_mode: 0o664,
+            output_dir: "extras".to_string(),
+            python_binding: PythonBinding::Ctypes,
+            go_nocgo: false,
+            flutter_ui: true,
+            python_async: false,
+            magic: crate::templates::channel_magic("extras"),
+            schema: Schema::demo(),
+            targets: Vec::new(),
+            clients,
+            root: String::new(),
+            service: false,
+            license,
+            extras: true,
+            license_year: 2026,
+        }
+    }
+
+    #[test]
+    fn test_gitignore_per_language() {
+        let config = project(vec![Language::Python, Language::Go, Language::Flutter], License::None);
+        let c = gitignore(&config, Language::C);
+        for line in ["/daemon/extras_daemon", "/client/extras_client", "/client/test_state", "*.o"] {
+            assert!(c.lines().any(|l| l == line), "{}: {}", line, c);
+        }
+        // Clients: anchored patterns under clients/<lang>/, no daemon of their own
+        for line in ["__pycache__/", "/clients/go/extras_client", "/clients/flutter/.dart_tool/"] {
+            assert!(c.lines().any(|l| l == line), "{}: {}", line, c);
+        }
+        assert!(!c.contains("/clients/go/extras_daemon"), "{}", c);
+
+        let rust = gitignore(&project(Vec::new(), License::None), Language::Rust);
+        assert_eq!(rust.lines().skip(1).collect::<Vec<_>>(), ["/target/"]);
+        let go = gitignore(&project(Vec::new(), License::None), Language::Go);
+        assert!(go.contains("/extras_daemon\n/extras_client\n"), "{}", go);
+        let dart = gitignore(&project(Vec::new(), License::None), Language::Flutter);
+        assert!(dart.contains("/.dart_tool/\n") && dart.contains("/daemon/extras_daemon\n"), "{}", dart);
+    }
+
+    #[test]
+    fn test_license() {
+        assert_eq!(license(&project(Vec::new(), License::None)), None);
+        let mit = license(&project(Vec::new(), License::Mit)).unwrap();
+        assert!(mit.starts_with("MIT License\n\nCopyright (c) 2026 The extras authors\n"), "{}", mit);
+        let apache = license(&project(Vec::new(), License::Apache2)).unwrap();
+        assert!(apache.starts_with("Copyright 2026 The extras authors\n"), "{}", apache);
+        assert!(apache.contains("http://www.apache.org/licenses/LICENSE-2.0"), "{}", apache);
+        assert_eq!(License::from_id("apache-2.0"), Some(License::Apache2));
+        assert_eq!(License::from_id("gpl"), None);
+    }
+
+    #[test]
+    fn test_launch_json() {
+        let config = project(Vec::new(), License::None);
+        for lang in [Language::C, Language::Cpp, Language::Rust] {
+            let launch: serde_json::Value = serde_json::from_str(&launch_json(&config, lang).unwrap()).unwrap();
+            let names: Vec<&str> = launch["configurations"].as_array().unwrap().iter().map(|c| c["name"].as_str().unwrap()).collect();
+            assert_eq!(names, ["extras daemon", "extras client"]);
+            assert_eq!(launch["compounds"][0]["configurations"], serde_json::json!(names));
+        }
+        let c: serde_json::Value = serde_json::from_str(&launch_json(&config, Language::C).unwrap()).unwrap();
+        assert_eq!(c["configurations"][0]["program"], "${workspaceFolder}/daemon/extras_daemon");
+        let tasks: serde_json::Value = serde_json::from_str(&tasks_json(Language::C).unwrap()).unwrap();
+        assert_eq!(tasks["tasks"][0]["label"], c["configurations"][0]["preLaunchTask"]);
+        let rust: serde_json::Value = serde_json::from_str(&launch_json(&config, Language::Rust).unwrap()).unwrap();
+        assert_eq!(rust["configurations"][1]["cargo"]["args"], serde_json::json!(["build", "--bin=client"]));
+        assert!(launch_json(&config, Language::Python).is_none());
+        assert!(tasks_json(Language::Rust).is_none());
+    }
+
+    #[test]
+    fn test_year_of() {
+        // 1970-01-01, 1999-12-31, 2000-01-01, 2000-02-29, 2100-03-01
+        assert_eq!(year_of(0), 1970);
+        assert_eq!(year_of(10_956), 1999);
+        assert_eq!(year_of(10_957), 2000);
+        assert_eq!(year_of(11_016), 2000);
+        assert_eq!(year_of(47_541), 2100);
+    }
+}
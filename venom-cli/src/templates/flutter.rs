@@ -1,12 +1,19 @@
 //! Flutter/Dart Templates for VenomMemory projects
 //!
 //! Generates a complete Flutter project with:
-//! - lib/venom_binding.dart - FFI bindings and state classes  
-//! - lib/venom_shell.dart - Shell wrapper with library loading
+//! - lib/venom_binding.dart - FFI bindings, state class and VenomShell
+//! - bin/<name>.dart - Console client, for headless use
 //! - pubspec.yaml - Package configuration
 //! - README.md with usage instructions
+//!
+//! With `flutter_ui` (the default) it is also a Flutter app:
+//! - lib/main.dart - MaterialApp showing the live state
+//! - lib/venom_reader.dart - Reads the channel on a background isolate
+//! - linux/venom_memory.cmake - Bundles the library with the Linux desktop app
+//! - android/app/src/main/jniLibs/<abi>/ - The library for each Android `--target`
 
-use super::ProjectConfig;
+use super::{DecodedField, DecoderOffsets, ProjectConfig};
+use crate::schema::{camel_case, Field, FieldType};
 
 pub fn generate(config: &ProjectConfig) {
     let base = &config.output_dir;
@@ -15,16 +22,10 @@ pub fn generate(config: &ProjectConfig) {
     // - lib/ for library code (venom_binding.dart)
     // - bin/ for executables (main.dart)
     // - native/ for the bundled .so library
-    crate::create_dir(&format!("{}/lib", base));
-    crate::create_dir(&format!("{}/bin", base));
-    crate::create_dir(&format!("{}/native", base));
     crate::create_dir(&format!("{}/daemon/src", base));
     
     // Dart client files
-    let snake = config.name.replace("-", "_");
-    crate::write_file(&format!("{}/lib/venom_binding.dart", base), &venom_binding(config));
-    crate::write_file(&format!("{}/bin/{}.dart", base, snake), &main_dart(config));
-    crate::write_file(&format!("{}/pubspec.yaml", base), &pubspec(config));
+    generate_client(config);
     
     // C Daemon files (so Flutter project is self-contained)
     crate::write_file(&format!("{}/daemon/src/main.c", base), &daemon_c(config));
@@ -33,48 +34,72 @@ pub fn generate(config: &ProjectConfig) {
     
     crate::write_file(&format!("{}/README.md", base), &readme(config));
     
-    // Copy the bundled library to native/ folder (for Dart) and daemon/ (for C daemon)
-    let native_dir = format!("{}/native", base);
-    let lib_path = format!("{}/libvenom_memory.so", native_dir);
-    std::fs::write(&lib_path, crate::library::LIBRARY_BINARY)
-        .expect(&format!("Failed to write library to: {}", lib_path));
-    
-    // Also copy to daemon folder
-    let daemon_lib_path = format!("{}/daemon/libvenom_memory.so", base);
-    std::fs::write(&daemon_lib_path, crate::library::LIBRARY_BINARY)
-        .expect(&format!("Failed to write library to: {}", daemon_lib_path));
-    
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        for path in &[&lib_path, &daemon_lib_path] {
-            if let Ok(meta) = std::fs::metadata(path) {
-                let mut perms = meta.permissions();
-                perms.set_mode(0o755);
-                std::fs::set_permissions(path, perms).ok();
-            }
-        }
-    }
+    // Also copy the library to the daemon folder (for the C daemon)
+    crate::library::install(&format!("{}/daemon", base), &config.targets);
+}
+
+/// Client files only (used by `venom add-client`), plus the library in native/
+pub fn generate_client(config: &ProjectConfig) {
+    let base = &config.output_dir;
+    
+    crate::create_dir(&format!("{}/lib", base));
+    crate::create_dir(&format!("{}/bin", base));
+    
+    let snake = config.names().snake;
+    crate::write_file(&format!("{}/lib/venom_binding.dart", base), &venom_binding(config));
+    crate::write_file(&format!("{}/test/venom_binding_test.dart", base), &binding_test_dart(config));
+    state_parser(config).offsets.write(&format!("{}/lib", base));
+    crate::write_file(&format!("{}/bin/{}.dart", base, snake), &main_dart(config));
+    crate::write_file(&format!("{}/pubspec.yaml", base), &pubspec(config));
+    
+    crate::library::install(&format!("{}/native", base), &config.targets);
     
-    println!("   {} {}", console::style("✓").green(), lib_path);
-    println!("   {} {}", console::style("✓").green(), daemon_lib_path);
+    if config.flutter_ui {
+        generate_app(config);
+    }
 }
 
-fn magic(channel: &str) -> u32 {
-    channel.bytes().fold(0x564E4Fu32, |acc, b| acc.wrapping_add(b as u32))
+/// The Flutter app: widgets, background reader and the native library
+/// where each platform's loader finds it
+fn generate_app(config: &ProjectConfig) {
+    let base = &config.output_dir;
+    crate::write_file(&format!("{}/lib/main.dart", base), &app_main_dart(config));
+    crate::write_file(&format!("{}/lib/venom_reader.dart", base), &venom_reader_dart(config));
+    
+    crate::create_dir(&format!("{}/linux", base));
+    crate::write_file(&format!("{}/linux/venom_memory.cmake", base), &linux_cmake(config));
+    
+    // Android loads libraries from jniLibs/<abi>/ by name
+    for triple in &config.targets {
+        if let Some(abi) = android_abi(triple) {
+            let path = format!("{}/android/app/src/main/jniLibs/{}/{}", base, abi, crate::library::LIBRARY_NAME);
+            crate::library::install_at(&path, triple);
+        }
+    }
 }
 
-fn upper_name(name: &str) -> String {
-    name.to_uppercase().replace("-", "_")
+/// Android ABI directory of an Android target triple
+fn android_abi(triple: &str) -> Option<&'static str> {
+    if !triple.contains("android") {
+        return None;
+    }
+    match triple.split('-').next()? {
+        "aarch64" => Some("arm64-v8a"),
+        "armv7" | "thumbv7neon" => Some("armeabi-v7a"),
+        "x86_64" => Some("x86_64"),
+        "i686" => Some("x86"),
+        _ => None,
+    }
 }
 
+
 // ═══════════════════════════════════════════════════════════════════════════
 // C Daemon (so Flutter project is self-contained)
 // ═══════════════════════════════════════════════════════════════════════════
 
 fn protocol_h(config: &ProjectConfig) -> String {
-    let upper = upper_name(&config.name);
-    let pascal = pascal_case(&config.name);
+    let upper = config.names().upper;
+    let pascal = config.names().pascal;
     
     format!(r#"#ifndef {upper}_PROTOCOL_H
 #define {upper}_PROTOCOL_H
@@ -82,33 +107,33 @@ fn protocol_h(config: &ProjectConfig) -> String {
 #include <stdint.h>
 #define {upper}_CHANNEL_NAME "{channel}"
 #define {upper}_MAGIC 0x{magic:08X}
-#define {upper}_MAX_CORES 16
-
+#define {upper}_STATE_SIZE {state_size}
+{max_cores}
 typedef struct __attribute__((packed)) {{
-    uint32_t magic;
-    uint32_t version;
-    float cpu_usage_percent;
-    float cpu_cores[{upper}_MAX_CORES];
-    uint32_t core_count;
-    uint32_t memory_used_mb;
-    uint32_t memory_total_mb;
-    uint64_t uptime_seconds;
-    uint64_t update_counter;
-    uint64_t timestamp_ns;
-}} {pascal}State;
+{fields}}} {pascal}State;
 
+_Static_assert(sizeof({pascal}State) == {upper}_STATE_SIZE, "{pascal}State size mismatch");
+
+{commands}
 #endif
 "#,
         upper = upper,
         pascal = pascal,
         channel = config.channel,
-        magic = magic(&config.channel)
+        magic = config.magic,
+        state_size = config.schema.size,
+        max_cores = super::c::max_cores_define(config),
+        fields = config.schema.c_fields("    "),
+        commands = super::c::commands_h(config),
     )
 }
 
 fn daemon_c(config: &ProjectConfig) -> String {
-    let upper = upper_name(&config.name);
-    let pascal = pascal_case(&config.name);
+    if !config.schema.has_demo_fields() {
+        return super::c::generic_daemon_c(config, "../protocol.h");
+    }
+    let upper = config.names().upper;
+    let pascal = config.names().pascal;
     
     format!(r#"/* {name} Daemon - VenomMemory */
 #include <stdio.h>
@@ -120,10 +145,11 @@ fn daemon_c(config: &ProjectConfig) -> String {
 #include "../protocol.h"
 
 typedef struct VenomDaemonHandle VenomDaemonHandle;
-typedef struct {{ size_t data_size; size_t cmd_slots; size_t max_clients; }} VenomConfig;
-extern VenomDaemonHandle* venom_daemon_create(const char* name, VenomConfig config);
+typedef struct {{ size_t struct_size; size_t data_size; size_t cmd_slots; size_t max_clients; size_t cmd_payload_size; size_t cmd_full_policy; size_t shm_mode; }} VenomConfigEx;
+extern VenomDaemonHandle* venom_daemon_create_ex(const char* name, const VenomConfigEx* config);
 extern void venom_daemon_destroy(VenomDaemonHandle* handle);
 extern void venom_daemon_write_data(VenomDaemonHandle* handle, const uint8_t* data, size_t len);
+extern size_t venom_daemon_try_recv_command(VenomDaemonHandle* handle, uint8_t* buf, size_t max_len, uint32_t* out_client_id);
 
 static VenomDaemonHandle* g_daemon = NULL;
 static {pascal}State g_state = {{0}};
@@ -133,6 +159,8 @@ static uint64_t prev_idle[{upper}_MAX_CORES + 1] = {{0}};
 
 static void signal_handler(int sig) {{ (void)sig; g_running = 0; }}
 
+{commands}
+{channel_arg}
 static void read_cpu(void) {{
     FILE* f = fopen("/proc/stat", "r");
     if (!f) return;
@@ -176,41 +204,47 @@ static void read_uptime(void) {{
     fclose(f);
 }}
 
-int main(void) {{
+int main(int argc, char** argv) {{
     printf("🖥️  {name} Daemon (VenomMemory)\\n");
     printf("═══════════════════════════════════════════════════════════════\\n");
     signal(SIGINT, signal_handler); signal(SIGTERM, signal_handler);
     
-    VenomConfig cfg = {{ .data_size = 16384, .cmd_slots = 32, .max_clients = 16 }};
-    g_daemon = venom_daemon_create({upper}_CHANNEL_NAME, cfg);
+    VenomConfigEx cfg = {{ .struct_size = sizeof(VenomConfigEx), .data_size = 16384, .cmd_slots = 32, .max_clients = 16, .cmd_payload_size = sizeof({pascal}Command), .shm_mode = 0{shm_mode:o} }};
+    const char* channel = channel_from_args(argc, argv);
+    g_daemon = venom_daemon_create_ex(channel, &cfg);
     if (!g_daemon) {{ printf("❌ Failed to create channel\\n"); return 1; }}
     
-    printf("✅ Channel: %s\\n🚀 Publishing... (Ctrl+C to stop)\\n\\n", {upper}_CHANNEL_NAME);
+    printf("✅ Channel: %s\\n🚀 Publishing... (Ctrl+C to stop)\\n\\n", channel);
     
+    uint64_t next_publish = 0;
     while (g_running) {{
+        int refresh = handle_commands(g_daemon);
+        if (!refresh && now_ms() < next_publish) {{ usleep(10000); continue; }}
+        next_publish = now_ms() + g_interval_ms;
+        
         read_cpu(); read_mem(); read_uptime();
         g_state.magic = {upper}_MAGIC; g_state.version = 1; g_state.update_counter++;
         struct timespec ts; clock_gettime(CLOCK_MONOTONIC, &ts);
         g_state.timestamp_ns = (uint64_t)ts.tv_sec * 1000000000ULL + ts.tv_nsec;
-        venom_daemon_write_data(g_daemon, (const uint8_t*)&g_state, sizeof(g_state));
-        printf("\\r🖥️  CPU: %5.1f%% | RAM: %u/%u MB | #%lu   ",
+        publish(g_daemon, &g_state);
+        printf("\\r🖥️  CPU: %5.1f%% | RAM: %u/%u MB | #%lu | %u ms   ",
             g_state.cpu_usage_percent, g_state.memory_used_mb, g_state.memory_total_mb,
-            (unsigned long)g_state.update_counter);
-        fflush(stdout); usleep(100000);
+            (unsigned long)g_state.update_counter, g_interval_ms);
+        fflush(stdout);
     }}
     venom_daemon_destroy(g_daemon);
     printf("\\n\\n👋 Goodbye!\\n");
     return 0;
 }}
-"#, name = config.name, upper = upper, pascal = pascal)
+"#, name = config.name, upper = upper, pascal = pascal, shm_mode = config.shm_mode, commands = super::c::command_handler_c(config), channel_arg = super::c::channel_arg_c(config))
 }
 
 fn daemon_makefile(config: &ProjectConfig) -> String {
     format!(r#"# {name} Daemon Makefile
 
-CC = gcc
+{target_var}CC = gcc
 CFLAGS = -Wall -Wextra -O2
-LDFLAGS = -L. -lvenom_memory -Wl,-rpath,'$$ORIGIN'
+LDFLAGS = -L.{sub} -lvenom_memory -Wl,-rpath,'$$ORIGIN{sub}'
 
 TARGET = {name}_daemon
 
@@ -227,33 +261,86 @@ clean:
 	@rm -f $(TARGET)
 
 run: $(TARGET)
-	@./$(TARGET)
-"#, name = config.name)
+	@./$(TARGET) $(ARGS)
+"#,
+        name = config.name,
+        target_var = super::make_target_var(config),
+        sub = super::make_lib_dir(config, ""),
+    )
 }
 
-fn pascal_case(s: &str) -> String {
-    s.split(|c| c == '_' || c == '-')
-        .map(|word| {
-            let mut chars = word.chars();
-            match chars.next() {
-                None => String::new(),
-                Some(first) => first.to_uppercase().chain(chars).collect(),
+
+pub(super) fn venom_binding(config: &ProjectConfig) -> String {
+    let pascal = config.names().pascal;
+    let snake = config.names().snake;
+    let schema = &config.schema;
+    
+    let max_cores = match schema.field("cpu_cores").and_then(|f| f.len) {
+        Some(n) if schema.has_demo_fields() => format!("const int maxCores = {};\n", n),
+        _ => String::new(),
+    };
+    
+    let StateParser { decls, params, parse, .. } = state_parser(config);
+    
+    // With several bundled targets each native/<triple>/ copy is tried, and
+    // the first one that loads was built for this machine
+    let (loads, locations, found, library_location) = if config.targets.is_empty() {
+        (
+            String::new(),
+            r#"    // Next to a Flutter desktop app (bundle/lib/)
+    '$appLib/libvenom_memory.so',
+    // Relative to executable (for deployed apps)
+    'native/libvenom_memory.so',
+    '../native/libvenom_memory.so',
+    '../../native/libvenom_memory.so',
+    // Standard lib location
+    'lib/libvenom_memory.so',
+    '../lib/libvenom_memory.so', 
+    // Absolute fallback
+    '/usr/local/lib/libvenom_memory.so',
+    '/usr/lib/libvenom_memory.so',
+"#.to_string(),
+            "File(path).existsSync",
+            "native/libvenom_memory.so".to_string(),
+        )
+    } else {
+        let mut locations = String::new();
+        for t in config.targets.iter().filter(|t| android_abi(t).is_none()) {
+            locations.push_str(&format!("    '$appLib/{}/{}',\n", t, crate::library::library_name_for(t)));
+        }
+        for t in &config.targets {
+            let file = crate::library::library_name_for(t);
+            for prefix in ["", "../", "../../"] {
+                locations.push_str(&format!("    '{}native/{}/{}',\n", prefix, t, file));
             }
-        })
-        .collect()
+        }
+        (
+            r#"/// Whether [path] exists and loads (a library built for another target doesn't)
+bool _loads(String path) {
+  if (!File(path).existsSync()) return false;
+  try {
+    DynamicLibrary.open(path);
+    return true;
+  } catch (_) {
+    return false;
+  }
 }
 
-fn venom_binding(config: &ProjectConfig) -> String {
-    let pascal = pascal_case(&config.name);
-    let snake = config.name.replace("-", "_");
-    
+"#.to_string(),
+            locations,
+            "_loads",
+            "native/<triple>/ for this machine".to_string(),
+        )
+    };
+
     format!(r#"/// VenomMemory FFI Bindings for {name}
 /// 
 /// Provides:
 /// - {pascal}State: System stats from daemon
+/// - encodeCommand: Commands for the daemon (cmdRefresh, cmdSetInterval, cmdPing)
 /// - VenomShell: Connection to daemon
 ///
-/// Library location: native/libvenom_memory.so
+/// Library location: {library_location}
 
 import 'dart:ffi';
 import 'dart:io';
@@ -266,105 +353,77 @@ import 'package:ffi/ffi.dart';
 
 const String channelName = '{channel}';
 const int magic = 0x{magic:08X};
-const int maxCores = 16;
+const int stateSize = {state_size};
+{max_cores}
+/// The channel of one of several instances: '{channel}-SUFFIX', or
+/// [channelName] itself for no suffix or an empty one
+String instanceChannel(String? suffix) =>
+    suffix == null || suffix.isEmpty ? channelName : '$channelName-$suffix';
 
 // ═══════════════════════════════════════════════════════════════════════════
-// State Structure (matches C struct layout exactly)
+// State Structure (matches C struct layout exactly, generated from venom.toml)
 // ═══════════════════════════════════════════════════════════════════════════
 
 class {pascal}State {{
-  final int magic;
-  final int version;
-  final double cpuUsage;
-  final List<double> cpuCores;
-  final int coreCount;
-  final int memoryUsedMb;
-  final int memoryTotalMb;
-  final int uptimeSeconds;
-  final int updateCounter;
-  final int timestampNs;
-
+{decls}
   {pascal}State({{
-    required this.magic,
-    required this.version,
-    required this.cpuUsage,
-    required this.cpuCores,
-    required this.coreCount,
-    required this.memoryUsedMb,
-    required this.memoryTotalMb,
-    required this.uptimeSeconds,
-    required this.updateCounter,
-    required this.timestampNs,
-  }});
+{params}  }});
 
   /// Parse state from raw bytes (must match C struct layout)
   factory {pascal}State.fromBytes(Uint8List bytes) {{
-    if (bytes.length < 112) return {pascal}State.empty();
+    if (bytes.length < stateSize) return {pascal}State.empty();
     
     final data = ByteData.view(bytes.buffer, bytes.offsetInBytes, bytes.length);
     
-    // Parse per-core CPU usage (16 floats starting at offset 12)
-    final cores = <double>[];
-    for (int i = 0; i < maxCores; i++) {{
-      cores.add(data.getFloat32(12 + i * 4, Endian.little));
-    }}
-    
     return {pascal}State(
-      magic: data.getUint32(0, Endian.little),
-      version: data.getUint32(4, Endian.little),
-      cpuUsage: data.getFloat32(8, Endian.little),
-      cpuCores: cores,
-      coreCount: data.getUint32(76, Endian.little),
-      memoryUsedMb: data.getUint32(80, Endian.little),
-      memoryTotalMb: data.getUint32(84, Endian.little),
-      uptimeSeconds: data.getUint64(88, Endian.little),
-      updateCounter: data.getUint64(96, Endian.little),
-      timestampNs: data.getUint64(104, Endian.little),
-    );
+{parse}    );
   }}
 
-  factory {pascal}State.empty() => {pascal}State(
-    magic: 0, version: 0, cpuUsage: 0, cpuCores: List.filled(maxCores, 0.0),
-    coreCount: 0, memoryUsedMb: 0, memoryTotalMb: 0, 
-    uptimeSeconds: 0, updateCounter: 0, timestampNs: 0,
-  );
+  factory {pascal}State.empty() => {pascal}State.fromBytes(Uint8List(stateSize));
 
   bool get isValid => magic == {snake}Magic;
-  
-  double get memoryUsagePercent => 
-    memoryTotalMb > 0 ? memoryUsedMb / memoryTotalMb * 100 : 0;
-  
-  String get uptimeFormatted {{
-    final hours = uptimeSeconds ~/ 3600;
-    final minutes = (uptimeSeconds % 3600) ~/ 60;
-    return '${{hours}}h ${{minutes}}m';
-  }}
-}}
+{helpers}}}
 
 const int {snake}Magic = magic;
 
+// ═══════════════════════════════════════════════════════════════════════════
+// 📨 Commands (Clients send, Daemon applies)
+// ═══════════════════════════════════════════════════════════════════════════
+
+const int intervalMs = 100;    // Default publish interval
+const int cmdRefresh = 1;      // Publish immediately
+const int cmdSetInterval = 2;  // value: publish interval in ms (10-10000)
+const int cmdPing = 3;         // value: sequence number, echoed back in a pong
+const int commandSize = 8;
+/// Reply to the last ping, published right after the state: client ID and seq (u32 each)
+const int pongSize = 8;
+
+/// Packed Command, a typed command: cmd u16 (the tag), len u16 (4, the
+/// payload's size), value i32
+Uint8List encodeCommand(int cmd, [int value = 0]) {{
+  final data = ByteData(commandSize);
+  data.setUint16(0, cmd, Endian.little);
+  data.setUint16(2, 4, Endian.little);
+  data.setInt32(4, value, Endian.little);
+  return data.buffer.asUint8List();
+}}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // VenomShell - Connection to VenomMemory Daemon
 // ═══════════════════════════════════════════════════════════════════════════
 
-/// Finds the native library in various possible locations
+{loads}/// Finds the native library in various possible locations
 String _findLibraryPath() {{
+  // Android apps carry it in jniLibs/<abi>/, where the loader finds it by name
+  if (Platform.isAndroid) return 'libvenom_memory.so';
+  final appLib = '${{File(Platform.resolvedExecutable).parent.path}}/lib';
+
   // List of possible locations to search
   final locations = [
-    // Relative to executable (for deployed apps)
-    'native/libvenom_memory.so',
-    '../native/libvenom_memory.so',
-    '../../native/libvenom_memory.so',
-    // Standard lib location
-    'lib/libvenom_memory.so',
-    '../lib/libvenom_memory.so', 
-    // Absolute fallback
-    '/usr/local/lib/libvenom_memory.so',
-    '/usr/lib/libvenom_memory.so',
-  ];
+{locations}  ];
   
   for (final path in locations) {{
-    if (File(path).existsSync()) {{
+    if ({found}(path)) {{
       return path;
     }}
   }}
@@ -373,7 +432,7 @@ String _findLibraryPath() {{
   final cwd = Directory.current.path;
   for (final path in locations) {{
     final fullPath = '$cwd/$path';
-    if (File(fullPath).existsSync()) {{
+    if ({found}(fullPath)) {{
       return fullPath;
     }}
   }}
@@ -381,7 +440,7 @@ String _findLibraryPath() {{
   throw Exception(
     'Could not find libvenom_memory.so. Searched in:\n'
     '${{locations.join("\n")}}\n\n'
-    'Make sure native/libvenom_memory.so exists in your project.'
+    'Make sure {library_location} exists in your project.'
   );
 }}
 
@@ -390,7 +449,10 @@ class VenomShell {{
   Pointer<Void>? _handle;
   bool _disposed = false;
 
-  VenomShell() {{
+  /// µs between the daemon's write and the last read (0 if unstamped)
+  double writeAgeUs = 0;
+
+  VenomShell({{String channel = channelName}}) {{
     // Load library from native/ directory
     final libPath = _findLibraryPath();
     _lib ??= DynamicLibrary.open(libPath);
@@ -401,12 +463,12 @@ class VenomShell {{
       Pointer<Void> Function(Pointer<Utf8>)
     >('venom_shell_connect');
     
-    final namePtr = channelName.toNativeUtf8();
+    final namePtr = channel.toNativeUtf8();
     _handle = connect(namePtr);
     calloc.free(namePtr);
     
     if (_handle == nullptr) {{
-      throw Exception('Failed to connect to channel "$channelName". Is the daemon running?');
+      throw Exception('Failed to connect to channel "$channel". Is the daemon running?');
     }}
   }}
 
@@ -420,29 +482,88 @@ class VenomShell {{
     return fn(_handle!);
   }}
 
+  /// Version of the libvenom_memory that was loaded
+  String get libraryVersion {{
+    _checkDisposed();
+    final fn = _lib!.lookupFunction<
+      Pointer<Utf8> Function(),
+      Pointer<Utf8> Function()
+    >('venom_version');
+    return fn().toDartString();
+  }}
+
   /// Read raw data from shared memory
   Uint8List readRawData(int maxLen) {{
     _checkDisposed();
     final fn = _lib!.lookupFunction<
-      IntPtr Function(Pointer<Void>, Pointer<Uint8>, IntPtr), 
-      int Function(Pointer<Void>, Pointer<Uint8>, int)
-    >('venom_shell_read_data');
+      IntPtr Function(Pointer<Void>, Pointer<Uint8>, IntPtr, Pointer<Uint64>),
+      int Function(Pointer<Void>, Pointer<Uint8>, int, Pointer<Uint64>)
+    >('venom_shell_read_data_stamped');
+    final clockNs = _lib!.lookupFunction<
+      Uint64 Function(),
+      int Function()
+    >('venom_clock_ns');
     
     final buf = calloc<Uint8>(maxLen);
+    final writtenNs = calloc<Uint64>();
     try {{
-      final len = fn(_handle!, buf, maxLen);
+      final len = fn(_handle!, buf, maxLen, writtenNs);
+      if (writtenNs.value != 0) {{
+        writeAgeUs = (clockNs() - writtenNs.value) / 1000.0;
+      }}
       return Uint8List.fromList(buf.asTypedList(len));
     }} finally {{
       calloc.free(buf);
+      calloc.free(writtenNs);
     }}
   }}
 
   /// Read and parse state from daemon
   {pascal}State readState() {{
-    final bytes = readRawData(256);
+    final bytes = readRawData(stateSize + 64);
     return {pascal}State.fromBytes(bytes);
   }}
 
+  /// Send a command (see [encodeCommand]) to the daemon
+  bool sendCommand(Uint8List cmd) {{
+    _checkDisposed();
+    final fn = _lib!.lookupFunction<
+      Bool Function(Pointer<Void>, Pointer<Uint8>, IntPtr),
+      bool Function(Pointer<Void>, Pointer<Uint8>, int)
+    >('venom_shell_send_command');
+    
+    final buf = calloc<Uint8>(cmd.length);
+    try {{
+      buf.asTypedList(cmd.length).setAll(0, cmd);
+      return fn(_handle!, buf, cmd.length);
+    }} finally {{
+      calloc.free(buf);
+    }}
+  }}
+
+  bool setInterval(int ms) => sendCommand(encodeCommand(cmdSetInterval, ms));
+
+  bool refresh() => sendCommand(encodeCommand(cmdRefresh));
+
+  /// Round trip of a ping carrying [seq] (u32) in µs, or null if no pong came back within a second
+  ///
+  /// The daemon answers by publishing at once with a pong after the state. It keeps
+  /// only the last one, so clients pinging at the same time can miss their reply.
+  double? ping(int seq) {{
+    final stopwatch = Stopwatch()..start();
+    if (!sendCommand(encodeCommand(cmdPing, seq.toSigned(32)))) return null;
+    final id = clientId;
+    while (stopwatch.elapsedMicroseconds < 1000000) {{
+      final data = readRawData(stateSize + pongSize);
+      if (data.length < stateSize + pongSize) continue;
+      final pong = ByteData.sublistView(data, stateSize);
+      if (pong.getUint32(0, Endian.little) == id && pong.getUint32(4, Endian.little) == seq) {{
+        return stopwatch.elapsedMicroseconds.toDouble();
+      }}
+    }}
+    return null;
+  }}
+
   /// Clean up resources
   void dispose() {{
     if (_disposed) return;
@@ -463,14 +584,240 @@ class VenomShell {{
 "#,
         name = config.name,
         channel = config.channel,
-        magic = magic(&config.channel),
+        magic = config.magic,
         pascal = pascal,
-        snake = snake
+        snake = snake,
+        loads = loads,
+        locations = locations,
+        found = found,
+        library_location = library_location,
+        state_size = schema.size,
+        max_cores = max_cores,
+        decls = decls,
+        params = params,
+        parse = parse,
+        helpers = if schema.has_demo_fields() { DEMO_HELPERS } else { "" }
     )
 }
 
+/// Getters used by the system-monitor demo
+const DEMO_HELPERS: &str = r#"  
+  double get memoryUsagePercent => 
+    memoryTotalMb > 0 ? memoryUsedMb / memoryTotalMb * 100 : 0;
+  
+  String get uptimeFormatted {
+    final hours = uptimeSeconds ~/ 3600;
+    final minutes = (uptimeSeconds % 3600) ~/ 60;
+    return '${hours}h ${minutes}m';
+  }
+"#;
+
+/// Dart type of a field (`int`, `double` or a `List` of them)
+/// `dart test` / `flutter test`: a golden decode that always runs and a live
+/// read gated on `VENOM_LIVE_TEST`
+/// The state as `venom_binding.dart` handles it: field declarations,
+/// constructor parameters, `fromBytes` arguments, and the offsets those read
+struct StateParser {
+    decls: String,
+    params: String,
+    parse: String,
+    offsets: DecoderOffsets,
+}
+
+fn state_parser(config: &ProjectConfig) -> StateParser {
+    let mut decls = String::new();
+    let mut params = String::new();
+    let mut parse = String::new();
+    let mut decoded = Vec::new();
+    for f in config.schema.data_fields() {
+        let name = camel_case(&f.name);
+        let offset = f.offset;
+        decoded.push(DecodedField { name: f.name.clone(), offset, size: f.count() * f.ty.size() });
+        decls.push_str(&format!("  final {} {};\n", dart_type(f), name));
+        params.push_str(&format!("    required this.{},\n", name));
+        let value = match f.len {
+            Some(n) => format!(
+                "List.generate({}, (i) => {})",
+                n,
+                dart_get(f.ty, &format!("{} + i * {}", offset, f.ty.size()))
+            ),
+            None => dart_get(f.ty, &offset.to_string()),
+        };
+        parse.push_str(&format!("      {}: {},\n", name, value));
+    }
+    let offsets = DecoderOffsets { decoder: "venom_binding.dart".to_string(), size: config.schema.size, fields: decoded };
+    StateParser { decls, params, parse, offsets }
+}
+
+fn binding_test_dart(config: &ProjectConfig) -> String {
+    let mut checks = String::new();
+    for (f, values) in config.schema.golden(config.magic) {
+        let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+        let want = match f.len {
+            Some(_) => format!("[{}]", values.join(", ")),
+            None => values.join(""),
+        };
+        checks.push_str(&format!("    expect(s.{}, {});\n", camel_case(&f.name), want));
+    }
+    let test_package = if config.flutter_ui { "flutter_test/flutter_test.dart" } else { "test/test.dart" };
+
+    format!(r#"// Tests of lib/venom_binding.dart
+//
+// 'decodes golden state' decodes a state built from venom.toml, so it fails
+// when {pascal}State no longer matches the daemon's layout. 'reads live state'
+// needs the daemon running and {live_env}=1.
+
+import 'dart:io';
+import 'dart:typed_data';
+
+import 'package:{snake}/venom_binding.dart';
+import 'package:{test_package}';
+
+// Every field holds a distinct known value (see 'decodes golden state')
+final golden = Uint8List.fromList([
+{golden}]);
+
+void main() {{
+  test('decodes golden state', () {{
+    expect(golden.length, stateSize);
+    final s = {pascal}State.fromBytes(golden);
+{checks}  }});
+
+  test('rejects a short buffer', () {{
+    expect({pascal}State.fromBytes(golden.sublist(1)).isValid, isFalse);
+  }});
+
+  test('appends the channel suffix', () {{
+    expect(instanceChannel('2'), '$channelName-2');
+    expect(instanceChannel(''), channelName);
+    expect(instanceChannel(null), channelName);
+  }});
+
+  test('reads live state', () async {{
+    final shell = VenomShell();
+    try {{
+      var s = shell.readState();
+      // The daemon may not have published yet
+      for (var i = 0; i < 50 && !s.isValid; i++) {{
+        await Future.delayed(const Duration(milliseconds: 20));
+        s = shell.readState();
+      }}
+      expect(s.magic, magic);
+      expect(s.version, 1);
+    }} finally {{
+      shell.dispose();
+    }}
+  }}, skip: Platform.environment['{live_env}'] == null ? 'set {live_env}=1 with the daemon running' : false);
+}}
+"#,
+        pascal = config.names().pascal,
+        snake = config.names().snake,
+        test_package = test_package,
+        golden = super::byte_rows(&config.schema.golden_bytes(config.magic), "  "),
+        checks = checks,
+        live_env = super::LIVE_TEST_ENV
+    )
+}
+
+fn dart_type(field: &Field) -> String {
+    let scalar = if field.ty.is_float() { "double" } else { "int" };
+    match field.len {
+        Some(_) => format!("List<{}>", scalar),
+        None => scalar.to_string(),
+    }
+}
+
+/// `ByteData` read of one element at `offset`
+fn dart_get(ty: FieldType, offset: &str) -> String {
+    let getter = match ty {
+        FieldType::U8 => return format!("data.getUint8({})", offset),
+        FieldType::I8 => return format!("data.getInt8({})", offset),
+        FieldType::U16 => "getUint16",
+        FieldType::U32 => "getUint32",
+        FieldType::U64 => "getUint64",
+        FieldType::I16 => "getInt16",
+        FieldType::I32 => "getInt32",
+        FieldType::I64 => "getInt64",
+        FieldType::F32 => "getFloat32",
+        FieldType::F64 => "getFloat64",
+    };
+    format!("data.{}({}, Endian.little)", getter, offset)
+}
+
+/// Client-side keys, shared by both clients: `+`/`-` halve or double the
+/// daemon's publish interval, `r` asks for an immediate refresh; also
+/// `--ping` and `--channel-suffix`
+const COMMAND_KEYS: &str = r#"var currentIntervalMs = intervalMs;
+
+/// Read single keys without Enter or echo (Ctrl+C still works)
+void listenForKeys(VenomShell shell) {
+  if (!stdin.hasTerminal) return;
+  stdin.echoMode = false;
+  stdin.lineMode = false;
+  stdin.listen((bytes) {
+    for (final key in String.fromCharCodes(bytes).split('')) {
+      if (key == '+' || key == '=') {
+        currentIntervalMs = (currentIntervalMs ~/ 2).clamp(10, 10000);
+        shell.setInterval(currentIntervalMs);
+      } else if (key == '-') {
+        currentIntervalMs = (currentIntervalMs * 2).clamp(10, 10000);
+        shell.setInterval(currentIntervalMs);
+      } else if (key == 'r') {
+        shell.refresh();
+      }
+    }
+  });
+}
+
+void restoreTerminal() {
+  if (!stdin.hasTerminal) return;
+  stdin.lineMode = true;
+  stdin.echoMode = true;
+}
+
+/// `--ping COUNT` from the command line, or 0
+int pingArg(List<String> args) {
+  final i = args.indexOf('--ping');
+  return i >= 0 && i + 1 < args.length ? int.tryParse(args[i + 1]) ?? 0 : 0;
+}
+
+/// `--channel-suffix SUFFIX` from the command line: the daemon instance to connect to
+String channelArg(List<String> args) {
+  final i = args.indexOf('--channel-suffix');
+  return instanceChannel(i >= 0 && i + 1 < args.length ? args[i + 1] : null);
+}
+
+/// Time [count] round trips through the daemon; false if none came back
+Future<bool> runPing(VenomShell shell, int count) async {
+  // A pong for an earlier client with the same ID may still be published
+  var seq = DateTime.now().microsecondsSinceEpoch & 0xFFFFFFFF;
+  final rtts = <double>[];
+  for (var i = 1; i <= count; i++) {
+    seq = (seq + 1) & 0xFFFFFFFF;
+    final rttUs = shell.ping(seq);
+    if (rttUs == null) {
+      print('⚠️  No reply to ping #$i');
+      continue;
+    }
+    print('🏓 Pong #$i: ${rttUs.toStringAsFixed(1)} µs');
+    rtts.add(rttUs);
+    await Future.delayed(const Duration(milliseconds: 100));
+  }
+  if (rtts.isEmpty) return false;
+  final rttMin = rtts.reduce((a, b) => a < b ? a : b);
+  final rttMax = rtts.reduce((a, b) => a > b ? a : b);
+  final rttAvg = rtts.reduce((a, b) => a + b) / rtts.length;
+  print('📊 Round trip: ${rtts.length}/$count replies, min ${rttMin.toStringAsFixed(1)} µs, '
+      'avg ${rttAvg.toStringAsFixed(1)} µs, max ${rttMax.toStringAsFixed(1)} µs');
+  return true;
+}
+"#;
+
 fn main_dart(config: &ProjectConfig) -> String {
-    let snake = config.name.replace("-", "_");
+    if !config.schema.has_demo_fields() {
+        return generic_main_dart(config);
+    }
+    let snake = config.names().snake;
     
     format!(r#"/// {name} - VenomMemory Client Example - with Benchmarking
 /// 
@@ -484,7 +831,8 @@ import 'package:{snake}/venom_binding.dart';
 const cyan = '\x1B[96m';
 const reset = '\x1B[0m';
 
-void main() async {{
+{keys}
+void main(List<String> args) async {{
   print('🖥️  {name} Client (Flutter/Dart)');
   print('═══════════════════════════════════════════════════════════════');
   
@@ -496,12 +844,21 @@ void main() async {{
   var frame = 0;
   
   try {{
-    final shell = VenomShell();
-    print('✅ Connected! Client ID: ${{shell.clientId}}');
+    final shell = VenomShell(channel: channelArg(args));
+    print('✅ Connected! Client ID: ${{shell.clientId}} (libvenom_memory ${{shell.libraryVersion}})');
+    final pings = pingArg(args);
+    if (pings > 0) {{
+      final replied = await runPing(shell, pings);
+      shell.dispose();
+      exit(replied ? 0 : 1);
+    }}
     print('📊 Reading system stats... (Ctrl+C to exit)\n');
     
+    listenForKeys(shell);
+    
     // Handle Ctrl+C for final stats
     ProcessSignal.sigint.watch().listen((_) {{
+      restoreTerminal();
       print('\n');
       print('📊 ${{cyan}}Final Latency Stats (Flutter/Dart):${{reset}}');
       print('   Samples: $latencyCount');
@@ -535,7 +892,7 @@ void main() async {{
         print('╔═══════════════════════════════════════════════════════════════╗');
         print('║  🖥️  {name} Monitor (Flutter)    Frame: ${{frame.toString().padRight(6)}}         ║');
         print('╠═══════════════════════════════════════════════════════════════╣');
-        print('║  CPU: ${{state.cpuUsage.toStringAsFixed(1).padLeft(5)}}%  |  '
+        print('║  CPU: ${{state.cpuUsagePercent.toStringAsFixed(1).padLeft(5)}}%  |  '
               'RAM: ${{state.memoryUsedMb}}/${{state.memoryTotalMb}} MB  |  '
               'Uptime: ${{state.uptimeFormatted}}  ║');
         print('╠═══════════════════════════════════════════════════════════════╣');
@@ -550,8 +907,10 @@ void main() async {{
         print('║  Memory: ${{state.memoryUsagePercent.toStringAsFixed(1)}}% used                                           ║');
         print('╠═══════════════════════════════════════════════════════════════╣');
         print('║  📊 ${{cyan}}Read Latency:${{reset}} ${{latencyUs.toStringAsFixed(2)}} µs (min: ${{latencyMin.toStringAsFixed(2)}}, max: ${{latencyMax.toStringAsFixed(2)}}, avg: ${{avgUs.toStringAsFixed(2)}})  ║');
+        print('║  📡 ${{cyan}}Daemon→client:${{reset}} ${{shell.writeAgeUs.toStringAsFixed(2)}} µs since the write                 ║');
         print('╚═══════════════════════════════════════════════════════════════╝');
-        print('  Updates: ${{state.updateCounter}} | Press Ctrl+C to exit');
+        print('  Updates: ${{state.updateCounter}} | Interval: $currentIntervalMs ms');
+        print('  +/- faster/slower | r refresh | Press Ctrl+C to exit');
         frame++;
       }} else {{
         print('⏳ Waiting for valid data from daemon...');
@@ -560,6 +919,7 @@ void main() async {{
       await Future.delayed(Duration(milliseconds: 100));
     }}
   }} catch (e) {{
+    restoreTerminal();
     print('❌ Error: $e');
     print('\nMake sure:');
     print('  1. The daemon is running');
@@ -567,11 +927,575 @@ void main() async {{
     exit(1);
   }}
 }}
-"#, name = config.name, snake = snake)
+"#, name = config.name, snake = snake, keys = COMMAND_KEYS)
+}
+
+/// Client for a custom schema: prints every field of the state
+fn generic_main_dart(config: &ProjectConfig) -> String {
+    let snake = config.names().snake;
+    let fields: String = config.schema.user_fields()
+        .map(|f| format!(
+            "        print('  {:<24} ${{state.{}}}');\n",
+            f.name,
+            camel_case(&f.name)
+        ))
+        .collect();
+    
+    format!(r#"/// {name} - VenomMemory Client - prints every field of the state (see venom.toml)
+
+import 'dart:io';
+import 'package:{snake}/venom_binding.dart';
+
+{keys}
+void main(List<String> args) async {{
+  print('🖥️  {name} Client (Flutter/Dart)');
+  print('═══════════════════════════════════════════════════════════════');
+  
+  var latencyMin = double.maxFinite;
+  var latencyMax = 0.0;
+  var latencySum = 0.0;
+  var latencyCount = 0;
+  
+  try {{
+    final shell = VenomShell(channel: channelArg(args));
+    print('✅ Connected! Client ID: ${{shell.clientId}} (libvenom_memory ${{shell.libraryVersion}})');
+    final pings = pingArg(args);
+    if (pings > 0) {{
+      final replied = await runPing(shell, pings);
+      shell.dispose();
+      exit(replied ? 0 : 1);
+    }}
+    
+    listenForKeys(shell);
+    ProcessSignal.sigint.watch().listen((_) {{
+      restoreTerminal();
+      exit(0);
+    }});
+    
+    while (true) {{
+      final stopwatch = Stopwatch()..start();
+      final state = shell.readState();
+      stopwatch.stop();
+      final latencyUs = stopwatch.elapsedMicroseconds.toDouble();
+      
+      if (latencyUs < latencyMin) latencyMin = latencyUs;
+      if (latencyUs > latencyMax) latencyMax = latencyUs;
+      latencySum += latencyUs;
+      latencyCount++;
+      
+      if (state.isValid) {{
+        stdout.write('\x1B[2J\x1B[H');
+        print('🖥️  {name} | State v${{state.version}} | $stateSize bytes');
+        print('═══════════════════════════════════════════════════════════════');
+{fields}        print('═══════════════════════════════════════════════════════════════');
+        print('📊 Read Latency: ${{latencyUs.toStringAsFixed(2)}} µs (min: ${{latencyMin.toStringAsFixed(2)}}, max: ${{latencyMax.toStringAsFixed(2)}}, avg: ${{(latencySum / latencyCount).toStringAsFixed(2)}})');
+        print('📡 Daemon→client: ${{shell.writeAgeUs.toStringAsFixed(2)}} µs since the write');
+        print('⏱️  Daemon interval: $currentIntervalMs ms | +/- faster/slower | r refresh');
+      }} else {{
+        print('⏳ Waiting for valid data from daemon...');
+      }}
+      
+      await Future.delayed(Duration(milliseconds: 100));
+    }}
+  }} catch (e) {{
+    restoreTerminal();
+    print('❌ Error: $e');
+    exit(1);
+  }}
+}}
+"#, name = config.name, snake = snake, fields = fields, keys = COMMAND_KEYS)
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Flutter App
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// README section on the app's platform setup
+fn app_readme(config: &ProjectConfig) -> String {
+    let android: Vec<String> = config
+        .targets
+        .iter()
+        .filter_map(|t| android_abi(t).map(|abi| format!("`jniLibs/{}/` ({})", abi, t)))
+        .collect();
+    let android = if android.is_empty() {
+        "No Android library is bundled yet: regenerate with `--target aarch64-linux-android`\n\
+         (its build comes from `$VENOM_LIB_DIR/aarch64-linux-android/`) and it is copied to\n\
+         `android/app/src/main/jniLibs/arm64-v8a/`.".to_string()
+    } else {
+        format!("The library is in `android/app/src/main/jniLibs/`: {}.", android.join(", "))
+    };
+    format!(r#"
+## Flutter App
+
+`lib/main.dart` shows CPU, per-core and memory usage as the daemon publishes
+them. `VenomReader` (`lib/venom_reader.dart`) keeps the `VenomShell` on a
+background isolate and samples it with `Timer.periodic`, so the UI isolate
+only receives copies of the state. The toolbar buttons change the daemon's
+publish interval and ask for a refresh.
+
+`flutter create` adds the platform runners this project doesn't ship (it
+keeps the files that already exist). It also adds a `test/widget_test.dart`
+for its counter demo, which doesn't apply here.
+
+### Linux desktop
+
+`linux/venom_memory.cmake` installs the library into the app bundle's `lib/`,
+where `venom_binding.dart` looks first. Include it at the end of
+`linux/CMakeLists.txt` (the `echo` in Quick Start does that), then
+`flutter run -d linux` or `flutter build linux`.
+
+### Android
+
+{android} The loader finds it by name, and
+`venom_binding.dart` opens it that way on Android. The daemon has to run on
+the same device, with a channel the app can open.
+"#, android = android)
+}
+
+/// `lib/venom_reader.dart`: the shell lives on its own isolate and sends
+/// each read to the UI isolate, so neither can hold up the other
+fn venom_reader_dart(config: &ProjectConfig) -> String {
+    let pascal = config.names().pascal;
+    format!(r#"/// Reads the {name} channel on a background isolate
+///
+/// The VenomShell (a native handle) never leaves the reader isolate: a
+/// Timer.periodic there reads the state, and the UI isolate gets a copy of
+/// the bytes through a port. Commands go the other way.
+
+import 'dart:async';
+import 'dart:isolate';
+import 'dart:typed_data';
+
+import 'venom_binding.dart';
+
+class VenomReader {{
+  VenomReader._(this._isolate, this._port, this._commands, this._states);
+
+  final Isolate _isolate;
+  final ReceivePort _port;
+  final SendPort _commands;
+  final StreamController<{pascal}State> _states;
+
+  /// States as the reader samples them, every `period` of [start]
+  Stream<{pascal}State> get states => _states.stream;
+
+  /// Connect on a new isolate; throws if the daemon isn't running
+  static Future<VenomReader> start({{Duration period = const Duration(milliseconds: 100)}}) async {{
+    final port = ReceivePort();
+    final ready = Completer<SendPort>();
+    final states = StreamController<{pascal}State>.broadcast();
+    port.listen((message) {{
+      if (message is SendPort) {{
+        ready.complete(message);
+      }} else if (message is TransferableTypedData) {{
+        states.add({pascal}State.fromBytes(message.materialize().asUint8List()));
+      }} else if (!ready.isCompleted) {{
+        ready.completeError(Exception(message));
+      }}
+    }});
+    final isolate = await Isolate.spawn(_read, (port.sendPort, period));
+    try {{
+      return VenomReader._(isolate, port, await ready.future, states);
+    }} catch (_) {{
+      port.close();
+      isolate.kill();
+      await states.close();
+      rethrow;
+    }}
+  }}
+
+  void setInterval(int ms) => _commands.send(encodeCommand(cmdSetInterval, ms));
+
+  void refresh() => _commands.send(encodeCommand(cmdRefresh));
+
+  /// Disconnect; the reader isolate disposes its shell and exits
+  void dispose() {{
+    _commands.send(null);
+    _port.close();
+    _states.close();
+  }}
+}}
+
+/// Reader isolate: replies with its command port (or an error message),
+/// then with the state bytes on every tick
+void _read((SendPort, Duration) start) {{
+  final (replies, period) = start;
+  final VenomShell shell;
+  try {{
+    shell = VenomShell();
+  }} catch (e) {{
+    replies.send('$e');
+    return;
+  }}
+  final commands = ReceivePort();
+  replies.send(commands.sendPort);
+
+  final timer = Timer.periodic(period, (_) {{
+    replies.send(TransferableTypedData.fromList([shell.readRawData(stateSize + 64)]));
+  }});
+  commands.listen((message) {{
+    if (message is Uint8List) {{
+      shell.sendCommand(message);
+      return;
+    }}
+    timer.cancel();
+    commands.close();
+    shell.dispose();
+  }});
+}}
+"#, name = config.name, pascal = pascal)
+}
+
+/// `lib/main.dart`: a MaterialApp showing the state as it changes
+fn app_main_dart(config: &ProjectConfig) -> String {
+    let pascal = config.names().pascal;
+    let snake = config.names().snake;
+    let (stats, widgets) = if config.schema.has_demo_fields() {
+        (demo_stats_dart(&pascal), DEMO_WIDGETS.to_string())
+    } else {
+        (generic_stats_dart(config, &pascal), String::new())
+    };
+    
+    format!(r#"/// {name} - VenomMemory Flutter app
+///
+/// Shows the state the daemon publishes, live. Start the daemon first
+/// (cd daemon && make run), then: flutter run -d linux
+
+import 'dart:async';
+
+import 'package:flutter/material.dart';
+import 'package:{snake}/venom_binding.dart';
+import 'package:{snake}/venom_reader.dart';
+
+void main() => runApp(const {pascal}App());
+
+class {pascal}App extends StatelessWidget {{
+  const {pascal}App({{super.key}});
+
+  @override
+  Widget build(BuildContext context) {{
+    return MaterialApp(
+      title: '{name}',
+      theme: ThemeData(colorSchemeSeed: Colors.teal, brightness: Brightness.dark, useMaterial3: true),
+      home: const MonitorPage(),
+    );
+  }}
+}}
+
+class MonitorPage extends StatefulWidget {{
+  const MonitorPage({{super.key}});
+
+  @override
+  State<MonitorPage> createState() => _MonitorPageState();
+}}
+
+class _MonitorPageState extends State<MonitorPage> {{
+  VenomReader? _reader;
+  StreamSubscription<{pascal}State>? _subscription;
+  {pascal}State? _state;
+  Object? _error;
+  var _intervalMs = intervalMs;
+
+  @override
+  void initState() {{
+    super.initState();
+    _connect();
+  }}
+
+  Future<void> _connect() async {{
+    try {{
+      final reader = await VenomReader.start();
+      if (!mounted) {{
+        reader.dispose();
+        return;
+      }}
+      _reader = reader;
+      _subscription = reader.states.listen((state) => setState(() => _state = state));
+    }} catch (e) {{
+      if (mounted) setState(() => _error = e);
+    }}
+  }}
+
+  @override
+  void dispose() {{
+    _subscription?.cancel();
+    _reader?.dispose();
+    super.dispose();
+  }}
+
+  /// The daemon's publish interval, 10-10000 ms
+  void _setInterval(int ms) {{
+    setState(() => _intervalMs = ms.clamp(10, 10000));
+    _reader?.setInterval(_intervalMs);
+  }}
+
+  @override
+  Widget build(BuildContext context) {{
+    final state = _state;
+    final Widget body;
+    if (_error != null) {{
+      body = _Message(
+        icon: Icons.link_off,
+        text: '$_error\n\nIs the daemon running? (cd daemon && make run)',
+        action: FilledButton(
+          onPressed: () {{
+            setState(() => _error = null);
+            _connect();
+          }},
+          child: const Text('Retry'),
+        ),
+      );
+    }} else if (state == null || !state.isValid) {{
+      body = const _Message(icon: Icons.hourglass_empty, text: 'Waiting for valid data from the daemon...');
+    }} else {{
+      body = _Stats(state: state);
+    }}
+    return Scaffold(
+      appBar: AppBar(
+        title: const Text('{name}'),
+        actions: [
+          IconButton(tooltip: 'Slower', icon: const Icon(Icons.remove), onPressed: () => _setInterval(_intervalMs * 2)),
+          Center(child: Text('$_intervalMs ms')),
+          IconButton(tooltip: 'Faster', icon: const Icon(Icons.add), onPressed: () => _setInterval(_intervalMs ~/ 2)),
+          IconButton(tooltip: 'Refresh now', icon: const Icon(Icons.refresh), onPressed: () => _reader?.refresh()),
+        ],
+      ),
+      body: body,
+    );
+  }}
+}}
+
+class _Message extends StatelessWidget {{
+  const _Message({{required this.icon, required this.text, this.action}});
+
+  final IconData icon;
+  final String text;
+  final Widget? action;
+
+  @override
+  Widget build(BuildContext context) {{
+    return Center(
+      child: Padding(
+        padding: const EdgeInsets.all(24),
+        child: Column(
+          mainAxisSize: MainAxisSize.min,
+          children: [
+            Icon(icon, size: 48),
+            const SizedBox(height: 16),
+            Text(text, textAlign: TextAlign.center),
+            if (action != null) ...[const SizedBox(height: 16), action!],
+          ],
+        ),
+      ),
+    );
+  }}
+}}
+
+{stats}{widgets}"#,
+        name = config.name,
+        pascal = pascal,
+        snake = snake,
+        stats = stats,
+        widgets = widgets,
+    )
+}
+
+/// System-monitor demo: CPU and memory gauges, one bar per core
+fn demo_stats_dart(pascal: &str) -> String {
+    format!(r#"class _Stats extends StatelessWidget {{
+  const _Stats({{required this.state}});
+
+  final {pascal}State state;
+
+  @override
+  Widget build(BuildContext context) {{
+    final theme = Theme.of(context);
+    final cores = state.coreCount.clamp(0, maxCores);
+    return ListView(
+      padding: const EdgeInsets.all(24),
+      children: [
+        Wrap(
+          alignment: WrapAlignment.spaceEvenly,
+          spacing: 24,
+          runSpacing: 24,
+          children: [
+            _Gauge(label: 'CPU', percent: state.cpuUsagePercent, detail: '$cores cores'),
+            _Gauge(
+              label: 'Memory',
+              percent: state.memoryUsagePercent,
+              detail: '${{state.memoryUsedMb}} / ${{state.memoryTotalMb}} MB',
+            ),
+          ],
+        ),
+        const SizedBox(height: 32),
+        Text('Cores', style: theme.textTheme.titleMedium),
+        const SizedBox(height: 8),
+        for (var i = 0; i < cores; i++) _CoreBar(index: i, percent: state.cpuCores[i]),
+        const SizedBox(height: 24),
+        Text(
+          'Uptime ${{state.uptimeFormatted}} · update #${{state.updateCounter}}',
+          style: theme.textTheme.bodySmall,
+        ),
+      ],
+    );
+  }}
+}}
+"#, pascal = pascal)
+}
+
+/// Widgets of the system-monitor demo
+const DEMO_WIDGETS: &str = r#"
+/// Green below 50%, orange below 80%, red above
+Color _loadColor(double percent) {
+  if (percent < 50) return Colors.green;
+  if (percent < 80) return Colors.orange;
+  return Colors.red;
+}
+
+class _Gauge extends StatelessWidget {
+  const _Gauge({required this.label, required this.percent, required this.detail});
+
+  final String label;
+  final double percent;
+  final String detail;
+
+  @override
+  Widget build(BuildContext context) {
+    final theme = Theme.of(context);
+    return Column(
+      children: [
+        SizedBox.square(
+          dimension: 160,
+          child: Stack(
+            fit: StackFit.expand,
+            children: [
+              CircularProgressIndicator(
+                value: (percent / 100).clamp(0.0, 1.0),
+                strokeWidth: 14,
+                color: _loadColor(percent),
+                backgroundColor: Colors.white10,
+              ),
+              Center(child: Text('${percent.toStringAsFixed(1)}%', style: theme.textTheme.headlineSmall)),
+            ],
+          ),
+        ),
+        const SizedBox(height: 12),
+        Text(label, style: theme.textTheme.titleMedium),
+        Text(detail, style: theme.textTheme.bodySmall),
+      ],
+    );
+  }
+}
+
+class _CoreBar extends StatelessWidget {
+  const _CoreBar({required this.index, required this.percent});
+
+  final int index;
+  final double percent;
+
+  @override
+  Widget build(BuildContext context) {
+    return Padding(
+      padding: const EdgeInsets.symmetric(vertical: 4),
+      child: Row(
+        children: [
+          SizedBox(width: 72, child: Text('Core $index')),
+          Expanded(
+            child: LinearProgressIndicator(
+              value: (percent / 100).clamp(0.0, 1.0),
+              minHeight: 10,
+              color: _loadColor(percent),
+              backgroundColor: Colors.white10,
+            ),
+          ),
+          SizedBox(width: 72, child: Text('${percent.toStringAsFixed(1)}%', textAlign: TextAlign.end)),
+        ],
+      ),
+    );
+  }
+}
+"#;
+
+/// Custom schema: one row per field of the state
+fn generic_stats_dart(config: &ProjectConfig, pascal: &str) -> String {
+    let rows: String = config.schema.user_fields()
+        .map(|f| format!(
+            "        ListTile(dense: true, title: const Text('{}'), trailing: Text('${{state.{}}}')),\n",
+            f.name,
+            camel_case(&f.name)
+        ))
+        .collect();
+    format!(r#"class _Stats extends StatelessWidget {{
+  const _Stats({{required this.state}});
+
+  final {pascal}State state;
+
+  @override
+  Widget build(BuildContext context) {{
+    return ListView(
+      padding: const EdgeInsets.symmetric(vertical: 8),
+      children: [
+{rows}        ListTile(dense: true, title: const Text('version'), trailing: Text('${{state.version}} ($stateSize bytes)')),
+      ],
+    );
+  }}
+}}
+"#, pascal = pascal, rows = rows)
+}
+
+/// `linux/venom_memory.cmake`: installs the library into the app bundle's
+/// `lib/`, which `_findLibraryPath` searches first
+fn linux_cmake(config: &ProjectConfig) -> String {
+    let install = if config.targets.is_empty() {
+        "install(FILES \"${CMAKE_CURRENT_SOURCE_DIR}/../native/libvenom_memory.so\"\n  \
+         DESTINATION \"${INSTALL_BUNDLE_LIB_DIR}\" COMPONENT Runtime)\n".to_string()
+    } else {
+        // Each triple keeps its directory; the binding loads the one built for this machine
+        config
+            .targets
+            .iter()
+            .filter(|t| android_abi(t).is_none())
+            .map(|t| format!(
+                "install(DIRECTORY \"${{CMAKE_CURRENT_SOURCE_DIR}}/../native/{}\"\n  \
+                 DESTINATION \"${{INSTALL_BUNDLE_LIB_DIR}}\" COMPONENT Runtime)\n",
+                t
+            ))
+            .collect()
+    };
+    format!(r#"# Bundle libvenom_memory.so with the {name} Linux app (bundle/lib/).
+# `flutter create --platforms=linux .` generates linux/CMakeLists.txt; include
+# this file at its end, after INSTALL_BUNDLE_LIB_DIR is set:
+#
+#   include(${{CMAKE_CURRENT_SOURCE_DIR}}/venom_memory.cmake)
+
+{install}"#, name = config.name, install = install)
 }
 
 fn pubspec(config: &ProjectConfig) -> String {
-    let snake = config.name.replace("-", "_");
+    let snake = config.names().snake;
+    if config.flutter_ui {
+        return format!(r#"name: {snake}
+description: VenomMemory client for {name} - Real-time system monitoring via shared memory IPC
+publish_to: 'none'
+version: 1.0.0+1
+
+environment:
+  sdk: '>=3.0.0 <4.0.0'
+  flutter: '>=3.10.0'
+
+dependencies:
+  flutter:
+    sdk: flutter
+  ffi: ^2.1.0
+
+dev_dependencies:
+  flutter_test:
+    sdk: flutter
+
+flutter:
+  uses-material-design: true
+"#, name = config.name, snake = snake);
+    }
     format!(r#"name: {snake}
 description: VenomMemory client for {name} - Real-time system monitoring via shared memory IPC
 
@@ -580,11 +1504,50 @@ environment:
 
 dependencies:
   ffi: ^2.1.0
+
+dev_dependencies:
+  test: ^1.24.0
 "#, name = config.name, snake = snake)
 }
 
 fn readme(config: &ProjectConfig) -> String {
-    let pascal = pascal_case(&config.name);
+    let pascal = config.names().pascal;
+    let snake = config.names().snake;
+    let client = format!("{}.dart", snake);
+    let (lib_files, quick_start) = if config.flutter_ui {
+        (
+            format!(
+                "│   ├── venom_binding.dart   # FFI bindings & {}State\n\
+                 │   ├── main.dart            # Flutter app\n\
+                 │   ├── venom_reader.dart    # Background-isolate reader for the app\n\
+                 │   └── offsets.json         # Offsets the binding reads (venom init --verify)\n",
+                pascal
+            ),
+            format!(r#"# Make sure daemon is running first! (cd daemon && make run)
+
+# Flutter app (once: add the platform runners; see Flutter App below)
+flutter create --platforms=linux,android --project-name {snake} .
+rm test/widget_test.dart
+echo 'include(${{CMAKE_CURRENT_SOURCE_DIR}}/venom_memory.cmake)' >> linux/CMakeLists.txt
+flutter run -d linux
+
+# Console client (headless)
+flutter pub get
+dart run bin/{snake}.dart"#, snake = snake),
+        )
+    } else {
+        (
+            format!(
+                "│   ├── venom_binding.dart   # FFI bindings & {}State\n\
+                 │   └── offsets.json         # Offsets the binding reads (venom init --verify)\n",
+                pascal
+            ),
+            format!(r#"# Make sure daemon is running first!
+
+# Run the Dart client
+dart run bin/{snake}.dart"#, snake = snake),
+        )
+    };
     format!(r#"# {name} (Flutter/Dart)
 
 VenomMemory Flutter client for real-time system monitoring.
@@ -594,8 +1557,10 @@ VenomMemory Flutter client for real-time system monitoring.
 ```
 {name}/
 ├── lib/
-│   ├── venom_binding.dart   # FFI bindings & {pascal}State
-│   └── main.dart            # Example client
+{lib_files}├── bin/
+│   └── {client:<20} # Console client
+├── test/
+│   └── venom_binding_test.dart
 ├── native/
 │   └── libvenom_memory.so   # Bundled VenomMemory library
 └── pubspec.yaml
@@ -604,12 +1569,15 @@ VenomMemory Flutter client for real-time system monitoring.
 ## Quick Start
 
 ```bash
-# Make sure daemon is running first!
-
-# Run the Dart client
-dart run
+{quick_start}
 ```
 
+## Tests
+
+`{test_cmd}` decodes a known state built from `venom.toml`, so it fails when
+`lib/venom_binding.dart` no longer matches the daemon's layout. With the
+daemon running, `{live_env}=1 {test_cmd}` also reads the live channel.
+{ping}{instances}{app_section}
 ## Usage in Your Code
 
 ```dart
@@ -622,7 +1590,7 @@ void main() {{
   // Read system stats
   final state = shell.readState();
   if (state.isValid) {{
-    print('CPU: ${{state.cpuUsage.toStringAsFixed(1)}}%');
+    print('CPU: ${{state.cpuUsagePercent.toStringAsFixed(1)}}%');
     print('RAM: ${{state.memoryUsedMb}}/${{state.memoryTotalMb}} MB');
     print('Uptime: ${{state.uptimeFormatted}}');
   }}
@@ -643,11 +1611,18 @@ void main() {{
 
 - The library is bundled in `native/libvenom_memory.so`
 - Make sure the daemon is running before starting the client
-- For Flutter mobile apps, you'll need platform-specific library setup
-"#,
+{service}"#,
         name = config.name,
+        client = client,
+        lib_files = lib_files,
+        quick_start = quick_start,
+        app_section = if config.flutter_ui { app_readme(config) } else { String::new() },
+        test_cmd = if config.flutter_ui { "flutter test" } else { "dart test" },
+        live_env = super::LIVE_TEST_ENV,
+        ping = super::ping_readme_section(&format!("dart run bin/{}.dart --ping 10", snake)),
+        instances = super::instances_readme_section(&config.channel, "cd daemon && make run ARGS=\"--channel-suffix SUFFIX\"", &format!("dart run bin/{}.dart --channel-suffix SUFFIX", snake), "`instanceChannel()` in `lib/venom_binding.dart`"),
+        service = super::service::readme_section(config),
         channel = config.channel,
-        magic = magic(&config.channel),
-        pascal = pascal
+        magic = config.magic,
     )
 }
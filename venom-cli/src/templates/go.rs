@@ -1,11 +1,14 @@
 //! Go Templates for VenomMemory projects
 //!
 //! Generates a complete Go project with:
-//! - CGO bindings for VenomMemory
+//! - CGO bindings for VenomMemory, or with `--go-nocgo` a pure-Go client
+//!   that maps the channel itself
 //! - System monitor daemon
 //! - Status bar client
 
-use super::ProjectConfig;
+use super::{DecodedField, DecoderOffsets, ProjectConfig};
+use crate::schema::FieldType;
+use venom_memory::layout;
 
 pub fn generate(config: &ProjectConfig) {
     let base = &config.output_dir;
@@ -23,99 +26,1018 @@ pub fn generate(config: &ProjectConfig) {
     crate::write_file(&format!("{}/venom/venom.go", base), &venom_go(config));
     crate::create_dir(&format!("{}/venom", base));
     crate::write_file(&format!("{}/venom/venom.go", base), &venom_go(config));
+    crate::write_file(&format!("{}/venom/venom_test.go", base), &venom_test_go(config));
+    state_codec(config).offsets.write(&format!("{}/venom", base));
+    if config.go_nocgo {
+        crate::write_file(&format!("{}/venom/daemon.go", base), &daemon_cgo_go(config));
+    }
     
     // go.mod
     crate::write_file(&format!("{}/go.mod", base), &go_mod(config));
     
     // Makefile
-    crate::write_file(&format!("{}/Makefile", base), &makefile(config));
+    crate::write_file(&format!("{}/Makefile", base), &makefile(config, true));
     
     // README
     crate::write_file(&format!("{}/README.md", base), &readme(config));
 }
 
-fn magic(channel: &str) -> u32 {
-    channel.bytes().fold(0x564E4Fu32, |acc, b| acc.wrapping_add(b as u32))
+/// Client files only (used by `venom add-client`)
+pub fn generate_client(config: &ProjectConfig) {
+    let base = &config.output_dir;
+    
+    crate::create_dir(&format!("{}/client", base));
+    crate::create_dir(&format!("{}/venom", base));
+    crate::write_file(&format!("{}/client/main.go", base), &client_main(config));
+    crate::write_file(&format!("{}/venom/venom.go", base), &venom_go(config));
+    crate::write_file(&format!("{}/venom/venom_test.go", base), &venom_test_go(config));
+    state_codec(config).offsets.write(&format!("{}/venom", base));
+    crate::write_file(&format!("{}/go.mod", base), &go_mod(config));
+    crate::write_file(&format!("{}/Makefile", base), &makefile(config, false));
 }
 
-fn pascal_case(s: &str) -> String {
-    s.split(|c| c == '_' || c == '-')
-        .map(|word| {
-            let mut chars = word.chars();
-            match chars.next() {
-                None => String::new(),
-                Some(first) => first.to_uppercase().chain(chars).collect(),
-            }
-        })
-        .collect()
-}
 
 // ═══════════════════════════════════════════════════════════════════════════
 // Venom bindings (Go + CGO)
 // ═══════════════════════════════════════════════════════════════════════════
 
-fn venom_go(config: &ProjectConfig) -> String {
-    let pascal = pascal_case(&config.name);
+pub(super) fn venom_go(config: &ProjectConfig) -> String {
+    let pascal = config.names().pascal;
+    let schema = &config.schema;
     
+    let StateCodec { fields, encode, decode, .. } = state_codec(config);
+    let demo = schema.has_demo_fields();
+    let max_cores = match schema.field("cpu_cores") {
+        Some(f) if demo => format!("\tMaxCores    = {}\n", f.count()),
+        _ => String::new(),
+    };
+    
+    let types = format!(r##"// ═══════════════════════════════════════════════════════════════════════════
+// Configuration
+// ═══════════════════════════════════════════════════════════════════════════
+
+const (
+	ChannelName = "{channel}"
+	Magic       = 0x{magic:08X}
+	DataSize    = {data_size}
+	CmdSlots    = {cmd_slots}
+	MaxClients  = {max_clients}
+	StateSize   = {state_size}
+{max_cores})
+
+// InstanceChannel is the channel of one of several instances:
+// "{channel}-suffix", or ChannelName itself for an empty suffix
+func InstanceChannel(suffix string) string {{
+	if suffix == "" {{
+		return ChannelName
+	}}
+	return ChannelName + "-" + suffix
+}}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// State Structure
+// ═══════════════════════════════════════════════════════════════════════════
+
+// {pascal}State mirrors the packed C struct (generated from venom.toml)
+type {pascal}State struct {{
+{fields}}}
+
+func (s *{pascal}State) IsValid() bool {{
+	return s.Magic == Magic
+}}
+{helpers}
+func (s *{pascal}State) ToBytes() []byte {{
+	buf := make([]byte, StateSize)
+{encode}	return buf
+}}
+
+func StateFromBytes(data []byte) *{pascal}State {{
+	if len(data) < StateSize {{
+		return &{pascal}State{{}}
+	}}
+	s := &{pascal}State{{}}
+{decode}	return s
+}}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// 📨 Commands (Clients send, Daemon applies)
+// ═══════════════════════════════════════════════════════════════════════════
+
+const (
+	IntervalMS     = 100 // Default publish interval
+	CommandSize    = 8   // Packed size of Command
+	CmdRefresh     = 1   // Publish immediately
+	CmdSetInterval = 2   // Value: publish interval in ms (10-10000)
+	CmdPing        = 3   // Value: sequence number, echoed back in a Pong
+	PongSize       = 8   // Packed size of Pong
+	SendTimeoutMS  = 100 // How long SendCommand waits for room in a full queue
+)
+
+// Command mirrors the packed C struct, a typed command: cmd u16 (the tag),
+// len u16 (4, the bytes of Value), value i32
+type Command struct {{
+	Cmd   uint16
+	Value int32
+}}
+
+func (c Command) ToBytes() []byte {{
+	buf := make([]byte, CommandSize)
+	binary.LittleEndian.PutUint16(buf, c.Cmd)
+	binary.LittleEndian.PutUint16(buf[2:], 4)
+	binary.LittleEndian.PutUint32(buf[4:], uint32(c.Value))
+	return buf
+}}
+
+// CommandFromBytes decodes a Command; false for messages too short, or typed
+// commands with another payload
+func CommandFromBytes(data []byte) (Command, bool) {{
+	if len(data) < CommandSize || binary.LittleEndian.Uint16(data[2:]) != 4 {{
+		return Command{{}}, false
+	}}
+	return Command{{Cmd: binary.LittleEndian.Uint16(data), Value: int32(binary.LittleEndian.Uint32(data[4:]))}}, true
+}}
+
+// Pong is the reply to the last CmdPing, published right after the state
+type Pong struct {{
+	ClientID uint32 // Client that sent the ping
+	Seq      uint32 // Its value
+}}
+
+func (p Pong) ToBytes() []byte {{
+	buf := make([]byte, PongSize)
+	binary.LittleEndian.PutUint32(buf, p.ClientID)
+	binary.LittleEndian.PutUint32(buf[4:], p.Seq)
+	return buf
+}}
+
+func PongFromBytes(data []byte) (Pong, bool) {{
+	if len(data) < PongSize {{
+		return Pong{{}}, false
+	}}
+	return Pong{{ClientID: binary.LittleEndian.Uint32(data), Seq: binary.LittleEndian.Uint32(data[4:])}}, true
+}}
+
+"##,
+        channel = config.channel,
+        magic = config.magic,
+        data_size = config.data_size,
+        cmd_slots = config.cmd_slots,
+        max_clients = config.max_clients,
+        pascal = pascal,
+        state_size = schema.size,
+        max_cores = max_cores,
+        fields = fields,
+        encode = encode,
+        decode = decode,
+        helpers = if demo { demo_helpers(&pascal) } else { String::new() },
+    );
+    // Only float fields need math.Float*bits, and Go rejects unused imports
+    let math_import = if schema.fields.iter().any(|f| f.ty.is_float()) { "\t\"math\"\n" } else { "" };
+    if config.go_nocgo {
+        return pure_venom_go(&pascal, &types, math_import);
+    }
+
     format!(r##"package venom
 
 /*
-#cgo LDFLAGS: -L${{SRCDIR}}/../lib -lvenom_memory -Wl,-rpath,$ORIGIN/../lib
+{cgo_ldflags}
 #include <stdint.h>
 #include <stddef.h>
 #include <stdlib.h>
+#include <stdbool.h>
 
-typedef struct {{ size_t data_size; size_t cmd_slots; size_t max_clients; }} VenomConfig;
-void* venom_daemon_create(const char* name, VenomConfig config);
-void venom_daemon_destroy(void* handle);
-void venom_daemon_write_data(void* handle, const uint8_t* data, size_t len);
+{daemon_decls}
 
-void* venom_shell_connect(const char* name);
+void* venom_shell_connect_named(const char* name, const char* client_name);
 void venom_shell_destroy(void* handle);
-size_t venom_shell_read_data(void* handle, uint8_t* buf, size_t max_len);
+size_t venom_shell_read_data_stamped(void* handle, uint8_t* buf, size_t max_len, uint64_t* out_timestamp_ns);
+uint64_t venom_clock_ns(void);
+const char* venom_version(void);
 uint32_t venom_shell_id(void* handle);
+int32_t venom_shell_send_command_timeout(void* handle, const uint8_t* cmd, size_t len, uint32_t timeout_ms);
+bool venom_shell_daemon_alive(void* handle);
 */
 import "C"
 import (
 	"encoding/binary"
 	"fmt"
+{math_import}	"time"
 	"unsafe"
 )
 
+{types}{daemon}// ═══════════════════════════════════════════════════════════════════════════
+// Shell (Client)
 // ═══════════════════════════════════════════════════════════════════════════
-// Configuration
+
+type Shell struct {{
+	handle unsafe.Pointer
+	// WriteAgeUs is how long before the last ReadState the daemon wrote it
+	WriteAgeUs float64
+}}
+
+// Connect connects without a name; ConnectNamed registers one that the
+// daemon shows in its log (the library keeps its first 32 bytes)
+func Connect() (*Shell, error) {{
+	return ConnectNamed("")
+}}
+
+func ConnectNamed(clientName string) (*Shell, error) {{
+	return ConnectChannel(ChannelName, clientName)
+}}
+
+// ConnectChannel connects to another channel than ChannelName, such as
+// one InstanceChannel names
+func ConnectChannel(channel, clientName string) (*Shell, error) {{
+	name := C.CString(channel)
+	defer C.free(unsafe.Pointer(name))
+	cName := C.CString(clientName)
+	defer C.free(unsafe.Pointer(cName))
+	
+	handle := C.venom_shell_connect_named(name, cName)
+	if handle == nil {{
+		return nil, fmt.Errorf("failed to connect - is daemon running?")
+	}}
+	return &Shell{{handle: handle}}, nil
+}}
+
+func (s *Shell) ClientID() uint32 {{
+	return uint32(C.venom_shell_id(s.handle))
+}}
+
+// DaemonAlive is false once the daemon has exited; reads then return its last state
+func (s *Shell) DaemonAlive() bool {{
+	return bool(C.venom_shell_daemon_alive(s.handle))
+}}
+
+// Library is the libvenom_memory that was loaded, with its version
+func Library() string {{
+	return "libvenom_memory " + C.GoString(C.venom_version())
+}}
+
+func (s *Shell) ReadState() *{pascal}State {{
+	buf := make([]byte, StateSize+64)
+	var writtenNs C.uint64_t
+	n := C.venom_shell_read_data_stamped(s.handle, (*C.uint8_t)(&buf[0]), C.size_t(len(buf)), &writtenNs)
+	if writtenNs != 0 {{
+		s.WriteAgeUs = float64(C.venom_clock_ns()-writtenNs) / 1000.0
+	}}
+	return StateFromBytes(buf[:n])
+}}
+
+// ReadData copies the latest payload into buf and returns how many bytes it copied
+func (s *Shell) ReadData(buf []byte) int {{
+	return int(C.venom_shell_read_data_stamped(s.handle, (*C.uint8_t)(&buf[0]), C.size_t(len(buf)), nil))
+}}
+
+// SendCommand queues cmd, waiting up to SendTimeoutMS for room if the
+// daemon has fallen behind; false if the queue stayed full
+func (s *Shell) SendCommand(cmd Command) bool {{
+	return s.SendCommandTimeout(cmd, SendTimeoutMS*time.Millisecond)
+}}
+
+// SendCommandTimeout is SendCommand with its own wait; 0 doesn't wait at all
+func (s *Shell) SendCommandTimeout(cmd Command, timeout time.Duration) bool {{
+	data := cmd.ToBytes()
+	return C.venom_shell_send_command_timeout(s.handle, (*C.uint8_t)(&data[0]), C.size_t(len(data)), C.uint32_t(timeout.Milliseconds())) == 0
+}}
+
+func (s *Shell) SetInterval(ms uint32) bool {{
+	return s.SendCommand(Command{{Cmd: CmdSetInterval, Value: int32(ms)}})
+}}
+
+func (s *Shell) Refresh() bool {{
+	return s.SendCommand(Command{{Cmd: CmdRefresh}})
+}}
+{ping}
+func (s *Shell) Close() {{
+	if s.handle != nil {{
+		C.venom_shell_destroy(s.handle)
+		s.handle = nil
+	}}
+}}
+"##,
+        cgo_ldflags = cgo_ldflags(config),
+        daemon_decls = CGO_DAEMON_DECLS,
+        math_import = math_import,
+        types = types,
+        daemon = daemon_go(&pascal, config.shm_mode),
+        ping = SHELL_PING,
+        pascal = pascal,
+    )
+}
+
+/// `Shell.Ping`, over either variant's `SendCommand` and `ReadData`
+const SHELL_PING: &str = r#"
+// Ping times a CmdPing carrying seq; false if no pong came back within a second
+//
+// The daemon answers by publishing at once with a Pong after the state. It
+// keeps only the last one, so clients pinging at the same time can miss
+// their reply.
+func (s *Shell) Ping(seq uint32) (time.Duration, bool) {
+	sent := time.Now()
+	if !s.SendCommand(Command{Cmd: CmdPing, Value: int32(seq)}) {
+		return 0, false
+	}
+	buf := make([]byte, StateSize+PongSize)
+	for time.Since(sent) < time.Second {
+		if s.ReadData(buf) < len(buf) {
+			continue
+		}
+		if pong, _ := PongFromBytes(buf[StateSize:]); pong.ClientID == s.ClientID() && pong.Seq == seq {
+			return time.Since(sent), true
+		}
+	}
+	return 0, false
+}
+"#;
+
+/// cgo declarations of the daemon half of the C API
+const CGO_DAEMON_DECLS: &str = "typedef struct { size_t struct_size; size_t data_size; size_t cmd_slots; size_t max_clients; size_t cmd_payload_size; size_t cmd_full_policy; size_t shm_mode; } VenomConfigEx;
+void* venom_daemon_create_ex(const char* name, const VenomConfigEx* config);
+void venom_daemon_destroy(void* handle);
+void venom_daemon_write_data(void* handle, const uint8_t* data, size_t len);
+size_t venom_daemon_try_recv_command(void* handle, uint8_t* buf, size_t max_len, uint32_t* out_client_id);
+size_t venom_daemon_client_name(void* handle, uint32_t client_id, char* buf, size_t max_len);";
+
+/// `Daemon`, over cgo in both variants
+/// The state as `venom.go` handles it: struct fields, `ToBytes` and
+/// `FromBytes` bodies, and the offsets those two use
+struct StateCodec {
+    fields: String,
+    encode: String,
+    decode: String,
+    offsets: DecoderOffsets,
+}
+
+fn state_codec(config: &ProjectConfig) -> StateCodec {
+    let schema = &config.schema;
+    let width = schema.fields.iter().map(|f| go_name(&f.name).len()).max().unwrap_or(0);
+    let mut fields = String::new();
+    let mut encode = String::new();
+    let mut decode = String::new();
+    let mut decoded = Vec::new();
+    for f in &schema.fields {
+        let ty = go_type(f.ty);
+        if f.padding {
+            fields.push_str(&format!("\t{:<width$} [{}]byte\n", "_", f.size(), width = width));
+            continue;
+        }
+        let name = go_name(&f.name);
+        let offset = f.offset;
+        decoded.push(DecodedField { name: f.name.clone(), offset, size: f.count() * f.ty.size() });
+        match f.len {
+            Some(n) => {
+                fields.push_str(&format!("\t{:<width$} [{}]{}\n", name, n, ty, width = width));
+                let at = format!("{}+i*{}", offset, f.ty.size());
+                encode.push_str(&format!("\tfor i := range s.{} {{\n\t\t{}\n\t}}\n", name, go_put(f.ty, &at, &format!("s.{}[i]", name))));
+                decode.push_str(&format!("\tfor i := range s.{} {{\n\t\ts.{}[i] = {}\n\t}}\n", name, name, go_get(f.ty, &at)));
+            }
+            None => {
+                fields.push_str(&format!("\t{:<width$} {}\n", name, ty, width = width));
+                let at = offset.to_string();
+                encode.push_str(&format!("\t{}\n", go_put(f.ty, &at, &format!("s.{}", name))));
+                decode.push_str(&format!("\ts.{} = {}\n", name, go_get(f.ty, &at)));
+            }
+        }
+    }
+    let offsets = DecoderOffsets { decoder: "venom.go".to_string(), size: schema.size, fields: decoded };
+    StateCodec { fields, encode, decode, offsets }
+}
+
+fn daemon_go(pascal: &str, shm_mode: u32) -> String {
+    format!(r##"// ═══════════════════════════════════════════════════════════════════════════
+// Daemon
+// ═══════════════════════════════════════════════════════════════════════════
+
+type Daemon struct {{
+	handle unsafe.Pointer
+	pong   *Pong // reply to the last ping, published with every state from then on
+}}
+
+func NewDaemon() (*Daemon, error) {{
+	return NewDaemonOn(ChannelName)
+}}
+
+// NewDaemonOn creates another channel than ChannelName, such as one
+// InstanceChannel names
+func NewDaemonOn(channel string) (*Daemon, error) {{
+	name := C.CString(channel)
+	defer C.free(unsafe.Pointer(name))
+	
+	cfg := C.VenomConfigEx{{
+		data_size:        C.size_t(DataSize),
+		cmd_slots:        C.size_t(CmdSlots),
+		max_clients:      C.size_t(MaxClients),
+		cmd_payload_size: C.size_t(CommandSize),
+		shm_mode:         0o{shm_mode:o},
+	}}
+	cfg.struct_size = C.size_t(unsafe.Sizeof(cfg))
+	
+	handle := C.venom_daemon_create_ex(name, &cfg)
+	if handle == nil {{
+		return nil, fmt.Errorf("failed to create daemon channel")
+	}}
+	return &Daemon{{handle: handle}}, nil
+}}
+
+// Write publishes the state, followed by the last pong once there is one
+func (d *Daemon) Write(state *{pascal}State) {{
+	data := state.ToBytes()
+	if d.pong != nil {{
+		data = append(data, d.pong.ToBytes()...)
+	}}
+	C.venom_daemon_write_data(d.handle, (*C.uint8_t)(&data[0]), C.size_t(len(data)))
+}}
+
+// TryRecvCommand returns the next queued Command, skipping messages that aren't one
+func (d *Daemon) TryRecvCommand() (Command, uint32, bool) {{
+	buf := make([]byte, 64)
+	for {{
+		var clientID C.uint32_t
+		n := C.venom_daemon_try_recv_command(d.handle, (*C.uint8_t)(&buf[0]), C.size_t(len(buf)), &clientID)
+		if n == 0 {{
+			return Command{{}}, 0, false
+		}}
+		if cmd, ok := CommandFromBytes(buf[:n]); ok {{
+			return cmd, uint32(clientID), true
+		}}
+	}}
+}}
+
+// Pong answers a CmdPing; the pong goes out with every Write until the next one
+func (d *Daemon) Pong(clientID, seq uint32) {{
+	d.pong = &Pong{{ClientID: clientID, Seq: seq}}
+}}
+
+// ClientLabel is "3", or "3 (gui-frontend)" for a client that connected with a name
+func (d *Daemon) ClientLabel(clientID uint32) string {{
+	var name [33]C.char
+	if C.venom_daemon_client_name(d.handle, C.uint32_t(clientID), &name[0], C.size_t(len(name))) == 0 {{
+		return fmt.Sprint(clientID)
+	}}
+	return fmt.Sprintf("%d (%s)", clientID, C.GoString(&name[0]))
+}}
+
+func (d *Daemon) Close() {{
+	if d.handle != nil {{
+		C.venom_daemon_destroy(d.handle)
+		d.handle = nil
+	}}
+}}
+
+"##, pascal = pascal, shm_mode = shm_mode)
+}
+
+/// `venom/venom.go` of a `--go-nocgo` project: the shell maps the channel
+/// itself, using the offsets of `venom_memory::layout`
+fn pure_venom_go(pascal: &str, types: &str, math_import: &str) -> String {
+    format!(r##"// Package venom reads a VenomMemory channel without cgo: the shell maps
+// /dev/shm/venom_<name> and follows the layout venom_memory documents as
+// stable for a channel version (venom_memory::layout). 64-bit targets only.
+// The daemon still links the library, in daemon.go (skipped with CGO_ENABLED=0).
+package venom
+
+import (
+	"encoding/binary"
+	"fmt"
+{math_import}	"runtime"
+	"sync/atomic"
+	"time"
+	"unsafe"
+
+	"golang.org/x/sys/unix"
+)
+
+{types}// ═══════════════════════════════════════════════════════════════════════════
+// Channel layout (generated from venom_memory::layout)
 // ═══════════════════════════════════════════════════════════════════════════
 
 const (
-	ChannelName = "{channel}"
-	Magic       = 0x{magic:08X}
-	DataSize    = {data_size}
-	CmdSlots    = {cmd_slots}
-	MaxClients  = {max_clients}
-	MaxCores    = 16
+	shmDir         = "/dev/shm"
+	channelMagic   = 0x{magic:08X}
+	channelVersion = {version}
+
+	// Channel header
+	hdrMagic          = {hdr_magic}
+	hdrVersion        = {hdr_version}
+	hdrMaxClients     = {hdr_max_clients}
+	hdrNextClientID   = {hdr_next_client_id}
+	hdrOwnerPID       = {hdr_owner_pid}
+	hdrSeqlockOffset  = {hdr_seqlock_offset}
+	hdrCmdQueueOffset = {hdr_cmd_queue_offset}
+	hdrClientCount    = {hdr_client_count}
+	hdrClientsOffset  = {hdr_clients_offset} // 0 for daemons older than the client table
+	hdrDataGeneration = {hdr_data_generation} // u32, bumped when the daemon moves the data region
+	hdrIDsWrapped     = {hdr_client_ids_wrapped} // u32, set before the client IDs start over at 1
+	hdrSize           = {hdr_size}
+
+	// SeqLock header; the data region follows it
+	seqSequence  = {seq_sequence}
+	seqDataSize  = {seq_data_size}
+	seqTimestamp = {seq_timestamp} // CLOCK_MONOTONIC_RAW ns of the last write, 0 if unstamped
+	seqSize      = {seq_size}
+	seqLenPrefix = {seq_len_prefix} // the daemon writes a little-endian u64 length, then the payload
+
+	// Command queue header; the slots follow it
+	queueWriteIdx        = {queue_write_idx}
+	queueReadIdx         = {queue_read_idx} // taken by moving a READY slot to PROCESSING, then this up by one
+	queueNumSlots        = {queue_num_slots}
+	queueCmdPayloadSize  = {queue_cmd_payload_size} // bytes of command each slot holds
+	queueDroppedCommands = {queue_dropped_commands}
+	queueTotalFullEvents = {queue_total_full_events} // sends that found the queue full, 0 for older daemons
+	queueCmdFullPolicy   = {queue_cmd_full_policy} // u8: 0 fails a send to a full queue, 1 drops the oldest command
+	queueSize            = {queue_size}
+	maxCmdPayloadSize    = {max_cmd_size}
+
+	// Command slot
+	slotState    = {slot_state}
+	slotClientID = {slot_client_id}
+	slotCmdLen   = {slot_cmd_len}
+	slotCmdData  = {slot_cmd_data} // a slot is slotCmdData + queueCmdPayloadSize bytes
+
+	slotEmpty      = {slot_empty}
+	slotWriting    = {slot_writing}
+	slotReady      = {slot_ready}
+	slotProcessing = {slot_processing}
+
+	// Client table slot; a client ID of 0 is free
+	clientSlotID      = {client_id}
+	clientSlotPID     = {client_pid}
+	clientSlotSince   = {client_since} // Unix ns
+	clientSlotNameLen = {client_name_len}
+	clientSlotName    = {client_name}
+	clientSlotDataGen = {client_data_generation} // hdrDataGeneration of the region the client reads
+	clientSlotSize    = {client_size}
+	clientClaiming    = {client_claiming}
+	clientNameMax     = {client_name_max}
+)
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Shell (Client)
+// ═══════════════════════════════════════════════════════════════════════════
+
+type Shell struct {{
+	path       string
+	mem        []byte
+	retired    [][]byte // mappings from before the daemon grew the channel
+	data       []byte   // the SeqLock-protected data region
+	generation uint32   // hdrDataGeneration data was published under
+	sequence   *uint64  // odd while the daemon is writing
+	stamp      *uint64  // when the daemon last wrote
+	queue      int      // offset of the command queue header
+	numSlots   uint64
+	slotSize   int    // bytes from one command slot to the next
+	overwrite  bool   // a full queue drops its oldest command rather than fail
+	slot       int    // offset of our client table slot, 0 if none
+	clientID   uint32
+	// WriteAgeUs is how long before the last ReadState the daemon wrote it
+	WriteAgeUs float64
+}}
+
+// Connect connects without a name; ConnectNamed registers one (up to
+// clientNameMax bytes) that the daemon shows in its log
+func Connect() (*Shell, error) {{
+	return ConnectNamed("")
+}}
+
+func ConnectNamed(clientName string) (*Shell, error) {{
+	return ConnectChannel(ChannelName, clientName)
+}}
+
+// ConnectChannel connects to another channel than ChannelName, such as
+// one InstanceChannel names
+func ConnectChannel(channel, clientName string) (*Shell, error) {{
+	if len(clientName) > clientNameMax {{
+		return nil, fmt.Errorf("client name %q is longer than %d bytes", clientName, clientNameMax)
+	}}
+	path := shmDir + "{shm_prefix}" + channel
+	fd, err := unix.Open(path, unix.O_RDWR|unix.O_CLOEXEC, 0)
+	if err != nil {{
+		return nil, fmt.Errorf("failed to connect - is daemon running? (%w)", err)
+	}}
+	defer unix.Close(fd)
+	var st unix.Stat_t
+	if err := unix.Fstat(fd, &st); err != nil {{
+		return nil, err
+	}}
+	if st.Size < hdrSize {{
+		return nil, fmt.Errorf("%s is too small to be a channel", path)
+	}}
+	mem, err := unix.Mmap(fd, 0, int(st.Size), unix.PROT_READ|unix.PROT_WRITE, unix.MAP_SHARED)
+	if err != nil {{
+		return nil, fmt.Errorf("failed to map %s: %w", path, err)
+	}}
+
+	s := &Shell{{path: path, mem: mem}}
+	if magic := *s.word32(hdrMagic); magic != channelMagic {{
+		unix.Munmap(mem)
+		return nil, fmt.Errorf("%s is not a VenomMemory channel (magic 0x%08X)", path, magic)
+	}}
+	if version := *s.word32(hdrVersion); version != channelVersion {{
+		unix.Munmap(mem)
+		return nil, fmt.Errorf("%s has layout version %d, this client reads version %d", path, version, channelVersion)
+	}}
+	s.generation = atomic.LoadUint32(s.word32(hdrDataGeneration))
+	s.queue = int(*s.word64(hdrCmdQueueOffset))
+	if !s.useDataRegion() || s.queue+queueSize > len(mem) {{
+		unix.Munmap(mem)
+		return nil, fmt.Errorf("%s is truncated", path)
+	}}
+	// Sizes are read once, as the library does; the payload is whole cache lines
+	payload := *s.word64(s.queue + queueCmdPayloadSize)
+	s.numSlots = *s.word64(s.queue + queueNumSlots)
+	if payload == 0 || payload%64 != 0 || payload > maxCmdPayloadSize || payload < CommandSize {{
+		unix.Munmap(mem)
+		return nil, fmt.Errorf("%s has a command payload of %d bytes", path, payload)
+	}}
+	s.slotSize = slotCmdData + int(payload)
+	if s.numSlots > uint64(len(mem)-s.queue-queueSize)/uint64(s.slotSize) {{
+		unix.Munmap(mem)
+		return nil, fmt.Errorf("%s is truncated", path)
+	}}
+	switch policy := mem[s.queue+queueCmdFullPolicy]; policy {{
+	case 0, 1:
+		s.overwrite = policy == 1
+	default:
+		unix.Munmap(mem)
+		return nil, fmt.Errorf("%s has an unknown full queue policy %d", path, policy)
+	}}
+	s.clientID = s.takeClientID()
+	if err := s.register(clientName); err != nil {{
+		unix.Munmap(mem)
+		return nil, fmt.Errorf("%s: %w", path, err)
+	}}
+	atomic.AddUint32(s.word32(hdrClientCount), 1)
+	return s, nil
+}}
+
+// takeClientID takes the next client ID as the library does: after
+// 0xFFFFFFFE comes 1, and the daemon is told the IDs started over first
+func (s *Shell) takeClientID() uint32 {{
+	next := s.word32(hdrNextClientID)
+	for {{
+		current := atomic.LoadUint32(next)
+		following := current + 1
+		if current >= clientClaiming-1 {{
+			following = 1
+		}}
+		if following == 1 {{
+			atomic.StoreUint32(s.word32(hdrIDsWrapped), 1)
+		}}
+		if atomic.CompareAndSwapUint32(next, current, following) && current != 0 && current != clientClaiming {{
+			return current
+		}}
+	}}
+}}
+
+// register takes a slot of the daemon's client table, as the library does:
+// a free one, or one whose process has died
+func (s *Shell) register(clientName string) error {{
+	table := int(*s.word64(hdrClientsOffset))
+	if table == 0 {{
+		return nil
+	}}
+	maxClients := int(*s.word64(hdrMaxClients))
+	if table%8 != 0 || maxClients > (len(s.mem)-table)/clientSlotSize {{
+		return fmt.Errorf("client table is truncated")
+	}}
+	for i := 0; i < maxClients; i++ {{
+		slot := table + i*clientSlotSize
+		owner := atomic.LoadUint32(s.word32(slot + clientSlotID))
+		if owner == clientClaiming || (owner != 0 && unix.Kill(int(atomic.LoadUint32(s.word32(slot+clientSlotPID))), 0) != unix.ESRCH) {{
+			continue
+		}}
+		if !atomic.CompareAndSwapUint32(s.word32(slot+clientSlotID), owner, clientClaiming) {{
+			continue
+		}}
+		atomic.StoreUint32(s.word32(slot+clientSlotPID), uint32(unix.Getpid()))
+		atomic.StoreUint64(s.word64(slot+clientSlotSince), uint64(time.Now().UnixNano()))
+		copy(s.mem[slot+clientSlotName:slot+clientSlotName+clientNameMax], clientName)
+		atomic.StoreUint32(s.word32(slot+clientSlotNameLen), uint32(len(clientName)))
+		atomic.StoreUint32(s.word32(slot+clientSlotDataGen), s.generation)
+		atomic.StoreUint32(s.word32(slot+clientSlotID), s.clientID)
+		s.slot = slot
+		return nil
+	}}
+	return fmt.Errorf("channel is full: %d clients connected", maxClients)
+}}
+
+// useDataRegion points the shell at the data region the header names, if it
+// lies inside the mapping
+func (s *Shell) useDataRegion() bool {{
+	seqlock := int(atomic.LoadUint64(s.word64(hdrSeqlockOffset)))
+	data := seqlock + seqSize
+	if seqlock < hdrSize || data > len(s.mem) {{
+		return false
+	}}
+	dataEnd := data + int(*s.word64(seqlock + seqDataSize))
+	if dataEnd > len(s.mem) || dataEnd < data+seqLenPrefix {{
+		return false
+	}}
+	s.data = s.mem[data:dataEnd]
+	s.sequence = s.word64(seqlock + seqSequence)
+	s.stamp = s.word64(seqlock + seqTimestamp)
+	return true
+}}
+
+// follow moves to the larger data region the daemon resized into, if it
+// has: map the channel again, now grown to hold it, and tell the daemon,
+// which keeps writing the old region for a while until then
+func (s *Shell) follow() {{
+	generation := atomic.LoadUint32(s.word32(hdrDataGeneration))
+	if generation == s.generation {{
+		return
+	}}
+	fd, err := unix.Open(s.path, unix.O_RDWR|unix.O_CLOEXEC, 0)
+	if err != nil {{
+		return
+	}}
+	defer unix.Close(fd)
+	var st unix.Stat_t
+	if unix.Fstat(fd, &st) != nil {{
+		return
+	}}
+	mem, err := unix.Mmap(fd, 0, int(st.Size), unix.PROT_READ|unix.PROT_WRITE, unix.MAP_SHARED)
+	if err != nil {{
+		return
+	}}
+	old := s.mem
+	s.mem = mem
+	if !s.useDataRegion() {{
+		s.mem = old
+		unix.Munmap(mem)
+		return
+	}}
+	s.retired = append(s.retired, old)
+	s.generation = generation
+	if s.slot != 0 {{
+		atomic.StoreUint32(s.word32(s.slot+clientSlotDataGen), generation)
+	}}
+}}
+
+// word32 and word64 point at a native-endian field of the mapping
+func (s *Shell) word32(off int) *uint32 {{
+	return (*uint32)(unsafe.Pointer(&s.mem[off]))
+}}
+
+func (s *Shell) word64(off int) *uint64 {{
+	return (*uint64)(unsafe.Pointer(&s.mem[off]))
+}}
+
+func (s *Shell) ClientID() uint32 {{
+	return s.clientID
+}}
+
+// DaemonAlive is false once the daemon has exited; reads then return its last state
+func (s *Shell) DaemonAlive() bool {{
+	pid := int(atomic.LoadUint32(s.word32(hdrOwnerPID)))
+	return pid > 0 && unix.Kill(pid, 0) != unix.ESRCH
+}}
+
+// Library is what reads the channel; this binding maps it without libvenom_memory
+func Library() string {{
+	return "pure Go"
+}}
+
+// ReadData copies the latest payload into buf and returns how many bytes it
+// copied: retry while the daemon is writing, and until the sequence is the
+// same before and after the copy
+func (s *Shell) ReadData(buf []byte) int {{
+	n, _ := s.ReadDataStamped(buf)
+	return n
+}}
+
+// ReadDataStamped is ReadData that also returns when the daemon wrote the
+// payload, in CLOCK_MONOTONIC_RAW nanoseconds (0 if it doesn't stamp writes)
+func (s *Shell) ReadDataStamped(buf []byte) (int, uint64) {{
+	s.follow()
+	for {{
+		before := atomic.LoadUint64(s.sequence)
+		if before&1 == 1 {{
+			continue
+		}}
+		n := binary.LittleEndian.Uint64(s.data[:seqLenPrefix])
+		if n < uint64(len(buf)) {{
+			buf = buf[:n]
+		}}
+		copied := copy(buf, s.data[seqLenPrefix:])
+		written := atomic.LoadUint64(s.stamp)
+		if atomic.LoadUint64(s.sequence) == before {{
+			return copied, written
+		}}
+	}}
+}}
+
+func (s *Shell) ReadState() *{pascal}State {{
+	buf := make([]byte, StateSize)
+	n, writtenNs := s.ReadDataStamped(buf)
+	if writtenNs != 0 {{
+		var now unix.Timespec
+		if unix.ClockGettime(unix.CLOCK_MONOTONIC_RAW, &now) == nil {{
+			s.WriteAgeUs = float64(uint64(now.Nano())-writtenNs) / 1000.0
+		}}
+	}}
+	return StateFromBytes(buf[:n])
+}}
+
+// SendCommand queues cmd, waiting up to SendTimeoutMS for room if the
+// daemon has fallen behind; false if the queue stayed full
+func (s *Shell) SendCommand(cmd Command) bool {{
+	return s.SendCommandTimeout(cmd, SendTimeoutMS*time.Millisecond)
+}}
+
+// SendCommandTimeout is SendCommand with its own wait; 0 doesn't wait at all
+func (s *Shell) SendCommandTimeout(cmd Command, timeout time.Duration) bool {{
+	data := cmd.ToBytes()
+	if s.numSlots == 0 {{
+		return false
+	}}
+	if s.overwrite {{
+		s.sendOverwriting(data)
+		return true
+	}}
+	deadline := time.Now().Add(timeout)
+	counted := false
+	for {{
+		// Claim the next index only while the queue has room, as the library does:
+		// an index claimed and left unwritten would hold the daemon up
+		readIdx := atomic.LoadUint64(s.word64(s.queue + queueReadIdx))
+		idx := atomic.LoadUint64(s.word64(s.queue + queueWriteIdx))
+		if idx-readIdx < s.numSlots {{
+			if atomic.CompareAndSwapUint64(s.word64(s.queue+queueWriteIdx), idx, idx+1) {{
+				s.writeSlot(idx, data)
+				return true
+			}}
+			continue
+		}}
+		if !counted {{
+			atomic.AddUint64(s.word64(s.queue+queueTotalFullEvents), 1)
+			counted = true
+		}}
+		if !time.Now().Before(deadline) {{
+			return false // queue full
+		}}
+		time.Sleep(50 * time.Microsecond)
+	}}
+}}
+
+// sendOverwriting queues data on a queue that drops its oldest command when full
+func (s *Shell) sendOverwriting(data []byte) {{
+	idx := atomic.AddUint64(s.word64(s.queue+queueWriteIdx), 1) - 1
+	counted := false
+	for idx-atomic.LoadUint64(s.word64(s.queue+queueReadIdx)) >= s.numSlots {{
+		if !counted {{
+			atomic.AddUint64(s.word64(s.queue+queueTotalFullEvents), 1)
+			counted = true
+		}}
+		s.dropOldest()
+		runtime.Gosched()
+	}}
+	s.writeSlot(idx, data)
+}}
+
+// writeSlot fills the slot of the claimed command index idx and marks it READY
+func (s *Shell) writeSlot(idx uint64, data []byte) {{
+	slot := s.slotAt(idx)
+	// The command a lap before ours has been taken, but whoever took it may not
+	// have emptied the slot yet. The u8 state is followed by three zero bytes,
+	// so the whole word can be swapped
+	for !atomic.CompareAndSwapUint32(s.word32(slot+slotState), slotEmpty, slotWriting) {{
+		runtime.Gosched()
+	}}
+	atomic.StoreUint32(s.word32(slot+slotClientID), s.clientID)
+	atomic.StoreUint32(s.word32(slot+slotCmdLen), uint32(len(data)))
+	copy(s.mem[slot+slotCmdData:], data)
+	atomic.StoreUint32(s.word32(slot+slotState), slotReady)
+}}
+
+// slotAt is the offset of the slot for command index idx
+func (s *Shell) slotAt(idx uint64) int {{
+	return s.queue + queueSize + int(idx%s.numSlots)*s.slotSize
+}}
+
+// dropOldest takes the oldest command as the daemon would, claiming its
+// slot and then moving the read index past it, and drops it unread
+func (s *Shell) dropOldest() {{
+	readIdx := s.word64(s.queue + queueReadIdx)
+	idx := atomic.LoadUint64(readIdx)
+	slot := s.slotAt(idx)
+	if !atomic.CompareAndSwapUint32(s.word32(slot+slotState), slotReady, slotProcessing) {{
+		return // not written yet, or someone else is taking it
+	}}
+	if !atomic.CompareAndSwapUint64(readIdx, idx, idx+1) {{
+		// The index moved on meanwhile: this is a newer command, leave it
+		atomic.StoreUint32(s.word32(slot+slotState), slotReady)
+		return
+	}}
+	atomic.AddUint64(s.word64(s.queue+queueDroppedCommands), 1)
+	atomic.StoreUint32(s.word32(slot+slotState), slotEmpty)
+}}
+
+func (s *Shell) SetInterval(ms uint32) bool {{
+	return s.SendCommand(Command{{Cmd: CmdSetInterval, Value: int32(ms)}})
+}}
+
+func (s *Shell) Refresh() bool {{
+	return s.SendCommand(Command{{Cmd: CmdRefresh}})
+}}
+{ping}
+func (s *Shell) Close() {{
+	if s.mem != nil {{
+		if s.slot != 0 {{
+			atomic.StoreUint32(s.word32(s.slot+clientSlotID), 0)
+		}}
+		atomic.AddUint32(s.word32(hdrClientCount), ^uint32(0))
+		unix.Munmap(s.mem)
+		for _, mem := range s.retired {{
+			unix.Munmap(mem)
+		}}
+		s.mem, s.retired = nil, nil
+	}}
+}}
+"##,
+        math_import = math_import,
+        types = types,
+        ping = SHELL_PING,
+        pascal = pascal,
+        magic = layout::MAGIC,
+        version = layout::VERSION,
+        shm_prefix = layout::SHM_PREFIX,
+        hdr_magic = layout::header::MAGIC,
+        hdr_version = layout::header::VERSION,
+        hdr_max_clients = layout::header::MAX_CLIENTS,
+        hdr_next_client_id = layout::header::NEXT_CLIENT_ID,
+        hdr_owner_pid = layout::header::OWNER_PID,
+        hdr_seqlock_offset = layout::header::SEQLOCK_OFFSET,
+        hdr_cmd_queue_offset = layout::header::CMD_QUEUE_OFFSET,
+        hdr_client_count = layout::header::CLIENT_COUNT,
+        hdr_clients_offset = layout::header::CLIENTS_OFFSET,
+        hdr_data_generation = layout::header::DATA_GENERATION,
+        hdr_client_ids_wrapped = layout::header::CLIENT_IDS_WRAPPED,
+        hdr_size = layout::header::SIZE,
+        seq_sequence = layout::seqlock::SEQUENCE,
+        seq_data_size = layout::seqlock::DATA_SIZE,
+        seq_timestamp = layout::seqlock::TIMESTAMP,
+        seq_size = layout::seqlock::SIZE,
+        seq_len_prefix = layout::seqlock::LEN_PREFIX,
+        queue_write_idx = layout::queue::WRITE_IDX,
+        queue_read_idx = layout::queue::READ_IDX,
+        queue_num_slots = layout::queue::NUM_SLOTS,
+        queue_cmd_payload_size = layout::queue::CMD_PAYLOAD_SIZE,
+        queue_dropped_commands = layout::queue::DROPPED_COMMANDS,
+        queue_total_full_events = layout::queue::TOTAL_FULL_EVENTS,
+        queue_cmd_full_policy = layout::queue::CMD_FULL_POLICY,
+        max_cmd_size = venom_memory::mpsc_queue::MAX_CMD_SIZE,
+        queue_size = layout::queue::SIZE,
+        slot_state = layout::slot::STATE,
+        slot_client_id = layout::slot::CLIENT_ID,
+        slot_cmd_len = layout::slot::CMD_LEN,
+        slot_cmd_data = layout::slot::CMD_DATA,
+        slot_empty = layout::slot::EMPTY,
+        slot_writing = layout::slot::WRITING,
+        slot_ready = layout::slot::READY,
+        slot_processing = layout::slot::PROCESSING,
+        client_id = layout::client::CLIENT_ID,
+        client_pid = layout::client::PID,
+        client_since = layout::client::CONNECTED_SINCE,
+        client_name_len = layout::client::NAME_LEN,
+        client_name = layout::client::NAME,
+        client_data_generation = layout::client::DATA_GENERATION,
+        client_size = layout::client::SIZE,
+        client_claiming = layout::client::CLAIMING,
+        client_name_max = venom_memory::CLIENT_NAME_MAX,
+    )
+}
+
+/// `venom/daemon.go` of a `--go-nocgo` project: the cgo `Daemon`, which
+/// `CGO_ENABLED=0` builds of the client skip
+pub(super) fn daemon_cgo_go(config: &ProjectConfig) -> String {
+    format!(r##"package venom
+
+/*
+{cgo_ldflags}
+#include <stdint.h>
+#include <stddef.h>
+#include <stdlib.h>
+
+{daemon_decls}
+*/
+import "C"
+import (
+	"fmt"
+	"unsafe"
 )
 
-// ═══════════════════════════════════════════════════════════════════════════
-// State Structure
-// ═══════════════════════════════════════════════════════════════════════════
-
-type {pascal}State struct {{
-	Magic           uint32
-	Version         uint32
-	CPUUsagePercent float32
-	CPUCores        [MaxCores]float32
-	CoreCount       uint32
-	MemoryUsedMB    uint32
-	MemoryTotalMB   uint32
-	UptimeSeconds   uint64
-	UpdateCounter   uint64
-	TimestampNs     uint64
-}}
-
-func (s *{pascal}State) IsValid() bool {{
-	return s.Magic == Magic
-}}
+{daemon}"##,
+        cgo_ldflags = cgo_ldflags(config),
+        daemon_decls = CGO_DAEMON_DECLS,
+        daemon = daemon_go(&config.names().pascal, config.shm_mode),
+    )
+}
 
+/// Convenience methods used by the system-monitor demo
+fn demo_helpers(pascal: &str) -> String {
+    format!(r##"
 func (s *{pascal}State) MemoryPercent() float32 {{
 	if s.MemoryTotalMB > 0 {{
 		return float32(s.MemoryUsedMB) / float32(s.MemoryTotalMB) * 100
@@ -128,137 +1050,271 @@ func (s *{pascal}State) UptimeFormatted() string {{
 	m := (s.UptimeSeconds % 3600) / 60
 	return fmt.Sprintf("%dh %dm", h, m)
 }}
+"##, pascal = pascal)
+}
 
-func (s *{pascal}State) ToBytes() []byte {{
-	buf := make([]byte, 112)
-	binary.LittleEndian.PutUint32(buf[0:], s.Magic)
-	binary.LittleEndian.PutUint32(buf[4:], s.Version)
-	copy(buf[8:12], (*[4]byte)(unsafe.Pointer(&s.CPUUsagePercent))[:])
-	for i := 0; i < MaxCores; i++ {{
-		copy(buf[12+i*4:16+i*4], (*[4]byte)(unsafe.Pointer(&s.CPUCores[i]))[:])
-	}}
-	binary.LittleEndian.PutUint32(buf[76:], s.CoreCount)
-	binary.LittleEndian.PutUint32(buf[80:], s.MemoryUsedMB)
-	binary.LittleEndian.PutUint32(buf[84:], s.MemoryTotalMB)
-	binary.LittleEndian.PutUint64(buf[88:], s.UptimeSeconds)
-	binary.LittleEndian.PutUint64(buf[96:], s.UpdateCounter)
-	binary.LittleEndian.PutUint64(buf[104:], s.TimestampNs)
-	return buf
-}}
-
-func StateFromBytes(data []byte) *{pascal}State {{
-	if len(data) < 112 {{
-		return &{pascal}State{{}}
-	}}
-	s := &{pascal}State{{}}
-	s.Magic = binary.LittleEndian.Uint32(data[0:])
-	s.Version = binary.LittleEndian.Uint32(data[4:])
-	s.CPUUsagePercent = *(*float32)(unsafe.Pointer(&data[8]))
-	for i := 0; i < MaxCores; i++ {{
-		s.CPUCores[i] = *(*float32)(unsafe.Pointer(&data[12+i*4]))
-	}}
-	s.CoreCount = binary.LittleEndian.Uint32(data[76:])
-	s.MemoryUsedMB = binary.LittleEndian.Uint32(data[80:])
-	s.MemoryTotalMB = binary.LittleEndian.Uint32(data[84:])
-	s.UptimeSeconds = binary.LittleEndian.Uint64(data[88:])
-	s.UpdateCounter = binary.LittleEndian.Uint64(data[96:])
-	s.TimestampNs = binary.LittleEndian.Uint64(data[104:])
-	return s
-}}
+/// `go test ./venom`: a golden decode that always runs and a live read
+/// gated on `VENOM_LIVE_TEST`
+fn venom_test_go(config: &ProjectConfig) -> String {
+    let mut checks = String::new();
+    for (f, values) in config.schema.golden(config.magic) {
+        let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+        let want = match f.len {
+            Some(n) => format!("[{}]{}{{{}}}", n, go_type(f.ty), values.join(", ")),
+            None => format!("{}({})", go_type(f.ty), values.join("")),
+        };
+        checks.push_str(&format!(
+            "\tif want := {want}; s.{name} != want {{\n\t\tt.Errorf(\"{name} = %v, want %v\", s.{name}, want)\n\t}}\n",
+            want = want, name = go_name(&f.name)
+        ));
+    }
+
+    format!(r#"package venom
+
+// TestDecodesGoldenState decodes a state built from venom.toml, so it
+// fails when {pascal}State no longer matches the daemon's layout.
+// TestReadsLiveState needs the daemon running and {live_env}=1.
 
-// ═══════════════════════════════════════════════════════════════════════════
-// Daemon
-// ═══════════════════════════════════════════════════════════════════════════
+import (
+	"bytes"
+	"os"
+	"testing"
+	"time"
+)
 
-type Daemon struct {{
-	handle unsafe.Pointer
-}}
+// Every field holds a distinct known value (see TestDecodesGoldenState)
+var golden = []byte{{
+{golden}}}
 
-func NewDaemon() (*Daemon, error) {{
-	name := C.CString(ChannelName)
-	defer C.free(unsafe.Pointer(name))
-	
-	cfg := C.VenomConfig{{
-		data_size:   C.size_t(DataSize),
-		cmd_slots:   C.size_t(CmdSlots),
-		max_clients: C.size_t(MaxClients),
-	}}
-	
-	handle := C.venom_daemon_create(name, cfg)
-	if handle == nil {{
-		return nil, fmt.Errorf("failed to create daemon channel")
+func TestDecodesGoldenState(t *testing.T) {{
+	if len(golden) != StateSize {{
+		t.Fatalf("golden is %d bytes, want %d", len(golden), StateSize)
 	}}
-	return &Daemon{{handle: handle}}, nil
-}}
+	s := StateFromBytes(golden)
+{checks}}}
 
-func (d *Daemon) Write(state *{pascal}State) {{
-	data := state.ToBytes()
-	C.venom_daemon_write_data(d.handle, (*C.uint8_t)(&data[0]), C.size_t(len(data)))
+func TestEncodesGoldenState(t *testing.T) {{
+	if got := StateFromBytes(golden).ToBytes(); !bytes.Equal(got, golden) {{
+		t.Errorf("ToBytes() = % x, want % x", got, golden)
+	}}
 }}
 
-func (d *Daemon) Close() {{
-	if d.handle != nil {{
-		C.venom_daemon_destroy(d.handle)
-		d.handle = nil
+func TestInstanceChannel(t *testing.T) {{
+	if got := InstanceChannel("2"); got != ChannelName+"-2" {{
+		t.Errorf("InstanceChannel(\"2\") = %q", got)
+	}}
+	if got := InstanceChannel(""); got != ChannelName {{
+		t.Errorf("InstanceChannel(\"\") = %q", got)
 	}}
 }}
 
-// ═══════════════════════════════════════════════════════════════════════════
-// Shell (Client)
-// ═══════════════════════════════════════════════════════════════════════════
-
-type Shell struct {{
-	handle unsafe.Pointer
-}}
+func TestReadsLiveState(t *testing.T) {{
+	if os.Getenv("{live_env}") == "" {{
+		t.Skip("set {live_env}=1 with the daemon running")
+	}}
+	shell, err := Connect()
+	if err != nil {{
+		t.Fatal(err)
+	}}
+	defer shell.Close()
 
-func Connect() (*Shell, error) {{
-	name := C.CString(ChannelName)
-	defer C.free(unsafe.Pointer(name))
-	
-	handle := C.venom_shell_connect(name)
-	if handle == nil {{
-		return nil, fmt.Errorf("failed to connect - is daemon running?")
+	s := shell.ReadState()
+	// The daemon may not have published yet
+	for i := 0; i < 50 && !s.IsValid(); i++ {{
+		time.Sleep(20 * time.Millisecond)
+		s = shell.ReadState()
+	}}
+	if s.Magic != Magic || s.Version != 1 {{
+		t.Errorf("Magic = 0x%08X, Version = %d; want 0x%08X, 1", s.Magic, s.Version, Magic)
 	}}
-	return &Shell{{handle: handle}}, nil
 }}
+"#,
+        pascal = config.names().pascal,
+        golden = super::byte_rows(&config.schema.golden_bytes(config.magic), "\t"),
+        checks = checks,
+        live_env = super::LIVE_TEST_ENV
+    )
+}
 
-func (s *Shell) ClientID() uint32 {{
-	return uint32(C.venom_shell_id(s.handle))
-}}
+/// Exported Go field name, with Go's usual initialisms (`cpu_usage` -> `CPUUsage`)
+fn go_name(field: &str) -> String {
+    field.split('_')
+        .map(|word| match word {
+            "cpu" | "id" | "ip" | "mb" | "kb" | "gb" | "url" | "api" | "io" => word.to_uppercase(),
+            _ => {
+                let mut chars = word.chars();
+                match chars.next() {
+                    None => String::new(),
+                    Some(first) => first.to_uppercase().chain(chars).collect(),
+                }
+            }
+        })
+        .collect()
+}
 
-func (s *Shell) ReadState() *{pascal}State {{
-	buf := make([]byte, 256)
-	n := C.venom_shell_read_data(s.handle, (*C.uint8_t)(&buf[0]), C.size_t(len(buf)))
-	return StateFromBytes(buf[:n])
-}}
+fn go_type(ty: FieldType) -> &'static str {
+    match ty {
+        FieldType::U8 => "uint8",
+        FieldType::U16 => "uint16",
+        FieldType::U32 => "uint32",
+        FieldType::U64 => "uint64",
+        FieldType::I8 => "int8",
+        FieldType::I16 => "int16",
+        FieldType::I32 => "int32",
+        FieldType::I64 => "int64",
+        FieldType::F32 => "float32",
+        FieldType::F64 => "float64",
+    }
+}
 
-func (s *Shell) Close() {{
-	if s.handle != nil {{
-		C.venom_shell_destroy(s.handle)
-		s.handle = nil
-	}}
-}}
-"##,
-        channel = config.channel,
-        magic = magic(&config.channel),
-        data_size = config.data_size,
-        cmd_slots = config.cmd_slots,
-        max_clients = config.max_clients,
-        pascal = pascal
-    )
+/// Little-endian read of one value at byte offset `at`
+fn go_get(ty: FieldType, at: &str) -> String {
+    let bits = 8 * ty.size();
+    match ty {
+        FieldType::U8 => format!("data[{}]", at),
+        FieldType::I8 => format!("int8(data[{}])", at),
+        FieldType::F32 | FieldType::F64 => {
+            format!("math.Float{}frombits(binary.LittleEndian.Uint{}(data[{}:]))", bits, bits, at)
+        }
+        t if t.is_signed() => format!("int{}(binary.LittleEndian.Uint{}(data[{}:]))", bits, bits, at),
+        _ => format!("binary.LittleEndian.Uint{}(data[{}:])", bits, at),
+    }
+}
+
+/// Little-endian write of `value` at byte offset `at`
+fn go_put(ty: FieldType, at: &str, value: &str) -> String {
+    let bits = 8 * ty.size();
+    match ty {
+        FieldType::U8 => format!("buf[{}] = {}", at, value),
+        FieldType::I8 => format!("buf[{}] = byte({})", at, value),
+        FieldType::F32 | FieldType::F64 => {
+            format!("binary.LittleEndian.PutUint{}(buf[{}:], math.Float{}bits({}))", bits, at, bits, value)
+        }
+        t if t.is_signed() => format!("binary.LittleEndian.PutUint{}(buf[{}:], uint{}({}))", bits, at, bits, value),
+        _ => format!("binary.LittleEndian.PutUint{}(buf[{}:], {})", bits, at, value),
+    }
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
 // Daemon
 // ═══════════════════════════════════════════════════════════════════════════
 
+/// Daemon-side command handling, shared by both daemons: commands are
+/// polled every 10 ms, SetInterval resets the publish ticker and Refresh
+/// publishes at once
+const COMMAND_HANDLER: &str = r#"var intervalMs uint32 = venom.IntervalMS
+
+// handleCommands applies queued client commands and reports whether a client asked for a refresh
+func handleCommands(daemon *venom.Daemon, ticker *time.Ticker) bool {
+	refresh := false
+	for {
+		cmd, clientID, ok := daemon.TryRecvCommand()
+		if !ok {
+			return refresh
+		}
+		switch cmd.Cmd {
+		case venom.CmdRefresh:
+			refresh = true
+		case venom.CmdSetInterval:
+			intervalMs = uint32(min(max(cmd.Value, 10), 10000))
+			ticker.Reset(time.Duration(intervalMs) * time.Millisecond)
+			fmt.Printf("\n📥 Client %s: publishing every %d ms\n", daemon.ClientLabel(clientID), intervalMs)
+		case venom.CmdPing:
+			// Answered by publishing now, with the pong after the state
+			daemon.Pong(clientID, uint32(cmd.Value))
+			refresh = true
+		default:
+			fmt.Printf("\n📥 Client %s: unknown command %d\n", daemon.ClientLabel(clientID), cmd.Cmd)
+		}
+	}
+}
+"#;
+
+/// Client-side keys, shared by both clients: `+`/`-` halve or double the
+/// daemon's publish interval, `r` asks for an immediate refresh; also
+/// `runPing` for `--ping`
+const COMMAND_KEYS: &str = r#"var intervalMs uint32 = venom.IntervalMS
+
+// readKeys puts the terminal in single-key mode (no Enter or echo; Ctrl+C
+// still works) and sends each key pressed on the returned channel. The
+// returned func restores the terminal.
+func readKeys() (<-chan byte, func()) {
+	keys := make(chan byte)
+	saved, err := stty("-g")
+	if err != nil {
+		return keys, func() {} // stdin is not a terminal
+	}
+	stty("-icanon", "-echo", "min", "1")
+	go func() {
+		buf := make([]byte, 1)
+		for {
+			if _, err := os.Stdin.Read(buf); err != nil {
+				return
+			}
+			keys <- buf[0]
+		}
+	}()
+	return keys, func() { stty(strings.TrimSpace(saved)) }
+}
+
+func stty(args ...string) (string, error) {
+	cmd := exec.Command("stty", args...)
+	cmd.Stdin = os.Stdin
+	out, err := cmd.Output()
+	return string(out), err
+}
+
+func handleKey(shell *venom.Shell, key byte) {
+	switch key {
+	case '+', '=':
+		intervalMs = max(intervalMs/2, 10)
+		shell.SetInterval(intervalMs)
+	case '-':
+		intervalMs = min(intervalMs*2, 10000)
+		shell.SetInterval(intervalMs)
+	case 'r':
+		shell.Refresh()
+	}
+}
+
+// runPing is --ping COUNT: time COUNT round trips through the daemon; false if none came back
+func runPing(shell *venom.Shell, count int) bool {
+	// A pong for an earlier client with the same ID may still be published
+	seq := uint32(time.Now().UnixNano())
+	rttMin, rttMax, rttSum, replies := math.MaxFloat64, 0.0, 0.0, 0
+	for i := 1; i <= count; i++ {
+		seq++
+		rtt, ok := shell.Ping(seq)
+		if !ok {
+			fmt.Printf("⚠️  No reply to ping #%d\n", i)
+			continue
+		}
+		rttUs := float64(rtt.Nanoseconds()) / 1000.0
+		fmt.Printf("🏓 Pong #%d: %.1f µs\n", i, rttUs)
+		rttMin, rttMax, rttSum = min(rttMin, rttUs), max(rttMax, rttUs), rttSum+rttUs
+		replies++
+		time.Sleep(100 * time.Millisecond)
+	}
+	if replies == 0 {
+		return false
+	}
+	fmt.Printf("📊 Round trip: %d/%d replies, min %.1f µs, avg %.1f µs, max %.1f µs\n",
+		replies, count, rttMin, rttSum/float64(replies), rttMax)
+	return true
+}
+"#;
+
 fn daemon_main(config: &ProjectConfig) -> String {
-    let pascal = pascal_case(&config.name);
+    if !config.schema.has_demo_fields() {
+        return generic_daemon(config);
+    }
+    let pascal = config.names().pascal;
     
     format!(r##"package main
 
 import (
 	"bufio"
+	"flag"
 	"fmt"
 	"os"
 	"os/signal"
@@ -267,12 +1323,13 @@ import (
 	"syscall"
 	"time"
 
-	"{name}/venom"
+	"{module}/venom"
 )
 
 var prevTotal = make([]uint64, venom.MaxCores+1)
 var prevIdle = make([]uint64, venom.MaxCores+1)
 
+{commands}
 func readCPU(state *venom.{pascal}State) {{
 	f, err := os.Open("/proc/stat")
 	if err != nil {{
@@ -354,17 +1411,21 @@ func readUptime(state *venom.{pascal}State) {{
 }}
 
 func main() {{
+	suffix := flag.String("channel-suffix", "", "run as one of several instances, on the channel {channel}-`SUFFIX`")
+	flag.Parse()
+	channel := venom.InstanceChannel(*suffix)
+
 	fmt.Println("🖥️  {name} System Monitor (Go)")
 	fmt.Println("═══════════════════════════════════════════════════════════════")
 
-	daemon, err := venom.NewDaemon()
+	daemon, err := venom.NewDaemonOn(channel)
 	if err != nil {{
 		fmt.Printf("❌ Error: %v\n", err)
 		os.Exit(1)
 	}}
 	defer daemon.Close()
 
-	fmt.Printf("✅ Channel: %s\n", venom.ChannelName)
+	fmt.Printf("✅ Channel: %s\n", channel)
 	fmt.Println("🚀 Publishing... (Ctrl+C to stop)")
 
 	sigCh := make(chan os.Signal, 1)
@@ -375,28 +1436,112 @@ func main() {{
 		Version: 1,
 	}}
 
-	ticker := time.NewTicker(100 * time.Millisecond)
+	publish := func() {{
+		readCPU(state)
+		readMemory(state)
+		readUptime(state)
+		state.UpdateCounter++
+		state.TimestampNs = uint64(time.Now().UnixNano())
+		daemon.Write(state)
+
+		fmt.Printf("\r🖥️  CPU: %.1f%% | RAM: %d/%d MB | #%d | %d ms   ",
+			state.CPUUsagePercent, state.MemoryUsedMB, state.MemoryTotalMB, state.UpdateCounter, intervalMs)
+	}}
+
+	ticker := time.NewTicker(venom.IntervalMS * time.Millisecond)
+	defer ticker.Stop()
+	commands := time.NewTicker(10 * time.Millisecond)
+	defer commands.Stop()
+
+	for {{
+		select {{
+		case <-sigCh:
+			fmt.Println("\n\n👋 Goodbye!")
+			return
+		case <-commands.C:
+			if handleCommands(daemon, ticker) {{
+				publish()
+			}}
+		case <-ticker.C:
+			publish()
+		}}
+	}}
+}}
+"##, name = config.name, module = config.names().flat, pascal = pascal, channel = config.channel, commands = COMMAND_HANDLER)
+}
+
+/// Daemon for a custom schema: publishes the header and leaves the fields to the user
+fn generic_daemon(config: &ProjectConfig) -> String {
+    let pascal = config.names().pascal;
+    
+    format!(r##"package main
+
+import (
+	"flag"
+	"fmt"
+	"os"
+	"os/signal"
+	"syscall"
+	"time"
+
+	"{module}/venom"
+)
+
+{commands}
+func main() {{
+	suffix := flag.String("channel-suffix", "", "run as one of several instances, on the channel {channel}-`SUFFIX`")
+	flag.Parse()
+	channel := venom.InstanceChannel(*suffix)
+
+	fmt.Println("🚀 {name} Daemon (Go)")
+	fmt.Println("═══════════════════════════════════════════════════════════════")
+
+	daemon, err := venom.NewDaemonOn(channel)
+	if err != nil {{
+		fmt.Printf("❌ Error: %v\n", err)
+		os.Exit(1)
+	}}
+	defer daemon.Close()
+
+	fmt.Printf("✅ Channel: %s | State: %d bytes\n", channel, venom.StateSize)
+	fmt.Println("🚀 Publishing... (Ctrl+C to stop)")
+
+	sigCh := make(chan os.Signal, 1)
+	signal.Notify(sigCh, syscall.SIGINT, syscall.SIGTERM)
+
+	state := &venom.{pascal}State{{
+		Magic:   venom.Magic,
+		Version: 1,
+	}}
+
+	updates := 0
+	publish := func() {{
+		// TODO: fill in the fields of {pascal}State
+		daemon.Write(state)
+		updates++
+		fmt.Printf("\r📤 Published update #%d (every %d ms)   ", updates, intervalMs)
+	}}
+
+	ticker := time.NewTicker(venom.IntervalMS * time.Millisecond)
 	defer ticker.Stop()
+	commands := time.NewTicker(10 * time.Millisecond)
+	defer commands.Stop()
 
 	for {{
 		select {{
 		case <-sigCh:
 			fmt.Println("\n\n👋 Goodbye!")
 			return
+		case <-commands.C:
+			if handleCommands(daemon, ticker) {{
+				publish()
+			}}
 		case <-ticker.C:
-			readCPU(state)
-			readMemory(state)
-			readUptime(state)
-			state.UpdateCounter++
-			state.TimestampNs = uint64(time.Now().UnixNano())
-			daemon.Write(state)
-
-			fmt.Printf("\r🖥️  CPU: %.1f%% | RAM: %d/%d MB | #%d   ",
-				state.CPUUsagePercent, state.MemoryUsedMB, state.MemoryTotalMB, state.UpdateCounter)
+			publish()
 		}}
 	}}
 }}
-"##, name = config.name, pascal = pascal)
+"##, name = config.name, module = config.names().flat, pascal = pascal, channel = config.channel, commands = COMMAND_HANDLER)
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -404,17 +1549,23 @@ func main() {{
 // ═══════════════════════════════════════════════════════════════════════════
 
 fn client_main(config: &ProjectConfig) -> String {
+    if !config.schema.has_demo_fields() {
+        return generic_client(config);
+    }
     format!(r##"package main
 
 import (
+	"flag"
 	"fmt"
 	"math"
 	"os"
+	"os/exec"
 	"os/signal"
+	"strings"
 	"syscall"
 	"time"
 
-	"{name}/venom"
+	"{module}/venom"
 )
 
 const (
@@ -433,6 +1584,7 @@ var (
 	latencyCount uint64 = 0
 )
 
+{keys}
 func bar(pct float32, width int) string {{
 	filled := int((pct / 100) * float32(width))
 	color := Green
@@ -459,20 +1611,34 @@ func main() {{
 	fmt.Println("╚═══════════════════════════════════════════════════════════════╝")
 	fmt.Println()
 
-	shell, err := venom.Connect()
+	ping := flag.Int("ping", 0, "measure `COUNT` round trips through the daemon instead")
+	suffix := flag.String("channel-suffix", "", "connect to the daemon started with this `SUFFIX`")
+	flag.Parse()
+
+	shell, err := venom.ConnectChannel(venom.InstanceChannel(*suffix), "go-client")
 	if err != nil {{
 		fmt.Printf("❌ Error: %v\n", err)
 		os.Exit(1)
 	}}
 	defer shell.Close()
 
-	fmt.Printf("✅ Connected! ID: %d\n", shell.ClientID())
+	fmt.Printf("✅ Connected! ID: %d (%s)\n", shell.ClientID(), venom.Library())
+	if *ping > 0 {{
+		if !runPing(shell, *ping) {{
+			shell.Close()
+			os.Exit(1)
+		}}
+		return
+	}}
 	fmt.Println("📊 Reading stats... (Ctrl+C to exit)")
 	time.Sleep(1 * time.Second)
 
 	sigCh := make(chan os.Signal, 1)
 	signal.Notify(sigCh, syscall.SIGINT, syscall.SIGTERM)
 
+	keys, restore := readKeys()
+	defer restore()
+
 	ticker := time.NewTicker(100 * time.Millisecond)
 	defer ticker.Stop()
 
@@ -489,7 +1655,14 @@ func main() {{
 			fmt.Printf("   Avg: %.2f µs\n", latencySum/float64(latencyCount))
 			fmt.Println("\n👋 Goodbye!")
 			return
+		case key := <-keys:
+			handleKey(shell, key)
 		case <-ticker.C:
+			// Reads would keep returning the last state it wrote
+			if !shell.DaemonAlive() {{
+				fmt.Println("\n❌ Daemon offline! Restart it:\n   make run-daemon")
+				return
+			}}
 			// ═══════════════════════════════════════════════════════════════════
 			// 📊 BENCHMARK: Measure read latency
 			// ═══════════════════════════════════════════════════════════════════
@@ -527,49 +1700,251 @@ func main() {{
 				fmt.Println("╠═══════════════════════════════════════════════════════════════╣")
 				fmt.Printf("║  📊 %sRead Latency:%s %.2f µs (min: %.2f, max: %.2f, avg: %.2f)  ║\n", 
 					Cyan, Reset, latencyUs, latencyMin, latencyMax, avgUs)
+				fmt.Printf("║  📡 %sDaemon→client:%s %.2f µs since the write                 ║\n", Cyan, Reset, shell.WriteAgeUs)
 				fmt.Println("╚═══════════════════════════════════════════════════════════════╝")
-				fmt.Printf("  Cores: %d | Updates: %d | Ctrl+C to exit\n", state.CoreCount, state.UpdateCounter)
+				fmt.Printf("  Cores: %d | Updates: %d | Interval: %d ms\n", state.CoreCount, state.UpdateCounter, intervalMs)
+				fmt.Println("  +/- faster/slower | r refresh | Ctrl+C to exit")
 				frame++
 			}}
 		}}
 	}}
 }}
-"##, name = config.name)
+"##, name = config.name, module = config.names().flat, keys = COMMAND_KEYS)
+}
+
+/// Client for a custom schema: prints every field of the state
+fn generic_client(config: &ProjectConfig) -> String {
+    let fields: String = config.schema.user_fields()
+        .map(|f| format!("\t\t\t\tfmt.Printf(\"  %-24s %v\\n\", \"{}\", state.{})\n", f.name, go_name(&f.name)))
+        .collect();
+    
+    format!(r##"package main
+
+import (
+	"flag"
+	"fmt"
+	"math"
+	"os"
+	"os/exec"
+	"os/signal"
+	"strings"
+	"syscall"
+	"time"
+
+	"{module}/venom"
+)
+
+{keys}
+func main() {{
+	ping := flag.Int("ping", 0, "measure `COUNT` round trips through the daemon instead")
+	suffix := flag.String("channel-suffix", "", "connect to the daemon started with this `SUFFIX`")
+	flag.Parse()
+
+	shell, err := venom.ConnectChannel(venom.InstanceChannel(*suffix), "go-client")
+	if err != nil {{
+		fmt.Printf("❌ Error: %v\n", err)
+		os.Exit(1)
+	}}
+	defer shell.Close()
+	fmt.Printf("✅ Connected! ID: %d (%s)\n", shell.ClientID(), venom.Library())
+	if *ping > 0 {{
+		if !runPing(shell, *ping) {{
+			shell.Close()
+			os.Exit(1)
+		}}
+		return
+	}}
+
+	sigCh := make(chan os.Signal, 1)
+	signal.Notify(sigCh, syscall.SIGINT, syscall.SIGTERM)
+
+	keys, restore := readKeys()
+	defer restore()
+
+	ticker := time.NewTicker(100 * time.Millisecond)
+	defer ticker.Stop()
+
+	latMin, latMax, latSum, latCount := math.MaxFloat64, 0.0, 0.0, 0
+	for {{
+		select {{
+		case <-sigCh:
+			fmt.Printf("\n\n📊 Final Latency Stats (Go): %d samples, min %.2f µs, max %.2f µs, avg %.2f µs\n",
+				latCount, latMin, latMax, latSum/float64(latCount))
+			fmt.Println("\n👋 Goodbye!")
+			return
+		case key := <-keys:
+			handleKey(shell, key)
+		case <-ticker.C:
+			// Reads would keep returning the last state it wrote
+			if !shell.DaemonAlive() {{
+				fmt.Println("\n❌ Daemon offline! Restart it:\n   make run-daemon")
+				return
+			}}
+			tStart := time.Now()
+			state := shell.ReadState()
+			latencyUs := float64(time.Since(tStart).Nanoseconds()) / 1000.0
+
+			latMin = math.Min(latMin, latencyUs)
+			latMax = math.Max(latMax, latencyUs)
+			latSum += latencyUs
+			latCount++
+
+			if state.IsValid() {{
+				fmt.Print("\033[2J\033[H")
+				fmt.Printf("🐍 {name} (Go) | State v%d | %d bytes\n", state.Version, venom.StateSize)
+				fmt.Println("═══════════════════════════════════════════════════════════════")
+{fields}				fmt.Println("═══════════════════════════════════════════════════════════════")
+				fmt.Printf("📊 Read Latency: %.2f µs (min: %.2f, max: %.2f, avg: %.2f)\n",
+					latencyUs, latMin, latMax, latSum/float64(latCount))
+				fmt.Printf("📡 Daemon→client: %.2f µs since the write\n", shell.WriteAgeUs)
+				fmt.Printf("⏱️  Daemon interval: %d ms | +/- faster/slower | r refresh\n", intervalMs)
+			}}
+		}}
+	}}
+}}
+"##, name = config.name, module = config.names().flat, fields = fields, keys = COMMAND_KEYS)
+}
+
+/// `#cgo LDFLAGS` directive; with several bundled targets the library path
+/// comes from the Makefile's CGO_LDFLAGS instead
+fn cgo_ldflags(config: &ProjectConfig) -> String {
+    if config.targets.is_empty() {
+        format!(
+            "#cgo LDFLAGS: -L${{SRCDIR}}/../{lib} -lvenom_memory -Wl,-rpath,$ORIGIN/../{lib}",
+            lib = config.at_root("lib")
+        )
+    } else {
+        "#cgo LDFLAGS: -lvenom_memory".to_string()
+    }
+}
+
+/// Makefile preamble selecting the bundled library cgo links against
+fn make_target_vars(config: &ProjectConfig) -> String {
+    if config.targets.is_empty() {
+        return String::new();
+    }
+    format!(
+        "{}export CGO_LDFLAGS = -L$(CURDIR)/{lib}/$(VENOM_TARGET) -Wl,-rpath,$$ORIGIN/{lib}/$(VENOM_TARGET)\n\n",
+        super::make_target_var(config),
+        lib = config.at_root("lib")
+    )
 }
 
 fn go_mod(config: &ProjectConfig) -> String {
-    format!(r#"module {name}
+    let require = if config.go_nocgo { "\nrequire golang.org/x/sys v0.26.0\n" } else { "" };
+    format!(r#"module {module}
 
 go 1.21
-"#, name = config.name)
+{require}"#, module = config.names().flat, require = require)
 }
 
-fn makefile(config: &ProjectConfig) -> String {
+fn makefile(config: &ProjectConfig, with_daemon: bool) -> String {
+    if !with_daemon {
+        return client_makefile(config);
+    }
+    
     format!(r#"# {name} Go Project Makefile
 
-.PHONY: all daemon client clean run-daemon run-client
+{target_vars}.PHONY: all daemon client clean run-daemon run-client test
 
 all: daemon client
 
-daemon:
+daemon:{go_sum}
 	@echo "🔗 Building daemon..."
 	@cd daemon && CGO_ENABLED=1 go build -o ../{name}_daemon .
 	@echo "✅ Daemon built"
 
-client:
+client:{go_sum}
 	@echo "🔗 Building client..."
-	@cd client && CGO_ENABLED=1 go build -o ../{name}_client .
+	@cd client && CGO_ENABLED={cgo} go build -o ../{name}_client .
 	@echo "✅ Client built"
 
 run-daemon: daemon
-	@LD_LIBRARY_PATH=./lib ./{name}_daemon
+	@LD_LIBRARY_PATH=./{lib} ./{name}_daemon $(ARGS)
 
 run-client: client
-	@LD_LIBRARY_PATH=./lib ./{name}_client
+	@{client_env}./{name}_client $(ARGS)
 
+# {live_env}=1 make test also reads the running daemon
+test:{go_sum}
+	@{test_env}CGO_ENABLED={cgo} go test ./venom
+{go_sum_rule}
 clean:
 	@rm -f {name}_daemon {name}_client
-"#, name = config.name)
+"#,
+        name = config.name,
+        target_vars = make_target_vars(config),
+        lib = super::make_lib_dir(config, &config.at_root("lib")),
+        go_sum = if config.go_nocgo { " go.sum" } else { "" },
+        go_sum_rule = go_sum_rule(config),
+        cgo = if config.go_nocgo { 0 } else { 1 },
+        client_env = client_env(config),
+        test_env = test_env(config),
+        live_env = super::LIVE_TEST_ENV,
+    )
+}
+
+/// Makefile for a client added to a project whose daemon is built elsewhere
+fn client_makefile(config: &ProjectConfig) -> String {
+    format!(r#"# {name} Go Client Makefile
+
+{target_vars}.PHONY: all client clean run-client test
+
+all: client
+
+client:{go_sum}
+	@echo "🔗 Building client..."
+	@cd client && CGO_ENABLED={cgo} go build -o ../{name}_client .
+	@echo "✅ Client built"
+
+run-client: client
+	@{client_env}./{name}_client $(ARGS)
+
+# {live_env}=1 make test also reads the running daemon
+test:{go_sum}
+	@{test_env}CGO_ENABLED={cgo} go test ./venom
+{go_sum_rule}
+clean:
+	@rm -f {name}_client
+"#,
+        name = config.name,
+        // The pure-Go client links nothing
+        target_vars = if config.go_nocgo { String::new() } else { make_target_vars(config) },
+        go_sum = if config.go_nocgo { " go.sum" } else { "" },
+        go_sum_rule = go_sum_rule(config),
+        cgo = if config.go_nocgo { 0 } else { 1 },
+        client_env = client_env(config),
+        test_env = test_env(config),
+        live_env = super::LIVE_TEST_ENV,
+    )
+}
+
+/// Rule fetching golang.org/x/sys for the pure-Go client
+fn go_sum_rule(config: &ProjectConfig) -> &'static str {
+    if config.go_nocgo {
+        "\ngo.sum: go.mod\n\t@go mod tidy\n"
+    } else {
+        ""
+    }
+}
+
+/// Environment `run-client` starts the client with
+fn client_env(config: &ProjectConfig) -> String {
+    if config.go_nocgo {
+        String::new()
+    } else {
+        format!("LD_LIBRARY_PATH=./{} ", super::make_lib_dir(config, &config.at_root("lib")))
+    }
+}
+
+/// Environment `test` runs `go test` with; the test binary runs from
+/// `venom/`, so the library path is absolute
+fn test_env(config: &ProjectConfig) -> String {
+    if config.go_nocgo {
+        String::new()
+    } else {
+        format!("LD_LIBRARY_PATH=$(CURDIR)/{} ", super::make_lib_dir(config, &config.at_root("lib")))
+    }
 }
 
 fn readme(config: &ProjectConfig) -> String {
@@ -590,15 +1965,41 @@ make run-daemon
 make run-client
 ```
 
+## Tests
+
+`make test` runs `go test ./venom`, which decodes a known state built from
+`venom.toml`, so it fails when `venom/venom.go` no longer matches the
+daemon's layout. With the daemon running, `{live_env}=1 make test` also
+reads the live channel.
+{ping}{instances}
 ## Configuration
 
 | Setting | Value |
 |---------|-------|
 | Channel | `{channel}` |
 | Magic | `0x{magic:08X}` |
-"#,
+{nocgo}{service}"#,
         name = config.name,
+        nocgo = if config.go_nocgo { NOCGO_README } else { "" },
+        service = super::service::readme_section(config),
+        live_env = super::LIVE_TEST_ENV,
+        ping = super::ping_readme_section("make run-client ARGS=\"--ping 10\""),
+        instances = super::instances_readme_section(&config.channel, "make run-daemon ARGS=\"--channel-suffix SUFFIX\"", "make run-client ARGS=\"--channel-suffix SUFFIX\"", "`venom.InstanceChannel()`"),
         channel = config.channel,
-        magic = magic(&config.channel)
+        magic = config.magic
     )
 }
+
+const NOCGO_README: &str = r#"
+## Pure-Go Client
+
+The client needs no cgo: `venom/venom.go` maps the channel in `/dev/shm`
+itself, following the layout `venom_memory::layout` keeps stable for a
+channel version. Only the daemon links `lib/libvenom_memory.so`
+(`venom/daemon.go`, which `CGO_ENABLED=0` builds leave out), so the client
+builds statically and cross-compiles for any 64-bit Linux target:
+
+```bash
+cd client && CGO_ENABLED=0 GOARCH=arm64 go build -o ../client_arm64 .
+```
+"#;
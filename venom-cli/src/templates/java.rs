@@ -0,0 +1,1027 @@
+//! Java Templates for VenomMemory projects
+//!
+//! Generates a plain-javac project using the java.lang.foreign (Panama) FFI:
+//! - src/VenomShell.java - Downcall handles for the shell API
+//! - src/State.java - Little-endian decoder for the packed state (layout from venom.toml)
+//! - src/Main.java - Console monitor with read latency benchmarking
+//! - run.sh - Compiles and runs with java.library.path and native access set
+//! - native/libvenom_memory.so - Bundled library
+//! - daemon/ - Self-contained C daemon
+
+use super::ProjectConfig;
+use crate::schema::{camel_case, FieldType};
+
+pub fn generate(config: &ProjectConfig) {
+    let base = &config.output_dir;
+    
+    crate::create_dir(&format!("{}/daemon/src", base));
+    
+    // Java client files
+    generate_client(config);
+    
+    // C Daemon files (so the Java project is self-contained)
+    crate::write_file(&format!("{}/daemon/src/main.c", base), &daemon_c(config));
+    crate::write_file(&format!("{}/daemon/Makefile", base), &daemon_makefile(config));
+    crate::write_file(&format!("{}/daemon/protocol.h", base), &protocol_h(config));
+    
+    crate::write_file(&format!("{}/README.md", base), &readme(config));
+    
+    // Also copy the library to daemon/ (for the C daemon)
+    crate::library::install(&format!("{}/daemon", base), &config.targets);
+}
+
+/// Client files only (used by `venom add-client`), plus the library in native/
+pub fn generate_client(config: &ProjectConfig) {
+    let base = &config.output_dir;
+    
+    crate::create_dir(&format!("{}/src", base));
+    
+    crate::write_file(&format!("{}/src/VenomShell.java", base), &venom_shell_java(config));
+    crate::write_file(&format!("{}/src/State.java", base), &state_java(config));
+    crate::write_file(&format!("{}/src/Main.java", base), &main_java(config));
+    
+    let run_sh_path = format!("{}/run.sh", base);
+    crate::write_file(&run_sh_path, &run_sh(config));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Ok(meta) = std::fs::metadata(&run_sh_path) {
+            let mut perms = meta.permissions();
+            perms.set_mode(0o755);
+            std::fs::set_permissions(&run_sh_path, perms).ok();
+        }
+    }
+    
+    crate::library::install(&format!("{}/native", base), &config.targets);
+}
+
+
+
+// ═══════════════════════════════════════════════════════════════════════════
+// C Daemon (so the Java project is self-contained)
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn protocol_h(config: &ProjectConfig) -> String {
+    let upper = config.names().upper;
+    let pascal = config.names().pascal;
+    
+    format!(r#"#ifndef {upper}_PROTOCOL_H
+#define {upper}_PROTOCOL_H
+
+#include <stdint.h>
+#define {upper}_CHANNEL_NAME "{channel}"
+#define {upper}_MAGIC 0x{magic:08X}
+#define {upper}_STATE_SIZE {state_size}
+{max_cores}
+typedef struct __attribute__((packed)) {{
+{fields}}} {pascal}State;
+
+_Static_assert(sizeof({pascal}State) == {upper}_STATE_SIZE, "{pascal}State size mismatch");
+
+{commands}
+#endif
+"#,
+        upper = upper,
+        pascal = pascal,
+        channel = config.channel,
+        magic = config.magic,
+        state_size = config.schema.size,
+        max_cores = super::c::max_cores_define(config),
+        fields = config.schema.c_fields("    "),
+        commands = super::c::commands_h(config),
+    )
+}
+
+fn daemon_c(config: &ProjectConfig) -> String {
+    if !config.schema.has_demo_fields() {
+        return super::c::generic_daemon_c(config, "../protocol.h");
+    }
+    let upper = config.names().upper;
+    let pascal = config.names().pascal;
+    
+    format!(r#"/* {name} Daemon - VenomMemory */
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <signal.h>
+#include <unistd.h>
+#include <time.h>
+#include "../protocol.h"
+
+typedef struct VenomDaemonHandle VenomDaemonHandle;
+typedef struct {{ size_t struct_size; size_t data_size; size_t cmd_slots; size_t max_clients; size_t cmd_payload_size; size_t cmd_full_policy; size_t shm_mode; }} VenomConfigEx;
+extern VenomDaemonHandle* venom_daemon_create_ex(const char* name, const VenomConfigEx* config);
+extern void venom_daemon_destroy(VenomDaemonHandle* handle);
+extern void venom_daemon_write_data(VenomDaemonHandle* handle, const uint8_t* data, size_t len);
+extern size_t venom_daemon_try_recv_command(VenomDaemonHandle* handle, uint8_t* buf, size_t max_len, uint32_t* out_client_id);
+
+static VenomDaemonHandle* g_daemon = NULL;
+static {pascal}State g_state = {{0}};
+static volatile int g_running = 1;
+static uint64_t prev_total[{upper}_MAX_CORES + 1] = {{0}};
+static uint64_t prev_idle[{upper}_MAX_CORES + 1] = {{0}};
+
+static void signal_handler(int sig) {{ (void)sig; g_running = 0; }}
+
+{commands}
+{channel_arg}
+static void read_cpu(void) {{
+    FILE* f = fopen("/proc/stat", "r");
+    if (!f) return;
+    char line[256];
+    int idx = 0;
+    while (fgets(line, sizeof(line), f) && idx <= {upper}_MAX_CORES) {{
+        if (strncmp(line, "cpu", 3) != 0) continue;
+        uint64_t user, nice, system, idle, iowait, irq, softirq;
+        if (sscanf(line + (line[3] == ' ' ? 4 : 5), "%lu %lu %lu %lu %lu %lu %lu",
+                   &user, &nice, &system, &idle, &iowait, &irq, &softirq) != 7) continue;
+        uint64_t total = user + nice + system + idle + iowait + irq + softirq;
+        uint64_t idle_t = idle + iowait;
+        uint64_t td = total - prev_total[idx], id = idle_t - prev_idle[idx];
+        float usage = td > 0 ? (1.0f - (float)id / (float)td) * 100.0f : 0;
+        if (line[3] == ' ') g_state.cpu_usage_percent = usage;
+        else if (idx > 0 && idx <= {upper}_MAX_CORES) g_state.cpu_cores[idx-1] = usage;
+        prev_total[idx] = total; prev_idle[idx] = idle_t; idx++;
+    }}
+    g_state.core_count = idx > 1 ? idx - 1 : 0;
+    fclose(f);
+}}
+
+static void read_mem(void) {{
+    FILE* f = fopen("/proc/meminfo", "r");
+    if (!f) return;
+    char line[256];
+    uint64_t total = 0, avail = 0;
+    while (fgets(line, sizeof(line), f)) {{
+        if (strncmp(line, "MemTotal:", 9) == 0) sscanf(line + 9, "%lu", &total);
+        else if (strncmp(line, "MemAvailable:", 13) == 0) sscanf(line + 13, "%lu", &avail);
+    }}
+    g_state.memory_total_mb = (uint32_t)(total / 1024);
+    g_state.memory_used_mb = (uint32_t)((total - avail) / 1024);
+    fclose(f);
+}}
+
+static void read_uptime(void) {{
+    FILE* f = fopen("/proc/uptime", "r");
+    if (!f) return;
+    double up; if (fscanf(f, "%lf", &up) == 1) g_state.uptime_seconds = (uint64_t)up;
+    fclose(f);
+}}
+
+int main(int argc, char** argv) {{
+    printf("🖥️  {name} Daemon (VenomMemory)\\n");
+    printf("═══════════════════════════════════════════════════════════════\\n");
+    signal(SIGINT, signal_handler); signal(SIGTERM, signal_handler);
+    
+    VenomConfigEx cfg = {{ .struct_size = sizeof(VenomConfigEx), .data_size = 16384, .cmd_slots = 32, .max_clients = 16, .cmd_payload_size = sizeof({pascal}Command), .shm_mode = 0{shm_mode:o} }};
+    const char* channel = channel_from_args(argc, argv);
+    g_daemon = venom_daemon_create_ex(channel, &cfg);
+    if (!g_daemon) {{ printf("❌ Failed to create channel\\n"); return 1; }}
+    
+    printf("✅ Channel: %s\\n🚀 Publishing... (Ctrl+C to stop)\\n\\n", channel);
+    
+    uint64_t next_publish = 0;
+    while (g_running) {{
+        int refresh = handle_commands(g_daemon);
+        if (!refresh && now_ms() < next_publish) {{ usleep(10000); continue; }}
+        next_publish = now_ms() + g_interval_ms;
+        
+        read_cpu(); read_mem(); read_uptime();
+        g_state.magic = {upper}_MAGIC; g_state.version = 1; g_state.update_counter++;
+        struct timespec ts; clock_gettime(CLOCK_MONOTONIC, &ts);
+        g_state.timestamp_ns = (uint64_t)ts.tv_sec * 1000000000ULL + ts.tv_nsec;
+        publish(g_daemon, &g_state);
+        printf("\\r🖥️  CPU: %5.1f%% | RAM: %u/%u MB | #%lu | %u ms   ",
+            g_state.cpu_usage_percent, g_state.memory_used_mb, g_state.memory_total_mb,
+            (unsigned long)g_state.update_counter, g_interval_ms);
+        fflush(stdout);
+    }}
+    venom_daemon_destroy(g_daemon);
+    printf("\\n\\n👋 Goodbye!\\n");
+    return 0;
+}}
+"#, name = config.name, upper = upper, pascal = pascal, shm_mode = config.shm_mode, commands = super::c::command_handler_c(config), channel_arg = super::c::channel_arg_c(config))
+}
+
+fn daemon_makefile(config: &ProjectConfig) -> String {
+    format!(r#"# {name} Daemon Makefile
+
+{target_var}CC = gcc
+CFLAGS = -Wall -Wextra -O2
+LDFLAGS = -L.{sub} -lvenom_memory -Wl,-rpath,'$$ORIGIN{sub}'
+
+TARGET = {name}_daemon
+
+.PHONY: all clean run
+
+all: $(TARGET)
+
+$(TARGET): src/main.c
+	@echo "🔗 Building $(TARGET)..."
+	@$(CC) $(CFLAGS) src/main.c -o $(TARGET) $(LDFLAGS)
+	@echo "✅ Build complete"
+
+clean:
+	@rm -f $(TARGET)
+
+run: $(TARGET)
+	@./$(TARGET) $(ARGS)
+"#,
+        name = config.name,
+        target_var = super::make_target_var(config),
+        sub = super::make_lib_dir(config, ""),
+    )
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Java Client
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn venom_shell_java(config: &ProjectConfig) -> String {
+    format!(r#"/*
+ * VenomMemory Panama FFI Bindings for {name}
+ *
+ * Wraps venom_shell_connect/read_data_stamped/id/send_command/destroy,
+ * venom_clock_ns and venom_version with downcall handles.
+ * libvenom_memory.so is loaded from java.library.path (see run.sh).
+ *
+ * Only uses java.lang.foreign API that is identical in JDK 21 (preview)
+ * and JDK 22+ (final).
+ */
+
+import java.lang.foreign.Arena;
+import java.lang.foreign.FunctionDescriptor;
+import java.lang.foreign.Linker;
+import java.lang.foreign.MemorySegment;
+import java.lang.foreign.SymbolLookup;
+import java.lang.invoke.MethodHandle;
+import java.nio.ByteBuffer;
+import java.nio.ByteOrder;
+import java.nio.charset.StandardCharsets;
+
+import static java.lang.foreign.ValueLayout.ADDRESS;
+import static java.lang.foreign.ValueLayout.JAVA_BOOLEAN;
+import static java.lang.foreign.ValueLayout.JAVA_BYTE;
+import static java.lang.foreign.ValueLayout.JAVA_INT;
+import static java.lang.foreign.ValueLayout.JAVA_LONG;
+
+public final class VenomShell implements AutoCloseable {{
+    // Commands the daemon understands (CmdType in daemon/protocol.h)
+    public static final short CMD_REFRESH = 1;       // Publish now
+    public static final short CMD_SET_INTERVAL = 2;  // value = publish interval in ms (10-10000)
+    public static final short CMD_PING = 3;          // value = sequence number, echoed back in a pong
+    public static final int COMMAND_SIZE = 8;
+    // Reply to the last ping, published right after the state: client ID and seq (u32 each)
+    public static final int PONG_SIZE = 8;
+    public static final int DEFAULT_INTERVAL_MS = 100;
+
+    private static final MethodHandle CONNECT;
+    private static final MethodHandle DESTROY;
+    private static final MethodHandle READ_DATA;
+    private static final MethodHandle CLOCK_NS;
+    private static final MethodHandle VERSION;
+    private static final MethodHandle ID;
+    private static final MethodHandle SEND_COMMAND;
+
+    static {{
+        System.loadLibrary("venom_memory");
+        Linker linker = Linker.nativeLinker();
+        SymbolLookup lookup = SymbolLookup.loaderLookup();
+
+        CONNECT = linker.downcallHandle(find(lookup, "venom_shell_connect"),
+            FunctionDescriptor.of(ADDRESS, ADDRESS));
+        DESTROY = linker.downcallHandle(find(lookup, "venom_shell_destroy"),
+            FunctionDescriptor.ofVoid(ADDRESS));
+        READ_DATA = linker.downcallHandle(find(lookup, "venom_shell_read_data_stamped"),
+            FunctionDescriptor.of(JAVA_LONG, ADDRESS, ADDRESS, JAVA_LONG, ADDRESS));
+        CLOCK_NS = linker.downcallHandle(find(lookup, "venom_clock_ns"),
+            FunctionDescriptor.of(JAVA_LONG));
+        VERSION = linker.downcallHandle(find(lookup, "venom_version"),
+            FunctionDescriptor.of(ADDRESS));
+        ID = linker.downcallHandle(find(lookup, "venom_shell_id"),
+            FunctionDescriptor.of(JAVA_INT, ADDRESS));
+        SEND_COMMAND = linker.downcallHandle(find(lookup, "venom_shell_send_command"),
+            FunctionDescriptor.of(JAVA_BOOLEAN, ADDRESS, ADDRESS, JAVA_LONG));
+    }}
+
+    private static MemorySegment find(SymbolLookup lookup, String name) {{
+        return lookup.find(name)
+            .orElseThrow(() -> new UnsatisfiedLinkError("missing symbol: " + name));
+    }}
+
+    private final Arena arena = Arena.ofConfined();
+    private final MemorySegment readBuf = arena.allocate(State.SIZE + PONG_SIZE);
+    private final MemorySegment cmdBuf = arena.allocate(COMMAND_SIZE);
+    private final MemorySegment stampBuf = arena.allocate(JAVA_LONG);
+    private MemorySegment handle;
+    private double writeAgeUs;
+
+    /** Connect to the channel published by the daemon */
+    public VenomShell() {{
+        this(State.CHANNEL_NAME);
+    }}
+
+    public VenomShell(String channel) {{
+        byte[] name = channel.getBytes(StandardCharsets.UTF_8);
+        try (Arena tmp = Arena.ofConfined()) {{
+            MemorySegment cName = tmp.allocate(name.length + 1);
+            MemorySegment.copy(name, 0, cName, JAVA_BYTE, 0, name.length);
+            handle = (MemorySegment) CONNECT.invokeExact(cName);
+        }} catch (Throwable t) {{
+            arena.close();
+            throw new IllegalStateException("venom_shell_connect failed", t);
+        }}
+
+        if (handle.address() == 0) {{
+            arena.close();
+            throw new IllegalStateException(
+                "Failed to connect to channel \"" + channel + "\". Is the daemon running?");
+        }}
+    }}
+
+    /** Client ID assigned by the daemon */
+    public int clientId() {{
+        try {{
+            return (int) ID.invokeExact(live());
+        }} catch (Throwable t) {{
+            throw new IllegalStateException(t);
+        }}
+    }}
+
+    /** Version of the libvenom_memory that was loaded */
+    public static String libraryVersion() {{
+        try {{
+            // A static NUL-terminated string: read up to the NUL, never past it
+            MemorySegment version = ((MemorySegment) VERSION.invokeExact()).reinterpret(64);
+            StringBuilder out = new StringBuilder();
+            for (long i = 0; i < version.byteSize() && version.get(JAVA_BYTE, i) != 0; i++) {{
+                out.append((char) version.get(JAVA_BYTE, i));
+            }}
+            return out.toString();
+        }} catch (Throwable t) {{
+            throw new IllegalStateException(t);
+        }}
+    }}
+
+    /** Read the raw state bytes (and a pong after them); returns the full data length */
+    public int readRawData(byte[] out) {{
+        try {{
+            long len = (long) READ_DATA.invokeExact(live(), readBuf, readBuf.byteSize(), stampBuf);
+            MemorySegment.copy(readBuf, JAVA_BYTE, 0, out, 0, Math.min(out.length, (int) readBuf.byteSize()));
+            long writtenNs = stampBuf.get(JAVA_LONG, 0);
+            if (writtenNs != 0) {{
+                writeAgeUs = ((long) CLOCK_NS.invokeExact() - writtenNs) / 1000.0;
+            }}
+            return (int) len;
+        }} catch (Throwable t) {{
+            throw new IllegalStateException(t);
+        }}
+    }}
+
+    /** µs between the daemon's write and the last read (0 if unstamped) */
+    public double writeAgeUs() {{
+        return writeAgeUs;
+    }}
+
+    /** Read and decode state from daemon */
+    public State readState() {{
+        byte[] buf = new byte[State.SIZE];
+        int len = readRawData(buf);
+        return len < State.SIZE ? State.EMPTY : State.fromBytes(buf);
+    }}
+
+    /** Send a Command, a typed command (cmd as the tag, payload length 4, value; all
+     *  little-endian); false if the queue is full */
+    public boolean sendCommand(short cmd, int value) {{
+        byte[] bytes = ByteBuffer.allocate(COMMAND_SIZE).order(ByteOrder.LITTLE_ENDIAN)
+            .putShort(0, cmd).putShort(2, (short) Integer.BYTES).putInt(4, value).array();
+        try {{
+            MemorySegment.copy(bytes, 0, cmdBuf, JAVA_BYTE, 0, COMMAND_SIZE);
+            return (boolean) SEND_COMMAND.invokeExact(live(), cmdBuf, (long) COMMAND_SIZE);
+        }} catch (Throwable t) {{
+            throw new IllegalStateException(t);
+        }}
+    }}
+
+    /** Ask the daemon to publish every {{@code ms}} milliseconds */
+    public boolean setInterval(int ms) {{
+        return sendCommand(CMD_SET_INTERVAL, ms);
+    }}
+
+    /** Ask the daemon to publish right away */
+    public boolean refresh() {{
+        return sendCommand(CMD_REFRESH, 0);
+    }}
+
+    /**
+     * Round trip of a ping carrying {{@code seq}} in µs, or -1 if no pong came back within a second.
+     *
+     * <p>The daemon answers by publishing at once with a pong after the state. It keeps
+     * only the last one, so clients pinging at the same time can miss their reply.
+     */
+    public double ping(int seq) {{
+        long start = System.nanoTime();
+        if (!sendCommand(CMD_PING, seq)) return -1;
+        byte[] buf = new byte[State.SIZE + PONG_SIZE];
+        ByteBuffer pong = ByteBuffer.wrap(buf).order(ByteOrder.LITTLE_ENDIAN);
+        int id = clientId();
+        while (System.nanoTime() - start < 1_000_000_000L) {{
+            if (readRawData(buf) < buf.length) continue;
+            if (pong.getInt(State.SIZE) == id && pong.getInt(State.SIZE + 4) == seq) {{
+                return (System.nanoTime() - start) / 1000.0;
+            }}
+        }}
+        return -1;
+    }}
+
+    private MemorySegment live() {{
+        if (handle == null) throw new IllegalStateException("VenomShell has been closed");
+        return handle;
+    }}
+
+    @Override
+    public void close() {{
+        if (handle == null) return;
+        try {{
+            DESTROY.invokeExact(handle);
+        }} catch (Throwable t) {{
+            throw new IllegalStateException(t);
+        }} finally {{
+            handle = null;
+            arena.close();
+        }}
+    }}
+}}
+"#, name = config.name)
+}
+
+fn state_java(config: &ProjectConfig) -> String {
+    let upper = config.names().upper;
+    let schema = &config.schema;
+    
+    let max_cores = match schema.field("cpu_cores").and_then(|f| f.len) {
+        Some(n) if schema.has_demo_fields() => format!("    public static final int MAX_CORES = {};\n", n),
+        _ => String::new(),
+    };
+    
+    let mut components = Vec::new();
+    let mut offsets = String::new();
+    let mut arrays = String::new();
+    let mut args = Vec::new();
+    let mut used: Vec<FieldType> = Vec::new();
+    for f in schema.data_fields() {
+        let name = camel_case(&f.name);
+        let off = format!("OFF_{}", f.name.to_uppercase());
+        let ty = java_type(f.ty);
+        offsets.push_str(&format!("    public static final int {} = {};\n", off, f.offset));
+        if var_handle(f.ty).is_some() && !used.iter().any(|t| var_handle(*t) == var_handle(f.ty)) {
+            used.push(f.ty);
+        }
+        match f.len {
+            Some(n) => {
+                components.push(format!("    {}[] {}", ty, name));
+                arrays.push_str(&format!(
+                    "\n        {ty}[] {name} = new {ty}[{n}];\n        for (int i = 0; i < {n}; i++) {{\n            {name}[i] = {get};\n        }}\n",
+                    ty = ty,
+                    name = name,
+                    n = n,
+                    get = java_get(f.ty, &format!("{} + i * {}", off, f.ty.size()))
+                ));
+                args.push(format!("            {}", name));
+            }
+            None => {
+                components.push(format!("    {} {}", ty, name));
+                args.push(format!("            {}", java_get(f.ty, &off)));
+            }
+        }
+    }
+    
+    let handles: String = used.iter()
+        .map(|ty| format!(
+            "    private static final VarHandle {} =\n        MethodHandles.byteArrayViewVarHandle({}[].class, ByteOrder.LITTLE_ENDIAN);\n",
+            var_handle(*ty).unwrap_or_default(),
+            java_type(*ty)
+        ))
+        .collect();
+    
+    format!(r#"/*
+ * {name} state - decodes the packed C struct from daemon/protocol.h
+ *
+ * Every field is read with an explicit little-endian VarHandle at its
+ * documented offset, so the result doesn't depend on JVM byte order.
+ */
+
+import java.lang.invoke.MethodHandles;
+import java.lang.invoke.VarHandle;
+import java.nio.ByteOrder;
+
+public record State(
+{components}
+) {{
+    // ═══════════════════════════════════════════════════════════════════════
+    // Configuration (from {upper}_CHANNEL_NAME / {upper}_MAGIC)
+    // ═══════════════════════════════════════════════════════════════════════
+
+    public static final String CHANNEL_NAME = "{channel}";
+
+    /** The channel of one of several instances: "{channel}-SUFFIX", or CHANNEL_NAME itself for no suffix */
+    public static String instanceChannel(String suffix) {{
+        return suffix == null || suffix.isEmpty() ? CHANNEL_NAME : CHANNEL_NAME + "-" + suffix;
+    }}
+    public static final int MAGIC = 0x{magic:08X};
+{max_cores}    public static final int SIZE = {state_size};
+
+    // Field offsets (packed layout, generated from venom.toml)
+{offsets}
+{handles}
+    public static final State EMPTY = fromBytes(new byte[SIZE]);
+
+    /** Decode state from raw bytes (must match C struct layout) */
+    public static State fromBytes(byte[] b) {{
+        if (b.length < SIZE) return EMPTY;
+{arrays}
+        return new State(
+{args}
+        );
+    }}
+
+    public boolean isValid() {{
+        return magic == MAGIC;
+    }}
+{helpers}}}
+"#,
+        name = config.name,
+        upper = upper,
+        channel = config.channel,
+        magic = config.magic,
+        max_cores = max_cores,
+        state_size = schema.size,
+        components = components.join(",\n"),
+        offsets = offsets,
+        handles = handles,
+        arrays = arrays,
+        args = args.join(",\n"),
+        helpers = if schema.has_demo_fields() { DEMO_HELPERS } else { "" }
+    )
+}
+
+/// Methods used by the system-monitor demo
+const DEMO_HELPERS: &str = r#"
+    public double memoryUsagePercent() {
+        return memoryTotalMb > 0 ? memoryUsedMb * 100.0 / memoryTotalMb : 0;
+    }
+
+    public String uptimeFormatted() {
+        return (uptimeSeconds / 3600) + "h " + ((uptimeSeconds % 3600) / 60) + "m";
+    }
+"#;
+
+/// Java type of one element (unsigned values are read into the signed type of the same width)
+fn java_type(ty: FieldType) -> &'static str {
+    match ty {
+        FieldType::U8 | FieldType::I8 => "byte",
+        FieldType::U16 | FieldType::I16 => "short",
+        FieldType::U32 | FieldType::I32 => "int",
+        FieldType::U64 | FieldType::I64 => "long",
+        FieldType::F32 => "float",
+        FieldType::F64 => "double",
+    }
+}
+
+/// Name of the little-endian VarHandle that reads `ty` (`None` for single bytes)
+fn var_handle(ty: FieldType) -> Option<&'static str> {
+    match ty {
+        FieldType::U8 | FieldType::I8 => None,
+        FieldType::U16 | FieldType::I16 => Some("U16"),
+        FieldType::U32 | FieldType::I32 => Some("U32"),
+        FieldType::U64 | FieldType::I64 => Some("U64"),
+        FieldType::F32 => Some("F32"),
+        FieldType::F64 => Some("F64"),
+    }
+}
+
+/// Read of one element at `offset` from the byte array `b`
+fn java_get(ty: FieldType, offset: &str) -> String {
+    match var_handle(ty) {
+        Some(handle) => format!("({}) {}.get(b, {})", java_type(ty), handle, offset),
+        None => format!("b[{}]", offset),
+    }
+}
+
+/// Keyboard handling shared by both clients: +/- change the daemon's
+/// publish interval and r asks for an immediate publish; also `--ping` and
+/// `--channel-suffix`
+const COMMAND_KEYS: &str = r#"
+    // ═══════════════════════════════════════════════════════════════════════
+    // ⌨️  Keys: +/- faster/slower, r refresh
+    // ═══════════════════════════════════════════════════════════════════════
+
+    private static boolean keysActive = false;
+
+    /** Run stty on the controlling terminal; false if there isn't one */
+    private static boolean stty(String args) {
+        try {
+            Process p = new ProcessBuilder("sh", "-c", "stty " + args + " < /dev/tty")
+                .redirectErrorStream(true)
+                .redirectOutput(ProcessBuilder.Redirect.DISCARD)
+                .start();
+            return p.waitFor() == 0;
+        } catch (IOException e) {
+            return false;
+        } catch (InterruptedException e) {
+            Thread.currentThread().interrupt();
+            return false;
+        }
+    }
+
+    /** Keys arrive without Enter and aren't echoed until keysEnd() */
+    private static void keysBegin() {
+        keysActive = System.console() != null && stty("-icanon -echo min 1");
+    }
+
+    private static void keysEnd() {
+        if (keysActive) stty("icanon echo");
+        keysActive = false;
+    }
+
+    /** Apply pending keys; returns the interval the daemon was asked for */
+    private static int handleKeys(VenomShell shell, int intervalMs) throws IOException {
+        while (keysActive && System.in.available() > 0) {
+            switch (System.in.read()) {
+                case '+', '=' -> {
+                    intervalMs = Math.max(10, intervalMs / 2);
+                    shell.setInterval(intervalMs);
+                }
+                case '-' -> {
+                    intervalMs = Math.min(10000, intervalMs * 2);
+                    shell.setInterval(intervalMs);
+                }
+                case 'r' -> shell.refresh();
+                default -> { }
+            }
+        }
+        return intervalMs;
+    }
+
+    /** --ping COUNT from the command line, or 0 */
+    private static int pingArg(String[] args) {
+        for (int i = 0; i + 1 < args.length; i++) {
+            if (args[i].equals("--ping")) {
+                try {
+                    return Integer.parseInt(args[i + 1]);
+                } catch (NumberFormatException e) {
+                    return 0;
+                }
+            }
+        }
+        return 0;
+    }
+
+    /** --channel-suffix SUFFIX from the command line: the daemon instance to connect to */
+    private static String channelArg(String[] args) {
+        for (int i = 0; i + 1 < args.length; i++) {
+            if (args[i].equals("--channel-suffix")) return State.instanceChannel(args[i + 1]);
+        }
+        return State.CHANNEL_NAME;
+    }
+
+    /** 🏓 Time count round trips through the daemon; returns the exit code (1 if none came back) */
+    private static int runPing(VenomShell shell, int count) throws InterruptedException {
+        // A pong for an earlier client with the same ID may still be published
+        int seq = (int) System.nanoTime();
+        int replies = 0;
+        double rttMin = Double.MAX_VALUE;
+        double rttMax = 0;
+        double rttSum = 0;
+        for (int i = 1; i <= count; i++) {
+            double rttUs = shell.ping(++seq);
+            if (rttUs < 0) {
+                System.out.println("⚠️  No reply to ping #" + i);
+                continue;
+            }
+            System.out.printf("🏓 Pong #%d: %.1f µs%n", i, rttUs);
+            rttMin = Math.min(rttMin, rttUs);
+            rttMax = Math.max(rttMax, rttUs);
+            rttSum += rttUs;
+            replies++;
+            Thread.sleep(100);
+        }
+        if (replies == 0) return 1;
+        System.out.printf("📊 Round trip: %d/%d replies, min %.1f µs, avg %.1f µs, max %.1f µs%n",
+            replies, count, rttMin, rttSum / replies, rttMax);
+        return 0;
+    }
+"#;
+
+fn main_java(config: &ProjectConfig) -> String {
+    if !config.schema.has_demo_fields() {
+        return generic_main_java(config);
+    }
+    format!(r#"/*
+ * {name} - VenomMemory Client Example - with Benchmarking
+ *
+ * Demonstrates connecting to daemon and reading system stats.
+ * Includes read latency measurements.
+ */
+
+import java.io.IOException;
+
+public final class Main {{
+    // ANSI colors
+    private static final String CYAN = "\u001B[96m";
+    private static final String RESET = "\u001B[0m";
+
+    // Latency tracking
+    private static double latencyMin = Double.MAX_VALUE;
+    private static double latencyMax = 0;
+    private static double latencySum = 0;
+    private static long latencyCount = 0;
+{keys}
+    public static void main(String[] args) throws InterruptedException, IOException {{
+        System.out.println("🖥️  {name} Client (Java)");
+        System.out.println("═══════════════════════════════════════════════════════════════");
+
+        VenomShell shell;
+        try {{
+            shell = new VenomShell(channelArg(args));
+        }} catch (IllegalStateException | UnsatisfiedLinkError e) {{
+            System.out.println("❌ Error: " + e.getMessage());
+            System.out.println("\nMake sure:");
+            System.out.println("  1. The daemon is running");
+            System.out.println("  2. native/libvenom_memory.so exists (start with ./run.sh)");
+            System.exit(1);
+            return;
+        }}
+
+        System.out.println("✅ Connected! Client ID: " + shell.clientId() + " (libvenom_memory " + VenomShell.libraryVersion() + ")");
+        if (pingArg(args) > 0) {{
+            int status = runPing(shell, pingArg(args));
+            shell.close();
+            System.exit(status);
+        }}
+        System.out.println("📊 Reading system stats... (Ctrl+C to exit)\n");
+
+        // Print final stats on Ctrl+C
+        Runtime.getRuntime().addShutdownHook(new Thread(() -> {{
+            keysEnd();
+            System.out.println("\n");
+            System.out.println("📊 " + CYAN + "Final Latency Stats (Java):" + RESET);
+            System.out.println("   Samples: " + latencyCount);
+            System.out.printf("   Min: %.2f µs%n", latencyMin);
+            System.out.printf("   Max: %.2f µs%n", latencyMax);
+            System.out.printf("   Avg: %.2f µs%n", latencySum / latencyCount);
+            System.out.println("\n👋 Goodbye!");
+        }}));
+
+        keysBegin();
+        int frame = 0;
+        int intervalMs = VenomShell.DEFAULT_INTERVAL_MS;
+        while (true) {{
+            intervalMs = handleKeys(shell, intervalMs);
+
+            // ═══════════════════════════════════════════════════════════════
+            // 📊 BENCHMARK: Measure read latency
+            // ═══════════════════════════════════════════════════════════════
+            long start = System.nanoTime();
+            State state = shell.readState();
+            double latencyUs = (System.nanoTime() - start) / 1000.0;
+
+            // Update stats
+            latencyMin = Math.min(latencyMin, latencyUs);
+            latencyMax = Math.max(latencyMax, latencyUs);
+            latencySum += latencyUs;
+            latencyCount++;
+            double avgUs = latencySum / latencyCount;
+
+            if (state.isValid()) {{
+                // Clear screen and move cursor to top
+                System.out.print("\u001B[2J\u001B[H");
+
+                System.out.println("╔═══════════════════════════════════════════════════════════════╗");
+                System.out.printf("║  🖥️  {name} Monitor (Java)    Frame: %-6d            ║%n", frame);
+                System.out.println("╠═══════════════════════════════════════════════════════════════╣");
+                System.out.printf("║  CPU: %5.1f%%  |  RAM: %d/%d MB  |  Uptime: %s  ║%n",
+                    state.cpuUsagePercent(), state.memoryUsedMb(), state.memoryTotalMb(), state.uptimeFormatted());
+                System.out.println("╠═══════════════════════════════════════════════════════════════╣");
+
+                // Show per-core usage (all cores)
+                for (int i = 0; i < Math.min(state.coreCount(), State.MAX_CORES); i++) {{
+                    System.out.printf("║  Core %d: %5.1f%%                                                ║%n",
+                        i, state.cpuCores()[i]);
+                }}
+
+                System.out.println("╠═══════════════════════════════════════════════════════════════╣");
+                System.out.printf("║  Memory: %.1f%% used                                           ║%n",
+                    state.memoryUsagePercent());
+                System.out.println("╠═══════════════════════════════════════════════════════════════╣");
+                System.out.printf("║  📊 %sRead Latency:%s %.2f µs (min: %.2f, max: %.2f, avg: %.2f)  ║%n",
+                    CYAN, RESET, latencyUs, latencyMin, latencyMax, avgUs);
+                System.out.printf("║  📡 %sDaemon→client:%s %.2f µs since the write                 ║%n",
+                    CYAN, RESET, shell.writeAgeUs());
+                System.out.println("╚═══════════════════════════════════════════════════════════════╝");
+                System.out.println("  Updates: " + state.updateCounter() + " | Interval: " + intervalMs + " ms");
+                System.out.println("  +/- faster/slower | r refresh | Ctrl+C to exit");
+                frame++;
+            }} else {{
+                System.out.println("⏳ Waiting for valid data from daemon...");
+            }}
+
+            Thread.sleep(100);
+        }}
+    }}
+}}
+"#, name = config.name, keys = COMMAND_KEYS)
+}
+
+/// Client for a custom schema: prints every field of the state
+fn generic_main_java(config: &ProjectConfig) -> String {
+    let fields: String = config.schema.user_fields()
+        .map(|f| {
+            let value = match f.len {
+                Some(_) => format!("Arrays.toString(state.{}())", camel_case(&f.name)),
+                None => format!("state.{}()", camel_case(&f.name)),
+            };
+            format!("                System.out.println(\"  {:<24} \" + {});\n", f.name, value)
+        })
+        .collect();
+    
+    format!(r#"/*
+ * {name} - VenomMemory Client - prints every field of the state (see venom.toml)
+ */
+
+import java.io.IOException;
+import java.util.Arrays;
+
+public final class Main {{{keys}
+    public static void main(String[] args) throws InterruptedException, IOException {{
+        VenomShell shell;
+        try {{
+            shell = new VenomShell(channelArg(args));
+        }} catch (IllegalStateException | UnsatisfiedLinkError e) {{
+            System.out.println("❌ Error: " + e.getMessage());
+            System.exit(1);
+            return;
+        }}
+
+        System.out.println("✅ Connected! Client ID: " + shell.clientId() + " (libvenom_memory " + VenomShell.libraryVersion() + ")");
+        if (pingArg(args) > 0) {{
+            int status = runPing(shell, pingArg(args));
+            shell.close();
+            System.exit(status);
+        }}
+
+        keysBegin();
+        Runtime.getRuntime().addShutdownHook(new Thread(Main::keysEnd));
+
+        int intervalMs = VenomShell.DEFAULT_INTERVAL_MS;
+        double latencyMin = Double.MAX_VALUE;
+        double latencyMax = 0;
+        double latencySum = 0;
+        long latencyCount = 0;
+        while (true) {{
+            intervalMs = handleKeys(shell, intervalMs);
+
+            long start = System.nanoTime();
+            State state = shell.readState();
+            double latencyUs = (System.nanoTime() - start) / 1000.0;
+
+            latencyMin = Math.min(latencyMin, latencyUs);
+            latencyMax = Math.max(latencyMax, latencyUs);
+            latencySum += latencyUs;
+            latencyCount++;
+
+            if (state.isValid()) {{
+                System.out.print("\u001B[2J\u001B[H");
+                System.out.println("🖥️  {name} | State v" + state.version() + " | " + State.SIZE + " bytes");
+                System.out.println("═══════════════════════════════════════════════════════════════");
+{fields}                System.out.println("═══════════════════════════════════════════════════════════════");
+                System.out.printf("📊 Read Latency: %.2f µs (min: %.2f, max: %.2f, avg: %.2f)%n",
+                    latencyUs, latencyMin, latencyMax, latencySum / latencyCount);
+                System.out.printf("📡 Daemon→client: %.2f µs since the write%n", shell.writeAgeUs());
+                System.out.println("⏱️  Daemon interval: " + intervalMs + " ms | +/- faster/slower | r refresh");
+            }} else {{
+                System.out.println("⏳ Waiting for valid data from daemon...");
+            }}
+
+            Thread.sleep(100);
+        }}
+    }}
+}}
+"#, name = config.name, fields = fields, keys = COMMAND_KEYS)
+}
+
+fn run_sh(config: &ProjectConfig) -> String {
+    // With several bundled targets, use native/<triple>/ for this machine
+    let pick_native = if config.targets.is_empty() {
+        String::new()
+    } else {
+        format!(r#"# Bundled libraries: native/<triple>/ for {targets}
+ARCH=$(uname -m)
+case "$ARCH" in arm64) ARCH=aarch64 ;; esac
+OS=$(uname -s | tr '[:upper:]' '[:lower:]')
+NATIVE=$(ls -d native/"$ARCH"-*-"$OS"* 2>/dev/null | head -n 1)
+if [ -z "$NATIVE" ]; then
+    echo "No bundled library for $ARCH-$OS in native/ (bundled: {targets})" >&2
+    exit 1
+fi
+
+"#, targets = config.targets.join(" "))
+    };
+    format!(r#"#!/bin/sh
+# {name} - compile and run the Java client
+#
+# Requires JDK 21+. java.lang.foreign is final in JDK 22; on JDK 21 it is a
+# preview API, so preview features are enabled automatically.
+set -e
+cd "$(dirname "$0")"
+
+JAVA_MAJOR=$(java -XshowSettings:properties -version 2>&1 \
+    | sed -n 's/^ *java.specification.version = //p')
+
+JAVAC_FLAGS=""
+JAVA_FLAGS="--enable-native-access=ALL-UNNAMED"
+if [ "$JAVA_MAJOR" = "21" ]; then
+    JAVAC_FLAGS="--release 21 --enable-preview"
+    JAVA_FLAGS="$JAVA_FLAGS --enable-preview"
+fi
+
+{pick_native}mkdir -p build
+javac -encoding UTF-8 $JAVAC_FLAGS -d build src/*.java
+exec java $JAVA_FLAGS -Djava.library.path={native} -cp build Main "$@"
+"#,
+        name = config.name,
+        pick_native = pick_native,
+        native = if config.targets.is_empty() { "native" } else { "\"$NATIVE\"" },
+    )
+}
+
+fn readme(config: &ProjectConfig) -> String {
+    format!(r#"# {name} (Java + C Daemon)
+
+VenomMemory Java client using the java.lang.foreign (Panama) FFI - no JNI glue.
+
+## Project Structure
+
+```
+{name}/
+├── src/
+│   ├── VenomShell.java      # Downcall handles for the shell API
+│   ├── State.java           # Little-endian decoder for the packed state
+│   └── Main.java            # Example client
+├── native/
+│   └── libvenom_memory.so   # Bundled VenomMemory library
+├── daemon/                  # C daemon (system monitor)
+│   ├── src/main.c
+│   └── Makefile
+└── run.sh
+```
+
+## Quick Start
+
+```bash
+# Terminal 1 - Start C daemon
+cd daemon && make run
+
+# Terminal 2 - Compile and run the Java client (JDK 21+)
+./run.sh
+```
+
+`run.sh` is equivalent to:
+
+```bash
+javac -encoding UTF-8 -d build src/*.java
+java --enable-native-access=ALL-UNNAMED -Djava.library.path=native -cp build Main
+```
+
+(plus `--enable-preview` on JDK 21, where the FFM API is still a preview).
+
+## Usage in Your Code
+
+```java
+try (VenomShell shell = new VenomShell()) {{
+    System.out.println("Connected! ID: " + shell.clientId());
+
+    State state = shell.readState();
+    if (state.isValid()) {{
+        System.out.printf("CPU: %.1f%%%n", state.cpuUsage());
+        System.out.println("RAM: " + state.memoryUsedMb() + "/" + state.memoryTotalMb() + " MB");
+        System.out.println("Uptime: " + state.uptimeFormatted());
+    }}
+}}
+```
+
+## Configuration
+
+| Setting | Value |
+|---------|-------|
+| Channel | `{channel}` |
+| Magic | `0x{magic:08X}` |
+{ping}{instances}{service}"#,
+        name = config.name,
+        ping = super::ping_readme_section("./run.sh --ping 10"),
+        instances = super::instances_readme_section(&config.channel, "cd daemon && make run ARGS=\"--channel-suffix SUFFIX\"", "./run.sh --channel-suffix SUFFIX", "`State.instanceChannel()`"),
+        service = super::service::readme_section(config),
+        channel = config.channel,
+        magic = config.magic
+    )
+}
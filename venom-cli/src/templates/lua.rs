@@ -0,0 +1,956 @@
+//! Lua (LuaJIT FFI) Templates for VenomMemory projects
+//!
+//! Generates a complete LuaJIT project with:
+//! - C daemon (for system monitoring)
+//! - venom.lua - ffi.cdef of the shell API + library discovery
+//! - state.lua - ffi.cdef of the packed state (zero-parse field access)
+//! - client.lua - Status bar client with read latency benchmarking
+//! - Bundled libvenom_memory.so
+
+use super::ProjectConfig;
+
+pub fn generate(config: &ProjectConfig) {
+    let base = &config.output_dir;
+    
+    // Create directories
+    crate::create_dir(&format!("{}/lib", base));
+    crate::create_dir(&format!("{}/daemon/src", base));
+    crate::create_dir(&format!("{}/shared", base));
+    
+    // Shared protocol (C header)
+    crate::write_file(&format!("{}/shared/protocol.h", base), &protocol_h(config));
+    
+    // C Daemon
+    crate::write_file(&format!("{}/daemon/src/main.c", base), &daemon_main(config));
+    crate::write_file(&format!("{}/daemon/Makefile", base), &daemon_makefile(config));
+    
+    // Lua client
+    generate_client(config);
+    
+    // README
+    crate::write_file(&format!("{}/README.md", base), &readme(config));
+}
+
+/// Client files only (used by `venom add-client`)
+pub fn generate_client(config: &ProjectConfig) {
+    let base = &config.output_dir;
+    
+    crate::write_file(&format!("{}/venom.lua", base), &venom_lua(config));
+    crate::write_file(&format!("{}/state.lua", base), &state_lua(config));
+    crate::write_file(&format!("{}/client.lua", base), &client_lua(config));
+}
+
+
+
+// ═══════════════════════════════════════════════════════════════════════════
+// C Protocol Header (shared between daemon and Lua client)
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn protocol_h(config: &ProjectConfig) -> String {
+    let upper = config.names().upper;
+    let pascal = config.names().pascal;
+    
+    format!(r#"#ifndef {upper}_PROTOCOL_H
+#define {upper}_PROTOCOL_H
+
+#include <stdint.h>
+#include <stdbool.h>
+
+#define {upper}_CHANNEL_NAME "{channel}"
+#define {upper}_MAGIC 0x{magic:08X}
+#define {upper}_DATA_SIZE {data_size}
+#define {upper}_CMD_SLOTS {cmd_slots}
+#define {upper}_MAX_CLIENTS {max_clients}
+#define {upper}_STATE_SIZE {state_size}
+{max_cores}
+typedef struct __attribute__((packed)) {{
+{fields}}} {pascal}State;
+
+_Static_assert(sizeof({pascal}State) == {upper}_STATE_SIZE, "{pascal}State size mismatch");
+
+{commands}
+#endif // {upper}_PROTOCOL_H
+"#,
+        upper = upper,
+        pascal = pascal,
+        channel = config.channel,
+        magic = config.magic,
+        data_size = config.data_size,
+        cmd_slots = config.cmd_slots,
+        max_clients = config.max_clients,
+        state_size = config.schema.size,
+        max_cores = super::c::max_cores_define(config),
+        fields = config.schema.c_fields("    "),
+        commands = super::c::commands_h(config),
+    )
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// C Daemon
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn daemon_main(config: &ProjectConfig) -> String {
+    if !config.schema.has_demo_fields() {
+        return super::c::generic_daemon_c(config, "../shared/protocol.h");
+    }
+    let upper = config.names().upper;
+    let pascal = config.names().pascal;
+    
+    format!(r#"/**
+ * {name} System Monitor Daemon
+ * Reads CPU/RAM/Uptime from /proc and publishes to shared memory.
+ * Lua client connects to this daemon.
+ */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <signal.h>
+#include <unistd.h>
+#include <time.h>
+#include "../shared/protocol.h"
+
+typedef struct VenomDaemonHandle VenomDaemonHandle;
+typedef struct {{ size_t struct_size; size_t data_size; size_t cmd_slots; size_t max_clients; size_t cmd_payload_size; size_t cmd_full_policy; size_t shm_mode; }} VenomConfigEx;
+extern VenomDaemonHandle* venom_daemon_create_ex(const char* name, const VenomConfigEx* config);
+extern void venom_daemon_destroy(VenomDaemonHandle* handle);
+extern void venom_daemon_write_data(VenomDaemonHandle* handle, const uint8_t* data, size_t len);
+extern size_t venom_daemon_try_recv_command(VenomDaemonHandle* handle, uint8_t* buf, size_t max_len, uint32_t* out_client_id);
+
+static VenomDaemonHandle* g_daemon = NULL;
+static {pascal}State g_state = {{0}};
+static volatile int g_running = 1;
+static uint64_t prev_total[{upper}_MAX_CORES + 1] = {{0}};
+static uint64_t prev_idle[{upper}_MAX_CORES + 1] = {{0}};
+
+static void signal_handler(int sig) {{ (void)sig; g_running = 0; }}
+
+{commands}
+{channel_arg}
+static void read_cpu_stats(void) {{
+    FILE* f = fopen("/proc/stat", "r");
+    if (!f) return;
+    char line[256];
+    int core_idx = 0;
+    while (fgets(line, sizeof(line), f) && core_idx <= {upper}_MAX_CORES) {{
+        if (strncmp(line, "cpu", 3) != 0) continue;
+        uint64_t user, nice, system, idle, iowait, irq, softirq;
+        if (sscanf(line + (line[3] == ' ' ? 4 : 5), "%lu %lu %lu %lu %lu %lu %lu",
+                   &user, &nice, &system, &idle, &iowait, &irq, &softirq) != 7) continue;
+        uint64_t total = user + nice + system + idle + iowait + irq + softirq;
+        uint64_t idle_time = idle + iowait;
+        uint64_t total_delta = total - prev_total[core_idx];
+        uint64_t idle_delta = idle_time - prev_idle[core_idx];
+        float usage = total_delta > 0 ? (1.0f - (float)idle_delta / (float)total_delta) * 100.0f : 0;
+        if (line[3] == ' ') g_state.cpu_usage_percent = usage;
+        else if (core_idx - 1 >= 0 && core_idx - 1 < {upper}_MAX_CORES) g_state.cpu_cores[core_idx - 1] = usage;
+        prev_total[core_idx] = total;
+        prev_idle[core_idx] = idle_time;
+        core_idx++;
+    }}
+    g_state.core_count = core_idx > 1 ? core_idx - 1 : 0;
+    fclose(f);
+}}
+
+static void read_memory_stats(void) {{
+    FILE* f = fopen("/proc/meminfo", "r");
+    if (!f) return;
+    char line[256];
+    uint64_t total_kb = 0, available_kb = 0;
+    while (fgets(line, sizeof(line), f)) {{
+        if (strncmp(line, "MemTotal:", 9) == 0) sscanf(line + 9, "%lu", &total_kb);
+        else if (strncmp(line, "MemAvailable:", 13) == 0) sscanf(line + 13, "%lu", &available_kb);
+    }}
+    g_state.memory_total_mb = (uint32_t)(total_kb / 1024);
+    g_state.memory_used_mb = (uint32_t)((total_kb - available_kb) / 1024);
+    fclose(f);
+}}
+
+static void read_uptime(void) {{
+    FILE* f = fopen("/proc/uptime", "r");
+    if (!f) return;
+    double uptime;
+    if (fscanf(f, "%lf", &uptime) == 1) g_state.uptime_seconds = (uint64_t)uptime;
+    fclose(f);
+}}
+
+int main(int argc, char** argv) {{
+    printf("🖥️  {name} System Monitor Daemon\n");
+    printf("═══════════════════════════════════════════════════════════════\n");
+    signal(SIGINT, signal_handler);
+    signal(SIGTERM, signal_handler);
+    
+    VenomConfigEx config = {{ .struct_size = sizeof(VenomConfigEx), .data_size = {upper}_DATA_SIZE, .cmd_slots = {upper}_CMD_SLOTS, .max_clients = {upper}_MAX_CLIENTS, .cmd_payload_size = sizeof({pascal}Command), .shm_mode = 0{shm_mode:o} }};
+    const char* channel = channel_from_args(argc, argv);
+    g_daemon = venom_daemon_create_ex(channel, &config);
+    if (!g_daemon) {{ printf("❌ Failed to create channel\n"); return 1; }}
+    
+    g_state.magic = {upper}_MAGIC;
+    g_state.version = 1;
+    
+    printf("✅ Channel: %s\n", channel);
+    printf("🌙 Lua client can connect now!\n");
+    printf("🚀 Publishing... (Ctrl+C to stop)\n\n");
+    
+    uint64_t next_publish = 0;
+    while (g_running) {{
+        int refresh = handle_commands(g_daemon);
+        if (!refresh && now_ms() < next_publish) {{ usleep(10000); continue; }}
+        next_publish = now_ms() + g_interval_ms;
+        
+        read_cpu_stats();
+        read_memory_stats();
+        read_uptime();
+        g_state.update_counter++;
+        struct timespec ts;
+        clock_gettime(CLOCK_MONOTONIC, &ts);
+        g_state.timestamp_ns = (uint64_t)ts.tv_sec * 1000000000ULL + ts.tv_nsec;
+        publish(g_daemon, &g_state);
+        
+        printf("\r🖥️  CPU: %5.1f%% | RAM: %u/%u MB | Uptime: %luh%lum | #%lu | %u ms   ",
+            g_state.cpu_usage_percent, g_state.memory_used_mb, g_state.memory_total_mb,
+            (unsigned long)(g_state.uptime_seconds / 3600), (unsigned long)((g_state.uptime_seconds % 3600) / 60),
+            (unsigned long)g_state.update_counter, g_interval_ms);
+        fflush(stdout);
+    }}
+    venom_daemon_destroy(g_daemon);
+    printf("\n\n👋 Goodbye!\n");
+    return 0;
+}}
+"#, name = config.name, upper = upper, pascal = pascal, shm_mode = config.shm_mode, commands = super::c::command_handler_c(config), channel_arg = super::c::channel_arg_c(config))
+}
+
+fn daemon_makefile(config: &ProjectConfig) -> String {
+    format!(r#"# {name} Daemon Makefile
+
+{target_var}CC = gcc
+CFLAGS = -Wall -Wextra -O2 -I../shared
+LDFLAGS = -L{lib} -lvenom_memory -Wl,-rpath,'$$ORIGIN/{lib}'
+
+TARGET = {name}_daemon
+SOURCES = src/main.c
+
+.PHONY: all clean run
+
+all: $(TARGET)
+
+$(TARGET): $(SOURCES)
+	@echo "🔗 Building $(TARGET)..."
+	@$(CC) $(CFLAGS) $(SOURCES) -o $(TARGET) $(LDFLAGS)
+	@echo "✅ Build complete"
+
+clean:
+	@rm -f $(TARGET)
+
+run: $(TARGET)
+	@./$(TARGET) $(ARGS)
+"#,
+        name = config.name,
+        target_var = super::make_target_var(config),
+        lib = super::make_lib_dir(config, "../lib"),
+    )
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Lua Bindings
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn venom_lua(config: &ProjectConfig) -> String {
+    // With several bundled targets each lib/<triple>/ copy is tried, and the
+    // first one that loads was built for this machine
+    let lib = config.at_root("lib");
+    let (locations, probe, library_location) = if config.targets.is_empty() {
+        (
+            format!(r#"    -- Next to this script
+    dir .. "{lib}/libvenom_memory.so",
+    dir .. "native/libvenom_memory.so",
+    -- Relative to cwd
+    "lib/libvenom_memory.so",
+    "../lib/libvenom_memory.so",
+    -- Absolute fallback
+    "/usr/local/lib/libvenom_memory.so",
+    "/usr/lib/libvenom_memory.so",
+"#, lib = lib),
+            "      return path\n",
+            "lib/libvenom_memory.so".to_string(),
+        )
+    } else {
+        let mut locations = String::new();
+        for t in &config.targets {
+            let file = crate::library::library_name_for(t);
+            locations.push_str(&format!(
+                "    dir .. \"{lib}/{t}/{file}\",\n    \"lib/{t}/{file}\",\n    \"../lib/{t}/{file}\",\n",
+                lib = lib,
+                t = t,
+                file = file
+            ));
+        }
+        (
+            locations,
+            "      -- A library built for another target fails to load\n      \
+             if pcall(ffi.load, path) then\n        return path\n      end\n",
+            "lib/<triple>/ for this machine".to_string(),
+        )
+    };
+
+    format!(r#"--[[
+  VenomMemory LuaJIT FFI Bindings for {name}
+
+  Provides:
+  - venom.connect(): Connection to daemon (VenomShell)
+  - venom.library_version(): Version of the loaded libvenom_memory
+
+  Library location: {library_location}
+]]
+
+local ffi = require("ffi")
+local bit = require("bit")
+local state = require("state")
+
+ffi.cdef[[
+typedef struct VenomShellHandle VenomShellHandle;
+VenomShellHandle* venom_shell_connect(const char* name);
+void venom_shell_destroy(VenomShellHandle* handle);
+size_t venom_shell_read_data_stamped(VenomShellHandle* handle, uint8_t* buf, size_t max_len, uint64_t* out_timestamp_ns);
+uint64_t venom_clock_ns(void);
+const char* venom_version(void);
+uint32_t venom_shell_id(VenomShellHandle* handle);
+bool venom_shell_send_command(VenomShellHandle* handle, const char* cmd, size_t len);
+]]
+
+-- ═══════════════════════════════════════════════════════════════════════════
+-- Library loading
+-- ═══════════════════════════════════════════════════════════════════════════
+
+-- Directory this file lives in (so the project works from any cwd)
+local function script_dir()
+  local source = debug.getinfo(1, "S").source
+  return source:match("^@(.*/)") or "./"
+end
+
+--- Finds the native library in various possible locations
+local function find_library()
+  local dir = script_dir()
+  local locations = {{
+{locations}  }}
+
+  for _, path in ipairs(locations) do
+    local f = io.open(path, "rb")
+    if f then
+      f:close()
+{probe}    end
+  end
+
+  error("Could not find libvenom_memory.so. Searched in:\n  " ..
+        table.concat(locations, "\n  ") ..
+        "\n\nMake sure {library_location} exists in your project.")
+end
+
+local lib = ffi.load(find_library())
+
+-- ═══════════════════════════════════════════════════════════════════════════
+-- VenomShell - Connection to VenomMemory Daemon
+-- ═══════════════════════════════════════════════════════════════════════════
+
+local VenomShell = {{}}
+VenomShell.__index = VenomShell
+
+local M = {{
+  CHANNEL_NAME = state.CHANNEL_NAME,
+
+  -- Commands (mirror CmdType in protocol.h)
+  INTERVAL_MS = 100,      -- Default publish interval
+  CMD_REFRESH = 1,        -- Publish now
+  CMD_SET_INTERVAL = 2,   -- value = publish interval in ms (10-10000)
+  CMD_PING = 3,           -- value = sequence number, echoed back in a pong
+  -- Reply to the last ping, published right after the state: client ID and seq (u32 each)
+  PONG_SIZE = 8,
+}}
+
+--- Encode a Command, a typed command: u16 cmd (the tag), u16 payload length
+--- (4), then the i32 value, all little-endian
+function M.encode_command(cmd, value)
+  local v = value or 0
+  return string.char(bit.band(cmd, 0xFF), bit.rshift(cmd, 8), 4, 0,
+    bit.band(v, 0xFF), bit.band(bit.rshift(v, 8), 0xFF),
+    bit.band(bit.rshift(v, 16), 0xFF), bit.band(bit.rshift(v, 24), 0xFF))
+end
+
+--- Version of the libvenom_memory that was loaded
+function M.library_version()
+  return ffi.string(lib.venom_version())
+end
+
+--- The channel of one of several instances: "CHANNEL_NAME-SUFFIX", or
+--- CHANNEL_NAME itself for no suffix or an empty one
+function M.instance_channel(suffix)
+  if suffix == nil or suffix == "" then return state.CHANNEL_NAME end
+  return state.CHANNEL_NAME .. "-" .. suffix
+end
+
+--- Connect to a channel; returns nil and an error message on failure
+function M.connect(channel)
+  channel = channel or state.CHANNEL_NAME
+  local handle = lib.venom_shell_connect(channel)
+  if handle == nil then
+    return nil, ('Failed to connect to channel "%s". Is the daemon running?'):format(channel)
+  end
+
+  return setmetatable({{
+    -- Released by the GC if close() is never called
+    handle = ffi.gc(handle, lib.venom_shell_destroy),
+    buf = ffi.new("uint8_t[?]", state.SIZE + M.PONG_SIZE),
+    stamp = ffi.new("uint64_t[1]"),
+    -- µs between the daemon's write and the last read (0 if unstamped)
+    write_age_us = 0,
+  }}, VenomShell)
+end
+
+--- Client ID assigned by the daemon
+function VenomShell:client_id()
+  return tonumber(lib.venom_shell_id(self.handle))
+end
+
+--- Read state from daemon; returns a {pascal}State* view or nil
+---
+--- The view aliases an internal buffer and is overwritten by the next call.
+function VenomShell:read_state()
+  local len = tonumber(lib.venom_shell_read_data_stamped(self.handle, self.buf, state.SIZE, self.stamp))
+  if self.stamp[0] ~= 0 then
+    self.write_age_us = tonumber(lib.venom_clock_ns() - self.stamp[0]) / 1000
+  end
+  if len < state.SIZE then return nil end
+  return ffi.cast(state.ptr_t, self.buf)
+end
+
+--- Queue a command for the daemon; false if the queue is full
+function VenomShell:send_command(cmd)
+  return lib.venom_shell_send_command(self.handle, cmd, #cmd)
+end
+
+--- Ask the daemon to publish every `ms` milliseconds
+function VenomShell:set_interval(ms)
+  return self:send_command(M.encode_command(M.CMD_SET_INTERVAL, ms))
+end
+
+--- Ask the daemon to publish right away
+function VenomShell:refresh()
+  return self:send_command(M.encode_command(M.CMD_REFRESH))
+end
+
+--- Round trip of a ping carrying `seq` (u32) in µs, or nil if no pong came back within a second
+---
+--- The daemon answers by publishing at once with a pong after the state. It keeps
+--- only the last one, so clients pinging at the same time can miss their reply.
+function VenomShell:ping(seq)
+  local start = lib.venom_clock_ns()
+  if not self:send_command(M.encode_command(M.CMD_PING, seq)) then return nil end
+  local size = state.SIZE + M.PONG_SIZE
+  local pong = ffi.cast("const uint32_t*", self.buf + state.SIZE)
+  local id = self:client_id()
+  while lib.venom_clock_ns() - start < 1000000000ULL do
+    local len = lib.venom_shell_read_data_stamped(self.handle, self.buf, size, self.stamp)
+    if len >= size and pong[0] == id and pong[1] == seq then
+      return tonumber(lib.venom_clock_ns() - start) / 1000
+    end
+  end
+  return nil
+end
+
+--- Clean up resources
+function VenomShell:close()
+  if self.handle ~= nil then
+    lib.venom_shell_destroy(ffi.gc(self.handle, nil))
+    self.handle = nil
+  end
+end
+
+return M
+"#,
+        name = config.name,
+        pascal = config.names().pascal,
+        locations = locations,
+        probe = probe,
+        library_location = library_location,
+    )
+}
+
+fn state_lua(config: &ProjectConfig) -> String {
+    let upper = config.names().upper;
+    let pascal = config.names().pascal;
+    let schema = &config.schema;
+    
+    let max_cores = match schema.field("cpu_cores").and_then(|f| f.len) {
+        Some(n) if schema.has_demo_fields() => format!("  MAX_CORES = {},\n", n),
+        _ => String::new(),
+    };
+    
+    format!(r#"--[[
+  {name} state - mirrors the packed C struct from shared/protocol.h
+
+  Fields are read straight out of the buffer through a {pascal}State*,
+  so there is no parsing step.
+]]
+
+local ffi = require("ffi")
+
+ffi.cdef[[
+typedef struct __attribute__((packed)) {{
+{fields}}} {pascal}State;
+]]
+
+local M = {{
+  -- Configuration (from {upper}_CHANNEL_NAME / {upper}_MAGIC)
+  CHANNEL_NAME = "{channel}",
+  MAGIC = 0x{magic:08X},
+{max_cores}  SIZE = {state_size},
+}}
+
+M.ctype = ffi.typeof("{pascal}State")
+M.ptr_t = ffi.typeof("const {pascal}State*")
+assert(ffi.sizeof(M.ctype) == M.SIZE, "{pascal}State layout doesn't match protocol.h")
+
+function M.is_valid(s)
+  return s ~= nil and s.magic == M.MAGIC
+end
+{helpers}
+return M
+"#,
+        name = config.name,
+        upper = upper,
+        pascal = pascal,
+        channel = config.channel,
+        magic = config.magic,
+        fields = schema.c_fields("    "),
+        max_cores = max_cores,
+        state_size = schema.size,
+        helpers = if schema.has_demo_fields() { DEMO_HELPERS } else { "" }
+    )
+}
+
+/// Helpers used by the system-monitor demo
+const DEMO_HELPERS: &str = r#"
+function M.memory_usage_percent(s)
+  return s.memory_total_mb > 0 and s.memory_used_mb / s.memory_total_mb * 100 or 0
+end
+
+function M.uptime_formatted(s)
+  local up = tonumber(s.uptime_seconds)
+  return ("%dh %dm"):format(math.floor(up / 3600), math.floor(up % 3600 / 60))
+end
+"#;
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Lua Client
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Raw-mode keyboard handling shared by both clients: +/- change the
+/// daemon's publish interval and r asks for an immediate publish; also
+/// `--ping` and `--channel-suffix`
+const COMMAND_KEYS: &str = r#"-- ═══════════════════════════════════════════════════════════════════════════
+-- ⌨️  Keys: +/- faster/slower, r refresh
+-- ═══════════════════════════════════════════════════════════════════════════
+
+ffi.cdef[[
+ssize_t read(int fd, void* buf, size_t count);
+int isatty(int fd);
+]]
+
+local key_buf = ffi.new("char[1]")
+local saved_tty = nil
+
+-- Keys arrive without Enter and aren't echoed; read() never blocks
+local function keys_begin()
+  if ffi.C.isatty(0) == 0 then return end
+  local p = io.popen("stty -g")
+  saved_tty = p:read("*l")
+  p:close()
+  os.execute("stty -icanon -echo min 0 time 0")
+end
+
+local function keys_end()
+  if saved_tty then os.execute("stty " .. saved_tty) end
+  saved_tty = nil
+end
+
+-- Apply pending keys; returns the interval the daemon was asked for
+local function handle_keys(shell, interval_ms)
+  while saved_tty and ffi.C.read(0, key_buf, 1) == 1 do
+    local key = string.char(key_buf[0])
+    if key == "+" or key == "=" then
+      interval_ms = math.max(10, math.floor(interval_ms / 2))
+      shell:set_interval(interval_ms)
+    elseif key == "-" then
+      interval_ms = math.min(10000, interval_ms * 2)
+      shell:set_interval(interval_ms)
+    elseif key == "r" then
+      shell:refresh()
+    end
+  end
+  return interval_ms
+end
+
+-- --ping COUNT from the command line, or nil
+local function ping_arg()
+  for i = 1, #arg - 1 do
+    if arg[i] == "--ping" then return tonumber(arg[i + 1]) end
+  end
+end
+
+-- --channel-suffix SUFFIX from the command line: the daemon instance to connect to
+local function channel_arg()
+  for i = 1, #arg - 1 do
+    if arg[i] == "--channel-suffix" then return venom.instance_channel(arg[i + 1]) end
+  end
+  return venom.CHANNEL_NAME
+end
+
+-- Time `count` round trips through the daemon; false if none came back
+local function run_ping(shell, count)
+  -- A pong for an earlier client with the same ID may still be published
+  local seq = now_ns() % 0x100000000
+  local replies, rtt_min, rtt_max, rtt_sum = 0, math.huge, 0, 0
+  for i = 1, count do
+    seq = (seq + 1) % 0x100000000
+    local rtt_us = shell:ping(seq)
+    if rtt_us then
+      print(("🏓 Pong #%d: %.1f µs"):format(i, rtt_us))
+      rtt_min = math.min(rtt_min, rtt_us)
+      rtt_max = math.max(rtt_max, rtt_us)
+      rtt_sum = rtt_sum + rtt_us
+      replies = replies + 1
+      ffi.C.usleep(100000)
+    else
+      print(("⚠️  No reply to ping #%d"):format(i))
+    end
+  end
+  if replies == 0 then return false end
+  print(("📊 Round trip: %d/%d replies, min %.1f µs, avg %.1f µs, max %.1f µs"):format(
+    replies, count, rtt_min, rtt_sum / replies, rtt_max))
+  return true
+end
+"#;
+
+fn client_lua(config: &ProjectConfig) -> String {
+    if !config.schema.has_demo_fields() {
+        return generic_client_lua(config);
+    }
+    format!(r#"#!/usr/bin/env luajit
+--[[
+  {name} Status Bar - VenomMemory LuaJIT Client
+  Displays live CPU/RAM/Uptime stats with colored progress bars.
+  Includes read latency benchmarking.
+]]
+
+local ffi = require("ffi")
+local venom = require("venom")
+local state = require("state")
+
+ffi.cdef[[
+typedef struct {{ long tv_sec; long tv_nsec; }} venom_timespec;
+int clock_gettime(int clk_id, venom_timespec* tp);
+int usleep(unsigned int usec);
+]]
+
+local CLOCK_MONOTONIC = 1
+local ts = ffi.new("venom_timespec")
+
+local function now_ns()
+  ffi.C.clock_gettime(CLOCK_MONOTONIC, ts)
+  return tonumber(ts.tv_sec) * 1e9 + tonumber(ts.tv_nsec)
+end
+
+-- ANSI colors
+local G, Y, R, C, RST = "\27[92m", "\27[93m", "\27[91m", "\27[96m", "\27[0m"
+
+-- Latency tracking
+local latency_min = math.huge
+local latency_max = 0
+local latency_sum = 0
+local latency_count = 0
+
+local function bar(pct, w)
+  w = w or 25
+  local filled = math.floor(pct / 100 * w)
+  local c = pct > 80 and R or pct > 50 and Y or G
+  local out = {{}}
+  for i = 1, w do
+    out[i] = i <= filled and (c .. "█" .. RST) or " "
+  end
+  return "[" .. table.concat(out) .. "]"
+end
+
+{keys}
+local function run(shell)
+  local frame = 0
+  local interval_ms = venom.INTERVAL_MS
+  while true do
+    interval_ms = handle_keys(shell, interval_ms)
+
+    -- ═══════════════════════════════════════════════════════════════════
+    -- 📊 BENCHMARK: Measure read latency
+    -- ═══════════════════════════════════════════════════════════════════
+    local t_start = now_ns()
+    local s = shell:read_state()
+    local latency_us = (now_ns() - t_start) / 1000
+
+    -- Update stats
+    if latency_us < latency_min then latency_min = latency_us end
+    if latency_us > latency_max then latency_max = latency_us end
+    latency_sum = latency_sum + latency_us
+    latency_count = latency_count + 1
+    local avg_us = latency_sum / latency_count
+
+    if state.is_valid(s) then
+      io.write("\27[2J\27[H") -- Clear screen
+      print("╔═══════════════════════════════════════════════════════════════╗")
+      print(("║  🖥️  {name} Monitor (LuaJIT)  Frame: %-6d                   ║"):format(frame))
+      print("╠═══════════════════════════════════════════════════════════════╣")
+      print(("║  CPU: %s %5.1f%%             ║"):format(bar(s.cpu_usage_percent), s.cpu_usage_percent))
+      print("╠═══════════════════════════════════════════════════════════════╣")
+      for i = 0, math.min(s.core_count, state.MAX_CORES) - 1 do
+        print(("║  Core %d: %s %5.1f%%                ║"):format(i, bar(s.cpu_cores[i], 20), s.cpu_cores[i]))
+      end
+      print("╠═══════════════════════════════════════════════════════════════╣")
+      print(("║  RAM: %s %d/%d MB      ║"):format(
+        bar(state.memory_usage_percent(s)), s.memory_used_mb, s.memory_total_mb))
+      print("╠═══════════════════════════════════════════════════════════════╣")
+      print(("║  ⏱️ Uptime: %s                                        ║"):format(state.uptime_formatted(s)))
+      print("╠═══════════════════════════════════════════════════════════════╣")
+      print(("║  📊 %sRead Latency:%s %.2f µs (min: %.2f, max: %.2f, avg: %.2f)  ║"):format(
+        C, RST, latency_us, latency_min, latency_max, avg_us))
+      print(("║  📡 %sDaemon→client:%s %.2f µs since the write                 ║"):format(C, RST, shell.write_age_us))
+      print("╚═══════════════════════════════════════════════════════════════╝")
+      print(("  Cores: %d | Updates: %d | Interval: %d ms"):format(
+        s.core_count, tonumber(s.update_counter), interval_ms))
+      print("  +/- faster/slower | r refresh | Ctrl+C to exit")
+      frame = frame + 1
+    end
+    ffi.C.usleep(100000)
+  end
+end
+
+local function main()
+  print("╔═══════════════════════════════════════════════════════════════╗")
+  print("║   🖥️  {name} Status Bar (LuaJIT)                              ║")
+  print("╚═══════════════════════════════════════════════════════════════╝\n")
+
+  local shell, err = venom.connect(channel_arg())
+  if not shell then
+    print("\n❌ Error: " .. err)
+    print("\nMake sure daemon is running: cd daemon && make run")
+    os.exit(1)
+  end
+
+  print(("✅ Connected! ID: %d (libvenom_memory %s)"):format(shell:client_id(), venom.library_version()))
+  local pings = ping_arg()
+  if pings then
+    local replied = run_ping(shell, pings)
+    shell:close()
+    os.exit(replied and 0 or 1)
+  end
+  print("📊 Reading stats... (Ctrl+C to exit)\n")
+  ffi.C.usleep(1000000)
+
+  -- Ctrl+C surfaces as an "interrupted!" error
+  keys_begin()
+  local ok, run_err = pcall(run, shell)
+  keys_end()
+  shell:close()
+  if not ok and not tostring(run_err):find("interrupted") then
+    print("\n❌ Error: " .. tostring(run_err))
+    os.exit(1)
+  end
+
+  print("\n")
+  print(("📊 %sFinal Latency Stats (LuaJIT):%s"):format(C, RST))
+  print(("   Samples: %d"):format(latency_count))
+  print(("   Min: %.2f µs"):format(latency_min))
+  print(("   Max: %.2f µs"):format(latency_max))
+  print(("   Avg: %.2f µs"):format(latency_sum / latency_count))
+  print("\n👋 Goodbye!")
+end
+
+main()
+"#, name = config.name, keys = COMMAND_KEYS)
+}
+
+/// Client for a custom schema: prints every field of the state
+fn generic_client_lua(config: &ProjectConfig) -> String {
+    let fields: String = config.schema.user_fields()
+        .map(|f| {
+            let value = match f.len {
+                Some(n) => format!("list(s.{}, {})", f.name, n),
+                None => format!("show(s.{})", f.name),
+            };
+            format!("      print((\"  %-24s %s\"):format(\"{}\", {}))\n", f.name, value)
+        })
+        .collect();
+    
+    format!(r#"#!/usr/bin/env luajit
+--[[
+  {name} - VenomMemory LuaJIT Client
+  Prints every field of the state (see venom.toml).
+]]
+
+local ffi = require("ffi")
+local venom = require("venom")
+local state = require("state")
+
+ffi.cdef[[
+typedef struct {{ long tv_sec; long tv_nsec; }} venom_timespec;
+int clock_gettime(int clk_id, venom_timespec* tp);
+int usleep(unsigned int usec);
+]]
+
+local CLOCK_MONOTONIC = 1
+local ts = ffi.new("venom_timespec")
+
+local function now_ns()
+  ffi.C.clock_gettime(CLOCK_MONOTONIC, ts)
+  return tonumber(ts.tv_sec) * 1e9 + tonumber(ts.tv_nsec)
+end
+
+-- 64-bit cdata prints as "123ULL"; drop the suffix
+local function show(v)
+  return (tostring(v):gsub("U?LL$", ""))
+end
+
+local function list(arr, n)
+  local out = {{}}
+  for i = 0, n - 1 do
+    out[#out + 1] = show(arr[i])
+  end
+  return "[" .. table.concat(out, ", ") .. "]"
+end
+
+local latency_min = math.huge
+local latency_max = 0
+local latency_sum = 0
+local latency_count = 0
+
+{keys}
+local function run(shell)
+  local interval_ms = venom.INTERVAL_MS
+  while true do
+    interval_ms = handle_keys(shell, interval_ms)
+
+    local t_start = now_ns()
+    local s = shell:read_state()
+    local latency_us = (now_ns() - t_start) / 1000
+
+    if latency_us < latency_min then latency_min = latency_us end
+    if latency_us > latency_max then latency_max = latency_us end
+    latency_sum = latency_sum + latency_us
+    latency_count = latency_count + 1
+
+    if state.is_valid(s) then
+      io.write("\27[2J\27[H") -- Clear screen
+      print(("🖥️  {name} | State v%d | %d bytes"):format(s.version, state.SIZE))
+      print("═══════════════════════════════════════════════════════════════")
+{fields}      print("═══════════════════════════════════════════════════════════════")
+      print(("📊 Read Latency: %.2f µs (min: %.2f, max: %.2f, avg: %.2f)"):format(
+        latency_us, latency_min, latency_max, latency_sum / latency_count))
+      print(("📡 Daemon→client: %.2f µs since the write"):format(shell.write_age_us))
+      print(("⏱️  Daemon interval: %d ms | +/- faster/slower | r refresh"):format(interval_ms))
+    end
+    ffi.C.usleep(100000)
+  end
+end
+
+local function main()
+  local shell, err = venom.connect(channel_arg())
+  if not shell then
+    print("\n❌ Error: " .. err)
+    os.exit(1)
+  end
+
+  print(("✅ Connected! ID: %d (libvenom_memory %s)"):format(shell:client_id(), venom.library_version()))
+  local pings = ping_arg()
+  if pings then
+    local replied = run_ping(shell, pings)
+    shell:close()
+    os.exit(replied and 0 or 1)
+  end
+
+  -- Ctrl+C surfaces as an "interrupted!" error
+  keys_begin()
+  local ok, run_err = pcall(run, shell)
+  keys_end()
+  shell:close()
+  if not ok and not tostring(run_err):find("interrupted") then
+    print("\n❌ Error: " .. tostring(run_err))
+    os.exit(1)
+  end
+end
+
+main()
+"#, name = config.name, fields = fields, keys = COMMAND_KEYS)
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// README
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn readme(config: &ProjectConfig) -> String {
+    format!(r#"# {name} (LuaJIT + C Daemon)
+
+VenomMemory project with C daemon and LuaJIT client (FFI, no C glue).
+
+## Quick Start
+
+```bash
+# Terminal 1 - Start C daemon
+cd daemon && make run
+
+# Terminal 2 - Start LuaJIT client
+luajit client.lua
+```
+
+## Structure
+
+```
+{name}/
+├── daemon/           # C daemon (system monitor)
+│   ├── src/main.c
+│   └── Makefile
+├── shared/           # Shared protocol
+│   └── protocol.h
+├── venom.lua         # LuaJIT FFI bindings (VenomShell)
+├── state.lua         # ffi.cdef of the packed state
+├── client.lua        # LuaJIT status bar
+└── lib/
+    └── libvenom_memory.so
+```
+
+## Usage in Your Code
+
+```lua
+local venom = require("venom")
+local state = require("state")
+
+local shell = assert(venom.connect())
+local s = shell:read_state()          -- {pascal}State* view, no parsing
+if state.is_valid(s) then
+  print(("CPU: %.1f%%"):format(s.cpu_usage_percent))
+  print(("RAM: %d/%d MB"):format(s.memory_used_mb, s.memory_total_mb))
+end
+shell:close()
+```
+
+## Configuration
+
+| Setting | Value |
+|---------|-------|
+| Channel | `{channel}` |
+| Magic | `0x{magic:08X}` |
+{ping}{instances}{service}"#,
+        name = config.name,
+        ping = super::ping_readme_section("luajit client.lua --ping 10"),
+        instances = super::instances_readme_section(&config.channel, "cd daemon && make run ARGS=\"--channel-suffix SUFFIX\"", "luajit client.lua --channel-suffix SUFFIX", "`venom.instance_channel()`"),
+        service = super::service::readme_section(config),
+        pascal = config.names().pascal,
+        channel = config.channel,
+        magic = config.magic
+    )
+}
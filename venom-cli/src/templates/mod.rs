@@ -8,15 +8,173 @@ pub mod python;
 pub mod go;
 pub mod zig;
 pub mod nim;
+pub mod node;
+pub mod csharp;
+pub mod java;
+pub mod swift;
+pub mod lua;
+pub mod service;
+pub mod extras;
+
+use crate::schema::Schema;
+pub use extras::License;
 
 /// Project configuration passed to all template generators
+#[derive(Clone)]
 pub struct ProjectConfig {
     pub name: String,
     pub channel: String,
     pub data_size: usize,
     pub cmd_slots: usize,
     pub max_clients: usize,
+    /// Permission bits the daemon creates the channel with, deciding who
+    /// can connect (`0o660`: the daemon's user and group)
+    pub shm_mode: u32,
     pub output_dir: String,
+    pub python_binding: PythonBinding,
+    /// Go client maps the channel itself (`venom_memory::layout`) instead of
+    /// linking the library through cgo
+    pub go_nocgo: bool,
+    /// Flutter client is a widget app (`lib/main.dart`) besides the console
+    /// client in `bin/`
+    pub flutter_ui: bool,
+    /// Python client also has `venom_async.py`, an asyncio wrapper around
+    /// its binding, and the `main_async.py` example
+    pub python_async: bool,
+    /// Value of the state header's `magic` field; clients check it before decoding
+    pub magic: u32,
+    /// Layout of the state struct the daemon publishes
+    pub schema: Schema,
+    /// Target triples to bundle the library for under `lib/<triple>/`;
+    /// empty means the host library in `lib/`
+    pub targets: Vec<String>,
+    /// Extra client languages, each generated client-only under `clients/<lang>/`
+    pub clients: Vec<Language>,
+    /// Also generate `packaging/` with a systemd unit for the daemon
+    pub service: bool,
+    /// `LICENSE` to write, if any
+    pub license: License,
+    /// Year of the copyright line in `LICENSE`; recorded in `venom.toml` so
+    /// regenerating the project doesn't change it
+    pub license_year: u64,
+    /// Also generate `.gitignore`, `LICENSE` and `.vscode/` (`extras`)
+    pub extras: bool,
+    /// Project root as seen from `output_dir`, with a trailing slash
+    /// (`""`, or `"../../"` while generating one of `clients`)
+    pub root: String,
+}
+
+impl ProjectConfig {
+    /// Identifiers the templates derive from the project name
+    pub fn names(&self) -> Names {
+        Names::of(&self.name)
+    }
+
+    /// `path` in the project root (`lib`, `shared/...`) as seen from `output_dir`
+    pub fn at_root(&self, path: &str) -> String {
+        format!("{}{}", self.root, path)
+    }
+
+    /// Config for generating `lang`'s client under `clients/<lang>/`,
+    /// sharing this project's `lib/`, `shared/` and magic
+    pub fn for_client(&self, lang: Language) -> ProjectConfig {
+        ProjectConfig {
+            output_dir: format!("{}/clients/{}", self.output_dir, lang.id()),
+            clients: Vec::new(),
+            root: "../../".to_string(),
+            ..self.clone()
+        }
+    }
+}
+
+/// Longest project name `venom init` accepts
+pub const PROJECT_NAME_MAX: usize = 64;
+
+/// Normalize a project name as typed into the one the project is generated
+/// with: letters, digits, `-` and `_`, each run of anything else (spaces,
+/// punctuation) replaced with `-`
+///
+/// The name becomes a directory, binary and file names, so it must be ASCII
+/// and have a letter or digit in it.
+pub fn project_name(raw: &str) -> Result<String, String> {
+    if let Some(c) = raw.chars().find(|c| !c.is_ascii()) {
+        return Err(format!(
+            "project name {:?} has {:?}; use ASCII letters, digits, '-' and '_' (it names files and identifiers)",
+            raw, c
+        ));
+    }
+    let mut name = String::new();
+    for c in raw.trim().chars() {
+        if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+            name.push(c);
+        } else if !name.ends_with('-') {
+            name.push('-');
+        }
+    }
+    let name = name.trim_matches(['-', '_']).to_string();
+    if !name.bytes().any(|b| b.is_ascii_alphanumeric()) {
+        return Err(format!("project name {:?} needs at least one letter or digit", raw));
+    }
+    if name.len() > PROJECT_NAME_MAX {
+        return Err(format!("project name is {} characters; keep it to {}", name.len(), PROJECT_NAME_MAX));
+    }
+    Ok(name)
+}
+
+/// Spellings of a project name for the places each language needs one
+///
+/// Words are the parts of the name between `-` and `_`; a name starting
+/// with a digit gets a `venom` word in front, since identifiers can't.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Names {
+    /// `my_daemon`: Rust crate, Dart and Nim package, file names
+    pub snake: String,
+    /// `my-daemon`: npm package
+    pub kebab: String,
+    /// `MyDaemon`: type prefix, C# namespace, Swift package
+    pub pascal: String,
+    /// `MY_DAEMON`: macros and header guards
+    pub upper: String,
+    /// `mydaemon`: Go module path, C++ namespace
+    pub flat: String,
+}
+
+impl Names {
+    /// Spellings of `name`, a name [`project_name`] accepts
+    pub fn of(name: &str) -> Names {
+        let mut words: Vec<&str> = name.split(['-', '_']).filter(|w| !w.is_empty()).collect();
+        if words.first().is_none_or(|w| w.starts_with(|c: char| c.is_ascii_digit())) {
+            words.insert(0, "venom");
+        }
+        let lower: Vec<String> = words.iter().map(|w| w.to_ascii_lowercase()).collect();
+        let pascal = words
+            .iter()
+            .map(|w| {
+                let mut chars = w.chars();
+                match chars.next() {
+                    None => String::new(),
+                    Some(first) => first.to_uppercase().chain(chars).collect(),
+                }
+            })
+            .collect();
+        Names {
+            snake: lower.join("_"),
+            kebab: lower.join("-"),
+            pascal,
+            upper: lower.join("_").to_ascii_uppercase(),
+            flat: lower.concat(),
+        }
+    }
+}
+
+/// Which binding the Python client uses to talk to the channel
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum PythonBinding {
+    /// ctypes over the bundled libvenom_memory.so
+    #[default]
+    Ctypes,
+    /// Native venom_py extension (venom-py crate)
+    Pyo3,
 }
 
 /// Language enum for template selection
@@ -29,9 +187,69 @@ pub enum Language {
     Go,
     Zig,
     Nim,
+    Node,
+    CSharp,
+    Java,
+    Swift,
+    Lua,
     Flutter,
 }
 
+impl Language {
+    /// Identifier used on the command line and in `venom.toml`
+    pub fn id(self) -> &'static str {
+        match self {
+            Language::C => "c",
+            Language::Cpp => "cpp",
+            Language::Rust => "rust",
+            Language::Python => "python",
+            Language::Go => "go",
+            Language::Zig => "zig",
+            Language::Nim => "nim",
+            Language::Node => "node",
+            Language::CSharp => "csharp",
+            Language::Java => "java",
+            Language::Swift => "swift",
+            Language::Lua => "lua",
+            Language::Flutter => "flutter",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        ALL_LANGUAGES.iter().copied().find(|l| l.id() == id)
+    }
+}
+
+const ALL_LANGUAGES: &[Language] = &[
+    Language::C,
+    Language::Cpp,
+    Language::Rust,
+    Language::Python,
+    Language::Go,
+    Language::Zig,
+    Language::Nim,
+    Language::Node,
+    Language::CSharp,
+    Language::Java,
+    Language::Swift,
+    Language::Lua,
+    Language::Flutter,
+];
+
+/// Default state magic for a channel: the 32-bit FNV-1a hash of its name
+pub fn channel_magic(channel: &str) -> u32 {
+    channel.bytes().fold(0x811C_9DC5, |hash, b| (hash ^ b as u32).wrapping_mul(0x0100_0193))
+}
+
+/// State magic of projects generated before [`channel_magic`] was FNV-1a
+///
+/// A byte sum, so anagrams (`abc`, `cba`) and many other names collide; only
+/// kept so `venom init --legacy-magic` can regenerate a project that still
+/// talks to daemons already deployed with it.
+pub fn legacy_channel_magic(channel: &str) -> u32 {
+    channel.bytes().fold(0x564E4Fu32, |acc, b| acc.wrapping_add(b as u32))
+}
+
 /// Generate project based on language
 pub fn generate(config: &ProjectConfig, lang: Language) {
     match lang {
@@ -42,6 +260,270 @@ pub fn generate(config: &ProjectConfig, lang: Language) {
         Language::Go => go::generate(config),
         Language::Zig => zig::generate(config),
         Language::Nim => nim::generate(config),
+        Language::Node => node::generate(config),
+        Language::CSharp => csharp::generate(config),
+        Language::Java => java::generate(config),
+        Language::Swift => swift::generate(config),
+        Language::Lua => lua::generate(config),
         Language::Flutter => flutter::generate(config),
     }
 }
+
+/// Generate only the client side of a language into an existing project
+///
+/// Protocol headers the client includes are written only when missing, so
+/// the daemon's copy is never touched.
+pub fn generate_client(config: &ProjectConfig, lang: Language) {
+    match lang {
+        Language::C => c::generate_client(config),
+        Language::Cpp => cpp::generate_client(config),
+        Language::Rust => rust::generate_client(config),
+        Language::Python => python::generate_client(config),
+        Language::Go => go::generate_client(config),
+        Language::Zig => zig::generate_client(config),
+        Language::Nim => nim::generate_client(config),
+        Language::Node => node::generate_client(config),
+        Language::CSharp => csharp::generate_client(config),
+        Language::Java => java::generate_client(config),
+        Language::Swift => swift::generate_client(config),
+        Language::Lua => lua::generate_client(config),
+        Language::Flutter => flutter::generate_client(config),
+    }
+}
+
+/// A generated file, or part of one, that only wraps the library
+///
+/// `venom upgrade` regenerates these; everything else in a project is the user's.
+pub struct Binding {
+    /// Path relative to the project root
+    pub path: String,
+    pub content: String,
+    /// `content` is one block of `path` (the `extern "C"` block of a Rust
+    /// `lib.rs`) rather than the whole file
+    pub block: bool,
+}
+
+/// Binding files of `lang`'s project or client generated into `config.output_dir`
+fn bindings_of(config: &ProjectConfig, lang: Language) -> Vec<Binding> {
+    let file = |path: &str, content: String| Binding { path: path.to_string(), content, block: false };
+    match lang {
+        Language::Python => vec![file("venom_binding.py", python::venom_binding(config))],
+        Language::Flutter => vec![file("lib/venom_binding.dart", flutter::venom_binding(config))],
+        Language::Go if config.go_nocgo => vec![
+            file("venom/venom.go", go::venom_go(config)),
+            file("venom/daemon.go", go::daemon_cgo_go(config)),
+        ],
+        Language::Go => vec![file("venom/venom.go", go::venom_go(config))],
+        Language::Zig => vec![file("src/venom.zig", zig::venom_zig(config))],
+        Language::Nim => vec![file("src/venom.nim", nim::venom_nim(config))],
+        Language::Rust => vec![Binding { path: "src/lib.rs".to_string(), content: rust::FFI_BLOCK.to_string(), block: true }],
+        _ => Vec::new(),
+    }
+}
+
+/// Binding files of a whole project: the `lang` project's own and those of
+/// each client under `clients/<lang>/`
+pub fn bindings(config: &ProjectConfig, lang: Language) -> Vec<Binding> {
+    let mut all = bindings_of(config, lang);
+    for &client in &config.clients {
+        for b in bindings_of(&config.for_client(client), client) {
+            all.push(Binding { path: format!("clients/{}/{}", client.id(), b.path), ..b });
+        }
+    }
+    all
+}
+
+/// Makefile lines choosing which bundled library to link
+///
+/// Empty for a host-only project; otherwise `VENOM_TARGET` defaults to the
+/// first `--target` and can be overridden with `make VENOM_TARGET=<triple>`.
+pub fn make_target_var(config: &ProjectConfig) -> String {
+    match config.targets.first() {
+        None => String::new(),
+        Some(first) => format!(
+            "# Bundled libraries: lib/<triple>/ for {}\n\
+             # Pick one with `make VENOM_TARGET=<triple>` (and a matching CC when cross-compiling)\n\
+             VENOM_TARGET ?= {}\n\n",
+            config.targets.join(" "),
+            first
+        ),
+    }
+}
+
+/// Library directory `dir` as a Makefile sees it: `dir/$(VENOM_TARGET)` when
+/// the project bundles several targets
+pub fn make_lib_dir(config: &ProjectConfig, dir: &str) -> String {
+    if config.targets.is_empty() {
+        dir.to_string()
+    } else {
+        format!("{}/$(VENOM_TARGET)", dir)
+    }
+}
+
+/// Library paths a runtime loader should try, in order: `dir/<triple>/<lib>`
+/// for each bundled target, or just `dir/<lib>`
+pub fn bundled_libraries(config: &ProjectConfig, dir: &str) -> Vec<String> {
+    if config.targets.is_empty() {
+        return vec![format!("{}/{}", dir, crate::library::LIBRARY_NAME)];
+    }
+    config
+        .targets
+        .iter()
+        .map(|t| format!("{}/{}/{}", dir, t, crate::library::library_name_for(t)))
+        .collect()
+}
+
+/// Environment variable that turns on the live half of the generated tests
+/// (those that connect to a running daemon)
+pub const LIVE_TEST_ENV: &str = "VENOM_LIVE_TEST";
+
+/// README section on the clients' `--ping` mode; `command` runs the client
+/// with `--ping 10`
+pub fn ping_readme_section(command: &str) -> String {
+    format!(r#"
+## Round Trip
+
+`{command}` sends ten ping commands and times each reply
+instead of showing the state:
+
+```
+🏓 Pong #1: 5123.4 µs
+...
+📊 Round trip: 10/10 replies, min 212.9 µs, avg 5040.2 µs, max 9876.5 µs
+```
+
+Compare it with the read latency the client shows otherwise: a read only
+touches shared memory, while a ping waits for the daemon, which polls its
+command queue every 10 ms and answers by publishing the state right away
+with a pong (the client's ID and the ping's sequence number) after it. The
+daemon keeps only the last pong, so clients pinging at the same moment can
+miss their reply.
+"#, command = command)
+}
+
+/// README section on running two instances side by side with
+/// `--channel-suffix`: `daemon` and `client` are the commands starting each,
+/// with `SUFFIX` where the suffix goes, and `helper` names what builds the
+/// channel name in the generated code
+pub fn instances_readme_section(channel: &str, daemon: &str, client: &str, helper: &str) -> String {
+    format!(r#"
+## Several Instances
+
+The daemon and client take `--channel-suffix SUFFIX`, which moves them to
+the channel `{channel}-SUFFIX`. Daemons started with different suffixes
+publish independently, and each client reads the one it was pointed at:
+
+```bash
+# Terminals 1 and 2 - one daemon per suffix
+{daemon_a}
+{daemon_b}
+
+# Terminal 3 - sees the second daemon only
+{client_b}
+```
+
+Without the flag both use `{channel}`; {helper} builds the same names.
+"#,
+        channel = channel,
+        daemon_a = daemon.replace("SUFFIX", "a"),
+        daemon_b = daemon.replace("SUFFIX", "b"),
+        client_b = client.replace("SUFFIX", "b"),
+        helper = helper,
+    )
+}
+
+/// `bytes` as `0x..` literals, 16 per line, every line indented by `indent`
+/// and ending in a comma
+pub fn byte_rows(bytes: &[u8], indent: &str) -> String {
+    bytes
+        .chunks(16)
+        .map(|row| {
+            let row: Vec<String> = row.iter().map(|b| format!("0x{:02x}", b)).collect();
+            format!("{}{},\n", indent, row.join(", "))
+        })
+        .collect()
+}
+
+/// File the Python, Go and Dart generators record their decoder's offsets in
+pub const OFFSETS_FILE: &str = "offsets.json";
+
+/// Where a generated decoder reads each field of the state (`offsets.json`)
+///
+/// These decoders hold their offsets in generated code rather than a struct
+/// venom-watch can parse, so the generators write them down next to the
+/// binding for `venom init --verify`.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct DecoderOffsets {
+    /// Generated file the decoder is in, relative to `offsets.json`
+    pub decoder: String,
+    /// Bytes the decoder expects the state to take
+    pub size: usize,
+    pub fields: Vec<DecodedField>,
+}
+
+/// One field as a generated decoder reads it
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
+pub struct DecodedField {
+    pub name: String,
+    pub offset: usize,
+    /// Whole field, all elements of an array
+    pub size: usize,
+}
+
+impl DecoderOffsets {
+    /// Write `offsets.json` into `dir`
+    pub fn write(&self, dir: &str) {
+        let json = serde_json::to_string_pretty(self).expect("offsets serialize");
+        crate::write_file(&format!("{}/{}", dir, OFFSETS_FILE), &(json + "\n"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_magic_is_fnv1a() {
+        // Reference vectors of 32-bit FNV-1a
+        assert_eq!(channel_magic(""), 0x811C_9DC5);
+        assert_eq!(channel_magic("a"), 0xE40C_292C);
+        assert_eq!(channel_magic("foobar"), 0xBF9C_F968);
+        assert_ne!(channel_magic("abc"), channel_magic("cba"));
+    }
+
+    #[test]
+    fn test_project_name() {
+        assert_eq!(project_name("my-daemon").as_deref(), Ok("my-daemon"));
+        assert_eq!(project_name("my_daemon").as_deref(), Ok("my_daemon"));
+        assert_eq!(project_name("  My Daemon!  ").as_deref(), Ok("My-Daemon"));
+        assert_eq!(project_name("a .. b").as_deref(), Ok("a-b"));
+        assert_eq!(project_name("2fast").as_deref(), Ok("2fast"));
+        assert!(project_name("").is_err());
+        assert!(project_name("-_-").is_err());
+        assert!(project_name("!!!").is_err());
+        assert!(project_name(&"x".repeat(PROJECT_NAME_MAX + 1)).is_err());
+        assert!(project_name(&"x".repeat(PROJECT_NAME_MAX)).is_ok());
+        let unicode = project_name("café-monitor").unwrap_err();
+        assert!(unicode.contains("'é'"), "{}", unicode);
+    }
+
+    #[test]
+    fn test_names() {
+        let names = |name: &str| {
+            let n = Names::of(name);
+            [n.snake, n.kebab, n.pascal, n.upper, n.flat]
+        };
+        assert_eq!(names("sensors"), ["sensors", "sensors", "Sensors", "SENSORS", "sensors"]);
+        assert_eq!(names("my-daemon"), ["my_daemon", "my-daemon", "MyDaemon", "MY_DAEMON", "mydaemon"]);
+        assert_eq!(names("my_daemon"), names("my-daemon"));
+        assert_eq!(names("My-Daemon"), ["my_daemon", "my-daemon", "MyDaemon", "MY_DAEMON", "mydaemon"]);
+        assert_eq!(names("sysMon__v2"), ["sysmon_v2", "sysmon-v2", "SysMonV2", "SYSMON_V2", "sysmonv2"]);
+        assert_eq!(names("2fast"), ["venom_2fast", "venom-2fast", "Venom2fast", "VENOM_2FAST", "venom2fast"]);
+    }
+
+    #[test]
+    fn test_legacy_magic_is_unchanged() {
+        assert_eq!(legacy_channel_magic("sensors"), 0x0056_515C);
+        assert_eq!(legacy_channel_magic("abc"), legacy_channel_magic("cba"));
+    }
+}
@@ -6,6 +6,7 @@
 //! - Status bar client
 
 use super::ProjectConfig;
+use crate::schema::FieldType;
 
 pub fn generate(config: &ProjectConfig) {
     let base = &config.output_dir;
@@ -18,37 +19,55 @@ pub fn generate(config: &ProjectConfig) {
     crate::write_file(&format!("{}/src/client.nim", base), &client_nim(config));
     
     // Config file
-    crate::write_file(&format!("{}/{}.nimble", base, config.name), &nimble(config));
+    crate::write_file(&format!("{}/{}.nimble", base, config.names().snake), &nimble(config, true));
     
     // Makefile
-    crate::write_file(&format!("{}/Makefile", base), &makefile(config));
+    crate::write_file(&format!("{}/Makefile", base), &makefile(config, true));
     
     // README
     crate::write_file(&format!("{}/README.md", base), &readme(config));
 }
 
-fn magic(channel: &str) -> u32 {
-    channel.bytes().fold(0x564E4Fu32, |acc, b| acc.wrapping_add(b as u32))
+/// Client files only (used by `venom add-client`)
+pub fn generate_client(config: &ProjectConfig) {
+    let base = &config.output_dir;
+    
+    crate::create_dir(&format!("{}/src", base));
+    crate::write_file(&format!("{}/src/venom.nim", base), &venom_nim(config));
+    crate::write_file(&format!("{}/src/client.nim", base), &client_nim(config));
+    crate::write_file(&format!("{}/{}.nimble", base, config.names().snake), &nimble(config, false));
+    crate::write_file(&format!("{}/Makefile", base), &makefile(config, false));
 }
 
-fn pascal_case(s: &str) -> String {
-    s.split(|c| c == '_' || c == '-')
-        .map(|word| {
-            let mut chars = word.chars();
-            match chars.next() {
-                None => String::new(),
-                Some(first) => first.to_uppercase().chain(chars).collect(),
-            }
-        })
-        .collect()
-}
 
 // ═══════════════════════════════════════════════════════════════════════════
 // Venom bindings (Nim)
 // ═══════════════════════════════════════════════════════════════════════════
 
-fn venom_nim(config: &ProjectConfig) -> String {
-    let pascal = pascal_case(&config.name);
+pub(super) fn venom_nim(config: &ProjectConfig) -> String {
+    let pascal = config.names().pascal;
+    let schema = &config.schema;
+    let mut fields = String::new();
+    let mut pads = 0;
+    for f in &schema.fields {
+        let ty = nim_type(f.ty);
+        // Nim identifiers can't start with `_`, so padding gets a plain unexported name
+        let name = if f.padding {
+            pads += 1;
+            format!("padding{}", pads - 1)
+        } else {
+            format!("{}*", nim_name(&f.name))
+        };
+        match f.len {
+            Some(n) => fields.push_str(&format!("    {}: array[{}, {}]\n", name, n, ty)),
+            None => fields.push_str(&format!("    {}: {}\n", name, ty)),
+        }
+    }
+    let demo = schema.has_demo_fields();
+    let max_cores = match schema.field("cpu_cores") {
+        Some(f) if demo => format!("  MaxCores* = {}\n", f.count()),
+        _ => String::new(),
+    };
     
     format!(r##"## VenomMemory Nim Bindings
 
@@ -64,39 +83,61 @@ const
   DataSize* = {data_size}
   CmdSlots* = {cmd_slots}
   MaxClients* = {max_clients}
-  MaxCores* = 16
+  StateSize* = {state_size}
+{max_cores}
+proc instanceChannel*(suffix: string): string =
+  ## The channel of one of several instances: "{channel}-SUFFIX", or
+  ## ChannelName itself for an empty suffix
+  if suffix.len == 0: ChannelName else: ChannelName & "-" & suffix
+
+proc channelFromArgs*(): string =
+  ## The channel named by `--channel-suffix SUFFIX` on the command line, for
+  ## the daemon and clients alike
+  for i in 1 ..< paramCount():
+    if paramStr(i) == "--channel-suffix":
+      return instanceChannel(paramStr(i + 1))
+  ChannelName
 
 # ═══════════════════════════════════════════════════════════════════════════
-# State Structure (packed to match C layout)
+# State Structure (packed to match C layout, generated from venom.toml)
 # ═══════════════════════════════════════════════════════════════════════════
 
 type
   {pascal}State* {{.packed.}} = object
-    magic*: uint32
-    version*: uint32
-    cpuUsagePercent*: float32
-    cpuCores*: array[MaxCores, float32]
-    coreCount*: uint32
-    memoryUsedMB*: uint32
-    memoryTotalMB*: uint32
-    uptimeSeconds*: uint64
-    updateCounter*: uint64
-    timestampNs*: uint64
-
+{fields}
 proc isValid*(s: {pascal}State): bool = s.magic == Magic
+{helpers}
+static:
+  assert sizeof({pascal}State) == StateSize, "State size mismatch"
 
-proc memoryPercent*(s: {pascal}State): float32 =
-  if s.memoryTotalMB > 0:
-    return float32(s.memoryUsedMB) / float32(s.memoryTotalMB) * 100.0
-  return 0
+# ═══════════════════════════════════════════════════════════════════════════
+# 📨 Commands (Clients send, Daemon applies)
+# ═══════════════════════════════════════════════════════════════════════════
 
-proc uptimeFormatted*(s: {pascal}State): string =
-  let h = s.uptimeSeconds div 3600
-  let m = (s.uptimeSeconds mod 3600) div 60
-  return fmt"{{h}}h {{m}}m"
+const
+  IntervalMS* = 100  # Default publish interval
+
+type
+  CmdType* = enum
+    cmdRefresh = 1      ## Publish immediately
+    cmdSetInterval = 2  ## value: publish interval in ms (10-10000)
+    cmdPing = 3         ## value: sequence number, echoed back in a Pong
+
+  Command* {{.packed.}} = object
+    ## A typed command: `cmd` is the tag, `len` the bytes of payload after
+    ## the envelope, those of `value`
+    cmd*: uint16
+    len*: uint16
+    value*: int32
+
+  Pong* {{.packed.}} = object
+    ## Reply to the last cmdPing, published right after the state
+    clientId*: uint32  ## Client that sent the ping
+    seq*: uint32       ## Its value
 
 static:
-  assert sizeof({pascal}State) == 112, "State size mismatch"
+  assert sizeof(Command) == 8, "Command size mismatch"
+  assert sizeof(Pong) == 8, "Pong size mismatch"
 
 # ═══════════════════════════════════════════════════════════════════════════
 # C FFI Bindings
@@ -105,19 +146,29 @@ static:
 # C FFI Bindings (library path set via Makefile passL)
 
 type
-  VenomConfig {{.packed.}} = object
+  VenomConfigEx {{.packed.}} = object
+    struct_size: csize_t
     data_size: csize_t
     cmd_slots: csize_t
     max_clients: csize_t
+    cmd_payload_size: csize_t
+    cmd_full_policy: csize_t
+    shm_mode: csize_t
 
-proc venom_daemon_create(name: cstring, config: VenomConfig): pointer {{.importc, cdecl.}}
+proc venom_daemon_create_ex(name: cstring, config: ptr VenomConfigEx): pointer {{.importc, cdecl.}}
 proc venom_daemon_destroy(handle: pointer) {{.importc, cdecl.}}
 proc venom_daemon_write_data(handle: pointer, data: ptr uint8, len: csize_t) {{.importc, cdecl.}}
+proc venom_daemon_try_recv_command(handle: pointer, buf: ptr uint8, maxLen: csize_t, outClientId: ptr uint32): csize_t {{.importc, cdecl.}}
+proc venom_daemon_client_name(handle: pointer, clientId: uint32, buf: cstring, maxLen: csize_t): csize_t {{.importc, cdecl.}}
 
-proc venom_shell_connect(name: cstring): pointer {{.importc, cdecl.}}
+proc venom_shell_connect_named(name: cstring, clientName: cstring): pointer {{.importc, cdecl.}}
 proc venom_shell_destroy(handle: pointer) {{.importc, cdecl.}}
-proc venom_shell_read_data(handle: pointer, buf: ptr uint8, maxLen: csize_t): csize_t {{.importc, cdecl.}}
+proc venom_shell_read_data_stamped(handle: pointer, buf: ptr uint8, maxLen: csize_t, outTimestampNs: ptr uint64): csize_t {{.importc, cdecl.}}
+proc venom_clock_ns(): uint64 {{.importc, cdecl.}}
+proc venom_version(): cstring {{.importc, cdecl.}}
 proc venom_shell_id(handle: pointer): uint32 {{.importc, cdecl.}}
+proc venom_shell_daemon_alive(handle: pointer): bool {{.importc, cdecl.}}
+proc venom_shell_send_command(handle: pointer, cmd: ptr uint8, len: csize_t): bool {{.importc, cdecl.}}
 
 # ═══════════════════════════════════════════════════════════════════════════
 # Daemon Wrapper
@@ -125,21 +176,58 @@ proc venom_shell_id(handle: pointer): uint32 {{.importc, cdecl.}}
 
 type Daemon* = object
   handle: pointer
+  lastPong: Pong  ## Published with every state once pinged is set
+  pinged: bool
 
-proc newDaemon*(): Daemon =
-  let cfg = VenomConfig(
+proc newDaemon*(channel = ChannelName): Daemon =
+  var cfg = VenomConfigEx(
+    struct_size: sizeof(VenomConfigEx).csize_t,
     data_size: DataSize.csize_t,
     cmd_slots: CmdSlots.csize_t,
-    max_clients: MaxClients.csize_t
+    max_clients: MaxClients.csize_t,
+    cmd_payload_size: sizeof(Command).csize_t,
+    shm_mode: 0o{shm_mode:o}.csize_t
   )
-  let h = venom_daemon_create(ChannelName.cstring, cfg)
+  let h = venom_daemon_create_ex(channel.cstring, addr cfg)
   if h == nil:
     raise newException(IOError, "Failed to create daemon channel")
   result.handle = h
 
 proc write*(d: Daemon, state: {pascal}State) =
+  ## Publish the state, followed by the last pong once there is one
+  var buf: array[sizeof({pascal}State) + sizeof(Pong), uint8]
   var s = state
-  venom_daemon_write_data(d.handle, cast[ptr uint8](addr s), csize_t(sizeof(s)))
+  copyMem(addr buf[0], addr s, sizeof(s))
+  var len = sizeof(s)
+  if d.pinged:
+    var p = d.lastPong
+    copyMem(addr buf[len], addr p, sizeof(p))
+    len = buf.len
+  venom_daemon_write_data(d.handle, addr buf[0], csize_t(len))
+
+proc pong*(d: var Daemon, clientId, seq: uint32) =
+  ## Answer a ping; the pong goes out with every write until the next one
+  d.lastPong = Pong(clientId: clientId, seq: seq)
+  d.pinged = true
+
+proc tryRecv*(d: Daemon, cmd: var Command, clientId: var uint32): bool =
+  ## Next queued Command, skipping messages that aren't one
+  var buf: array[64, uint8]
+  while true:
+    let n = venom_daemon_try_recv_command(d.handle, addr buf[0], csize_t(buf.len), addr clientId)
+    if n == 0: return false
+    if n >= csize_t(sizeof(Command)):
+      copyMem(addr cmd, addr buf[0], sizeof(Command))
+      if cmd.len == uint16(sizeof(cmd.value)):
+        return true
+
+proc clientLabel*(d: Daemon, clientId: uint32): string =
+  ## "3", or "3 (gui-frontend)" for a client that connected with a name
+  var name: array[33, char]
+  let label = cast[cstring](addr name[0])
+  if venom_daemon_client_name(d.handle, clientId, label, csize_t(name.len)) == 0:
+    return $clientId
+  result = $clientId & " (" & $label & ")"
 
 proc close*(d: Daemon) =
   if d.handle != nil:
@@ -151,9 +239,11 @@ proc close*(d: Daemon) =
 
 type Shell* = object
   handle: pointer
+  writeAgeUs*: float  ## µs between the daemon's write and the last readState (0 if unstamped)
 
-proc connect*(): Shell =
-  let h = venom_shell_connect(ChannelName.cstring)
+proc connect*(clientName = "", channel = ChannelName): Shell =
+  ## clientName, if given, shows in the daemon's log (the library keeps its first 32 bytes)
+  let h = venom_shell_connect_named(channel.cstring, clientName.cstring)
   if h == nil:
     raise newException(IOError, "Failed to connect - is daemon running?")
   result.handle = h
@@ -161,27 +251,230 @@ proc connect*(): Shell =
 proc clientId*(s: Shell): uint32 =
   return venom_shell_id(s.handle)
 
-proc readState*(s: Shell): {pascal}State =
-  var buf: array[256, uint8]
-  let n = venom_shell_read_data(s.handle, addr buf[0], csize_t(buf.len))
+proc daemonAlive*(s: Shell): bool =
+  ## False once the daemon has exited; reads then return its last state
+  return venom_shell_daemon_alive(s.handle)
+
+proc libraryVersion*(): string =
+  ## Version of the libvenom_memory that was loaded
+  return $venom_version()
+
+proc readState*(s: var Shell): {pascal}State =
+  var buf: array[StateSize + 64, uint8]
+  var writtenNs: uint64
+  let n = venom_shell_read_data_stamped(s.handle, addr buf[0], csize_t(buf.len), addr writtenNs)
+  if writtenNs != 0:
+    s.writeAgeUs = float(venom_clock_ns() - writtenNs) / 1000.0
   if n >= csize_t(sizeof(result)):
     copyMem(addr result, addr buf[0], sizeof(result))
 
+proc command*(cmd: CmdType, value: int32 = 0): Command =
+  Command(cmd: uint16(cmd), len: uint16(sizeof(int32)), value: value)
+
+proc send*(s: Shell, cmd: Command): bool {{.discardable.}} =
+  var c = cmd
+  venom_shell_send_command(s.handle, cast[ptr uint8](addr c), csize_t(sizeof(c)))
+
+proc setInterval*(s: Shell, ms: int): bool {{.discardable.}} =
+  s.send(command(cmdSetInterval, int32(ms)))
+
+proc refresh*(s: Shell): bool {{.discardable.}} =
+  s.send(command(cmdRefresh))
+
+proc ping*(s: Shell, seq: uint32): float =
+  ## Round trip of a ping carrying seq in µs, or -1 if no pong came back within a second
+  ##
+  ## The daemon answers by publishing at once with a Pong after the state. It keeps
+  ## only the last one, so clients pinging at the same time can miss their reply.
+  let start = venom_clock_ns()
+  if not s.send(command(cmdPing, cast[int32](seq))): return -1
+  let id = s.clientId()
+  var buf: array[StateSize + sizeof(Pong), uint8]
+  var writtenNs: uint64
+  while venom_clock_ns() - start < 1_000_000_000'u64:
+    if venom_shell_read_data_stamped(s.handle, addr buf[0], csize_t(buf.len), addr writtenNs) < csize_t(buf.len):
+      continue
+    var reply: Pong
+    copyMem(addr reply, addr buf[StateSize], sizeof(reply))
+    if reply.clientId == id and reply.seq == seq:
+      return float(venom_clock_ns() - start) / 1000.0
+  return -1
+
 proc close*(s: Shell) =
   if s.handle != nil:
     venom_shell_destroy(s.handle)
 "##,
+        shm_mode = config.shm_mode,
         channel = config.channel,
-        magic = magic(&config.channel),
+        magic = config.magic,
         data_size = config.data_size,
         cmd_slots = config.cmd_slots,
         max_clients = config.max_clients,
-        pascal = pascal
+        pascal = pascal,
+        state_size = schema.size,
+        max_cores = max_cores,
+        fields = fields,
+        helpers = if demo { demo_helpers(&pascal) } else { String::new() }
     )
 }
 
+/// Convenience procs used by the system-monitor demo
+fn demo_helpers(pascal: &str) -> String {
+    format!(r##"
+proc memoryPercent*(s: {pascal}State): float32 =
+  if s.memoryTotalMB > 0:
+    return float32(s.memoryUsedMB) / float32(s.memoryTotalMB) * 100.0
+  return 0
+
+proc uptimeFormatted*(s: {pascal}State): string =
+  let h = s.uptimeSeconds div 3600
+  let m = (s.uptimeSeconds mod 3600) div 60
+  return fmt"{{h}}h {{m}}m"
+"##, pascal = pascal)
+}
+
+/// camelCase field name (`memory_used_mb` -> `memoryUsedMB`)
+fn nim_name(field: &str) -> String {
+    field.split('_')
+        .enumerate()
+        .map(|(i, word)| match word {
+            _ if i == 0 => word.to_string(),
+            "mb" | "kb" | "gb" | "id" => word.to_uppercase(),
+            _ => {
+                let mut chars = word.chars();
+                match chars.next() {
+                    None => String::new(),
+                    Some(first) => first.to_uppercase().chain(chars).collect(),
+                }
+            }
+        })
+        .collect()
+}
+
+fn nim_type(ty: FieldType) -> &'static str {
+    match ty {
+        FieldType::U8 => "uint8",
+        FieldType::U16 => "uint16",
+        FieldType::U32 => "uint32",
+        FieldType::U64 => "uint64",
+        FieldType::I8 => "int8",
+        FieldType::I16 => "int16",
+        FieldType::I32 => "int32",
+        FieldType::I64 => "int64",
+        FieldType::F32 => "float32",
+        FieldType::F64 => "float64",
+    }
+}
+
+/// Daemon-side command and signal handling, shared by both daemons: the
+/// daemon polls `handleCommands` every 10 ms and publishes when it returns
+/// true or `intervalMs` has passed, until a signal clears `running`
+const COMMAND_HANDLER: &str = r#"from posix import signal, SIGINT, SIGTERM
+
+var intervalMs = IntervalMS
+var running = true
+
+proc onSignal(sig: cint) {.noconv.} =
+  running = false
+
+proc stopOnSignals() =
+  ## Ctrl+C or SIGTERM ends the publish loop, so the deferred close removes the channel
+  discard signal(SIGINT, onSignal)
+  discard signal(SIGTERM, onSignal)
+
+proc handleCommands(daemon: var Daemon): bool =
+  ## Apply queued client commands; returns true if a client asked for a refresh
+  var cmd: Command
+  var clientId: uint32
+  while daemon.tryRecv(cmd, clientId):
+    case cmd.cmd
+    of uint16(cmdRefresh):
+      result = true
+    of uint16(cmdSetInterval):
+      intervalMs = clamp(int(cmd.value), 10, 10000)
+      echo "\n📥 Client ", daemon.clientLabel(clientId), ": publishing every ", intervalMs, " ms"
+    of uint16(cmdPing):
+      # Answered by publishing now, with the pong after the state
+      daemon.pong(clientId, cast[uint32](cmd.value))
+      result = true
+    else:
+      echo "\n📥 Client ", daemon.clientLabel(clientId), ": unknown command ", cmd.cmd
+"#;
+
+/// Client-side keys, shared by both clients: `+`/`-` halve or double the
+/// daemon's publish interval, `r` asks for an immediate refresh
+const COMMAND_KEYS: &str = r#"var intervalMs = IntervalMS
+var savedTty: Termios
+var rawTty = false
+
+proc keysEnd() =
+  if rawTty: discard tcSetAttr(STDIN_FILENO, TCSANOW, addr savedTty)
+
+proc keysBegin() =
+  ## Read single keys without Enter or echo (Ctrl+C still works)
+  if isatty(STDIN_FILENO) == 0 or tcGetAttr(STDIN_FILENO, addr savedTty) != 0: return
+  var raw = savedTty
+  raw.c_lflag = raw.c_lflag and not Cflag(ICANON or ECHO)
+  raw.c_cc[VMIN] = char(0)
+  raw.c_cc[VTIME] = char(0)
+  discard tcSetAttr(STDIN_FILENO, TCSANOW, addr raw)
+  rawTty = true
+  # Put the terminal back when Ctrl+C ends the client
+  setControlCHook(proc() {.noconv.} =
+    keysEnd()
+    quit(0))
+
+proc handleKeys(shell: Shell) =
+  var key: char
+  while rawTty and posix.read(STDIN_FILENO, addr key, 1) == 1:
+    case key
+    of '+', '=':
+      intervalMs = max(intervalMs div 2, 10)
+      shell.setInterval(intervalMs)
+    of '-':
+      intervalMs = min(intervalMs * 2, 10000)
+      shell.setInterval(intervalMs)
+    of 'r':
+      shell.refresh()
+    else: discard
+
+proc pingArg(): int =
+  ## `--ping COUNT`: measure round trips through the daemon instead (0 if absent)
+  for i in 1 ..< paramCount():
+    if paramStr(i) == "--ping":
+      return try: parseInt(paramStr(i + 1)) except ValueError: 0
+
+proc runPing(shell: Shell, count: int): bool =
+  ## Time count round trips through the daemon; false if none came back
+  # A pong for an earlier client with the same ID may still be published
+  var seq = uint32(getMonoTime().ticks and 0xffff_ffff)
+  var
+    rttMin = float.high
+    rttMax = 0.0
+    rttSum = 0.0
+    replies = 0
+  for i in 1 .. count:
+    seq += 1
+    let rttUs = shell.ping(seq)
+    if rttUs < 0:
+      echo fmt"⚠️  No reply to ping #{i}"
+      continue
+    echo fmt"🏓 Pong #{i}: {rttUs:.1f} µs"
+    rttMin = min(rttMin, rttUs)
+    rttMax = max(rttMax, rttUs)
+    rttSum += rttUs
+    replies.inc
+    sleep(100)
+  if replies == 0: return false
+  echo fmt"📊 Round trip: {replies}/{count} replies, min {rttMin:.1f} µs, avg {rttSum / float(replies):.1f} µs, max {rttMax:.1f} µs"
+  return true
+"#;
+
 fn daemon_nim(config: &ProjectConfig) -> String {
-    let pascal = pascal_case(&config.name);
+    if !config.schema.has_demo_fields() {
+        return generic_daemon_nim(config);
+    }
+    let pascal = config.names().pascal;
     
     format!(r##"## {name} System Monitor Daemon (Nim)
 
@@ -191,6 +484,7 @@ import venom
 var prevTotal: array[venom.MaxCores + 1, uint64]
 var prevIdle: array[venom.MaxCores + 1, uint64]
 
+{commands}
 proc readCpu(state: var {pascal}State) =
   let f = open("/proc/stat")
   defer: f.close()
@@ -256,10 +550,12 @@ proc main() =
   echo "🖥️  {name} System Monitor (Nim)"
   echo "═══════════════════════════════════════════════════════════════"
   
-  let daemon = newDaemon()
+  let channel = channelFromArgs()
+  var daemon = newDaemon(channel)
   defer: daemon.close()
+  stopOnSignals()
   
-  echo fmt"✅ Channel: {{venom.ChannelName}}"
+  echo fmt"✅ Channel: {{channel}}"
   echo "🚀 Publishing... (Ctrl+C to stop)"
   echo ""
   
@@ -268,7 +564,14 @@ proc main() =
     version: 1
   )
   
-  while true:
+  var nextPublish = epochTime()
+  while running:
+    let refresh = handleCommands(daemon)
+    if not refresh and epochTime() < nextPublish:
+      sleep(10)
+      continue
+    nextPublish = epochTime() + intervalMs / 1000
+    
     readCpu(state)
     readMemory(state)
     readUptime(state)
@@ -277,22 +580,74 @@ proc main() =
     
     daemon.write(state)
     
-    stdout.write fmt"\r🖥️  CPU: {{state.cpuUsagePercent:.1f}}% | RAM: {{state.memoryUsedMB}}/{{state.memoryTotalMB}} MB | #{{state.updateCounter}}   "
+    stdout.write fmt"\r🖥️  CPU: {{state.cpuUsagePercent:.1f}}% | RAM: {{state.memoryUsedMB}}/{{state.memoryTotalMB}} MB | #{{state.updateCounter}} | {{intervalMs}} ms   "
     stdout.flushFile()
+  echo "\n\n👋 Goodbye!"
+
+when isMainModule:
+  main()
+"##, name = config.name, pascal = pascal, commands = COMMAND_HANDLER)
+}
+
+/// Daemon for a custom schema: publishes the header and leaves the fields to the user
+fn generic_daemon_nim(config: &ProjectConfig) -> String {
+    let pascal = config.names().pascal;
     
-    sleep(100)
+    format!(r##"## {name} Daemon (Nim) - publishes {pascal}State (see venom.toml)
+
+import os, strformat, times
+import venom
+
+{commands}
+proc main() =
+  echo "🚀 {name} Daemon (Nim)"
+  echo "═══════════════════════════════════════════════════════════════"
+  
+  let channel = channelFromArgs()
+  var daemon = newDaemon(channel)
+  defer: daemon.close()
+  stopOnSignals()
+  
+  echo fmt"✅ Channel: {{channel}} | State: {{venom.StateSize}} bytes"
+  echo "🚀 Publishing... (Ctrl+C to stop)"
+  echo ""
+  
+  var state = {pascal}State(
+    magic: venom.Magic,
+    version: 1
+  )
+  
+  var updates = 0
+  var nextPublish = epochTime()
+  while running:
+    let refresh = handleCommands(daemon)
+    if not refresh and epochTime() < nextPublish:
+      sleep(10)
+      continue
+    nextPublish = epochTime() + intervalMs / 1000
+    
+    # TODO: fill in the fields of {pascal}State
+    daemon.write(state)
+    
+    updates.inc
+    stdout.write fmt"\r📤 Published update #{{updates}} (every {{intervalMs}} ms)   "
+    stdout.flushFile()
+  echo "\n\n👋 Goodbye!"
 
 when isMainModule:
   main()
-"##, name = config.name, pascal = pascal)
+"##, name = config.name, pascal = pascal, commands = COMMAND_HANDLER)
 }
 
 fn client_nim(config: &ProjectConfig) -> String {
-    let pascal = pascal_case(&config.name);
+    if !config.schema.has_demo_fields() {
+        return generic_client_nim(config);
+    }
     
     format!(r##"## {name} Status Bar Client (Nim) - with Benchmarking
 
-import os, strformat, terminal, times
+import os, strformat, strutils, terminal, termios, times, monotimes
+from posix import isatty, read, STDIN_FILENO
 import venom
 
 const
@@ -309,6 +664,7 @@ var
   latencySum = 0.0
   latencyCount: uint64 = 0
 
+{keys}
 proc printBar(pct: float32, width: int = 25): string =
   let filled = int((pct / 100.0) * float32(width))
   let color = if pct > 80: Red elif pct > 50: Yellow else: Green
@@ -327,23 +683,36 @@ proc main() =
   echo "╚═══════════════════════════════════════════════════════════════╝"
   echo ""
   
-  let shell = connect()
+  var shell = connect("nim-client", channelFromArgs())
   defer: shell.close()
   
-  echo fmt"✅ Connected! ID: {{shell.clientId()}}"
+  echo fmt"✅ Connected! ID: {{shell.clientId()}} (libvenom_memory {{libraryVersion()}})"
+  let pings = pingArg()
+  if pings > 0:
+    if not runPing(shell, pings): quit(1)
+    return
   echo "📊 Reading stats... (Ctrl+C to exit)"
   echo ""
   sleep(1000)
   
+  keysBegin()
   var frame = 0
   while true:
+    handleKeys(shell)
+    # Reads would keep returning the last state it wrote
+    if not shell.daemonAlive():
+      keysEnd()
+      echo "\n❌ Daemon offline! Restart it:\n   make run-daemon"
+      break
+    
     # ═══════════════════════════════════════════════════════════════════
     # 📊 BENCHMARK: Measure read latency
     # ═══════════════════════════════════════════════════════════════════
-    let tStart = cpuTime()
+    # Wall-clock time (cpuTime would only count this process's CPU time)
+    let tStart = getMonoTime()
     let state = shell.readState()
-    let tEnd = cpuTime()
-    let latencyUs = (tEnd - tStart) * 1_000_000
+    let tEnd = getMonoTime()
+    let latencyUs = float((tEnd - tStart).inNanoseconds) / 1000.0
     
     # Update stats
     if latencyUs < latencyMin: latencyMin = latencyUs
@@ -371,8 +740,10 @@ proc main() =
       echo fmt"║  ⏱️ Uptime: {{state.uptimeFormatted()}}                                        ║"
       echo "╠═══════════════════════════════════════════════════════════════╣"
       echo fmt"║  📊 {{Cyan}}Read Latency:{{Reset}} {{latencyUs:.2f}} µs (min: {{latencyMin:.2f}}, max: {{latencyMax:.2f}}, avg: {{avgUs:.2f}})  ║"
+      echo fmt"║  📡 {{Cyan}}Daemon→client:{{Reset}} {{shell.writeAgeUs:.2f}} µs since the write                 ║"
       echo "╚═══════════════════════════════════════════════════════════════╝"
-      echo fmt"  Cores: {{state.coreCount}} | Updates: {{state.updateCounter}} | Ctrl+C to exit"
+      echo fmt"  Cores: {{state.coreCount}} | Updates: {{state.updateCounter}} | Interval: {{intervalMs}} ms"
+      echo "  +/- faster/slower | r refresh | Ctrl+C to exit"
       
       frame.inc
     
@@ -380,10 +751,79 @@ proc main() =
 
 when isMainModule:
   main()
-"##, name = config.name)
+"##, name = config.name, keys = COMMAND_KEYS)
 }
 
-fn nimble(config: &ProjectConfig) -> String {
+/// Client for a custom schema: prints every field of the state
+fn generic_client_nim(config: &ProjectConfig) -> String {
+    let fields: String = config.schema.user_fields()
+        .map(|f| format!("      echo \"  \", alignLeft(\"{}\", 24), \" \", $state.{}\n", f.name, nim_name(&f.name)))
+        .collect();
+    
+    format!(r##"## {name} Client (Nim) - prints every field of the state (see venom.toml)
+
+import os, strformat, strutils, terminal, termios, times, monotimes
+from posix import isatty, read, STDIN_FILENO
+import venom
+
+{keys}
+proc main() =
+  var shell = connect("nim-client", channelFromArgs())
+  defer: shell.close()
+  echo fmt"✅ Connected! ID: {{shell.clientId()}} (libvenom_memory {{libraryVersion()}})"
+  let pings = pingArg()
+  if pings > 0:
+    if not runPing(shell, pings): quit(1)
+    return
+  keysBegin()
+  
+  var
+    latMin = float.high
+    latMax = 0.0
+    latSum = 0.0
+    latCount = 0
+  
+  while true:
+    handleKeys(shell)
+    # Reads would keep returning the last state it wrote
+    if not shell.daemonAlive():
+      keysEnd()
+      echo "\n❌ Daemon offline! Restart it:\n   make run-daemon"
+      break
+    
+    let tStart = getMonoTime()
+    let state = shell.readState()
+    let latencyUs = float((getMonoTime() - tStart).inNanoseconds) / 1000.0
+    
+    latMin = min(latMin, latencyUs)
+    latMax = max(latMax, latencyUs)
+    latSum += latencyUs
+    latCount.inc
+    
+    if state.isValid():
+      eraseScreen()
+      setCursorPos(0, 0)
+      echo fmt"🐍 {name} (Nim) | State v{{state.version}} | {{StateSize}} bytes"
+      echo "═══════════════════════════════════════════════════════════════"
+{fields}      echo "═══════════════════════════════════════════════════════════════"
+      echo fmt"📊 Read Latency: {{latencyUs:.2f}} µs (min: {{latMin:.2f}}, max: {{latMax:.2f}}, avg: {{latSum / float(latCount):.2f}})"
+      echo fmt"📡 Daemon→client: {{shell.writeAgeUs:.2f}} µs since the write"
+      echo fmt"⏱️  Daemon interval: {{intervalMs}} ms | +/- faster/slower | r refresh"
+    
+    sleep(100)
+
+when isMainModule:
+  main()
+"##, name = config.name, fields = fields, keys = COMMAND_KEYS)
+}
+
+fn nimble(config: &ProjectConfig, with_daemon: bool) -> String {
+    let bins = if with_daemon {
+        format!("\"{name}_daemon\", \"{name}_client\"", name = config.name)
+    } else {
+        format!("\"{}_client\"", config.name)
+    };
+    
     format!(r#"# {name} Nimble Package
 
 version       = "0.1.0"
@@ -391,38 +831,71 @@ author        = "VenomMemory"
 description   = "System monitor using VenomMemory"
 license       = "MIT"
 srcDir        = "src"
-bin           = @["{name}_daemon", "{name}_client"]
+bin           = @[{bins}]
 
 requires "nim >= 1.6.0"
-"#, name = config.name)
+"#, name = config.name, bins = bins)
 }
 
-fn makefile(config: &ProjectConfig) -> String {
+fn makefile(config: &ProjectConfig, with_daemon: bool) -> String {
+    if !with_daemon {
+        return client_makefile(config);
+    }
+    
     format!(r#"# {name} Nim Project Makefile
 
-.PHONY: all daemon client clean run-daemon run-client
+{target_var}.PHONY: all daemon client clean run-daemon run-client
 
 all: daemon client
 
 daemon:
 	@echo "🔗 Building daemon..."
-	@nim c --passL:"-L./lib -lvenom_memory -Wl,-rpath,\$$ORIGIN/lib" -o:{name}_daemon src/daemon.nim
+	@nim c --passL:"-L./{lib} -lvenom_memory -Wl,-rpath,\$$ORIGIN/{lib}" -o:{name}_daemon src/daemon.nim
 	@echo "✅ Daemon built"
 
 client:
 	@echo "🔗 Building client..."
-	@nim c --passL:"-L./lib -lvenom_memory -Wl,-rpath,\$$ORIGIN/lib" -o:{name}_client src/client.nim
+	@nim c --passL:"-L./{lib} -lvenom_memory -Wl,-rpath,\$$ORIGIN/{lib}" -o:{name}_client src/client.nim
 	@echo "✅ Client built"
 
 run-daemon: daemon
-	@LD_LIBRARY_PATH=./lib ./{name}_daemon
+	@LD_LIBRARY_PATH=./{lib} ./{name}_daemon $(ARGS)
 
 run-client: client
-	@LD_LIBRARY_PATH=./lib ./{name}_client
+	@LD_LIBRARY_PATH=./{lib} ./{name}_client $(ARGS)
 
 clean:
 	@rm -f {name}_daemon {name}_client
-"#, name = config.name)
+"#,
+        name = config.name,
+        target_var = super::make_target_var(config),
+        lib = super::make_lib_dir(config, &config.at_root("lib")),
+    )
+}
+
+/// Makefile for a client added to a project whose daemon is built elsewhere
+fn client_makefile(config: &ProjectConfig) -> String {
+    format!(r#"# {name} Nim Client Makefile
+
+{target_var}.PHONY: all client clean run-client
+
+all: client
+
+client:
+	@echo "🔗 Building client..."
+	@nim c --passL:"-L./{lib} -lvenom_memory -Wl,-rpath,\$$ORIGIN/{lib}" -o:{name}_client src/client.nim
+	@echo "✅ Client built"
+
+run-client: client
+	@LD_LIBRARY_PATH=./{lib} ./{name}_client $(ARGS)
+
+clean:
+	@rm -f {name}_client
+"#,
+        name = config.name,
+        target_var = super::make_target_var(config),
+        lib = super::make_lib_dir(config, &config.at_root("lib")),
+    )
 }
 
 fn readme(config: &ProjectConfig) -> String {
@@ -449,9 +922,12 @@ make run-client
 |---------|-------|
 | Channel | `{channel}` |
 | Magic | `0x{magic:08X}` |
-"#,
+{ping}{instances}{service}"#,
         name = config.name,
+        ping = super::ping_readme_section("make run-client ARGS=\"--ping 10\""),
+        instances = super::instances_readme_section(&config.channel, "make run-daemon ARGS=\"--channel-suffix SUFFIX\"", "make run-client ARGS=\"--channel-suffix SUFFIX\"", "`instanceChannel()` in the venom module"),
+        service = super::service::readme_section(config),
         channel = config.channel,
-        magic = magic(&config.channel)
+        magic = config.magic
     )
 }
@@ -0,0 +1,738 @@
+//! Node.js Templates for VenomMemory projects
+//!
+//! Generates a complete Node.js project with:
+//! - C daemon (for system monitoring)
+//! - index.js status bar client using the venom-node N-API addon
+//! - package.json
+//! - Bundled libvenom_memory.so (for the daemon)
+
+use super::ProjectConfig;
+use crate::schema::{camel_case, FieldType};
+
+pub fn generate(config: &ProjectConfig) {
+    let base = &config.output_dir;
+    
+    // Create directories
+    crate::create_dir(&format!("{}/daemon/src", base));
+    crate::create_dir(&format!("{}/shared", base));
+    
+    // Shared protocol (C header)
+    crate::write_file(&format!("{}/shared/protocol.h", base), &protocol_h(config));
+    
+    // C Daemon
+    crate::write_file(&format!("{}/daemon/src/main.c", base), &daemon_main(config));
+    crate::write_file(&format!("{}/daemon/Makefile", base), &daemon_makefile(config));
+    
+    // Node.js client
+    generate_client(config);
+    
+    // README
+    crate::write_file(&format!("{}/README.md", base), &readme(config));
+}
+
+/// Client files only (used by `venom add-client`)
+pub fn generate_client(config: &ProjectConfig) {
+    let base = &config.output_dir;
+    
+    crate::write_file(&format!("{}/package.json", base), &package_json(config));
+    crate::write_file(&format!("{}/index.js", base), &index_js(config));
+}
+
+
+
+// ═══════════════════════════════════════════════════════════════════════════
+// C Protocol Header (shared between daemon and Node.js client)
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn protocol_h(config: &ProjectConfig) -> String {
+    let upper = config.names().upper;
+    let pascal = config.names().pascal;
+    
+    format!(r#"#ifndef {upper}_PROTOCOL_H
+#define {upper}_PROTOCOL_H
+
+#include <stdint.h>
+#include <stdbool.h>
+
+#define {upper}_CHANNEL_NAME "{channel}"
+#define {upper}_MAGIC 0x{magic:08X}
+#define {upper}_DATA_SIZE {data_size}
+#define {upper}_CMD_SLOTS {cmd_slots}
+#define {upper}_MAX_CLIENTS {max_clients}
+#define {upper}_STATE_SIZE {state_size}
+{max_cores}
+typedef struct __attribute__((packed)) {{
+{fields}}} {pascal}State;
+
+_Static_assert(sizeof({pascal}State) == {upper}_STATE_SIZE, "{pascal}State size mismatch");
+
+{commands}
+#endif // {upper}_PROTOCOL_H
+"#,
+        upper = upper,
+        pascal = pascal,
+        channel = config.channel,
+        magic = config.magic,
+        data_size = config.data_size,
+        cmd_slots = config.cmd_slots,
+        max_clients = config.max_clients,
+        state_size = config.schema.size,
+        max_cores = super::c::max_cores_define(config),
+        fields = config.schema.c_fields("    "),
+        commands = super::c::commands_h(config),
+    )
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// C Daemon
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn daemon_main(config: &ProjectConfig) -> String {
+    if !config.schema.has_demo_fields() {
+        return super::c::generic_daemon_c(config, "../shared/protocol.h");
+    }
+    let upper = config.names().upper;
+    let pascal = config.names().pascal;
+    
+    format!(r#"/**
+ * {name} System Monitor Daemon
+ * Reads CPU/RAM/Uptime from /proc and publishes to shared memory.
+ * Node.js client connects to this daemon.
+ */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <signal.h>
+#include <unistd.h>
+#include <time.h>
+#include "../shared/protocol.h"
+
+typedef struct VenomDaemonHandle VenomDaemonHandle;
+typedef struct {{ size_t struct_size; size_t data_size; size_t cmd_slots; size_t max_clients; size_t cmd_payload_size; size_t cmd_full_policy; size_t shm_mode; }} VenomConfigEx;
+extern VenomDaemonHandle* venom_daemon_create_ex(const char* name, const VenomConfigEx* config);
+extern void venom_daemon_destroy(VenomDaemonHandle* handle);
+extern void venom_daemon_write_data(VenomDaemonHandle* handle, const uint8_t* data, size_t len);
+extern size_t venom_daemon_try_recv_command(VenomDaemonHandle* handle, uint8_t* buf, size_t max_len, uint32_t* out_client_id);
+
+static VenomDaemonHandle* g_daemon = NULL;
+static {pascal}State g_state = {{0}};
+static volatile int g_running = 1;
+static uint64_t prev_total[{upper}_MAX_CORES + 1] = {{0}};
+static uint64_t prev_idle[{upper}_MAX_CORES + 1] = {{0}};
+
+static void signal_handler(int sig) {{ (void)sig; g_running = 0; }}
+
+{commands}
+{channel_arg}
+static void read_cpu_stats(void) {{
+    FILE* f = fopen("/proc/stat", "r");
+    if (!f) return;
+    char line[256];
+    int core_idx = 0;
+    while (fgets(line, sizeof(line), f) && core_idx <= {upper}_MAX_CORES) {{
+        if (strncmp(line, "cpu", 3) != 0) continue;
+        uint64_t user, nice, system, idle, iowait, irq, softirq;
+        if (sscanf(line + (line[3] == ' ' ? 4 : 5), "%lu %lu %lu %lu %lu %lu %lu",
+                   &user, &nice, &system, &idle, &iowait, &irq, &softirq) != 7) continue;
+        uint64_t total = user + nice + system + idle + iowait + irq + softirq;
+        uint64_t idle_time = idle + iowait;
+        uint64_t total_delta = total - prev_total[core_idx];
+        uint64_t idle_delta = idle_time - prev_idle[core_idx];
+        float usage = total_delta > 0 ? (1.0f - (float)idle_delta / (float)total_delta) * 100.0f : 0;
+        if (line[3] == ' ') g_state.cpu_usage_percent = usage;
+        else if (core_idx - 1 >= 0 && core_idx - 1 < {upper}_MAX_CORES) g_state.cpu_cores[core_idx - 1] = usage;
+        prev_total[core_idx] = total;
+        prev_idle[core_idx] = idle_time;
+        core_idx++;
+    }}
+    g_state.core_count = core_idx > 1 ? core_idx - 1 : 0;
+    fclose(f);
+}}
+
+static void read_memory_stats(void) {{
+    FILE* f = fopen("/proc/meminfo", "r");
+    if (!f) return;
+    char line[256];
+    uint64_t total_kb = 0, available_kb = 0;
+    while (fgets(line, sizeof(line), f)) {{
+        if (strncmp(line, "MemTotal:", 9) == 0) sscanf(line + 9, "%lu", &total_kb);
+        else if (strncmp(line, "MemAvailable:", 13) == 0) sscanf(line + 13, "%lu", &available_kb);
+    }}
+    g_state.memory_total_mb = (uint32_t)(total_kb / 1024);
+    g_state.memory_used_mb = (uint32_t)((total_kb - available_kb) / 1024);
+    fclose(f);
+}}
+
+static void read_uptime(void) {{
+    FILE* f = fopen("/proc/uptime", "r");
+    if (!f) return;
+    double uptime;
+    if (fscanf(f, "%lf", &uptime) == 1) g_state.uptime_seconds = (uint64_t)uptime;
+    fclose(f);
+}}
+
+int main(int argc, char** argv) {{
+    printf("🖥️  {name} System Monitor Daemon\n");
+    printf("═══════════════════════════════════════════════════════════════\n");
+    signal(SIGINT, signal_handler);
+    signal(SIGTERM, signal_handler);
+    
+    VenomConfigEx config = {{ .struct_size = sizeof(VenomConfigEx), .data_size = {upper}_DATA_SIZE, .cmd_slots = {upper}_CMD_SLOTS, .max_clients = {upper}_MAX_CLIENTS, .cmd_payload_size = sizeof({pascal}Command), .shm_mode = 0{shm_mode:o} }};
+    const char* channel = channel_from_args(argc, argv);
+    g_daemon = venom_daemon_create_ex(channel, &config);
+    if (!g_daemon) {{ printf("❌ Failed to create channel\n"); return 1; }}
+    
+    g_state.magic = {upper}_MAGIC;
+    g_state.version = 1;
+    
+    printf("✅ Channel: %s\n", channel);
+    printf("🟩 Node.js client can connect now!\n");
+    printf("🚀 Publishing... (Ctrl+C to stop)\n\n");
+    
+    uint64_t next_publish = 0;
+    while (g_running) {{
+        int refresh = handle_commands(g_daemon);
+        if (!refresh && now_ms() < next_publish) {{ usleep(10000); continue; }}
+        next_publish = now_ms() + g_interval_ms;
+        
+        read_cpu_stats();
+        read_memory_stats();
+        read_uptime();
+        g_state.update_counter++;
+        struct timespec ts;
+        clock_gettime(CLOCK_MONOTONIC, &ts);
+        g_state.timestamp_ns = (uint64_t)ts.tv_sec * 1000000000ULL + ts.tv_nsec;
+        publish(g_daemon, &g_state);
+        
+        printf("\r🖥️  CPU: %5.1f%% | RAM: %u/%u MB | Uptime: %luh%lum | #%lu | %u ms   ",
+            g_state.cpu_usage_percent, g_state.memory_used_mb, g_state.memory_total_mb,
+            (unsigned long)(g_state.uptime_seconds / 3600), (unsigned long)((g_state.uptime_seconds % 3600) / 60),
+            (unsigned long)g_state.update_counter, g_interval_ms);
+        fflush(stdout);
+    }}
+    venom_daemon_destroy(g_daemon);
+    printf("\n\n👋 Goodbye!\n");
+    return 0;
+}}
+"#, name = config.name, upper = upper, pascal = pascal, shm_mode = config.shm_mode, commands = super::c::command_handler_c(config), channel_arg = super::c::channel_arg_c(config))
+}
+
+fn daemon_makefile(config: &ProjectConfig) -> String {
+    format!(r#"# {name} Daemon Makefile
+
+{target_var}CC = gcc
+CFLAGS = -Wall -Wextra -O2 -I../shared
+LDFLAGS = -L{lib} -lvenom_memory -Wl,-rpath,'$$ORIGIN/{lib}'
+
+TARGET = {name}_daemon
+SOURCES = src/main.c
+
+.PHONY: all clean run
+
+all: $(TARGET)
+
+$(TARGET): $(SOURCES)
+	@echo "🔗 Building $(TARGET)..."
+	@$(CC) $(CFLAGS) $(SOURCES) -o $(TARGET) $(LDFLAGS)
+	@echo "✅ Build complete"
+
+clean:
+	@rm -f $(TARGET)
+
+run: $(TARGET)
+	@./$(TARGET) $(ARGS)
+"#,
+        name = config.name,
+        target_var = super::make_target_var(config),
+        lib = super::make_lib_dir(config, "../lib"),
+    )
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Node.js Client
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn package_json(config: &ProjectConfig) -> String {
+    format!(r#"{{
+  "name": "{package}",
+  "version": "0.1.0",
+  "description": "VenomMemory client for {name} - Real-time system monitoring via shared memory IPC",
+  "main": "index.js",
+  "private": true,
+  "scripts": {{
+    "start": "node index.js"
+  }},
+  "dependencies": {{
+    "venom-node": "^0.1.0"
+  }}
+}}
+"#, name = config.name, package = config.names().kebab)
+}
+
+/// Commands and client-side keys, shared by both clients: `+`/`-` halve or
+/// double the daemon's publish interval, `r` asks for an immediate refresh,
+/// `--ping COUNT` times round trips instead of showing the state and
+/// `--channel-suffix SUFFIX` picks the daemon instance
+const COMMAND_KEYS: &str = r#"// ═══════════════════════════════════════════════════════════════════════════
+// 📨 Commands (Clients send, Daemon applies)
+// ═══════════════════════════════════════════════════════════════════════════
+
+const INTERVAL_MS = 100;     // Default publish interval
+const CMD_REFRESH = 1;       // Publish immediately
+const CMD_SET_INTERVAL = 2;  // value: publish interval in ms (10-10000)
+const CMD_PING = 3;          // value: sequence number, echoed back in a pong
+
+/** Packed Command, a typed command: cmd u16 (the tag), len u16 (4, the payload's size), value i32 */
+function encodeCommand(cmd, value = 0) {
+  const buf = Buffer.alloc(8);
+  buf.writeUInt16LE(cmd, 0);
+  buf.writeUInt16LE(4, 2);
+  buf.writeInt32LE(value, 4);
+  return buf;
+}
+
+let intervalMs = INTERVAL_MS;
+
+/** Read single keys without Enter or echo */
+function listenForKeys(shell) {
+  if (!process.stdin.isTTY) return;
+  process.stdin.setRawMode(true);
+  process.stdin.on('data', (data) => {
+    for (const key of data.toString()) {
+      if (key === '\u0003') {
+        process.emit('SIGINT');  // Raw mode delivers Ctrl+C as a key
+      } else if (key === '+' || key === '=') {
+        intervalMs = Math.max(Math.floor(intervalMs / 2), 10);
+        shell.sendCommand(encodeCommand(CMD_SET_INTERVAL, intervalMs));
+      } else if (key === '-') {
+        intervalMs = Math.min(intervalMs * 2, 10000);
+        shell.sendCommand(encodeCommand(CMD_SET_INTERVAL, intervalMs));
+      } else if (key === 'r') {
+        shell.sendCommand(encodeCommand(CMD_REFRESH));
+      }
+    }
+  });
+}
+
+/**
+ * Round trip of a ping carrying `seq` (u32) in µs, or null if no pong came back within a second
+ *
+ * The daemon answers by publishing at once with a pong (client ID and seq, both u32)
+ * after the state. It keeps only the last one, so clients pinging at the same time
+ * can miss their reply.
+ */
+async function ping(shell, seq) {
+  const start = process.hrtime.bigint();
+  if (!shell.sendCommand(encodeCommand(CMD_PING, seq | 0))) return null;
+  for (;;) {
+    const data = shell.readData();
+    if (data.length >= STATE_SIZE + 8 &&
+        data.readUInt32LE(STATE_SIZE) === shell.clientId &&
+        data.readUInt32LE(STATE_SIZE + 4) === seq) {
+      return Number(process.hrtime.bigint() - start) / 1000;
+    }
+    const remainingMs = 1000 - Number(process.hrtime.bigint() - start) / 1e6;
+    if (remainingMs <= 0) return null;
+    await shell.waitForUpdate(Math.ceil(remainingMs));
+  }
+}
+
+/** `--ping COUNT` from the command line, or 0 */
+function pingArg() {
+  const i = process.argv.indexOf('--ping');
+  return i === -1 ? 0 : parseInt(process.argv[i + 1], 10) || 0;
+}
+
+/** The channel of one of several instances: `CHANNEL_NAME-SUFFIX`, or CHANNEL_NAME itself for no suffix */
+function channelName(suffix) {
+  return suffix ? `${CHANNEL_NAME}-${suffix}` : CHANNEL_NAME;
+}
+
+/** The channel `--channel-suffix SUFFIX` on the command line names */
+function channelArg() {
+  const i = process.argv.indexOf('--channel-suffix');
+  return channelName(i === -1 ? '' : process.argv[i + 1]);
+}
+
+/** Time `count` round trips through the daemon, then exit (1 if none came back) */
+async function runPing(shell, count) {
+  // A pong for an earlier client with the same ID may still be published
+  let seq = Number(process.hrtime.bigint() & 0xFFFFFFFFn);
+  const rtts = [];
+  for (let i = 1; i <= count; i++) {
+    seq = (seq + 1) >>> 0;
+    const rttUs = await ping(shell, seq);
+    if (rttUs === null) {
+      console.log(`⚠️  No reply to ping #${i}`);
+      continue;
+    }
+    console.log(`🏓 Pong #${i}: ${rttUs.toFixed(1)} µs`);
+    rtts.push(rttUs);
+    await new Promise((resolve) => setTimeout(resolve, 100));
+  }
+  shell.close();
+  if (rtts.length === 0) process.exit(1);
+  const avg = rtts.reduce((a, b) => a + b, 0) / rtts.length;
+  console.log(`📊 Round trip: ${rtts.length}/${count} replies, min ${Math.min(...rtts).toFixed(1)} µs, ` +
+    `avg ${avg.toFixed(1)} µs, max ${Math.max(...rtts).toFixed(1)} µs`);
+  process.exit(0);
+}
+"#;
+
+fn index_js(config: &ProjectConfig) -> String {
+    if !config.schema.has_demo_fields() {
+        return generic_index_js(config);
+    }
+    
+    format!(r#"#!/usr/bin/env node
+/**
+ * {name} - VenomMemory Node.js Client - with Benchmarking
+ *
+ * Demonstrates connecting to daemon and reading system stats.
+ * Includes read latency measurements.
+ */
+
+'use strict';
+
+const {{ VenomShell }} = require('venom-node');
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Configuration
+// ═══════════════════════════════════════════════════════════════════════════
+
+const CHANNEL_NAME = '{channel}';
+const MAGIC = 0x{magic:08X};
+const STATE_SIZE = {state_size};
+
+// ANSI colors
+const cyan = '\x1B[96m';
+const reset = '\x1B[0m';
+
+// ═══════════════════════════════════════════════════════════════════════════
+// State Structure (matches C struct layout exactly, generated from venom.toml)
+// ═══════════════════════════════════════════════════════════════════════════
+
+{parse}
+function memoryUsagePercent(state) {{
+  return state.memoryTotalMb > 0 ? (state.memoryUsedMb / state.memoryTotalMb) * 100 : 0;
+}}
+
+function uptimeFormatted(state) {{
+  const secs = Number(state.uptimeSeconds);
+  return `${{Math.floor(secs / 3600)}}h ${{Math.floor((secs % 3600) / 60)}}m`;
+}}
+
+{keys}
+// ═══════════════════════════════════════════════════════════════════════════
+// Main
+// ═══════════════════════════════════════════════════════════════════════════
+
+function main() {{
+  console.log('🖥️  {name} Client (Node.js)');
+  console.log('═══════════════════════════════════════════════════════════════');
+
+  // Latency tracking
+  let latencyMin = Infinity;
+  let latencyMax = 0;
+  let latencySum = 0;
+  let latencyCount = 0;
+  let frame = 0;
+
+  let shell;
+  try {{
+    shell = VenomShell.connect(channelArg());
+  }} catch (e) {{
+    console.log(`❌ Error: ${{e.message}}`);
+    console.log('\nMake sure:');
+    console.log('  1. The daemon is running: cd daemon && make run');
+    console.log('  2. venom-node is installed: npm install');
+    process.exit(1);
+  }}
+
+  console.log(`✅ Connected! Client ID: ${{shell.clientId}} (libvenom_memory ${{shell.libraryVersion}})`);
+  if (pingArg() > 0) {{
+    runPing(shell, pingArg());
+    return;
+  }}
+  console.log('📊 Reading system stats... (Ctrl+C to exit)\n');
+  listenForKeys(shell);
+
+  // Handle Ctrl+C for final stats
+  process.on('SIGINT', () => {{
+    console.log('\n');
+    console.log(`📊 ${{cyan}}Final Latency Stats (Node.js):${{reset}}`);
+    console.log(`   Samples: ${{latencyCount}}`);
+    console.log(`   Min: ${{latencyMin.toFixed(2)}} µs`);
+    console.log(`   Max: ${{latencyMax.toFixed(2)}} µs`);
+    console.log(`   Avg: ${{(latencySum / latencyCount).toFixed(2)}} µs`);
+    console.log('\n👋 Goodbye!');
+    shell.close();
+    process.exit(0);
+  }});
+
+  setInterval(() => {{
+    // ═══════════════════════════════════════════════════════════════════
+    // 📊 BENCHMARK: Measure read latency
+    // ═══════════════════════════════════════════════════════════════════
+    const start = process.hrtime.bigint();
+    const {{ data, ageUs }} = shell.readDataStamped();
+    const state = parseState(data);
+    const latencyUs = Number(process.hrtime.bigint() - start) / 1000;
+
+    // Update stats
+    if (latencyUs < latencyMin) latencyMin = latencyUs;
+    if (latencyUs > latencyMax) latencyMax = latencyUs;
+    latencySum += latencyUs;
+    latencyCount++;
+    const avgUs = latencySum / latencyCount;
+
+    if (!state) {{
+      console.log('⏳ Waiting for valid data from daemon...');
+      return;
+    }}
+
+    // Clear screen and move cursor to top
+    process.stdout.write('\x1B[2J\x1B[H');
+
+    console.log('╔═══════════════════════════════════════════════════════════════╗');
+    console.log(`║  🖥️  {name} Monitor (Node.js)    Frame: ${{String(frame).padEnd(6)}}         ║`);
+    console.log('╠═══════════════════════════════════════════════════════════════╣');
+    console.log(`║  CPU: ${{state.cpuUsagePercent.toFixed(1).padStart(5)}}%  |  ` +
+      `RAM: ${{state.memoryUsedMb}}/${{state.memoryTotalMb}} MB  |  ` +
+      `Uptime: ${{uptimeFormatted(state)}}  ║`);
+    console.log('╠═══════════════════════════════════════════════════════════════╣');
+
+    // Show per-core usage (all cores)
+    for (let i = 0; i < state.coreCount; i++) {{
+      const usage = state.cpuCores[i].toFixed(1).padStart(5);
+      console.log(`║  Core ${{i}}: ${{usage}}%                                                ║`);
+    }}
+
+    console.log('╠═══════════════════════════════════════════════════════════════╣');
+    console.log(`║  Memory: ${{memoryUsagePercent(state).toFixed(1)}}% used                                           ║`);
+    console.log('╠═══════════════════════════════════════════════════════════════╣');
+    console.log(`║  📊 ${{cyan}}Read Latency:${{reset}} ${{latencyUs.toFixed(2)}} µs (min: ${{latencyMin.toFixed(2)}}, max: ${{latencyMax.toFixed(2)}}, avg: ${{avgUs.toFixed(2)}})  ║`);
+    console.log(`║  📡 ${{cyan}}Daemon→client:${{reset}} ${{ageUs.toFixed(2)}} µs since the write                 ║`);
+    console.log('╚═══════════════════════════════════════════════════════════════╝');
+    console.log(`  Updates: ${{state.updateCounter}} | Interval: ${{intervalMs}} ms`);
+    console.log('  +/- faster/slower | r refresh | Press Ctrl+C to exit');
+    frame++;
+  }}, 100);
+}}
+
+main();
+"#,
+        name = config.name,
+        channel = config.channel,
+        magic = config.magic,
+        state_size = config.schema.size,
+        parse = parse_state_js(config),
+        keys = COMMAND_KEYS
+    )
+}
+
+/// `parseState()` for the schema's layout
+fn parse_state_js(config: &ProjectConfig) -> String {
+    let fields: String = config.schema.data_fields()
+        .map(|f| {
+            let value = match f.len {
+                Some(n) => format!(
+                    "Array.from({{ length: {} }}, (_, i) => {})",
+                    n,
+                    js_read(f.ty, &format!("{} + i * {}", f.offset, f.ty.size()))
+                ),
+                None => js_read(f.ty, &f.offset.to_string()),
+            };
+            format!("    {}: {},\n", camel_case(&f.name), value)
+        })
+        .collect();
+    
+    format!(r#"/** Parse {pascal}State from raw bytes (must match C struct layout) */
+function parseState(buf) {{
+  if (buf.length < STATE_SIZE) return null;
+
+  const state = {{
+{fields}  }};
+  return state.magic === MAGIC ? state : null;
+}}
+"#, pascal = config.names().pascal, fields = fields)
+}
+
+/// `Buffer` read of one element at `offset` (64-bit integers come back as BigInt)
+fn js_read(ty: FieldType, offset: &str) -> String {
+    let method = match ty {
+        FieldType::U8 => "readUInt8",
+        FieldType::I8 => "readInt8",
+        FieldType::U16 => "readUInt16LE",
+        FieldType::I16 => "readInt16LE",
+        FieldType::U32 => "readUInt32LE",
+        FieldType::I32 => "readInt32LE",
+        FieldType::U64 => "readBigUInt64LE",
+        FieldType::I64 => "readBigInt64LE",
+        FieldType::F32 => "readFloatLE",
+        FieldType::F64 => "readDoubleLE",
+    };
+    format!("buf.{}({})", method, offset)
+}
+
+/// Client for a custom schema: prints every field of the state
+fn generic_index_js(config: &ProjectConfig) -> String {
+    let fields: String = config.schema.user_fields()
+        .map(|f| format!(
+            "    console.log(`  {:<24} ${{state.{}}}`);\n",
+            f.name,
+            camel_case(&f.name)
+        ))
+        .collect();
+    
+    format!(r#"#!/usr/bin/env node
+/**
+ * {name} - VenomMemory Node.js Client - prints every field of the state (see venom.toml)
+ */
+
+'use strict';
+
+const {{ VenomShell }} = require('venom-node');
+
+const CHANNEL_NAME = '{channel}';
+const MAGIC = 0x{magic:08X};
+const STATE_SIZE = {state_size};
+
+{parse}
+{keys}
+function main() {{
+  let latencyMin = Infinity;
+  let latencyMax = 0;
+  let latencySum = 0;
+  let latencyCount = 0;
+
+  let shell;
+  try {{
+    shell = VenomShell.connect(channelArg());
+  }} catch (e) {{
+    console.log(`❌ Error: ${{e.message}}`);
+    process.exit(1);
+  }}
+
+  console.log(`✅ Connected! Client ID: ${{shell.clientId}} (libvenom_memory ${{shell.libraryVersion}})`);
+  if (pingArg() > 0) {{
+    runPing(shell, pingArg());
+    return;
+  }}
+  listenForKeys(shell);
+
+  process.on('SIGINT', () => {{
+    shell.close();
+    process.exit(0);
+  }});
+
+  setInterval(() => {{
+    const start = process.hrtime.bigint();
+    const {{ data, ageUs }} = shell.readDataStamped();
+    const state = parseState(data);
+    const latencyUs = Number(process.hrtime.bigint() - start) / 1000;
+
+    if (latencyUs < latencyMin) latencyMin = latencyUs;
+    if (latencyUs > latencyMax) latencyMax = latencyUs;
+    latencySum += latencyUs;
+    latencyCount++;
+
+    if (!state) {{
+      console.log('⏳ Waiting for valid data from daemon...');
+      return;
+    }}
+
+    process.stdout.write('\x1B[2J\x1B[H');
+    console.log(`🖥️  {name} | State v${{state.version}} | ${{STATE_SIZE}} bytes`);
+    console.log('═══════════════════════════════════════════════════════════════');
+{fields}    console.log('═══════════════════════════════════════════════════════════════');
+    console.log(`📊 Read Latency: ${{latencyUs.toFixed(2)}} µs (min: ${{latencyMin.toFixed(2)}}, max: ${{latencyMax.toFixed(2)}}, avg: ${{(latencySum / latencyCount).toFixed(2)}})`);
+    console.log(`📡 Daemon→client: ${{ageUs.toFixed(2)}} µs since the write`);
+    console.log(`⏱️  Daemon interval: ${{intervalMs}} ms | +/- faster/slower | r refresh`);
+  }}, 100);
+}}
+
+main();
+"#,
+        name = config.name,
+        channel = config.channel,
+        magic = config.magic,
+        state_size = config.schema.size,
+        parse = parse_state_js(config),
+        fields = fields,
+        keys = COMMAND_KEYS
+    )
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// README
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn readme(config: &ProjectConfig) -> String {
+    format!(r#"# {name} (Node.js + C Daemon)
+
+VenomMemory project with C daemon and Node.js client (venom-node N-API addon).
+
+## Quick Start
+
+```bash
+# Once - build and install the venom-node addon
+# (from a checkout of venom_memory_rs)
+(cd venom_memory_rs/venom-node && npm install && npm run build)
+npm install venom_memory_rs/venom-node
+
+# Terminal 1 - Start C daemon
+cd daemon && make run
+
+# Terminal 2 - Start Node.js client
+npm start
+```
+
+## Structure
+
+```
+{name}/
+├── daemon/           # C daemon (system monitor)
+│   ├── src/main.c
+│   └── Makefile
+├── shared/           # Shared protocol
+│   └── protocol.h
+├── index.js          # Node.js status bar
+├── package.json
+└── lib/
+    └── libvenom_memory.so
+```
+
+## Usage in Your Code
+
+```js
+const {{ VenomShell }} = require('venom-node');
+
+const shell = VenomShell.connect('{channel}');
+console.log(`Connected! ID: ${{shell.clientId}}`);
+
+// Poll...
+const data = shell.readIfChanged();     // Buffer, or null if nothing new
+
+// ...or await updates without blocking the event loop
+const next = await shell.waitForUpdate(1000);
+
+shell.close();
+```
+
+## Configuration
+
+| Setting | Value |
+|---------|-------|
+| Channel | `{channel}` |
+| Magic | `0x{magic:08X}` |
+{ping}{instances}{service}"#,
+        name = config.name,
+        ping = super::ping_readme_section("npm start -- --ping 10"),
+        instances = super::instances_readme_section(&config.channel, "cd daemon && make run ARGS=\"--channel-suffix SUFFIX\"", "npm start -- --channel-suffix SUFFIX", "`channelName()` in the client"),
+        service = super::service::readme_section(config),
+        channel = config.channel,
+        magic = config.magic
+    )
+}
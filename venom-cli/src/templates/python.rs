@@ -5,7 +5,8 @@
 //! - Python client with ctypes FFI bindings
 //! - Bundled libvenom_memory.so
 
-use super::ProjectConfig;
+use super::{DecodedField, DecoderOffsets, ProjectConfig, PythonBinding};
+use crate::schema::FieldType;
 
 pub fn generate(config: &ProjectConfig) {
     let base = &config.output_dir;
@@ -23,40 +24,36 @@ pub fn generate(config: &ProjectConfig) {
     crate::write_file(&format!("{}/daemon/Makefile", base), &daemon_makefile(config));
     
     // Python client
-    crate::write_file(&format!("{}/venom_binding.py", base), &venom_binding(config));
-    crate::write_file(&format!("{}/client.py", base), &client_py(config));
+    generate_client(config);
     
     // README
     crate::write_file(&format!("{}/README.md", base), &readme(config));
 }
 
-fn magic(channel: &str) -> u32 {
-    channel.bytes().fold(0x564E4Fu32, |acc, b| acc.wrapping_add(b as u32))
+/// Client files only (used by `venom add-client`)
+pub fn generate_client(config: &ProjectConfig) {
+    let base = &config.output_dir;
+    
+    crate::write_file(&format!("{}/venom_binding.py", base), &venom_binding(config));
+    crate::write_file(&format!("{}/client.py", base), &client_py(config));
+    crate::write_file(&format!("{}/test_venom_binding.py", base), &test_binding_py(config));
+    if config.python_async {
+        crate::write_file(&format!("{}/venom_async.py", base), &venom_async_py(config));
+        crate::write_file(&format!("{}/main_async.py", base), &main_async_py(config));
+        crate::write_file(&format!("{}/test_venom_async.py", base), &test_async_py(config));
+    }
+    state_layout(config).offsets.write(base);
 }
 
-fn upper_name(name: &str) -> String {
-    name.to_uppercase().replace("-", "_")
-}
 
-fn pascal_case(s: &str) -> String {
-    s.split(|c| c == '_' || c == '-')
-        .map(|word| {
-            let mut chars = word.chars();
-            match chars.next() {
-                None => String::new(),
-                Some(first) => first.to_uppercase().chain(chars).collect(),
-            }
-        })
-        .collect()
-}
 
 // ═══════════════════════════════════════════════════════════════════════════
 // C Protocol Header (shared between daemon and Python client)
 // ═══════════════════════════════════════════════════════════════════════════
 
 fn protocol_h(config: &ProjectConfig) -> String {
-    let upper = upper_name(&config.name);
-    let pascal = pascal_case(&config.name);
+    let upper = config.names().upper;
+    let pascal = config.names().pascal;
     
     format!(r#"#ifndef {upper}_PROTOCOL_H
 #define {upper}_PROTOCOL_H
@@ -69,30 +66,27 @@ fn protocol_h(config: &ProjectConfig) -> String {
 #define {upper}_DATA_SIZE {data_size}
 #define {upper}_CMD_SLOTS {cmd_slots}
 #define {upper}_MAX_CLIENTS {max_clients}
-#define {upper}_MAX_CORES 16
-
+#define {upper}_STATE_SIZE {state_size}
+{max_cores}
 typedef struct __attribute__((packed)) {{
-    uint32_t magic;
-    uint32_t version;
-    float cpu_usage_percent;
-    float cpu_cores[{upper}_MAX_CORES];
-    uint32_t core_count;
-    uint32_t memory_used_mb;
-    uint32_t memory_total_mb;
-    uint64_t uptime_seconds;
-    uint64_t update_counter;
-    uint64_t timestamp_ns;
-}} {pascal}State;
+{fields}}} {pascal}State;
+
+_Static_assert(sizeof({pascal}State) == {upper}_STATE_SIZE, "{pascal}State size mismatch");
 
+{commands}
 #endif // {upper}_PROTOCOL_H
 "#,
         upper = upper,
         pascal = pascal,
         channel = config.channel,
-        magic = magic(&config.channel),
+        magic = config.magic,
         data_size = config.data_size,
         cmd_slots = config.cmd_slots,
-        max_clients = config.max_clients
+        max_clients = config.max_clients,
+        state_size = config.schema.size,
+        max_cores = super::c::max_cores_define(config),
+        fields = config.schema.c_fields("    "),
+        commands = super::c::commands_h(config),
     )
 }
 
@@ -101,8 +95,11 @@ typedef struct __attribute__((packed)) {{
 // ═══════════════════════════════════════════════════════════════════════════
 
 fn daemon_main(config: &ProjectConfig) -> String {
-    let upper = upper_name(&config.name);
-    let pascal = pascal_case(&config.name);
+    if !config.schema.has_demo_fields() {
+        return super::c::generic_daemon_c(config, "../shared/protocol.h");
+    }
+    let upper = config.names().upper;
+    let pascal = config.names().pascal;
     
     format!(r#"/**
  * {name} System Monitor Daemon
@@ -119,10 +116,11 @@ fn daemon_main(config: &ProjectConfig) -> String {
 #include "../shared/protocol.h"
 
 typedef struct VenomDaemonHandle VenomDaemonHandle;
-typedef struct {{ size_t data_size; size_t cmd_slots; size_t max_clients; }} VenomConfig;
-extern VenomDaemonHandle* venom_daemon_create(const char* name, VenomConfig config);
+typedef struct {{ size_t struct_size; size_t data_size; size_t cmd_slots; size_t max_clients; size_t cmd_payload_size; size_t cmd_full_policy; size_t shm_mode; }} VenomConfigEx;
+extern VenomDaemonHandle* venom_daemon_create_ex(const char* name, const VenomConfigEx* config);
 extern void venom_daemon_destroy(VenomDaemonHandle* handle);
 extern void venom_daemon_write_data(VenomDaemonHandle* handle, const uint8_t* data, size_t len);
+extern size_t venom_daemon_try_recv_command(VenomDaemonHandle* handle, uint8_t* buf, size_t max_len, uint32_t* out_client_id);
 
 static VenomDaemonHandle* g_daemon = NULL;
 static {pascal}State g_state = {{0}};
@@ -132,6 +130,8 @@ static uint64_t prev_idle[{upper}_MAX_CORES + 1] = {{0}};
 
 static void signal_handler(int sig) {{ (void)sig; g_running = 0; }}
 
+{commands}
+{channel_arg}
 static void read_cpu_stats(void) {{
     FILE* f = fopen("/proc/stat", "r");
     if (!f) return;
@@ -179,24 +179,30 @@ static void read_uptime(void) {{
     fclose(f);
 }}
 
-int main(void) {{
+int main(int argc, char** argv) {{
     printf("🖥️  {name} System Monitor Daemon\n");
     printf("═══════════════════════════════════════════════════════════════\n");
     signal(SIGINT, signal_handler);
     signal(SIGTERM, signal_handler);
     
-    VenomConfig config = {{ .data_size = {upper}_DATA_SIZE, .cmd_slots = {upper}_CMD_SLOTS, .max_clients = {upper}_MAX_CLIENTS }};
-    g_daemon = venom_daemon_create({upper}_CHANNEL_NAME, config);
+    VenomConfigEx config = {{ .struct_size = sizeof(VenomConfigEx), .data_size = {upper}_DATA_SIZE, .cmd_slots = {upper}_CMD_SLOTS, .max_clients = {upper}_MAX_CLIENTS, .cmd_payload_size = sizeof({pascal}Command), .shm_mode = 0{shm_mode:o} }};
+    const char* channel = channel_from_args(argc, argv);
+    g_daemon = venom_daemon_create_ex(channel, &config);
     if (!g_daemon) {{ printf("❌ Failed to create channel\n"); return 1; }}
     
     g_state.magic = {upper}_MAGIC;
     g_state.version = 1;
     
-    printf("✅ Channel: %s\n", {upper}_CHANNEL_NAME);
+    printf("✅ Channel: %s\n", channel);
     printf("🐍 Python client can connect now!\n");
     printf("🚀 Publishing... (Ctrl+C to stop)\n\n");
     
+    uint64_t next_publish = 0;
     while (g_running) {{
+        int refresh = handle_commands(g_daemon);
+        if (!refresh && now_ms() < next_publish) {{ usleep(10000); continue; }}
+        next_publish = now_ms() + g_interval_ms;
+        
         read_cpu_stats();
         read_memory_stats();
         read_uptime();
@@ -204,28 +210,27 @@ int main(void) {{
         struct timespec ts;
         clock_gettime(CLOCK_MONOTONIC, &ts);
         g_state.timestamp_ns = (uint64_t)ts.tv_sec * 1000000000ULL + ts.tv_nsec;
-        venom_daemon_write_data(g_daemon, (const uint8_t*)&g_state, sizeof(g_state));
+        publish(g_daemon, &g_state);
         
-        printf("\r🖥️  CPU: %5.1f%% | RAM: %u/%u MB | Uptime: %luh%lum | #%lu   ",
+        printf("\r🖥️  CPU: %5.1f%% | RAM: %u/%u MB | Uptime: %luh%lum | #%lu | %u ms   ",
             g_state.cpu_usage_percent, g_state.memory_used_mb, g_state.memory_total_mb,
             (unsigned long)(g_state.uptime_seconds / 3600), (unsigned long)((g_state.uptime_seconds % 3600) / 60),
-            (unsigned long)g_state.update_counter);
+            (unsigned long)g_state.update_counter, g_interval_ms);
         fflush(stdout);
-        usleep(100000);
     }}
     venom_daemon_destroy(g_daemon);
     printf("\n\n👋 Goodbye!\n");
     return 0;
 }}
-"#, name = config.name, upper = upper, pascal = pascal)
+"#, name = config.name, upper = upper, pascal = pascal, shm_mode = config.shm_mode, commands = super::c::command_handler_c(config), channel_arg = super::c::channel_arg_c(config))
 }
 
 fn daemon_makefile(config: &ProjectConfig) -> String {
     format!(r#"# {name} Daemon Makefile
 
-CC = gcc
+{target_var}CC = gcc
 CFLAGS = -Wall -Wextra -O2 -I../shared
-LDFLAGS = -L../lib -lvenom_memory -Wl,-rpath,'$$ORIGIN/../lib'
+LDFLAGS = -L{lib} -lvenom_memory -Wl,-rpath,'$$ORIGIN/{lib}'
 
 TARGET = {name}_daemon
 SOURCES = src/main.c
@@ -243,16 +248,28 @@ clean:
 	@rm -f $(TARGET)
 
 run: $(TARGET)
-	@./$(TARGET)
-"#, name = config.name)
+	@./$(TARGET) $(ARGS)
+"#,
+        name = config.name,
+        target_var = super::make_target_var(config),
+        lib = super::make_lib_dir(config, "../lib"),
+    )
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
 // Python Bindings
 // ═══════════════════════════════════════════════════════════════════════════
 
-fn venom_binding(config: &ProjectConfig) -> String {
-    let pascal = pascal_case(&config.name);
+pub(super) fn venom_binding(config: &ProjectConfig) -> String {
+    let pascal = config.names().pascal;
+    let schema = &config.schema;
+    
+    let StateLayout { format: layout, fields, decode, .. } = state_layout(config);
+    let demo = schema.has_demo_fields();
+    let max_cores = match schema.field("cpu_cores") {
+        Some(f) if demo => format!("MAX_CORES = {}\n", f.count()),
+        _ => String::new(),
+    };
     
     format!(r#"#!/usr/bin/env python3
 """
@@ -260,11 +277,13 @@ VenomMemory Python Bindings for {name}
 
 Provides:
 - {pascal}State: System stats from daemon
+- encode_command: Commands for the daemon (CMD_REFRESH, CMD_SET_INTERVAL, CMD_PING)
 - VenomShell: Connection to daemon via shared memory
 """
 
 import ctypes
 import struct
+import time
 from dataclasses import dataclass
 from typing import List, Optional
 from pathlib import Path
@@ -275,7 +294,16 @@ from pathlib import Path
 
 CHANNEL_NAME = "{channel}"
 MAGIC = 0x{magic:08X}
-MAX_CORES = 16
+STATE_SIZE = {state_size}
+{max_cores}
+# Packed layout from venom.toml, one struct code per field ('x' = padding)
+_LAYOUT = struct.Struct('{layout}')
+assert _LAYOUT.size == STATE_SIZE
+
+def channel_name(suffix: Optional[str] = None) -> str:
+    """The channel of one of several instances: "{channel}-SUFFIX", or
+    CHANNEL_NAME itself for no suffix or an empty one."""
+    return f"{{CHANNEL_NAME}}-{{suffix}}" if suffix else CHANNEL_NAME
 
 # ═══════════════════════════════════════════════════════════════════════════
 # State Structure
@@ -283,22 +311,163 @@ MAX_CORES = 16
 
 @dataclass
 class {pascal}State:
-    """System state published by the daemon."""
-    magic: int
-    version: int
-    cpu_usage_percent: float
-    cpu_cores: List[float]
-    core_count: int
-    memory_used_mb: int
-    memory_total_mb: int
-    uptime_seconds: int
-    update_counter: int
-    timestamp_ns: int
-    
+    """State published by the daemon."""
+{fields}    
     @property
     def is_valid(self) -> bool:
         return self.magic == MAGIC
+{helpers}    
+    @classmethod
+    def from_bytes(cls, data: bytes) -> '{pascal}State':
+        if len(data) < STATE_SIZE:
+            return cls.empty()
+        v = _LAYOUT.unpack_from(data, 0)
+        return cls({decode})
     
+    @classmethod
+    def empty(cls) -> '{pascal}State':
+        return cls.from_bytes(bytes(STATE_SIZE))
+
+# ═══════════════════════════════════════════════════════════════════════════
+# 📨 Commands (Clients send, Daemon applies)
+# ═══════════════════════════════════════════════════════════════════════════
+
+INTERVAL_MS = 100     # Default publish interval
+CMD_REFRESH = 1       # Publish immediately
+CMD_SET_INTERVAL = 2  # value: publish interval in ms (10-10000)
+CMD_PING = 3          # value: sequence number, echoed back in a pong
+SEND_TIMEOUT_MS = 100 # How long send_command waits for room in a full queue
+
+# Packed Command, a typed command: cmd u16 (the tag), len u16 (4, the
+# payload's size), value i32
+_COMMAND = struct.Struct('<HHi')
+# Reply to the last CMD_PING, published right after the state: client_id u32, seq u32
+_PONG = struct.Struct('<II')
+
+def encode_command(cmd: int, value: int = 0) -> bytes:
+    return _COMMAND.pack(cmd, 4, value)
+
+{shell}
+if __name__ == "__main__":
+    print(f"Channel: {{CHANNEL_NAME}} | Magic: 0x{{MAGIC:08X}}")
+    try:
+        with VenomShell() as shell:
+            print(f"Connected! ID: {{shell.client_id}}")
+            print(shell.read_state())
+    except Exception as e:
+        print(f"Error: {{e}}")
+"#,
+        name = config.name,
+        channel = config.channel,
+        magic = config.magic,
+        pascal = pascal,
+        state_size = schema.size,
+        max_cores = max_cores,
+        layout = layout,
+        fields = fields,
+        helpers = if demo { DEMO_HELPERS } else { "" },
+        decode = decode.join(",\n                   "),
+        shell = match config.python_binding {
+            PythonBinding::Ctypes => shell_ctypes(config, &pascal),
+            PythonBinding::Pyo3 => shell_pyo3(&pascal),
+        }
+    )
+}
+
+/// Tests of `venom_binding.py`: a golden decode that always runs and a live
+/// read gated on `VENOM_LIVE_TEST`
+fn test_binding_py(config: &ProjectConfig) -> String {
+    let mut asserts = String::new();
+    for (f, values) in config.schema.golden(config.magic) {
+        let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+        let expected = match f.len {
+            Some(_) => format!("[{}]", values.join(", ")),
+            None => values.join(""),
+        };
+        asserts.push_str(&format!("    assert state.{0} == {1}, state.{0}\n", f.name, expected));
+    }
+
+    format!(r#"#!/usr/bin/env python3
+"""
+Tests for venom_binding.py
+
+test_decodes_golden_state decodes a state built from venom.toml, so it
+fails when {pascal}State no longer matches the daemon's layout.
+test_reads_live_state needs the daemon running and {live_env}=1.
+
+Run with pytest, or without it: python3 test_venom_binding.py
+"""
+
+import os
+import time
+import unittest
+
+from venom_binding import (CHANNEL_NAME, CMD_SET_INTERVAL, MAGIC, STATE_SIZE, {pascal}State, VenomShell,
+                           channel_name, encode_command)
+
+# Every field holds a distinct known value (see test_decodes_golden_state)
+GOLDEN = bytes([
+{golden}])
+
+
+def test_decodes_golden_state():
+    assert len(GOLDEN) == STATE_SIZE
+    state = {pascal}State.from_bytes(GOLDEN)
+{asserts}
+
+def test_short_buffer_is_invalid():
+    assert not {pascal}State.from_bytes(GOLDEN[:-1]).is_valid
+
+
+def test_appends_channel_suffix():
+    assert channel_name("2") == CHANNEL_NAME + "-2"
+    assert channel_name("") == channel_name(None) == CHANNEL_NAME
+
+
+def test_encodes_typed_command():
+    # Tag, payload length, then the value, little-endian
+    assert encode_command(CMD_SET_INTERVAL, 250) == bytes([2, 0, 4, 0, 250, 0, 0, 0])
+
+
+def test_reads_live_state():
+    if not os.environ.get("{live_env}"):
+        raise unittest.SkipTest("set {live_env}=1 with the daemon running")
+    with VenomShell() as shell:
+        # The daemon may not have published yet
+        for _ in range(50):
+            state = shell.read_state()
+            if state.is_valid:
+                break
+            time.sleep(0.02)
+    assert state.magic == MAGIC, hex(state.magic)
+    assert state.version == 1, state.version
+
+
+if __name__ == "__main__":
+    import sys
+    failed = 0
+    for name, test in list(globals().items()):
+        if not name.startswith("test_"):
+            continue
+        try:
+            test()
+            print(f"{{name}} ... ok")
+        except unittest.SkipTest as e:
+            print(f"{{name}} ... skipped: {{e}}")
+        except Exception as e:
+            failed += 1
+            print(f"{{name}} ... FAILED: {{e!r}}")
+    sys.exit(1 if failed else 0)
+"#,
+        pascal = config.names().pascal,
+        golden = super::byte_rows(&config.schema.golden_bytes(config.magic), "    "),
+        asserts = asserts,
+        live_env = super::LIVE_TEST_ENV
+    )
+}
+
+/// Convenience properties used by the system-monitor demo
+const DEMO_HELPERS: &str = r#"    
     @property
     def memory_usage_percent(self) -> float:
         if self.memory_total_mb > 0:
@@ -309,36 +478,90 @@ class {pascal}State:
     def uptime_formatted(self) -> str:
         hours = self.uptime_seconds // 3600
         minutes = (self.uptime_seconds % 3600) // 60
-        return f"{{hours}}h {{minutes}}m"
-    
-    @classmethod
-    def from_bytes(cls, data: bytes) -> '{pascal}State':
-        if len(data) < 112:
-            return cls.empty()
-        magic, version, cpu_usage = struct.unpack_from('<IIf', data, 0)
-        cpu_cores = list(struct.unpack_from('<16f', data, 12))
-        core_count, mem_used, mem_total = struct.unpack_from('<III', data, 76)
-        uptime, counter, timestamp = struct.unpack_from('<QQQ', data, 88)
-        return cls(magic=magic, version=version, cpu_usage_percent=cpu_usage,
-                   cpu_cores=cpu_cores, core_count=core_count,
-                   memory_used_mb=mem_used, memory_total_mb=mem_total,
-                   uptime_seconds=uptime, update_counter=counter, timestamp_ns=timestamp)
-    
-    @classmethod
-    def empty(cls) -> '{pascal}State':
-        return cls(magic=0, version=0, cpu_usage_percent=0.0,
-                   cpu_cores=[0.0] * MAX_CORES, core_count=0,
-                   memory_used_mb=0, memory_total_mb=0,
-                   uptime_seconds=0, update_counter=0, timestamp_ns=0)
+        return f"{hours}h {minutes}m"
+"#;
 
-# ═══════════════════════════════════════════════════════════════════════════
-# VenomShell - Connection to Daemon
-# ═══════════════════════════════════════════════════════════════════════════
+/// The state as `venom_binding.py` decodes it: `struct` format, dataclass
+/// fields, one keyword argument per field, and where the format reads each
+/// field
+struct StateLayout {
+    format: String,
+    fields: String,
+    decode: Vec<String>,
+    offsets: DecoderOffsets,
+}
 
-def _find_library() -> str:
-    script_dir = Path(__file__).parent.absolute()
-    locations = [
-        script_dir / "lib" / "libvenom_memory.so",
+fn state_layout(config: &ProjectConfig) -> StateLayout {
+    let mut format = String::from("<");
+    let mut fields = String::new();
+    let mut decode = Vec::new();
+    let mut decoded = Vec::new();
+    let mut idx = 0;
+    let mut pos = 0;
+    for f in &config.schema.fields {
+        if f.padding {
+            format.push_str(&format!("{}x", f.size()));
+            pos += f.size();
+            continue;
+        }
+        let (code, py_type) = struct_code(f.ty);
+        let size = f.count() * code_size(code);
+        decoded.push(DecodedField { name: f.name.clone(), offset: pos, size });
+        pos += size;
+        match f.len {
+            Some(n) => {
+                format.push_str(&format!("{}{}", n, code));
+                fields.push_str(&format!("    {}: List[{}]\n", f.name, py_type));
+                decode.push(format!("{}=list(v[{}:{}])", f.name, idx, idx + n));
+                idx += n;
+            }
+            None => {
+                format.push(code);
+                fields.push_str(&format!("    {}: {}\n", f.name, py_type));
+                decode.push(format!("{}=v[{}]", f.name, idx));
+                idx += 1;
+            }
+        }
+    }
+    let offsets = DecoderOffsets { decoder: "venom_binding.py".to_string(), size: pos, fields: decoded };
+    StateLayout { format, fields, decode, offsets }
+}
+
+/// Standard size of a `struct` format code under `<`
+fn code_size(code: char) -> usize {
+    match code {
+        'B' | 'b' => 1,
+        'H' | 'h' => 2,
+        'I' | 'i' | 'f' => 4,
+        _ => 8,
+    }
+}
+
+/// `struct` module format code and Python type for a field
+fn struct_code(ty: FieldType) -> (char, &'static str) {
+    match ty {
+        FieldType::U8 => ('B', "int"),
+        FieldType::U16 => ('H', "int"),
+        FieldType::U32 => ('I', "int"),
+        FieldType::U64 => ('Q', "int"),
+        FieldType::I8 => ('b', "int"),
+        FieldType::I16 => ('h', "int"),
+        FieldType::I32 => ('i', "int"),
+        FieldType::I64 => ('q', "int"),
+        FieldType::F32 => ('f', "float"),
+        FieldType::F64 => ('d', "float"),
+    }
+}
+
+/// Body of `_find_library`
+///
+/// With several bundled targets every `lib/<triple>/` copy is tried and the
+/// first one that loads on this machine wins.
+fn find_library(config: &ProjectConfig) -> String {
+    let lib = config.at_root("lib");
+    if config.targets.is_empty() {
+        return format!(r#"    locations = [
+        script_dir / "{lib}" / "libvenom_memory.so",
         script_dir / "../lib" / "libvenom_memory.so",
         Path("lib/libvenom_memory.so"),
     ]
@@ -346,52 +569,116 @@ def _find_library() -> str:
         if path.exists():
             return str(path.absolute())
     raise FileNotFoundError(f"libvenom_memory.so not found in: {{locations}}")
+"#, lib = lib);
+    }
+    let mut locations = String::new();
+    for t in &config.targets {
+        let file = crate::library::library_name_for(t);
+        locations.push_str(&format!(
+            "        script_dir / \"{lib}\" / \"{t}\" / \"{file}\",\n        script_dir / \"../lib\" / \"{t}\" / \"{file}\",\n        Path(\"lib/{t}/{file}\"),\n",
+            lib = lib,
+            t = t,
+            file = file
+        ));
+    }
+    format!(r#"    # One library per bundled target; the first that loads was built for this machine
+    locations = [
+{locations}    ]
+    for path in locations:
+        if path.exists():
+            try:
+                ctypes.CDLL(str(path))
+            except OSError:
+                continue  # built for another target
+            return str(path.absolute())
+    raise FileNotFoundError(f"no libvenom_memory for this machine in: {{locations}}")
+"#, locations = locations)
+}
 
+fn shell_ctypes(config: &ProjectConfig, pascal: &str) -> String {
+    format!(r#"# ═══════════════════════════════════════════════════════════════════════════
+# VenomShell - Connection to Daemon
+# ═══════════════════════════════════════════════════════════════════════════
+
+def _find_library() -> str:
+    script_dir = Path(__file__).parent.absolute()
+{find_library}
 
 class VenomShell:
     """Connection to VenomMemory daemon."""
     
     _lib: Optional[ctypes.CDLL] = None
     
-    def __init__(self, channel_name: str = CHANNEL_NAME):
+    def __init__(self, channel_name: str = CHANNEL_NAME, client_name: Optional[str] = None):
+        """client_name is what the daemon shows in its log"""
         self._handle = None
         self._disposed = False  # Initialize BEFORE connection attempt
+        # µs between the daemon's write and the last read (0 if unstamped)
+        self.write_age_us = 0.0
         
         if VenomShell._lib is None:
             VenomShell._lib = ctypes.CDLL(_find_library())
             self._setup_bindings()
         
         channel_bytes = channel_name.encode('utf-8')
-        self._handle = VenomShell._lib.venom_shell_connect(channel_bytes)
+        name_bytes = client_name.encode('utf-8') if client_name else None
+        self._handle = VenomShell._lib.venom_shell_connect_named(channel_bytes, name_bytes)
         
         if not self._handle:
             raise ConnectionError(f"Failed to connect to '{{channel_name}}'. Is daemon running?")
     
     def _setup_bindings(self):
         lib = VenomShell._lib
-        lib.venom_shell_connect.argtypes = [ctypes.c_char_p]
-        lib.venom_shell_connect.restype = ctypes.c_void_p
+        lib.venom_shell_connect_named.argtypes = [ctypes.c_char_p, ctypes.c_char_p]
+        lib.venom_shell_connect_named.restype = ctypes.c_void_p
         lib.venom_shell_destroy.argtypes = [ctypes.c_void_p]
         lib.venom_shell_destroy.restype = None
-        lib.venom_shell_read_data.argtypes = [ctypes.c_void_p, ctypes.POINTER(ctypes.c_uint8), ctypes.c_size_t]
-        lib.venom_shell_read_data.restype = ctypes.c_size_t
+        lib.venom_shell_read_data_stamped.argtypes = [ctypes.c_void_p, ctypes.POINTER(ctypes.c_uint8), ctypes.c_size_t, ctypes.POINTER(ctypes.c_uint64)]
+        lib.venom_shell_read_data_stamped.restype = ctypes.c_size_t
+        lib.venom_clock_ns.argtypes = []
+        lib.venom_clock_ns.restype = ctypes.c_uint64
+        lib.venom_version.argtypes = []
+        lib.venom_version.restype = ctypes.c_char_p
         lib.venom_shell_id.argtypes = [ctypes.c_void_p]
         lib.venom_shell_id.restype = ctypes.c_uint32
+        lib.venom_shell_send_command_timeout.argtypes = [ctypes.c_void_p, ctypes.POINTER(ctypes.c_uint8), ctypes.c_size_t, ctypes.c_uint32]
+        lib.venom_shell_send_command_timeout.restype = ctypes.c_int32
     
     @property
     def client_id(self) -> int:
         self._check_disposed()
         return VenomShell._lib.venom_shell_id(self._handle)
     
+    @property
+    def library_version(self) -> str:
+        """Version of the libvenom_memory that was loaded"""
+        return VenomShell._lib.venom_version().decode()
+    
     def read_raw_data(self, max_len: int = 256) -> bytes:
         self._check_disposed()
         buf = (ctypes.c_uint8 * max_len)()
-        length = VenomShell._lib.venom_shell_read_data(self._handle, buf, max_len)
+        written_ns = ctypes.c_uint64()
+        length = VenomShell._lib.venom_shell_read_data_stamped(self._handle, buf, max_len, ctypes.byref(written_ns))
+        if written_ns.value:
+            self.write_age_us = (VenomShell._lib.venom_clock_ns() - written_ns.value) / 1000.0
         return bytes(buf[:length])
     
     def read_state(self) -> {pascal}State:
-        return {pascal}State.from_bytes(self.read_raw_data(256))
+        return {pascal}State.from_bytes(self.read_raw_data(STATE_SIZE + 64))
+    
+    def send_command(self, cmd: bytes, timeout_ms: int = SEND_TIMEOUT_MS) -> bool:
+        """Queue cmd, waiting up to timeout_ms for room if the daemon has fallen behind; False if the queue stayed full"""
+        self._check_disposed()
+        buf = (ctypes.c_uint8 * len(cmd)).from_buffer_copy(cmd)
+        return VenomShell._lib.venom_shell_send_command_timeout(self._handle, buf, len(cmd), timeout_ms) == 0
+    
+    def set_interval(self, ms: int) -> bool:
+        return self.send_command(encode_command(CMD_SET_INTERVAL, ms))
+    
+    def refresh(self) -> bool:
+        return self.send_command(encode_command(CMD_REFRESH))
     
+{ping}
     def close(self):
         if self._disposed or not self._handle:
             return
@@ -407,29 +694,193 @@ class VenomShell:
     def __exit__(self, *_): self.close()
     def __del__(self): self.close()
 
+"#, pascal = pascal, ping = SHELL_PING, find_library = find_library(config))
+}
 
-if __name__ == "__main__":
-    print(f"Channel: {{CHANNEL_NAME}} | Magic: 0x{{MAGIC:08X}}")
-    try:
-        with VenomShell() as shell:
-            print(f"Connected! ID: {{shell.client_id}}")
-            state = shell.read_state()
-            print(f"CPU: {{state.cpu_usage_percent:.1f}}% | RAM: {{state.memory_used_mb}}/{{state.memory_total_mb}} MB")
-    except Exception as e:
-        print(f"Error: {{e}}")
-"#,
-        name = config.name,
-        channel = config.channel,
-        magic = magic(&config.channel),
-        pascal = pascal
-    )
+fn shell_pyo3(pascal: &str) -> String {
+    format!(r#"# ═══════════════════════════════════════════════════════════════════════════
+# VenomShell - Connection to Daemon (native venom_py extension)
+# ═══════════════════════════════════════════════════════════════════════════
+
+try:
+    import venom_py
+except ImportError as e:
+    raise ImportError(
+        "venom_py is not installed. Build it from the venom_memory_rs repository:\n"
+        "    maturin develop --release -m venom-py/Cargo.toml"
+    ) from e
+
+
+class VenomShell:
+    """Connection to VenomMemory daemon."""
+    
+    def __init__(self, channel_name: str = CHANNEL_NAME, client_name: Optional[str] = None):
+        """client_name is what the daemon shows in its log"""
+        self._shell = None
+        self._disposed = False
+        self._shell = venom_py.Shell(channel_name, client_name=client_name)
+        # Reused across reads so polling doesn't allocate
+        self._buf = bytearray(STATE_SIZE + 64)
+        # µs between the daemon's write and the last read (0 if unstamped)
+        self.write_age_us = 0.0
+    
+    @property
+    def client_id(self) -> int:
+        self._check_disposed()
+        return self._shell.client_id
+    
+    @property
+    def library_version(self) -> str:
+        """Version of the venom_memory venom_py was built from"""
+        return venom_py.LIBRARY_VERSION
+    
+    def read_raw_data(self, max_len: int = 256) -> bytes:
+        self._check_disposed()
+        return self._shell.read_bytes(max_len)
+    
+    def read_state(self) -> {pascal}State:
+        self._check_disposed()
+        length, written_ns = self._shell.read_into_stamped(self._buf)
+        if written_ns:
+            self.write_age_us = (venom_py.clock_ns() - written_ns) / 1000.0
+        return {pascal}State.from_bytes(bytes(self._buf[:min(length, len(self._buf))]))
+    
+    def send_command(self, cmd: bytes, timeout_ms: int = SEND_TIMEOUT_MS) -> bool:
+        """Queue cmd, waiting up to timeout_ms for room if the daemon has fallen behind; False if the queue stayed full"""
+        self._check_disposed()
+        return self._shell.send_command_timeout(cmd, timeout_ms)
+    
+    def set_interval(self, ms: int) -> bool:
+        return self.send_command(encode_command(CMD_SET_INTERVAL, ms))
+    
+    def refresh(self) -> bool:
+        return self.send_command(encode_command(CMD_REFRESH))
+    
+{ping}
+    def close(self):
+        if self._disposed:
+            return
+        self._disposed = True
+        self._shell = None
+    
+    def _check_disposed(self):
+        if self._disposed:
+            raise RuntimeError("VenomShell has been closed")
+    
+    def __enter__(self): return self
+    def __exit__(self, *_): self.close()
+    def __del__(self): self.close()
+
+"#, pascal = pascal, ping = SHELL_PING)
 }
 
+/// `VenomShell.ping`, the same for both bindings since it only needs
+/// `send_command` and `read_raw_data`
+const SHELL_PING: &str = r#"    def ping(self, seq: int) -> Optional[float]:
+        """Round trip of a ping carrying seq (u32) in µs, or None if no pong came back within a second.
+        
+        The daemon answers by publishing at once with a pong after the state.
+        It keeps only the last one, so clients pinging at the same time can
+        miss their reply.
+        """
+        start = time.perf_counter_ns()
+        if not self.send_command(encode_command(CMD_PING, seq - (1 << 32) if seq >= 1 << 31 else seq)):
+            return None
+        size = STATE_SIZE + _PONG.size
+        while time.perf_counter_ns() - start < 1_000_000_000:
+            data = self.read_raw_data(size)
+            if len(data) == size and _PONG.unpack_from(data, STATE_SIZE) == (self.client_id, seq):
+                return (time.perf_counter_ns() - start) / 1000.0
+        return None
+    "#;
+
 // ═══════════════════════════════════════════════════════════════════════════
 // Python Client
 // ═══════════════════════════════════════════════════════════════════════════
 
+/// Client-side keys, shared by both clients: `+`/`-` halve or double the
+/// daemon's publish interval, `r` asks for an immediate refresh
+const COMMAND_KEYS: &str = r#"class Keys:
+    """Single keys from the terminal without Enter or echo (Ctrl+C still works)."""
+    
+    def __init__(self):
+        self._saved = None
+    
+    def begin(self):
+        if not sys.stdin.isatty():
+            return
+        fd = sys.stdin.fileno()
+        self._saved = termios.tcgetattr(fd)
+        raw = termios.tcgetattr(fd)
+        raw[3] &= ~(termios.ICANON | termios.ECHO)
+        raw[6][termios.VMIN] = 0
+        raw[6][termios.VTIME] = 0
+        termios.tcsetattr(fd, termios.TCSANOW, raw)
+    
+    def end(self):
+        if self._saved is not None:
+            termios.tcsetattr(sys.stdin.fileno(), termios.TCSANOW, self._saved)
+            self._saved = None
+    
+    def poll(self) -> str:
+        """Keys pressed since the last call."""
+        if self._saved is None:
+            return ""
+        return os.read(sys.stdin.fileno(), 64).decode(errors="ignore")
+
+def handle_keys(keys: Keys, shell: VenomShell, interval_ms: int) -> int:
+    for key in keys.poll():
+        if key in "+=":
+            interval_ms = max(interval_ms // 2, 10)
+            shell.set_interval(interval_ms)
+        elif key == "-":
+            interval_ms = min(interval_ms * 2, 10000)
+            shell.set_interval(interval_ms)
+        elif key == "r":
+            shell.refresh()
+    return interval_ms
+"#;
+
+/// Command line of the clients: `--duration SECONDS`, so scripted runs
+/// (`venom run`) end on their own, `--ping COUNT` for the round-trip demo
+/// and `--channel-suffix SUFFIX` for the daemon instance to connect to
+const CLIENT_ARGS: &str = r#"def client_args() -> argparse.Namespace:
+    """--duration SECONDS stops as if Ctrl+C was pressed (for scripted runs)."""
+    parser = argparse.ArgumentParser()
+    parser.add_argument("--duration", type=float, metavar="SECONDS", help="exit after SECONDS")
+    parser.add_argument("--ping", type=int, metavar="COUNT", help="measure COUNT round trips through the daemon instead")
+    parser.add_argument("--channel-suffix", metavar="SUFFIX", help="connect to the daemon started with this suffix")
+    args = parser.parse_args()
+    if args.duration:
+        signal.signal(signal.SIGALRM, signal.default_int_handler)
+        signal.setitimer(signal.ITIMER_REAL, args.duration)
+    return args
+
+def run_ping(shell: VenomShell, count: int) -> bool:
+    """Time count round trips through the daemon; False if none came back."""
+    # A pong for an earlier client with the same ID may still be published
+    seq = time.monotonic_ns() & 0xFFFFFFFF
+    rtts = []
+    for i in range(1, count + 1):
+        seq = (seq + 1) & 0xFFFFFFFF
+        rtt_us = shell.ping(seq)
+        if rtt_us is None:
+            print(f"⚠️  No reply to ping #{i}")
+            continue
+        print(f"🏓 Pong #{i}: {rtt_us:.1f} µs")
+        rtts.append(rtt_us)
+        time.sleep(0.1)
+    if not rtts:
+        return False
+    print(f"📊 Round trip: {len(rtts)}/{count} replies, min {min(rtts):.1f} µs, "
+          f"avg {sum(rtts) / len(rtts):.1f} µs, max {max(rtts):.1f} µs")
+    return True
+"#;
+
 fn client_py(config: &ProjectConfig) -> String {
+    if !config.schema.has_demo_fields() {
+        return generic_client_py(config);
+    }
     
     format!(r#"#!/usr/bin/env python3
 """
@@ -438,9 +889,13 @@ Displays live CPU/RAM/Uptime stats with colored progress bars.
 Includes read latency benchmarking.
 """
 
+import argparse
+import os
+import signal
 import sys
+import termios
 import time
-from venom_binding import VenomShell, CHANNEL_NAME
+from venom_binding import VenomShell, INTERVAL_MS, channel_name
 
 # ANSI colors
 G, Y, R, C, RST = '\033[92m', '\033[93m', '\033[91m', '\033[96m', '\033[0m'
@@ -456,21 +911,33 @@ def bar(pct: float, w: int = 25) -> str:
     c = R if pct > 80 else Y if pct > 50 else G
     return "[" + "".join(c + "█" + RST if i < filled else " " for i in range(w)) + "]"
 
+{keys}
+{args}
 def main():
     global latency_min, latency_max, latency_sum, latency_count
+    args = client_args()
     
     print("╔═══════════════════════════════════════════════════════════════╗")
     print("║   🖥️  {name} Status Bar (Python)                              ║")
     print("╚═══════════════════════════════════════════════════════════════╝\n")
     
+    keys = Keys()
     try:
-        shell = VenomShell()
-        print(f"✅ Connected! ID: {{shell.client_id}}")
+        shell = VenomShell(channel_name(args.channel_suffix), client_name="python-client")
+        print(f"✅ Connected! ID: {{shell.client_id}} (libvenom_memory {{shell.library_version}})")
+        if args.ping:
+            replied = run_ping(shell, args.ping)
+            shell.close()
+            sys.exit(0 if replied else 1)
         print("📊 Reading stats... (Ctrl+C to exit)\n")
         time.sleep(1)
         
+        keys.begin()
+        interval_ms = INTERVAL_MS
         frame = 0
         while True:
+            interval_ms = handle_keys(keys, shell, interval_ms)
+            
             # ═══════════════════════════════════════════════════════════════════
             # 📊 BENCHMARK: Measure read latency
             # ═══════════════════════════════════════════════════════════════════
@@ -501,8 +968,10 @@ def main():
                 print(f"║  ⏱️ Uptime: {{state.uptime_formatted}}                                        ║")
                 print("╠═══════════════════════════════════════════════════════════════╣")
                 print(f"║  📊 {{C}}Read Latency:{{RST}} {{latency_us:.2f}} µs (min: {{latency_min:.2f}}, max: {{latency_max:.2f}}, avg: {{avg_us:.2f}})  ║")
+                print(f"║  📡 {{C}}Daemon→client:{{RST}} {{shell.write_age_us:.2f}} µs since the write                 ║")
                 print("╚═══════════════════════════════════════════════════════════════╝")
-                print(f"  Cores: {{state.core_count}} | Updates: {{state.update_counter}} | Ctrl+C to exit")
+                print(f"  Cores: {{state.core_count}} | Updates: {{state.update_counter}} | Interval: {{interval_ms}} ms")
+                print("  +/- faster/slower | r refresh | Ctrl+C to exit")
                 frame += 1
             time.sleep(0.1)
     except KeyboardInterrupt:
@@ -511,30 +980,520 @@ def main():
         print(f"   Samples: {{latency_count}}")
         print(f"   Min: {{latency_min:.2f}} µs")
         print(f"   Max: {{latency_max:.2f}} µs")
-        print(f"   Avg: {{latency_sum / latency_count:.2f}} µs")
+        print(f"   Avg: {{latency_sum / max(latency_count, 1):.2f}} µs")
         print("\n👋 Goodbye!")
     except Exception as e:
         print(f"\n❌ Error: {{e}}")
         print("\nMake sure daemon is running: cd daemon && make run")
         sys.exit(1)
+    finally:
+        keys.end()
 
 if __name__ == "__main__":
     main()
+"#, name = config.name, keys = COMMAND_KEYS, args = CLIENT_ARGS)
+}
+
+/// Client for a custom schema: prints every field of the state
+fn generic_client_py(config: &ProjectConfig) -> String {
+    format!(r#"#!/usr/bin/env python3
+"""
+{name} Client - VenomMemory Python Client
+Prints every field of the state (see venom.toml).
+Includes read latency benchmarking.
+"""
+
+import dataclasses
+import argparse
+import os
+import signal
+import sys
+import termios
+import time
+from venom_binding import VenomShell, STATE_SIZE, INTERVAL_MS, channel_name
+
+{keys}
+{args}
+def main():
+    args = client_args()
+    lat_min, lat_max, lat_sum, lat_count = float('inf'), 0.0, 0.0, 0
+    keys = Keys()
+    try:
+        shell = VenomShell(channel_name(args.channel_suffix), client_name="python-client")
+        print(f"✅ Connected! ID: {{shell.client_id}} (libvenom_memory {{shell.library_version}})")
+        if args.ping:
+            replied = run_ping(shell, args.ping)
+            shell.close()
+            sys.exit(0 if replied else 1)
+        keys.begin()
+        interval_ms = INTERVAL_MS
+        while True:
+            interval_ms = handle_keys(keys, shell, interval_ms)
+            
+            t_start = time.perf_counter_ns()
+            state = shell.read_state()
+            latency_us = (time.perf_counter_ns() - t_start) / 1000.0
+            
+            lat_min = min(lat_min, latency_us)
+            lat_max = max(lat_max, latency_us)
+            lat_sum += latency_us
+            lat_count += 1
+            
+            if state.is_valid:
+                print('\033[2J\033[H', end='')  # Clear screen
+                print(f"🐍 {name} (Python) | State v{{state.version}} | {{STATE_SIZE}} bytes")
+                print("═══════════════════════════════════════════════════════════════")
+                for name, value in dataclasses.asdict(state).items():
+                    if name not in ("magic", "version"):
+                        print(f"  {{name:<24}} {{value}}")
+                print("═══════════════════════════════════════════════════════════════")
+                print(f"📊 Read Latency: {{latency_us:.2f}} µs (min: {{lat_min:.2f}}, max: {{lat_max:.2f}}, avg: {{lat_sum / lat_count:.2f}})")
+                print(f"📡 Daemon→client: {{shell.write_age_us:.2f}} µs since the write")
+                print(f"⏱️  Daemon interval: {{interval_ms}} ms | +/- faster/slower | r refresh")
+            time.sleep(0.1)
+    except KeyboardInterrupt:
+        print(f"\n\n📊 Final Latency Stats (Python): {{lat_count}} samples, "
+              f"min {{lat_min:.2f}} µs, max {{lat_max:.2f}} µs, avg {{lat_sum / max(lat_count, 1):.2f}} µs")
+        print("\n👋 Goodbye!")
+    except Exception as e:
+        print(f"\n❌ Error: {{e}}")
+        print("\nMake sure daemon is running: cd daemon && make run")
+        sys.exit(1)
+    finally:
+        keys.end()
+
+if __name__ == "__main__":
+    main()
+"#, name = config.name, keys = COMMAND_KEYS, args = CLIENT_ARGS)
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// asyncio Client (--python-async)
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// `venom_async.py`: `AsyncVenomShell`, which runs `VenomShell`'s calls in
+/// the event loop's default executor
+fn venom_async_py(config: &ProjectConfig) -> String {
+    format!(r#"#!/usr/bin/env python3
+"""
+asyncio wrapper around venom_binding.VenomShell
+
+Every call into the library runs in the event loop's default executor, so
+an asyncio application (a textual dashboard, an aiohttp service) never
+waits on it. updates() polls with a backoff and yields each new state.
+"""
+
+import asyncio
+from typing import AsyncIterator, Optional
+
+from venom_binding import (CHANNEL_NAME, CMD_REFRESH, CMD_SET_INTERVAL, SEND_TIMEOUT_MS,
+                           {pascal}State, VenomShell, encode_command)
+
+# Pause between two polls of updates(): it doubles, up to POLL_MAX_S,
+# while the state stays the same, and drops back once it changes
+POLL_MIN_S = 0.001
+POLL_MAX_S = 0.05
+
+
+class AsyncVenomShell:
+    """Connection to VenomMemory daemon, with awaitable calls."""
+    
+    def __init__(self, channel_name: str = CHANNEL_NAME, client_name: Optional[str] = None):
+        """client_name is what the daemon shows in its log"""
+        # The synchronous connection, for code that isn't async
+        self.shell = VenomShell(channel_name, client_name=client_name)
+    
+    @property
+    def client_id(self) -> int:
+        return self.shell.client_id
+    
+    @property
+    def write_age_us(self) -> float:
+        """µs between the daemon's write and the last read (0 if unstamped)"""
+        return self.shell.write_age_us
+    
+    async def _call(self, fn, *args):
+        return await asyncio.get_running_loop().run_in_executor(None, fn, *args)
+    
+    async def read_state(self) -> {pascal}State:
+        return await self._call(self.shell.read_state)
+    
+    async def updates(self) -> AsyncIterator[{pascal}State]:
+        """Each valid state that differs from the one before, starting with the current one"""
+        last = None
+        pause = POLL_MIN_S
+        while True:
+            state = await self.read_state()
+            if state.is_valid and state != last:
+                last = state
+                pause = POLL_MIN_S
+                yield state
+            else:
+                await asyncio.sleep(pause)
+                pause = min(pause * 2, POLL_MAX_S)
+    
+    async def send_command(self, cmd: bytes, timeout_ms: int = SEND_TIMEOUT_MS) -> bool:
+        """Queue cmd, waiting up to timeout_ms for room; False if the queue stayed full"""
+        return await self._call(self.shell.send_command, cmd, timeout_ms)
+    
+    async def set_interval(self, ms: int) -> bool:
+        return await self.send_command(encode_command(CMD_SET_INTERVAL, ms))
+    
+    async def refresh(self) -> bool:
+        return await self.send_command(encode_command(CMD_REFRESH))
+    
+    async def ping(self, seq: int) -> Optional[float]:
+        """Round trip of a ping in µs, as VenomShell.ping"""
+        return await self._call(self.shell.ping, seq)
+    
+    def close(self):
+        self.shell.close()
+    
+    async def __aenter__(self): return self
+    async def __aexit__(self, *_): self.close()
+"#, pascal = config.names().pascal)
+}
+
+/// `main_async.py`: the status bar of `client.py` on `AsyncVenomShell`
+fn main_async_py(config: &ProjectConfig) -> String {
+    let render = if config.schema.has_demo_fields() {
+        format!(r#"# ANSI colors
+G, Y, R, C, RST = '\033[92m', '\033[93m', '\033[91m', '\033[96m', '\033[0m'
+
+def bar(pct: float, w: int = 25) -> str:
+    filled = int((pct / 100) * w)
+    c = R if pct > 80 else Y if pct > 50 else G
+    return "[" + "".join(c + "█" + RST if i < filled else " " for i in range(w)) + "]"
+
+def render(state, shell: AsyncVenomShell, interval_ms: int, frame: int):
+    print('\033[2J\033[H', end='')  # Clear screen
+    print("╔═══════════════════════════════════════════════════════════════╗")
+    print(f"║  🖥️  {name} Monitor (asyncio)  Frame: {{frame:<6}}                  ║")
+    print("╠═══════════════════════════════════════════════════════════════╣")
+    print(f"║  CPU: {{bar(state.cpu_usage_percent)}} {{state.cpu_usage_percent:5.1f}}%             ║")
+    print("╠═══════════════════════════════════════════════════════════════╣")
+    for i in range(state.core_count):
+        print(f"║  Core {{i}}: {{bar(state.cpu_cores[i], 20)}} {{state.cpu_cores[i]:5.1f}}%                ║")
+    print("╠═══════════════════════════════════════════════════════════════╣")
+    print(f"║  RAM: {{bar(state.memory_usage_percent)}} {{state.memory_used_mb}}/{{state.memory_total_mb}} MB      ║")
+    print("╠═══════════════════════════════════════════════════════════════╣")
+    print(f"║  ⏱️ Uptime: {{state.uptime_formatted}}                                        ║")
+    print("╠═══════════════════════════════════════════════════════════════╣")
+    print(f"║  📡 {{C}}Daemon→client:{{RST}} {{shell.write_age_us:.2f}} µs since the write                 ║")
+    print("╚═══════════════════════════════════════════════════════════════╝")
+    print(f"  Cores: {{state.core_count}} | Updates: {{state.update_counter}} | Interval: {{interval_ms}} ms")
+    print("  +/- faster/slower | r refresh | Ctrl+C to exit")
+"#, name = config.name)
+    } else {
+        format!(r#"def render(state, shell: AsyncVenomShell, interval_ms: int, frame: int):
+    print('\033[2J\033[H', end='')  # Clear screen
+    print(f"🐍 {name} (asyncio) | State v{{state.version}} | {{STATE_SIZE}} bytes | Frame {{frame}}")
+    print("═══════════════════════════════════════════════════════════════")
+    for name, value in dataclasses.asdict(state).items():
+        if name not in ("magic", "version"):
+            print(f"  {{name:<24}} {{value}}")
+    print("═══════════════════════════════════════════════════════════════")
+    print(f"📡 Daemon→client: {{shell.write_age_us:.2f}} µs since the write")
+    print(f"⏱️  Daemon interval: {{interval_ms}} ms | +/- faster/slower | r refresh")
 "#, name = config.name)
+    };
+
+    format!(r#"#!/usr/bin/env python3
+"""
+{name} Status Bar (asyncio) - VenomMemory Python Client
+The status bar of client.py, driven by AsyncVenomShell.updates(): it draws
+each state as it arrives and leaves the event loop free in between.
+"""
+
+import argparse
+import asyncio
+import dataclasses
+import os
+import signal
+import sys
+import termios
+import time
+from venom_binding import VenomShell, STATE_SIZE, INTERVAL_MS, channel_name
+from venom_async import AsyncVenomShell
+
+{keys}
+{args}
+{render}
+async def main(args: argparse.Namespace) -> int:
+    keys = Keys()
+    async with AsyncVenomShell(channel_name(args.channel_suffix), client_name="python-async-client") as shell:
+        print(f"✅ Connected! ID: {{shell.client_id}} (libvenom_memory {{shell.shell.library_version}})")
+        loop = asyncio.get_running_loop()
+        if args.ping:
+            replied = await loop.run_in_executor(None, run_ping, shell.shell, args.ping)
+            return 0 if replied else 1
+        
+        interval_ms = INTERVAL_MS
+        def on_keys():
+            nonlocal interval_ms
+            interval_ms = handle_keys(keys, shell.shell, interval_ms)
+        
+        keys.begin()
+        if sys.stdin.isatty():
+            loop.add_reader(sys.stdin.fileno(), on_keys)
+        try:
+            frame = 0
+            async for state in shell.updates():
+                render(state, shell, interval_ms, frame)
+                frame += 1
+        finally:
+            if sys.stdin.isatty():
+                loop.remove_reader(sys.stdin.fileno())
+            keys.end()
+    return 0
+
+if __name__ == "__main__":
+    args = client_args()
+    try:
+        sys.exit(asyncio.run(main(args)))
+    except KeyboardInterrupt:
+        print("\n👋 Goodbye!")
+    except Exception as e:
+        print(f"\n❌ Error: {{e}}")
+        print("\nMake sure daemon is running: cd daemon && make run")
+        sys.exit(1)
+"#, name = config.name, keys = COMMAND_KEYS, args = CLIENT_ARGS, render = render)
+}
+
+/// `test_venom_async.py`: `AsyncVenomShell` against a stand-in for the
+/// library, so it runs without a daemon
+fn test_async_py(config: &ProjectConfig) -> String {
+    let (imports, install) = match config.python_binding {
+        PythonBinding::Ctypes => ("import ctypes\n", r#"class FakeLib(FakeChannel):
+    """The functions of libvenom_memory VenomShell calls"""
+    
+    def venom_shell_connect_named(self, channel, client_name): return 1
+    def venom_shell_destroy(self, handle): pass
+    def venom_shell_id(self, handle): return CLIENT_ID
+    def venom_version(self): return b"fake"
+    def venom_clock_ns(self): return WRITTEN_NS + 1_000
+    
+    def venom_shell_read_data_stamped(self, handle, buf, max_len, written_ns):
+        length = min(len(self.data), max_len)
+        ctypes.memmove(buf, self.data, length)
+        written_ns._obj.value = WRITTEN_NS
+        return length
+    
+    def venom_shell_send_command_timeout(self, handle, buf, length, timeout_ms):
+        self.commands.append(bytes(buf[:length]))
+        return 0
+
+
+from venom_binding import VenomShell
+
+CHANNEL = FakeLib()
+VenomShell._lib = CHANNEL  # already loaded, as far as VenomShell can tell
+"#),
+        PythonBinding::Pyo3 => ("import sys\nimport types\n", r#"class FakeShell:
+    """venom_py.Shell over CHANNEL"""
+    
+    def __init__(self, channel_name, client_name=None):
+        self.client_id = CLIENT_ID
+    
+    def read_into_stamped(self, buf):
+        length = min(len(CHANNEL.data), len(buf))
+        buf[:length] = CHANNEL.data[:length]
+        return length, WRITTEN_NS
+    
+    def read_bytes(self, max_len):
+        return CHANNEL.data[:max_len]
+    
+    def send_command_timeout(self, cmd, timeout_ms):
+        CHANNEL.commands.append(bytes(cmd))
+        return True
+
+
+CHANNEL = FakeChannel()
+# Imported by venom_binding in place of the extension
+sys.modules["venom_py"] = types.SimpleNamespace(
+    Shell=FakeShell, LIBRARY_VERSION="fake", clock_ns=lambda: WRITTEN_NS + 1_000)
+"#),
+    };
+
+    format!(r#"#!/usr/bin/env python3
+"""
+Tests for venom_async.py
+
+AsyncVenomShell runs against a stand-in for the library holding one state,
+so no daemon is needed.
+
+Run with pytest, or without it: python3 test_venom_async.py
+"""
+
+import asyncio
+{imports}import unittest
+
+CLIENT_ID = 7
+WRITTEN_NS = 5_000
+
+# Every field holds a distinct known value, as in test_venom_binding.py
+GOLDEN = bytes([
+{golden}])
+
+
+class FakeChannel:
+    """The channel: the daemon's last write and the commands sent since"""
+    
+    def __init__(self):
+        self.data = b""
+        self.commands = []
+
+
+{install}
+from venom_binding import CMD_SET_INTERVAL, {pascal}State, encode_command
+from venom_async import AsyncVenomShell
+
+
+def with_version(version: int) -> bytes:
+    """GOLDEN with another version: a different, still valid state"""
+    return GOLDEN[:4] + version.to_bytes(4, "little") + GOLDEN[8:]
+
+
+def test_read_state():
+    CHANNEL.data = GOLDEN
+    async def read():
+        async with AsyncVenomShell() as shell:
+            assert shell.client_id == CLIENT_ID
+            state = await shell.read_state()
+            assert shell.write_age_us == 1.0, shell.write_age_us
+            return state
+    assert asyncio.run(read()) == {pascal}State.from_bytes(GOLDEN)
+
+
+def test_updates_yield_each_new_state():
+    CHANNEL.data = b""
+    async def collect():
+        seen = []
+        async with AsyncVenomShell() as shell:
+            updates = shell.updates()
+            # Nothing published yet
+            try:
+                await asyncio.wait_for(updates.__anext__(), 0.05)
+                raise AssertionError("an update before the daemon wrote")
+            except asyncio.TimeoutError:
+                pass
+            updates = shell.updates()
+            CHANNEL.data = GOLDEN
+            seen.append(await updates.__anext__())
+            # The same state again isn't an update
+            try:
+                await asyncio.wait_for(updates.__anext__(), 0.05)
+                raise AssertionError("the same state twice")
+            except asyncio.TimeoutError:
+                pass
+            updates = shell.updates()
+            CHANNEL.data = with_version(2)
+            seen.append(await updates.__anext__())
+        return seen
+    first, second = asyncio.run(collect())
+    assert first == {pascal}State.from_bytes(GOLDEN)
+    assert second.version == 2, second.version
+
+
+def test_reads_leave_the_loop_free():
+    CHANNEL.data = GOLDEN
+    async def interleave():
+        ticks = 0
+        async def tick():
+            nonlocal ticks
+            while True:
+                ticks += 1
+                await asyncio.sleep(0)
+        ticker = asyncio.ensure_future(tick())
+        async with AsyncVenomShell() as shell:
+            for _ in range(20):
+                await shell.read_state()
+        ticker.cancel()
+        return ticks
+    assert asyncio.run(interleave()) >= 20
+
+
+def test_commands():
+    CHANNEL.commands.clear()
+    async def send():
+        async with AsyncVenomShell() as shell:
+            return await shell.set_interval(250)
+    assert asyncio.run(send())
+    assert CHANNEL.commands == [encode_command(CMD_SET_INTERVAL, 250)], CHANNEL.commands
+
+
+if __name__ == "__main__":
+    import sys
+    failed = 0
+    for name, test in list(globals().items()):
+        if not name.startswith("test_"):
+            continue
+        try:
+            test()
+            print(f"{{name}} ... ok")
+        except unittest.SkipTest as e:
+            print(f"{{name}} ... skipped: {{e}}")
+        except Exception as e:
+            failed += 1
+            print(f"{{name}} ... FAILED: {{e!r}}")
+    sys.exit(1 if failed else 0)
+"#,
+        pascal = config.names().pascal,
+        golden = super::byte_rows(&config.schema.golden_bytes(config.magic), "    "),
+        imports = imports,
+        install = install
+    )
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
 // README
 // ═══════════════════════════════════════════════════════════════════════════
 
+/// README section of `--python-async` projects
+const ASYNC_README: &str = r#"
+## asyncio
+
+`venom_async.py` wraps `VenomShell` for asyncio applications: its calls run
+in the event loop's default executor, and `updates()` yields each new state.
+
+```python
+async with AsyncVenomShell() as shell:
+    async for state in shell.updates():
+        print(state)
+```
+
+```bash
+python3 main_async.py         # the status bar, on asyncio
+python3 test_venom_async.py   # against a stand-in for the library; no daemon needed
+```
+"#;
+
 fn readme(config: &ProjectConfig) -> String {
+    let (binding, setup, binding_file) = match config.python_binding {
+        PythonBinding::Ctypes => (
+            "ctypes",
+            "",
+            "venom_binding.py  # Python FFI bindings (ctypes)",
+        ),
+        PythonBinding::Pyo3 => (
+            "PyO3",
+            r#"
+# Once - build and install the native venom_py extension
+# (from a checkout of venom_memory_rs)
+pip install maturin
+maturin develop --release -m venom_memory_rs/venom-py/Cargo.toml
+"#,
+            "venom_binding.py  # Python wrapper around venom_py (PyO3)",
+        ),
+    };
+
     format!(r#"# {name} (Python + C Daemon)
 
-VenomMemory project with C daemon and Python client.
+VenomMemory project with C daemon and Python client ({binding} binding).
 
 ## Quick Start
 
-```bash
+```bash{setup}
 # Terminal 1 - Start C daemon
 cd daemon && make run
 
@@ -542,6 +1501,16 @@ cd daemon && make run
 python3 client.py
 ```
 
+## Tests
+
+```bash
+python3 test_venom_binding.py                       # or: pytest
+{live_env}=1 python3 test_venom_binding.py    # also reads the running daemon
+```
+
+The golden test decodes a known state built from `venom.toml`, so it fails
+when the binding no longer matches the daemon's layout.
+{async_section}{ping}{instances}
 ## Structure
 
 ```
@@ -551,8 +1520,11 @@ python3 client.py
 │   └── Makefile
 ├── shared/           # Shared protocol
 │   └── protocol.h
-├── venom_binding.py  # Python FFI bindings
+├── {binding_file}
 ├── client.py         # Python status bar
+├── test_venom_binding.py{async_files}
+├── offsets.json      # Offsets the binding reads (venom init --verify)
+├── venom.toml        # Protocol schema (state struct)
 └── lib/
     └── libvenom_memory.so
 ```
@@ -563,9 +1535,23 @@ python3 client.py
 |---------|-------|
 | Channel | `{channel}` |
 | Magic | `0x{magic:08X}` |
-"#,
+| Binding | {binding} |
+{service}"#,
         name = config.name,
+        service = super::service::readme_section(config),
+        ping = super::ping_readme_section("python3 client.py --ping 10"),
+        instances = super::instances_readme_section(&config.channel, "cd daemon && make run ARGS=\"--channel-suffix SUFFIX\"", "python3 client.py --channel-suffix SUFFIX", "`venom_binding.channel_name()`"),
+        async_section = if config.python_async { ASYNC_README } else { "" },
+        async_files = if config.python_async {
+            "\n├── venom_async.py    # AsyncVenomShell (asyncio)\n├── main_async.py     # Status bar on asyncio\n├── test_venom_async.py"
+        } else {
+            ""
+        },
+        binding = binding,
+        setup = setup,
+        binding_file = binding_file,
         channel = config.channel,
-        magic = magic(&config.channel)
+        magic = config.magic,
+        live_env = super::LIVE_TEST_ENV
     )
 }
@@ -14,7 +14,7 @@ pub fn generate(config: &ProjectConfig) {
     
     crate::create_dir(&format!("{}/src/bin", base));
     
-    crate::write_file(&format!("{}/Cargo.toml", base), &cargo_toml(config));
+    crate::write_file(&format!("{}/Cargo.toml", base), &cargo_toml(config, true));
     crate::write_file(&format!("{}/build.rs", base), &build_rs(config));
     crate::write_file(&format!("{}/.cargo/config.toml", base), &cargo_config(config));
     crate::write_file(&format!("{}/src/lib.rs", base), &lib_rs(config));
@@ -23,55 +23,141 @@ pub fn generate(config: &ProjectConfig) {
     crate::write_file(&format!("{}/README.md", base), &readme(config));
 }
 
-fn magic(channel: &str) -> u32 {
-    channel.bytes().fold(0x564E4Fu32, |acc, b| acc.wrapping_add(b as u32))
+/// Client files only (used by `venom add-client`); the crate builds just the client binary
+pub fn generate_client(config: &ProjectConfig) {
+    let base = &config.output_dir;
+    
+    crate::create_dir(&format!("{}/src/bin", base));
+    
+    crate::write_file(&format!("{}/Cargo.toml", base), &cargo_toml(config, false));
+    crate::write_file(&format!("{}/build.rs", base), &build_rs(config));
+    crate::write_file(&format!("{}/.cargo/config.toml", base), &cargo_config(config));
+    crate::write_file(&format!("{}/src/lib.rs", base), &lib_rs(config));
+    crate::write_file(&format!("{}/src/bin/client.rs", base), &client_rs(config));
 }
 
+
 // Cargo.toml - uses local venom_memory via build.rs linking
-fn cargo_toml(config: &ProjectConfig) -> String {
+fn cargo_toml(config: &ProjectConfig, with_daemon: bool) -> String {
+    let daemon_bin = if with_daemon {
+        "\n[[bin]]\nname = \"daemon\"\npath = \"src/bin/daemon.rs\"\n"
+    } else {
+        ""
+    };
+    
     format!(r#"[package]
-name = "{name}"
+name = "{package}"
 version = "0.1.0"
 edition = "2021"
 build = "build.rs"
 
-# Uses bundled library via FFI + ctrlc for signal handling
+# Uses bundled library via FFI + ctrlc for signal handling + libc for raw key input and --daemonize
 [dependencies]
-ctrlc = "3.4"
-
-[[bin]]
-name = "daemon"
-path = "src/bin/daemon.rs"
-
+ctrlc = {{ version = "3.4", features = ["termination"] }}
+libc = "0.2"
+{daemon_bin}
 [[bin]]
 name = "client"
 path = "src/bin/client.rs"
-"#, name = config.name)
+"#, package = config.names().snake, daemon_bin = daemon_bin)
 }
 
 // build.rs - tells cargo where to find the library
-fn build_rs(_config: &ProjectConfig) -> String {
-    r#"fn main() {
-    // Tell cargo to look for libvenom_memory.so in the lib/ directory
-    println!("cargo:rustc-link-search=native={}", 
-        std::env::current_dir().unwrap().join("lib").display());
+fn build_rs(config: &ProjectConfig) -> String {
+    let lib = config.at_root("lib");
+    if !config.targets.is_empty() {
+        return format!(r#"fn main() {{
+    // Link the library bundled for the target being built: {lib}/<triple>/
+    // (bundled: {targets})
+    let target = std::env::var("TARGET").unwrap();
+    let lib_dir = std::env::current_dir().unwrap().join("{lib}").join(&target);
+    if !lib_dir.is_dir() {{
+        panic!("no bundled libvenom_memory for {{}} in {lib}/ (bundled: {targets})", target);
+    }}
+    println!("cargo:rustc-link-search=native={{}}", lib_dir.display());
+    println!("cargo:rustc-link-lib=dylib=venom_memory");
+
+    // Set rpath so the binary can find the library at runtime
+    // Binary is in target/debug/ (tests in target/debug/deps/), or
+    // target/<triple>/debug/ when cross-compiling
+    println!("cargo:rustc-link-arg=-Wl,-rpath,$ORIGIN/../../{lib}/{{}}", target);
+    println!("cargo:rustc-link-arg=-Wl,-rpath,$ORIGIN/../../../{lib}/{{}}", target);
+}}
+"#, lib = lib, targets = config.targets.join(" "));
+    }
+    format!(r#"fn main() {{
+    // Tell cargo to look for libvenom_memory.so in the {lib}/ directory
+    println!("cargo:rustc-link-search=native={{}}", 
+        std::env::current_dir().unwrap().join("{lib}").display());
     println!("cargo:rustc-link-lib=dylib=venom_memory");
     
     // Set rpath so the binary can find the library at runtime
-    // Binary is in target/debug/ or target/release/, lib is in lib/
-    println!("cargo:rustc-link-arg=-Wl,-rpath,$ORIGIN/../../lib");
-}
-"#.to_string()
+    // Binary is in target/debug/ or target/release/ (tests in target/debug/deps/), lib is in {lib}/
+    println!("cargo:rustc-link-arg=-Wl,-rpath,$ORIGIN/../../{lib}");
+    println!("cargo:rustc-link-arg=-Wl,-rpath,$ORIGIN/../../../{lib}");
+}}
+"#, lib = lib)
 }
 
 // .cargo/config.toml - runtime library path
-fn cargo_config(_config: &ProjectConfig) -> String {
-    r#"[env]
-LD_LIBRARY_PATH = { value = "lib", relative = true }
-"#.to_string()
+fn cargo_config(config: &ProjectConfig) -> String {
+    format!(r#"[env]
+LD_LIBRARY_PATH = {{ value = "{lib}", relative = true }}
+"#, lib = config.at_root("lib"))
+}
+
+/// The `extern "C"` block of `src/lib.rs`; `venom upgrade` replaces just this
+pub(super) const FFI_BLOCK: &str = r#"#[link(name = "venom_memory")]
+extern "C" {
+    pub fn venom_daemon_create_ex(name: *const i8, config: *const VenomConfigEx) -> *mut std::ffi::c_void;
+    pub fn venom_daemon_destroy(handle: *mut std::ffi::c_void);
+    pub fn venom_daemon_write_data(handle: *mut std::ffi::c_void, data: *const u8, len: usize);
+    pub fn venom_daemon_try_recv_command(handle: *mut std::ffi::c_void, buf: *mut u8, max_len: usize, out_client_id: *mut u32) -> usize;
+    pub fn venom_daemon_client_name(handle: *mut std::ffi::c_void, client_id: u32, buf: *mut i8, max_len: usize) -> usize;
+    
+    pub fn venom_shell_connect(name: *const i8) -> *mut std::ffi::c_void;
+    pub fn venom_shell_connect_named(name: *const i8, client_name: *const i8) -> *mut std::ffi::c_void;
+    pub fn venom_shell_destroy(handle: *mut std::ffi::c_void);
+    pub fn venom_shell_read_data(handle: *mut std::ffi::c_void, buf: *mut u8, max_len: usize) -> usize;
+    pub fn venom_shell_read_data_stamped(handle: *mut std::ffi::c_void, buf: *mut u8, max_len: usize, out_timestamp_ns: *mut u64) -> usize;
+    pub fn venom_shell_read_frame(handle: *mut std::ffi::c_void, buf: *mut u8, max_len: usize, out_frame: *mut VenomFrame) -> bool;
+    pub fn venom_clock_ns() -> u64;
+    pub fn venom_version() -> *const i8;
+    pub fn venom_shell_id(handle: *mut std::ffi::c_void) -> u32;
+    pub fn venom_shell_daemon_alive(handle: *mut std::ffi::c_void) -> bool;
+    pub fn venom_shell_send_command(handle: *mut std::ffi::c_void, cmd: *const u8, len: usize) -> bool;
+}
+"#;
+
+/// `assert_eq!`s of `decodes_golden_state`, one per data field
+fn golden_asserts(config: &ProjectConfig) -> String {
+    let mut out = String::new();
+    for (f, values) in config.schema.golden(config.magic) {
+        let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+        let expected = match f.len {
+            Some(_) => format!("[{}]", values.join(", ")),
+            None => values.join(""),
+        };
+        out.push_str(&format!("        assert_eq!(state.{}, {});
+", f.name, expected));
+    }
+    out
 }
 
 fn lib_rs(config: &ProjectConfig) -> String {
+    let schema = &config.schema;
+    let mut fields = String::new();
+    for f in &schema.fields {
+        match f.len {
+            Some(n) => fields.push_str(&format!("    pub {}: [{}; {}],\n", f.name, f.ty.as_str(), n)),
+            None => fields.push_str(&format!("    pub {}: {},\n", f.name, f.ty.as_str())),
+        }
+    }
+    let max_cores = match schema.field("cpu_cores") {
+        Some(f) if schema.has_demo_fields() => format!("pub const MAX_CORES: usize = {};\n", f.count()),
+        _ => String::new(),
+    };
+    
     format!(r#"//! {name} Protocol - Shared types for daemon/client communication
 //!
 //! This module defines:
@@ -84,91 +170,260 @@ pub const MAGIC: u32 = 0x{magic:08X};
 pub const DATA_SIZE: usize = {data_size};
 pub const CMD_SLOTS: usize = {cmd_slots};
 pub const MAX_CLIENTS: usize = {max_clients};
-pub const MAX_CORES: usize = 16;
+pub const STATE_SIZE: usize = {state_size};
+/// Default publish interval; clients change it with `CmdType::SetInterval`
+pub const INTERVAL_MS: u32 = 100;
+{max_cores}
+/// The channel of one of several instances: `"{channel}-SUFFIX"`, or
+/// [`CHANNEL_NAME`] itself for no suffix or an empty one
+pub fn channel_name(suffix: Option<&str>) -> String {{
+    match suffix {{
+        Some(suffix) if !suffix.is_empty() => format!("{{}}-{{}}", CHANNEL_NAME, suffix),
+        _ => CHANNEL_NAME.to_string(),
+    }}
+}}
 
-/// System state published by daemon
-#[repr(C)]
-#[derive(Clone, Copy, Default, Debug)]
-pub struct State {{
-    pub magic: u32,
-    pub version: u32,
-    pub cpu_usage_percent: f32,
-    pub cpu_cores: [f32; MAX_CORES],
-    pub core_count: u32,
-    pub memory_used_mb: u32,
-    pub memory_total_mb: u32,
-    pub uptime_seconds: u64,
-    pub update_counter: u64,
-    pub timestamp_ns: u64,
+/// The channel named by `--channel-suffix SUFFIX` on the command line, for
+/// the daemon and clients alike
+pub fn channel_from_args() -> String {{
+    let args: Vec<String> = std::env::args().collect();
+    channel_name(args.windows(2).find(|w| w[0] == "--channel-suffix").map(|w| w[1].as_str()))
 }}
 
-/// Command types
-#[repr(u8)]
+/// State published by daemon (generated from venom.toml)
+#[repr(C)]
 #[derive(Clone, Copy, Debug)]
+pub struct {pascal}State {{
+{fields}}}
+
+pub type State = {pascal}State;
+
+const _: () = assert!(std::mem::size_of::<State>() == STATE_SIZE);
+
+impl Default for {pascal}State {{
+    fn default() -> Self {{
+        // Every field is a plain number, so all-zero bytes are a valid State
+        unsafe {{ std::mem::zeroed() }}
+    }}
+}}
+
+impl {pascal}State {{
+    /// Decode a state read from the channel; None if `buf` is too short
+    pub fn from_bytes(buf: &[u8]) -> Option<Self> {{
+        let b = buf.get(..STATE_SIZE)?;
+        // Any bytes are a valid State, and `buf` need not be aligned
+        Some(unsafe {{ std::ptr::read_unaligned(b.as_ptr() as *const Self) }})
+    }}
+}}
+
+/// Command types, the tags of typed commands
+#[repr(u16)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum CmdType {{
+    /// Publish immediately
     Refresh = 1,
+    /// value: publish interval in ms (10-10000)
     SetInterval = 2,
+    /// value: sequence number, echoed back in a [`Pong`]
+    Ping = 3,
+}}
+
+impl CmdType {{
+    pub fn from_u16(v: u16) -> Option<Self> {{
+        match v {{
+            1 => Some(Self::Refresh),
+            2 => Some(Self::SetInterval),
+            3 => Some(Self::Ping),
+            _ => None,
+        }}
+    }}
 }}
 
-/// Command sent from client to daemon
+/// Command sent from client to daemon: a typed command whose tag is a
+/// [`CmdType`] and whose payload is `value`
 #[repr(C)]
 #[derive(Clone, Copy)]
 pub struct Command {{
-    pub cmd: u8,
-    pub _pad: [u8; 3],
+    pub cmd: u16,
+    /// Bytes of payload after the envelope: 4, for `value`
+    pub len: u16,
     pub value: i32,
 }}
 
+const _: () = assert!(std::mem::size_of::<Command>() == 8);
+
+impl Command {{
+    pub fn new(cmd: CmdType, value: i32) -> Self {{
+        Self {{ cmd: cmd as u16, len: 4, value }}
+    }}
+    
+    pub fn to_bytes(&self) -> [u8; 8] {{
+        let (c, l, v) = (self.cmd.to_le_bytes(), self.len.to_le_bytes(), self.value.to_le_bytes());
+        [c[0], c[1], l[0], l[1], v[0], v[1], v[2], v[3]]
+    }}
+    
+    /// None for messages too short, or typed commands with another payload
+    pub fn from_bytes(buf: &[u8]) -> Option<Self> {{
+        let b = buf.get(..8)?;
+        let len = u16::from_le_bytes([b[2], b[3]]);
+        (len == 4).then(|| Self {{ cmd: u16::from_le_bytes([b[0], b[1]]), len, value: i32::from_le_bytes([b[4], b[5], b[6], b[7]]) }})
+    }}
+}}
+
+pub const PONG_SIZE: usize = 8;
+
+/// Reply to the last `CmdType::Ping`, published right after the state
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pong {{
+    /// Client that sent the ping
+    pub client_id: u32,
+    /// Its value
+    pub seq: u32,
+}}
+
+impl Pong {{
+    pub fn to_bytes(&self) -> [u8; PONG_SIZE] {{
+        let (id, seq) = (self.client_id.to_le_bytes(), self.seq.to_le_bytes());
+        [id[0], id[1], id[2], id[3], seq[0], seq[1], seq[2], seq[3]]
+    }}
+    
+    pub fn from_bytes(buf: &[u8]) -> Option<Self> {{
+        let b = buf.get(..PONG_SIZE)?;
+        Some(Self {{ client_id: u32::from_le_bytes([b[0], b[1], b[2], b[3]]), seq: u32::from_le_bytes([b[4], b[5], b[6], b[7]]) }})
+    }}
+}}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // FFI Bindings to VenomMemory (lib/libvenom_memory.so)
 // ═══════════════════════════════════════════════════════════════════════════
 
 #[repr(C)]
-pub struct VenomConfig {{
+pub struct VenomConfigEx {{
+    pub struct_size: usize,
     pub data_size: usize,
     pub cmd_slots: usize,
     pub max_clients: usize,
+    pub cmd_payload_size: usize,
+    pub cmd_full_policy: usize,
+    pub shm_mode: usize,
 }}
 
-#[link(name = "venom_memory")]
-extern "C" {{
-    pub fn venom_daemon_create(name: *const i8, config: VenomConfig) -> *mut std::ffi::c_void;
-    pub fn venom_daemon_destroy(handle: *mut std::ffi::c_void);
-    pub fn venom_daemon_write_data(handle: *mut std::ffi::c_void, data: *const u8, len: usize);
-    pub fn venom_daemon_try_recv_command(handle: *mut std::ffi::c_void, buf: *mut u8, max_len: usize, out_client_id: *mut u32) -> usize;
-    
-    pub fn venom_shell_connect(name: *const i8) -> *mut std::ffi::c_void;
-    pub fn venom_shell_destroy(handle: *mut std::ffi::c_void);
-    pub fn venom_shell_read_data(handle: *mut std::ffi::c_void, buf: *mut u8, max_len: usize) -> usize;
-    pub fn venom_shell_id(handle: *mut std::ffi::c_void) -> u32;
+#[repr(C)]
+#[derive(Default)]
+pub struct VenomFrame {{
+    pub len: usize,
+    pub generation: u64,
+    pub timestamp_ns: u64,
+    pub truncated: bool,
+}}
+
+{ffi}
+/// Version of the libvenom_memory that was loaded
+pub fn library_version() -> String {{
+    unsafe {{ std::ffi::CStr::from_ptr(venom_version()) }}.to_string_lossy().into_owned()
+}}
+
+/// One write of the daemon, from [`Shell::snapshot`]
+#[derive(Debug, Clone)]
+pub struct Frame {{
+    pub data: Vec<u8>,
+    /// Larger for every later write
+    pub generation: u64,
+    /// When the daemon wrote it, in `venom_clock_ns` time; None if unstamped
+    pub timestamp_ns: Option<u64>,
+    /// The write was longer than DATA_SIZE and `data` is its start
+    pub truncated: bool,
+}}
+
+impl Frame {{
+    /// How long ago the daemon wrote the frame, in µs (0 if unstamped)
+    pub fn age_us(&self) -> f64 {{
+        self.timestamp_ns.map_or(0.0, |written_ns| unsafe {{ venom_clock_ns() }}.saturating_sub(written_ns) as f64 / 1000.0)
+    }}
+}}
+
+impl std::ops::Deref for Frame {{
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {{
+        &self.data
+    }}
 }}
 
 /// Safe wrapper for VenomMemory Daemon
 pub struct Daemon {{
     handle: *mut std::ffi::c_void,
+    /// Reply to the last ping, published with every state from then on
+    pong: std::cell::Cell<Option<Pong>>,
 }}
 
 impl Daemon {{
     pub fn create(name: &str) -> Option<Self> {{
         let c_name = std::ffi::CString::new(name).ok()?;
-        let config = VenomConfig {{
+        let config = VenomConfigEx {{
+            struct_size: std::mem::size_of::<VenomConfigEx>(),
             data_size: DATA_SIZE,
             cmd_slots: CMD_SLOTS,
             max_clients: MAX_CLIENTS,
+            cmd_payload_size: std::mem::size_of::<Command>(),
+            cmd_full_policy: 0,
+            shm_mode: 0o{shm_mode:o},
         }};
-        let handle = unsafe {{ venom_daemon_create(c_name.as_ptr(), config) }};
-        if handle.is_null() {{ None }} else {{ Some(Self {{ handle }}) }}
+        let handle = unsafe {{ venom_daemon_create_ex(c_name.as_ptr(), &config) }};
+        if handle.is_null() {{ None }} else {{ Some(Self {{ handle, pong: std::cell::Cell::new(None) }}) }}
     }}
     
     pub fn write_data(&self, data: &[u8]) {{
         unsafe {{ venom_daemon_write_data(self.handle, data.as_ptr(), data.len()) }};
     }}
     
+    /// Publish `state`, followed by the last pong once there is one
+    pub fn write_state(&self, state: &State) {{
+        let mut buf = [0u8; STATE_SIZE + PONG_SIZE];
+        // State is all plain numbers, padding included as fields
+        let bytes = unsafe {{ std::slice::from_raw_parts(state as *const State as *const u8, STATE_SIZE) }};
+        buf[..STATE_SIZE].copy_from_slice(bytes);
+        match self.pong.get() {{
+            Some(pong) => {{
+                buf[STATE_SIZE..].copy_from_slice(&pong.to_bytes());
+                self.write_data(&buf);
+            }}
+            None => self.write_data(&buf[..STATE_SIZE]),
+        }}
+    }}
+    
+    /// Answer a ping; the pong goes out with every `write_state` until the next one
+    pub fn pong(&self, client_id: u32, seq: u32) {{
+        self.pong.set(Some(Pong {{ client_id, seq }}));
+    }}
+    
     pub fn try_recv_command(&self, buf: &mut [u8]) -> Option<(u32, usize)> {{
         let mut client_id = 0u32;
         let len = unsafe {{ venom_daemon_try_recv_command(self.handle, buf.as_mut_ptr(), buf.len(), &mut client_id) }};
         if len > 0 {{ Some((client_id, len)) }} else {{ None }}
     }}
+    
+    /// "3", or "3 (gui-frontend)" for a client that connected with a name
+    pub fn client_label(&self, client_id: u32) -> String {{
+        let mut name = [0i8; 33];
+        let len = unsafe {{ venom_daemon_client_name(self.handle, client_id, name.as_mut_ptr(), name.len()) }};
+        if len == 0 {{
+            return client_id.to_string();
+        }}
+        let name = unsafe {{ std::ffi::CStr::from_ptr(name.as_ptr()) }};
+        format!("{{}} ({{}})", client_id, name.to_string_lossy())
+    }}
+    
+    /// Next queued Command, skipping messages that aren't one
+    pub fn try_recv(&self) -> Option<(u32, Command)> {{
+        let mut buf = [0u8; 64];
+        while let Some((client_id, len)) = self.try_recv_command(&mut buf) {{
+            if let Some(cmd) = Command::from_bytes(&buf[..len]) {{
+                return Some((client_id, cmd));
+            }}
+        }}
+        None
+    }}
 }}
 
 impl Drop for Daemon {{
@@ -189,13 +444,67 @@ impl Shell {{
         if handle.is_null() {{ None }} else {{ Some(Self {{ handle }}) }}
     }}
     
+    /// Connect under a name the daemon shows in its log
+    pub fn connect_named(name: &str, client_name: &str) -> Option<Self> {{
+        let c_name = std::ffi::CString::new(name).ok()?;
+        let c_client_name = std::ffi::CString::new(client_name).ok()?;
+        let handle = unsafe {{ venom_shell_connect_named(c_name.as_ptr(), c_client_name.as_ptr()) }};
+        if handle.is_null() {{ None }} else {{ Some(Self {{ handle }}) }}
+    }}
+    
     pub fn client_id(&self) -> u32 {{
         unsafe {{ venom_shell_id(self.handle) }}
     }}
     
+    /// False once the daemon has exited; reads then return its last state
+    pub fn daemon_alive(&self) -> bool {{
+        unsafe {{ venom_shell_daemon_alive(self.handle) }}
+    }}
+    
     pub fn read_data(&self, buf: &mut [u8]) -> usize {{
         unsafe {{ venom_shell_read_data(self.handle, buf.as_mut_ptr(), buf.len()) }}
     }}
+    
+    /// The daemon's latest write, with its generation and timestamp from
+    /// the same read; None before its first write
+    pub fn snapshot(&self) -> Option<Frame> {{
+        let mut data = vec![0u8; DATA_SIZE];
+        let mut frame = VenomFrame::default();
+        if !unsafe {{ venom_shell_read_frame(self.handle, data.as_mut_ptr(), data.len(), &mut frame) }} {{
+            return None;
+        }}
+        data.truncate(frame.len);
+        let timestamp_ns = (frame.timestamp_ns != 0).then_some(frame.timestamp_ns);
+        Some(Frame {{ data, generation: frame.generation, timestamp_ns, truncated: frame.truncated }})
+    }}
+    
+    pub fn send_command(&self, cmd: Command) -> bool {{
+        let bytes = cmd.to_bytes();
+        unsafe {{ venom_shell_send_command(self.handle, bytes.as_ptr(), bytes.len()) }}
+    }}
+    
+    /// Round trip of a `CmdType::Ping` carrying `seq`; None if no pong came
+    /// back within a second
+    ///
+    /// The daemon answers by publishing at once with a [`Pong`] after the
+    /// state. It keeps only the last one, so clients pinging at the same time
+    /// can miss their reply.
+    pub fn ping(&self, seq: u32) -> Option<std::time::Duration> {{
+        let sent = std::time::Instant::now();
+        if !self.send_command(Command::new(CmdType::Ping, seq as i32)) {{
+            return None;
+        }}
+        let id = self.client_id();
+        let mut buf = [0u8; STATE_SIZE + PONG_SIZE];
+        while sent.elapsed() < std::time::Duration::from_secs(1) {{
+            let len = self.read_data(&mut buf);
+            let pong = buf[..len].get(STATE_SIZE..).and_then(Pong::from_bytes);
+            if pong == Some(Pong {{ client_id: id, seq }}) {{
+                return Some(sent.elapsed());
+            }}
+        }}
+        None
+    }}
 }}
 
 impl Drop for Shell {{
@@ -203,34 +512,274 @@ impl Drop for Shell {{
         unsafe {{ venom_shell_destroy(self.handle) }};
     }}
 }}
+
+#[cfg(test)]
+mod tests {{
+    use super::*;
+
+    /// The state `venom.toml` describes, as the daemon publishes it: every
+    /// field holds a distinct known value (see `decodes_golden_state`)
+    const GOLDEN: [u8; STATE_SIZE] = [
+{golden}    ];
+
+    #[test]
+    fn decodes_golden_state() {{
+        let state = State::from_bytes(&GOLDEN).unwrap();
+{asserts}    }}
+
+    #[test]
+    fn rejects_short_buffer() {{
+        assert!(State::from_bytes(&GOLDEN[..STATE_SIZE - 1]).is_none());
+    }}
+
+    #[test]
+    fn appends_channel_suffix() {{
+        assert_eq!(channel_name(Some("2")), format!("{{}}-2", CHANNEL_NAME));
+        assert_eq!(channel_name(Some("")), CHANNEL_NAME);
+        assert_eq!(channel_name(None), CHANNEL_NAME);
+    }}
+
+    #[test]
+    fn encodes_typed_command() {{
+        let bytes = Command::new(CmdType::SetInterval, 250).to_bytes();
+        assert_eq!(bytes, [2, 0, 4, 0, 250, 0, 0, 0]);
+        let cmd = Command::from_bytes(&bytes).unwrap();
+        assert_eq!((CmdType::from_u16(cmd.cmd), cmd.value), (Some(CmdType::SetInterval), 250));
+        assert!(Command::from_bytes(&[2, 0, 0, 0, 250, 0, 0, 0]).is_none());
+    }}
+
+    /// Needs the daemon running: `{live_env}=1 cargo test`
+    #[test]
+    fn reads_live_state() {{
+        if std::env::var_os("{live_env}").is_none() {{
+            return;
+        }}
+        let shell = Shell::connect(CHANNEL_NAME).expect("daemon not running");
+        let mut buf = vec![0u8; DATA_SIZE];
+        // The daemon may not have published yet
+        let mut len = 0;
+        for _ in 0..50 {{
+            len = shell.read_data(&mut buf);
+            if len > 0 {{
+                break;
+            }}
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }}
+        let state = State::from_bytes(&buf[..len]).expect("state shorter than STATE_SIZE");
+        assert_eq!(state.magic, MAGIC);
+        assert_eq!(state.version, 1);
+    }}
+}}
 "#,
+        shm_mode = config.shm_mode,
         name = config.name,
         channel = config.channel,
-        magic = magic(&config.channel),
+        magic = config.magic,
         data_size = config.data_size,
         cmd_slots = config.cmd_slots,
-        max_clients = config.max_clients
+        max_clients = config.max_clients,
+        pascal = config.names().pascal,
+        state_size = schema.size,
+        max_cores = max_cores,
+        ffi = FFI_BLOCK,
+        fields = fields,
+        golden = super::byte_rows(&schema.golden_bytes(config.magic), "        "),
+        asserts = golden_asserts(config),
+        live_env = super::LIVE_TEST_ENV
     )
 }
 
+/// Daemon-side command handling, shared by both daemons: the daemon polls
+/// `handle_commands` every 10 ms and publishes when it returns true or the
+/// interval has passed
+const COMMAND_HANDLER: &str = r#"/// Apply queued client commands; returns true if a client asked for a refresh
+fn handle_commands(daemon: &Daemon, interval_ms: &mut u32) -> bool {
+    let mut refresh = false;
+    while let Some((client_id, cmd)) = daemon.try_recv() {
+        match CmdType::from_u16(cmd.cmd) {
+            Some(CmdType::Refresh) => refresh = true,
+            Some(CmdType::SetInterval) => {
+                *interval_ms = cmd.value.clamp(10, 10_000) as u32;
+                println!("\n📥 Client {}: publishing every {} ms", daemon.client_label(client_id), interval_ms);
+            }
+            // Answered by publishing now, with the pong after the state
+            Some(CmdType::Ping) => {
+                daemon.pong(client_id, cmd.value as u32);
+                refresh = true;
+            }
+            None => println!("\n📥 Client {}: unknown command {}", daemon.client_label(client_id), cmd.cmd),
+        }
+    }
+    refresh
+}
+"#;
+
+/// `--daemonize` for both daemons: fork into the background, write the
+/// child's pid to `--pidfile` and send stdout/stderr to `--logfile`
+const DAEMONIZE: &str = r#"/// `--daemonize [--pidfile PATH] [--logfile PATH]`: detach from the terminal;
+/// the status line is left out and everything else goes to the logfile.
+/// Returns the pidfile to remove on exit. Runs before any thread is started.
+fn daemonize_from_args() -> Option<String> {
+    use std::os::fd::AsRawFd;
+    
+    let args: Vec<String> = std::env::args().collect();
+    if !args.iter().any(|a| a == "--daemonize") {
+        return None;
+    }
+    let value = |flag: &str, default: &str| {
+        args.windows(2).find(|w| w[0] == flag).map_or(default.to_string(), |w| w[1].clone())
+    };
+    let pidfile = value("--pidfile", "daemon.pid");
+    let logfile = value("--logfile", "daemon.log");
+    fn fail(what: &str, e: std::io::Error) -> ! {
+        eprintln!("❌ {}: {}", what, e);
+        std::process::exit(1);
+    }
+    
+    let log = std::fs::OpenOptions::new().create(true).append(true).open(&logfile)
+        .unwrap_or_else(|e| fail(&logfile, e));
+    match unsafe { libc::fork() } {
+        -1 => fail("fork", std::io::Error::last_os_error()),
+        0 => {}
+        child => {
+            // Written before the parent exits, so a supervisor reading it finds the child
+            if let Err(e) = std::fs::write(&pidfile, format!("{}\n", child)) {
+                unsafe { libc::kill(child, libc::SIGTERM) };
+                fail(&pidfile, e);
+            }
+            std::process::exit(0);
+        }
+    }
+    
+    unsafe {
+        libc::setsid();
+        let null = libc::open(b"/dev/null\0".as_ptr() as *const libc::c_char, libc::O_RDONLY);
+        if null >= 0 {
+            libc::dup2(null, libc::STDIN_FILENO);
+            libc::close(null);
+        }
+        libc::dup2(log.as_raw_fd(), libc::STDOUT_FILENO);
+        libc::dup2(log.as_raw_fd(), libc::STDERR_FILENO);
+    }
+    Some(pidfile)
+}
+"#;
+
+/// Client-side keys, shared by both clients: `+`/`-` halve or double the
+/// daemon's publish interval, `r` asks for an immediate refresh; also
+/// `run_ping()` for `--ping`
+const COMMAND_KEYS: &str = r#"/// Reads single keys without Enter or echo while alive (Ctrl+C still works)
+struct RawKeys {
+    saved: Option<libc::termios>,
+}
+
+impl RawKeys {
+    fn new() -> Self {
+        unsafe {
+            let mut saved: libc::termios = std::mem::zeroed();
+            if libc::isatty(libc::STDIN_FILENO) == 0 || libc::tcgetattr(libc::STDIN_FILENO, &mut saved) != 0 {
+                return Self { saved: None };
+            }
+            let mut raw = saved;
+            raw.c_lflag &= !(libc::ICANON | libc::ECHO);
+            raw.c_cc[libc::VMIN] = 0;
+            raw.c_cc[libc::VTIME] = 0;
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw);
+            Self { saved: Some(saved) }
+        }
+    }
+    
+    /// Next pressed key, if one is waiting
+    fn poll(&self) -> Option<u8> {
+        self.saved?;
+        let mut key = 0u8;
+        let n = unsafe { libc::read(libc::STDIN_FILENO, &mut key as *mut u8 as *mut libc::c_void, 1) };
+        (n == 1).then_some(key)
+    }
+}
+
+impl Drop for RawKeys {
+    fn drop(&mut self) {
+        if let Some(saved) = &self.saved {
+            unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, saved) };
+        }
+    }
+}
+
+fn handle_keys(keys: &RawKeys, shell: &Shell, interval_ms: &mut u32) {
+    while let Some(key) = keys.poll() {
+        match key {
+            b'+' | b'=' => {
+                *interval_ms = (*interval_ms / 2).max(10);
+                shell.send_command(Command::new(CmdType::SetInterval, *interval_ms as i32));
+            }
+            b'-' => {
+                *interval_ms = (*interval_ms * 2).min(10_000);
+                shell.send_command(Command::new(CmdType::SetInterval, *interval_ms as i32));
+            }
+            b'r' => {
+                shell.send_command(Command::new(CmdType::Refresh, 0));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// `--ping COUNT`: time COUNT round trips through the daemon; false if none came back
+fn run_ping(shell: &Shell, count: u32) -> bool {
+    // A pong for an earlier client with the same ID may still be published
+    let mut seq = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |d| d.subsec_nanos());
+    let mut rtts = Vec::new();
+    for i in 1..=count {
+        seq = seq.wrapping_add(1);
+        match shell.ping(seq) {
+            Some(rtt) => {
+                let rtt_us = rtt.as_nanos() as f64 / 1000.0;
+                println!("🏓 Pong #{}: {:.1} µs", i, rtt_us);
+                rtts.push(rtt_us);
+                std::thread::sleep(std::time::Duration::from_millis(100));
+            }
+            None => println!("⚠️  No reply to ping #{}", i),
+        }
+    }
+    if rtts.is_empty() {
+        return false;
+    }
+    let min = rtts.iter().copied().fold(f64::MAX, f64::min);
+    let max = rtts.iter().copied().fold(0.0, f64::max);
+    let avg = rtts.iter().sum::<f64>() / rtts.len() as f64;
+    println!("📊 Round trip: {}/{} replies, min {:.1} µs, avg {:.1} µs, max {:.1} µs", rtts.len(), count, min, avg, max);
+    true
+}
+"#;
+
 fn daemon_rs(config: &ProjectConfig) -> String {
-    let name_snake = config.name.replace("-", "_");
+    if !config.schema.has_demo_fields() {
+        return generic_daemon_rs(config);
+    }
+    let name_snake = config.names().snake;
     
     format!(r##"//! {name} System Monitor Daemon
 //!
 //! Reads CPU/RAM/Uptime from /proc and publishes via VenomMemory IPC.
 
-use {name_snake}::{{CHANNEL_NAME, MAGIC, MAX_CORES, State, Daemon}};
+use {name_snake}::{{channel_from_args, INTERVAL_MS, MAGIC, MAX_CORES, CmdType, State, Daemon}};
 use std::fs::File;
 use std::io::{{BufRead, BufReader}};
+use std::sync::atomic::{{AtomicBool, Ordering}};
+use std::sync::Arc;
 use std::time::{{Duration, Instant}};
 
+{commands}
+{daemonize}
 fn main() {{
+    let pidfile = daemonize_from_args();
     println!("🖥️  {name} System Monitor (VenomMemory)");
     println!("═══════════════════════════════════════════════════════════════");
     
-    let daemon = Daemon::create(CHANNEL_NAME).expect("Failed to create channel");
-    println!("✅ Channel: {{}} | Publishing...", CHANNEL_NAME);
+    let channel = channel_from_args();
+    let daemon = Daemon::create(&channel).expect("Failed to create channel");
+    println!("✅ Channel: {{}} | Publishing...", channel);
     
     let mut state = State::default();
     state.magic = MAGIC;
@@ -239,9 +788,23 @@ fn main() {{
     let start = Instant::now();
     let mut prev_total = vec![0u64; MAX_CORES + 1];
     let mut prev_idle = vec![0u64; MAX_CORES + 1];
-    let mut cmd_buf = [0u8; 64];
+    let mut interval_ms = INTERVAL_MS;
+    let mut next_publish = Instant::now();
+    
+    // Ctrl+C or SIGTERM ends the loop, and dropping the Daemon removes the channel
+    let running = Arc::new(AtomicBool::new(true));
+    let r = running.clone();
+    ctrlc::set_handler(move || r.store(false, Ordering::SeqCst)).ok();
     
-    loop {{
+    while running.load(Ordering::SeqCst) {{
+        // Apply client commands; publish on refresh or when the interval is up
+        let refresh = handle_commands(&daemon, &mut interval_ms);
+        if !refresh && Instant::now() < next_publish {{
+            std::thread::sleep(Duration::from_millis(10));
+            continue;
+        }}
+        next_publish = Instant::now() + Duration::from_millis(interval_ms as u64);
+        
         // Read CPU from /proc/stat
         if let Ok(f) = File::open("/proc/stat") {{
             let mut core_idx = 0;
@@ -284,48 +847,146 @@ fn main() {{
         // Publish state
         state.update_counter += 1;
         state.timestamp_ns = start.elapsed().as_nanos() as u64;
-        let bytes = unsafe {{ std::slice::from_raw_parts(&state as *const State as *const u8, std::mem::size_of::<State>()) }};
-        daemon.write_data(bytes);
+        daemon.write_state(&state);
         
-        // Check for commands
-        if let Some((client_id, _)) = daemon.try_recv_command(&mut cmd_buf) {{
-            println!("\n📥 Command from client {{}}", client_id);
+        if pidfile.is_none() {{
+            print!("\r🖥️  CPU: {{:5.1}}% | RAM: {{}}/{{}} MB | #{{}} | {{}} ms   ", 
+                state.cpu_usage_percent, state.memory_used_mb, state.memory_total_mb, state.update_counter, interval_ms);
+        }}
+    }}
+    println!("\n\n👋 Goodbye!");
+    if let Some(pidfile) = pidfile {{
+        std::fs::remove_file(pidfile).ok();
+    }}
+}}
+"##,
+        name = config.name,
+        name_snake = name_snake,
+        commands = COMMAND_HANDLER,
+        daemonize = DAEMONIZE
+    )
+}
+
+/// Daemon for a custom schema: publishes the header and leaves the fields to the user
+fn generic_daemon_rs(config: &ProjectConfig) -> String {
+    let name_snake = config.names().snake;
+    
+    format!(r##"//! {name} Daemon
+//!
+//! Publishes State (see venom.toml) via VenomMemory IPC.
+
+use {name_snake}::{{channel_from_args, INTERVAL_MS, MAGIC, CmdType, State, Daemon}};
+use std::io::Write;
+use std::sync::atomic::{{AtomicBool, Ordering}};
+use std::sync::Arc;
+use std::time::{{Duration, Instant}};
+
+{commands}
+{daemonize}
+fn main() {{
+    let pidfile = daemonize_from_args();
+    println!("🚀 {name} Daemon (VenomMemory)");
+    println!("═══════════════════════════════════════════════════════════════");
+    
+    let channel = channel_from_args();
+    let daemon = Daemon::create(&channel).expect("Failed to create channel");
+    println!("✅ Channel: {{}} | State: {{}} bytes | Publishing...", channel, std::mem::size_of::<State>());
+    
+    let mut state = State::default();
+    state.magic = MAGIC;
+    state.version = 1;
+    
+    let mut updates = 0u64;
+    let mut interval_ms = INTERVAL_MS;
+    let mut next_publish = Instant::now();
+    
+    // Ctrl+C or SIGTERM ends the loop, and dropping the Daemon removes the channel
+    let running = Arc::new(AtomicBool::new(true));
+    let r = running.clone();
+    ctrlc::set_handler(move || r.store(false, Ordering::SeqCst)).ok();
+    
+    while running.load(Ordering::SeqCst) {{
+        let refresh = handle_commands(&daemon, &mut interval_ms);
+        if !refresh && Instant::now() < next_publish {{
+            std::thread::sleep(Duration::from_millis(10));
+            continue;
         }}
+        next_publish = Instant::now() + Duration::from_millis(interval_ms as u64);
+        
+        // TODO: fill in the fields of State
+        
+        daemon.write_state(&state);
         
-        print!("\r🖥️  CPU: {{:5.1}}% | RAM: {{}}/{{}} MB | #{{}}   ", 
-            state.cpu_usage_percent, state.memory_used_mb, state.memory_total_mb, state.update_counter);
-        std::thread::sleep(Duration::from_millis(100));
+        updates += 1;
+        if pidfile.is_none() {{
+            print!("\r📤 Published update #{{}} (every {{}} ms)   ", updates, interval_ms);
+            std::io::stdout().flush().ok();
+        }}
+    }}
+    println!("\n\n👋 Goodbye!");
+    if let Some(pidfile) = pidfile {{
+        std::fs::remove_file(pidfile).ok();
     }}
 }}
 "##,
         name = config.name,
-        name_snake = name_snake
+        name_snake = name_snake,
+        commands = COMMAND_HANDLER,
+        daemonize = DAEMONIZE
     )
 }
 
+/// `--duration SECONDS` for the clients, so scripted runs (`venom run`) end
+/// on their own, and `--ping COUNT`; `--channel-suffix` is read by
+/// `channel_from_args()` in lib.rs
+const CLIENT_ARGS: &str = r#"/// `--duration SECONDS`: stop on our own after that long (for scripted runs)
+fn duration_arg() -> Option<std::time::Duration> {
+    let args: Vec<String> = std::env::args().collect();
+    args.windows(2)
+        .find(|w| w[0] == "--duration")
+        .and_then(|w| w[1].parse().ok())
+        .map(std::time::Duration::from_secs_f64)
+}
+
+/// `--ping COUNT`: measure round trips through the daemon instead
+fn ping_arg() -> Option<u32> {
+    let args: Vec<String> = std::env::args().collect();
+    args.windows(2).find(|w| w[0] == "--ping").and_then(|w| w[1].parse().ok())
+}
+"#;
+
 fn client_rs(config: &ProjectConfig) -> String {
-    let name_snake = config.name.replace("-", "_");
+    if !config.schema.has_demo_fields() {
+        return generic_client_rs(config);
+    }
+    let name_snake = config.names().snake;
     
     format!(r##"//! {name} Status Bar Client - with Benchmarking
 //!
 //! Connects to daemon and displays live system stats.
 //! Includes read latency measurements.
 
-use {name_snake}::{{CHANNEL_NAME, MAGIC, State, Shell}};
+use {name_snake}::{{channel_from_args, library_version, INTERVAL_MS, MAGIC, CmdType, Command, Frame, State, Shell}};
 use std::time::Instant;
 
 // ANSI colors
 const CYAN: &str = "\x1b[96m";
 const RST: &str = "\x1b[0m";
 
+{keys}
+{args}
 fn main() {{
     println!("🖥️  {name} Status Bar (Rust)");
     println!("═══════════════════════════════════════════════════════════════");
     
-    let shell = Shell::connect(CHANNEL_NAME).expect("Failed to connect - is daemon running?");
-    println!("✅ Connected! ID: {{}}", shell.client_id());
+    let shell = Shell::connect_named(&channel_from_args(), "rust-client").expect("Failed to connect - is daemon running?");
+    println!("✅ Connected! ID: {{}} (libvenom_memory {{}})", shell.client_id(), library_version());
+    if let Some(count) = ping_arg() {{
+        let replied = run_ping(&shell, count);
+        drop(shell);
+        std::process::exit(if replied {{ 0 }} else {{ 1 }});
+    }}
     
-    let mut buf = vec![0u8; std::mem::size_of::<State>() + 64];
     
     // Latency tracking
     let mut latency_min = f64::MAX;
@@ -341,12 +1002,23 @@ fn main() {{
         r.store(false, std::sync::atomic::Ordering::SeqCst);
     }}).ok();
     
-    while running.load(std::sync::atomic::Ordering::SeqCst) {{
+    let keys = RawKeys::new();
+    let mut interval_ms = INTERVAL_MS;
+    let deadline = duration_arg().map(|d| Instant::now() + d);
+    
+    while running.load(std::sync::atomic::Ordering::SeqCst) && deadline.map_or(true, |d| Instant::now() < d) {{
+        handle_keys(&keys, &shell, &mut interval_ms);
+        // Reads would keep returning the last state it wrote
+        if !shell.daemon_alive() {{
+            println!("\n❌ Daemon offline! Restart it:\n   cargo run --bin daemon");
+            break;
+        }}
+        
         // ═══════════════════════════════════════════════════════════════════
         // 📊 BENCHMARK: Measure read latency
         // ═══════════════════════════════════════════════════════════════════
         let t_start = Instant::now();
-        let len = shell.read_data(&mut buf);
+        let latest = shell.snapshot();
         let latency_us = t_start.elapsed().as_nanos() as f64 / 1000.0;
         
         // Update stats
@@ -356,8 +1028,8 @@ fn main() {{
         latency_count += 1;
         let avg_us = latency_sum / latency_count as f64;
         
-        if len >= std::mem::size_of::<State>() {{
-            let state: State = unsafe {{ std::ptr::read(buf.as_ptr() as *const State) }};
+        let age_us = latest.as_ref().map_or(0.0, Frame::age_us);
+        if let Some(state) = latest.as_deref().and_then(State::from_bytes) {{
             if state.magic == MAGIC {{
                 print!("\x1b[2J\x1b[H");
                 println!("╔═══════════════════════════════════════════════════════════════╗");
@@ -369,8 +1041,10 @@ fn main() {{
                 println!("╠═══════════════════════════════════════════════════════════════╣");
                 println!("║  📊 {{}}Read Latency:{{}} {{:.2}} µs (min: {{:.2}}, max: {{:.2}}, avg: {{:.2}})  ║",
                     CYAN, RST, latency_us, latency_min, latency_max, avg_us);
+                println!("║  📡 {{}}Daemon→client:{{}} {{:.2}} µs since the write                 ║", CYAN, RST, age_us);
                 println!("╚═══════════════════════════════════════════════════════════════╝");
-                println!("  Cores: {{}} | Updates: {{}}", state.core_count, state.update_counter);
+                println!("  Cores: {{}} | Updates: {{}} | Interval: {{}} ms", state.core_count, state.update_counter, interval_ms);
+                println!("  +/- faster/slower | r refresh | Ctrl+C to exit");
                 frame += 1;
             }}
         }}
@@ -387,7 +1061,93 @@ fn main() {{
 }}
 "##,
         name = config.name,
-        name_snake = name_snake
+        name_snake = name_snake,
+        keys = COMMAND_KEYS,
+        args = CLIENT_ARGS
+    )
+}
+
+/// Client for a custom schema: prints every field of the state
+fn generic_client_rs(config: &ProjectConfig) -> String {
+    let name_snake = config.names().snake;
+    let fields: String = config.schema.user_fields()
+        .map(|f| format!("                println!(\"  {{:<24}} {{:?}}\", \"{name}\", state.{name});\n", name = f.name))
+        .collect();
+    
+    format!(r##"//! {name} Client - with Benchmarking
+//!
+//! Connects to the daemon and prints every field of State (see venom.toml).
+//! Includes read latency measurements.
+
+use {name_snake}::{{channel_from_args, library_version, INTERVAL_MS, MAGIC, CmdType, Command, Frame, State, Shell}};
+use std::sync::atomic::{{AtomicBool, Ordering}};
+use std::sync::Arc;
+use std::time::Instant;
+
+{keys}
+{args}
+fn main() {{
+    let shell = Shell::connect_named(&channel_from_args(), "rust-client").expect("Failed to connect - is daemon running?");
+    println!("✅ Connected! ID: {{}} (libvenom_memory {{}})", shell.client_id(), library_version());
+    if let Some(count) = ping_arg() {{
+        let replied = run_ping(&shell, count);
+        drop(shell);
+        std::process::exit(if replied {{ 0 }} else {{ 1 }});
+    }}
+    
+    let (mut lat_min, mut lat_max, mut lat_sum, mut lat_count) = (f64::MAX, 0.0_f64, 0.0_f64, 0_u64);
+    
+    let running = Arc::new(AtomicBool::new(true));
+    let r = running.clone();
+    ctrlc::set_handler(move || r.store(false, Ordering::SeqCst)).ok();
+    
+    let keys = RawKeys::new();
+    let mut interval_ms = INTERVAL_MS;
+    let deadline = duration_arg().map(|d| Instant::now() + d);
+    
+    while running.load(Ordering::SeqCst) && deadline.map_or(true, |d| Instant::now() < d) {{
+        handle_keys(&keys, &shell, &mut interval_ms);
+        // Reads would keep returning the last state it wrote
+        if !shell.daemon_alive() {{
+            println!("\n❌ Daemon offline! Restart it:\n   cargo run --bin daemon");
+            break;
+        }}
+        
+        let t_start = Instant::now();
+        let latest = shell.snapshot();
+        let latency_us = t_start.elapsed().as_nanos() as f64 / 1000.0;
+        
+        lat_min = lat_min.min(latency_us);
+        lat_max = lat_max.max(latency_us);
+        lat_sum += latency_us;
+        lat_count += 1;
+        
+        let age_us = latest.as_ref().map_or(0.0, Frame::age_us);
+        if let Some(state) = latest.as_deref().and_then(State::from_bytes) {{
+            if state.magic == MAGIC {{
+                print!("\x1b[2J\x1b[H");
+                println!("🐍 {name} (Rust) | State v{{}} | {{}} bytes", state.version, std::mem::size_of::<State>());
+                println!("═══════════════════════════════════════════════════════════════");
+{fields}                println!("═══════════════════════════════════════════════════════════════");
+                println!("📊 Read Latency: {{:.2}} µs (min: {{:.2}}, max: {{:.2}}, avg: {{:.2}})",
+                    latency_us, lat_min, lat_max, lat_sum / lat_count as f64);
+                println!("📡 Daemon→client: {{:.2}} µs since the write", age_us);
+                println!("⏱️  Daemon interval: {{}} ms | +/- faster/slower | r refresh", interval_ms);
+            }}
+        }}
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }}
+    
+    println!("\n\n📊 Final Latency Stats (Rust): {{}} samples, min {{:.2}} µs, max {{:.2}} µs, avg {{:.2}} µs",
+        lat_count, lat_min, lat_max, lat_sum / lat_count as f64);
+    println!("\n👋 Goodbye!");
+}}
+"##,
+        name = config.name,
+        name_snake = name_snake,
+        fields = fields,
+        keys = COMMAND_KEYS,
+        args = CLIENT_ARGS
     )
 }
 
@@ -406,6 +1166,12 @@ cargo run --bin daemon
 cargo run --bin client
 ```
 
+## Tests
+
+`cargo test` decodes a known state built from `venom.toml`, so it catches a
+protocol struct that no longer matches the daemon's layout. With the daemon
+running, `{live_env}=1 cargo test` also reads the live channel.
+{ping}{instances}
 ## Configuration
 
 | Setting | Value |
@@ -417,12 +1183,16 @@ cargo run --bin client
 
 ## Project Structure
 
-- `src/lib.rs` - Protocol types and FFI bindings
+- `src/lib.rs` - Protocol types and FFI bindings, with their tests
 - `src/bin/daemon.rs` - System monitor daemon
 - `src/bin/client.rs` - Status display client
 - `lib/libvenom_memory.so` - VenomMemory library (bundled)
-"#,
+{service}"#,
         name = config.name,
+        service = super::service::readme_section(config),
+        live_env = super::LIVE_TEST_ENV,
+        ping = super::ping_readme_section("cargo run --bin client -- --ping 10"),
+        instances = super::instances_readme_section(&config.channel, "cargo run --bin daemon -- --channel-suffix SUFFIX", "cargo run --bin client -- --channel-suffix SUFFIX", "`channel_name()` in `src/lib.rs`"),
         channel = config.channel,
         data_size = config.data_size,
         cmd_slots = config.cmd_slots,
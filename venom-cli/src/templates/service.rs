@@ -0,0 +1,164 @@
+//! systemd packaging (`venom init --service`)
+//!
+//! `packaging/<name>.service` runs the daemon this project builds, in place,
+//! and `packaging/Makefile` installs and removes the unit. The unit names the
+//! project directory as [`ROOT`], which the Makefile fills in on install, so
+//! the generated files don't depend on where the project was generated.
+
+use super::{Language, ProjectConfig};
+
+/// Placeholder for the absolute project directory in the generated unit
+pub const ROOT: &str = "@ROOT@";
+
+/// Generate `packaging/` for the daemon of a `lang` project
+pub fn generate(config: &ProjectConfig, lang: Language) {
+    let dir = format!("{}/packaging", config.output_dir);
+    crate::create_dir(&dir);
+    crate::write_file(&format!("{}/{}.service", dir, config.name), &unit(config, lang));
+    crate::write_file(&format!("{}/Makefile", dir), &makefile(config, lang));
+}
+
+/// Built daemon relative to the project root, and the command that builds it
+fn daemon_binary(config: &ProjectConfig, lang: Language) -> (String, &'static str) {
+    let name = &config.name;
+    match lang {
+        Language::Rust => ("target/release/daemon".to_string(), "cargo build --release --bin daemon"),
+        Language::Go | Language::Nim => (format!("{}_daemon", name), "make daemon"),
+        Language::Zig => (format!("zig-out/bin/{}_daemon", name), "zig build"),
+        // C, C++, and the languages that ship the C daemon in daemon/
+        _ => (format!("daemon/{}_daemon", name), "make -C daemon"),
+    }
+}
+
+/// Whether the daemon has `--daemonize` (the C and Rust daemon templates)
+fn daemonizes(lang: Language) -> bool {
+    matches!(lang, Language::C | Language::Rust)
+}
+
+fn unit(config: &ProjectConfig, lang: Language) -> String {
+    let root = ROOT;
+    let (binary, build) = daemon_binary(config, lang);
+    let name = &config.name;
+    let start = if daemonizes(lang) {
+        format!(
+            "Type=forking\n\
+             ExecStart={root}/{binary} --daemonize --pidfile /run/{name}/{name}.pid --logfile /var/log/{name}/{name}.log\n\
+             PIDFile=/run/{name}/{name}.pid\n\
+             RuntimeDirectory={name}\n\
+             LogsDirectory={name}\n",
+            root = root,
+            binary = binary,
+            name = name
+        )
+    } else {
+        format!(
+            "Type=simple\n\
+             ExecStart={root}/{binary}\n\
+             # The status line is redrawn with \\r; errors still reach the journal\n\
+             StandardOutput=null\n",
+            root = root,
+            binary = binary
+        )
+    };
+    format!(r#"# {name} VenomMemory daemon
+# Build the daemon first ({build}), then `make -C packaging install`,
+# which installs it with {root} replaced by the project directory
+
+[Unit]
+Description={name} VenomMemory daemon (channel {channel})
+
+[Service]
+{start}WorkingDirectory={root}
+# The daemon removes its channel on SIGINT
+KillSignal=SIGINT
+Restart=on-failure
+
+# Sandbox: everything read-only except /dev/shm, where the channel lives.
+# No PrivateDevices, DynamicUser or RemoveIPC: they hide or delete the channel.
+NoNewPrivileges=yes
+ProtectSystem=strict
+ProtectHome=read-only
+ReadWritePaths=/dev/shm
+ProtectKernelTunables=yes
+ProtectKernelModules=yes
+ProtectControlGroups=yes
+RestrictAddressFamilies=AF_UNIX
+RestrictNamespaces=yes
+LockPersonality=yes
+
+[Install]
+WantedBy=multi-user.target
+"#,
+        name = name,
+        build = build,
+        channel = config.channel,
+        start = start,
+        root = root,
+    )
+}
+
+fn makefile(config: &ProjectConfig, lang: Language) -> String {
+    let (binary, build) = daemon_binary(config, lang);
+    format!(r#"# {name} systemd service
+# The unit runs ../{binary} in place; build it first ({build})
+
+UNIT = {name}.service
+DAEMON = ../{binary}
+ROOT := $(abspath $(CURDIR)/..)
+SYSTEMD_DIR ?= /etc/systemd/system
+
+.PHONY: install uninstall
+
+install:
+	@test -x $(DAEMON) || {{ echo "❌ $(DAEMON) is not built yet: {build}"; exit 1; }}
+	mkdir -p $(DESTDIR)$(SYSTEMD_DIR)
+	sed 's|{root}|$(ROOT)|g' $(UNIT) > $(DESTDIR)$(SYSTEMD_DIR)/$(UNIT)
+	chmod 644 $(DESTDIR)$(SYSTEMD_DIR)/$(UNIT)
+	@[ -n "$(DESTDIR)" ] || systemctl daemon-reload
+	@echo "✅ Installed; start it with: systemctl enable --now {name}"
+
+uninstall:
+	@[ -n "$(DESTDIR)" ] || systemctl disable --now $(UNIT) || true
+	rm -f $(DESTDIR)$(SYSTEMD_DIR)/$(UNIT)
+	@[ -n "$(DESTDIR)" ] || systemctl daemon-reload
+"#,
+        name = config.name,
+        binary = binary,
+        build = build,
+        root = ROOT,
+    )
+}
+
+/// Commands to install and start the service, for "Next steps"
+pub fn next_steps(config: &ProjectConfig) -> Vec<String> {
+    if !config.service {
+        return Vec::new();
+    }
+    vec![
+        format!("sudo make -C {}/packaging install   # after building the daemon", config.output_dir),
+        format!("sudo systemctl enable --now {}", config.name),
+        format!("systemctl status {}", config.name),
+    ]
+}
+
+/// README section on the service; empty without `--service`
+pub fn readme_section(config: &ProjectConfig) -> String {
+    if !config.service {
+        return String::new();
+    }
+    format!(r#"
+## systemd Service
+
+`packaging/{name}.service` runs the daemon built in this directory.
+
+```bash
+sudo make -C packaging install
+sudo systemctl enable --now {name}
+systemctl status {name}
+journalctl -u {name}
+
+# Remove it again
+sudo make -C packaging uninstall
+```
+"#, name = config.name)
+}
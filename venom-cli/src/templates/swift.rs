@@ -0,0 +1,917 @@
+//! Swift Templates for VenomMemory projects
+//!
+//! Generates a SwiftPM package with:
+//! - Sources/CVenom - System library target (module map + header) for libvenom_memory
+//! - Sources/{Name}/VenomShell.swift - RAII wrapper around the shell API
+//! - Sources/{Name}/State.swift - Decoder for the packed state (layout from venom.toml)
+//! - Sources/{Name}/main.swift - Terminal monitor with read latency benchmarking
+//! - native/libvenom_memory.{so,dylib} - Bundled library
+//! - daemon/ - Self-contained C daemon
+
+use super::ProjectConfig;
+use crate::schema::{camel_case, FieldType};
+
+pub fn generate(config: &ProjectConfig) {
+    let base = &config.output_dir;
+    
+    crate::create_dir(&format!("{}/daemon/src", base));
+    
+    // SwiftPM package
+    generate_client(config);
+    
+    // C Daemon files (so the Swift package is self-contained)
+    crate::write_file(&format!("{}/daemon/src/main.c", base), &daemon_c(config));
+    crate::write_file(&format!("{}/daemon/Makefile", base), &daemon_makefile(config));
+    crate::write_file(&format!("{}/daemon/protocol.h", base), &protocol_h(config));
+    
+    crate::write_file(&format!("{}/README.md", base), &readme(config));
+    
+    // Also copy the library to daemon/ (for the C daemon)
+    if config.targets.is_empty() {
+        crate::library::write_library(&format!("{}/daemon/libvenom_memory.{}", base, lib_ext()));
+    } else {
+        crate::library::install(&format!("{}/daemon", base), &config.targets);
+    }
+}
+
+/// Client files only (used by `venom add-client`), plus the library in native/ for SwiftPM
+pub fn generate_client(config: &ProjectConfig) {
+    let base = &config.output_dir;
+    let pascal = config.names().pascal;
+    
+    crate::create_dir(&format!("{}/Sources/CVenom", base));
+    crate::create_dir(&format!("{}/Sources/{}", base, pascal));
+    
+    crate::write_file(&format!("{}/Package.swift", base), &package_swift(config));
+    crate::write_file(&format!("{}/Sources/CVenom/module.modulemap", base), &module_map());
+    crate::write_file(&format!("{}/Sources/CVenom/venom.h", base), &venom_h());
+    crate::write_file(&format!("{}/Sources/{}/VenomShell.swift", base, pascal), &venom_shell_swift(config));
+    crate::write_file(&format!("{}/Sources/{}/State.swift", base, pascal), &state_swift(config));
+    crate::write_file(&format!("{}/Sources/{}/main.swift", base, pascal), &main_swift(config));
+    
+    if config.targets.is_empty() {
+        crate::library::write_library(&format!("{}/native/libvenom_memory.{}", base, lib_ext()));
+    } else {
+        crate::library::install(&format!("{}/native", base), &config.targets);
+    }
+}
+
+/// Shared library extension for the platform the project is generated on
+fn lib_ext() -> &'static str {
+    if cfg!(target_os = "macos") { "dylib" } else { "so" }
+}
+
+
+
+// ═══════════════════════════════════════════════════════════════════════════
+// C Daemon (so the Swift package is self-contained)
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn protocol_h(config: &ProjectConfig) -> String {
+    let upper = config.names().upper;
+    let pascal = config.names().pascal;
+    
+    format!(r#"#ifndef {upper}_PROTOCOL_H
+#define {upper}_PROTOCOL_H
+
+#include <stdint.h>
+#define {upper}_CHANNEL_NAME "{channel}"
+#define {upper}_MAGIC 0x{magic:08X}
+#define {upper}_STATE_SIZE {state_size}
+{max_cores}
+typedef struct __attribute__((packed)) {{
+{fields}}} {pascal}State;
+
+_Static_assert(sizeof({pascal}State) == {upper}_STATE_SIZE, "{pascal}State size mismatch");
+
+{commands}
+#endif
+"#,
+        upper = upper,
+        pascal = pascal,
+        channel = config.channel,
+        magic = config.magic,
+        state_size = config.schema.size,
+        max_cores = super::c::max_cores_define(config),
+        fields = config.schema.c_fields("    "),
+        commands = super::c::commands_h(config),
+    )
+}
+
+fn daemon_c(config: &ProjectConfig) -> String {
+    if !config.schema.has_demo_fields() {
+        return super::c::generic_daemon_c(config, "../protocol.h");
+    }
+    let upper = config.names().upper;
+    let pascal = config.names().pascal;
+    
+    format!(r#"/* {name} Daemon - VenomMemory */
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <signal.h>
+#include <unistd.h>
+#include <time.h>
+#include "../protocol.h"
+
+typedef struct VenomDaemonHandle VenomDaemonHandle;
+typedef struct {{ size_t struct_size; size_t data_size; size_t cmd_slots; size_t max_clients; size_t cmd_payload_size; size_t cmd_full_policy; size_t shm_mode; }} VenomConfigEx;
+extern VenomDaemonHandle* venom_daemon_create_ex(const char* name, const VenomConfigEx* config);
+extern void venom_daemon_destroy(VenomDaemonHandle* handle);
+extern void venom_daemon_write_data(VenomDaemonHandle* handle, const uint8_t* data, size_t len);
+extern size_t venom_daemon_try_recv_command(VenomDaemonHandle* handle, uint8_t* buf, size_t max_len, uint32_t* out_client_id);
+
+static VenomDaemonHandle* g_daemon = NULL;
+static {pascal}State g_state = {{0}};
+static volatile int g_running = 1;
+static uint64_t prev_total[{upper}_MAX_CORES + 1] = {{0}};
+static uint64_t prev_idle[{upper}_MAX_CORES + 1] = {{0}};
+
+static void signal_handler(int sig) {{ (void)sig; g_running = 0; }}
+
+{commands}
+{channel_arg}
+static void read_cpu(void) {{
+    FILE* f = fopen("/proc/stat", "r");
+    if (!f) return;
+    char line[256];
+    int idx = 0;
+    while (fgets(line, sizeof(line), f) && idx <= {upper}_MAX_CORES) {{
+        if (strncmp(line, "cpu", 3) != 0) continue;
+        uint64_t user, nice, system, idle, iowait, irq, softirq;
+        if (sscanf(line + (line[3] == ' ' ? 4 : 5), "%lu %lu %lu %lu %lu %lu %lu",
+                   &user, &nice, &system, &idle, &iowait, &irq, &softirq) != 7) continue;
+        uint64_t total = user + nice + system + idle + iowait + irq + softirq;
+        uint64_t idle_t = idle + iowait;
+        uint64_t td = total - prev_total[idx], id = idle_t - prev_idle[idx];
+        float usage = td > 0 ? (1.0f - (float)id / (float)td) * 100.0f : 0;
+        if (line[3] == ' ') g_state.cpu_usage_percent = usage;
+        else if (idx > 0 && idx <= {upper}_MAX_CORES) g_state.cpu_cores[idx-1] = usage;
+        prev_total[idx] = total; prev_idle[idx] = idle_t; idx++;
+    }}
+    g_state.core_count = idx > 1 ? idx - 1 : 0;
+    fclose(f);
+}}
+
+static void read_mem(void) {{
+    FILE* f = fopen("/proc/meminfo", "r");
+    if (!f) return;
+    char line[256];
+    uint64_t total = 0, avail = 0;
+    while (fgets(line, sizeof(line), f)) {{
+        if (strncmp(line, "MemTotal:", 9) == 0) sscanf(line + 9, "%lu", &total);
+        else if (strncmp(line, "MemAvailable:", 13) == 0) sscanf(line + 13, "%lu", &avail);
+    }}
+    g_state.memory_total_mb = (uint32_t)(total / 1024);
+    g_state.memory_used_mb = (uint32_t)((total - avail) / 1024);
+    fclose(f);
+}}
+
+static void read_uptime(void) {{
+    FILE* f = fopen("/proc/uptime", "r");
+    if (!f) return;
+    double up; if (fscanf(f, "%lf", &up) == 1) g_state.uptime_seconds = (uint64_t)up;
+    fclose(f);
+}}
+
+int main(int argc, char** argv) {{
+    printf("🖥️  {name} Daemon (VenomMemory)\\n");
+    printf("═══════════════════════════════════════════════════════════════\\n");
+    signal(SIGINT, signal_handler); signal(SIGTERM, signal_handler);
+    
+    VenomConfigEx cfg = {{ .struct_size = sizeof(VenomConfigEx), .data_size = 16384, .cmd_slots = 32, .max_clients = 16, .cmd_payload_size = sizeof({pascal}Command), .shm_mode = 0{shm_mode:o} }};
+    const char* channel = channel_from_args(argc, argv);
+    g_daemon = venom_daemon_create_ex(channel, &cfg);
+    if (!g_daemon) {{ printf("❌ Failed to create channel\\n"); return 1; }}
+    
+    printf("✅ Channel: %s\\n🚀 Publishing... (Ctrl+C to stop)\\n\\n", channel);
+    
+    uint64_t next_publish = 0;
+    while (g_running) {{
+        int refresh = handle_commands(g_daemon);
+        if (!refresh && now_ms() < next_publish) {{ usleep(10000); continue; }}
+        next_publish = now_ms() + g_interval_ms;
+        
+        read_cpu(); read_mem(); read_uptime();
+        g_state.magic = {upper}_MAGIC; g_state.version = 1; g_state.update_counter++;
+        struct timespec ts; clock_gettime(CLOCK_MONOTONIC, &ts);
+        g_state.timestamp_ns = (uint64_t)ts.tv_sec * 1000000000ULL + ts.tv_nsec;
+        publish(g_daemon, &g_state);
+        printf("\\r🖥️  CPU: %5.1f%% | RAM: %u/%u MB | #%lu | %u ms   ",
+            g_state.cpu_usage_percent, g_state.memory_used_mb, g_state.memory_total_mb,
+            (unsigned long)g_state.update_counter, g_interval_ms);
+        fflush(stdout);
+    }}
+    venom_daemon_destroy(g_daemon);
+    printf("\\n\\n👋 Goodbye!\\n");
+    return 0;
+}}
+"#, name = config.name, upper = upper, pascal = pascal, shm_mode = config.shm_mode, commands = super::c::command_handler_c(config), channel_arg = super::c::channel_arg_c(config))
+}
+
+fn daemon_makefile(config: &ProjectConfig) -> String {
+    format!(r#"# {name} Daemon Makefile
+
+{target_var}CC = gcc
+CFLAGS = -Wall -Wextra -O2
+LDFLAGS = -L.{sub} -lvenom_memory -Wl,-rpath,'$$ORIGIN{sub}'
+
+TARGET = {name}_daemon
+
+.PHONY: all clean run
+
+all: $(TARGET)
+
+$(TARGET): src/main.c
+	@echo "🔗 Building $(TARGET)..."
+	@$(CC) $(CFLAGS) src/main.c -o $(TARGET) $(LDFLAGS)
+	@echo "✅ Build complete"
+
+clean:
+	@rm -f $(TARGET)
+
+run: $(TARGET)
+	@./$(TARGET) $(ARGS)
+"#,
+        name = config.name,
+        target_var = super::make_target_var(config),
+        sub = super::make_lib_dir(config, ""),
+    )
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// SwiftPM Package
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn package_swift(config: &ProjectConfig) -> String {
+    let pascal = config.names().pascal;
+    let rpath_origin = if cfg!(target_os = "macos") { "@loader_path" } else { "$ORIGIN" };
+    let (native_dir, rpath_native) = if config.targets.is_empty() {
+        (
+            format!(r#"// native/ holds libvenom_memory.{ext}; link against it and embed an rpath so
+// the built executable finds it without LD_LIBRARY_PATH / DYLD_LIBRARY_PATH
+let nativeDir = URL(fileURLWithPath: #filePath).deletingLastPathComponent()
+    .appendingPathComponent("native").path
+"#, ext = lib_ext()),
+            format!("\"{}/../../../native\"", rpath_origin),
+        )
+    } else {
+        (
+            format!(r#"// native/<triple>/ holds one library per bundled target ({targets}); link
+// against the one built for this machine and embed an rpath so the built
+// executable finds it without LD_LIBRARY_PATH / DYLD_LIBRARY_PATH
+#if arch(arm64)
+let venomArch = "aarch64"
+#else
+let venomArch = "x86_64"
+#endif
+#if os(macOS)
+let venomOS = "darwin"
+#else
+let venomOS = "linux"
+#endif
+let nativeRoot = URL(fileURLWithPath: #filePath).deletingLastPathComponent()
+    .appendingPathComponent("native")
+let venomTarget = (try? FileManager.default.contentsOfDirectory(atPath: nativeRoot.path))?
+    .first {{ $0.hasPrefix(venomArch + "-") && $0.contains(venomOS) }} ?? "\(venomArch)-unknown-\(venomOS)"
+let nativeDir = nativeRoot.appendingPathComponent(venomTarget).path
+"#, targets = config.targets.join(" ")),
+            format!("\"{}/../../../native/\" + venomTarget", rpath_origin),
+        )
+    };
+    
+    format!(r#"// swift-tools-version:5.9
+// {name} - VenomMemory Swift client
+
+import PackageDescription
+import Foundation
+
+{native_dir}
+let package = Package(
+    name: "{pascal}",
+    targets: [
+        .systemLibrary(name: "CVenom", path: "Sources/CVenom"),
+        .executableTarget(
+            name: "{pascal}",
+            dependencies: ["CVenom"],
+            path: "Sources/{pascal}",
+            linkerSettings: [
+                .unsafeFlags([
+                    "-L", nativeDir,
+                    "-Xlinker", "-rpath", "-Xlinker", nativeDir,
+                    "-Xlinker", "-rpath", "-Xlinker", {rpath_native},
+                ]),
+            ]
+        ),
+    ]
+)
+"#,
+        name = config.name,
+        pascal = pascal,
+        native_dir = native_dir,
+        rpath_native = rpath_native,
+    )
+}
+
+fn module_map() -> String {
+    r#"module CVenom [system] {
+    header "venom.h"
+    link "venom_memory"
+    export *
+}
+"#.to_string()
+}
+
+fn venom_h() -> String {
+    r#"#ifndef CVENOM_H
+#define CVENOM_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+typedef struct VenomShellHandle VenomShellHandle;
+
+VenomShellHandle* venom_shell_connect(const char* name);
+void venom_shell_destroy(VenomShellHandle* handle);
+size_t venom_shell_read_data_stamped(VenomShellHandle* handle, uint8_t* buf, size_t max_len, uint64_t* out_timestamp_ns);
+uint64_t venom_clock_ns(void);
+const char* venom_version(void);
+uint32_t venom_shell_id(VenomShellHandle* handle);
+bool venom_shell_send_command(VenomShellHandle* handle, const uint8_t* cmd, size_t len);
+
+#endif
+"#.to_string()
+}
+
+fn venom_shell_swift(config: &ProjectConfig) -> String {
+    format!(r#"// VenomMemory Swift Bindings for {name}
+//
+// VenomShell owns a venom_shell handle and releases it in deinit.
+
+import CVenom
+
+public enum VenomError: Error, CustomStringConvertible {{
+    case connectFailed(String)
+
+    public var description: String {{
+        switch self {{
+        case .connectFailed(let channel):
+            return "Failed to connect to channel \"\(channel)\". Is the daemon running?"
+        }}
+    }}
+}}
+
+/// Commands the daemon understands (CmdType in daemon/protocol.h)
+public enum CmdType: UInt16 {{
+    /// Publish now
+    case refresh = 1
+    /// value = publish interval in ms (10-10000)
+    case setInterval = 2
+    /// value = sequence number, echoed back in a pong after the state
+    case ping = 3
+}}
+
+public final class VenomShell {{
+    /// Publish interval the daemon starts with
+    public static let defaultIntervalMs = 100
+    /// Reply to the last ping, published right after the state: client ID and seq (u32 each)
+    public static let pongSize = 8
+
+    private let handle: OpaquePointer
+    private var buffer = [UInt8](repeating: 0, count: State.size)
+
+    /// µs between the daemon's write and the last read (0 if unstamped)
+    public private(set) var writeAgeUs = 0.0
+
+    /// Connect to the channel published by the daemon
+    public init(channel: String = State.channelName) throws {{
+        guard let handle = venom_shell_connect(channel) else {{
+            throw VenomError.connectFailed(channel)
+        }}
+        self.handle = handle
+    }}
+
+    deinit {{
+        venom_shell_destroy(handle)
+    }}
+
+    /// Client ID assigned by the daemon
+    public var clientId: UInt32 {{
+        venom_shell_id(handle)
+    }}
+
+    /// Version of the libvenom_memory that was loaded
+    public static var libraryVersion: String {{
+        String(cString: venom_version())
+    }}
+
+    /// Read and decode state from daemon
+    public func readState() -> State {{
+        var writtenNs: UInt64 = 0
+        let len = buffer.withUnsafeMutableBufferPointer {{ buf in
+            venom_shell_read_data_stamped(handle, buf.baseAddress, buf.count, &writtenNs)
+        }}
+        if writtenNs != 0 {{
+            writeAgeUs = Double(venom_clock_ns() &- writtenNs) / 1000.0
+        }}
+        return len < State.size ? .empty : State(bytes: buffer)
+    }}
+
+    /// Queue a command for the daemon; false if the queue is full
+    @discardableResult
+    public func sendCommand(_ cmd: [UInt8]) -> Bool {{
+        cmd.withUnsafeBufferPointer {{ buf in
+            venom_shell_send_command(handle, buf.baseAddress, buf.count)
+        }}
+    }}
+
+    /// Send a Command, a typed command: cmd as the tag, the payload's
+    /// length (4), then value, all little-endian
+    @discardableResult
+    public func sendCommand(_ cmd: CmdType, value: Int32 = 0) -> Bool {{
+        let (c, v) = (cmd.rawValue, UInt32(bitPattern: value))
+        return sendCommand([UInt8(c & 0xFF), UInt8(c >> 8), 4, 0,
+                            UInt8(v & 0xFF), UInt8(v >> 8 & 0xFF), UInt8(v >> 16 & 0xFF), UInt8(v >> 24)])
+    }}
+
+    /// Ask the daemon to publish every `ms` milliseconds
+    @discardableResult
+    public func setInterval(_ ms: Int) -> Bool {{
+        sendCommand(.setInterval, value: Int32(ms))
+    }}
+
+    /// Ask the daemon to publish right away
+    @discardableResult
+    public func refresh() -> Bool {{
+        sendCommand(.refresh)
+    }}
+
+    /// Round trip of a ping carrying `seq` in µs, or nil if no pong came back within a second
+    ///
+    /// The daemon answers by publishing at once with a pong after the state. It keeps
+    /// only the last one, so clients pinging at the same time can miss their reply.
+    public func ping(seq: UInt32) -> Double? {{
+        let start = venom_clock_ns()
+        guard sendCommand(.ping, value: Int32(bitPattern: seq)) else {{ return nil }}
+        let id = clientId
+        var buf = [UInt8](repeating: 0, count: State.size + VenomShell.pongSize)
+        var writtenNs: UInt64 = 0
+        while venom_clock_ns() &- start < 1_000_000_000 {{
+            let len = buf.withUnsafeMutableBufferPointer {{ b in
+                venom_shell_read_data_stamped(handle, b.baseAddress, b.count, &writtenNs)
+            }}
+            guard len >= buf.count else {{ continue }}
+            let (client, echoed) = buf.withUnsafeBytes {{ raw in
+                (UInt32(littleEndian: raw.loadUnaligned(fromByteOffset: State.size, as: UInt32.self)),
+                 UInt32(littleEndian: raw.loadUnaligned(fromByteOffset: State.size + 4, as: UInt32.self)))
+            }}
+            if client == id && echoed == seq {{
+                return Double(venom_clock_ns() &- start) / 1000.0
+            }}
+        }}
+        return nil
+    }}
+}}
+"#, name = config.name)
+}
+
+fn state_swift(config: &ProjectConfig) -> String {
+    let upper = config.names().upper;
+    let schema = &config.schema;
+    
+    let max_cores = match schema.field("cpu_cores").and_then(|f| f.len) {
+        Some(n) if schema.has_demo_fields() => format!("    public static let maxCores = {}\n", n),
+        _ => String::new(),
+    };
+    
+    let mut decls = String::new();
+    let mut inits = String::new();
+    for f in schema.data_fields() {
+        let name = camel_case(&f.name);
+        let ty = swift_type(f.ty);
+        match f.len {
+            Some(n) => {
+                decls.push_str(&format!("    public let {}: [{}]\n", name, ty));
+                inits.push_str(&format!(
+                    "        {} = (0..<{}).map {{ {} }}\n",
+                    name,
+                    n,
+                    swift_load(f.ty, &format!("{} + $0 * {}", f.offset, f.ty.size()))
+                ));
+            }
+            None => {
+                decls.push_str(&format!("    public let {}: {}\n", name, ty));
+                inits.push_str(&format!("        {} = {}\n", name, swift_load(f.ty, &f.offset.to_string())));
+            }
+        }
+    }
+    
+    format!(r#"// {name} state - decodes the packed C struct from daemon/protocol.h
+
+public struct State {{
+    // Configuration (from {upper}_CHANNEL_NAME / {upper}_MAGIC)
+    public static let channelName = "{channel}"
+    public static let magic: UInt32 = 0x{magic:08X}
+{max_cores}    public static let size = {state_size}
+
+    // Fields (packed layout, generated from venom.toml)
+{decls}
+    public static let empty = State(bytes: [UInt8](repeating: 0, count: size))
+
+    /// The channel of one of several instances: "{channel}-SUFFIX", or
+    /// `channelName` itself for no suffix or an empty one
+    public static func instanceChannel(_ suffix: String?) -> String {{
+        guard let suffix = suffix, !suffix.isEmpty else {{ return channelName }}
+        return "\(channelName)-\(suffix)"
+    }}
+
+    /// Decode state from raw bytes (must match C struct layout)
+    public init(bytes: [UInt8]) {{
+        precondition(bytes.count >= State.size, "state needs \(State.size) bytes")
+        self = bytes.withUnsafeBytes {{ State(raw: $0) }}
+    }}
+
+    private init(raw: UnsafeRawBufferPointer) {{
+        func load<T: FixedWidthInteger>(_ offset: Int, as _: T.Type) -> T {{
+            T(littleEndian: raw.loadUnaligned(fromByteOffset: offset, as: T.self))
+        }}
+
+{inits}    }}
+
+    public var isValid: Bool {{ magic == State.magic }}
+{helpers}}}
+"#,
+        name = config.name,
+        upper = upper,
+        channel = config.channel,
+        magic = config.magic,
+        max_cores = max_cores,
+        state_size = schema.size,
+        decls = decls,
+        inits = inits,
+        helpers = if schema.has_demo_fields() { DEMO_HELPERS } else { "" }
+    )
+}
+
+/// Properties used by the system-monitor demo
+const DEMO_HELPERS: &str = r#"
+    public var memoryUsagePercent: Double {
+        memoryTotalMb > 0 ? Double(memoryUsedMb) / Double(memoryTotalMb) * 100 : 0
+    }
+
+    public var uptimeFormatted: String {
+        "\(uptimeSeconds / 3600)h \((uptimeSeconds % 3600) / 60)m"
+    }
+"#;
+
+fn swift_type(ty: FieldType) -> &'static str {
+    match ty {
+        FieldType::U8 => "UInt8",
+        FieldType::U16 => "UInt16",
+        FieldType::U32 => "UInt32",
+        FieldType::U64 => "UInt64",
+        FieldType::I8 => "Int8",
+        FieldType::I16 => "Int16",
+        FieldType::I32 => "Int32",
+        FieldType::I64 => "Int64",
+        FieldType::F32 => "Float",
+        FieldType::F64 => "Double",
+    }
+}
+
+/// Little-endian load of one element at `offset` (floats go through their bit pattern)
+fn swift_load(ty: FieldType, offset: &str) -> String {
+    match ty {
+        FieldType::F32 => format!("Float(bitPattern: load({}, as: UInt32.self))", offset),
+        FieldType::F64 => format!("Double(bitPattern: load({}, as: UInt64.self))", offset),
+        _ => format!("load({}, as: {}.self)", offset, swift_type(ty)),
+    }
+}
+
+/// Keyboard handling shared by both clients: +/- change the daemon's
+/// publish interval and r asks for an immediate publish
+const COMMAND_KEYS: &str = r#"
+// ⌨️  +/- faster/slower, r refresh (stdin unbuffered and silent while running)
+var savedTerm = termios()
+let keysActive = isatty(STDIN_FILENO) != 0 && tcgetattr(STDIN_FILENO, &savedTerm) == 0
+if keysActive {
+    var raw = savedTerm
+    raw.c_lflag &= ~tcflag_t(ICANON | ECHO)
+    tcsetattr(STDIN_FILENO, TCSANOW, &raw)
+}
+
+/// Apply pending keys; returns the interval the daemon was asked for
+func handleKeys(_ shell: VenomShell, _ intervalMs: Int) -> Int {
+    var intervalMs = intervalMs
+    var pfd = pollfd(fd: STDIN_FILENO, events: Int16(POLLIN), revents: 0)
+    var key: UInt8 = 0
+    while keysActive && poll(&pfd, 1, 0) > 0 && read(STDIN_FILENO, &key, 1) == 1 {
+        switch key {
+        case UInt8(ascii: "+"), UInt8(ascii: "="):
+            intervalMs = max(10, intervalMs / 2)
+            shell.setInterval(intervalMs)
+        case UInt8(ascii: "-"):
+            intervalMs = min(10000, intervalMs * 2)
+            shell.setInterval(intervalMs)
+        case UInt8(ascii: "r"):
+            shell.refresh()
+        default:
+            break
+        }
+    }
+    return intervalMs
+}
+"#;
+
+/// `--ping COUNT`, shared by both clients: times round trips through the
+/// daemon instead of showing the state
+const PING_ARG: &str = r#"
+/// Time `count` round trips through the daemon; false if none came back
+func runPing(_ shell: VenomShell, count: Int) -> Bool {
+    // A pong for an earlier client with the same ID may still be published
+    var seq = UInt32(truncatingIfNeeded: DispatchTime.now().uptimeNanoseconds)
+    var rtts: [Double] = []
+    for i in 1...count {
+        seq &+= 1
+        guard let rttUs = shell.ping(seq: seq) else {
+            print("⚠️  No reply to ping #\(i)")
+            continue
+        }
+        print("🏓 Pong #\(i): \(String(format: "%.1f", rttUs)) µs")
+        rtts.append(rttUs)
+        usleep(100_000)
+    }
+    guard let rttMin = rtts.min(), let rttMax = rtts.max() else { return false }
+    let rttAvg = rtts.reduce(0, +) / Double(rtts.count)
+    print("📊 Round trip: \(rtts.count)/\(count) replies, "
+        + String(format: "min %.1f µs, avg %.1f µs, max %.1f µs", rttMin, rttAvg, rttMax))
+    return true
+}
+
+let args = CommandLine.arguments
+if let i = args.firstIndex(of: "--ping"), i + 1 < args.count, let count = Int(args[i + 1]), count > 0 {
+    exit(runPing(shell, count: count) ? 0 : 1)
+}
+"#;
+
+fn main_swift(config: &ProjectConfig) -> String {
+    if !config.schema.has_demo_fields() {
+        return generic_main_swift(config);
+    }
+    format!(r#"// {name} - VenomMemory Client Example - with Benchmarking
+//
+// Demonstrates connecting to daemon and reading system stats.
+// Includes read latency measurements.
+
+#if canImport(Glibc)
+import Glibc
+#else
+import Darwin
+#endif
+import Foundation
+
+// ANSI colors
+let cyan = "\u{{1B}}[96m"
+let reset = "\u{{1B}}[0m"
+
+// Handle Ctrl+C for final stats
+var running = true
+signal(SIGINT) {{ _ in running = false }}
+
+print("🖥️  {name} Client (Swift)")
+print("═══════════════════════════════════════════════════════════════")
+
+// Latency tracking
+var latencyMin = Double.greatestFiniteMagnitude
+var latencyMax = 0.0
+var latencySum = 0.0
+var latencyCount = 0
+var frame = 0
+
+// --channel-suffix SUFFIX: connect to that instance of the daemon
+let channelSuffix = CommandLine.arguments.drop(while: {{ $0 != "--channel-suffix" }}).dropFirst().first
+
+let shell: VenomShell
+do {{
+    shell = try VenomShell(channel: State.instanceChannel(channelSuffix))
+}} catch {{
+    print("❌ Error: \(error)")
+    print("\nMake sure:")
+    print("  1. The daemon is running")
+    print("  2. native/libvenom_memory.{ext} exists")
+    exit(1)
+}}
+
+print("✅ Connected! Client ID: \(shell.clientId) (libvenom_memory \(VenomShell.libraryVersion))")
+{ping}
+print("📊 Reading system stats... (Ctrl+C to exit)\n")
+{keys}
+var intervalMs = VenomShell.defaultIntervalMs
+
+func fmt(_ value: Double, _ spec: String = "%.2f") -> String {{
+    String(format: spec, value)
+}}
+
+while running {{
+    intervalMs = handleKeys(shell, intervalMs)
+
+    // ═══════════════════════════════════════════════════════════════════
+    // 📊 BENCHMARK: Measure read latency
+    // ═══════════════════════════════════════════════════════════════════
+    let start = DispatchTime.now().uptimeNanoseconds
+    let state = shell.readState()
+    let latencyUs = Double(DispatchTime.now().uptimeNanoseconds - start) / 1000
+
+    // Update stats
+    latencyMin = min(latencyMin, latencyUs)
+    latencyMax = max(latencyMax, latencyUs)
+    latencySum += latencyUs
+    latencyCount += 1
+    let avgUs = latencySum / Double(latencyCount)
+
+    if state.isValid {{
+        // Clear screen and move cursor to top
+        print("\u{{1B}}[2J\u{{1B}}[H", terminator: "")
+
+        print("╔═══════════════════════════════════════════════════════════════╗")
+        print("║  🖥️  {name} Monitor (Swift)    Frame: \(String(frame).padding(toLength: 6, withPad: " ", startingAt: 0))           ║")
+        print("╠═══════════════════════════════════════════════════════════════╣")
+        print("║  CPU: \(fmt(Double(state.cpuUsagePercent), "%5.1f"))%  |  "
+            + "RAM: \(state.memoryUsedMb)/\(state.memoryTotalMb) MB  |  "
+            + "Uptime: \(state.uptimeFormatted)  ║")
+        print("╠═══════════════════════════════════════════════════════════════╣")
+
+        // Show per-core usage (all cores)
+        for i in 0..<min(Int(state.coreCount), State.maxCores) {{
+            print("║  Core \(i): \(fmt(Double(state.cpuCores[i]), "%5.1f"))%                                                ║")
+        }}
+
+        print("╠═══════════════════════════════════════════════════════════════╣")
+        print("║  Memory: \(fmt(state.memoryUsagePercent, "%.1f"))% used                                           ║")
+        print("╠═══════════════════════════════════════════════════════════════╣")
+        print("║  📊 \(cyan)Read Latency:\(reset) \(fmt(latencyUs)) µs (min: \(fmt(latencyMin)), max: \(fmt(latencyMax)), avg: \(fmt(avgUs)))  ║")
+        print("║  📡 \(cyan)Daemon→client:\(reset) \(fmt(shell.writeAgeUs)) µs since the write                 ║")
+        print("╚═══════════════════════════════════════════════════════════════╝")
+        print("  Updates: \(state.updateCounter) | Interval: \(intervalMs) ms")
+        print("  +/- faster/slower | r refresh | Ctrl+C to exit")
+        frame += 1
+    }} else {{
+        print("⏳ Waiting for valid data from daemon...")
+    }}
+
+    usleep(100_000)
+}}
+
+if keysActive {{ tcsetattr(STDIN_FILENO, TCSANOW, &savedTerm) }}
+
+print("\n")
+print("📊 \(cyan)Final Latency Stats (Swift):\(reset)")
+print("   Samples: \(latencyCount)")
+print("   Min: \(fmt(latencyMin)) µs")
+print("   Max: \(fmt(latencyMax)) µs")
+print("   Avg: \(fmt(latencySum / Double(latencyCount))) µs")
+print("\n👋 Goodbye!")
+"#, name = config.name, ext = lib_ext(), ping = PING_ARG, keys = COMMAND_KEYS)
+}
+
+/// Client for a custom schema: prints every field of the state
+fn generic_main_swift(config: &ProjectConfig) -> String {
+    let fields: String = config.schema.user_fields()
+        .map(|f| format!("        print(\"  {:<24} \\(state.{})\")\n", f.name, camel_case(&f.name)))
+        .collect();
+    
+    format!(r#"// {name} - VenomMemory Client - prints every field of the state (see venom.toml)
+
+#if canImport(Glibc)
+import Glibc
+#else
+import Darwin
+#endif
+import Foundation
+
+var running = true
+signal(SIGINT) {{ _ in running = false }}
+
+// --channel-suffix SUFFIX: connect to that instance of the daemon
+let channelSuffix = CommandLine.arguments.drop(while: {{ $0 != "--channel-suffix" }}).dropFirst().first
+
+let shell: VenomShell
+do {{
+    shell = try VenomShell(channel: State.instanceChannel(channelSuffix))
+}} catch {{
+    print("❌ Error: \(error)")
+    exit(1)
+}}
+
+print("✅ Connected! Client ID: \(shell.clientId) (libvenom_memory \(VenomShell.libraryVersion))")
+{ping}{keys}
+var intervalMs = VenomShell.defaultIntervalMs
+var latencyMin = Double.greatestFiniteMagnitude
+var latencyMax = 0.0
+var latencySum = 0.0
+var latencyCount = 0
+
+func fmt(_ value: Double) -> String {{
+    String(format: "%.2f", value)
+}}
+
+while running {{
+    intervalMs = handleKeys(shell, intervalMs)
+
+    let start = DispatchTime.now().uptimeNanoseconds
+    let state = shell.readState()
+    let latencyUs = Double(DispatchTime.now().uptimeNanoseconds - start) / 1000
+
+    latencyMin = min(latencyMin, latencyUs)
+    latencyMax = max(latencyMax, latencyUs)
+    latencySum += latencyUs
+    latencyCount += 1
+
+    if state.isValid {{
+        print("\u{{1B}}[2J\u{{1B}}[H", terminator: "")
+        print("🖥️  {name} | State v\(state.version) | \(State.size) bytes")
+        print("═══════════════════════════════════════════════════════════════")
+{fields}        print("═══════════════════════════════════════════════════════════════")
+        print("📊 Read Latency: \(fmt(latencyUs)) µs (min: \(fmt(latencyMin)), max: \(fmt(latencyMax)), avg: \(fmt(latencySum / Double(latencyCount))))")
+        print("📡 Daemon→client: \(fmt(shell.writeAgeUs)) µs since the write")
+        print("⏱️  Daemon interval: \(intervalMs) ms | +/- faster/slower | r refresh")
+    }} else {{
+        print("⏳ Waiting for valid data from daemon...")
+    }}
+
+    usleep(100_000)
+}}
+
+if keysActive {{ tcsetattr(STDIN_FILENO, TCSANOW, &savedTerm) }}
+"#, name = config.name, fields = fields, ping = PING_ARG, keys = COMMAND_KEYS)
+}
+
+fn readme(config: &ProjectConfig) -> String {
+    let pascal = config.names().pascal;
+    format!(r#"# {name} (Swift + C Daemon)
+
+VenomMemory Swift client (SwiftPM, macOS/Linux).
+
+## Project Structure
+
+```
+{name}/
+├── Package.swift
+├── Sources/
+│   ├── CVenom/                  # System library target
+│   │   ├── module.modulemap
+│   │   └── venom.h
+│   └── {pascal}/
+│       ├── VenomShell.swift     # RAII wrapper (deinit destroys the handle)
+│       ├── State.swift          # Packed state decoder
+│       └── main.swift           # Example client
+├── native/
+│   └── libvenom_memory.{ext}    # Bundled VenomMemory library
+└── daemon/                      # C daemon (system monitor)
+    ├── src/main.c
+    └── Makefile
+```
+
+## Quick Start
+
+```bash
+# Terminal 1 - Start C daemon
+cd daemon && make run
+
+# Terminal 2 - Build and run the Swift client
+swift run
+```
+
+## Usage in Your Code
+
+```swift
+let shell = try VenomShell()
+print("Connected! ID: \(shell.clientId)")
+
+let state = shell.readState()
+if state.isValid {{
+    print("CPU: \(state.cpuUsage)%")
+    print("RAM: \(state.memoryUsedMb)/\(state.memoryTotalMb) MB")
+    print("Uptime: \(state.uptimeFormatted)")
+}}
+// The handle is released when `shell` goes out of scope
+```
+
+## Configuration
+
+| Setting | Value |
+|---------|-------|
+| Channel | `{channel}` |
+| Magic | `0x{magic:08X}` |
+{ping}{instances}{service}"#,
+        name = config.name,
+        ping = super::ping_readme_section(&format!("swift run {} --ping 10", pascal)),
+        instances = super::instances_readme_section(&config.channel, "cd daemon && make run ARGS=\"--channel-suffix SUFFIX\"", &format!("swift run {} --channel-suffix SUFFIX", pascal), "`State.instanceChannel()`"),
+        service = super::service::readme_section(config),
+        pascal = pascal,
+        ext = lib_ext(),
+        channel = config.channel,
+        magic = config.magic
+    )
+}
@@ -14,38 +14,47 @@ pub fn generate(config: &ProjectConfig) {
     
     // Main source files
     crate::write_file(&format!("{}/src/venom.zig", base), &venom_zig(config));
+    crate::write_file(&format!("{}/src/venom_test.zig", base), &venom_test_zig(config));
     crate::write_file(&format!("{}/src/daemon.zig", base), &daemon_zig(config));
     crate::write_file(&format!("{}/src/client.zig", base), &client_zig(config));
     
     // Build file
-    crate::write_file(&format!("{}/build.zig", base), &build_zig(config));
+    crate::write_file(&format!("{}/build.zig", base), &build_zig(config, true));
     
     // README
     crate::write_file(&format!("{}/README.md", base), &readme(config));
 }
 
-fn magic(channel: &str) -> u32 {
-    channel.bytes().fold(0x564E4Fu32, |acc, b| acc.wrapping_add(b as u32))
-}
-
-fn pascal_case(s: &str) -> String {
-    s.split(|c| c == '_' || c == '-')
-        .map(|word| {
-            let mut chars = word.chars();
-            match chars.next() {
-                None => String::new(),
-                Some(first) => first.to_uppercase().chain(chars).collect(),
-            }
-        })
-        .collect()
+/// Client files only (used by `venom add-client`)
+pub fn generate_client(config: &ProjectConfig) {
+    let base = &config.output_dir;
+    
+    crate::create_dir(&format!("{}/src", base));
+    crate::write_file(&format!("{}/src/venom.zig", base), &venom_zig(config));
+    crate::write_file(&format!("{}/src/venom_test.zig", base), &venom_test_zig(config));
+    crate::write_file(&format!("{}/src/client.zig", base), &client_zig(config));
+    crate::write_file(&format!("{}/build.zig", base), &build_zig(config, false));
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
 // Venom bindings (Zig)
 // ═══════════════════════════════════════════════════════════════════════════
 
-fn venom_zig(config: &ProjectConfig) -> String {
-    let pascal = pascal_case(&config.name);
+pub(super) fn venom_zig(config: &ProjectConfig) -> String {
+    let schema = &config.schema;
+    let mut fields = String::new();
+    for f in &schema.fields {
+        let ty = f.ty.as_str();
+        match f.len {
+            Some(n) => fields.push_str(&format!("    {}: [{n}]{ty} = [_]{ty}{{0}} ** {n},\n", zig_name(&f.name), n = n, ty = ty)),
+            None => fields.push_str(&format!("    {}: {} = 0,\n", zig_name(&f.name), ty)),
+        }
+    }
+    let demo = schema.has_demo_fields();
+    let max_cores = match schema.field("cpu_cores") {
+        Some(f) if demo => format!("pub const max_cores: usize = {};\n", f.count()),
+        _ => String::new(),
+    };
     
     format!(r##"//! VenomMemory Zig Bindings
 const std = @import("std");
@@ -59,35 +68,39 @@ pub const magic: u32 = 0x{magic:08X};
 pub const data_size: usize = {data_size};
 pub const cmd_slots: usize = {cmd_slots};
 pub const max_clients: usize = {max_clients};
-pub const max_cores: usize = 16;
+pub const state_size: usize = {state_size};
+{max_cores}
+/// Longest channel name `instanceChannel` builds, as the library accepts it
+pub const channel_name_max = 255;
+
+/// The channel of one of several instances: "{channel}-SUFFIX", formatted
+/// into `buf`, or `channel_name` itself for no suffix or an empty one
+pub fn instanceChannel(buf: []u8, suffix: ?[]const u8) ![:0]const u8 {{
+    const s = suffix orelse return channel_name;
+    if (s.len == 0) return channel_name;
+    return std.fmt.bufPrintZ(buf, "{{s}}-{{s}}", .{{ channel_name, s }});
+}}
+
+/// The channel named by `--channel-suffix SUFFIX` on the command line, for
+/// the daemon and clients alike
+pub fn channelFromArgs(buf: []u8) ![:0]const u8 {{
+    var args = std.process.args();
+    while (args.next()) |arg| {{
+        if (std.mem.eql(u8, arg, "--channel-suffix")) return instanceChannel(buf, args.next());
+    }}
+    return channel_name;
+}}
 
 // ═══════════════════════════════════════════════════════════════════════════
-// State Structure (packed to match C layout)
+// State Structure (C layout, generated from venom.toml)
 // ═══════════════════════════════════════════════════════════════════════════
 
 pub const State = extern struct {{
-    magic_num: u32 = 0,
-    version: u32 = 0,
-    cpu_usage_percent: f32 = 0,
-    cpu_cores: [max_cores]f32 = [_]f32{{0}} ** max_cores,
-    core_count: u32 = 0,
-    memory_used_mb: u32 = 0,
-    memory_total_mb: u32 = 0,
-    uptime_seconds: u64 = 0,
-    update_counter: u64 = 0,
-    timestamp_ns: u64 = 0,
-
+{fields}
     pub fn isValid(self: *const State) bool {{
         return self.magic_num == magic;
     }}
-
-    pub fn memoryPercent(self: *const State) f32 {{
-        if (self.memory_total_mb > 0) {{
-            return @as(f32, @floatFromInt(self.memory_used_mb)) / @as(f32, @floatFromInt(self.memory_total_mb)) * 100.0;
-        }}
-        return 0;
-    }}
-
+{helpers}
     pub fn fromBytes(data: []const u8) State {{
         if (data.len < @sizeOf(State)) return State{{}};
         return std.mem.bytesToValue(State, data[0..@sizeOf(State)]);
@@ -99,27 +112,69 @@ pub const State = extern struct {{
 }};
 
 comptime {{
-    if (@sizeOf(State) != 112) @compileError("State size mismatch");
+    if (@sizeOf(State) != state_size) @compileError("State size mismatch");
+}}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// 📨 Commands (Clients send, Daemon applies)
+// ═══════════════════════════════════════════════════════════════════════════
+
+pub const interval_ms: u32 = 100; // Default publish interval
+
+pub const CmdType = enum(u16) {{
+    refresh = 1, // Publish immediately
+    set_interval = 2, // value: publish interval in ms (10-10000)
+    ping = 3, // value: sequence number, echoed back in a Pong
+    _,
+}};
+
+/// A typed command: `cmd` is the tag, `len` the bytes of payload after the
+/// envelope, those of `value`
+pub const Command = extern struct {{
+    cmd: CmdType,
+    len: u16 = @sizeOf(i32),
+    value: i32 = 0,
+}};
+
+/// Reply to the last ping, published right after the state
+pub const Pong = extern struct {{
+    client_id: u32 = 0, // Client that sent the ping
+    seq: u32 = 0, // Its value
+}};
+
+comptime {{
+    if (@sizeOf(Command) != 8) @compileError("Command size mismatch");
+    if (@sizeOf(Pong) != 8) @compileError("Pong size mismatch");
 }}
 
 // ═══════════════════════════════════════════════════════════════════════════
 // C FFI Bindings
 // ═══════════════════════════════════════════════════════════════════════════
 
-const VenomConfig = extern struct {{
+const VenomConfigEx = extern struct {{
+    struct_size: usize,
     data_size: usize,
     cmd_slots: usize,
     max_clients: usize,
+    cmd_payload_size: usize,
+    cmd_full_policy: usize = 0,
+    shm_mode: usize,
 }};
 
-extern fn venom_daemon_create(name: [*:0]const u8, config: VenomConfig) ?*anyopaque;
+extern fn venom_daemon_create_ex(name: [*:0]const u8, config: *const VenomConfigEx) ?*anyopaque;
 extern fn venom_daemon_destroy(handle: *anyopaque) void;
 extern fn venom_daemon_write_data(handle: *anyopaque, data: [*]const u8, len: usize) void;
+extern fn venom_daemon_try_recv_command(handle: *anyopaque, buf: [*]u8, max_len: usize, out_client_id: *u32) usize;
+extern fn venom_daemon_client_name(handle: *anyopaque, client_id: u32, buf: [*]u8, max_len: usize) usize;
 
-extern fn venom_shell_connect(name: [*:0]const u8) ?*anyopaque;
+extern fn venom_shell_connect_named(name: [*:0]const u8, client_name: ?[*:0]const u8) ?*anyopaque;
 extern fn venom_shell_destroy(handle: *anyopaque) void;
-extern fn venom_shell_read_data(handle: *anyopaque, buf: [*]u8, max_len: usize) usize;
+extern fn venom_shell_read_data_stamped(handle: *anyopaque, buf: [*]u8, max_len: usize, out_timestamp_ns: *u64) usize;
+extern fn venom_clock_ns() u64;
+extern fn venom_version() [*:0]const u8;
 extern fn venom_shell_id(handle: *anyopaque) u32;
+extern fn venom_shell_daemon_alive(handle: *anyopaque) bool;
+extern fn venom_shell_send_command(handle: *anyopaque, cmd: [*]const u8, len: usize) bool;
 
 // ═══════════════════════════════════════════════════════════════════════════
 // Daemon Wrapper
@@ -127,20 +182,64 @@ extern fn venom_shell_id(handle: *anyopaque) u32;
 
 pub const Daemon = struct {{
     handle: *anyopaque,
+    /// Reply to the last ping, published with every state from then on
+    last_pong: ?Pong = null,
 
     pub fn init() !Daemon {{
-        const cfg = VenomConfig{{
+        return initOn(channel_name);
+    }}
+
+    /// Create another channel than `channel_name`, such as one `instanceChannel` names
+    pub fn initOn(channel: [*:0]const u8) !Daemon {{
+        const cfg = VenomConfigEx{{
+            .struct_size = @sizeOf(VenomConfigEx),
             .data_size = data_size,
             .cmd_slots = cmd_slots,
             .max_clients = max_clients,
+            .cmd_payload_size = @sizeOf(Command),
+            .shm_mode = 0o{shm_mode:o},
         }};
-        const h = venom_daemon_create(channel_name, cfg) orelse return error.CreateFailed;
+        const h = venom_daemon_create_ex(channel, &cfg) orelse return error.CreateFailed;
         return Daemon{{ .handle = h }};
     }}
 
+    /// Publish the state, followed by the last pong once there is one
     pub fn write(self: *Daemon, state: *const State) void {{
-        const bytes = state.toBytes();
-        venom_daemon_write_data(self.handle, &bytes, bytes.len);
+        var buf: [@sizeOf(State) + @sizeOf(Pong)]u8 = undefined;
+        @memcpy(buf[0..@sizeOf(State)], &state.toBytes());
+        var len: usize = @sizeOf(State);
+        if (self.last_pong) |last| {{
+            @memcpy(buf[@sizeOf(State)..], &std.mem.toBytes(last));
+            len = buf.len;
+        }}
+        venom_daemon_write_data(self.handle, &buf, len);
+    }}
+
+    /// Answer a ping; the pong goes out with every write until the next one
+    pub fn pong(self: *Daemon, client_id: u32, seq: u32) void {{
+        self.last_pong = .{{ .client_id = client_id, .seq = seq }};
+    }}
+
+    pub const Received = struct {{ client_id: u32, cmd: Command }};
+
+    /// Next queued Command, skipping messages that aren't one
+    pub fn tryRecv(self: *Daemon) ?Received {{
+        var buf: [64]u8 = undefined;
+        var client_id: u32 = 0;
+        while (true) {{
+            const n = venom_daemon_try_recv_command(self.handle, &buf, buf.len, &client_id);
+            if (n == 0) return null;
+            if (n < @sizeOf(Command)) continue;
+            const cmd = std.mem.bytesToValue(Command, buf[0..@sizeOf(Command)]);
+            if (cmd.len != @sizeOf(i32)) continue;
+            return .{{ .client_id = client_id, .cmd = cmd }};
+        }}
+    }}
+
+    /// Name the client connected with, copied into `buf`; null if it has none
+    pub fn clientName(self: *Daemon, client_id: u32, buf: []u8) ?[]const u8 {{
+        const n = venom_daemon_client_name(self.handle, client_id, buf.ptr, buf.len);
+        return if (n == 0) null else buf[0..n];
     }}
 
     pub fn deinit(self: *Daemon) void {{
@@ -154,9 +253,21 @@ pub const Daemon = struct {{
 
 pub const Shell = struct {{
     handle: *anyopaque,
+    /// µs between the daemon's write and the last readState (0 if unstamped)
+    write_age_us: f64 = 0.0,
 
     pub fn connect() !Shell {{
-        const h = venom_shell_connect(channel_name) orelse return error.ConnectFailed;
+        return connectNamed(null);
+    }}
+
+    /// Connect with a name the daemon shows in its log (the library keeps its first 32 bytes)
+    pub fn connectNamed(client_name: ?[*:0]const u8) !Shell {{
+        return connectChannel(channel_name, client_name);
+    }}
+
+    /// Connect to another channel than `channel_name`, such as one `instanceChannel` names
+    pub fn connectChannel(channel: [*:0]const u8, client_name: ?[*:0]const u8) !Shell {{
+        const h = venom_shell_connect_named(channel, client_name) orelse return error.ConnectFailed;
         return Shell{{ .handle = h }};
     }}
 
@@ -164,26 +275,307 @@ pub const Shell = struct {{
         return venom_shell_id(self.handle);
     }}
 
+    /// False once the daemon has exited; reads then return its last state
+    pub fn daemonAlive(self: *Shell) bool {{
+        return venom_shell_daemon_alive(self.handle);
+    }}
+
+    /// Version of the libvenom_memory that was loaded
+    pub fn libraryVersion() []const u8 {{
+        return std.mem.span(venom_version());
+    }}
+
     pub fn readState(self: *Shell) State {{
-        var buf: [256]u8 = undefined;
-        const n = venom_shell_read_data(self.handle, &buf, buf.len);
+        var buf: [state_size + 64]u8 = undefined;
+        var written_ns: u64 = 0;
+        const n = venom_shell_read_data_stamped(self.handle, &buf, buf.len, &written_ns);
+        if (written_ns != 0) {{
+            self.write_age_us = @as(f64, @floatFromInt(venom_clock_ns() -% written_ns)) / 1000.0;
+        }}
         return State.fromBytes(buf[0..n]);
     }}
 
+    pub fn send(self: *Shell, cmd: Command) bool {{
+        const bytes = std.mem.toBytes(cmd);
+        return venom_shell_send_command(self.handle, &bytes, bytes.len);
+    }}
+
+    pub fn setInterval(self: *Shell, ms: u32) bool {{
+        return self.send(.{{ .cmd = .set_interval, .value = @intCast(ms) }});
+    }}
+
+    pub fn refresh(self: *Shell) bool {{
+        return self.send(.{{ .cmd = .refresh }});
+    }}
+
+    /// Round trip of a ping carrying `seq` in ns; null if no pong came back within a second
+    ///
+    /// The daemon answers by publishing at once with a Pong after the state.
+    /// It keeps only the last one, so clients pinging at the same time can
+    /// miss their reply.
+    pub fn ping(self: *Shell, seq: u32) ?u64 {{
+        var timer = std.time.Timer.start() catch return null;
+        if (!self.send(.{{ .cmd = .ping, .value = @bitCast(seq) }})) return null;
+        const id = self.clientId();
+        var buf: [@sizeOf(State) + @sizeOf(Pong)]u8 = undefined;
+        var written_ns: u64 = 0;
+        while (timer.read() < std.time.ns_per_s) {{
+            if (venom_shell_read_data_stamped(self.handle, &buf, buf.len, &written_ns) < buf.len) continue;
+            const reply = std.mem.bytesToValue(Pong, buf[@sizeOf(State)..][0..@sizeOf(Pong)]);
+            if (reply.client_id == id and reply.seq == seq) return timer.read();
+        }}
+        return null;
+    }}
+
     pub fn deinit(self: *Shell) void {{
         venom_shell_destroy(self.handle);
     }}
 }};
 "##,
+        shm_mode = config.shm_mode,
         channel = config.channel,
-        magic = magic(&config.channel),
+        magic = config.magic,
         data_size = config.data_size,
         cmd_slots = config.cmd_slots,
         max_clients = config.max_clients,
+        state_size = schema.size,
+        max_cores = max_cores,
+        fields = fields,
+        helpers = if demo { DEMO_HELPERS } else { "" },
+    )
+}
+
+/// Convenience methods used by the system-monitor demo
+const DEMO_HELPERS: &str = r#"
+    pub fn memoryPercent(self: *const State) f32 {
+        if (self.memory_total_mb > 0) {
+            return @as(f32, @floatFromInt(self.memory_used_mb)) / @as(f32, @floatFromInt(self.memory_total_mb)) * 100.0;
+        }
+        return 0;
+    }
+"#;
+
+/// `zig build test`: a golden decode that always runs and a live read gated
+/// on `VENOM_LIVE_TEST`
+fn venom_test_zig(config: &ProjectConfig) -> String {
+    let mut checks = String::new();
+    for (f, values) in config.schema.golden(config.magic) {
+        let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+        let want = match f.len {
+            Some(n) => format!("[{}]{}{{ {} }}", n, f.ty.as_str(), values.join(", ")),
+            None => format!("@as({}, {})", f.ty.as_str(), values.join("")),
+        };
+        checks.push_str(&format!("    try std.testing.expectEqual({}, s.{});\n", want, zig_name(&f.name)));
+    }
+
+    format!(r##"//! Tests of the bindings in venom.zig
+//!
+//! "decodes golden state" decodes a state built from venom.toml, so it fails
+//! when State no longer matches the daemon's layout. "reads live state"
+//! needs the daemon running and {live_env}=1.
+
+const std = @import("std");
+const venom = @import("venom.zig");
+
+// Every field holds a distinct known value (see "decodes golden state")
+const golden = [_]u8{{
+{golden}}};
+
+test "decodes golden state" {{
+    try std.testing.expectEqual(venom.state_size, golden.len);
+    const s = venom.State.fromBytes(&golden);
+{checks}}}
+
+test "encodes golden state" {{
+    const s = venom.State.fromBytes(&golden);
+    try std.testing.expectEqualSlices(u8, &golden, &s.toBytes());
+}}
+
+test "appends channel suffix" {{
+    var buf: [venom.channel_name_max + 1]u8 = undefined;
+    try std.testing.expectEqualStrings(venom.channel_name ++ "-2", try venom.instanceChannel(&buf, "2"));
+    try std.testing.expectEqualStrings(venom.channel_name, try venom.instanceChannel(&buf, ""));
+    try std.testing.expectEqualStrings(venom.channel_name, try venom.instanceChannel(&buf, null));
+}}
+
+test "reads live state" {{
+    const live = std.process.getEnvVarOwned(std.testing.allocator, "{live_env}") catch return error.SkipZigTest;
+    std.testing.allocator.free(live);
+
+    var shell = try venom.Shell.connect();
+    defer shell.deinit();
+    var s = shell.readState();
+    // The daemon may not have published yet
+    var i: usize = 0;
+    while (i < 50 and !s.isValid()) : (i += 1) {{
+        std.time.sleep(20 * std.time.ns_per_ms);
+        s = shell.readState();
+    }}
+    try std.testing.expectEqual(venom.magic, s.magic_num);
+    try std.testing.expectEqual(@as(u32, 1), s.version);
+}}
+"##,
+        golden = super::byte_rows(&config.schema.golden_bytes(config.magic), "    "),
+        checks = checks,
+        live_env = super::LIVE_TEST_ENV
     )
 }
 
+/// Zig field name; `magic` is taken by the module-level constant
+fn zig_name(field: &str) -> &str {
+    if field == "magic" { "magic_num" } else { field }
+}
+
+/// Daemon-side command and signal handling, shared by both daemons: the
+/// daemon polls `handleCommands` every 10 ms and publishes when it returns
+/// true or `g_interval_ms` has passed, until a signal clears `g_running`
+const COMMAND_HANDLER: &str = r#"const c = @cImport({
+    @cInclude("signal.h");
+});
+
+var g_interval_ms: u32 = venom.interval_ms;
+var g_running = std.atomic.Value(bool).init(true);
+
+fn onSignal(_: c_int) callconv(.C) void {
+    g_running.store(false, .release);
+}
+
+/// Ctrl+C or SIGTERM ends the publish loop, so the deferred deinit removes the channel
+fn stopOnSignals() void {
+    _ = c.signal(c.SIGINT, onSignal);
+    _ = c.signal(c.SIGTERM, onSignal);
+}
+
+/// Start a log line about a client: "3", or "3 (gui-frontend)" if it connected with a name
+fn printClient(daemon: *venom.Daemon, stdout: anytype, client_id: u32) !void {
+    var name: [33]u8 = undefined;
+    try stdout.print("\n📥 Client {d}", .{client_id});
+    if (daemon.clientName(client_id, &name)) |n| try stdout.print(" ({s})", .{n});
+}
+
+/// Apply queued client commands; returns true if a client asked for a refresh
+fn handleCommands(daemon: *venom.Daemon, stdout: anytype) !bool {
+    var refresh = false;
+    while (daemon.tryRecv()) |msg| {
+        switch (msg.cmd.cmd) {
+            .refresh => refresh = true,
+            .set_interval => {
+                g_interval_ms = @intCast(std.math.clamp(msg.cmd.value, 10, 10000));
+                try printClient(daemon, stdout, msg.client_id);
+                try stdout.print(": publishing every {d} ms\n", .{g_interval_ms});
+            },
+            .ping => {
+                // Answered by publishing now, with the pong after the state
+                daemon.pong(msg.client_id, @bitCast(msg.cmd.value));
+                refresh = true;
+            },
+            _ => {
+                try printClient(daemon, stdout, msg.client_id);
+                try stdout.print(": unknown command {d}\n", .{@intFromEnum(msg.cmd.cmd)});
+            },
+        }
+    }
+    return refresh;
+}
+"#;
+
+/// Client-side keys, shared by both clients: `+`/`-` halve or double the
+/// daemon's publish interval, `r` asks for an immediate refresh
+const COMMAND_KEYS: &str = r#"const c = @cImport({
+    @cInclude("termios.h");
+    @cInclude("unistd.h");
+    @cInclude("signal.h");
+});
+
+var g_interval_ms: u32 = venom.interval_ms;
+var g_saved_tty: c.struct_termios = undefined;
+var g_raw_tty = false;
+
+/// Read single keys without Enter or echo (Ctrl+C still works)
+fn keysBegin() void {
+    if (c.isatty(c.STDIN_FILENO) == 0 or c.tcgetattr(c.STDIN_FILENO, &g_saved_tty) != 0) return;
+    var raw = g_saved_tty;
+    raw.c_lflag &= ~@as(c.tcflag_t, c.ICANON | c.ECHO);
+    raw.c_cc[@intCast(c.VMIN)] = 0;
+    raw.c_cc[@intCast(c.VTIME)] = 0;
+    _ = c.tcsetattr(c.STDIN_FILENO, c.TCSANOW, &raw);
+    g_raw_tty = true;
+    // Put the terminal back when Ctrl+C ends the client
+    _ = c.signal(c.SIGINT, onSignal);
+    _ = c.signal(c.SIGTERM, onSignal);
+}
+
+fn keysEnd() void {
+    if (g_raw_tty) _ = c.tcsetattr(c.STDIN_FILENO, c.TCSANOW, &g_saved_tty);
+}
+
+fn onSignal(_: c_int) callconv(.C) void {
+    keysEnd();
+    c._exit(0);
+}
+
+fn handleKeys(shell: *venom.Shell) void {
+    var key: u8 = 0;
+    while (g_raw_tty and c.read(c.STDIN_FILENO, &key, 1) == 1) {
+        switch (key) {
+            '+', '=' => {
+                g_interval_ms = @max(g_interval_ms / 2, 10);
+                _ = shell.setInterval(g_interval_ms);
+            },
+            '-' => {
+                g_interval_ms = @min(g_interval_ms * 2, 10000);
+                _ = shell.setInterval(g_interval_ms);
+            },
+            'r' => _ = shell.refresh(),
+            else => {},
+        }
+    }
+}
+
+/// `--ping COUNT`: measure round trips through the daemon instead
+fn pingArg() ?u32 {
+    var args = std.process.args();
+    while (args.next()) |arg| {
+        if (std.mem.eql(u8, arg, "--ping")) return std.fmt.parseInt(u32, args.next() orelse return null, 10) catch null;
+    }
+    return null;
+}
+
+/// Time `count` round trips through the daemon; false if none came back
+fn runPing(shell: *venom.Shell, stdout: anytype, count: u32) !bool {
+    // A pong for an earlier client with the same ID may still be published
+    var seq: u32 = @truncate(@as(u128, @bitCast(std.time.nanoTimestamp())));
+    var rtt_min: f64 = std.math.floatMax(f64);
+    var rtt_max: f64 = 0.0;
+    var rtt_sum: f64 = 0.0;
+    var replies: u32 = 0;
+    var i: u32 = 1;
+    while (i <= count) : (i += 1) {
+        seq +%= 1;
+        const rtt_ns = shell.ping(seq) orelse {
+            try stdout.print("⚠️  No reply to ping #{d}\n", .{i});
+            continue;
+        };
+        const rtt_us = @as(f64, @floatFromInt(rtt_ns)) / 1000.0;
+        try stdout.print("🏓 Pong #{d}: {d:.1} µs\n", .{ i, rtt_us });
+        rtt_min = @min(rtt_min, rtt_us);
+        rtt_max = @max(rtt_max, rtt_us);
+        rtt_sum += rtt_us;
+        replies += 1;
+        std.time.sleep(100 * std.time.ns_per_ms);
+    }
+    if (replies == 0) return false;
+    try stdout.print("📊 Round trip: {d}/{d} replies, min {d:.1} µs, avg {d:.1} µs, max {d:.1} µs\n", .{
+        replies, count, rtt_min, rtt_sum / @as(f64, @floatFromInt(replies)), rtt_max,
+    });
+    return true;
+}
+"#;
+
 fn daemon_zig(config: &ProjectConfig) -> String {
+    if !config.schema.has_demo_fields() {
+        return generic_daemon_zig(config);
+    }
     format!(r##"//! {name} System Monitor Daemon (Zig)
 const std = @import("std");
 const venom = @import("venom.zig");
@@ -191,6 +583,7 @@ const venom = @import("venom.zig");
 var prev_total: [venom.max_cores + 1]u64 = [_]u64{{0}} ** (venom.max_cores + 1);
 var prev_idle: [venom.max_cores + 1]u64 = [_]u64{{0}} ** (venom.max_cores + 1);
 
+{commands}
 fn readCpu(state: *venom.State) void {{
     const file = std.fs.openFileAbsolute("/proc/stat", .{{}}) catch return;
     defer file.close();
@@ -275,13 +668,19 @@ pub fn main() !void {{
     try stdout.print("🖥️  {name} System Monitor (Zig)\n", .{{}});
     try stdout.print("═══════════════════════════════════════════════════════════════\n", .{{}});
     
-    var daemon = venom.Daemon.init() catch {{
+    var channel_buf: [venom.channel_name_max + 1]u8 = undefined;
+    const channel = venom.channelFromArgs(&channel_buf) catch {{
+        try stdout.print("❌ --channel-suffix is too long\n", .{{}});
+        return;
+    }};
+    var daemon = venom.Daemon.initOn(channel.ptr) catch {{
         try stdout.print("❌ Failed to create daemon\n", .{{}});
         return;
     }};
     defer daemon.deinit();
+    stopOnSignals();
     
-    try stdout.print("✅ Channel: {{s}}\n", .{{venom.channel_name}});
+    try stdout.print("✅ Channel: {{s}}\n", .{{channel}});
     try stdout.print("🚀 Publishing... (Ctrl+C to stop)\n\n", .{{}});
     
     var state = venom.State{{
@@ -289,7 +688,15 @@ pub fn main() !void {{
         .version = 1,
     }};
     
-    while (true) {{
+    var next_publish = std.time.milliTimestamp();
+    while (g_running.load(.acquire)) {{
+        const refresh = try handleCommands(&daemon, stdout);
+        if (!refresh and std.time.milliTimestamp() < next_publish) {{
+            std.time.sleep(10 * std.time.ns_per_ms);
+            continue;
+        }}
+        next_publish = std.time.milliTimestamp() + @as(i64, g_interval_ms);
+        
         readCpu(&state);
         readMemory(&state);
         readUptime(&state);
@@ -298,20 +705,77 @@ pub fn main() !void {{
         
         daemon.write(&state);
         
-        try stdout.print("\r🖥️  CPU: {{d:.1}}% | RAM: {{d}}/{{d}} MB | #{{d}}   ", .{{
+        try stdout.print("\r🖥️  CPU: {{d:.1}}% | RAM: {{d}}/{{d}} MB | #{{d}} | {{d}} ms   ", .{{
             state.cpu_usage_percent,
             state.memory_used_mb,
             state.memory_total_mb,
             state.update_counter,
+            g_interval_ms,
         }});
+    }}
+    try stdout.print("\n\n👋 Goodbye!\n", .{{}});
+}}
+"##, name = config.name, commands = COMMAND_HANDLER)
+}
+
+/// Daemon for a custom schema: publishes the header and leaves the fields to the user
+fn generic_daemon_zig(config: &ProjectConfig) -> String {
+    format!(r##"//! {name} Daemon (Zig) - publishes State (see venom.toml)
+const std = @import("std");
+const venom = @import("venom.zig");
+
+{commands}
+pub fn main() !void {{
+    const stdout = std.io.getStdOut().writer();
+    
+    try stdout.print("🚀 {name} Daemon (Zig)\n", .{{}});
+    try stdout.print("═══════════════════════════════════════════════════════════════\n", .{{}});
+    
+    var channel_buf: [venom.channel_name_max + 1]u8 = undefined;
+    const channel = venom.channelFromArgs(&channel_buf) catch {{
+        try stdout.print("❌ --channel-suffix is too long\n", .{{}});
+        return;
+    }};
+    var daemon = venom.Daemon.initOn(channel.ptr) catch {{
+        try stdout.print("❌ Failed to create daemon\n", .{{}});
+        return;
+    }};
+    defer daemon.deinit();
+    stopOnSignals();
+    
+    try stdout.print("✅ Channel: {{s}} | State: {{d}} bytes\n", .{{ channel, venom.state_size }});
+    try stdout.print("🚀 Publishing... (Ctrl+C to stop)\n\n", .{{}});
+    
+    var state = venom.State{{
+        .magic_num = venom.magic,
+        .version = 1,
+    }};
+    
+    var updates: u64 = 0;
+    var next_publish = std.time.milliTimestamp();
+    while (g_running.load(.acquire)) {{
+        const refresh = try handleCommands(&daemon, stdout);
+        if (!refresh and std.time.milliTimestamp() < next_publish) {{
+            std.time.sleep(10 * std.time.ns_per_ms);
+            continue;
+        }}
+        next_publish = std.time.milliTimestamp() + @as(i64, g_interval_ms);
         
-        std.time.sleep(100 * std.time.ns_per_ms);
+        // TODO: fill in the fields of State
+        daemon.write(&state);
+        
+        updates += 1;
+        try stdout.print("\r📤 Published update #{{d}} (every {{d}} ms)   ", .{{ updates, g_interval_ms }});
     }}
+    try stdout.print("\n\n👋 Goodbye!\n", .{{}});
 }}
-"##, name = config.name)
+"##, name = config.name, commands = COMMAND_HANDLER)
 }
 
 fn client_zig(config: &ProjectConfig) -> String {
+    if !config.schema.has_demo_fields() {
+        return generic_client_zig(config);
+    }
     format!(r##"//! {name} Status Bar Client (Zig) - with Benchmarking
 const std = @import("std");
 const venom = @import("venom.zig");
@@ -328,6 +792,7 @@ var g_latency_max: f64 = 0.0;
 var g_latency_sum: f64 = 0.0;
 var g_latency_count: u64 = 0;
 
+{keys}
 fn printBar(writer: anytype, pct: f32, width: usize) !void {{
     const filled = @as(usize, @intFromFloat((pct / 100.0) * @as(f32, @floatFromInt(width))));
     const color = if (pct > 80) red else if (pct > 50) yellow else green;
@@ -350,18 +815,37 @@ pub fn main() !void {{
     try stdout.print("║   🖥️  {name} Status Bar (Zig)                                  ║\n", .{{}});
     try stdout.print("╚═══════════════════════════════════════════════════════════════╝\n\n", .{{}});
     
-    var shell = venom.Shell.connect() catch {{
+    var channel_buf: [venom.channel_name_max + 1]u8 = undefined;
+    const channel = venom.channelFromArgs(&channel_buf) catch {{
+        try stdout.print("❌ --channel-suffix is too long\n", .{{}});
+        return;
+    }};
+    var shell = venom.Shell.connectChannel(channel.ptr, "zig-client") catch {{
         try stdout.print("❌ Failed to connect - is daemon running?\n", .{{}});
         return;
     }};
     defer shell.deinit();
     
-    try stdout.print("✅ Connected! ID: {{d}}\n", .{{shell.clientId()}});
+    try stdout.print("✅ Connected! ID: {{d}} (libvenom_memory {{s}})\n", .{{ shell.clientId(), venom.Shell.libraryVersion() }});
+    if (pingArg()) |count| {{
+        if (!try runPing(&shell, stdout, count)) return error.NoReply;
+        return;
+    }}
     try stdout.print("📊 Reading stats... (Ctrl+C to exit)\n\n", .{{}});
     std.time.sleep(1 * std.time.ns_per_s);
     
+    keysBegin();
+    defer keysEnd();
+    
     var frame: u64 = 0;
     while (true) {{
+        handleKeys(&shell);
+        // Reads would keep returning the last state it wrote
+        if (!shell.daemonAlive()) {{
+            try stdout.print("\n❌ Daemon offline! Restart it:\n   zig build run-daemon\n", .{{}});
+            break;
+        }}
+        
         // ═══════════════════════════════════════════════════════════════════
         // 📊 BENCHMARK: Measure read latency
         // ═══════════════════════════════════════════════════════════════════
@@ -405,56 +889,176 @@ pub fn main() !void {{
             try stdout.print("╠═══════════════════════════════════════════════════════════════╣\n", .{{}});
             try stdout.print("║  📊 {{s}}Read Latency:{{s}} {{d:.2}} µs (min: {{d:.2}}, max: {{d:.2}}, avg: {{d:.2}})  ║\n", 
                 .{{ cyan, reset, latency_us, g_latency_min, g_latency_max, avg_us }});
+            try stdout.print("║  📡 {{s}}Daemon→client:{{s}} {{d:.2}} µs since the write                 ║\n", .{{ cyan, reset, shell.write_age_us }});
             try stdout.print("╚═══════════════════════════════════════════════════════════════╝\n", .{{}});
-            try stdout.print("  Cores: {{d}} | Updates: {{d}} | Ctrl+C to exit\n", .{{state.core_count, state.update_counter}});
+            try stdout.print("  Cores: {{d}} | Updates: {{d}} | Interval: {{d}} ms\n", .{{ state.core_count, state.update_counter, g_interval_ms }});
+            try stdout.print("  +/- faster/slower | r refresh | Ctrl+C to exit\n", .{{}});
             frame += 1;
         }}
         std.time.sleep(100 * std.time.ns_per_ms);
     }}
 }}
-"##, name = config.name)
+"##, name = config.name, keys = COMMAND_KEYS)
 }
 
-fn build_zig(config: &ProjectConfig) -> String {
-    format!(r##"const std = @import("std");
+/// Client for a custom schema: prints every field of the state
+fn generic_client_zig(config: &ProjectConfig) -> String {
+    let fields: String = config.schema.user_fields()
+        .map(|f| {
+            let spec = if f.len.is_some() { "any" } else { "d" };
+            format!("            try stdout.print(\"  {{s:<24}} {{{}}}\\n\", .{{ \"{}\", state.{} }});\n", spec, f.name, f.name)
+        })
+        .collect();
+    
+    format!(r##"//! {name} Client (Zig) - prints every field of State (see venom.toml)
+const std = @import("std");
+const venom = @import("venom.zig");
 
-pub fn build(b: *std.Build) void {{
-    const target = b.standardTargetOptions(.{{}});
-    const optimize = b.standardOptimizeOption(.{{}});
+{keys}
+pub fn main() !void {{
+    const stdout = std.io.getStdOut().writer();
+    
+    var channel_buf: [venom.channel_name_max + 1]u8 = undefined;
+    const channel = venom.channelFromArgs(&channel_buf) catch {{
+        try stdout.print("❌ --channel-suffix is too long\n", .{{}});
+        return;
+    }};
+    var shell = venom.Shell.connectChannel(channel.ptr, "zig-client") catch {{
+        try stdout.print("❌ Failed to connect - is daemon running?\n", .{{}});
+        return;
+    }};
+    defer shell.deinit();
+    try stdout.print("✅ Connected! ID: {{d}} (libvenom_memory {{s}})\n", .{{ shell.clientId(), venom.Shell.libraryVersion() }});
+    if (pingArg()) |count| {{
+        if (!try runPing(&shell, stdout, count)) return error.NoReply;
+        return;
+    }}
+    
+    keysBegin();
+    defer keysEnd();
+    
+    var lat_min: f64 = std.math.floatMax(f64);
+    var lat_max: f64 = 0.0;
+    var lat_sum: f64 = 0.0;
+    var lat_count: u64 = 0;
+    
+    while (true) {{
+        handleKeys(&shell);
+        // Reads would keep returning the last state it wrote
+        if (!shell.daemonAlive()) {{
+            try stdout.print("\n❌ Daemon offline! Restart it:\n   zig build run-daemon\n", .{{}});
+            break;
+        }}
+        
+        const t_start = std.time.nanoTimestamp();
+        const state = shell.readState();
+        const latency_us = @as(f64, @floatFromInt(std.time.nanoTimestamp() - t_start)) / 1000.0;
+        
+        lat_min = @min(lat_min, latency_us);
+        lat_max = @max(lat_max, latency_us);
+        lat_sum += latency_us;
+        lat_count += 1;
+        
+        if (state.isValid()) {{
+            try stdout.print("\x1b[2J\x1b[H", .{{}});
+            try stdout.print("🐍 {name} (Zig) | State v{{d}} | {{d}} bytes\n", .{{ state.version, venom.state_size }});
+            try stdout.print("═══════════════════════════════════════════════════════════════\n", .{{}});
+{fields}            try stdout.print("═══════════════════════════════════════════════════════════════\n", .{{}});
+            try stdout.print("📊 Read Latency: {{d:.2}} µs (min: {{d:.2}}, max: {{d:.2}}, avg: {{d:.2}})\n", .{{
+                latency_us, lat_min, lat_max, lat_sum / @as(f64, @floatFromInt(lat_count)),
+            }});
+            try stdout.print("📡 Daemon→client: {{d:.2}} µs since the write\n", .{{shell.write_age_us}});
+            try stdout.print("⏱️  Daemon interval: {{d}} ms | +/- faster/slower | r refresh\n", .{{g_interval_ms}});
+        }}
+        std.time.sleep(100 * std.time.ns_per_ms);
+    }}
+}}
+"##, name = config.name, fields = fields, keys = COMMAND_KEYS)
+}
+
+fn build_zig(config: &ProjectConfig, with_daemon: bool) -> String {
+    // Bundled library directory; with several targets, picked by -Dvenom-target
+    let (venom_target, lib) = match config.targets.first() {
+        Some(first) => (
+            format!(r##"    // Bundled library to link: lib/<triple>/ for {all}
+    const venom_target = b.option([]const u8, "venom-target", "Bundled library to link (lib/<triple>/)") orelse "{first}";
+    const venom_lib = b.fmt("{lib}/{{s}}", .{{venom_target}});
 
-    // Daemon
+"##, all = config.targets.join(" "), first = first, lib = config.at_root("lib")),
+            "venom_lib".to_string(),
+        ),
+        None => (String::new(), format!("\"{}\"", config.at_root("lib"))),
+    };
+    // Daemon artifact and run step, left out for a client-only project
+    let (daemon, run_daemon, daemon_step) = if with_daemon {
+        (
+            format!(r##"    // Daemon
     const daemon = b.addExecutable(.{{
         .name = "{name}_daemon",
         .root_source_file = .{{ .path = "src/daemon.zig" }},
         .target = target,
         .optimize = optimize,
     }});
-    daemon.addLibraryPath(.{{ .path = "lib" }});
+    daemon.addLibraryPath(.{{ .path = {lib} }});
     daemon.linkSystemLibrary("venom_memory");
     daemon.linkLibC();
-    daemon.addRPath(.{{ .path = "lib" }});
+    daemon.addRPath(.{{ .path = {lib} }});
     b.installArtifact(daemon);
 
-    // Client
+"##, name = config.name, lib = lib),
+            "    const run_daemon = b.addRunArtifact(daemon);\n".to_string(),
+            "    b.step(\"run-daemon\", \"Run the daemon\").dependOn(&run_daemon.step);\n".to_string(),
+        )
+    } else {
+        (String::new(), String::new(), String::new())
+    };
+    
+    format!(r##"const std = @import("std");
+
+pub fn build(b: *std.Build) void {{
+    const target = b.standardTargetOptions(.{{}});
+    const optimize = b.standardOptimizeOption(.{{}});
+
+{venom_target}{daemon}    // Client
     const client = b.addExecutable(.{{
         .name = "{name}_client",
         .root_source_file = .{{ .path = "src/client.zig" }},
         .target = target,
         .optimize = optimize,
     }});
-    client.addLibraryPath(.{{ .path = "lib" }});
+    client.addLibraryPath(.{{ .path = {lib} }});
     client.linkSystemLibrary("venom_memory");
     client.linkLibC();
-    client.addRPath(.{{ .path = "lib" }});
+    client.addRPath(.{{ .path = {lib} }});
     b.installArtifact(client);
 
+    // Tests; {live_env}=1 zig build test also reads the running daemon
+    const tests = b.addTest(.{{
+        .root_source_file = .{{ .path = "src/venom_test.zig" }},
+        .target = target,
+        .optimize = optimize,
+    }});
+    tests.addLibraryPath(.{{ .path = {lib} }});
+    tests.linkSystemLibrary("venom_memory");
+    tests.linkLibC();
+    tests.addRPath(.{{ .path = {lib} }});
+    const run_tests = b.addRunArtifact(tests);
+    b.step("test", "Run the binding tests").dependOn(&run_tests.step);
+
     // Run steps
-    const run_daemon = b.addRunArtifact(daemon);
-    const run_client = b.addRunArtifact(client);
-    b.step("run-daemon", "Run the daemon").dependOn(&run_daemon.step);
-    b.step("run-client", "Run the client").dependOn(&run_client.step);
+{run_daemon}    const run_client = b.addRunArtifact(client);
+    if (b.args) |args| run_client.addArgs(args);
+{daemon_step}    b.step("run-client", "Run the client").dependOn(&run_client.step);
 }}
-"##, name = config.name)
+"##,
+        name = config.name,
+        venom_target = venom_target,
+        lib = lib,
+        daemon = daemon,
+        run_daemon = run_daemon,
+        daemon_step = daemon_step,
+        live_env = super::LIVE_TEST_ENV,
+    )
 }
 
 fn readme(config: &ProjectConfig) -> String {
@@ -475,15 +1079,25 @@ zig build run-daemon
 zig build run-client
 ```
 
+## Tests
+
+`zig build test` decodes a known state built from `venom.toml`, so it fails
+when `src/venom.zig` no longer matches the daemon's layout. With the daemon
+running, `{live_env}=1 zig build test` also reads the live channel.
+{ping}{instances}
 ## Configuration
 
 | Setting | Value |
 |---------|-------|
 | Channel | `{channel}` |
 | Magic | `0x{magic:08X}` |
-"#,
+{service}"#,
         name = config.name,
+        service = super::service::readme_section(config),
+        live_env = super::LIVE_TEST_ENV,
+        ping = super::ping_readme_section("zig build run-client -- --ping 10"),
+        instances = super::instances_readme_section(&config.channel, "zig build run-daemon -- --channel-suffix SUFFIX", "zig build run-client -- --channel-suffix SUFFIX", "`venom.instanceChannel()`"),
         channel = config.channel,
-        magic = magic(&config.channel)
+        magic = config.magic
     )
 }
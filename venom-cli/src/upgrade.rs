@@ -0,0 +1,290 @@
+//! `venom upgrade`: bring an existing project up to this CLI's library
+//!
+//! A project keeps the `libvenom_memory.so` it was generated with until it is
+//! replaced. Upgrading writes the CLI's library over `lib/` and any `native/`
+//! copies, and regenerates the files that only wrap the library
+//! (`templates::bindings`). Daemon and client code is never touched.
+//!
+//! A binding file whose hash no longer matches the `[bindings]` table of
+//! `venom.toml` was edited after generation; it is kept and reported unless
+//! `--force` is given.
+
+use console::style;
+use std::collections::BTreeMap;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+use crate::library;
+use crate::project;
+use crate::templates::{self, ProjectConfig};
+
+/// What an upgrade changed
+#[derive(Debug, Default)]
+pub struct Report {
+    /// Project-relative path and a short description of the change
+    pub updated: Vec<(String, String)>,
+    /// Binding files edited since generation and left alone
+    pub edited: Vec<String>,
+}
+
+/// Upgrade the project in `dir`; `force` also overwrites edited binding files
+pub fn upgrade(dir: &str, force: bool) -> Result<Report, String> {
+    let (config, lang) = project::load(dir)?;
+    // What to replace is decided here, file by file
+    crate::set_write_mode(crate::WriteMode::Force);
+    let mut hashes = project::load_binding_hashes(dir)?;
+    library::use_project_libraries(dir);
+    let mut report = Report::default();
+
+    upgrade_libraries(&config, &mut report)?;
+
+    for binding in templates::bindings(&config, lang) {
+        let path = Path::new(dir).join(&binding.path);
+        // A client someone removed stays removed
+        let Ok(old) = fs::read_to_string(&path) else { continue };
+        let range = if binding.block { block_range(&old, &binding.content) } else { Some(0..old.len()) };
+        let current = range.as_ref().map(|r| &old[r.clone()]);
+        if current == Some(binding.content.as_str()) {
+            hashes.insert(binding.path, project::content_hash(&binding.content));
+            continue;
+        }
+        let untouched = current.is_some_and(|c| hashes.get(&binding.path) == Some(&project::content_hash(c)));
+        let Some(range) = range.filter(|_| untouched || force) else {
+            report.edited.push(binding.path);
+            continue;
+        };
+        let mut new = old.clone();
+        new.replace_range(range, &binding.content);
+        crate::write_file(&path.display().to_string(), &new);
+        report.updated.push((binding.path.clone(), line_stat(&old, &new)));
+        hashes.insert(binding.path, project::content_hash(&binding.content));
+    }
+
+    write_hashes(dir, &hashes)?;
+    Ok(report)
+}
+
+/// Replace every bundled library that differs from this CLI's
+fn upgrade_libraries(config: &ProjectConfig, report: &mut Report) -> Result<(), String> {
+    let root = &config.output_dir;
+    let mut dirs = vec!["lib".to_string()];
+    for dir in std::iter::once("native".to_string())
+        .chain(config.clients.iter().map(|c| format!("clients/{}/native", c.id())))
+    {
+        if Path::new(root).join(&dir).is_dir() {
+            dirs.push(dir);
+        }
+    }
+    for dir in dirs {
+        let mut changed = Vec::new();
+        for (file, target) in library_files(config, &dir) {
+            let new = library::library_for(&target)?;
+            let old = fs::read(Path::new(root).join(&file)).ok();
+            if old.as_deref() != Some(new.as_slice()) {
+                let was = old.map_or("missing".to_string(), |b| format!("{} bytes", b.len()));
+                changed.push((file, format!("library replaced ({} → {} bytes)", was, new.len())));
+            }
+        }
+        if !changed.is_empty() && dir == "lib" {
+            // Rewrites libvenom_memory.json along with the libraries
+            library::copy_library_to(root, &config.targets);
+            report.updated.extend(changed);
+        } else if !changed.is_empty() {
+            library::install(&format!("{}/{}", root, dir), &config.targets);
+            report.updated.extend(changed);
+        }
+    }
+    Ok(())
+}
+
+/// Library files `library::install` lays out in `dir`, with their target triple
+fn library_files(config: &ProjectConfig, dir: &str) -> Vec<(String, String)> {
+    if config.targets.is_empty() {
+        return vec![(format!("{}/{}", dir, library::LIBRARY_NAME), library::EMBEDDED_TARGET.to_string())];
+    }
+    config
+        .targets
+        .iter()
+        .map(|t| (format!("{}/{}/{}", dir, t, library::library_name_for(t)), t.clone()))
+        .collect()
+}
+
+/// Where `block` (whose first line opens it and which ends with a `}` line)
+/// sits in `text`
+fn block_range(text: &str, block: &str) -> Option<Range<usize>> {
+    let start = text.find(block.lines().next()?)?;
+    let end = start + text[start..].find("\n}\n")? + "\n}\n".len();
+    Some(start..end)
+}
+
+/// `+added -removed` lines from `old` to `new`
+fn line_stat(old: &str, new: &str) -> String {
+    let diff = crate::diff::unified("", old, new).unwrap_or_default();
+    let count = |sign: char| {
+        diff.lines()
+            .filter(|l| l.starts_with(sign) && !l.starts_with("+++") && !l.starts_with("---"))
+            .count()
+    };
+    format!("+{} -{} lines", count('+'), count('-'))
+}
+
+/// `venom.toml` text with its `[bindings]` table replaced by `hashes`
+fn with_hashes(text: &str, hashes: &BTreeMap<String, String>) -> String {
+    let kept = match text.find(project::BINDINGS_HEADER) {
+        Some(i) => &text[..i],
+        None => text,
+    };
+    kept.to_string() + &project::bindings_toml(hashes)
+}
+
+/// Rewrite the `[bindings]` table of `venom.toml`
+fn write_hashes(dir: &str, hashes: &BTreeMap<String, String>) -> Result<(), String> {
+    let path = Path::new(dir).join("venom.toml");
+    let text = fs::read_to_string(&path).map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+    let new = with_hashes(&text, hashes);
+    if new != text {
+        crate::write_file(&path.display().to_string(), &new);
+    }
+    Ok(())
+}
+
+/// Print what `upgrade` did
+pub fn print_report(report: &Report) {
+    println!();
+    if report.updated.is_empty() {
+        println!("{}", style("✅ Already up to date").green().bold());
+    } else {
+        println!("{}", style("✅ Upgraded:").green().bold());
+        let width = report.updated.iter().map(|(p, _)| p.len()).max().unwrap_or(0);
+        for (path, change) in &report.updated {
+            println!("   {:width$}  {}", path, style(change).dim(), width = width);
+        }
+    }
+    if !report.edited.is_empty() {
+        println!();
+        println!("{}", style("⚠️  Edited since generation, kept (use --force to overwrite):").yellow());
+        for path in &report.edited {
+            println!("   {}", path);
+        }
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::Schema;
+    use crate::templates::{Language, License, PythonBinding};
+
+    fn generate(lang: Language, name: &str) -> ProjectConfig {
+        let dir = std::env::temp_dir().join(format!("venom-upgrade-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let config = ProjectConfig {
+            name: name.to_string(),
+            channel: name.to_string(),
+            data_size: 16 * 1024,
+            cmd_slots: 32,
+            max_clients: 16,
+            shm_mode: 0o664,
+            output_dir: dir.display().to_string(),
+            python_binding: PythonBinding::Ctypes,
+            go_nocgo: false,
+            flutter_ui: true,
+            python_async: false,
+            magic: templates::channel_magic(name),
+            schema: Schema::demo(),
+            targets: Vec::new(),
+            clients: Vec::new(),
+            root: String::new(),
+            service: false,
+            license: License::None,
+            extras: true,
+            license_year: 2026,
+        };
+        crate::generate_project(&config, lang);
+        config
+    }
+
+    /// Pretend `path` was generated as `content` by an older venom
+    fn age(config: &ProjectConfig, path: &str, content: &str) {
+        fs::write(Path::new(&config.output_dir).join(path), content).unwrap();
+        let mut hashes = project::load_binding_hashes(&config.output_dir).unwrap();
+        hashes.insert(path.to_string(), project::content_hash(&block_or_file(config, path)));
+        let manifest = Path::new(&config.output_dir).join("venom.toml");
+        fs::write(&manifest, with_hashes(&fs::read_to_string(&manifest).unwrap(), &hashes)).unwrap();
+    }
+
+    fn block_or_file(config: &ProjectConfig, path: &str) -> String {
+        let text = fs::read_to_string(Path::new(&config.output_dir).join(path)).unwrap();
+        match templates::bindings(config, Language::Rust).pop() {
+            Some(b) if b.path == path => text[block_range(&text, &b.content).unwrap()].to_string(),
+            _ => text,
+        }
+    }
+
+    #[test]
+    fn test_upgrade_restores_library_and_keeps_user_code() {
+        let config = generate(Language::Python, "upgradepy");
+        let dir = Path::new(&config.output_dir);
+        let lib = dir.join("lib").join(library::LIBRARY_NAME);
+        let daemon = dir.join("daemon/src/main.c");
+        fs::write(&lib, b"an older library").unwrap();
+        let daemon_code = fs::read_to_string(&daemon).unwrap() + "// my change\n";
+        fs::write(&daemon, &daemon_code).unwrap();
+        let binding = fs::read_to_string(dir.join("venom_binding.py")).unwrap();
+        age(&config, "venom_binding.py", &binding.replace("VenomShell", "OldShell"));
+
+        let report = upgrade(&config.output_dir, false).unwrap();
+        let library = fs::read(&lib).unwrap();
+        let daemon_after = fs::read_to_string(&daemon).unwrap();
+        let binding_after = fs::read_to_string(dir.join("venom_binding.py")).unwrap();
+        let again = upgrade(&config.output_dir, false).unwrap();
+        fs::remove_dir_all(dir).ok();
+
+        assert_eq!(library, library::library_for(library::EMBEDDED_TARGET).unwrap());
+        assert_eq!(daemon_after, daemon_code);
+        assert_eq!(binding_after, binding);
+        let updated: Vec<&str> = report.updated.iter().map(|(p, _)| p.as_str()).collect();
+        assert_eq!(updated, ["lib/libvenom_memory.so", "venom_binding.py"]);
+        assert!(report.edited.is_empty());
+        assert!(again.updated.is_empty());
+    }
+
+    #[test]
+    fn test_edited_binding_is_kept_unless_forced() {
+        let config = generate(Language::Go, "upgradego");
+        let binding = Path::new(&config.output_dir).join("venom/venom.go");
+        fs::write(&binding, "// my own binding\n").unwrap();
+
+        let report = upgrade(&config.output_dir, false).unwrap();
+        let kept = fs::read_to_string(&binding).unwrap();
+        let forced = upgrade(&config.output_dir, true).unwrap();
+        let overwritten = fs::read_to_string(&binding).unwrap();
+        fs::remove_dir_all(&config.output_dir).ok();
+
+        assert_eq!(report.edited, ["venom/venom.go"]);
+        assert_eq!(kept, "// my own binding\n");
+        assert!(forced.edited.is_empty());
+        assert_eq!(overwritten, templates::bindings(&config, Language::Go)[0].content);
+    }
+
+    #[test]
+    fn test_rust_upgrade_replaces_only_the_extern_block() {
+        let config = generate(Language::Rust, "upgraders");
+        let lib_rs = Path::new(&config.output_dir).join("src/lib.rs");
+        let generated = fs::read_to_string(&lib_rs).unwrap();
+        let block = templates::bindings(&config, Language::Rust)[0].content.clone();
+        let old_block = block.replace("    pub fn venom_shell_id(handle: *mut std::ffi::c_void) -> u32;\n", "");
+        let user_code = "\npub fn my_helper() {}\n";
+        fs::write(&lib_rs, generated.replace(&block, &old_block) + user_code).unwrap();
+        age(&config, "src/lib.rs", &fs::read_to_string(&lib_rs).unwrap());
+
+        let report = upgrade(&config.output_dir, false).unwrap();
+        let upgraded = fs::read_to_string(&lib_rs).unwrap();
+        fs::remove_dir_all(&config.output_dir).ok();
+
+        assert_eq!(report.updated, [("src/lib.rs".to_string(), "+1 -0 lines".to_string())]);
+        assert_eq!(upgraded, generated + user_code);
+    }
+}
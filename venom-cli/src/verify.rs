@@ -0,0 +1,395 @@
+//! `venom init --verify`: every generated decoder against the C header
+//!
+//! The reference is the state struct of the project's C header
+//! (`shared/protocol.h`, or `daemon/protocol.h` in Flutter projects) as
+//! venom-watch parses it; a project without a header is checked against
+//! `venom.toml` instead. Compared with it are the Rust `{Name}State` in
+//! `src/lib.rs`, the C++ `{name}::State` in `shared/protocol.hpp` and the
+//! Zig `State` in `src/venom.zig`, also parsed by venom-watch, and every
+//! `offsets.json` the
+//! Python, Go and Dart generators leave next to their decoder. The project
+//! root and each `clients/<lang>/` are searched.
+
+use console::style;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::schema::Schema;
+use crate::templates::{DecoderOffsets, Names, OFFSETS_FILE};
+
+/// C headers the reference layout is taken from, first one found wins
+const HEADERS: &[&str] = &["shared/protocol.h", "daemon/protocol.h"];
+
+/// The C++ template's header, next to (or instead of) the C one
+const CPP_HEADER: &str = "shared/protocol.hpp";
+
+/// The Zig template's bindings; `magic` is spelled `magic_num` there, as
+/// `magic` is the constant it's checked against
+const ZIG_SOURCE: &str = "src/venom.zig";
+
+/// Where a generator leaves `offsets.json`, relative to the project root or
+/// a `clients/<lang>/` directory (Python, Go, Dart)
+const OFFSETS_DIRS: &[&str] = &["", "venom", "lib"];
+
+/// Pseudo field the state size is reported under
+const STATE_SIZE: &str = "(state size)";
+
+/// Where a field sits: byte offset and size (all elements of an array)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Slot {
+    pub offset: usize,
+    pub size: usize,
+}
+
+/// Fields in declaration order, padding left out, plus the state size
+#[derive(Debug)]
+struct Layout {
+    fields: Vec<(String, Slot)>,
+    size: usize,
+}
+
+/// A field one decoder places differently from the reference; `None` when
+/// one side doesn't have the field at all
+#[derive(Debug)]
+pub struct Mismatch {
+    pub decoder: String,
+    pub field: String,
+    pub expected: Option<Slot>,
+    pub found: Option<Slot>,
+}
+
+#[derive(Debug)]
+pub struct Report {
+    /// What the layouts were compared against
+    pub reference: String,
+    /// Decoders that were compared, relative to the project root
+    pub checked: Vec<String>,
+    pub mismatches: Vec<Mismatch>,
+    /// Decoders that couldn't be read, with the reason
+    pub errors: Vec<(String, String)>,
+}
+
+impl Report {
+    pub fn passed(&self) -> bool {
+        self.mismatches.is_empty() && self.errors.is_empty()
+    }
+}
+
+/// Compare the decoders of the project generated in `dir` for `name`
+pub fn run(dir: &str, name: &str, schema: &Schema) -> Result<Report, String> {
+    let root = Path::new(dir);
+    let names = Names::of(name);
+    let struct_name = format!("{}State", names.pascal);
+    let cpp_struct_name = format!("{}::State", names.flat);
+
+    let (reference, expected) = match HEADERS.iter().map(|h| root.join(h)).find(|p| p.exists()) {
+        Some(header) => {
+            let layout = analyze(&header, &struct_name)?;
+            (format!("{} ({})", relative(root, &header), struct_name), layout)
+        }
+        None => ("venom.toml".to_string(), from_schema(schema)),
+    };
+
+    let mut report = Report { reference, checked: Vec::new(), mismatches: Vec::new(), errors: Vec::new() };
+    for base in decoder_dirs(root) {
+        let sources = [("src/lib.rs", struct_name.as_str()), (CPP_HEADER, cpp_struct_name.as_str()), (ZIG_SOURCE, "State")];
+        for (source, source_struct) in sources {
+            let path = base.join(source);
+            if !path.exists() {
+                continue;
+            }
+            let subject = relative(root, &path);
+            match analyze(&path, source_struct) {
+                Ok(mut found) => {
+                    if source == ZIG_SOURCE {
+                        for (name, _) in &mut found.fields {
+                            if name == "magic_num" {
+                                *name = "magic".to_string();
+                            }
+                        }
+                    }
+                    compare(&subject, &expected, &found, &mut report.mismatches)
+                }
+                Err(e) => report.errors.push((subject.clone(), e)),
+            }
+            report.checked.push(subject);
+        }
+        for dir in OFFSETS_DIRS {
+            let path = base.join(dir).join(OFFSETS_FILE);
+            if !path.exists() {
+                continue;
+            }
+            let read = fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|text| serde_json::from_str::<DecoderOffsets>(&text).map_err(|e| e.to_string()));
+            match read {
+                Ok(offsets) => {
+                    let subject = relative(root, &path.with_file_name(&offsets.decoder));
+                    compare(&subject, &expected, &from_offsets(&offsets), &mut report.mismatches);
+                    report.checked.push(subject);
+                }
+                Err(e) => {
+                    let subject = relative(root, &path);
+                    report.errors.push((subject.clone(), e));
+                    report.checked.push(subject);
+                }
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Print the result; mismatches as a table
+pub fn print_report(report: &Report) {
+    if report.passed() {
+        println!(
+            "{}",
+            style(format!("✅ Verified {} decoder(s) against {}", report.checked.len(), report.reference)).green()
+        );
+        return;
+    }
+
+    println!("{}", style(format!("❌ Decoder layouts don't match {}", report.reference)).red().bold());
+    println!();
+    let width = report
+        .mismatches
+        .iter()
+        .map(|m| m.decoder.len())
+        .chain(report.errors.iter().map(|(d, _)| d.len()))
+        .max()
+        .unwrap_or(0)
+        .max("decoder".len());
+    let field_width = report.mismatches.iter().map(|m| m.field.len()).max().unwrap_or(0).max("field".len());
+    if !report.mismatches.is_empty() {
+        println!(
+            "  {:<width$}  {:<field_width$}  {:<16}  found",
+            "decoder", "field", "expected",
+            width = width, field_width = field_width
+        );
+        for m in &report.mismatches {
+            let total = m.field == STATE_SIZE;
+            println!(
+                "  {:<width$}  {:<field_width$}  {:<16}  {}",
+                m.decoder,
+                m.field,
+                slot(m.expected, total),
+                style(slot(m.found, total)).red(),
+                width = width,
+                field_width = field_width
+            );
+        }
+    }
+    for (decoder, e) in &report.errors {
+        println!("  {:<width$}  {}", decoder, style(format!("could not read: {}", e)).red(), width = width);
+    }
+    println!();
+}
+
+fn slot(slot: Option<Slot>, total: bool) -> String {
+    match slot {
+        None => "-".to_string(),
+        Some(s) if total => format!("{} bytes", s.size),
+        Some(s) => format!("@{} ({} bytes)", s.offset, s.size),
+    }
+}
+
+/// Record every difference between `found` and `expected` under `decoder`
+fn compare(decoder: &str, expected: &Layout, found: &Layout, out: &mut Vec<Mismatch>) {
+    let mut push = |field: &str, expected: Option<Slot>, found: Option<Slot>| {
+        out.push(Mismatch { decoder: decoder.to_string(), field: field.to_string(), expected, found })
+    };
+    for (name, want) in &expected.fields {
+        let got = found.fields.iter().find(|(n, _)| n == name).map(|(_, s)| *s);
+        if got != Some(*want) {
+            push(name, Some(*want), got);
+        }
+    }
+    for (name, got) in &found.fields {
+        if !expected.fields.iter().any(|(n, _)| n == name) {
+            push(name, None, Some(*got));
+        }
+    }
+    if found.size != expected.size {
+        push(STATE_SIZE, Some(Slot { offset: 0, size: expected.size }), Some(Slot { offset: 0, size: found.size }));
+    }
+}
+
+fn analyze(path: &Path, struct_name: &str) -> Result<Layout, String> {
+    let layout = venom_watch::analyze_file(&path.to_path_buf(), struct_name)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    let fields = layout
+        .fields
+        .iter()
+        .filter(|f| f.depth == 0 && !f.name.starts_with("_pad"))
+        .map(|f| (f.name.clone(), Slot { offset: f.offset, size: f.size }))
+        .collect();
+    Ok(Layout { fields, size: layout.total_size })
+}
+
+fn from_schema(schema: &Schema) -> Layout {
+    let fields = schema
+        .data_fields()
+        .map(|f| (f.name.clone(), Slot { offset: f.offset, size: f.size() }))
+        .collect();
+    Layout { fields, size: schema.size }
+}
+
+fn from_offsets(offsets: &DecoderOffsets) -> Layout {
+    let fields = offsets
+        .fields
+        .iter()
+        .map(|f| (f.name.clone(), Slot { offset: f.offset, size: f.size }))
+        .collect();
+    Layout { fields, size: offsets.size }
+}
+
+/// The project root, then each `clients/<lang>/`
+fn decoder_dirs(root: &Path) -> Vec<PathBuf> {
+    let mut dirs = vec![root.to_path_buf()];
+    if let Ok(entries) = fs::read_dir(root.join("clients")) {
+        let mut clients: Vec<PathBuf> = entries.flatten().map(|e| e.path()).filter(|p| p.is_dir()).collect();
+        clients.sort();
+        dirs.extend(clients);
+    }
+    dirs
+}
+
+fn relative(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root).unwrap_or(path).display().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::templates::{channel_magic, Language, License, ProjectConfig, PythonBinding};
+
+    fn generate(name: &str, lang: Language, clients: Vec<Language>) -> ProjectConfig {
+        let dir = std::env::temp_dir().join(format!("venom-verify-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        let config = ProjectConfig {
+            name: name.to_string(),
+            channel: format!("{}_chan", name),
+            data_size: 16 * 1024,
+            cmd_slots: 32,
+            max_clients: 16,
+            shm_mode: 0o664,
+            output_dir: dir.display().to_string(),
+            python_binding: PythonBinding::Ctypes,
+            go_nocgo: false,
+            flutter_ui: false,
+            python_async: false,
+            magic: channel_magic(&format!("{}_chan", name)),
+            schema: Schema::parse(
+                r#"
+                [[field]]
+                name = "flag"
+                type = "u8"
+                [[field]]
+                name = "temps"
+                type = "f32"
+                len = 3
+                [[field]]
+                name = "count"
+                type = "u64"
+                [[field]]
+                name = "level"
+                type = "i16"
+                "#,
+            )
+            .unwrap(),
+            targets: Vec::new(),
+            clients,
+            root: String::new(),
+            service: false,
+            license: License::None,
+            extras: true,
+            license_year: 2026,
+        };
+        crate::generate_project(&config, lang);
+        config
+    }
+
+    #[test]
+    fn test_fresh_project_passes() {
+        let config = generate("fresh", Language::C, vec![Language::Rust, Language::Python, Language::Go, Language::Flutter, Language::Zig]);
+        let report = run(&config.output_dir, &config.name, &config.schema).unwrap();
+        fs::remove_dir_all(&config.output_dir).ok();
+
+        assert!(report.passed(), "{:#?}", report);
+        assert_eq!(report.reference, "shared/protocol.h (FreshState)");
+        for decoder in [
+            "clients/rust/src/lib.rs",
+            "clients/python/venom_binding.py",
+            "clients/go/venom/venom.go",
+            "clients/flutter/lib/venom_binding.dart",
+            "clients/zig/src/venom.zig",
+        ] {
+            assert!(report.checked.iter().any(|c| c == decoder), "{} not checked: {:?}", decoder, report.checked);
+        }
+    }
+
+    #[test]
+    fn test_rust_project_checks_against_schema() {
+        let config = generate("solo", Language::Rust, Vec::new());
+        let report = run(&config.output_dir, &config.name, &config.schema).unwrap();
+        fs::remove_dir_all(&config.output_dir).ok();
+
+        assert!(report.passed(), "{:#?}", report);
+        assert_eq!(report.reference, "venom.toml");
+        assert_eq!(report.checked, ["src/lib.rs"]);
+    }
+
+    #[test]
+    fn test_shifted_offset_fails() {
+        let config = generate("shifted", Language::Python, Vec::new());
+        let path = Path::new(&config.output_dir).join(OFFSETS_FILE);
+        let mut offsets: DecoderOffsets = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let temps = offsets.fields.iter_mut().find(|f| f.name == "temps").unwrap();
+        let expected = Slot { offset: temps.offset, size: temps.size };
+        temps.offset += 1;
+        fs::write(&path, serde_json::to_string(&offsets).unwrap()).unwrap();
+
+        let report = run(&config.output_dir, &config.name, &config.schema).unwrap();
+        fs::remove_dir_all(&config.output_dir).ok();
+
+        assert!(!report.passed());
+        assert_eq!(report.mismatches.len(), 1, "{:#?}", report.mismatches);
+        let m = &report.mismatches[0];
+        assert_eq!((m.decoder.as_str(), m.field.as_str()), ("venom_binding.py", "temps"));
+        assert_eq!(m.expected, Some(expected));
+        assert_eq!(m.found, Some(Slot { offset: expected.offset + 1, size: expected.size }));
+    }
+
+    #[test]
+    fn test_changed_rust_struct_fails() {
+        let config = generate("changed", Language::C, vec![Language::Rust]);
+        let lib_rs = Path::new(&config.output_dir).join("clients/rust/src/lib.rs");
+        let text = fs::read_to_string(&lib_rs).unwrap();
+        fs::write(&lib_rs, text.replacen("pub level: i16", "pub level: i32", 1)).unwrap();
+
+        let report = run(&config.output_dir, &config.name, &config.schema).unwrap();
+        fs::remove_dir_all(&config.output_dir).ok();
+
+        let fields: Vec<&str> = report.mismatches.iter().map(|m| m.field.as_str()).collect();
+        assert_eq!(fields, ["level", STATE_SIZE], "{:#?}", report.mismatches);
+        assert!(report.mismatches.iter().all(|m| m.decoder == "clients/rust/src/lib.rs"));
+    }
+
+    #[test]
+    fn test_cpp_header_checked() {
+        let config = generate("cpp", Language::C, vec![Language::Cpp]);
+        let report = run(&config.output_dir, &config.name, &config.schema).unwrap();
+        assert!(report.passed(), "{:#?}", report);
+        assert!(report.checked.iter().any(|c| c == CPP_HEADER), "{:?}", report.checked);
+
+        let hpp = Path::new(&config.output_dir).join(CPP_HEADER);
+        let text = fs::read_to_string(&hpp).unwrap();
+        fs::write(&hpp, text.replacen("std::array<float, 3>", "std::array<float, 2>", 1)).unwrap();
+        let report = run(&config.output_dir, &config.name, &config.schema).unwrap();
+        fs::remove_dir_all(&config.output_dir).ok();
+
+        let fields: Vec<&str> = report.mismatches.iter().map(|m| m.field.as_str()).collect();
+        assert_eq!(fields, ["temps", "count", "level", STATE_SIZE], "{:#?}", report.mismatches);
+        assert!(report.mismatches.iter().all(|m| m.decoder == CPP_HEADER));
+    }
+}
@@ -0,0 +1,108 @@
+'use strict';
+
+// Run with `npm test` (builds the helper daemon first), or:
+//   cargo build --example test_daemon && node --test __test__/
+
+const { test, before, after } = require('node:test');
+const assert = require('node:assert');
+const { spawn } = require('node:child_process');
+const { join } = require('node:path');
+
+const { VenomShell, VenomDaemon } = require('..');
+
+const DAEMON = join(__dirname, '..', 'target', 'debug', 'examples', 'test_daemon');
+const CHANNEL = `node_test_${process.pid}`;
+
+let daemon;
+
+before(async () => {
+  daemon = spawn(DAEMON, [CHANNEL], { stdio: ['pipe', 'pipe', 'inherit'] });
+  await new Promise((resolve, reject) => {
+    daemon.once('error', reject);
+    daemon.stdout.once('data', (chunk) => {
+      assert.match(chunk.toString(), /ready/);
+      resolve();
+    });
+  });
+});
+
+after(async () => {
+  daemon.stdin.end();
+  await new Promise((resolve) => daemon.once('exit', resolve));
+});
+
+test('readData returns what the daemon published', () => {
+  const shell = VenomShell.connect(CHANNEL);
+  assert.ok(shell.clientId > 0);
+  assert.deepStrictEqual(shell.readData(), Buffer.from('hello from rust'));
+  assert.strictEqual(shell.readIfChanged(), null);
+  shell.close();
+});
+
+test('readDataStamped reports how old the data is', () => {
+  const shell = VenomShell.connect(CHANNEL);
+  const { data, ageUs } = shell.readDataStamped();
+  assert.deepStrictEqual(data, Buffer.from('hello from rust'));
+  assert.ok(ageUs > 0 && ageUs < 60e6, `written ${ageUs} µs ago`);
+  shell.close();
+});
+
+test('request resolves with the daemon reply', async () => {
+  const shell = VenomShell.connect(CHANNEL);
+  const reply = await shell.request(Buffer.from('ping'), 2000);
+  assert.strictEqual(reply.toString(), `${shell.clientId}:PING`);
+  shell.close();
+});
+
+test('sendCommand + waitForUpdate', async () => {
+  const shell = VenomShell.connect(CHANNEL);
+  shell.readData();
+  assert.strictEqual(await shell.waitForUpdate(20), null);
+
+  const pending = shell.waitForUpdate(2000);
+  assert.ok(shell.sendCommand(Buffer.from('wait')));
+  assert.strictEqual((await pending).toString(), `${shell.clientId}:WAIT`);
+  assert.strictEqual(shell.readIfChanged(), null);
+  shell.close();
+});
+
+test('watch delivers updates through a threadsafe function', async () => {
+  const shell = VenomShell.connect(CHANNEL);
+  const expected = `${shell.clientId}:WATCH`;
+
+  const seen = await new Promise((resolve) => {
+    const watcher = shell.watch((data) => {
+      if (data.toString() === expected) {
+        watcher.stop();
+        resolve(data.toString());
+      }
+    });
+    shell.sendCommand(Buffer.from('watch'));
+  });
+
+  assert.strictEqual(seen, expected);
+  shell.close();
+});
+
+test('in-process daemon and errors', () => {
+  const name = `node_local_${process.pid}`;
+  const local = VenomDaemon.create(name, { dataSize: 64 });
+  const shell = VenomShell.connect(name);
+
+  local.writeData(Buffer.from('local'));
+  assert.strictEqual(shell.readIfChanged().toString(), 'local');
+
+  assert.strictEqual(local.tryRecvCommand(), null);
+  shell.sendCommand(Buffer.from('cmd'));
+  const cmd = local.tryRecvCommand();
+  assert.strictEqual(cmd.clientId, shell.clientId);
+  assert.strictEqual(cmd.data.toString(), 'cmd');
+
+  assert.throws(() => local.writeData(Buffer.alloc(64)), { code: 'InvalidArg' });
+  assert.throws(() => VenomShell.connect('bad/name'), { code: 'InvalidArg' });
+  assert.throws(() => VenomShell.connect(`node_missing_${process.pid}`));
+
+  shell.close();
+  assert.throws(() => shell.readData(), /closed/);
+  local.close();
+});
@@ -0,0 +1,50 @@
+//! Helper daemon for the `node --test` suite
+//!
+//! Usage: test_daemon <channel>
+//!
+//! Publishes "hello from rust", prints "ready" once the channel exists, then
+//! answers every command with "<client_id>:<COMMAND>" until stdin closes.
+
+use std::io::{Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use venom_memory::mpsc_queue::MAX_CMD_SIZE;
+use venom_memory::{ChannelConfig, DaemonChannel};
+
+fn main() {
+    let channel = std::env::args().nth(1).expect("usage: test_daemon <channel>");
+
+    let config = ChannelConfig {
+        data_size: 4096,
+        stamp_writes: true,
+        ..ChannelConfig::default()
+    };
+    let daemon = DaemonChannel::create(&channel, config).expect("failed to create channel");
+    daemon.write_data_with_len(b"hello from rust");
+
+    println!("ready");
+    std::io::stdout().flush().unwrap();
+
+    // The test harness closes our stdin when it's done with us
+    let running = Arc::new(AtomicBool::new(true));
+    let r = Arc::clone(&running);
+    thread::spawn(move || {
+        let mut sink = Vec::new();
+        let _ = std::io::stdin().read_to_end(&mut sink);
+        r.store(false, Ordering::Release);
+    });
+
+    let mut buf = [0u8; MAX_CMD_SIZE];
+    while running.load(Ordering::Acquire) {
+        match daemon.try_recv_command(&mut buf) {
+            Some((client_id, len)) => {
+                let mut reply = format!("{}:", client_id).into_bytes();
+                reply.extend(buf[..len].to_ascii_uppercase());
+                daemon.write_data_with_len(&reply);
+            }
+            None => thread::sleep(Duration::from_micros(100)),
+        }
+    }
+}
@@ -0,0 +1,21 @@
+'use strict';
+
+// Loads the native addon built by `npm run build` (napi-rs names it after the
+// target triple) or by `npm run build:debug` / a plain cargo build.
+
+const { existsSync } = require('fs');
+const { join } = require('path');
+
+const candidates = [
+  `venom-node.${process.platform}-${process.arch}${process.platform === 'linux' ? '-gnu' : ''}.node`,
+  'venom-node.node',
+];
+
+const found = candidates.map((f) => join(__dirname, f)).find((p) => existsSync(p));
+if (!found) {
+  throw new Error(
+    `venom-node: native addon not found (looked for ${candidates.join(', ')}); run \`npm run build\``,
+  );
+}
+
+module.exports = require(found);
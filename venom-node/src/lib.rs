@@ -0,0 +1,396 @@
+//! venom-node - Node.js bindings for VenomMemory
+//!
+//! Exposes `VenomShell` and `VenomDaemon` to JavaScript through N-API.
+//! Handles are released by `Drop` when the JS object is garbage collected,
+//! or eagerly with `close()`.
+//!
+//! Nothing here blocks the JS thread: waiting for data runs on the libuv
+//! thread pool (`waitForUpdate`, `request`) or on a watcher thread that
+//! pushes updates back through a threadsafe function (`watch`).
+
+#[macro_use]
+extern crate napi_derive;
+
+use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{
+    ErrorStrategy, ThreadSafeCallContext, ThreadsafeFunction, ThreadsafeFunctionCallMode,
+};
+use napi::JsFunction;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use venom_memory::mpsc_queue::MAX_CMD_SIZE;
+use venom_memory::seqlock::now_ns;
+use venom_memory::{ChannelConfig, DaemonChannel, ShellChannel, VenomError};
+
+/// Size of the length prefix written by `write_data_with_len`
+const LEN_PREFIX: usize = 8;
+
+/// Poll interval used while waiting off the JS thread
+const POLL_INTERVAL: Duration = Duration::from_micros(200);
+
+fn to_napi_err(e: VenomError) -> Error {
+    let status = match e {
+        VenomError::InvalidNamespace { .. }
+        | VenomError::NamespaceTooLong { .. }
+        | VenomError::BufferOverflow { .. } => Status::InvalidArg,
+        _ => Status::GenericFailure,
+    };
+    Error::new(status, e.to_string())
+}
+
+fn closed_err() -> Error {
+    Error::new(Status::GenericFailure, "handle has been closed".to_string())
+}
+
+fn read_latest(shell: &ShellChannel) -> Vec<u8> {
+    let mut buf = vec![0u8; shell.data_size().saturating_sub(LEN_PREFIX)];
+    let len = shell.read_data_with_len(&mut buf).min(buf.len());
+    buf.truncate(len);
+    buf
+}
+
+/// Block (off the JS thread) until the sequence moves past `last_seq`
+fn wait_for_change(shell: &ShellChannel, last_seq: u64, timeout: Duration) -> Option<u64> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let seq = shell.data_sequence();
+        if seq & 1 == 0 && seq != last_seq {
+            return Some(seq);
+        }
+        if Instant::now() >= deadline {
+            return None;
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Shell
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Client side of a channel: reads daemon data and sends commands
+#[napi]
+pub struct VenomShell {
+    inner: Option<Arc<ShellChannel>>,
+    /// Sequence of the last update handed to JS, shared with pending waits
+    last_seq: Arc<AtomicU64>,
+}
+
+#[napi]
+impl VenomShell {
+    /// Connect to an existing channel
+    #[napi(factory)]
+    pub fn connect(name: String) -> Result<Self> {
+        let inner = ShellChannel::connect(&name).map_err(to_napi_err)?;
+        Ok(Self {
+            inner: Some(Arc::new(inner)),
+            last_seq: Arc::new(AtomicU64::new(0)),
+        })
+    }
+
+    fn shell(&self) -> Result<&Arc<ShellChannel>> {
+        self.inner.as_ref().ok_or_else(closed_err)
+    }
+
+    /// Unique ID assigned by the daemon
+    #[napi(getter)]
+    pub fn client_id(&self) -> Result<u32> {
+        Ok(self.shell()?.client_id())
+    }
+
+    /// Version of the venom_memory this addon was built from (it links it statically)
+    #[napi(getter)]
+    pub fn library_version(&self) -> String {
+        venom_memory::VERSION.to_string()
+    }
+
+    /// Read the latest data published by the daemon
+    #[napi]
+    pub fn read_data(&self) -> Result<Buffer> {
+        let shell = self.shell()?;
+        self.last_seq
+            .store(shell.data_sequence(), Ordering::Relaxed);
+        Ok(read_latest(shell).into())
+    }
+
+    /// Read the latest data along with how long ago the daemon wrote it
+    #[napi]
+    pub fn read_data_stamped(&self) -> Result<StampedData> {
+        let shell = self.shell()?;
+        self.last_seq
+            .store(shell.data_sequence(), Ordering::Relaxed);
+        let mut buf = vec![0u8; shell.data_size().saturating_sub(LEN_PREFIX)];
+        let (written, len) = shell
+            .try_read_data_with_len_stamped(&mut buf)
+            .map_err(to_napi_err)?;
+        buf.truncate(len);
+        let age_us = if written == 0 {
+            0.0
+        } else {
+            now_ns().saturating_sub(written) as f64 / 1000.0
+        };
+        Ok(StampedData { data: buf.into(), age_us })
+    }
+
+    /// Read the latest data only if the daemon wrote since the last read
+    #[napi]
+    pub fn read_if_changed(&self) -> Result<Option<Buffer>> {
+        let shell = self.shell()?;
+        let seq = shell.data_sequence();
+        if seq & 1 == 1 || seq == self.last_seq.load(Ordering::Relaxed) {
+            return Ok(None);
+        }
+        self.last_seq.store(seq, Ordering::Relaxed);
+        Ok(Some(read_latest(shell).into()))
+    }
+
+    /// Queue a command for the daemon; returns false if the queue is full
+    #[napi]
+    pub fn send_command(&self, cmd: Buffer) -> Result<bool> {
+        let shell = self.shell()?;
+        if cmd.len() > shell.cmd_payload_size() {
+            return Err(to_napi_err(VenomError::BufferOverflow {
+                max: shell.cmd_payload_size(),
+                got: cmd.len(),
+            }));
+        }
+        Ok(shell.try_send_command(&cmd))
+    }
+
+    /// Resolve with the next update, or null after `timeoutMs`
+    #[napi(ts_return_type = "Promise<Buffer | null>")]
+    pub fn wait_for_update(&self, timeout_ms: u32) -> Result<AsyncTask<WaitForUpdate>> {
+        Ok(AsyncTask::new(WaitForUpdate {
+            shell: self.shell()?.clone(),
+            last_seq: Arc::clone(&self.last_seq),
+            timeout: Duration::from_millis(timeout_ms as u64),
+        }))
+    }
+
+    /// Send a command and resolve with the daemon's next write
+    ///
+    /// Rejects if no response arrives within `timeoutMs`.
+    #[napi(ts_return_type = "Promise<Buffer>")]
+    pub fn request(&self, cmd: Buffer, timeout_ms: u32) -> Result<AsyncTask<Request>> {
+        let shell = self.shell()?;
+        if cmd.len() > shell.cmd_payload_size() {
+            return Err(to_napi_err(VenomError::BufferOverflow {
+                max: shell.cmd_payload_size(),
+                got: cmd.len(),
+            }));
+        }
+        Ok(AsyncTask::new(Request {
+            shell: shell.clone(),
+            cmd: cmd.to_vec(),
+            timeout: Duration::from_millis(timeout_ms as u64),
+        }))
+    }
+
+    /// Call `callback(buffer)` on every update until the watcher is stopped
+    #[napi(ts_args_type = "callback: (data: Buffer) => void")]
+    pub fn watch(&self, callback: JsFunction) -> Result<VenomWatcher> {
+        let shell = self.shell()?.clone();
+        let tsfn: ThreadsafeFunction<Vec<u8>, ErrorStrategy::Fatal> = callback
+            .create_threadsafe_function(0, |ctx: ThreadSafeCallContext<Vec<u8>>| {
+                Ok(vec![Buffer::from(ctx.value)])
+            })?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        thread::spawn(move || {
+            let mut last_seq = 0;
+            while !thread_stop.load(Ordering::Acquire) {
+                if let Some(seq) = wait_for_change(&shell, last_seq, Duration::from_millis(50)) {
+                    last_seq = seq;
+                    tsfn.call(read_latest(&shell), ThreadsafeFunctionCallMode::NonBlocking);
+                }
+            }
+        });
+
+        Ok(VenomWatcher { stop })
+    }
+
+    /// Release the shared memory mapping now instead of waiting for GC
+    #[napi]
+    pub fn close(&mut self) {
+        self.inner = None;
+    }
+}
+
+/// Handle returned by `VenomShell.watch`
+#[napi]
+pub struct VenomWatcher {
+    stop: Arc<AtomicBool>,
+}
+
+#[napi]
+impl VenomWatcher {
+    /// Stop delivering updates
+    #[napi]
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Release);
+    }
+}
+
+impl Drop for VenomWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+    }
+}
+
+pub struct WaitForUpdate {
+    shell: Arc<ShellChannel>,
+    last_seq: Arc<AtomicU64>,
+    timeout: Duration,
+}
+
+impl Task for WaitForUpdate {
+    type Output = Option<Vec<u8>>;
+    type JsValue = Option<Buffer>;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        let last_seq = self.last_seq.load(Ordering::Relaxed);
+        Ok(
+            wait_for_change(&self.shell, last_seq, self.timeout).map(|seq| {
+                self.last_seq.store(seq, Ordering::Relaxed);
+                read_latest(&self.shell)
+            }),
+        )
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output.map(Buffer::from))
+    }
+}
+
+pub struct Request {
+    shell: Arc<ShellChannel>,
+    cmd: Vec<u8>,
+    timeout: Duration,
+}
+
+impl Task for Request {
+    type Output = Vec<u8>;
+    type JsValue = Buffer;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        let deadline = Instant::now() + self.timeout;
+        let start_seq = self.shell.data_sequence();
+
+        while !self.shell.try_send_command(&self.cmd) {
+            if Instant::now() >= deadline {
+                return Err(to_napi_err(VenomError::QueueFull));
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match wait_for_change(&self.shell, start_seq, remaining) {
+            Some(_) => Ok(read_latest(&self.shell)),
+            None => Err(Error::new(
+                Status::GenericFailure,
+                "timed out waiting for response".to_string(),
+            )),
+        }
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output.into())
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Daemon
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[napi(object)]
+pub struct DaemonOptions {
+    pub data_size: Option<u32>,
+    pub cmd_slots: Option<u32>,
+    pub max_clients: Option<u32>,
+    pub stamp_writes: Option<bool>,
+}
+
+#[napi(object)]
+pub struct VenomCommand {
+    pub client_id: u32,
+    pub data: Buffer,
+}
+
+/// Data from `readDataStamped`. The age is computed here rather than handing
+/// JS the raw CLOCK_MONOTONIC_RAW timestamp, which needs a BigInt (napi6).
+#[napi(object)]
+pub struct StampedData {
+    pub data: Buffer,
+    /// Microseconds between the daemon's write and this read; 0 if the
+    /// daemon doesn't stamp its writes
+    pub age_us: f64,
+}
+
+/// Owner side of a channel: publishes data and receives commands
+#[napi]
+pub struct VenomDaemon {
+    inner: Option<DaemonChannel>,
+}
+
+#[napi]
+impl VenomDaemon {
+    /// Create a channel and become its owner
+    #[napi(factory)]
+    pub fn create(name: String, options: Option<DaemonOptions>) -> Result<Self> {
+        let defaults = ChannelConfig::default();
+        let config = match options {
+            Some(o) => ChannelConfig {
+                data_size: o.data_size.map_or(defaults.data_size, |v| v as usize),
+                cmd_slots: o.cmd_slots.map_or(defaults.cmd_slots, |v| v as usize),
+                max_clients: o.max_clients.map_or(defaults.max_clients, |v| v as usize),
+                stamp_writes: o.stamp_writes.unwrap_or(defaults.stamp_writes),
+                ..defaults
+            },
+            None => defaults,
+        };
+
+        let inner = DaemonChannel::create(&name, config).map_err(to_napi_err)?;
+        Ok(Self { inner: Some(inner) })
+    }
+
+    fn daemon(&self) -> Result<&DaemonChannel> {
+        self.inner.as_ref().ok_or_else(closed_err)
+    }
+
+    /// Publish data to every connected shell
+    #[napi]
+    pub fn write_data(&self, data: Buffer) -> Result<()> {
+        let daemon = self.daemon()?;
+        let max = daemon.data_size().saturating_sub(LEN_PREFIX);
+        if data.len() > max {
+            return Err(to_napi_err(VenomError::BufferOverflow {
+                max,
+                got: data.len(),
+            }));
+        }
+        daemon.write_data_with_len(&data);
+        Ok(())
+    }
+
+    /// Return the next queued command, or null if there is none
+    #[napi]
+    pub fn try_recv_command(&self) -> Result<Option<VenomCommand>> {
+        let mut buf = [0u8; MAX_CMD_SIZE];
+        Ok(self
+            .daemon()?
+            .try_recv_command(&mut buf)
+            .map(|(client_id, len)| VenomCommand {
+                client_id,
+                data: buf[..len].to_vec().into(),
+            }))
+    }
+
+    /// Destroy the channel now instead of waiting for GC
+    #[napi]
+    pub fn close(&mut self) {
+        self.inner = None;
+    }
+}
@@ -0,0 +1,237 @@
+//! venom_py - Native Python bindings for VenomMemory
+//!
+//! A PyO3 alternative to the ctypes binding generated by venom-cli. Reads go
+//! straight from shared memory into a Python `bytes` (or a caller-provided
+//! buffer) with a single copy and no per-call argument marshalling.
+//!
+//! ```python
+//! import venom_py
+//!
+//! shell = venom_py.Shell("my_channel")
+//! data = shell.read_bytes()
+//! shell.send_command(b"refresh")
+//! ```
+
+use pyo3::buffer::PyBuffer;
+use pyo3::exceptions::{PyBufferError, PyConnectionError, PyOSError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use std::sync::Mutex;
+use std::time::Duration;
+use venom_memory::mpsc_queue::MAX_CMD_SIZE;
+use venom_memory::{ChannelConfig, DaemonChannel, ShellChannel, VenomError};
+
+/// Size of the length prefix written by `write_data_with_len`
+const LEN_PREFIX: usize = 8;
+
+fn to_py_err(e: VenomError) -> PyErr {
+    match e {
+        VenomError::ShmOpen { .. } | VenomError::InvalidMagic { .. } => {
+            PyConnectionError::new_err(e.to_string())
+        }
+        VenomError::InvalidNamespace { .. }
+        | VenomError::NamespaceTooLong { .. }
+        | VenomError::InvalidClientName { .. }
+        | VenomError::BufferOverflow { .. } => PyValueError::new_err(e.to_string()),
+        _ => PyOSError::new_err(e.to_string()),
+    }
+}
+
+/// `buffer` as a byte slice to read into
+fn writable(buffer: &mut PyBuffer<u8>) -> PyResult<&mut [u8]> {
+    if buffer.readonly() {
+        return Err(PyBufferError::new_err("buffer is read-only"));
+    }
+    if !buffer.is_c_contiguous() {
+        return Err(PyBufferError::new_err("buffer must be C-contiguous"));
+    }
+
+    let ptr = buffer.buf_ptr() as *mut u8;
+    let len = buffer.item_count();
+    // SAFETY: the buffer is writable, contiguous and `len` bytes long; the
+    // exporter keeps it alive for as long as `buffer` holds the view
+    Ok(unsafe { std::slice::from_raw_parts_mut(ptr, len) })
+}
+
+/// Client side of a channel: reads daemon data and sends commands
+#[pyclass(module = "venom_py")]
+struct Shell {
+    inner: ShellChannel,
+}
+
+#[pymethods]
+impl Shell {
+    /// `client_name` is shown by the daemon, e.g. in its logs
+    #[new]
+    #[pyo3(signature = (channel_name, client_name=None))]
+    fn new(channel_name: &str, client_name: Option<&str>) -> PyResult<Self> {
+        let inner = match client_name {
+            Some(client_name) => ShellChannel::connect_named(channel_name, client_name),
+            None => ShellChannel::connect(channel_name),
+        }
+        .map_err(to_py_err)?;
+        Ok(Self { inner })
+    }
+
+    /// Unique ID assigned by the daemon
+    #[getter]
+    fn client_id(&self) -> u32 {
+        self.inner.client_id()
+    }
+
+    /// Size of the channel's data region in bytes
+    #[getter]
+    fn data_size(&self) -> usize {
+        self.inner.data_size()
+    }
+
+    /// Read the latest data as `bytes`, truncated to `max_len` if given
+    #[pyo3(signature = (max_len=None))]
+    fn read_bytes<'py>(&self, py: Python<'py>, max_len: Option<usize>) -> Bound<'py, PyBytes> {
+        let capacity = self.inner.data_size().saturating_sub(LEN_PREFIX);
+        let max_len = max_len.map_or(capacity, |m| m.min(capacity));
+        let mut buf = vec![0u8; max_len];
+        let len = self.inner.read_data_with_len(&mut buf).min(max_len);
+        PyBytes::new(py, &buf[..len])
+    }
+
+    /// Read the latest data into a writable buffer (bytearray, memoryview, ...)
+    ///
+    /// Returns the full length of the published data, which may be larger
+    /// than the buffer.
+    fn read_into(&self, mut buffer: PyBuffer<u8>) -> PyResult<usize> {
+        Ok(self.inner.read_data_with_len(writable(&mut buffer)?))
+    }
+
+    /// `read_into`, returning `(length, timestamp_ns)`: when the daemon wrote
+    /// the data, comparable with `venom_py.clock_ns()`; 0 if the daemon
+    /// doesn't stamp its writes
+    fn read_into_stamped(&self, mut buffer: PyBuffer<u8>) -> PyResult<(usize, u64)> {
+        let (timestamp, len) = self.inner.try_read_data_with_len_stamped(writable(&mut buffer)?).map_err(to_py_err)?;
+        Ok((len, timestamp))
+    }
+
+    /// When the daemon last wrote, as in `read_into_stamped`
+    #[getter]
+    fn last_write_timestamp(&self) -> u64 {
+        self.inner.last_write_timestamp()
+    }
+
+    /// Queue a command for the daemon; returns False if the queue is full
+    fn send_command(&self, cmd: &[u8]) -> PyResult<bool> {
+        if cmd.len() > self.inner.cmd_payload_size() {
+            return Err(to_py_err(VenomError::BufferOverflow {
+                max: self.inner.cmd_payload_size(),
+                got: cmd.len(),
+            }));
+        }
+        Ok(self.inner.try_send_command(cmd))
+    }
+
+    /// `send_command`, waiting up to `timeout_ms` for room in a full queue
+    /// before returning False
+    fn send_command_timeout(&self, py: Python<'_>, cmd: &[u8], timeout_ms: u32) -> PyResult<bool> {
+        let timeout = Duration::from_millis(u64::from(timeout_ms));
+        match py.allow_threads(|| self.inner.send_command_timeout(cmd, timeout)) {
+            Ok(()) => Ok(true),
+            Err(VenomError::QueueFull) => Ok(false),
+            Err(e) => Err(to_py_err(e)),
+        }
+    }
+
+    /// Sends this shell gave up on because the queue stayed full
+    #[getter]
+    fn rejected_sends(&self) -> u64 {
+        self.inner.rejected_sends()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Shell(channel={:?}, client_id={})",
+            self.inner.namespace(),
+            self.inner.client_id()
+        )
+    }
+}
+
+/// Owner side of a channel: publishes data and receives commands
+#[pyclass(module = "venom_py")]
+struct Daemon {
+    inner: Mutex<DaemonChannel>,
+}
+
+#[pymethods]
+impl Daemon {
+    #[new]
+    #[pyo3(signature = (channel_name, data_size=65536, cmd_slots=32, max_clients=16, stamp_writes=false))]
+    fn new(
+        channel_name: &str,
+        data_size: usize,
+        cmd_slots: usize,
+        max_clients: usize,
+        stamp_writes: bool,
+    ) -> PyResult<Self> {
+        let config = ChannelConfig {
+            data_size,
+            cmd_slots,
+            max_clients,
+            stamp_writes,
+            ..ChannelConfig::default()
+        };
+        let inner = DaemonChannel::create(channel_name, config).map_err(to_py_err)?;
+        Ok(Self {
+            inner: Mutex::new(inner),
+        })
+    }
+
+    /// Publish data to every connected shell
+    fn write_data(&self, data: &[u8]) -> PyResult<()> {
+        let daemon = self.inner.lock().unwrap();
+        let max = daemon.data_size().saturating_sub(LEN_PREFIX);
+        if data.len() > max {
+            return Err(to_py_err(VenomError::BufferOverflow {
+                max,
+                got: data.len(),
+            }));
+        }
+        daemon.write_data_with_len(data);
+        Ok(())
+    }
+
+    /// Return `(client_id, command)` if a command is waiting, else None
+    fn try_recv_command<'py>(&self, py: Python<'py>) -> Option<(u32, Bound<'py, PyBytes>)> {
+        let mut buf = [0u8; MAX_CMD_SIZE];
+        let (client_id, len) = self.inner.lock().unwrap().try_recv_command(&mut buf)?;
+        Some((client_id, PyBytes::new(py, &buf[..len])))
+    }
+
+    /// Name the shell with this client ID connected with, or None
+    fn client_name(&self, client_id: u32) -> Option<String> {
+        self.inner.lock().unwrap().client_name(client_id)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Daemon(channel={:?})",
+            self.inner.lock().unwrap().namespace()
+        )
+    }
+}
+
+/// Nanoseconds of CLOCK_MONOTONIC_RAW, the clock of write timestamps; they
+/// only compare on the same machine
+#[pyfunction]
+fn clock_ns() -> u64 {
+    venom_memory::seqlock::now_ns()
+}
+
+#[pymodule]
+fn venom_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Shell>()?;
+    m.add_class::<Daemon>()?;
+    m.add_function(wrap_pyfunction!(clock_ns, m)?)?;
+    m.add("MAX_CMD_SIZE", MAX_CMD_SIZE)?;
+    // The venom_memory this extension was built from (it links it statically)
+    m.add("LIBRARY_VERSION", venom_memory::VERSION)?;
+    Ok(())
+}
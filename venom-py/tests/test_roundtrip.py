@@ -0,0 +1,145 @@
+"""Round-trip tests for the venom_py extension.
+
+Build the extension first (`maturin develop` or `pip install .`), then run:
+
+    pytest tests/
+"""
+
+import os
+import threading
+import time
+
+import pytest
+
+import venom_py
+
+
+def unique(name: str) -> str:
+    return f"pytest_{name}_{os.getpid()}"
+
+
+def test_shell_reads_daemon_data():
+    channel = unique("read")
+    daemon = venom_py.Daemon(channel, data_size=4096)
+    shell = venom_py.Shell(channel)
+
+    daemon.write_data(b"hello from rust")
+    assert shell.read_bytes() == b"hello from rust"
+    assert shell.read_bytes(5) == b"hello"
+
+    buf = bytearray(32)
+    n = shell.read_into(buf)
+    assert n == len(b"hello from rust")
+    assert bytes(buf[:n]) == b"hello from rust"
+
+    view = memoryview(bytearray(4))
+    assert shell.read_into(view) == len(b"hello from rust")
+    assert view.tobytes() == b"hell"
+
+
+def test_stamped_reads():
+    channel = unique("stamped")
+    daemon = venom_py.Daemon(channel, data_size=4096, stamp_writes=True)
+    shell = venom_py.Shell(channel)
+    buf = bytearray(32)
+
+    last = 0
+    for data in (b"first", b"second"):
+        before = venom_py.clock_ns()
+        daemon.write_data(data)
+        n, written_ns = shell.read_into_stamped(buf)
+        assert bytes(buf[:n]) == data
+        assert before <= written_ns <= venom_py.clock_ns()
+        assert written_ns >= last and shell.last_write_timestamp == written_ns
+        last = written_ns
+
+    plain = venom_py.Daemon(unique("plain"), data_size=4096)
+    plain.write_data(b"x")
+    assert venom_py.Shell(unique("plain")).read_into_stamped(buf) == (1, 0)
+
+
+def test_command_round_trip_with_daemon_thread():
+    channel = unique("echo")
+    daemon = venom_py.Daemon(channel, data_size=4096)
+    stop = threading.Event()
+
+    def serve():
+        while not stop.is_set():
+            msg = daemon.try_recv_command()
+            if msg is None:
+                time.sleep(0.001)
+                continue
+            client_id, cmd = msg
+            daemon.write_data(b"%d:" % client_id + cmd.upper())
+
+    server = threading.Thread(target=serve)
+    server.start()
+    try:
+        shell = venom_py.Shell(channel)
+        assert shell.send_command(b"ping")
+
+        expected = b"%d:PING" % shell.client_id
+        deadline = time.monotonic() + 2.0
+        while shell.read_bytes() != expected:
+            assert time.monotonic() < deadline, "daemon never answered"
+            time.sleep(0.001)
+    finally:
+        stop.set()
+        server.join()
+
+
+def test_client_names():
+    channel = unique("names")
+    daemon = venom_py.Daemon(channel, data_size=4096, max_clients=2)
+    gui = venom_py.Shell(channel, client_name="gui-frontend")
+    anonymous = venom_py.Shell(channel)
+    assert daemon.client_name(gui.client_id) == "gui-frontend"
+    assert daemon.client_name(anonymous.client_id) is None
+
+    # The channel is full until a shell leaves
+    with pytest.raises(OSError):
+        venom_py.Shell(channel)
+    del anonymous
+    cli = venom_py.Shell(channel, client_name="cli")
+    assert daemon.client_name(cli.client_id) == "cli"
+
+    with pytest.raises(ValueError):
+        venom_py.Shell(channel, client_name="x" * 33)
+
+
+def test_send_command_timeout():
+    channel = unique("full")
+    daemon = venom_py.Daemon(channel, data_size=64, cmd_slots=2)
+    shell = venom_py.Shell(channel)
+    assert shell.send_command_timeout(b"a", 10)
+    assert shell.send_command_timeout(b"b", 10)
+
+    # Nobody drains the queue, so the third send waits out its timeout
+    start = time.monotonic()
+    assert not shell.send_command_timeout(b"c", 20)
+    assert 0.015 < time.monotonic() - start < 1.0
+    assert shell.rejected_sends == 1
+
+    assert daemon.try_recv_command() == (shell.client_id, b"a")
+    assert shell.send_command_timeout(b"c", 20)
+
+
+def test_errors():
+    with pytest.raises(ConnectionError):
+        venom_py.Shell(unique("missing"))
+
+    with pytest.raises(ValueError):
+        venom_py.Shell("bad/name")
+
+    channel = unique("limits")
+    daemon = venom_py.Daemon(channel, data_size=64)
+    shell = venom_py.Shell(channel)
+
+    with pytest.raises(ValueError):
+        daemon.write_data(b"x" * 64)
+
+    with pytest.raises(ValueError):
+        shell.send_command(b"x" * (venom_py.MAX_CMD_SIZE + 1))
+
+    with pytest.raises(BufferError):
+        shell.read_into(b"read-only")
@@ -0,0 +1,199 @@
+//! The channels on the machine as venom-top last sampled them
+//!
+//! Each channel is mapped once with a read-only shell, so watching it
+//! neither takes a client ID nor shows up in its client count. Rates come
+//! from the difference between two samples; the age of the last write is
+//! the time since a sample first saw the current write, so it reads
+//! "at least" until a write has been seen.
+
+use std::time::{Duration, Instant};
+use venom_memory::discover::ChannelSummary;
+use venom_memory::ShellChannel;
+
+/// What one sample of a channel reads
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    pub at: Instant,
+    /// The data region's sequence number: two per write
+    pub sequence: u64,
+    pub commands_received: u64,
+    pub commands_pending: usize,
+    pub data_size: usize,
+}
+
+impl Sample {
+    fn read(shell: &ShellChannel, at: Instant) -> Sample {
+        Sample {
+            at,
+            sequence: shell.data_sequence(),
+            commands_received: shell.commands_received(),
+            commands_pending: shell.commands_pending(),
+            data_size: shell.data_size(),
+        }
+    }
+
+    /// Writes completed so far
+    pub fn generation(&self) -> u64 {
+        self.sequence / 2
+    }
+
+    /// Whether the daemon was in the middle of a write
+    pub fn writing(&self) -> bool {
+        self.sequence & 1 == 1
+    }
+
+    /// Writes and commands per second between `earlier` and this sample
+    pub fn rates_since(&self, earlier: &Sample) -> Rates {
+        let secs = self.at.duration_since(earlier.at).as_secs_f64();
+        if secs <= 0.0 {
+            return Rates::default();
+        }
+        Rates {
+            writes: self.generation().saturating_sub(earlier.generation()) as f64 / secs,
+            commands: self.commands_received.saturating_sub(earlier.commands_received) as f64 / secs,
+        }
+    }
+}
+
+/// Per second, between the last two samples
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Rates {
+    pub writes: f64,
+    pub commands: f64,
+}
+
+/// A channel venom-top is watching
+pub struct Tracked {
+    pub summary: ChannelSummary,
+    /// None if the channel couldn't be mapped (e.g. another user's)
+    pub shell: Option<ShellChannel>,
+    pub sample: Option<Sample>,
+    pub rates: Rates,
+    /// When the channel was first sampled
+    first_seen: Instant,
+    /// When a sample first saw the current generation, if one saw it change
+    last_write: Option<Instant>,
+}
+
+impl Tracked {
+    fn new(summary: ChannelSummary, now: Instant) -> Tracked {
+        Tracked { summary, shell: None, sample: None, rates: Rates::default(), first_seen: now, last_write: None }
+    }
+
+    /// Time since the last write, and whether it's only a lower bound
+    /// because no write has been seen yet
+    pub fn last_write_age(&self, now: Instant) -> (Duration, bool) {
+        match self.last_write {
+            Some(at) => (now.duration_since(at), false),
+            None => (now.duration_since(self.first_seen), true),
+        }
+    }
+
+    fn sample(&mut self, now: Instant) {
+        // A daemon that took the namespace over may have resized it
+        if self.shell.as_ref().is_some_and(|shell| shell.daemon_pid() != self.summary.owner_pid) {
+            (self.shell, self.sample) = (None, None);
+        }
+        if self.shell.is_none() {
+            self.shell = ShellChannel::connect_read_only(&self.summary.namespace).ok();
+        }
+        let Some(shell) = &self.shell else { return };
+        self.record(Sample::read(shell, now));
+    }
+
+    fn record(&mut self, sample: Sample) {
+        if let Some(previous) = &self.sample {
+            self.rates = sample.rates_since(previous);
+            if sample.sequence != previous.sequence {
+                self.last_write = Some(sample.at);
+            }
+        }
+        self.sample = Some(sample);
+    }
+}
+
+/// Every channel of the last listing, in its order
+#[derive(Default)]
+pub struct Channels {
+    pub list: Vec<Tracked>,
+}
+
+impl Channels {
+    /// Sample the channels of a new listing; channels no longer in it are
+    /// dropped, new ones start without rates
+    pub fn refresh(&mut self, summaries: Vec<ChannelSummary>, now: Instant) {
+        let mut previous = std::mem::take(&mut self.list);
+        for summary in summaries {
+            let mut tracked = match previous.iter().position(|t| t.summary.namespace == summary.namespace) {
+                Some(i) => {
+                    let mut tracked = previous.swap_remove(i);
+                    tracked.summary = summary;
+                    tracked
+                }
+                None => Tracked::new(summary, now),
+            };
+            tracked.sample(now);
+            self.list.push(tracked);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use venom_memory::{ChannelConfig, DaemonChannel};
+
+    fn sample(at: Instant, sequence: u64, commands_received: u64) -> Sample {
+        Sample { at, sequence, commands_received, commands_pending: 0, data_size: 64 }
+    }
+
+    fn summary(namespace: &str) -> ChannelSummary {
+        ChannelSummary { namespace: namespace.to_string(), size: 0, version: 2, owner_pid: std::process::id(), owner_alive: true, client_count: 0 }
+    }
+
+    #[test]
+    fn test_rates() {
+        let start = Instant::now();
+        let later = start + Duration::from_millis(500);
+        let rates = sample(later, 41, 30).rates_since(&sample(start, 20, 10));
+        assert_eq!(rates, Rates { writes: 20.0, commands: 40.0 });
+        assert!(sample(later, 41, 0).writing());
+        assert_eq!(sample(start, 1, 0).rates_since(&sample(start, 0, 0)), Rates::default());
+    }
+
+    #[test]
+    fn test_last_write_age() {
+        let start = Instant::now();
+        let mut tracked = Tracked::new(summary("age"), start);
+        tracked.record(sample(start, 4, 0));
+        assert_eq!(tracked.last_write_age(start + Duration::from_secs(3)), (Duration::from_secs(3), true));
+
+        tracked.record(sample(start + Duration::from_secs(1), 4, 0));
+        tracked.record(sample(start + Duration::from_secs(2), 6, 0));
+        assert_eq!(tracked.last_write_age(start + Duration::from_secs(3)), (Duration::from_secs(1), false));
+        assert_eq!(tracked.rates.writes, 1.0);
+    }
+
+    #[test]
+    fn test_refresh() {
+        let namespace = "test_top_refresh";
+        let daemon = DaemonChannel::create(namespace, ChannelConfig::default()).unwrap();
+        let mut channels = Channels::default();
+        let start = Instant::now();
+        channels.refresh(vec![summary(namespace), summary("test_top_missing")], start);
+        assert_eq!(channels.list.len(), 2);
+        assert!(channels.list[0].shell.as_ref().is_some_and(|shell| shell.is_read_only()));
+        assert!(channels.list[1].shell.is_none(), "no such channel");
+
+        daemon.write_data(b"one");
+        daemon.write_data(b"two");
+        channels.refresh(vec![summary(namespace)], start + Duration::from_secs(1));
+        let tracked = &channels.list[0];
+        assert_eq!(tracked.sample.unwrap().generation(), 2);
+        assert_eq!(tracked.rates.writes, 2.0);
+        assert_eq!(tracked.shell.as_ref().unwrap().client_count(), 0, "not counted as a client");
+
+        channels.refresh(Vec::new(), start + Duration::from_secs(2));
+        assert!(channels.list.is_empty());
+    }
+}
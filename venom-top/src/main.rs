@@ -0,0 +1,233 @@
+use clap::Parser;
+use std::io;
+use std::time::{Duration, Instant, SystemTime};
+use ratatui::{
+    backend::CrosstermBackend,
+    widgets::{Block, Borders, List, ListItem, Paragraph, Row, Table},
+    layout::{Layout, Constraint, Direction},
+    style::{Style, Color, Modifier},
+    Terminal,
+};
+use crossterm::{
+    event::{self, Event, KeyCode},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use venom_memory::discover;
+
+mod channels;
+mod tui;
+
+use channels::{Channels, Tracked};
+
+#[derive(Parser, Debug)]
+#[command(name = "venom-top")]
+#[command(about = "📡 Live view of the VenomMemory channels on this machine", long_about = None)]
+struct Cli {
+    /// Time between samples, in milliseconds
+    #[arg(short, long, default_value_t = 500)]
+    interval: u64,
+
+    /// Bytes of the data region the hexdump shows
+    #[arg(short, long, default_value_t = 256)]
+    bytes: usize,
+}
+
+fn main() {
+    let cli = Cli::parse();
+    if let Err(e) = run(Duration::from_millis(cli.interval.max(50)), cli.bytes) {
+        eprintln!("[venom-top] {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run(interval: Duration, bytes: usize) -> Result<(), io::Error> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    let result = run_loop(&mut terminal, interval, bytes);
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    result
+}
+
+fn run_loop(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, interval: Duration, bytes: usize) -> Result<(), io::Error> {
+    let mut channels = Channels::default();
+    let mut state = tui::TopState::new();
+    let mut dump = Vec::new();
+    let mut events = Vec::new();
+    let mut read_dump = true;
+    // Sample right away
+    let mut next_sample = Instant::now();
+
+    loop {
+        let now = Instant::now();
+        if now >= next_sample {
+            channels.refresh(discover::list_channels(), now);
+            state.set_rows(channels.list.len());
+            next_sample = now + interval;
+            read_dump = true;
+        }
+        if read_dump {
+            read_dump = false;
+            let selected = state.selected.and_then(|i| channels.list[i].shell.as_ref());
+            match selected {
+                Some(shell) if state.hexdump => {
+                    // The last one stays while a write is in progress
+                    let mut buf = vec![0u8; bytes];
+                    if let Some(len) = shell.try_read_data(&mut buf) {
+                        buf.truncate(len);
+                        dump = buf;
+                    }
+                }
+                _ => dump.clear(),
+            }
+            events = match selected {
+                Some(shell) if state.events => shell.recent_events(),
+                _ => Vec::new(),
+            };
+        }
+
+        terminal.draw(|f| {
+            let constraints = if state.pane_open() {
+                vec![Constraint::Percentage(50), Constraint::Percentage(50), Constraint::Length(1)]
+            } else {
+                vec![Constraint::Min(3), Constraint::Length(1)]
+            };
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(constraints)
+                .split(f.size());
+
+            // 1. Channels
+            let header = Row::new(["NAMESPACE", "DATA", "CLIENTS", "PID", "DAEMON", "GEN", "WRITES/s", "QUEUE", "CMDS/s", "LAST WRITE"])
+                .style(Style::default().add_modifier(Modifier::BOLD));
+            let rows: Vec<Row> = channels
+                .list
+                .iter()
+                .enumerate()
+                .map(|(i, tracked)| {
+                    let mut style = Style::default();
+                    if !tracked.summary.owner_alive {
+                        style = style.fg(Color::Red);
+                    }
+                    if state.selected == Some(i) {
+                        style = style.add_modifier(Modifier::REVERSED);
+                    }
+                    Row::new(cells(tracked, now)).style(style)
+                })
+                .collect();
+            let widths = [
+                Constraint::Min(20),
+                Constraint::Length(9),
+                Constraint::Length(7),
+                Constraint::Length(8),
+                Constraint::Length(6),
+                Constraint::Length(10),
+                Constraint::Length(10),
+                Constraint::Length(5),
+                Constraint::Length(10),
+                Constraint::Length(10),
+            ];
+            let title = format!(" Channels ({}) ", channels.list.len());
+            let table = Table::new(rows, widths).header(header).block(Block::default().borders(Borders::ALL).title(title));
+            f.render_widget(table, chunks[0]);
+
+            // 2. Hexdump of the selected channel
+            if state.hexdump
+                && let Some(tracked) = state.selected.map(|i| &channels.list[i])
+            {
+                let lines: Vec<ListItem> = if tracked.shell.is_none() {
+                    vec![ListItem::new(" Channel could not be mapped").style(Style::default().fg(Color::Red))]
+                } else {
+                    tui::hexdump(&dump).into_iter().map(|line| ListItem::new(format!(" {}", line))).collect()
+                };
+                let title = format!(" {}: first {} bytes ", tracked.summary.namespace, dump.len());
+                f.render_widget(List::new(lines).block(Block::default().borders(Borders::ALL).title(title)), chunks[1]);
+            }
+
+            // 3. Event log of the selected channel, in the same place
+            if state.events
+                && let Some(tracked) = state.selected.map(|i| &channels.list[i])
+            {
+                let lines: Vec<ListItem> = if tracked.shell.is_none() {
+                    vec![ListItem::new(" Channel could not be mapped").style(Style::default().fg(Color::Red))]
+                } else if events.is_empty() {
+                    vec![ListItem::new(" No events logged")]
+                } else {
+                    tui::event_lines(&events, SystemTime::now()).into_iter().map(|line| ListItem::new(format!(" {}", line))).collect()
+                };
+                let title = format!(" {}: {} events, newest first ", tracked.summary.namespace, events.len());
+                f.render_widget(List::new(lines).block(Block::default().borders(Borders::ALL).title(title)), chunks[1]);
+            }
+
+            let help = " (q: exit, ↑/↓: select, h: hexdump, e: events, Esc: close pane)";
+            f.render_widget(Paragraph::new(help), chunks[chunks.len() - 1]);
+        })?;
+
+        let timeout = next_sample.saturating_duration_since(Instant::now());
+        if event::poll(timeout)?
+            && let Event::Key(key) = event::read()? {
+            if key.code == KeyCode::Char('q') {
+                return Ok(());
+            }
+            // Show the hexdump or events of a new selection without
+            // waiting for the next sample
+            read_dump = state.key(key.code);
+        }
+    }
+}
+
+/// One row of the channel table
+fn cells(tracked: &Tracked, now: Instant) -> Vec<String> {
+    let summary = &tracked.summary;
+    let mut cells = vec![
+        summary.namespace.clone(),
+        String::new(),
+        summary.client_count.to_string(),
+        summary.owner_pid.to_string(),
+        if summary.owner_alive { "live" } else { "dead" }.to_string(),
+    ];
+    match &tracked.sample {
+        Some(sample) => {
+            cells[1] = format_size(sample.data_size as u64);
+            let (age, at_least) = tracked.last_write_age(now);
+            cells.extend([
+                format!("{}{}", sample.generation(), if sample.writing() { "*" } else { "" }),
+                format!("{:.0}", tracked.rates.writes),
+                sample.commands_pending.to_string(),
+                format!("{:.0}", tracked.rates.commands),
+                format!("{}{}", if at_least { ">" } else { "" }, format_age(age)),
+            ]);
+        }
+        None => {
+            cells[1] = format_size(summary.size);
+            cells.extend(["-"; 5].map(String::from));
+        }
+    }
+    cells
+}
+
+fn format_size(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else if bytes >= 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+fn format_age(age: Duration) -> String {
+    match age.as_secs() {
+        0 => format!("{}ms", age.as_millis()),
+        secs @ 1..=119 => format!("{}s", secs),
+        secs @ 120..=7199 => format!("{}m", secs / 60),
+        secs => format!("{}h", secs / 3600),
+    }
+}
@@ -0,0 +1,165 @@
+//! What's selected and shown, apart from the drawing, so that the keys can
+//! be tested without a terminal
+
+use crossterm::event::KeyCode;
+use std::time::SystemTime;
+use venom_memory::LoggedEvent;
+
+pub struct TopState {
+    /// Selected row of the channel table
+    pub selected: Option<usize>,
+    /// Whether the hexdump pane of the selected channel is open
+    pub hexdump: bool,
+    /// Whether the event log pane of the selected channel is open, in
+    /// place of the hexdump
+    pub events: bool,
+    rows: usize,
+}
+
+impl TopState {
+    pub fn new() -> TopState {
+        TopState { selected: None, hexdump: false, events: false, rows: 0 }
+    }
+
+    /// A new listing of `rows` channels; the selection stays on its row
+    /// as far as the table still reaches
+    pub fn set_rows(&mut self, rows: usize) {
+        self.rows = rows;
+        self.selected = match self.selected {
+            _ if rows == 0 => None,
+            Some(i) => Some(i.min(rows - 1)),
+            None => Some(0),
+        };
+        self.hexdump &= self.selected.is_some();
+        self.events &= self.selected.is_some();
+    }
+
+    /// Whether a pane below the table is open
+    pub fn pane_open(&self) -> bool {
+        self.hexdump || self.events
+    }
+
+    /// Handle a key; false if it isn't one of venom-top's
+    pub fn key(&mut self, code: KeyCode) -> bool {
+        match code {
+            KeyCode::Up => self.selected = self.selected.map(|i| i.saturating_sub(1)),
+            KeyCode::Down => self.selected = self.selected.map(|i| (i + 1).min(self.rows.saturating_sub(1))),
+            KeyCode::Home => self.selected = self.selected.map(|_| 0),
+            KeyCode::End => self.selected = self.selected.map(|_| self.rows.saturating_sub(1)),
+            KeyCode::Char('h') | KeyCode::Enter => {
+                self.hexdump = !self.hexdump && self.selected.is_some();
+                self.events = false;
+            }
+            KeyCode::Char('e') => {
+                self.events = !self.events && self.selected.is_some();
+                self.hexdump = false;
+            }
+            KeyCode::Esc => (self.hexdump, self.events) = (false, false),
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// `bytes` as hexdump lines of 16: offset, hex, and the printable ones
+pub fn hexdump(bytes: &[u8]) -> Vec<String> {
+    bytes
+        .chunks(16)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let hex: Vec<String> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+            let text: String = chunk.iter().map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' }).collect();
+            format!("{:08x}  {:<47}  |{}|", i * 16, hex.join(" "), text)
+        })
+        .collect()
+}
+
+/// `events` as lines of the event pane, newest first, each with how long
+/// before `now` it happened
+pub fn event_lines(events: &[LoggedEvent], now: SystemTime) -> Vec<String> {
+    events
+        .iter()
+        .rev()
+        .map(|logged| {
+            let age = now.duration_since(logged.time).unwrap_or_default();
+            format!("{:>9.1}s ago  {}", age.as_secs_f64(), logged.event)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selection() {
+        let mut state = TopState::new();
+        state.set_rows(0);
+        assert!(state.key(KeyCode::Down));
+        assert!(state.key(KeyCode::Char('h')));
+        assert_eq!((state.selected, state.hexdump), (None, false));
+        assert!(!state.key(KeyCode::Char('x')));
+
+        state.set_rows(3);
+        assert_eq!(state.selected, Some(0));
+        state.key(KeyCode::Up);
+        assert_eq!(state.selected, Some(0));
+        for _ in 0..5 {
+            state.key(KeyCode::Down);
+        }
+        assert_eq!(state.selected, Some(2));
+        state.key(KeyCode::Char('h'));
+        assert!(state.hexdump);
+
+        // A channel gone takes the selection up with it; none left closes
+        // the hexdump
+        state.set_rows(2);
+        assert_eq!((state.selected, state.hexdump), (Some(1), true));
+        state.set_rows(0);
+        assert_eq!((state.selected, state.hexdump), (None, false));
+    }
+
+    #[test]
+    fn test_panes() {
+        let mut state = TopState::new();
+        state.set_rows(2);
+        assert!(state.key(KeyCode::Char('e')));
+        assert_eq!((state.hexdump, state.events), (false, true));
+        // One pane at a time
+        state.key(KeyCode::Char('h'));
+        assert_eq!((state.hexdump, state.events), (true, false));
+        state.key(KeyCode::Char('e'));
+        assert_eq!((state.hexdump, state.events), (false, true));
+        state.key(KeyCode::Esc);
+        assert!(!state.pane_open());
+
+        state.key(KeyCode::Char('e'));
+        state.set_rows(0);
+        assert!(!state.pane_open());
+    }
+
+    #[test]
+    fn test_event_lines() {
+        use std::time::Duration;
+        use venom_memory::ChannelEvent;
+
+        let now = SystemTime::now();
+        let events = [
+            LoggedEvent { time: now - Duration::from_millis(2500), event: ChannelEvent::DaemonCreated { pid: 7 } },
+            LoggedEvent { time: now, event: ChannelEvent::ClientDisconnected { client_id: 3 } },
+        ];
+        assert_eq!(
+            event_lines(&events, now),
+            ["      0.0s ago  client 3 disconnected", "      2.5s ago  daemon created the channel (pid 7)"]
+        );
+    }
+
+    #[test]
+    fn test_hexdump() {
+        let lines = hexdump(b"venom\0\x01 memory, 24 bytes");
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], "00000000  76 65 6e 6f 6d 00 01 20 6d 65 6d 6f 72 79 2c 20  |venom.. memory, |");
+        assert_eq!(lines[1], format!("00000010  {:<47}  |24 bytes|", "32 34 20 62 79 74 65 73"));
+        assert!(hexdump(&[]).is_empty());
+    }
+}
@@ -23,7 +23,7 @@ void test_borrowing() {
 void test_heuristic() {
     int *p2 = malloc(20);
     destroy_data(p2);
-    // ⚠️ Should report 50% warning (destroy_data has 'destroy' keyword)
+    // ✅ Should be silent (destroy_data frees it whenever it is not NULL)
 }
 
 void test_annotation() {
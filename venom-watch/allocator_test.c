@@ -0,0 +1,76 @@
+#define _GNU_SOURCE
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+/* Memory from allocators besides malloc: strdup, asprintf's out parameter,
+ * a project's own wrapper (checked with --allocators venom_alloc), realloc */
+
+void *venom_alloc(size_t n);
+
+void dup_freed(const char *s) {
+    char *copy = strdup(s);
+    if (!copy) return;
+    puts(copy);
+    free(copy);
+}
+
+void dup_leaked(const char *s) {
+    char *dup = strdup(s); // ❌ Never freed
+    puts(dup);
+}
+
+int format_freed(int n) {
+    char *msg;
+    if (asprintf(&msg, "%d items", n) < 0)
+        return -1;
+    puts(msg);
+    free(msg);
+    return 0;
+}
+
+int format_leaked(int n) {
+    char *label;
+    if (asprintf(&label, "%d items", n) < 0)
+        return -1;
+    puts(label);
+    return 0; // ❌ label is still allocated
+}
+
+void wrapper_leaked(void) {
+    char *block = venom_alloc(64); // ❌ Only seen with --allocators venom_alloc
+    memset(block, 0, 64);
+}
+
+int grow_safely(size_t n) {
+    char *items = malloc(n);
+    char *grown;
+    if (!items) return -1;
+    grown = realloc(items, 2 * n);
+    if (!grown) {
+        free(items);
+        return -1;
+    }
+    items = grown;
+    free(items);
+    return 0;
+}
+
+int grow_in_place(size_t n) {
+    char *buffer = malloc(n);
+    if (!buffer) return -1;
+    buffer = realloc(buffer, 2 * n); // ❌ The old block is lost if realloc fails
+    if (!buffer) return -1;
+    free(buffer);
+    return 0;
+}
+
+void grow_leaked(size_t n) {
+    char *table = malloc(n);
+    char *bigger = realloc(table, 2 * n);
+    if (!bigger) {
+        free(table);
+        return;
+    }
+    puts(bigger); // ❌ The reallocated block is never freed
+}
@@ -0,0 +1,56 @@
+#include <stdlib.h>
+#include <string.h>
+
+/* Frees on either side of a branch, behind a NULL check, and twice in a row */
+
+void free_either_branch(int flag) {
+    char *either = malloc(16);
+    if (flag) {
+        either[0] = 'a';
+        free(either);      // ✅ Only one of the two runs
+    } else {
+        free(either);
+    }
+}
+
+void free_if_not_null(void) {
+    char *guarded = malloc(16);
+    if (guarded) free(guarded);   // ✅ Frees whenever there's something to free
+}
+
+void free_per_case(int mode) {
+    char *cased = malloc(16);
+    switch (mode) {
+    case 0:
+        free(cased);
+        break;
+    default:
+        cased[0] = 'x';    // ✅ Not after the free on any path
+        free(cased);
+        break;
+    }
+}
+
+void free_renew_free(void) {
+    char *renewed = malloc(16);
+    free(renewed);
+    renewed = malloc(32);  // ✅ A new block
+    renewed[0] = 'x';
+    free(renewed);
+}
+
+void free_twice(void) {
+    char *twice = malloc(16);
+    free(twice);
+    free(twice);           // ❌ Double free
+}
+
+void free_branch_then_again(int flag) {
+    char *again = malloc(16);
+    if (flag) {
+        free(again);
+    } else {
+        free(again);
+    }
+    free(again);           // ❌ Double free on both paths
+}
@@ -0,0 +1,57 @@
+#include <string.h>
+
+#define SIZE 8
+
+int next_index(void);
+void read_index(int *out);
+
+void constant_index(void) {
+    char buf[8];
+    int i = 10;
+    buf[i] = 0;          // ❌ i is 10
+    i = 3;
+    buf[i] = 0;          // ✅ i is 3 now
+}
+
+void index_from_call(void) {
+    char buf[8];
+    int i = 10;
+    i = next_index();
+    buf[i] = 0;          // ✅ Unknown, not reported
+}
+
+void index_from_define(void) {
+    char buf[SIZE];
+    int i;
+    i = SIZE;
+    buf[i] = 0;          // ❌ i is SIZE (8)
+    buf[i - 1] = 0;      // ✅ Last element
+}
+
+void updated_index(void) {
+    char buf[8];
+    int i = 4;
+    i += 1;
+    buf[i] = 0;          // ✅ Dropped after +=, not reported
+    read_index(&i);
+}
+
+void branch_index(int flag) {
+    char buf[8];
+    int i = 2;
+    if (flag) {
+        i = 12;
+    }
+    buf[i] = 0;          // ✅ 2 or 12, not reported
+    int n = 2 * SIZE;
+    memset(buf, 0, n);   // ❌ 16 bytes into 8
+}
+
+void loop_index(void) {
+    char buf[8];
+    int i = 0;
+    while (i < 4) {
+        buf[i] = 0;      // ✅ Changes inside the loop
+        i++;
+    }
+}
@@ -0,0 +1,37 @@
+#include <stdio.h>
+#include <string.h>
+
+#define NAME_LEN 16
+#define PACKET_SIZE 64
+
+void copy_packet(const char *src) {
+    char packet[PACKET_SIZE];
+    char header[32];
+    memcpy(packet, src, PACKET_SIZE);     // ✅ Fits
+    memcpy(header, src, PACKET_SIZE);     // ❌ 64 bytes into 32
+    memmove(header, src, sizeof(header)); // ✅ Fits
+    memmove(header, src, sizeof(packet)); // ❌ 64 bytes into 32
+}
+
+void clear_counters(void) {
+    int counters[8];
+    memset(counters, 0, sizeof(counters));    // ✅ 32 bytes
+    memset(counters, 0, 8 * sizeof(int) + 4); // ❌ 36 bytes into 32
+}
+
+void copy_name(const char *user_input) {
+    char name[NAME_LEN];
+    char greeting[8];
+    strncpy(name, user_input, NAME_LEN - 1); // ✅ Bounded
+    strncpy(name, user_input, 32);           // ❌ Bound larger than the buffer
+    strcpy(name, user_input);                // ⚠️ Unbounded
+    strcpy(greeting, "hello, world");        // ❌ 13 bytes into 8
+    strcat(name, user_input);                // ⚠️ Unbounded
+}
+
+void format_name(const char *user_input) {
+    char line[NAME_LEN];
+    snprintf(line, sizeof(line), "%s", user_input); // ✅ Bounded
+    snprintf(line, 100, "%s", user_input);          // ❌ Bound larger than the buffer
+    sprintf(line, "%s", user_input);                // ⚠️ Unbounded
+}
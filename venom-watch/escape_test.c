@@ -0,0 +1,80 @@
+#include <stdio.h>
+#include <stdlib.h>
+
+/* Allocations that leave the function without being freed: returned,
+ * stored where the caller can reach them, or their address handed out.
+ * Stored into another allocation of the same function, they go wherever
+ * it goes. */
+
+struct node {
+    struct node *next;
+    int value;
+};
+
+struct node *g_list[16];
+static char *g_last;
+
+void hand_over(char **slot);
+
+static void release_buffer(char *buf) {
+    free(buf);
+}
+
+struct node *make_node(int value) {
+    struct node *made = malloc(sizeof *made);
+    if (!made)
+        return NULL;
+    made->value = value;
+    made->next = NULL;
+    return made; // ✅ The caller owns it
+}
+
+void register_node(int i) {
+    struct node *registered = malloc(sizeof *registered);
+    g_list[i] = registered; // ✅ Kept in a global array
+}
+
+void remember(const char *s) {
+    char *last = malloc(64);
+    snprintf(last, 64, "%s", s);
+    g_last = last; // ✅ Kept in a global
+}
+
+void open_out(char **out) {
+    char *opened = malloc(32);
+    *out = opened; // ✅ Through the caller's pointer
+}
+
+void share(void) {
+    char *shared = malloc(8);
+    hand_over(&shared); // ✅ Its address is handed out
+}
+
+struct node *build_pair(void) {
+    struct node *first = malloc(sizeof *first);
+    struct node *second = malloc(sizeof *second);
+    first->next = second;
+    return first; // ✅ second goes with first
+}
+
+void free_pair(void) {
+    struct node *front = malloc(sizeof *front);
+    struct node *back = malloc(sizeof *back);
+    front->next = back;
+    free(front->next);
+    free(front);
+}
+
+void partial_free(void) {
+    struct node *head = malloc(sizeof *head);
+    struct node *tail = malloc(sizeof *tail);
+    head->next = tail;
+    free(head); // ❌ tail is only reachable through head, freed without it
+}
+
+void store_then_release(void) {
+    char *cached = malloc(16);
+    g_last = cached;
+    release_buffer(cached);
+    printf("%s\n", cached); // ❌ Use-After-Free
+}
@@ -0,0 +1,57 @@
+#include <stdlib.h>
+#include <stdio.h>
+#include <string.h>
+
+/* goto_cleanup_test.c with one resource left out of the cleanup on an
+ * error path each */
+
+int load_config(const char *path, size_t n)
+{
+    int ret = -1;
+    char *name;
+    int *table;
+    char *buf;
+
+    name = malloc(strlen(path) + 1);
+    if (!name)
+        goto out;
+    strcpy(name, path);
+
+    table = calloc(n, sizeof(int));
+    if (!table)
+        goto free_name;
+
+    buf = malloc(n);
+    if (!buf)
+        goto free_name; // ❌ Skips free(table)
+
+    if (n > 4096)
+        goto free_buf;
+
+    printf("%s: %d %c\n", name, table[0], buf[0]);
+    ret = 0;
+
+free_buf:
+    free(buf);
+    free(table);
+free_name:
+    free(name);
+out:
+    return ret;
+}
+
+char *read_name(const char *path)
+{
+    char *name = malloc(64);
+    char *copy;
+
+    if (!name)
+        return NULL;
+    copy = malloc(strlen(path) + 1);
+    if (!copy)
+        return NULL; // ❌ name is still allocated
+    strcpy(copy, path);
+    snprintf(name, 64, "%s", copy);
+    free(name);
+    return copy;
+}
@@ -0,0 +1,87 @@
+#include <stdlib.h>
+#include <stdio.h>
+#include <string.h>
+
+/* Kernel-style error handling: each failure jumps to the label that frees
+ * what was allocated before it, and success falls through all of them */
+
+int load_config(const char *path, size_t n)
+{
+    int ret = -1;
+    char *name;
+    int *table;
+    char *buf;
+
+    name = malloc(strlen(path) + 1);
+    if (!name)
+        goto out;
+    strcpy(name, path);
+
+    table = calloc(n, sizeof(int));
+    if (!table)
+        goto free_name;
+
+    buf = malloc(n);
+    if (!buf)
+        goto free_table;
+
+    if (n > 4096)
+        goto free_buf;
+
+    printf("%s: %d %c\n", name, table[0], buf[0]);
+    ret = 0;
+
+free_buf:
+    free(buf);
+free_table:
+    free(table);
+free_name:
+    free(name);
+out:
+    return ret;
+}
+
+/* One label for everything: free(NULL) is fine */
+int single_label(size_t n)
+{
+    int ret = -1;
+    char *a = NULL;
+    char *b = NULL;
+    char *c = NULL;
+
+    a = malloc(n);
+    if (a == NULL)
+        goto cleanup;
+    b = malloc(n);
+    if (!b)
+        goto cleanup;
+    c = malloc(n);
+    if (!c)
+        goto cleanup;
+    ret = 0;
+
+cleanup:
+    free(c);
+    free(b);
+    free(a);
+    return ret;
+}
+
+/* Early returns free what's already there */
+char *read_name(const char *path)
+{
+    char *name = malloc(64);
+    char *copy;
+
+    if (!name)
+        return NULL;
+    copy = malloc(strlen(path) + 1);
+    if (!copy) {
+        free(name);
+        return NULL;
+    }
+    strcpy(copy, path);
+    snprintf(name, 64, "%s", copy);
+    free(name);
+    return copy;
+}
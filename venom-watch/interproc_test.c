@@ -0,0 +1,111 @@
+#include <stdlib.h>
+#include <stdio.h>
+#include <string.h>
+
+/* Allocations handed to helpers defined in this file: what each helper
+ * does with its parameter decides whether the caller leaks */
+
+struct Store {
+    char *items[8];
+    int count;
+};
+
+static struct Store store;
+static char *latest;
+
+static void release_buffer(char *buf) {
+    free(buf);
+}
+
+static void process_and_store(char *buf) {
+    buf[0] = 'x';
+    store.items[store.count++] = buf;
+}
+
+static void keep_latest(char *buf) {
+    latest = buf;
+}
+
+static void print_buffer(const char *buf) {
+    printf("%s\n", buf);
+}
+
+/* Named like a destructor, but only reads */
+static void cleanup_view(char *buf) {
+    printf("%zu\n", strlen(buf));
+}
+
+static void hand_off(char *buf) {
+    process_and_store(buf);
+}
+
+static void drain(char *buf) {
+    release_buffer(buf);
+}
+
+static char *tag(char *buf) {
+    buf[0] = '#';
+    return buf;
+}
+
+static void spin_free(char *buf, int depth) {
+    if (depth > 0) {
+        spin_free(buf, depth - 1);
+    }
+}
+
+void frees_via_helper(void) {
+    char *freed = malloc(16);
+    release_buffer(freed);
+}
+
+void stores_via_helper(void) {
+    char *stored = malloc(16);
+    process_and_store(stored);
+}
+
+void stores_in_global(void) {
+    char *global = malloc(16);
+    keep_latest(global);
+}
+
+void reads_only(void) {
+    char *read = malloc(16);
+    print_buffer(read);
+}
+
+void reads_despite_name(void) {
+    char *misnamed = malloc(16);
+    cleanup_view(misnamed);
+}
+
+void stores_through_two_helpers(void) {
+    char *forwarded = malloc(16);
+    hand_off(forwarded);
+}
+
+void frees_through_two_helpers(void) {
+    char *drained = malloc(16);
+    drain(drained);
+}
+
+void uses_after_helper_free(void) {
+    char *reused = malloc(16);
+    release_buffer(reused);
+    printf("%s\n", reused);
+}
+
+void frees_recursively(void) {
+    char *recursed = malloc(16);
+    spin_free(recursed, 3);
+}
+
+char *returns_tagged(void) {
+    char *returned = malloc(16);
+    return tag(returned);
+}
+
+void drops_tagged(void) {
+    char *returned_dropped = malloc(16);
+    tag(returned_dropped);
+}
@@ -0,0 +1,8 @@
+#include <stdint.h>
+
+#define NAME_LEN 12
+
+typedef struct {
+    uint16_t kind;
+    uint16_t flags;
+} Header;
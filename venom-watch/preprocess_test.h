@@ -0,0 +1,17 @@
+#ifndef PREPROCESS_TEST_H
+#define PREPROCESS_TEST_H
+
+#include "preprocess_sub.h"
+
+#define COUNTER(name) uint32_t name##_count
+
+typedef struct {
+    Header header;
+#ifdef VARIANT_A
+    uint64_t extended;
+#endif
+    COUNTER(packet);
+    char name[NAME_LEN];
+} Packet;
+
+#endif
@@ -0,0 +1,347 @@
+//! Statement-level control flow of a C function body, for the leak check:
+//! which statements can run between an allocation and the function
+//! returning. Follows blocks, `if`/`else`, loops, `switch`, `break`,
+//! `continue`, `return`, `goto` and labels; `exit()` and `abort()` end the
+//! path.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use tree_sitter::Node;
+
+pub const ENTRY: usize = 0;
+const EXIT: usize = 1;
+
+/// Functions that never return to their caller
+const NO_RETURN: &[&str] = &["exit", "_exit", "_Exit", "abort"];
+
+/// The other end of an edge, and the condition that has to come out as
+/// the bool for it to be taken
+type Edge<'a> = (usize, Option<(Node<'a>, bool)>);
+
+struct Block<'a> {
+    /// The statement, or the condition of a branch or loop; `None` for the
+    /// entry, the exit and labels
+    ast: Option<Node<'a>>,
+    next: Vec<Edge<'a>>,
+}
+
+pub struct Cfg<'a> {
+    code: &'a str,
+    blocks: Vec<Block<'a>>,
+    /// Line of the body's closing brace
+    end_line: usize,
+}
+
+/// Where a path leaves the function
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exit {
+    /// A `return` statement, at this line
+    Return(usize),
+    /// Falling off the closing brace at this line
+    End(usize),
+}
+
+impl<'a> Cfg<'a> {
+    pub fn build(body: Node<'a>, code: &'a str) -> Self {
+        let cfg = Cfg { code, blocks: Vec::new(), end_line: body.end_position().row + 1 };
+        let mut builder = Builder { cfg, labels: HashMap::new(), gotos: Vec::new(), breaks: Vec::new(), continues: Vec::new() };
+        builder.block(None, Vec::new());
+        builder.block(None, Vec::new());
+        let open = builder.statement(body, vec![(ENTRY, None)]);
+        builder.link(open, EXIT);
+        // A label the file doesn't define (a macro's) is taken to leave the function
+        for (from, label) in std::mem::take(&mut builder.gotos) {
+            let to = builder.labels.get(label).copied().unwrap_or(EXIT);
+            builder.cfg.blocks[from].next.push((to, None));
+        }
+        builder.cfg
+    }
+
+    /// The innermost statement or condition containing byte `at`
+    pub fn statement_at(&self, at: usize) -> Option<usize> {
+        self.blocks
+            .iter()
+            .enumerate()
+            .filter_map(|(id, block)| block.ast.filter(|n| n.start_byte() <= at && at < n.end_byte()).map(|n| (id, n.end_byte() - n.start_byte())))
+            .min_by_key(|&(_, len)| len)
+            .map(|(id, _)| id)
+    }
+
+    pub fn statements(&self) -> impl Iterator<Item = Node<'a>> + '_ {
+        self.blocks.iter().filter_map(|block| block.ast)
+    }
+
+    /// The first way out of the function found from block `from` that
+    /// doesn't go through a statement `releases` accepts, nor through a
+    /// branch taken only when the pointer, under any of its `names`, is NULL
+    pub fn escape(&self, from: usize, names: &[&str], releases: impl Fn(Node<'a>) -> bool) -> Option<Exit> {
+        let mut seen = HashSet::from([from]);
+        let mut queue = VecDeque::from([from]);
+        while let Some(id) = queue.pop_front() {
+            for &(to, cond) in &self.blocks[id].next {
+                if cond.is_some_and(|(cond, branch)| names.iter().any(|var| null_when(cond, var, branch, self.code))) {
+                    continue;
+                }
+                if to == EXIT {
+                    return Some(match self.blocks[id].ast {
+                        Some(stmt) if stmt.kind() == "return_statement" => Exit::Return(stmt.start_position().row + 1),
+                        _ => Exit::End(self.end_line),
+                    });
+                }
+                if seen.insert(to) && !self.blocks[to].ast.is_some_and(&releases) {
+                    queue.push_back(to);
+                }
+            }
+        }
+        None
+    }
+
+    /// Whether block `to` can run after block `from` without going through
+    /// a statement `stops` accepts
+    pub fn reaches(&self, from: usize, to: usize, stops: impl Fn(Node<'a>) -> bool) -> bool {
+        let mut seen = HashSet::from([from]);
+        let mut queue = VecDeque::from([from]);
+        while let Some(id) = queue.pop_front() {
+            for &(next, _) in &self.blocks[id].next {
+                if next == to {
+                    return true;
+                }
+                if seen.insert(next) && !self.blocks[next].ast.is_some_and(&stops) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        false
+    }
+}
+
+struct Builder<'a> {
+    cfg: Cfg<'a>,
+    labels: HashMap<&'a str, usize>,
+    gotos: Vec<(usize, &'a str)>,
+    /// Edges leaving through `break` or `continue`, one list per
+    /// enclosing loop or `switch`
+    breaks: Vec<Vec<Edge<'a>>>,
+    continues: Vec<Vec<Edge<'a>>>,
+}
+
+impl<'a> Builder<'a> {
+    /// A new block, reached through the open edges `from`
+    fn block(&mut self, ast: Option<Node<'a>>, from: Vec<Edge<'a>>) -> usize {
+        let id = self.cfg.blocks.len();
+        self.cfg.blocks.push(Block { ast, next: Vec::new() });
+        self.link(from, id);
+        id
+    }
+
+    /// Points the open edges `from` (block, condition) at block `to`
+    fn link(&mut self, from: Vec<Edge<'a>>, to: usize) {
+        for (id, cond) in from {
+            self.cfg.blocks[id].next.push((to, cond));
+        }
+    }
+
+    /// Adds `stmt`, reached through the open edges `from`, and returns the
+    /// edges open after it for whatever follows
+    fn statement(&mut self, stmt: Node<'a>, from: Vec<Edge<'a>>) -> Vec<Edge<'a>> {
+        let code = self.cfg.code;
+        let field = |name: &str| stmt.child_by_field_name(name);
+        match stmt.kind() {
+            "compound_statement" => {
+                let mut cursor = stmt.walk();
+                let children: Vec<_> = stmt.named_children(&mut cursor).collect();
+                children.into_iter().fold(from, |open, child| self.statement(child, open))
+            }
+            "labeled_statement" => {
+                let id = self.block(None, from);
+                let label = field("label");
+                if let Some(label) = label {
+                    self.labels.insert(label.utf8_text(code.as_bytes()).unwrap_or(""), id);
+                }
+                let mut cursor = stmt.walk();
+                let inner = stmt.named_children(&mut cursor).filter(|n| Some(n.id()) != label.map(|l| l.id())).last();
+                match inner {
+                    Some(inner) => self.statement(inner, vec![(id, None)]),
+                    None => vec![(id, None)],
+                }
+            }
+            "goto_statement" => {
+                let id = self.block(Some(stmt), from);
+                if let Some(label) = field("label") {
+                    self.gotos.push((id, label.utf8_text(code.as_bytes()).unwrap_or("")));
+                }
+                Vec::new()
+            }
+            "return_statement" => {
+                let id = self.block(Some(stmt), from);
+                self.link(vec![(id, None)], EXIT);
+                Vec::new()
+            }
+            "if_statement" => {
+                let Some(cond) = field("condition") else { return vec![(self.block(Some(stmt), from), None)] };
+                let test = self.block(Some(cond), from);
+                let mut open = match field("consequence") {
+                    Some(then) => self.statement(then, vec![(test, Some((cond, true)))]),
+                    None => Vec::new(),
+                };
+                let otherwise = vec![(test, Some((cond, false)))];
+                open.extend(match field("alternative").and_then(|clause| clause.named_child(0)) {
+                    Some(alternative) => self.statement(alternative, otherwise),
+                    None => otherwise,
+                });
+                open
+            }
+            "while_statement" | "for_statement" => {
+                let mut open = from;
+                if let Some(init) = field("initializer") {
+                    open = vec![(self.block(Some(init), open), None)];
+                }
+                let cond = field("condition");
+                let test = self.block(cond, open);
+                let (body, breaks, continues) = self.loop_body(field("body"), vec![(test, cond.map(|c| (c, true)))]);
+                let mut back = [body, continues].concat();
+                if let Some(update) = field("update") {
+                    back = vec![(self.block(Some(update), back), None)];
+                }
+                self.link(back, test);
+                let mut open = breaks;
+                open.extend(cond.map(|c| (test, Some((c, false)))));
+                open
+            }
+            "do_statement" => {
+                let head = self.block(None, from);
+                let (body, breaks, continues) = self.loop_body(field("body"), vec![(head, None)]);
+                let cond = field("condition");
+                let test = self.block(cond, [body, continues].concat());
+                self.link(vec![(test, cond.map(|c| (c, true)))], head);
+                let mut open = breaks;
+                open.extend(cond.map(|c| (test, Some((c, false)))));
+                open
+            }
+            "switch_statement" => {
+                let test = self.block(field("condition"), from);
+                self.breaks.push(Vec::new());
+                let mut open = Vec::new();
+                let mut default = false;
+                if let Some(body) = field("body") {
+                    let mut cursor = body.walk();
+                    let children: Vec<_> = body.named_children(&mut cursor).collect();
+                    for child in children {
+                        if child.kind() != "case_statement" {
+                            open = self.statement(child, open);
+                            continue;
+                        }
+                        // Entered from the switch, or by falling through the case before
+                        let value = child.child_by_field_name("value");
+                        default |= value.is_none();
+                        open.push((test, None));
+                        open = vec![(self.block(None, open), None)];
+                        let mut case_cursor = child.walk();
+                        let statements: Vec<_> = child.named_children(&mut case_cursor).filter(|s| Some(s.id()) != value.map(|v| v.id())).collect();
+                        for statement in statements {
+                            open = self.statement(statement, open);
+                        }
+                    }
+                }
+                open.extend(self.breaks.pop().unwrap_or_default());
+                if !default {
+                    open.push((test, None));
+                }
+                open
+            }
+            "break_statement" | "continue_statement" => {
+                let id = self.block(Some(stmt), from);
+                let targets = if stmt.kind() == "break_statement" { self.breaks.last_mut() } else { self.continues.last_mut() };
+                match targets {
+                    Some(targets) => {
+                        targets.push((id, None));
+                        Vec::new()
+                    }
+                    None => vec![(id, None)],
+                }
+            }
+            "expression_statement" if ends_process(stmt, code) => {
+                self.block(Some(stmt), from);
+                Vec::new()
+            }
+            _ => vec![(self.block(Some(stmt), from), None)],
+        }
+    }
+
+    /// The edges open after a loop body, and the ones leaving it through
+    /// `break` and `continue`
+    fn loop_body(&mut self, body: Option<Node<'a>>, from: Vec<Edge<'a>>) -> (Vec<Edge<'a>>, Vec<Edge<'a>>, Vec<Edge<'a>>) {
+        self.breaks.push(Vec::new());
+        self.continues.push(Vec::new());
+        let open = match body {
+            Some(body) => self.statement(body, from),
+            None => from,
+        };
+        (open, self.breaks.pop().unwrap_or_default(), self.continues.pop().unwrap_or_default())
+    }
+}
+
+fn ends_process(stmt: Node, code: &str) -> bool {
+    stmt.named_child(0)
+        .filter(|call| call.kind() == "call_expression")
+        .and_then(|call| call.child_by_field_name("function"))
+        .is_some_and(|func| NO_RETURN.contains(&func.utf8_text(code.as_bytes()).unwrap_or("")))
+}
+
+/// Whether `cond` coming out as `branch` means `var` is NULL, so that
+/// there's nothing left to free: the true side of `!p`, `p == NULL`,
+/// `!(p = malloc(n))` or `asprintf(&p, ...) < 0`, the false side of `if (p)`
+fn null_when(cond: Node, var: &str, branch: bool, code: &str) -> bool {
+    let text = |n: Node| n.utf8_text(code.as_bytes()).unwrap_or("");
+    match cond.kind() {
+        "parenthesized_expression" => cond.named_child(0).is_some_and(|inner| null_when(inner, var, branch, code)),
+        "unary_expression" if cond.child_by_field_name("operator").map(text) == Some("!") => {
+            cond.child_by_field_name("argument").is_some_and(|arg| null_when(arg, var, !branch, code))
+        }
+        "binary_expression" => {
+            let (Some(left), Some(right), Some(op)) = (cond.child_by_field_name("left"), cond.child_by_field_name("right"), cond.child_by_field_name("operator")) else {
+                return false;
+            };
+            match text(op) {
+                "&&" => branch && (null_when(left, var, true, code) || null_when(right, var, true, code)),
+                "||" => !branch && (null_when(left, var, false, code) || null_when(right, var, false, code)),
+                op @ ("==" | "!=") => {
+                    let null = |n: Node| matches!(text(n), "NULL" | "0" | "nullptr");
+                    let compared = (names(left, var, code) && null(right)) || (names(right, var, code) && null(left));
+                    let failed = allocates_through(left, var, code) && text(right) == "-1";
+                    (compared || failed) && branch == (op == "==")
+                }
+                op @ ("<" | ">=") => allocates_through(left, var, code) && text(right) == "0" && branch == (op == "<"),
+                _ => false,
+            }
+        }
+        _ => !branch && names(cond, var, code),
+    }
+}
+
+/// Whether the true side of `cond` is taken whenever `var` isn't NULL:
+/// `if (p)`, `if (p != NULL)`, the guard of `if (p) free(p);`
+pub fn guards(cond: Node, var: &str, code: &str) -> bool {
+    null_when(cond, var, false, code)
+}
+
+/// A call given `&var`, whose status says whether it stored anything there
+fn allocates_through(expr: Node, var: &str, code: &str) -> bool {
+    let Some(args) = expr.child_by_field_name("arguments").filter(|_| expr.kind() == "call_expression") else { return false };
+    let mut cursor = args.walk();
+    args.named_children(&mut cursor).any(|arg| {
+        arg.kind() == "pointer_expression"
+            && arg.child_by_field_name("operator").is_some_and(|op| op.kind() == "&")
+            && arg.child_by_field_name("argument").is_some_and(|a| names(a, var, code))
+    })
+}
+
+/// `var` itself, in parentheses, cast, or as the value of an assignment to it
+fn names(expr: Node, var: &str, code: &str) -> bool {
+    match expr.kind() {
+        "identifier" => expr.utf8_text(code.as_bytes()) == Ok(var),
+        "parenthesized_expression" => expr.named_child(0).is_some_and(|inner| names(inner, var, code)),
+        "cast_expression" => expr.child_by_field_name("value").is_some_and(|value| names(value, var, code)),
+        "assignment_expression" => expr.child_by_field_name("left").is_some_and(|left| names(left, var, code)),
+        _ => false,
+    }
+}
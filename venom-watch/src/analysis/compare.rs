@@ -0,0 +1,806 @@
+//! Server and client layouts side by side, as data: each field's row in the
+//! table and what differs about it. `venom-watch` prints these as a table
+//! or JSON; `issues()` words them the way the JSON report always has.
+
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use crate::models::{Decoder, EnumLayout, EnumMember, Field, StructLayout, Target};
+use crate::same_name;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Side {
+    Server,
+    Client,
+}
+
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Side::Server => "Server",
+            Side::Client => "Client",
+        })
+    }
+}
+
+/// What differs about one field. A matched pair gets at most one of the
+/// offset, size, bit and name issues: the first of them that applies.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FieldIssue {
+    OffsetMismatch { server: usize, client: usize },
+    SizeMismatch { server: usize, client: usize },
+    /// `(bit_offset, bit_width)` of each side
+    BitMismatch { server: (Option<u32>, Option<u32>), client: (Option<u32>, Option<u32>) },
+    /// A flexible array member on the `flexible` side, a field of
+    /// `fixed_size` bytes on the other; takes the place of the size mismatch
+    FlexibleArray { flexible: Side, fixed_size: usize },
+    /// Same place, but not the same name in either naming convention
+    NameDiff { client: String },
+    /// A pointer, or a type as wide as one, on targets whose pointers
+    /// differ in width; takes the place of the size mismatch
+    TargetWidth { server: usize, client: usize },
+    /// A multi-byte value, on targets of opposite byte order
+    ByteOrder,
+    /// Either side is a pointer, meaningless in another process
+    PointerDanger,
+    MissingInClient,
+    ExtraInClient,
+}
+
+impl FieldIssue {
+    /// False for the issues a check passes with: a name difference, a pointer
+    pub fn fails(&self) -> bool {
+        !matches!(self, FieldIssue::NameDiff { .. } | FieldIssue::PointerDanger)
+    }
+}
+
+impl fmt::Display for FieldIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            FieldIssue::OffsetMismatch { .. } => "Offset Mismatch",
+            FieldIssue::SizeMismatch { .. } => "Size Mismatch",
+            FieldIssue::BitMismatch { .. } => "Bit Mismatch",
+            FieldIssue::FlexibleArray { flexible, fixed_size } => {
+                let fixed = if *flexible == Side::Server { Side::Client } else { Side::Server };
+                return write!(f, "Flexible array in {}, fixed {} bytes in {}", flexible.to_string().to_lowercase(), fixed_size, fixed.to_string().to_lowercase());
+            }
+            FieldIssue::NameDiff { .. } => "Name Diff",
+            FieldIssue::TargetWidth { .. } => "Target Width Mismatch",
+            FieldIssue::ByteOrder => "Byte Order Mismatch",
+            FieldIssue::PointerDanger => "🚨 POINTER DANGER!",
+            FieldIssue::MissingInClient => "missing in client",
+            FieldIssue::ExtraInClient => "extra in client",
+        })
+    }
+}
+
+/// A field of either side or both, with the line each is declared on
+/// (`Field::line`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldDiff {
+    pub server: Option<Field>,
+    pub client: Option<Field>,
+    pub issues: Vec<FieldIssue>,
+}
+
+impl FieldDiff {
+    /// The server's name for the field, the client's for one only it has
+    pub fn name(&self) -> &str {
+        self.server.as_ref().or(self.client.as_ref()).map_or("", |f| f.name.as_str())
+    }
+
+    pub fn server_line(&self) -> Option<usize> {
+        self.server.as_ref().map(|f| f.line)
+    }
+
+    pub fn client_line(&self) -> Option<usize> {
+        self.client.as_ref().map(|f| f.line)
+    }
+}
+
+/// `Field x: Offset Mismatch`, `Field x missing in client`
+pub(crate) fn field_issue_text(field: &str, issues: &[FieldIssue]) -> String {
+    match issues {
+        [issue @ (FieldIssue::MissingInClient | FieldIssue::ExtraInClient)] => format!("Field {} {}", field, issue),
+        _ => format!("Field {}: {}", field, issues.iter().map(|i| i.to_string()).collect::<Vec<_>>().join(", ")),
+    }
+}
+
+/// One line of the comparison table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Row {
+    Field(Box<FieldDiff>),
+    /// Bytes one side skips before the field named `before`
+    Padding { side: Side, bytes: usize, before: String },
+    /// Bytes after each side's last field; a padding diff when they differ
+    TrailingPadding { server: usize, client: usize },
+}
+
+/// What only a decoder's reads can get wrong
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReadIssue {
+    BigEndian { field: String, offset: usize, line: usize },
+    PastEnd { field: String, start: usize, end: usize, struct_size: usize, line: usize },
+}
+
+impl ReadIssue {
+    pub fn field(&self) -> &str {
+        match self {
+            ReadIssue::BigEndian { field, .. } | ReadIssue::PastEnd { field, .. } => field,
+        }
+    }
+
+    pub fn line(&self) -> usize {
+        match self {
+            ReadIssue::BigEndian { line, .. } | ReadIssue::PastEnd { line, .. } => *line,
+        }
+    }
+}
+
+impl fmt::Display for ReadIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReadIssue::BigEndian { field, offset, line } => write!(f, "Field {}: Big-endian read at offset {} (line {})", field, offset, line),
+            ReadIssue::PastEnd { field, start, end, struct_size, line } => {
+                write!(f, "Field {}: Reads bytes {}..{}, past the end of the {}-byte struct (line {})", field, start, end, struct_size, line)
+            }
+        }
+    }
+}
+
+/// A top-level field both sides declare, at another place in the client's
+/// order: its index among each side's top-level fields
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MovedField {
+    pub name: String,
+    pub server: usize,
+    pub client: usize,
+}
+
+/// A struct compared field by field
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayoutDiff {
+    pub name: String,
+    pub server_size: usize,
+    pub client_size: usize,
+    pub server_packing: Option<usize>,
+    pub client_packing: Option<usize>,
+    /// `StructLayout::wire` of each side
+    pub server_wire: Option<String>,
+    pub client_wire: Option<String>,
+    /// `StructLayout::target` of each side
+    #[serde(default)]
+    pub server_target: Target,
+    #[serde(default)]
+    pub client_target: Target,
+    /// Each side's `StructLayout::warnings`, server first
+    pub warnings: Vec<(Side, String)>,
+    pub reads: Vec<ReadIssue>,
+    /// The fields that moved, in server order, when both sides declare the
+    /// same top-level fields (by name and size) in another order; their
+    /// name differences aren't listed
+    #[serde(default)]
+    pub moved: Vec<MovedField>,
+    /// Top-level fields in offset order, each followed by its nested
+    /// members, with the padding between them
+    pub rows: Vec<Row>,
+    pub server_fields: Vec<Field>,
+    pub client_fields: Vec<Field>,
+}
+
+impl LayoutDiff {
+    /// Same size, every field in the same place; packing and warnings aside
+    pub fn success(&self) -> bool {
+        self.server_size == self.client_size
+            && self.reads.is_empty()
+            && self.moved.is_empty()
+            && self.rows.iter().all(|row| match row {
+                Row::Field(f) => !f.issues.iter().any(FieldIssue::fails),
+                Row::Padding { .. } => true,
+                Row::TrailingPadding { server, client } => server == client,
+            })
+    }
+
+    /// Every difference in the words of the JSON report, padding included
+    /// as `Info:`
+    pub fn issues(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        if self.server_size != self.client_size {
+            issues.push(format!("Size mismatch: Server={} bytes, Client={} bytes", self.server_size, self.client_size));
+        }
+        issues.extend(self.order_issue());
+        // Only agrees by accident (explicit padding fields); the next field edit breaks it
+        if self.server_packing != self.client_packing {
+            issues.push(format!("Packing mismatch: Server is {}, Client is {}", packing_name(self.server_packing), packing_name(self.client_packing)));
+        }
+        if self.server_target != self.client_target {
+            issues.push(format!("Target mismatch: Server is {}, Client is {}", self.server_target, self.client_target));
+        }
+        for (side, warning) in &self.warnings {
+            issues.push(format!("Warning: {}: {}", side, warning));
+        }
+        issues.extend(self.reads.iter().map(|r| r.to_string()));
+        for row in &self.rows {
+            match row {
+                Row::Field(f) if !f.issues.is_empty() => issues.push(field_issue_text(f.name(), &f.issues)),
+                Row::Field(_) => {}
+                Row::Padding { side, bytes, before } => {
+                    issues.push(format!("Info: Internal padding in {} before {} ({} bytes)", side.to_string().to_lowercase(), before, bytes));
+                }
+                Row::TrailingPadding { server, client } if server != client => {
+                    issues.push(format!("Trailing padding mismatch: Server={} bytes, Client={} bytes", server, client));
+                }
+                Row::TrailingPadding { server, .. } => issues.push(format!("Info: Trailing padding detected ({} bytes)", server)),
+            }
+        }
+        issues
+    }
+
+    /// `Field order differs: Server declares a, b; Client declares b, a`,
+    /// the moved fields in each side's order
+    pub fn order_issue(&self) -> Option<String> {
+        if self.moved.is_empty() {
+            return None;
+        }
+        let names = |moved: &[&MovedField]| moved.iter().map(|m| m.name.as_str()).collect::<Vec<_>>().join(", ");
+        let server: Vec<&MovedField> = self.moved.iter().collect();
+        let mut client = server.clone();
+        client.sort_by_key(|m| m.client);
+        Some(format!("Field order differs: Server declares {}; Client declares {}", names(&server), names(&client)))
+    }
+}
+
+pub fn packing_name(packing: Option<usize>) -> String {
+    match packing {
+        None => "naturally aligned".to_string(),
+        Some(1) => "packed".to_string(),
+        Some(n) => format!("packed to {} bytes", n),
+    }
+}
+
+/// The top-level fields of both sides in order, paired up one by one
+/// (renamed fields still pair), nested members by name under their parent
+pub fn compare_struct_layouts(server: &StructLayout, client: &StructLayout) -> LayoutDiff {
+    let targets = (server.target, client.target);
+    let mut rows = Vec::new();
+    let (mut s_idx, mut c_idx) = (0, 0);
+    let (mut s_current_offset, mut c_current_offset) = (0, 0);
+    let s_groups = top_level(&server.fields);
+    let c_groups = top_level(&client.fields);
+    let moved = moved_fields(&s_groups, &c_groups);
+
+    loop {
+        let s_field = s_groups.get(s_idx).map(|g| g.0);
+        let c_field = c_groups.get(c_idx).map(|g| g.0);
+
+        if s_field.is_none() && c_field.is_none() {
+            let s_pad = server.total_size.saturating_sub(s_current_offset);
+            let c_pad = client.total_size.saturating_sub(c_current_offset);
+            if s_pad > 0 || c_pad > 0 {
+                rows.push(Row::TrailingPadding { server: s_pad, client: c_pad });
+            }
+            break;
+        }
+
+        if let Some(s) = s_field
+            && s.offset > s_current_offset {
+            rows.push(Row::Padding { side: Side::Server, bytes: s.offset - s_current_offset, before: s.name.clone() });
+            s_current_offset = s.offset;
+        }
+        if let Some(c) = c_field
+            && c.offset > c_current_offset {
+            rows.push(Row::Padding { side: Side::Client, bytes: c.offset - c_current_offset, before: c.name.clone() });
+            c_current_offset = c.offset;
+        }
+
+        match (s_field, c_field) {
+            (Some(s), Some(c)) => {
+                let mut issues: Vec<FieldIssue> = mismatch(s, c).into_iter().collect();
+                // A field that moved, not one renamed; said once for the struct
+                if issues.is_empty() && !same_name(&s.name, &c.name) && moved.is_empty() {
+                    issues.push(FieldIssue::NameDiff { client: c.name.clone() });
+                }
+                let (s_members, c_members) = (&s_groups[s_idx].1, &c_groups[c_idx].1);
+                if s_members.is_empty() && c_members.is_empty() {
+                    target_issues(s, c, targets, &mut issues);
+                }
+                if s.is_pointer || c.is_pointer {
+                    issues.push(FieldIssue::PointerDanger);
+                }
+                rows.push(Row::Field(Box::new(FieldDiff { server: Some(s.clone()), client: Some(c.clone()), issues })));
+                rows.extend(compare_members(s_members, c_members, targets));
+                s_current_offset = s.offset + s.size;
+                c_current_offset = c.offset + c.size;
+                s_idx += 1;
+                c_idx += 1;
+            }
+            (Some(s), None) => {
+                rows.push(Row::Field(Box::new(FieldDiff { server: Some(s.clone()), client: None, issues: vec![FieldIssue::MissingInClient] })));
+                s_current_offset = s.offset + s.size;
+                s_idx += 1;
+            }
+            (None, Some(c)) => {
+                rows.push(Row::Field(Box::new(FieldDiff { server: None, client: Some(c.clone()), issues: vec![FieldIssue::ExtraInClient] })));
+                c_current_offset = c.offset + c.size;
+                c_idx += 1;
+            }
+            (None, None) => unreachable!(),
+        }
+    }
+
+    let mut warnings = Vec::new();
+    for (side, layout) in [(Side::Server, server), (Side::Client, client)] {
+        warnings.extend(layout.warnings.iter().map(|w| (side, w.clone())));
+    }
+    LayoutDiff {
+        name: server.name.clone(),
+        server_size: server.total_size,
+        client_size: client.total_size,
+        server_packing: server.packing,
+        client_packing: client.packing,
+        server_wire: server.wire.clone(),
+        client_wire: client.wire.clone(),
+        server_target: server.target,
+        client_target: client.target,
+        warnings,
+        reads: Vec::new(),
+        moved,
+        rows,
+        server_fields: server.fields.clone(),
+        client_fields: client.fields.clone(),
+    }
+}
+
+/// A Dart or Python decoder's reads as a client layout, plus what only
+/// reads can get wrong: big-endian reads and reads past the end of the
+/// server struct
+pub fn compare_decoder_layout(server: &StructLayout, decoder: &Decoder) -> LayoutDiff {
+    let mut diff = compare_struct_layouts(server, &decoder.layout);
+    for read in &decoder.reads {
+        if read.big_endian {
+            diff.reads.push(ReadIssue::BigEndian { field: read.field.clone(), offset: read.offset, line: read.line });
+        }
+        if read.end() > server.total_size {
+            diff.reads.push(ReadIssue::PastEnd {
+                field: read.field.clone(),
+                start: read.offset,
+                end: read.end(),
+                struct_size: server.total_size,
+                line: read.line,
+            });
+        }
+    }
+    diff
+}
+
+/// The first of offset, size and bit position that differs, a flexible
+/// array against a fixed one in place of the size
+fn mismatch(s: &Field, c: &Field) -> Option<FieldIssue> {
+    if s.offset != c.offset {
+        Some(FieldIssue::OffsetMismatch { server: s.offset, client: c.offset })
+    } else if s.is_flexible != c.is_flexible {
+        let (flexible, fixed) = if s.is_flexible { (Side::Server, c) } else { (Side::Client, s) };
+        Some(FieldIssue::FlexibleArray { flexible, fixed_size: fixed.size })
+    } else if s.size != c.size {
+        Some(FieldIssue::SizeMismatch { server: s.size, client: c.size })
+    } else if (s.bit_offset, s.bit_width) != (c.bit_offset, c.bit_width) {
+        Some(FieldIssue::BitMismatch { server: (s.bit_offset, s.bit_width), client: (c.bit_offset, c.bit_width) })
+    } else {
+        None
+    }
+}
+
+/// What `targets` (server's, client's) do to a matched pair of scalars: a
+/// pointer-wide type that isn't as wide on both, in place of the size
+/// mismatch it makes, and a multi-byte value in the other byte order
+fn target_issues(s: &Field, c: &Field, (server, client): (Target, Target), issues: &mut Vec<FieldIssue>) {
+    let pointer_wide = |f: &Field| f.is_pointer || Target::pointer_sized(&f.type_name);
+    if server.pointer_size != client.pointer_size && s.size != c.size && (pointer_wide(s) || pointer_wide(c)) {
+        issues.retain(|i| !matches!(i, FieldIssue::SizeMismatch { .. }));
+        issues.push(FieldIssue::TargetWidth { server: s.size, client: c.size });
+    }
+    let multi_byte = |f: &Field| f.size / f.array_len.max(1) > 1;
+    if server.big_endian != client.big_endian && (multi_byte(s) || multi_byte(c)) {
+        issues.push(FieldIssue::ByteOrder);
+    }
+}
+
+/// The top-level fields at another index in the client's order, when each
+/// side's are the other's by name (in either naming convention) and size;
+/// none otherwise. Sizes stand in for types, which languages spell apart.
+fn moved_fields(server: &[(&Field, Vec<&Field>)], client: &[(&Field, Vec<&Field>)]) -> Vec<MovedField> {
+    if server.len() != client.len() {
+        return Vec::new();
+    }
+    let mut taken = vec![false; client.len()];
+    let mut moved = Vec::new();
+    for (s_idx, (s, _)) in server.iter().enumerate() {
+        let found = (0..client.len()).find(|&i| !taken[i] && same_name(&s.name, &client[i].0.name) && s.size == client[i].0.size);
+        let Some(c_idx) = found else {
+            return Vec::new();
+        };
+        taken[c_idx] = true;
+        if c_idx != s_idx {
+            moved.push(MovedField { name: s.name.clone(), server: s_idx, client: c_idx });
+        }
+    }
+    moved
+}
+
+/// Each top-level field with the struct/union members listed after it
+fn top_level(fields: &[Field]) -> Vec<(&Field, Vec<&Field>)> {
+    let mut groups: Vec<(&Field, Vec<&Field>)> = Vec::new();
+    for f in fields {
+        match groups.last_mut() {
+            Some((_, members)) if f.depth > 0 => members.push(f),
+            _ => groups.push((f, Vec::new())),
+        }
+    }
+    groups
+}
+
+/// The members of a nested struct/union matched by name, server order
+/// first, then the ones only the client has
+fn compare_members(server: &[&Field], client: &[&Field], targets: (Target, Target)) -> Vec<Row> {
+    let mut rows = Vec::new();
+    for s in server {
+        let row = match client.iter().find(|c| c.name == s.name) {
+            Some(c) => {
+                let mut issues: Vec<FieldIssue> = mismatch(s, c).into_iter().collect();
+                // Structs and unions in the struct: their members say it
+                let nested = |f: &Field| f.name.ends_with('>') || server.iter().chain(client).any(|m| m.name.starts_with(&format!("{}.", f.name)));
+                if !nested(s) && !nested(c) {
+                    target_issues(s, c, targets, &mut issues);
+                }
+                FieldDiff { server: Some((*s).clone()), client: Some((*c).clone()), issues }
+            }
+            None => FieldDiff { server: Some((*s).clone()), client: None, issues: vec![FieldIssue::MissingInClient] },
+        };
+        rows.push(Row::Field(Box::new(row)));
+    }
+    for c in client.iter().filter(|c| !server.iter().any(|s| s.name == c.name)) {
+        rows.push(Row::Field(Box::new(FieldDiff { server: None, client: Some((*c).clone()), issues: vec![FieldIssue::ExtraInClient] })));
+    }
+    rows
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MemberIssue {
+    ValueMismatch { server: i64, client: i64 },
+    MissingInClient,
+}
+
+/// A server enum member and the client's namesake
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemberDiff {
+    pub server: EnumMember,
+    pub client: Option<EnumMember>,
+    pub issue: Option<MemberIssue>,
+}
+
+/// An enum compared member by member, in the server's order, and by the
+/// width of its values. Members only the client has don't count against it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnumDiff {
+    pub name: String,
+    pub server_members: usize,
+    pub client_members: usize,
+    pub members: Vec<MemberDiff>,
+    /// Bytes each side's values take, when it declares it
+    #[serde(default)]
+    pub server_repr: Option<usize>,
+    #[serde(default)]
+    pub client_repr: Option<usize>,
+}
+
+impl EnumDiff {
+    pub fn success(&self) -> bool {
+        self.members.iter().all(|m| m.issue.is_none()) && !self.repr_mismatch()
+    }
+
+    /// Both sides declare how wide the values are, and not alike
+    pub fn repr_mismatch(&self) -> bool {
+        matches!((self.server_repr, self.client_repr), (Some(server), Some(client)) if server != client)
+    }
+
+    /// Every difference in the words of the JSON report
+    pub fn issues(&self) -> Vec<String> {
+        self.members
+            .iter()
+            .filter_map(|m| match &m.issue {
+                Some(MemberIssue::ValueMismatch { server, client }) => Some(format!("Enum member {} mismatch: Server={}, Client={}", m.server.name, server, client)),
+                Some(MemberIssue::MissingInClient) => Some(format!("Enum member {} missing in client", m.server.name)),
+                None => None,
+            })
+            .chain(self.repr_mismatch().then(|| self.repr_issue()))
+            .collect()
+    }
+
+    fn repr_issue(&self) -> String {
+        format!("Enum width mismatch: Server={} bytes, Client={} bytes", self.server_repr.unwrap_or(0), self.client_repr.unwrap_or(0))
+    }
+}
+
+pub fn compare_enum_layouts(server: &EnumLayout, client: &EnumLayout) -> EnumDiff {
+    let members = server
+        .members
+        .iter()
+        .map(|s| {
+            // By name, else as the other language would spell it
+            let c = client.members.iter().find(|c| c.name == s.name).or_else(|| {
+                let spelled = spellings(&s.name);
+                client.members.iter().find(|c| spelled.contains(&spellings(&c.name)[0]) || spellings(&c.name).contains(&spelled[0]))
+            });
+            let issue = match c {
+                Some(c) if c.value != s.value => Some(MemberIssue::ValueMismatch { server: s.value, client: c.value }),
+                Some(_) => None,
+                None => Some(MemberIssue::MissingInClient),
+            };
+            MemberDiff { server: s.clone(), client: c.cloned(), issue }
+        })
+        .collect();
+    EnumDiff {
+        name: server.name.clone(),
+        server_members: server.members.len(),
+        client_members: client.members.len(),
+        members,
+        server_repr: server.repr,
+        client_repr: client.repr,
+    }
+}
+
+/// A member's name the way either language may spell it: lowercase without
+/// underscores, whole and without each prefix ending in `_`. C's
+/// `CMD_SET_INTERVAL` is also `setinterval`, Rust's `SetInterval`.
+fn spellings(name: &str) -> Vec<String> {
+    let spell = |name: &str| name.replace('_', "").to_lowercase();
+    let mut spellings = vec![spell(name)];
+    spellings.extend(name.match_indices('_').map(|(at, _)| spell(&name[at + 1..])).filter(|s| !s.is_empty()));
+    spellings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::WireRead;
+
+    fn field(name: &str, offset: usize, size: usize, line: usize) -> Field {
+        Field {
+            name: name.to_string(),
+            type_name: "uint32_t".to_string(),
+            size,
+            offset,
+            align: size,
+            is_array: false,
+            array_len: 0,
+            is_flexible: false,
+            line,
+            is_pointer: false,
+            depth: 0,
+            parent: None,
+            bit_offset: None,
+            bit_width: None,
+        }
+    }
+
+    fn layout(fields: Vec<Field>, total_size: usize) -> StructLayout {
+        StructLayout { name: "Sample".to_string(), fields, total_size, file_path: String::new(), packing: None, warnings: Vec::new(), wire: None, target: Target::default() }
+    }
+
+    /// The issues of the field rows, by field name
+    fn field_issues(diff: &LayoutDiff) -> Vec<(&str, &[FieldIssue])> {
+        diff.rows
+            .iter()
+            .filter_map(|r| match r {
+                Row::Field(f) if !f.issues.is_empty() => Some((f.name(), f.issues.as_slice())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn sample() -> Vec<Field> {
+        vec![field("id", 0, 4, 2), field("value", 4, 4, 3), field("count", 8, 4, 4)]
+    }
+
+    #[test]
+    fn test_identical_layouts() {
+        let diff = compare_struct_layouts(&layout(sample(), 12), &layout(sample(), 12));
+        assert!(diff.success());
+        assert!(diff.issues().is_empty());
+        assert_eq!(diff.rows.len(), 3);
+    }
+
+    #[test]
+    fn test_offset_and_size_mismatch() {
+        let mut client = sample();
+        client[1].size = 8;
+        client[2].offset = 12;
+        client[2].line = 9;
+        let diff = compare_struct_layouts(&layout(sample(), 12), &layout(client, 16));
+        assert!(!diff.success());
+        assert_eq!(
+            field_issues(&diff),
+            [
+                ("value", &[FieldIssue::SizeMismatch { server: 4, client: 8 }][..]),
+                ("count", &[FieldIssue::OffsetMismatch { server: 8, client: 12 }][..]),
+            ]
+        );
+        let Row::Field(count) = &diff.rows[2] else { panic!("{:?}", diff.rows[2]) };
+        assert_eq!((count.server_line(), count.client_line()), (Some(4), Some(9)));
+        assert_eq!(diff.issues(), ["Size mismatch: Server=12 bytes, Client=16 bytes", "Field value: Size Mismatch", "Field count: Offset Mismatch"]);
+    }
+
+    #[test]
+    fn test_bit_mismatch() {
+        let flags = |bit| Field { bit_offset: Some(bit), bit_width: Some(3), ..field("flags", 0, 4, 2) };
+        let diff = compare_struct_layouts(&layout(vec![flags(0)], 4), &layout(vec![flags(3)], 4));
+        assert!(!diff.success());
+        assert_eq!(field_issues(&diff), [("flags", &[FieldIssue::BitMismatch { server: (Some(0), Some(3)), client: (Some(3), Some(3)) }][..])]);
+        assert_eq!(diff.issues(), ["Field flags: Bit Mismatch"]);
+    }
+
+    // Renamed and pointer fields are reported without failing the check
+    #[test]
+    fn test_name_diff_and_pointer_danger() {
+        let mut server = sample();
+        server[2].is_pointer = true;
+        let mut client = sample();
+        client[1].name = "amount".to_string();
+        client[2].is_pointer = true;
+        let diff = compare_struct_layouts(&layout(server, 12), &layout(client, 12));
+        assert!(diff.success());
+        assert_eq!(
+            field_issues(&diff),
+            [("value", &[FieldIssue::NameDiff { client: "amount".to_string() }][..]), ("count", &[FieldIssue::PointerDanger][..])]
+        );
+        assert_eq!(diff.issues(), ["Field value: Name Diff", "Field count: 🚨 POINTER DANGER!"]);
+
+        // camelCase for snake_case is the same name
+        let mut client = sample();
+        client[0].name = "ID".to_string();
+        assert!(field_issues(&compare_struct_layouts(&layout(sample(), 12), &layout(client, 12))).is_empty());
+    }
+
+    // Two fields swapped keep every offset: said once, and failing
+    #[test]
+    fn test_field_order() {
+        let mut client = sample();
+        client[1].name = "count".to_string();
+        client[2].name = "value".to_string();
+        let diff = compare_struct_layouts(&layout(sample(), 12), &layout(client, 12));
+        assert!(!diff.success());
+        assert_eq!(diff.moved, [MovedField { name: "value".to_string(), server: 1, client: 2 }, MovedField { name: "count".to_string(), server: 2, client: 1 }]);
+        assert!(field_issues(&diff).is_empty());
+        assert_eq!(diff.issues(), ["Field order differs: Server declares value, count; Client declares count, value"]);
+
+        // A field of another size isn't the same field
+        let mut client = sample();
+        client.swap(1, 2);
+        client[1].size = 2;
+        assert!(compare_struct_layouts(&layout(sample(), 12), &layout(client, 12)).moved.is_empty());
+    }
+
+    #[test]
+    fn test_missing_and_extra_fields() {
+        let diff = compare_struct_layouts(&layout(sample(), 12), &layout(sample()[..2].to_vec(), 8));
+        assert!(!diff.success());
+        let Row::Field(count) = &diff.rows[2] else { panic!("{:?}", diff.rows[2]) };
+        assert_eq!((count.name(), count.issues.as_slice(), count.server_line(), count.client_line()), ("count", &[FieldIssue::MissingInClient][..], Some(4), None));
+        assert!(diff.issues().contains(&"Field count missing in client".to_string()));
+
+        let diff = compare_struct_layouts(&layout(sample()[..2].to_vec(), 8), &layout(sample(), 12));
+        assert_eq!(field_issues(&diff), [("count", &[FieldIssue::ExtraInClient][..])]);
+        assert!(diff.issues().contains(&"Field count extra in client".to_string()));
+    }
+
+    #[test]
+    fn test_padding() {
+        // A byte then a u32: three bytes of padding on the server; the
+        // client packs the u32 right after it and pads the end instead
+        let server = layout(vec![field("flag", 0, 1, 2), field("value", 4, 4, 3)], 8);
+        let client = layout(vec![field("flag", 0, 1, 2), field("value", 1, 4, 3)], 8);
+        let diff = compare_struct_layouts(&server, &client);
+        assert!(!diff.success());
+        assert!(matches!(&diff.rows[1], Row::Padding { side: Side::Server, bytes: 3, before } if before == "value"));
+        assert!(matches!(diff.rows.last(), Some(Row::TrailingPadding { server: 0, client: 3 })));
+        assert_eq!(
+            diff.issues(),
+            [
+                "Info: Internal padding in server before value (3 bytes)",
+                "Field value: Offset Mismatch",
+                "Trailing padding mismatch: Server=0 bytes, Client=3 bytes",
+            ]
+        );
+
+        // The same trailing padding on both sides is only noted
+        let padded = layout(vec![field("value", 0, 4, 2), field("flag", 4, 1, 3)], 8);
+        let diff = compare_struct_layouts(&padded, &padded);
+        assert!(diff.success());
+        assert_eq!(diff.issues(), ["Info: Trailing padding detected (3 bytes)"]);
+    }
+
+    // A flexible array against a fixed one is said as much, not as a size
+    #[test]
+    fn test_flexible_array() {
+        let payload = |size, is_flexible| Field { is_array: true, array_len: size, is_flexible, ..field("payload", 4, size, 3) };
+        let server = layout(vec![field("len", 0, 4, 2), payload(0, true)], 4);
+        let client = layout(vec![field("len", 0, 4, 2), payload(256, false)], 260);
+        let diff = compare_struct_layouts(&server, &client);
+        assert!(!diff.success());
+        assert_eq!(field_issues(&diff), [("payload", &[FieldIssue::FlexibleArray { flexible: Side::Server, fixed_size: 256 }][..])]);
+        assert_eq!(diff.issues(), ["Size mismatch: Server=4 bytes, Client=260 bytes", "Field payload: Flexible array in server, fixed 256 bytes in client"]);
+
+        let diff = compare_struct_layouts(&client, &server);
+        assert_eq!(diff.issues()[1], "Field payload: Flexible array in client, fixed 256 bytes in server");
+        assert!(compare_struct_layouts(&server, &server).success());
+    }
+
+    #[test]
+    fn test_nested_members() {
+        let member = |name: &str, offset| Field { depth: 1, ..field(name, offset, 4, 5) };
+        let server = layout(vec![field("body", 0, 8, 4), member("body.a", 0), member("body.b", 4)], 8);
+        let client = layout(vec![field("body", 0, 8, 4), member("body.b", 0), member("body.c", 4)], 8);
+        let diff = compare_struct_layouts(&server, &client);
+        assert!(!diff.success());
+        assert_eq!(
+            field_issues(&diff),
+            [
+                ("body.a", &[FieldIssue::MissingInClient][..]),
+                ("body.b", &[FieldIssue::OffsetMismatch { server: 4, client: 0 }][..]),
+                ("body.c", &[FieldIssue::ExtraInClient][..]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_packing_and_warnings() {
+        let server = layout(sample(), 12);
+        let client = StructLayout { packing: Some(1), warnings: vec!["Unknown size of COUNT".to_string()], ..layout(sample(), 12) };
+        let diff = compare_struct_layouts(&server, &client);
+        assert!(diff.success());
+        assert_eq!(diff.warnings, [(Side::Client, "Unknown size of COUNT".to_string())]);
+        assert_eq!(diff.issues(), ["Packing mismatch: Server is naturally aligned, Client is packed", "Warning: Client: Unknown size of COUNT"]);
+    }
+
+    #[test]
+    fn test_decoder_reads() {
+        let read = |field: &str, offset, big_endian, line| WireRead { field: field.to_string(), offset, width: 4, count: 1, stride: 4, big_endian, line };
+        let decoder = Decoder {
+            reads: vec![read("id", 0, true, 10), read("value", 4, false, 11), read("count", 10, false, 12)],
+            layout: layout(vec![field("id", 0, 4, 10), field("value", 4, 4, 11), field("count", 10, 4, 12)], 14),
+        };
+        let diff = compare_decoder_layout(&layout(sample(), 12), &decoder);
+        assert!(!diff.success());
+        assert_eq!(
+            diff.reads,
+            [
+                ReadIssue::BigEndian { field: "id".to_string(), offset: 0, line: 10 },
+                ReadIssue::PastEnd { field: "count".to_string(), start: 10, end: 14, struct_size: 12, line: 12 },
+            ]
+        );
+        assert_eq!(diff.reads[1].line(), 12);
+        assert_eq!(
+            diff.issues()[1..3],
+            ["Field id: Big-endian read at offset 0 (line 10)", "Field count: Reads bytes 10..14, past the end of the 12-byte struct (line 12)"]
+        );
+    }
+
+    #[test]
+    fn test_enum_members() {
+        let member = |name: &str, value, line| EnumMember { name: name.to_string(), value, line };
+        let server = EnumLayout { name: "Mode".to_string(), members: vec![member("IDLE", 0, 2), member("RUN", 1, 3), member("STOP", 2, 4)], file_path: String::new(), repr: Some(4) };
+        let client = EnumLayout { name: "Mode".to_string(), members: vec![member("IDLE", 0, 5), member("RUN", 2, 6), member("PAUSE", 3, 7)], file_path: String::new(), repr: Some(4) };
+
+        let diff = compare_enum_layouts(&server, &client);
+        assert!(!diff.success());
+        assert_eq!((diff.server_members, diff.client_members), (3, 3));
+        let issues: Vec<_> = diff.members.iter().map(|m| (m.server.name.as_str(), m.issue.clone())).collect();
+        assert_eq!(
+            issues,
+            [("IDLE", None), ("RUN", Some(MemberIssue::ValueMismatch { server: 1, client: 2 })), ("STOP", Some(MemberIssue::MissingInClient))]
+        );
+        assert_eq!(diff.members[1].client.as_ref().map(|c| c.line), Some(6));
+        assert_eq!(diff.issues(), ["Enum member RUN mismatch: Server=1, Client=2", "Enum member STOP missing in client"]);
+
+        assert!(compare_enum_layouts(&server, &server).success());
+    }
+}
@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+use tree_sitter::Node;
+
+/// Integer constants a file defines, by name, for resolving array dimensions
+pub type Constants = HashMap<String, i64>;
+
+/// `#define NAME <integer expression>`, enum members and C++ `constexpr`
+/// integers, in file order so a constant may be defined in terms of earlier
+/// ones
+pub fn collect_c(code: &str, root_node: Node) -> Constants {
+    let mut constants = Constants::new();
+    walk(root_node, &mut |node| match node.kind() {
+        "preproc_def" => {
+            let (Some(name), Some(value)) = (node.child_by_field_name("name"), node.child_by_field_name("value")) else {
+                return;
+            };
+            let value = value.utf8_text(code.as_bytes()).unwrap_or("");
+            if let Some(v) = eval(value, &constants) {
+                constants.insert(name.utf8_text(code.as_bytes()).unwrap_or("").to_string(), v);
+            }
+        }
+        "enumerator_list" => {
+            let mut next = Some(0);
+            let mut cursor = node.walk();
+            for member in node.children(&mut cursor).filter(|n| n.kind() == "enumerator") {
+                let Some(name) = member.child_by_field_name("name") else { continue };
+                if let Some(value) = member.child_by_field_name("value") {
+                    next = eval(value.utf8_text(code.as_bytes()).unwrap_or(""), &constants);
+                }
+                if let Some(v) = next {
+                    constants.insert(name.utf8_text(code.as_bytes()).unwrap_or("").to_string(), v);
+                }
+                next = next.map(|v| v + 1);
+            }
+        }
+        "declaration" | "field_declaration" if is_constexpr(node, code) => {
+            // `constexpr size_t N = 4;` / `static constexpr int N = 4;`
+            let declarator = node.child_by_field_name("declarator");
+            let (name, value) = match declarator {
+                Some(d) if d.kind() == "init_declarator" => (d.child_by_field_name("declarator"), d.child_by_field_name("value")),
+                d => (d, node.child_by_field_name("default_value")),
+            };
+            let (Some(name), Some(value)) = (name, value) else { return };
+            if let Some(v) = eval(value.utf8_text(code.as_bytes()).unwrap_or(""), &constants) {
+                constants.insert(name.utf8_text(code.as_bytes()).unwrap_or("").to_string(), v);
+            }
+        }
+        _ => {}
+    });
+    constants
+}
+
+/// `const NAME = <expression>` specs, alone or in a `const (...)` block
+pub fn collect_go(code: &str, root_node: Node) -> Constants {
+    let mut constants = Constants::new();
+    walk(root_node, &mut |node| {
+        if node.kind() != "const_spec" {
+            return;
+        }
+        let (Some(name), Some(value)) = (node.child_by_field_name("name"), node.child_by_field_name("value").and_then(|v| v.named_child(0))) else {
+            return;
+        };
+        if let Some(v) = eval(value.utf8_text(code.as_bytes()).unwrap_or(""), &constants) {
+            constants.insert(name.utf8_text(code.as_bytes()).unwrap_or("").to_string(), v);
+        }
+    });
+    constants
+}
+
+/// `const name = <expression>;` and `const name: T = <expression>;`
+pub fn collect_zig(code: &str, root_node: Node) -> Constants {
+    let mut constants = Constants::new();
+    walk(root_node, &mut |node| {
+        let is_const = node.children(&mut node.walk()).any(|n| n.kind() == "const");
+        if node.kind() != "variable_declaration" || !is_const {
+            return;
+        }
+        let mut cursor = node.walk();
+        let named: Vec<Node> = node.named_children(&mut cursor).collect();
+        let (Some(name), Some(value)) = (named.first(), named.last()) else { return };
+        if name.kind() != "identifier" || named.len() < 2 {
+            return;
+        }
+        if let Some(v) = eval(value.utf8_text(code.as_bytes()).unwrap_or(""), &constants) {
+            constants.insert(name.utf8_text(code.as_bytes()).unwrap_or("").to_string(), v);
+        }
+    });
+    constants
+}
+
+fn is_constexpr(node: Node, code: &str) -> bool {
+    let mut cursor = node.walk();
+    node.children(&mut cursor).any(|n| n.kind() == "type_qualifier" && n.utf8_text(code.as_bytes()) == Ok("constexpr"))
+}
+
+/// `const NAME: <integer type> = <expression>;` items, in file order
+pub fn collect_rust(code: &str, root_node: Node) -> Constants {
+    let mut constants = Constants::new();
+    walk(root_node, &mut |node| {
+        if node.kind() != "const_item" {
+            return;
+        }
+        let (Some(name), Some(value)) = (node.child_by_field_name("name"), node.child_by_field_name("value")) else {
+            return;
+        };
+        if let Some(v) = eval(value.utf8_text(code.as_bytes()).unwrap_or(""), &constants) {
+            constants.insert(name.utf8_text(code.as_bytes()).unwrap_or("").to_string(), v);
+        }
+    });
+    constants
+}
+
+fn walk(node: Node, visit: &mut impl FnMut(Node)) {
+    visit(node);
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        walk(child, visit);
+    }
+}
+
+/// Evaluate an integer expression of literals and known constants with
+/// `+ - * / % << >>` and parentheses; `None` if anything is unknown
+pub fn eval(expr: &str, constants: &Constants) -> Option<i64> {
+    let tokens = tokenize(expr)?;
+    let mut pos = 0;
+    let value = shift(&tokens, &mut pos, constants)?;
+    (pos == tokens.len()).then_some(value)
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Num(i64),
+    Ident(String),
+    Op(&'static str),
+}
+
+fn tokenize(expr: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = expr.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Num(number(&chars[start..i].iter().collect::<String>())?));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else {
+            let two: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            let op = match two.as_str() {
+                "<<" => "<<",
+                ">>" => ">>",
+                _ => match c {
+                    '+' => "+",
+                    '-' => "-",
+                    '*' => "*",
+                    '/' => "/",
+                    '%' => "%",
+                    '(' => "(",
+                    ')' => ")",
+                    _ => return None,
+                },
+            };
+            i += op.len();
+            tokens.push(Token::Op(op));
+        }
+    }
+    Some(tokens)
+}
+
+/// Integer literal: decimal or hex, C suffixes (`16u`, `4UL`), Rust
+/// separators and suffixes (`1_024`, `16usize`)
+fn number(text: &str) -> Option<i64> {
+    let text = text.replace('_', "");
+    let (digits, radix) = match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => (hex.to_string(), 16),
+        None => (text.clone(), 10),
+    };
+    let end = digits.find(|c: char| !c.is_digit(radix)).unwrap_or(digits.len());
+    let suffix = &digits[end..];
+    let known = ["", "u", "l", "ul", "lu", "ll", "ull", "llu"].contains(&suffix.to_lowercase().as_str())
+        || ["usize", "isize", "u8", "u16", "u32", "u64", "i8", "i16", "i32", "i64"].contains(&suffix);
+    if !known {
+        return None;
+    }
+    i64::from_str_radix(&digits[..end], radix).ok()
+}
+
+fn shift(tokens: &[Token], pos: &mut usize, constants: &Constants) -> Option<i64> {
+    let mut value = sum(tokens, pos, constants)?;
+    while let Some(Token::Op(op @ ("<<" | ">>"))) = tokens.get(*pos) {
+        *pos += 1;
+        let rhs = u32::try_from(sum(tokens, pos, constants)?).ok()?;
+        value = if *op == "<<" { value.checked_shl(rhs)? } else { value.checked_shr(rhs)? };
+    }
+    Some(value)
+}
+
+fn sum(tokens: &[Token], pos: &mut usize, constants: &Constants) -> Option<i64> {
+    let mut value = product(tokens, pos, constants)?;
+    while let Some(Token::Op(op @ ("+" | "-"))) = tokens.get(*pos) {
+        *pos += 1;
+        let rhs = product(tokens, pos, constants)?;
+        value = if *op == "+" { value.checked_add(rhs)? } else { value.checked_sub(rhs)? };
+    }
+    Some(value)
+}
+
+fn product(tokens: &[Token], pos: &mut usize, constants: &Constants) -> Option<i64> {
+    let mut value = atom(tokens, pos, constants)?;
+    while let Some(Token::Op(op @ ("*" | "/" | "%"))) = tokens.get(*pos) {
+        *pos += 1;
+        let rhs = atom(tokens, pos, constants)?;
+        value = match *op {
+            "*" => value.checked_mul(rhs)?,
+            "/" => value.checked_div(rhs)?,
+            _ => value.checked_rem(rhs)?,
+        };
+    }
+    Some(value)
+}
+
+fn atom(tokens: &[Token], pos: &mut usize, constants: &Constants) -> Option<i64> {
+    let token = tokens.get(*pos)?;
+    *pos += 1;
+    match token {
+        Token::Num(n) => Some(*n),
+        Token::Ident(name) => constants.get(name).copied(),
+        Token::Op("-") => atom(tokens, pos, constants)?.checked_neg(),
+        Token::Op("(") => {
+            let value = shift(tokens, pos, constants)?;
+            (tokens.get(*pos) == Some(&Token::Op(")"))).then(|| {
+                *pos += 1;
+                value
+            })
+        }
+        Token::Op(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eval() {
+        let constants = Constants::from([("MAX_CORES".to_string(), 16), ("SLOTS".to_string(), 3)]);
+        assert_eq!(eval("16", &constants), Some(16));
+        assert_eq!(eval("0x20u", &constants), Some(32));
+        assert_eq!(eval("1_024usize", &constants), Some(1024));
+        assert_eq!(eval("MAX_CORES * 2", &constants), Some(32));
+        assert_eq!(eval("(MAX_CORES + SLOTS) * 2 - 1", &constants), Some(37));
+        assert_eq!(eval("1 << 4", &constants), Some(16));
+        assert_eq!(eval("MAX_CORES / (SLOTS - 3)", &constants), None);
+        assert_eq!(eval("UNKNOWN * 2", &constants), None);
+        assert_eq!(eval("sizeof(int)", &constants), None);
+        assert_eq!(eval("16.0f", &constants), None);
+    }
+}
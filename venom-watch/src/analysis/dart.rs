@@ -0,0 +1,162 @@
+//! Dart decoders: the `fromBytes` factory of `venom_binding.dart`
+//!
+//! The generated Flutter client never maps a struct; it reads each field
+//! from a `ByteData` at a hand-written offset, `data.getFloat32(12, ...)`.
+//! This pass finds the factory, takes the arguments of the constructor it
+//! returns, and records every `get*` read in each of them.
+
+use std::fs;
+use std::path::PathBuf;
+use crate::models::{Decoder, WireRead};
+use super::constants::{self, Constants};
+use super::wire::{self, wire_layout};
+
+const COMMENT: &str = "//";
+
+/// Reads of `class_name.fromBytes` (or of the first `fromBytes` factory in
+/// the file when there's no such class)
+pub fn analyze_dart_decoder(path: &PathBuf, class_name: &str) -> Result<Decoder, String> {
+    let code = fs::read_to_string(path).map_err(|e| format!("Could not read file {}: {}", path.display(), e))?;
+    let constants = dart_constants(&code);
+    let not_found = || format!("No fromBytes factory found in {}", path.display());
+
+    let (class, params) = find_factory(&code, class_name).ok_or_else(not_found)?;
+    let params_end = wire::closing(&code, params, COMMENT).ok_or_else(not_found)?;
+    let body = params_end + code[params_end..].find('{').ok_or_else(not_found)?;
+    let body_end = wire::closing(&code, body, COMMENT).ok_or_else(not_found)?;
+    let call = constructor_call(&code, body, body_end, &class)
+        .ok_or_else(|| format!("{}.fromBytes doesn't return a {}(...) in {}", class, class, path.display()))?;
+    let call_end = wire::closing(&code, call, COMMENT).ok_or_else(not_found)?;
+
+    let mut reads = Vec::new();
+    let mut warnings = Vec::new();
+    for (start, end) in wire::split_top_level(&code, call + 1, call_end, COMMENT) {
+        let Some(colon) = code[start..end].find(':') else { continue };
+        let name = code[start..start + colon].trim();
+        collect_reads(&code, name, start + colon + 1, end, &constants, &mut reads, &mut warnings);
+    }
+
+    let layout = wire_layout(&class, path.to_string_lossy().to_string(), &reads, warnings, format!("{}.fromBytes", class));
+    Ok(Decoder { reads, layout })
+}
+
+/// `const int name = <expression>;` at any level, in file order
+fn dart_constants(code: &str) -> Constants {
+    let mut constants = Constants::new();
+    for line in code.lines() {
+        let line = line.trim();
+        let Some(decl) = line.strip_prefix("static ").unwrap_or(line).strip_prefix("const ") else { continue };
+        let Some((name, value)) = decl.split_once('=') else { continue };
+        let (Some(name), Some(value)) = (name.split_whitespace().last(), value.split(';').next()) else { continue };
+        if let Some(v) = constants::eval(value, &constants) {
+            constants.insert(name.to_string(), v);
+        }
+    }
+    constants
+}
+
+/// Class name and the `(` of `factory Class.fromBytes(`
+fn find_factory(code: &str, class_name: &str) -> Option<(String, usize)> {
+    let mut first = None;
+    let mut search = 0;
+    while let Some(at) = code[search..].find("factory ") {
+        let start = search + at + "factory ".len();
+        search = start;
+        let rest = &code[start..];
+        let Some(dot) = rest.find(".fromBytes") else { break };
+        let class = rest[..dot].trim();
+        if class.is_empty() || !class.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            continue;
+        }
+        let Some(paren) = rest[dot..].find('(').map(|p| start + dot + p) else { continue };
+        if class == class_name {
+            return Some((class.to_string(), paren));
+        }
+        first = first.or(Some((class.to_string(), paren)));
+    }
+    first
+}
+
+/// The `(` of the `Class(...)` call in `code[from..to]`; not `Class.empty()`
+fn constructor_call(code: &str, from: usize, to: usize, class: &str) -> Option<usize> {
+    let body = &code[from..to];
+    let mut search = 0;
+    while let Some(at) = body[search..].find(class) {
+        let start = search + at;
+        search = start + class.len();
+        let before = body[..start].chars().next_back();
+        if before.is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '.') {
+            continue;
+        }
+        if body[search..].starts_with('(') {
+            return Some(from + search);
+        }
+    }
+    None
+}
+
+/// Every `get(Uint|Int|Float)N(offset[, endian])` in `code[from..to]`; in a
+/// `List.generate(count, (i) => ...)` the offset is evaluated for `i` = 0
+/// and 1 for the first element and the stride
+fn collect_reads(code: &str, field: &str, from: usize, to: usize, constants: &Constants, reads: &mut Vec<WireRead>, warnings: &mut Vec<String>) {
+    let mut vars = constants.clone();
+    let mut count = 1;
+    let mut index_var = None;
+    let expr = code[from..to].trim_start();
+    if let Some(rest) = expr.strip_prefix("List.generate(") {
+        let open = to - expr.len() + "List.generate".len();
+        let args = wire::split_top_level(code, open + 1, wire::closing(code, open, COMMENT).unwrap_or(to), COMMENT);
+        if let Some(&(s, e)) = args.first() {
+            count = constants::eval(&code[s..e], constants).and_then(|n| usize::try_from(n).ok()).unwrap_or_else(|| {
+                warnings.push(format!("Could not resolve the length '{}' of field {}; counted as 1 element", code[s..e].trim(), field));
+                1
+            });
+        }
+        index_var = rest.split_once("=>").and_then(|(params, _)| {
+            let params = params.rsplit(',').next()?.trim().trim_start_matches('(').trim_end_matches(')').trim();
+            (!params.is_empty()).then(|| params.to_string())
+        });
+    }
+
+    let mut search = from;
+    while let Some(at) = code[search..to].find(".get") {
+        let start = search + at + ".get".len();
+        search = start;
+        let rest = &code[start..to];
+        let Some(kind) = ["Uint", "Int", "Float"].iter().find(|k| rest.starts_with(**k)) else { continue };
+        let digits: String = rest[kind.len()..].chars().take_while(|c| c.is_ascii_digit()).collect();
+        let open = start + kind.len() + digits.len();
+        let Ok(bits) = digits.parse::<usize>() else { continue };
+        if code.as_bytes().get(open) != Some(&b'(') {
+            continue;
+        }
+        let Some(close) = wire::closing(code, open, COMMENT) else { continue };
+        let args = wire::split_top_level(code, open + 1, close, COMMENT);
+        let Some(&(s, e)) = args.first() else { continue };
+        let offset_expr = code[s..e].trim();
+        let line = code[..start].matches('\n').count() + 1;
+
+        let mut at_index = |i: i64| {
+            if let Some(var) = &index_var {
+                vars.insert(var.clone(), i);
+            }
+            constants::eval(offset_expr, &vars).and_then(|v| usize::try_from(v).ok())
+        };
+        let (Some(offset), second) = (at_index(0), at_index(1)) else {
+            warnings.push(format!("Could not resolve offset '{}' of field {} (line {})", offset_expr, field, line));
+            continue;
+        };
+        let width = bits / 8;
+        let endian = args.get(1).map(|&(s, e)| code[s..e].trim());
+        reads.push(WireRead {
+            field: field.to_string(),
+            offset,
+            width,
+            count,
+            stride: if count > 1 { second.map_or(width, |o| o.saturating_sub(offset)) } else { width },
+            big_endian: width > 1 && endian.is_none_or(|e| e.ends_with("Endian.big")),
+            line,
+        });
+        search = close;
+    }
+}
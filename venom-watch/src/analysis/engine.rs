@@ -1,10 +1,276 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
-use tree_sitter::{Parser as TSParser, Query, QueryCursor};
+use tree_sitter::{Node, Parser as TSParser, Query, QueryCursor};
 use streaming_iterator::StreamingIterator;
-use crate::models::{LeakReport, MemoryEvent, MemoryEventKind};
+use crate::models::{LeakReport, MemoryEvent, MemoryEventKind, Severity};
+use super::cfg::{self, Cfg, Exit, ENTRY};
+
+/// What a function defined in the file does with one of its parameters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ParamUse {
+    /// Frees it on every path: `free(p)`, `@Venom:Owns(p)`, or a helper that does
+    Frees,
+    /// Keeps it: assigned into a struct, array or global, or through a pointer
+    Stores,
+    /// Hands it back as the return value
+    Returns,
+    /// Only reads it
+    Reads,
+    /// Can't tell: recursion, a copy into a local, a call into another file
+    Unknown,
+}
+
+/// C library functions that only read the pointers they're given
+const BORROWING: &[&str] = &[
+    "printf", "fprintf", "sprintf", "snprintf", "puts", "fputs", "fwrite", "fread", "write", "read", "strlen", "strcmp", "strncmp",
+    "strcpy", "strncpy", "strcat", "strncat", "strchr", "strrchr", "strstr", "memcpy", "memmove", "memset", "memcmp", "sscanf", "atoi",
+    "atol", "strtol", "strtoul", "strtod", "strdup", "strndup",
+];
+
+/// Functions whose result, or what they store through a `&p` argument, has
+/// to be freed; `--allocators` adds a project's own
+pub const DEFAULT_ALLOCATORS: &[&str] = &["malloc", "calloc", "realloc", "strdup", "strndup", "asprintf", "vasprintf"];
+
+/// Allocators that return a status and store the memory through their `&p`
+/// argument, even when the status is assigned
+const OUT_PARAM: &[&str] = &["asprintf", "vasprintf"];
+
+/// Words in the name of a function the file doesn't define that suggest it
+/// takes ownership of what it's given
+const OWNING_KEYWORDS: &[&str] = &["free", "destroy", "clean", "delete", "release", "drop", "close"];
+
+/// What a statement does with an allocation, weakest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Release {
+    /// Passes it to a function whose name suggests it takes ownership
+    ByName,
+    /// Stores it, returns it, or hands it to a helper of the file that keeps it
+    Keeps,
+    /// Frees it, or hands it to a helper of the file that does
+    Frees,
+}
+
+/// A call passing a variable as argument `arg`
+struct Call {
+    func: String,
+    line: usize,
+    arg: usize,
+    /// The call's value is assigned, returned or passed on rather than dropped
+    result_used: bool,
+}
+
+/// Where a variable is given memory it has to free
+struct Allocation {
+    var: String,
+    line: usize,
+    /// Byte offset, to find the statement in the function's control flow
+    at: usize,
+}
+
+/// What one function body does with its variables
+#[derive(Default)]
+struct Scan {
+    /// In source order; a variable allocated again, by `realloc` or
+    /// otherwise, has one for each
+    allocations: Vec<Allocation>,
+    /// Line and byte offset of each read; writing a new pointer over it isn't one
+    usages: HashMap<String, Vec<(usize, usize)>>,
+    /// Line, byte offset and kind of the last free or move on every path
+    deaths: HashMap<String, (usize, usize, MemoryEventKind)>,
+    calls: HashMap<String, Vec<Call>>,
+    conditional_frees: HashMap<String, Vec<usize>>,
+    /// Line and byte offset of every free, in branches too
+    frees: HashMap<String, Vec<(usize, usize)>>,
+    findings: Vec<String>,
+    events: Vec<MemoryEvent>,
+}
+
+/// Parameter summaries of the functions the file defines, each worked out
+/// from a scan of its body the first time a caller asks
+struct Summaries<'a> {
+    code: &'a str,
+    allocators: Vec<&'a str>,
+    functions: HashMap<String, (Vec<String>, Node<'a>)>,
+    known: RefCell<HashMap<String, Vec<ParamUse>>>,
+    /// Functions whose summary is being worked out: a call back into one is recursion
+    pending: RefCell<HashSet<String>>,
+}
+
+impl<'a> Summaries<'a> {
+    fn new(root: Node<'a>, code: &'a str, allocators: &'a [String]) -> Self {
+        let mut functions = HashMap::new();
+        collect_functions(root, code, &mut functions);
+        let allocators = DEFAULT_ALLOCATORS.iter().copied().chain(allocators.iter().map(|a| a.as_str())).collect();
+        Summaries { code, allocators, functions, known: RefCell::new(HashMap::new()), pending: RefCell::new(HashSet::new()) }
+    }
+
+    /// What `func` does with its argument `index`; `None` if the file
+    /// doesn't define it
+    fn param(&self, func: &str, index: usize) -> Option<ParamUse> {
+        let (params, body) = self.functions.get(func)?;
+        if let Some(uses) = self.known.borrow().get(func) {
+            return Some(uses.get(index).copied().unwrap_or(ParamUse::Unknown));
+        }
+        if !self.pending.borrow_mut().insert(func.to_string()) {
+            return Some(ParamUse::Unknown);
+        }
+        let scan = scan_body(func, *body, self.code, self);
+        let cfg = Cfg::build(*body, self.code);
+        let locals: HashSet<&str> = params.iter().map(|p| p.as_str()).chain(declared_names(*body, self.code)).collect();
+        let uses: Vec<ParamUse> = params.iter().map(|p| self.param_use(p, &scan, &cfg, *body, &locals)).collect();
+        self.pending.borrow_mut().remove(func);
+        let param = uses.get(index).copied().unwrap_or(ParamUse::Unknown);
+        self.known.borrow_mut().insert(func.to_string(), uses);
+        Some(param)
+    }
+
+    fn param_use(&self, param: &str, scan: &Scan, cfg: &Cfg, body: Node, locals: &HashSet<&str>) -> ParamUse {
+        if param.is_empty() {
+            return ParamUse::Unknown;
+        }
+        if matches!(scan.deaths.get(param), Some((_, _, MemoryEventKind::ExplicitMove))) {
+            return ParamUse::Frees;
+        }
+        // Freed on every path that gets it non-NULL, `if (p) free(p);` included
+        let freed = scan.deaths.contains_key(param) || scan.conditional_frees.contains_key(param);
+        if freed && cfg.escape(ENTRY, &[param], |stmt| self.release(stmt, param, locals, &[]) == Some(Release::Frees)).is_none() {
+            return ParamUse::Frees;
+        }
+        let flow = flows(param, body, self.code, locals);
+        if flow.stored {
+            return ParamUse::Stores;
+        }
+        let mut unknown = flow.aliased || scan.conditional_frees.contains_key(param);
+        for call in scan.calls.get(param).into_iter().flatten() {
+            match self.param(&call.func, call.arg) {
+                Some(ParamUse::Stores) => return ParamUse::Stores,
+                Some(ParamUse::Frees) => return ParamUse::Frees,
+                Some(ParamUse::Reads) => {}
+                Some(ParamUse::Returns) if !call.result_used => {}
+                Some(ParamUse::Returns | ParamUse::Unknown) => unknown = true,
+                None => unknown |= !BORROWING.contains(&call.func.as_str()),
+            }
+        }
+        match (unknown, flow.returned) {
+            (true, _) => ParamUse::Unknown,
+            (false, true) => ParamUse::Returns,
+            (false, false) => ParamUse::Reads,
+        }
+    }
+
+    /// The most a statement (or condition) does with the allocation in
+    /// `var`; storing it into one of `owned`, blocks the function allocated
+    /// itself, isn't handing it on
+    fn release(&self, stmt: Node, var: &str, locals: &HashSet<&str>, owned: &[&str]) -> Option<Release> {
+        let code = self.code;
+        descendants(stmt)
+            .into_iter()
+            .filter_map(|node| {
+                let escaped = self.escape(node, var, locals, owned).map(|_| Release::Keeps);
+                if node.kind() != "call_expression" {
+                    return escaped;
+                }
+                let func = node.child_by_field_name("function").filter(|f| f.kind() == "identifier")?.utf8_text(code.as_bytes()).ok()?;
+                let args = node.child_by_field_name("arguments")?;
+                let mut cursor = args.walk();
+                let positions: Vec<usize> = args.named_children(&mut cursor).filter(|n| n.kind() != "comment").enumerate().filter(|(_, n)| refers_to(Some(*n), var, code)).map(|(i, _)| i).collect();
+                let result_used = node.parent().is_some_and(|p| p.kind() != "expression_statement");
+                positions.into_iter().filter_map(|arg| match self.param(func, arg) {
+                    // `realloc` frees the block it's given, or leaves it to the caller when it fails
+                    _ if func == "free" || (func == "realloc" && arg == 0) => Some(Release::Frees),
+                    Some(ParamUse::Frees) => Some(Release::Frees),
+                    Some(ParamUse::Stores) => Some(Release::Keeps),
+                    Some(ParamUse::Returns) if result_used => Some(Release::Keeps),
+                    Some(ParamUse::Reads | ParamUse::Returns) => None,
+                    None | Some(ParamUse::Unknown) => {
+                        let f_low = func.to_lowercase();
+                        OWNING_KEYWORDS.iter().any(|kw| f_low.contains(kw)).then_some(Release::ByName)
+                    }
+                }).max().max(escaped)
+            })
+            .max()
+    }
+
+    /// How `node` lets the allocation in `var` escape, if it does. Stores into
+    /// `owned` move it into another allocation of the function instead.
+    fn escape(&self, node: Node, var: &str, locals: &HashSet<&str>, owned: &[&str]) -> Option<Escape<'a>> {
+        let code = self.code;
+        let text = |n: Node| n.utf8_text(code.as_bytes()).unwrap_or("");
+        let address_of = |n: Node| n.kind() == "pointer_expression" && n.child_by_field_name("operator").is_some_and(|op| op.kind() == "&") && refers_to(n.child_by_field_name("argument"), var, code);
+        match node.kind() {
+            "return_statement" if refers_to(node.named_child(0), var, code) => Some(Escape::Returned),
+            "assignment_expression" => {
+                let right = node.child_by_field_name("right")?;
+                let left = node.child_by_field_name("left")?;
+                if !refers_to(Some(right), var, code) && !address_of(right) {
+                    return None;
+                }
+                let root = text(lvalue_root(left));
+                let copied = left.kind() == "identifier" && locals.contains(root);
+                (!copied && !owned.contains(&root)).then(|| Escape::Stored(text(left)))
+            }
+            "call_expression" => {
+                let args = node.child_by_field_name("arguments")?;
+                let mut cursor = args.walk();
+                let func = node.child_by_field_name("function").map(text).unwrap_or("");
+                let passed = args.named_children(&mut cursor).any(address_of);
+                (passed && !self.allocators.contains(&func)).then_some(Escape::AddressPassed(func))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// How an allocation leaves the function without being freed
+enum Escape<'a> {
+    Returned,
+    /// Into a field, an array element or a global the caller can reach
+    Stored(&'a str),
+    /// `&p` given to a function
+    AddressPassed(&'a str),
+}
+
+/// The variable an lvalue belongs to: `p` for `p->next`, `p[i]`, `*p`, `p.f`
+fn lvalue_root(mut expr: Node) -> Node {
+    loop {
+        let inner = match expr.kind() {
+            "field_expression" | "subscript_expression" | "pointer_expression" => expr.child_by_field_name("argument"),
+            "parenthesized_expression" => expr.named_child(0),
+            _ => None,
+        };
+        match inner {
+            Some(inner) => expr = inner,
+            None => return expr,
+        }
+    }
+}
+
+/// Whether `stmt` gives `var` a new value: `p = malloc(n)`, `p = NULL`
+fn reassigns(stmt: Node, var: &str, code: &str) -> bool {
+    descendants(stmt).into_iter().any(|node| {
+        node.kind() == "assignment_expression"
+            && node.child_by_field_name("operator").is_some_and(|op| op.kind() == "=")
+            && refers_to(node.child_by_field_name("left"), var, code)
+    })
+}
+
+/// Whether `stmt` calls `free` on exactly `lvalue` (`free(p->next)`)
+fn frees_lvalue(stmt: Node, lvalue: &str, code: &str) -> bool {
+    descendants(stmt).into_iter().any(|node| {
+        node.kind() == "call_expression"
+            && node.child_by_field_name("function").and_then(|f| f.utf8_text(code.as_bytes()).ok()) == Some("free")
+            && node.child_by_field_name("arguments").and_then(|args| unwrapped(args.named_child(0))).and_then(|arg| arg.utf8_text(code.as_bytes()).ok()) == Some(lvalue)
+    })
+}
 
 pub fn check_leaks(path: &PathBuf) -> Result<LeakReport, String> {
+    check_leaks_with(path, &[])
+}
+
+/// `check_leaks`, with `allocators` tracked besides `DEFAULT_ALLOCATORS`
+pub fn check_leaks_with(path: &PathBuf, allocators: &[String]) -> Result<LeakReport, String> {
     let code = fs::read_to_string(path).map_err(|e| format!("Could not read file {}: {}", path.display(), e))?;
     let mut parser = TSParser::new();
     let language = tree_sitter_c::LANGUAGE;
@@ -12,229 +278,615 @@ pub fn check_leaks(path: &PathBuf) -> Result<LeakReport, String> {
 
     let tree = parser.parse(&code, None).expect("Failed to parse code");
     let root_node = tree.root_node();
+    let summaries = Summaries::new(root_node, &code, allocators);
 
     let mut findings = Vec::new();
     let mut events = Vec::new();
-    let owning_keywords = vec!["free", "destroy", "clean", "delete", "release", "drop", "close"];
-
-    let func_query_str = r#"
-        (function_definition
-            declarator: (function_declarator
-                declarator: (identifier) @func_name
-            )
-            body: (compound_statement) @body
-        )
-    "#;
-    let func_query = Query::new(&language.into(), func_query_str).unwrap();
-    let mut func_cursor = QueryCursor::new();
-    let mut func_matches = func_cursor.matches(&func_query, root_node, code.as_bytes());
-
-    while let Some(m) = func_matches.next() {
-        let func_name = m.captures[0].node.utf8_text(code.as_bytes()).unwrap();
-        let body_node = m.captures[1].node;
-
-        let mut allocations = std::collections::HashMap::new();
-        let mut usages = std::collections::HashMap::new();
-        let mut deaths = std::collections::HashMap::new();
-        let mut usage_in_calls = std::collections::HashMap::new();
-        let mut unconditional_frees = std::collections::HashSet::new();
-        let mut conditional_frees = std::collections::HashMap::new();
-
-        let comment_query_str = "(comment) @comment";
-        let comment_query = Query::new(&language.into(), comment_query_str).unwrap();
-        let mut comment_cursor = QueryCursor::new();
-        let mut comment_matches = comment_cursor.matches(&comment_query, body_node, code.as_bytes());
-
-        while let Some(cm) = comment_matches.next() {
-            let comment_text = cm.captures[0].node.utf8_text(code.as_bytes()).unwrap();
-            if comment_text.contains("@Venom:Owns") {
-                if let Some(start) = comment_text.find('(') {
-                    if let Some(end) = comment_text.find(')') {
-                        let var_name = comment_text[start+1..end].trim().to_string();
-                        let line = cm.captures[0].node.start_position().row + 1;
-                        deaths.insert(var_name.clone(), (line, MemoryEventKind::ExplicitMove));
-                        events.push(MemoryEvent {
-                            kind: MemoryEventKind::ExplicitMove,
-                            variable: var_name,
-                            line,
-                            context: format!("Ownership transferred via annotation in {}", func_name),
-                        });
+    // In source order, the ones returning pointers and the ones inside `#ifdef`s too
+    let mut functions: Vec<_> = summaries.functions.iter().collect();
+    functions.sort_by_key(|(_, (_, body))| body.start_byte());
+
+    for (func_name, (params, body_node)) in functions {
+        let body_node = *body_node;
+        let scan = scan_body(func_name, body_node, &code, &summaries);
+        findings.extend(scan.findings);
+        events.extend(scan.events);
+        let Scan { allocations, usages, deaths, calls, .. } = scan;
+        let cfg = Cfg::build(body_node, &code);
+        let locals: HashSet<&str> = params.iter().map(|p| p.as_str()).chain(declared_names(body_node, &code)).collect();
+        let copies = copies(body_node, &code, &locals);
+        let owned: Vec<&str> = allocations.iter().map(|a| a.var.as_str()).collect();
+        let mut checked = HashSet::new();
+
+        for Allocation { var, line: alloc_line, at } in &allocations {
+            let var_calls = calls.get(var).map(Vec::as_slice).unwrap_or_default();
+            if checked.insert(var) {
+                if let Some(&(death_line, death_at, _)) = deaths.get(var)
+                    && let Some(usage_lines) = usages.get(var) {
+                    // Not after it on any path, in another `case` say, or
+                    // only once it's been given a new block
+                    let after = |u_at: usize| match (cfg.statement_at(death_at), cfg.statement_at(u_at)) {
+                        (Some(from), Some(to)) => cfg.reaches(from, to, |stmt| reassigns(stmt, var, &code)),
+                        _ => true,
+                    };
+                    for &(u_line, u_at) in usage_lines {
+                        if u_line > death_line && after(u_at) {
+                            findings.push(format!("CRITICAL: Use-After-Free of '{}' at line {} (freed/moved at line {})", var, u_line, death_line));
+                            events.push(MemoryEvent {
+                                kind: MemoryEventKind::UseAfterFree,
+                                variable: var.clone(),
+                                line: u_line,
+                                context: format!("Accessed variable '{}' after it was freed/moved", var),
+                            });
+                        }
+                    }
+                }
+
+                // A helper in this file that keeps the pointer owns it now
+                for call in var_calls {
+                    let how = match summaries.param(&call.func, call.arg) {
+                        Some(ParamUse::Stores) => "Stored by",
+                        Some(ParamUse::Returns) if call.result_used => "Returned by",
+                        _ => continue,
+                    };
+                    events.push(MemoryEvent {
+                        kind: MemoryEventKind::ExplicitMove,
+                        variable: var.clone(),
+                        line: call.line,
+                        context: format!("{} {} in {}", how, call.func, func_name),
+                    });
+                }
+
+                for node in cfg.statements().flat_map(descendants) {
+                    let context = match summaries.escape(node, var, &locals, &owned) {
+                        Some(Escape::Returned) => format!("Returned from {}", func_name),
+                        Some(Escape::Stored(to)) => format!("Stored in {} in {}", to, func_name),
+                        Some(Escape::AddressPassed(func)) => format!("Address passed to {} in {}", func, func_name),
+                        None => continue,
+                    };
+                    events.push(MemoryEvent {
+                        kind: MemoryEventKind::Escape,
+                        variable: var.clone(),
+                        line: node.start_position().row + 1,
+                        context,
+                    });
+                }
+            }
+
+            if matches!(deaths.get(var), Some((_, _, MemoryEventKind::ExplicitMove))) {
+                continue;
+            }
+
+            // Leaked only if some path from the allocation leaves the
+            // function without freeing or handing it on, under any of the
+            // names it's copied to
+            let names = aliases(var, &copies);
+            // Another allocation it's stored in (`head->next = tail`) takes it
+            // along when it escapes or is handed to a helper, but `free(head)`
+            // alone leaves it behind
+            let holders: Vec<(&str, &str)> = descendants(body_node)
+                .into_iter()
+                .filter(|n| n.kind() == "assignment_expression" && names.iter().any(|name| refers_to(n.child_by_field_name("right"), name, &code)))
+                .filter_map(|n| n.child_by_field_name("left"))
+                .filter_map(|left| Some((lvalue_root(left).utf8_text(code.as_bytes()).ok()?, left.utf8_text(code.as_bytes()).ok()?)))
+                .filter(|(holder, lvalue)| owned.contains(holder) && lvalue != holder)
+                .collect();
+            let Some(start) = cfg.statement_at(*at) else { continue };
+            let released = |stmt: Node| {
+                let own = names.iter().filter_map(|name| summaries.release(stmt, name, &locals, &owned)).max();
+                let held = holders.iter().filter_map(|&(holder, lvalue)| {
+                    if frees_lvalue(stmt, lvalue, &code) {
+                        return Some(Release::Frees);
                     }
+                    summaries.release(stmt, holder, &locals, &owned).filter(|_| !frees_lvalue(stmt, holder, &code))
+                }).max();
+                own.max(held)
+            };
+            let Some(exit) = cfg.escape(start, &names, |stmt| released(stmt) >= Some(Release::Keeps)) else { continue };
+
+            let mut matched_heuristics = Vec::new();
+            for call in var_calls {
+                // The names of helpers whose bodies settle nothing (defined
+                // elsewhere, recursive) are all there is to go on
+                if !matches!(summaries.param(&call.func, call.arg), None | Some(ParamUse::Unknown)) {
+                    continue;
                 }
+                let f_low = call.func.to_lowercase();
+                if OWNING_KEYWORDS.iter().any(|kw| f_low.contains(kw)) {
+                    matched_heuristics.push(call.func.clone());
+                    events.push(MemoryEvent {
+                        kind: MemoryEventKind::PotentialMove,
+                        variable: var.clone(),
+                        line: call.line,
+                        context: format!("Heuristic match: variable passed to {}", call.func),
+                    });
+                }
+            }
+
+            let exit = match exit {
+                Exit::Return(line) => format!("the return at line {}", line),
+                Exit::End(line) => format!("the end of {} at line {}", func_name, line),
+            };
+            if !matched_heuristics.is_empty() && cfg.escape(start, &names, |stmt| released(stmt).is_some()).is_none() {
+                findings.push(format!("⚠️  Warning (50%): variable '{}' (line {}) might have transferred ownership to {}", var, alloc_line, matched_heuristics.join(", ")));
+            } else if let Some((_, lvalue)) = holders.first() {
+                findings.push(format!("Potential leak in {}: variable '{}' (line {}) is stored in {} but not freed itself on the path to {}", func_name, var, alloc_line, lvalue, exit));
+            } else if cfg.statements().any(|stmt| released(stmt).is_some()) {
+                findings.push(format!("Potential leak in {}: variable '{}' (line {}) is not freed on the path to {}", func_name, var, alloc_line, exit));
+            } else if !var_calls.is_empty() {
+                let funcs_only: Vec<_> = var_calls.iter().map(|c| c.func.as_str()).collect();
+                findings.push(format!("Potential leak in {}: variable '{}' (line {}) is passed to {} but never freed; likely a borrow leak", func_name, var, alloc_line, funcs_only.join(", ")));
+            } else {
+                findings.push(format!("Potential leak in {}: variable '{}' allocated at line {} is never freed in the same scope", func_name, var, alloc_line));
             }
         }
+    }
+
+    // Uses after a free and double frees say so; leaks and heuristics may be wrong
+    let severities = findings.iter().map(|f| if f.starts_with("CRITICAL") { Severity::Critical } else { Severity::Warning }).collect();
+    Ok(LeakReport {
+        success: findings.is_empty(),
+        findings,
+        severities,
+        events,
+        file_path: path.to_string_lossy().to_string(),
+    })
+}
+
+/// Allocations, uses, frees and calls of one function body. A call to a
+/// helper of the file that frees its argument counts as a `free`.
+fn scan_body(func_name: &str, body_node: Node, code: &str, summaries: &Summaries) -> Scan {
+    let language = tree_sitter_c::LANGUAGE;
+    let mut scan = Scan::default();
+
+    let comment_query_str = "(comment) @comment";
+    let comment_query = Query::new(&language.into(), comment_query_str).unwrap();
+    let mut comment_cursor = QueryCursor::new();
+    let mut comment_matches = comment_cursor.matches(&comment_query, body_node, code.as_bytes());
 
-        let usage_query_str = "(identifier) @usage";
-        let usage_query = Query::new(&language.into(), usage_query_str).unwrap();
-        let mut usage_cursor = QueryCursor::new();
-        let mut usage_matches = usage_cursor.matches(&usage_query, body_node, code.as_bytes());
-        while let Some(um) = usage_matches.next() {
-            let var_name = um.captures[0].node.utf8_text(code.as_bytes()).unwrap().to_string();
-            let line = um.captures[0].node.start_position().row + 1;
-            usages.entry(var_name).or_insert_with(Vec::new).push(line);
-        }
-
-        let alloc_query_str = r#"
-            (assignment_expression
-                left: [
-                    (identifier) @var
-                    (pointer_declarator declarator: (identifier) @var)
-                ]
-                right: (call_expression
-                    function: (identifier) @func
-                    arguments: (argument_list)
-                    (#match? @func "^(malloc|calloc|realloc)$")
-                )
-            )
-            (init_declarator
-                declarator: [
-                    (identifier) @var
-                    (pointer_declarator declarator: (identifier) @var)
-                ]
-                value: (call_expression
-                    function: (identifier) @func
-                    arguments: (argument_list)
-                    (#match? @func "^(malloc|calloc|realloc)$")
-                )
-            )
-        "#;
-        let alloc_query = Query::new(&language.into(), alloc_query_str).unwrap();
-        let mut alloc_cursor = QueryCursor::new();
-        let mut alloc_matches = alloc_cursor.matches(&alloc_query, body_node, code.as_bytes());
-
-        while let Some(am) = alloc_matches.next() {
-            let var_name = am.captures[0].node.utf8_text(code.as_bytes()).unwrap().to_string();
-            let line = am.captures[0].node.start_position().row + 1;
-            allocations.insert(var_name.clone(), line);
-            events.push(MemoryEvent {
-                kind: MemoryEventKind::Allocation,
+    while let Some(cm) = comment_matches.next() {
+        let comment_text = cm.captures[0].node.utf8_text(code.as_bytes()).unwrap();
+        if comment_text.contains("@Venom:Owns")
+            && let Some(start) = comment_text.find('(')
+            && let Some(end) = comment_text.find(')') {
+            let var_name = comment_text[start+1..end].trim().to_string();
+            let line = cm.captures[0].node.start_position().row + 1;
+            scan.deaths.insert(var_name.clone(), (line, cm.captures[0].node.start_byte(), MemoryEventKind::ExplicitMove));
+            scan.events.push(MemoryEvent {
+                kind: MemoryEventKind::ExplicitMove,
                 variable: var_name,
                 line,
-                context: format!("Allocated in {}", func_name),
+                context: format!("Ownership transferred via annotation in {}", func_name),
             });
         }
+    }
+
+    let cfg = Cfg::build(body_node, code);
+    let usage_query_str = "(identifier) @usage";
+    let usage_query = Query::new(&language.into(), usage_query_str).unwrap();
+    let mut usage_cursor = QueryCursor::new();
+    let mut usage_matches = usage_cursor.matches(&usage_query, body_node, code.as_bytes());
+    while let Some(um) = usage_matches.next() {
+        let usage = um.captures[0].node;
+        let written = usage.parent().is_some_and(|p| {
+            p.kind() == "assignment_expression"
+                && p.child_by_field_name("operator").is_some_and(|op| op.kind() == "=")
+                && p.child_by_field_name("left").is_some_and(|left| left.id() == usage.id())
+        });
+        if written {
+            continue;
+        }
+        let var_name = usage.utf8_text(code.as_bytes()).unwrap().to_string();
+        let line = usage.start_position().row + 1;
+        scan.usages.entry(var_name).or_insert_with(Vec::new).push((line, usage.start_byte()));
+    }
 
-        let call_query_str = r#"
-            (call_expression
+    let alloc_query_str = r#"
+        (assignment_expression
+            left: [
+                (identifier) @var
+                (pointer_declarator declarator: (identifier) @var)
+            ]
+            right: (call_expression
                 function: (identifier) @func
-                arguments: (argument_list (identifier) @var)
+                arguments: (argument_list) @args
             ) @call
-        "#;
-        let call_query = Query::new(&language.into(), call_query_str).unwrap();
-        let mut call_cursor = QueryCursor::new();
-        let mut call_matches = call_cursor.matches(&call_query, body_node, code.as_bytes());
-
-        while let Some(cm) = call_matches.next() {
-            let call_node = cm.captures[0].node;
-            let func_called = cm.captures[1].node.utf8_text(code.as_bytes()).unwrap().to_string();
-            let var_name = cm.captures[2].node.utf8_text(code.as_bytes()).unwrap().to_string();
-            let line = call_node.start_position().row + 1;
-            
-            if func_called == "free" {
-                if let Some((death_line, _)) = deaths.get(&var_name) {
-                    findings.push(format!("CRITICAL: Double Free of '{}' in {} at line {} (previously freed at line {})", var_name, func_name, line, death_line));
-                    events.push(MemoryEvent {
-                        kind: MemoryEventKind::DoubleFree,
-                        variable: var_name.clone(),
-                        line,
-                        context: format!("Variable '{}' freed again!", var_name),
-                    });
-                    continue;
-                }
+        )
+        (init_declarator
+            declarator: [
+                (identifier) @var
+                (pointer_declarator declarator: (identifier) @var)
+            ]
+            value: (call_expression
+                function: (identifier) @func
+                arguments: (argument_list) @args
+            ) @call
+        )
+        (call_expression
+            function: (identifier) @func
+            arguments: (argument_list
+                (pointer_expression operator: "&" argument: (identifier) @var)
+            ) @args
+        ) @call
+    "#;
+    let alloc_query = Query::new(&language.into(), alloc_query_str).unwrap();
+    let capture = |name| alloc_query.capture_index_for_name(name).unwrap();
+    let (var_index, func_index, args_index, call_index) = (capture("var"), capture("func"), capture("args"), capture("call"));
+    let mut alloc_cursor = QueryCursor::new();
+    let mut alloc_matches = alloc_cursor.matches(&alloc_query, body_node, code.as_bytes());
 
-                let mut is_conditional = false;
-                let mut parent = call_node.parent();
-                while let Some(p) = parent {
-                    if p.kind() == "if_statement" {
+    while let Some(am) = alloc_matches.next() {
+        let node = |index| am.captures.iter().find(|c| c.index == index).map(|c| c.node).unwrap();
+        let var_node = node(var_index);
+        let func = node(func_index).utf8_text(code.as_bytes()).unwrap();
+        if !summaries.allocators.contains(&func) {
+            continue;
+        }
+        // `&p` is where the memory goes when the call's value isn't assigned
+        // (a status, or nothing)
+        let call = node(call_index);
+        let assigned = call.parent().is_some_and(|p| matches!(p.kind(), "assignment_expression" | "init_declarator"));
+        if am.pattern_index == 2 && assigned && !OUT_PARAM.contains(&func) {
+            continue;
+        }
+        let var_name = var_node.utf8_text(code.as_bytes()).unwrap().to_string();
+        let line = var_node.start_position().row + 1;
+        let first_arg = node(args_index).named_children(&mut node(args_index).walk()).find(|n| n.kind() != "comment");
+        let context = match func {
+            "malloc" | "calloc" => format!("Allocated in {}", func_name),
+            "realloc" => format!("Reallocated in {}", func_name),
+            _ => format!("Allocated by {} in {}", func, func_name),
+        };
+        if func == "realloc" && am.pattern_index != 2 && refers_to(first_arg, &var_name, code) {
+            scan.findings.push(format!("⚠️  Warning: realloc failure leak of '{}' in {} at line {}: when realloc returns NULL the assignment loses the only pointer to the old block; assign to a temporary first", var_name, func_name, line));
+        }
+        scan.allocations.push(Allocation { var: var_name.clone(), line, at: var_node.start_byte() });
+        scan.events.push(MemoryEvent {
+            kind: MemoryEventKind::Allocation,
+            variable: var_name,
+            line,
+            context,
+        });
+    }
+
+    let call_query_str = r#"
+        (call_expression
+            function: (identifier) @func
+            arguments: (argument_list (identifier) @var)
+        ) @call
+    "#;
+    let call_query = Query::new(&language.into(), call_query_str).unwrap();
+    let mut call_cursor = QueryCursor::new();
+    let mut call_matches = call_cursor.matches(&call_query, body_node, code.as_bytes());
+
+    while let Some(cm) = call_matches.next() {
+        let call_node = cm.captures[0].node;
+        let func_called = cm.captures[1].node.utf8_text(code.as_bytes()).unwrap().to_string();
+        let var_node = cm.captures[2].node;
+        let var_name = var_node.utf8_text(code.as_bytes()).unwrap().to_string();
+        let line = call_node.start_position().row + 1;
+        let arg = var_node.parent().and_then(|args| {
+            let mut cursor = args.walk();
+            args.named_children(&mut cursor).filter(|n| n.kind() != "comment").position(|n| n.id() == var_node.id())
+        });
+        let arg = arg.unwrap_or(0);
+        let by_helper = func_called != "free" && summaries.param(&func_called, arg) == Some(ParamUse::Frees);
+
+        if func_called == "free" || by_helper {
+            let freer = if by_helper { format!(" by {}", func_called) } else { String::new() };
+            // Freed before on a path to this one, without a new block in
+            // between: not when the two are on either side of an `if`
+            let here = cfg.statement_at(call_node.start_byte());
+            let reached = |&&(_, at): &&(usize, usize)| match (cfg.statement_at(at), here) {
+                (Some(from), Some(to)) => cfg.reaches(from, to, |stmt| reassigns(stmt, &var_name, code)),
+                _ => true,
+            };
+            let moved = scan.deaths.get(&var_name).filter(|(_, _, kind)| *kind == MemoryEventKind::ExplicitMove).map(|&(line, _, _)| line);
+            let earlier = moved.or_else(|| scan.frees.get(&var_name).into_iter().flatten().find(reached).map(|&(line, _)| line));
+            if let Some(death_line) = earlier {
+                scan.findings.push(format!("CRITICAL: Double Free of '{}' in {} at line {} (previously freed at line {})", var_name, func_name, line, death_line));
+                scan.events.push(MemoryEvent {
+                    kind: MemoryEventKind::DoubleFree,
+                    variable: var_name.clone(),
+                    line,
+                    context: format!("Variable '{}' freed again!", var_name),
+                });
+                continue;
+            }
+
+            scan.frees.entry(var_name.clone()).or_insert_with(Vec::new).push((line, call_node.start_byte()));
+
+            // `if (p) free(p);` frees it whenever there's something to free
+            let mut is_conditional = false;
+            let mut child = call_node;
+            let mut parent = call_node.parent();
+            while let Some(p) = parent {
+                if p.kind() == "if_statement" {
+                    let guarded = p.child_by_field_name("consequence").is_some_and(|c| c.id() == child.id())
+                        && p.child_by_field_name("condition").is_some_and(|cond| cfg::guards(cond, &var_name, code));
+                    if !guarded {
                         is_conditional = true;
                         break;
                     }
-                    if p.kind() == "compound_statement" && p.parent().map(|pp| pp.kind() == "function_definition").unwrap_or(false) {
-                        break;
-                    }
-                    parent = p.parent();
                 }
-
-                if is_conditional {
-                    conditional_frees.entry(var_name.clone()).or_insert_with(Vec::new).push(line);
-                    events.push(MemoryEvent {
-                        kind: MemoryEventKind::ConditionalFree,
-                        variable: var_name,
-                        line,
-                        context: format!("Freed inside branch in {}", func_name),
-                    });
-                } else {
-                    unconditional_frees.insert(var_name.clone());
-                    deaths.insert(var_name.clone(), (line, MemoryEventKind::Free));
-                    events.push(MemoryEvent {
-                        kind: MemoryEventKind::Free,
-                        variable: var_name,
-                        line,
-                        context: format!("Unconditionally freed in {}", func_name),
-                    });
+                if p.kind() == "compound_statement" && p.parent().map(|pp| pp.kind() == "function_definition").unwrap_or(false) {
+                    break;
                 }
+                child = p;
+                parent = p.parent();
+            }
+
+            if is_conditional {
+                scan.conditional_frees.entry(var_name.clone()).or_insert_with(Vec::new).push(line);
+                scan.events.push(MemoryEvent {
+                    kind: MemoryEventKind::ConditionalFree,
+                    variable: var_name,
+                    line,
+                    context: format!("Freed inside branch{} in {}", freer, func_name),
+                });
             } else {
-                usage_in_calls.entry(var_name).or_insert_with(Vec::new).push((func_called, line));
+                scan.deaths.insert(var_name.clone(), (line, call_node.start_byte(), MemoryEventKind::Free));
+                scan.events.push(MemoryEvent {
+                    kind: MemoryEventKind::Free,
+                    variable: var_name,
+                    line,
+                    context: format!("Unconditionally freed{} in {}", freer, func_name),
+                });
             }
+        } else {
+            let result_used = call_node.parent().is_some_and(|p| p.kind() != "expression_statement");
+            scan.calls.entry(var_name).or_insert_with(Vec::new).push(Call { func: func_called, line, arg, result_used });
         }
+    }
+    scan
+}
 
-        for (var, alloc_line) in allocations {
-            if let Some(&(death_line, _)) = deaths.get(&var) {
-                if let Some(usage_lines) = usages.get(&var) {
-                    for &u_line in usage_lines {
-                        if u_line > death_line {
-                            findings.push(format!("CRITICAL: Use-After-Free of '{}' at line {} (freed/moved at line {})", var, u_line, death_line));
-                            events.push(MemoryEvent {
-                                kind: MemoryEventKind::UseAfterFree,
-                                variable: var.clone(),
-                                line: u_line,
-                                context: format!("Accessed variable '{}' after it was freed/moved", var),
-                            });
-                        }
+/// Name, parameter names (empty for an unnamed one) and body of each
+/// function the file defines, including the ones returning pointers and
+/// the ones inside `#ifdef`s
+fn collect_functions<'a>(node: Node<'a>, code: &str, functions: &mut HashMap<String, (Vec<String>, Node<'a>)>) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        match child.kind() {
+            "function_definition" => {
+                let mut declarator = child.child_by_field_name("declarator");
+                while let Some(d) = declarator.filter(|d| d.kind() != "function_declarator") {
+                    declarator = d.child_by_field_name("declarator");
+                }
+                let (Some(declarator), Some(body)) = (declarator, child.child_by_field_name("body")) else { continue };
+                let Some(name) = declarator.child_by_field_name("declarator").filter(|n| n.kind() == "identifier") else { continue };
+                let mut params = Vec::new();
+                if let Some(list) = declarator.child_by_field_name("parameters") {
+                    let mut list_cursor = list.walk();
+                    for param in list.named_children(&mut list_cursor).filter(|p| p.kind() == "parameter_declaration") {
+                        params.push(param.child_by_field_name("declarator").and_then(|d| declared_name(d, code)).unwrap_or_default().to_string());
                     }
                 }
+                functions.insert(name.utf8_text(code.as_bytes()).unwrap().to_string(), (params, body));
             }
-
-            if unconditional_frees.contains(&var) || deaths.contains_key(&var) {
-                continue;
+            kind if kind.starts_with("preproc_if") || kind == "preproc_else" || kind == "linkage_specification" || kind == "declaration_list" => {
+                collect_functions(child, code, functions);
             }
+            _ => {}
+        }
+    }
+}
 
-            if let Some(free_lines) = conditional_frees.get(&var) {
-                findings.push(format!("⚠️  Warning (70%): variable '{}' (line {}) is only freed conditionally at line(s) {}; potential leak in other paths", var, alloc_line, free_lines.iter().map(|l| l.to_string()).collect::<Vec<_>>().join(", ")));
-                continue;
+/// The identifier a (pointer, array, init) declarator declares
+fn declared_name<'a>(mut declarator: Node, code: &'a str) -> Option<&'a str> {
+    while declarator.kind() != "identifier" {
+        declarator = declarator.child_by_field_name("declarator")?;
+    }
+    declarator.utf8_text(code.as_bytes()).ok()
+}
+
+/// Every variable a body declares, at any depth
+fn declared_names<'a>(body: Node, code: &'a str) -> Vec<&'a str> {
+    descendants(body)
+        .into_iter()
+        .filter(|n| n.kind() == "declaration")
+        .flat_map(|d| {
+            let mut cursor = d.walk();
+            d.children_by_field_name("declarator", &mut cursor).filter_map(|v| declared_name(v, code)).collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn descendants(node: Node) -> Vec<Node> {
+    let mut nodes = Vec::new();
+    let mut stack = vec![node];
+    while let Some(n) = stack.pop() {
+        nodes.push(n);
+        let mut cursor = n.walk();
+        stack.extend(n.named_children(&mut cursor));
+    }
+    nodes
+}
+
+/// Where a parameter's pointer goes besides calls
+#[derive(Default)]
+struct Flow {
+    /// Into a field, an array element, a global or through a pointer
+    stored: bool,
+    /// Copied into a local or another parameter, which aren't followed
+    aliased: bool,
+    returned: bool,
+}
+
+/// `expr` without the casts and parentheses around it
+fn unwrapped(mut expr: Option<Node>) -> Option<Node> {
+    while let Some(e) = expr.filter(|e| matches!(e.kind(), "cast_expression" | "parenthesized_expression")) {
+        expr = e.child_by_field_name("value").or_else(|| e.named_child(0));
+    }
+    expr
+}
+
+/// `var` itself, possibly cast or in parentheses
+fn refers_to(expr: Option<Node>, var: &str, code: &str) -> bool {
+    unwrapped(expr).is_some_and(|e| e.kind() == "identifier" && e.utf8_text(code.as_bytes()) == Ok(var))
+}
+
+/// `(to, from)` for each pointer copied between locals: `q = p`, `char *q = p`
+fn copies<'a>(body: Node, code: &'a str, locals: &HashSet<&str>) -> Vec<(&'a str, &'a str)> {
+    let name = |expr: Option<Node>| unwrapped(expr).filter(|e| e.kind() == "identifier").and_then(|e| e.utf8_text(code.as_bytes()).ok());
+    descendants(body)
+        .into_iter()
+        .filter_map(|node| match node.kind() {
+            "assignment_expression" if node.child_by_field_name("operator").is_some_and(|op| op.kind() == "=") => {
+                Some((name(node.child_by_field_name("left"))?, name(node.child_by_field_name("right"))?))
             }
+            "init_declarator" => Some((declared_name(node.child_by_field_name("declarator")?, code)?, name(node.child_by_field_name("value"))?)),
+            _ => None,
+        })
+        .filter(|(to, _)| locals.contains(to))
+        .collect()
+}
 
-            if let Some(funcs_with_lines) = usage_in_calls.get(&var) {
-                let mut matched_heuristics = Vec::new();
-                for (f, l) in funcs_with_lines {
-                    let f_low = f.to_lowercase();
-                    if owning_keywords.iter().any(|kw| f_low.contains(kw)) {
-                        matched_heuristics.push(f.clone());
-                        events.push(MemoryEvent {
-                            kind: MemoryEventKind::PotentialMove,
-                            variable: var.clone(),
-                            line: *l,
-                            context: format!("Heuristic match: variable passed to {}", f),
-                        });
-                    }
-                }
+/// `var` and every local it's copied to, directly or through another copy
+fn aliases<'a>(var: &'a str, copies: &[(&'a str, &'a str)]) -> Vec<&'a str> {
+    let mut names = vec![var];
+    let mut i = 0;
+    while i < names.len() {
+        for &(to, from) in copies {
+            if from == names[i] && !names.contains(&to) {
+                names.push(to);
+            }
+        }
+        i += 1;
+    }
+    names
+}
 
-                if !matched_heuristics.is_empty() {
-                    findings.push(format!("⚠️  Warning (50%): variable '{}' (line {}) might have transferred ownership to {}", var, alloc_line, matched_heuristics.join(", ")));
-                } else {
-                    let funcs_only: Vec<_> = funcs_with_lines.iter().map(|(f, _)| f.as_str()).collect();
-                    findings.push(format!("Potential leak in {}: variable '{}' (line {}) is passed to {} but never freed; likely a borrow leak", func_name, var, alloc_line, funcs_only.join(", ")));
+fn flows(param: &str, body: Node, code: &str, locals: &HashSet<&str>) -> Flow {
+    let is_param = |expr: Option<Node>| refers_to(expr, param, code);
+    let mut flow = Flow::default();
+    for node in descendants(body) {
+        match node.kind() {
+            "assignment_expression" if is_param(node.child_by_field_name("right")) => {
+                match node.child_by_field_name("left") {
+                    Some(left) if left.kind() == "identifier" && locals.contains(left.utf8_text(code.as_bytes()).unwrap_or("")) => flow.aliased = true,
+                    Some(_) => flow.stored = true,
+                    None => {}
                 }
-            } else {
-                findings.push(format!("Potential leak in {}: variable '{}' allocated at line {} is never freed in the same scope", func_name, var, alloc_line));
             }
+            "init_declarator" if is_param(node.child_by_field_name("value")) => flow.aliased = true,
+            "return_statement" if is_param(node.named_child(0)) => flow.returned = true,
+            _ => {}
         }
     }
+    flow
+}
 
-    Ok(LeakReport {
-        success: findings.is_empty(),
-        findings,
-        events,
-        file_path: path.to_string_lossy().to_string(),
-    })
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::fixture;
+
+    // interproc_test.c passes each allocation to a helper defined in the
+    // same file; only the helpers that merely read it leak
+    #[test]
+    fn test_helper_summaries() {
+        let report = check_leaks(&fixture("interproc_test.c")).unwrap();
+        let about = |var: &str| report.findings.iter().filter(|f| f.contains(&format!("'{}'", var))).collect::<Vec<_>>();
+
+        for var in ["freed", "stored", "global", "forwarded", "drained", "returned"] {
+            assert!(about(var).is_empty(), "{}: {:?}", var, about(var));
+        }
+        for var in ["read", "misnamed"] {
+            let found = about(var);
+            assert!(found.len() == 1 && found[0].starts_with("Potential leak in"), "{}: {:?}", var, found);
+        }
+        // A call to a helper that frees is a free: using the pointer after it isn't
+        assert!(about("reused").iter().any(|f| f.starts_with("CRITICAL: Use-After-Free of 'reused'")), "{:?}", report.findings);
+        // Recursive helpers fall back to the name heuristic
+        assert!(about("recursed").iter().any(|f| f.starts_with("⚠️  Warning (50%)")), "{:?}", report.findings);
+        assert!(about("returned_dropped").iter().any(|f| f.starts_with("Potential leak in")), "{:?}", report.findings);
+
+        let stored = report.events.iter().find(|e| e.variable == "stored" && e.kind == MemoryEventKind::ExplicitMove).unwrap();
+        assert_eq!(stored.context, "Stored by process_and_store in stores_via_helper");
+        let freed = report.events.iter().find(|e| e.variable == "freed" && e.kind == MemoryEventKind::Free).unwrap();
+        assert_eq!(freed.context, "Unconditionally freed by release_buffer in frees_via_helper");
+    }
+
+    // goto_cleanup_test.c unwinds through cleanup labels and frees before
+    // early returns; goto_cleanup_broken.c misses one resource on an error
+    // path in each function
+    #[test]
+    fn test_goto_cleanup() {
+        let report = check_leaks(&fixture("goto_cleanup_test.c")).unwrap();
+        assert!(report.success, "{:?}", report.findings);
+
+        let report = check_leaks(&fixture("goto_cleanup_broken.c")).unwrap();
+        assert_eq!(report.findings, vec![
+            "Potential leak in load_config: variable 'table' (line 20) is not freed on the path to the return at line 40",
+            "Potential leak in read_name: variable 'name' (line 45) is not freed on the path to the return at line 52",
+        ]);
+    }
+
+    // allocator_test.c allocates through strdup, asprintf, realloc and a
+    // wrapper only named on the command line
+    #[test]
+    fn test_allocators() {
+        let report = check_leaks(&fixture("allocator_test.c")).unwrap();
+        let leaked = |report: &LeakReport| report.findings.iter().filter_map(|f| f.split('\'').nth(1)).map(String::from).collect::<Vec<_>>();
+        assert_eq!(leaked(&report), vec!["dup", "label", "buffer", "bigger"], "{:?}", report.findings);
+        assert!(report.findings[2].starts_with("⚠️  Warning: realloc failure leak of 'buffer' in grow_in_place at line 62"), "{:?}", report.findings);
+
+        let report = check_leaks_with(&fixture("allocator_test.c"), &["venom_alloc".to_string()]).unwrap();
+        assert_eq!(leaked(&report), vec!["dup", "label", "block", "buffer", "bigger"], "{:?}", report.findings);
+        let label = report.events.iter().find(|e| e.variable == "label" && e.kind == MemoryEventKind::Allocation).unwrap();
+        assert_eq!(label.context, "Allocated by asprintf in format_leaked");
+    }
+
+    // escape_test.c returns, stores and hands out its allocations; the ones
+    // stored into another allocation share its fate
+    #[test]
+    fn test_escapes() {
+        let report = check_leaks(&fixture("escape_test.c")).unwrap();
+        assert_eq!(report.findings, vec![
+            "Potential leak in partial_free: variable 'tail' (line 70) is stored in head->next but not freed itself on the path to the end of partial_free at line 73",
+            "CRITICAL: Use-After-Free of 'cached' at line 79 (freed/moved at line 78)",
+        ]);
+
+        let escapes: Vec<_> = report.events.iter().filter(|e| e.kind == MemoryEventKind::Escape).map(|e| (e.variable.as_str(), e.context.as_str())).collect();
+        assert_eq!(escapes, vec![
+            ("made", "Returned from make_node"),
+            ("registered", "Stored in g_list[i] in register_node"),
+            ("last", "Stored in g_last in remember"),
+            ("opened", "Stored in *out in open_out"),
+            ("shared", "Address passed to hand_over in share"),
+            ("first", "Returned from build_pair"),
+            ("cached", "Stored in g_last in store_then_release"),
+        ]);
+    }
+
+    // A NULL check isn't a leak path, a branch that doesn't free is
+    #[test]
+    fn test_conditional_paths() {
+        let report = check_leaks(&fixture("leak_test.c")).unwrap();
+        let about = |func: &str| report.findings.iter().filter(|f| f.contains(&format!(" in {}:", func))).collect::<Vec<_>>();
+
+        assert!(about("normal_flow").is_empty(), "{:?}", report.findings);
+        assert_eq!(about("conditional_leak"), vec!["Potential leak in conditional_leak: variable 'buf' (line 24) is not freed on the path to the end of conditional_leak at line 29"]);
+        assert_eq!(about("simple_leak").len(), 1, "{:?}", report.findings);
+    }
+
+    // branch_free_test.c frees on either side of an `if`, in separate
+    // `case`s, behind a NULL check and after a new block; only the frees
+    // that follow another on some path are double
+    #[test]
+    fn test_exclusive_frees() {
+        let report = check_leaks(&fixture("branch_free_test.c")).unwrap();
+        let doubles: Vec<_> = report.findings.iter().filter(|f| f.starts_with("CRITICAL: Double Free")).collect();
+        assert_eq!(doubles, vec![
+            "CRITICAL: Double Free of 'twice' in free_twice at line 45 (previously freed at line 44)",
+            "CRITICAL: Double Free of 'again' in free_branch_then_again at line 55 (previously freed at line 51)",
+        ]);
+        for var in ["either", "guarded", "cased", "renewed"] {
+            assert!(!report.findings.iter().any(|f| f.contains(&format!("'{}'", var))), "{}: {:?}", var, report.findings);
+        }
+
+        let guarded = report.events.iter().find(|e| e.variable == "guarded" && e.line == 18).unwrap();
+        assert_eq!(guarded.kind, MemoryEventKind::Free);
+    }
 }
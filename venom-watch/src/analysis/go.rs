@@ -0,0 +1,293 @@
+//! Go structs, as `venom init --lang go` generates them
+//!
+//! The in-memory layout is the one gc gives `type X struct {...}` on amd64.
+//! The generated client never maps its struct onto the channel, though: it
+//! copies fields in and out with `binary.LittleEndian` at explicit offsets.
+//! When the file has such functions for the struct (`ToBytes`,
+//! `StateFromBytes`), the offsets they use are the layout that has to match
+//! the C header, so that wire layout is reported instead.
+
+use std::collections::HashMap;
+use tree_sitter::Node;
+use crate::models::{Field, StructLayout, Target};
+use super::constants::{self, Constants};
+use super::layout::{array_len, unresolved_size};
+
+struct GoContext<'a> {
+    code: &'a str,
+    /// Type declarations of the file by name (`type X <type>`)
+    types: HashMap<&'a str, Node<'a>>,
+    constants: Constants,
+    warnings: Vec<String>,
+    target: Target,
+}
+
+pub fn analyze_go_struct(struct_name: &str, code: &str, root_node: Node, file_path: String, target: Target) -> Result<StructLayout, String> {
+    let mut ctx = GoContext {
+        code,
+        types: type_declarations(code, root_node),
+        constants: constants::collect_go(code, root_node),
+        warnings: Vec::new(),
+        target,
+    };
+    let body = ctx
+        .types
+        .get(struct_name)
+        .filter(|t| t.kind() == "struct_type")
+        .and_then(|t| t.named_child(0))
+        .ok_or_else(|| format!("Go struct '{}' not found", struct_name))?;
+    let (fields, size, _) = layout_fields(body, &mut ctx, 0);
+
+    let mut layout = StructLayout {
+        name: struct_name.to_string(),
+        fields,
+        total_size: size,
+        file_path,
+        packing: None,
+        warnings: Vec::new(),
+        wire: None,
+        target,
+    };
+    apply_wire_layout(&mut layout, code, root_node, &mut ctx);
+    layout.warnings = ctx.warnings;
+    Ok(layout)
+}
+
+fn type_declarations<'a>(code: &'a str, root_node: Node<'a>) -> HashMap<&'a str, Node<'a>> {
+    let mut types = HashMap::new();
+    let mut cursor = root_node.walk();
+    for decl in root_node.children(&mut cursor).filter(|n| n.kind() == "type_declaration") {
+        let mut decl_cursor = decl.walk();
+        for spec in decl.children(&mut decl_cursor).filter(|n| n.kind() == "type_spec") {
+            if let (Some(name), Some(ty)) = (spec.child_by_field_name("name"), spec.child_by_field_name("type")) {
+                types.insert(name.utf8_text(code.as_bytes()).unwrap(), ty);
+            }
+        }
+    }
+    types
+}
+
+/// Fields of a `field_declaration_list`, naturally aligned; size and
+/// alignment of the struct. Embedded fields are named after their type.
+fn layout_fields(list: Node, ctx: &mut GoContext, nesting: usize) -> (Vec<Field>, usize, usize) {
+    let code = ctx.code;
+    let mut fields = Vec::new();
+    let mut offset = 0usize;
+    let mut max_align = 1;
+
+    let mut cursor = list.walk();
+    for decl in list.children(&mut cursor).filter(|n| n.kind() == "field_declaration") {
+        let Some(type_node) = decl.child_by_field_name("type") else { continue };
+        let type_text = type_node.utf8_text(code.as_bytes()).unwrap();
+        let mut name_cursor = decl.walk();
+        let mut names: Vec<&str> = decl.children_by_field_name("name", &mut name_cursor).map(|n| n.utf8_text(code.as_bytes()).unwrap()).collect();
+        if names.is_empty() {
+            names.push(type_text.trim_start_matches('*').rsplit('.').next().unwrap_or(type_text));
+        }
+        for name in names {
+            let line = decl.start_position().row + 1;
+            let (size, align, array) = type_info(type_node, name, line, ctx, nesting);
+            offset = offset.next_multiple_of(align);
+            max_align = max_align.max(align);
+            fields.push(Field {
+                name: name.to_string(),
+                type_name: type_text.to_string(),
+                size,
+                offset,
+                align,
+                is_array: array.is_some(),
+                array_len: array.unwrap_or(1),
+                is_flexible: false,
+                line,
+                is_pointer: matches!(type_node.kind(), "pointer_type" | "slice_type" | "map_type" | "channel_type" | "function_type" | "interface_type")
+                    || matches!(type_text, "string" | "uintptr"),
+                depth: 0,
+                parent: None,
+                bit_offset: None,
+                bit_width: None,
+            });
+            offset += size;
+        }
+    }
+    (fields, offset.next_multiple_of(max_align), max_align)
+}
+
+/// Size, alignment and (for an array) length of a Go type on the target
+/// (amd64 unless told otherwise)
+fn type_info(type_node: Node, field: &str, line: usize, ctx: &mut GoContext, nesting: usize) -> (usize, usize, Option<usize>) {
+    let code = ctx.code;
+    let word = ctx.target.pointer_size;
+    let text = type_node.utf8_text(code.as_bytes()).unwrap();
+    let (size, align) = match type_node.kind() {
+        "array_type" => {
+            let length = type_node.child_by_field_name("length").map(|n| n.utf8_text(code.as_bytes()).unwrap()).unwrap_or("");
+            let len = array_len(length, &ctx.constants).unwrap_or_else(|| {
+                ctx.warnings.push(unresolved_size(field, length, line));
+                1
+            });
+            let (size, align, _) = match type_node.child_by_field_name("element") {
+                Some(element) => type_info(element, field, line, ctx, nesting),
+                None => (1, 1, None),
+            };
+            return (size * len, align, Some(len));
+        }
+        "struct_type" if nesting < 8 => match type_node.named_child(0) {
+            Some(body) => {
+                let (_, size, align) = layout_fields(body, ctx, nesting + 1);
+                (size, align)
+            }
+            None => (0, 1),
+        },
+        "pointer_type" | "map_type" | "channel_type" | "function_type" => (word, word),
+        "slice_type" => (3 * word, word),
+        "interface_type" => (2 * word, word),
+        _ => match text {
+            "int8" | "uint8" | "byte" | "bool" => (1, 1),
+            "int16" | "uint16" => (2, 2),
+            "int32" | "uint32" | "rune" | "float32" => (4, 4),
+            // 64-bit values are only 4-aligned on 32-bit targets
+            "int64" | "uint64" | "float64" => (8, word),
+            "int" | "uint" | "uintptr" => (word, word),
+            "complex64" => (8, 4),
+            "complex128" => (16, word),
+            "string" | "any" => (2 * word, word),
+            _ => match ctx.types.get(text).copied() {
+                // `type Kind uint8`, `type Header struct {...}`
+                Some(declared) if nesting < 8 => {
+                    let (size, align, _) = type_info(declared, field, line, ctx, nesting + 1);
+                    (size, align)
+                }
+                _ => {
+                    ctx.warnings.push(format!("Unknown type '{}' of field {} (line {}); counted as 8 bytes", text, field, line));
+                    (8, 8)
+                }
+            },
+        },
+    };
+    (size, align, None)
+}
+
+/// Replace the in-memory offsets with the ones the struct's serializers use,
+/// when the file has any: functions and methods with `Bytes` in their name
+/// that take or return the struct. A decoder (`...FromBytes`) is preferred;
+/// the others are checked against it.
+fn apply_wire_layout(layout: &mut StructLayout, code: &str, root_node: Node, ctx: &mut GoContext) {
+    let names: Vec<&str> = layout.fields.iter().map(|f| f.name.as_str()).collect();
+    let mut serializers: Vec<(String, Vec<(String, usize)>)> = Vec::new();
+    let mut cursor = root_node.walk();
+    for func in root_node.children(&mut cursor) {
+        if !matches!(func.kind(), "function_declaration" | "method_declaration") {
+            continue;
+        }
+        let (Some(name), Some(body)) = (func.child_by_field_name("name"), func.child_by_field_name("body")) else { continue };
+        let name = name.utf8_text(code.as_bytes()).unwrap();
+        let signature = ["receiver", "result"]
+            .iter()
+            .filter_map(|f| func.child_by_field_name(f))
+            .map(|n| n.utf8_text(code.as_bytes()).unwrap())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let takes_struct = signature.split(|c: char| !c.is_alphanumeric() && c != '_').any(|t| t == layout.name);
+        if !name.contains("Bytes") || !takes_struct {
+            continue;
+        }
+        let offsets = serialized_offsets(body, &names, code, &ctx.constants);
+        if !offsets.is_empty() {
+            serializers.push((name.to_string(), offsets));
+        }
+    }
+    serializers.sort_by_key(|(name, _)| !name.contains("From"));
+    let Some((wire_name, wire)) = serializers.first() else { return };
+
+    for (other, offsets) in &serializers[1..] {
+        for (field, offset) in offsets {
+            match wire.iter().find(|(f, _)| f == field) {
+                Some((_, expected)) if expected != offset => ctx.warnings.push(format!(
+                    "{} puts {} at offset {} but {} reads it at {}",
+                    other, field, offset, wire_name, expected
+                )),
+                None => ctx.warnings.push(format!("{} writes {}, which {} doesn't read", other, field, wire_name)),
+                _ => {}
+            }
+        }
+    }
+
+    let mut fields = Vec::new();
+    for field in layout.fields.drain(..) {
+        match wire.iter().find(|(f, _)| *f == field.name) {
+            Some((_, offset)) => fields.push(Field { offset: *offset, ..field }),
+            None => ctx.warnings.push(format!("Field {} isn't read by {}; left out of the wire layout", field.name, wire_name)),
+        }
+    }
+    fields.sort_by_key(|f| f.offset);
+    layout.total_size = fields.iter().map(|f| f.offset + f.size).max().unwrap_or(0);
+    layout.fields = fields;
+    layout.packing = Some(1);
+    layout.wire = Some(wire_name.clone());
+}
+
+/// Field and buffer offset of each statement of `body` that moves a field to
+/// or from a byte slice: `s.Magic = binary.LittleEndian.Uint32(data[0:])`,
+/// `buf[8] = s.Flag`, `Command{Cmd: data[0]}`. Loop variables count as 0,
+/// so `data[12+i*4:]` in a loop over an array is the array's offset.
+fn serialized_offsets(body: Node, names: &[&str], code: &str, constants: &Constants) -> Vec<(String, usize)> {
+    let mut vars = constants.clone();
+    visit(body, &mut |node| {
+        if node.kind() == "range_clause"
+            && let Some(left) = node.child_by_field_name("left")
+        {
+            let mut cursor = left.walk();
+            for var in left.named_children(&mut cursor) {
+                vars.insert(var.utf8_text(code.as_bytes()).unwrap().to_string(), 0);
+            }
+        }
+        true
+    });
+
+    let mut offsets: Vec<(String, usize)> = Vec::new();
+    visit(body, &mut |node| {
+        if !matches!(node.kind(), "expression_statement" | "assignment_statement" | "keyed_element") {
+            return true;
+        }
+        let mut field = match node.kind() {
+            "keyed_element" => node.child_by_field_name("key").map(|k| k.utf8_text(code.as_bytes()).unwrap()),
+            _ => None,
+        };
+        let mut offset: Option<usize> = None;
+        visit(node, &mut |inner| {
+            match inner.kind() {
+                "selector_expression" if field.is_none() => {
+                    field = inner.child_by_field_name("field").map(|f| f.utf8_text(code.as_bytes()).unwrap()).filter(|f| names.contains(f));
+                }
+                "slice_expression" | "index_expression" => {
+                    let start = inner.child_by_field_name(if inner.kind() == "slice_expression" { "start" } else { "index" });
+                    let on_buffer = inner.child_by_field_name("operand").is_some_and(|o| o.kind() == "identifier");
+                    if let Some(start) = start.filter(|_| on_buffer)
+                        && let Some(at) = constants::eval(start.utf8_text(code.as_bytes()).unwrap(), &vars).and_then(|v| usize::try_from(v).ok())
+                    {
+                        offset = Some(offset.map_or(at, |o| o.min(at)));
+                    }
+                }
+                _ => {}
+            }
+            true
+        });
+        if let (Some(field), Some(offset)) = (field.filter(|f| names.contains(f)), offset)
+            && !offsets.iter().any(|(f, _)| f == field)
+        {
+            offsets.push((field.to_string(), offset));
+        }
+        false
+    });
+    offsets
+}
+
+/// Depth-first walk; `visit` returns whether to descend into the node
+fn visit<'t>(node: Node<'t>, f: &mut impl FnMut(Node<'t>) -> bool) {
+    if f(node) {
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            visit(child, f);
+        }
+    }
+}
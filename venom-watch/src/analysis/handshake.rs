@@ -0,0 +1,353 @@
+//! `--check-constants`: the constants every side of a channel has to agree
+//! on, the channel name and the magic number, compared across files
+//!
+//! A generated project defines them once per language: `#define
+//! MONITOR_MAGIC 0x763E0219` in protocol.h, `MAGIC = 0x763E0219` in
+//! venom_binding.py, `const int magic = ...;` in venom_binding.dart,
+//! `Magic = ...` in venom.go and `const MAGIC: u32 = ...;` in lib.rs.
+//! Editing one of them breaks the handshake without any layout changing.
+//!
+//! Constants are matched by how their name ends, ignoring case and
+//! underscores, so `MONITOR_CHANNEL_NAME`, `CHANNEL_NAME` and `channelName`
+//! are all `channel_name`. C, C++, Rust, Go and Zig definitions are read
+//! with their grammars, Python and Dart ones line by line; a constant
+//! defined as another one of the same file takes its value. The value most
+//! files give is the right one (the first file's on a tie), and any
+//! definition with another is reported.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+use tree_sitter::Node;
+use crate::models::{ConstantDefinition, ConstantsReport, SharedConstant};
+use super::constants::{self, Constants};
+use super::layout;
+
+/// Name endings of the constants compared when none are given
+pub const DEFAULT_CONSTANT_NAMES: &[&str] = &["magic", "channel_name"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+    Int(i64),
+    Str(String),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Int(v) if *v < 0 => write!(f, "-0x{:X}", v.unsigned_abs()),
+            Value::Int(v) => write!(f, "0x{:X}", v),
+            Value::Str(s) => write!(f, "{:?}", s),
+        }
+    }
+}
+
+/// A constant as written: name, value text and line
+type Written = (String, String, usize);
+
+/// A constant of one of the files: the file, its value, its name as written
+/// and its line
+type Found = (String, Value, String, usize);
+
+/// The constants whose name ends in one of `names` (`DEFAULT_CONSTANT_NAMES`
+/// when empty) in each of `paths`, each compared with the value most files
+/// give it
+pub fn check_constants(paths: &[PathBuf], names: &[String]) -> Result<ConstantsReport, String> {
+    let names: Vec<String> = if names.is_empty() { DEFAULT_CONSTANT_NAMES.iter().map(|n| n.to_string()).collect() } else { names.to_vec() };
+    let mut found: Vec<(String, Vec<Found>)> = names.into_iter().map(|n| (n, Vec::new())).collect();
+    for path in paths {
+        let file = path.to_string_lossy().to_string();
+        for (name, value, line) in definitions(path)? {
+            if let Some((_, defined)) = found.iter_mut().find(|(pattern, _)| normalized(&name).ends_with(&normalized(pattern))) {
+                defined.push((file.clone(), value, name, line));
+            }
+        }
+    }
+
+    let mut constants = Vec::new();
+    for (pattern, defined) in found {
+        // Files giving each value, in the order the values are first seen
+        let mut votes: Vec<(&Value, Vec<&str>)> = Vec::new();
+        for (file, value, _, _) in &defined {
+            match votes.iter_mut().find(|(v, _)| *v == value) {
+                Some((_, files)) if files.contains(&file.as_str()) => {}
+                Some((_, files)) => files.push(file),
+                None => votes.push((value, vec![file])),
+            }
+        }
+        let most = votes.iter().map(|(_, files)| files.len()).max().unwrap_or(0);
+        let majority = votes.iter().find(|(_, files)| files.len() == most).map(|(v, _)| (*v).clone());
+        let definitions = defined
+            .iter()
+            .map(|(file, value, name, line)| ConstantDefinition {
+                file: file.clone(),
+                line: *line,
+                name: name.clone(),
+                value: value.to_string(),
+                agrees: Some(value) == majority.as_ref(),
+            })
+            .collect();
+        constants.push(SharedConstant { name: pattern, value: majority.map(|v| v.to_string()), definitions });
+    }
+    Ok(ConstantsReport {
+        success: constants.iter().all(|c| c.definitions.iter().all(|d| d.agrees)),
+        files: paths.iter().map(|p| p.to_string_lossy().to_string()).collect(),
+        constants,
+    })
+}
+
+/// `MONITOR_CHANNEL_NAME` and `monitorChannelName` alike:
+/// `monitorchannelname`. A `*` is dropped too, so that `*_MAGIC` is `magic`
+fn normalized(name: &str) -> String {
+    name.replace(['_', '*'], "").to_lowercase()
+}
+
+/// Every constant of the file with a value this can work out, in file order
+fn definitions(path: &PathBuf) -> Result<Vec<(String, Value, usize)>, String> {
+    let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("");
+    let written = match ext {
+        "py" | "dart" => {
+            let code = std::fs::read_to_string(path).map_err(|e| format!("Could not read file {}: {}", path.display(), e))?;
+            if ext == "py" { python(&code) } else { dart(&code) }
+        }
+        _ => {
+            let (code, tree) = layout::parse(path)?;
+            let mut written = Vec::new();
+            grammar(tree.root_node(), &code, ext, &mut written);
+            written
+        }
+    };
+
+    let mut ints = Constants::new();
+    let mut strings: HashMap<String, String> = HashMap::new();
+    let mut values = Vec::new();
+    for (name, text, line) in written {
+        let text = text.trim();
+        let value = match literal(text) {
+            Some(s) => Value::Str(s),
+            None => match constants::eval(uncast(text), &ints) {
+                Some(v) => Value::Int(v),
+                None => match strings.get(text) {
+                    Some(s) => Value::Str(s.clone()),
+                    None => continue,
+                },
+            },
+        };
+        match &value {
+            Value::Int(v) => {
+                ints.insert(name.clone(), *v);
+            }
+            Value::Str(s) => {
+                strings.insert(name.clone(), s.clone());
+            }
+        }
+        values.push((name, value, line));
+    }
+    Ok(values)
+}
+
+/// `#define`s, `const` declarations and `constexpr`s outside functions (C
+/// and C++), `const`/`static` items (Rust), `const` specs (Go) and `const`
+/// declarations (Zig)
+fn grammar(node: Node, code: &str, ext: &str, written: &mut Vec<Written>) {
+    let text = |n: Node| n.utf8_text(code.as_bytes()).unwrap_or("").to_string();
+    let line = node.start_position().row + 1;
+    match (ext, node.kind()) {
+        // Locals aren't shared with anyone
+        (_, "function_definition" | "function_item" | "function_declaration" | "method_declaration") => return,
+        ("rs", "const_item" | "static_item") | ("go", "const_spec") => {
+            let value = node.child_by_field_name("value").map(|v| if v.kind() == "expression_list" { v.named_child(0).unwrap_or(v) } else { v });
+            if let (Some(name), Some(value)) = (node.child_by_field_name("name"), value) {
+                written.push((text(name), text(value), line));
+            }
+            return;
+        }
+        ("zig", "variable_declaration") if node.children(&mut node.walk()).any(|n| n.kind() == "const") => {
+            let mut cursor = node.walk();
+            let named: Vec<Node> = node.named_children(&mut cursor).collect();
+            if let (Some(name), Some(value)) = (named.first(), named.last())
+                && name.kind() == "identifier" && named.len() > 1 {
+                written.push((text(*name), text(*value), line));
+            }
+            return;
+        }
+        ("rs" | "go" | "zig", _) => {}
+        (_, "preproc_def") => {
+            if let (Some(name), Some(value)) = (node.child_by_field_name("name"), node.child_by_field_name("value")) {
+                written.push((text(name), text(value), line));
+            }
+            return;
+        }
+        (_, "declaration") => {
+            let constant = node.children(&mut node.walk()).any(|n| n.kind() == "type_qualifier" && matches!(text(n).as_str(), "const" | "constexpr"));
+            let mut cursor = node.walk();
+            for declarator in node.children_by_field_name("declarator", &mut cursor).filter(|d| constant && d.kind() == "init_declarator") {
+                if let (Some(name), Some(value)) = (declarator.child_by_field_name("declarator").map(identifier), declarator.child_by_field_name("value")) {
+                    written.push((text(name), text(value), line));
+                }
+            }
+            return;
+        }
+        _ => {}
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        grammar(child, code, ext, written);
+    }
+}
+
+/// The name a C declarator declares: `NAME` of `*const NAME` or `NAME[]`
+fn identifier(declarator: Node) -> Node {
+    match declarator.child_by_field_name("declarator") {
+        Some(inner) => identifier(inner),
+        None => declarator,
+    }
+}
+
+/// Module-level `NAME = value` and `NAME: type = value` assignments
+fn python(code: &str) -> Vec<Written> {
+    let mut written = Vec::new();
+    for (i, line) in code.lines().enumerate() {
+        if line.starts_with(char::is_whitespace) {
+            continue;
+        }
+        let Some((target, value)) = line.split_once('=') else { continue };
+        let name = target.split(':').next().unwrap_or("").trim();
+        if value.starts_with('=') || !is_identifier(name) {
+            continue;
+        }
+        written.push((name.to_string(), uncomment(value, '#').to_string(), i + 1));
+    }
+    written
+}
+
+/// `const`, `static const` and `final` declarations with a value, at the
+/// top level or in a class: `const int magic = 0x763E0219;`
+fn dart(code: &str) -> Vec<Written> {
+    let mut written = Vec::new();
+    for (i, line) in code.lines().enumerate() {
+        let line = line.trim();
+        let rest = line.strip_prefix("static ").unwrap_or(line);
+        let Some(rest) = rest.strip_prefix("const ").or_else(|| rest.strip_prefix("final ")) else { continue };
+        let Some((declared, value)) = rest.split_once('=') else { continue };
+        let Some(name) = declared.split_whitespace().last().filter(|n| is_identifier(n)) else { continue };
+        let Some(value) = uncomment(value, '/').strip_suffix(';') else { continue };
+        written.push((name.to_string(), value.to_string(), i + 1));
+    }
+    written
+}
+
+fn is_identifier(text: &str) -> bool {
+    !text.is_empty() && text.chars().all(|c| c.is_alphanumeric() || c == '_') && !text.starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// `text` up to a comment starting with `marker` (`#`, or `//` for `/`)
+/// outside of quotes, trimmed
+fn uncomment(text: &str, marker: char) -> &str {
+    let mut quote = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match (quote, c) {
+            (Some(_), '\\') => {
+                chars.next();
+            }
+            (Some(q), c) if c == q => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, c) if c == marker && (marker != '/' || chars.peek().is_some_and(|&(_, next)| next == '/')) => return text[..i].trim(),
+            _ => {}
+        }
+    }
+    text.trim()
+}
+
+/// The text of a string literal: `"monitor"`, `'monitor'`, Rust's `b"..."`
+/// and `r"..."`, C's `L"..."`. A terminating `\0` isn't part of the name.
+fn literal(text: &str) -> Option<String> {
+    let text = text.trim_start_matches(['b', 'r', 'L', 'u', 'U', '8']);
+    let quote = text.chars().next().filter(|&c| c == '"' || c == '\'')?;
+    let inner = text.strip_prefix(quote)?.strip_suffix(quote)?;
+    let mut value = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        value.push(match c {
+            '\\' => match chars.next()? {
+                '0' => '\0',
+                'n' => '\n',
+                't' => '\t',
+                escaped => escaped,
+            },
+            c => c,
+        });
+    }
+    Some(value.trim_end_matches('\0').to_string())
+}
+
+/// A C value without its cast: `0x10u` of `(uint32_t)0x10u`
+fn uncast(text: &str) -> &str {
+    match text.strip_prefix('(').and_then(|rest| rest.split_once(')')) {
+        Some((cast, value)) if !value.trim().is_empty() && cast.chars().all(|c| c.is_alphanumeric() || c == '_' || c == ' ') => value.trim(),
+        _ => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::fixture;
+
+    /// (name, line, value) of the definitions that disagree
+    fn stale(constant: &SharedConstant) -> Vec<(String, usize, String)> {
+        constant.definitions.iter().filter(|d| !d.agrees).map(|d| (d.name.clone(), d.line, d.value.clone())).collect()
+    }
+
+    #[test]
+    fn test_stale_magic() {
+        let files = [fixture("test_protocol.h"), fixture("test_python_client.py"), fixture("test_handshake.rs")];
+        let report = check_constants(&files, &[]).unwrap();
+        assert!(!report.success);
+        let [magic, channel] = &report.constants[..] else { panic!("{:?}", report.constants) };
+
+        assert_eq!((magic.name.as_str(), magic.value.as_deref()), ("magic", Some("0x763E0219")));
+        let names: Vec<&str> = magic.definitions.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, ["MONITOR_MAGIC", "MAGIC", "MAGIC"], "the function's own constant is left out");
+        assert_eq!(stale(magic), [("MAGIC".to_string(), 4, "0x763E0218".to_string())]);
+        assert!(magic.definitions[2].file.ends_with("test_handshake.rs"));
+
+        assert_eq!(channel.value.as_deref(), Some("\"monitor\""));
+        assert_eq!(channel.definitions.len(), 3);
+        assert!(stale(channel).is_empty());
+    }
+
+    #[test]
+    fn test_bindings_agree() {
+        let files = ["test_protocol.h", "test_python_client.py", "test_dart_client.dart", "test_go_client.go"].map(fixture);
+        let report = check_constants(&files, &[]).unwrap();
+        assert!(report.success, "{:?}", report.constants);
+        // The Dart binding's `monitorMagic = magic` is the same constant again
+        let dart: Vec<(&str, usize)> = report.constants[0].definitions.iter().filter(|d| d.file.ends_with(".dart")).map(|d| (d.name.as_str(), d.line)).collect();
+        assert_eq!(dart, [("magic", 20), ("monitorMagic", 87)]);
+        assert_eq!(report.constants[1].definitions.iter().map(|d| d.name.as_str()).collect::<Vec<_>>(), ["MONITOR_CHANNEL_NAME", "CHANNEL_NAME", "channelName", "ChannelName"]);
+
+        // Only the names asked for
+        let report = check_constants(&files, &["*_CHANNEL_NAME".to_string()]).unwrap();
+        assert_eq!(report.constants.len(), 1);
+        assert_eq!(report.constants[0].definitions.len(), 4);
+    }
+
+    #[test]
+    fn test_values() {
+        assert_eq!(literal("b\"monitor\\0\""), Some("monitor".to_string()));
+        assert_eq!(literal("'it\\'s'"), Some("it's".to_string()));
+        assert_eq!(literal("0x10"), None);
+        assert_eq!(uncast("(uint32_t)0x10u"), "0x10u");
+        assert_eq!(uncast("(1 + 2)"), "(1 + 2)");
+        assert_eq!(uncomment("'a # b'  # comment", '#'), "'a # b'");
+        assert_eq!(uncomment("0x10; // 16", '/'), "0x10;");
+
+        let python = python("MAGIC: int = 1983775257  # 0x763E0219\nif MAGIC == 1:\n    INNER = 2\n");
+        assert_eq!(python, [("MAGIC".to_string(), "1983775257".to_string(), 1)]);
+        let dart = dart("class Protocol {\n  static const String channelName = \"monitor\"; // name\n  final int count;\n}\n");
+        assert_eq!(dart, [("channelName".to_string(), "\"monitor\"".to_string(), 2)]);
+        assert_eq!(Value::Int(1983775257).to_string(), "0x763E0219");
+    }
+}
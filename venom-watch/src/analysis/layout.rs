@@ -1,161 +1,587 @@
 use std::fs;
-use std::path::PathBuf;
-use tree_sitter::{Parser as TSParser, Query, QueryCursor};
+use std::path::{Path, PathBuf};
+use tree_sitter::{Parser as TSParser, Query, QueryCursor, Tree};
 use streaming_iterator::StreamingIterator;
-use crate::models::{Field, StructLayout, EnumMember, EnumLayout};
+use crate::models::{DeclaredTypes, Field, StructLayout, EnumMember, EnumLayout, Target};
+use super::constants::{self, Constants};
+use super::{go, zig};
 
 pub fn analyze_file(path: &PathBuf, struct_name: &str) -> Result<StructLayout, String> {
-    let code = fs::read_to_string(path).map_err(|e| format!("Could not read file {}: {}", path.display(), e))?;
+    analyze_file_for(path, struct_name, Target::default())
+}
+
+/// `analyze_file` for a struct on `target`: pointers, and the types as wide
+/// as them, take its pointer size
+pub fn analyze_file_for(path: &PathBuf, struct_name: &str, target: Target) -> Result<StructLayout, String> {
+    let (code, tree) = parse(path)?;
     let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("");
-    
-    let mut parser = TSParser::new();
-    let is_rust = ext == "rs";
-    let language = if is_rust {
-        tree_sitter_rust::LANGUAGE
-    } else {
-        tree_sitter_c::LANGUAGE
+    let root_node = tree.root_node();
+
+    match ext {
+        "rs" => return analyze_rust_struct(struct_name, &code, root_node, path.to_string_lossy().to_string(), target),
+        "go" => return go::analyze_go_struct(struct_name, &code, root_node, path.to_string_lossy().to_string(), target),
+        "zig" => return zig::analyze_zig_struct(struct_name, &code, root_node, path.to_string_lossy().to_string(), target),
+        _ => {}
+    }
+
+    match find_c_aggregate(struct_name, &code, root_node) {
+        Some(fields_node) => parse_fields(fields_node, struct_name, &code, root_node, path.to_string_lossy().to_string(), target),
+        None => Err(format!("Struct '{}' not found in {}", struct_name, path.display())),
+    }
+}
+
+/// Source of the file and its syntax tree, in the grammar its extension
+/// names (C for anything unknown)
+pub(super) fn parse(path: &PathBuf) -> Result<(String, Tree), String> {
+    let code = fs::read_to_string(path).map_err(|e| format!("Could not read file {}: {}", path.display(), e))?;
+    let language = match path.extension().and_then(|s| s.to_str()).unwrap_or("") {
+        "rs" => tree_sitter_rust::LANGUAGE,
+        "hpp" | "cc" | "cpp" => tree_sitter_cpp::LANGUAGE,
+        "go" => tree_sitter_go::LANGUAGE,
+        "zig" => tree_sitter_zig::LANGUAGE,
+        _ => tree_sitter_c::LANGUAGE,
     };
-    
+    let mut parser = TSParser::new();
     parser.set_language(&language.into()).expect("Error loading grammar");
-
     let tree = parser.parse(&code, None).expect("Failed to parse code");
-    let root_node = tree.root_node();
+    Ok((code, tree))
+}
+
+/// Whether `analyze_enum` can read the file's enums: C and Rust
+pub fn enums_supported(path: &Path) -> bool {
+    !matches!(path.extension().and_then(|s| s.to_str()).unwrap_or(""), "hpp" | "cc" | "cpp" | "go" | "zig")
+}
+
+/// Every struct (C unions and C++ classes included) and enum with a body
+/// the file defines, by the name `analyze_file`/`analyze_enum` find it
+/// under, in file order. A typedef'd C aggregate goes by its typedef name.
+pub fn declared_types(path: &PathBuf) -> Result<DeclaredTypes, String> {
+    let (code, tree) = parse(path)?;
+    let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("");
+    let text = |n: tree_sitter::Node| n.utf8_text(code.as_bytes()).unwrap().to_string();
+    let mut types = DeclaredTypes::default();
 
-    if is_rust {
-        return analyze_rust_struct(struct_name, &code, root_node, path.to_string_lossy().to_string());
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor).collect::<Vec<_>>().into_iter().rev());
+
+        let (list, name) = match (ext, node.kind()) {
+            ("rs", "struct_item") if node.child_by_field_name("body").is_some_and(|b| b.kind() == "field_declaration_list") => {
+                (&mut types.structs, node.child_by_field_name("name").map(text))
+            }
+            ("rs", "enum_item") => (&mut types.enums, node.child_by_field_name("name").map(text)),
+            ("go", "type_spec") if node.child_by_field_name("type").is_some_and(|t| t.kind() == "struct_type") => {
+                (&mut types.structs, node.child_by_field_name("name").map(text))
+            }
+            ("zig", "variable_declaration") => {
+                let mut decl_cursor = node.walk();
+                let named: Vec<tree_sitter::Node> = node.named_children(&mut decl_cursor).collect();
+                match (named.first(), named.last()) {
+                    (Some(name), Some(value)) if name.kind() == "identifier" && value.kind() == "struct_declaration" => (&mut types.structs, Some(text(*name))),
+                    _ => continue,
+                }
+            }
+            ("rs" | "go" | "zig", _) => continue,
+            // Enums only where `analyze_enum` looks: at the top level or behind a typedef
+            (_, "enum_specifier") if node.parent().is_some_and(|p| !matches!(p.kind(), "translation_unit" | "type_definition")) => continue,
+            (_, "struct_specifier" | "union_specifier" | "class_specifier" | "enum_specifier") if node.child_by_field_name("body").is_some() => {
+                let list = if node.kind() == "enum_specifier" { &mut types.enums } else { &mut types.structs };
+                let typedef = node.parent().filter(|p| p.kind() == "type_definition").and_then(|p| p.child_by_field_name("declarator"));
+                (list, typedef.or(node.child_by_field_name("name")).map(text))
+            }
+            _ => continue,
+        };
+        if let Some(name) = name.filter(|n| !list.contains(n)) {
+            list.push(name);
+        }
     }
+    if !enums_supported(path) {
+        types.enums.clear();
+    }
+    Ok(types)
+}
 
-    // Query to find the struct definition
-    let query_str = format!(
-        r#"
-        (struct_specifier
-            name: (type_identifier) @struct_name
-            body: (field_declaration_list) @fields
-        )
-        "#
-    );
-    
-    let query = Query::new(&language.into(), &query_str).expect("Invalid query");
+/// Body of the struct, union or (C++) class called `name`, by tag or by
+/// typedef. In C++ `name` may be qualified (`monitor::State`) or bare
+/// (`State`), whatever namespaces the type is declared in.
+fn find_c_aggregate<'t>(name: &str, code: &str, root_node: tree_sitter::Node<'t>) -> Option<tree_sitter::Node<'t>> {
+    let language = root_node.language();
+    let class = if is_cpp(root_node) {
+        "(class_specifier name: (_) @name body: (field_declaration_list) @fields)"
+    } else {
+        ""
+    };
+    let query_str = format!(r#"
+        [
+            (struct_specifier name: (_) @name body: (field_declaration_list) @fields)
+            (union_specifier name: (_) @name body: (field_declaration_list) @fields)
+            {class}
+            (type_definition
+                type: [
+                    (struct_specifier body: (field_declaration_list) @fields)
+                    (union_specifier body: (field_declaration_list) @fields)
+                ]
+                declarator: (type_identifier) @name
+            )
+        ]
+        "#);
+    let query = Query::new(&language, &query_str).expect("Invalid query");
     let mut cursor = QueryCursor::new();
     let mut matches = cursor.matches(&query, root_node, code.as_bytes());
 
     while let Some(m) = matches.next() {
-        let name_node = m.captures[0].node;
-        let struct_def_name = name_node.utf8_text(code.as_bytes()).unwrap();
+        let capture = |wanted: &str| m.captures.iter().find(|c| query.capture_names()[c.index as usize] == wanted).map(|c| c.node);
+        let (Some(name_node), Some(fields_node)) = (capture("name"), capture("fields")) else { continue };
+        let qualified = qualified_name(fields_node, name_node.utf8_text(code.as_bytes()).unwrap(), code);
+        if qualified == name || qualified.ends_with(&format!("::{}", name)) {
+            return Some(fields_node);
+        }
+    }
+    None
+}
+
+fn is_cpp(root_node: tree_sitter::Node) -> bool {
+    root_node.language().id_for_node_kind("class_specifier", true) != 0
+}
 
-        if struct_def_name == struct_name {
-            let fields_node = m.captures[1].node;
-            return parse_fields(fields_node, struct_name, &code, root_node, path.to_string_lossy().to_string());
+/// `name` prefixed with the namespaces and classes the aggregate whose body
+/// is `fields_node` is declared in
+fn qualified_name(fields_node: tree_sitter::Node, name: &str, code: &str) -> String {
+    let mut parts = vec![name.to_string()];
+    let mut scope = fields_node.parent().and_then(|spec| spec.parent());
+    while let Some(node) = scope {
+        if matches!(node.kind(), "namespace_definition" | "struct_specifier" | "class_specifier" | "union_specifier")
+            && let Some(scope_name) = node.child_by_field_name("name")
+        {
+            parts.push(scope_name.utf8_text(code.as_bytes()).unwrap().to_string());
         }
+        scope = node.parent();
     }
-    
-    // Check for typedef struct
-    let typedef_query_str = format!(
-        r#"
-        (type_definition
-            type: (struct_specifier
-                body: (field_declaration_list) @fields
-            )
-            declarator: (type_identifier) @typedef_name
-        )
-        "#
-    );
-    let td_query = Query::new(&language.into(), &typedef_query_str).expect("Invalid typedef query");
-    let mut td_cursor = QueryCursor::new();
-    let mut td_matches = td_cursor.matches(&td_query, root_node, code.as_bytes());
-
-    while let Some(m) = td_matches.next() {
-        let name_node = m.captures[1].node;
-        let type_name = name_node.utf8_text(code.as_bytes()).unwrap();
+    parts.reverse();
+    parts.join("::")
+}
+
+fn parse_fields(fields_list_node: tree_sitter::Node, struct_name: &str, code: &str, root_node: tree_sitter::Node, file_path: String, target: Target) -> Result<StructLayout, String> {
+    let mut ctx = CContext { code, root_node, constants: constants::collect_c(code, root_node), warnings: Vec::new(), target, open: vec![fields_list_node.id()] };
+    let packing = c_packing(fields_list_node, code, root_node);
+    if fields_list_node.parent().is_some_and(|p| p.children(&mut p.walk()).any(|n| n.kind() == "base_class_clause")) {
+        ctx.warnings.push(format!("{} derives from a base class; only its own members are laid out", struct_name));
+    }
+    let members = layout_c_members(fields_list_node, "", 0, &mut ctx)?;
+
+    Ok(StructLayout {
+        name: struct_name.to_string(),
+        fields: members.fields,
+        total_size: members.size,
+        file_path,
+        packing,
+        warnings: ctx.warnings,
+        wire: None,
+        target,
+    })
+}
+
+struct CContext<'a> {
+    code: &'a str,
+    root_node: tree_sitter::Node<'a>,
+    constants: Constants,
+    warnings: Vec<String>,
+    target: Target,
+    /// Bodies of the named structs/unions being laid out, outermost first;
+    /// one met again contains itself
+    open: Vec<usize>,
+}
+
+/// Levels of struct/union members listed under a field
+const MAX_NESTING: usize = 4;
+
+/// A struct or union body laid out from offset 0
+struct Members {
+    fields: Vec<Field>,
+    size: usize,
+    align: usize,
+}
+
+/// Lay out a struct or union body. Members of nested aggregates follow the
+/// aggregate's own field one `depth` further in, named `prefix` + path;
+/// members of an anonymous struct/union keep the enclosing `prefix`
+fn layout_c_members(list: tree_sitter::Node, prefix: &str, depth: usize, ctx: &mut CContext) -> Result<Members, String> {
+    let code = ctx.code;
+    let is_union = list.parent().is_some_and(|p| p.kind() == "union_specifier");
+    let pack = c_packing(list, code, ctx.root_node).unwrap_or(usize::MAX);
+    let mut placer = Placer { is_union, pack, fields: Vec::new(), offset: 0, bits: None, max_align: 1 };
 
-        if type_name == struct_name {
-             let fields_node = m.captures[0].node;
-             return parse_fields(fields_node, struct_name, &code, root_node, path.to_string_lossy().to_string());
+    let mut cursor = list.walk();
+    for child in list.children(&mut cursor) {
+        // Access specifiers, member functions and static members take no space
+        let is_static = child.children(&mut child.walk()).any(|n| n.kind() == "storage_class_specifier" && n.utf8_text(code.as_bytes()).unwrap() == "static");
+        if child.kind() != "field_declaration" || is_static {
+            continue;
+        }
+        let type_node = child.child_by_field_name("type").ok_or("No type")?;
+        let type_text = type_node.utf8_text(code.as_bytes()).unwrap();
+        let mut decl_cursor = child.walk();
+        let declarators: Vec<_> = child.children_by_field_name("declarator", &mut decl_cursor).filter(|d| d.kind() != "function_declarator").collect();
+
+        let bitfield = child.children(&mut child.walk()).find(|n| n.kind() == "bitfield_clause");
+        if let Some(clause) = bitfield {
+            let width_text = clause.named_child(0).map(|n| n.utf8_text(code.as_bytes()).unwrap()).unwrap_or("");
+            let (size, align, _, _) = c_type_info(type_node, prefix, depth + 1, ctx)?;
+            let named = declarators.first().map(|d| d.utf8_text(code.as_bytes()).unwrap()).filter(|name| !name.is_empty());
+            let line = child.start_position().row + 1;
+            let width = constants::eval(width_text, &ctx.constants).and_then(|w| usize::try_from(w).ok()).unwrap_or_else(|| {
+                ctx.warnings.push(format!("Could not resolve bitfield width '{}' of field {} (line {}); counted as 1 bit", width_text, named.unwrap_or("<unnamed>"), line));
+                1
+            });
+            let field = named.map(|name| Field {
+                name: format!("{}{}", prefix, name),
+                type_name: type_text.to_string(),
+                size,
+                offset: 0,
+                align,
+                is_array: false,
+                array_len: 1,
+                is_flexible: false,
+                line,
+                is_pointer: false,
+                depth,
+                parent: None,
+                bit_offset: None,
+                bit_width: None,
+            });
+            placer.place_bits(field, size, align, width);
+            continue;
+        }
+
+        if declarators.is_empty() {
+            // A nested type declaration (`struct Inner {...};`, an enum) or a
+            // member function declares no storage here
+            let Some(body) = aggregate_body(type_node).filter(|_| type_node.child_by_field_name("name").is_none()) else {
+                continue;
+            };
+            // Anonymous struct/union: its members belong to this scope
+            let keyword = if type_node.kind() == "union_specifier" { "union" } else { "struct" };
+            let inner = layout_c_members(body, prefix, depth + 1, ctx)?;
+            let field = Field {
+                name: format!("{}<anonymous {}>", prefix, keyword),
+                type_name: keyword.to_string(),
+                size: inner.size,
+                offset: 0,
+                align: inner.align,
+                is_array: false,
+                array_len: 1,
+                is_flexible: false,
+                line: type_node.start_position().row + 1,
+                is_pointer: false,
+                depth,
+                parent: None,
+                bit_offset: None,
+                bit_width: None,
+            };
+            placer.place(field, inner.align, inner.fields);
+            continue;
+        }
+
+        for decl_node in declarators {
+            let (name_node, size_text, is_pointer) = match decl_node.kind() {
+                "array_declarator" => {
+                    let inner_decl = decl_node.child_by_field_name("declarator").unwrap();
+                    let size_text = decl_node.child_by_field_name("size").map(|n| n.utf8_text(code.as_bytes()).unwrap()).unwrap_or("");
+                    if inner_decl.kind() == "pointer_declarator" {
+                        (inner_decl.child_by_field_name("declarator").unwrap(), Some(size_text), true)
+                    } else {
+                        (inner_decl, Some(size_text), false)
+                    }
+                }
+                "pointer_declarator" => (decl_node.child_by_field_name("declarator").unwrap(), None, true),
+                _ => (decl_node, None, false),
+            };
+            let name = name_node.utf8_text(code.as_bytes()).unwrap();
+            let line = decl_node.start_position().row + 1;
+            // `std::array<T, N>` is laid out as `T[N]`
+            let (type_node, size_text) = match std_array(type_node, code) {
+                Some((elem, len)) if size_text.is_none() => (elem, Some(len)),
+                _ => (type_node, size_text),
+            };
+            let type_text = type_node.utf8_text(code.as_bytes()).unwrap();
+            // `payload[]`, or GNU's `payload[0]`: takes no bytes of the struct
+            let is_flexible = decl_node.kind() == "array_declarator" && size_text.is_some_and(|size| size.is_empty() || constants::eval(size, &ctx.constants) == Some(0));
+            let array_len = match size_text {
+                _ if is_flexible => 0,
+                Some(size) => array_len(size, &ctx.constants).unwrap_or_else(|| {
+                    ctx.warnings.push(unresolved_size(name, size, line));
+                    1
+                }),
+                None => 1,
+            };
+            let is_pointer = is_pointer || type_text.contains('*');
+
+            let (elem_size, align, type_name, children) = if is_pointer {
+                (ctx.target.pointer_size, ctx.target.pointer_size, type_text.to_string(), Vec::new())
+            } else {
+                let child_prefix = format!("{}{}.", prefix, name);
+                c_type_info(type_node, &child_prefix, depth + 1, ctx)?
+            };
+            let field = Field {
+                name: format!("{}{}", prefix, name),
+                type_name,
+                size: elem_size * array_len,
+                offset: 0,
+                align,
+                is_array: size_text.is_some(),
+                array_len,
+                is_flexible,
+                line,
+                is_pointer,
+                depth,
+                parent: None,
+                bit_offset: None,
+                bit_width: None,
+            };
+            // Members are listed for a single aggregate, not per array element
+            let children = if size_text.is_some() { Vec::new() } else { children };
+            placer.place(field, align, children);
         }
     }
 
-    Err(format!("Struct '{}' not found in {}", struct_name, path.display()))
+    let mut members = placer.finish();
+    // Laid out for their size all the same
+    if depth > MAX_NESTING && !members.fields.is_empty() {
+        if depth == MAX_NESTING + 1 {
+            ctx.warnings.push(format!("Members of {} are nested more than {} deep; it is compared as a whole", prefix.trim_end_matches('.'), MAX_NESTING));
+        }
+        members.fields.clear();
+    }
+    Ok(members)
 }
 
-fn parse_fields(fields_list_node: tree_sitter::Node, struct_name: &str, code: &str, root_node: tree_sitter::Node, file_path: String) -> Result<StructLayout, String> {
-    let mut fields = Vec::new();
-    let mut current_offset = 0;
-    
-    let mut cursor = fields_list_node.walk();
-    for child in fields_list_node.children(&mut cursor) {
-        if child.kind() == "field_declaration" {
-             let type_node = child.child_by_field_name("type").ok_or("No type")?;
-             let decl_node = child.child_by_field_name("declarator").ok_or("No declarator")?;
-             
-             let type_text = type_node.utf8_text(code.as_bytes()).unwrap();
-             
-             let (name, is_array, array_len) = if decl_node.kind() == "array_declarator" {
-                  let inner_decl = decl_node.child_by_field_name("declarator").unwrap();
-                  let size_node = decl_node.child_by_field_name("size").unwrap();
-                  let size_str = size_node.utf8_text(code.as_bytes()).unwrap();
-                  let len = parse_array_size(size_str);
-                  let actual_name = if inner_decl.kind() == "pointer_declarator" {
-                      inner_decl.child_by_field_name("declarator").unwrap().utf8_text(code.as_bytes()).unwrap()
-                  } else {
-                      inner_decl.utf8_text(code.as_bytes()).unwrap()
-                  };
-                  (actual_name, true, len)
-             } else if decl_node.kind() == "pointer_declarator" {
-                  (decl_node.child_by_field_name("declarator").unwrap().utf8_text(code.as_bytes()).unwrap(), false, 1)
-             } else {
-                  (decl_node.utf8_text(code.as_bytes()).unwrap(), false, 1)
-             };
+/// Running position while laying out a struct or union body
+struct Placer {
+    is_union: bool,
+    /// Max alignment (`#pragma pack`/packed), `usize::MAX` when natural
+    pack: usize,
+    fields: Vec<Field>,
+    /// Bytes taken so far (for a union: by its largest member)
+    offset: usize,
+    /// Bit position while a run of bitfields is open
+    bits: Option<usize>,
+    max_align: usize,
+}
 
-             let is_pointer = type_text.contains('*') || decl_node.kind() == "pointer_declarator";
-             let size = if is_pointer { 8 } else { get_type_size(type_text, code, root_node) } * array_len;
-             let align = if is_pointer { 8 } else { get_type_alignment(type_text, code, root_node) };
-             
-             let padding = (align - (current_offset % align)) % align;
-             current_offset += padding;
+impl Placer {
+    fn place(&mut self, field: Field, align: usize, children: Vec<Field>) {
+        self.close_bits();
+        let align = align.min(self.pack);
+        self.max_align = self.max_align.max(align);
+        let offset = if self.is_union { 0 } else { self.offset.next_multiple_of(align) };
+        self.offset = if self.is_union { self.offset.max(field.size) } else { offset + field.size };
+        let (parent, depth) = (field.name.clone(), field.depth);
+        self.fields.push(Field { offset, align, ..field });
+        self.fields.extend(children.into_iter().map(|c| Field {
+            offset: c.offset + offset,
+            // Deeper members already name the member they're in
+            parent: c.parent.or_else(|| (c.depth == depth + 1).then(|| parent.clone())),
+            ..c
+        }));
+    }
 
-             let start_pos = decl_node.start_position();
-             let line = start_pos.row + 1;
+    /// A bitfield of `width` bits declared with a `size`-byte type, placed the
+    /// way GCC does on the SysV ABI: right after the previous bitfield, even
+    /// one of another type, unless it would cross an `align` boundary of its
+    /// own type (never moved when packed). `:0` skips to that boundary.
+    /// Unnamed bitfields (`field` is `None`) don't affect the alignment.
+    fn place_bits(&mut self, field: Option<Field>, size: usize, align: usize, width: usize) {
+        let align = align.min(self.pack);
+        let unit = align * 8;
+        if field.is_some() {
+            self.max_align = self.max_align.max(align);
+        }
+        if self.is_union {
+            self.offset = self.offset.max(width.div_ceil(8));
+            if let Some(field) = field {
+                self.fields.push(Field { offset: 0, align, bit_offset: Some(0), bit_width: Some(width as u32), ..field });
+            }
+            return;
+        }
 
-             fields.push(Field {
-                 name: name.to_string(),
-                 type_name: type_text.to_string(),
-                 size,
-                 offset: current_offset,
-                 is_array,
-                 array_len,
-                 line,
-                 is_pointer,
-             });
+        let mut bit = self.bits.unwrap_or(self.offset * 8);
+        if width == 0 {
+            self.bits = Some(bit.next_multiple_of(unit));
+            return;
+        }
+        let unit_start = bit - bit % unit;
+        if self.pack > 1 && bit + width > unit_start + size * 8 {
+            bit = bit.next_multiple_of(unit);
+        }
+        if let Some(field) = field {
+            let offset = bit / unit * align;
+            self.fields.push(Field {
+                offset,
+                align,
+                bit_offset: Some((bit - offset * 8) as u32),
+                bit_width: Some(width as u32),
+                ..field
+            });
+        }
+        self.bits = Some(bit + width);
+    }
 
-             current_offset += size;
+    /// Normal fields start at the first whole byte after a bitfield run
+    fn close_bits(&mut self) {
+        if let Some(bits) = self.bits.take() {
+            self.offset = self.offset.max(bits.div_ceil(8));
         }
     }
-    
-    let max_align = fields.iter().map(|f| {
-        let is_ptr = f.type_name.contains('*') || f.is_pointer;
-        if is_ptr { 8 } else { get_type_alignment(&f.type_name, code, root_node) }
-    }).max().unwrap_or(1);
-    let padding = (max_align - (current_offset % max_align)) % max_align;
-    current_offset += padding;
 
-    Ok(StructLayout {
-        name: struct_name.to_string(),
-        fields,
-        total_size: current_offset,
-        file_path,
+    fn finish(mut self) -> Members {
+        self.close_bits();
+        let size = self.offset.next_multiple_of(self.max_align);
+        Members { fields: self.fields, size, align: self.max_align }
+    }
+}
+
+/// Element type and length of a `std::array<T, N>` type
+fn std_array<'t>(type_node: tree_sitter::Node<'t>, code: &'t str) -> Option<(tree_sitter::Node<'t>, &'t str)> {
+    let template = match type_node.kind() {
+        "qualified_identifier" if type_node.child_by_field_name("scope")?.utf8_text(code.as_bytes()).ok()? == "std" => type_node.child_by_field_name("name")?,
+        _ => return None,
+    };
+    if template.kind() != "template_type" || template.child_by_field_name("name")?.utf8_text(code.as_bytes()).ok()? != "array" {
+        return None;
+    }
+    let arguments = template.child_by_field_name("arguments")?;
+    let elem = arguments.named_child(0)?.child_by_field_name("type")?;
+    let len = arguments.named_child(1)?.utf8_text(code.as_bytes()).ok()?;
+    Some((elem, len))
+}
+
+fn aggregate_body(type_node: tree_sitter::Node) -> Option<tree_sitter::Node> {
+    if matches!(type_node.kind(), "struct_specifier" | "union_specifier" | "class_specifier") {
+        type_node.child_by_field_name("body")
+    } else {
+        None
+    }
+}
+
+/// Size, alignment, type name and (for a struct/union) members of a field type
+fn c_type_info(type_node: tree_sitter::Node, prefix: &str, depth: usize, ctx: &mut CContext) -> Result<(usize, usize, String, Vec<Field>), String> {
+    let code = ctx.code;
+    let text = type_node.utf8_text(code.as_bytes()).unwrap();
+    if let Some(body) = aggregate_body(type_node) {
+        let keyword = if type_node.kind() == "union_specifier" { "union" } else { "struct" };
+        let inner = layout_c_members(body, prefix, depth, ctx)?;
+        return Ok((inner.size, inner.align, keyword.to_string(), inner.fields));
+    }
+
+    let (size, align) = match c_primitive(text, ctx.target) {
+        Some(primitive) => primitive,
+        None => {
+            // `struct Name`/`union Name` or a typedef of either
+            let name = type_node.child_by_field_name("name").map(|n| n.utf8_text(code.as_bytes()).unwrap()).unwrap_or(text);
+            return match find_c_aggregate(name, code, ctx.root_node) {
+                Some(body) if ctx.open.contains(&body.id()) => {
+                    ctx.warnings.push(format!("{} contains itself at {}; counted as 0 bytes", name, prefix.trim_end_matches('.')));
+                    Ok((0, 1, text.to_string(), Vec::new()))
+                }
+                Some(body) => {
+                    ctx.open.push(body.id());
+                    let inner = layout_c_members(body, prefix, depth, ctx);
+                    ctx.open.pop();
+                    let inner = inner?;
+                    Ok((inner.size, inner.align, text.to_string(), inner.fields))
+                }
+                None => Ok((4, 1, text.to_string(), Vec::new())),
+            };
+        }
+    };
+    Ok((size, align, text.to_string(), Vec::new()))
+}
+
+/// Size and alignment of a C scalar type on `target`
+fn c_primitive(text: &str, target: Target) -> Option<(usize, usize)> {
+    let pointer = target.pointer_size;
+    Some(match text.strip_prefix("std::").unwrap_or(text) {
+        "char" | "int8_t" | "uint8_t" | "bool" => (1, 1),
+        "short" | "int16_t" | "uint16_t" => (2, 2),
+        "int" | "int32_t" | "uint32_t" | "float" | "gint" | "guint32" | "gboolean" => (4, 4),
+        "int64_t" | "uint64_t" | "double" | "guint64" => (8, 8),
+        "long" | "unsigned long" | "long int" | "size_t" | "ssize_t" | "intptr_t" | "uintptr_t" | "ptrdiff_t" => (pointer, pointer),
+        _ => return None,
     })
 }
 
-fn analyze_rust_struct(struct_name: &str, code: &str, root_node: tree_sitter::Node, file_path: String) -> Result<StructLayout, String> {
+/// Array dimension resolved against the file's constants; `None` unless a
+/// positive integer
+pub(super) fn array_len(size: &str, constants: &Constants) -> Option<usize> {
+    constants::eval(size, constants).filter(|&n| n > 0).and_then(|n| usize::try_from(n).ok())
+}
+
+pub(super) fn unresolved_size(field: &str, size: &str, line: usize) -> String {
+    format!("Could not resolve array size '{}' of field {} (line {}); counted as 1 element", size, field, line)
+}
+
+/// Max alignment a C struct is laid out with, `None` for natural alignment:
+/// 1 for `__attribute__((packed))` on the struct or its typedef, otherwise N
+/// from the `#pragma pack` in effect where the struct starts
+fn c_packing(fields_list_node: tree_sitter::Node, code: &str, root_node: tree_sitter::Node) -> Option<usize> {
+    let struct_node = fields_list_node.parent()?;
+    let mut holders = vec![struct_node];
+    if let Some(parent) = struct_node.parent().filter(|p| p.kind() == "type_definition") {
+        holders.push(parent);
+    }
+    for holder in holders {
+        let mut cursor = holder.walk();
+        for child in holder.children(&mut cursor) {
+            if child.kind() == "attribute_specifier" && child.utf8_text(code.as_bytes()).unwrap_or("").contains("packed") {
+                return Some(1);
+            }
+        }
+    }
+    pragma_pack_at(code, root_node, struct_node.start_byte())
+}
+
+/// `#pragma pack` value in effect at byte `at`, following `push`/`pop`
+fn pragma_pack_at(code: &str, root_node: tree_sitter::Node, at: usize) -> Option<usize> {
+    let query = Query::new(&root_node.language(), "(preproc_call directive: (preproc_directive) @directive argument: (preproc_arg) @arg)").expect("Invalid pragma query");
+    let mut cursor = QueryCursor::new();
+    let mut matches = cursor.matches(&query, root_node, code.as_bytes());
+
+    let mut current = None;
+    let mut stack = Vec::new();
+    while let Some(m) = matches.next() {
+        let directive = m.captures[0].node;
+        if directive.start_byte() >= at || directive.utf8_text(code.as_bytes()).unwrap_or("") != "#pragma" {
+            continue;
+        }
+        let arg: String = m.captures[1].node.utf8_text(code.as_bytes()).unwrap_or("").split_whitespace().collect();
+        let Some(args) = arg.strip_prefix("pack(").and_then(|a| a.strip_suffix(')')) else {
+            continue;
+        };
+        let mut parts = args.split(',');
+        match parts.next().unwrap_or("") {
+            "push" => {
+                stack.push(current);
+                if let Some(n) = parts.next() {
+                    current = n.parse().ok();
+                }
+            }
+            "pop" => current = stack.pop().flatten(),
+            "" => current = None,
+            n => current = n.parse().ok(),
+        }
+    }
+    current
+}
+
+fn analyze_rust_struct(struct_name: &str, code: &str, root_node: tree_sitter::Node, file_path: String, target: Target) -> Result<StructLayout, String> {
     let language = tree_sitter_rust::LANGUAGE;
-    let query_str = format!(
-        r#"
+    let query_str = r#"
         (struct_item
             name: (type_identifier) @name
             body: (field_declaration_list) @fields
         ) @item
-        "#
-    );
+        "#.to_string();
     
     let query = Query::new(&language.into(), &query_str).expect("Invalid query");
     let mut cursor = QueryCursor::new();
@@ -167,16 +593,43 @@ fn analyze_rust_struct(struct_name: &str, code: &str, root_node: tree_sitter::No
 
         if r_struct_name == struct_name {
             let fields_node = m.captures[2].node;
-            return parse_rust_fields(fields_node, struct_name, code, root_node, file_path);
+            let packing = rust_packing(m.captures[0].node, code);
+            let constants = constants::collect_rust(code, root_node);
+            return parse_rust_fields(fields_node, struct_name, code, file_path, packing, &constants, target);
         }
     }
     
     Err(format!("Rust struct '{}' not found", struct_name))
 }
 
-fn parse_rust_fields(fields_list_node: tree_sitter::Node, struct_name: &str, code: &str, root_node: tree_sitter::Node, file_path: String) -> Result<StructLayout, String> {
+/// Max alignment from `#[repr(packed)]`/`#[repr(packed(N))]` on a struct item
+fn rust_packing(item: tree_sitter::Node, code: &str) -> Option<usize> {
+    let mut prev = item.prev_sibling();
+    while let Some(node) = prev {
+        match node.kind() {
+            "attribute_item" => {
+                let text: String = node.utf8_text(code.as_bytes()).unwrap_or("").split_whitespace().collect();
+                if let Some(rest) = text.strip_prefix("#[repr(").and_then(|r| r.split("packed").nth(1)) {
+                    return Some(match rest.strip_prefix('(') {
+                        Some(n) => n.split(')').next().and_then(|n| n.parse().ok()).unwrap_or(1),
+                        None => 1,
+                    });
+                }
+            }
+            "line_comment" | "block_comment" => {}
+            _ => break,
+        }
+        prev = node.prev_sibling();
+    }
+    None
+}
+
+fn parse_rust_fields(fields_list_node: tree_sitter::Node, struct_name: &str, code: &str, file_path: String, packing: Option<usize>, constants: &Constants, target: Target) -> Result<StructLayout, String> {
     let mut fields = Vec::new();
     let mut current_offset = 0;
+    let pack = packing.unwrap_or(usize::MAX);
+    let mut max_align = 1;
+    let mut warnings = Vec::new();
     
     let mut cursor = fields_list_node.walk();
     for child in fields_list_node.children(&mut cursor) {
@@ -187,7 +640,18 @@ fn parse_rust_fields(fields_list_node: tree_sitter::Node, struct_name: &str, cod
              let name = name_node.utf8_text(code.as_bytes()).unwrap();
              let type_text = type_node.utf8_text(code.as_bytes()).unwrap();
              
-             let (size, align, is_array, array_len) = get_rust_type_info(type_text, code, root_node);
+             let line = name_node.start_position().row + 1;
+             let (size, align, is_array, array_len) = match get_rust_type_info(type_text, constants, target) {
+                 Ok(info) => info,
+                 // `[T; 0]`, the flexible array member of a `repr(C)` struct
+                 Err((_, align, len)) if constants::eval(len, constants) == Some(0) => (0, align, true, 0),
+                 Err((size, align, len)) => {
+                     warnings.push(unresolved_size(name, len, line));
+                     (size, align, true, 1)
+                 }
+             };
+             let align = align.min(pack);
+             max_align = max_align.max(align);
              
              let padding = (align - (current_offset % align)) % align;
              current_offset += padding;
@@ -197,20 +661,22 @@ fn parse_rust_fields(fields_list_node: tree_sitter::Node, struct_name: &str, cod
                  type_name: type_text.to_string(),
                  size,
                  offset: current_offset,
+                 align,
                  is_array,
                  array_len,
-                 line: name_node.start_position().row + 1,
+                 is_flexible: is_array && array_len == 0,
+                 line,
                  is_pointer: type_text.contains('*') || type_text.starts_with('&'),
+                 depth: 0,
+                 parent: None,
+                 bit_offset: None,
+                 bit_width: None,
              });
 
              current_offset += size;
         }
     }
     
-    let max_align = fields.iter().map(|f| {
-        let (_, align, _, _) = get_rust_type_info(&f.type_name, code, root_node);
-        align
-    }).max().unwrap_or(1);
     let padding = (max_align - (current_offset % max_align)) % max_align;
     current_offset += padding;
 
@@ -219,10 +685,19 @@ fn parse_rust_fields(fields_list_node: tree_sitter::Node, struct_name: &str, cod
         fields,
         total_size: current_offset,
         file_path,
+        packing,
+        warnings,
+        wire: None,
+        target,
     })
 }
 
-fn get_rust_type_info(t: &str, code: &str, root_node: tree_sitter::Node) -> (usize, usize, bool, usize) {
+/// Size, alignment, whether it's an array and its length
+type RustTypeInfo = (usize, usize, bool, usize);
+
+/// For an array whose length isn't a known constant, `Err` with one
+/// element's size and alignment and the length text
+fn get_rust_type_info<'a>(t: &'a str, constants: &Constants, target: Target) -> Result<RustTypeInfo, (usize, usize, &'a str)> {
     let t = t.trim();
     if t.starts_with('[') && t.contains(';') {
         let inner = &t[1..t.len()-1];
@@ -230,9 +705,9 @@ fn get_rust_type_info(t: &str, code: &str, root_node: tree_sitter::Node) -> (usi
         if parts.len() == 2 {
             let inner_type = parts[0].trim();
             let size_str = parts[1].trim();
-            let len = size_str.parse::<usize>().unwrap_or(1);
-            let (inner_size, inner_align, _, _) = get_rust_type_info(inner_type, code, root_node);
-            return (inner_size * len, inner_align, true, len);
+            let (inner_size, inner_align, _, _) = get_rust_type_info(inner_type, constants, target).map_err(|(size, align, _)| (size, align, size_str))?;
+            let len = array_len(size_str, constants).ok_or((inner_size, inner_align, size_str))?;
+            return Ok((inner_size * len, inner_align, true, len));
         }
     }
 
@@ -240,113 +715,20 @@ fn get_rust_type_info(t: &str, code: &str, root_node: tree_sitter::Node) -> (usi
         "u8" | "i8" | "bool" => (1, 1),
         "u16" | "i16" => (2, 2),
         "u32" | "i32" | "f32" => (4, 4),
-        "u64" | "i64" | "f64" | "usize" | "isize" => (8, 8),
-        _ if t.starts_with('&') || t.contains('*') => (8, 8),
+        "u64" | "i64" | "f64" => (8, 8),
+        "usize" | "isize" => (target.pointer_size, target.pointer_size),
+        _ if t.starts_with('&') || t.contains('*') => (target.pointer_size, target.pointer_size),
         _ => (4, 4), // Fallback
     };
     
-    (size, align, false, 1)
-}
-
-fn parse_array_size(s: &str) -> usize {
-    if let Ok(n) = s.parse::<usize>() {
-        return n;
-    }
-    match s {
-        "MAX_DEVICE_NAME" => 128,
-        "MAX_DEVICES" => 16,
-        "MAX_APP_STREAMS" => 32,
-        _ => 1,
-    }
-}
-
-fn get_type_size(t: &str, code: &str, root_node: tree_sitter::Node) -> usize {
-    match t {
-        "char" | "int8_t" | "uint8_t" => 1,
-        "short" | "int16_t" | "uint16_t" => 2,
-        "int" | "int32_t" | "uint32_t" | "float" | "gint" | "guint32" | "gboolean" => 4,
-        "long" | "int64_t" | "uint64_t" | "double" | "size_t" | "guint64" | "uintptr_t" => 8,
-        _ => {
-            if t.ends_with('*') { 
-                8 
-            } else {
-                if let Ok(layout) = find_and_parse_struct(t, code, root_node) {
-                    layout.total_size
-                } else {
-                    4 
-                }
-            }
-        }
-    }
-}
-
-fn find_and_parse_struct(struct_name: &str, code: &str, root_node: tree_sitter::Node) -> Result<StructLayout, String> {
-    let query_str = format!(
-        r#"
-        (struct_specifier
-            name: (type_identifier) @struct_name
-            body: (field_declaration_list) @fields
-        )
-        "#
-    );
-    let language = tree_sitter_c::LANGUAGE;
-    let query = Query::new(&language.into(), &query_str).unwrap();
-    let mut cursor = QueryCursor::new();
-    let mut matches = cursor.matches(&query, root_node, code.as_bytes());
-
-    while let Some(m) = matches.next() {
-        let name_node = m.captures[0].node;
-        let name = name_node.utf8_text(code.as_bytes()).unwrap();
-        if name == struct_name {
-            return parse_fields(m.captures[1].node, struct_name, code, root_node, "nested".to_string());
-        }
-    }
-
-    let typedef_query_str = format!(
-        r#"
-        (type_definition
-            type: (struct_specifier
-                body: (field_declaration_list) @fields
-            )
-            declarator: (type_identifier) @typedef_name
-        )
-        "#
-    );
-    let td_query = Query::new(&language.into(), &typedef_query_str).unwrap();
-    let mut td_cursor = QueryCursor::new();
-    let mut td_matches = td_cursor.matches(&td_query, root_node, code.as_bytes());
-
-    while let Some(m) = td_matches.next() {
-        let name_node = m.captures[1].node;
-        let name = name_node.utf8_text(code.as_bytes()).unwrap();
-        if name == struct_name {
-             return parse_fields(m.captures[0].node, struct_name, code, root_node, "nested".to_string());
-        }
-    }
-
-    Err("Struct not found".to_string())
-}
-
-fn get_type_alignment(t: &str, code: &str, root_node: tree_sitter::Node) -> usize {
-    match t {
-        "char" | "int8_t" | "uint8_t" => 1,
-        "short" | "int16_t" | "uint16_t" => 2,
-        "int" | "int32_t" | "uint32_t" | "float" | "gint" | "guint32" | "gboolean" => 4,
-        "long" | "int64_t" | "uint64_t" | "double" | "size_t" | "guint64" | "uintptr_t" => 8,
-        _ => {
-            if let Ok(layout) = find_and_parse_struct(t, code, root_node) {
-                layout.fields.iter().map(|f| {
-                    let is_ptr = f.type_name.contains('*') || f.is_pointer;
-                    if is_ptr { 8 } else { get_type_alignment(&f.type_name, code, root_node) }
-                }).max().unwrap_or(1)
-            } else {
-                1
-            }
-        }
-    }
+    Ok((size, align, false, 1))
 }
 
 pub fn analyze_enum(path: &PathBuf, enum_name: &str) -> Result<EnumLayout, String> {
+    if path.extension().is_some_and(|ext| ext == "rs") {
+        let (code, tree) = parse(path)?;
+        return analyze_rust_enum(enum_name, &code, tree.root_node(), path.to_string_lossy().to_string());
+    }
     let code = fs::read_to_string(path).map_err(|e| format!("Could not read file {}: {}", path.display(), e))?;
     let mut parser = TSParser::new();
     let language = tree_sitter_c::LANGUAGE;
@@ -354,40 +736,39 @@ pub fn analyze_enum(path: &PathBuf, enum_name: &str) -> Result<EnumLayout, Strin
 
     let tree = parser.parse(&code, None).expect("Failed to parse code");
     let root_node = tree.root_node();
+    let constants = constants::collect_c(&code, root_node);
 
     let mut members = Vec::new();
     let mut cursor = root_node.walk();
     
-    let mut found = false;
+    let mut found = None;
     for node in root_node.children(&mut cursor) {
-        if node.kind() == "enum_specifier" {
-            if let Some(name_node) = node.child_by_field_name("name") {
-                if name_node.utf8_text(code.as_bytes()).unwrap() == enum_name {
-                    found = true;
-                    let body = node.child_by_field_name("body").ok_or("Enum has no body")?;
-                    let mut body_cursor = body.walk();
-                    let mut current_val = 0;
-                    for member in body.children(&mut body_cursor) {
-                        if member.kind() == "enumerator" {
-                            let name = member.child_by_field_name("name").unwrap().utf8_text(code.as_bytes()).unwrap();
-                            if let Some(val_node) = member.child_by_field_name("value") {
-                                let val_text = val_node.utf8_text(code.as_bytes()).unwrap();
-                                current_val = val_text.parse::<i64>().unwrap_or(0);
-                            }
-                            members.push(EnumMember {
-                                name: name.to_string(),
-                                value: current_val,
-                                line: member.start_position().row + 1,
-                            });
-                            current_val += 1;
-                        }
+        if node.kind() == "enum_specifier"
+            && let Some(name_node) = node.child_by_field_name("name")
+            && name_node.utf8_text(code.as_bytes()).unwrap() == enum_name {
+            found = Some(node);
+            let body = node.child_by_field_name("body").ok_or("Enum has no body")?;
+            let mut body_cursor = body.walk();
+            let mut current_val = 0;
+            for member in body.children(&mut body_cursor) {
+                if member.kind() == "enumerator" {
+                    let name = member.child_by_field_name("name").unwrap().utf8_text(code.as_bytes()).unwrap();
+                    if let Some(val_node) = member.child_by_field_name("value") {
+                        let val_text = val_node.utf8_text(code.as_bytes()).unwrap();
+                        current_val = constants::eval(val_text, &constants).unwrap_or(0);
                     }
+                    members.push(EnumMember {
+                        name: name.to_string(),
+                        value: current_val,
+                        line: member.start_position().row + 1,
+                    });
+                    current_val += 1;
                 }
             }
         }
     }
 
-    if !found {
+    if found.is_none() {
         let td_query_str = r#"
             (type_definition
                 type: (enum_specifier
@@ -413,8 +794,7 @@ pub fn analyze_enum(path: &PathBuf, enum_name: &str) -> Result<EnumLayout, Strin
                         
                         if let Some(value_node) = child.child_by_field_name("value") {
                             let value_text = value_node.utf8_text(code.as_bytes()).unwrap();
-                            let val = value_text.parse::<i64>().unwrap_or(current_value);
-                            current_value = val;
+                            current_value = constants::eval(value_text, &constants).unwrap_or(current_value);
                         }
 
                         members.push(EnumMember {
@@ -426,19 +806,410 @@ pub fn analyze_enum(path: &PathBuf, enum_name: &str) -> Result<EnumLayout, Strin
                         current_value += 1;
                     }
                 }
-                found = true;
+                found = list_node.parent();
                 break;
             }
         }
     }
 
-    if !found {
+    let Some(enum_node) = found else {
         return Err(format!("Enum '{}' not found in {}", enum_name, path.display()));
-    }
+    };
 
     Ok(EnumLayout {
         name: enum_name.to_string(),
+        repr: Some(c_enum_repr(enum_node, &code, &members)),
         members,
         file_path: path.to_string_lossy().to_string(),
     })
 }
+
+/// Bytes a C enum's values take: its C23 underlying type's size; with
+/// `__attribute__((packed))` (on it or its typedef), the fewest bytes that
+/// hold every value, as gcc does; otherwise an `int`, or 8 bytes for values
+/// past 32 bits
+fn c_enum_repr(enum_node: tree_sitter::Node, code: &str, members: &[EnumMember]) -> usize {
+    if let Some((size, _)) = enum_node.child_by_field_name("underlying_type").and_then(|t| c_primitive(t.utf8_text(code.as_bytes()).unwrap(), Target::default())) {
+        return size;
+    }
+    let (min, max) = (members.iter().map(|m| m.value).min().unwrap_or(0), members.iter().map(|m| m.value).max().unwrap_or(0));
+    let fits = |bytes: u32| {
+        let bits = 8 * bytes;
+        if min < 0 { min >= -(1i64 << (bits - 1)) && max < 1i64 << (bits - 1) } else { max < 1i64 << bits }
+    };
+    // The attribute is written around the body, not in it
+    let outer = enum_node.parent().filter(|p| matches!(p.kind(), "type_definition" | "declaration")).unwrap_or(enum_node);
+    let around = match enum_node.child_by_field_name("body") {
+        Some(body) => format!("{} {}", &code[outer.start_byte()..body.start_byte()], &code[body.end_byte()..outer.end_byte()]),
+        None => code[outer.byte_range()].to_string(),
+    };
+    if around.contains("__attribute__") && around.contains("packed") {
+        return [1, 2, 4].into_iter().find(|&bytes| fits(bytes)).unwrap_or(8) as usize;
+    }
+    if fits(4) { 4 } else { 8 }
+}
+
+/// A Rust enum's variants with their discriminants, each one past the one
+/// before unless given, and the width its `#[repr(...)]` sets
+fn analyze_rust_enum(enum_name: &str, code: &str, root_node: tree_sitter::Node, file_path: String) -> Result<EnumLayout, String> {
+    let text = |n: tree_sitter::Node| n.utf8_text(code.as_bytes()).unwrap();
+    let mut stack = vec![root_node];
+    while let Some(node) = stack.pop() {
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor).collect::<Vec<_>>().into_iter().rev());
+        if node.kind() != "enum_item" || node.child_by_field_name("name").map(text) != Some(enum_name) {
+            continue;
+        }
+
+        let constants = constants::collect_rust(code, root_node);
+        let mut members = Vec::new();
+        let mut next = 0;
+        if let Some(body) = node.child_by_field_name("body") {
+            let mut body_cursor = body.walk();
+            for variant in body.named_children(&mut body_cursor).filter(|n| n.kind() == "enum_variant") {
+                let Some(name) = variant.child_by_field_name("name") else { continue };
+                if let Some(value) = variant.child_by_field_name("value") {
+                    next = constants::eval(text(value), &constants)
+                        .ok_or_else(|| format!("Could not resolve the discriminant '{}' of {}::{} (line {})", text(value), enum_name, text(name), name.start_position().row + 1))?;
+                }
+                members.push(EnumMember { name: text(name).to_string(), value: next, line: name.start_position().row + 1 });
+                next += 1;
+            }
+        }
+        return Ok(EnumLayout { name: enum_name.to_string(), members, file_path, repr: rust_enum_repr(node, code) });
+    }
+    Err(format!("Rust enum '{}' not found", enum_name))
+}
+
+/// Bytes a Rust enum's discriminant takes, from `#[repr(u8)]`,
+/// `#[repr(C, u16)]` or `#[repr(C)]` (an `int`); `None` without a `repr`
+fn rust_enum_repr(item: tree_sitter::Node, code: &str) -> Option<usize> {
+    let mut repr = None;
+    let mut prev = item.prev_sibling();
+    while let Some(node) = prev {
+        match node.kind() {
+            "attribute_item" => {
+                let text: String = node.utf8_text(code.as_bytes()).unwrap_or("").split_whitespace().collect();
+                if let Some(args) = text.strip_prefix("#[repr(").and_then(|r| r.strip_suffix(")]")) {
+                    for arg in args.split(',') {
+                        repr = match arg {
+                            "C" => repr.or(Some(4)),
+                            "u8" | "i8" => Some(1),
+                            "u16" | "i16" => Some(2),
+                            "u32" | "i32" => Some(4),
+                            "u64" | "i64" | "usize" | "isize" => Some(8),
+                            _ => repr,
+                        };
+                    }
+                }
+            }
+            "line_comment" | "block_comment" => {}
+            _ => break,
+        }
+        prev = node.prev_sibling();
+    }
+    repr
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::fixture;
+
+    fn offsets(layout: &StructLayout) -> Vec<usize> {
+        layout.fields.iter().map(|f| f.offset).collect()
+    }
+
+    // Expected sizes and offsets are what gcc reports on x86_64 for
+    // test_packed.h (sizeof/offsetof), also noted in the header
+    #[test]
+    fn test_enum_values_and_widths() {
+        let values = |layout: &EnumLayout| layout.members.iter().map(|m| (m.name.clone(), m.value, m.line)).collect::<Vec<_>>();
+        let member = |name: &str, value, line| (name.to_string(), value, line);
+
+        let c = fixture("test_enum_repr.h");
+        let cmd = analyze_enum(&c, "CmdType").unwrap();
+        assert_eq!(values(&cmd), [member("CMD_REFRESH", 1, 6), member("CMD_SET_INTERVAL", 2, 7), member("CMD_SHUTDOWN", 3, 8)]);
+        assert_eq!(cmd.repr, Some(1), "packed");
+        assert_eq!(analyze_enum(&c, "Channel").unwrap().repr, Some(4), "an int");
+
+        let rust = fixture("test_enum_repr.rs");
+        let cmd = analyze_enum(&rust, "CmdType").unwrap();
+        assert_eq!(values(&cmd), [member("Refresh", 1, 6), member("SetInterval", 2, 7), member("Shutdown", 3, 8)]);
+        assert_eq!(cmd.repr, Some(1));
+        assert_eq!(analyze_enum(&rust, "Level").unwrap().repr, Some(2));
+        let channel = analyze_enum(&rust, "Channel").unwrap();
+        assert_eq!((channel.members[2].value, channel.repr), (19, Some(4)), "a constant's value, then one past it");
+        let plain = analyze_enum(&rust, "Plain").unwrap();
+        assert_eq!((plain.members[1].value, plain.repr), (5, None));
+
+        assert!(enums_supported(&rust));
+        assert_eq!(declared_types(&rust).unwrap().enums, ["CmdType", "Level", "Channel", "Plain"]);
+    }
+
+    #[test]
+    fn test_c_packing() {
+        let path = fixture("test_packed.h");
+
+        let plain = analyze_file(&path, "PlainStruct").unwrap();
+        assert_eq!((plain.total_size, plain.packing), (32, None));
+        assert_eq!(offsets(&plain), [0, 4, 8, 16, 24]);
+
+        let packed = analyze_file(&path, "PackedStruct").unwrap();
+        assert_eq!((packed.total_size, packed.packing), (16, Some(1)));
+        assert_eq!(offsets(&packed), [0, 1, 5, 7, 15]);
+
+        let pack4 = analyze_file(&path, "Pack4Struct").unwrap();
+        assert_eq!((pack4.total_size, pack4.packing), (24, Some(4)));
+        assert_eq!(offsets(&pack4), [0, 4, 8, 12, 20]);
+
+        let after_pop = analyze_file(&path, "AfterPopStruct").unwrap();
+        assert_eq!((after_pop.total_size, after_pop.packing), (16, None));
+        assert_eq!(offsets(&after_pop), [0, 8]);
+    }
+
+    #[test]
+    fn test_rust_packing() {
+        let path = fixture("test_packed.rs");
+
+        let packed = analyze_file(&path, "PackedStruct").unwrap();
+        assert_eq!((packed.total_size, packed.packing), (16, Some(1)));
+        assert_eq!(offsets(&packed), [0, 1, 5, 7, 15]);
+
+        let pack4 = analyze_file(&path, "Pack4Struct").unwrap();
+        assert_eq!((pack4.total_size, pack4.packing), (24, Some(4)));
+        assert_eq!(offsets(&pack4), [0, 4, 8, 12, 20]);
+
+        let padding = analyze_file(&fixture("test_padding.h"), "PaddingStruct").unwrap();
+        assert_eq!((padding.total_size, padding.packing), (32, None));
+    }
+
+    // Sizes gcc reports on x86_64 for test_constants.h, also noted in the header
+    #[test]
+    fn test_array_sizes_from_constants() {
+        let path = fixture("test_constants.h");
+
+        let by_constant = analyze_file(&path, "ConstSizes").unwrap();
+        let by_literal = analyze_file(&path, "LiteralSizes").unwrap();
+        assert_eq!(by_constant.total_size, 104);
+        assert_eq!(by_constant.total_size, by_literal.total_size);
+        assert_eq!(offsets(&by_constant), offsets(&by_literal));
+        assert_eq!(by_constant.fields.iter().map(|f| f.array_len).collect::<Vec<_>>(), [16, 8, 32]);
+        assert!(by_constant.warnings.is_empty(), "{:?}", by_constant.warnings);
+
+        let by_enum = analyze_file(&path, "EnumSizes").unwrap();
+        assert_eq!(by_enum.total_size, 10);
+        assert_eq!(offsets(&by_enum), [0, 6]);
+
+        let rust = analyze_file(&fixture("test_constants.rs"), "ConstSizes").unwrap();
+        assert_eq!(rust.total_size, 104);
+        assert_eq!(offsets(&rust), offsets(&by_literal));
+        assert!(rust.warnings.is_empty(), "{:?}", rust.warnings);
+    }
+
+    #[test]
+    fn test_unresolved_array_size_warns() {
+        let unknown = analyze_file(&fixture("test_constants.h"), "UnknownSize").unwrap();
+        assert_eq!(unknown.total_size, 4);
+        assert_eq!(unknown.warnings.len(), 1);
+        assert!(unknown.warnings[0].contains("'FROM_ANOTHER_HEADER' of field values"), "{}", unknown.warnings[0]);
+    }
+
+    // Offsets gcc reports on x86_64 for test_fam_server.h, also noted in the
+    // header
+    #[test]
+    fn test_flexible_array_members() {
+        let flexible = |layout: &StructLayout| layout.fields.iter().map(|f| (f.offset, f.size, f.is_flexible)).collect::<Vec<_>>();
+
+        let packet = analyze_file(&fixture("test_fam_server.h"), "Packet").unwrap();
+        assert_eq!(packet.total_size, 8);
+        assert_eq!(flexible(&packet), [(0, 4, false), (4, 2, false), (6, 0, true)]);
+        assert!(packet.warnings.is_empty(), "{:?}", packet.warnings);
+
+        // `words[0]`: aligned as a u32 all the same
+        let batch = analyze_file(&fixture("test_fam_server.h"), "Batch").unwrap();
+        assert_eq!(batch.total_size, 4);
+        assert_eq!(flexible(&batch), [(0, 1, false), (1, 1, false), (4, 0, true)]);
+        assert!(batch.warnings.is_empty(), "{:?}", batch.warnings);
+
+        let fixed = analyze_file(&fixture("test_fam_client.h"), "Packet").unwrap();
+        assert_eq!((fixed.total_size, fixed.fields[2].is_flexible), (264, false));
+
+        for name in ["Packet", "Batch"] {
+            let rust = analyze_file(&fixture("test_fam.rs"), name).unwrap();
+            let c = analyze_file(&fixture("test_fam_server.h"), name).unwrap();
+            assert_eq!((rust.total_size, flexible(&rust)), (c.total_size, flexible(&c)));
+            assert!(rust.warnings.is_empty(), "{:?}", rust.warnings);
+        }
+    }
+
+    // Offsets gcc reports on x86_64 for test_nested_server.h, also noted in
+    // the header
+    #[test]
+    fn test_nested_members_and_parents() {
+        let frame = analyze_file(&fixture("test_nested_server.h"), "Frame").unwrap();
+        let at = |name: &str| {
+            let f = frame.fields.iter().find(|f| f.name == name).unwrap_or_else(|| panic!("no field {}", name));
+            (f.offset, f.depth, f.parent.as_deref(), f.line)
+        };
+        assert_eq!(at("hdr"), (0, 0, None, 18));
+        assert_eq!(at("hdr.flags"), (1, 1, Some("hdr"), 8));
+        assert_eq!(at("body.checksum"), (20, 1, Some("body"), 14));
+
+        let message = analyze_file(&fixture("test_union_server.h"), "Message").unwrap();
+        let color = message.fields.iter().find(|f| f.name == "body.paint.color").unwrap();
+        assert_eq!(color.parent.as_deref(), Some("body.paint"));
+    }
+
+    #[test]
+    fn test_nesting_capped_and_cycles() {
+        let deep = analyze_file(&fixture("test_nested_deep.h"), "Deep").unwrap();
+        assert_eq!(deep.total_size, 8);
+        let names: Vec<_> = deep.fields.iter().map(|f| (f.name.as_str(), f.depth)).collect();
+        assert_eq!(names, [("id", 0), ("a", 0), ("a.b", 1), ("a.b.c", 2), ("a.b.c.d", 3), ("a.b.c.d.e", 4)]);
+        assert_eq!(deep.warnings, ["Members of a.b.c.d.e are nested more than 4 deep; it is compared as a whole"]);
+
+        let looped = analyze_file(&fixture("test_nested_deep.h"), "Loop").unwrap();
+        assert_eq!(looped.total_size, 4);
+        assert_eq!(looped.warnings, ["Loop contains itself at next; counted as 0 bytes"]);
+    }
+
+    // Offsets gcc reports on x86_64 for test_union_server.h (offsetof), also
+    // noted in the header
+    #[test]
+    fn test_unions_and_nested_members() {
+        let layout = analyze_file(&fixture("test_union_server.h"), "Message").unwrap();
+        assert_eq!(layout.total_size, 20);
+
+        let at = |name: &str| {
+            let f = layout.fields.iter().find(|f| f.name == name).unwrap_or_else(|| panic!("no field {}", name));
+            (f.offset, f.size, f.depth)
+        };
+        assert_eq!(at("hdr"), (0, 4, 0));
+        assert_eq!(at("hdr.seq"), (2, 2, 1));
+        assert_eq!(at("body"), (4, 8, 0));
+        assert_eq!(at("body.move.y"), (6, 2, 2));
+        assert_eq!(at("body.paint"), (4, 8, 1));
+        assert_eq!(at("body.paint.alpha"), (8, 1, 2));
+        assert_eq!(at("body.raw"), (4, 8, 1));
+        // Anonymous members are promoted: no prefix, at the union's offset
+        assert_eq!(at("<anonymous union>"), (12, 4, 0));
+        assert_eq!(at("value"), (12, 4, 1));
+        assert_eq!(at("hi"), (14, 2, 2));
+        assert_eq!(at("flags"), (16, 1, 0));
+    }
+
+    #[test]
+    fn test_bitfields() {
+        let layout = analyze_file(&fixture("test_bitfields.h"), "DeviceRegs").unwrap();
+        assert_eq!(layout.total_size, 24);
+
+        let at = |layout: &StructLayout, name: &str| {
+            let f = layout.fields.iter().find(|f| f.name == name).unwrap_or_else(|| panic!("no field {}", name));
+            (f.offset, f.bit_offset, f.bit_width)
+        };
+        assert_eq!(at(&layout, "id"), (0, None, None));
+        assert_eq!(at(&layout, "enabled"), (0, Some(8), Some(1)));
+        assert_eq!(at(&layout, "mode"), (0, Some(9), Some(3)));
+        assert_eq!(at(&layout, "level"), (0, Some(12), Some(20)));
+        assert_eq!(at(&layout, "overflow"), (4, Some(0), Some(4)));
+        assert_eq!(at(&layout, "channel"), (6, None, None));
+        assert_eq!(at(&layout, "small"), (8, Some(0), Some(3)));
+        assert_eq!(at(&layout, "after_zero"), (9, Some(0), Some(2)));
+        assert_eq!(at(&layout, "wide"), (10, Some(0), Some(9)));
+        assert_eq!(at(&layout, "stamp"), (16, None, None));
+        // Unnamed bitfields take space but aren't fields
+        assert_eq!(layout.fields.len(), 10);
+
+        let packed = analyze_file(&fixture("test_bitfields.h"), "PackedRegs").unwrap();
+        assert_eq!(packed.total_size, 7);
+        assert_eq!(at(&packed, "enabled"), (1, Some(0), Some(1)));
+        assert_eq!(at(&packed, "level"), (1, Some(4), Some(20)));
+        assert_eq!(at(&packed, "overflow"), (4, Some(0), Some(4)));
+        assert_eq!(at(&packed, "channel"), (5, None, None));
+    }
+
+    // Offsets are what g++ reports on x86_64 for test_cpp.hpp
+    #[test]
+    fn test_cpp_classes_and_std_array() {
+        let sample = analyze_file(&fixture("test_cpp.hpp"), "detail::Sample").unwrap();
+        assert_eq!(sample.total_size, 40);
+        // Statics, nested types and member functions take no space
+        let names: Vec<&str> = sample.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["kind", "raw", "stamp", "valid"]);
+        assert_eq!(offsets(&sample), [0, 2, 24, 32]);
+        // `std::array<uint16_t, MAX_SENSORS * 2>` with a constexpr size
+        assert_eq!((sample.fields[1].array_len, sample.fields[1].size), (8, 16));
+
+        let frame = analyze_file(&fixture("test_cpp.hpp"), "telemetry::Frame").unwrap();
+        assert_eq!(frame.total_size, 57);
+        assert_eq!(frame.packing, Some(1));
+        let at = |name: &str| frame.fields.iter().find(|f| f.name == name).map(|f| (f.offset, f.size));
+        assert_eq!(at("sample"), Some((1, 40)));
+        assert_eq!(at("sample.stamp"), Some((25, 8)));
+        assert_eq!(at("temps"), Some((41, 16)));
+        assert!(frame.warnings.is_empty(), "{:?}", frame.warnings);
+
+        // A bare name finds the struct in whatever namespace it's in
+        assert_eq!(analyze_file(&fixture("test_cpp.hpp"), "Frame").unwrap().total_size, 57);
+        assert!(analyze_file(&fixture("test_cpp.hpp"), "other::Frame").is_err());
+    }
+
+    // Offsets per gc on amd64, as noted in test_go.go
+    #[test]
+    fn test_go_memory_and_wire_layouts() {
+        let reading = analyze_file(&fixture("test_go.go"), "Reading").unwrap();
+        assert_eq!(reading.total_size, 104);
+        assert_eq!(reading.wire, None);
+        assert_eq!(offsets(&reading), [0, 2, 8, 56, 72, 96, 100]);
+        // Embedded field named after its type; `[Slots * 2]` from a const
+        assert_eq!(reading.fields[1].name, "Header");
+        assert_eq!((reading.fields[2].array_len, reading.fields[2].size), (6, 48));
+
+        // Serialized by hand: the decoder's offsets, packed
+        let packet = analyze_file(&fixture("test_go.go"), "Packet").unwrap();
+        assert_eq!(packet.wire.as_deref(), Some("PacketFromBytes"));
+        assert_eq!((packet.total_size, packet.packing), (6, Some(1)));
+        assert_eq!(offsets(&packet), [0, 2]);
+        assert_eq!(packet.warnings, [
+            "ToBytes puts Count at offset 1 but PacketFromBytes reads it at 2",
+            "Field Spare isn't read by PacketFromBytes; left out of the wire layout",
+        ]);
+    }
+
+    #[test]
+    fn test_target_widths() {
+        let header = fixture("test_target.h");
+        let le64 = analyze_file(&header, "Counters").unwrap();
+        assert_eq!((le64.total_size, offsets(&le64)), (40, vec![0, 8, 16, 18, 26, 32]));
+        let be32 = analyze_file_for(&header, "Counters", "be32".parse().unwrap()).unwrap();
+        assert_eq!((be32.total_size, offsets(&be32)), (24, vec![0, 4, 8, 10, 18, 20]));
+        assert_eq!(be32.target, Target { big_endian: true, pointer_size: 4 });
+
+        assert_eq!("le32".parse::<Target>().unwrap().to_string(), "le32");
+        assert_eq!("x86".parse::<Target>().unwrap_err(), "'x86' is not a target: use le64, le32, be64 or be32");
+    }
+
+    #[test]
+    fn test_zig_extern_and_packed_structs() {
+        let sample = analyze_file(&fixture("test_zig.zig"), "Sample").unwrap();
+        assert_eq!(sample.total_size, 40);
+        assert_eq!(offsets(&sample), [0, 4, 8, 24, 32]);
+        assert_eq!(sample.fields[2].array_len, 4);
+        assert!(sample.fields[4].is_pointer);
+
+        let flags = analyze_file(&fixture("test_zig.zig"), "Flags").unwrap();
+        assert_eq!((flags.total_size, flags.packing), (4, Some(1)));
+        let bits: Vec<_> = flags.fields.iter().map(|f| (f.offset, f.bit_offset, f.bit_width)).collect();
+        assert_eq!(bits, [
+            (0, Some(0), Some(1)),
+            (0, Some(1), Some(2)),
+            (0, Some(3), Some(5)),
+            (1, None, None),
+            (2, Some(0), Some(12)),
+        ]);
+
+        let loose = analyze_file(&fixture("test_zig.zig"), "Loose").unwrap();
+        assert_eq!(loose.warnings.len(), 1, "{:?}", loose.warnings);
+    }
+}
@@ -1,3 +1,14 @@
 pub mod layout;
 pub mod engine;
 pub mod overflow;
+pub mod constants;
+pub mod go;
+pub mod zig;
+pub mod wire;
+pub mod dart;
+pub mod python;
+pub mod compare;
+mod cfg;
+pub mod project;
+pub mod reorder;
+pub mod handshake;
@@ -2,7 +2,17 @@ use std::fs;
 use std::path::PathBuf;
 use tree_sitter::{Parser as TSParser, Query, QueryCursor, Node};
 use streaming_iterator::StreamingIterator;
+use std::collections::{HashMap, HashSet};
 use crate::models::{MemoryEvent, MemoryEventKind};
+use super::constants::{self, Constants};
+use super::layout::array_len;
+
+/// Functions that write into their first argument, and which argument says
+/// how many bytes
+const BOUNDED: &[(&str, usize)] = &[("memcpy", 2), ("memmove", 2), ("memset", 2), ("strncpy", 2), ("snprintf", 1), ("vsnprintf", 1), ("fgets", 1)];
+
+/// Functions that write into their first argument as much as their input holds
+const UNBOUNDED: &[&str] = &["strcpy", "strcat", "sprintf", "vsprintf", "gets"];
 
 pub fn check_overflows(path: PathBuf) -> Result<Vec<MemoryEvent>, String> {
     let code = fs::read_to_string(&path).map_err(|e| format!("Could not read file {}: {}", path.display(), e))?;
@@ -12,6 +22,7 @@ pub fn check_overflows(path: PathBuf) -> Result<Vec<MemoryEvent>, String> {
 
     let tree = parser.parse(&code, None).expect("Failed to parse code");
     let root_node = tree.root_node();
+    let constants = constants::collect_c(&code, root_node);
 
     let mut events = Vec::new();
 
@@ -31,25 +42,41 @@ pub fn check_overflows(path: PathBuf) -> Result<Vec<MemoryEvent>, String> {
         let func_name = m.captures[0].node.utf8_text(code.as_bytes()).unwrap();
         let body_node = m.captures[1].node;
 
-        let mut arrays = std::collections::HashMap::new();
+        let mut arrays = HashMap::new();
+        // Bytes of the arrays whose element type has a known size
+        let mut bytes = HashMap::new();
 
-        // 1. Find fixed-size arrays
+        // 1. Find fixed-size arrays, sized by literals or `#define`s
         let decl_query_str = r#"
             (declaration
-                declarator: (array_declarator
-                    declarator: (identifier) @name
-                    size: (number_literal) @size
-                )
+                type: (_) @type
+                declarator: [
+                    (array_declarator
+                        declarator: (identifier) @name
+                        size: (_) @size
+                    )
+                    (init_declarator
+                        declarator: (array_declarator
+                            declarator: (identifier) @name
+                            size: (_) @size
+                        )
+                    )
+                ]
             )
         "#;
         let decl_query = Query::new(&language.into(), decl_query_str).unwrap();
+        let capture = |name| decl_query.capture_index_for_name(name).unwrap();
+        let (type_index, name_index, size_index) = (capture("type"), capture("name"), capture("size"));
         let mut decl_cursor = QueryCursor::new();
         let mut decl_matches = decl_cursor.matches(&decl_query, body_node, code.as_bytes());
 
         while let Some(dm) = decl_matches.next() {
-            let name = dm.captures[0].node.utf8_text(code.as_bytes()).unwrap().to_string();
-            let size_str = dm.captures[1].node.utf8_text(code.as_bytes()).unwrap();
-            if let Ok(size) = size_str.parse::<usize>() {
+            let text = |index| dm.captures.iter().find(|c| c.index == index).map(|c| c.node.utf8_text(code.as_bytes()).unwrap()).unwrap_or("");
+            let name = text(name_index).to_string();
+            if let Some(size) = array_len(text(size_index), &constants) {
+                if let Some(width) = element_size(text(type_index)) {
+                    bytes.insert(name.clone(), size * width);
+                }
                 arrays.insert(name, size);
             }
         }
@@ -134,9 +161,6 @@ pub fn check_overflows(path: PathBuf) -> Result<Vec<MemoryEvent>, String> {
 
             if let Some(be) = binary_expr {
                 let mut be_cursor = be.walk();
-                for child in be.children(&mut be_cursor) {
-                }
-                be_cursor = be.walk();
                 let mut var_name = None;
                 let mut op = None;
                 let mut val = None;
@@ -175,24 +199,308 @@ pub fn check_overflows(path: PathBuf) -> Result<Vec<MemoryEvent>, String> {
             let index_str = am.captures[1].node.utf8_text(code.as_bytes()).unwrap();
             let line = am.captures[1].node.start_position().row + 1;
 
-            if let Some(&size) = arrays.get(&name) {
-                if let Ok(index) = index_str.parse::<usize>() {
-                    if index >= size {
-                        events.push(MemoryEvent {
+            if let Some(&size) = arrays.get(&name)
+                && let Ok(index) = index_str.parse::<usize>()
+                && index >= size {
+                events.push(MemoryEvent {
+                    kind: MemoryEventKind::BufferOverflow,
+                    variable: name.clone(),
+                    line,
+                    context: format!("CRITICAL: Buffer Overflow in {}. Accessing {}[{}] but size is {}", func_name, name, index, size),
+                });
+            }
+        }
+
+        // 5. Indexes and lengths held in variables, and copies into fixed
+        // arrays: a length larger than the array, or no length at all
+        let mut propagation = Propagation {
+            code: code.as_bytes(),
+            func_name,
+            arrays: &arrays,
+            bytes: &bytes,
+            escaped: address_taken(body_node, code.as_bytes()),
+            assigned: assigned(body_node, code.as_bytes()),
+            events: Vec::new(),
+        };
+        propagation.statement(body_node, &mut constants.clone());
+        events.extend(propagation.events);
+    }
+
+    Ok(events)
+}
+
+/// Bytes of one element of a C primitive type; `None` for the rest
+fn element_size(type_name: &str) -> Option<usize> {
+    match type_name {
+        "char" | "signed char" | "unsigned char" | "int8_t" | "uint8_t" | "bool" => Some(1),
+        "short" | "unsigned short" | "int16_t" | "uint16_t" => Some(2),
+        "int" | "unsigned" | "unsigned int" | "int32_t" | "uint32_t" | "float" => Some(4),
+        "long" | "unsigned long" | "long long" | "unsigned long long" | "int64_t" | "uint64_t" | "double" | "size_t" | "uintptr_t" => Some(8),
+        _ => None,
+    }
+}
+
+/// Value of an index or length: literals, `#define`s and variables known
+/// to hold a constant, `sizeof` an array or primitive type of the function,
+/// and arithmetic on them
+fn value(node: Node, code: &[u8], known: &Constants, bytes: &HashMap<String, usize>) -> Option<i64> {
+    let text = node.utf8_text(code).ok()?;
+    match node.kind() {
+        "parenthesized_expression" => value(node.named_child(0)?, code, known, bytes),
+        "sizeof_expression" => {
+            if let Some(ty) = node.child_by_field_name("type") {
+                return element_size(ty.utf8_text(code).ok()?).and_then(|n| i64::try_from(n).ok());
+            }
+            let operand = node.child_by_field_name("value")?;
+            let operand = if operand.kind() == "parenthesized_expression" { operand.named_child(0)? } else { operand };
+            bytes.get(operand.utf8_text(code).ok()?).and_then(|&n| i64::try_from(n).ok())
+        }
+        "binary_expression" => {
+            let left = value(node.child_by_field_name("left")?, code, known, bytes)?;
+            let right = value(node.child_by_field_name("right")?, code, known, bytes)?;
+            match node.child_by_field_name("operator")?.kind() {
+                "+" => left.checked_add(right),
+                "-" => left.checked_sub(right),
+                "*" => left.checked_mul(right),
+                "/" => left.checked_div(right),
+                "%" => left.checked_rem(right),
+                _ => None,
+            }
+        }
+        _ => constants::eval(text, known),
+    }
+}
+
+/// Straight-line constant propagation through one function body: the
+/// constant an integer variable holds where it's used as an index or a
+/// length. Branches keep what they agree on; a loop forgets what it
+/// assigns, a label everything assigned anywhere, since a `goto` can come
+/// back to it.
+struct Propagation<'a> {
+    code: &'a [u8],
+    func_name: &'a str,
+    arrays: &'a HashMap<String, usize>,
+    bytes: &'a HashMap<String, usize>,
+    /// Variables whose address is taken: anything may change them
+    escaped: HashSet<String>,
+    assigned: HashSet<String>,
+    events: Vec<MemoryEvent>,
+}
+
+impl Propagation<'_> {
+    fn statement(&mut self, node: Node, known: &mut Constants) {
+        let field = |name: &str| node.child_by_field_name(name);
+        match node.kind() {
+            "compound_statement" => {
+                let mut cursor = node.walk();
+                let children: Vec<_> = node.named_children(&mut cursor).collect();
+                for child in children {
+                    self.statement(child, known);
+                }
+            }
+            "if_statement" => {
+                if let Some(cond) = field("condition") {
+                    self.expression(cond, known);
+                }
+                let mut then = known.clone();
+                if let Some(consequence) = field("consequence") {
+                    self.statement(consequence, &mut then);
+                }
+                if let Some(alternative) = field("alternative") {
+                    self.statement(alternative, known);
+                }
+                known.retain(|name, v| then.get(name) == Some(v));
+            }
+            "else_clause" => {
+                if let Some(inner) = node.named_child(0) {
+                    self.statement(inner, known);
+                }
+            }
+            "for_statement" | "while_statement" | "do_statement" | "switch_statement" => {
+                let changed = assigned(node, self.code);
+                known.retain(|name, _| !changed.contains(name));
+                let mut inside = known.clone();
+                let mut cursor = node.walk();
+                let children: Vec<_> = node.named_children(&mut cursor).collect();
+                for child in children {
+                    if child.kind().ends_with("statement") {
+                        self.statement(child, &mut inside);
+                    } else {
+                        self.expression(child, &inside);
+                    }
+                }
+            }
+            "labeled_statement" | "case_statement" => {
+                if node.kind() == "labeled_statement" {
+                    known.retain(|name, _| !self.assigned.contains(name));
+                }
+                let mut cursor = node.walk();
+                let children: Vec<_> = node.named_children(&mut cursor).filter(|c| c.kind().ends_with("statement") || c.kind() == "declaration").collect();
+                for child in children {
+                    self.statement(child, known);
+                }
+            }
+            _ => {
+                self.expression(node, known);
+                self.assign(node, known);
+            }
+        }
+    }
+
+    /// Indexes and copies in `node`, with the values known before it runs
+    fn expression(&mut self, node: Node, known: &Constants) {
+        let code = self.code;
+        for n in descendants(node) {
+            match n.kind() {
+                "subscript_expression" => {
+                    let (Some(array), Some(index)) = (n.child_by_field_name("argument"), n.child_by_field_name("index")) else { continue };
+                    // Literal indexes are checked on their own
+                    if index.kind() == "number_literal" {
+                        continue;
+                    }
+                    let name = array.utf8_text(code).unwrap_or("");
+                    let Some(&size) = self.arrays.get(name) else { continue };
+                    if let Some(i) = value(index, code, known, self.bytes).and_then(|i| usize::try_from(i).ok())
+                        && i >= size {
+                        self.events.push(MemoryEvent {
                             kind: MemoryEventKind::BufferOverflow,
-                            variable: name.clone(),
-                            line,
-                            context: format!("CRITICAL: Buffer Overflow in {}. Accessing {}[{}] but size is {}", func_name, name, index, size),
+                            variable: name.to_string(),
+                            line: n.start_position().row + 1,
+                            context: format!("CRITICAL: Buffer Overflow in {}. Accessing {}[{}] ({}) but size is {}", self.func_name, name, index.utf8_text(code).unwrap_or(""), i, size),
                         });
                     }
                 }
+                "call_expression" => {
+                    if let Some(event) = self.copy(n, known) {
+                        self.events.push(event);
+                    }
+                }
+                _ => {}
             }
         }
     }
 
-    Ok(events)
+    /// A copy into a fixed array past its end, or without a length
+    fn copy(&self, call: Node, known: &Constants) -> Option<MemoryEvent> {
+        let code = self.code;
+        let func = call.child_by_field_name("function").filter(|f| f.kind() == "identifier")?.utf8_text(code).ok()?;
+        let arguments = call.child_by_field_name("arguments")?;
+        let mut cursor = arguments.walk();
+        let args: Vec<Node> = arguments.named_children(&mut cursor).filter(|n| n.kind() != "comment").collect();
+        let dst = args.first().filter(|d| d.kind() == "identifier")?.utf8_text(code).ok()?;
+        let &size = self.bytes.get(dst)?;
+
+        let context = if let Some(&(_, len_arg)) = BOUNDED.iter().find(|(name, _)| *name == func) {
+            let len = value(*args.get(len_arg)?, code, known, self.bytes).and_then(|n| usize::try_from(n).ok())?;
+            if len <= size {
+                return None;
+            }
+            format!("CRITICAL: Buffer Overflow in {}. {} writes up to {} bytes into {} but its size is {}", self.func_name, func, len, dst, size)
+        } else if UNBOUNDED.contains(&func) {
+            // A literal source that can't fit is certain
+            let literal = args.get(1).filter(|src| func == "strcpy" && src.kind() == "string_literal").and_then(|src| string_len(*src, code));
+            match literal {
+                Some(len) if len + 1 > size => format!("CRITICAL: Buffer Overflow in {}. {} writes {} bytes into {} but its size is {}", self.func_name, func, len + 1, dst, size),
+                _ => format!("⚠️  Warning: unbounded copy into fixed buffer in {}. {} into {} ({} bytes) doesn't limit the length", self.func_name, func, dst, size),
+            }
+        } else {
+            return None;
+        };
+        Some(MemoryEvent {
+            kind: MemoryEventKind::BufferOverflow,
+            variable: dst.to_string(),
+            line: call.start_position().row + 1,
+            context,
+        })
+    }
+
+    /// What `node` leaves in its variables: `i = 10`, `int i = SIZE`, `n = 2 * i`.
+    /// `i += 1`, `i++` and values that aren't constant forget them.
+    fn assign(&self, node: Node, known: &mut Constants) {
+        let code = self.code;
+        for n in descendants(node) {
+            let (target, source) = match n.kind() {
+                "init_declarator" => (n.child_by_field_name("declarator"), n.child_by_field_name("value")),
+                "assignment_expression" => {
+                    let plain = n.child_by_field_name("operator").is_some_and(|op| op.kind() == "=");
+                    (n.child_by_field_name("left"), n.child_by_field_name("right").filter(|_| plain))
+                }
+                "update_expression" => (n.child_by_field_name("argument"), None),
+                "declaration" => {
+                    let mut cursor = n.walk();
+                    for declarator in n.children_by_field_name("declarator", &mut cursor).filter(|d| d.kind() == "identifier") {
+                        known.remove(declarator.utf8_text(code).unwrap_or(""));
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+            let Some(name) = target.filter(|t| t.kind() == "identifier").and_then(|t| t.utf8_text(code).ok()) else { continue };
+            match source.and_then(|s| value(s, code, known, self.bytes)) {
+                Some(v) if !self.escaped.contains(name) => {
+                    known.insert(name.to_string(), v);
+                }
+                _ => {
+                    known.remove(name);
+                }
+            }
+        }
+    }
 }
 
+/// Variables `node` assigns, increments or declares
+fn assigned(node: Node, code: &[u8]) -> HashSet<String> {
+    descendants(node)
+        .into_iter()
+        .filter_map(|n| match n.kind() {
+            "init_declarator" => n.child_by_field_name("declarator"),
+            "assignment_expression" => n.child_by_field_name("left"),
+            "update_expression" => n.child_by_field_name("argument"),
+            _ => None,
+        })
+        .filter(|t| t.kind() == "identifier")
+        .filter_map(|t| t.utf8_text(code).ok().map(String::from))
+        .collect()
+}
+
+/// Variables whose address is taken anywhere in `node`
+fn address_taken(node: Node, code: &[u8]) -> HashSet<String> {
+    descendants(node)
+        .into_iter()
+        .filter(|n| n.kind() == "pointer_expression" && n.child_by_field_name("operator").is_some_and(|op| op.kind() == "&"))
+        .filter_map(|n| n.child_by_field_name("argument").filter(|a| a.kind() == "identifier"))
+        .filter_map(|a| a.utf8_text(code).ok().map(String::from))
+        .collect()
+}
+
+/// `node` and everything under it, in source order
+fn descendants(node: Node) -> Vec<Node> {
+    let mut nodes = Vec::new();
+    let mut stack = vec![node];
+    while let Some(n) = stack.pop() {
+        nodes.push(n);
+        let mut cursor = n.walk();
+        let children: Vec<_> = n.named_children(&mut cursor).collect();
+        stack.extend(children.into_iter().rev());
+    }
+    nodes
+}
+
+/// Characters a plain string literal holds, escapes counted once
+fn string_len(literal: Node, code: &[u8]) -> Option<usize> {
+    let mut cursor = literal.walk();
+    let mut len = 0;
+    for part in literal.named_children(&mut cursor) {
+        len += match part.kind() {
+            "string_content" => part.utf8_text(code).ok()?.len(),
+            "escape_sequence" => 1,
+            _ => return None,
+        };
+    }
+    Some(len)
+}
+
+#[allow(clippy::too_many_arguments)]
 fn check_block_for_overflows(
     node: Node, 
     var_name: &str, 
@@ -215,35 +523,63 @@ fn check_block_for_overflows(
     let mut matches = cursor.matches(&query, node, code);
 
     while let Some(m) = matches.next() {
-        let subscript_node = m.captures[0].node.parent().unwrap();
-        let mut sc = subscript_node.walk();
-        for child in subscript_node.children(&mut sc) {
-        }
         let arr_name = m.captures[0].node.utf8_text(code).unwrap().to_string();
         let idx_name = m.captures[1].node.utf8_text(code).unwrap();
         let line = m.captures[1].node.start_position().row + 1;
 
-        if idx_name == var_name {
-            if let Some(&arr_size) = arrays.get(&arr_name) {
-                // Deduce if 'op val' guarantees an overflow
-                // e.g. if we know idx >= 5 and arr_size is 5, then it's an overflow.
-                let is_overflow = match op {
-                    ">=" => val >= arr_size,
-                    ">" => val >= arr_size - 1,
-                    "==" => val >= arr_size,
-                    "<=" => val >= arr_size,
-                    _ => false, // We favor false negatives over false positives for now
-                };
+        if idx_name == var_name
+            && let Some(&arr_size) = arrays.get(&arr_name) {
+            // Deduce if 'op val' guarantees an overflow
+            // e.g. if we know idx >= 5 and arr_size is 5, then it's an overflow.
+            let is_overflow = match op {
+                ">=" => val >= arr_size,
+                ">" => val >= arr_size - 1,
+                "==" => val >= arr_size,
+                "<=" => val >= arr_size,
+                _ => false, // We favor false negatives over false positives for now
+            };
 
-                if is_overflow {
-                    events.push(MemoryEvent {
-                        kind: MemoryEventKind::BufferOverflow,
-                        variable: arr_name.clone(),
-                        line,
-                        context: format!("CRITICAL: Deductive Overflow in {}. Path constraint '{} {} {}' violates {} size {}", func_name, var_name, op, val, arr_name, arr_size),
-                    });
-                }
+            if is_overflow {
+                events.push(MemoryEvent {
+                    kind: MemoryEventKind::BufferOverflow,
+                    variable: arr_name.clone(),
+                    line,
+                    context: format!("CRITICAL: Deductive Overflow in {}. Path constraint '{} {} {}' violates {} size {}", func_name, var_name, op, val, arr_name, arr_size),
+                });
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::fixture;
+
+    // copy_overflow_test.c calls each function once within the buffer and
+    // once past it; the unbounded ones are flagged either way
+    #[test]
+    fn test_copy_overflows() {
+        let events = check_overflows(fixture("copy_overflow_test.c")).unwrap();
+        let found: Vec<_> = events.iter().map(|e| (e.line, e.variable.as_str())).collect();
+        assert_eq!(found, vec![(11, "header"), (13, "header"), (19, "counters"), (26, "name"), (27, "name"), (28, "greeting"), (29, "name"), (35, "line"), (36, "line")]);
+        assert!(events.iter().all(|e| e.kind == MemoryEventKind::BufferOverflow));
+
+        assert_eq!(events[0].context, "CRITICAL: Buffer Overflow in copy_packet. memcpy writes up to 64 bytes into header but its size is 32");
+        assert_eq!(events[4].context, "⚠️  Warning: unbounded copy into fixed buffer in copy_name. strcpy into name (16 bytes) doesn't limit the length");
+        assert_eq!(events[5].context, "CRITICAL: Buffer Overflow in copy_name. strcpy writes 13 bytes into greeting but its size is 8");
+    }
+
+    // const_index_test.c indexes with variables holding constants, ones
+    // changed by calls, `+=`, branches and loops, and a length kept in one
+    #[test]
+    fn test_constant_indexes() {
+        let events = check_overflows(fixture("const_index_test.c")).unwrap();
+        let found: Vec<_> = events.iter().map(|e| (e.line, e.variable.as_str())).collect();
+        assert_eq!(found, vec![(11, "buf"), (27, "buf"), (47, "buf")]);
+
+        assert_eq!(events[0].context, "CRITICAL: Buffer Overflow in constant_index. Accessing buf[i] (10) but size is 8");
+        assert_eq!(events[1].context, "CRITICAL: Buffer Overflow in index_from_define. Accessing buf[i] (8) but size is 8");
+        assert_eq!(events[2].context, "CRITICAL: Buffer Overflow in branch_index. memset writes up to 16 bytes into buf but its size is 8");
+    }
+}
@@ -0,0 +1,135 @@
+//! `--check-leaks` over a directory: every `.c` and `.h` file under it,
+//! analyzed in parallel and reported in path order
+
+use std::path::{Path, PathBuf};
+use rayon::prelude::*;
+use crate::models::{AnalysisError, ProjectReport, Severity, Totals};
+
+/// Extensions of the files a directory's analysis covers
+const SOURCE_EXTENSIONS: &[&str] = &["c", "h"];
+
+/// The C sources under `dir`, sorted, leaving out what an `exclude` glob
+/// matches
+pub fn source_files(dir: &Path, exclude: &[String]) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    walk(dir, dir, exclude, &mut files)?;
+    files.sort();
+    Ok(files)
+}
+
+fn walk(root: &Path, dir: &Path, exclude: &[String], files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = std::fs::read_dir(dir).map_err(|e| format!("Could not read directory {}: {}", dir.display(), e))?;
+    for entry in entries {
+        let path = entry.map_err(|e| format!("Could not read directory {}: {}", dir.display(), e))?.path();
+        let relative = path.strip_prefix(root).unwrap_or(&path).to_string_lossy().replace('\\', "/");
+        if exclude.iter().any(|pattern| excluded(pattern, &relative)) {
+            continue;
+        }
+        if path.is_dir() {
+            walk(root, &path, exclude, files)?;
+        } else if path.extension().is_some_and(|ext| SOURCE_EXTENSIONS.iter().any(|e| ext == *e)) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Leaks and overflows of every C source under `dir`, on all cores. A file
+/// that can't be read or parsed is an error of the report, not skipped.
+pub fn check_project(dir: &Path, allocators: &[String], exclude: &[String]) -> Result<ProjectReport, String> {
+    let files = source_files(dir, exclude)?;
+    let results: Vec<_> = files.par_iter().map(|file| (file, crate::run_safety_analysis(file, allocators))).collect();
+
+    let mut reports = Vec::new();
+    let mut errors = Vec::new();
+    for (file, result) in results {
+        match result {
+            Ok(report) => reports.push(report),
+            Err(error) => errors.push(AnalysisError { file_path: file.to_string_lossy().to_string(), error }),
+        }
+    }
+    let severities = || reports.iter().flat_map(|r| &r.severities);
+    let totals = Totals {
+        files: files.len(),
+        findings: reports.iter().map(|r| r.findings.len()).sum(),
+        critical: severities().filter(|&&s| s == Severity::Critical).count(),
+        warning: severities().filter(|&&s| s == Severity::Warning).count(),
+        info: severities().filter(|&&s| s == Severity::Info).count(),
+        errors: errors.len(),
+    };
+    Ok(ProjectReport {
+        success: errors.is_empty() && reports.iter().all(|r| r.success),
+        reports,
+        errors,
+        totals,
+    })
+}
+
+/// Whether an `--exclude` glob matches a path relative to the directory.
+/// One without a `/` matches any file or directory of that name
+/// (`*_test.c`, `vendor`); one with a `/` the whole path (`gen/**`).
+pub fn excluded(pattern: &str, relative: &str) -> bool {
+    if pattern.contains('/') {
+        glob(pattern.as_bytes(), relative.as_bytes())
+    } else {
+        relative.rsplit('/').next().is_some_and(|name| glob(pattern.as_bytes(), name.as_bytes()))
+    }
+}
+
+/// `*` within one path component, `**` across any number of them, `?` one
+/// character other than `/`
+fn glob(pattern: &[u8], path: &[u8]) -> bool {
+    match pattern {
+        [] => path.is_empty(),
+        [b'*', b'*', rest @ ..] => {
+            let rest = rest.strip_prefix(b"/").unwrap_or(rest);
+            rest.is_empty() || (0..=path.len()).any(|i| (i == 0 || path[i - 1] == b'/') && glob(rest, &path[i..]))
+        }
+        [b'*', rest @ ..] => (0..=path.len()).take_while(|&i| i == 0 || path[i - 1] != b'/').any(|i| glob(rest, &path[i..])),
+        [b'?', rest @ ..] => path.first().is_some_and(|&c| c != b'/') && glob(rest, &path[1..]),
+        [c, rest @ ..] => path.first() == Some(c) && glob(rest, &path[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use crate::test_support::fixture;
+
+    // A clean file, a leaking one and one that isn't text, plus a vendored
+    // copy of the leaking one that `--exclude` leaves out
+    #[test]
+    fn test_check_project() {
+        let dir = std::env::temp_dir().join(format!("venom-watch-{}-project", std::process::id()));
+        fs::create_dir_all(dir.join("vendor")).unwrap();
+        fs::copy(fixture("goto_cleanup_test.c"), dir.join("clean.c")).unwrap();
+        fs::copy(fixture("leak_test.c"), dir.join("leaky.c")).unwrap();
+        fs::copy(fixture("leak_test.c"), dir.join("vendor/leaky.c")).unwrap();
+        fs::write(dir.join("broken.c"), [0xff, 0xfe, 0x00, 0x9f]).unwrap();
+        fs::write(dir.join("notes.txt"), "not C").unwrap();
+
+        let report = check_project(&dir, &[], &["vendor".to_string()]).unwrap();
+        let files: Vec<_> = report.reports.iter().map(|r| Path::new(&r.file_path).file_name().unwrap().to_string_lossy().to_string()).collect();
+        assert_eq!(files, ["clean.c", "leaky.c"]);
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].file_path.ends_with("broken.c"), "{:?}", report.errors);
+        assert_eq!(report.totals, Totals { files: 3, findings: 2, critical: 0, warning: 2, info: 0, errors: 1 });
+        assert!(!report.success && !report.passes(Severity::Critical));
+
+        let all = check_project(&dir, &[], &[]).unwrap();
+        assert_eq!((all.totals.files, all.totals.findings), (4, 4));
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_exclude_globs() {
+        assert!(excluded("*_test.c", "src/leak_test.c"));
+        assert!(excluded("vendor", "vendor"));
+        assert!(excluded("gen/**", "gen/a/b.c"));
+        assert!(excluded("**/fixtures/*.c", "a/b/fixtures/x.c"));
+        assert!(excluded("src/?.c", "src/a.c"));
+        assert!(!excluded("src/*.c", "src/sub/a.c"));
+        assert!(!excluded("*.h", "src/a.c"));
+    }
+}
@@ -0,0 +1,344 @@
+//! Python decoders: the `struct` unpack calls of `venom_binding.py`
+//!
+//! The generated Python client decodes the state with a format string,
+//! `_LAYOUT = struct.Struct('<IIf16fIIIQQQ')` and
+//! `v = _LAYOUT.unpack_from(data, 0)`, then hands the values to the
+//! constructor (`cpu_cores=list(v[3:19])`). This pass decodes the format of
+//! each unpack call in the class into reads at the offsets it implies, and
+//! names them after the argument or variable their values end up in.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use crate::models::{Decoder, WireRead};
+use super::constants::{self, Constants};
+use super::wire::{self, wire_layout};
+
+const COMMENT: &str = "#";
+
+/// One item of a format string: `16f`, `3x`, `8s`
+struct FormatItem {
+    code: char,
+    count: usize,
+    offset: usize,
+    /// Bytes per value (the whole item for `s` and `p`)
+    width: usize,
+    /// Index of its first value in the unpacked tuple
+    value: usize,
+}
+
+/// Reads of the unpack calls in `class_name` (or in the whole file when
+/// there's no such class)
+pub fn analyze_python_decoder(path: &PathBuf, class_name: &str) -> Result<Decoder, String> {
+    let code = fs::read_to_string(path).map_err(|e| format!("Could not read file {}: {}", path.display(), e))?;
+    let constants = python_constants(&code);
+    let formats = struct_formats(&code);
+    let (from, to) = class_body(&code, class_name).unwrap_or((0, code.len()));
+
+    let mut reads = Vec::new();
+    let mut warnings = Vec::new();
+    let mut types = HashMap::new();
+    let mut wire_name = None;
+    let mut calls = Vec::new();
+    for method in ["unpack_from(", "unpack("] {
+        let mut search = from;
+        while let Some(at) = code[search..to].find(method) {
+            let open = search + at + method.len() - 1;
+            search = open;
+            if code[..open - method.len() + 1].ends_with('.') {
+                calls.push((open - method.len(), open));
+            }
+        }
+    }
+    calls.sort();
+
+    for (dot, open) in calls {
+        let receiver_start = code[..dot].rfind(|c: char| !(c.is_alphanumeric() || c == '_')).map_or(0, |i| i + 1);
+        let receiver = &code[receiver_start..dot];
+        let Some(close) = wire::closing(&code, open, COMMENT) else { continue };
+        let args = wire::split_top_level(&code, open + 1, close, COMMENT);
+        let line = code[..open].matches('\n').count() + 1;
+        let mut positional = Vec::new();
+        let mut keywords = HashMap::new();
+        for &(s, e) in &args {
+            let arg = code[s..e].trim();
+            match keyword_arg(arg) {
+                Some((keyword, value)) => {
+                    keywords.insert(keyword, value);
+                }
+                None => positional.push(arg),
+            }
+        }
+        let arg = |i: usize, keyword: &str| keywords.get(keyword).copied().or(positional.get(i).copied());
+
+        // `struct.unpack_from(fmt, buffer, offset)` or `NAME.unpack_from(buffer, offset)`
+        let (format, first) = if receiver == "struct" {
+            let Some(format) = arg(0, "format").and_then(|f| string_literal(f).or_else(|| formats.get(f).cloned())) else {
+                warnings.push(format!("Could not resolve the format of the unpack call on line {}", line));
+                continue;
+            };
+            (format, 1)
+        } else if let Some(format) = formats.get(receiver) {
+            (format.clone(), 0)
+        } else {
+            continue;
+        };
+
+        // A sliced buffer (`data[12:]`) moves the offsets along with the slice
+        let buffer = arg(first, "buffer").unwrap_or("");
+        let slice_start = buffer
+            .strip_suffix(']')
+            .and_then(|b| b.split_once('['))
+            .map(|(_, index)| index.split(':').next().unwrap_or("").trim())
+            .map(|start| if start.is_empty() { Some(0) } else { constants::eval(start, &constants) });
+        let offset_expr = arg(first + 1, "offset").unwrap_or("0");
+        let base = match (slice_start, constants::eval(offset_expr, &constants)) {
+            (Some(None), _) | (_, None) => {
+                warnings.push(format!("Could not resolve the offset '{}' of the unpack call on line {}", offset_expr, line));
+                continue;
+            }
+            (Some(Some(start)), Some(offset)) => start + offset,
+            (None, Some(offset)) => offset,
+        };
+        let Ok(base) = usize::try_from(base) else { continue };
+
+        let (items, prefix) = match decode_format(&format) {
+            Ok(decoded) => decoded,
+            Err(e) => {
+                warnings.push(format!("{} (line {})", e, line));
+                continue;
+            }
+        };
+        if matches!(prefix, '@' | '=') {
+            let alignment = if prefix == '@' { " and alignment" } else { "" };
+            warnings.push(format!("Format '{}' (line {}) uses the native byte order{} of the machine running the client", format, line, alignment));
+        }
+        let big_endian = matches!(prefix, '>' | '!');
+        wire_name.get_or_insert_with(|| code[receiver_start..open].to_string());
+
+        let names = value_names(&code, to, receiver_start);
+        for item in items.iter().filter(|i| i.code != 'x') {
+            let values = if matches!(item.code, 's' | 'p') { 1 } else { item.count };
+            let mut j = 0;
+            while j < values {
+                // Consecutive values that land in the same name make one read
+                let name = names.get(&(item.value + j));
+                let mut n = 1;
+                while j + n < values && names.get(&(item.value + j + n)) == name {
+                    n += 1;
+                }
+                let field = match name {
+                    Some(name) => name.clone(),
+                    None if n > 1 => format!("v[{}:{}]", item.value + j, item.value + j + n),
+                    None => format!("v[{}]", item.value + j),
+                };
+                let type_name = match item.code {
+                    's' | 'p' => format!("{}{}", item.count, item.code),
+                    code if n > 1 => format!("{}{}", n, code),
+                    code => code.to_string(),
+                };
+                types.insert(field.clone(), type_name);
+                reads.push(WireRead {
+                    field,
+                    offset: base + item.offset + j * item.width,
+                    width: item.width,
+                    count: n,
+                    stride: item.width,
+                    big_endian: big_endian && item.width > 1 && !matches!(item.code, 's' | 'p'),
+                    line,
+                });
+                j += n;
+            }
+        }
+    }
+
+    let Some(wire_name) = wire_name.filter(|_| !reads.is_empty()) else {
+        return Err(format!("No struct unpack call found in {}", path.display()));
+    };
+    let mut layout = wire_layout(class_name, path.to_string_lossy().to_string(), &reads, warnings, wire_name);
+    for field in &mut layout.fields {
+        if let Some(ty) = types.get(&field.name) {
+            field.type_name = ty.clone();
+        }
+    }
+    Ok(Decoder { reads, layout })
+}
+
+/// Items of a `struct` format string and its byte order prefix (`@` when
+/// there's none)
+fn decode_format(format: &str) -> Result<(Vec<FormatItem>, char), String> {
+    let (prefix, body) = match format.chars().next() {
+        Some(c @ ('@' | '=' | '<' | '>' | '!')) => (c, &format[1..]),
+        _ => ('@', format),
+    };
+    let aligned = prefix == '@';
+    let mut items = Vec::new();
+    let mut offset = 0;
+    let mut value = 0;
+    let mut count = String::new();
+    for c in body.chars() {
+        if c.is_ascii_digit() {
+            count.push(c);
+            continue;
+        }
+        if c.is_whitespace() {
+            continue;
+        }
+        let width = match c {
+            'x' | 'c' | 'b' | 'B' | '?' | 's' | 'p' => 1,
+            'h' | 'H' | 'e' => 2,
+            'i' | 'I' | 'f' => 4,
+            'l' | 'L' => if aligned { 8 } else { 4 },
+            'q' | 'Q' | 'd' => 8,
+            'n' | 'N' | 'P' if aligned => 8,
+            _ => return Err(format!("Unsupported format character '{}' in '{}'", c, format)),
+        };
+        let n = if count.is_empty() { 1 } else { count.parse().map_err(|_| format!("Bad repeat count in '{}'", format))? };
+        count.clear();
+        if aligned && !matches!(c, 'x' | 's' | 'p') {
+            offset = usize::next_multiple_of(offset, width);
+        }
+        let (width, values) = match c {
+            's' | 'p' => (n, 1),
+            'x' => (1, 0),
+            _ => (width, n),
+        };
+        items.push(FormatItem { code: c, count: n, offset, width, value });
+        offset += if matches!(c, 's' | 'p') { n } else { width * n };
+        value += values;
+    }
+    Ok((items, prefix))
+}
+
+/// Name of each unpacked value, by index: from `a, b, c = X.unpack_from(...)`,
+/// or from the arguments and assignments (`core_count=v[19]`,
+/// `self.cores = list(v[3:19])`) after `v = X.unpack_from(...)`, up to `to`
+fn value_names(code: &str, to: usize, call: usize) -> HashMap<usize, String> {
+    let mut names = HashMap::new();
+    let line_start = code[..call].rfind('\n').map_or(0, |i| i + 1);
+    let Some((target, _)) = code[line_start..call].split_once('=') else { return names };
+    let targets: Vec<&str> = target.trim().trim_matches(|c| c == '(' || c == ')').split(',').map(str::trim).filter(|t| !t.is_empty()).collect();
+    if targets.len() > 1 {
+        for (i, t) in targets.iter().enumerate() {
+            names.insert(i, attribute(t).to_string());
+        }
+        return names;
+    }
+    let Some(var) = targets.first().filter(|v| v.chars().all(|c| c.is_alphanumeric() || c == '_')) else { return names };
+
+    // Only uses after the unpack call
+    let after = line_start + code[line_start..].find('\n').unwrap_or(code.len() - line_start);
+    let uses = format!("{}[", var);
+    let mut search = after.min(to);
+    while let Some(at) = code[search..to].find(&uses) {
+        let start = search + at;
+        search = start + uses.len();
+        if code[..start].ends_with(|c: char| c.is_alphanumeric() || c == '_' || c == '.') {
+            continue;
+        }
+        let Some(close) = code[search..to].find(']').map(|i| search + i) else { break };
+        let index = &code[search..close];
+        let range = match index.split_once(':') {
+            Some((a, b)) => a.trim().parse::<usize>().ok().zip(b.trim().parse::<usize>().ok()),
+            None => index.trim().parse::<usize>().ok().map(|i| (i, i + 1)),
+        };
+        // `name=` or `name =` before the value, past a `list(`/`tuple(` wrapper
+        let before = code[..start].trim_end();
+        let before = before.strip_suffix("list(").or_else(|| before.strip_suffix("tuple(")).unwrap_or(before).trim_end();
+        let name = before.strip_suffix('=').filter(|b| !b.ends_with(['=', '!', '<', '>'])).map(|b| {
+            let b = b.trim_end();
+            attribute(&b[b.rfind(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.')).map_or(0, |i| i + 1)..])
+        });
+        if let (Some((a, b)), Some(name)) = (range, name.filter(|n| !n.is_empty())) {
+            for i in a..b {
+                names.entry(i).or_insert_with(|| name.to_string());
+            }
+        }
+    }
+    names
+}
+
+/// `name=value` (not `a == b`) as `(name, value)`
+fn keyword_arg(arg: &str) -> Option<(&str, &str)> {
+    let (name, value) = arg.split_once('=')?;
+    let name = name.trim();
+    let is_name = !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    (is_name && !value.starts_with('=')).then(|| (name, value.trim()))
+}
+
+/// `self.magic` is the field `magic`
+fn attribute(target: &str) -> &str {
+    target.rsplit('.').next().unwrap_or(target)
+}
+
+/// Byte range of the body of `class Name`: the lines after it indented
+/// deeper than the `class` line
+fn class_body(code: &str, class_name: &str) -> Option<(usize, usize)> {
+    let mut offset = 0;
+    let mut body: Option<(usize, usize)> = None;
+    for line in code.split_inclusive('\n') {
+        let indent = line.len() - line.trim_start().len();
+        let text = line.trim();
+        match body {
+            None => {
+                let header = text.strip_prefix("class ").map(|rest| rest.split(['(', ':']).next().unwrap_or("").trim());
+                if header == Some(class_name) {
+                    body = Some((offset + line.len(), indent));
+                }
+            }
+            Some((start, class_indent)) => {
+                if !text.is_empty() && !text.starts_with('#') && indent <= class_indent {
+                    return Some((start, offset));
+                }
+            }
+        }
+        offset += line.len();
+    }
+    body.map(|(start, _)| (start, code.len()))
+}
+
+/// Module-level `NAME = <integer expression>` assignments, in file order
+fn python_constants(code: &str) -> Constants {
+    let mut constants = Constants::new();
+    for line in code.lines().filter(|l| !l.starts_with(char::is_whitespace)) {
+        let line = line.split('#').next().unwrap_or("");
+        let Some((name, value)) = line.split_once('=') else { continue };
+        let name = name.split(':').next().unwrap_or("").trim();
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') || value.starts_with('=') {
+            continue;
+        }
+        if let Some(v) = constants::eval(value.trim(), &constants) {
+            constants.insert(name.to_string(), v);
+        }
+    }
+    constants
+}
+
+/// Format strings by name: `FMT = '<II'` and `_LAYOUT = struct.Struct(FMT)`
+fn struct_formats(code: &str) -> HashMap<String, String> {
+    let mut formats = HashMap::new();
+    for line in code.lines() {
+        let Some((name, value)) = line.split_once('=') else { continue };
+        let name = name.trim();
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') || value.starts_with('=') {
+            continue;
+        }
+        let value = value.split('#').next().unwrap_or("").trim();
+        let format = match value.strip_prefix("struct.Struct(").and_then(|v| v.strip_suffix(')')) {
+            Some(arg) => string_literal(arg.trim()).or_else(|| formats.get(arg.trim()).cloned()),
+            None => string_literal(value),
+        };
+        if let Some(format) = format {
+            formats.insert(name.to_string(), format);
+        }
+    }
+    formats
+}
+
+/// The text of `'...'`, `"..."` or `b'...'`
+fn string_literal(text: &str) -> Option<String> {
+    let text = text.strip_prefix('b').unwrap_or(text);
+    let quote = text.chars().next().filter(|c| matches!(c, '\'' | '"'))?;
+    let inner = text[1..].strip_suffix(quote)?;
+    (!inner.contains(quote)).then(|| inner.to_string())
+}
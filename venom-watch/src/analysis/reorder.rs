@@ -0,0 +1,160 @@
+//! `--optimize`: the order of a struct's fields that wastes the least
+//! padding
+//!
+//! The top-level fields are sorted by alignment, largest first, keeping
+//! their order among equals; since a field's size is a multiple of its
+//! alignment, each one then starts where the one before it ends. A run of
+//! bitfields moves as one, aligned as its widest type, and a flexible array
+//! member stays last. Packed structs and wire layouts are refused: their
+//! offsets are the protocol.
+
+use std::path::Path;
+use crate::models::{Field, Reordering, StructLayout};
+
+/// The fields of `layout` rearranged for the least padding, with the C or
+/// Rust definition in that order when the file is one of those
+pub fn suggest_order(layout: &StructLayout) -> Result<Reordering, String> {
+    if let Some(packing) = layout.packing {
+        let how = if packing == 1 { "packed".to_string() } else { format!("packed to {} bytes", packing) };
+        return Err(format!("{} is {}, a layout fixed on purpose: reordering its fields breaks whoever reads it", layout.name, how));
+    }
+    if let Some(serializer) = &layout.wire {
+        return Err(format!("{}'s offsets are the ones {} reads and writes: reordering its fields breaks the wire protocol", layout.name, serializer));
+    }
+    let top: Vec<&Field> = layout.fields.iter().filter(|f| f.depth == 0).collect();
+    if top.len() > 1 && top.iter().all(|f| f.offset == 0) {
+        return Err(format!("{} is a union: its members all start at offset 0", layout.name));
+    }
+
+    // Bitfields sharing a run of storage stay together, in their order
+    let mut units: Vec<Vec<&Field>> = Vec::new();
+    for field in top {
+        match units.last_mut() {
+            Some(unit) if field.bit_width.is_some() && unit.last().is_some_and(|f| f.bit_width.is_some()) => unit.push(field),
+            _ => units.push(vec![field]),
+        }
+    }
+    let align = |unit: &[&Field]| unit.iter().map(|f| f.align.max(1)).max().unwrap_or(1);
+    let size = |unit: &[&Field]| unit.iter().map(|f| f.offset + f.size).max().unwrap_or(0) - unit[0].offset;
+    units.sort_by_key(|unit| (unit[0].is_flexible, std::cmp::Reverse(align(unit))));
+
+    let mut offset = 0usize;
+    let mut max_align = 1usize;
+    for unit in &units {
+        offset = offset.next_multiple_of(align(unit)) + size(unit);
+        max_align = max_align.max(align(unit));
+    }
+    let suggested_size = offset.next_multiple_of(max_align);
+    let fields: Vec<&Field> = units.into_iter().flatten().collect();
+    Ok(Reordering {
+        name: layout.name.clone(),
+        current_size: layout.total_size,
+        suggested_size,
+        saved_bytes: layout.total_size.saturating_sub(suggested_size),
+        suggested_order: fields.iter().map(|f| f.name.clone()).collect(),
+        definition: definition(layout, &fields),
+    })
+}
+
+/// The struct declared with `fields` in their new order: `typedef struct
+/// Name {...} Name;` for C and C++ (either spelling still compiles), a
+/// `#[repr(C)]` struct for Rust. None for other languages, and for fields
+/// that can't be written back from the layout (anonymous structs and unions)
+fn definition(layout: &StructLayout, fields: &[&Field]) -> Option<String> {
+    let ext = Path::new(&layout.file_path).extension().and_then(|s| s.to_str()).unwrap_or("");
+    let name = layout.name.rsplit("::").next().unwrap_or(&layout.name);
+    if fields.iter().any(|f| f.name.starts_with('<')) {
+        return None;
+    }
+    match ext {
+        "rs" => {
+            let body: String = fields.iter().map(|f| format!("    pub {}: {},\n", f.name, f.type_name)).collect();
+            Some(format!("#[repr(C)]\npub struct {} {{\n{}}}\n", name, body))
+        }
+        "c" | "h" | "cc" | "cpp" | "cxx" | "hpp" | "hh" | "hxx" => {
+            let body: String = fields
+                .iter()
+                .map(|f| {
+                    let pointer = if f.is_pointer && !f.type_name.contains('*') { "*" } else { "" };
+                    let array = match (f.is_array, f.is_flexible) {
+                        (_, true) => "[]".to_string(),
+                        (true, false) => format!("[{}]", f.array_len),
+                        (false, false) => String::new(),
+                    };
+                    let bits = f.bit_width.map_or(String::new(), |width| format!(" : {}", width));
+                    format!("    {} {}{}{}{};\n", f.type_name, pointer, f.name, array, bits)
+                })
+                .collect();
+            Some(format!("typedef struct {0} {{\n{1}}} {0};\n", name, body))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyze_file;
+    use crate::test_support::fixture;
+
+    /// `definition` written to a file of its own and laid out again
+    fn reanalyze(definition: &str, name: &str, file: &str) -> StructLayout {
+        let path = std::env::temp_dir().join(format!("venom-watch-{}-{}", std::process::id(), file));
+        std::fs::write(&path, definition).unwrap();
+        let layout = analyze_file(&path, name).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        layout
+    }
+
+    #[test]
+    fn test_suggest_order() {
+        let layout = analyze_file(&fixture("test_optimize.h"), "Wasteful").unwrap();
+        let padding = layout.total_size - layout.fields.iter().map(|f| f.size).sum::<usize>();
+        assert_eq!((layout.total_size, padding), (24, 10));
+
+        let reordering = suggest_order(&layout).unwrap();
+        assert_eq!((reordering.current_size, reordering.suggested_size, reordering.saved_bytes), (24, 16, 8));
+        assert_eq!(reordering.suggested_order, ["timestamp", "count", "flag", "kind"]);
+        let definition = reordering.definition.unwrap();
+        assert_eq!(
+            definition,
+            "typedef struct Wasteful {\n    uint64_t timestamp;\n    uint32_t count;\n    uint8_t flag;\n    uint8_t kind;\n} Wasteful;\n"
+        );
+        assert_eq!(reanalyze(&definition, "Wasteful", "optimized.h").total_size, 16);
+
+        // Already as small as it gets
+        let again = suggest_order(&reanalyze(&definition, "Wasteful", "again.h")).unwrap();
+        assert_eq!(again.saved_bytes, 0);
+    }
+
+    #[test]
+    fn test_suggest_order_rust() {
+        let layout = analyze_file(&fixture("test_padding.rs"), "PaddingStruct").unwrap();
+        let reordering = suggest_order(&layout).unwrap();
+        assert_eq!((reordering.current_size, reordering.suggested_size), (32, 16));
+        let definition = reordering.definition.unwrap();
+        assert_eq!(definition, "#[repr(C)]\npub struct PaddingStruct {\n    pub d: f64,\n    pub b: u32,\n    pub a: u8,\n    pub c: u8,\n    pub e: u8,\n}\n");
+        assert_eq!(reanalyze(&definition, "PaddingStruct", "optimized.rs").total_size, 16);
+    }
+
+    #[test]
+    fn test_flexible_array_stays_last() {
+        // `words[0]` is the most aligned, and still goes last
+        let layout = analyze_file(&fixture("test_fam_server.h"), "Batch").unwrap();
+        let reordering = suggest_order(&layout).unwrap();
+        assert_eq!(reordering.suggested_order, ["tag", "count", "words"]);
+        assert_eq!((reordering.current_size, reordering.suggested_size), (4, 4));
+        let definition = reordering.definition.unwrap();
+        assert_eq!(definition, "typedef struct Batch {\n    uint8_t tag;\n    uint8_t count;\n    uint32_t words[];\n} Batch;\n");
+        assert_eq!(reanalyze(&definition, "Batch", "flexible.h").total_size, 4);
+    }
+
+    #[test]
+    fn test_refused() {
+        let packed = analyze_file(&fixture("test_packed.h"), "PackedStruct").unwrap();
+        assert!(suggest_order(&packed).unwrap_err().contains("packed"));
+        let mut wire = analyze_file(&fixture("test_optimize.h"), "Wasteful").unwrap();
+        wire.wire = Some("StateFromBytes".to_string());
+        assert!(suggest_order(&wire).unwrap_err().contains("wire protocol"));
+    }
+}
@@ -0,0 +1,109 @@
+//! What the hand-written decoder scanners (Dart, Python) share: bracket
+//! matching over source text, and turning the reads they find into a
+//! packed wire layout
+
+use crate::models::{Field, StructLayout, Target, WireRead};
+
+/// Index of the bracket closing the one at `open`, skipping strings and
+/// `comment`-to-end-of-line comments
+pub(super) fn closing(code: &str, open: usize, comment: &str) -> Option<usize> {
+    let bytes = code.as_bytes();
+    let mut depth = 0;
+    let mut i = open;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            quote @ (b'\'' | b'"') => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != quote {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+            }
+            _ if bytes[i..].starts_with(comment.as_bytes()) => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// `code[from..to]` split at the commas outside brackets, as ranges; empty
+/// pieces (a trailing comma) left out
+pub(super) fn split_top_level(code: &str, from: usize, to: usize, comment: &str) -> Vec<(usize, usize)> {
+    let mut pieces = Vec::new();
+    let mut start = from;
+    let mut i = from;
+    while i < to {
+        match code.as_bytes()[i] {
+            b'(' | b'[' | b'{' => i = closing(code, i, comment).unwrap_or(to),
+            b',' => {
+                pieces.push((start, i));
+                start = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    pieces.push((start, to));
+    pieces.into_iter().filter(|&(s, e)| !code[s..e].trim().is_empty()).collect()
+}
+
+/// The reads as a packed layout, one field per name; several reads for one
+/// field (`hi << 32 | lo`) cover the bytes they span together
+pub(super) fn wire_layout(name: &str, file_path: String, reads: &[WireRead], warnings: Vec<String>, wire: String) -> StructLayout {
+    let mut fields: Vec<Field> = Vec::new();
+    for read in reads {
+        match fields.iter_mut().find(|f| f.name == read.field) {
+            Some(field) => {
+                let end = (field.offset + field.size).max(read.end());
+                field.offset = field.offset.min(read.offset);
+                field.size = end - field.offset;
+            }
+            None => fields.push(Field {
+                name: read.field.clone(),
+                type_name: read_type(read),
+                size: read.end() - read.offset,
+                offset: read.offset,
+                align: 1,
+                is_array: read.count > 1,
+                array_len: read.count,
+                is_flexible: false,
+                line: read.line,
+                is_pointer: false,
+                depth: 0,
+                parent: None,
+                bit_offset: None,
+                bit_width: None,
+            }),
+        }
+    }
+    fields.sort_by_key(|f| f.offset);
+    StructLayout {
+        name: name.to_string(),
+        total_size: fields.iter().map(|f| f.offset + f.size).max().unwrap_or(0),
+        fields,
+        file_path,
+        packing: Some(1),
+        warnings,
+        wire: Some(wire),
+        target: Target::default(),
+    }
+}
+
+fn read_type(read: &WireRead) -> String {
+    if read.count > 1 {
+        format!("List<{} bytes>[{}]", read.width, read.count)
+    } else {
+        format!("{} bytes", read.width)
+    }
+}
@@ -0,0 +1,262 @@
+//! Zig structs: `extern struct` follows the C ABI, `packed struct` is laid
+//! out bit by bit in a backing integer
+
+use std::collections::HashMap;
+use tree_sitter::Node;
+use crate::models::{Field, StructLayout, Target};
+use super::constants::{self, Constants};
+use super::layout::{array_len, unresolved_size};
+
+struct ZigContext<'a> {
+    code: &'a str,
+    /// `const X = <container>` declarations of the file by name
+    decls: HashMap<&'a str, Node<'a>>,
+    constants: Constants,
+    warnings: Vec<String>,
+    target: Target,
+}
+
+pub fn analyze_zig_struct(struct_name: &str, code: &str, root_node: Node, file_path: String, target: Target) -> Result<StructLayout, String> {
+    let mut ctx = ZigContext {
+        code,
+        decls: declarations(code, root_node),
+        constants: constants::collect_zig(code, root_node),
+        warnings: Vec::new(),
+        target,
+    };
+    let decl = ctx
+        .decls
+        .get(struct_name)
+        .copied()
+        .filter(|d| d.kind() == "struct_declaration")
+        .ok_or_else(|| format!("Zig struct '{}' not found", struct_name))?;
+
+    let (fields, size, _) = match container_layout(decl, code) {
+        "packed" => layout_packed(decl, &mut ctx, 0),
+        "extern" => layout_extern(decl, &mut ctx, 0),
+        _ => {
+            ctx.warnings.push(format!("{} is neither extern nor packed; Zig may reorder its fields, laid out as extern", struct_name));
+            layout_extern(decl, &mut ctx, 0)
+        }
+    };
+    let packing = (container_layout(decl, code) == "packed").then_some(1);
+
+    Ok(StructLayout {
+        name: struct_name.to_string(),
+        fields,
+        total_size: size,
+        file_path,
+        packing,
+        warnings: ctx.warnings,
+        wire: None,
+        target,
+    })
+}
+
+fn declarations<'a>(code: &'a str, root_node: Node<'a>) -> HashMap<&'a str, Node<'a>> {
+    let mut decls = HashMap::new();
+    let mut cursor = root_node.walk();
+    for decl in root_node.children(&mut cursor).filter(|n| n.kind() == "variable_declaration") {
+        let mut decl_cursor = decl.walk();
+        let named: Vec<Node> = decl.named_children(&mut decl_cursor).collect();
+        if let (Some(name), Some(value)) = (named.first(), named.last())
+            && name.kind() == "identifier"
+        {
+            decls.insert(name.utf8_text(code.as_bytes()).unwrap(), *value);
+        }
+    }
+    decls
+}
+
+/// `extern`, `packed` or `` (auto) for a struct declaration
+fn container_layout<'a>(decl: Node, code: &'a str) -> &'a str {
+    let mut cursor = decl.walk();
+    let keyword = decl.children(&mut cursor).find(|n| matches!(n.kind(), "extern" | "packed"));
+    keyword.map(|k| &code[k.byte_range()]).unwrap_or("")
+}
+
+fn container_fields<'t>(decl: Node<'t>) -> Vec<Node<'t>> {
+    let mut cursor = decl.walk();
+    decl.children(&mut cursor).filter(|n| n.kind() == "container_field").collect()
+}
+
+/// Fields of an extern struct, C-aligned; size and alignment of the struct
+fn layout_extern(decl: Node, ctx: &mut ZigContext, nesting: usize) -> (Vec<Field>, usize, usize) {
+    let code = ctx.code;
+    let mut fields = Vec::new();
+    let mut offset = 0usize;
+    let mut max_align = 1;
+    for field in container_fields(decl) {
+        let (Some(name), Some(type_node)) = (field.child_by_field_name("name"), field.child_by_field_name("type")) else { continue };
+        let name = name.utf8_text(code.as_bytes()).unwrap();
+        let line = field.start_position().row + 1;
+        let (size, align, array) = type_info(type_node, name, line, ctx, nesting);
+        offset = offset.next_multiple_of(align);
+        max_align = max_align.max(align);
+        fields.push(new_field(name, type_node, code, (size, align), offset, array, line));
+        offset += size;
+    }
+    (fields, offset.next_multiple_of(max_align), max_align)
+}
+
+/// Fields of a packed struct, one after the other without padding: those
+/// not on whole bytes get bit positions. Its size is that of the backing
+/// integer (`packed struct(u32)`, or as wide as the fields).
+fn layout_packed(decl: Node, ctx: &mut ZigContext, nesting: usize) -> (Vec<Field>, usize, usize) {
+    let code = ctx.code;
+    let mut fields = Vec::new();
+    let mut bit = 0usize;
+    for field in container_fields(decl) {
+        let (Some(name), Some(type_node)) = (field.child_by_field_name("name"), field.child_by_field_name("type")) else { continue };
+        let name = name.utf8_text(code.as_bytes()).unwrap();
+        let line = field.start_position().row + 1;
+        let width = bit_width(type_node, name, line, ctx, nesting);
+        let mut f = new_field(name, type_node, code, ((bit % 8 + width).div_ceil(8), 1), bit / 8, None, line);
+        if !bit.is_multiple_of(8) || !width.is_multiple_of(8) {
+            f.bit_offset = Some((bit % 8) as u32);
+            f.bit_width = Some(width as u32);
+        }
+        fields.push(f);
+        bit += width;
+    }
+    let mut cursor = decl.walk();
+    let backing = decl.children(&mut cursor).find(|n| n.kind() == "builtin_type").and_then(|b| int_bits(&code[b.byte_range()], ctx.target));
+    let (size, align) = int_layout(backing.unwrap_or(bit));
+    (fields, size, align)
+}
+
+/// `(size, align)` is what the field takes up and is aligned to
+fn new_field(name: &str, type_node: Node, code: &str, (size, align): (usize, usize), offset: usize, array: Option<usize>, line: usize) -> Field {
+    Field {
+        name: name.to_string(),
+        type_name: type_node.utf8_text(code.as_bytes()).unwrap().to_string(),
+        size,
+        offset,
+        align,
+        is_array: array.is_some(),
+        array_len: array.unwrap_or(1),
+        is_flexible: false,
+        line,
+        is_pointer: matches!(type_node.kind(), "pointer_type" | "slice_type")
+            || (type_node.kind() == "nullable_type" && type_node.named_child(0).is_some_and(|t| t.kind() == "pointer_type")),
+        depth: 0,
+        parent: None,
+        bit_offset: None,
+        bit_width: None,
+    }
+}
+
+/// Width of `u7`/`i12`/`usize`-style integer types in bits on `target`
+fn int_bits(text: &str, target: Target) -> Option<usize> {
+    match text {
+        "usize" | "isize" => Some(target.pointer_size * 8),
+        _ => text.strip_prefix(['u', 'i']).and_then(|bits| bits.parse().ok()),
+    }
+}
+
+/// Size and alignment of an integer of `bits` bits on x86_64: a power of two
+/// bytes, at most 16-aligned
+fn int_layout(bits: usize) -> (usize, usize) {
+    let bytes = bits.div_ceil(8).max(1);
+    let align = bytes.next_power_of_two().min(16);
+    (bytes.next_multiple_of(align), align)
+}
+
+/// Size, alignment and (for an array) length of a Zig type in an extern struct
+fn type_info(type_node: Node, field: &str, line: usize, ctx: &mut ZigContext, nesting: usize) -> (usize, usize, Option<usize>) {
+    let code = ctx.code;
+    let text = type_node.utf8_text(code.as_bytes()).unwrap();
+    let (size, align) = match type_node.kind() {
+        "array_type" => {
+            let mut cursor = type_node.walk();
+            let parts: Vec<Node> = type_node.named_children(&mut cursor).collect();
+            let (Some(length), Some(element)) = (parts.first(), parts.last()) else { return (0, 1, Some(0)) };
+            let length = length.utf8_text(code.as_bytes()).unwrap();
+            let len = array_len(length, &ctx.constants).unwrap_or_else(|| {
+                ctx.warnings.push(unresolved_size(field, length, line));
+                1
+            });
+            let (size, align, _) = type_info(*element, field, line, ctx, nesting);
+            return (size * len, align, Some(len));
+        }
+        "pointer_type" | "nullable_type" => (ctx.target.pointer_size, ctx.target.pointer_size),
+        "slice_type" => (2 * ctx.target.pointer_size, ctx.target.pointer_size),
+        _ => match text {
+            "bool" | "c_char" => (1, 1),
+            "f16" | "c_short" | "c_ushort" => (2, 2),
+            "f32" | "c_int" | "c_uint" => (4, 4),
+            "f64" | "c_longlong" | "c_ulonglong" => (8, 8),
+            "c_long" | "c_ulong" => (ctx.target.pointer_size, ctx.target.pointer_size),
+            "f80" | "f128" => (16, 16),
+            _ => match int_bits(text, ctx.target) {
+                Some(bits) => int_layout(bits),
+                None => return declared_type_info(text, field, line, ctx, nesting),
+            },
+        },
+    };
+    (size, align, None)
+}
+
+/// A struct or enum declared in the same file
+fn declared_type_info(name: &str, field: &str, line: usize, ctx: &mut ZigContext, nesting: usize) -> (usize, usize, Option<usize>) {
+    let code = ctx.code;
+    let decl = ctx.decls.get(name).copied().filter(|_| nesting < 8);
+    let (size, align) = match decl.map(|d| (d.kind(), d)) {
+        Some(("struct_declaration", d)) if container_layout(d, code) == "packed" => {
+            let (_, size, align) = layout_packed(d, ctx, nesting + 1);
+            (size, align)
+        }
+        Some(("struct_declaration", d)) => {
+            let (_, size, align) = layout_extern(d, ctx, nesting + 1);
+            (size, align)
+        }
+        Some(("enum_declaration", d)) => {
+            let mut cursor = d.walk();
+            let tag = d.children(&mut cursor).find(|n| n.kind() == "builtin_type");
+            match tag.and_then(|t| int_bits(&code[t.byte_range()], ctx.target)) {
+                Some(bits) => int_layout(bits),
+                None => {
+                    ctx.warnings.push(format!("Enum '{}' of field {} (line {}) has no integer tag type; counted as 4 bytes", name, field, line));
+                    (4, 4)
+                }
+            }
+        }
+        _ => {
+            ctx.warnings.push(format!("Unknown type '{}' of field {} (line {}); counted as 8 bytes", name, field, line));
+            (8, 8)
+        }
+    };
+    (size, align, None)
+}
+
+/// Bits a field of a packed struct takes
+fn bit_width(type_node: Node, field: &str, line: usize, ctx: &mut ZigContext, nesting: usize) -> usize {
+    let code = ctx.code;
+    let text = type_node.utf8_text(code.as_bytes()).unwrap();
+    if text == "bool" {
+        return 1;
+    }
+    if let Some(bits) = int_bits(text, ctx.target) {
+        return bits;
+    }
+    match (ctx.decls.get(text).copied(), type_node.kind()) {
+        (Some(d), _) if d.kind() == "struct_declaration" && container_layout(d, code) == "packed" && nesting < 8 => {
+            let mut cursor = d.walk();
+            let backing = d.children(&mut cursor).find(|n| n.kind() == "builtin_type").and_then(|b| int_bits(&code[b.byte_range()], ctx.target));
+            match backing {
+                Some(bits) => bits,
+                None => container_fields(d)
+                    .iter()
+                    .filter_map(|f| f.child_by_field_name("type"))
+                    .map(|t| bit_width(t, field, line, ctx, nesting + 1))
+                    .sum(),
+            }
+        }
+        (Some(d), _) if d.kind() == "enum_declaration" => {
+            let mut cursor = d.walk();
+            let tag = d.children(&mut cursor).find(|n| n.kind() == "builtin_type");
+            tag.and_then(|t| int_bits(&code[t.byte_range()], ctx.target)).unwrap_or(8)
+        }
+        _ => type_info(type_node, field, line, ctx, nesting).0 * 8,
+    }
+}
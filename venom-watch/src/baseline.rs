@@ -0,0 +1,95 @@
+//! `--baseline`: findings accepted as they are, so that a known, intentional
+//! deviation doesn't fail every run
+//!
+//! Layout findings are keyed by rule and `Struct.field`, wherever the field
+//! moves to. Leak, overflow and constant findings are keyed by rule, file
+//! and variable (or constant), and match within `LINE_DRIFT` lines of where they were, so
+//! that unrelated edits above them don't bring them back.
+
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::sarif::Finding;
+
+pub const VERSION: u32 = 1;
+
+/// How far a leak or overflow finding may move and still match
+pub const LINE_DRIFT: usize = 3;
+
+/// Rules whose findings are about a variable or constant at a line of a
+/// source file
+const SOURCE_RULES: &[&str] = &["leak", "use-after-free", "double-free", "buffer-overflow", "constant-mismatch"];
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Baseline {
+    pub version: u32,
+    pub findings: Vec<Entry>,
+}
+
+/// One accepted finding. `file` and `line` are only kept for leak,
+/// overflow and constant findings; `message` is for whoever reads the file.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Entry {
+    pub rule: String,
+    pub subject: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl Entry {
+    pub fn new(finding: &Finding) -> Entry {
+        let source = SOURCE_RULES.contains(&finding.rule);
+        Entry {
+            rule: finding.rule.to_string(),
+            subject: finding.subject.clone(),
+            file: source.then(|| finding.file.clone()),
+            line: if source { finding.line } else { None },
+            message: finding.message.clone(),
+        }
+    }
+
+    pub fn matches(&self, finding: &Finding) -> bool {
+        if self.rule != finding.rule || self.subject != finding.subject {
+            return false;
+        }
+        if !SOURCE_RULES.contains(&finding.rule) {
+            return true;
+        }
+        let near = match (self.line, finding.line) {
+            (Some(a), Some(b)) => a.abs_diff(b) <= LINE_DRIFT,
+            (a, b) => a == b,
+        };
+        self.file.as_deref() == Some(finding.file.as_str()) && near
+    }
+}
+
+impl Baseline {
+    /// Every finding of a run, sorted so that regenerating an unchanged
+    /// baseline rewrites the same file
+    pub fn from_findings(findings: &[Finding]) -> Baseline {
+        let mut entries: Vec<Entry> = findings.iter().map(Entry::new).collect();
+        entries.sort();
+        entries.dedup();
+        Baseline { version: VERSION, findings: entries }
+    }
+
+    pub fn load(path: &Path) -> Result<Baseline, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("Could not read baseline {}: {}", path.display(), e))?;
+        let baseline: Baseline = serde_json::from_str(&text).map_err(|e| format!("Invalid baseline {}: {}", path.display(), e))?;
+        if baseline.version != VERSION {
+            return Err(format!("Baseline {} has version {}; this venom-watch reads version {}", path.display(), baseline.version, VERSION));
+        }
+        Ok(baseline)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, text + "\n").map_err(|e| format!("Could not write baseline {}: {}", path.display(), e))
+    }
+
+    pub fn accepts(&self, finding: &Finding) -> bool {
+        self.findings.iter().any(|entry| entry.matches(finding))
+    }
+}
@@ -0,0 +1,494 @@
+//! `--export`: a struct's layout as documentation, a Markdown table or an
+//! HTML page, instead of copying the terminal table
+//!
+//! One layout is listed field by field with its padding; a comparison puts
+//! the client's offsets next to the server's, with the status of each row.
+//! A byte map follows, with a line (Markdown) or a row of cells (HTML) per
+//! top-level field over the bytes it takes up. Both are rendered from the
+//! `LayoutDiff` rows, a single layout as a comparison with itself.
+
+use std::fmt::Write;
+use crate::analysis::compare::{compare_struct_layouts, packing_name, FieldDiff, FieldIssue, LayoutDiff, Row, Side};
+use crate::models::{Field, StructLayout};
+
+/// Widest byte map line, in characters; a larger struct gets more than one
+/// byte per character
+const MAP_WIDTH: usize = 64;
+
+/// HTML backgrounds: a failing row, a row with a note, padding, and a
+/// field's bytes in the map
+const MISMATCH: &str = "#f8d7da";
+const NOTE: &str = "#fff3cd";
+const PADDING: &str = "#eeeeee";
+const FIELD: &str = "#cfe2ff";
+
+/// Where a row is on one side of the table
+struct Place {
+    offset: String,
+    size: String,
+    line: Option<usize>,
+}
+
+enum Status {
+    Ok,
+    Padding,
+    Note(String),
+    Mismatch(String),
+}
+
+/// A row of the table
+struct Entry {
+    label: String,
+    padding: bool,
+    /// The field's type, or the server's and the client's if they differ
+    types: Vec<String>,
+    server: Option<Place>,
+    client: Option<Place>,
+    status: Status,
+}
+
+/// A run of bytes in the byte map: a top-level field, or padding
+struct Span {
+    label: String,
+    start: usize,
+    end: usize,
+    padding: bool,
+    failing: bool,
+}
+
+pub fn layout_markdown(layout: &StructLayout) -> String {
+    let diff = compare_struct_layouts(layout, layout);
+    let mut out = format!("## {}\n\n`{}`: {} bytes, {}.\n\n", diff.name, layout.file_path, diff.server_size, packing_name(diff.server_packing));
+    for (_, warning) in diff.warnings.iter().filter(|(side, _)| *side == Side::Server) {
+        writeln!(out, "- Warning: {}", md(warning)).unwrap();
+    }
+    if !diff.warnings.is_empty() {
+        out.push('\n');
+    }
+    out.push_str("| Field | Type | Offset | Size | Line |\n|:--|:--|--:|--:|--:|\n");
+    for entry in entries(&diff, true) {
+        let place = entry.server.as_ref().unwrap();
+        writeln!(out, "| {} | {} | {} | {} | {} |", md_label(&entry), md_types(&entry.types), place.offset, place.size, line(place)).unwrap();
+    }
+    out.push_str("\n### Byte map\n\n```text\n");
+    out.push_str(&text_map(&spans(&diff.server_fields, diff.server_size, &[]), diff.server_size));
+    out.push_str("```\n");
+    out
+}
+
+pub fn diff_markdown(diff: &LayoutDiff, server_file: &str, client_file: &str) -> String {
+    let mut out = format!("## {}\n\n", diff.name);
+    writeln!(out, "- Server: `{}`, {} bytes, {}", server_file, diff.server_size, packing_name(diff.server_packing)).unwrap();
+    writeln!(out, "- Client: `{}`, {} bytes, {}\n", client_file, diff.client_size, packing_name(diff.client_packing)).unwrap();
+    if diff.success() {
+        out.push_str("**✅ The layouts match.**\n\n");
+    } else {
+        out.push_str("**❌ The layouts differ.**\n\n");
+    }
+    for issue in struct_issues(diff) {
+        writeln!(out, "- {}", md(&issue)).unwrap();
+    }
+    if !struct_issues(diff).is_empty() {
+        out.push('\n');
+    }
+    out.push_str("| Field | Type | Server offset | Server size | Server line | Client offset | Client size | Client line | Status |\n");
+    out.push_str("|:--|:--|--:|--:|--:|--:|--:|--:|:--|\n");
+    for entry in entries(diff, false) {
+        let mut row = format!("| {} | {} |", md_label(&entry), md_types(&entry.types));
+        for place in [&entry.server, &entry.client] {
+            match place {
+                Some(place) => write!(row, " {} | {} | {} |", place.offset, place.size, line(place)).unwrap(),
+                None => row.push_str(" | | |"),
+            }
+        }
+        let status = match &entry.status {
+            Status::Ok => "✅ OK".to_string(),
+            Status::Padding => String::new(),
+            Status::Note(note) => format!("⚠️ {}", md(note)),
+            Status::Mismatch(issue) => format!("❌ {}", md(issue)),
+        };
+        writeln!(out, "{} {} |", row, status).unwrap();
+    }
+    out.push_str("\n### Byte map\n\n```text\n");
+    for (side, fields, size) in [(Side::Server, &diff.server_fields, diff.server_size), (Side::Client, &diff.client_fields, diff.client_size)] {
+        if side == Side::Client {
+            out.push('\n');
+        }
+        writeln!(out, "{} ({} bytes)", side, size).unwrap();
+        out.push_str(&text_map(&spans(fields, size, &failing(diff, side)), size));
+    }
+    out.push_str("```\n");
+    out
+}
+
+pub fn layout_html(layout: &StructLayout) -> String {
+    let diff = compare_struct_layouts(layout, layout);
+    let mut body = format!("<p><code>{}</code>: {} bytes, {}.</p>\n", html(&layout.file_path), diff.server_size, packing_name(diff.server_packing));
+    body.push_str(&html_list(diff.warnings.iter().filter(|(side, _)| *side == Side::Server).map(|(_, w)| format!("Warning: {}", w))));
+    body.push_str("<table style=\"border-collapse: collapse\">\n<tr>");
+    for heading in ["Field", "Type", "Offset", "Size", "Line"] {
+        write!(body, "<th style=\"{}\">{}</th>", CELL, heading).unwrap();
+    }
+    body.push_str("</tr>\n");
+    for entry in entries(&diff, true) {
+        let place = entry.server.as_ref().unwrap();
+        write!(body, "<tr{}>{}", row_style(&entry.status), html_label(&entry)).unwrap();
+        writeln!(body, "{}{}</tr>", html_cell(&html_types(&entry.types)), html_place(Some(place))).unwrap();
+    }
+    body.push_str("</table>\n<h3>Byte map</h3>\n");
+    body.push_str(&html_map(&[(None, spans(&diff.server_fields, diff.server_size, &[]))]));
+    html_page(&diff.name, &body)
+}
+
+pub fn diff_html(diff: &LayoutDiff, server_file: &str, client_file: &str) -> String {
+    let mut body = html_list([
+        format!("Server: <code>{}</code>, {} bytes, {}", html(server_file), diff.server_size, packing_name(diff.server_packing)),
+        format!("Client: <code>{}</code>, {} bytes, {}", html(client_file), diff.client_size, packing_name(diff.client_packing)),
+    ].into_iter().map(Html));
+    if diff.success() {
+        body.push_str("<p style=\"color: #146c43\"><strong>✅ The layouts match.</strong></p>\n");
+    } else {
+        body.push_str("<p style=\"color: #b02a37\"><strong>❌ The layouts differ.</strong></p>\n");
+    }
+    body.push_str(&html_list(struct_issues(diff)));
+    body.push_str("<table style=\"border-collapse: collapse\">\n<tr>");
+    for heading in ["Field", "Type", "Server offset", "Server size", "Server line", "Client offset", "Client size", "Client line", "Status"] {
+        write!(body, "<th style=\"{}\">{}</th>", CELL, heading).unwrap();
+    }
+    body.push_str("</tr>\n");
+    for entry in entries(diff, false) {
+        let status = match &entry.status {
+            Status::Ok => "✅ OK".to_string(),
+            Status::Padding => String::new(),
+            Status::Note(note) => format!("⚠️ {}", html(note)),
+            Status::Mismatch(issue) => format!("❌ {}", html(issue)),
+        };
+        write!(body, "<tr{}>{}{}", row_style(&entry.status), html_label(&entry), html_cell(&html_types(&entry.types))).unwrap();
+        writeln!(body, "{}{}{}</tr>", html_place(entry.server.as_ref()), html_place(entry.client.as_ref()), html_cell(&status)).unwrap();
+    }
+    body.push_str("</table>\n<h3>Byte map</h3>\n");
+    body.push_str(&html_map(&[
+        (Some(Side::Server), spans(&diff.server_fields, diff.server_size, &failing(diff, Side::Server))),
+        (Some(Side::Client), spans(&diff.client_fields, diff.client_size, &failing(diff, Side::Client))),
+    ]));
+    html_page(&diff.name, &body)
+}
+
+/// The table rows of a comparison; of the server side only for a single
+/// layout
+fn entries(diff: &LayoutDiff, single: bool) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    for row in &diff.rows {
+        match row {
+            Row::Field(f) => entries.push(Entry {
+                label: f.name().to_string(),
+                padding: false,
+                types: match (&f.server, &f.client) {
+                    (Some(s), Some(c)) if s.type_name != c.type_name && !single => vec![s.type_name.clone(), c.type_name.clone()],
+                    (Some(field), _) | (None, Some(field)) => vec![field.type_name.clone()],
+                    (None, None) => Vec::new(),
+                },
+                server: f.server.as_ref().map(place),
+                client: f.client.as_ref().map(place),
+                status: status(f),
+            }),
+            Row::Padding { side: Side::Client, .. } if single => {}
+            Row::Padding { side, bytes, before } => {
+                let fields = match side {
+                    Side::Server => &diff.server_fields,
+                    Side::Client => &diff.client_fields,
+                };
+                let offset = fields.iter().find(|f| f.depth == 0 && f.name == *before).map_or(0, |f| f.offset - bytes);
+                let at = Some(Place { offset: offset.to_string(), size: bytes.to_string(), line: None });
+                let (server, client) = match side {
+                    Side::Server => (at, None),
+                    Side::Client => (None, at),
+                };
+                entries.push(Entry { label: "padding".to_string(), padding: true, types: Vec::new(), server, client, status: Status::Padding });
+            }
+            Row::TrailingPadding { server, client } => {
+                let at = |bytes: usize, size: usize| (bytes > 0).then(|| Place { offset: (size - bytes).to_string(), size: bytes.to_string(), line: None });
+                let status = if server == client || single { Status::Padding } else { Status::Mismatch("Trailing padding differs".to_string()) };
+                let (server_at, client_at) = (at(*server, diff.server_size), at(*client, diff.client_size));
+                if single && server_at.is_none() {
+                    continue;
+                }
+                entries.push(Entry { label: "trailing padding".to_string(), padding: true, types: Vec::new(), server: server_at, client: client_at, status });
+            }
+        }
+    }
+    entries
+}
+
+fn place(field: &Field) -> Place {
+    match (field.bit_offset, field.bit_width) {
+        (Some(bit), Some(width)) => Place { offset: format!("{}.{}", field.offset, bit), size: format!("{} bits", width), line: Some(field.line) },
+        _ => Place { offset: field.offset.to_string(), size: field.size.to_string(), line: Some(field.line) },
+    }
+}
+
+fn status(f: &FieldDiff) -> Status {
+    let text = |issue: &FieldIssue| match issue {
+        FieldIssue::MissingInClient => "Missing in client".to_string(),
+        FieldIssue::ExtraInClient => "Extra in client".to_string(),
+        FieldIssue::NameDiff { client } => format!("Named {} in the client", client),
+        FieldIssue::PointerDanger => "Pointer, meaningless in another process".to_string(),
+        FieldIssue::TargetWidth { server, client } => format!("Pointer-wide: {} bytes on the server's target, {} on the client's", server, client),
+        FieldIssue::ByteOrder => "Read in the other byte order".to_string(),
+        issue => issue.to_string(),
+    };
+    let all = f.issues.iter().map(text).collect::<Vec<_>>().join(", ");
+    if f.issues.iter().any(FieldIssue::fails) {
+        Status::Mismatch(all)
+    } else if !f.issues.is_empty() {
+        Status::Note(all)
+    } else {
+        Status::Ok
+    }
+}
+
+/// What's wrong with the struct as a whole, rather than with a row
+fn struct_issues(diff: &LayoutDiff) -> Vec<String> {
+    let mut issues = Vec::new();
+    if diff.server_size != diff.client_size {
+        issues.push(format!("Size mismatch: Server={} bytes, Client={} bytes", diff.server_size, diff.client_size));
+    }
+    issues.extend(diff.order_issue());
+    if diff.server_packing != diff.client_packing {
+        issues.push(format!("Packing mismatch: Server is {}, Client is {}", packing_name(diff.server_packing), packing_name(diff.client_packing)));
+    }
+    if diff.server_target != diff.client_target {
+        issues.push(format!("Target mismatch: Server is {}, Client is {}", diff.server_target, diff.client_target));
+    }
+    issues.extend(diff.warnings.iter().map(|(side, warning)| format!("Warning: {}: {}", side, warning)));
+    issues.extend(diff.reads.iter().map(|r| r.to_string()));
+    issues
+}
+
+/// Names of one side's fields in a failing row
+fn failing(diff: &LayoutDiff, side: Side) -> Vec<String> {
+    diff.rows
+        .iter()
+        .filter_map(|row| match row {
+            Row::Field(f) if f.issues.iter().any(FieldIssue::fails) => match side {
+                Side::Server => f.server.as_ref(),
+                Side::Client => f.client.as_ref(),
+            },
+            _ => None,
+        })
+        .map(|field| field.name.clone())
+        .collect()
+}
+
+/// The top-level fields in offset order with the padding around them
+fn spans(fields: &[Field], size: usize, failing: &[String]) -> Vec<Span> {
+    let mut top: Vec<&Field> = fields.iter().filter(|f| f.depth == 0).collect();
+    top.sort_by_key(|f| f.offset);
+    let mut spans = Vec::new();
+    let mut end = 0;
+    for field in top {
+        if field.offset > end {
+            spans.push(Span { label: "(padding)".to_string(), start: end, end: field.offset, padding: true, failing: false });
+        }
+        spans.push(Span { label: field.name.clone(), start: field.offset, end: field.offset + field.size, padding: false, failing: failing.contains(&field.name) });
+        end = end.max(field.offset + field.size);
+    }
+    if size > end {
+        spans.push(Span { label: "(padding)".to_string(), start: end, end: size, padding: true, failing: false });
+    }
+    spans
+}
+
+/// A line per span: `#` over a field's bytes, `.` over padding, under a
+/// ruler every 8 bytes
+fn text_map(spans: &[Span], size: usize) -> String {
+    let per_char = size.div_ceil(MAP_WIDTH).max(1);
+    let width = size.div_ceil(per_char);
+    let label_width = spans.iter().map(|s| s.label.chars().count()).max().unwrap_or(0);
+    let mut ruler = String::new();
+    for column in (0..width).step_by(8) {
+        let mark = (column * per_char).to_string();
+        ruler.push_str(&format!("{:<8}", mark));
+    }
+    let mut out = format!("{:<w$}  {}\n", "", ruler.trim_end(), w = label_width);
+    for span in spans {
+        let bar: String = (0..width)
+            .map(|column| {
+                let (from, to) = (column * per_char, (column + 1) * per_char);
+                match (from < span.end && span.start < to, span.padding) {
+                    (true, true) => '.',
+                    (true, false) => '#',
+                    (false, _) => ' ',
+                }
+            })
+            .collect();
+        writeln!(out, "{:<w$} |{}| {}..{}", span.label, bar, span.start, span.end, w = label_width).unwrap();
+    }
+    out
+}
+
+/// Cell style of the tables
+const CELL: &str = "border: 1px solid #999; padding: 2px 6px";
+
+fn html_page(name: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{0} layout</title>\n</head>\n<body style=\"font-family: sans-serif\">\n<h2>{0}</h2>\n{1}</body>\n</html>\n",
+        html(name),
+        body
+    )
+}
+
+/// A row's background by its status
+fn row_style(status: &Status) -> String {
+    match status {
+        Status::Ok => String::new(),
+        Status::Padding => format!(" style=\"background: {}\"", PADDING),
+        Status::Note(_) => format!(" style=\"background: {}\"", NOTE),
+        Status::Mismatch(_) => format!(" style=\"background: {}\"", MISMATCH),
+    }
+}
+
+fn html_cell(content: &str) -> String {
+    format!("<td style=\"{}\">{}</td>", CELL, content)
+}
+
+fn html_label(entry: &Entry) -> String {
+    if entry.padding {
+        html_cell(&format!("<em>{}</em>", entry.label))
+    } else {
+        html_cell(&html_code(&entry.label))
+    }
+}
+
+fn html_types(types: &[String]) -> String {
+    types.iter().map(|t| html_code(t)).collect::<Vec<_>>().join(" / ")
+}
+
+fn html_code(text: &str) -> String {
+    if text.is_empty() { String::new() } else { format!("<code>{}</code>", html(text)) }
+}
+
+fn html_place(place: Option<&Place>) -> String {
+    let right = |content: String| format!("<td style=\"{}; text-align: right\">{}</td>", CELL, content);
+    match place {
+        Some(place) => format!("{}{}{}", right(place.offset.clone()), right(place.size.clone()), right(line(place))),
+        None => right(String::new()).repeat(3),
+    }
+}
+
+/// Already HTML, not to be escaped again
+struct Html(String);
+
+impl From<String> for Html {
+    fn from(text: String) -> Html {
+        Html(html(&text))
+    }
+}
+
+/// A bulleted list of `items`, nothing if there are none
+fn html_list<T: Into<Html>>(items: impl IntoIterator<Item = T>) -> String {
+    let items: Vec<String> = items.into_iter().map(|item| format!("<li>{}</li>\n", item.into().0)).collect();
+    if items.is_empty() { String::new() } else { format!("<ul>\n{}</ul>\n", items.concat()) }
+}
+
+/// A row per side, a cell per span as many bytes wide as it is
+fn html_map(sides: &[(Option<Side>, Vec<Span>)]) -> String {
+    let mut out = String::from("<table style=\"border-collapse: collapse; table-layout: fixed; width: 100%; font-size: small\">\n");
+    for (side, spans) in sides {
+        out.push_str("<tr>");
+        if let Some(side) = side {
+            write!(out, "<th style=\"width: 4em; text-align: left\">{}</th>", side).unwrap();
+        }
+        for span in spans {
+            let background = if span.padding { PADDING } else if span.failing { MISMATCH } else { FIELD };
+            write!(
+                out,
+                "<td colspan=\"{}\" title=\"{}: bytes {}..{}\" style=\"{}; background: {}; overflow: hidden; white-space: nowrap\">{}</td>",
+                span.end - span.start,
+                html(&span.label),
+                span.start,
+                span.end,
+                CELL,
+                background,
+                if span.padding { String::new() } else { html(&span.label) }
+            )
+            .unwrap();
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</table>\n");
+    out
+}
+
+fn line(place: &Place) -> String {
+    place.line.map_or(String::new(), |line| line.to_string())
+}
+
+fn md_label(entry: &Entry) -> String {
+    if entry.padding { format!("*{}*", entry.label) } else { md_code(&entry.label) }
+}
+
+fn md_types(types: &[String]) -> String {
+    types.iter().map(|t| md_code(t)).collect::<Vec<_>>().join(" / ")
+}
+
+fn md_code(text: &str) -> String {
+    if text.is_empty() { String::new() } else { format!("`{}`", md(text)) }
+}
+
+/// Text for a Markdown table cell
+fn md(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+fn html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyze_file;
+    use crate::test_support::fixture;
+
+    /// `document` is the golden file `name`; `VENOM_UPDATE_GOLDEN=1`
+    /// rewrites it instead
+    fn assert_golden(document: &str, name: &str) {
+        if std::env::var_os("VENOM_UPDATE_GOLDEN").is_some() {
+            std::fs::write(fixture(name), document).unwrap();
+        }
+        let golden = std::fs::read_to_string(fixture(name)).unwrap();
+        assert!(document == golden, "{} differs from the export:\n{}", name, document);
+    }
+
+    /// A fixture's struct, with its file named as on a command line
+    fn layout(file: &str, name: &str) -> StructLayout {
+        let mut layout = analyze_file(&fixture(file), name).unwrap();
+        layout.file_path = file.to_string();
+        layout
+    }
+
+    #[test]
+    fn test_export_layout() {
+        let layout = layout("test_padding.h", "PaddingStruct");
+        assert_golden(&layout_markdown(&layout), "test_export_layout.md");
+        assert_golden(&layout_html(&layout), "test_export_layout.html");
+    }
+
+    #[test]
+    fn test_export_diff() {
+        let diff = compare_struct_layouts(&layout("test_all_server.h", "Sample"), &layout("test_all_client.h", "Sample"));
+        assert_golden(&diff_markdown(&diff, "test_all_server.h", "test_all_client.h"), "test_export_diff.md");
+        assert_golden(&diff_html(&diff, "test_all_server.h", "test_all_client.h"), "test_export_diff.html");
+    }
+
+    #[test]
+    fn test_byte_map_scale() {
+        // 256 bytes: 4 to a character
+        let fields = [Field { name: "data".to_string(), type_name: "char".to_string(), size: 250, offset: 0, align: 1, is_array: true, array_len: 250, is_flexible: false, line: 1, is_pointer: false, depth: 0, parent: None, bit_offset: None, bit_width: None }];
+        let map = text_map(&spans(&fields, 256, &[]), 256);
+        let lines: Vec<&str> = map.lines().collect();
+        assert_eq!(lines[1], format!("data      |{} | 0..250", "#".repeat(63)));
+        assert_eq!(lines[2], format!("(padding) |{}..| 250..256", " ".repeat(62)));
+    }
+}
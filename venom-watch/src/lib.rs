@@ -1,21 +1,43 @@
 pub mod models;
 pub mod analysis;
+pub mod sarif;
+pub mod baseline;
+pub mod preprocess;
+pub mod export;
+pub mod saved;
+#[cfg(test)]
+mod test_support;
 
 pub use models::*;
-pub use analysis::layout::{analyze_file, analyze_enum};
-pub use analysis::engine::check_leaks;
+pub use analysis::layout::{analyze_file, analyze_file_for, analyze_enum, declared_types, enums_supported};
+pub use analysis::dart::analyze_dart_decoder;
+pub use analysis::python::analyze_python_decoder;
+pub use analysis::compare::{compare_struct_layouts, compare_enum_layouts, compare_decoder_layout, LayoutDiff, EnumDiff, FieldDiff, FieldIssue, MemberIssue, MovedField, ReadIssue, Row, Side};
+pub use analysis::engine::{check_leaks, check_leaks_with, DEFAULT_ALLOCATORS};
 pub use analysis::overflow::check_overflows;
+pub use analysis::project::{check_project, source_files};
+pub use analysis::reorder::suggest_order;
+pub use analysis::handshake::{check_constants, DEFAULT_CONSTANT_NAMES};
+pub use preprocess::{Preprocessor, Source};
 
 use std::path::PathBuf;
 
-pub fn run_safety_analysis(path: &PathBuf) -> Result<LeakReport, String> {
-    let mut report = check_leaks(path)?;
+/// Leaks (with `allocators` tracked besides `DEFAULT_ALLOCATORS`) and overflows
+pub fn run_safety_analysis(path: &PathBuf, allocators: &[String]) -> Result<LeakReport, String> {
+    let mut report = check_leaks_with(path, allocators)?;
     if let Ok(overflow_events) = check_overflows(path.clone()) {
         for event in overflow_events {
             report.findings.push(event.context.clone());
+            report.severities.push(event.kind.severity().unwrap_or(Severity::Critical));
             report.events.push(event);
             report.success = false;
         }
     }
     Ok(report)
 }
+
+/// `cpu_usage_percent`, `cpuUsagePercent` and `CPUUsagePercent` are the
+/// same field in each language's naming convention
+pub fn same_name(a: &str, b: &str) -> bool {
+    a.replace('_', "").eq_ignore_ascii_case(&b.replace('_', ""))
+}
@@ -1,8 +1,11 @@
 use clap::Parser;
 use colored::Colorize;
 use std::path::PathBuf;
-use venom_watch::{analyze_file, analyze_enum, run_safety_analysis, StructLayout, EnumLayout, ValidationResult, MemoryEventKind};
-use serde_json;
+use serde::Serialize;
+use venom_watch::{sarif, export, suggest_order, check_constants, enums_supported, analyze_dart_decoder, analyze_python_decoder, check_project, source_files, Preprocessor, Source, compare_struct_layouts, compare_enum_layouts, compare_decoder_layout, LayoutDiff, EnumDiff, FieldDiff, FieldIssue, Row, Decoder, ValidationResult, HeaderReport, TypeReport, MemoryEventKind, Severity, LeakReport, Reordering, ConstantsReport, StructLayout, EnumLayout, Target};
+use venom_watch::saved::{SavedLayout, TypeLayout};
+use venom_watch::analysis::compare::packing_name;
+use venom_watch::baseline::Baseline;
 use std::io;
 use ratatui::{
     backend::CrosstermBackend,
@@ -12,11 +15,17 @@ use ratatui::{
     Terminal,
 };
 use crossterm::{
+    cursor::MoveTo,
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
     execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    terminal::{disable_raw_mode, enable_raw_mode, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
 };
 
+#[cfg(test)]
+mod test_support;
+mod tui;
+mod watch;
+
 #[derive(Parser, Debug)]
 #[command(name = "venom-watch")]
 #[command(about = "🕵️ VenomMemory Structure Validator", long_about = None)]
@@ -25,10 +34,20 @@ struct Cli {
     #[arg(short, long)]
     server: Option<PathBuf>,
 
-    /// Path to the client source file (C or Rust)
+    /// Path to the client source file (C, C++, Rust, Go or Zig)
     #[arg(short, long)]
     client: Option<PathBuf>,
 
+    /// Dart binding whose `fromBytes` offsets to check against the server
+    /// struct (`venom_binding.dart`)
+    #[arg(long)]
+    client_dart: Option<PathBuf>,
+
+    /// Python binding whose `struct` format strings to check against the
+    /// server struct (`venom_binding.py`)
+    #[arg(long)]
+    client_python: Option<PathBuf>,
+
     /// Name of the struct to validate
     #[arg(short = 'n', long)]
     struct_name: Option<String>,
@@ -37,37 +56,215 @@ struct Cli {
     #[arg(short = 'e', long)]
     enum_name: Option<String>,
 
-    /// Check for memory leaks in a C file
+    /// Validate every struct and enum of the server file against the client
+    #[arg(long, requires_all = ["server", "client"], conflicts_with_all = ["struct_name", "enum_name"])]
+    all: bool,
+
+    /// Check for memory leaks in a C file, or in every .c and .h file under
+    /// a directory
     #[arg(long)]
     check_leaks: Option<PathBuf>,
 
-    /// Output results in JSON format
+    /// With a directory for --check-leaks, leave out the files and
+    /// directories matching this glob: `vendor`, `*_test.c`, `gen/**`.
+    /// Repeatable
+    #[arg(long, requires = "check_leaks")]
+    exclude: Vec<String>,
+
+    /// More functions whose result (or what they store through a `&p`
+    /// argument) has to be freed, comma-separated: `venom_alloc,xstrdup`.
+    /// malloc, calloc, realloc, strdup, strndup, asprintf and vasprintf
+    /// always are
+    #[arg(long, value_delimiter = ',', requires = "check_leaks")]
+    allocators: Vec<String>,
+
+    /// Check that these files (C, C++, Rust, Go, Zig, Python or Dart) define
+    /// the channel name and magic number the same way, and report the ones
+    /// that don't agree with the most
+    #[arg(long, num_args = 1..)]
+    check_constants: Vec<PathBuf>,
+
+    /// Constants --check-constants compares, by how their names end,
+    /// ignoring case and underscores, comma-separated: `*_VERSION,magic`.
+    /// `magic,channel_name` by default
+    #[arg(long, value_delimiter = ',', requires = "check_constants")]
+    constant_names: Vec<String>,
+
+    /// Read C and C++ files the way the compiler does, through `$CC -E`
+    /// (`cc` by default): with `#if`s decided, macros expanded and included
+    /// types known. Lines are still reported in the original file
+    #[arg(long)]
+    preprocess: bool,
+
+    /// Directory to search for includes with --preprocess. Repeatable
+    #[arg(short = 'I', long = "include-dir", requires = "preprocess")]
+    include_dirs: Vec<PathBuf>,
+
+    /// Macro to define with --preprocess, `NAME` or `NAME=VALUE`. Repeatable
+    #[arg(short = 'D', long = "define", requires = "preprocess")]
+    defines: Vec<String>,
+
+    /// Output results in JSON format (same as `--format json`)
     #[arg(short, long)]
     json: bool,
 
+    /// Output format: the text report, JSON, or a SARIF 2.1.0 log for code
+    /// scanning
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
     /// Launch interactive TUI for memory lifecycle visualization
     #[arg(long)]
     tui: bool,
+
+    /// Re-run the analyses whenever one of their files changes
+    #[arg(long)]
+    watch: bool,
+
+    /// JSON file of accepted findings: they're reported as notes and don't
+    /// fail the run
+    #[arg(long)]
+    baseline: Option<PathBuf>,
+
+    /// Write every finding of this run to the `--baseline` file
+    #[arg(long, requires = "baseline", conflicts_with = "watch")]
+    update_baseline: bool,
+
+    /// Write the struct's layout to --out as documentation, with a byte
+    /// map: the comparison when --client is given too, the server's layout
+    /// otherwise
+    #[arg(long, value_enum, requires_all = ["server", "struct_name", "out"])]
+    export: Option<Export>,
+
+    /// File --export writes
+    #[arg(long, requires = "export")]
+    out: Option<PathBuf>,
+
+    /// Save the struct's or enum's layout to this file, for --compare-json
+    /// on another machine: the --server file's, or --client's without one
+    #[arg(long, value_name = "FILE.json")]
+    dump_layout: Option<PathBuf>,
+
+    /// Compare two layouts saved with --dump-layout, the server's and the
+    /// client's, without their sources
+    #[arg(long, num_args = 2, value_names = ["SERVER.json", "CLIENT.json"])]
+    compare_json: Vec<PathBuf>,
+
+    /// Suggest the order of the struct's fields that wastes the least
+    /// padding. Not for a packed struct, nor one compared with a client:
+    /// reordering would break what the other side reads
+    #[arg(long, requires_all = ["server", "struct_name"])]
+    optimize: bool,
+
+    /// With --optimize, also print the struct declared in that order (C,
+    /// C++ or Rust)
+    #[arg(long, requires = "optimize")]
+    emit_definition: bool,
+
+    /// List the members of every struct/union field in the table, leaf by
+    /// leaf at their absolute offsets (`hdr.flags`), up to 4 levels deep.
+    /// Without it, only the members of one where something differs
+    #[arg(long)]
+    expand_nested: bool,
+
+    /// Least severe finding that fails the run: info, warning or critical.
+    /// The ones below it are still reported
+    #[arg(long, default_value_t = Severity::Warning)]
+    fail_on: Severity,
+
+    /// Byte order and pointer width the server is built for: le64, le32,
+    /// be64 or be32. Sizes pointers, long, size_t, usize and the like
+    #[arg(long, value_name = "TARGET", default_value_t = Target::LE64)]
+    target_server: Target,
+
+    /// The same for the client; fields read in the other byte order are
+    /// reported when the two differ
+    #[arg(long, value_name = "TARGET", default_value_t = Target::LE64)]
+    target_client: Target,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+enum Format {
+    Text,
+    Json,
+    Sarif,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+enum Export {
+    Md,
+    Html,
+}
+
+/// `analyze_dart_decoder`, `analyze_python_decoder`
+type AnalyzeDecoder = fn(&PathBuf, &str) -> Result<Decoder, String>;
+
+impl Cli {
+    /// `--preprocess` and its flags
+    fn preprocessor(&self) -> Option<Preprocessor> {
+        self.preprocess.then(|| Preprocessor { include_dirs: self.include_dirs.clone(), defines: self.defines.clone() })
+    }
+
+    /// `--json` is `--format json`; from here on it means no text report
+    fn resolve_format(mut self) -> Self {
+        if self.json {
+            self.format = Format::Json;
+        }
+        self.json = self.format != Format::Text;
+        self
+    }
 }
 
 fn main() {
-    let args = Cli::parse();
+    let args = Cli::parse().resolve_format();
+    if args.watch {
+        watch_and_rerun(&args);
+    } else if !run_checks(&args) {
+        std::process::exit(1);
+    }
+}
+
+/// Every analysis the arguments ask for; false if any of them fails with
+/// findings the baseline doesn't accept
+fn run_checks(args: &Cli) -> bool {
     let mut overall_success = true;
+    let mut run = Run { findings: Vec::new(), baseline: None, updating: args.update_baseline, fail_on: args.fail_on };
+    if let Some(path) = &args.baseline
+        && !args.update_baseline {
+        match Baseline::load(path) {
+            Ok(baseline) => run.baseline = Some(baseline),
+            Err(e) => {
+                if !args.json { println!("{} {}", "Error:".red(), e); }
+                return false;
+            }
+        }
+    }
 
     if !args.json {
         println!("{}", "🕵️ Venom Watch: Advanced Memory Analysis...".cyan().bold());
     }
 
     // 1. Structure/Enum Validation
-    if let (Some(server_path), Some(client_path)) = (&args.server, &args.client) {
-        if let Some(struct_name) = &args.struct_name {
-            match analyze_file(server_path, struct_name) {
+    let preprocessor = args.preprocessor();
+    let server = args.server.as_ref().map(|path| source(preprocessor.as_ref(), path).for_target(args.target_server));
+    if let (Some(server), Some(client_path)) = (&server, &args.client) {
+        let client = source(preprocessor.as_ref(), client_path).for_target(args.target_client);
+        if args.all {
+            match check_all(server, &client, args.json, args.expand_nested) {
+                Ok((report, findings)) => {
+                    if !emit(args, &report, report.success, findings, &mut run) { overall_success = false; }
+                }
+                Err(e) => {
+                    if !args.json { println!("{} {}", "Error:".red(), e); }
+                    overall_success = false;
+                }
+            }
+        } else if let Some(struct_name) = &args.struct_name {
+            match server.analyze_file(struct_name) {
                 Ok(server_layout) => {
-                    match analyze_file(client_path, struct_name) {
+                    match client.analyze_file(struct_name) {
                         Ok(client_layout) => {
-                            if !compare_layouts(&server_layout, &client_layout, args.json) {
-                                overall_success = false;
-                            }
+                            if !check_struct(args, &server_layout, &client_layout, &mut run) { overall_success = false; }
                         }
                         Err(e) => {
                             if !args.json { println!("{} {}", "Error:".red(), e); }
@@ -81,13 +278,11 @@ fn main() {
                 }
             }
         } else if let Some(enum_name) = &args.enum_name {
-            match analyze_enum(server_path, enum_name) {
+            match server.analyze_enum(enum_name) {
                 Ok(server_layout) => {
-                    match analyze_enum(client_path, enum_name) {
+                    match client.analyze_enum(enum_name) {
                         Ok(client_layout) => {
-                            if !compare_enums(&server_layout, &client_layout, args.json) {
-                                overall_success = false;
-                            }
+                            if !check_enum(args, &server_layout, &client_layout, &mut run) { overall_success = false; }
                         }
                         Err(e) => {
                             if !args.json { println!("{} {}", "Error:".red(), e); }
@@ -102,32 +297,185 @@ fn main() {
             }
         }
     }
+    // Layouts saved by --dump-layout, compared as if they'd just been read
+    if let [server_json, client_json] = &args.compare_json[..] {
+        let loaded = SavedLayout::load(server_json).and_then(|server| SavedLayout::load(client_json).map(|client| (server, client)));
+        match loaded.map(|(server, client)| (server.layout, client.layout)) {
+            Ok((TypeLayout::Struct(server_layout), TypeLayout::Struct(client_layout))) => {
+                if !check_struct(args, &server_layout, &client_layout, &mut run) { overall_success = false; }
+            }
+            Ok((TypeLayout::Enum(server_layout), TypeLayout::Enum(client_layout))) => {
+                if !check_enum(args, &server_layout, &client_layout, &mut run) { overall_success = false; }
+            }
+            Ok((server_layout, client_layout)) => {
+                let kind = |layout: &TypeLayout| if matches!(layout, TypeLayout::Struct(_)) { "a struct" } else { "an enum" };
+                if !args.json {
+                    println!("{} {} is {} and {} {}: nothing to compare", "Error:".red(), server_json.display(), kind(&server_layout), client_json.display(), kind(&client_layout));
+                }
+                overall_success = false;
+            }
+            Err(e) => {
+                if !args.json { println!("{} {}", "Error:".red(), e); }
+                overall_success = false;
+            }
+        }
+    }
+
+    // Documenting the server's layout alone
+    if let (Some(server), None, Some(struct_name), Some(format)) = (&server, &args.client, &args.struct_name, args.export) {
+        match server.analyze_file(struct_name) {
+            Ok(layout) => {
+                let document = match format {
+                    Export::Md => export::layout_markdown(&layout),
+                    Export::Html => export::layout_html(&layout),
+                };
+                if !write_export(args, &document) { overall_success = false; }
+            }
+            Err(e) => {
+                if !args.json { println!("{} {}", "Error:".red(), e); }
+                overall_success = false;
+            }
+        }
+    }
+
+    // The layout saved for --compare-json somewhere else
+    if let Some(out) = &args.dump_layout {
+        let dumped_source = match (&args.server, &args.client) {
+            (Some(path), _) => Some(source(preprocessor.as_ref(), path).for_target(args.target_server)),
+            (None, Some(path)) => Some(source(preprocessor.as_ref(), path).for_target(args.target_client)),
+            (None, None) => None,
+        };
+        let dumped = match (dumped_source, &args.struct_name, &args.enum_name) {
+            (Some(dumped), Some(struct_name), _) => dumped.analyze_file(struct_name).map(TypeLayout::Struct),
+            (Some(dumped), None, Some(enum_name)) => dumped.analyze_enum(enum_name).map(TypeLayout::Enum),
+            _ => Err("--dump-layout needs --server (or --client) and --struct-name or --enum-name".to_string()),
+        };
+        match dumped.and_then(|layout| {
+            let name = layout.name().to_string();
+            SavedLayout::new(layout).save(out).map(|()| name)
+        }) {
+            Ok(name) => {
+                if !args.json { println!("\n💾 Saved the layout of {} to {}", name.blue(), out.display()); }
+            }
+            Err(e) => {
+                if !args.json { println!("{} {}", "Error:".red(), e); }
+                overall_success = false;
+            }
+        }
+    }
+
+    // Field order with the least padding
+    if let (true, Some(server), Some(struct_name)) = (args.optimize, &server, &args.struct_name) {
+        let compared = args.client.is_some() || args.client_dart.is_some() || args.client_python.is_some();
+        let suggested = server.analyze_file(struct_name).and_then(|layout| {
+            if compared {
+                return Err(format!("{} is compared with a client: reordering its fields breaks compatibility with it", layout.name));
+            }
+            suggest_order(&layout)
+        });
+        match suggested {
+            Ok(mut reordering) => {
+                if !args.emit_definition { reordering.definition = None; }
+                if args.format == Format::Json {
+                    println!("{}", serde_json::to_string_pretty(&reordering).unwrap());
+                } else if !args.json {
+                    print_reordering(&reordering);
+                }
+            }
+            Err(e) => {
+                if args.format == Format::Json {
+                    println!("{}", serde_json::to_string_pretty(&serde_json::json!({ "name": struct_name, "error": e })).unwrap());
+                } else if !args.json {
+                    println!("\n{} {}", "⚠️  Not reordering:".yellow().bold(), e);
+                }
+            }
+        }
+    }
+
+    let decoders: [(&Option<PathBuf>, AnalyzeDecoder); 2] = [(&args.client_dart, analyze_dart_decoder), (&args.client_python, analyze_python_decoder)];
+    for (client, analyze_decoder) in decoders {
+        let (Some(server), Some(client_path), Some(struct_name)) = (&server, client, &args.struct_name) else { continue };
+        let checked = server.analyze_file(struct_name)
+            .and_then(|server| analyze_decoder(client_path, struct_name).map(|decoder| compare_decoder_layout(&server, &decoder)));
+        match checked {
+            Ok(diff) => {
+                if !args.json { print_layout_diff(&diff, args.expand_nested); }
+                let findings = sarif::layout_findings(&diff, &display(&server.path), &display(client_path));
+                if !emit(args, &layout_result(&diff), diff.success(), findings, &mut run) { overall_success = false; }
+            }
+            Err(e) => {
+                if !args.json { println!("{} {}", "Error:".red(), e); }
+                overall_success = false;
+            }
+        }
+    }
 
     // 2. Leak Detection
-    if let Some(leak_path) = &args.check_leaks {
-        match run_safety_analysis(leak_path) {
+    if let Some(leak_path) = args.check_leaks.as_ref().filter(|p| p.is_dir()) {
+        match check_project(leak_path, &args.allocators, &args.exclude) {
+            Ok(project) => {
+                let findings: Vec<_> = project.reports.iter().flat_map(sarif::leak_findings).collect();
+                let success = project.reports.iter().all(|r| r.passes(args.fail_on));
+                if !project.errors.is_empty() { overall_success = false; }
+                if args.tui {
+                    if let Err(e) = run_tui(project.reports, None) {
+                        eprintln!("TUI Error: {}", e);
+                    }
+                    for error in &project.errors {
+                        println!("{} {}: {}", "Could not analyze".red(), error.file_path, error.error);
+                    }
+                    if !run.accept(findings, success) { overall_success = false; }
+                } else {
+                    if !args.json {
+                        println!("\n{} {}", "🔍 Memory Leak Report:".bold(), display(leak_path));
+                        println!("{}", "--------------------------------------------------".dimmed());
+                        for report in &project.reports {
+                            if report.findings.is_empty() {
+                                println!("✅ {}", report.file_path.green());
+                            } else {
+                                println!("📄 {}", report.file_path.bold());
+                                print_leak_findings(report, "   ");
+                            }
+                        }
+                        for error in &project.errors {
+                            println!("⛔ {}: {}", error.file_path.red(), error.error);
+                        }
+                        let totals = &project.totals;
+                        println!(
+                            "\n{} files, {} findings ({} critical, {} warning, {} info), {} could not be analyzed",
+                            totals.files, totals.findings, totals.critical, totals.warning, totals.info, totals.errors
+                        );
+                    }
+                    if !emit(args, &project, success, findings, &mut run) { overall_success = false; }
+                }
+            }
+            Err(e) => {
+                if !args.json { println!("{} {}", "Error:".red(), e); }
+                overall_success = false;
+            }
+        }
+    } else if let Some(leak_path) = &args.check_leaks {
+        match source(preprocessor.as_ref(), leak_path).safety_analysis(&args.allocators) {
             Ok(report) => {
+                let findings = sarif::leak_findings(&report);
                 if args.tui {
-                    if let Err(e) = run_tui(&report) {
+                    let success = report.passes(args.fail_on);
+                    if let Err(e) = run_tui(vec![report], None) {
                         eprintln!("TUI Error: {}", e);
                     }
-                } else if args.json {
-                    // JSON mode handles its own output for leaks too if needed, 
-                    // but usually we want a combined JSON.
-                    // For now, let's keep it simple: if leaks are requested, print leak report.
-                    println!("{}", serde_json::to_string_pretty(&report).unwrap());
+                    if !run.accept(findings, success) { overall_success = false; }
                 } else {
-                    println!("\n{}", "🔍 Memory Leak Report:".bold());
-                    println!("{}", "--------------------------------------------------".dimmed());
-                    if report.success {
-                        println!("{}", "✅ No obvious leaks detected in local scopes.".green());
-                    } else {
-                        for finding in &report.findings {
-                            println!("❌ {}", finding.red());
+                    if !args.json {
+                        println!("\n{}", "🔍 Memory Leak Report:".bold());
+                        println!("{}", "--------------------------------------------------".dimmed());
+                        if report.success {
+                            println!("{}", "✅ No obvious leaks detected in local scopes.".green());
+                        } else {
+                            print_leak_findings(&report, "");
                         }
                     }
+                    if !emit(args, &report, report.passes(args.fail_on), findings, &mut run) { overall_success = false; }
                 }
-                if !report.success { overall_success = false; }
             }
             Err(e) => {
                 if !args.json { println!("{} {}", "Error:".red(), e); }
@@ -136,76 +484,403 @@ fn main() {
         }
     }
 
-    if !overall_success {
+    // 3. Constants every side defines for itself
+    if !args.check_constants.is_empty() {
+        match check_constants(&args.check_constants, &args.constant_names) {
+            Ok(report) => {
+                if !args.json { print_constants(&report); }
+                let findings = sarif::constant_findings(&report);
+                if !emit(args, &report, report.success, findings, &mut run) { overall_success = false; }
+            }
+            Err(e) => {
+                if !args.json { println!("{} {}", "Error:".red(), e); }
+                overall_success = false;
+            }
+        }
+    }
+
+    if args.format == Format::Sarif {
+        println!("{}", serde_json::to_string_pretty(&sarif::document(&run.findings)).unwrap());
+    }
+    if let Some(path) = args.baseline.as_ref().filter(|_| args.update_baseline) {
+        let baseline = Baseline::from_findings(&run.findings);
+        match baseline.save(path) {
+            Ok(()) => {
+                if !args.json { println!("\n📌 Wrote {} findings to the baseline {}", baseline.findings.len(), path.display()); }
+            }
+            Err(e) => {
+                if !args.json { println!("{} {}", "Error:".red(), e); }
+                overall_success = false;
+            }
+        }
+    }
+    overall_success
+}
+
+/// The findings of a run so far, and the baseline they're checked against
+struct Run {
+    findings: Vec<sarif::Finding>,
+    baseline: Option<Baseline>,
+    /// `--update-baseline`: every finding is about to be accepted
+    updating: bool,
+    fail_on: Severity,
+}
+
+impl Run {
+    /// Keep a check's findings, marking the ones the baseline accepts; true
+    /// unless one it doesn't accept is at `--fail-on` or above, or the
+    /// check failed without any
+    fn accept(&mut self, mut findings: Vec<sarif::Finding>, success: bool) -> bool {
+        for finding in &mut findings {
+            finding.baselined = self.updating || self.baseline.as_ref().is_some_and(|b| b.accepts(finding));
+        }
+        let failing = findings.iter().any(|f| !f.baselined && f.severity() >= self.fail_on);
+        let passed = !failing && (success || !findings.is_empty());
+        self.findings.extend(findings);
+        passed
+    }
+}
+
+/// A report in the chosen machine format: printed as JSON right away, or its
+/// findings kept for the SARIF log printed once every analysis has run.
+/// False if it has findings at `--fail-on` or above the baseline doesn't
+/// accept.
+fn emit<T: Serialize>(args: &Cli, report: &T, success: bool, findings: Vec<sarif::Finding>, run: &mut Run) -> bool {
+    if args.format == Format::Json {
+        println!("{}", serde_json::to_string_pretty(report).unwrap());
+    }
+    let kept = run.findings.len();
+    let passed = run.accept(findings, success);
+    if !args.json {
+        for finding in run.findings[kept..].iter().filter(|f| f.baselined) {
+            println!("ℹ️  {} {}", "Accepted by the baseline:".dimmed(), finding.message.dimmed());
+        }
+    }
+    passed
+}
+
+/// A struct comparison, in the text report, `--export`'s document and the
+/// chosen machine format; false if it fails
+fn check_struct(args: &Cli, server: &StructLayout, client: &StructLayout, run: &mut Run) -> bool {
+    let diff = compare_struct_layouts(server, client);
+    if !args.json { print_layout_diff(&diff, args.expand_nested); }
+    let mut exported = true;
+    if let Some(format) = args.export {
+        let document = match format {
+            Export::Md => export::diff_markdown(&diff, &server.file_path, &client.file_path),
+            Export::Html => export::diff_html(&diff, &server.file_path, &client.file_path),
+        };
+        exported = write_export(args, &document);
+    }
+    let findings = sarif::layout_findings(&diff, &server.file_path, &client.file_path);
+    emit(args, &layout_result(&diff), diff.success(), findings, run) && exported
+}
+
+/// An enum comparison, in the text report and the chosen machine format;
+/// false if it fails
+fn check_enum(args: &Cli, server: &EnumLayout, client: &EnumLayout, run: &mut Run) -> bool {
+    let diff = compare_enum_layouts(server, client);
+    if !args.json { print_enum_diff(&diff); }
+    let findings = sarif::enum_findings(&diff, &server.file_path, &client.file_path);
+    emit(args, &enum_result(&diff), diff.success(), findings, run)
+}
+
+/// `--export`'s document to `--out`; false if it can't be written
+fn write_export(args: &Cli, document: &str) -> bool {
+    let Some(out) = &args.out else { return true };
+    match std::fs::write(out, document) {
+        Ok(()) => {
+            if !args.json { println!("\n📝 Wrote the layout documentation to {}", out.display()); }
+            true
+        }
+        Err(e) => {
+            if !args.json { println!("{} Could not write {}: {}", "Error:".red(), out.display(), e); }
+            false
+        }
+    }
+}
+
+/// `--optimize`'s suggestion: the new order, what it saves, and the
+/// definition if asked for
+fn print_reordering(reordering: &Reordering) {
+    if reordering.saved_bytes == 0 {
+        println!("\n{} {} already wastes as little padding as it can ({} bytes)", "✅".green(), reordering.name.blue(), reordering.current_size);
+        return;
+    }
+    println!(
+        "\n💡 {} {}: {} → {} bytes, {} saved",
+        "Field order with the least padding for".bold(),
+        reordering.name.blue(),
+        reordering.current_size,
+        reordering.suggested_size,
+        format!("{} bytes", reordering.saved_bytes).green().bold()
+    );
+    for (i, name) in reordering.suggested_order.iter().enumerate() {
+        println!("   {:>2}. {}", i + 1, name);
+    }
+    if let Some(definition) = &reordering.definition {
+        println!("\n{}", definition.trim_end());
+    }
+}
+
+/// `--check-constants`: each constant's value, and every definition that
+/// differs from it
+fn print_constants(report: &ConstantsReport) {
+    println!("\n{} {} files", "🔑 Constants across".bold(), report.files.len());
+    println!("{}", "--------------------------------------------------".dimmed());
+    for constant in &report.constants {
+        let Some(value) = &constant.value else {
+            println!("{} No file defines a constant ending in {}", "ℹ️ ".dimmed(), constant.name.blue());
+            continue;
+        };
+        let stale: Vec<_> = constant.definitions.iter().filter(|d| !d.agrees).collect();
+        if stale.is_empty() {
+            println!("✅ {} is {} in all {} definitions", constant.name.blue(), value.green(), constant.definitions.len());
+            continue;
+        }
+        println!("❌ {} is {} in {} of {} definitions", constant.name.blue(), value, constant.definitions.len() - stale.len(), constant.definitions.len());
+        for definition in stale {
+            println!("   {} {}:{}: {} = {}", "[CRITICAL]".red().bold(), definition.file, definition.line, definition.name, definition.value.red());
+        }
+    }
+}
+
+/// A leak report's findings, one per line, marked with their severity
+fn print_leak_findings(report: &LeakReport, indent: &str) {
+    for (finding, severity) in report.findings.iter().zip(&report.severities) {
+        let tag = format!("[{}]", severity);
+        match severity {
+            Severity::Critical => println!("{}❌ {} {}", indent, tag.red().bold(), finding.red()),
+            Severity::Warning => println!("{}⚠️  {} {}", indent, tag.yellow().bold(), finding.yellow()),
+            Severity::Info => println!("{}ℹ️  {} {}", indent, tag.dimmed(), finding.dimmed()),
+        }
+    }
+}
+
+/// A file given on the command line, through the preprocessor with
+/// `--preprocess`. One it can't preprocess is read as it is, with a warning.
+fn source(preprocessor: Option<&Preprocessor>, path: &std::path::Path) -> Source {
+    let Some(preprocessor) = preprocessor else { return Source::plain(path) };
+    preprocessor.source(path).unwrap_or_else(|e| {
+        eprintln!("{} {}; reading {} as it is", "Warning:".yellow(), e, path.display());
+        Source::plain(path)
+    })
+}
+
+fn display(path: &std::path::Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+/// `--watch`: the analyses once, then again after each change to one of the
+/// files they read, until interrupted. With `--tui` only the leak check is
+/// re-run, and the TUI picks up each new report in place.
+fn watch_and_rerun(args: &Cli) {
+    // A directory's sources as they are now; files added later aren't watched
+    let leak_sources = match &args.check_leaks {
+        Some(dir) if dir.is_dir() => source_files(dir, &args.exclude).unwrap_or_else(|e| {
+            println!("{} {}", "Error:".red(), e);
+            std::process::exit(1);
+        }),
+        leak_path => leak_path.iter().cloned().collect(),
+    };
+    let paths: Vec<PathBuf> = [&args.server, &args.client, &args.client_dart, &args.client_python]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .chain(leak_sources.iter().cloned())
+        .chain(args.check_constants.iter().cloned())
+        .chain(args.compare_json.iter().cloned())
+        .collect();
+    if paths.is_empty() {
+        println!("{} Nothing to watch: give --server/--client, --compare-json, --check-leaks or --check-constants", "Error:".red());
         std::process::exit(1);
     }
+    let paths: Vec<PathBuf> = paths.into_iter().chain(args.baseline.clone()).collect();
+    let (_watcher, changes) = match watch::watch_files(&paths) {
+        Ok(watching) => watching,
+        Err(e) => {
+            println!("{} Could not watch files: {}", "Error:".red(), e);
+            std::process::exit(1);
+        }
+    };
+
+    if args.tui && let Some(leak_path) = &args.check_leaks {
+        let analyze = {
+            let (leak_path, allocators, exclude, preprocessor) = (leak_path.clone(), args.allocators.clone(), args.exclude.clone(), args.preprocessor());
+            move || if leak_path.is_dir() {
+                check_project(&leak_path, &allocators, &exclude).map(|project| project.reports)
+            } else {
+                source(preprocessor.as_ref(), &leak_path).safety_analysis(&allocators).map(|report| vec![report])
+            }
+        };
+        let first = match analyze() {
+            Ok(reports) => reports,
+            Err(e) => {
+                println!("{} {}", "Error:".red(), e);
+                std::process::exit(1);
+            }
+        };
+        let (reports, updates) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            watch::run_loop(&leak_sources, &changes, watch::DEBOUNCE, |_| {
+                if let Ok(latest) = analyze() {
+                    reports.send(latest).ok();
+                }
+            })
+        });
+        if let Err(e) = run_tui(first, Some(updates)) {
+            eprintln!("TUI Error: {}", e);
+        }
+        return;
+    }
+
+    let rerun = |changed: &[PathBuf]| {
+        if !args.json {
+            execute!(io::stdout(), Clear(ClearType::All), MoveTo(0, 0)).ok();
+        }
+        let success = run_checks(args);
+        let banner = format!(
+            "[{}] {}{}",
+            watch::timestamp(),
+            if success { "✅ PASS".green().bold() } else { "❌ FAIL".red().bold() },
+            if changed.is_empty() { String::new() } else { format!(" after changes to {}", file_names(changed)) }
+        );
+        // stderr in JSON mode, so stdout stays a stream of reports
+        if args.json {
+            eprintln!("{}", banner);
+        } else {
+            println!("\n{}\n{}", banner, "Watching for changes (Ctrl+C to stop)...".dimmed());
+        }
+    };
+    rerun(&[]);
+    watch::run_loop(&paths, &changes, watch::DEBOUNCE, rerun);
 }
 
-fn run_tui(report: &venom_watch::LeakReport) -> Result<(), io::Error> {
+fn file_names(paths: &[PathBuf]) -> String {
+    paths.iter().map(|p| p.file_name().unwrap_or(p.as_os_str()).to_string_lossy()).collect::<Vec<_>>().join(", ")
+}
+
+/// The leak reports over their sources, with a list to switch between them
+/// when there are several; `updates` replaces the reports while the TUI is
+/// open (`--watch`)
+fn run_tui(mut reports: Vec<LeakReport>, updates: Option<std::sync::mpsc::Receiver<Vec<LeakReport>>>) -> Result<(), io::Error> {
+    if reports.is_empty() {
+        println!("No C files to show");
+        return Ok(());
+    }
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let code_content = std::fs::read_to_string(&report.file_path).unwrap_or_default();
-    let lines: Vec<&str> = code_content.lines().collect();
-    
-    let mut scroll = 0;
+    let mut code_content = std::fs::read_to_string(&reports[0].file_path).unwrap_or_default();
+    let mut updated_at = None;
+    let mut state = tui::TuiState::new(&reports[0], code_content.lines().count());
+    state.set_files(reports.len());
+    let mut shown = 0;
 
     loop {
+        if let Some(updates) = &updates
+            && let Some(latest) = updates.try_iter().last()
+            && !latest.is_empty()
+        {
+            reports = latest;
+            state.set_files(reports.len());
+            updated_at = Some(watch::timestamp());
+            // Read again below
+            shown = usize::MAX;
+        }
+        if state.file != shown {
+            shown = state.file;
+            code_content = std::fs::read_to_string(&reports[shown].file_path).unwrap_or_default();
+            state.set_report(&reports[shown], code_content.lines().count());
+        }
+        let report = &reports[shown];
+        let lines: Vec<&str> = code_content.lines().collect();
+        // The code view's borders take a line above and below
+        state.resize(terminal.size()?.height.saturating_sub(2) as usize);
+
         terminal.draw(|f| {
-            let chunks = Layout::default()
+            let constraints = if reports.len() > 1 {
+                vec![Constraint::Percentage(20), Constraint::Percentage(55), Constraint::Percentage(25)]
+            } else {
+                vec![Constraint::Percentage(70), Constraint::Percentage(30)]
+            };
+            let mut chunks = Layout::default()
                 .direction(Direction::Horizontal)
-                .constraints([Constraint::Percentage(70), Constraint::Percentage(30)].as_ref())
-                .split(f.size());
+                .constraints(constraints)
+                .split(f.size())
+                .to_vec();
+
+            // 0. Files, with how many findings each has
+            if reports.len() > 1 {
+                let files: Vec<ListItem> = reports
+                    .iter()
+                    .enumerate()
+                    .map(|(i, r)| {
+                        let name = std::path::Path::new(&r.file_path).file_name().map_or(r.file_path.clone(), |n| n.to_string_lossy().to_string());
+                        let mut style = Style::default().fg(if r.findings.is_empty() { Color::Green } else { Color::Red });
+                        if i == shown {
+                            style = style.add_modifier(Modifier::REVERSED);
+                        }
+                        ListItem::new(format!(" {} ({})", name, r.findings.len())).style(style)
+                    })
+                    .collect();
+                f.render_widget(List::new(files).block(Block::default().borders(Borders::ALL).title(" Files ")), chunks.remove(0));
+            }
 
             // 1. Code View
             let mut code_items = Vec::new();
-            for (i, line) in lines.iter().enumerate().skip(scroll) {
+            for (i, line) in lines.iter().enumerate().skip(state.scroll) {
                 let line_num = i + 1;
                 let mut style = Style::default();
                 let mut prefix = format!("{:>3} | ", line_num);
-                
+
                 // Overlay markers
-                for event in &report.events {
-                    if event.line == line_num {
-                        match event.kind {
-                            MemoryEventKind::Allocation => {
-                                prefix = format!("🟢 {:>1} | ", "A");
-                                style = style.fg(Color::Green);
-                            }
-                            MemoryEventKind::Free => {
-                                prefix = format!("🔴 {:>1} | ", "F");
-                                style = style.fg(Color::Red);
-                            }
-                            MemoryEventKind::PotentialMove => {
-                                prefix = format!("🟡 {:>1} | ", "M");
-                                style = style.fg(Color::Yellow);
-                            }
-                            MemoryEventKind::ExplicitMove => {
-                                prefix = format!("🔵 {:>1} | ", "E");
-                                style = style.fg(Color::Blue);
-                            }
-                            MemoryEventKind::ConditionalFree => {
-                                prefix = format!("🟧 {:>1} | ", "C");
-                                style = style.fg(Color::Rgb(255, 165, 0));
-                            }
-                            MemoryEventKind::UseAfterFree => {
-                                prefix = format!("💀 {:>1} | ", "U");
-                                style = style.fg(Color::Magenta);
-                            }
-                            MemoryEventKind::DoubleFree => {
-                                prefix = format!("🚫 {:>1} | ", "D");
-                                style = style.fg(Color::LightRed);
-                            }
-                            MemoryEventKind::BufferOverflow => {
-                                prefix = format!("⚠️  {:>1} | ", "O");
-                                style = style.fg(Color::LightRed).add_modifier(Modifier::BOLD);
-                            }
+                if let Some(kind) = state.marker(i) {
+                    match kind {
+                        MemoryEventKind::Allocation => {
+                            prefix = format!("🟢 {:>1} | ", "A");
+                            style = style.fg(Color::Green);
+                        }
+                        MemoryEventKind::Free => {
+                            prefix = format!("🔴 {:>1} | ", "F");
+                            style = style.fg(Color::Red);
+                        }
+                        MemoryEventKind::PotentialMove => {
+                            prefix = format!("🟡 {:>1} | ", "M");
+                            style = style.fg(Color::Yellow);
+                        }
+                        MemoryEventKind::ExplicitMove => {
+                            prefix = format!("🔵 {:>1} | ", "E");
+                            style = style.fg(Color::Blue);
+                        }
+                        MemoryEventKind::Escape => {
+                            prefix = format!("🟣 {:>1} | ", "X");
+                            style = style.fg(Color::LightBlue);
+                        }
+                        MemoryEventKind::ConditionalFree => {
+                            prefix = format!("🟧 {:>1} | ", "C");
+                            style = style.fg(Color::Rgb(255, 165, 0));
+                        }
+                        MemoryEventKind::UseAfterFree => {
+                            prefix = format!("💀 {:>1} | ", "U");
+                            style = style.fg(Color::Magenta);
+                        }
+                        MemoryEventKind::DoubleFree => {
+                            prefix = format!("🚫 {:>1} | ", "D");
+                            style = style.fg(Color::LightRed);
+                        }
+                        MemoryEventKind::BufferOverflow => {
+                            prefix = format!("⚠️  {:>1} | ", "O");
+                            style = style.fg(Color::LightRed).add_modifier(Modifier::BOLD);
                         }
                     }
                 }
+                if i == state.cursor {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
 
                 code_items.push(ListItem::new(format!("{}{}", prefix, line)).style(style));
             }
@@ -218,12 +893,13 @@ fn run_tui(report: &venom_watch::LeakReport) -> Result<(), io::Error> {
                 ListItem::new(" LEYEND:").style(Style::default().add_modifier(Modifier::BOLD)),
                 ListItem::new(" 🟢 A: Allocation").style(Style::default().fg(Color::Green)),
                 ListItem::new(" 🔴 F: Free").style(Style::default().fg(Color::Red)),
-                ListItem::new(" 🟡 M: Potential Move").style(Style::default().fg(Color::Yellow)),
+                ListItem::new(" 🟡 M: Potential Move (warning)").style(Style::default().fg(Color::Yellow)),
                 ListItem::new(" 🔵 E: Explicit Move").style(Style::default().fg(Color::Blue)),
-                ListItem::new(" 🟧 C: Conditional Free").style(Style::default().fg(Color::Rgb(255, 165, 0))),
-                ListItem::new(" 💀 U: Use-After-Free").style(Style::default().fg(Color::Magenta)),
-                ListItem::new(" 🚫 D: Double Free").style(Style::default().fg(Color::LightRed)),
-                ListItem::new(" ⚠️  O: Buffer Overflow").style(Style::default().fg(Color::LightRed).add_modifier(Modifier::BOLD)),
+                ListItem::new(" 🟣 X: Escape").style(Style::default().fg(Color::LightBlue)),
+                ListItem::new(" 🟧 C: Conditional Free (warning)").style(Style::default().fg(Color::Rgb(255, 165, 0))),
+                ListItem::new(" 💀 U: Use-After-Free (critical)").style(Style::default().fg(Color::Magenta)),
+                ListItem::new(" 🚫 D: Double Free (critical)").style(Style::default().fg(Color::LightRed)),
+                ListItem::new(" ⚠️  O: Buffer Overflow (critical)").style(Style::default().fg(Color::LightRed).add_modifier(Modifier::BOLD)),
                 ListItem::new(""),
                 ListItem::new(" FINDINGS:").style(Style::default().add_modifier(Modifier::BOLD)),
             ];
@@ -231,32 +907,37 @@ fn run_tui(report: &venom_watch::LeakReport) -> Result<(), io::Error> {
             if report.findings.is_empty() {
                 status_text.push(ListItem::new(" ✅ No leaks!").style(Style::default().fg(Color::Green)));
             } else {
-                for finding in &report.findings {
-                    status_text.push(ListItem::new(format!(" ❌ {}", finding)).style(Style::default().fg(Color::Red)));
+                for (i, finding) in report.findings.iter().enumerate() {
+                    let (icon, color) = match report.severities.get(i) {
+                        Some(Severity::Info) => ("ℹ️ ", Color::Gray),
+                        Some(Severity::Warning) => ("⚠️ ", Color::Yellow),
+                        _ => ("❌", Color::Red),
+                    };
+                    let mut style = Style::default().fg(color);
+                    if state.selected == Some(i) {
+                        style = style.add_modifier(Modifier::REVERSED);
+                    }
+                    status_text.push(ListItem::new(format!(" {} {}", icon, finding)).style(style));
                 }
             }
 
             status_text.push(ListItem::new(""));
-            status_text.push(ListItem::new(" (Press 'q' to exit, arrows to scroll)"));
+            if let Some(at) = &updated_at {
+                status_text.push(ListItem::new(format!(" 🔄 Re-analyzed at {}", at)).style(Style::default().fg(Color::Cyan)));
+            }
+            status_text.push(ListItem::new(" (q: exit, arrows/PgUp/PgDn: scroll, g/G: top/bottom, n/p: next/previous event, Tab: next finding, ←/→: previous/next file)"));
 
             let status_list = List::new(status_text)
                 .block(Block::default().borders(Borders::ALL).title(" Memory Lifecycle "));
             f.render_widget(status_list, chunks[1]);
         })?;
 
-        if event::poll(std::time::Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                match key.code {
-                    KeyCode::Char('q') => break,
-                    KeyCode::Up => {
-                        if scroll > 0 { scroll -= 1; }
-                    }
-                    KeyCode::Down => {
-                        if scroll < lines.len() - 1 { scroll += 1; }
-                    }
-                    _ => {}
-                }
+        if event::poll(std::time::Duration::from_millis(100))?
+            && let Event::Key(key) = event::read()? {
+            if key.code == KeyCode::Char('q') {
+                break;
             }
+            state.key(key.code);
         }
     }
 
@@ -271,225 +952,652 @@ fn run_tui(report: &venom_watch::LeakReport) -> Result<(), io::Error> {
     Ok(())
 }
 
-fn compare_layouts(server: &StructLayout, client: &StructLayout, json_mode: bool) -> bool {
-    let mut all_match = true;
-    let mut issues = Vec::new();
+fn wire_note(wire: &Option<String>) -> String {
+    match wire {
+        Some(serializer) => format!(" (wire layout, offsets from {})", serializer),
+        None => String::new(),
+    }
+}
+
+/// `--all`: each struct and enum of the server file against the client's
+/// namesake, a table each, then a summary. Types the client lacks fail;
+/// those only the client has are noted. The findings are the report's, for
+/// `--format sarif`.
+fn check_all(server: &Source, client: &Source, json_mode: bool, expand_nested: bool) -> Result<(HeaderReport, Vec<sarif::Finding>), String> {
+    let server_types = server.declared_types()?;
+    let client_types = client.declared_types()?;
+    let (server_file, client_file) = (display(&server.path), display(&client.path));
+    let mut types = Vec::new();
+    let mut notes = Vec::new();
+    let mut findings = Vec::new();
 
-    if server.total_size != client.total_size {
-        all_match = false;
-        issues.push(format!("Size mismatch: Server={} bytes, Client={} bytes", server.total_size, client.total_size));
+    for name in &server_types.structs {
+        let checked = server.analyze_file(name)
+            .and_then(|server| client.analyze_file(name).map(|client| compare_struct_layouts(&server, &client)))
+            .map(|diff| {
+                if !json_mode { print_layout_diff(&diff, expand_nested); }
+                findings.extend(sarif::layout_findings(&diff, &server_file, &client_file));
+                layout_result(&diff)
+            });
+        types.push(type_report(name, "struct", checked, json_mode));
+    }
+    for name in &server_types.enums {
+        if !enums_supported(&client.path) {
+            notes.push(format!("Enum {} not checked: enums are only read from C files", name));
+            continue;
+        }
+        let checked = server.analyze_enum(name)
+            .and_then(|server| client.analyze_enum(name).map(|client| compare_enum_layouts(&server, &client)))
+            .map(|diff| {
+                if !json_mode { print_enum_diff(&diff); }
+                findings.extend(sarif::enum_findings(&diff, &server_file, &client_file));
+                enum_result(&diff)
+            });
+        types.push(type_report(name, "enum", checked, json_mode));
+    }
+    for t in &types {
+        if let Some(error) = &t.error {
+            findings.push(sarif::missing_type(&t.kind, &t.name, error, &client_file));
+        }
+    }
+    for (kind, client_names, server_names) in [("Struct", &client_types.structs, &server_types.structs), ("Enum", &client_types.enums, &server_types.enums)] {
+        for name in client_names.iter().filter(|n| !server_names.contains(n)) {
+            notes.push(format!("{} {} is only in the client", kind, name));
+        }
     }
 
+    let report = HeaderReport { success: types.iter().all(|t| t.success), types, notes };
     if !json_mode {
-        println!("\n{} {}", "Validating Structure:".bold(), server.name.blue());
-        println!("{}: {} bytes", "Server Struct".green(), server.total_size);
-        println!("{}: {} bytes", "Client Struct".yellow(), client.total_size);
-        println!("--------------------------------------------------");
-        if all_match { println!("{}", "✅ Total sizes match.".green()); }
-        else { println!("{}", "⚠️  SIZE MISMATCH IDENTIFIED!".red().bold()); }
-        println!("\n{:<20} | {:<16} | {:<16} | {:<30}", "Field", "Server (Line)", "Client (Line)", "Status");
-        println!("{}", "-".repeat(90));
+        let matching = report.types.iter().filter(|t| t.success).count();
+        let missing = report.types.iter().filter(|t| t.error.is_some()).count();
+        println!("\n{}", "=".repeat(50));
+        println!(
+            "{} {} types: {} match, {} mismatched, {} not compared",
+            "Summary:".bold(),
+            report.types.len(),
+            matching.to_string().green(),
+            (report.types.len() - matching - missing).to_string().red(),
+            missing.to_string().red()
+        );
+        for note in &report.notes {
+            println!("ℹ️  {}", note.dimmed());
+        }
     }
+    Ok((report, findings))
+}
 
-    let mut s_idx = 0;
-    let mut c_idx = 0;
-    let mut s_current_offset = 0;
-    let mut c_current_offset = 0;
+fn type_report(name: &str, kind: &str, checked: Result<ValidationResult, String>, json_mode: bool) -> TypeReport {
+    match checked {
+        Ok(result) => TypeReport { name: name.to_string(), kind: kind.to_string(), success: result.success, error: None, result: Some(result) },
+        Err(e) => {
+            if !json_mode { println!("\n{} {} {}: {}", "❌".red(), kind, name.blue(), e.red()); }
+            TypeReport { name: name.to_string(), kind: kind.to_string(), success: false, error: Some(e), result: None }
+        }
+    }
+}
 
-    loop {
-        let s_field = server.fields.get(s_idx);
-        let c_field = client.fields.get(c_idx);
-
-        if s_field.is_none() && c_field.is_none() {
-            // Check for trailing padding (struct total size vs last field)
-            if s_current_offset < server.total_size || c_current_offset < client.total_size {
-                let s_pad = server.total_size - s_current_offset;
-                let c_pad = client.total_size - c_current_offset;
-                if s_pad > 0 || c_pad > 0 {
-                    if !json_mode {
-                        println!("{:<20} | {:<16} | {:<16} | {}", 
-                            "[TRAILING PAD]".cyan().dimmed(),
-                            if s_pad > 0 { format!("{} bytes", s_pad).cyan() } else { "N/A".into() },
-                            if c_pad > 0 { format!("{} bytes", c_pad).cyan() } else { "N/A".into() },
-                            if s_pad == c_pad { "✅ OK".green() } else { "⚠️  Mismatch".yellow() }
-                        );
-                    } else {
-                        if s_pad != c_pad {
-                            issues.push(format!("Trailing padding mismatch: Server={} bytes, Client={} bytes", s_pad, c_pad));
-                            all_match = false;
-                        } else if s_pad > 0 {
-                            issues.push(format!("Info: Trailing padding detected ({} bytes)", s_pad));
-                        }
-                    }
-                }
+/// The JSON report of a struct comparison
+fn layout_result(diff: &LayoutDiff) -> ValidationResult {
+    ValidationResult {
+        success: diff.success(),
+        server_size: diff.server_size,
+        client_size: diff.client_size,
+        issues: diff.issues(),
+        server_fields: diff.server_fields.clone(),
+        client_fields: diff.client_fields.clone(),
+    }
+}
+
+/// The JSON report of an enum comparison
+fn enum_result(diff: &EnumDiff) -> ValidationResult {
+    ValidationResult {
+        success: diff.success(),
+        server_size: diff.server_repr.unwrap_or(0),
+        client_size: diff.client_repr.unwrap_or(0),
+        issues: diff.issues(),
+        server_fields: Vec::new(),
+        client_fields: Vec::new(),
+    }
+}
+
+/// The table of a struct comparison, one row per field; a struct/union
+/// field's members under it with `expand_nested` or when one differs
+fn print_layout_diff(diff: &LayoutDiff, expand_nested: bool) {
+    println!("\n{} {}", "Validating Structure:".bold(), diff.name.blue());
+    println!("{}: {} bytes{}", "Server Struct".green(), diff.server_size, wire_note(&diff.server_wire));
+    println!("{}: {} bytes{}", "Client Struct".yellow(), diff.client_size, wire_note(&diff.client_wire));
+    println!("--------------------------------------------------");
+    if diff.server_size == diff.client_size { println!("{}", "✅ Total sizes match.".green()); }
+    else { println!("{}", "⚠️  SIZE MISMATCH IDENTIFIED!".red().bold()); }
+    if let Some(order) = diff.order_issue() {
+        println!("{}", format!("❌ {}", order).red().bold());
+    }
+    if diff.server_packing != diff.client_packing {
+        println!("{} Server is {}, Client is {}", "⚠️  PACKING DIFFERS:".yellow().bold(), packing_name(diff.server_packing), packing_name(diff.client_packing));
+    }
+    if diff.server_target != diff.client_target {
+        println!("{} Server is {}, Client is {}", "⚠️  TARGETS DIFFER:".yellow().bold(), diff.server_target, diff.client_target);
+    }
+    for (side, warning) in &diff.warnings {
+        println!("{} {}: {}", "⚠️ ".yellow(), side, warning.yellow());
+    }
+    for read in &diff.reads {
+        println!("❌ {}", read.to_string().red());
+    }
+    println!("\n{:<20} | {:<16} | {:<16} | {:<30}", "Field", "Server (Line)", "Client (Line)", "Status");
+    println!("{}", "-".repeat(90));
+
+    for (row, shown) in diff.rows.iter().zip(rows_shown(&diff.rows, expand_nested)) {
+        match row {
+            Row::Field(_) if !shown => {}
+            Row::Field(f) => print_field_row(f),
+            Row::Padding { side, bytes, .. } => {
+                let pad = format!("{} bytes", bytes).cyan();
+                let (server, client) = match side {
+                    venom_watch::Side::Server => (pad, "".into()),
+                    venom_watch::Side::Client => ("".into(), pad),
+                };
+                println!("{:<20} | {:<16} | {:<16} | {}", "[PADDING]".cyan().dimmed(), server, client, "INTERNAL".dimmed());
+            }
+            Row::TrailingPadding { server, client } => {
+                println!("{:<20} | {:<16} | {:<16} | {}", 
+                    "[TRAILING PAD]".cyan().dimmed(),
+                    if *server > 0 { format!("{} bytes", server).cyan() } else { "N/A".into() },
+                    if *client > 0 { format!("{} bytes", client).cyan() } else { "N/A".into() },
+                    if server == client { "✅ OK".green() } else { "⚠️  Mismatch".yellow() }
+                );
             }
-            break;
         }
+    }
+}
 
-        // Check for internal padding in server
-        if let Some(s) = s_field {
-            if s.offset > s_current_offset {
-                let pad = s.offset - s_current_offset;
-                if !json_mode {
-                    println!("{:<20} | {:<16} | {:<16} | {}", 
-                        "[PADDING]".cyan().dimmed(),
-                        format!("{} bytes", pad).cyan(),
-                        "",
-                        "INTERNAL".dimmed()
-                    );
-                } else {
-                    issues.push(format!("Info: Internal padding in server before {} ({} bytes)", s.name, pad));
-                }
-                s_current_offset = s.offset;
+/// Which of `rows` the table prints: all of them with `expand_nested`,
+/// otherwise a nested member only when a row of its top-level field's
+/// group has an issue
+fn rows_shown(rows: &[Row], expand_nested: bool) -> Vec<bool> {
+    let depth = |row: &Row| match row {
+        Row::Field(f) => f.server.as_ref().or(f.client.as_ref()).map_or(0, |f| f.depth),
+        _ => 0,
+    };
+    let mut shown = vec![true; rows.len()];
+    let mut start = 0;
+    while start < rows.len() {
+        let end = (start + 1..rows.len()).find(|&i| depth(&rows[i]) == 0).unwrap_or(rows.len());
+        let differs = rows[start..end].iter().any(|row| matches!(row, Row::Field(f) if !f.issues.is_empty()));
+        shown[start + 1..end].fill(expand_nested || differs);
+        start = end;
+    }
+    shown
+}
+
+/// A field's row: a nested member indented under its parent, by its own name
+fn print_field_row(f: &FieldDiff) {
+    let label = match (&f.server, &f.client) {
+        (Some(field), _) | (None, Some(field)) if field.depth > 0 => {
+            let member = field.name.rsplit('.').next().unwrap_or(&field.name);
+            format!("{}{}", "  ".repeat(field.depth), member).chars().take(20).collect::<String>()
+        }
+        (Some(s), Some(_)) => s.name.chars().take(20).collect(),
+        _ => f.name().to_string(),
+    };
+    let s_info = f.server.as_ref().map_or("MISSING".to_string(), position);
+    let c_info = f.client.as_ref().map_or("MISSING".to_string(), position);
+    let mut status = match f.issues.first() {
+        Some(FieldIssue::MissingInClient) => "❌ Missing in Client".red(),
+        Some(FieldIssue::ExtraInClient) => "❌ Extra in Client".red(),
+        Some(issue @ (FieldIssue::OffsetMismatch { .. } | FieldIssue::SizeMismatch { .. } | FieldIssue::BitMismatch { .. } | FieldIssue::FlexibleArray { .. } | FieldIssue::TargetWidth { .. } | FieldIssue::ByteOrder)) => {
+            format!("❌ {}", issue).red()
+        }
+        Some(FieldIssue::NameDiff { .. }) => "⚠️ Name Diff".yellow(),
+        Some(FieldIssue::PointerDanger) | None => "✅ OK".green(),
+    }
+    .to_string();
+    // What the targets add to an offset or size mismatch
+    for issue in f.issues.iter().skip(1).filter(|i| matches!(i, FieldIssue::TargetWidth { .. } | FieldIssue::ByteOrder)) {
+        status = format!("{}, {}", status, issue.to_string().red());
+    }
+    if f.issues.contains(&FieldIssue::PointerDanger) {
+        status = format!("{} | {}", status, "🚨 POINTER DANGER!".on_red().white().bold());
+    }
+    println!("{:<20} | {:<16} | {:<16} | {}", label, s_info, c_info, status);
+}
+
+/// Where a field sits in the table: `@byte`, or `@byte.bit:width` for a bitfield
+fn position(f: &venom_watch::Field) -> String {
+    match (f.bit_offset, f.bit_width) {
+        (Some(bit), Some(width)) => format!("@{}.{}:{} (L{})", f.offset, bit, width, f.line),
+        _ => format!("@{: <4} (L{})", f.offset, f.line),
+    }
+}
+
+/// The table of an enum comparison, one row per server member
+fn print_enum_diff(diff: &EnumDiff) {
+    println!("\n{} {}", "Validating Enum:".bold(), diff.name.blue());
+    let width = |repr: Option<usize>| repr.map_or(String::new(), |bytes| format!(", {} bytes each", bytes));
+    println!("{}: {} members{}", "Server Enum".green(), diff.server_members, width(diff.server_repr));
+    println!("{}: {} members{}", "Client Enum".yellow(), diff.client_members, width(diff.client_repr));
+    println!("--------------------------------------------------");
+    println!("{:<25} | {:<15} | {:<15} | {:<20}", "Member", "Server (Val)", "Client (Val)", "Status");
+    println!("{}", "-".repeat(80));
+
+    for m in &diff.members {
+        let s = &m.server;
+        match &m.client {
+            Some(c) => {
+                let spelled = if c.name == s.name { String::new() } else { format!(" as {}", c.name) };
+                let status = if m.issue.is_none() { format!("✅ OK{}", spelled).green() } else { format!("❌ Mismatch{} (@L{})", spelled, c.line).red() };
+                println!("{:<25} | {:<15} | {:<15} | {}", s.name, format!("{} (L{})", s.value, s.line), format!("{} (L{})", c.value, c.line), status);
             }
+            None => println!("{:<25} | {:<15} | {:<15} | {}", s.name, s.value, "MISSING", "❌ Missing in Client".red()),
         }
+    }
+    if diff.repr_mismatch() {
+        println!("{}", format!("❌ Width mismatch: Server={} bytes, Client={} bytes", diff.server_repr.unwrap_or(0), diff.client_repr.unwrap_or(0)).red());
+    }
 
-        // Check for internal padding in client
-        if let Some(c) = c_field {
-            if c.offset > c_current_offset {
-                let pad = c.offset - c_current_offset;
-                if !json_mode {
-                    println!("{:<20} | {:<16} | {:<16} | {}", 
-                        "[PADDING]".cyan().dimmed(),
-                        "",
-                        format!("{} bytes", pad).cyan(),
-                        "INTERNAL".dimmed()
-                    );
-                } else {
-                    issues.push(format!("Info: Internal padding in client before {} ({} bytes)", c.name, pad));
-                }
-                c_current_offset = c.offset;
-            }
-        }
-
-        match (s_field, c_field) {
-            (Some(s), Some(c)) => {
-                let mut status_issues = Vec::new();
-                let status = if s.offset != c.offset {
-                    all_match = false;
-                    status_issues.push("Offset Mismatch".to_string());
-                    "❌ Offset Mismatch".red()
-                } else if s.size != c.size {
-                    all_match = false;
-                    status_issues.push("Size Mismatch".to_string());
-                    "❌ Size Mismatch".red()
-                } else if s.name != c.name {
-                    status_issues.push("Name Diff".to_string());
-                     "⚠️ Name Diff".yellow()
-                } else {
-                     "✅ OK".green()
-                };
+    if diff.success() { println!("\n{}", "✅ Enums are fully consistent!".green().bold()); }
+    else { println!("\n{}", "⚠️  ENUM INCONSISTENCY DETECTED!".red().bold()); }
+}
 
-                if s.is_pointer || c.is_pointer {
-                    status_issues.push("🚨 POINTER DANGER!".to_string());
-                }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use venom_watch::{analyze_enum, analyze_file, analyze_file_for, run_safety_analysis};
+    use crate::test_support::fixture;
 
-                if !json_mode {
-                    let mut status_str = status.to_string();
-                    if s.is_pointer || c.is_pointer {
-                        status_str = format!("{} | {}", status_str, "🚨 POINTER DANGER!".on_red().white().bold());
-                    }
-                    let s_info = format!("@{: <4} (L{})", s.offset, s.line);
-                    let c_info = format!("@{: <4} (L{})", c.offset, c.line);
-                    println!("{:<20} | {:<16} | {:<16} | {}", s.name.chars().take(20).collect::<String>(), s_info, c_info, status_str);
-                }
-                
-                if !status_issues.is_empty() {
-                    issues.push(format!("Field {}: {}", s.name, status_issues.join(", ")));
-                }
+    #[test]
+    fn test_union_member_order_mismatch() {
+        let server = analyze_file(&fixture("test_union_server.h"), "Message").unwrap();
+        let same = analyze_file(&fixture("test_union_client.h"), "Message").unwrap();
+        let reordered = analyze_file(&fixture("test_union_reordered.h"), "Message").unwrap();
 
-                s_current_offset = s.offset + s.size;
-                c_current_offset = c.offset + c.size;
-                s_idx += 1;
-                c_idx += 1;
-            },
-            (Some(s), None) => {
-                 all_match = false;
-                 issues.push(format!("Field {} missing in client", s.name));
-                 if !json_mode {
-                     let s_info = format!("@{: <4} (L{})", s.offset, s.line);
-                     println!("{:<20} | {:<16} | {:<16} | {}", s.name, s_info, "MISSING", "❌ Missing in Client".red());
-                 }
-                 s_current_offset = s.offset + s.size;
-                 s_idx += 1;
-            },
-            (None, Some(c)) => {
-                 all_match = false;
-                 issues.push(format!("Field {} extra in client", c.name));
-                 if !json_mode {
-                     let c_info = format!("@{: <4} (L{})", c.offset, c.line);
-                     println!("{:<20} | {:<16} | {:<16} | {}", c.name, "MISSING", c_info, "❌ Extra in Client".red());
-                 }
-                 c_current_offset = c.offset + c.size;
-                 c_idx += 1;
-            },
-            _ => unreachable!(),
-        }
-    }
-
-    if json_mode {
-        let result = ValidationResult {
-            success: all_match,
-            server_size: server.total_size,
-            client_size: client.total_size,
-            issues,
+        let result = layout_result(&compare_struct_layouts(&server, &same));
+        assert!(result.success, "{:?}", result.issues);
+
+        // Same size and top-level offsets; only the swapped union members differ
+        let result = layout_result(&compare_struct_layouts(&server, &reordered));
+        assert!(!result.success);
+        let problems: Vec<&String> = result.issues.iter().filter(|i| !i.starts_with("Info:")).collect();
+        assert_eq!(problems, ["Field body.paint.color: Offset Mismatch", "Field body.paint.alpha: Offset Mismatch"]);
+    }
+
+    #[test]
+    fn test_flexible_array_against_fixed_tail() {
+        let (server, client) = (fixture("test_fam_server.h"), fixture("test_fam_client.h"));
+
+        // `words[0]` and `words[]` are the same thing
+        let batch = compare_struct_layouts(&analyze_file(&server, "Batch").unwrap(), &analyze_file(&client, "Batch").unwrap());
+        assert!(batch.success(), "{:?}", batch.issues());
+
+        let diff = compare_struct_layouts(&analyze_file(&server, "Packet").unwrap(), &analyze_file(&client, "Packet").unwrap());
+        let result = layout_result(&diff);
+        assert!(!result.success);
+        let problems: Vec<&String> = result.issues.iter().filter(|i| !i.starts_with("Info:")).collect();
+        assert_eq!(problems, ["Size mismatch: Server=8 bytes, Client=264 bytes", "Field payload: Flexible array in server, fixed 256 bytes in client"]);
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!((json["server_fields"][2]["is_flexible"].as_bool(), json["client_fields"][2]["is_flexible"].as_bool()), (Some(true), Some(false)));
+
+        let findings = sarif::layout_findings(&diff, "test_fam_server.h", "test_fam_client.h");
+        let payload: Vec<_> = findings.iter().filter(|f| f.subject == "Packet.payload").map(|f| (f.rule, f.line)).collect();
+        assert_eq!(payload, [("flexible-array", Some(10))]);
+    }
+
+    #[test]
+    fn test_field_order_is_critical() {
+        let path = fixture("test_field_order.h");
+        let frame = analyze_file(&path, "Frame").unwrap();
+
+        let diff = compare_struct_layouts(&frame, &analyze_file(&path, "SwappedFrame").unwrap());
+        let result = layout_result(&diff);
+        assert!(!result.success);
+        assert_eq!(result.issues, ["Field order differs: Server declares width, height; Client declares height, width", "Info: Trailing padding detected (6 bytes)"]);
+        let findings = sarif::layout_findings(&diff, "test_field_order.h", "test_field_order.h");
+        let order: Vec<_> = findings.iter().filter(|f| f.rule == "field-order").map(|f| (f.severity(), f.line)).collect();
+        assert_eq!(order, [(Severity::Critical, Some(15))]);
+
+        // A rename is only noted
+        let diff = compare_struct_layouts(&frame, &analyze_file(&path, "RenamedFrame").unwrap());
+        assert!(diff.success());
+        assert!(diff.moved.is_empty());
+        assert_eq!(diff.issues(), ["Field flags: Name Diff", "Info: Trailing padding detected (6 bytes)"]);
+    }
+
+    // Frame is the same on both sides; only its Header's flags moved
+    #[test]
+    fn test_nested_field_named() {
+        let server = analyze_file(&fixture("test_nested_server.h"), "Frame").unwrap();
+        let client = analyze_file(&fixture("test_nested_client.h"), "Frame").unwrap();
+        let diff = compare_struct_layouts(&server, &client);
+        assert!(!diff.success());
+        let problems: Vec<String> = diff.issues().into_iter().filter(|i| !i.starts_with("Info:")).collect();
+        assert_eq!(problems, ["Field hdr: Size Mismatch", "Field hdr.flags: Offset Mismatch"]);
+
+        let findings = sarif::layout_findings(&diff, "test_nested_server.h", "test_nested_client.h");
+        let flags: Vec<_> = findings.iter().filter(|f| f.rule == "offset-mismatch").map(|f| (f.subject.as_str(), f.line)).collect();
+        assert_eq!(flags, [("Frame.hdr.flags", Some(10))]);
+        let json = serde_json::to_value(&diff).unwrap();
+        let row = json["rows"].as_array().unwrap().iter().find(|r| r["Field"]["server"]["name"] == "hdr.flags").unwrap();
+        assert_eq!(row["Field"]["server"]["parent"], "hdr");
+
+        // hdr's members are listed for the mismatch, body's only when asked
+        let names = |expand_nested| {
+            diff.rows.iter().zip(rows_shown(&diff.rows, expand_nested)).filter_map(|(row, shown)| match row {
+                Row::Field(f) if shown => Some(f.name().to_string()),
+                _ => None,
+            }).collect::<Vec<_>>()
         };
-        println!("{}", serde_json::to_string_pretty(&result).unwrap());
+        assert_eq!(names(false), ["hdr", "hdr.version", "hdr.flags", "hdr.kind", "seq", "body"]);
+        assert_eq!(names(true), ["hdr", "hdr.version", "hdr.flags", "hdr.kind", "seq", "body", "body.length", "body.checksum"]);
     }
 
-    all_match
-}
+    // test_protocol.h and test_cpp_client.hpp are the protocol.h and
+    // protocol.hpp `venom init --lang c,cpp` generates for the demo schema
+    #[test]
+    fn test_generated_cpp_client_matches_c_header() {
+        let server = analyze_file(&fixture("test_protocol.h"), "MonitorState").unwrap();
+        let client = analyze_file(&fixture("test_cpp_client.hpp"), "monitor::State").unwrap();
+        assert_eq!(client.total_size, 112);
 
-fn compare_enums(server: &EnumLayout, client: &EnumLayout, json_mode: bool) -> bool {
-    let mut all_match = true;
-    let mut issues = Vec::new();
+        let result = layout_result(&compare_struct_layouts(&server, &client));
+        assert!(result.success, "{:?}", result.issues);
 
-    if !json_mode {
-        println!("\n{}: {} members", "Server Enum".green(), server.members.len());
-        println!("{}: {} members", "Client Enum".yellow(), client.members.len());
-        println!("--------------------------------------------------");
-        println!("{:<25} | {:<15} | {:<15} | {:<20}", "Member", "Server (Val)", "Client (Val)", "Status");
-        println!("{}", "-".repeat(80));
-    }
-
-    use std::collections::HashMap;
-    let client_members: HashMap<String, &venom_watch::EnumMember> = client.members.iter().map(|m| (m.name.clone(), m)).collect();
-
-    for s in &server.members {
-        let c = client_members.get(&s.name);
-        match c {
-            Some(c_member) => {
-                let matches = s.value == c_member.value;
-                if !matches {
-                    all_match = false;
-                    issues.push(format!("Enum member {} mismatch: Server={}, Client={}", s.name, s.value, c_member.value));
-                }
-                if !json_mode {
-                    let status = if matches { "✅ OK".green() } else { format!("❌ Mismatch (@L{})", c_member.line).red() };
-                    println!("{:<25} | {:<15} | {:<15} | {}", s.name, format!("{} (L{})", s.value, s.line), format!("{} (L{})", c_member.value, c_member.line), status);
-                }
-            }
-            None => {
-                all_match = false;
-                issues.push(format!("Enum member {} missing in client", s.name));
-                if !json_mode {
-                    println!("{:<25} | {:<15} | {:<15} | {}", s.name, s.value, "MISSING", "❌ Missing in Client".red());
-                }
-            }
+        // cpu_cores sized MAX_CORES - 1: everything after it moves
+        let mutated = analyze_file(&fixture("test_cpp_mutated.hpp"), "State").unwrap();
+        let result = layout_result(&compare_struct_layouts(&server, &mutated));
+        assert!(!result.success);
+        assert!(result.issues.contains(&"Field cpu_cores: Size Mismatch".to_string()), "{:?}", result.issues);
+        assert!(result.issues.contains(&"Field core_count: Offset Mismatch".to_string()), "{:?}", result.issues);
+    }
+
+    #[test]
+    fn test_rust_enums_against_c() {
+        let (server, client) = (fixture("test_enum_repr.h"), fixture("test_enum_repr.rs"));
+        let compare = |name: &str| compare_enum_layouts(&analyze_enum(&server, name).unwrap(), &analyze_enum(&client, name).unwrap());
+
+        // CMD_SET_INTERVAL is SetInterval in Rust
+        let cmd = compare("CmdType");
+        assert!(cmd.success(), "{:?}", cmd.issues());
+        assert_eq!(cmd.members[1].client.as_ref().map(|c| c.name.as_str()), Some("SetInterval"));
+
+        let level = compare("Level");
+        assert_eq!(level.issues(), ["Enum width mismatch: Server=1 bytes, Client=2 bytes"]);
+        let findings = sarif::enum_findings(&level, "test_enum_repr.h", "test_enum_repr.rs");
+        assert_eq!(findings.iter().map(|f| (f.rule, f.line)).collect::<Vec<_>>(), [("size-mismatch", Some(14))]);
+
+        // VIDEO is 18 in Rust, and INPUT follows it
+        let channel = compare("Channel");
+        assert_eq!(channel.issues(), ["Enum member CHANNEL_VIDEO mismatch: Server=17, Client=18", "Enum member CHANNEL_INPUT mismatch: Server=18, Client=19"]);
+        assert_eq!(enum_result(&channel).server_size, 4);
+    }
+
+    #[test]
+    fn test_targets() {
+        let header = fixture("test_target.h");
+        let le64 = analyze_file(&header, "Counters").unwrap();
+        let result = layout_result(&compare_struct_layouts(&le64, &analyze_file_for(&header, "Counters", Target::LE64).unwrap()));
+        assert!(result.success, "{:?}", result.issues);
+
+        let be32 = analyze_file_for(&header, "Counters", "be32".parse().unwrap()).unwrap();
+        let diff = compare_struct_layouts(&le64, &be32);
+        assert!(!diff.success());
+        let problems: Vec<String> = diff.issues().into_iter().filter(|i| !i.starts_with("Info:")).collect();
+        assert_eq!(problems, [
+            "Size mismatch: Server=40 bytes, Client=24 bytes",
+            "Target mismatch: Server is le64, Client is be32",
+            "Field id: Byte Order Mismatch",
+            "Field total: Offset Mismatch, Target Width Mismatch, Byte Order Mismatch",
+            "Field port: Offset Mismatch, Byte Order Mismatch",
+            "Field name: Offset Mismatch",
+            "Field flags: Offset Mismatch",
+            "Field label: Offset Mismatch, Target Width Mismatch, Byte Order Mismatch, 🚨 POINTER DANGER!",
+        ]);
+        let findings = sarif::layout_findings(&diff, "test_target.h", "test_target.h");
+        let total: Vec<_> = findings.iter().filter(|f| f.subject == "Counters.total" && f.rule != "padding").map(|f| (f.rule, f.line)).collect();
+        assert_eq!(total, [("offset-mismatch", Some(6)), ("width-mismatch", Some(6)), ("byte-order", Some(6))]);
+    }
+
+    // test_go_client.go and test_zig_client.zig are the venom.go and
+    // venom.zig `venom init --lang c,go,zig` generates with test_protocol.h
+    #[test]
+    fn test_generated_go_and_zig_clients_match_c_header() {
+        let server = analyze_file(&fixture("test_protocol.h"), "MonitorState").unwrap();
+
+        let go = analyze_file(&fixture("test_go_client.go"), "MonitorState").unwrap();
+        assert_eq!(go.wire.as_deref(), Some("StateFromBytes"));
+        let result = layout_result(&compare_struct_layouts(&server, &go));
+        assert!(result.success, "{:?}", result.issues);
+
+        let zig = analyze_file(&fixture("test_zig_client.zig"), "State").unwrap();
+        let result = layout_result(&compare_struct_layouts(&server, &zig));
+        assert!(result.success, "{:?}", result.issues);
+
+        // A decoder reading memory_used_mb one field late
+        let text = std::fs::read_to_string(fixture("test_go_client.go")).unwrap();
+        let shifted = std::env::temp_dir().join(format!("venom-watch-{}-shifted.go", std::process::id()));
+        std::fs::write(&shifted, text.replace("s.MemoryUsedMB = binary.LittleEndian.Uint32(data[80:])", "s.MemoryUsedMB = binary.LittleEndian.Uint32(data[84:])")).unwrap();
+        let go = analyze_file(&shifted, "MonitorState").unwrap();
+        std::fs::remove_file(&shifted).ok();
+        let result = layout_result(&compare_struct_layouts(&server, &go));
+        assert!(!result.success);
+        assert!(result.issues.iter().any(|i| i.starts_with("Field memory_used_mb: Offset Mismatch")), "{:?}", result.issues);
+    }
+
+    // test_dart_client.dart is the venom_binding.dart `venom init --lang
+    // c,flutter` generates with test_protocol.h; test_dart_shifted.dart reads
+    // memoryUsedMb one byte late
+    #[test]
+    fn test_dart_decoder_offsets() {
+        let server = analyze_file(&fixture("test_protocol.h"), "MonitorState").unwrap();
+
+        let decoder = analyze_dart_decoder(&fixture("test_dart_client.dart"), "MonitorState").unwrap();
+        assert_eq!(decoder.reads.len(), 10);
+        let cores = decoder.reads.iter().find(|r| r.field == "cpuCores").unwrap();
+        assert_eq!((cores.offset, cores.width, cores.count, cores.stride), (12, 4, 16, 4));
+        let result = layout_result(&compare_decoder_layout(&server, &decoder));
+        assert!(result.success, "{:?}", result.issues);
+        assert!(!result.issues.iter().any(|i| i.contains("Name Diff")), "{:?}", result.issues);
+
+        let decoder = analyze_dart_decoder(&fixture("test_dart_shifted.dart"), "MonitorState").unwrap();
+        let result = layout_result(&compare_decoder_layout(&server, &decoder));
+        assert!(!result.success);
+        let problems: Vec<&String> = result.issues.iter().filter(|i| !i.starts_with("Info:")).collect();
+        assert_eq!(problems, ["Field memory_used_mb: Offset Mismatch"]);
+
+        // Endianness left to Dart's big-endian default, and a read running
+        // past the struct
+        let text = std::fs::read_to_string(fixture("test_dart_client.dart")).unwrap()
+            .replace("data.getUint32(4, Endian.little)", "data.getUint32(4)")
+            .replace("data.getUint64(104, Endian.little)", "data.getUint64(108, Endian.little)");
+        let broken = std::env::temp_dir().join(format!("venom-watch-{}-broken.dart", std::process::id()));
+        std::fs::write(&broken, text).unwrap();
+        let decoder = analyze_dart_decoder(&broken, "MonitorState").unwrap();
+        std::fs::remove_file(&broken).ok();
+        let result = layout_result(&compare_decoder_layout(&server, &decoder));
+        assert!(!result.success);
+        assert!(result.issues.contains(&"Field version: Big-endian read at offset 4 (line 61)".to_string()), "{:?}", result.issues);
+        assert!(result.issues.iter().any(|i| i.starts_with("Field timestampNs: Reads bytes 108..116, past the end of the 112-byte struct")), "{:?}", result.issues);
+    }
+
+    // test_python_client.py is the venom_binding.py `venom init --lang
+    // c,python` generates with test_protocol.h
+    #[test]
+    fn test_python_struct_formats() {
+        let server = analyze_file(&fixture("test_protocol.h"), "MonitorState").unwrap();
+
+        let decoder = analyze_python_decoder(&fixture("test_python_client.py"), "MonitorState").unwrap();
+        assert_eq!(decoder.layout.wire.as_deref(), Some("_LAYOUT.unpack_from"));
+        let cores = decoder.reads.iter().find(|r| r.field == "cpu_cores").unwrap();
+        assert_eq!((cores.offset, cores.width, cores.count), (12, 4, 16));
+        let result = layout_result(&compare_decoder_layout(&server, &decoder));
+        assert!(result.success, "{:?}", result.issues);
+        assert!(!result.issues.iter().any(|i| i.contains("Name Diff")), "{:?}", result.issues);
+
+        let text = std::fs::read_to_string(fixture("test_python_client.py")).unwrap();
+        let corrupted = std::env::temp_dir().join(format!("venom-watch-{}-corrupted.py", std::process::id()));
+        let check = |from: &str, to: &str| {
+            std::fs::write(&corrupted, text.replace(from, to)).unwrap();
+            let decoder = analyze_python_decoder(&corrupted, "MonitorState").unwrap();
+            let result = layout_result(&compare_decoder_layout(&server, &decoder));
+            assert!(!result.success);
+            result.issues
+        };
+
+        // core_count unpacked as a u64: too wide, and everything after it late
+        let issues = check("'<IIf16fIIIQQQ'", "'<IIf16fQIIQQQ'");
+        assert!(issues.contains(&"Field core_count: Size Mismatch".to_string()), "{:?}", issues);
+        assert!(issues.contains(&"Field memory_used_mb: Offset Mismatch".to_string()), "{:?}", issues);
+
+        // The whole struct unpacked from the wrong offset
+        let issues = check("_LAYOUT.unpack_from(data, 0)", "_LAYOUT.unpack_from(data, 2)");
+        assert!(issues.contains(&"Field magic: Offset Mismatch".to_string()), "{:?}", issues);
+
+        // timestamp_ns left out of the format
+        let issues = check("'<IIf16fIIIQQQ'", "'<IIf16fIIIQQ'");
+        assert!(issues.contains(&"Field timestamp_ns missing in client".to_string()), "{:?}", issues);
+        assert!(issues.contains(&"Size mismatch: Server=112 bytes, Client=104 bytes".to_string()), "{:?}", issues);
+
+        let issues = check("'<IIf16fIIIQQQ'", "'>IIf16fIIIQQQ'");
+        assert!(issues.contains(&"Field magic: Big-endian read at offset 0 (line 68)".to_string()), "{:?}", issues);
+        std::fs::remove_file(&corrupted).ok();
+    }
+
+    #[test]
+    fn test_all_types() {
+        let (report, _) = check_all(&Source::plain(&fixture("test_all_server.h")), &Source::plain(&fixture("test_all_client.h")), true, false).unwrap();
+        assert!(!report.success);
+        let summary: Vec<(&str, &str, bool)> = report.types.iter().map(|t| (t.name.as_str(), t.kind.as_str(), t.success)).collect();
+        assert_eq!(summary, [("Header", "struct", true), ("Sample", "struct", false), ("Mode", "enum", true), ("Status", "enum", false)]);
+
+        let sample = report.types[1].result.as_ref().unwrap();
+        assert!(sample.issues.contains(&"Field value: Size Mismatch".to_string()), "{:?}", sample.issues);
+        let status = &report.types[3];
+        assert!(status.result.is_none());
+        assert_eq!(status.error.as_deref(), Some(format!("Enum 'Status' not found in {}", fixture("test_all_client.h").display()).as_str()));
+        assert_eq!(report.notes, ["Struct ClientCache is only in the client"]);
+
+        let (report, _) = check_all(&Source::plain(&fixture("test_all_server.h")), &Source::plain(&fixture("test_all_server.h")), true, false).unwrap();
+        assert!(report.success);
+        assert!(report.notes.is_empty());
+    }
+
+    // sarif-schema-2.1.0.json is the OASIS SARIF 2.1.0 JSON schema, with
+    // the run.language pattern as corrected in errata 01 (the original is
+    // not a valid regex)
+    #[test]
+    fn test_sarif_output() {
+        let server_path = fixture("test_all_server.h");
+        let client_path = fixture("test_all_client.h");
+        let (_, mut findings) = check_all(&Source::plain(&server_path), &Source::plain(&client_path), true, false).unwrap();
+        let leaks = run_safety_analysis(&fixture("safety_test.c"), &[]).unwrap();
+        let overflows = run_safety_analysis(&fixture("overflow_test.c"), &[]).unwrap();
+        findings.extend(sarif::leak_findings(&leaks));
+        findings.extend(sarif::leak_findings(&overflows));
+        // Accepted in a baseline: a suppressed note
+        findings[0].baselined = true;
+
+        let log = sarif::document(&findings);
+        let schema: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(fixture("sarif-schema-2.1.0.json")).unwrap()).unwrap();
+        let validator = jsonschema::validator_for(&schema).unwrap();
+        let errors: Vec<String> = validator.iter_errors(&log).map(|e| e.to_string()).collect();
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        let results = log["runs"][0]["results"].as_array().unwrap();
+        let find = |rule: &str| results.iter().find(|r| r["ruleId"] == rule).unwrap_or_else(|| panic!("no {} result", rule));
+        // Sample.value is widened on line 20 of the client
+        let size = results.iter().find(|r| r["message"]["text"] == "Sample: Field value: Size Mismatch").unwrap();
+        assert_eq!(size["ruleId"], "size-mismatch");
+        assert_eq!(size["locations"][0]["physicalLocation"]["artifactLocation"]["uri"], display(&client_path));
+        assert_eq!(size["locations"][0]["physicalLocation"]["region"]["startLine"], 20);
+        assert_eq!(find("offset-mismatch")["level"], "error");
+        assert_eq!((results[0]["level"].as_str(), results[0]["suppressions"][0]["kind"].as_str()), (Some("note"), Some("external")));
+        assert_eq!(find("missing-type")["message"]["text"], format!("enum Status: Enum 'Status' not found in {}", display(&client_path)));
+        for rule in ["use-after-free", "double-free", "buffer-overflow"] {
+            assert!(find(rule)["locations"][0]["physicalLocation"]["region"]["startLine"].as_u64().unwrap() > 0);
         }
     }
 
-    if json_mode {
-        let result = ValidationResult {
-            success: all_match,
-            server_size: 0,
-            client_size: 0,
-            issues,
+    // A stale client and a file with use-after-frees fail until their
+    // findings are in the baseline; a finding that isn't still fails
+    #[test]
+    fn test_baseline() {
+        let dir = std::env::temp_dir().join(format!("venom-watch-{}-baseline", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (client, leaks, baseline) = (dir.join("client.h"), dir.join("leaks.c"), dir.join("baseline.json"));
+        std::fs::copy(fixture("test_all_client.h"), &client).unwrap();
+        std::fs::copy(fixture("safety_test.c"), &leaks).unwrap();
+        let server = display(&fixture("test_all_server.h"));
+        let run = |extra: &[&str]| {
+            let mut argv = vec!["venom-watch", "-s", &server, "-c", client.to_str().unwrap(), "--all", "--check-leaks", leaks.to_str().unwrap(), "--json"];
+            argv.extend(extra);
+            run_checks(&Cli::parse_from(argv).resolve_format())
         };
-        println!("{}", serde_json::to_string_pretty(&result).unwrap());
-    } else {
-        if all_match { println!("\n{}", "✅ Enums are fully consistent!".green().bold()); }
-        else { println!("\n{}", "⚠️  ENUM INCONSISTENCY DETECTED!".red().bold()); }
+        let with_baseline = ["--baseline", baseline.to_str().unwrap()];
+
+        assert!(!run(&[]));
+        assert!(!run(&with_baseline), "a baseline that can't be read fails the run");
+        assert!(run(&[&with_baseline[..], &["--update-baseline"]].concat()));
+        let saved: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&baseline).unwrap()).unwrap();
+        assert_eq!(saved["version"], 1);
+        assert!(run(&with_baseline));
+
+        // Lines moved a little, the struct findings anywhere in the file
+        let text = std::fs::read_to_string(&leaks).unwrap();
+        std::fs::write(&leaks, format!("\n\n\n{}", text)).unwrap();
+        let header = std::fs::read_to_string(&client).unwrap();
+        std::fs::write(&client, format!("\n\n\n\n\n\n\n\n{}", header)).unwrap();
+        assert!(run(&with_baseline));
+
+        // Moved too far to be the same use-after-free
+        std::fs::write(&leaks, format!("\n\n\n\n\n\n{}", text)).unwrap();
+        assert!(!run(&with_baseline));
+        std::fs::write(&leaks, &text).unwrap();
+        assert!(run(&with_baseline));
+
+        // Header.flags widened: a new mismatch beside the accepted ones
+        std::fs::write(&client, header.replace("uint16_t flags;", "uint32_t flags;")).unwrap();
+        assert!(!run(&with_baseline));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    // Each file's worst finding against each threshold: a run fails once
+    // its worst finding reaches `--fail-on`
+    #[test]
+    fn test_fail_on() {
+        let padding = display(&fixture("test_padding.h"));
+        let checks: [(&str, Vec<String>, Option<Severity>); 4] = [
+            ("clean", vec!["--check-leaks".into(), display(&fixture("goto_cleanup_test.c"))], None),
+            ("padding", vec!["-s".into(), padding.clone(), "-c".into(), padding, "-n".into(), "PaddingStruct".into()], Some(Severity::Info)),
+            ("leaks", vec!["--check-leaks".into(), display(&fixture("leak_test.c"))], Some(Severity::Warning)),
+            ("use-after-free", vec!["--check-leaks".into(), display(&fixture("safety_test.c"))], Some(Severity::Critical)),
+        ];
+        for (name, argv, worst) in &checks {
+            for fail_on in [Severity::Info, Severity::Warning, Severity::Critical] {
+                let mut args = vec!["venom-watch".to_string(), "--json".into(), "--fail-on".into(), fail_on.to_string()];
+                args.extend(argv.iter().cloned());
+                let passed = run_checks(&Cli::parse_from(args).resolve_format());
+                assert_eq!(passed, worst.is_none_or(|worst| worst < fail_on), "{} with --fail-on {}", name, fail_on);
+            }
+        }
+        // The default is warning
+        let leaks = Cli::parse_from(["venom-watch", "--check-leaks", "leak_test.c"]);
+        assert_eq!(leaks.fail_on, Severity::Warning);
+        assert!(Cli::try_parse_from(["venom-watch", "--fail-on", "fatal"]).is_err());
+
+        let report = run_safety_analysis(&fixture("safety_test.c"), &[]).unwrap();
+        assert_eq!(report.severities.len(), report.findings.len());
+        assert!(!report.passes(Severity::Critical));
+        let leaks = run_safety_analysis(&fixture("leak_test.c"), &[]).unwrap();
+        assert!(leaks.passes(Severity::Critical) && !leaks.passes(Severity::Warning));
+    }
+
+    // `--format json` prints the same ValidationResult as `--json` always has
+    #[test]
+    fn test_json_output_shape() {
+        let server = analyze_file(&fixture("test_all_server.h"), "Sample").unwrap();
+        let client = analyze_file(&fixture("test_all_client.h"), "Sample").unwrap();
+        let json = serde_json::to_value(layout_result(&compare_struct_layouts(&server, &client))).unwrap();
+        let keys: Vec<&String> = json.as_object().unwrap().keys().collect();
+        assert_eq!(keys, ["client_fields", "client_size", "issues", "server_fields", "server_size", "success"]);
+        assert_eq!((json["server_size"].as_u64(), json["client_size"].as_u64()), (Some(16), Some(24)));
     }
-    all_match
 }
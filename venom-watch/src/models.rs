@@ -6,10 +6,29 @@ pub struct Field {
     pub type_name: String,
     pub size: usize,
     pub offset: usize,
+    /// Alignment the field is placed at, after any packing; 1 in a wire
+    /// layout
+    #[serde(default)]
+    pub align: usize,
     pub is_array: bool,
     pub array_len: usize,
+    /// A flexible array member (`payload[]`, GNU's `payload[0]`, Rust's
+    /// `[T; 0]`): no bytes of its own, only its alignment
+    #[serde(default)]
+    pub is_flexible: bool,
     pub line: usize,
     pub is_pointer: bool,
+    /// 0 for the struct's own fields; members of a nested struct/union follow
+    /// their parent one level deeper, named `parent.member`
+    pub depth: usize,
+    /// Dotted name of the struct/union field this one is a member of
+    /// (`hdr` for `hdr.flags`); `None` at depth 0
+    #[serde(default)]
+    pub parent: Option<String>,
+    /// For a bitfield: first bit, counted from `offset`, whose `size` bytes
+    /// are the storage unit of its declared type
+    pub bit_offset: Option<u32>,
+    pub bit_width: Option<u32>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -18,6 +37,131 @@ pub struct StructLayout {
     pub fields: Vec<Field>,
     pub total_size: usize,
     pub file_path: String,
+    /// Max alignment the fields are laid out with: `Some(1)` when packed,
+    /// `Some(N)` under `#pragma pack(N)`/`repr(packed(N))`, `None` for natural
+    pub packing: Option<usize>,
+    /// What the analysis had to guess at, such as array sizes it couldn't resolve
+    pub warnings: Vec<String>,
+    /// Set when the offsets are the ones a serializer reads and writes (Go's
+    /// `StateFromBytes`), named here, rather than the in-memory layout
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wire: Option<String>,
+    /// The machine the layout is for
+    #[serde(default)]
+    pub target: Target,
+}
+
+/// The machine a layout is for: its byte order, and how wide pointers and
+/// the types that follow them are (`long`, `size_t`, `usize`, Go's `int`).
+/// Written `le64`, `be32`: little-endian 64-bit (x86_64, aarch64) unless
+/// given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct Target {
+    pub big_endian: bool,
+    /// Bytes of a pointer
+    pub pointer_size: usize,
+}
+
+/// Types as wide as a pointer, in each language the layouts are read from
+const POINTER_SIZED: &[&str] = &[
+    "long", "unsigned long", "long int", "size_t", "ssize_t", "intptr_t", "uintptr_t", "ptrdiff_t",
+    "usize", "isize", "int", "uint", "uintptr", "c_long", "c_ulong",
+];
+
+impl Target {
+    pub const LE64: Target = Target { big_endian: false, pointer_size: 8 };
+
+    /// Whether a scalar of this type is as wide as a pointer (Go's `int`
+    /// included; a C `int` never differs in size between these targets)
+    pub fn pointer_sized(type_name: &str) -> bool {
+        POINTER_SIZED.contains(&type_name.trim_start_matches("std::"))
+    }
+}
+
+impl Default for Target {
+    fn default() -> Target {
+        Target::LE64
+    }
+}
+
+impl std::fmt::Display for Target {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}{}", if self.big_endian { "be" } else { "le" }, self.pointer_size * 8)
+    }
+}
+
+impl std::str::FromStr for Target {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Target, String> {
+        let invalid = || format!("'{}' is not a target: use le64, le32, be64 or be32", s);
+        let lower = s.to_lowercase();
+        let (big_endian, bits) = match lower.split_at_checked(2).ok_or_else(invalid)? {
+            ("le", bits) => (false, bits),
+            ("be", bits) => (true, bits),
+            _ => return Err(invalid()),
+        };
+        match bits {
+            "32" => Ok(Target { big_endian, pointer_size: 4 }),
+            "64" => Ok(Target { big_endian, pointer_size: 8 }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl From<Target> for String {
+    fn from(target: Target) -> String {
+        target.to_string()
+    }
+}
+
+impl TryFrom<String> for Target {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Target, String> {
+        s.parse()
+    }
+}
+
+/// One read of a hand-written decoder: a Dart `data.getUint32(76,
+/// Endian.little)` or the reads of a `List.generate(count, (i) => ...)`,
+/// a Python `struct` format item (`16f` at offset 12)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WireRead {
+    pub field: String,
+    pub offset: usize,
+    /// Bytes per read (4 for `getFloat32` or `f`)
+    pub width: usize,
+    /// Reads in a `List.generate` or repeated format item, `stride` bytes
+    /// apart; 1 otherwise
+    pub count: usize,
+    pub stride: usize,
+    /// `Endian.big`, or no endianness for a multi-byte read (Dart's
+    /// default); a `>` or `!` format
+    pub big_endian: bool,
+    pub line: usize,
+}
+
+impl WireRead {
+    /// One past the last byte read
+    pub fn end(&self) -> usize {
+        self.offset + self.stride * (self.count - 1) + self.width
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Decoder {
+    pub reads: Vec<WireRead>,
+    /// The reads as a packed wire layout, one field per constructor argument
+    pub layout: StructLayout,
+}
+
+/// The structs and enums a file defines, in file order
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DeclaredTypes {
+    pub structs: Vec<String>,
+    pub enums: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,6 +176,24 @@ pub struct EnumLayout {
     pub name: String,
     pub members: Vec<EnumMember>,
     pub file_path: String,
+    /// Bytes each value takes: a C enum's `int` (or what it's packed to), a
+    /// Rust enum's `#[repr(u8)]`; `None` for a Rust enum without a `repr`
+    #[serde(default)]
+    pub repr: Option<usize>,
+}
+
+/// `--optimize`: a struct's top-level fields in the order that wastes the
+/// least padding
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Reordering {
+    pub name: String,
+    pub current_size: usize,
+    pub suggested_size: usize,
+    pub saved_bytes: usize,
+    pub suggested_order: Vec<String>,
+    /// The struct declared in that order, for C, C++ and Rust
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub definition: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -40,6 +202,71 @@ pub struct ValidationResult {
     pub server_size: usize,
     pub client_size: usize,
     pub issues: Vec<String>,
+    /// Both layouts field by field (with bit positions of bitfields); empty
+    /// for enum checks
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub server_fields: Vec<Field>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub client_fields: Vec<Field>,
+}
+
+/// `--all`: every struct and enum of the server file against the client
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HeaderReport {
+    pub success: bool,
+    pub types: Vec<TypeReport>,
+    /// Informational: types only the client defines, enums left unchecked
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TypeReport {
+    pub name: String,
+    /// `struct` or `enum`
+    pub kind: String,
+    pub success: bool,
+    /// Why the type couldn't be compared: missing from the client, or either
+    /// side failed to parse
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<ValidationResult>,
+}
+
+/// How much a finding matters; `--fail-on` is the least that fails a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Worth knowing, not wrong: padding, what the analysis had to guess at
+    Info,
+    /// May be wrong: leaks, frees in only some branches, ownership the
+    /// analysis guessed from a name, packing and pointer fields
+    Warning,
+    /// Wrong: use-after-free, double free, overflows, layouts that disagree
+    Critical,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Critical => "critical",
+        })
+    }
+}
+
+impl std::str::FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Severity, String> {
+        match s.to_lowercase().as_str() {
+            "info" => Ok(Severity::Info),
+            "warning" => Ok(Severity::Warning),
+            "critical" => Ok(Severity::Critical),
+            _ => Err(format!("'{}' is not a severity: use info, warning or critical", s)),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -47,11 +274,24 @@ pub enum MemoryEventKind {
     Allocation,
     Free,
     PotentialMove, // Heuristic
-    ExplicitMove,  // Annotation
+    ExplicitMove,  // Annotation, or a helper of the file that keeps it
+    Escape,        // Returned, stored where the caller can reach it, or its address handed out
     ConditionalFree,
     UseAfterFree,
     DoubleFree,
-    BufferOverflow, // Reserved for future use
+    BufferOverflow,
+}
+
+impl MemoryEventKind {
+    /// Severity of the finding an event of this kind is; `None` for the
+    /// ones that only trace the lifecycle
+    pub fn severity(&self) -> Option<Severity> {
+        match self {
+            MemoryEventKind::UseAfterFree | MemoryEventKind::DoubleFree | MemoryEventKind::BufferOverflow => Some(Severity::Critical),
+            MemoryEventKind::ConditionalFree | MemoryEventKind::PotentialMove => Some(Severity::Warning),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -66,6 +306,83 @@ pub struct MemoryEvent {
 pub struct LeakReport {
     pub success: bool,
     pub findings: Vec<String>,
+    /// Severity of each of `findings`, in the same order
+    #[serde(default)]
+    pub severities: Vec<Severity>,
     pub events: Vec<MemoryEvent>,
     pub file_path: String,
 }
+
+impl LeakReport {
+    /// No finding at `fail_on` or above
+    pub fn passes(&self, fail_on: Severity) -> bool {
+        self.severities.iter().all(|&severity| severity < fail_on)
+    }
+}
+
+/// `--check-leaks` over a directory: the report of each C file under it,
+/// in path order
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectReport {
+    pub success: bool,
+    pub reports: Vec<LeakReport>,
+    /// Files that couldn't be analyzed, and why
+    pub errors: Vec<AnalysisError>,
+    pub totals: Totals,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnalysisError {
+    pub file_path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Totals {
+    /// Files analyzed, the ones that failed included
+    pub files: usize,
+    pub findings: usize,
+    pub critical: usize,
+    pub warning: usize,
+    pub info: usize,
+    pub errors: usize,
+}
+
+impl ProjectReport {
+    /// Every file analyzed, none with a finding at `fail_on` or above
+    pub fn passes(&self, fail_on: Severity) -> bool {
+        self.errors.is_empty() && self.reports.iter().all(|r| r.passes(fail_on))
+    }
+}
+
+/// `--check-constants`: the constants each side of a channel defines for
+/// itself, compared across files
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConstantsReport {
+    pub success: bool,
+    /// The files read, in the order given
+    pub files: Vec<String>,
+    pub constants: Vec<SharedConstant>,
+}
+
+/// The definitions of one constant name, by every file that has one
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SharedConstant {
+    /// How the names end: `magic`, `channel_name`
+    pub name: String,
+    /// The value most files give it; `None` when no file defines it
+    pub value: Option<String>,
+    pub definitions: Vec<ConstantDefinition>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConstantDefinition {
+    pub file: String,
+    pub line: usize,
+    /// The name as written: `MONITOR_MAGIC`, `channelName`
+    pub name: String,
+    /// `0x763E0219` for an integer, `"monitor"` for a string
+    pub value: String,
+    /// Whether it's `value` of its `SharedConstant`
+    pub agrees: bool,
+}
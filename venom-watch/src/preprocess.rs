@@ -0,0 +1,271 @@
+//! `--preprocess`: C and C++ sources read the way the compiler reads them,
+//! with `#if`s decided, macros expanded and includes pulled in, for layouts
+//! that depend on `-D` flags or types from other headers
+//!
+//! The source goes through `$CC -E` (`cc` by default) into a temporary file
+//! that the analyses read instead. The `# 12 "file.h"` line markers are
+//! kept out of it (blank lines in their place) and map each of its lines
+//! back: to the same line of the original file, or, for what an included
+//! file brought in, to the `#include` line. Everything a `Source` reports
+//! is in the original file's lines.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use crate::models::{DeclaredTypes, EnumLayout, LeakReport, StructLayout, Target};
+use crate::analysis::layout;
+
+/// Extensions of the sources the preprocessor is run on; the rest are read as written
+const C_FAMILY: &[&str] = &["c", "h", "cc", "cpp", "cxx", "hpp", "hh", "hxx"];
+
+/// Temporary files made so far by this process, to name the next one
+static TEMPORARIES: AtomicUsize = AtomicUsize::new(0);
+
+/// `-I` and `-D` flags for the preprocessor
+#[derive(Debug, Default, Clone)]
+pub struct Preprocessor {
+    pub include_dirs: Vec<PathBuf>,
+    /// `NAME` or `NAME=VALUE`
+    pub defines: Vec<String>,
+}
+
+impl Preprocessor {
+    /// `path` as the preprocessor leaves it, or as it is for a language
+    /// other than C and C++. An error if the compiler can't be run or
+    /// rejects the file.
+    pub fn source(&self, path: &Path) -> Result<Source, String> {
+        let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("");
+        if !C_FAMILY.contains(&ext) {
+            return Ok(Source::plain(path));
+        }
+        let compiler = std::env::var("CC").ok().filter(|cc| !cc.trim().is_empty()).unwrap_or_else(|| "cc".to_string());
+        // `$CC` may carry flags of its own: `ccache gcc`, `clang --target=...`
+        let mut words = compiler.split_whitespace();
+        let mut command = Command::new(words.next().unwrap());
+        // `-C` keeps the comments, where the `@venom` annotations are
+        command.args(words).args(["-E", "-C"]);
+        for dir in &self.include_dirs {
+            command.arg("-I").arg(dir);
+        }
+        for define in &self.defines {
+            command.arg(format!("-D{}", define));
+        }
+        let output = command.arg(path).output().map_err(|e| format!("Could not run {}: {}", compiler, e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("{} -E failed on {}: {}", compiler, path.display(), stderr.trim()));
+        }
+        let (code, lines) = unmark(&String::from_utf8_lossy(&output.stdout), &path.to_string_lossy());
+
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let count = TEMPORARIES.fetch_add(1, Ordering::Relaxed);
+        let parsed = std::env::temp_dir().join(format!("venom-watch-{}-{}-{}", std::process::id(), count, name));
+        fs::write(&parsed, code).map_err(|e| format!("Could not write {}: {}", parsed.display(), e))?;
+        Ok(Source { path: path.to_path_buf(), parsed, lines, target: Target::default() })
+    }
+}
+
+/// The preprocessor's output without its line markers, and the original
+/// line of each of its lines, from 1 (0 for lines before the file starts),
+/// with whether it's the file's own rather than an include's
+fn unmark(output: &str, file: &str) -> (String, Vec<(usize, bool)>) {
+    let mut code = String::with_capacity(output.len());
+    let mut lines = Vec::new();
+    // Where the original file is at: the line of its `#include` while an
+    // included file's lines come through
+    let mut line = 0;
+    let mut in_file = false;
+    for text in output.lines() {
+        if let Some((marked, marked_file)) = marker(text) {
+            in_file = marked_file == file;
+            if in_file {
+                line = marked;
+            }
+            lines.push((line, false));
+            code.push('\n');
+            continue;
+        }
+        lines.push((line, in_file));
+        if in_file {
+            line += 1;
+        }
+        code.push_str(text);
+        code.push('\n');
+    }
+    (code, lines)
+}
+
+/// `# 12 "file.h" 1 3`: the line the next one is, and of which file
+fn marker(text: &str) -> Option<(usize, &str)> {
+    let rest = text.strip_prefix('#')?.trim_start();
+    let rest = rest.strip_prefix("line").unwrap_or(rest).trim_start();
+    let (number, rest) = rest.split_once(' ')?;
+    let line = number.parse().ok()?;
+    let file = rest.trim_start().strip_prefix('"')?;
+    Some((line, &file[..file.rfind('"')?]))
+}
+
+/// A file for the analyses to read: as it is, or the preprocessor's output
+/// with what they report mapped back to it
+#[derive(Debug)]
+pub struct Source {
+    /// The file as given
+    pub path: PathBuf,
+    /// What the analyses read: `path`, or the preprocessed copy
+    parsed: PathBuf,
+    /// Original line of each line of `parsed`, from 1, and whether it's
+    /// the file's own; empty when `parsed` is `path`
+    lines: Vec<(usize, bool)>,
+    /// The machine its structs are laid out for
+    target: Target,
+}
+
+impl Source {
+    pub fn plain(path: &Path) -> Source {
+        Source { path: path.to_path_buf(), parsed: path.to_path_buf(), lines: Vec::new(), target: Target::default() }
+    }
+
+    /// The same source, its structs laid out for `target`
+    pub fn for_target(mut self, target: Target) -> Source {
+        self.target = target;
+        self
+    }
+
+    pub fn is_preprocessed(&self) -> bool {
+        self.parsed != self.path
+    }
+
+    /// Line `line` of what the analyses read, in the original file
+    pub fn line(&self, line: usize) -> usize {
+        match line.checked_sub(1).and_then(|i| self.lines.get(i)) {
+            Some(&(original, _)) => original,
+            None => line,
+        }
+    }
+
+    pub fn analyze_file(&self, struct_name: &str) -> Result<StructLayout, String> {
+        let mut layout = layout::analyze_file_for(&self.parsed, struct_name, self.target).map_err(|e| self.unmap(&e))?;
+        for field in &mut layout.fields {
+            field.line = self.line(field.line);
+        }
+        layout.warnings = layout.warnings.iter().map(|w| self.unmap(w)).collect();
+        layout.file_path = self.path.to_string_lossy().to_string();
+        Ok(layout)
+    }
+
+    pub fn analyze_enum(&self, enum_name: &str) -> Result<EnumLayout, String> {
+        let mut layout = layout::analyze_enum(&self.parsed, enum_name).map_err(|e| self.unmap(&e))?;
+        for member in &mut layout.members {
+            member.line = self.line(member.line);
+        }
+        layout.file_path = self.path.to_string_lossy().to_string();
+        Ok(layout)
+    }
+
+    /// The types the file itself defines, not the ones its includes bring in
+    pub fn declared_types(&self) -> Result<DeclaredTypes, String> {
+        if !self.is_preprocessed() {
+            return layout::declared_types(&self.parsed);
+        }
+        let code = fs::read_to_string(&self.parsed).map_err(|e| format!("Could not read file {}: {}", self.parsed.display(), e))?;
+        // Only its own lines, the others left blank
+        let own: String = code
+            .lines()
+            .zip(&self.lines)
+            .map(|(text, &(_, own))| if own { format!("{}\n", text) } else { "\n".to_string() })
+            .collect();
+        let own_path = self.parsed.with_file_name(format!("own-{}", self.parsed.file_name().unwrap().to_string_lossy()));
+        fs::write(&own_path, own).map_err(|e| format!("Could not write {}: {}", own_path.display(), e))?;
+        let types = layout::declared_types(&own_path).map_err(|e| self.unmap(&e));
+        fs::remove_file(&own_path).ok();
+        types
+    }
+
+    /// Leaks and overflows, as `run_safety_analysis`
+    pub fn safety_analysis(&self, allocators: &[String]) -> Result<LeakReport, String> {
+        let mut report = crate::run_safety_analysis(&self.parsed, allocators).map_err(|e| self.unmap(&e))?;
+        for event in &mut report.events {
+            event.line = self.line(event.line);
+            event.context = self.unmap(&event.context);
+        }
+        report.findings = report.findings.iter().map(|f| self.unmap(f)).collect();
+        report.file_path = self.path.to_string_lossy().to_string();
+        Ok(report)
+    }
+
+    /// A message about what the analyses read, about the original file:
+    /// the temporary file's name replaced, and every `line N` mapped
+    fn unmap(&self, message: &str) -> String {
+        if !self.is_preprocessed() {
+            return message.to_string();
+        }
+        let message = message.replace(&*self.parsed.to_string_lossy(), &self.path.to_string_lossy());
+        let mut out = String::with_capacity(message.len());
+        let mut rest = message.as_str();
+        while let Some(at) = rest.find("line ") {
+            let (before, after) = rest.split_at(at + "line ".len());
+            out.push_str(before);
+            let digits = after.len() - after.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            if let Ok(line) = after[..digits].parse::<usize>() {
+                out.push_str(&self.line(line).to_string());
+            }
+            rest = &after[digits..];
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+impl Drop for Source {
+    fn drop(&mut self) {
+        if self.is_preprocessed() {
+            fs::remove_file(&self.parsed).ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::fixture;
+
+    #[test]
+    fn test_defines_change_layout() {
+        let path = fixture("preprocess_test.h");
+        let plain = Preprocessor::default().source(&path).unwrap();
+        let variant = Preprocessor { include_dirs: vec![fixture("")], defines: vec!["VARIANT_A".to_string()] }.source(&path).unwrap();
+        assert!(plain.is_preprocessed() && variant.is_preprocessed());
+
+        let (plain, variant) = (plain.analyze_file("Packet").unwrap(), variant.analyze_file("Packet").unwrap());
+        assert_eq!((plain.total_size, variant.total_size), (20, 32));
+        let offsets = |layout: &StructLayout| layout.fields.iter().filter(|f| f.depth == 0).map(|f| (f.name.clone(), f.offset, f.line)).collect::<Vec<_>>();
+        assert_eq!(offsets(&plain), [("header".to_string(), 0, 9), ("packet_count".to_string(), 4, 13), ("name".to_string(), 8, 14)]);
+        assert_eq!(offsets(&variant), [("header".to_string(), 0, 9), ("extended".to_string(), 8, 11), ("packet_count".to_string(), 16, 13), ("name".to_string(), 20, 14)]);
+        assert_eq!(plain.file_path, path.to_string_lossy());
+        // Header's members are declared in the included file: at the `#include`
+        assert!(plain.fields.iter().filter(|f| f.depth == 1).all(|f| f.line == 4));
+    }
+
+    #[test]
+    fn test_own_types() {
+        let source = Preprocessor::default().source(&fixture("preprocess_test.h")).unwrap();
+        let types = source.declared_types().unwrap();
+        assert_eq!(types.structs, ["Packet"]);
+        let parsed = source.parsed.clone();
+        assert!(parsed.exists());
+        drop(source);
+        assert!(!parsed.exists(), "the preprocessed copy is removed");
+    }
+
+    #[test]
+    fn test_line_markers() {
+        let output = "# 1 \"main.c\"\n# 1 \"<built-in>\"\n# 1 \"main.c\"\n# 1 \"a.h\" 1\nint a;\nint b;\n# 3 \"main.c\" 2\nint c;\n\nint d;\n";
+        let (code, lines) = unmark(output, "main.c");
+        assert_eq!(code, "\n\n\n\nint a;\nint b;\n\nint c;\n\nint d;\n");
+        assert_eq!(lines.iter().map(|&(line, _)| line).collect::<Vec<_>>(), [1, 1, 1, 1, 1, 1, 3, 3, 4, 5]);
+        assert_eq!(lines.iter().map(|&(_, own)| own).collect::<Vec<_>>(), [false, false, false, false, false, false, false, true, true, true]);
+        assert_eq!(marker("#line 7 \"x y.h\" 1 3"), Some((7, "x y.h")));
+        assert_eq!(marker("#pragma pack(1)"), None);
+    }
+}
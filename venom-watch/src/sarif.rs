@@ -0,0 +1,301 @@
+//! SARIF 2.1.0 output (`--format sarif`), for CI code scanning
+//!
+//! Each finding becomes a result under one rule per category. Layout
+//! findings point at the client's field when it has one by that name, and
+//! at the server's otherwise; leak findings at the line the analysis names.
+//! Findings the `--baseline` accepts stay in the log as suppressed notes.
+
+use serde_json::{json, Value};
+use crate::analysis::compare::{field_issue_text, packing_name, EnumDiff, FieldIssue, LayoutDiff, ReadIssue, Row, Side};
+use crate::models::{ConstantsReport, Field, LeakReport, MemoryEventKind, Severity};
+
+/// Rule id, description and severity of each finding category
+const RULES: &[(&str, &str, Severity)] = &[
+    ("offset-mismatch", "Field starts at a different offset in the client", Severity::Critical),
+    ("size-mismatch", "Field, struct or enum has a different size in the client", Severity::Critical),
+    ("bit-mismatch", "Bitfield sits at different bits in the client", Severity::Critical),
+    ("field-order", "Same fields in another order in the client", Severity::Critical),
+    ("flexible-array", "Flexible array member on one side, a fixed-size field on the other", Severity::Critical),
+    ("missing-field", "Field only one side declares", Severity::Critical),
+    ("missing-type", "Struct or enum the client doesn't declare", Severity::Critical),
+    ("byte-order", "Multi-byte value read in the other byte order", Severity::Critical),
+    ("width-mismatch", "Pointer-wide field the client's target makes a different width", Severity::Critical),
+    ("enum-mismatch", "Enum member with a different value, or missing, in the client", Severity::Critical),
+    ("packing-mismatch", "One side is packed and the other isn't", Severity::Warning),
+    ("pointer-field", "Pointer in shared memory; meaningless in another process", Severity::Warning),
+    ("analysis-warning", "Something the analysis had to guess at", Severity::Info),
+    ("padding", "Bytes the compiler inserts between or after fields", Severity::Info),
+    ("leak", "Allocation that may never be freed", Severity::Warning),
+    ("use-after-free", "Use of memory after it was freed", Severity::Critical),
+    ("double-free", "Memory freed twice", Severity::Critical),
+    ("buffer-overflow", "Write past the end of a buffer", Severity::Critical),
+    ("constant-mismatch", "Channel name or magic number the other files define differently", Severity::Critical),
+];
+
+/// One result of a SARIF run
+pub struct Finding {
+    pub rule: &'static str,
+    /// What it is about: `Struct.field`, `Struct`, `Enum.MEMBER`, or the
+    /// variable of a leak or overflow
+    pub subject: String,
+    pub message: String,
+    pub file: String,
+    pub line: Option<usize>,
+    /// Accepted in the `--baseline` file: a suppressed note
+    pub baselined: bool,
+}
+
+impl Finding {
+    pub fn severity(&self) -> Severity {
+        RULES.iter().find(|(id, _, _)| *id == self.rule).map_or(Severity::Critical, |rule| rule.2)
+    }
+}
+
+/// SARIF level of a severity
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "error",
+        Severity::Warning => "warning",
+        Severity::Info => "note",
+    }
+}
+
+/// Findings of a struct comparison: each at the client's field when it has
+/// one, else at the server's; size and packing at the client's first field
+pub fn layout_findings(diff: &LayoutDiff, server_path: &str, client_path: &str) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let first_line = |fields: &[Field]| fields.first().map(|f| f.line);
+    let client_struct = (client_path, first_line(&diff.client_fields));
+    let field = |name: &str| format!("{}.{}", diff.name, name);
+    let mut push = |rule, subject: String, message: String, (file, line): (&str, Option<usize>)| {
+        findings.push(Finding { rule, subject, message: format!("{}: {}", diff.name, message), file: file.to_string(), line, baselined: false });
+    };
+
+    if diff.server_size != diff.client_size {
+        push("size-mismatch", diff.name.clone(), format!("Size mismatch: Server={} bytes, Client={} bytes", diff.server_size, diff.client_size), client_struct);
+    }
+    if let Some(order) = diff.order_issue() {
+        push("field-order", diff.name.clone(), order, client_struct);
+    }
+    if diff.server_packing != diff.client_packing {
+        let message = format!("Packing mismatch: Server is {}, Client is {}", packing_name(diff.server_packing), packing_name(diff.client_packing));
+        push("packing-mismatch", diff.name.clone(), message, client_struct);
+    }
+    for (side, warning) in &diff.warnings {
+        let location = match side {
+            Side::Server => (server_path, line_in(warning).or(first_line(&diff.server_fields))),
+            Side::Client => (client_path, line_in(warning).or(first_line(&diff.client_fields))),
+        };
+        push("analysis-warning", diff.name.clone(), format!("Warning: {}: {}", side, warning), location);
+    }
+    for read in &diff.reads {
+        let rule = match read {
+            ReadIssue::BigEndian { .. } => "byte-order",
+            ReadIssue::PastEnd { .. } => "size-mismatch",
+        };
+        push(rule, field(read.field()), read.to_string(), (client_path, Some(read.line())));
+    }
+    for row in &diff.rows {
+        match row {
+            Row::Field(f) => {
+                let location = match (f.client_line(), f.server_line()) {
+                    (Some(line), _) => (client_path, Some(line)),
+                    (None, line) => (server_path, line),
+                };
+                for issue in &f.issues {
+                    let rule = match issue {
+                        FieldIssue::OffsetMismatch { .. } => "offset-mismatch",
+                        FieldIssue::SizeMismatch { .. } => "size-mismatch",
+                        FieldIssue::BitMismatch { .. } => "bit-mismatch",
+                        FieldIssue::FlexibleArray { .. } => "flexible-array",
+                        FieldIssue::MissingInClient | FieldIssue::ExtraInClient => "missing-field",
+                        FieldIssue::TargetWidth { .. } => "width-mismatch",
+                        FieldIssue::ByteOrder => "byte-order",
+                        FieldIssue::PointerDanger => "pointer-field",
+                        FieldIssue::NameDiff { .. } => continue,
+                    };
+                    push(rule, field(f.name()), field_issue_text(f.name(), std::slice::from_ref(issue)), location);
+                }
+            }
+            Row::TrailingPadding { server, client } if server != client => {
+                push("size-mismatch", diff.name.clone(), format!("Trailing padding mismatch: Server={} bytes, Client={} bytes", server, client), client_struct);
+            }
+            Row::TrailingPadding { server, .. } => {
+                push("padding", diff.name.clone(), format!("Info: Trailing padding detected ({} bytes)", server), client_struct);
+            }
+            Row::Padding { side, bytes, before } => {
+                let (file, fields) = match side {
+                    Side::Server => (server_path, &diff.server_fields),
+                    Side::Client => (client_path, &diff.client_fields),
+                };
+                let line = fields.iter().find(|f| f.name == *before).map(|f| f.line);
+                push("padding", field(before), format!("Info: Internal padding in {} before {} ({} bytes)", side.to_string().to_lowercase(), before, bytes), (file, line));
+            }
+        }
+    }
+    findings
+}
+
+/// Findings of an enum comparison: a changed value at the client's member,
+/// a missing one at the server's, different widths at the client's first
+/// member
+pub fn enum_findings(diff: &EnumDiff, server_path: &str, client_path: &str) -> Vec<Finding> {
+    let issues = diff.members.iter().filter(|m| m.issue.is_some());
+    let mut findings: Vec<Finding> = issues
+        .zip(diff.issues())
+        .map(|(member, issue)| {
+            let (file, line) = match &member.client {
+                Some(c) => (client_path, c.line),
+                None => (server_path, member.server.line),
+            };
+            Finding {
+                rule: "enum-mismatch",
+                subject: format!("{}.{}", diff.name, member.server.name),
+                message: format!("{}: {}", diff.name, issue),
+                file: file.to_string(),
+                line: Some(line),
+                baselined: false,
+            }
+        })
+        .collect();
+    if diff.repr_mismatch() {
+        findings.push(Finding {
+            rule: "size-mismatch",
+            subject: diff.name.clone(),
+            message: format!("{}: {}", diff.name, diff.issues().last().unwrap()),
+            file: client_path.to_string(),
+            line: diff.members.iter().find_map(|m| m.client.as_ref()).map(|c| c.line),
+            baselined: false,
+        });
+    }
+    findings
+}
+
+/// A `missing-type` finding for a type of `--all` that couldn't be compared
+pub fn missing_type(kind: &str, name: &str, error: &str, client_path: &str) -> Finding {
+    Finding {
+        rule: "missing-type",
+        subject: name.to_string(),
+        message: format!("{} {}: {}", kind, name, error),
+        file: client_path.to_string(),
+        line: None,
+        baselined: false,
+    }
+}
+
+/// Findings of the leak and overflow checks: use-after-free, double free
+/// and overflows from their events, leaks from the report's findings
+pub fn leak_findings(report: &LeakReport) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for event in &report.events {
+        let rule = match event.kind {
+            MemoryEventKind::UseAfterFree => "use-after-free",
+            MemoryEventKind::DoubleFree => "double-free",
+            MemoryEventKind::BufferOverflow => "buffer-overflow",
+            _ => continue,
+        };
+        findings.push(Finding {
+            rule,
+            subject: event.variable.clone(),
+            message: event.context.clone(),
+            file: report.file_path.clone(),
+            line: Some(event.line),
+            baselined: false,
+        });
+    }
+    for finding in &report.findings {
+        let from_event = finding.starts_with("CRITICAL") || report.events.iter().any(|e| e.kind == MemoryEventKind::BufferOverflow && e.context == *finding);
+        if !from_event {
+            findings.push(Finding {
+                rule: "leak",
+                subject: quoted(finding).unwrap_or_default().to_string(),
+                message: finding.clone(),
+                file: report.file_path.clone(),
+                line: line_in(finding),
+                baselined: false,
+            });
+        }
+    }
+    findings
+}
+
+/// Findings of `--check-constants`: each definition that disagrees with
+/// the other files, at its line
+pub fn constant_findings(report: &ConstantsReport) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for constant in &report.constants {
+        let Some(value) = &constant.value else { continue };
+        for definition in constant.definitions.iter().filter(|d| !d.agrees) {
+            findings.push(Finding {
+                rule: "constant-mismatch",
+                subject: definition.name.clone(),
+                message: format!("{} is {}; the other files' {} is {}", definition.name, definition.value, constant.name, value),
+                file: definition.file.clone(),
+                line: Some(definition.line),
+                baselined: false,
+            });
+        }
+    }
+    findings
+}
+
+/// The SARIF log of one run with `findings` as its results
+pub fn document(findings: &[Finding]) -> Value {
+    let rules: Vec<Value> = RULES
+        .iter()
+        .map(|&(id, description, severity)| {
+            json!({
+                "id": id,
+                "shortDescription": { "text": description },
+                "defaultConfiguration": { "level": level(severity) },
+            })
+        })
+        .collect();
+    let results: Vec<Value> = findings
+        .iter()
+        .map(|f| {
+            let index = RULES.iter().position(|(id, _, _)| *id == f.rule).unwrap_or(0);
+            let mut location = json!({ "artifactLocation": { "uri": f.file.replace('\\', "/") } });
+            if let Some(line) = f.line.filter(|l| *l > 0) {
+                location["region"] = json!({ "startLine": line });
+            }
+            let mut result = json!({
+                "ruleId": f.rule,
+                "ruleIndex": index,
+                "level": if f.baselined { "note" } else { level(RULES[index].2) },
+                "message": { "text": f.message },
+                "locations": [{ "physicalLocation": location }],
+            });
+            if f.baselined {
+                result["suppressions"] = json!([{ "kind": "external", "justification": "Accepted in the venom-watch baseline" }]);
+            }
+            result
+        })
+        .collect();
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "venom-watch",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }],
+    })
+}
+
+/// The first `'name'` in a message
+fn quoted(message: &str) -> Option<&str> {
+    let (_, rest) = message.split_once('\'')?;
+    rest.split_once('\'').map(|(name, _)| name)
+}
+
+/// N of the first `line N` in a message
+pub fn line_in(message: &str) -> Option<usize> {
+    let (_, rest) = message.split_once("line ")?;
+    rest.split(|c: char| !c.is_ascii_digit()).next()?.parse().ok()
+}
@@ -0,0 +1,112 @@
+//! `--dump-layout` and `--compare-json`: a struct's or enum's layout saved
+//! where its sources are, and compared somewhere else
+//!
+//! The document keeps everything the comparison reads, the source file's
+//! path and each field's line included, so that a report from saved layouts
+//! reads the same as one from the sources.
+
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::models::{EnumLayout, StructLayout};
+
+pub const VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedLayout {
+    pub version: u32,
+    /// Version of the venom-watch that analyzed the sources
+    pub analyzer_version: String,
+    #[serde(flatten)]
+    pub layout: TypeLayout,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "layout", rename_all = "lowercase")]
+pub enum TypeLayout {
+    Struct(StructLayout),
+    Enum(EnumLayout),
+}
+
+impl TypeLayout {
+    pub fn name(&self) -> &str {
+        match self {
+            TypeLayout::Struct(layout) => &layout.name,
+            TypeLayout::Enum(layout) => &layout.name,
+        }
+    }
+}
+
+impl SavedLayout {
+    pub fn new(layout: TypeLayout) -> SavedLayout {
+        SavedLayout { version: VERSION, analyzer_version: env!("CARGO_PKG_VERSION").to_string(), layout }
+    }
+
+    pub fn load(path: &Path) -> Result<SavedLayout, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("Could not read layout {}: {}", path.display(), e))?;
+        let saved: SavedLayout = serde_json::from_str(&text).map_err(|e| format!("Invalid layout {}: {}", path.display(), e))?;
+        if saved.version != VERSION {
+            return Err(format!("Layout {} has version {}; this venom-watch reads version {}", path.display(), saved.version, VERSION));
+        }
+        Ok(saved)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, text + "\n").map_err(|e| format!("Could not write layout {}: {}", path.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{analyze_enum, analyze_file, compare_enum_layouts, compare_struct_layouts};
+    use crate::test_support::fixture;
+
+    /// `layout` saved to a file and loaded back
+    fn round_trip(layout: TypeLayout, file: &str) -> TypeLayout {
+        let path = std::env::temp_dir().join(format!("venom-watch-{}-{}", std::process::id(), file));
+        SavedLayout::new(layout).save(&path).unwrap();
+        let saved = SavedLayout::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!((saved.version, saved.analyzer_version.as_str()), (VERSION, env!("CARGO_PKG_VERSION")));
+        saved.layout
+    }
+
+    #[test]
+    fn test_struct_round_trip() {
+        let (server, client) = (fixture("test_all_server.h"), fixture("test_all_client.h"));
+        let live = compare_struct_layouts(&analyze_file(&server, "Sample").unwrap(), &analyze_file(&client, "Sample").unwrap());
+        assert!(!live.success(), "the fixtures differ");
+
+        let TypeLayout::Struct(saved_server) = round_trip(TypeLayout::Struct(analyze_file(&server, "Sample").unwrap()), "server.json") else { panic!() };
+        let TypeLayout::Struct(saved_client) = round_trip(TypeLayout::Struct(analyze_file(&client, "Sample").unwrap()), "client.json") else { panic!() };
+        assert_eq!(saved_server.file_path, server.to_string_lossy());
+        let saved = compare_struct_layouts(&saved_server, &saved_client);
+        assert_eq!(serde_json::to_value(&saved).unwrap(), serde_json::to_value(&live).unwrap());
+    }
+
+    #[test]
+    fn test_enum_round_trip() {
+        let (server, client) = (fixture("test_all_server.h"), fixture("test_all_client.h"));
+        let name = crate::declared_types(&server).unwrap().enums[0].clone();
+        let live = compare_enum_layouts(&analyze_enum(&server, &name).unwrap(), &analyze_enum(&client, &name).unwrap());
+
+        let saved_server = round_trip(TypeLayout::Enum(analyze_enum(&server, &name).unwrap()), "server-enum.json");
+        let saved_client = round_trip(TypeLayout::Enum(analyze_enum(&client, &name).unwrap()), "client-enum.json");
+        assert_eq!(saved_server.name(), name);
+        let (TypeLayout::Enum(saved_server), TypeLayout::Enum(saved_client)) = (saved_server, saved_client) else { panic!() };
+        let saved = compare_enum_layouts(&saved_server, &saved_client);
+        assert_eq!(serde_json::to_value(&saved).unwrap(), serde_json::to_value(&live).unwrap());
+    }
+
+    #[test]
+    fn test_version_checked() {
+        let path = std::env::temp_dir().join(format!("venom-watch-{}-future.json", std::process::id()));
+        let mut saved = SavedLayout::new(TypeLayout::Enum(EnumLayout { name: "E".to_string(), members: Vec::new(), file_path: "e.h".to_string(), repr: Some(4) }));
+        saved.version = VERSION + 1;
+        saved.save(&path).unwrap();
+        let error = SavedLayout::load(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert!(error.contains("this venom-watch reads version 1"), "{}", error);
+    }
+}
@@ -0,0 +1,9 @@
+//! Helpers shared by the tests of the library and of the binary, which
+//! each declare this module
+
+use std::path::PathBuf;
+
+/// Test file `name`, kept in the crate's root
+pub fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(name)
+}
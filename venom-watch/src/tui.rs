@@ -0,0 +1,257 @@
+//! `--tui`: where the code view is and what's selected, apart from the
+//! drawing, so that the keys can be tested without a terminal
+//!
+//! Navigation moves a cursor line; the view scrolls only as far as it
+//! takes to show it, and never past the point where the last line of the
+//! file is at the bottom.
+
+use std::collections::BTreeMap;
+use crossterm::event::KeyCode;
+use venom_watch::{sarif, LeakReport, MemoryEventKind};
+
+pub struct TuiState {
+    /// First line in view, from 0
+    pub scroll: usize,
+    /// Line the navigation is at, from 0
+    pub cursor: usize,
+    /// Selected entry of the findings panel
+    pub selected: Option<usize>,
+    /// Report shown, of `files`
+    pub file: usize,
+    files: usize,
+    lines: usize,
+    /// Lines in view at once
+    height: usize,
+    /// Kind of the last event at each line that has one, from 0
+    markers: BTreeMap<usize, MemoryEventKind>,
+    /// Line each finding points at, from 0, if it names one
+    finding_lines: Vec<Option<usize>>,
+}
+
+impl TuiState {
+    pub fn new(report: &LeakReport, lines: usize) -> TuiState {
+        let mut state = TuiState { scroll: 0, cursor: 0, selected: None, file: 0, files: 1, lines: 0, height: 1, markers: BTreeMap::new(), finding_lines: Vec::new() };
+        state.set_report(report, lines);
+        state
+    }
+
+    /// A new report of a source now `lines` long: the place in it is kept
+    /// as far as the file still reaches
+    pub fn set_report(&mut self, report: &LeakReport, lines: usize) {
+        self.lines = lines;
+        self.markers = report.events.iter().map(|e| (e.line.saturating_sub(1), e.kind.clone())).collect();
+        self.finding_lines = report
+            .findings
+            .iter()
+            .map(|finding| {
+                let event = report.events.iter().find(|e| e.context == *finding).map(|e| e.line);
+                event.or_else(|| sarif::line_in(finding)).map(|line| line.saturating_sub(1))
+            })
+            .collect();
+        self.selected = self.selected.filter(|&i| i < self.finding_lines.len());
+        self.cursor = self.cursor.min(self.last_line());
+        self.follow();
+    }
+
+    /// How many reports there are to switch between; the last one is
+    /// shown if the one that was is gone
+    pub fn set_files(&mut self, files: usize) {
+        self.files = files.max(1);
+        self.file = self.file.min(self.files - 1);
+    }
+
+    /// Lines the code view shows at once
+    pub fn resize(&mut self, height: usize) {
+        self.height = height.max(1);
+        self.follow();
+    }
+
+    pub fn marker(&self, line: usize) -> Option<&MemoryEventKind> {
+        self.markers.get(&line)
+    }
+
+    /// Handle a key; false if it isn't one of the navigation keys
+    pub fn key(&mut self, code: KeyCode) -> bool {
+        match code {
+            KeyCode::Up => self.go_to(self.cursor.saturating_sub(1)),
+            KeyCode::Down => self.go_to(self.cursor.saturating_add(1)),
+            KeyCode::PageUp => self.go_to(self.cursor.saturating_sub(self.height)),
+            KeyCode::PageDown => self.go_to(self.cursor.saturating_add(self.height)),
+            KeyCode::Char('g') | KeyCode::Home => self.go_to(0),
+            KeyCode::Char('G') | KeyCode::End => self.go_to(self.last_line()),
+            KeyCode::Char('n') => {
+                if let Some((&line, _)) = self.markers.range(self.cursor + 1..).next() {
+                    self.go_to_event(line);
+                }
+            }
+            KeyCode::Char('p') => {
+                if let Some((&line, _)) = self.markers.range(..self.cursor).next_back() {
+                    self.go_to_event(line);
+                }
+            }
+            KeyCode::Tab => self.select(self.selected.map_or(0, |i| i + 1)),
+            KeyCode::BackTab => self.select(self.selected.map_or(usize::MAX, |i| i.wrapping_sub(1))),
+            KeyCode::Left => self.switch(self.file.checked_sub(1)),
+            KeyCode::Right => self.switch(Some(self.file + 1)),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Show another report from its top; `set_report` gives its lines
+    fn switch(&mut self, file: Option<usize>) {
+        if let Some(file) = file.filter(|&f| f < self.files && f != self.file) {
+            self.file = file;
+            (self.scroll, self.cursor, self.selected) = (0, 0, None);
+        }
+    }
+
+    /// Select finding `index`, wrapping around either end, and show its line
+    fn select(&mut self, index: usize) {
+        let count = self.finding_lines.len();
+        if count == 0 {
+            return;
+        }
+        let index = if index == usize::MAX { count - 1 } else { index % count };
+        self.selected = Some(index);
+        if let Some(line) = self.finding_lines[index] {
+            self.go_to(line);
+        }
+    }
+
+    /// Go to an event's line, and select the finding there if there is one
+    fn go_to_event(&mut self, line: usize) {
+        self.go_to(line);
+        self.selected = self.finding_lines.iter().position(|&l| l == Some(line));
+    }
+
+    fn go_to(&mut self, line: usize) {
+        self.cursor = line.min(self.last_line());
+        self.follow();
+    }
+
+    /// Scroll just enough to show the cursor
+    fn follow(&mut self) {
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + self.height {
+            self.scroll = self.cursor + 1 - self.height;
+        }
+        self.scroll = self.scroll.min(self.lines.saturating_sub(self.height));
+    }
+
+    fn last_line(&self) -> usize {
+        self.lines.saturating_sub(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use venom_watch::{MemoryEvent, Severity};
+
+    fn report(events: &[(usize, MemoryEventKind)], findings: &[&str]) -> LeakReport {
+        LeakReport {
+            success: findings.is_empty(),
+            findings: findings.iter().map(|f| f.to_string()).collect(),
+            severities: vec![Severity::Warning; findings.len()],
+            events: events.iter().map(|(line, kind)| MemoryEvent { kind: kind.clone(), variable: "p".to_string(), line: *line, context: String::new() }).collect(),
+            file_path: "test.c".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_scroll_bounds() {
+        // An empty file doesn't move at all
+        let mut state = TuiState::new(&report(&[], &[]), 0);
+        state.resize(10);
+        for code in [KeyCode::Down, KeyCode::PageDown, KeyCode::Char('G'), KeyCode::Up, KeyCode::Char('n'), KeyCode::Tab] {
+            assert!(state.key(code));
+            assert_eq!((state.scroll, state.cursor), (0, 0));
+        }
+        assert!(!state.key(KeyCode::Char('x')));
+
+        // The last line stays at the bottom of the view
+        let mut state = TuiState::new(&report(&[], &[]), 100);
+        state.resize(10);
+        state.key(KeyCode::Char('G'));
+        assert_eq!((state.scroll, state.cursor), (90, 99));
+        state.key(KeyCode::Down);
+        assert_eq!((state.scroll, state.cursor), (90, 99));
+        state.key(KeyCode::PageUp);
+        assert_eq!((state.scroll, state.cursor), (89, 89));
+        state.key(KeyCode::Char('g'));
+        assert_eq!((state.scroll, state.cursor), (0, 0));
+        for _ in 0..12 {
+            state.key(KeyCode::Down);
+        }
+        assert_eq!((state.scroll, state.cursor), (3, 12));
+
+        // A shorter file, or a taller terminal, pulls the view back
+        state.set_report(&report(&[], &[]), 5);
+        assert_eq!((state.scroll, state.cursor), (0, 4));
+        state.set_report(&report(&[], &[]), 100);
+        state.key(KeyCode::Char('G'));
+        state.resize(40);
+        assert_eq!((state.scroll, state.cursor), (60, 99));
+    }
+
+    #[test]
+    fn test_finding_navigation() {
+        let events = [(5, MemoryEventKind::Allocation), (40, MemoryEventKind::UseAfterFree), (5, MemoryEventKind::Free), (70, MemoryEventKind::Allocation)];
+        let findings = ["CRITICAL: Use-After-Free of 'p' at line 40 (freed/moved at line 39)", "Potential leak in f: variable 'p' allocated at line 70 is never freed in the same scope"];
+        let mut state = TuiState::new(&report(&events, &findings), 100);
+        state.resize(10);
+        assert_eq!(state.marker(4), Some(&MemoryEventKind::Free));
+
+        state.key(KeyCode::Char('n'));
+        assert_eq!((state.cursor, state.selected), (4, None));
+        state.key(KeyCode::Char('n'));
+        assert_eq!((state.scroll, state.cursor, state.selected), (30, 39, Some(0)));
+        state.key(KeyCode::Char('n'));
+        assert_eq!((state.cursor, state.selected), (69, Some(1)));
+        state.key(KeyCode::Char('n'));
+        assert_eq!(state.cursor, 69);
+        state.key(KeyCode::Char('p'));
+        assert_eq!((state.cursor, state.selected), (39, Some(0)));
+
+        // Selecting a finding takes the code view to its line, wrapping around
+        state.key(KeyCode::Char('g'));
+        state.key(KeyCode::Tab);
+        assert_eq!((state.scroll, state.cursor, state.selected), (60, 69, Some(1)));
+        state.key(KeyCode::Tab);
+        assert_eq!((state.scroll, state.cursor, state.selected), (39, 39, Some(0)));
+        state.key(KeyCode::BackTab);
+        assert_eq!((state.cursor, state.selected), (69, Some(1)));
+
+        // A report with fewer findings drops a selection past them
+        state.set_report(&report(&events, &findings[..1]), 100);
+        assert_eq!(state.selected, None);
+    }
+
+    #[test]
+    fn test_file_switching() {
+        let mut state = TuiState::new(&report(&[], &["leak at line 50"]), 100);
+        state.resize(10);
+        state.key(KeyCode::Left);
+        assert_eq!(state.file, 0);
+        state.key(KeyCode::Right);
+        assert_eq!(state.file, 0, "only one file");
+
+        state.set_files(3);
+        state.key(KeyCode::Tab);
+        assert_eq!((state.cursor, state.selected), (49, Some(0)));
+        state.key(KeyCode::Right);
+        assert_eq!((state.file, state.scroll, state.cursor, state.selected), (1, 0, 0, None));
+        state.key(KeyCode::Right);
+        state.key(KeyCode::Right);
+        assert_eq!(state.file, 2);
+        state.key(KeyCode::Left);
+        assert_eq!(state.file, 1);
+
+        // Re-analyzed with a file gone
+        state.key(KeyCode::Right);
+        state.set_files(2);
+        assert_eq!(state.file, 1);
+    }
+}
@@ -0,0 +1,189 @@
+//! `--watch`: re-run the analyses whenever one of their files changes
+//!
+//! The watcher only feeds changed paths into a channel; debouncing and
+//! re-running read from it, so tests drive them with a channel of their own.
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+/// Quiet time after a change before re-running: one save is often several
+/// events (truncate, write, rename)
+pub const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// How long a file may stay missing or empty after a change, as when an
+/// editor saves through a swap file, before the analyses run anyway
+const SETTLE_ATTEMPTS: usize = 25;
+const SETTLE_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Watch the directories of `paths` and send each of `paths` that changes.
+/// Directories rather than the files themselves: an editor replacing a file
+/// by renaming a new one over it would end a watch on the old inode.
+pub fn watch_files(paths: &[PathBuf]) -> notify::Result<(RecommendedWatcher, Receiver<PathBuf>)> {
+    let targets: Vec<PathBuf> = paths.iter().map(|p| absolute(p)).collect();
+    let (tx, rx) = mpsc::channel();
+    let watched = targets.clone();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else { return };
+        if matches!(event.kind, EventKind::Access(_)) {
+            return;
+        }
+        for path in event.paths.iter().map(|p| absolute(p)) {
+            if watched.contains(&path) {
+                tx.send(path).ok();
+            }
+        }
+    })?;
+    let mut dirs: Vec<&Path> = targets.iter().filter_map(|p| p.parent()).collect();
+    dirs.sort();
+    dirs.dedup();
+    for dir in dirs {
+        watcher.watch(dir, RecursiveMode::NonRecursive)?;
+    }
+    Ok((watcher, rx))
+}
+
+/// `path` with its directory resolved, so the paths of events compare equal
+/// to the ones given on the command line
+fn absolute(path: &Path) -> PathBuf {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let dir = dir.canonicalize().unwrap_or(dir);
+    path.file_name().map_or(dir.clone(), |name| dir.join(name))
+}
+
+/// Blocks until a path arrives, then collects the ones that follow until
+/// `debounce` passes without another; `None` once the sender is gone
+pub fn next_change(changes: &Receiver<PathBuf>, debounce: Duration) -> Option<Vec<PathBuf>> {
+    let mut changed = vec![changes.recv().ok()?];
+    loop {
+        match changes.recv_timeout(debounce) {
+            Ok(path) => {
+                if !changed.contains(&path) {
+                    changed.push(path);
+                }
+            }
+            Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => return Some(changed),
+        }
+    }
+}
+
+/// Wait for every file to exist with some content; false if one is still
+/// missing or empty after `attempts` tries `interval` apart
+pub fn wait_for_files(paths: &[PathBuf], attempts: usize, interval: Duration) -> bool {
+    for attempt in 0..attempts {
+        if paths.iter().all(|p| p.metadata().is_ok_and(|m| m.len() > 0)) {
+            return true;
+        }
+        if attempt + 1 < attempts {
+            std::thread::sleep(interval);
+        }
+    }
+    false
+}
+
+/// Call `analyze` with the changed files after each debounced change, once
+/// `paths` have settled, until `changes` closes. A file that doesn't come
+/// back only makes that run fail; the loop waits for the next change.
+pub fn run_loop(paths: &[PathBuf], changes: &Receiver<PathBuf>, debounce: Duration, mut analyze: impl FnMut(&[PathBuf])) {
+    while let Some(changed) = next_change(changes, debounce) {
+        wait_for_files(paths, SETTLE_ATTEMPTS, SETTLE_INTERVAL);
+        analyze(&changed);
+    }
+}
+
+/// Wall-clock time of day, `HH:MM:SS` UTC
+pub fn timestamp() -> String {
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()) % 86_400;
+    format!("{:02}:{:02}:{:02} UTC", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::sync::mpsc::channel;
+    use std::thread;
+    use crate::test_support::fixture;
+
+    #[test]
+    fn test_debounce() {
+        let (tx, rx) = channel();
+        let (a, b) = (PathBuf::from("a.h"), PathBuf::from("b.h"));
+        for path in [&a, &b, &a] {
+            tx.send(path.clone()).unwrap();
+        }
+        assert_eq!(next_change(&rx, Duration::from_millis(50)), Some(vec![a.clone(), b]));
+
+        // A change arriving inside the window joins the batch
+        let late = tx.clone();
+        let sender = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            late.send(PathBuf::from("c.h")).unwrap();
+        });
+        tx.send(a.clone()).unwrap();
+        assert_eq!(next_change(&rx, Duration::from_millis(200)), Some(vec![a, PathBuf::from("c.h")]));
+        sender.join().unwrap();
+
+        drop(tx);
+        assert_eq!(next_change(&rx, Duration::from_millis(50)), None);
+    }
+
+    // The analysis runs again for each change with the file as it is then,
+    // including a save that deletes the file and writes it back shortly after
+    #[test]
+    fn test_rerun_on_change() {
+        let dir = std::env::temp_dir().join(format!("venom-watch-{}-watch", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let header = dir.join("protocol.h");
+        let original = fs::read_to_string(fixture("test_all_server.h")).unwrap();
+        fs::write(&header, &original).unwrap();
+
+        let (tx, rx) = channel();
+        let (results_tx, results) = channel();
+        let paths = vec![header.clone()];
+        let watcher = {
+            let header = header.clone();
+            thread::spawn(move || {
+                run_loop(&paths, &rx, Duration::from_millis(20), |changed| {
+                    let size = venom_watch::analyze_file(&header, "Sample").map(|l| l.total_size);
+                    results_tx.send((changed.to_vec(), size)).unwrap();
+                })
+            })
+        };
+
+        fs::write(&header, original.replace("uint32_t value;", "uint64_t value;")).unwrap();
+        tx.send(header.clone()).unwrap();
+        let (changed, size) = results.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!((changed, size), (vec![header.clone()], Ok(24)));
+
+        fs::remove_file(&header).unwrap();
+        tx.send(header.clone()).unwrap();
+        thread::sleep(Duration::from_millis(60));
+        fs::write(&header, &original).unwrap();
+        let (_, size) = results.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(size, Ok(16));
+
+        drop(tx);
+        watcher.join().unwrap();
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_watch_files_reports_writes() {
+        let dir = std::env::temp_dir().join(format!("venom-watch-{}-notify", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let header = dir.join("protocol.h");
+        fs::write(&header, "struct A { int a; };\n").unwrap();
+
+        let (_watcher, changes) = watch_files(std::slice::from_ref(&header)).unwrap();
+        fs::write(dir.join("unrelated.h"), "x").unwrap();
+        fs::write(&header, "struct A { long a; };\n").unwrap();
+        let changed = changes.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(changed, absolute(&header));
+        fs::remove_dir_all(&dir).ok();
+    }
+}
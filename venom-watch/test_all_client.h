@@ -0,0 +1,27 @@
+#include <stdint.h>
+
+/* test_all_server.h as a stale client has it: Sample.value widened to 64
+ * bits, enum Status never copied over, and a type of its own */
+
+typedef enum {
+    MODE_IDLE = 0,
+    MODE_RUN = 1,
+    MODE_STOP = 2,
+} Mode;
+
+struct Header {
+    uint32_t magic;
+    uint16_t version;
+    uint16_t flags;
+};
+
+typedef struct {
+    uint64_t timestamp_ns;
+    uint64_t value;
+    uint8_t mode;
+} Sample;
+
+struct ClientCache {
+    Sample last;
+    uint32_t hits;
+};
@@ -0,0 +1,26 @@
+#include <stdint.h>
+
+/* Two structs and two enums for --all; test_all_client.h matches only some */
+
+typedef enum {
+    MODE_IDLE = 0,
+    MODE_RUN = 1,
+    MODE_STOP = 2,
+} Mode;
+
+enum Status {
+    STATUS_OK = 0,
+    STATUS_ERROR = 1,
+};
+
+struct Header {
+    uint32_t magic;
+    uint16_t version;
+    uint16_t flags;
+}; // 8 bytes
+
+typedef struct {
+    uint64_t timestamp_ns;
+    uint32_t value;
+    uint8_t mode;
+} Sample; // 16 bytes
@@ -0,0 +1,29 @@
+#include <stdint.h>
+
+/* Device-register mirror mixing bitfields and normal fields; byte offsets,
+ * bit positions and sizes as gcc (x86_64) lays them out */
+
+struct DeviceRegs {
+    uint8_t id;                 // offset 0
+    uint32_t enabled : 1;       // unit @0, bit 8
+    uint32_t mode : 3;          // unit @0, bit 9
+    uint32_t level : 20;        // unit @0, bit 12 (ends at bit 32, fits)
+    uint32_t overflow : 4;      // doesn't fit the unit at @0: unit @4, bit 0
+    uint16_t channel;           // offset 6 (run ends in byte 4, then aligned)
+    uint8_t small : 3;          // unit @8, bit 0
+    uint8_t : 0;                // closes the byte
+    uint8_t after_zero : 2;     // unit @9, bit 0
+    uint32_t : 4;               // unnamed, takes bits 74..77
+    uint16_t wide : 9;          // would cross the 16-bit unit @8: unit @10, bit 0
+    uint64_t stamp;             // offset 16
+}; // Total: 24 bytes
+
+/* Same fields, __attribute__((packed)): bitfields are bit-contiguous */
+struct __attribute__((packed)) PackedRegs {
+    uint8_t id;                 // offset 0
+    uint32_t enabled : 1;       // bit 8
+    uint32_t mode : 3;          // bit 9
+    uint32_t level : 20;        // bit 12
+    uint32_t overflow : 4;      // bit 32
+    uint16_t channel;           // offset 5
+}; // Total: 7 bytes
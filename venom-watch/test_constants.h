@@ -0,0 +1,31 @@
+#include <stdint.h>
+
+/* Array dimensions from #define and enum constants; laid out exactly like
+ * LiteralSizes below (gcc, x86_64: 104 bytes) */
+
+#define MAX_CORES 16
+#define NAME_LEN (MAX_CORES / 2)
+#define HISTORY MAX_CORES * 2
+
+enum { SLOTS = 3, SLOT_BYTES };
+
+struct ConstSizes {
+    float cpu_cores[MAX_CORES];         // offset 0, 64 bytes
+    char name[NAME_LEN];                // offset 64, 8 bytes
+    uint8_t history[HISTORY];           // offset 72, 32 bytes
+};
+
+struct LiteralSizes {
+    float cpu_cores[16];
+    char name[8];
+    uint8_t history[32];
+};
+
+struct EnumSizes {
+    uint16_t slots[SLOTS];              // offset 0, 6 bytes
+    uint8_t slot[SLOT_BYTES];           // offset 6, 4 bytes
+}; // Total: 10 bytes
+
+struct UnknownSize {
+    uint32_t values[FROM_ANOTHER_HEADER];
+};
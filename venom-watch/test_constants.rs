@@ -0,0 +1,11 @@
+// Rust counterpart of test_constants.h
+
+pub const MAX_CORES: usize = 16;
+const NAME_LEN: usize = MAX_CORES / 2;
+
+#[repr(C)]
+pub struct ConstSizes {
+    pub cpu_cores: [f32; MAX_CORES],
+    pub name: [u8; NAME_LEN],
+    pub history: [u8; MAX_CORES * 2],
+} // Total: 104 bytes
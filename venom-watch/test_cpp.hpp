@@ -0,0 +1,41 @@
+#pragma once
+#include <array>
+#include <cstdint>
+
+/* C++ client layout: namespaces, classes with access specifiers, std::array,
+ * inline initializers and constexpr sizes; offsets as g++ (x86_64) lays them out */
+
+namespace telemetry {
+
+constexpr size_t MAX_SENSORS = 4;
+
+namespace detail {
+
+class Sample {
+public:
+    static constexpr int VERSION = 2;   // static: no storage
+    uint8_t kind = 0;                   // offset 0
+    std::array<uint16_t, MAX_SENSORS * 2> raw{};  // offset 2, 16 bytes
+
+    struct Limits { int32_t lo; int32_t hi; };  // nested type: no storage
+    enum class Unit : uint8_t { Celsius, Kelvin };
+
+    [[nodiscard]] bool empty() const { return kind == 0; }
+    void reset();
+
+private:
+    std::uint64_t stamp = 0;            // offset 24
+    bool valid{false};                  // offset 32
+}; // Total: 40 bytes
+
+} // namespace detail
+
+#pragma pack(push, 1)
+struct Frame {
+    uint8_t id = 0;                     // offset 0
+    detail::Sample sample;              // offset 1, 40 bytes
+    std::array<float, MAX_SENSORS> temps{};  // offset 41
+}; // Total: 57 bytes
+#pragma pack(pop)
+
+} // namespace telemetry
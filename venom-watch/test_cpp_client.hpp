@@ -0,0 +1,75 @@
+#pragma once
+#include <cstdint>
+#include <string>
+#include <array>
+
+namespace monitor {
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Configuration
+// ═══════════════════════════════════════════════════════════════════════════
+
+constexpr const char* CHANNEL_NAME = "monitor";
+constexpr uint32_t MAGIC = 0x763E0219;
+constexpr size_t DATA_SIZE = 16384;
+constexpr size_t CMD_SLOTS = 32;
+constexpr size_t MAX_CLIENTS = 16;
+constexpr size_t STATE_SIZE = 112;
+constexpr size_t MAX_CORES = 16;
+
+// ═══════════════════════════════════════════════════════════════════════════
+// State Structure (generated from venom.toml)
+// ═══════════════════════════════════════════════════════════════════════════
+
+#pragma pack(push, 1)
+struct State {
+    uint32_t magic = 0;
+    uint32_t version = 0;
+    float cpu_usage_percent = 0.0f;
+    std::array<float, 16> cpu_cores{};
+    uint32_t core_count = 0;
+    uint32_t memory_used_mb = 0;
+    uint32_t memory_total_mb = 0;
+    uint64_t uptime_seconds = 0;
+    uint64_t update_counter = 0;
+    uint64_t timestamp_ns = 0;
+    
+    [[nodiscard]] bool is_valid() const { return magic == MAGIC; }
+    
+    [[nodiscard]] float memory_percent() const {
+        return memory_total_mb > 0 ? 
+            static_cast<float>(memory_used_mb) / memory_total_mb * 100.0f : 0.0f;
+    }
+    
+    [[nodiscard]] std::string uptime_formatted() const {
+        auto h = uptime_seconds / 3600;
+        auto m = (uptime_seconds % 3600) / 60;
+        return std::to_string(h) + "h " + std::to_string(m) + "m";
+    }
+};
+#pragma pack(pop)
+
+static_assert(sizeof(State) == STATE_SIZE, "State struct size mismatch");
+
+// ═══════════════════════════════════════════════════════════════════════════
+// 📨 Commands (Clients send, Daemon applies)
+// ═══════════════════════════════════════════════════════════════════════════
+
+constexpr uint32_t INTERVAL_MS = 100;  // Default publish interval
+
+enum class CmdType : uint8_t {
+    Refresh = 1,      // Publish immediately
+    SetInterval = 2,  // value: publish interval in ms (10-10000)
+};
+
+#pragma pack(push, 1)
+struct Command {
+    CmdType cmd = CmdType::Refresh;
+    uint8_t _pad[3] = {};
+    int32_t value = 0;
+};
+#pragma pack(pop)
+
+static_assert(sizeof(Command) == 8, "Command struct size mismatch");
+
+} // namespace monitor
@@ -0,0 +1,249 @@
+/// VenomMemory FFI Bindings for monitor
+/// 
+/// Provides:
+/// - MonitorState: System stats from daemon
+/// - encodeCommand: Commands for the daemon (cmdRefresh, cmdSetInterval)
+/// - VenomShell: Connection to daemon
+///
+/// Library location: native/libvenom_memory.so
+
+import 'dart:ffi';
+import 'dart:io';
+import 'dart:typed_data';
+import 'package:ffi/ffi.dart';
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Configuration
+// ═══════════════════════════════════════════════════════════════════════════
+
+const String channelName = 'monitor';
+const int magic = 0x763E0219;
+const int stateSize = 112;
+const int maxCores = 16;
+
+// ═══════════════════════════════════════════════════════════════════════════
+// State Structure (matches C struct layout exactly, generated from venom.toml)
+// ═══════════════════════════════════════════════════════════════════════════
+
+class MonitorState {
+  final int magic;
+  final int version;
+  final double cpuUsagePercent;
+  final List<double> cpuCores;
+  final int coreCount;
+  final int memoryUsedMb;
+  final int memoryTotalMb;
+  final int uptimeSeconds;
+  final int updateCounter;
+  final int timestampNs;
+
+  MonitorState({
+    required this.magic,
+    required this.version,
+    required this.cpuUsagePercent,
+    required this.cpuCores,
+    required this.coreCount,
+    required this.memoryUsedMb,
+    required this.memoryTotalMb,
+    required this.uptimeSeconds,
+    required this.updateCounter,
+    required this.timestampNs,
+  });
+
+  /// Parse state from raw bytes (must match C struct layout)
+  factory MonitorState.fromBytes(Uint8List bytes) {
+    if (bytes.length < stateSize) return MonitorState.empty();
+    
+    final data = ByteData.view(bytes.buffer, bytes.offsetInBytes, bytes.length);
+    
+    return MonitorState(
+      magic: data.getUint32(0, Endian.little),
+      version: data.getUint32(4, Endian.little),
+      cpuUsagePercent: data.getFloat32(8, Endian.little),
+      cpuCores: List.generate(16, (i) => data.getFloat32(12 + i * 4, Endian.little)),
+      coreCount: data.getUint32(76, Endian.little),
+      memoryUsedMb: data.getUint32(81, Endian.little),
+      memoryTotalMb: data.getUint32(84, Endian.little),
+      uptimeSeconds: data.getUint64(88, Endian.little),
+      updateCounter: data.getUint64(96, Endian.little),
+      timestampNs: data.getUint64(104, Endian.little),
+    );
+  }
+
+  factory MonitorState.empty() => MonitorState.fromBytes(Uint8List(stateSize));
+
+  bool get isValid => magic == monitorMagic;
+  
+  double get memoryUsagePercent => 
+    memoryTotalMb > 0 ? memoryUsedMb / memoryTotalMb * 100 : 0;
+  
+  String get uptimeFormatted {
+    final hours = uptimeSeconds ~/ 3600;
+    final minutes = (uptimeSeconds % 3600) ~/ 60;
+    return '${hours}h ${minutes}m';
+  }
+}
+
+const int monitorMagic = magic;
+
+// ═══════════════════════════════════════════════════════════════════════════
+// 📨 Commands (Clients send, Daemon applies)
+// ═══════════════════════════════════════════════════════════════════════════
+
+const int intervalMs = 100;    // Default publish interval
+const int cmdRefresh = 1;      // Publish immediately
+const int cmdSetInterval = 2;  // value: publish interval in ms (10-10000)
+const int commandSize = 8;
+
+/// Packed Command: cmd u8, 3 bytes padding, value i32
+Uint8List encodeCommand(int cmd, [int value = 0]) {
+  final data = ByteData(commandSize);
+  data.setUint8(0, cmd);
+  data.setInt32(4, value, Endian.little);
+  return data.buffer.asUint8List();
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// VenomShell - Connection to VenomMemory Daemon
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// Finds the native library in various possible locations
+String _findLibraryPath() {
+  // Android apps carry it in jniLibs/<abi>/, where the loader finds it by name
+  if (Platform.isAndroid) return 'libvenom_memory.so';
+  final appLib = '${File(Platform.resolvedExecutable).parent.path}/lib';
+
+  // List of possible locations to search
+  final locations = [
+    // Next to a Flutter desktop app (bundle/lib/)
+    '$appLib/libvenom_memory.so',
+    // Relative to executable (for deployed apps)
+    'native/libvenom_memory.so',
+    '../native/libvenom_memory.so',
+    '../../native/libvenom_memory.so',
+    // Standard lib location
+    'lib/libvenom_memory.so',
+    '../lib/libvenom_memory.so', 
+    // Absolute fallback
+    '/usr/local/lib/libvenom_memory.so',
+    '/usr/lib/libvenom_memory.so',
+  ];
+  
+  for (final path in locations) {
+    if (File(path).existsSync(path)) {
+      return path;
+    }
+  }
+  
+  // Try from current directory
+  final cwd = Directory.current.path;
+  for (final path in locations) {
+    final fullPath = '$cwd/$path';
+    if (File(path).existsSync(fullPath)) {
+      return fullPath;
+    }
+  }
+  
+  throw Exception(
+    'Could not find libvenom_memory.so. Searched in:\n'
+    '${locations.join("\n")}\n\n'
+    'Make sure native/libvenom_memory.so exists in your project.'
+  );
+}
+
+class VenomShell {
+  static DynamicLibrary? _lib;
+  Pointer<Void>? _handle;
+  bool _disposed = false;
+
+  VenomShell() {
+    // Load library from native/ directory
+    final libPath = _findLibraryPath();
+    _lib ??= DynamicLibrary.open(libPath);
+    
+    // Connect to channel
+    final connect = _lib!.lookupFunction<
+      Pointer<Void> Function(Pointer<Utf8>),
+      Pointer<Void> Function(Pointer<Utf8>)
+    >('venom_shell_connect');
+    
+    final namePtr = channelName.toNativeUtf8();
+    _handle = connect(namePtr);
+    calloc.free(namePtr);
+    
+    if (_handle == nullptr) {
+      throw Exception('Failed to connect to channel "$channelName". Is the daemon running?');
+    }
+  }
+
+  /// Get the client ID assigned by the daemon
+  int get clientId {
+    _checkDisposed();
+    final fn = _lib!.lookupFunction<
+      Uint32 Function(Pointer<Void>), 
+      int Function(Pointer<Void>)
+    >('venom_shell_id');
+    return fn(_handle!);
+  }
+
+  /// Read raw data from shared memory
+  Uint8List readRawData(int maxLen) {
+    _checkDisposed();
+    final fn = _lib!.lookupFunction<
+      IntPtr Function(Pointer<Void>, Pointer<Uint8>, IntPtr), 
+      int Function(Pointer<Void>, Pointer<Uint8>, int)
+    >('venom_shell_read_data');
+    
+    final buf = calloc<Uint8>(maxLen);
+    try {
+      final len = fn(_handle!, buf, maxLen);
+      return Uint8List.fromList(buf.asTypedList(len));
+    } finally {
+      calloc.free(buf);
+    }
+  }
+
+  /// Read and parse state from daemon
+  MonitorState readState() {
+    final bytes = readRawData(stateSize + 64);
+    return MonitorState.fromBytes(bytes);
+  }
+
+  /// Send a command (see [encodeCommand]) to the daemon
+  bool sendCommand(Uint8List cmd) {
+    _checkDisposed();
+    final fn = _lib!.lookupFunction<
+      Bool Function(Pointer<Void>, Pointer<Uint8>, IntPtr),
+      bool Function(Pointer<Void>, Pointer<Uint8>, int)
+    >('venom_shell_send_command');
+    
+    final buf = calloc<Uint8>(cmd.length);
+    try {
+      buf.asTypedList(cmd.length).setAll(0, cmd);
+      return fn(_handle!, buf, cmd.length);
+    } finally {
+      calloc.free(buf);
+    }
+  }
+
+  bool setInterval(int ms) => sendCommand(encodeCommand(cmdSetInterval, ms));
+
+  bool refresh() => sendCommand(encodeCommand(cmdRefresh));
+
+  /// Clean up resources
+  void dispose() {
+    if (_disposed) return;
+    _disposed = true;
+    
+    final fn = _lib!.lookupFunction<
+      Void Function(Pointer<Void>), 
+      void Function(Pointer<Void>)
+    >('venom_shell_destroy');
+    fn(_handle!);
+    _handle = null;
+  }
+  
+  void _checkDisposed() {
+    if (_disposed) throw StateError('VenomShell has been disposed');
+  }
+}
@@ -0,0 +1,20 @@
+// Command codes the daemon reads, with the Rust binding's in test_enum_repr.rs
+#include <stdint.h>
+
+// One byte on the wire: the smallest type that holds the values
+typedef enum {
+    CMD_REFRESH = 0x01,
+    CMD_SET_INTERVAL = 0x02,
+    CMD_SHUTDOWN,
+} __attribute__((packed)) CmdType;
+
+typedef enum {
+    LEVEL_LOW,
+    LEVEL_HIGH,
+} __attribute__((packed)) Level;
+
+enum Channel {
+    CHANNEL_AUDIO = 16,
+    CHANNEL_VIDEO,
+    CHANNEL_INPUT,
+};
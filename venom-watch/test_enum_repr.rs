@@ -0,0 +1,31 @@
+// The Rust binding's side of test_enum_repr.h
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug)]
+pub enum CmdType {
+    Refresh = 1,
+    SetInterval = 2,
+    Shutdown,
+}
+
+/// Two bytes here, one in the header
+#[repr(u16)]
+pub enum Level {
+    Low,
+    High,
+}
+
+const VIDEO: i32 = 18;
+
+#[repr(C)]
+pub enum Channel {
+    Audio = 16,
+    Video = VIDEO,
+    Input,
+}
+
+/// No repr: only the values are compared
+pub enum Plain {
+    First = 1 << 2,
+    Second,
+}
@@ -0,0 +1,15 @@
+// Rust counterparts of test_fam_server.h: `[T; 0]` for the flexible array
+
+#[repr(C)]
+pub struct Packet {
+    pub kind: u32,
+    pub len: u16,
+    pub payload: [u8; 0],
+} // Total: 8 bytes
+
+#[repr(C)]
+pub struct Batch {
+    pub tag: u8,
+    pub count: u8,
+    pub words: [u32; 0],
+} // Total: 4 bytes
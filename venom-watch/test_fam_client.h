@@ -0,0 +1,19 @@
+#include <stdint.h>
+
+/* test_fam_server.h as a client declares it, with room for 256 bytes of
+ * payload in place of the flexible array; offsets as gcc (x86_64) lays
+ * them out */
+
+typedef struct {
+    uint32_t kind;                  // offset 0
+    uint16_t len;                   // offset 4
+    uint8_t payload[256];           // offset 6, 256 bytes
+    // 2 bytes trailing padding
+} Packet; // Total: 264 bytes
+
+typedef struct {
+    uint8_t tag;                    // offset 0
+    uint8_t count;                  // offset 1
+    // 2 bytes padding
+    uint32_t words[];               // offset 4, 0 bytes
+} Batch; // Total: 4 bytes
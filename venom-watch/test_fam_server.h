@@ -0,0 +1,19 @@
+#include <stdint.h>
+
+/* Variable-length messages ending in a flexible array member, the C99 way
+ * and as GNU's older zero-length array; offsets as gcc (x86_64) lays them
+ * out. The array takes no bytes of the struct, only its alignment. */
+
+typedef struct {
+    uint32_t kind;                  // offset 0
+    uint16_t len;                   // offset 4
+    uint8_t payload[];              // offset 6, 0 bytes
+    // 2 bytes trailing padding
+} Packet; // Total: 8 bytes
+
+typedef struct {
+    uint8_t tag;                    // offset 0
+    uint8_t count;                  // offset 1
+    // 2 bytes padding
+    uint32_t words[0];              // offset 4, 0 bytes
+} Batch; // Total: 4 bytes
@@ -0,0 +1,27 @@
+#include <stdint.h>
+
+/* One struct and two edits of it that keep every offset and the total
+ * size; offsets as gcc (x86_64) lays them out */
+
+typedef struct {
+    uint64_t timestamp;             // offset 0
+    uint32_t width;                 // offset 8
+    uint32_t height;                // offset 12
+    uint16_t flags;                 // offset 16
+} Frame; // Total: 24 bytes
+
+/* width and height swapped: each side reads the other's */
+typedef struct {
+    uint64_t timestamp;             // offset 0
+    uint32_t height;                // offset 8
+    uint32_t width;                 // offset 12
+    uint16_t flags;                 // offset 16
+} SwappedFrame; // Total: 24 bytes
+
+/* flags renamed, in the same place */
+typedef struct {
+    uint64_t timestamp;             // offset 0
+    uint32_t width;                 // offset 8
+    uint32_t height;                // offset 12
+    uint16_t options;               // offset 16
+} RenamedFrame; // Total: 24 bytes
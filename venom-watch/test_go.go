@@ -0,0 +1,44 @@
+package fixture
+
+import "encoding/binary"
+
+// Offsets are what gc lays out on amd64
+
+const Slots = 3
+
+type Kind uint16
+
+type Header struct {
+	Seq  uint16
+	Kind Kind
+}
+
+// Reading has no serializer: its in-memory layout is the one reported
+type Reading struct {
+	Flag   bool               // offset 0
+	Header                    // offset 2 (embedded, 4 bytes, 2-aligned)
+	Values [Slots * 2]float64 // offset 8, 48 bytes
+	Label  string             // offset 56, 16 bytes
+	Tags   []string           // offset 72, 24 bytes
+	A, B   int32              // offsets 96 and 100
+} // Total: 104 bytes
+
+// Packet is copied by hand: PacketFromBytes defines the wire layout
+// (ID at 0, Count at 2; 6 bytes), ToBytes disagrees about Count and Spare
+// is never serialized
+type Packet struct {
+	ID    uint8
+	Count uint32
+	Spare uint64
+}
+
+func (p *Packet) ToBytes() []byte {
+	buf := make([]byte, 6)
+	buf[0] = p.ID
+	binary.LittleEndian.PutUint32(buf[1:], p.Count)
+	return buf
+}
+
+func PacketFromBytes(data []byte) Packet {
+	return Packet{ID: data[0], Count: binary.LittleEndian.Uint32(data[2:])}
+}
@@ -0,0 +1,246 @@
+package venom
+
+/*
+#cgo LDFLAGS: -L${SRCDIR}/../../../lib -lvenom_memory -Wl,-rpath,$ORIGIN/../../../lib
+#include <stdint.h>
+#include <stddef.h>
+#include <stdlib.h>
+#include <stdbool.h>
+
+typedef struct { size_t data_size; size_t cmd_slots; size_t max_clients; } VenomConfig;
+void* venom_daemon_create(const char* name, VenomConfig config);
+void venom_daemon_destroy(void* handle);
+void venom_daemon_write_data(void* handle, const uint8_t* data, size_t len);
+size_t venom_daemon_try_recv_command(void* handle, uint8_t* buf, size_t max_len, uint32_t* out_client_id);
+
+void* venom_shell_connect(const char* name);
+void venom_shell_destroy(void* handle);
+size_t venom_shell_read_data(void* handle, uint8_t* buf, size_t max_len);
+uint32_t venom_shell_id(void* handle);
+bool venom_shell_send_command(void* handle, const uint8_t* cmd, size_t len);
+*/
+import "C"
+import (
+	"encoding/binary"
+	"fmt"
+	"math"
+	"unsafe"
+)
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Configuration
+// ═══════════════════════════════════════════════════════════════════════════
+
+const (
+	ChannelName = "monitor"
+	Magic       = 0x763E0219
+	DataSize    = 16384
+	CmdSlots    = 32
+	MaxClients  = 16
+	StateSize   = 112
+	MaxCores    = 16
+)
+
+// ═══════════════════════════════════════════════════════════════════════════
+// State Structure
+// ═══════════════════════════════════════════════════════════════════════════
+
+// MonitorState mirrors the packed C struct (generated from venom.toml)
+type MonitorState struct {
+	Magic           uint32
+	Version         uint32
+	CPUUsagePercent float32
+	CPUCores        [16]float32
+	CoreCount       uint32
+	MemoryUsedMB    uint32
+	MemoryTotalMB   uint32
+	UptimeSeconds   uint64
+	UpdateCounter   uint64
+	TimestampNs     uint64
+}
+
+func (s *MonitorState) IsValid() bool {
+	return s.Magic == Magic
+}
+
+func (s *MonitorState) MemoryPercent() float32 {
+	if s.MemoryTotalMB > 0 {
+		return float32(s.MemoryUsedMB) / float32(s.MemoryTotalMB) * 100
+	}
+	return 0
+}
+
+func (s *MonitorState) UptimeFormatted() string {
+	h := s.UptimeSeconds / 3600
+	m := (s.UptimeSeconds % 3600) / 60
+	return fmt.Sprintf("%dh %dm", h, m)
+}
+
+func (s *MonitorState) ToBytes() []byte {
+	buf := make([]byte, StateSize)
+	binary.LittleEndian.PutUint32(buf[0:], s.Magic)
+	binary.LittleEndian.PutUint32(buf[4:], s.Version)
+	binary.LittleEndian.PutUint32(buf[8:], math.Float32bits(s.CPUUsagePercent))
+	for i := range s.CPUCores {
+		binary.LittleEndian.PutUint32(buf[12+i*4:], math.Float32bits(s.CPUCores[i]))
+	}
+	binary.LittleEndian.PutUint32(buf[76:], s.CoreCount)
+	binary.LittleEndian.PutUint32(buf[80:], s.MemoryUsedMB)
+	binary.LittleEndian.PutUint32(buf[84:], s.MemoryTotalMB)
+	binary.LittleEndian.PutUint64(buf[88:], s.UptimeSeconds)
+	binary.LittleEndian.PutUint64(buf[96:], s.UpdateCounter)
+	binary.LittleEndian.PutUint64(buf[104:], s.TimestampNs)
+	return buf
+}
+
+func StateFromBytes(data []byte) *MonitorState {
+	if len(data) < StateSize {
+		return &MonitorState{}
+	}
+	s := &MonitorState{}
+	s.Magic = binary.LittleEndian.Uint32(data[0:])
+	s.Version = binary.LittleEndian.Uint32(data[4:])
+	s.CPUUsagePercent = math.Float32frombits(binary.LittleEndian.Uint32(data[8:]))
+	for i := range s.CPUCores {
+		s.CPUCores[i] = math.Float32frombits(binary.LittleEndian.Uint32(data[12+i*4:]))
+	}
+	s.CoreCount = binary.LittleEndian.Uint32(data[76:])
+	s.MemoryUsedMB = binary.LittleEndian.Uint32(data[80:])
+	s.MemoryTotalMB = binary.LittleEndian.Uint32(data[84:])
+	s.UptimeSeconds = binary.LittleEndian.Uint64(data[88:])
+	s.UpdateCounter = binary.LittleEndian.Uint64(data[96:])
+	s.TimestampNs = binary.LittleEndian.Uint64(data[104:])
+	return s
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// 📨 Commands (Clients send, Daemon applies)
+// ═══════════════════════════════════════════════════════════════════════════
+
+const (
+	IntervalMS     = 100 // Default publish interval
+	CommandSize    = 8   // Packed size of Command
+	CmdRefresh     = 1   // Publish immediately
+	CmdSetInterval = 2   // Value: publish interval in ms (10-10000)
+)
+
+// Command mirrors the packed C struct: cmd u8, 3 bytes padding, value i32
+type Command struct {
+	Cmd   uint8
+	Value int32
+}
+
+func (c Command) ToBytes() []byte {
+	buf := make([]byte, CommandSize)
+	buf[0] = c.Cmd
+	binary.LittleEndian.PutUint32(buf[4:], uint32(c.Value))
+	return buf
+}
+
+func CommandFromBytes(data []byte) (Command, bool) {
+	if len(data) < CommandSize {
+		return Command{}, false
+	}
+	return Command{Cmd: data[0], Value: int32(binary.LittleEndian.Uint32(data[4:]))}, true
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Daemon
+// ═══════════════════════════════════════════════════════════════════════════
+
+type Daemon struct {
+	handle unsafe.Pointer
+}
+
+func NewDaemon() (*Daemon, error) {
+	name := C.CString(ChannelName)
+	defer C.free(unsafe.Pointer(name))
+	
+	cfg := C.VenomConfig{
+		data_size:   C.size_t(DataSize),
+		cmd_slots:   C.size_t(CmdSlots),
+		max_clients: C.size_t(MaxClients),
+	}
+	
+	handle := C.venom_daemon_create(name, cfg)
+	if handle == nil {
+		return nil, fmt.Errorf("failed to create daemon channel")
+	}
+	return &Daemon{handle: handle}, nil
+}
+
+func (d *Daemon) Write(state *MonitorState) {
+	data := state.ToBytes()
+	C.venom_daemon_write_data(d.handle, (*C.uint8_t)(&data[0]), C.size_t(len(data)))
+}
+
+// TryRecvCommand returns the next queued Command, skipping messages too short to be one
+func (d *Daemon) TryRecvCommand() (Command, uint32, bool) {
+	buf := make([]byte, 64)
+	for {
+		var clientID C.uint32_t
+		n := C.venom_daemon_try_recv_command(d.handle, (*C.uint8_t)(&buf[0]), C.size_t(len(buf)), &clientID)
+		if n == 0 {
+			return Command{}, 0, false
+		}
+		if cmd, ok := CommandFromBytes(buf[:n]); ok {
+			return cmd, uint32(clientID), true
+		}
+	}
+}
+
+func (d *Daemon) Close() {
+	if d.handle != nil {
+		C.venom_daemon_destroy(d.handle)
+		d.handle = nil
+	}
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Shell (Client)
+// ═══════════════════════════════════════════════════════════════════════════
+
+type Shell struct {
+	handle unsafe.Pointer
+}
+
+func Connect() (*Shell, error) {
+	name := C.CString(ChannelName)
+	defer C.free(unsafe.Pointer(name))
+	
+	handle := C.venom_shell_connect(name)
+	if handle == nil {
+		return nil, fmt.Errorf("failed to connect - is daemon running?")
+	}
+	return &Shell{handle: handle}, nil
+}
+
+func (s *Shell) ClientID() uint32 {
+	return uint32(C.venom_shell_id(s.handle))
+}
+
+func (s *Shell) ReadState() *MonitorState {
+	buf := make([]byte, StateSize+64)
+	n := C.venom_shell_read_data(s.handle, (*C.uint8_t)(&buf[0]), C.size_t(len(buf)))
+	return StateFromBytes(buf[:n])
+}
+
+func (s *Shell) SendCommand(cmd Command) bool {
+	data := cmd.ToBytes()
+	return bool(C.venom_shell_send_command(s.handle, (*C.uint8_t)(&data[0]), C.size_t(len(data))))
+}
+
+func (s *Shell) SetInterval(ms uint32) bool {
+	return s.SendCommand(Command{Cmd: CmdSetInterval, Value: int32(ms)})
+}
+
+func (s *Shell) Refresh() bool {
+	return s.SendCommand(Command{Cmd: CmdRefresh})
+}
+
+func (s *Shell) Close() {
+	if s.handle != nil {
+		C.venom_shell_destroy(s.handle)
+		s.handle = nil
+	}
+}
@@ -0,0 +1,23 @@
+#include <stdint.h>
+
+/* test_nested_server.h as a client declares it: Frame is the same, but its
+ * Header has flags moved to the end; offsets as gcc (x86_64) lays them
+ * out, members' within the frame */
+
+struct Header {
+    uint8_t version;                // hdr.version @0
+    uint16_t kind;                  // hdr.kind @2
+    uint8_t flags;                  // hdr.flags @4
+}; // Total: 6 bytes
+
+struct Payload {
+    uint32_t length;                // body.length @16
+    uint32_t checksum;              // body.checksum @20
+}; // Total: 8 bytes
+
+typedef struct {
+    struct Header hdr;              // offset 0, 6 bytes
+    // 2 bytes padding
+    uint64_t seq;                   // offset 8
+    struct Payload body;            // offset 16, 8 bytes
+} Frame; // Total: 24 bytes
@@ -0,0 +1,22 @@
+#include <stdint.h>
+
+/* Members nested five deep, one level more than are listed; offsets as gcc
+ * (x86_64) lays them out */
+
+struct Level5 { uint32_t value; };          // a.b.c.d.e.value @4, not listed
+struct Level4 { struct Level5 e; };         // a.b.c.d.e @4
+struct Level3 { struct Level4 d; };         // a.b.c.d @4
+struct Level2 { struct Level3 c; };         // a.b.c @4
+struct Level1 { struct Level2 b; };         // a.b @4
+
+typedef struct {
+    uint32_t id;                    // offset 0
+    struct Level1 a;                // offset 4, 4 bytes
+} Deep; // Total: 8 bytes
+
+/* Not valid C: a struct can't hold itself. A file the analysis may still
+ * be pointed at */
+struct Loop {
+    uint32_t id;
+    struct Loop next;
+};
@@ -0,0 +1,22 @@
+#include <stdint.h>
+
+/* A frame embedding two structs by value; offsets as gcc (x86_64) lays
+ * them out, members' within the frame */
+
+struct Header {
+    uint8_t version;                // hdr.version @0
+    uint8_t flags;                  // hdr.flags @1
+    uint16_t kind;                  // hdr.kind @2
+}; // Total: 4 bytes
+
+struct Payload {
+    uint32_t length;                // body.length @16
+    uint32_t checksum;              // body.checksum @20
+}; // Total: 8 bytes
+
+typedef struct {
+    struct Header hdr;              // offset 0, 4 bytes
+    // 4 bytes padding
+    uint64_t seq;                   // offset 8
+    struct Payload body;            // offset 16, 8 bytes
+} Frame; // Total: 24 bytes
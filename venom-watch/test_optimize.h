@@ -0,0 +1,9 @@
+#include <stdint.h>
+
+/* 10 of its 24 bytes are padding; timestamp, count, flag, kind fits in 16 */
+struct Wasteful {
+    uint8_t flag;       // 0, then 7 bytes of padding
+    uint64_t timestamp; // 8
+    uint8_t kind;       // 16, then 3 bytes of padding
+    uint32_t count;     // 20
+};
@@ -0,0 +1,41 @@
+#include <stdint.h>
+
+/* Same fields three ways; sizes and offsets as gcc (x86_64) lays them out */
+
+struct PlainStruct {
+    uint8_t flag;       // offset 0
+    // 3 bytes padding
+    uint32_t count;     // offset 4
+    uint16_t level;     // offset 8
+    // 6 bytes padding
+    double ratio;       // offset 16
+    uint8_t tail;       // offset 24
+    // 7 bytes trailing padding
+}; // Total: 32 bytes
+
+typedef struct __attribute__((packed)) {
+    uint8_t flag;       // offset 0
+    uint32_t count;     // offset 1
+    uint16_t level;     // offset 5
+    double ratio;       // offset 7
+    uint8_t tail;       // offset 15
+} PackedStruct; // Total: 16 bytes
+
+#pragma pack(push, 4)
+struct Pack4Struct {
+    uint8_t flag;       // offset 0
+    // 3 bytes padding
+    uint32_t count;     // offset 4
+    uint16_t level;     // offset 8
+    // 2 bytes padding (double aligned to 4, not 8)
+    double ratio;       // offset 12
+    uint8_t tail;       // offset 20
+    // 3 bytes trailing padding
+}; // Total: 24 bytes
+#pragma pack(pop)
+
+/* Back to natural alignment after the pop */
+struct AfterPopStruct {
+    uint8_t flag;       // offset 0
+    double ratio;       // offset 8
+}; // Total: 16 bytes
@@ -0,0 +1,19 @@
+// Rust counterparts of test_packed.h
+
+#[repr(C, packed)]
+pub struct PackedStruct {
+    pub flag: u8,
+    pub count: u32,
+    pub level: u16,
+    pub ratio: f64,
+    pub tail: u8,
+} // Total: 16 bytes
+
+#[repr(C, packed(4))]
+pub struct Pack4Struct {
+    pub flag: u8,
+    pub count: u32,
+    pub level: u16,
+    pub ratio: f64,
+    pub tail: u8,
+} // Total: 24 bytes
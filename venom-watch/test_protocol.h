@@ -0,0 +1,55 @@
+#ifndef MONITOR_PROTOCOL_H
+#define MONITOR_PROTOCOL_H
+
+#include <stdint.h>
+#include <stdbool.h>
+
+// ═══════════════════════════════════════════════════════════════════════════
+// 📡 Channel Configuration
+// ═══════════════════════════════════════════════════════════════════════════
+
+#define MONITOR_CHANNEL_NAME "monitor"
+#define MONITOR_MAGIC 0x763E0219
+#define MONITOR_DATA_SIZE 16384
+#define MONITOR_CMD_SLOTS 32
+#define MONITOR_MAX_CLIENTS 16
+#define MONITOR_STATE_SIZE 112
+#define MONITOR_MAX_CORES 16
+
+// ═══════════════════════════════════════════════════════════════════════════
+// 📊 State (Daemon writes, Clients read) - generated from venom.toml
+// ═══════════════════════════════════════════════════════════════════════════
+
+typedef struct __attribute__((packed)) {
+    uint32_t magic;
+    uint32_t version;
+    float cpu_usage_percent;
+    float cpu_cores[16];
+    uint32_t core_count;
+    uint32_t memory_used_mb;
+    uint32_t memory_total_mb;
+    uint64_t uptime_seconds;
+    uint64_t update_counter;
+    uint64_t timestamp_ns;
+} MonitorState;
+
+_Static_assert(sizeof(MonitorState) == MONITOR_STATE_SIZE, "MonitorState size mismatch");
+
+// ═══════════════════════════════════════════════════════════════════════════
+// 📨 Commands (Clients send, Daemon applies)
+// ═══════════════════════════════════════════════════════════════════════════
+
+#define MONITOR_INTERVAL_MS 100  // Default publish interval
+
+typedef enum {
+    CMD_REFRESH = 1,     // Publish immediately
+    CMD_SET_INTERVAL,    // value: publish interval in ms (10-10000)
+} MonitorCmdType;
+
+typedef struct __attribute__((packed)) {
+    uint8_t cmd;
+    uint8_t _pad[3];
+    int32_t value;
+} MonitorCommand;
+
+#endif // MONITOR_PROTOCOL_H
@@ -0,0 +1,195 @@
+#!/usr/bin/env python3
+"""
+VenomMemory Python Bindings for monitor
+
+Provides:
+- MonitorState: System stats from daemon
+- encode_command: Commands for the daemon (CMD_REFRESH, CMD_SET_INTERVAL)
+- VenomShell: Connection to daemon via shared memory
+"""
+
+import ctypes
+import struct
+from dataclasses import dataclass
+from typing import List, Optional
+from pathlib import Path
+
+# ═══════════════════════════════════════════════════════════════════════════
+# Configuration
+# ═══════════════════════════════════════════════════════════════════════════
+
+CHANNEL_NAME = "monitor"
+MAGIC = 0x763E0219
+STATE_SIZE = 112
+MAX_CORES = 16
+
+# Packed layout from venom.toml, one struct code per field ('x' = padding)
+_LAYOUT = struct.Struct('<IIf16fIIIQQQ')
+assert _LAYOUT.size == STATE_SIZE
+
+# ═══════════════════════════════════════════════════════════════════════════
+# State Structure
+# ═══════════════════════════════════════════════════════════════════════════
+
+@dataclass
+class MonitorState:
+    """State published by the daemon."""
+    magic: int
+    version: int
+    cpu_usage_percent: float
+    cpu_cores: List[float]
+    core_count: int
+    memory_used_mb: int
+    memory_total_mb: int
+    uptime_seconds: int
+    update_counter: int
+    timestamp_ns: int
+    
+    @property
+    def is_valid(self) -> bool:
+        return self.magic == MAGIC
+    
+    @property
+    def memory_usage_percent(self) -> float:
+        if self.memory_total_mb > 0:
+            return self.memory_used_mb / self.memory_total_mb * 100
+        return 0.0
+    
+    @property
+    def uptime_formatted(self) -> str:
+        hours = self.uptime_seconds // 3600
+        minutes = (self.uptime_seconds % 3600) // 60
+        return f"{hours}h {minutes}m"
+    
+    @classmethod
+    def from_bytes(cls, data: bytes) -> 'MonitorState':
+        if len(data) < STATE_SIZE:
+            return cls.empty()
+        v = _LAYOUT.unpack_from(data, 0)
+        return cls(magic=v[0],
+                   version=v[1],
+                   cpu_usage_percent=v[2],
+                   cpu_cores=list(v[3:19]),
+                   core_count=v[19],
+                   memory_used_mb=v[20],
+                   memory_total_mb=v[21],
+                   uptime_seconds=v[22],
+                   update_counter=v[23],
+                   timestamp_ns=v[24])
+    
+    @classmethod
+    def empty(cls) -> 'MonitorState':
+        return cls.from_bytes(bytes(STATE_SIZE))
+
+# ═══════════════════════════════════════════════════════════════════════════
+# 📨 Commands (Clients send, Daemon applies)
+# ═══════════════════════════════════════════════════════════════════════════
+
+INTERVAL_MS = 100     # Default publish interval
+CMD_REFRESH = 1       # Publish immediately
+CMD_SET_INTERVAL = 2  # value: publish interval in ms (10-10000)
+
+# Packed Command: cmd u8, 3 bytes padding, value i32
+_COMMAND = struct.Struct('<B3xi')
+
+def encode_command(cmd: int, value: int = 0) -> bytes:
+    return _COMMAND.pack(cmd, value)
+
+# ═══════════════════════════════════════════════════════════════════════════
+# VenomShell - Connection to Daemon
+# ═══════════════════════════════════════════════════════════════════════════
+
+def _find_library() -> str:
+    script_dir = Path(__file__).parent.absolute()
+    locations = [
+        script_dir / "../../lib" / "libvenom_memory.so",
+        script_dir / "../lib" / "libvenom_memory.so",
+        Path("lib/libvenom_memory.so"),
+    ]
+    for path in locations:
+        if path.exists():
+            return str(path.absolute())
+    raise FileNotFoundError(f"libvenom_memory.so not found in: {locations}")
+
+
+class VenomShell:
+    """Connection to VenomMemory daemon."""
+    
+    _lib: Optional[ctypes.CDLL] = None
+    
+    def __init__(self, channel_name: str = CHANNEL_NAME):
+        self._handle = None
+        self._disposed = False  # Initialize BEFORE connection attempt
+        
+        if VenomShell._lib is None:
+            VenomShell._lib = ctypes.CDLL(_find_library())
+            self._setup_bindings()
+        
+        channel_bytes = channel_name.encode('utf-8')
+        self._handle = VenomShell._lib.venom_shell_connect(channel_bytes)
+        
+        if not self._handle:
+            raise ConnectionError(f"Failed to connect to '{channel_name}'. Is daemon running?")
+    
+    def _setup_bindings(self):
+        lib = VenomShell._lib
+        lib.venom_shell_connect.argtypes = [ctypes.c_char_p]
+        lib.venom_shell_connect.restype = ctypes.c_void_p
+        lib.venom_shell_destroy.argtypes = [ctypes.c_void_p]
+        lib.venom_shell_destroy.restype = None
+        lib.venom_shell_read_data.argtypes = [ctypes.c_void_p, ctypes.POINTER(ctypes.c_uint8), ctypes.c_size_t]
+        lib.venom_shell_read_data.restype = ctypes.c_size_t
+        lib.venom_shell_id.argtypes = [ctypes.c_void_p]
+        lib.venom_shell_id.restype = ctypes.c_uint32
+        lib.venom_shell_send_command.argtypes = [ctypes.c_void_p, ctypes.POINTER(ctypes.c_uint8), ctypes.c_size_t]
+        lib.venom_shell_send_command.restype = ctypes.c_bool
+    
+    @property
+    def client_id(self) -> int:
+        self._check_disposed()
+        return VenomShell._lib.venom_shell_id(self._handle)
+    
+    def read_raw_data(self, max_len: int = 256) -> bytes:
+        self._check_disposed()
+        buf = (ctypes.c_uint8 * max_len)()
+        length = VenomShell._lib.venom_shell_read_data(self._handle, buf, max_len)
+        return bytes(buf[:length])
+    
+    def read_state(self) -> MonitorState:
+        return MonitorState.from_bytes(self.read_raw_data(STATE_SIZE + 64))
+    
+    def send_command(self, cmd: bytes) -> bool:
+        self._check_disposed()
+        buf = (ctypes.c_uint8 * len(cmd)).from_buffer_copy(cmd)
+        return VenomShell._lib.venom_shell_send_command(self._handle, buf, len(cmd))
+    
+    def set_interval(self, ms: int) -> bool:
+        return self.send_command(encode_command(CMD_SET_INTERVAL, ms))
+    
+    def refresh(self) -> bool:
+        return self.send_command(encode_command(CMD_REFRESH))
+    
+    def close(self):
+        if self._disposed or not self._handle:
+            return
+        self._disposed = True
+        VenomShell._lib.venom_shell_destroy(self._handle)
+        self._handle = None
+    
+    def _check_disposed(self):
+        if self._disposed:
+            raise RuntimeError("VenomShell has been closed")
+    
+    def __enter__(self): return self
+    def __exit__(self, *_): self.close()
+    def __del__(self): self.close()
+
+
+if __name__ == "__main__":
+    print(f"Channel: {CHANNEL_NAME} | Magic: 0x{MAGIC:08X}")
+    try:
+        with VenomShell() as shell:
+            print(f"Connected! ID: {shell.client_id}")
+            print(shell.read_state())
+    except Exception as e:
+        print(f"Error: {e}")
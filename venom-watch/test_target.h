@@ -0,0 +1,11 @@
+#include <stdint.h>
+
+// 40 bytes on x86_64; 24 on a 32-bit target, where long and pointers are 4
+typedef struct Counters {
+    uint32_t id;
+    long total;
+    uint16_t port;
+    char name[8];
+    uint8_t flags;
+    const char *label;
+} Counters;
@@ -0,0 +1,26 @@
+#include <stdint.h>
+
+/* Tagged-union message as a client declares it, identical to test_union_server.h; offsets as gcc (x86_64)
+ * lays them out */
+
+struct MsgHeader {
+    uint8_t kind;                   // offset 0
+    uint16_t seq;                   // offset 2
+}; // Total: 4 bytes
+
+typedef struct {
+    struct MsgHeader hdr;           // offset 0, 4 bytes (hdr.kind @0, hdr.seq @2)
+    union {
+        struct { int16_t x, y; } move;          // offset 4, 4 bytes
+        struct {
+            uint32_t color;         // offset 4
+            uint8_t alpha;          // offset 8
+        } paint;                    // offset 4, 8 bytes
+        uint32_t raw[2];            // offset 4, 8 bytes
+    } body;                         // offset 4, 8 bytes
+    union {
+        uint32_t value;             // offset 12
+        struct { uint16_t lo, hi; };            // lo @12, hi @14
+    };                              // offset 12, 4 bytes
+    uint8_t flags;                  // offset 16
+} Message; // Total: 20 bytes
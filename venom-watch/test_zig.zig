@@ -0,0 +1,23 @@
+// Offsets and bit positions as Zig lays out extern and packed structs on x86_64
+
+const slots = 2 + 2;
+
+pub const Mode = enum(u2) { off, low, high };
+
+pub const Flags = packed struct {
+    enabled: bool, // bit 0
+    mode: Mode, // bits 1-2
+    level: u5, // bits 3-7
+    channel: u8, // byte 1
+    limit: u12, // bits 16-27
+}; // Backing integer u28: 4 bytes, 4-aligned
+
+pub const Sample = extern struct {
+    kind: u8, // offset 0
+    flags: Flags, // offset 4
+    temps: [slots]f32, // offset 8, 16 bytes
+    stamp: u64, // offset 24
+    next: ?*Sample, // offset 32
+}; // Total: 40 bytes
+
+pub const Loose = struct { a: u8, b: u32 }; // auto layout: Zig may reorder it
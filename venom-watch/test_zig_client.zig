@@ -0,0 +1,179 @@
+//! VenomMemory Zig Bindings
+const std = @import("std");
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Configuration
+// ═══════════════════════════════════════════════════════════════════════════
+
+pub const channel_name = "monitor";
+pub const magic: u32 = 0x763E0219;
+pub const data_size: usize = 16384;
+pub const cmd_slots: usize = 32;
+pub const max_clients: usize = 16;
+pub const state_size: usize = 112;
+pub const max_cores: usize = 16;
+
+// ═══════════════════════════════════════════════════════════════════════════
+// State Structure (C layout, generated from venom.toml)
+// ═══════════════════════════════════════════════════════════════════════════
+
+pub const State = extern struct {
+    magic_num: u32 = 0,
+    version: u32 = 0,
+    cpu_usage_percent: f32 = 0,
+    cpu_cores: [16]f32 = [_]f32{0} ** 16,
+    core_count: u32 = 0,
+    memory_used_mb: u32 = 0,
+    memory_total_mb: u32 = 0,
+    uptime_seconds: u64 = 0,
+    update_counter: u64 = 0,
+    timestamp_ns: u64 = 0,
+
+    pub fn isValid(self: *const State) bool {
+        return self.magic_num == magic;
+    }
+
+    pub fn memoryPercent(self: *const State) f32 {
+        if (self.memory_total_mb > 0) {
+            return @as(f32, @floatFromInt(self.memory_used_mb)) / @as(f32, @floatFromInt(self.memory_total_mb)) * 100.0;
+        }
+        return 0;
+    }
+
+    pub fn fromBytes(data: []const u8) State {
+        if (data.len < @sizeOf(State)) return State{};
+        return std.mem.bytesToValue(State, data[0..@sizeOf(State)]);
+    }
+
+    pub fn toBytes(self: *const State) [@sizeOf(State)]u8 {
+        return std.mem.toBytes(self.*);
+    }
+};
+
+comptime {
+    if (@sizeOf(State) != state_size) @compileError("State size mismatch");
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// 📨 Commands (Clients send, Daemon applies)
+// ═══════════════════════════════════════════════════════════════════════════
+
+pub const interval_ms: u32 = 100; // Default publish interval
+
+pub const CmdType = enum(u8) {
+    refresh = 1, // Publish immediately
+    set_interval = 2, // value: publish interval in ms (10-10000)
+    _,
+};
+
+pub const Command = extern struct {
+    cmd: CmdType,
+    _pad: [3]u8 = .{ 0, 0, 0 },
+    value: i32 = 0,
+};
+
+comptime {
+    if (@sizeOf(Command) != 8) @compileError("Command size mismatch");
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// C FFI Bindings
+// ═══════════════════════════════════════════════════════════════════════════
+
+const VenomConfig = extern struct {
+    data_size: usize,
+    cmd_slots: usize,
+    max_clients: usize,
+};
+
+extern fn venom_daemon_create(name: [*:0]const u8, config: VenomConfig) ?*anyopaque;
+extern fn venom_daemon_destroy(handle: *anyopaque) void;
+extern fn venom_daemon_write_data(handle: *anyopaque, data: [*]const u8, len: usize) void;
+extern fn venom_daemon_try_recv_command(handle: *anyopaque, buf: [*]u8, max_len: usize, out_client_id: *u32) usize;
+
+extern fn venom_shell_connect(name: [*:0]const u8) ?*anyopaque;
+extern fn venom_shell_destroy(handle: *anyopaque) void;
+extern fn venom_shell_read_data(handle: *anyopaque, buf: [*]u8, max_len: usize) usize;
+extern fn venom_shell_id(handle: *anyopaque) u32;
+extern fn venom_shell_send_command(handle: *anyopaque, cmd: [*]const u8, len: usize) bool;
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Daemon Wrapper
+// ═══════════════════════════════════════════════════════════════════════════
+
+pub const Daemon = struct {
+    handle: *anyopaque,
+
+    pub fn init() !Daemon {
+        const cfg = VenomConfig{
+            .data_size = data_size,
+            .cmd_slots = cmd_slots,
+            .max_clients = max_clients,
+        };
+        const h = venom_daemon_create(channel_name, cfg) orelse return error.CreateFailed;
+        return Daemon{ .handle = h };
+    }
+
+    pub fn write(self: *Daemon, state: *const State) void {
+        const bytes = state.toBytes();
+        venom_daemon_write_data(self.handle, &bytes, bytes.len);
+    }
+
+    pub const Received = struct { client_id: u32, cmd: Command };
+
+    /// Next queued Command, skipping messages too short to be one
+    pub fn tryRecv(self: *Daemon) ?Received {
+        var buf: [64]u8 = undefined;
+        var client_id: u32 = 0;
+        while (true) {
+            const n = venom_daemon_try_recv_command(self.handle, &buf, buf.len, &client_id);
+            if (n == 0) return null;
+            if (n < @sizeOf(Command)) continue;
+            return .{ .client_id = client_id, .cmd = std.mem.bytesToValue(Command, buf[0..@sizeOf(Command)]) };
+        }
+    }
+
+    pub fn deinit(self: *Daemon) void {
+        venom_daemon_destroy(self.handle);
+    }
+};
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Shell (Client) Wrapper
+// ═══════════════════════════════════════════════════════════════════════════
+
+pub const Shell = struct {
+    handle: *anyopaque,
+
+    pub fn connect() !Shell {
+        const h = venom_shell_connect(channel_name) orelse return error.ConnectFailed;
+        return Shell{ .handle = h };
+    }
+
+    pub fn clientId(self: *Shell) u32 {
+        return venom_shell_id(self.handle);
+    }
+
+    pub fn readState(self: *Shell) State {
+        var buf: [state_size + 64]u8 = undefined;
+        const n = venom_shell_read_data(self.handle, &buf, buf.len);
+        return State.fromBytes(buf[0..n]);
+    }
+
+    pub fn send(self: *Shell, cmd: Command) bool {
+        const bytes = std.mem.toBytes(cmd);
+        return venom_shell_send_command(self.handle, &bytes, bytes.len);
+    }
+
+    pub fn setInterval(self: *Shell, ms: u32) bool {
+        return self.send(.{ .cmd = .set_interval, .value = @intCast(ms) });
+    }
+
+    pub fn refresh(self: *Shell) bool {
+        return self.send(.{ .cmd = .refresh });
+    }
+
+    pub fn deinit(self: *Shell) void {
+        venom_shell_destroy(self.handle);
+    }
+};
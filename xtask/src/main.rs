@@ -0,0 +1,111 @@
+//! Build tasks for venom_memory, run from the repository root as
+//! `cargo xtask <task> [cargo args]`; cargo args (`--release`, `--offline`,
+//! ...) are passed on to every cargo command the task runs.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+
+/// Library features checked in every combination; `gui` only builds an
+/// example, and pulls in a whole GUI toolkit to do it
+const FEATURES: [&str; 4] = ["bindings", "discover", "serde", "async"];
+
+const USAGE: &str = "\
+Usage: cargo xtask <task> [cargo args]
+
+Tasks:
+  check-features   cargo check --all-targets with every combination of
+                   the features bindings, discover, serde and async
+  cdylib           Build the C library, libvenom_memory.so, with the
+                   bindings feature on
+  layout           Write docs/LAYOUT.md from venom_memory::layout";
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let task = args.next();
+    let cargo_args: Vec<String> = args.collect();
+    let ok = match task.as_deref() {
+        Some("check-features") => check_features(&cargo_args),
+        Some("cdylib") => cdylib(&cargo_args),
+        Some("layout") => layout(),
+        Some("help" | "--help" | "-h") => {
+            println!("{}", USAGE);
+            true
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            false
+        }
+    };
+    if ok { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+}
+
+fn check_features(cargo_args: &[String]) -> bool {
+    let mut failed = Vec::new();
+    for mask in 0..1u32 << FEATURES.len() {
+        let features: Vec<&str> = (0..FEATURES.len()).filter(|i| mask & 1 << i != 0).map(|i| FEATURES[i]).collect();
+        let features = features.join(",");
+        println!("==> features [{}]", features);
+        let ok = cargo(
+            &["check", "--all-targets", "--no-default-features", "--features", &features],
+            cargo_args,
+        );
+        if !ok {
+            failed.push(features);
+        }
+    }
+
+    if failed.is_empty() {
+        println!("All {} feature combinations build", 1 << FEATURES.len());
+        true
+    } else {
+        for features in &failed {
+            eprintln!("Failed: [{}]", features);
+        }
+        false
+    }
+}
+
+fn cdylib(cargo_args: &[String]) -> bool {
+    if !cargo(&["rustc", "--lib", "--crate-type", "cdylib", "--features", "bindings"], cargo_args) {
+        return false;
+    }
+    let profile = if cargo_args.iter().any(|arg| arg == "--release" || arg == "-r") { "release" } else { "debug" };
+    let library = target_dir().join(profile).join("libvenom_memory.so");
+    println!("Built {}", library.display());
+    true
+}
+
+fn layout() -> bool {
+    venom_memory::layout::self_test();
+    let path = root().join("docs/LAYOUT.md");
+    match std::fs::write(&path, venom_memory::layout::markdown()) {
+        Ok(()) => {
+            println!("Wrote {}", path.display());
+            true
+        }
+        Err(e) => {
+            eprintln!("Failed to write {}: {}", path.display(), e);
+            false
+        }
+    }
+}
+
+/// Run cargo in the repository root; false if it fails or can't be run
+fn cargo(args: &[&str], cargo_args: &[String]) -> bool {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    match Command::new(cargo).current_dir(root()).args(args).args(cargo_args).status() {
+        Ok(status) => status.success(),
+        Err(e) => {
+            eprintln!("Failed to run cargo: {}", e);
+            false
+        }
+    }
+}
+
+fn root() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap()
+}
+
+fn target_dir() -> PathBuf {
+    std::env::var_os("CARGO_TARGET_DIR").map_or_else(|| root().join("target"), PathBuf::from)
+}